@@ -4,7 +4,8 @@ use std::path::Path;
  * Unified Command Execution Utility
  *
  * This module provides a cross-platform command execution utility that handles
- * different operating systems and shell environments consistently.
+ * different operating systems and shell environments consistently. Processes
+ * that outlive a single call go through `supervisor` instead.
  */
 use std::sync::OnceLock;
 use std::time::Duration;
@@ -12,6 +13,8 @@ use tokio::process::Command;
 
 use crate::process_ext::NoWindowExt;
 
+pub mod supervisor;
+
 /// Command execution result
 #[derive(Debug, Clone)]
 pub struct CommandResult {
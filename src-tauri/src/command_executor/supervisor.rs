@@ -0,0 +1,583 @@
+/**
+ * Process Supervisor
+ *
+ * The one way long-lived child processes are started: terminals, pipeline
+ * steps, script runs, deployments and SDK services. A supervised process is
+ * spawned with its env and working directory, registered by PID under the
+ * domain that owns it, streams its output line by line and is killed along
+ * with every process it started. Whatever is still registered when the app
+ * exits is killed then, unless it was spawned as persistent (database
+ * services the user expects to keep running).
+ *
+ * Killing a tree, e.g. `sh -c "npm run dev"` with node under npm:
+ * - Unix: each process is spawned as the leader of its own process group, so
+ *   the group takes everything that stayed in it. Descendants that moved to
+ *   groups of their own (job-control shells do that per job) are signalled
+ *   one by one. SIGTERM first; SIGKILL for whatever is left after a grace
+ *   period.
+ * - Windows: each process is put in a Job Object, and terminating the job
+ *   terminates every process in it. Non-persistent jobs also die when their
+ *   handle closes, so they don't outlive the app even if it crashes.
+ *   Processes without a job fall back to `taskkill /T`.
+ */
+use crate::process_ext::NoWindowExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+
+/// How long a process tree gets to exit after SIGTERM before it's killed
+const KILL_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+impl OutputStream {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputStream::Stdout => "stdout",
+            OutputStream::Stderr => "stderr",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub line: String,
+}
+
+/// A registered process
+#[derive(Debug, Clone, Serialize)]
+pub struct SupervisedProcessInfo {
+    pub pid: u32,
+    /// Domain that started it, e.g. `pipelines`
+    pub owner: String,
+    pub command: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Left running when the app exits
+    pub persistent: bool,
+}
+
+/// What to spawn
+#[derive(Debug, Clone)]
+pub struct ProcessSpec {
+    owner: String,
+    program: String,
+    args: Vec<String>,
+    working_directory: Option<PathBuf>,
+    env: HashMap<String, String>,
+    capture_output: bool,
+    persistent: bool,
+}
+
+impl ProcessSpec {
+    pub fn new(owner: &str, program: impl Into<String>) -> Self {
+        Self {
+            owner: owner.to_string(),
+            program: program.into(),
+            args: Vec::new(),
+            working_directory: None,
+            env: HashMap::new(),
+            capture_output: true,
+            persistent: false,
+        }
+    }
+
+    /// A command line run by `sh -c`, or `cmd /C` on Windows
+    pub fn shell(owner: &str, command: &str) -> Self {
+        let mut spec = if cfg!(target_os = "windows") {
+            let mut spec = Self::new(owner, "cmd");
+            spec.arg("/C");
+            spec
+        } else {
+            let mut spec = Self::new(owner, "sh");
+            spec.arg("-c");
+            spec
+        };
+        spec.arg(command);
+        spec
+    }
+
+    pub fn arg(&mut self, arg: impl Into<String>) -> &mut Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_string()));
+        self
+    }
+
+    pub fn current_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.working_directory = Some(dir.into());
+        self
+    }
+
+    pub fn env(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn envs<I, K, V>(&mut self, vars: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        self.env.extend(
+            vars.into_iter()
+                .map(|(key, value)| (key.as_ref().to_string(), value.as_ref().to_string())),
+        );
+        self
+    }
+
+    /// Send stdout and stderr nowhere instead of piping them for
+    /// `take_output`
+    pub fn discard_output(&mut self) -> &mut Self {
+        self.capture_output = false;
+        self
+    }
+
+    /// Keep the process running when the app exits
+    pub fn persistent(&mut self) -> &mut Self {
+        self.persistent = true;
+        self
+    }
+
+    fn describe(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A spawned process. Dropping it kills the process (unless persistent) and
+/// unregisters it; `detach` lets it run on without a handle.
+pub struct SupervisedChild {
+    child: Child,
+    pid: Option<u32>,
+    detached: bool,
+}
+
+impl SupervisedChild {
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// The process's stdout and stderr, line by line, in the order they were
+    /// printed. The channel closes once both streams do.
+    pub fn take_output(&mut self) -> mpsc::UnboundedReceiver<OutputLine> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if let Some(stdout) = self.child.stdout.take() {
+            forward_lines(stdout, OutputStream::Stdout, sender.clone());
+        }
+        if let Some(stderr) = self.child.stderr.take() {
+            forward_lines(stderr, OutputStream::Stderr, sender);
+        }
+        receiver
+    }
+
+    pub async fn wait(&mut self) -> std::io::Result<ExitStatus> {
+        self.child.wait().await
+    }
+
+    pub fn try_wait(&mut self) -> std::io::Result<Option<ExitStatus>> {
+        self.child.try_wait()
+    }
+
+    /// Kill the process and everything it started, then reap it
+    pub async fn kill_tree(&mut self) {
+        if let Some(pid) = self.pid {
+            kill_process_tree(pid).await;
+        }
+        let _ = self.child.start_kill();
+        let _ = tokio::time::timeout(Duration::from_secs(1), self.child.wait()).await;
+    }
+
+    /// Give up the handle and leave the process running, still registered
+    /// so it can be killed by PID and, unless persistent, on app exit.
+    /// Returns its PID.
+    pub fn detach(mut self) -> Option<u32> {
+        self.detached = true;
+        self.pid
+    }
+}
+
+impl Drop for SupervisedChild {
+    fn drop(&mut self) {
+        if self.detached {
+            return;
+        }
+        if let Some(pid) = self.pid {
+            supervisor().unregister(pid);
+        }
+    }
+}
+
+fn forward_lines<R>(reader: R, stream: OutputStream, sender: mpsc::UnboundedSender<OutputLine>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if sender.send(OutputLine { stream, line }).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+struct Registered {
+    info: SupervisedProcessInfo,
+    #[cfg(windows)]
+    job: Option<job::Job>,
+}
+
+pub struct ProcessSupervisor {
+    processes: Mutex<HashMap<u32, Registered>>,
+}
+
+/// The app-wide supervisor
+pub fn supervisor() -> &'static ProcessSupervisor {
+    static SUPERVISOR: OnceLock<ProcessSupervisor> = OnceLock::new();
+    SUPERVISOR.get_or_init(|| ProcessSupervisor {
+        processes: Mutex::new(HashMap::new()),
+    })
+}
+
+impl ProcessSupervisor {
+    pub fn spawn(&self, spec: &ProcessSpec) -> std::io::Result<SupervisedChild> {
+        let mut cmd = Command::new(&spec.program);
+        cmd.no_window();
+        cmd.args(&spec.args);
+        if let Some(dir) = &spec.working_directory {
+            cmd.current_dir(dir);
+        }
+        cmd.envs(&spec.env);
+        cmd.stdin(Stdio::null());
+        if spec.capture_output {
+            cmd.stdout(Stdio::piped());
+            cmd.stderr(Stdio::piped());
+        } else {
+            cmd.stdout(Stdio::null());
+            cmd.stderr(Stdio::null());
+        }
+        cmd.kill_on_drop(!spec.persistent);
+        // Its own group, so killing the group takes what it starts
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let child = cmd.spawn()?;
+        let pid = child.id();
+        if let Some(pid) = pid {
+            self.register(pid, &spec.owner, &spec.describe(), spec.persistent);
+        }
+        Ok(SupervisedChild {
+            child,
+            pid,
+            detached: false,
+        })
+    }
+
+    /// Track a process spawned some other way, e.g. a PTY shell. Processes
+    /// the PTY layer spawned are session leaders already, so on Unix their
+    /// group is their own.
+    pub fn register(&self, pid: u32, owner: &str, command: &str, persistent: bool) {
+        let registered = Registered {
+            info: SupervisedProcessInfo {
+                pid,
+                owner: owner.to_string(),
+                command: command.to_string(),
+                started_at: chrono::Utc::now(),
+                persistent,
+            },
+            #[cfg(windows)]
+            job: job::Job::assign(pid, !persistent),
+        };
+        self.processes.lock().unwrap().insert(pid, registered);
+    }
+
+    pub fn unregister(&self, pid: u32) {
+        self.processes.lock().unwrap().remove(&pid);
+    }
+
+    pub fn list(&self) -> Vec<SupervisedProcessInfo> {
+        self.processes
+            .lock()
+            .unwrap()
+            .values()
+            .map(|process| process.info.clone())
+            .collect()
+    }
+
+    /// Kill a process by PID along with everything it started
+    pub async fn kill_tree(&self, pid: u32) {
+        kill_process_tree(pid).await;
+        self.unregister(pid);
+    }
+
+    /// Kill every registered process that isn't persistent. Called as the
+    /// app exits, so it blocks.
+    pub fn shutdown(&self) {
+        let pids: Vec<u32> = self
+            .processes
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|process| !process.info.persistent)
+            .map(|process| process.info.pid)
+            .collect();
+        kill_trees_blocking(&pids);
+        let mut processes = self.processes.lock().unwrap();
+        for pid in &pids {
+            processes.remove(pid);
+        }
+    }
+}
+
+/// Kill a process and its descendants
+pub async fn kill_process_tree(pid: u32) {
+    let _ = tokio::task::spawn_blocking(move || kill_trees_blocking(&[pid])).await;
+}
+
+#[cfg(windows)]
+fn kill_trees_blocking(pids: &[u32]) {
+    for pid in pids {
+        let terminated = supervisor()
+            .processes
+            .lock()
+            .unwrap()
+            .get(pid)
+            .and_then(|process| process.job.as_ref())
+            .is_some_and(|job| job.terminate());
+        if !terminated {
+            // /T takes the whole tree, e.g. node under `cmd /C npm run dev`
+            let _ = std::process::Command::new("taskkill")
+                .no_window()
+                .args(["/PID", &pid.to_string(), "/T", "/F"])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn kill_trees_blocking(roots: &[u32]) {
+    use sysinfo::{Pid, ProcessStatus, System};
+
+    if roots.is_empty() {
+        return;
+    }
+    let mut system = System::new();
+    system.refresh_processes();
+    let parents: Vec<(u32, u32)> = system
+        .processes()
+        .iter()
+        .filter_map(|(pid, process)| {
+            process
+                .parent()
+                .map(|parent| (pid.as_u32(), parent.as_u32()))
+        })
+        .collect();
+    let targets: Vec<u32> = roots
+        .iter()
+        .flat_map(|root| std::iter::once(*root).chain(descendants(*root, &parents)))
+        .collect();
+
+    signal_trees(roots, &targets, libc::SIGTERM);
+    let deadline = std::time::Instant::now() + KILL_GRACE;
+    while std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+        system.refresh_processes();
+        // Zombies are done; they only wait for their parent to reap them
+        let running = targets.iter().any(|pid| {
+            system
+                .process(Pid::from_u32(*pid))
+                .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+        });
+        if !running {
+            return;
+        }
+    }
+    signal_trees(roots, &targets, libc::SIGKILL);
+}
+
+#[cfg(unix)]
+fn signal_trees(roots: &[u32], targets: &[u32], signal: libc::c_int) {
+    // SAFETY: plain signal delivery; a group or PID that's gone already
+    // fails with ESRCH, which is ignored
+    unsafe {
+        for root in roots {
+            libc::killpg(*root as libc::pid_t, signal);
+        }
+        for pid in targets {
+            libc::kill(*pid as libc::pid_t, signal);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE,
+    };
+
+    /// A Job Object holding a process and every process it starts
+    pub struct Job(HANDLE);
+
+    // The handle is only ever passed to the thread-safe Win32 job calls
+    unsafe impl Send for Job {}
+    unsafe impl Sync for Job {}
+
+    impl Job {
+        /// Put a running process in a new job. With `kill_on_close` the job's
+        /// processes are terminated once its handle closes.
+        pub fn assign(pid: u32, kill_on_close: bool) -> Option<Self> {
+            // SAFETY: every handle is checked before use and closed by its owner
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return None;
+                }
+                let job = Job(handle);
+                if kill_on_close {
+                    let mut limits: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                    limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                    SetInformationJobObject(
+                        job.0,
+                        JobObjectExtendedLimitInformation,
+                        &limits as *const _ as *const std::ffi::c_void,
+                        std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                    );
+                }
+                let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+                if process.is_null() {
+                    return None;
+                }
+                let assigned = AssignProcessToJobObject(job.0, process) != 0;
+                CloseHandle(process);
+                assigned.then_some(job)
+            }
+        }
+
+        /// Terminate every process in the job
+        pub fn terminate(&self) -> bool {
+            // SAFETY: the handle is open for as long as `self` lives
+            unsafe { TerminateJobObject(self.0, 1) != 0 }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: closed exactly once, here
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+/// Every process below `root`, nearest first, from (pid, parent) pairs
+#[cfg_attr(windows, allow(dead_code))]
+fn descendants(root: u32, parents: &[(u32, u32)]) -> Vec<u32> {
+    let mut found = Vec::new();
+    let mut frontier = vec![root];
+    while let Some(parent) = frontier.pop() {
+        for (pid, _) in parents
+            .iter()
+            .filter(|(pid, of)| *of == parent && *pid != root)
+        {
+            if !found.contains(pid) {
+                found.push(*pid);
+                frontier.push(*pid);
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_every_descendant_once() {
+        // 1 -> 2 -> 4 -> 5, 1 -> 3; 6 is unrelated
+        let parents = [(2, 1), (3, 1), (4, 2), (5, 4), (6, 9)];
+        let mut found = descendants(1, &parents);
+        found.sort();
+        assert_eq!(found, vec![2, 3, 4, 5]);
+        assert!(descendants(5, &parents).is_empty());
+    }
+
+    #[tokio::test]
+    async fn streams_output_and_unregisters_on_drop() {
+        let command = if cfg!(target_os = "windows") {
+            "echo out& echo err 1>&2"
+        } else {
+            "echo out; echo err 1>&2"
+        };
+        let mut child = supervisor()
+            .spawn(&ProcessSpec::shell("tests", command))
+            .unwrap();
+        let pid = child.pid().unwrap();
+        assert!(supervisor().list().iter().any(|p| p.pid == pid));
+
+        let mut output = child.take_output();
+        let mut lines = Vec::new();
+        while let Some(line) = output.recv().await {
+            lines.push((line.stream, line.line.trim().to_string()));
+        }
+        assert!(child.wait().await.unwrap().success());
+        assert!(lines.contains(&(OutputStream::Stdout, "out".to_string())));
+        assert!(lines.contains(&(OutputStream::Stderr, "err".to_string())));
+
+        drop(child);
+        assert!(!supervisor().list().iter().any(|p| p.pid == pid));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn kill_tree_takes_grandchildren() {
+        use sysinfo::{Pid, ProcessStatus, System};
+
+        // The backgrounded sleep reports its pid, then the shell waits on it
+        let mut child = supervisor()
+            .spawn(&ProcessSpec::shell("tests", "sleep 30 & echo $!; wait"))
+            .unwrap();
+        let mut output = child.take_output();
+        let grandchild: u32 = output.recv().await.unwrap().line.trim().parse().unwrap();
+
+        child.kill_tree().await;
+
+        let mut system = System::new();
+        system.refresh_processes();
+        let alive = system
+            .process(Pid::from_u32(grandchild))
+            .is_some_and(|process| process.status() != ProcessStatus::Zombie);
+        assert!(!alive);
+    }
+}
@@ -0,0 +1,325 @@
+//! Rotated snapshots of the main database in `<app data>/backups`.
+//!
+//! Snapshots go through SQLite's online backup API, so they're consistent even
+//! while the app is writing, and restores copy pages back into the live file
+//! the same way rather than swapping it out from under open connections.
+//! Files are named `portal_desktop-<UTC timestamp>-<reason>.db`; the name is
+//! the only metadata, so a backup copied in by hand shows up as long as it
+//! follows the pattern. An encrypted database's backups are encrypted under
+//! the same key.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::backup::Backup;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::encryption::open_keyed;
+use super::DatabaseManager;
+use crate::domains::settings::services::settings_service::BackupSettings;
+use crate::{log_info, log_warn};
+
+const FILE_PREFIX: &str = "portal_desktop-";
+const FILE_EXTENSION: &str = "db";
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+/// How long a snapshot waits on a writer holding the database lock
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Pause between backup steps held up by a writer
+const STEP_PAUSE: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupReason {
+    Manual,
+    Scheduled,
+    PreMigration,
+    PreRestore,
+    PreImport,
+}
+
+impl BackupReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupReason::Manual => "manual",
+            BackupReason::Scheduled => "scheduled",
+            BackupReason::PreMigration => "pre-migration",
+            BackupReason::PreRestore => "pre-restore",
+            BackupReason::PreImport => "pre-import",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "manual" => Some(BackupReason::Manual),
+            "scheduled" => Some(BackupReason::Scheduled),
+            "pre-migration" => Some(BackupReason::PreMigration),
+            "pre-restore" => Some(BackupReason::PreRestore),
+            "pre-import" => Some(BackupReason::PreImport),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub reason: BackupReason,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseBackups {
+    db_path: PathBuf,
+    dir: PathBuf,
+    key: Option<[u8; 32]>,
+}
+
+impl DatabaseBackups {
+    pub fn new(db_path: PathBuf, dir: PathBuf, key: Option<[u8; 32]>) -> Self {
+        Self { db_path, dir, key }
+    }
+
+    /// Snapshot the database, then drop the oldest backups beyond `keep`
+    pub fn create(&self, reason: BackupReason, keep: usize) -> Result<BackupInfo, String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+        DatabaseManager::set_dir_permissions(&self.dir);
+
+        let created_at = Utc::now();
+        let file_name = file_name(created_at, reason);
+        let path = self.dir.join(&file_name);
+        // Written under a temporary name so a failed snapshot never lists
+        let partial = path.with_extension("partial");
+        let _ = std::fs::remove_file(&partial);
+
+        let result = self
+            .copy(&self.db_path, &partial)
+            .map_err(|e| format!("Failed to back up database: {}", e))
+            .and_then(|()| {
+                std::fs::rename(&partial, &path)
+                    .map_err(|e| format!("Failed to save database backup: {}", e))
+            });
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+        DatabaseManager::set_file_permissions(&path);
+
+        let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        log_info!(
+            "DatabaseBackups",
+            "Backed up database to {} ({})",
+            file_name,
+            reason.as_str()
+        );
+
+        self.rotate(keep);
+
+        Ok(BackupInfo {
+            file_name,
+            reason,
+            created_at,
+            size_bytes,
+        })
+    }
+
+    /// Backups on disk, newest first
+    pub fn list(&self) -> Result<Vec<BackupInfo>, String> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read backup directory: {}", e)),
+        };
+
+        let mut backups: Vec<BackupInfo> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_str()?.to_string();
+                let (created_at, reason) = parse_file_name(&file_name)?;
+                let size_bytes = entry.metadata().ok()?.len();
+                Some(BackupInfo {
+                    file_name,
+                    reason,
+                    created_at,
+                    size_bytes,
+                })
+            })
+            .collect();
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+        Ok(backups)
+    }
+
+    /// Copy a backup back over the live database. The current contents are
+    /// backed up first so a restore can itself be undone.
+    pub fn restore(&self, file_name: &str, keep: usize) -> Result<(), String> {
+        // Looked up rather than joined, so only listed backups can be restored
+        let backup = self
+            .list()?
+            .into_iter()
+            .find(|backup| backup.file_name == file_name)
+            .ok_or_else(|| format!("Backup not found: {}", file_name))?;
+        let source = self.dir.join(&backup.file_name);
+
+        // The pre-restore snapshot isn't rotated away before it's needed
+        self.create(BackupReason::PreRestore, keep.max(2))?;
+
+        self.copy(&source, &self.db_path)
+            .map_err(|e| format!("Failed to restore database: {}", e))?;
+
+        log_info!("DatabaseBackups", "Restored database from {}", file_name);
+        Ok(())
+    }
+
+    /// Take a scheduled backup if the last one is older than the configured
+    /// interval. Returns the backup when one was taken.
+    pub fn backup_if_due(&self, settings: &BackupSettings) -> Result<Option<BackupInfo>, String> {
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let last = self
+            .list()?
+            .into_iter()
+            .find(|backup| backup.reason == BackupReason::Scheduled)
+            .map(|backup| backup.created_at);
+        if !is_due(last, Utc::now(), settings.interval_hours) {
+            return Ok(None);
+        }
+        self.create(BackupReason::Scheduled, settings.keep as usize)
+            .map(Some)
+    }
+
+    /// Copy one database into another through the online backup API
+    fn copy(&self, from: &Path, to: &Path) -> rusqlite::Result<()> {
+        let source = self.open(from)?;
+        let mut destination = self.open(to)?;
+        let backup = Backup::new(&source, &mut destination)?;
+        backup.run_to_completion(100, STEP_PAUSE, None)
+    }
+
+    fn open(&self, path: &Path) -> rusqlite::Result<Connection> {
+        let conn = open_keyed(path, self.key.as_ref())?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(conn)
+    }
+
+    fn rotate(&self, keep: usize) {
+        let Ok(backups) = self.list() else {
+            return;
+        };
+        for backup in backups.into_iter().skip(keep.max(1)) {
+            if let Err(e) = std::fs::remove_file(self.dir.join(&backup.file_name)) {
+                log_warn!(
+                    "DatabaseBackups",
+                    "Failed to remove old backup {}: {}",
+                    backup.file_name,
+                    e
+                );
+            }
+        }
+    }
+}
+
+fn file_name(created_at: DateTime<Utc>, reason: BackupReason) -> String {
+    format!(
+        "{}{}-{}.{}",
+        FILE_PREFIX,
+        created_at.format(TIMESTAMP_FORMAT),
+        reason.as_str(),
+        FILE_EXTENSION
+    )
+}
+
+fn parse_file_name(file_name: &str) -> Option<(DateTime<Utc>, BackupReason)> {
+    let stem = file_name
+        .strip_prefix(FILE_PREFIX)?
+        .strip_suffix(&format!(".{}", FILE_EXTENSION))?;
+    let (timestamp, reason) = stem.split_once('-')?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()?
+        .and_utc();
+    Some((created_at, BackupReason::parse(reason)?))
+}
+
+fn is_due(last: Option<DateTime<Utc>>, now: DateTime<Utc>, interval_hours: u32) -> bool {
+    match last {
+        Some(last) => now - last >= chrono::Duration::hours(interval_hours.max(1) as i64),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_row(db_path: &Path, value: &str) {
+        let conn = Connection::open(db_path).unwrap();
+        conn.execute_batch("CREATE TABLE IF NOT EXISTS notes (value TEXT)")
+            .unwrap();
+        conn.execute("DELETE FROM notes", []).unwrap();
+        conn.execute("INSERT INTO notes (value) VALUES (?1)", [value])
+            .unwrap();
+    }
+
+    fn read_row(db_path: &Path) -> String {
+        Connection::open(db_path)
+            .unwrap()
+            .query_row("SELECT value FROM notes", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn file_names_round_trip() {
+        let created_at = DateTime::parse_from_rfc3339("2026-10-18T09:30:15.250Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let name = file_name(created_at, BackupReason::PreMigration);
+        assert_eq!(name, "portal_desktop-20261018T093015250Z-pre-migration.db");
+        assert_eq!(
+            parse_file_name(&name),
+            Some((created_at, BackupReason::PreMigration))
+        );
+        assert_eq!(parse_file_name("portal_desktop.db"), None);
+        assert_eq!(
+            parse_file_name("portal_desktop-20261018T093015250Z-unknown.db"),
+            None
+        );
+    }
+
+    #[test]
+    fn scheduled_backups_respect_the_interval() {
+        let now = Utc::now();
+        assert!(is_due(None, now, 24));
+        assert!(!is_due(Some(now - chrono::Duration::hours(23)), now, 24));
+        assert!(is_due(Some(now - chrono::Duration::hours(24)), now, 24));
+    }
+
+    #[test]
+    fn restores_a_backup_and_rotates_old_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("portal_desktop.db");
+        let backups = DatabaseBackups::new(db_path.clone(), dir.path().join("backups"), None);
+
+        write_row(&db_path, "first");
+        let first = backups.create(BackupReason::Manual, 5).unwrap();
+        write_row(&db_path, "second");
+        // Names carry millisecond timestamps; keep them apart
+        std::thread::sleep(Duration::from_millis(5));
+
+        backups.restore(&first.file_name, 5).unwrap();
+        assert_eq!(read_row(&db_path), "first");
+
+        // The pre-restore snapshot holds what the restore replaced
+        let listed = backups.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].reason, BackupReason::PreRestore);
+        assert!(backups.restore("../portal_desktop.db", 5).is_err());
+
+        std::thread::sleep(Duration::from_millis(5));
+        backups.create(BackupReason::Manual, 1).unwrap();
+        let listed = backups.list().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].reason, BackupReason::Manual);
+    }
+}
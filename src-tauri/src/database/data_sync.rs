@@ -0,0 +1,876 @@
+//! Sync of settings and other non-secret data through a shared folder, e.g.
+//! one kept in Dropbox, Syncthing or a git repo.
+//!
+//! Every item is a small JSON file of its own, so file sync and git see one
+//! change per edited item rather than one ever-conflicting blob:
+//!
+//! - `settings.json`: the settings document, without machine-specific keys
+//! - `ides/<name>.json` and `frameworks/<name>.json`
+//! - `ide-mappings/<framework>.json`: the IDE a framework opens with, by name
+//! - `prompt-templates/<id>.json`
+//!
+//! Each workspace keeps the last synced copy of every item. A sync compares
+//! both sides with it: an item changed on one side is copied to the other,
+//! and one changed on both is merged key by key. Only a key changed
+//! differently on both sides makes a conflict; the item is then left alone on
+//! both sides until [`DataSync::resolve`] picks a side. Credentials and other
+//! secrets are never written to the folder.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::domains::ai::prompt_templates::{PromptTemplateInput, PromptTemplateService};
+use crate::domains::ide::repositories::framework_ide_mapping_repository::FrameworkIdeMappingRepository;
+use crate::domains::ide::repositories::framework_repository::FrameworkRepository;
+use crate::domains::ide::repositories::ide_repository::IdeRepository;
+use crate::domains::settings::services::settings_schema;
+use crate::domains::settings::services::settings_service::SettingsService;
+use crate::entities::{framework, framework_ide_mapping, ide};
+
+/// Event emitted after a sync changed local data
+pub const DATA_SYNC_EVENT: &str = "data-sync-changed";
+
+const FORMAT: &str = "portal-desktop-sync";
+const FORMAT_VERSION: u32 = 1;
+const MARKER_FILE: &str = "portal-sync.json";
+const STATE_FILE: &str = "data-sync-state.json";
+
+const SETTINGS_ITEM: &str = "settings.json";
+const IDES: &str = "ides";
+const FRAMEWORKS: &str = "frameworks";
+const IDE_MAPPINGS: &str = "ide-mappings";
+const PROMPT_TEMPLATES: &str = "prompt-templates";
+/// Item folders, in the order their changes are applied: mappings name the
+/// IDEs and frameworks they join
+const FOLDERS: &[&str] = &[IDES, FRAMEWORKS, IDE_MAPPINGS, PROMPT_TEMPLATES];
+
+/// Settings keys that only make sense on this machine, or hold secrets
+const LOCAL_SETTINGS: &[&str] = &[
+    "id",
+    "created_at",
+    "updated_at",
+    "app.window_state",
+    "app.automation.webhooks",
+    "app.data_sync",
+    "app.logging",
+    "terminal.default_shell",
+];
+
+/// One sync at a time, whether asked for or in the background
+static SYNC_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(Default::default);
+
+/// Item contents by path relative to the sync folder
+type Items = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflict {
+    /// Path of the item in the sync folder
+    pub item: String,
+    /// Dotted keys changed differently on both sides; empty when one side
+    /// deleted the item and the other edited it
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataSyncReport {
+    pub folder: String,
+    /// Items written to (or deleted from) the folder
+    pub exported: Vec<String>,
+    /// Items changed here from the folder
+    pub imported: Vec<String>,
+    pub conflicts: Vec<SyncConflict>,
+    /// Files that couldn't be read and items that couldn't be applied
+    pub errors: Vec<String>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Overwrite the folder's copy with this machine's
+    KeepLocal,
+    /// Overwrite this machine's copy with the folder's
+    KeepRemote,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    /// Folder `base` was synced with; another folder starts from scratch
+    folder: Option<String>,
+    last_synced_at: Option<DateTime<Utc>>,
+    /// Every item as of its last sync
+    #[serde(default)]
+    base: Items,
+}
+
+#[derive(Deserialize)]
+struct Marker {
+    format: String,
+    version: u32,
+}
+
+/// Both sides of a sync, read at its start
+struct Snapshot {
+    state: SyncState,
+    local: Items,
+    remote: Items,
+    /// Items whose file couldn't be read; never taken as deleted
+    unreadable: HashSet<String>,
+}
+
+pub struct DataSync {
+    conn: DatabaseConnection,
+    state_path: PathBuf,
+}
+
+impl DataSync {
+    /// Sync for the workspace whose database is `conn`
+    pub fn new(conn: DatabaseConnection, workspace_dir: &Path) -> Self {
+        Self {
+            conn,
+            state_path: workspace_dir.join(STATE_FILE),
+        }
+    }
+
+    /// Bring this machine and the folder up to date with each other. With
+    /// `dry_run`, only report what would change.
+    pub async fn sync(&self, folder: &Path, dry_run: bool) -> Result<DataSyncReport, String> {
+        let _guard = SYNC_LOCK.lock().await;
+        let mut snapshot = self.snapshot(folder, !dry_run).await?;
+        let mut report = report(folder, &snapshot);
+
+        for item in item_order(&snapshot) {
+            if snapshot.unreadable.contains(&item) {
+                continue;
+            }
+            let local = snapshot.local.get(&item).cloned();
+            let remote = snapshot.remote.get(&item).cloned();
+            let base = snapshot.state.base.get(&item);
+            match merge3(base, local.as_ref(), remote.as_ref(), "") {
+                Ok(merged) => {
+                    self.settle(
+                        folder,
+                        &item,
+                        local.as_ref(),
+                        remote.as_ref(),
+                        merged,
+                        dry_run,
+                        &mut snapshot.state,
+                        &mut report,
+                    )
+                    .await
+                }
+                Err(keys) => report.conflicts.push(SyncConflict {
+                    item,
+                    keys: keys.into_iter().filter(|key| !key.is_empty()).collect(),
+                }),
+            }
+        }
+
+        if !dry_run {
+            self.finish(folder, snapshot.state, &mut report)?;
+        }
+        Ok(report)
+    }
+
+    /// Settle conflicts by taking one side's copy of each item; `items`
+    /// limits it to those, otherwise every conflicting item is settled
+    pub async fn resolve(
+        &self,
+        folder: &Path,
+        items: Option<Vec<String>>,
+        resolution: ConflictResolution,
+    ) -> Result<DataSyncReport, String> {
+        let _guard = SYNC_LOCK.lock().await;
+        let mut snapshot = self.snapshot(folder, true).await?;
+        let mut report = report(folder, &snapshot);
+        let wanted: Option<HashSet<String>> = items.map(|items| items.into_iter().collect());
+
+        for item in item_order(&snapshot) {
+            if snapshot.unreadable.contains(&item) {
+                continue;
+            }
+            let local = snapshot.local.get(&item).cloned();
+            let remote = snapshot.remote.get(&item).cloned();
+            let conflicting = merge3(
+                snapshot.state.base.get(&item),
+                local.as_ref(),
+                remote.as_ref(),
+                "",
+            )
+            .is_err();
+            let chosen = match &wanted {
+                Some(wanted) => wanted.contains(&item),
+                None => conflicting,
+            };
+            if !chosen {
+                continue;
+            }
+            let winner = match resolution {
+                ConflictResolution::KeepLocal => local.clone(),
+                ConflictResolution::KeepRemote => remote.clone(),
+            };
+            self.settle(
+                folder,
+                &item,
+                local.as_ref(),
+                remote.as_ref(),
+                winner,
+                false,
+                &mut snapshot.state,
+                &mut report,
+            )
+            .await;
+        }
+
+        self.finish(folder, snapshot.state, &mut report)?;
+        Ok(report)
+    }
+
+    /// Make both sides of `item` hold `value` and record it as synced
+    #[allow(clippy::too_many_arguments)]
+    async fn settle(
+        &self,
+        folder: &Path,
+        item: &str,
+        local: Option<&Value>,
+        remote: Option<&Value>,
+        value: Option<Value>,
+        dry_run: bool,
+        state: &mut SyncState,
+        report: &mut DataSyncReport,
+    ) {
+        // Settings are never deleted; a missing file is written again
+        let value = match value {
+            None if item == SETTINGS_ITEM => local.cloned(),
+            value => value,
+        };
+
+        if value.as_ref() != local {
+            report.imported.push(item.to_string());
+            if !dry_run {
+                if let Err(e) = self.apply(item, local, value.as_ref()).await {
+                    report.imported.pop();
+                    report.errors.push(format!("{}: {}", item, e));
+                    return;
+                }
+            }
+        }
+        if value.as_ref() != remote {
+            report.exported.push(item.to_string());
+            if !dry_run {
+                if let Err(e) = write_item(folder, item, value.as_ref()) {
+                    report.exported.pop();
+                    report.errors.push(format!("{}: {}", item, e));
+                    return;
+                }
+            }
+        }
+        if !dry_run {
+            match value {
+                Some(value) => state.base.insert(item.to_string(), value),
+                None => state.base.remove(item),
+            };
+        }
+    }
+
+    async fn snapshot(&self, folder: &Path, create: bool) -> Result<Snapshot, String> {
+        check_folder(folder, create)?;
+        let mut state = self.load_state();
+        if state.folder.as_deref() != Some(&*folder.to_string_lossy()) {
+            state = SyncState::default();
+        }
+        let (remote, unreadable) = read_folder(folder);
+        Ok(Snapshot {
+            state,
+            local: self.local_items().await?,
+            remote,
+            unreadable,
+        })
+    }
+
+    fn finish(
+        &self,
+        folder: &Path,
+        mut state: SyncState,
+        report: &mut DataSyncReport,
+    ) -> Result<(), String> {
+        let now = Utc::now();
+        state.folder = Some(folder.to_string_lossy().to_string());
+        state.last_synced_at = Some(now);
+        report.last_synced_at = Some(now);
+        let content = serde_json::to_string(&state)
+            .map_err(|e| format!("Failed to serialize sync state: {}", e))?;
+        std::fs::write(&self.state_path, content)
+            .map_err(|e| format!("Failed to save sync state: {}", e))
+    }
+
+    fn load_state(&self) -> SyncState {
+        std::fs::read_to_string(&self.state_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// This machine's copy of every item
+    async fn local_items(&self) -> Result<Items, String> {
+        let mut items = Items::new();
+
+        let settings = SettingsService::new().load_settings()?;
+        let mut document = settings_schema::to_document(&settings)?;
+        for key in LOCAL_SETTINGS {
+            take_key(&mut document, key);
+        }
+        items.insert(SETTINGS_ITEM.to_string(), document);
+
+        let mut ides = ide::Entity::find().all(&self.conn).await.map_err(db_err)?;
+        ides.sort_by(|a, b| a.name.cmp(&b.name));
+        for ide in &ides {
+            insert_named(
+                &mut items,
+                IDES,
+                &ide.name,
+                json!({
+                    "name": ide.name,
+                    "executable": ide.executable,
+                    "is_default": ide.is_default,
+                }),
+            );
+        }
+
+        let mut frameworks = framework::Entity::find()
+            .all(&self.conn)
+            .await
+            .map_err(db_err)?;
+        frameworks.sort_by(|a, b| a.name.cmp(&b.name));
+        for framework in frameworks {
+            insert_named(
+                &mut items,
+                FRAMEWORKS,
+                &framework.name,
+                json!({
+                    "name": framework.name,
+                    "icon": framework.icon,
+                    "icon_type": framework.icon_type,
+                    "category": framework.category,
+                }),
+            );
+        }
+
+        let mut mappings = framework_ide_mapping::Entity::find()
+            .all(&self.conn)
+            .await
+            .map_err(db_err)?;
+        mappings.sort_by(|a, b| a.framework.cmp(&b.framework));
+        for mapping in mappings {
+            if let Some(ide) = ides.iter().find(|ide| ide.id == mapping.ide_id) {
+                insert_named(
+                    &mut items,
+                    IDE_MAPPINGS,
+                    &mapping.framework,
+                    json!({ "framework": mapping.framework, "ide": ide.name }),
+                );
+            }
+        }
+
+        for template in PromptTemplateService::new(self.conn.clone())
+            .list(None)
+            .await?
+        {
+            insert_named(
+                &mut items,
+                PROMPT_TEMPLATES,
+                &template.id,
+                json!({
+                    "id": template.id,
+                    "name": template.name,
+                    "description": template.description,
+                    "system_prompt": template.system_prompt,
+                    "user_prompt": template.user_prompt,
+                    "variables": template.variables,
+                    "tags": template.tags,
+                }),
+            );
+        }
+
+        Ok(items)
+    }
+
+    /// Change this machine's copy of `item` from `current` to `value`
+    async fn apply(
+        &self,
+        item: &str,
+        current: Option<&Value>,
+        value: Option<&Value>,
+    ) -> Result<(), String> {
+        if item == SETTINGS_ITEM {
+            return match value {
+                Some(value) => apply_settings(value),
+                None => Ok(()),
+            };
+        }
+        let folder = item.split('/').next().unwrap_or_default();
+        let Some(value) = value else {
+            return self.delete(folder, current.unwrap_or(&Value::Null)).await;
+        };
+        let conn = &self.conn;
+
+        match folder {
+            IDES => {
+                let name = field(value, "name")?;
+                let executable = field(value, "executable")?;
+                let is_default = value["is_default"].as_bool().unwrap_or(false);
+                match ide_by_name(conn, name).await? {
+                    Some(ide) => IdeRepository::update(
+                        conn,
+                        ide.id,
+                        None,
+                        Some(executable.to_string()),
+                        Some(is_default),
+                    )
+                    .await
+                    .map(|_| ()),
+                    None => IdeRepository::create(
+                        conn,
+                        name.to_string(),
+                        executable.to_string(),
+                        is_default,
+                    )
+                    .await
+                    .map(|_| ()),
+                }
+                .map_err(db_err)
+            }
+            FRAMEWORKS => {
+                let name = field(value, "name")?;
+                let icon = field(value, "icon")?.to_string();
+                let icon_type = field(value, "icon_type")?.to_string();
+                let category = field(value, "category")?.to_string();
+                match FrameworkRepository::get_by_name(conn, name)
+                    .await
+                    .map_err(db_err)?
+                {
+                    Some(framework) => FrameworkRepository::update(
+                        conn,
+                        framework.id,
+                        None,
+                        Some(icon),
+                        Some(icon_type),
+                        Some(category),
+                    )
+                    .await
+                    .map(|_| ()),
+                    None => FrameworkRepository::create(
+                        conn,
+                        name.to_string(),
+                        icon,
+                        icon_type,
+                        category,
+                    )
+                    .await
+                    .map(|_| ()),
+                }
+                .map_err(db_err)
+            }
+            IDE_MAPPINGS => {
+                let framework = field(value, "framework")?;
+                let ide_name = field(value, "ide")?;
+                let ide = ide_by_name(conn, ide_name)
+                    .await?
+                    .ok_or_else(|| format!("IDE not found: {}", ide_name))?;
+                FrameworkIdeMappingRepository::create_or_update(conn, framework.to_string(), ide.id)
+                    .await
+                    .map(|_| ())
+                    .map_err(db_err)
+            }
+            PROMPT_TEMPLATES => {
+                let id = field(value, "id")?.to_string();
+                let mut input: PromptTemplateInput = serde_json::from_value(value.clone())
+                    .map_err(|e| format!("Invalid prompt template: {}", e))?;
+                let templates = PromptTemplateService::new(conn.clone());
+                if templates.get(&id).await.is_ok() {
+                    input.change_note = Some("Synced from another machine".to_string());
+                    templates.update(&id, input).await.map(|_| ())
+                } else {
+                    templates.create_with_id(id, input).await.map(|_| ())
+                }
+            }
+            _ => Err("Unknown item".to_string()),
+        }
+    }
+
+    async fn delete(&self, folder: &str, current: &Value) -> Result<(), String> {
+        let conn = &self.conn;
+        match folder {
+            IDES => match ide_by_name(conn, field(current, "name")?).await? {
+                Some(ide) => IdeRepository::delete(conn, ide.id).await.map_err(db_err),
+                None => Ok(()),
+            },
+            FRAMEWORKS => match FrameworkRepository::get_by_name(conn, field(current, "name")?)
+                .await
+                .map_err(db_err)?
+            {
+                Some(framework) => FrameworkRepository::delete(conn, framework.id)
+                    .await
+                    .map_err(db_err),
+                None => Ok(()),
+            },
+            IDE_MAPPINGS => {
+                FrameworkIdeMappingRepository::delete(conn, field(current, "framework")?)
+                    .await
+                    .map_err(db_err)
+            }
+            PROMPT_TEMPLATES => {
+                PromptTemplateService::new(conn.clone())
+                    .delete(field(current, "id")?)
+                    .await
+            }
+            _ => Err("Unknown item".to_string()),
+        }
+    }
+}
+
+/// Three-way merge of one item. Object keys are merged one by one; anything
+/// else changed differently on both sides is a conflict, returned as the
+/// dotted keys involved.
+fn merge3(
+    base: Option<&Value>,
+    local: Option<&Value>,
+    remote: Option<&Value>,
+    path: &str,
+) -> Result<Option<Value>, Vec<String>> {
+    if local == remote || remote == base {
+        return Ok(local.cloned());
+    }
+    if local == base {
+        return Ok(remote.cloned());
+    }
+    let (Some(Value::Object(local)), Some(Value::Object(remote))) = (local, remote) else {
+        return Err(vec![path.to_string()]);
+    };
+    let empty = Map::new();
+    let base = match base {
+        Some(Value::Object(base)) => base,
+        _ => &empty,
+    };
+
+    let keys: std::collections::BTreeSet<&String> = base
+        .keys()
+        .chain(local.keys())
+        .chain(remote.keys())
+        .collect();
+    let mut merged = Map::new();
+    let mut conflicts = Vec::new();
+    for key in keys {
+        let key_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        match merge3(base.get(key), local.get(key), remote.get(key), &key_path) {
+            Ok(Some(value)) => {
+                merged.insert(key.clone(), value);
+            }
+            Ok(None) => {}
+            Err(keys) => conflicts.extend(keys),
+        }
+    }
+    if conflicts.is_empty() {
+        Ok(Some(Value::Object(merged)))
+    } else {
+        Err(conflicts)
+    }
+}
+
+/// Every item on either side or in the last sync, in the order to apply them
+fn item_order(snapshot: &Snapshot) -> Vec<String> {
+    let mut items: Vec<String> = snapshot
+        .local
+        .keys()
+        .chain(snapshot.remote.keys())
+        .chain(snapshot.state.base.keys())
+        .cloned()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let rank = |item: &str| {
+        FOLDERS
+            .iter()
+            .position(|folder| item.starts_with(&format!("{}/", folder)))
+            .map_or(0, |position| position + 1)
+    };
+    items.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+    items
+}
+
+fn report(folder: &Path, snapshot: &Snapshot) -> DataSyncReport {
+    DataSyncReport {
+        folder: folder.to_string_lossy().to_string(),
+        exported: Vec::new(),
+        imported: Vec::new(),
+        conflicts: Vec::new(),
+        errors: snapshot
+            .unreadable
+            .iter()
+            .map(|item| format!("{}: not valid JSON", item))
+            .collect(),
+        last_synced_at: snapshot.state.last_synced_at,
+    }
+}
+
+/// Refuse folders written by a newer sync format; mark new ones as ours
+fn check_folder(folder: &Path, create: bool) -> Result<(), String> {
+    let marker_path = folder.join(MARKER_FILE);
+    match std::fs::read_to_string(&marker_path) {
+        Ok(content) => {
+            let marker: Marker = serde_json::from_str(&content)
+                .map_err(|_| format!("{} isn't a Portal Desktop sync folder", folder.display()))?;
+            if marker.format != FORMAT {
+                return Err(format!(
+                    "{} isn't a Portal Desktop sync folder",
+                    folder.display()
+                ));
+            }
+            if marker.version > FORMAT_VERSION {
+                return Err(format!(
+                    "{} was synced by a newer version of Portal Desktop. Update before syncing with it.",
+                    folder.display()
+                ));
+            }
+            Ok(())
+        }
+        Err(_) if create => {
+            std::fs::create_dir_all(folder)
+                .map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+            write_json(
+                &marker_path,
+                &json!({ "format": FORMAT, "version": FORMAT_VERSION }),
+            )
+        }
+        Err(_) => Ok(()),
+    }
+}
+
+/// The folder's copy of every item, and the items whose file was unreadable
+fn read_folder(folder: &Path) -> (Items, HashSet<String>) {
+    let mut items = Items::new();
+    let mut unreadable = HashSet::new();
+    let mut read = |path: &Path, item: String| match std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    {
+        Some(value) => {
+            items.insert(item, value);
+        }
+        None => {
+            unreadable.insert(item);
+        }
+    };
+
+    let settings = folder.join(SETTINGS_ITEM);
+    if settings.exists() {
+        read(&settings, SETTINGS_ITEM.to_string());
+    }
+    for name in FOLDERS {
+        let Ok(entries) = std::fs::read_dir(folder.join(name)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(file_name) = path.file_name().and_then(|name| name.to_str()) {
+                read(&path, format!("{}/{}", name, file_name));
+            }
+        }
+    }
+    (items, unreadable)
+}
+
+fn write_item(folder: &Path, item: &str, value: Option<&Value>) -> Result<(), String> {
+    let path = folder.join(item);
+    match value {
+        Some(value) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            write_json(&path, value)
+        }
+        None if path.exists() => std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to delete {}: {}", path.display(), e)),
+        None => Ok(()),
+    }
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<(), String> {
+    let mut content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    content.push('\n');
+    std::fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Save synced settings, keeping this machine's own keys
+fn apply_settings(value: &Value) -> Result<(), String> {
+    let service = SettingsService::new();
+    let current = settings_schema::to_document(&service.load_settings()?)?;
+    let mut document = value.clone();
+    for key in LOCAL_SETTINGS {
+        if let Some(local) = current.pointer(&pointer(key)) {
+            put_key(&mut document, key, local.clone());
+        }
+    }
+    let settings = service.parse_document(document)?;
+    service.save_settings(&settings)
+}
+
+/// Add an item named after `name`, numbering names that clash once made
+/// file-safe
+fn insert_named(items: &mut Items, folder: &str, name: &str, value: Value) {
+    let base = file_name(name);
+    let mut item = format!("{}/{}.json", folder, base);
+    let mut suffix = 2;
+    while items.contains_key(&item) {
+        item = format!("{}/{}-{}.json", folder, base, suffix);
+        suffix += 1;
+    }
+    items.insert(item, value);
+}
+
+fn file_name(name: &str) -> String {
+    let mut file_name = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            file_name.push(c.to_ascii_lowercase());
+        } else if c == '+' {
+            file_name.push('p');
+        } else if c == '#' {
+            file_name.push_str("sharp");
+        } else if !file_name.ends_with('-') {
+            file_name.push('-');
+        }
+    }
+    match file_name.trim_matches('-') {
+        "" => "item".to_string(),
+        file_name => file_name.to_string(),
+    }
+}
+
+fn pointer(key: &str) -> String {
+    format!("/{}", key.replace('.', "/"))
+}
+
+/// Remove a dotted key from a document
+fn take_key(document: &mut Value, key: &str) -> Option<Value> {
+    let (parent, last) = match key.rsplit_once('.') {
+        Some((parent, last)) => (document.pointer_mut(&pointer(parent))?, last),
+        None => (document, key),
+    };
+    parent.as_object_mut()?.remove(last)
+}
+
+/// Set a dotted key in a document whose parent object exists
+fn put_key(document: &mut Value, key: &str, value: Value) {
+    let (parent, last) = match key.rsplit_once('.') {
+        Some((parent, last)) => (document.pointer_mut(&pointer(parent)), last),
+        None => (Some(document), key),
+    };
+    if let Some(Value::Object(parent)) = parent {
+        parent.insert(last.to_string(), value);
+    }
+}
+
+fn field<'a>(value: &'a Value, key: &str) -> Result<&'a str, String> {
+    value[key]
+        .as_str()
+        .filter(|field| !field.trim().is_empty())
+        .ok_or_else(|| format!("missing {}", key))
+}
+
+async fn ide_by_name(conn: &DatabaseConnection, name: &str) -> Result<Option<ide::Model>, String> {
+    ide::Entity::find()
+        .filter(ide::Column::Name.eq(name))
+        .one(conn)
+        .await
+        .map_err(db_err)
+}
+
+fn db_err(e: sea_orm::DbErr) -> String {
+    format!("Database error: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_changes_from_both_sides_key_by_key() {
+        let base = json!({ "editor": { "font_size": 14, "tab_size": 4 }, "theme": "dark" });
+        let local = json!({ "editor": { "font_size": 16, "tab_size": 4 }, "theme": "dark" });
+        let remote = json!({ "editor": { "font_size": 14, "tab_size": 2 }, "theme": "light" });
+        assert_eq!(
+            merge3(Some(&base), Some(&local), Some(&remote), ""),
+            Ok(Some(
+                json!({ "editor": { "font_size": 16, "tab_size": 2 }, "theme": "light" })
+            ))
+        );
+
+        let remote = json!({ "editor": { "font_size": 12, "tab_size": 4 }, "theme": "dark" });
+        assert_eq!(
+            merge3(Some(&base), Some(&local), Some(&remote), ""),
+            Err(vec!["editor.font_size".to_string()])
+        );
+    }
+
+    #[test]
+    fn deletions_follow_the_unchanged_side() {
+        let ide = json!({ "name": "Code", "executable": "code" });
+        let edited = json!({ "name": "Code", "executable": "code-insiders" });
+        // Deleted remotely, untouched here
+        assert_eq!(merge3(Some(&ide), Some(&ide), None, ""), Ok(None));
+        // New here
+        assert_eq!(merge3(None, Some(&ide), None, ""), Ok(Some(ide.clone())));
+        // Deleted remotely, edited here
+        assert_eq!(
+            merge3(Some(&ide), Some(&edited), None, ""),
+            Err(vec![String::new()])
+        );
+    }
+
+    #[test]
+    fn local_settings_keys_are_stripped_and_restored() {
+        let mut document = json!({ "app": { "window_state": { "width": 800 }, "theme": "dark" } });
+        assert_eq!(
+            take_key(&mut document, "app.window_state"),
+            Some(json!({ "width": 800 }))
+        );
+        assert_eq!(document, json!({ "app": { "theme": "dark" } }));
+        put_key(&mut document, "app.window_state", json!({ "width": 1024 }));
+        assert_eq!(document["app"]["window_state"]["width"], 1024);
+    }
+
+    #[test]
+    fn item_names_are_file_safe_and_unique() {
+        let mut items = Items::new();
+        insert_named(&mut items, IDES, "VS Code", json!(1));
+        insert_named(&mut items, IDES, "vs-code", json!(2));
+        insert_named(&mut items, FRAMEWORKS, "C#", json!(3));
+        insert_named(&mut items, FRAMEWORKS, "../..", json!(4));
+        let names: Vec<&str> = items.keys().map(String::as_str).collect();
+        assert_eq!(
+            names,
+            vec![
+                "frameworks/csharp.json",
+                "frameworks/item.json",
+                "ides/vs-code-2.json",
+                "ides/vs-code.json"
+            ]
+        );
+    }
+}
@@ -0,0 +1,429 @@
+//! Opt-in encryption at rest for the main database, through SQLCipher.
+//!
+//! The key is derived from a passphrase the user picks (PBKDF2, salt kept in
+//! `database-encryption.json` next to the database) and stored in the OS
+//! keychain, so normal starts need no prompt. When the keychain doesn't have
+//! it, e.g. on a new machine or after the keychain was reset, the app starts
+//! locked until `unlock_database` is given the passphrase.
+//!
+//! Switching encryption on or off re-writes the whole file, which can't happen
+//! under open connections. The switch is recorded as pending and carried out
+//! by [`prepare`] on the next start, before anything connects. Every
+//! workspace's database is converted, along with its backups so no plaintext
+//! copy is left behind; all workspaces share the one key.
+//!
+//! SQLCipher is only built with the `sqlcipher` cargo feature (on by default).
+//! Without it encryption can't be switched on, and an encrypted database
+//! can't be opened.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::workspaces;
+use crate::{log_info, log_warn};
+
+const CONFIG_FILE: &str = "database-encryption.json";
+const KEYCHAIN_KEY_NAME: &str = "database-key";
+const PBKDF2_ITERATIONS: u32 = 256_000;
+/// Built with the `sqlcipher` feature
+const AVAILABLE: bool = cfg!(feature = "sqlcipher");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingChange {
+    Encrypt,
+    Decrypt,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    /// The database file is encrypted
+    #[serde(default)]
+    pub enabled: bool,
+    /// Carried out on the next start
+    #[serde(default)]
+    pub pending: Option<PendingChange>,
+    #[serde(default)]
+    pub salt: Option<String>,
+    #[serde(default)]
+    pub iterations: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EncryptionStatus {
+    /// This build includes SQLCipher
+    pub available: bool,
+    pub enabled: bool,
+    pub pending: Option<PendingChange>,
+    /// Encrypted, and the key wasn't available at startup
+    pub locked: bool,
+}
+
+/// Managed instead of the database when it starts locked
+pub struct LockedDatabase {
+    pub data_dir: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum PrepareError {
+    /// Encrypted, and the keychain has no key for it
+    Locked,
+    Failed(String),
+}
+
+impl std::fmt::Display for PrepareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrepareError::Locked => write!(f, "The database is encrypted and locked"),
+            PrepareError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<String> for PrepareError {
+    fn from(msg: String) -> Self {
+        PrepareError::Failed(msg)
+    }
+}
+
+pub fn load_config(data_dir: &Path) -> EncryptionConfig {
+    std::fs::read_to_string(data_dir.join(CONFIG_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_config(data_dir: &Path, config: &EncryptionConfig) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize encryption settings: {}", e))?;
+    std::fs::write(data_dir.join(CONFIG_FILE), content)
+        .map_err(|e| format!("Failed to save encryption settings: {}", e))
+}
+
+/// Apply a pending switch and return the key to open the databases with, if
+/// they're encrypted. Runs before any database is connected.
+pub fn prepare(data_dir: &Path) -> Result<Option<[u8; 32]>, PrepareError> {
+    let mut config = load_config(data_dir);
+    if !AVAILABLE && (config.enabled || config.pending == Some(PendingChange::Decrypt)) {
+        return Err(PrepareError::Failed(
+            "The database is encrypted, and this build doesn't include SQLCipher to open it"
+                .to_string(),
+        ));
+    }
+
+    match config.pending {
+        Some(PendingChange::Encrypt) => {
+            let key = stored_key()?.ok_or(PrepareError::Locked)?;
+            log_info!("DatabaseEncryption", "Encrypting the database...");
+            // Databases and backups are only swapped once fully converted,
+            // and put back if a later one fails, so a failure leaves
+            // everything readable as it was; carry on unencrypted
+            if let Err(e) = convert_all(data_dir, None, Some(&key)) {
+                log_warn!("DatabaseEncryption", "Encryption abandoned: {}", e);
+                save_config(data_dir, &EncryptionConfig::default())?;
+                let _ = delete_stored_key();
+                return Ok(None);
+            }
+            config.enabled = true;
+            config.pending = None;
+            save_config(data_dir, &config)?;
+            Ok(Some(key))
+        }
+        Some(PendingChange::Decrypt) => {
+            let key = stored_key()?.ok_or(PrepareError::Locked)?;
+            log_info!("DatabaseEncryption", "Decrypting the database...");
+            convert_all(data_dir, Some(&key), None)?;
+            config = EncryptionConfig::default();
+            save_config(data_dir, &config)?;
+            if let Err(e) = delete_stored_key() {
+                log_warn!("DatabaseEncryption", "{}", e);
+            }
+            Ok(None)
+        }
+        None if config.enabled => stored_key()?.map(Some).ok_or(PrepareError::Locked),
+        None => Ok(None),
+    }
+}
+
+/// Schedule encryption under `passphrase` for the next start
+pub fn enable(data_dir: &Path, passphrase: &str) -> Result<EncryptionStatus, String> {
+    if !AVAILABLE {
+        return Err(
+            "This build doesn't include SQLCipher, so the database can't be encrypted".to_string(),
+        );
+    }
+    if passphrase.trim().is_empty() {
+        return Err("The database passphrase can't be empty".to_string());
+    }
+    let mut config = load_config(data_dir);
+    if config.enabled {
+        return Err("The database is already encrypted".to_string());
+    }
+
+    let mut salt = [0u8; 32];
+    {
+        use rand::RngCore;
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+    }
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS);
+    store_key(&key)?;
+
+    config.salt = Some(hex::encode(salt));
+    config.iterations = PBKDF2_ITERATIONS;
+    config.pending = Some(PendingChange::Encrypt);
+    save_config(data_dir, &config)?;
+    Ok(status(data_dir, false))
+}
+
+/// Schedule decryption for the next start. Asks for the passphrase again so a
+/// session left open can't quietly drop the encryption.
+pub fn disable(
+    data_dir: &Path,
+    db_path: &Path,
+    passphrase: &str,
+) -> Result<EncryptionStatus, String> {
+    let mut config = load_config(data_dir);
+    match config.pending {
+        // Not encrypted yet; just call it off
+        Some(PendingChange::Encrypt) => {
+            config = EncryptionConfig::default();
+            save_config(data_dir, &config)?;
+            delete_stored_key()?;
+            return Ok(status(data_dir, false));
+        }
+        Some(PendingChange::Decrypt) => return Ok(status(data_dir, false)),
+        None if !config.enabled => return Err("The database isn't encrypted".to_string()),
+        None => {}
+    }
+
+    let key = key_for_passphrase(&config, db_path, passphrase)?;
+    store_key(&key)?;
+    config.pending = Some(PendingChange::Decrypt);
+    save_config(data_dir, &config)?;
+    Ok(status(data_dir, false))
+}
+
+/// Put the key for `passphrase` back in the keychain after a locked start
+pub fn unlock(data_dir: &Path, db_path: &Path, passphrase: &str) -> Result<(), String> {
+    let config = load_config(data_dir);
+    let key = key_for_passphrase(&config, db_path, passphrase)?;
+    store_key(&key)
+}
+
+pub fn status(data_dir: &Path, locked: bool) -> EncryptionStatus {
+    let config = load_config(data_dir);
+    EncryptionStatus {
+        available: AVAILABLE,
+        enabled: config.enabled,
+        pending: config.pending,
+        locked,
+    }
+}
+
+/// Value for `PRAGMA key`: a raw key, so SQLCipher skips its own derivation
+pub fn key_pragma(key: &[u8; 32]) -> String {
+    format!("\"x'{}'\"", hex::encode(key))
+}
+
+/// Open a database file with rusqlite, keyed when `key` is given
+pub fn open_keyed(path: &Path, key: Option<&[u8; 32]>) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    if let Some(key) = key {
+        conn.execute_batch(&format!("PRAGMA key = {};", key_pragma(key)))?;
+    }
+    Ok(conn)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        std::num::NonZeroU32::new(iterations.max(1)).unwrap(),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    key
+}
+
+/// Derive the key for `passphrase` and check it opens the database
+fn key_for_passphrase(
+    config: &EncryptionConfig,
+    db_path: &Path,
+    passphrase: &str,
+) -> Result<[u8; 32], String> {
+    let salt = config
+        .salt
+        .as_deref()
+        .and_then(|salt| hex::decode(salt).ok())
+        .ok_or_else(|| "The database isn't encrypted".to_string())?;
+    let key = derive_key(passphrase, &salt, config.iterations);
+    if !opens_with(db_path, &key) {
+        return Err("Wrong database passphrase".to_string());
+    }
+    Ok(key)
+}
+
+fn opens_with(db_path: &Path, key: &[u8; 32]) -> bool {
+    open_keyed(db_path, Some(key))
+        .and_then(|conn| conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())))
+        .is_ok()
+}
+
+/// Re-write every workspace database and backup from one key to the other;
+/// `None` is plaintext. Each file is written next to itself and swapped in
+/// once done. If any file fails, the ones already done are put back, so a
+/// backup that can't be converted doesn't stay behind under the old key.
+fn convert_all(
+    data_dir: &Path,
+    from: Option<&[u8; 32]>,
+    to: Option<&[u8; 32]>,
+) -> Result<(), String> {
+    let mut files = workspaces::database_paths(data_dir);
+    for dir in workspaces::workspace_dirs(data_dir) {
+        let Ok(entries) = std::fs::read_dir(dir.join("backups")) else {
+            continue;
+        };
+        files.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "db")),
+        );
+    }
+
+    for (converted, path) in files.iter().enumerate() {
+        if let Err(e) = convert(path, from, to) {
+            // Put back the ones already done, so all stay under one key
+            for path in &files[..converted] {
+                if let Err(e) = convert(path, to, from) {
+                    log_warn!("DatabaseEncryption", "{}", e);
+                }
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+fn convert(path: &Path, from: Option<&[u8; 32]>, to: Option<&[u8; 32]>) -> Result<(), String> {
+    let converted = path.with_extension("converting");
+    let _ = std::fs::remove_file(&converted);
+
+    let result = open_keyed(path, from).and_then(|conn| {
+        let target_key = to.map(key_pragma).unwrap_or_else(|| "''".to_string());
+        conn.execute_batch(&format!(
+            "ATTACH DATABASE '{}' AS converted KEY {};
+             SELECT sqlcipher_export('converted');
+             DETACH DATABASE converted;",
+            converted.display().to_string().replace('\'', "''"),
+            target_key
+        ))
+    });
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&converted);
+        return Err(format!("Failed to convert {}: {}", path.display(), e));
+    }
+    std::fs::rename(&converted, path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+    // Journal files belong to the old file
+    for suffix in ["-wal", "-shm"] {
+        let mut journal = path.as_os_str().to_owned();
+        journal.push(suffix);
+        let _ = std::fs::remove_file(PathBuf::from(journal));
+    }
+    Ok(())
+}
+
+fn keychain_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(crate::app_paths::APP_IDENTIFIER, KEYCHAIN_KEY_NAME)
+        .map_err(|e| format!("Keychain access failed: {}", e))
+}
+
+fn stored_key() -> Result<Option<[u8; 32]>, String> {
+    match keychain_entry()?.get_secret() {
+        Ok(bytes) => bytes
+            .try_into()
+            .map(Some)
+            .map_err(|_| "Stored database key has unexpected length".to_string()),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read database key from keychain: {}", e)),
+    }
+}
+
+fn store_key(key: &[u8; 32]) -> Result<(), String> {
+    keychain_entry()?
+        .set_secret(key)
+        .map_err(|e| format!("Failed to store database key in keychain: {}", e))
+}
+
+fn delete_stored_key() -> Result<(), String> {
+    match keychain_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!(
+            "Failed to remove database key from keychain: {}",
+            e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "sqlcipher")]
+    fn converts_between_plaintext_and_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("portal_desktop.db");
+        Connection::open(&db_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (value TEXT); INSERT INTO notes VALUES ('kept');")
+            .unwrap();
+        let key = derive_key("correct horse", b"salt", 1_000);
+
+        convert(&db_path, None, Some(&key)).unwrap();
+        assert!(!opens_with(&db_path, &derive_key("wrong", b"salt", 1_000)));
+        assert!(Connection::open(&db_path)
+            .unwrap()
+            .query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+            .is_err());
+        let value: String = open_keyed(&db_path, Some(&key))
+            .unwrap()
+            .query_row("SELECT value FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(value, "kept");
+
+        convert(&db_path, Some(&key), None).unwrap();
+        let value: String = Connection::open(&db_path)
+            .unwrap()
+            .query_row("SELECT value FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(value, "kept");
+    }
+
+    #[test]
+    fn puts_everything_back_when_a_backup_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join(super::super::DATABASE_FILE);
+        let backups = dir.path().join("backups");
+        std::fs::create_dir_all(&backups).unwrap();
+        for path in [&db_path, &backups.join("a.db")] {
+            Connection::open(path)
+                .unwrap()
+                .execute_batch("CREATE TABLE notes (value TEXT);")
+                .unwrap();
+        }
+        std::fs::write(backups.join("b.db"), b"not a database").unwrap();
+        let key = derive_key("correct horse", b"salt", 1_000);
+
+        assert!(convert_all(dir.path(), None, Some(&key)).is_err());
+        for path in [&db_path, &backups.join("a.db")] {
+            assert!(Connection::open(path)
+                .unwrap()
+                .query_row("SELECT count(*) FROM notes", [], |_| Ok(()))
+                .is_ok());
+        }
+    }
+}
@@ -0,0 +1,341 @@
+pub mod backup;
+pub mod data_sync;
+pub mod encryption;
+pub mod maintenance;
+pub mod transfer;
+pub mod workspaces;
+
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use sea_orm_migration::MigratorTrait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+#[cfg(unix)]
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+use crate::domains::settings::services::settings_service::SettingsService;
+use crate::entities::project as project_entity;
+use crate::migrations::runner::run_migrations;
+use crate::migrations::Migrator;
+use crate::{log_error, log_info, log_warn};
+use backup::{BackupInfo, BackupReason, DatabaseBackups};
+use maintenance::IntegrityReport;
+
+pub use project_entity::Model as ProjectModel;
+
+/// The main database, in each workspace's directory
+pub const DATABASE_FILE: &str = "portal_desktop.db";
+
+pub struct DatabaseManager {
+    key: Option<[u8; 32]>,
+    /// Database of the active workspace
+    active: RwLock<&'static OpenDatabase>,
+    /// Every workspace database opened so far, by directory. They stay open
+    /// for the life of the app so references from `get_connection` never
+    /// dangle across a switch, and switching back is instant.
+    opened: tokio::sync::Mutex<HashMap<PathBuf, &'static OpenDatabase>>,
+}
+
+struct OpenDatabase {
+    conn: DatabaseConnection,
+    db_path: PathBuf,
+    backups: DatabaseBackups,
+    /// Result of the last integrity check, starting with the one on opening
+    health: Mutex<IntegrityReport>,
+}
+
+impl DatabaseManager {
+    /// Initialize the database of the workspace in `workspace_dir`, which is
+    /// `data_dir` itself for the default workspace. `key` opens an encrypted
+    /// database; see [`encryption::prepare`].
+    pub async fn new(
+        data_dir: PathBuf,
+        workspace_dir: PathBuf,
+        key: Option<[u8; 32]>,
+    ) -> Result<Self, sea_orm::DbErr> {
+        log_info!("DatabaseManager", "Starting database initialization...");
+
+        std::fs::create_dir_all(&data_dir).map_err(|e| {
+            log_error!("DatabaseManager", "Failed to create data dir: {}", e);
+            sea_orm::DbErr::Custom(format!("Failed to create data dir: {}", e))
+        })?;
+
+        Self::set_dir_permissions(&data_dir);
+
+        // Migrate legacy database from CWD-relative path if present
+        Self::migrate_legacy_database(&data_dir);
+
+        let database = Self::open(&workspace_dir, key).await?;
+
+        log_info!(
+            "DatabaseManager",
+            "Database initialization completed successfully"
+        );
+
+        Ok(DatabaseManager {
+            key,
+            active: RwLock::new(database),
+            opened: tokio::sync::Mutex::new(HashMap::from([(workspace_dir, database)])),
+        })
+    }
+
+    /// Make the workspace in `workspace_dir` the active one, opening and
+    /// migrating its database if this is the first switch to it
+    pub async fn switch_to(&self, workspace_dir: PathBuf) -> Result<(), String> {
+        let mut opened = self.opened.lock().await;
+        let database = match opened.get(&workspace_dir).copied() {
+            Some(database) => database,
+            None => {
+                let database = Self::open(&workspace_dir, self.key)
+                    .await
+                    .map_err(|e| format!("Failed to open workspace database: {}", e))?;
+                opened.insert(workspace_dir, database);
+                database
+            }
+        };
+        *self.active.write().unwrap() = database;
+        Ok(())
+    }
+
+    /// Connect to the database in `dir`, creating it if needed, and bring it
+    /// up to the current schema
+    async fn open(
+        dir: &Path,
+        key: Option<[u8; 32]>,
+    ) -> Result<&'static OpenDatabase, sea_orm::DbErr> {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            log_error!("DatabaseManager", "Failed to create workspace dir: {}", e);
+            sea_orm::DbErr::Custom(format!("Failed to create workspace dir: {}", e))
+        })?;
+        Self::set_dir_permissions(dir);
+
+        let db_path = dir.join(DATABASE_FILE);
+        let existing_database = db_path.exists();
+        let backups = DatabaseBackups::new(db_path.clone(), dir.join("backups"), key);
+        let database_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        log_info!("DatabaseManager", "Database path: {}", db_path.display());
+
+        log_info!("DatabaseManager", "Connecting to database...");
+        let mut options = ConnectOptions::new(database_url);
+        if let Some(key) = key {
+            let pragma = encryption::key_pragma(&key);
+            options.map_sqlx_sqlite_opts(move |opts| opts.pragma("key", pragma.clone()));
+        }
+        let conn = Database::connect(options).await.map_err(|e| {
+            log_error!("DatabaseManager", "Failed to connect to database: {}", e);
+            e
+        })?;
+
+        // A damaged database isn't backed up: the copy would be damaged too,
+        // and rotation could push out the good backups needed to recover
+        let health = Self::startup_health_check(&conn).await;
+        if existing_database && health.ok {
+            Self::backup_before_migrations(&conn, &backups).await;
+        }
+
+        log_info!("DatabaseManager", "Running migrations...");
+        run_migrations(&conn).await.map_err(|e| {
+            log_error!("DatabaseManager", "Failed to run migrations: {}", e);
+            e
+        })?;
+
+        Self::set_file_permissions(&db_path);
+
+        // Leaked on purpose, once per workspace; see `opened`
+        Ok(Box::leak(Box::new(OpenDatabase {
+            conn,
+            db_path,
+            backups,
+            health: Mutex::new(health),
+        })))
+    }
+
+    fn active(&self) -> &'static OpenDatabase {
+        *self.active.read().unwrap()
+    }
+
+    pub fn get_connection(&self) -> &DatabaseConnection {
+        &self.active().conn
+    }
+
+    pub fn get_connection_clone(&self) -> DatabaseConnection {
+        self.active().conn.clone()
+    }
+
+    pub fn backups(&self) -> &DatabaseBackups {
+        &self.active().backups
+    }
+
+    /// Restore a backup over the live database, then bring it up to the
+    /// current schema in case it predates recent migrations.
+    pub async fn restore_backup(&self, file_name: String) -> Result<(), String> {
+        let backups = self.backups().clone();
+        tokio::task::spawn_blocking(move || backups.restore(&file_name, backup_keep()))
+            .await
+            .map_err(|e| format!("Restore task failed: {}", e))??;
+        run_migrations(self.get_connection())
+            .await
+            .map_err(|e| format!("Failed to migrate restored database: {}", e))?;
+        self.check_integrity(true).await.map(|_| ())
+    }
+
+    /// Result of the last integrity check
+    pub fn health(&self) -> IntegrityReport {
+        self.active().health.lock().unwrap().clone()
+    }
+
+    pub async fn check_integrity(&self, quick: bool) -> Result<IntegrityReport, String> {
+        let database = self.active();
+        let report = maintenance::integrity_check(&database.conn, quick).await?;
+        *database.health.lock().unwrap() = report.clone();
+        Ok(report)
+    }
+
+    /// Directory of the active workspace, holding its database and backups
+    pub fn workspace_dir(&self) -> PathBuf {
+        let db_path = &self.active().db_path;
+        db_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| db_path.clone())
+    }
+
+    /// Size of the database file and its write-ahead log, in bytes
+    pub fn file_sizes(&self) -> (u64, u64) {
+        let db_path = &self.active().db_path;
+        let size = |path: &Path| std::fs::metadata(path).map_or(0, |m| m.len());
+        let mut wal = db_path.as_os_str().to_owned();
+        wal.push("-wal");
+        (size(db_path), size(Path::new(&wal)))
+    }
+
+    pub async fn create_backup(&self, reason: BackupReason) -> Result<BackupInfo, String> {
+        let backups = self.backups().clone();
+        tokio::task::spawn_blocking(move || backups.create(reason, backup_keep()))
+            .await
+            .map_err(|e| format!("Backup task failed: {}", e))?
+    }
+
+    async fn startup_health_check(conn: &DatabaseConnection) -> IntegrityReport {
+        let report = maintenance::integrity_check(conn, true)
+            .await
+            .unwrap_or_else(|e| IntegrityReport {
+                ok: false,
+                problems: vec![e],
+                checked_at: chrono::Utc::now(),
+            });
+        if !report.ok {
+            log_error!(
+                "DatabaseManager",
+                "Database failed its integrity check: {}",
+                report.problems.join("; ")
+            );
+        }
+        report
+    }
+
+    /// Snapshot the database when migrations are about to change it, so a
+    /// failed or unwanted upgrade can be rolled back. Best effort: a failed
+    /// backup is logged and startup carries on.
+    async fn backup_before_migrations(conn: &DatabaseConnection, backups: &DatabaseBackups) {
+        match Migrator::get_pending_migrations(conn).await {
+            Ok(pending) if !pending.is_empty() => {
+                log_info!(
+                    "DatabaseManager",
+                    "{} pending migrations, backing up first",
+                    pending.len()
+                );
+                if let Err(e) = backups.create(BackupReason::PreMigration, backup_keep()) {
+                    log_warn!("DatabaseManager", "Pre-migration backup failed: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log_warn!(
+                "DatabaseManager",
+                "Failed to check for pending migrations: {}",
+                e
+            ),
+        }
+    }
+
+    fn migrate_legacy_database(data_dir: &Path) {
+        let new_db = data_dir.join("portal_desktop.db");
+        if new_db.exists() {
+            return;
+        }
+
+        // Data dir of the pre-rename identifier. Copy the whole directory so the
+        // disk-utility DB and any sibling state come across with the main DB.
+        if let Some(legacy_dir) =
+            dirs::data_dir().map(|d| d.join(crate::app_paths::LEGACY_APP_IDENTIFIER))
+        {
+            if legacy_dir.join("portal_desktop.db").exists() {
+                crate::app_paths::copy_files_into(&legacy_dir, data_dir);
+                return;
+            }
+        }
+
+        if let Ok(cwd) = std::env::current_dir() {
+            let legacy_db = cwd.join("data").join("portal_desktop.db");
+            if legacy_db.exists() {
+                if let Err(e) = std::fs::copy(&legacy_db, &new_db) {
+                    log_warn!(
+                        "DatabaseManager",
+                        "Failed to migrate legacy database: {}",
+                        e
+                    );
+                } else {
+                    log_info!(
+                        "DatabaseManager",
+                        "Migrated legacy database from {}",
+                        legacy_db.display()
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    fn set_dir_permissions(path: &Path) {
+        if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(0o700)) {
+            log_warn!(
+                "DatabaseManager",
+                "Failed to set directory permissions: {}",
+                e
+            );
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn set_dir_permissions(_path: &Path) {}
+
+    #[cfg(unix)]
+    fn set_file_permissions(path: &Path) {
+        if path.exists() {
+            if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(0o600)) {
+                log_warn!(
+                    "DatabaseManager",
+                    "Failed to set database file permissions: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn set_file_permissions(_path: &Path) {}
+}
+
+/// Number of backups to keep, from the user's settings
+fn backup_keep() -> usize {
+    SettingsService::new()
+        .load_settings()
+        .map(|settings| settings.app.backups)
+        .unwrap_or_default()
+        .keep
+        .max(1) as usize
+}
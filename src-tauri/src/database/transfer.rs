@@ -0,0 +1,515 @@
+//! Whole-app data bundles for moving to another machine.
+//!
+//! A bundle is one JSON document holding projects, tasks, documents, pipelines,
+//! settings and, when a passphrase is given, credentials re-encrypted under it.
+//! It records the last migration of the build that wrote it; a build that
+//! doesn't know that migration refuses the bundle rather than guess at columns
+//! it has never seen.
+//!
+//! Imports either replace the exported tables outright, keeping the bundle's
+//! IDs, or merge into what's there. Merging gives every imported row a new ID
+//! and rewrites the references between them; projects are matched by path, so
+//! importing the same bundle twice doesn't duplicate them.
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ConnectionTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, Set, TransactionTrait,
+};
+use sea_orm_migration::MigratorTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::domains::credentials::entities as credential;
+use crate::domains::credentials::services::credential_transfer::{self, PortableCredentials};
+use crate::domains::settings::services::settings_schema;
+use crate::domains::settings::services::settings_service::SettingsService;
+use crate::domains::tasks::services::sprint_planner::parse_task_ids;
+use crate::entities::{document, pipeline, project, task};
+use crate::migrations::Migrator;
+
+pub const BUNDLE_FORMAT: &str = "portal-desktop-data";
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataBundle {
+    pub format: String,
+    pub version: u32,
+    /// Last migration of the build that wrote the bundle
+    pub schema_version: String,
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    /// Versioned settings document, as written by `export_settings`
+    pub settings: Option<serde_json::Value>,
+    pub projects: Vec<project::Model>,
+    pub tasks: Vec<task::Model>,
+    pub documents: Vec<document::Model>,
+    pub pipelines: Vec<pipeline::Model>,
+    /// Left out unless the export was given a passphrase
+    pub credentials: Option<PortableCredentials>,
+}
+
+/// The fields checked before the rest of a bundle is parsed
+#[derive(Deserialize)]
+struct BundleHeader {
+    format: String,
+    version: u32,
+    schema_version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Add the bundle's rows alongside existing data
+    Merge,
+    /// Delete the exported tables and load the bundle in their place. Local
+    /// credentials are only replaced when the bundle has credentials.
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TransferSummary {
+    pub projects: usize,
+    pub tasks: usize,
+    pub documents: usize,
+    pub pipelines: usize,
+    pub credentials: usize,
+    pub settings: bool,
+    /// Rows left out: projects and credentials already here when merging, and
+    /// pipelines whose project is missing
+    pub skipped: usize,
+}
+
+/// Collect everything into a bundle. Credentials are included, re-encrypted
+/// under `passphrase`, only when one is given.
+pub async fn export_bundle(
+    conn: &DatabaseConnection,
+    passphrase: Option<&str>,
+) -> Result<DataBundle, String> {
+    let credentials = match passphrase {
+        Some(passphrase) if passphrase.trim().is_empty() => {
+            return Err("The export passphrase can't be empty".to_string())
+        }
+        Some(passphrase) => {
+            let credentials = credential::Entity::find().all(conn).await.map_err(db_err)?;
+            let passphrase = passphrase.to_string();
+            // Reading the master key can block on a keychain prompt
+            Some(
+                tokio::task::spawn_blocking(move || {
+                    credential_transfer::seal(credentials, &passphrase)
+                })
+                .await
+                .map_err(|e| format!("Failed to export credentials: {}", e))??,
+            )
+        }
+        None => None,
+    };
+
+    Ok(DataBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        schema_version: migration_names().pop().unwrap_or_default(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: Utc::now(),
+        settings: Some(settings_schema::to_document(
+            &SettingsService::new().load_settings()?,
+        )?),
+        projects: project::Entity::find().all(conn).await.map_err(db_err)?,
+        tasks: task::Entity::find().all(conn).await.map_err(db_err)?,
+        documents: document::Entity::find().all(conn).await.map_err(db_err)?,
+        pipelines: pipeline::Entity::find().all(conn).await.map_err(db_err)?,
+        credentials,
+    })
+}
+
+/// Parse a bundle, refusing ones this build can't read
+pub fn parse_bundle(json: &str) -> Result<DataBundle, String> {
+    let not_a_bundle = || "Not a Portal Desktop data export".to_string();
+    let header: BundleHeader = serde_json::from_str(json).map_err(|_| not_a_bundle())?;
+    if header.format != BUNDLE_FORMAT {
+        return Err(not_a_bundle());
+    }
+    if header.version > BUNDLE_VERSION {
+        return Err(format!(
+            "This export uses format version {}, this build reads up to {}. Update Portal Desktop before importing it.",
+            header.version, BUNDLE_VERSION
+        ));
+    }
+    if !migration_names().contains(&header.schema_version) {
+        return Err(format!(
+            "This export comes from a newer database schema ({}). Update Portal Desktop before importing it.",
+            header.schema_version
+        ));
+    }
+    serde_json::from_str(json).map_err(|e| format!("Failed to read data export: {}", e))
+}
+
+/// Load a bundle in one transaction; nothing is written if any row fails.
+/// Bundled credentials need the passphrase they were exported with.
+pub async fn import_bundle(
+    conn: &DatabaseConnection,
+    bundle: DataBundle,
+    mode: ImportMode,
+    passphrase: Option<&str>,
+) -> Result<TransferSummary, String> {
+    // Opened first so a wrong passphrase fails before anything changes
+    let credentials = match (bundle.credentials, passphrase) {
+        (Some(portable), Some(passphrase)) => {
+            let passphrase = passphrase.to_string();
+            // Reading the master key can block on a keychain prompt
+            tokio::task::spawn_blocking(move || credential_transfer::open(portable, &passphrase))
+                .await
+                .map_err(|e| format!("Failed to import credentials: {}", e))??
+        }
+        (Some(_), None) => {
+            return Err(
+                "This export includes credentials; enter the passphrase it was exported with"
+                    .to_string(),
+            )
+        }
+        (None, _) => Vec::new(),
+    };
+    // Likewise settings that are invalid or from a newer build
+    let settings = match (mode, bundle.settings) {
+        (ImportMode::Replace, Some(document)) => {
+            Some(SettingsService::new().parse_document(document)?)
+        }
+        _ => None,
+    };
+    let keep_ids = mode == ImportMode::Replace;
+    let mut summary = TransferSummary::default();
+    let txn = conn.begin().await.map_err(db_err)?;
+
+    if keep_ids {
+        pipeline::Entity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(db_err)?;
+        task::Entity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(db_err)?;
+        document::Entity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(db_err)?;
+        project::Entity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(db_err)?;
+        if !credentials.is_empty() {
+            credential::Entity::delete_many()
+                .exec(&txn)
+                .await
+                .map_err(db_err)?;
+        }
+    }
+
+    let mut ids = IdMaps::default();
+
+    // When merging, a project at the same path is the same project
+    let existing_paths: HashMap<String, i32> = if keep_ids {
+        HashMap::new()
+    } else {
+        project::Entity::find()
+            .all(&txn)
+            .await
+            .map_err(db_err)?
+            .into_iter()
+            .map(|project| (project.path, project.id))
+            .collect()
+    };
+    for project in bundle.projects {
+        if let Some(id) = existing_paths.get(&project.path) {
+            ids.projects.insert(project.id, *id);
+            summary.skipped += 1;
+            continue;
+        }
+        let old_id = project.id;
+        let mut active = project.into_active_model();
+        if !keep_ids {
+            active.id = NotSet;
+        }
+        let id = active.insert(&txn).await.map_err(db_err)?.id;
+        ids.projects.insert(old_id, id);
+        summary.projects += 1;
+    }
+
+    for mut document in bundle.documents {
+        let old_id = document.id;
+        document.project_id = document
+            .project_id
+            .and_then(|id| ids.projects.get(&id).copied());
+        let mut active = document.into_active_model();
+        if !keep_ids {
+            active.id = NotSet;
+        }
+        let id = active.insert(&txn).await.map_err(db_err)?.id;
+        ids.documents.insert(old_id, id);
+        summary.documents += 1;
+    }
+
+    for mut pipeline in bundle.pipelines {
+        let Some(project_id) = ids.projects.get(&pipeline.project_id).copied() else {
+            summary.skipped += 1;
+            continue;
+        };
+        let old_id = pipeline.id;
+        pipeline.project_id = project_id;
+        let mut active = pipeline.into_active_model();
+        if !keep_ids {
+            active.id = NotSet;
+        }
+        let id = active.insert(&txn).await.map_err(db_err)?.id;
+        ids.pipelines.insert(old_id, id);
+        summary.pipelines += 1;
+    }
+
+    // Tasks point at each other, so they go in without those references and
+    // get them once every task has its new ID
+    let mut task_links = Vec::new();
+    for mut task in bundle.tasks {
+        task_links.push((
+            task.id,
+            task.parent_id.take(),
+            task.blocked_by.take(),
+            task.blocks.take(),
+        ));
+        task.resource_id = ids.remap_resource(task.resource_type.as_deref(), task.resource_id);
+        let old_id = task.id;
+        let mut active = task.into_active_model();
+        if !keep_ids {
+            active.id = NotSet;
+        }
+        let id = active.insert(&txn).await.map_err(db_err)?.id;
+        ids.tasks.insert(old_id, id);
+        summary.tasks += 1;
+    }
+    for (old_id, parent_id, blocked_by, blocks) in task_links {
+        if parent_id.is_none() && blocked_by.is_none() && blocks.is_none() {
+            continue;
+        }
+        task::ActiveModel {
+            id: Set(ids.tasks[&old_id]),
+            parent_id: Set(parent_id.and_then(|id| ids.tasks.get(&id).copied())),
+            blocked_by: Set(remap_task_ids(blocked_by.as_deref(), &ids.tasks)),
+            blocks: Set(remap_task_ids(blocks.as_deref(), &ids.tasks)),
+            ..Default::default()
+        }
+        .update(&txn)
+        .await
+        .map_err(db_err)?;
+    }
+
+    summary.credentials =
+        import_credentials(&txn, credentials, keep_ids, &mut summary.skipped).await?;
+
+    txn.commit().await.map_err(db_err)?;
+
+    // Settings live in a file, outside the transaction; a merge keeps the
+    // local ones
+    if let Some(settings) = settings {
+        SettingsService::new().save_settings(&settings)?;
+        summary.settings = true;
+    }
+
+    Ok(summary)
+}
+
+async fn import_credentials<C: ConnectionTrait>(
+    conn: &C,
+    credentials: Vec<credential::Model>,
+    keep_all: bool,
+    skipped: &mut usize,
+) -> Result<usize, String> {
+    // When merging, credentials with a known ID or name are already here
+    let (mut existing_ids, mut existing_names) = (HashSet::new(), HashSet::new());
+    if !keep_all {
+        for existing in credential::Entity::find().all(conn).await.map_err(db_err)? {
+            existing_ids.insert(existing.id);
+            existing_names.insert(existing.name.to_lowercase());
+        }
+    }
+
+    let mut imported = 0;
+    for credential in credentials {
+        if existing_ids.contains(&credential.id)
+            || !existing_names.insert(credential.name.to_lowercase())
+        {
+            *skipped += 1;
+            continue;
+        }
+        existing_ids.insert(credential.id.clone());
+        credential
+            .into_active_model()
+            .insert(conn)
+            .await
+            .map_err(db_err)?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+/// Old ID to new ID, per table
+#[derive(Default)]
+struct IdMaps {
+    projects: HashMap<i32, i32>,
+    documents: HashMap<i32, i32>,
+    pipelines: HashMap<i32, i32>,
+    tasks: HashMap<i32, i32>,
+}
+
+impl IdMaps {
+    /// Point a task's linked resource at its imported copy. Links to resources
+    /// that aren't in the bundle, like deployments, are kept as they are.
+    fn remap_resource(
+        &self,
+        resource_type: Option<&str>,
+        resource_id: Option<String>,
+    ) -> Option<String> {
+        let map = match resource_type {
+            Some("project") => &self.projects,
+            Some("document") => &self.documents,
+            Some("pipeline") => &self.pipelines,
+            _ => return resource_id,
+        };
+        let id: i32 = resource_id.as_deref()?.trim().parse().ok()?;
+        map.get(&id).map(|id| id.to_string())
+    }
+}
+
+/// Rewrite a JSON array of task IDs, dropping ones that weren't imported
+fn remap_task_ids(raw: Option<&str>, tasks: &HashMap<i32, i32>) -> Option<String> {
+    raw?;
+    let ids: Vec<i32> = parse_task_ids(raw)
+        .into_iter()
+        .filter_map(|id| tasks.get(&id).copied())
+        .collect();
+    serde_json::to_string(&ids).ok()
+}
+
+fn migration_names() -> Vec<String> {
+    Migrator::migrations()
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect()
+}
+
+fn db_err(e: sea_orm::DbErr) -> String {
+    format!("Database error: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::runner::run_migrations;
+    use sea_orm::{Database, PaginatorTrait};
+
+    fn bundle() -> DataBundle {
+        let project: project::Model = serde_json::from_value(serde_json::json!({
+            "id": 7, "name": "api", "description": null, "path": "/work/api",
+            "status": "active", "build_command": null, "start_command": null,
+            "test_command": null, "output_directory": null, "dev_port": null,
+            "prod_port": null, "starred": false, "open_count": 0, "last_opened": null,
+            "size": 0, "file_count": 0, "git_repository": null, "git_branch": null,
+            "git_commit": null, "has_uncommitted_changes": false, "last_commit": null,
+            "created_at": null, "updated_at": null
+        }))
+        .unwrap();
+        let task = |id: i32, parent_id: Option<i32>, blocked_by: Option<&str>| -> task::Model {
+            serde_json::from_value(serde_json::json!({
+                "id": id, "title": format!("task {}", id), "description": null,
+                "status": "pending", "priority": "medium", "type_": null,
+                "parent_id": parent_id, "resource_id": "7", "resource_type": "project",
+                "due_date": null, "completed_at": null, "created_at": null,
+                "updated_at": null, "estimated_time": null, "actual_time": null,
+                "tags": null, "assignee": null, "recurring_pattern": null,
+                "recurring_interval": null, "recurring_end_date": null,
+                "recurring_last_generated": null, "blocked_by": blocked_by, "blocks": null
+            }))
+            .unwrap()
+        };
+
+        DataBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            schema_version: migration_names().pop().unwrap(),
+            app_version: "test".to_string(),
+            exported_at: Utc::now(),
+            settings: None,
+            projects: vec![project],
+            tasks: vec![
+                task(20, None, None),
+                task(21, Some(20), Some("[20, \"99\"]")),
+            ],
+            documents: Vec::new(),
+            pipelines: Vec::new(),
+            credentials: None,
+        }
+    }
+
+    #[test]
+    fn refuses_bundles_from_newer_builds() {
+        let mut newer = serde_json::to_value(bundle()).unwrap();
+        newer["schema_version"] = "m29990101_000001_from_the_future".into();
+        assert!(parse_bundle(&newer.to_string())
+            .unwrap_err()
+            .contains("newer database schema"));
+
+        let mut newer = serde_json::to_value(bundle()).unwrap();
+        newer["version"] = (BUNDLE_VERSION + 1).into();
+        assert!(parse_bundle(&newer.to_string())
+            .unwrap_err()
+            .contains("format version"));
+
+        assert!(parse_bundle("{\"projects\": []}").is_err());
+        assert!(parse_bundle(&serde_json::to_string(&bundle()).unwrap()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn merging_twice_reuses_projects_and_remaps_task_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("test.db").display());
+        let conn = Database::connect(&url).await.unwrap();
+        run_migrations(&conn).await.unwrap();
+
+        let first = import_bundle(&conn, bundle(), ImportMode::Merge, None)
+            .await
+            .unwrap();
+        assert_eq!((first.projects, first.tasks, first.skipped), (1, 2, 0));
+        let second = import_bundle(&conn, bundle(), ImportMode::Merge, None)
+            .await
+            .unwrap();
+        assert_eq!((second.projects, second.tasks, second.skipped), (0, 2, 1));
+
+        let projects = project::Entity::find().all(&conn).await.unwrap();
+        assert_eq!(projects.len(), 1);
+        let project_id = projects[0].id.to_string();
+
+        let tasks = task::Entity::find().all(&conn).await.unwrap();
+        assert_eq!(tasks.len(), 4);
+        for child in tasks.iter().filter(|task| task.title == "task 21") {
+            let parent = tasks
+                .iter()
+                .find(|task| Some(task.id) == child.parent_id)
+                .unwrap();
+            assert_eq!(parent.title, "task 20");
+            assert_eq!(
+                child.blocked_by.as_deref(),
+                Some(format!("[{}]", parent.id).as_str())
+            );
+            assert_eq!(child.resource_id.as_deref(), Some(project_id.as_str()));
+        }
+
+        let replaced = import_bundle(&conn, bundle(), ImportMode::Replace, None)
+            .await
+            .unwrap();
+        assert_eq!((replaced.projects, replaced.tasks), (1, 2));
+        assert_eq!(task::Entity::find().count(&conn).await.unwrap(), 2);
+        assert!(task::Entity::find_by_id(21)
+            .one(&conn)
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,253 @@
+//! Isolated workspaces, each with its own database and backups, e.g. one per
+//! client so their projects, tasks and credentials never mix.
+//!
+//! The default workspace is the app data dir itself, so installs from before
+//! workspaces existed carry on unchanged; others live in
+//! `<app data>/workspaces/<id>`. Which one is active is kept in
+//! `workspaces.json`. Settings, the OS keychain entries and the database
+//! encryption key are shared by all workspaces.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::DATABASE_FILE;
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+/// Event emitted once the app has switched workspaces
+pub const WORKSPACE_SWITCHED_EVENT: &str = "workspace-switched";
+
+const REGISTRY_FILE: &str = "workspaces.json";
+const WORKSPACES_DIR: &str = "workspaces";
+
+/// Serializes read-modify-write cycles on the registry file
+static REGISTRY_LOCK: Lazy<Mutex<()>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceList {
+    pub active: String,
+    pub workspaces: Vec<Workspace>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    #[serde(default)]
+    active: Option<String>,
+    /// Every workspace but the default one
+    #[serde(default)]
+    workspaces: Vec<Workspace>,
+}
+
+pub struct Workspaces {
+    data_dir: PathBuf,
+}
+
+impl Workspaces {
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self { data_dir }
+    }
+
+    /// All workspaces, the default one first
+    pub fn list(&self) -> WorkspaceList {
+        let registry = self.load();
+        let active = self.active_id(&registry);
+        let mut workspaces = vec![default_workspace()];
+        workspaces.extend(registry.workspaces);
+        WorkspaceList { active, workspaces }
+    }
+
+    pub fn active(&self) -> Workspace {
+        let list = self.list();
+        list.workspaces
+            .into_iter()
+            .find(|workspace| workspace.id == list.active)
+            .unwrap_or_else(default_workspace)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Workspace, String> {
+        self.list()
+            .workspaces
+            .into_iter()
+            .find(|workspace| workspace.id == id)
+            .ok_or_else(|| format!("Workspace not found: {}", id))
+    }
+
+    /// Add a workspace; its database is created the first time it's opened
+    pub fn create(&self, name: &str) -> Result<Workspace, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Workspace name can't be empty".to_string());
+        }
+
+        let _guard = REGISTRY_LOCK.lock().unwrap();
+        let mut registry = self.load();
+        let taken: Vec<Workspace> = std::iter::once(default_workspace())
+            .chain(registry.workspaces.iter().cloned())
+            .collect();
+        if taken
+            .iter()
+            .any(|workspace| workspace.name.eq_ignore_ascii_case(name))
+        {
+            return Err(format!("A workspace named \"{}\" already exists", name));
+        }
+
+        let base = slug(name);
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while taken.iter().any(|workspace| workspace.id == id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        let workspace = Workspace {
+            id,
+            name: name.to_string(),
+            created_at: Utc::now(),
+        };
+        std::fs::create_dir_all(self.dir(&workspace.id))
+            .map_err(|e| format!("Failed to create workspace directory: {}", e))?;
+        registry.workspaces.push(workspace.clone());
+        self.save(&registry)?;
+        Ok(workspace)
+    }
+
+    /// Record `id` as the workspace to open, now and on later starts
+    pub fn set_active(&self, id: &str) -> Result<Workspace, String> {
+        let workspace = self.get(id)?;
+        let _guard = REGISTRY_LOCK.lock().unwrap();
+        let mut registry = self.load();
+        registry.active = Some(workspace.id.clone());
+        self.save(&registry)?;
+        Ok(workspace)
+    }
+
+    /// Directory holding a workspace's database and backups
+    pub fn dir(&self, id: &str) -> PathBuf {
+        workspace_dir(&self.data_dir, id)
+    }
+
+    fn active_id(&self, registry: &Registry) -> String {
+        registry
+            .active
+            .clone()
+            .filter(|id| {
+                registry
+                    .workspaces
+                    .iter()
+                    .any(|workspace| &workspace.id == id)
+            })
+            .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+    }
+
+    fn load(&self) -> Registry {
+        std::fs::read_to_string(self.data_dir.join(REGISTRY_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, registry: &Registry) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(registry)
+            .map_err(|e| format!("Failed to serialize workspaces: {}", e))?;
+        std::fs::write(self.data_dir.join(REGISTRY_FILE), content)
+            .map_err(|e| format!("Failed to save workspaces: {}", e))
+    }
+}
+
+/// Directories of every workspace, whether or not its database exists yet
+pub fn workspace_dirs(data_dir: &Path) -> Vec<PathBuf> {
+    Workspaces::new(data_dir.to_path_buf())
+        .list()
+        .workspaces
+        .iter()
+        .map(|workspace| workspace_dir(data_dir, &workspace.id))
+        .collect()
+}
+
+/// Database files of every workspace that has one
+pub fn database_paths(data_dir: &Path) -> Vec<PathBuf> {
+    workspace_dirs(data_dir)
+        .into_iter()
+        .map(|dir| dir.join(DATABASE_FILE))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+fn workspace_dir(data_dir: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_WORKSPACE {
+        data_dir.to_path_buf()
+    } else {
+        data_dir.join(WORKSPACES_DIR).join(id)
+    }
+}
+
+fn default_workspace() -> Workspace {
+    Workspace {
+        id: DEFAULT_WORKSPACE.to_string(),
+        name: "Default".to_string(),
+        created_at: DateTime::<Utc>::UNIX_EPOCH,
+    }
+}
+
+/// Directory-safe id from a workspace name
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    match slug {
+        "" => "workspace".to_string(),
+        // Would alias the app data dir
+        DEFAULT_WORKSPACE => "default-2".to_string(),
+        slug => slug.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugs_are_directory_safe() {
+        assert_eq!(slug("Acme Corp."), "acme-corp");
+        assert_eq!(slug("../../etc"), "etc");
+        assert_eq!(slug("Ünïcode"), "n-code");
+        assert_eq!(slug("!!!"), "workspace");
+        assert_eq!(slug("Default"), "default-2");
+    }
+
+    #[test]
+    fn creates_and_switches_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspaces = Workspaces::new(dir.path().to_path_buf());
+        assert_eq!(workspaces.active().id, DEFAULT_WORKSPACE);
+        assert_eq!(workspaces.dir(DEFAULT_WORKSPACE), dir.path());
+
+        let work = workspaces.create("Work").unwrap();
+        assert_eq!(work.id, "work");
+        assert!(workspaces.dir("work").is_dir());
+        assert!(workspaces.create("work").is_err());
+        assert!(workspaces.create("  ").is_err());
+        // Same slug, different name
+        assert_eq!(workspaces.create("Work!").unwrap().id, "work-2");
+
+        workspaces.set_active("work").unwrap();
+        assert_eq!(workspaces.active().name, "Work");
+        assert!(workspaces.set_active("missing").is_err());
+        assert_eq!(workspace_dirs(dir.path()).len(), 3);
+    }
+}
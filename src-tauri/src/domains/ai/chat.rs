@@ -1,7 +1,9 @@
 pub use crate::domains::ai::message::ChatMessage;
 use crate::domains::ai::message::ChatMessage as ChatTurn;
+use crate::domains::ai::prompt_templates::{PromptTemplateRef, PromptTemplateService};
 use crate::domains::ai::providers::ProviderType;
 use crate::domains::ai::services::AIService;
+use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::State;
@@ -16,12 +18,16 @@ pub struct SendMessageRequest {
     pub max_tokens: Option<u32>,
     pub model: Option<String>,
     pub llm_provider: Option<String>,
+    /// Render the message through a saved prompt template
+    #[serde(default)]
+    pub prompt_template: Option<PromptTemplateRef>,
 }
 
 /// Send a message to the AI provider
 pub async fn send_message(
     request: SendMessageRequest,
     ai_service: State<'_, Arc<AIService>>,
+    db: DatabaseConnection,
 ) -> Result<String, String> {
     let provider = request.provider;
     let options = crate::domains::ai::providers::GenerationOptions {
@@ -31,13 +37,25 @@ pub async fn send_message(
         model: request.model,
         llm_provider: request.llm_provider,
         extra_options: None,
+        feature: Some("chat".to_string()),
+        bypass_cache: true,
     };
 
-    let mut messages = request.history;
-    messages.push(ChatTurn {
-        role: "user".to_string(),
-        content: request.message,
-    });
+    let messages = match &request.prompt_template {
+        Some(template) => {
+            PromptTemplateService::new(db)
+                .chat_messages(template, request.history, request.message)
+                .await?
+        }
+        None => {
+            let mut messages = request.history;
+            messages.push(ChatTurn {
+                role: "user".to_string(),
+                content: request.message,
+            });
+            messages
+        }
+    };
 
     let result = ai_service
         .generate_chat(&messages, Some(options), provider)
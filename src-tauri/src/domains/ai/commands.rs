@@ -6,21 +6,39 @@ use crate::domains::ai::chat_title::{
     should_apply_generated_title, smart_titles_enabled, PLACEHOLDER_CHAT,
 };
 use crate::domains::ai::conversation::{
-    Conversation, ConversationMessage, ConversationWithMessages,
+    history_through, Conversation, ConversationMessage, ConversationWithMessages,
+};
+use crate::domains::ai::embeddings::{
+    EmbedTextsResponse, Embedder, EmbeddingIndexReport, EmbeddingItem, EmbeddingService,
+    SemanticMatch, SemanticSearchQuery,
+};
+use crate::domains::ai::local_models::{
+    LlamaCppRuntime, LlamaCppStatus, LocalModel, LocalModelProgress, LOCAL_MODEL_PROGRESS_EVENT,
 };
 use crate::domains::ai::logging::{AILog, LogFilters};
 use crate::domains::ai::message::ChatMessage;
 use crate::domains::ai::platform_config::PlatformConfig;
+use crate::domains::ai::prompt_templates::{
+    PromptTemplate, PromptTemplateInput, PromptTemplateRef, PromptTemplateService,
+    PromptTemplateVersion, RenderedPrompt,
+};
 use crate::domains::ai::providers::{
     AIError, AgentPlatformProvider, ConfigurationStatus, GenerationOptions, GenerationResult,
     ProviderConfig, ProviderType,
 };
-use crate::domains::ai::services::{AIService, AISettingsService};
+use crate::domains::ai::response_cache::{ResponseCacheSettings, ResponseCacheStats};
+use crate::domains::ai::services::{AIService, AISettingsService, FallbackTarget, FeatureRoute};
+use crate::domains::ai::structured::{StructuredRequest, StructuredResult};
+use crate::domains::ai::tool_runner::{ToolCallResolution, ToolChatResponse, ToolRun, ToolRunner};
+use crate::domains::ai::tools::ToolSpec;
+use crate::domains::ai::usage::{UsageBucket, UsageStatsQuery};
+use crate::domains::sdk::download::InstallProgress;
 use reqwest::Client;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
-    QuerySelect, Set,
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{Emitter, State};
 // Use the centralized logger from utils
@@ -182,8 +200,23 @@ pub async fn generate_ai_text_with_system(
     user_message: String,
     options: Option<GenerationOptions>,
     provider_type: Option<ProviderType>,
+    prompt_template: Option<PromptTemplateRef>,
     ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
 ) -> Result<GenerationResult, String> {
+    let (system_message, user_message) = match prompt_template {
+        Some(template) => {
+            let rendered = PromptTemplateService::new(db_manager.get_connection_clone())
+                .render(&template, Some(&user_message))
+                .await?;
+            (
+                rendered.system.unwrap_or(system_message),
+                rendered.user.unwrap_or(user_message),
+            )
+        }
+        None => (system_message, user_message),
+    };
+
     ai_service
         .generate_with_system(&system_message, &user_message, options, provider_type)
         .await
@@ -199,6 +232,20 @@ pub async fn generate_ai_text_with_system(
         })
 }
 
+/// Generate JSON that validates against a JSON schema, retrying when the
+/// reply is malformed or breaks the schema
+#[tauri::command]
+pub async fn generate_ai_structured(
+    request: StructuredRequest,
+    options: Option<GenerationOptions>,
+    provider_type: Option<ProviderType>,
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<StructuredResult, String> {
+    ai_service
+        .generate_structured(&request, options, provider_type)
+        .await
+}
+
 /// Send a message to AI (chat) - non-streaming
 #[tauri::command]
 pub async fn ai_send_message(
@@ -210,7 +257,9 @@ pub async fn ai_send_message(
     max_tokens: Option<u32>,
     model: Option<String>,
     llm_provider: Option<String>,
+    prompt_template: Option<PromptTemplateRef>,
     ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
 ) -> Result<String, String> {
     let request = chat::SendMessageRequest {
         message,
@@ -221,8 +270,9 @@ pub async fn ai_send_message(
         max_tokens,
         model,
         llm_provider,
+        prompt_template,
     };
-    chat::send_message(request, ai_service).await
+    chat::send_message(request, ai_service, db_manager.get_connection_clone()).await
 }
 
 /// Send a message to AI (chat) with streaming support
@@ -237,6 +287,7 @@ pub async fn ai_send_message_stream(
     model: Option<String>,
     llm_provider: Option<String>,
     stream_id: String, // Unique ID for this stream
+    prompt_template: Option<PromptTemplateRef>,
     app_handle: tauri::AppHandle,
     ai_service: State<'_, Arc<AIService>>,
     db_manager: State<'_, Arc<DatabaseManager>>,
@@ -309,26 +360,107 @@ pub async fn ai_send_message_stream(
         model,
         llm_provider,
         extra_options: None,
+        feature: Some("chat".to_string()),
+        bypass_cache: true,
     };
 
-    let mut messages = history;
-    messages.push(ChatMessage {
-        role: "user".to_string(),
-        content: message,
-    });
+    let messages = chat_messages(
+        db_manager.get_connection_clone(),
+        prompt_template.as_ref(),
+        history,
+        message,
+    )
+    .await?;
+
+    let final_title = async {
+        let conv_id = conversation_id.as_ref()?;
+        ConversationEntity::find_by_id(conv_id)
+            .one(db_manager.get_connection())
+            .await
+            .ok()
+            .flatten()
+            .map(|m| m.title)
+    };
+
+    stream_reply(
+        &app_handle,
+        &ai_service,
+        &stream_id,
+        &messages,
+        options,
+        provider,
+        final_title,
+    )
+    .await
+    .map(StreamedReply::into_content)
+}
+
+/// History plus the new user message, rendered through the prompt template
+/// when one is given
+async fn chat_messages(
+    db: DatabaseConnection,
+    prompt_template: Option<&PromptTemplateRef>,
+    mut history: Vec<ChatMessage>,
+    message: String,
+) -> Result<Vec<ChatMessage>, String> {
+    match prompt_template {
+        Some(template) => {
+            PromptTemplateService::new(db)
+                .chat_messages(template, history, message)
+                .await
+        }
+        None => {
+            history.push(ChatMessage {
+                role: "user".to_string(),
+                content: message,
+            });
+            Ok(history)
+        }
+    }
+}
+
+/// How a streamed reply ended
+enum StreamedReply {
+    Complete(String),
+    /// Stopped by `cancel_ai_stream`, with the text streamed until then
+    Cancelled(String),
+}
 
-    let provider_type = provider;
+impl StreamedReply {
+    fn into_content(self) -> String {
+        match self {
+            StreamedReply::Complete(content) | StreamedReply::Cancelled(content) => content,
+        }
+    }
+}
 
-    let mut full_response = String::new();
+/// Stream a reply for `messages` as `ai-stream-chunk-{stream_id}` events and
+/// finish with `ai-stream-complete-{stream_id}`, or `ai-stream-cancelled-{id}`
+/// carrying the partial text if `cancel_ai_stream` stops it.
+async fn stream_reply(
+    app_handle: &tauri::AppHandle,
+    ai_service: &AIService,
+    stream_id: &str,
+    messages: &[ChatMessage],
+    options: GenerationOptions,
+    provider: Option<ProviderType>,
+    final_title: impl std::future::Future<Output = Option<String>>,
+) -> Result<StreamedReply, String> {
+    let partial = Arc::new(std::sync::Mutex::new(String::new()));
+    let partial_clone = partial.clone();
     let app_handle_clone = app_handle.clone();
-    let stream_id_clone = stream_id.clone();
+    let stream_id_clone = stream_id.to_string();
     let result = ai_service
-        .generate_chat_stream(
-            &messages,
+        .generate_chat_stream_cancellable(
+            stream_id,
+            messages,
             Some(options),
-            provider_type,
+            provider,
             Box::new(move |chunk: String| {
-                full_response.push_str(&chunk);
+                partial_clone
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push_str(&chunk);
                 app_handle_clone
                     .emit(&format!("ai-stream-chunk-{}", stream_id_clone), &chunk)
                     .map_err(|e| AIError::GenericError(format!("Failed to emit event: {}", e)))?;
@@ -337,37 +469,544 @@ pub async fn ai_send_message_stream(
         )
         .await;
 
-    let final_title = if let Some(conv_id) = &conversation_id {
-        let db = db_manager.get_connection();
-        ConversationEntity::find_by_id(conv_id)
-            .one(db)
-            .await
-            .ok()
-            .flatten()
-            .map(|m| m.title)
-    } else {
-        None
-    };
-
     match result {
-        Ok(gen_result) => {
-            let complete_payload = if let Some(title) = final_title {
-                serde_json::json!({ "content": gen_result.content, "title": title })
-            } else {
-                serde_json::json!({ "content": gen_result.content })
-            };
+        Ok(Some(gen_result)) => {
+            let mut complete_payload = serde_json::json!({
+                "content": gen_result.content,
+                "served_by": gen_result.served_by,
+                "fell_back_from": gen_result.fell_back_from,
+            });
+            if let Some(title) = final_title.await {
+                complete_payload["title"] = serde_json::json!(title);
+            }
             app_handle
                 .emit(
                     &format!("ai-stream-complete-{}", stream_id),
                     &complete_payload,
                 )
                 .map_err(|e| format!("Failed to emit completion event: {}", e))?;
-            Ok(gen_result.content)
+            Ok(StreamedReply::Complete(gen_result.content))
+        }
+        Ok(None) => {
+            let content = partial.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            log_info!("AI", "Stream {} cancelled", stream_id);
+            let _ = app_handle.emit(
+                &format!("ai-stream-cancelled-{}", stream_id),
+                serde_json::json!({ "content": content }),
+            );
+            Ok(StreamedReply::Cancelled(content))
         }
         Err(e) => Err(format!("AI generation error: {}", e)),
     }
 }
 
+/// Stop a streaming reply started by `ai_send_message_stream` or
+/// `regenerate_last_message`. Returns false if the stream already ended.
+#[tauri::command]
+pub async fn cancel_ai_stream(
+    stream_id: String,
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<bool, String> {
+    Ok(ai_service.cancel_stream(&stream_id))
+}
+
+/// Stream a new reply for the conversation's last user message, replaying
+/// the history before it, and put it in place of the replies after that
+/// message. A failed or cancelled regeneration leaves the conversation as it
+/// was.
+#[tauri::command]
+pub async fn regenerate_last_message(
+    conversation_id: String,
+    stream_id: String,
+    provider: Option<ProviderType>,
+    options: Option<GenerationOptions>,
+    app_handle: tauri::AppHandle,
+    ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<String, String> {
+    let db = db_manager.get_connection();
+    let stored = ConversationMessageEntity::find()
+        .filter(ConversationMessageColumn::ConversationId.eq(&conversation_id))
+        .order_by_asc(ConversationMessageColumn::Sequence)
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to load messages: {}", e))?;
+
+    let last_user = stored
+        .iter()
+        .rposition(|m| m.role == "user")
+        .ok_or_else(|| "Conversation has no message to regenerate a reply for".to_string())?;
+
+    let messages: Vec<ChatMessage> = stored[..=last_user]
+        .iter()
+        .map(|m| ChatMessage {
+            role: m.role.clone(),
+            content: m.content.clone(),
+        })
+        .collect();
+
+    let content = match stream_reply(
+        &app_handle,
+        &ai_service,
+        &stream_id,
+        &messages,
+        options.unwrap_or_default(),
+        provider,
+        async { None },
+    )
+    .await?
+    {
+        StreamedReply::Complete(content) => content,
+        StreamedReply::Cancelled(content) => return Ok(content),
+    };
+
+    // Replies after the last user message are replaced by the regenerated one
+    let stale: Vec<String> = stored[last_user + 1..]
+        .iter()
+        .map(|m| m.id.clone())
+        .collect();
+    let reply = ConversationMessage::new(
+        conversation_id.clone(),
+        "assistant".to_string(),
+        content.clone(),
+        stored[last_user].sequence + 1,
+    );
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    if !stale.is_empty() {
+        ConversationMessageEntity::delete_many()
+            .filter(ConversationMessageColumn::Id.is_in(stale))
+            .exec(&txn)
+            .await
+            .map_err(|e| format!("Failed to remove previous reply: {}", e))?;
+    }
+    ConversationMessageEntity::insert(ConversationMessageActiveModel {
+        id: Set(reply.id),
+        conversation_id: Set(reply.conversation_id),
+        role: Set(reply.role),
+        content: Set(reply.content),
+        timestamp: Set(reply.timestamp),
+        sequence: Set(reply.sequence),
+    })
+    .exec_without_returning(&txn)
+    .await
+    .map_err(|e| format!("Failed to save regenerated reply: {}", e))?;
+    ConversationEntity::update_many()
+        .col_expr(
+            ConversationColumn::UpdatedAt,
+            Expr::value(chrono::Utc::now().to_rfc3339()),
+        )
+        .filter(ConversationColumn::Id.eq(&conversation_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| format!("Failed to update conversation: {}", e))?;
+    txn.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(content)
+}
+
+/// Token and cost totals from the AI usage log, grouped by day, provider,
+/// model or feature
+#[tauri::command]
+pub async fn ai_get_usage_stats(
+    query: Option<UsageStatsQuery>,
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<Vec<UsageBucket>, String> {
+    ai_service
+        .usage_log()
+        .ok_or_else(|| "AI usage tracking is not initialized".to_string())?
+        .stats(query.unwrap_or_default())
+        .await
+}
+
+/// Embed texts with the configured embedding model (provider, then Ollama)
+#[tauri::command]
+pub async fn embed_texts(
+    texts: Vec<String>,
+    model: Option<String>,
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<EmbedTextsResponse, String> {
+    let embedder = Embedder::new(ai_service.inner().clone(), model);
+    let embeddings = embedder.embed(&texts).await?;
+    Ok(EmbedTextsResponse {
+        model: embedder.model().to_string(),
+        embeddings,
+    })
+}
+
+/// Semantic search over tasks, documents, learned patterns and any custom
+/// namespaces indexed with `index_embeddings`
+#[tauri::command]
+pub async fn semantic_search(
+    query: SemanticSearchQuery,
+    model: Option<String>,
+    ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<SemanticMatch>, String> {
+    EmbeddingService::new(
+        db_manager.get_connection_clone(),
+        ai_service.inner().clone(),
+        model,
+    )
+    .search(query)
+    .await
+}
+
+/// Embed items into a custom namespace; unchanged items are skipped
+#[tauri::command]
+pub async fn index_embeddings(
+    namespace: String,
+    items: Vec<EmbeddingItem>,
+    model: Option<String>,
+    ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<EmbeddingIndexReport, String> {
+    EmbeddingService::new(
+        db_manager.get_connection_clone(),
+        ai_service.inner().clone(),
+        model,
+    )
+    .index(&namespace, items)
+    .await
+}
+
+/// Drop stored embeddings for some items, or the whole namespace
+#[tauri::command]
+pub async fn remove_embeddings(
+    namespace: String,
+    item_ids: Option<Vec<String>>,
+    ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<u64, String> {
+    EmbeddingService::new(
+        db_manager.get_connection_clone(),
+        ai_service.inner().clone(),
+        None,
+    )
+    .remove(&namespace, item_ids)
+    .await
+}
+
+/// GGUF models available to the llama.cpp backend
+#[tauri::command]
+pub async fn list_local_models(
+    runtime: State<'_, Arc<LlamaCppRuntime>>,
+) -> Result<Vec<LocalModel>, String> {
+    runtime.list_models()
+}
+
+/// Download a GGUF model, emitting `local-model-progress` as it goes.
+/// `name` defaults to the file name in the URL.
+#[tauri::command]
+pub async fn download_local_model(
+    url: String,
+    name: Option<String>,
+    app_handle: tauri::AppHandle,
+    runtime: State<'_, Arc<LlamaCppRuntime>>,
+) -> Result<LocalModel, String> {
+    let target = name.clone().unwrap_or_else(|| url.clone());
+    let progress = forward_local_model_progress(app_handle, target);
+    runtime.download_model(&url, name, progress).await
+}
+
+#[tauri::command]
+pub async fn delete_local_model(
+    name: String,
+    runtime: State<'_, Arc<LlamaCppRuntime>>,
+) -> Result<(), String> {
+    runtime.delete_model(&name).await
+}
+
+/// Download the llama.cpp server for this platform; progress is reported
+/// with target `runtime`
+#[tauri::command]
+pub async fn install_llama_cpp_runtime(
+    app_handle: tauri::AppHandle,
+    runtime: State<'_, Arc<LlamaCppRuntime>>,
+) -> Result<String, String> {
+    let progress = forward_local_model_progress(app_handle, "runtime".to_string());
+    runtime
+        .install_runtime(progress)
+        .await
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn get_llama_cpp_status(
+    runtime: State<'_, Arc<LlamaCppRuntime>>,
+) -> Result<LlamaCppStatus, String> {
+    Ok(runtime.status().await)
+}
+
+/// Stop the llama.cpp server to free the model's memory
+#[tauri::command]
+pub async fn stop_llama_cpp_server(
+    runtime: State<'_, Arc<LlamaCppRuntime>>,
+) -> Result<bool, String> {
+    Ok(runtime.stop().await)
+}
+
+/// Sender whose progress updates are emitted as `local-model-progress` events
+fn forward_local_model_progress(
+    app_handle: tauri::AppHandle,
+    target: String,
+) -> tokio::sync::mpsc::UnboundedSender<InstallProgress> {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<InstallProgress>();
+    tauri::async_runtime::spawn(async move {
+        while let Some(progress) = receiver.recv().await {
+            let _ = app_handle.emit(
+                LOCAL_MODEL_PROGRESS_EVENT,
+                LocalModelProgress {
+                    target: target.clone(),
+                    progress,
+                },
+            );
+        }
+    });
+    sender
+}
+
+/// Prompt templates by name, optionally filtered to one use-case tag
+#[tauri::command]
+pub async fn list_prompt_templates(
+    tag: Option<String>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<PromptTemplate>, String> {
+    PromptTemplateService::new(db_manager.get_connection_clone())
+        .list(tag)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_prompt_template(
+    id: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<PromptTemplate, String> {
+    PromptTemplateService::new(db_manager.get_connection_clone())
+        .get(&id)
+        .await
+}
+
+#[tauri::command]
+pub async fn create_prompt_template(
+    input: PromptTemplateInput,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<PromptTemplate, String> {
+    PromptTemplateService::new(db_manager.get_connection_clone())
+        .create(input)
+        .await
+}
+
+/// Save edits to a prompt template as its next version
+#[tauri::command]
+pub async fn update_prompt_template(
+    id: String,
+    input: PromptTemplateInput,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<PromptTemplate, String> {
+    PromptTemplateService::new(db_manager.get_connection_clone())
+        .update(&id, input)
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_prompt_template(
+    id: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    PromptTemplateService::new(db_manager.get_connection_clone())
+        .delete(&id)
+        .await
+}
+
+/// Saved versions of a prompt template, newest first
+#[tauri::command]
+pub async fn list_prompt_template_versions(
+    id: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<PromptTemplateVersion>, String> {
+    PromptTemplateService::new(db_manager.get_connection_clone())
+        .versions(&id)
+        .await
+}
+
+#[tauri::command]
+pub async fn restore_prompt_template_version(
+    id: String,
+    version: i32,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<PromptTemplate, String> {
+    PromptTemplateService::new(db_manager.get_connection_clone())
+        .restore(&id, version)
+        .await
+}
+
+/// Preview a template with the given variables without generating
+#[tauri::command]
+pub async fn render_prompt_template(
+    template: PromptTemplateRef,
+    input: Option<String>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<RenderedPrompt, String> {
+    PromptTemplateService::new(db_manager.get_connection_clone())
+        .render(&template, input.as_deref())
+        .await
+}
+
+/// Backends tried in order when the requested one fails
+#[tauri::command]
+pub async fn get_ai_fallback_chain(
+    settings_service: State<'_, Arc<AISettingsService>>,
+) -> Result<Vec<FallbackTarget>, String> {
+    settings_service.get_fallback_chain()
+}
+
+#[tauri::command]
+pub async fn set_ai_fallback_chain(
+    chain: Vec<FallbackTarget>,
+    settings_service: State<'_, Arc<AISettingsService>>,
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<Vec<FallbackTarget>, String> {
+    settings_service.set_fallback_chain(chain)?;
+    let chain = settings_service.get_fallback_chain()?;
+    ai_service.set_fallback_chain(chain.clone()).await;
+    Ok(chain)
+}
+
+/// Backend and model per app feature
+#[tauri::command]
+pub async fn get_ai_feature_routes(
+    settings_service: State<'_, Arc<AISettingsService>>,
+) -> Result<HashMap<String, FeatureRoute>, String> {
+    settings_service.get_feature_routes()
+}
+
+#[tauri::command]
+pub async fn set_ai_feature_routes(
+    routes: HashMap<String, FeatureRoute>,
+    settings_service: State<'_, Arc<AISettingsService>>,
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<HashMap<String, FeatureRoute>, String> {
+    settings_service.set_feature_routes(routes)?;
+    let routes = settings_service.get_feature_routes()?;
+    ai_service.set_feature_routes(routes.clone()).await;
+    Ok(routes)
+}
+
+#[tauri::command]
+pub async fn get_ai_response_cache_settings(
+    settings_service: State<'_, Arc<AISettingsService>>,
+) -> Result<ResponseCacheSettings, String> {
+    settings_service.get_response_cache_settings()
+}
+
+#[tauri::command]
+pub async fn set_ai_response_cache_settings(
+    settings: ResponseCacheSettings,
+    settings_service: State<'_, Arc<AISettingsService>>,
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<ResponseCacheSettings, String> {
+    settings_service.set_response_cache_settings(settings.clone())?;
+    ai_service.configure_response_cache(settings.clone());
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn get_ai_response_cache_stats(
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<ResponseCacheStats, String> {
+    Ok(ai_service.response_cache_stats())
+}
+
+/// Drop every cached reply. Returns how many were dropped.
+#[tauri::command]
+pub async fn clear_ai_response_cache(
+    ai_service: State<'_, Arc<AIService>>,
+) -> Result<usize, String> {
+    Ok(ai_service.clear_response_cache())
+}
+
+/// Send a message and let the assistant call registered app tools. Calls the
+/// autonomy domain doesn't clear pause the run until `ai_resolve_tool_call`.
+#[tauri::command]
+pub async fn ai_send_message_with_tools(
+    request: chat::SendMessageRequest,
+    app_handle: tauri::AppHandle,
+    ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<ToolChatResponse, String> {
+    let options = GenerationOptions {
+        temperature: request.temperature,
+        max_tokens: request.max_tokens,
+        timeout_ms: None,
+        model: request.model,
+        llm_provider: request.llm_provider,
+        extra_options: None,
+        feature: Some("chat".to_string()),
+        bypass_cache: true,
+    };
+    let messages = chat_messages(
+        db_manager.get_connection_clone(),
+        request.prompt_template.as_ref(),
+        request.history,
+        request.message,
+    )
+    .await?;
+
+    ToolRunner::new(
+        app_handle,
+        ai_service.inner().clone(),
+        db_manager.inner().clone(),
+    )
+    .drive(ToolRun::new(messages, options, request.provider))
+    .await
+}
+
+/// Approve or reject a tool call waiting on the user and continue its chat
+#[tauri::command]
+pub async fn ai_resolve_tool_call(
+    call_id: String,
+    approved: bool,
+    feedback: Option<String>,
+    app_handle: tauri::AppHandle,
+    ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<ToolChatResponse, String> {
+    ToolRunner::new(
+        app_handle,
+        ai_service.inner().clone(),
+        db_manager.inner().clone(),
+    )
+    .resolve(&call_id, approved, feedback)
+    .await
+}
+
+/// Approve or reject several queued tool calls at once, continuing each
+/// one's chat. A call that fails to resolve doesn't stop the others.
+#[tauri::command]
+pub async fn ai_resolve_tool_calls(
+    call_ids: Vec<String>,
+    approved: bool,
+    feedback: Option<String>,
+    app_handle: tauri::AppHandle,
+    ai_service: State<'_, Arc<AIService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<ToolCallResolution>, String> {
+    Ok(ToolRunner::new(
+        app_handle,
+        ai_service.inner().clone(),
+        db_manager.inner().clone(),
+    )
+    .resolve_many(call_ids, approved, feedback)
+    .await)
+}
+
+/// List the tools the assistant can call
+#[tauri::command]
+pub async fn ai_list_tools(ai_service: State<'_, Arc<AIService>>) -> Result<Vec<ToolSpec>, String> {
+    Ok(ai_service.tools().specs())
+}
+
 /// Create a new conversation
 #[tauri::command]
 pub async fn ai_create_conversation(
@@ -395,6 +1034,8 @@ pub async fn ai_create_conversation(
         model: Set(conversation.model.clone()),
         created_at: Set(conversation.created_at.clone()),
         updated_at: Set(conversation.updated_at.clone()),
+        parent_conversation_id: Set(None),
+        forked_from_message_id: Set(None),
     };
 
     // Insert the conversation
@@ -610,9 +1251,114 @@ pub async fn ai_delete_conversation(
         .await
         .map_err(|e| format!("Failed to delete conversation: {}", e))?;
 
+    // Forks keep their copied history; they just stop pointing at the parent
+    ConversationEntity::update_many()
+        .col_expr(
+            ConversationColumn::ParentConversationId,
+            Expr::value(Option::<String>::None),
+        )
+        .col_expr(
+            ConversationColumn::ForkedFromMessageId,
+            Expr::value(Option::<String>::None),
+        )
+        .filter(ConversationColumn::ParentConversationId.eq(&id))
+        .exec(db)
+        .await
+        .map_err(|e| format!("Failed to detach conversation branches: {}", e))?;
+
     Ok(())
 }
 
+/// Fork a conversation at `message_id` into a new conversation holding the
+/// history up to and including that message. The original is left untouched.
+#[tauri::command]
+pub async fn ai_fork_conversation(
+    conversation_id: String,
+    message_id: String,
+    title: Option<String>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<ConversationWithMessages, String> {
+    let db = db_manager.get_connection();
+
+    let parent = ConversationEntity::find_by_id(&conversation_id)
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to load conversation: {}", e))?
+        .map(Conversation::from)
+        .ok_or_else(|| "Conversation not found".to_string())?;
+    let messages: Vec<ConversationMessage> = ConversationMessageEntity::find()
+        .filter(ConversationMessageColumn::ConversationId.eq(&conversation_id))
+        .order_by_asc(ConversationMessageColumn::Sequence)
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to load messages: {}", e))?
+        .into_iter()
+        .map(ConversationMessage::from)
+        .collect();
+    let shared = history_through(&messages, &message_id)
+        .ok_or_else(|| format!("Message {} is not part of this conversation", message_id))?;
+
+    let fork = Conversation::fork(&parent, &message_id, title);
+    let fork_messages: Vec<ConversationMessage> =
+        shared.iter().map(|m| m.copy_to(&fork.id)).collect();
+
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    ConversationEntity::insert(ConversationActiveModel {
+        id: Set(fork.id.clone()),
+        title: Set(fork.title.clone()),
+        provider: Set(fork.provider.clone()),
+        model: Set(fork.model.clone()),
+        created_at: Set(fork.created_at.clone()),
+        updated_at: Set(fork.updated_at.clone()),
+        parent_conversation_id: Set(fork.parent_conversation_id.clone()),
+        forked_from_message_id: Set(fork.forked_from_message_id.clone()),
+    })
+    .exec_without_returning(&txn)
+    .await
+    .map_err(|e| format!("Failed to create fork: {}", e))?;
+    ConversationMessageEntity::insert_many(fork_messages.iter().map(|msg| {
+        ConversationMessageActiveModel {
+            id: Set(msg.id.clone()),
+            conversation_id: Set(msg.conversation_id.clone()),
+            role: Set(msg.role.clone()),
+            content: Set(msg.content.clone()),
+            timestamp: Set(msg.timestamp.clone()),
+            sequence: Set(msg.sequence),
+        }
+    }))
+    .exec_without_returning(&txn)
+    .await
+    .map_err(|e| format!("Failed to copy messages to fork: {}", e))?;
+    txn.commit().await.map_err(|e| e.to_string())?;
+
+    log_info!(
+        "AI",
+        "Forked conversation {} at message {} into {}",
+        conversation_id,
+        message_id,
+        fork.id
+    );
+    Ok(ConversationWithMessages {
+        conversation: fork,
+        messages: fork_messages,
+    })
+}
+
+/// Conversations forked from this one, oldest first
+#[tauri::command]
+pub async fn ai_list_conversation_branches(
+    id: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<Conversation>, String> {
+    let branches = ConversationEntity::find()
+        .filter(ConversationColumn::ParentConversationId.eq(&id))
+        .order_by_asc(ConversationColumn::CreatedAt)
+        .all(db_manager.get_connection())
+        .await
+        .map_err(|e| format!("Failed to list conversation branches: {}", e))?;
+    Ok(branches.into_iter().map(Conversation::from).collect())
+}
+
 /// Update conversation title
 #[tauri::command]
 pub async fn ai_update_conversation_title(
@@ -14,6 +14,10 @@ pub struct Conversation {
     pub updated_at: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_count: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_conversation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from_message_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +40,8 @@ impl From<ConversationModel> for Conversation {
             created_at: model.created_at,
             updated_at: model.updated_at,
             message_count: None,
+            parent_conversation_id: model.parent_conversation_id,
+            forked_from_message_id: model.forked_from_message_id,
         }
     }
 }
@@ -64,11 +70,34 @@ impl Conversation {
             created_at: now.clone(),
             updated_at: now,
             message_count: None,
+            parent_conversation_id: None,
+            forked_from_message_id: None,
         }
     }
+
+    /// Fork of `parent` sharing its history up to `message_id`
+    pub fn fork(parent: &Conversation, message_id: &str, title: Option<String>) -> Self {
+        let title = title
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| format!("{} (branch)", parent.title));
+        let mut fork = Self::new(title, parent.provider.clone(), parent.model.clone());
+        fork.parent_conversation_id = Some(parent.id.clone());
+        fork.forked_from_message_id = Some(message_id.to_string());
+        fork
+    }
 }
 
 impl ConversationMessage {
+    /// Copy of the message belonging to another conversation
+    pub fn copy_to(&self, conversation_id: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            conversation_id: conversation_id.to_string(),
+            ..self.clone()
+        }
+    }
+
     pub fn new(conversation_id: String, role: String, content: String, sequence: i32) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
@@ -86,3 +115,50 @@ pub struct ConversationWithMessages {
     pub conversation: Conversation,
     pub messages: Vec<ConversationMessage>,
 }
+
+/// Messages up to and including `message_id`, in order. `None` if the message
+/// isn't part of the conversation.
+pub fn history_through<'a>(
+    messages: &'a [ConversationMessage],
+    message_id: &str,
+) -> Option<&'a [ConversationMessage]> {
+    let end = messages.iter().position(|m| m.id == message_id)?;
+    Some(&messages[..=end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forks_share_history_up_to_the_branch_point() {
+        let parent = Conversation::new("Deploy plan".to_string(), "Ollama".to_string(), None);
+        let messages: Vec<ConversationMessage> = ["a", "b", "c"]
+            .iter()
+            .enumerate()
+            .map(|(i, content)| {
+                ConversationMessage::new(
+                    parent.id.clone(),
+                    "user".to_string(),
+                    content.to_string(),
+                    i as i32,
+                )
+            })
+            .collect();
+
+        let shared = history_through(&messages, &messages[1].id).unwrap();
+        assert_eq!(shared.len(), 2);
+        assert!(history_through(&messages, "missing").is_none());
+
+        let fork = Conversation::fork(&parent, &messages[1].id, None);
+        assert_eq!(fork.title, "Deploy plan (branch)");
+        assert_eq!(
+            fork.parent_conversation_id.as_deref(),
+            Some(parent.id.as_str())
+        );
+
+        let copy = shared[1].copy_to(&fork.id);
+        assert_eq!((copy.content.as_str(), copy.sequence), ("b", 1));
+        assert_ne!(copy.id, shared[1].id);
+    }
+}
@@ -0,0 +1,550 @@
+//! Text embeddings and a SQLite-backed vector store.
+//!
+//! `Embedder` turns text into vectors with the agent-platform provider, falling
+//! back to a local Ollama. `EmbeddingService` keeps vectors in the ai_embeddings
+//! table keyed by namespace and item id, and answers semantic searches with an
+//! exact cosine scan, which is plenty fast at desktop scale. Tasks, documents and
+//! learned patterns are built-in namespaces synced lazily before each search;
+//! anything else is indexed explicitly.
+
+use crate::domains::ai::entities::{
+    EmbeddingActiveModel, EmbeddingColumn, EmbeddingEntity, EmbeddingModel,
+};
+use crate::domains::ai::services::AIService;
+use crate::domains::documents::services::document_chunks::{
+    cosine_similarity, decode_embedding, encode_embedding,
+};
+use crate::domains::documents::services::markdown_sync::file_hash;
+use crate::entities::{document, learned_pattern, task};
+use crate::log_warn;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+const EMBED_BATCH_SIZE: usize = 32;
+/// Text beyond this is not embedded (most embedding models cap their context)
+const MAX_EMBED_CHARS: usize = 8_000;
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Namespaces kept in sync with their tables automatically
+pub const BUILTIN_NAMESPACES: &[&str] = &["tasks", "documents", "learning"];
+
+/// Embeds text with the AI provider, or Ollama when the provider can't
+pub struct Embedder {
+    ai_service: Arc<AIService>,
+    client: reqwest::Client,
+    model: String,
+    ollama_url: String,
+    /// Set after both sources fail, so a batch job doesn't retry per item
+    unavailable: AtomicBool,
+}
+
+impl Embedder {
+    pub fn new(ai_service: Arc<AIService>, model: Option<String>) -> Self {
+        let ollama_url = std::env::var("OLLAMA_HOST")
+            .ok()
+            .filter(|host| !host.trim().is_empty())
+            .map(|host| {
+                let host = host.trim().trim_end_matches('/');
+                if host.starts_with("http") {
+                    host.to_string()
+                } else {
+                    format!("http://{}", host)
+                }
+            })
+            .unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string());
+
+        Self {
+            ai_service,
+            client: reqwest::Client::new(),
+            model: model
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string()),
+            ollama_url,
+            unavailable: AtomicBool::new(false),
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// False once embedding has failed with both sources
+    pub fn is_available(&self) -> bool {
+        !self.unavailable.load(Ordering::Relaxed)
+    }
+
+    /// One vector per text, in order
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        if !self.is_available() {
+            return Err("No embedding model is available".to_string());
+        }
+
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(EMBED_BATCH_SIZE) {
+            let provider_error = match self.ai_service.embed(batch, Some(&self.model)).await {
+                Ok(result) if result.embeddings.len() == batch.len() => {
+                    vectors.extend(result.embeddings);
+                    continue;
+                }
+                Ok(result) => format!(
+                    "provider returned {} embeddings for {} inputs",
+                    result.embeddings.len(),
+                    batch.len()
+                ),
+                Err(e) => e.to_string(),
+            };
+
+            match self.embed_with_ollama(batch).await {
+                Ok(batch_vectors) => vectors.extend(batch_vectors),
+                Err(ollama_error) => {
+                    self.unavailable.store(true, Ordering::Relaxed);
+                    return Err(format!(
+                        "Embeddings unavailable (provider: {}; ollama: {})",
+                        provider_error, ollama_error
+                    ));
+                }
+            }
+        }
+        Ok(vectors)
+    }
+
+    async fn embed_with_ollama(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let response = self
+            .client
+            .post(format!("{}/api/embed", self.ollama_url))
+            .json(&json!({ "model": self.model, "input": texts }))
+            .timeout(std::time::Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| format!("Cannot reach Ollama at {}: {}", self.ollama_url, e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Ollama returned status {}: {}", status, text));
+        }
+
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid Ollama response: {}", e))?;
+        let vectors: Vec<Vec<f32>> = value
+            .get("embeddings")
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        item.as_array()
+                            .map(|v| {
+                                v.iter()
+                                    .filter_map(Value::as_f64)
+                                    .map(|x| x as f32)
+                                    .collect()
+                            })
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if vectors.len() != texts.len() || vectors.iter().any(Vec::is_empty) {
+            return Err(format!(
+                "Ollama returned {} embeddings for {} inputs",
+                vectors.len(),
+                texts.len()
+            ));
+        }
+        Ok(vectors)
+    }
+}
+
+/// Item to index under a namespace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingItem {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbedTextsResponse {
+    pub model: String,
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbeddingIndexReport {
+    pub namespace: String,
+    pub model: String,
+    /// Items embedded because they were new or changed
+    pub embedded: usize,
+    pub unchanged: usize,
+    /// Stored items no longer present in the source (built-in namespaces only)
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SemanticSearchQuery {
+    pub query: String,
+    /// Namespaces to search; defaults to the built-in ones
+    pub namespaces: Option<Vec<String>>,
+    pub limit: Option<usize>,
+    /// Drop matches scoring below this cosine similarity
+    pub min_score: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticMatch {
+    pub namespace: String,
+    pub item_id: String,
+    pub score: f32,
+    pub content: String,
+    pub metadata: Option<Value>,
+}
+
+pub struct EmbeddingService {
+    db: DatabaseConnection,
+    embedder: Embedder,
+}
+
+impl EmbeddingService {
+    pub fn new(db: DatabaseConnection, ai_service: Arc<AIService>, model: Option<String>) -> Self {
+        Self {
+            db,
+            embedder: Embedder::new(ai_service, model),
+        }
+    }
+
+    /// Embed `items` into `namespace`, skipping items whose text and model are
+    /// unchanged since they were last embedded.
+    pub async fn index(
+        &self,
+        namespace: &str,
+        items: Vec<EmbeddingItem>,
+    ) -> Result<EmbeddingIndexReport, String> {
+        let namespace = namespace.trim();
+        if namespace.is_empty() {
+            return Err("Namespace is required".to_string());
+        }
+
+        let existing: HashMap<String, EmbeddingModel> = EmbeddingEntity::find()
+            .filter(EmbeddingColumn::Namespace.eq(namespace))
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load embeddings: {}", e))?
+            .into_iter()
+            .map(|row| (row.item_id.clone(), row))
+            .collect();
+
+        let mut report = EmbeddingIndexReport {
+            namespace: namespace.to_string(),
+            model: self.embedder.model().to_string(),
+            ..Default::default()
+        };
+        let mut stale = Vec::new();
+        for item in items {
+            let text = truncate_chars(item.text.trim(), MAX_EMBED_CHARS);
+            if text.is_empty() {
+                continue;
+            }
+            let hash = file_hash(text.as_bytes());
+            match existing.get(&item.id) {
+                Some(row) if row.content_hash == hash && row.model == self.embedder.model() => {
+                    report.unchanged += 1;
+                }
+                _ => stale.push((item, text, hash)),
+            }
+        }
+
+        for batch in stale.chunks(EMBED_BATCH_SIZE) {
+            let texts: Vec<String> = batch.iter().map(|(_, text, _)| text.clone()).collect();
+            let vectors = self.embedder.embed(&texts).await?;
+            for ((item, text, hash), vector) in batch.iter().zip(vectors) {
+                self.upsert(
+                    namespace,
+                    item,
+                    text,
+                    hash,
+                    &vector,
+                    existing.get(&item.id).map(|row| row.id),
+                )
+                .await?;
+                report.embedded += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    async fn upsert(
+        &self,
+        namespace: &str,
+        item: &EmbeddingItem,
+        text: &str,
+        hash: &str,
+        vector: &[f32],
+        existing_id: Option<i32>,
+    ) -> Result<(), String> {
+        let mut row = EmbeddingActiveModel {
+            namespace: Set(namespace.to_string()),
+            item_id: Set(item.id.clone()),
+            content_hash: Set(hash.to_string()),
+            content: Set(text.to_string()),
+            metadata: Set(item.metadata.as_ref().map(Value::to_string)),
+            embedding: Set(encode_embedding(vector)),
+            model: Set(self.embedder.model().to_string()),
+            dimensions: Set(vector.len() as i32),
+            updated_at: Set(Some(chrono::Utc::now().into())),
+            ..Default::default()
+        };
+        let result = match existing_id {
+            Some(id) => {
+                row.id = Set(id);
+                row.update(&self.db).await.map(|_| ())
+            }
+            None => row.insert(&self.db).await.map(|_| ()),
+        };
+        result.map_err(|e| format!("Failed to store embedding: {}", e))
+    }
+
+    /// Remove items from a namespace, or the whole namespace when `item_ids` is `None`
+    pub async fn remove(
+        &self,
+        namespace: &str,
+        item_ids: Option<Vec<String>>,
+    ) -> Result<u64, String> {
+        let mut delete =
+            EmbeddingEntity::delete_many().filter(EmbeddingColumn::Namespace.eq(namespace));
+        if let Some(item_ids) = item_ids {
+            delete = delete.filter(EmbeddingColumn::ItemId.is_in(item_ids));
+        }
+        delete
+            .exec(&self.db)
+            .await
+            .map(|result| result.rows_affected)
+            .map_err(|e| format!("Failed to remove embeddings: {}", e))
+    }
+
+    /// Re-embed a built-in namespace from its source table and drop items that
+    /// no longer exist.
+    pub async fn sync_builtin(&self, namespace: &str) -> Result<EmbeddingIndexReport, String> {
+        let items = match namespace {
+            "tasks" => self.task_items().await?,
+            "documents" => self.document_items().await?,
+            "learning" => self.learning_items().await?,
+            other => return Err(format!("{} is not a built-in namespace", other)),
+        };
+
+        let current: Vec<String> = items.iter().map(|item| item.id.clone()).collect();
+        let mut report = self.index(namespace, items).await?;
+
+        let stored: Vec<String> = EmbeddingEntity::find()
+            .select_only()
+            .column(EmbeddingColumn::ItemId)
+            .filter(EmbeddingColumn::Namespace.eq(namespace))
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load embeddings: {}", e))?;
+        let gone: Vec<String> = stored
+            .into_iter()
+            .filter(|id| !current.contains(id))
+            .collect();
+        if !gone.is_empty() {
+            report.removed = self.remove(namespace, Some(gone)).await? as usize;
+        }
+        Ok(report)
+    }
+
+    /// Items most similar to the query, best first. Built-in namespaces are
+    /// synced first so results reflect the current data.
+    pub async fn search(&self, query: SemanticSearchQuery) -> Result<Vec<SemanticMatch>, String> {
+        let text = query.query.trim();
+        if text.is_empty() {
+            return Err("Search query is required".to_string());
+        }
+        let namespaces = query.namespaces.unwrap_or_else(|| {
+            BUILTIN_NAMESPACES
+                .iter()
+                .map(|namespace| namespace.to_string())
+                .collect()
+        });
+
+        for namespace in &namespaces {
+            if BUILTIN_NAMESPACES.contains(&namespace.as_str()) {
+                if let Err(e) = self.sync_builtin(namespace).await {
+                    log_warn!("AI", "Failed to sync {} embeddings: {}", namespace, e);
+                }
+            }
+        }
+
+        let query_vector = self
+            .embedder
+            .embed(&[text.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| "Embedding the query returned nothing".to_string())?;
+
+        let rows = EmbeddingEntity::find()
+            .filter(EmbeddingColumn::Namespace.is_in(namespaces))
+            .filter(EmbeddingColumn::Model.eq(self.embedder.model()))
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load embeddings: {}", e))?;
+
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+        Ok(rank_matches(
+            &query_vector,
+            rows,
+            limit,
+            query.min_score.unwrap_or(0.0),
+        ))
+    }
+
+    async fn task_items(&self) -> Result<Vec<EmbeddingItem>, String> {
+        let tasks = task::Entity::find()
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load tasks: {}", e))?;
+        Ok(tasks
+            .into_iter()
+            .map(|task| EmbeddingItem {
+                id: task.id.to_string(),
+                text: match &task.description {
+                    Some(description) => format!("{}\n\n{}", task.title, description),
+                    None => task.title.clone(),
+                },
+                metadata: Some(json!({
+                    "title": task.title,
+                    "status": task.status,
+                    "priority": task.priority,
+                })),
+            })
+            .collect())
+    }
+
+    async fn document_items(&self) -> Result<Vec<EmbeddingItem>, String> {
+        let documents = document::Entity::find()
+            .filter(document::Column::IsArchived.eq(false))
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load documents: {}", e))?;
+        Ok(documents
+            .into_iter()
+            .map(|doc| EmbeddingItem {
+                id: doc.id.to_string(),
+                text: format!("{}\n\n{}", doc.title, doc.content),
+                metadata: Some(json!({ "title": doc.title, "project_id": doc.project_id })),
+            })
+            .collect())
+    }
+
+    async fn learning_items(&self) -> Result<Vec<EmbeddingItem>, String> {
+        let patterns = learned_pattern::Entity::find()
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load learned patterns: {}", e))?;
+        Ok(patterns
+            .into_iter()
+            .map(|pattern| EmbeddingItem {
+                id: pattern.id.to_string(),
+                text: format!(
+                    "{} {} {}",
+                    pattern.pattern_type,
+                    pattern.context.as_deref().unwrap_or_default(),
+                    pattern.pattern_data
+                ),
+                metadata: Some(json!({
+                    "pattern_type": pattern.pattern_type,
+                    "context": pattern.context,
+                    "frequency": pattern.frequency,
+                })),
+            })
+            .collect())
+    }
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((cut, _)) => text[..cut].to_string(),
+        None => text.to_string(),
+    }
+}
+
+fn rank_matches(
+    query: &[f32],
+    rows: Vec<EmbeddingModel>,
+    limit: usize,
+    min_score: f32,
+) -> Vec<SemanticMatch> {
+    let mut matches: Vec<SemanticMatch> = rows
+        .into_iter()
+        .filter(|row| row.dimensions as usize == query.len())
+        .map(|row| SemanticMatch {
+            score: cosine_similarity(query, &decode_embedding(&row.embedding)),
+            namespace: row.namespace,
+            item_id: row.item_id,
+            content: row.content,
+            metadata: row
+                .metadata
+                .as_deref()
+                .and_then(|m| serde_json::from_str(m).ok()),
+        })
+        .filter(|m| m.score >= min_score)
+        .collect();
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(limit);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(item_id: &str, vector: &[f32]) -> EmbeddingModel {
+        EmbeddingModel {
+            id: 0,
+            namespace: "tasks".to_string(),
+            item_id: item_id.to_string(),
+            content_hash: String::new(),
+            content: item_id.to_string(),
+            metadata: Some("{\"title\":\"t\"}".to_string()),
+            embedding: encode_embedding(vector),
+            model: DEFAULT_EMBEDDING_MODEL.to_string(),
+            dimensions: vector.len() as i32,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn ranks_by_similarity_and_skips_other_dimensions() {
+        let rows = vec![
+            row("far", &[0.0, 1.0]),
+            row("near", &[1.0, 0.1]),
+            row("other-model", &[1.0, 0.0, 0.0]),
+        ];
+
+        let matches = rank_matches(&[1.0, 0.0], rows.clone(), 10, 0.0);
+        let ids: Vec<&str> = matches.iter().map(|m| m.item_id.as_str()).collect();
+        assert_eq!(ids, ["near", "far"]);
+        assert_eq!(matches[0].metadata, Some(json!({ "title": "t" })));
+
+        assert_eq!(rank_matches(&[1.0, 0.0], rows, 10, 0.5).len(), 1);
+    }
+}
@@ -11,6 +11,9 @@ pub struct Model {
     pub model: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// Set on forks: the conversation and message they branched from
+    pub parent_conversation_id: Option<String>,
+    pub forked_from_message_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
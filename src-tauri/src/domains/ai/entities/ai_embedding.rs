@@ -0,0 +1,25 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "ai_embeddings")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub namespace: String,
+    pub item_id: String,
+    pub content_hash: String,
+    #[sea_orm(column_type = "Text")]
+    pub content: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub metadata: Option<String>,
+    pub embedding: Vec<u8>, // little-endian f32s
+    pub model: String,
+    pub dimensions: i32,
+    pub updated_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
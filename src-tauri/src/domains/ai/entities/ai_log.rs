@@ -1,7 +1,7 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "ai_logs")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -16,6 +16,13 @@ pub struct Model {
     pub error_message: Option<String>,
     pub timestamp: String,
     pub conversation_id: Option<String>,
+    pub model: Option<String>,
+    /// App feature that made the request (usage rows)
+    pub feature: Option<String>,
+    pub prompt_tokens: Option<i32>,
+    pub completion_tokens: Option<i32>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub estimated_cost_usd: Option<f64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,41 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "ai_prompt_templates")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub name: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub description: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub system_prompt: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub user_prompt: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub variables: String, // JSON array of PromptVariable
+    #[sea_orm(column_type = "Text", nullable)]
+    pub tags: Option<String>, // JSON array of strings
+    pub version: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        has_many = "crate::domains::ai::entities::ai_prompt_template_version::Entity",
+        from = "Column::Id",
+        to = "crate::domains::ai::entities::ai_prompt_template_version::Column::TemplateId"
+    )]
+    Versions,
+}
+
+impl Related<crate::domains::ai::entities::ai_prompt_template_version::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Versions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
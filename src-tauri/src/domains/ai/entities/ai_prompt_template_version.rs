@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "ai_prompt_template_versions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub template_id: String,
+    pub version: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub system_prompt: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub user_prompt: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub variables: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub change_note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::domains::ai::entities::ai_prompt_template::Entity",
+        from = "Column::TemplateId",
+        to = "crate::domains::ai::entities::ai_prompt_template::Column::Id"
+    )]
+    Template,
+}
+
+impl Related<crate::domains::ai::entities::ai_prompt_template::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Template.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
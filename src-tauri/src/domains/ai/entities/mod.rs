@@ -1,6 +1,9 @@
 pub mod ai_conversation;
 pub mod ai_conversation_message;
+pub mod ai_embedding;
 pub mod ai_log;
+pub mod ai_prompt_template;
+pub mod ai_prompt_template_version;
 pub mod ai_training_data;
 
 pub use ai_conversation::{
@@ -11,9 +14,20 @@ pub use ai_conversation_message::{
     ActiveModel as ConversationMessageActiveModel, Entity as ConversationMessageEntity,
     Model as ConversationMessageModel,
 };
+pub use ai_embedding::{
+    ActiveModel as EmbeddingActiveModel, Column as EmbeddingColumn, Entity as EmbeddingEntity,
+    Model as EmbeddingModel,
+};
+pub use ai_log::ActiveModel as AILogActiveModel;
 pub use ai_log::{Column as AILogColumn, Entity as AILogEntity, Model as AILogModel};
-// AILogActiveModel is used directly where needed, not re-exported
-// pub use ai_log::ActiveModel as AILogActiveModel;
+pub use ai_prompt_template::{
+    ActiveModel as PromptTemplateActiveModel, Column as PromptTemplateColumn,
+    Entity as PromptTemplateEntity, Model as PromptTemplateModel,
+};
+pub use ai_prompt_template_version::{
+    ActiveModel as PromptTemplateVersionActiveModel, Column as PromptTemplateVersionColumn,
+    Entity as PromptTemplateVersionEntity, Model as PromptTemplateVersionModel,
+};
 pub use ai_training_data::Entity as TrainingDataEntity;
 // TrainingDataModel is not used directly, only Entity and ActiveModel
 // pub use ai_training_data::Model as TrainingDataModel;
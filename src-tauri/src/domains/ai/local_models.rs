@@ -0,0 +1,540 @@
+//! GGUF models served locally by a llama.cpp `llama-server` the app runs.
+//!
+//! For machines where the Ollama daemon can't run. The app keeps GGUF files in
+//! its data dir and runs llama.cpp's `llama-server` itself: the runtime is
+//! downloaded from the llama.cpp releases with the SDK download infrastructure
+//! (or found on PATH), started on demand with the requested model on a loopback
+//! port, and spoken to over its OpenAI-compatible API by `LlamaCppProvider`.
+
+use crate::command_executor::supervisor::{supervisor, ProcessSpec, SupervisedChild};
+use crate::domains::sdk::download::archive_handler::ArchiveHandler;
+use crate::domains::sdk::download::binary_downloader::BinaryDownloader;
+use crate::domains::sdk::download::{DownloadProgress, InstallProgress, InstallStage};
+use crate::log_info;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+/// Backend id routing a generation to the local runtime instead of agent-platform
+pub const LLAMA_CPP_BACKEND: &str = "llama_cpp";
+
+/// Progress of model and runtime downloads, as `LocalModelProgress`
+pub const LOCAL_MODEL_PROGRESS_EVENT: &str = "local-model-progress";
+
+const RELEASES_URL: &str = "https://github.com/ggml-org/llama.cpp/releases/download";
+/// llama.cpp release the runtime is installed from. Bump it together with
+/// `RUNTIME_ASSETS`.
+const RUNTIME_RELEASE: &str = "b6000";
+/// CPU builds of `RUNTIME_RELEASE` and the SHA-256 of each, copied from the
+/// release's asset list. Platforms without an entry use `llama-server` from
+/// PATH; nothing unverified is installed.
+const RUNTIME_ASSETS: &[(&str, &str)] = &[];
+const MODELS_DIR: &str = "models";
+const RUNTIME_DIR: &str = "runtime";
+const DOWNLOADS_DIR: &str = "downloads";
+/// Loading a large model from disk can take a while
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+const DEFAULT_CONTEXT_SIZE: u32 = 8192;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalModel {
+    /// File name without the `.gguf` extension; what generation requests name
+    pub name: String,
+    pub file_name: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlamaCppStatus {
+    pub runtime_path: Option<String>,
+    pub models_dir: String,
+    /// Model the server is running, if any
+    pub loaded_model: Option<String>,
+    /// The server is still loading `loaded_model`
+    pub loading: bool,
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalModelProgress {
+    /// Model name, or `runtime` for the llama.cpp runtime
+    pub target: String,
+    #[serde(flatten)]
+    pub progress: InstallProgress,
+}
+
+struct ServerProcess {
+    child: SupervisedChild,
+    model: String,
+    port: u16,
+    /// Still loading the model, not answering requests yet
+    starting: bool,
+}
+
+impl ServerProcess {
+    fn is(&self, model: &str, port: u16) -> bool {
+        self.model == model && self.port == port
+    }
+}
+
+pub struct LlamaCppRuntime {
+    root: PathBuf,
+    client: reqwest::Client,
+    server: Mutex<Option<ServerProcess>>,
+}
+
+impl LlamaCppRuntime {
+    pub fn new(app_data_dir: &Path) -> Self {
+        Self {
+            root: app_data_dir.join("llama_cpp"),
+            client: reqwest::Client::new(),
+            server: Mutex::new(None),
+        }
+    }
+
+    pub fn models_dir(&self) -> PathBuf {
+        self.root.join(MODELS_DIR)
+    }
+
+    /// GGUF files in the models dir, by name
+    pub fn list_models(&self) -> Result<Vec<LocalModel>, String> {
+        let entries = match std::fs::read_dir(self.models_dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read models directory: {}", e)),
+        };
+
+        let mut models: Vec<LocalModel> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let file_name = path.file_name()?.to_str()?.to_string();
+                let name = file_name.strip_suffix(".gguf")?.to_string();
+                Some(LocalModel {
+                    name,
+                    file_name,
+                    size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    path: path.to_string_lossy().to_string(),
+                })
+            })
+            .collect();
+        models.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(models)
+    }
+
+    /// Model to use when a request doesn't name one: the loaded model, else
+    /// the first installed one
+    pub async fn default_model(&self) -> Option<String> {
+        if let Some(server) = self.server.lock().await.as_ref() {
+            return Some(server.model.clone());
+        }
+        self.list_models().ok()?.into_iter().next().map(|m| m.name)
+    }
+
+    fn model_path(&self, name: &str) -> Result<PathBuf, String> {
+        let name = name.trim().trim_end_matches(".gguf");
+        if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+            return Err(format!("Invalid model name: {}", name));
+        }
+        let path = self.models_dir().join(format!("{}.gguf", name));
+        if !path.is_file() {
+            return Err(format!(
+                "Local model {} is not installed. Download a GGUF file first.",
+                name
+            ));
+        }
+        Ok(path)
+    }
+
+    /// Download a GGUF model into the models dir
+    pub async fn download_model(
+        &self,
+        url: &str,
+        name: Option<String>,
+        progress: mpsc::UnboundedSender<InstallProgress>,
+    ) -> Result<LocalModel, String> {
+        let file_name = match name.map(|n| n.trim().trim_end_matches(".gguf").to_string()) {
+            Some(name) if !name.is_empty() => format!("{}.gguf", name),
+            _ => url
+                .split(['?', '#'])
+                .next()
+                .and_then(|u| u.rsplit('/').next())
+                .filter(|f| f.ends_with(".gguf"))
+                .ok_or_else(|| {
+                    "The URL doesn't point at a .gguf file; give the model a name".to_string()
+                })?
+                .to_string(),
+        };
+        if file_name.contains(['/', '\\']) || file_name.contains("..") {
+            return Err(format!("Invalid model file name: {}", file_name));
+        }
+
+        let models_dir = self.models_dir();
+        tokio::fs::create_dir_all(&models_dir)
+            .await
+            .map_err(|e| format!("Failed to create models directory: {}", e))?;
+        let target = models_dir.join(&file_name);
+        if target.exists() {
+            return Err(format!("{} is already installed", file_name));
+        }
+
+        // Download beside the target so a failed download never shows up as a model
+        let partial = models_dir.join(format!("{}.part", file_name));
+        let downloader = BinaryDownloader::new(models_dir.clone());
+        if let Err(e) = downloader
+            .download_with_progress(url, &partial, progress.clone())
+            .await
+        {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e.to_string());
+        }
+        tokio::fs::rename(&partial, &target)
+            .await
+            .map_err(|e| format!("Failed to save model: {}", e))?;
+
+        let name = file_name.trim_end_matches(".gguf");
+        log_info!("AI", "Downloaded local model {}", name);
+        let model = self
+            .list_models()?
+            .into_iter()
+            .find(|m| m.name == name)
+            .ok_or_else(|| format!("Downloaded model {} is missing", name))?;
+        let _ = progress.send(InstallProgress {
+            stage: InstallStage::Complete,
+            progress: DownloadProgress {
+                total_bytes: model.size_bytes,
+                downloaded_bytes: model.size_bytes,
+                percentage: 100.0,
+                speed: 0,
+                eta: None,
+            },
+            message: format!("{} is ready", name),
+        });
+        Ok(model)
+    }
+
+    pub async fn delete_model(&self, name: &str) -> Result<(), String> {
+        let path = self.model_path(name)?;
+        let name = name.trim().trim_end_matches(".gguf");
+        if self
+            .server
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|s| s.model == name)
+        {
+            self.stop().await;
+        }
+        tokio::fs::remove_file(&path)
+            .await
+            .map_err(|e| format!("Failed to delete model: {}", e))
+    }
+
+    /// `llama-server` from the downloaded runtime, or from PATH
+    pub fn server_binary(&self) -> Option<PathBuf> {
+        let binary = if cfg!(target_os = "windows") {
+            "llama-server.exe"
+        } else {
+            "llama-server"
+        };
+        find_file(&self.root.join(RUNTIME_DIR), binary, 4)
+            .or_else(|| which::which("llama-server").ok())
+    }
+
+    /// Download the pinned llama.cpp release for this platform into the
+    /// runtime dir, replacing any earlier download
+    pub async fn install_runtime(
+        &self,
+        progress: mpsc::UnboundedSender<InstallProgress>,
+    ) -> Result<PathBuf, String> {
+        let (os, arch) = (std::env::consts::OS, std::env::consts::ARCH);
+        let (asset, sha256) = pick_runtime_asset(RUNTIME_ASSETS, os, arch).ok_or_else(|| {
+            format!(
+                "No verified llama.cpp build for {}-{}; install llama-server and put it on PATH",
+                os, arch
+            )
+        })?;
+        let url = format!("{}/{}/{}", RELEASES_URL, RUNTIME_RELEASE, asset);
+
+        self.stop().await;
+        let downloads = self.root.join(DOWNLOADS_DIR);
+        tokio::fs::create_dir_all(&downloads)
+            .await
+            .map_err(|e| format!("Failed to create download directory: {}", e))?;
+        let archive = downloads.join(asset);
+        let downloader = BinaryDownloader::new(downloads.clone());
+        downloader
+            .download_with_progress(&url, &archive, progress.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        match downloader.verify_checksum(&archive, sha256).await {
+            Ok(true) => {}
+            verified => {
+                let _ = tokio::fs::remove_file(&archive).await;
+                return Err(match verified {
+                    Err(e) => e.to_string(),
+                    _ => format!("{} doesn't match its SHA-256; not installing it", asset),
+                });
+            }
+        }
+
+        let runtime_dir = self.root.join(RUNTIME_DIR);
+        if runtime_dir.exists() {
+            tokio::fs::remove_dir_all(&runtime_dir)
+                .await
+                .map_err(|e| format!("Failed to remove old runtime: {}", e))?;
+        }
+        let extracted = ArchiveHandler
+            .extract_archive(&archive, &runtime_dir, progress)
+            .await
+            .map_err(|e| e.to_string());
+        let _ = tokio::fs::remove_file(&archive).await;
+        extracted?;
+
+        let binary = self
+            .server_binary()
+            .filter(|path| path.starts_with(&runtime_dir))
+            .ok_or_else(|| format!("{} has no llama-server binary", asset))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755));
+        }
+        log_info!("AI", "Installed llama.cpp runtime {}", asset);
+        Ok(binary)
+    }
+
+    /// Base URL of a server running `model`, starting (or restarting) it if
+    /// needed. The server lock is only held to check on the process, not
+    /// while the model loads.
+    pub async fn ensure_running(&self, model: &str) -> Result<String, String> {
+        let model = model.trim().trim_end_matches(".gguf");
+        let port = {
+            let mut server = self.server.lock().await;
+            if let Some(running) = server.as_mut() {
+                let alive = matches!(running.child.try_wait(), Ok(None));
+                if alive && running.model == model && !running.starting {
+                    return Ok(base_url(running.port));
+                }
+                if !alive || running.model != model {
+                    running.child.kill_tree().await;
+                    *server = None;
+                }
+            }
+            match server.as_ref() {
+                // Another request is loading it already
+                Some(starting) => starting.port,
+                None => {
+                    let process = self.spawn_server(model)?;
+                    let port = process.port;
+                    *server = Some(process);
+                    port
+                }
+            }
+        };
+        self.wait_until_ready(model, port).await
+    }
+
+    fn spawn_server(&self, model: &str) -> Result<ServerProcess, String> {
+        let path = self.model_path(model)?;
+        let binary = self.server_binary().ok_or_else(|| {
+            "The llama.cpp runtime is not installed. Install it from the local models settings."
+                .to_string()
+        })?;
+        let port = free_port()?;
+
+        log_info!(
+            "AI",
+            "Starting llama-server with {} on port {}",
+            model,
+            port
+        );
+        let mut spec = ProcessSpec::new("ai", binary.to_string_lossy());
+        spec.arg("--model")
+            .arg(path.to_string_lossy())
+            .args(["--host", "127.0.0.1", "--port", &port.to_string()])
+            .args(["--ctx-size", &DEFAULT_CONTEXT_SIZE.to_string()])
+            .discard_output();
+        let child = supervisor()
+            .spawn(&spec)
+            .map_err(|e| format!("Failed to start llama-server: {}", e))?;
+        Ok(ServerProcess {
+            child,
+            model: model.to_string(),
+            port,
+            starting: true,
+        })
+    }
+
+    /// Poll the server started for `model` on `port` until it answers, exits,
+    /// is stopped or replaced, or runs out of time
+    async fn wait_until_ready(&self, model: &str, port: u16) -> Result<String, String> {
+        let url = base_url(port);
+        let started = std::time::Instant::now();
+        loop {
+            {
+                let mut server = self.server.lock().await;
+                let Some(process) = server.as_mut().filter(|s| s.is(model, port)) else {
+                    return Err(format!("llama-server was stopped while loading {}", model));
+                };
+                if !process.starting {
+                    return Ok(url);
+                }
+                if let Ok(Some(status)) = process.child.try_wait() {
+                    *server = None;
+                    return Err(format!(
+                        "llama-server exited while loading {}: {}",
+                        model, status
+                    ));
+                }
+                if started.elapsed() > STARTUP_TIMEOUT {
+                    if let Some(mut process) = server.take() {
+                        process.child.kill_tree().await;
+                    }
+                    return Err(format!("llama-server did not load {} in time", model));
+                }
+            }
+
+            let healthy = self
+                .client
+                .get(format!("{}/health", url))
+                .timeout(Duration::from_secs(2))
+                .send()
+                .await
+                .is_ok_and(|r| r.status().is_success());
+            if healthy {
+                if let Some(process) = self
+                    .server
+                    .lock()
+                    .await
+                    .as_mut()
+                    .filter(|s| s.is(model, port))
+                {
+                    process.starting = false;
+                    return Ok(url);
+                }
+                continue;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Stop the server. Returns false if it wasn't running.
+    pub async fn stop(&self) -> bool {
+        match self.server.lock().await.take() {
+            Some(mut running) => {
+                running.child.kill_tree().await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn status(&self) -> LlamaCppStatus {
+        let mut server = self.server.lock().await;
+        if server
+            .as_mut()
+            .is_some_and(|s| !matches!(s.child.try_wait(), Ok(None)))
+        {
+            *server = None;
+        }
+        LlamaCppStatus {
+            runtime_path: self
+                .server_binary()
+                .map(|p| p.to_string_lossy().to_string()),
+            models_dir: self.models_dir().to_string_lossy().to_string(),
+            loaded_model: server.as_ref().map(|s| s.model.clone()),
+            loading: server.as_ref().is_some_and(|s| s.starting),
+            port: server.as_ref().map(|s| s.port),
+        }
+    }
+}
+
+fn base_url(port: u16) -> String {
+    format!("http://127.0.0.1:{}", port)
+}
+
+fn free_port() -> Result<u16, String> {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("No free port for llama-server: {}", e))
+}
+
+fn find_file(dir: &Path, name: &str, depth: usize) -> Option<PathBuf> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if path.file_name().is_some_and(|n| n == name) {
+            return Some(path);
+        }
+    }
+    if depth == 0 {
+        return None;
+    }
+    subdirs
+        .into_iter()
+        .find_map(|sub| find_file(&sub, name, depth - 1))
+}
+
+/// CPU build of llama.cpp for this platform, with its SHA-256, from a
+/// release's assets. GPU builds (CUDA, Vulkan, ...) need matching drivers,
+/// so they're skipped.
+fn pick_runtime_asset<'a>(
+    assets: &[(&'a str, &'a str)],
+    os: &str,
+    arch: &str,
+) -> Option<(&'a str, &'a str)> {
+    let arch = match arch {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        other => other,
+    };
+    let platforms: &[&str] = match os {
+        "linux" => &["ubuntu"],
+        "macos" => &["macos"],
+        "windows" => &["win-cpu", "win"],
+        _ => return None,
+    };
+    platforms.iter().find_map(|platform| {
+        let wanted = format!("-bin-{}-{}.", platform, arch);
+        assets.iter().copied().find(|(name, _)| {
+            name.contains(&wanted) && (name.ends_with(".zip") || name.ends_with(".tar.gz"))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_cpu_runtime_for_platform() {
+        let assets = [
+            ("llama-b6000-bin-ubuntu-vulkan-x64.zip", "a1"),
+            ("llama-b6000-bin-ubuntu-x64.zip", "b2"),
+            ("llama-b6000-bin-macos-arm64.zip", "c3"),
+            ("llama-b6000-bin-win-cuda-12.4-x64.zip", "d4"),
+            ("llama-b6000-bin-win-cpu-x64.zip", "e5"),
+            ("cudart-llama-bin-win-cuda-12.4-x64.zip", "f6"),
+        ];
+        assert_eq!(
+            pick_runtime_asset(&assets, "linux", "x86_64"),
+            Some(("llama-b6000-bin-ubuntu-x64.zip", "b2"))
+        );
+        assert_eq!(
+            pick_runtime_asset(&assets, "macos", "aarch64"),
+            Some(("llama-b6000-bin-macos-arm64.zip", "c3"))
+        );
+        assert_eq!(
+            pick_runtime_asset(&assets, "windows", "x86_64"),
+            Some(("llama-b6000-bin-win-cpu-x64.zip", "e5"))
+        );
+        assert_eq!(pick_runtime_asset(&assets, "linux", "aarch64"), None);
+    }
+}
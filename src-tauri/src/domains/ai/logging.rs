@@ -13,6 +13,11 @@ pub struct AILog {
     pub error_message: Option<String>,
     pub timestamp: String,
     pub conversation_id: Option<String>,
+    pub model: Option<String>,
+    pub feature: Option<String>,
+    pub prompt_tokens: Option<i32>,
+    pub completion_tokens: Option<i32>,
+    pub estimated_cost_usd: Option<f64>,
 }
 
 impl From<AILogModel> for AILog {
@@ -26,6 +31,11 @@ impl From<AILogModel> for AILog {
             error_message: model.error_message,
             timestamp: model.timestamp,
             conversation_id: model.conversation_id,
+            model: model.model,
+            feature: model.feature,
+            prompt_tokens: model.prompt_tokens,
+            completion_tokens: model.completion_tokens,
+            estimated_cost_usd: model.estimated_cost_usd,
         }
     }
 }
@@ -54,6 +64,11 @@ impl AILog {
             error_message: None,
             timestamp: chrono::Utc::now().to_rfc3339(),
             conversation_id,
+            model: None,
+            feature: None,
+            prompt_tokens: None,
+            completion_tokens: None,
+            estimated_cost_usd: None,
         }
     }
 
@@ -71,6 +86,11 @@ impl AILog {
             error_message: None,
             timestamp: chrono::Utc::now().to_rfc3339(),
             conversation_id,
+            model: None,
+            feature: None,
+            prompt_tokens: None,
+            completion_tokens: None,
+            estimated_cost_usd: None,
         }
     }
 
@@ -88,6 +108,11 @@ impl AILog {
             error_message: Some(error_message),
             timestamp: chrono::Utc::now().to_rfc3339(),
             conversation_id,
+            model: None,
+            feature: None,
+            prompt_tokens: None,
+            completion_tokens: None,
+            estimated_cost_usd: None,
         }
     }
 }
@@ -4,12 +4,21 @@ pub mod chat_title;
 pub mod commands;
 pub mod context_usage;
 pub mod conversation;
+pub mod embeddings;
 pub mod entities;
+pub mod local_models;
 pub mod logging;
 pub mod message;
 pub mod platform_config;
+pub mod prompt_templates;
 pub mod providers;
+pub mod redaction;
+pub mod response_cache;
 pub mod services;
+pub mod structured;
+pub mod tool_runner;
+pub mod tools;
+pub mod usage;
 
 // Commands are registered in lib.rs, not re-exported here
 // pub use commands::*;
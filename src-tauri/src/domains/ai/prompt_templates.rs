@@ -0,0 +1,552 @@
+//! Reusable system/user prompts with variables.
+//!
+//! Templates hold `{{variable}}` placeholders filled in at generation time; the
+//! user's own message is always available as `{{input}}`. Every edit bumps the
+//! template's version and snapshots it, so callers can pin a version and older
+//! revisions can be restored.
+
+use crate::domains::ai::entities::{
+    PromptTemplateActiveModel, PromptTemplateColumn, PromptTemplateEntity, PromptTemplateModel,
+    PromptTemplateVersionActiveModel, PromptTemplateVersionColumn, PromptTemplateVersionEntity,
+    PromptTemplateVersionModel,
+};
+use crate::domains::ai::message::ChatMessage;
+use crate::domains::documents::services::document_template_service::render_template;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Variable bound to the user's message
+pub const INPUT_VARIABLE: &str = "input";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Used when the caller gives no value
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: Option<String>,
+    pub user_prompt: Option<String>,
+    pub variables: Vec<PromptVariable>,
+    pub tags: Vec<String>,
+    pub version: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplateInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: Option<String>,
+    pub user_prompt: Option<String>,
+    #[serde(default)]
+    pub variables: Vec<PromptVariable>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Stored with the new version
+    #[serde(default)]
+    pub change_note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplateVersion {
+    pub version: i32,
+    pub system_prompt: Option<String>,
+    pub user_prompt: Option<String>,
+    pub variables: Vec<PromptVariable>,
+    pub change_note: Option<String>,
+    pub created_at: String,
+}
+
+/// Reference to a template from a generation request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptTemplateRef {
+    pub id: String,
+    /// Pinned version; the current one when unset
+    #[serde(default)]
+    pub version: Option<i32>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenderedPrompt {
+    pub template_id: String,
+    pub version: i32,
+    pub system: Option<String>,
+    pub user: Option<String>,
+}
+
+pub struct PromptTemplateService {
+    db: DatabaseConnection,
+}
+
+impl PromptTemplateService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// All templates by name, optionally only those carrying `tag`
+    pub async fn list(&self, tag: Option<String>) -> Result<Vec<PromptTemplate>, String> {
+        let templates = PromptTemplateEntity::find()
+            .order_by_asc(PromptTemplateColumn::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load prompt templates: {}", e))?
+            .into_iter()
+            .map(to_template);
+        Ok(match tag {
+            Some(tag) => templates
+                .filter(|t| t.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)))
+                .collect(),
+            None => templates.collect(),
+        })
+    }
+
+    pub async fn get(&self, id: &str) -> Result<PromptTemplate, String> {
+        self.find(id).await.map(to_template)
+    }
+
+    pub async fn create(&self, input: PromptTemplateInput) -> Result<PromptTemplate, String> {
+        self.create_with_id(uuid::Uuid::new_v4().to_string(), input)
+            .await
+    }
+
+    /// Create a template under a known id, e.g. one synced from another machine
+    pub async fn create_with_id(
+        &self,
+        id: String,
+        input: PromptTemplateInput,
+    ) -> Result<PromptTemplate, String> {
+        let input = validate(input)?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let txn = self.db.begin().await.map_err(|e| e.to_string())?;
+        PromptTemplateActiveModel {
+            id: Set(id.clone()),
+            name: Set(input.name.clone()),
+            description: Set(input.description.clone()),
+            system_prompt: Set(input.system_prompt.clone()),
+            user_prompt: Set(input.user_prompt.clone()),
+            variables: Set(to_json(&input.variables)),
+            tags: Set(tags_json(&input.tags)),
+            version: Set(1),
+            created_at: Set(now.clone()),
+            updated_at: Set(now),
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| format!("Failed to create prompt template: {}", e))?;
+        version_row(&id, 1, &input)
+            .insert(&txn)
+            .await
+            .map_err(|e| format!("Failed to store prompt template version: {}", e))?;
+        txn.commit().await.map_err(|e| e.to_string())?;
+
+        self.get(&id).await
+    }
+
+    /// Save a new version of the template
+    pub async fn update(
+        &self,
+        id: &str,
+        input: PromptTemplateInput,
+    ) -> Result<PromptTemplate, String> {
+        let input = validate(input)?;
+        let existing = self.find(id).await?;
+        let version = existing.version + 1;
+
+        let txn = self.db.begin().await.map_err(|e| e.to_string())?;
+        let mut template: PromptTemplateActiveModel = existing.into();
+        template.name = Set(input.name.clone());
+        template.description = Set(input.description.clone());
+        template.system_prompt = Set(input.system_prompt.clone());
+        template.user_prompt = Set(input.user_prompt.clone());
+        template.variables = Set(to_json(&input.variables));
+        template.tags = Set(tags_json(&input.tags));
+        template.version = Set(version);
+        template.updated_at = Set(chrono::Utc::now().to_rfc3339());
+        template
+            .update(&txn)
+            .await
+            .map_err(|e| format!("Failed to update prompt template: {}", e))?;
+        version_row(id, version, &input)
+            .insert(&txn)
+            .await
+            .map_err(|e| format!("Failed to store prompt template version: {}", e))?;
+        txn.commit().await.map_err(|e| e.to_string())?;
+
+        self.get(id).await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<(), String> {
+        let template = self.find(id).await?;
+        PromptTemplateVersionEntity::delete_many()
+            .filter(PromptTemplateVersionColumn::TemplateId.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| format!("Failed to delete prompt template versions: {}", e))?;
+        template
+            .delete(&self.db)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to delete prompt template: {}", e))
+    }
+
+    /// Every saved version, newest first
+    pub async fn versions(&self, id: &str) -> Result<Vec<PromptTemplateVersion>, String> {
+        Ok(PromptTemplateVersionEntity::find()
+            .filter(PromptTemplateVersionColumn::TemplateId.eq(id))
+            .order_by_desc(PromptTemplateVersionColumn::Version)
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load prompt template versions: {}", e))?
+            .into_iter()
+            .map(to_version)
+            .collect())
+    }
+
+    /// Make an older version current again, saved as a new version
+    pub async fn restore(&self, id: &str, version: i32) -> Result<PromptTemplate, String> {
+        let current = self.get(id).await?;
+        let old = self.find_version(id, version).await?;
+        self.update(
+            id,
+            PromptTemplateInput {
+                name: current.name,
+                description: current.description,
+                system_prompt: old.system_prompt,
+                user_prompt: old.user_prompt,
+                variables: old.variables,
+                tags: current.tags,
+                change_note: Some(format!("Restored version {}", version)),
+            },
+        )
+        .await
+    }
+
+    /// Fill in a template. `input` is the user's message, bound to `{{input}}`.
+    pub async fn render(
+        &self,
+        template: &PromptTemplateRef,
+        input: Option<&str>,
+    ) -> Result<RenderedPrompt, String> {
+        let (version, system, user, variables) = match template.version {
+            Some(version) => {
+                let row = self.find_version(&template.id, version).await?;
+                (
+                    row.version,
+                    row.system_prompt,
+                    row.user_prompt,
+                    row.variables,
+                )
+            }
+            None => {
+                let row = self.get(&template.id).await?;
+                (
+                    row.version,
+                    row.system_prompt,
+                    row.user_prompt,
+                    row.variables,
+                )
+            }
+        };
+
+        let (system, user) = render_prompts(
+            system.as_deref(),
+            user.as_deref(),
+            &variables,
+            &template.variables,
+            input,
+        )?;
+        Ok(RenderedPrompt {
+            template_id: template.id.clone(),
+            version,
+            system,
+            user,
+        })
+    }
+
+    /// Chat messages for `message` rendered through a template: its system
+    /// prompt leads the history and its user prompt replaces the message.
+    pub async fn chat_messages(
+        &self,
+        template: &PromptTemplateRef,
+        history: Vec<ChatMessage>,
+        message: String,
+    ) -> Result<Vec<ChatMessage>, String> {
+        let rendered = self.render(template, Some(&message)).await?;
+        let mut messages = Vec::with_capacity(history.len() + 2);
+        if let Some(system) = rendered.system {
+            messages.push(ChatMessage {
+                role: "system".to_string(),
+                content: system,
+            });
+        }
+        messages.extend(history);
+        messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: rendered.user.unwrap_or(message),
+        });
+        Ok(messages)
+    }
+
+    async fn find(&self, id: &str) -> Result<PromptTemplateModel, String> {
+        PromptTemplateEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load prompt template: {}", e))?
+            .ok_or_else(|| format!("Prompt template {} not found", id))
+    }
+
+    async fn find_version(&self, id: &str, version: i32) -> Result<PromptTemplateVersion, String> {
+        PromptTemplateVersionEntity::find()
+            .filter(PromptTemplateVersionColumn::TemplateId.eq(id))
+            .filter(PromptTemplateVersionColumn::Version.eq(version))
+            .one(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load prompt template version: {}", e))?
+            .map(to_version)
+            .ok_or_else(|| format!("Prompt template {} has no version {}", id, version))
+    }
+}
+
+/// Render both prompts. Declared defaults apply when no value is given, and
+/// any placeholder left without a value is an error.
+fn render_prompts(
+    system: Option<&str>,
+    user: Option<&str>,
+    declared: &[PromptVariable],
+    values: &HashMap<String, String>,
+    input: Option<&str>,
+) -> Result<(Option<String>, Option<String>), String> {
+    let mut resolved: HashMap<String, String> = declared
+        .iter()
+        .filter_map(|v| Some((v.name.clone(), v.default.clone()?)))
+        .collect();
+    resolved.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+    if let Some(input) = input {
+        resolved
+            .entry(INPUT_VARIABLE.to_string())
+            .or_insert_with(|| input.to_string());
+    }
+
+    let mut missing: Vec<String> = declared
+        .iter()
+        .filter(|v| v.required && !resolved.contains_key(&v.name))
+        .map(|v| v.name.clone())
+        .collect();
+    let mut render = |text: Option<&str>| {
+        text.map(|text| {
+            let (output, unresolved) = render_template(text, &resolved);
+            for name in unresolved {
+                if !missing.contains(&name) {
+                    missing.push(name);
+                }
+            }
+            output
+        })
+    };
+    let system = render(system);
+    let user = render(user);
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "Missing values for template variables: {}",
+            missing.join(", ")
+        ));
+    }
+    Ok((system, user))
+}
+
+fn validate(mut input: PromptTemplateInput) -> Result<PromptTemplateInput, String> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err("Template name is required".to_string());
+    }
+    input.system_prompt = input.system_prompt.filter(|p| !p.trim().is_empty());
+    input.user_prompt = input.user_prompt.filter(|p| !p.trim().is_empty());
+    if input.system_prompt.is_none() && input.user_prompt.is_none() {
+        return Err("A template needs a system prompt, a user prompt, or both".to_string());
+    }
+
+    for (i, variable) in input.variables.iter().enumerate() {
+        let valid = !variable.name.is_empty()
+            && variable
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!(
+                "Invalid variable name '{}': use letters, digits and underscores",
+                variable.name
+            ));
+        }
+        if input.variables[..i].iter().any(|v| v.name == variable.name) {
+            return Err(format!("Variable '{}' is declared twice", variable.name));
+        }
+    }
+
+    let no_values = HashMap::new();
+    for prompt in [&input.system_prompt, &input.user_prompt]
+        .into_iter()
+        .flatten()
+    {
+        let (_, used) = render_template(prompt, &no_values);
+        if let Some(undeclared) = used.iter().find(|name| {
+            *name != INPUT_VARIABLE && !input.variables.iter().any(|v| &v.name == *name)
+        }) {
+            return Err(format!(
+                "Placeholder {{{{{}}}}} is not a declared variable",
+                undeclared
+            ));
+        }
+    }
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input
+        .tags
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+    {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    input.tags = tags;
+    Ok(input)
+}
+
+fn version_row(
+    id: &str,
+    version: i32,
+    input: &PromptTemplateInput,
+) -> PromptTemplateVersionActiveModel {
+    PromptTemplateVersionActiveModel {
+        template_id: Set(id.to_string()),
+        version: Set(version),
+        system_prompt: Set(input.system_prompt.clone()),
+        user_prompt: Set(input.user_prompt.clone()),
+        variables: Set(to_json(&input.variables)),
+        change_note: Set(input.change_note.clone()),
+        created_at: Set(chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
+    }
+}
+
+fn to_json(variables: &[PromptVariable]) -> String {
+    serde_json::to_string(variables).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn tags_json(tags: &[String]) -> Option<String> {
+    (!tags.is_empty()).then(|| serde_json::to_string(tags).unwrap_or_default())
+}
+
+fn to_template(model: PromptTemplateModel) -> PromptTemplate {
+    PromptTemplate {
+        variables: serde_json::from_str(&model.variables).unwrap_or_default(),
+        tags: model
+            .tags
+            .as_deref()
+            .and_then(|t| serde_json::from_str(t).ok())
+            .unwrap_or_default(),
+        id: model.id,
+        name: model.name,
+        description: model.description,
+        system_prompt: model.system_prompt,
+        user_prompt: model.user_prompt,
+        version: model.version,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
+
+fn to_version(model: PromptTemplateVersionModel) -> PromptTemplateVersion {
+    PromptTemplateVersion {
+        version: model.version,
+        system_prompt: model.system_prompt,
+        user_prompt: model.user_prompt,
+        variables: serde_json::from_str(&model.variables).unwrap_or_default(),
+        change_note: model.change_note,
+        created_at: model.created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(name: &str, default: Option<&str>, required: bool) -> PromptVariable {
+        PromptVariable {
+            name: name.to_string(),
+            description: None,
+            default: default.map(str::to_string),
+            required,
+        }
+    }
+
+    #[test]
+    fn renders_defaults_values_and_input() {
+        let declared = [
+            variable("language", Some("Rust"), false),
+            variable("tone", None, true),
+        ];
+        let values = HashMap::from([("tone".to_string(), "terse".to_string())]);
+
+        let (system, user) = render_prompts(
+            Some("You review {{language}} code. Be {{ tone }}."),
+            Some("Review:\n{{input}}"),
+            &declared,
+            &values,
+            Some("fn main() {}"),
+        )
+        .unwrap();
+        assert_eq!(system.unwrap(), "You review Rust code. Be terse.");
+        assert_eq!(user.unwrap(), "Review:\nfn main() {}");
+
+        let err = render_prompts(Some("Be {{tone}}"), None, &declared, &HashMap::new(), None)
+            .unwrap_err();
+        assert_eq!(err, "Missing values for template variables: tone");
+    }
+
+    #[test]
+    fn rejects_undeclared_placeholders() {
+        let input = PromptTemplateInput {
+            name: " Review ".to_string(),
+            description: None,
+            system_prompt: Some("Use {{style}} for {{input}}".to_string()),
+            user_prompt: None,
+            variables: vec![],
+            tags: vec!["code".to_string(), "Code".to_string()],
+            change_note: None,
+        };
+        assert_eq!(
+            validate(input.clone()).unwrap_err(),
+            "Placeholder {{style}} is not a declared variable"
+        );
+
+        let input = validate(PromptTemplateInput {
+            variables: vec![variable("style", None, false)],
+            ..input
+        })
+        .unwrap();
+        assert_eq!(input.name, "Review");
+        assert_eq!(input.tags, ["code"]);
+    }
+}
@@ -2,8 +2,8 @@ use crate::domains::ai::catalog::{CatalogQuery, PlatformCatalog};
 use crate::domains::ai::message::ChatMessage;
 use crate::domains::ai::platform_config::DEFAULT_PLATFORM_BASE;
 use crate::domains::ai::providers::{
-    AIError, AIProvider, ConfigurationStatus, GenerationOptions, GenerationResult, ProviderConfig,
-    ProviderType,
+    AIError, AIProvider, ConfigurationStatus, EmbeddingResult, GenerationOptions, GenerationResult,
+    ProviderConfig, ProviderType, TokenUsage,
 };
 use futures_util::StreamExt;
 use reqwest::Client;
@@ -86,10 +86,7 @@ impl AgentPlatformProvider {
             req = req.query(&[("live", if live { "true" } else { "false" })]);
         }
         if let Some(probe) = query.probe_capabilities {
-            req = req.query(&[(
-                "probe_capabilities",
-                if probe { "true" } else { "false" },
-            )]);
+            req = req.query(&[("probe_capabilities", if probe { "true" } else { "false" })]);
         }
 
         let response = req
@@ -140,6 +137,10 @@ impl AgentPlatformProvider {
         if let Some(max_tokens) = options.max_tokens {
             body["max_tokens"] = json!(max_tokens);
         }
+        if stream {
+            // Ask for a final chunk carrying token usage
+            body["stream_options"] = json!({ "include_usage": true });
+        }
         if let Some(provider) = options
             .llm_provider
             .as_ref()
@@ -148,6 +149,12 @@ impl AgentPlatformProvider {
         {
             body["provider"] = json!(provider);
         }
+        // Provider-specific fields (response_format, top_p, ...) pass straight through
+        if let Some(Value::Object(extra)) = &options.extra_options {
+            for (key, value) in extra {
+                body[key.as_str()] = value.clone();
+            }
+        }
 
         let timeout = std::time::Duration::from_millis(options.timeout_ms.unwrap_or(120_000));
         let response = self
@@ -173,6 +180,13 @@ impl AgentPlatformProvider {
                 "Invalid or missing agent-platform API token".to_string(),
             ));
         }
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(AIError::RateLimitError(format!(
+                "agent-platform is rate limited: {}",
+                error_text
+            )));
+        }
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response
@@ -187,7 +201,9 @@ impl AgentPlatformProvider {
         Ok(response)
     }
 
-    fn parse_completion(response: Value) -> Result<(String, String, Option<u32>), AIError> {
+    pub(crate) fn parse_completion(
+        response: Value,
+    ) -> Result<(String, String, Option<TokenUsage>), AIError> {
         let content = response
             .get("choices")
             .and_then(|c| c.get(0))
@@ -201,21 +217,17 @@ impl AgentPlatformProvider {
             .and_then(Value::as_str)
             .unwrap_or("unknown")
             .to_string();
-        let tokens = response
-            .get("usage")
-            .and_then(|u| u.get("total_tokens"))
-            .and_then(Value::as_u64)
-            .map(|n| n as u32);
-        Ok((content, model, tokens))
+        let usage = response.get("usage").and_then(TokenUsage::from_json);
+        Ok((content, model, usage))
     }
 
-    async fn consume_sse_stream(
-        &self,
+    pub(crate) async fn consume_sse_stream(
         response: reqwest::Response,
         mut on_chunk: Option<Box<dyn FnMut(String) -> Result<(), AIError> + Send>>,
-    ) -> Result<(String, String, Option<u32>), AIError> {
+    ) -> Result<(String, String, Option<TokenUsage>), AIError> {
         let mut content = String::new();
         let mut model = String::new();
+        let mut usage = None;
         let mut buf: Vec<u8> = Vec::new();
         let mut stream = response.bytes_stream();
 
@@ -240,6 +252,9 @@ impl AgentPlatformProvider {
                 if let Some(m) = value.get("model").and_then(Value::as_str) {
                     model = m.to_string();
                 }
+                if let Some(reported) = value.get("usage").and_then(TokenUsage::from_json) {
+                    usage = Some(reported);
+                }
                 if let Some(delta) = value
                     .get("choices")
                     .and_then(|c| c.get(0))
@@ -257,7 +272,7 @@ impl AgentPlatformProvider {
             }
         }
 
-        Ok((content, model, None))
+        Ok((content, model, usage))
     }
 }
 
@@ -332,12 +347,15 @@ impl AIProvider for AgentPlatformProvider {
             .json()
             .await
             .map_err(|e| AIError::InvalidResponse(e.to_string()))?;
-        let (content, model, tokens_used) = Self::parse_completion(value)?;
+        let (content, model, usage) = Self::parse_completion(value)?;
         Ok(GenerationResult {
             content,
             model,
-            tokens_used,
+            tokens_used: usage.map(|u| u.total()),
+            usage,
             generation_time_ms: Some(start.elapsed().as_millis() as u64),
+            served_by: None,
+            fell_back_from: Vec::new(),
         })
     }
 
@@ -350,13 +368,15 @@ impl AIProvider for AgentPlatformProvider {
         let start = Instant::now();
         let messages = vec![json!({"role": "user", "content": prompt})];
         let response = self.chat_completion(messages, options, true).await?;
-        let (content, model, tokens_used) =
-            self.consume_sse_stream(response, Some(on_chunk)).await?;
+        let (content, model, usage) = Self::consume_sse_stream(response, Some(on_chunk)).await?;
         Ok(GenerationResult {
             content,
             model,
-            tokens_used,
+            tokens_used: usage.map(|u| u.total()),
+            usage,
             generation_time_ms: Some(start.elapsed().as_millis() as u64),
+            served_by: None,
+            fell_back_from: Vec::new(),
         })
     }
 
@@ -376,12 +396,15 @@ impl AIProvider for AgentPlatformProvider {
             .json()
             .await
             .map_err(|e| AIError::InvalidResponse(e.to_string()))?;
-        let (content, model, tokens_used) = Self::parse_completion(value)?;
+        let (content, model, usage) = Self::parse_completion(value)?;
         Ok(GenerationResult {
             content,
             model,
-            tokens_used,
+            tokens_used: usage.map(|u| u.total()),
+            usage,
             generation_time_ms: Some(start.elapsed().as_millis() as u64),
+            served_by: None,
+            fell_back_from: Vec::new(),
         })
     }
 
@@ -400,12 +423,15 @@ impl AIProvider for AgentPlatformProvider {
             .json()
             .await
             .map_err(|e| AIError::InvalidResponse(e.to_string()))?;
-        let (content, model, tokens_used) = Self::parse_completion(value)?;
+        let (content, model, usage) = Self::parse_completion(value)?;
         Ok(GenerationResult {
             content,
             model,
-            tokens_used,
+            tokens_used: usage.map(|u| u.total()),
+            usage,
             generation_time_ms: Some(start.elapsed().as_millis() as u64),
+            served_by: None,
+            fell_back_from: Vec::new(),
         })
     }
 
@@ -421,13 +447,108 @@ impl AIProvider for AgentPlatformProvider {
             .map(|m| json!({"role": m.role, "content": m.content}))
             .collect();
         let response = self.chat_completion(api_messages, options, true).await?;
-        let (content, model, tokens_used) =
-            self.consume_sse_stream(response, Some(on_chunk)).await?;
+        let (content, model, usage) = Self::consume_sse_stream(response, Some(on_chunk)).await?;
         Ok(GenerationResult {
             content,
             model,
-            tokens_used,
+            tokens_used: usage.map(|u| u.total()),
+            usage,
             generation_time_ms: Some(start.elapsed().as_millis() as u64),
+            served_by: None,
+            fell_back_from: Vec::new(),
+        })
+    }
+
+    async fn embed(
+        &self,
+        inputs: &[String],
+        model: Option<&str>,
+    ) -> Result<EmbeddingResult, AIError> {
+        let model = model
+            .map(str::to_string)
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or_else(|| self.config.model.clone());
+        let url = format!("{}/v1/embeddings", self.base_url());
+        let body = json!({ "model": model, "input": inputs });
+
+        let response = self
+            .authed(self.client.post(&url).json(&body))
+            .timeout(std::time::Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    AIError::NetworkError(format!(
+                        "Cannot reach agent-platform at {}. Is it running?",
+                        self.base_url()
+                    ))
+                } else {
+                    AIError::NetworkError(format!("Network error: {}", e))
+                }
+            })?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(AIError::AuthenticationError(
+                "Invalid or missing agent-platform API token".to_string(),
+            ));
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AIError::InvalidResponse(format!(
+                "agent-platform embeddings returned status {}: {}",
+                status, error_text
+            )));
+        }
+
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| AIError::InvalidResponse(e.to_string()))?;
+        let mut data: Vec<(u64, Vec<f32>)> = value
+            .get("data")
+            .and_then(Value::as_array)
+            .ok_or_else(|| AIError::InvalidResponse("Embeddings response has no data".into()))?
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let index = item
+                    .get("index")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(i as u64);
+                let vector = item
+                    .get("embedding")
+                    .and_then(Value::as_array)
+                    .map(|v| {
+                        v.iter()
+                            .filter_map(Value::as_f64)
+                            .map(|x| x as f32)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (index, vector)
+            })
+            .collect();
+        data.sort_by_key(|(index, _)| *index);
+
+        if data.len() != inputs.len() || data.iter().any(|(_, v)| v.is_empty()) {
+            return Err(AIError::InvalidResponse(format!(
+                "Expected {} embeddings, got {}",
+                inputs.len(),
+                data.len()
+            )));
+        }
+
+        Ok(EmbeddingResult {
+            embeddings: data.into_iter().map(|(_, v)| v).collect(),
+            model: value
+                .get("model")
+                .and_then(Value::as_str)
+                .unwrap_or(&model)
+                .to_string(),
         })
     }
 
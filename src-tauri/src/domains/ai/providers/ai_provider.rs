@@ -6,6 +6,8 @@ use std::error::Error;
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ProviderType {
     AgentPlatform,
+    /// GGUF models on this machine, run by a managed llama.cpp server
+    LlamaCpp,
 }
 
 /// Configuration options for AI generation
@@ -23,6 +25,13 @@ pub struct GenerationOptions {
     pub llm_provider: Option<String>,
     /// Additional provider-specific options
     pub extra_options: Option<serde_json::Value>,
+    /// App feature making the request (chat, task_generation, docs, ...), used
+    /// to group usage stats
+    #[serde(default)]
+    pub feature: Option<String>,
+    /// Skip the response cache, e.g. when the same prompt should get a fresh reply
+    #[serde(default)]
+    pub bypass_cache: bool,
 }
 
 impl Default for GenerationOptions {
@@ -34,6 +43,8 @@ impl Default for GenerationOptions {
             model: None,
             llm_provider: None,
             extra_options: None,
+            feature: None,
+            bypass_cache: false,
         }
     }
 }
@@ -47,8 +58,58 @@ pub struct GenerationResult {
     pub model: String,
     /// Tokens used (if available)
     pub tokens_used: Option<u32>,
+    /// Prompt/completion split, when the backend reports it
+    #[serde(default)]
+    pub usage: Option<TokenUsage>,
     /// Generation time in milliseconds
     pub generation_time_ms: Option<u64>,
+    /// agent-platform backend that produced the result; `None` is the
+    /// platform's default
+    #[serde(default)]
+    pub served_by: Option<String>,
+    /// Backends tried first that failed, in order
+    #[serde(default)]
+    pub fell_back_from: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+impl TokenUsage {
+    /// Read an OpenAI-style `usage` object
+    pub fn from_json(usage: &serde_json::Value) -> Option<Self> {
+        let count = |key: &str| {
+            usage
+                .get(key)
+                .and_then(serde_json::Value::as_u64)
+                .map(|n| n as u32)
+        };
+        let prompt_tokens = count("prompt_tokens");
+        let completion_tokens = count("completion_tokens");
+        if prompt_tokens.is_none() && completion_tokens.is_none() {
+            return None;
+        }
+        Some(Self {
+            prompt_tokens: prompt_tokens.unwrap_or(0),
+            completion_tokens: completion_tokens.unwrap_or(0),
+        })
+    }
+
+    pub fn total(&self) -> u32 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// Result of an embeddings request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingResult {
+    /// One vector per input, in input order
+    pub embeddings: Vec<Vec<f32>>,
+    /// Model that produced the vectors
+    pub model: String,
 }
 
 /// Provider configuration
@@ -208,6 +269,19 @@ pub trait AIProvider: Send + Sync {
         self.generate_stream(&prompt, options, on_chunk).await
     }
 
+    /// Embed each of `inputs` as a vector.
+    async fn embed(
+        &self,
+        inputs: &[String],
+        model: Option<&str>,
+    ) -> Result<EmbeddingResult, AIError> {
+        let _ = (inputs, model);
+        Err(AIError::ProviderNotAvailable(format!(
+            "{} does not support embeddings",
+            self.name()
+        )))
+    }
+
     /// Get available models for this provider
     async fn get_available_models(&self) -> Result<Vec<String>, AIError>;
 
@@ -0,0 +1,272 @@
+use crate::domains::ai::local_models::{LlamaCppRuntime, LLAMA_CPP_BACKEND};
+use crate::domains::ai::message::ChatMessage;
+use crate::domains::ai::providers::{
+    AIError, AIProvider, AgentPlatformProvider, ConfigurationStatus, GenerationOptions,
+    GenerationResult, ProviderConfig, ProviderType,
+};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// GGUF models run by the app's own llama.cpp server. The server speaks the
+/// same OpenAI-style API as agent-platform, so replies are parsed the same way.
+pub struct LlamaCppProvider {
+    runtime: Arc<LlamaCppRuntime>,
+    client: Client,
+}
+
+impl LlamaCppProvider {
+    pub fn new(runtime: Arc<LlamaCppRuntime>) -> Self {
+        Self {
+            runtime,
+            client: Client::new(),
+        }
+    }
+
+    async fn chat_completion(
+        &self,
+        messages: Vec<Value>,
+        options: &GenerationOptions,
+        stream: bool,
+    ) -> Result<reqwest::Response, AIError> {
+        let model = match options.model.as_deref().map(str::trim) {
+            Some(model) if !model.is_empty() => model.to_string(),
+            _ => self.runtime.default_model().await.ok_or_else(|| {
+                AIError::ProviderNotAvailable("No local GGUF models are installed".to_string())
+            })?,
+        };
+        let base_url = self
+            .runtime
+            .ensure_running(&model)
+            .await
+            .map_err(AIError::ProviderNotAvailable)?;
+
+        let mut body = json!({
+            "model": model,
+            "messages": messages,
+            "stream": stream,
+        });
+        if let Some(temp) = options.temperature {
+            body["temperature"] = json!(temp);
+        }
+        if let Some(max_tokens) = options.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        if stream {
+            body["stream_options"] = json!({ "include_usage": true });
+        }
+        if let Some(Value::Object(extra)) = &options.extra_options {
+            for (key, value) in extra {
+                body[key.as_str()] = value.clone();
+            }
+        }
+
+        let timeout = std::time::Duration::from_millis(options.timeout_ms.unwrap_or(120_000));
+        let response = self
+            .client
+            .post(format!("{}/v1/chat/completions", base_url))
+            .json(&body)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    AIError::TimeoutError(format!("Request timed out after {:?}", timeout))
+                } else {
+                    AIError::NetworkError(format!("llama-server request failed: {}", e))
+                }
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AIError::InvalidResponse(format!(
+                "llama-server returned status {}: {}",
+                status, error_text
+            )));
+        }
+        Ok(response)
+    }
+
+    async fn complete(
+        &self,
+        messages: Vec<Value>,
+        options: &GenerationOptions,
+    ) -> Result<GenerationResult, AIError> {
+        let start = Instant::now();
+        let value: Value = self
+            .chat_completion(messages, options, false)
+            .await?
+            .json()
+            .await
+            .map_err(|e| AIError::InvalidResponse(e.to_string()))?;
+        let (content, model, usage) = AgentPlatformProvider::parse_completion(value)?;
+        Ok(GenerationResult {
+            content,
+            model,
+            tokens_used: usage.map(|u| u.total()),
+            usage,
+            generation_time_ms: Some(start.elapsed().as_millis() as u64),
+            served_by: Some(LLAMA_CPP_BACKEND.to_string()),
+            fell_back_from: Vec::new(),
+        })
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: Vec<Value>,
+        options: &GenerationOptions,
+        on_chunk: Box<dyn FnMut(String) -> Result<(), AIError> + Send>,
+    ) -> Result<GenerationResult, AIError> {
+        let start = Instant::now();
+        let response = self.chat_completion(messages, options, true).await?;
+        let (content, model, usage) =
+            AgentPlatformProvider::consume_sse_stream(response, Some(on_chunk)).await?;
+        Ok(GenerationResult {
+            content,
+            model,
+            tokens_used: usage.map(|u| u.total()),
+            usage,
+            generation_time_ms: Some(start.elapsed().as_millis() as u64),
+            served_by: Some(LLAMA_CPP_BACKEND.to_string()),
+            fell_back_from: Vec::new(),
+        })
+    }
+}
+
+fn api_messages(messages: &[ChatMessage]) -> Vec<Value> {
+    messages
+        .iter()
+        .map(|m| json!({"role": m.role, "content": m.content}))
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl AIProvider for LlamaCppProvider {
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::LlamaCpp
+    }
+
+    fn name(&self) -> &str {
+        "llama.cpp"
+    }
+
+    async fn is_available(&self) -> Result<bool, AIError> {
+        let has_models = !self
+            .runtime
+            .list_models()
+            .map_err(AIError::GenericError)?
+            .is_empty();
+        Ok(has_models && self.runtime.server_binary().is_some())
+    }
+
+    async fn test_connection(&self) -> Result<(), AIError> {
+        let status = self.check_configuration();
+        if !status.is_configured {
+            return Err(AIError::ConfigurationIncomplete(status));
+        }
+        let model = self.runtime.default_model().await.ok_or_else(|| {
+            AIError::ProviderNotAvailable("No local GGUF models are installed".to_string())
+        })?;
+        self.runtime
+            .ensure_running(&model)
+            .await
+            .map(|_| ())
+            .map_err(AIError::ProviderNotAvailable)
+    }
+
+    async fn generate(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+    ) -> Result<GenerationResult, AIError> {
+        self.complete(vec![json!({"role": "user", "content": prompt})], options)
+            .await
+    }
+
+    async fn generate_stream(
+        &self,
+        prompt: &str,
+        options: &GenerationOptions,
+        on_chunk: Box<dyn FnMut(String) -> Result<(), AIError> + Send>,
+    ) -> Result<GenerationResult, AIError> {
+        self.complete_stream(
+            vec![json!({"role": "user", "content": prompt})],
+            options,
+            on_chunk,
+        )
+        .await
+    }
+
+    async fn generate_with_system(
+        &self,
+        system_message: &str,
+        user_message: &str,
+        options: &GenerationOptions,
+    ) -> Result<GenerationResult, AIError> {
+        let messages = vec![
+            json!({"role": "system", "content": system_message}),
+            json!({"role": "user", "content": user_message}),
+        ];
+        self.complete(messages, options).await
+    }
+
+    async fn generate_chat(
+        &self,
+        messages: &[ChatMessage],
+        options: &GenerationOptions,
+    ) -> Result<GenerationResult, AIError> {
+        self.complete(api_messages(messages), options).await
+    }
+
+    async fn generate_chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        options: &GenerationOptions,
+        on_chunk: Box<dyn FnMut(String) -> Result<(), AIError> + Send>,
+    ) -> Result<GenerationResult, AIError> {
+        self.complete_stream(api_messages(messages), options, on_chunk)
+            .await
+    }
+
+    async fn get_available_models(&self) -> Result<Vec<String>, AIError> {
+        Ok(self
+            .runtime
+            .list_models()
+            .map_err(AIError::GenericError)?
+            .into_iter()
+            .map(|m| m.name)
+            .collect())
+    }
+
+    /// Requests without a model use the loaded (or first installed) GGUF
+    fn default_model(&self) -> &str {
+        ""
+    }
+
+    fn check_configuration(&self) -> ConfigurationStatus {
+        let mut missing_fields = Vec::new();
+        if self.runtime.server_binary().is_none() {
+            missing_fields.push("llama_cpp_runtime".to_string());
+        }
+        if self.runtime.list_models().map_or(true, |m| m.is_empty()) {
+            missing_fields.push("model".to_string());
+        }
+        ConfigurationStatus {
+            is_configured: missing_fields.is_empty(),
+            missing_fields,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn update_config(&mut self, config: ProviderConfig) -> Result<(), AIError> {
+        if config.provider_type != ProviderType::LlamaCpp {
+            return Err(AIError::GenericError("Provider type mismatch".to_string()));
+        }
+        Ok(())
+    }
+}
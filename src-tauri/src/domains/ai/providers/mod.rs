@@ -1,8 +1,10 @@
 pub mod agent_platform_provider;
 pub mod ai_provider;
+pub mod llama_cpp_provider;
 
 pub use agent_platform_provider::AgentPlatformProvider;
 pub use ai_provider::{
-    AIError, AIProvider, ConfigurationStatus, GenerationOptions, GenerationResult, ProviderConfig,
-    ProviderType,
+    AIError, AIProvider, ConfigurationStatus, EmbeddingResult, GenerationOptions, GenerationResult,
+    ProviderConfig, ProviderType, TokenUsage,
 };
+pub use llama_cpp_provider::LlamaCppProvider;
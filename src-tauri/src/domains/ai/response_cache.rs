@@ -0,0 +1,224 @@
+//! Reuse of generations for identical requests.
+//!
+//! Non-streaming generations are keyed by a hash of the backend, model, the
+//! options that shape the reply and the prompt/messages. A hit within the TTL
+//! is returned without calling the provider (and without recording usage).
+//! Callers that want a fresh reply every time, like chat, set `bypass_cache`.
+
+use crate::domains::ai::providers::{GenerationOptions, GenerationResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseCacheSettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// How long a cached reply is reused
+    #[serde(default = "default_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// Oldest entries are evicted beyond this
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_ttl_seconds() -> u64 {
+    3600
+}
+
+fn default_max_entries() -> usize {
+    500
+}
+
+impl Default for ResponseCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            ttl_seconds: default_ttl_seconds(),
+            max_entries: default_max_entries(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseCacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+struct CachedResponse {
+    stored_at: Instant,
+    result: GenerationResult,
+}
+
+#[derive(Default)]
+struct CacheState {
+    settings: ResponseCacheSettings,
+    entries: HashMap<String, CachedResponse>,
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Default)]
+pub struct ResponseCache {
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    pub fn configure(&self, settings: ResponseCacheSettings) {
+        let mut state = self.lock();
+        if !settings.enabled {
+            state.entries.clear();
+        }
+        state.settings = settings;
+        let max_entries = state.settings.max_entries;
+        evict(&mut state.entries, max_entries);
+    }
+
+    /// Key for a request, or `None` when caching is off or bypassed
+    pub fn key(&self, request: &Value, options: &GenerationOptions) -> Option<String> {
+        if options.bypass_cache || !self.lock().settings.enabled {
+            return None;
+        }
+        Some(cache_key(request, options))
+    }
+
+    pub fn get(&self, key: &str) -> Option<GenerationResult> {
+        let mut state = self.lock();
+        let ttl = Duration::from_secs(state.settings.ttl_seconds);
+        let fresh = state
+            .entries
+            .get(key)
+            .filter(|entry| entry.stored_at.elapsed() < ttl)
+            .map(|entry| entry.result.clone());
+        match fresh {
+            Some(result) => {
+                state.hits += 1;
+                Some(result)
+            }
+            None => {
+                state.entries.remove(key);
+                state.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn put(&self, key: String, result: &GenerationResult) {
+        let mut state = self.lock();
+        state.entries.insert(
+            key,
+            CachedResponse {
+                stored_at: Instant::now(),
+                result: result.clone(),
+            },
+        );
+        let max_entries = state.settings.max_entries;
+        evict(&mut state.entries, max_entries);
+    }
+
+    /// Drop every entry. Returns how many there were.
+    pub fn clear(&self) -> usize {
+        let mut state = self.lock();
+        let cleared = state.entries.len();
+        state.entries.clear();
+        cleared
+    }
+
+    pub fn stats(&self) -> ResponseCacheStats {
+        let state = self.lock();
+        ResponseCacheStats {
+            entries: state.entries.len(),
+            hits: state.hits,
+            misses: state.misses,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Hash of everything that shapes the reply. Timeouts and the feature tag
+/// don't, so they're left out.
+fn cache_key(request: &Value, options: &GenerationOptions) -> String {
+    let material = json!({
+        "llm_provider": options.llm_provider,
+        "model": options.model,
+        "temperature": options.temperature,
+        "max_tokens": options.max_tokens,
+        "extra_options": options.extra_options,
+        "request": request,
+    });
+    format!("{:x}", Sha256::digest(material.to_string().as_bytes()))
+}
+
+fn evict(entries: &mut HashMap<String, CachedResponse>, max_entries: usize) {
+    while entries.len() > max_entries {
+        let Some(oldest) = entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.stored_at)
+            .map(|(key, _)| key.clone())
+        else {
+            break;
+        };
+        entries.remove(&oldest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(content: &str) -> GenerationResult {
+        GenerationResult {
+            content: content.to_string(),
+            model: "llama3".to_string(),
+            tokens_used: None,
+            usage: None,
+            generation_time_ms: None,
+            served_by: None,
+            fell_back_from: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn caches_by_request_and_options() {
+        let cache = ResponseCache::default();
+        cache.configure(ResponseCacheSettings {
+            max_entries: 1,
+            ..Default::default()
+        });
+        let options = GenerationOptions::default();
+        let request = json!({ "prompt": "Detect the framework" });
+
+        let key = cache.key(&request, &options).unwrap();
+        assert!(cache.get(&key).is_none());
+        cache.put(key.clone(), &result("react"));
+        assert_eq!(cache.get(&key).unwrap().content, "react");
+
+        let warmer = GenerationOptions {
+            temperature: Some(1.2),
+            ..GenerationOptions::default()
+        };
+        let other = cache.key(&request, &warmer).unwrap();
+        assert_ne!(key, other);
+        cache.put(other, &result("vue"));
+        assert!(cache.get(&key).is_none(), "oldest entry is evicted");
+
+        let bypass = GenerationOptions {
+            bypass_cache: true,
+            ..GenerationOptions::default()
+        };
+        assert!(cache.key(&request, &bypass).is_none());
+        assert_eq!(cache.stats().hits, 1);
+    }
+}
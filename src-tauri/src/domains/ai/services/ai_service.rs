@@ -1,27 +1,242 @@
+use crate::domains::ai::local_models::LLAMA_CPP_BACKEND;
 use crate::domains::ai::message::ChatMessage;
 use crate::domains::ai::providers::{
-    AIError, AIProvider, AgentPlatformProvider, ConfigurationStatus, GenerationOptions,
-    GenerationResult, ProviderConfig, ProviderType,
+    AIError, AIProvider, AgentPlatformProvider, ConfigurationStatus, EmbeddingResult,
+    GenerationOptions, GenerationResult, ProviderConfig, ProviderType,
 };
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use crate::domains::ai::redaction::{PromptRedactor, RedactionReport};
+use crate::domains::ai::response_cache::{
+    ResponseCache, ResponseCacheSettings, ResponseCacheStats,
+};
+use crate::domains::ai::services::{FallbackTarget, FeatureRoute};
+use crate::domains::ai::tools::ToolRegistry;
+use crate::domains::ai::usage::AIUsageLog;
+use crate::{log_info, log_warn};
+use sea_orm::DatabaseConnection;
+use serde_json::{json, Value};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{oneshot, RwLock};
+
+/// A running stream's token, telling it apart from a later stream reusing
+/// its id, and the sender that cancels it
+type StreamHandle = (u64, oneshot::Sender<()>);
 
-/// Centralized AI service — all inference goes through agent-platform.
+/// Centralized AI service — inference goes through agent-platform, except
+/// requests for the `llama_cpp` backend, which run on local GGUF models.
 pub struct AIService {
     provider: Arc<RwLock<Option<Arc<dyn AIProvider>>>>,
+    local_provider: RwLock<Option<Arc<dyn AIProvider>>>,
+    tools: Arc<ToolRegistry>,
+    /// In-flight streams by stream id; sending on the channel cancels one
+    streams: Mutex<HashMap<String, StreamHandle>>,
+    next_stream_token: AtomicU64,
+    fallback_chain: RwLock<Vec<FallbackTarget>>,
+    feature_routes: RwLock<HashMap<String, FeatureRoute>>,
+    response_cache: ResponseCache,
+    /// Scrubs secrets from every prompt before it's sent
+    redactor: PromptRedactor,
+    /// Where generations are accounted; unset until the database is up
+    usage_log: Mutex<Option<AIUsageLog>>,
 }
 
 impl AIService {
     pub fn new() -> Self {
         Self {
             provider: Arc::new(RwLock::new(None)),
+            local_provider: RwLock::new(None),
+            tools: Arc::new(ToolRegistry::new()),
+            streams: Mutex::new(HashMap::new()),
+            next_stream_token: AtomicU64::new(0),
+            fallback_chain: RwLock::new(Vec::new()),
+            feature_routes: RwLock::new(HashMap::new()),
+            response_cache: ResponseCache::default(),
+            redactor: PromptRedactor::default(),
+            usage_log: Mutex::new(None),
+        }
+    }
+
+    /// Record token usage for every generation from now on, in place of any
+    /// log attached before
+    pub fn attach_usage_log(&self, usage_log: AIUsageLog) {
+        *self.usage_log.lock().unwrap() = Some(usage_log);
+    }
+
+    /// Redact stored credential values too, not just token formats. Replaces
+    /// the database attached before, e.g. after switching workspaces.
+    pub fn attach_redaction_credentials(&self, db: DatabaseConnection) {
+        self.redactor.attach_credentials(db);
+    }
+
+    async fn record_redaction(&self, options: &GenerationOptions, report: RedactionReport) {
+        if report.is_empty() {
+            return;
+        }
+        log_info!(
+            "AI",
+            "Redacted {:?} from a {} prompt",
+            report.redactions,
+            options.feature.as_deref().unwrap_or("other")
+        );
+        if let Some(usage_log) = self.usage_log() {
+            if let Err(e) = usage_log.record_redaction(options, &report).await {
+                log_warn!("AI", "{}", e);
+            }
+        }
+    }
+
+    pub fn usage_log(&self) -> Option<AIUsageLog> {
+        self.usage_log.lock().unwrap().clone()
+    }
+
+    pub async fn set_fallback_chain(&self, chain: Vec<FallbackTarget>) {
+        *self.fallback_chain.write().await = chain;
+    }
+
+    pub fn configure_response_cache(&self, settings: ResponseCacheSettings) {
+        self.response_cache.configure(settings);
+    }
+
+    pub fn clear_response_cache(&self) -> usize {
+        self.response_cache.clear()
+    }
+
+    pub fn response_cache_stats(&self) -> ResponseCacheStats {
+        self.response_cache.stats()
+    }
+
+    /// Serve `request` from the response cache, or run it and cache the reply
+    async fn with_cache<F, Fut>(
+        &self,
+        request: Value,
+        options: GenerationOptions,
+        run: F,
+    ) -> Result<GenerationResult, AIError>
+    where
+        F: FnOnce(GenerationOptions) -> Fut,
+        Fut: Future<Output = Result<GenerationResult, AIError>>,
+    {
+        let key = self.response_cache.key(&request, &options);
+        if let Some(cached) = key.as_deref().and_then(|key| self.response_cache.get(key)) {
+            return Ok(cached);
+        }
+        let result = run(options).await?;
+        if let Some(key) = key {
+            self.response_cache.put(key, &result);
+        }
+        Ok(result)
+    }
+
+    pub async fn set_feature_routes(&self, routes: HashMap<String, FeatureRoute>) {
+        *self.feature_routes.write().await = routes;
+    }
+
+    /// Options for a request: sent to the local backend when `LlamaCpp` is
+    /// asked for, otherwise routed by feature if the caller didn't pick a model
+    async fn resolve_options(
+        &self,
+        options: Option<GenerationOptions>,
+        provider_type: Option<ProviderType>,
+    ) -> GenerationOptions {
+        let mut options = options.unwrap_or_default();
+        if provider_type == Some(ProviderType::LlamaCpp) {
+            options.llm_provider = Some(LLAMA_CPP_BACKEND.to_string());
+            return options;
+        }
+        let routes = self.feature_routes.read().await;
+        if let Some(route) = options.feature.as_ref().and_then(|f| routes.get(f)) {
+            apply_route(&mut options, route);
+        }
+        options
+    }
+
+    /// Options for each backend to try: the requested one first, then the
+    /// fallback chain (skipping the requested backend if it's listed).
+    async fn fallback_attempts(&self, options: GenerationOptions) -> Vec<GenerationOptions> {
+        let chain = self.fallback_chain.read().await;
+        let requested = options.llm_provider.clone();
+        let mut attempts = vec![options.clone()];
+        attempts.extend(
+            chain
+                .iter()
+                .filter(|target| requested.as_deref() != Some(target.llm_provider.as_str()))
+                .map(|target| GenerationOptions {
+                    llm_provider: Some(target.llm_provider.clone()),
+                    model: target.model.clone(),
+                    ..options.clone()
+                }),
+        );
+        attempts
+    }
+
+    /// Run `attempt` against each backend until one succeeds or fails with an
+    /// error another backend can't fix, recording which backend served it.
+    async fn with_fallback<F, Fut>(
+        &self,
+        options: GenerationOptions,
+        mut attempt: F,
+    ) -> Result<GenerationResult, AIError>
+    where
+        F: FnMut(GenerationOptions) -> Fut,
+        Fut: Future<Output = Result<GenerationResult, AIError>>,
+    {
+        let attempts = self.fallback_attempts(options).await;
+        let last = attempts.len() - 1;
+        // Local models don't depend on agent-platform, so any failure can fall back to them
+        let next_is_local: Vec<bool> = attempts
+            .iter()
+            .skip(1)
+            .map(|o| o.llm_provider.as_deref() == Some(LLAMA_CPP_BACKEND))
+            .collect();
+        let mut fell_back_from = Vec::new();
+        for (index, options) in attempts.into_iter().enumerate() {
+            let backend = options.llm_provider.clone();
+            let mut outcome = attempt(options.clone()).await;
+            if let Ok(result) = &mut outcome {
+                result.served_by = backend.clone();
+            }
+            if let Some(usage_log) = self.usage_log() {
+                let recorded = match &outcome {
+                    Ok(result) => usage_log.record_success(&options, result).await,
+                    Err(e) => usage_log.record_failure(&options, &e.to_string()).await,
+                };
+                if let Err(e) = recorded {
+                    log_warn!("AI", "{}", e);
+                }
+            }
+            match outcome {
+                Ok(mut result) => {
+                    result.fell_back_from = fell_back_from;
+                    return Ok(result);
+                }
+                Err(e) if index < last && (should_fall_back(&e) || next_is_local[index]) => {
+                    let backend = backend.unwrap_or_else(|| "default".to_string());
+                    log_warn!("AI", "{} failed ({}), trying next fallback", backend, e);
+                    fell_back_from.push(backend);
+                }
+                Err(e) => return Err(e),
+            }
         }
+        unreachable!("fallback attempts always include the requested backend")
+    }
+
+    /// Tools domains expose to chat
+    pub fn tools(&self) -> &Arc<ToolRegistry> {
+        &self.tools
     }
 
     pub async fn register_provider(&self, provider: Arc<dyn AIProvider>) {
         *self.provider.write().await = Some(provider);
     }
 
+    /// Serve `llama_cpp` requests (and fallbacks) from local models
+    pub async fn register_local_provider(&self, provider: Arc<dyn AIProvider>) {
+        *self.local_provider.write().await = Some(provider);
+    }
+
     pub async fn register_provider_from_config(&self, config: ProviderConfig) {
         if config.provider_type != ProviderType::AgentPlatform {
             return;
@@ -48,12 +263,43 @@ impl AIService {
         })
     }
 
-    /// Legacy optional type — always resolves to the registered agent-platform provider.
+    async fn get_local_provider(&self) -> Result<Arc<dyn AIProvider>, AIError> {
+        self.local_provider
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| {
+                AIError::ProviderNotAvailable("Local models are not available".to_string())
+            })
+    }
+
+    /// The local provider for `LlamaCpp`, otherwise agent-platform
     pub async fn get_provider_typed(
         &self,
-        _provider_type: Option<ProviderType>,
+        provider_type: Option<ProviderType>,
     ) -> Result<Arc<dyn AIProvider>, AIError> {
-        self.get_provider().await
+        match provider_type {
+            Some(ProviderType::LlamaCpp) => self.get_local_provider().await,
+            _ => self.get_provider().await,
+        }
+    }
+
+    /// Provider for one attempt: local for the `llama_cpp` backend, else a
+    /// configured agent-platform
+    async fn provider_for(
+        &self,
+        options: &GenerationOptions,
+    ) -> Result<Arc<dyn AIProvider>, AIError> {
+        if options.llm_provider.as_deref() == Some(LLAMA_CPP_BACKEND) {
+            return self.get_local_provider().await;
+        }
+        let provider = self.get_provider().await?;
+        let status = provider.check_configuration();
+        if !status.is_configured {
+            return Err(AIError::ConfigurationIncomplete(status));
+        }
+        Ok(provider)
     }
 
     pub async fn generate(
@@ -62,38 +308,17 @@ impl AIService {
         options: Option<GenerationOptions>,
         provider_type: Option<ProviderType>,
     ) -> Result<GenerationResult, AIError> {
-        let config_status = self
-            .check_provider_configuration(provider_type.clone())
-            .await?;
-        if !config_status.is_configured {
-            return Err(AIError::ConfigurationIncomplete(config_status));
-        }
-
-        let options = options.unwrap_or_default();
-        let provider = self.get_provider_typed(provider_type).await?;
-
-        let max_retries = 3;
-        let mut last_error = None;
-
-        for attempt in 0..max_retries {
-            match provider.generate(prompt, &options).await {
-                Ok(result) => return Ok(result),
-                Err(e) => {
-                    if matches!(&e, AIError::ConfigurationIncomplete(_)) {
-                        return Err(e);
-                    }
-                    last_error = Some(e);
-                    if attempt < max_retries - 1 {
-                        let delay = std::time::Duration::from_millis(100 * (1 << attempt));
-                        tokio::time::sleep(delay).await;
-                    }
-                }
-            }
-        }
-
-        Err(last_error.unwrap_or_else(|| {
-            AIError::GenericError("Generation failed after retries".to_string())
-        }))
+        let options = self.resolve_options(options, provider_type).await;
+        let (prompt, report) = self.redactor.redact_text(prompt).await;
+        self.record_redaction(&options, report).await;
+        let prompt = prompt.as_str();
+        self.with_cache(json!({ "prompt": prompt }), options, |options| {
+            self.with_fallback(options, |options| async move {
+                let provider = self.provider_for(&options).await?;
+                generate_with_retries(provider.as_ref(), prompt, &options).await
+            })
+        })
+        .await
     }
 
     pub async fn generate_with_system(
@@ -103,23 +328,60 @@ impl AIService {
         options: Option<GenerationOptions>,
         provider_type: Option<ProviderType>,
     ) -> Result<GenerationResult, AIError> {
-        let options = options.unwrap_or_default();
-        let provider = self.get_provider_typed(provider_type).await?;
-        provider
-            .generate_with_system(system_message, user_message, &options)
-            .await
+        let options = self.resolve_options(options, provider_type).await;
+        let (system_message, mut report) = self.redactor.redact_text(system_message).await;
+        let (user_message, user_report) = self.redactor.redact_text(user_message).await;
+        report.merge(user_report);
+        self.record_redaction(&options, report).await;
+        let (system_message, user_message) = (system_message.as_str(), user_message.as_str());
+        let request = json!({ "system": system_message, "user": user_message });
+        self.with_cache(request, options, |options| {
+            self.with_fallback(options, |options| async move {
+                let provider = self.provider_for(&options).await?;
+                provider
+                    .generate_with_system(system_message, user_message, &options)
+                    .await
+            })
+        })
+        .await
+    }
+
+    pub async fn embed(
+        &self,
+        inputs: &[String],
+        model: Option<&str>,
+    ) -> Result<EmbeddingResult, AIError> {
+        let mut redacted = Vec::with_capacity(inputs.len());
+        let mut report = RedactionReport::default();
+        for input in inputs {
+            let (input, found) = self.redactor.redact_text(input).await;
+            redacted.push(input);
+            report.merge(found);
+        }
+        let options = GenerationOptions {
+            model: model.map(str::to_string),
+            feature: Some("embeddings".to_string()),
+            ..GenerationOptions::default()
+        };
+        self.record_redaction(&options, report).await;
+
+        let provider = self.get_provider().await?;
+        provider.embed(&redacted, model).await
     }
 
     pub async fn get_registered_providers(&self) -> Vec<ProviderType> {
+        let mut providers = Vec::new();
         if self.provider.read().await.is_some() {
-            vec![ProviderType::AgentPlatform]
-        } else {
-            vec![]
+            providers.push(ProviderType::AgentPlatform);
+        }
+        if self.local_provider.read().await.is_some() {
+            providers.push(ProviderType::LlamaCpp);
         }
+        providers
     }
 
-    pub async fn test_provider(&self, _provider_type: ProviderType) -> Result<(), AIError> {
-        let provider = self.get_provider().await?;
+    pub async fn test_provider(&self, provider_type: ProviderType) -> Result<(), AIError> {
+        let provider = self.get_provider_typed(Some(provider_type)).await?;
         provider.test_connection().await
     }
 
@@ -133,9 +395,9 @@ impl AIService {
 
     pub async fn check_provider_configuration(
         &self,
-        _provider_type: Option<ProviderType>,
+        provider_type: Option<ProviderType>,
     ) -> Result<ConfigurationStatus, AIError> {
-        let provider = self.get_provider().await?;
+        let provider = self.get_provider_typed(provider_type).await?;
         Ok(provider.check_configuration())
     }
 
@@ -167,15 +429,17 @@ impl AIService {
         options: Option<GenerationOptions>,
         provider_type: Option<ProviderType>,
     ) -> Result<GenerationResult, AIError> {
-        let config_status = self
-            .check_provider_configuration(provider_type.clone())
-            .await?;
-        if !config_status.is_configured {
-            return Err(AIError::ConfigurationIncomplete(config_status));
-        }
-        let options = options.unwrap_or_default();
-        let provider = self.get_provider_typed(provider_type).await?;
-        provider.generate_chat(messages, &options).await
+        let options = self.resolve_options(options, provider_type).await;
+        let (messages, report) = self.redactor.redact_messages(messages).await;
+        self.record_redaction(&options, report).await;
+        let messages = messages.as_slice();
+        self.with_cache(json!({ "messages": messages }), options, |options| {
+            self.with_fallback(options, |options| async move {
+                let provider = self.provider_for(&options).await?;
+                provider.generate_chat(messages, &options).await
+            })
+        })
+        .await
     }
 
     pub async fn generate_chat_stream(
@@ -185,18 +449,164 @@ impl AIService {
         provider_type: Option<ProviderType>,
         on_chunk: Box<dyn FnMut(String) -> Result<(), AIError> + Send>,
     ) -> Result<GenerationResult, AIError> {
-        let config_status = self
-            .check_provider_configuration(provider_type.clone())
-            .await?;
-        if !config_status.is_configured {
-            return Err(AIError::ConfigurationIncomplete(config_status));
+        let options = self.resolve_options(options, provider_type).await;
+        let (messages, report) = self.redactor.redact_messages(messages).await;
+        self.record_redaction(&options, report).await;
+        let messages = messages.as_slice();
+
+        // A backend that already streamed part of a reply isn't retried
+        // elsewhere, or the caller would see two answers spliced together.
+        let on_chunk = Arc::new(Mutex::new(on_chunk));
+        let streamed = Arc::new(AtomicBool::new(false));
+        self.with_fallback(options, |options| {
+            let on_chunk = on_chunk.clone();
+            let streamed = streamed.clone();
+            async move {
+                let provider = self.provider_for(&options).await?;
+                let forward = streamed.clone();
+                let result = provider
+                    .generate_chat_stream(
+                        messages,
+                        &options,
+                        Box::new(move |chunk: String| {
+                            forward.store(true, Ordering::Relaxed);
+                            (on_chunk.lock().unwrap_or_else(|e| e.into_inner()))(chunk)
+                        }),
+                    )
+                    .await;
+                match result {
+                    Err(e) if streamed.load(Ordering::Relaxed) => {
+                        Err(AIError::GenericError(format!("Stream interrupted: {}", e)))
+                    }
+                    result => result,
+                }
+            }
+        })
+        .await
+    }
+
+    /// Stream chat like `generate_chat_stream`, but stoppable with
+    /// `cancel_stream(stream_id)`. Cancelling drops the provider request and
+    /// returns `Ok(None)`. A `stream_id` that is already streaming is refused.
+    pub async fn generate_chat_stream_cancellable(
+        &self,
+        stream_id: &str,
+        messages: &[ChatMessage],
+        options: Option<GenerationOptions>,
+        provider_type: Option<ProviderType>,
+        on_chunk: Box<dyn FnMut(String) -> Result<(), AIError> + Send>,
+    ) -> Result<Option<GenerationResult>, AIError> {
+        self.run_cancellable(
+            stream_id,
+            self.generate_chat_stream(messages, options, provider_type, on_chunk),
+        )
+        .await
+    }
+
+    /// Run `work` as the stream `stream_id` until it finishes or is cancelled
+    async fn run_cancellable<T>(
+        &self,
+        stream_id: &str,
+        work: impl Future<Output = Result<T, AIError>>,
+    ) -> Result<Option<T>, AIError> {
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        let token = self.next_stream_token.fetch_add(1, Ordering::Relaxed);
+        match self.lock_streams().entry(stream_id.to_string()) {
+            Entry::Occupied(_) => {
+                return Err(AIError::GenericError(format!(
+                    "Stream {} is already running",
+                    stream_id
+                )));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((token, cancel_tx));
+            }
         }
-        let options = options.unwrap_or_default();
-        let provider = self.get_provider_typed(provider_type).await?;
-        provider
-            .generate_chat_stream(messages, &options, on_chunk)
-            .await
+
+        let result = tokio::select! {
+            result = work => result.map(Some),
+            Ok(()) = cancel_rx => Ok(None),
+        };
+
+        // Only our own entry: after a cancel the id may belong to a new stream
+        let mut streams = self.lock_streams();
+        if streams
+            .get(stream_id)
+            .is_some_and(|(owner, _)| *owner == token)
+        {
+            streams.remove(stream_id);
+        }
+        result
     }
+
+    /// Cancel an in-flight stream. Returns false if it already finished.
+    pub fn cancel_stream(&self, stream_id: &str) -> bool {
+        match self.lock_streams().remove(stream_id) {
+            Some((_, cancel)) => cancel.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    fn lock_streams(&self) -> std::sync::MutexGuard<'_, HashMap<String, StreamHandle>> {
+        self.streams.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Fill in a feature's backend and model. An explicit model wins, and a
+/// request pinned to another backend keeps that backend's default model.
+fn apply_route(options: &mut GenerationOptions, route: &FeatureRoute) {
+    if options.model.is_some() {
+        return;
+    }
+    match (&options.llm_provider, &route.llm_provider) {
+        (Some(requested), Some(routed)) if requested != routed => {}
+        _ => {
+            if options.llm_provider.is_none() {
+                options.llm_provider = route.llm_provider.clone();
+            }
+            options.model = route.model.clone();
+        }
+    }
+}
+
+/// Errors a different backend might not hit. Configuration and auth problems
+/// are with agent-platform itself, so falling back wouldn't help.
+fn should_fall_back(error: &AIError) -> bool {
+    matches!(
+        error,
+        AIError::TimeoutError(_)
+            | AIError::RateLimitError(_)
+            | AIError::NetworkError(_)
+            | AIError::InvalidResponse(_)
+    )
+}
+
+async fn generate_with_retries(
+    provider: &dyn AIProvider,
+    prompt: &str,
+    options: &GenerationOptions,
+) -> Result<GenerationResult, AIError> {
+    let max_retries = 3;
+    let mut last_error = None;
+
+    for attempt in 0..max_retries {
+        match provider.generate(prompt, options).await {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                if matches!(&e, AIError::ConfigurationIncomplete(_)) {
+                    return Err(e);
+                }
+                last_error = Some(e);
+                if attempt < max_retries - 1 {
+                    let delay = std::time::Duration::from_millis(100 * (1 << attempt));
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    Err(last_error
+        .unwrap_or_else(|| AIError::GenericError("Generation failed after retries".to_string())))
 }
 
 impl Default for AIService {
@@ -204,3 +614,109 @@ impl Default for AIService {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tries_requested_backend_then_chain() {
+        let service = AIService::new();
+        service
+            .set_fallback_chain(vec![
+                FallbackTarget {
+                    llm_provider: "ollama".to_string(),
+                    model: None,
+                },
+                FallbackTarget {
+                    llm_provider: "openai".to_string(),
+                    model: Some("gpt-4o-mini".to_string()),
+                },
+            ])
+            .await;
+
+        let requested = GenerationOptions {
+            llm_provider: Some("ollama".to_string()),
+            model: Some("llama3".to_string()),
+            ..GenerationOptions::default()
+        };
+        let attempts = service.fallback_attempts(requested).await;
+        let backends: Vec<_> = attempts
+            .iter()
+            .map(|o| (o.llm_provider.as_deref(), o.model.as_deref()))
+            .collect();
+        assert_eq!(
+            backends,
+            [
+                (Some("ollama"), Some("llama3")),
+                (Some("openai"), Some("gpt-4o-mini"))
+            ]
+        );
+
+        assert!(should_fall_back(&AIError::RateLimitError(String::new())));
+        assert!(!should_fall_back(&AIError::AuthenticationError(
+            String::new()
+        )));
+    }
+
+    #[test]
+    fn routes_requests_without_a_model() {
+        let route = FeatureRoute {
+            llm_provider: Some("openai".to_string()),
+            model: Some("gpt-4o-mini".to_string()),
+        };
+        let routed = |llm_provider: Option<&str>, model: Option<&str>| {
+            let mut options = GenerationOptions {
+                llm_provider: llm_provider.map(str::to_string),
+                model: model.map(str::to_string),
+                ..GenerationOptions::default()
+            };
+            apply_route(&mut options, &route);
+            (options.llm_provider, options.model)
+        };
+
+        let openai = (Some("openai".to_string()), Some("gpt-4o-mini".to_string()));
+        assert_eq!(routed(None, None), openai);
+        assert_eq!(routed(Some("openai"), None), openai);
+        assert_eq!(
+            routed(Some("ollama"), None),
+            (Some("ollama".to_string()), None)
+        );
+        assert_eq!(
+            routed(None, Some("llama3")),
+            (None, Some("llama3".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn cancels_streams_by_id() {
+        let service = Arc::new(AIService::new());
+
+        // Normal completion releases the id
+        let done = service.run_cancellable("a", async { Ok(1) }).await;
+        assert!(matches!(done, Ok(Some(1))));
+        assert!(!service.cancel_stream("a"));
+
+        // Cancelling resolves the stream with None
+        let running = {
+            let service = service.clone();
+            tokio::spawn(async move {
+                service
+                    .run_cancellable("b", std::future::pending::<Result<(), AIError>>())
+                    .await
+            })
+        };
+        while !service.lock_streams().contains_key("b") {
+            tokio::task::yield_now().await;
+        }
+
+        // A second stream with the same id is refused and leaves the first alone
+        let duplicate = service.run_cancellable("b", async { Ok(2) }).await;
+        assert!(matches!(duplicate, Err(AIError::GenericError(_))));
+        assert!(service.lock_streams().contains_key("b"));
+
+        assert!(service.cancel_stream("b"));
+        assert!(matches!(running.await.unwrap(), Ok(None)));
+        assert!(service.lock_streams().is_empty());
+    }
+}
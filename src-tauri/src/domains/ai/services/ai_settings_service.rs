@@ -1,4 +1,5 @@
 use crate::domains::ai::providers::{ProviderConfig, ProviderType};
+use crate::domains::ai::response_cache::ResponseCacheSettings;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -10,6 +11,37 @@ use std::path::PathBuf;
 pub struct AISettings {
     pub providers: HashMap<String, ProviderConfig>,
     pub default_provider: Option<String>,
+    /// Backends to retry on, in order, when the requested one times out, errors
+    /// or is rate limited
+    #[serde(default)]
+    pub fallback_chain: Vec<FallbackTarget>,
+    /// Backend and model per app feature (`chat`, `task_generation`, `docs`,
+    /// ...), used when a request doesn't name a model itself
+    #[serde(default)]
+    pub feature_routes: HashMap<String, FeatureRoute>,
+    #[serde(default)]
+    pub response_cache: ResponseCacheSettings,
+}
+
+/// An agent-platform backend to fall back to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FallbackTarget {
+    /// Backend id, e.g. `ollama`, `openai`, `anthropic`
+    pub llm_provider: String,
+    /// Model to use there; `None` uses the platform's default for the backend
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Where one feature's requests go
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct FeatureRoute {
+    /// Backend id; `None` keeps the platform's default backend
+    #[serde(default)]
+    pub llm_provider: Option<String>,
+    /// Model; `None` uses the backend's default
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 impl Default for AISettings {
@@ -22,6 +54,9 @@ impl Default for AISettings {
         Self {
             providers,
             default_provider: Some("AgentPlatform".to_string()),
+            fallback_chain: Vec::new(),
+            feature_routes: HashMap::new(),
+            response_cache: ResponseCacheSettings::default(),
         }
     }
 }
@@ -165,6 +200,79 @@ impl AISettingsService {
         self.save_settings(&settings)
     }
 
+    pub fn get_fallback_chain(&self) -> Result<Vec<FallbackTarget>, String> {
+        Ok(self.load_settings()?.fallback_chain)
+    }
+
+    pub fn set_fallback_chain(&self, chain: Vec<FallbackTarget>) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for target in &chain {
+            let id = target.llm_provider.trim();
+            if id.is_empty() {
+                return Err("Fallback backends need a provider id".to_string());
+            }
+            if !seen.insert(id.to_lowercase()) {
+                return Err(format!("{} is listed more than once", id));
+            }
+        }
+        let mut settings = self.load_settings()?;
+        settings.fallback_chain = chain
+            .into_iter()
+            .map(|target| FallbackTarget {
+                llm_provider: target.llm_provider.trim().to_string(),
+                model: target.model.filter(|m| !m.trim().is_empty()),
+            })
+            .collect();
+        self.save_settings(&settings)
+    }
+
+    pub fn get_feature_routes(&self) -> Result<HashMap<String, FeatureRoute>, String> {
+        Ok(self.load_settings()?.feature_routes)
+    }
+
+    /// Replace the per-feature routes. Routes that set neither a backend nor
+    /// a model are dropped.
+    pub fn set_feature_routes(&self, routes: HashMap<String, FeatureRoute>) -> Result<(), String> {
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let mut cleaned = HashMap::new();
+        for (feature, route) in routes {
+            let feature = feature.trim().to_lowercase();
+            if feature.is_empty() {
+                return Err("Feature routes need a feature name".to_string());
+            }
+            let route = FeatureRoute {
+                llm_provider: non_empty(route.llm_provider),
+                model: non_empty(route.model),
+            };
+            if route != FeatureRoute::default() && cleaned.insert(feature.clone(), route).is_some()
+            {
+                return Err(format!("{} is listed more than once", feature));
+            }
+        }
+        let mut settings = self.load_settings()?;
+        settings.feature_routes = cleaned;
+        self.save_settings(&settings)
+    }
+
+    pub fn get_response_cache_settings(&self) -> Result<ResponseCacheSettings, String> {
+        Ok(self.load_settings()?.response_cache)
+    }
+
+    pub fn set_response_cache_settings(&self, cache: ResponseCacheSettings) -> Result<(), String> {
+        if cache.enabled && (cache.ttl_seconds == 0 || cache.max_entries == 0) {
+            return Err(
+                "The response cache needs a TTL and room for at least one entry".to_string(),
+            );
+        }
+        let mut settings = self.load_settings()?;
+        settings.response_cache = cache;
+        self.save_settings(&settings)
+    }
+
     pub fn delete_provider_config(&self, _provider_type: ProviderType) -> Result<(), String> {
         Err("Cannot delete the agent-platform provider".to_string())
     }
@@ -2,6 +2,6 @@ pub mod ai_service;
 pub mod ai_settings_service;
 
 pub use ai_service::AIService;
-pub use ai_settings_service::AISettingsService;
+pub use ai_settings_service::{AISettingsService, FallbackTarget, FeatureRoute};
 // AISettings is used internally, not exported
 // pub use ai_settings_service::AISettings;
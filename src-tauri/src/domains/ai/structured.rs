@@ -0,0 +1,244 @@
+//! JSON generation checked against a JSON schema.
+//!
+//! The schema goes to the model twice: in the system prompt, and as an
+//! OpenAI-style `response_format` for backends with a native JSON mode. Replies
+//! are parsed and validated here either way; a reply that isn't valid JSON or
+//! breaks the schema is sent back with the errors for another attempt.
+
+use crate::domains::ai::message::ChatMessage;
+use crate::domains::ai::providers::{GenerationOptions, ProviderType, TokenUsage};
+use crate::domains::ai::services::AIService;
+use crate::log_warn;
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const MAX_ATTEMPTS: u32 = 6;
+/// Schema errors reported back to the model per attempt
+const MAX_REPORTED_ERRORS: usize = 10;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StructuredRequest {
+    pub prompt: String,
+    /// JSON schema the reply must satisfy
+    pub schema: Value,
+    /// Extra instructions placed before the schema in the system prompt
+    #[serde(default)]
+    pub system: Option<String>,
+    /// Name for the schema in native JSON modes
+    #[serde(default)]
+    pub schema_name: Option<String>,
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+    /// Ask the backend for native JSON output too; on by default
+    #[serde(default)]
+    pub native_json_mode: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredResult {
+    /// The validated JSON value
+    pub data: Value,
+    pub attempts: u32,
+    pub model: String,
+    pub served_by: Option<String>,
+    /// Token usage summed over every attempt
+    pub usage: Option<TokenUsage>,
+}
+
+impl AIService {
+    /// Generate JSON matching `request.schema`, retrying on invalid replies
+    pub async fn generate_structured(
+        &self,
+        request: &StructuredRequest,
+        options: Option<GenerationOptions>,
+        provider: Option<ProviderType>,
+    ) -> Result<StructuredResult, String> {
+        let validator = JSONSchema::compile(&request.schema)
+            .map_err(|e| format!("Invalid JSON schema: {}", e))?;
+
+        let mut options = options.unwrap_or_default();
+        options
+            .feature
+            .get_or_insert_with(|| "structured".to_string());
+        if request.native_json_mode.unwrap_or(true) {
+            let mut extra = match options.extra_options.take() {
+                Some(Value::Object(extra)) => extra,
+                _ => serde_json::Map::new(),
+            };
+            extra.insert(
+                "response_format".to_string(),
+                json!({
+                    "type": "json_schema",
+                    "json_schema": {
+                        "name": request.schema_name.as_deref().unwrap_or("response"),
+                        "schema": request.schema,
+                    },
+                }),
+            );
+            options.extra_options = Some(Value::Object(extra));
+        }
+
+        let mut messages = vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt(request),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: request.prompt.clone(),
+            },
+        ];
+        let max_attempts = request
+            .max_attempts
+            .unwrap_or(DEFAULT_MAX_ATTEMPTS)
+            .clamp(1, MAX_ATTEMPTS);
+        let mut usage: Option<TokenUsage> = None;
+        let mut last_error = String::new();
+
+        for attempt in 1..=max_attempts {
+            let reply = self
+                .generate_chat(&messages, Some(options.clone()), provider.clone())
+                .await
+                .map_err(|e| format!("AI generation error: {}", e))?;
+            if let Some(reply_usage) = reply.usage {
+                let total = usage.get_or_insert_with(TokenUsage::default);
+                total.prompt_tokens += reply_usage.prompt_tokens;
+                total.completion_tokens += reply_usage.completion_tokens;
+            }
+
+            match check_reply(&validator, &reply.content) {
+                Ok(data) => {
+                    return Ok(StructuredResult {
+                        data,
+                        attempts: attempt,
+                        model: reply.model,
+                        served_by: reply.served_by,
+                        usage,
+                    })
+                }
+                Err(problem) => {
+                    log_warn!(
+                        "AI",
+                        "Structured reply {} of {} rejected: {}",
+                        attempt,
+                        max_attempts,
+                        problem
+                    );
+                    messages.push(ChatMessage {
+                        role: "assistant".to_string(),
+                        content: reply.content,
+                    });
+                    messages.push(ChatMessage {
+                        role: "user".to_string(),
+                        content: format!(
+                            "That reply was rejected: {}\n\nRespond again with only the corrected JSON.",
+                            problem
+                        ),
+                    });
+                    last_error = problem;
+                }
+            }
+        }
+
+        Err(format!(
+            "No valid structured reply after {} attempts: {}",
+            max_attempts, last_error
+        ))
+    }
+}
+
+fn system_prompt(request: &StructuredRequest) -> String {
+    let schema = serde_json::to_string_pretty(&request.schema).unwrap_or_default();
+    let rules = format!(
+        "Respond with a single JSON value that validates against this JSON schema. Output \
+         only the JSON: no prose, no markdown fences.\n\n{}",
+        schema
+    );
+    match request.system.as_deref().map(str::trim) {
+        Some(system) if !system.is_empty() => format!("{}\n\n{}", system, rules),
+        _ => rules,
+    }
+}
+
+/// Parse and validate a reply, describing what's wrong if it fails
+fn check_reply(validator: &JSONSchema, content: &str) -> Result<Value, String> {
+    let value = extract_json(content).ok_or_else(|| "the reply is not valid JSON".to_string())?;
+    if let Err(errors) = validator.validate(&value) {
+        let errors: Vec<String> = errors
+            .take(MAX_REPORTED_ERRORS)
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{}: {}", path, e)
+                }
+            })
+            .collect();
+        return Err(format!("schema violations: {}", errors.join("; ")));
+    }
+    Ok(value)
+}
+
+/// The JSON value in a reply: the whole text, a fenced block, or the span
+/// from the first `{`/`[` to the last matching bracket.
+pub fn extract_json(content: &str) -> Option<Value> {
+    let trimmed = content.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+
+    if let Some(start) = trimmed.find("```") {
+        let body = &trimmed[start + 3..];
+        let body = body.split_once('\n').map(|(_, rest)| rest).unwrap_or(body);
+        if let Some(end) = body.find("```") {
+            if let Ok(value) = serde_json::from_str(body[..end].trim()) {
+                return Some(value);
+            }
+        }
+    }
+
+    let start = trimmed.find(['{', '['])?;
+    let close = if trimmed[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = trimmed.rfind(close)?;
+    (end > start)
+        .then(|| serde_json::from_str(&trimmed[start..=end]).ok())
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_and_validates_replies() {
+        let schema = json!({
+            "type": "object",
+            "required": ["title", "priority"],
+            "properties": {
+                "title": { "type": "string" },
+                "priority": { "enum": ["low", "medium", "high"] }
+            }
+        });
+        let validator = JSONSchema::compile(&schema).unwrap();
+
+        let fenced = "Here you go:\n```json\n{\"title\": \"Ship\", \"priority\": \"high\"}\n```";
+        assert_eq!(check_reply(&validator, fenced).unwrap()["title"], "Ship");
+
+        let prose = "Sure! {\"title\": \"Ship\", \"priority\": \"urgent\"} Hope that helps.";
+        let err = check_reply(&validator, prose).unwrap_err();
+        assert!(err.starts_with("schema violations: /priority"), "{}", err);
+
+        assert_eq!(
+            check_reply(&validator, "no json here").unwrap_err(),
+            "the reply is not valid JSON"
+        );
+        assert_eq!(extract_json("[1, 2]"), Some(json!([1, 2])));
+    }
+}
@@ -0,0 +1,374 @@
+//! Chat loop that executes the tools a reply asks for.
+//!
+//! Each requested call is evaluated by the autonomy domain. Calls it clears run
+//! straight away; anything else is simulated if its tool has a preview, then
+//! pauses the run: the call and the run's state go into the autonomy approval
+//! queue, so they survive restarts, `ai-tool-approval-required` is emitted and
+//! the run waits for `ai_resolve_tool_call`. Results are fed back to the model until
+//! it answers without calling a tool.
+
+use super::message::ChatMessage;
+use super::providers::{GenerationOptions, ProviderType};
+use super::services::AIService;
+use super::tools::{
+    format_tool_results, parse_tool_calls, strip_tool_calls, ActionPreview, ToolCall,
+};
+use crate::database::DatabaseManager;
+use crate::domains::autonomy::commands::{evaluate_action, record_action_outcome};
+use crate::domains::autonomy::services::approval_queue::{self, NewApproval};
+use crate::domains::autonomy::services::autonomy_service::AutonomousActionRequest;
+use crate::{log_info, log_warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+pub const TOOL_APPROVAL_EVENT: &str = "ai-tool-approval-required";
+
+/// Model replies per run, so a model stuck calling tools can't loop forever
+const MAX_TOOL_ROUNDS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallStatus {
+    Executed,
+    Failed,
+    PendingApproval,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallOutcome {
+    pub call: ToolCall,
+    pub status: ToolCallStatus,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+/// A call held for the user's approval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingToolCall {
+    pub call: ToolCall,
+    pub description: String,
+    pub safety_level: String,
+    pub reason: String,
+    /// What the call would do, when its tool can simulate it
+    pub preview: Option<ActionPreview>,
+    /// Why the simulation failed, if it did
+    pub preview_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolChatResponse {
+    /// Latest assistant reply with tool call blocks removed
+    pub content: String,
+    /// Conversation so far, including tool results, to send back as history
+    pub messages: Vec<ChatMessage>,
+    pub tool_calls: Vec<ToolCallOutcome>,
+    /// Set when the run is paused waiting on approval
+    pub pending: Option<PendingToolCall>,
+}
+
+/// Outcome of one call in a bulk resolution
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCallResolution {
+    pub call_id: String,
+    pub response: Option<ToolChatResponse>,
+    pub error: Option<String>,
+}
+
+/// State of a chat run, stored with the approval request while paused
+#[derive(Serialize, Deserialize)]
+pub struct ToolRun {
+    messages: Vec<ChatMessage>,
+    options: GenerationOptions,
+    provider: Option<ProviderType>,
+    /// Calls from the latest reply not handled yet
+    queue: VecDeque<ToolCall>,
+    /// Results gathered for the latest reply
+    results: Vec<(String, Result<Value, String>)>,
+    outcomes: Vec<ToolCallOutcome>,
+    rounds: usize,
+    /// Call paused on approval and the autonomy action id it was evaluated as
+    awaiting: Option<(ToolCall, String)>,
+}
+
+impl ToolRun {
+    pub fn new(
+        messages: Vec<ChatMessage>,
+        options: GenerationOptions,
+        provider: Option<ProviderType>,
+    ) -> Self {
+        Self {
+            messages,
+            options,
+            provider,
+            queue: VecDeque::new(),
+            results: Vec::new(),
+            outcomes: Vec::new(),
+            rounds: 0,
+            awaiting: None,
+        }
+    }
+
+    fn record(&mut self, call: ToolCall, result: Result<Value, String>) {
+        let status = if result.is_ok() {
+            ToolCallStatus::Executed
+        } else {
+            ToolCallStatus::Failed
+        };
+        self.outcomes.push(ToolCallOutcome {
+            call: call.clone(),
+            status,
+            result: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().cloned(),
+        });
+        self.results.push((call.tool, result));
+    }
+
+    fn response(&self, pending: Option<PendingToolCall>) -> ToolChatResponse {
+        let content = self
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "assistant")
+            .map(|m| strip_tool_calls(&m.content))
+            .unwrap_or_default();
+        ToolChatResponse {
+            content,
+            messages: self.messages.clone(),
+            tool_calls: self.outcomes.clone(),
+            pending,
+        }
+    }
+}
+
+pub struct ToolRunner {
+    app: AppHandle,
+    ai_service: Arc<AIService>,
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ToolRunner {
+    pub fn new(
+        app: AppHandle,
+        ai_service: Arc<AIService>,
+        db_manager: Arc<DatabaseManager>,
+    ) -> Self {
+        Self {
+            app,
+            ai_service,
+            db_manager,
+        }
+    }
+
+    /// Approve or reject the call a run is paused on, then carry the run on
+    pub async fn resolve(
+        &self,
+        call_id: &str,
+        approved: bool,
+        feedback: Option<String>,
+    ) -> Result<ToolChatResponse, String> {
+        let queued = approval_queue::take(
+            self.db_manager.get_connection(),
+            call_id,
+            approved,
+            feedback.clone(),
+        )
+        .await?;
+        let mut run: ToolRun = serde_json::from_value(queued)
+            .map_err(|e| format!("Failed to restore the paused chat: {}", e))?;
+        let (call, action_id) = run
+            .awaiting
+            .take()
+            .ok_or_else(|| "Tool run is not waiting for approval".to_string())?;
+        run.outcomes.retain(|outcome| outcome.call.id != call.id);
+
+        if let Err(e) = record_action_outcome(
+            self.db_manager.get_connection(),
+            &action_id,
+            &call.tool,
+            &self.context(&call),
+            approved,
+            feedback.clone(),
+        )
+        .await
+        {
+            log_warn!("AI", "Failed to record tool approval: {}", e);
+        }
+
+        if approved {
+            let result = self
+                .ai_service
+                .tools()
+                .execute(self.app.clone(), &call)
+                .await;
+            run.record(call, result);
+        } else {
+            let reason = match feedback {
+                Some(feedback) => format!("The user declined this action: {}", feedback),
+                None => "The user declined this action".to_string(),
+            };
+            run.outcomes.push(ToolCallOutcome {
+                call: call.clone(),
+                status: ToolCallStatus::Rejected,
+                result: None,
+                error: Some(reason.clone()),
+            });
+            run.results.push((call.tool, Err(reason)));
+        }
+        self.drive(run).await
+    }
+
+    /// Resolve several queued calls the same way, continuing each one's run.
+    /// A call that fails to resolve doesn't stop the others.
+    pub async fn resolve_many(
+        &self,
+        call_ids: Vec<String>,
+        approved: bool,
+        feedback: Option<String>,
+    ) -> Vec<ToolCallResolution> {
+        let mut resolutions = Vec::with_capacity(call_ids.len());
+        for call_id in call_ids {
+            let result = self.resolve(&call_id, approved, feedback.clone()).await;
+            resolutions.push(ToolCallResolution {
+                call_id,
+                error: result.as_ref().err().cloned(),
+                response: result.ok(),
+            });
+        }
+        resolutions
+    }
+
+    /// Generate replies and run the tools they call until the model answers
+    /// without tools, the round limit is hit, or a call needs approval.
+    pub async fn drive(&self, mut run: ToolRun) -> Result<ToolChatResponse, String> {
+        let tools = self.ai_service.tools();
+        loop {
+            while let Some(call) = run.queue.pop_front() {
+                if tools.spec(&call.tool).is_none() {
+                    run.record(call.clone(), Err(format!("Unknown tool: {}", call.tool)));
+                    continue;
+                }
+
+                let evaluation = evaluate_action(
+                    self.db_manager.get_connection(),
+                    AutonomousActionRequest {
+                        action_type: call.tool.clone(),
+                        action_data: call.arguments.clone(),
+                        context: self.context(&call),
+                        user_id: None,
+                    },
+                )
+                .await?;
+
+                if evaluation.executed {
+                    let result = tools.execute(self.app.clone(), &call).await;
+                    run.record(call, result);
+                    continue;
+                }
+
+                let (preview, preview_error) = match tools.preview(self.app.clone(), &call).await {
+                    Some(Ok(preview)) => (Some(preview), None),
+                    Some(Err(e)) => {
+                        log_warn!("AI", "Failed to preview tool call {}: {}", call.tool, e);
+                        (None, Some(e))
+                    }
+                    None => (None, None),
+                };
+                let pending = PendingToolCall {
+                    description: tools
+                        .spec(&call.tool)
+                        .map(|spec| spec.description)
+                        .unwrap_or_default(),
+                    safety_level: format!("{:?}", evaluation.classification.safety_level),
+                    reason: evaluation.classification.reason,
+                    preview,
+                    preview_error,
+                    call: call.clone(),
+                };
+                run.outcomes.push(ToolCallOutcome {
+                    call: call.clone(),
+                    status: ToolCallStatus::PendingApproval,
+                    result: None,
+                    error: None,
+                });
+                run.awaiting = Some((call.clone(), evaluation.action_id));
+                let response = run.response(Some(pending.clone()));
+                approval_queue::enqueue(
+                    self.db_manager.get_connection(),
+                    NewApproval {
+                        id: call.id.clone(),
+                        action_type: call.tool.clone(),
+                        context: self.context(&call),
+                        description: pending.description.clone(),
+                        safety_level: pending.safety_level.clone(),
+                        reason: pending.reason.clone(),
+                        arguments: call.arguments.clone(),
+                        preview: pending
+                            .preview
+                            .as_ref()
+                            .and_then(|preview| serde_json::to_value(preview).ok()),
+                        resume: serde_json::to_value(&run).map_err(|e| e.to_string())?,
+                    },
+                )
+                .await?;
+                let _ = self.app.emit(TOOL_APPROVAL_EVENT, &pending);
+                log_info!(
+                    "AI",
+                    "Tool call {} is waiting for approval",
+                    pending.call.tool
+                );
+                return Ok(response);
+            }
+
+            if !run.results.is_empty() {
+                let results = std::mem::take(&mut run.results);
+                run.messages.push(ChatMessage {
+                    role: "user".to_string(),
+                    content: format_tool_results(&results),
+                });
+            } else if run.messages.last().is_some_and(|m| m.role == "assistant") {
+                return Ok(run.response(None));
+            }
+
+            if run.rounds >= MAX_TOOL_ROUNDS {
+                log_warn!("AI", "Stopped tool run after {} rounds", MAX_TOOL_ROUNDS);
+                return Ok(run.response(None));
+            }
+
+            let mut prompt = Vec::with_capacity(run.messages.len() + 1);
+            if let Some(system) = tools.system_prompt() {
+                prompt.push(ChatMessage {
+                    role: "system".to_string(),
+                    content: system,
+                });
+            }
+            prompt.extend(run.messages.iter().cloned());
+
+            let reply = self
+                .ai_service
+                .generate_chat(&prompt, Some(run.options.clone()), run.provider.clone())
+                .await
+                .map_err(|e| format!("AI generation error: {}", e))?;
+            run.rounds += 1;
+            run.queue = parse_tool_calls(&reply.content).into();
+            run.messages.push(ChatMessage {
+                role: "assistant".to_string(),
+                content: reply.content,
+            });
+        }
+    }
+
+    /// Autonomy context for a call: approvals are learned per domain
+    fn context(&self, call: &ToolCall) -> String {
+        let domain = self
+            .ai_service
+            .tools()
+            .spec(&call.tool)
+            .map(|spec| spec.domain)
+            .unwrap_or_default();
+        format!("ai_tool:{}", domain)
+    }
+}
@@ -0,0 +1,267 @@
+//! Registry of app actions the assistant can call.
+//!
+//! Domains register tools (list pods, run a pipeline, create a task, ...) with a
+//! JSON-schema description and an async handler. The registry renders them into
+//! the system prompt, and replies that ask for a tool do so with a fenced
+//! ```tool_call block holding `{"tool": ..., "arguments": {...}}`. A tool with
+//! side effects can also register a preview that simulates the call (a dry
+//! run, a diff, a plan), shown with the approval request before it runs.
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use tauri::AppHandle;
+
+/// Info string of the fenced block a reply uses to request a tool
+pub const TOOL_CALL_FENCE: &str = "tool_call";
+
+/// Tool results longer than this are cut before going back to the model
+const MAX_RESULT_CHARS: usize = 8_000;
+
+type ToolHandler =
+    Arc<dyn Fn(AppHandle, Value) -> BoxFuture<'static, Result<Value, String>> + Send + Sync>;
+type PreviewHandler = Arc<
+    dyn Fn(AppHandle, Value) -> BoxFuture<'static, Result<ActionPreview, String>> + Send + Sync,
+>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolSpec {
+    /// Unique name the model calls the tool by. Autonomy classifies calls by
+    /// this name, so it should start with a verb (`list_`, `create_`, ...).
+    pub name: String,
+    pub description: String,
+    /// Domain that registered the tool
+    pub domain: String,
+    /// JSON schema for the arguments object
+    pub parameters: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub tool: String,
+    pub arguments: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewMethod {
+    /// The action was sent in a mode that changes nothing, e.g. a server-side
+    /// dry run
+    DryRun,
+    /// The content change, as a line diff
+    Diff,
+    /// What would run, worked out without running it
+    Plan,
+}
+
+/// Simulated effects of a tool call, attached to its approval request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionPreview {
+    pub method: PreviewMethod,
+    /// One line saying what the call would do
+    pub summary: String,
+    /// Dry-run output, diff or plan, as preformatted text
+    pub details: Option<String>,
+}
+
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: RwLock<BTreeMap<String, (ToolSpec, ToolHandler)>>,
+    previews: RwLock<HashMap<String, PreviewHandler>>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a tool, replacing any earlier tool with the same name
+    pub fn register<F, Fut>(&self, spec: ToolSpec, handler: F)
+    where
+        F: Fn(AppHandle, Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, String>> + Send + 'static,
+    {
+        let handler: ToolHandler = Arc::new(move |app, args| Box::pin(handler(app, args)));
+        self.tools
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(spec.name.clone(), (spec, handler));
+    }
+
+    /// Register how to simulate calls to the tool `name`
+    pub fn register_preview<F, Fut>(&self, name: &str, handler: F)
+    where
+        F: Fn(AppHandle, Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ActionPreview, String>> + Send + 'static,
+    {
+        let handler: PreviewHandler = Arc::new(move |app, args| Box::pin(handler(app, args)));
+        self.previews
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), handler);
+    }
+
+    pub fn specs(&self) -> Vec<ToolSpec> {
+        self.tools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .map(|(spec, _)| spec.clone())
+            .collect()
+    }
+
+    pub fn spec(&self, name: &str) -> Option<ToolSpec> {
+        self.tools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .map(|(spec, _)| spec.clone())
+    }
+
+    pub async fn execute(&self, app: AppHandle, call: &ToolCall) -> Result<Value, String> {
+        let handler = self
+            .tools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&call.tool)
+            .map(|(_, handler)| handler.clone())
+            .ok_or_else(|| format!("Unknown tool: {}", call.tool))?;
+        handler(app, call.arguments.clone()).await
+    }
+
+    /// Simulate a call without running it; `None` when the tool has no preview
+    pub async fn preview(
+        &self,
+        app: AppHandle,
+        call: &ToolCall,
+    ) -> Option<Result<ActionPreview, String>> {
+        let handler = self
+            .previews
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&call.tool)
+            .cloned()?;
+        Some(handler(app, call.arguments.clone()).await)
+    }
+
+    /// System prompt describing the registered tools and how to call them
+    pub fn system_prompt(&self) -> Option<String> {
+        let specs = self.specs();
+        if specs.is_empty() {
+            return None;
+        }
+
+        let mut prompt = String::from(
+            "You can act inside the Portal Desktop app by calling tools. To call one, reply \
+             with a fenced block like:\n\n```tool_call\n{\"tool\": \"<name>\", \"arguments\": \
+             {...}}\n```\n\nYou may call several tools in one reply. Results come back in the \
+             next message; use them to answer. Don't call tools you don't need, and never \
+             invent results.\n\nAvailable tools:\n",
+        );
+        for spec in specs {
+            prompt.push_str(&format!(
+                "\n- {}: {}\n  arguments: {}",
+                spec.name, spec.description, spec.parameters
+            ));
+        }
+        Some(prompt)
+    }
+}
+
+/// Pull the tool calls out of a reply. Blocks that aren't valid JSON or don't
+/// name a tool are ignored.
+pub fn parse_tool_calls(content: &str) -> Vec<ToolCall> {
+    tool_call_blocks(content)
+        .into_iter()
+        .filter_map(|(_, body)| {
+            let value: Value = serde_json::from_str(body.trim()).ok()?;
+            let tool = value
+                .get("tool")
+                .or_else(|| value.get("name"))
+                .and_then(Value::as_str)?
+                .trim()
+                .to_string();
+            if tool.is_empty() {
+                return None;
+            }
+            Some(ToolCall {
+                id: uuid::Uuid::new_v4().to_string(),
+                tool,
+                arguments: value
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_else(|| Value::Object(Default::default())),
+            })
+        })
+        .collect()
+}
+
+/// The reply with its tool call blocks removed
+pub fn strip_tool_calls(content: &str) -> String {
+    let mut stripped = content.to_string();
+    for (block, _) in tool_call_blocks(content).into_iter().rev() {
+        stripped.replace_range(block, "");
+    }
+    stripped.trim().to_string()
+}
+
+/// Message handing tool results back to the model
+pub fn format_tool_results(results: &[(String, Result<Value, String>)]) -> String {
+    let mut message = String::from("Tool results:");
+    for (tool, result) in results {
+        let body = match result {
+            Ok(value) => {
+                let text = value.to_string();
+                match text.char_indices().nth(MAX_RESULT_CHARS) {
+                    Some((cut, _)) => format!("{}... (truncated)", &text[..cut]),
+                    None => text,
+                }
+            }
+            Err(e) => format!("error: {}", e),
+        };
+        message.push_str(&format!("\n\n[{}]\n{}", tool, body));
+    }
+    message
+}
+
+/// Byte range of each ```tool_call block and its body
+fn tool_call_blocks(content: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let opening = format!("```{}", TOOL_CALL_FENCE);
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = content[offset..].find(&opening) {
+        let start = offset + found;
+        let body_start = start + opening.len();
+        let Some(body_len) = content[body_start..].find("```") else {
+            break;
+        };
+        let end = body_start + body_len + 3;
+        blocks.push((start..end, &content[body_start..body_start + body_len]));
+        offset = end;
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_strips_tool_call_blocks() {
+        let reply = "Let me check.\n```tool_call\n{\"tool\": \"list_pods\", \"arguments\": {\"namespace\": \"web\"}}\n```\n```tool_call\nnot json\n```\n```tool_call\n{\"tool\": \"read_document\"}\n```";
+
+        let calls = parse_tool_calls(reply);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].tool, "list_pods");
+        assert_eq!(calls[0].arguments["namespace"], "web");
+        assert_eq!(calls[1].tool, "read_document");
+        assert!(calls[1].arguments.is_object());
+
+        assert_eq!(strip_tool_calls(reply), "Let me check.");
+        assert!(parse_tool_calls("```tool_call\n{\"tool\": \"x\"").is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+//! Token accounting and cost estimates for every generation.
+//!
+//! AIService records one `usage` row in ai_logs per generation (and an `error`
+//! row per failed attempt) with the backend, model, feature and token counts.
+//! Costs are estimated from a built-in price list; local backends cost nothing
+//! and unknown hosted models are left unpriced.
+
+use crate::domains::ai::entities::{AILogActiveModel, AILogColumn, AILogEntity};
+use crate::domains::ai::providers::{GenerationOptions, GenerationResult, TokenUsage};
+use crate::domains::ai::redaction::RedactionReport;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Backends that run on the user's machine
+const LOCAL_BACKENDS: &[&str] = &["ollama", "lm_studio", "llama_cpp", "local"];
+
+/// USD per million prompt/completion tokens, matched against the model name.
+/// More specific names come first.
+const PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("o3-mini", 1.10, 4.40),
+    ("o4-mini", 1.10, 4.40),
+    ("gpt-3.5-turbo", 0.50, 1.50),
+    ("claude-3-5-haiku", 0.80, 4.00),
+    ("claude-3-haiku", 0.25, 1.25),
+    ("haiku", 0.80, 4.00),
+    ("sonnet", 3.00, 15.00),
+    ("opus", 15.00, 75.00),
+    ("gemini-1.5-flash", 0.075, 0.30),
+    ("gemini-2.0-flash", 0.10, 0.40),
+    ("gemini-2.5-flash", 0.30, 2.50),
+    ("gemini-1.5-pro", 1.25, 5.00),
+    ("gemini-2.5-pro", 1.25, 10.00),
+];
+
+/// Estimated cost in USD, or `None` for hosted models without a known price
+pub fn estimate_cost(backend: Option<&str>, model: &str, usage: TokenUsage) -> Option<f64> {
+    if backend.is_some_and(|b| LOCAL_BACKENDS.contains(&b.to_lowercase().as_str())) {
+        return Some(0.0);
+    }
+    let model = model.to_lowercase();
+    let (_, prompt, completion) = PRICES.iter().find(|(name, _, _)| model.contains(name))?;
+    Some(
+        (f64::from(usage.prompt_tokens) * prompt + f64::from(usage.completion_tokens) * completion)
+            / 1_000_000.0,
+    )
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGroupBy {
+    #[default]
+    Day,
+    Provider,
+    Model,
+    Feature,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UsageStatsQuery {
+    #[serde(default)]
+    pub group_by: UsageGroupBy,
+    /// RFC 3339 lower bound on the log timestamp
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub feature: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageBucket {
+    pub key: String,
+    pub requests: u64,
+    pub failed: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub estimated_cost_usd: f64,
+    /// Successful requests with no price, so the cost is a lower bound
+    pub unpriced_requests: u64,
+}
+
+/// One ai_logs row as far as usage stats care
+#[derive(Debug, Clone)]
+struct UsageRow {
+    timestamp: String,
+    provider: String,
+    model: Option<String>,
+    feature: Option<String>,
+    failed: bool,
+    prompt_tokens: Option<i32>,
+    completion_tokens: Option<i32>,
+    estimated_cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AIUsageLog {
+    db: DatabaseConnection,
+}
+
+impl AIUsageLog {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn record_success(
+        &self,
+        options: &GenerationOptions,
+        result: &GenerationResult,
+    ) -> Result<(), String> {
+        let backend = result.served_by.as_deref();
+        let mut row = log_row("usage", options, backend, Some(result.model.clone()));
+        if let Some(usage) = result.usage {
+            row.prompt_tokens = Set(Some(usage.prompt_tokens as i32));
+            row.completion_tokens = Set(Some(usage.completion_tokens as i32));
+            row.estimated_cost_usd = Set(estimate_cost(backend, &result.model, usage));
+        }
+        self.insert(row).await
+    }
+
+    pub async fn record_failure(
+        &self,
+        options: &GenerationOptions,
+        error: &str,
+    ) -> Result<(), String> {
+        let mut row = log_row(
+            "error",
+            options,
+            options.llm_provider.as_deref(),
+            options.model.clone(),
+        );
+        row.error_message = Set(Some(error.to_string()));
+        self.insert(row).await
+    }
+
+    /// Note that secrets were scrubbed from a request: kinds and counts only
+    pub async fn record_redaction(
+        &self,
+        options: &GenerationOptions,
+        report: &RedactionReport,
+    ) -> Result<(), String> {
+        let mut row = log_row(
+            "redaction",
+            options,
+            options.llm_provider.as_deref(),
+            options.model.clone(),
+        );
+        row.request_data = Set(serde_json::to_string(report).ok());
+        self.insert(row).await
+    }
+
+    async fn insert(&self, row: AILogActiveModel) -> Result<(), String> {
+        row.insert(&self.db)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to record AI usage: {}", e))
+    }
+
+    pub async fn stats(&self, query: UsageStatsQuery) -> Result<Vec<UsageBucket>, String> {
+        let mut select = AILogEntity::find().filter(AILogColumn::LogType.is_in(["usage", "error"]));
+        if let Some(from) = &query.date_from {
+            select = select.filter(AILogColumn::Timestamp.gte(from.as_str()));
+        }
+        if let Some(to) = &query.date_to {
+            select = select.filter(AILogColumn::Timestamp.lte(to.as_str()));
+        }
+        if let Some(feature) = &query.feature {
+            select = select.filter(AILogColumn::Feature.eq(feature.as_str()));
+        }
+
+        let rows: Vec<UsageRow> = select
+            .all(&self.db)
+            .await
+            .map_err(|e| format!("Failed to load AI usage: {}", e))?
+            .into_iter()
+            .map(|log| UsageRow {
+                failed: log.log_type == "error",
+                timestamp: log.timestamp,
+                provider: log.provider,
+                model: log.model,
+                feature: log.feature,
+                prompt_tokens: log.prompt_tokens,
+                completion_tokens: log.completion_tokens,
+                estimated_cost_usd: log.estimated_cost_usd,
+            })
+            .collect();
+        Ok(aggregate(&rows, query.group_by))
+    }
+}
+
+/// Provider recorded when the request didn't pick an agent-platform backend
+const DEFAULT_BACKEND: &str = "default";
+
+fn log_row(
+    log_type: &str,
+    options: &GenerationOptions,
+    backend: Option<&str>,
+    model: Option<String>,
+) -> AILogActiveModel {
+    AILogActiveModel {
+        id: Set(uuid::Uuid::new_v4().to_string()),
+        provider: Set(backend.unwrap_or(DEFAULT_BACKEND).to_string()),
+        log_type: Set(log_type.to_string()),
+        request_data: Set(None),
+        response_data: Set(None),
+        error_message: Set(None),
+        timestamp: Set(chrono::Utc::now().to_rfc3339()),
+        conversation_id: Set(None),
+        model: Set(model.filter(|m| !m.is_empty())),
+        feature: Set(Some(
+            options
+                .feature
+                .clone()
+                .unwrap_or_else(|| "other".to_string()),
+        )),
+        prompt_tokens: Set(None),
+        completion_tokens: Set(None),
+        estimated_cost_usd: Set(None),
+    }
+}
+
+fn aggregate(rows: &[UsageRow], group_by: UsageGroupBy) -> Vec<UsageBucket> {
+    let mut buckets: BTreeMap<String, UsageBucket> = BTreeMap::new();
+    for row in rows {
+        let key = match group_by {
+            UsageGroupBy::Day => row.timestamp.chars().take(10).collect(),
+            UsageGroupBy::Provider => row.provider.clone(),
+            UsageGroupBy::Model => row.model.clone().unwrap_or_else(|| "unknown".to_string()),
+            UsageGroupBy::Feature => row.feature.clone().unwrap_or_else(|| "other".to_string()),
+        };
+        let bucket = buckets.entry(key.clone()).or_insert_with(|| UsageBucket {
+            key,
+            ..Default::default()
+        });
+
+        bucket.requests += 1;
+        if row.failed {
+            bucket.failed += 1;
+            continue;
+        }
+        let prompt = row.prompt_tokens.unwrap_or(0).max(0) as u64;
+        let completion = row.completion_tokens.unwrap_or(0).max(0) as u64;
+        bucket.prompt_tokens += prompt;
+        bucket.completion_tokens += completion;
+        bucket.total_tokens += prompt + completion;
+        match row.estimated_cost_usd {
+            Some(cost) => bucket.estimated_cost_usd += cost,
+            None => bucket.unpriced_requests += 1,
+        }
+    }
+    buckets.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_known_models_and_local_backends() {
+        let usage = TokenUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 500_000,
+        };
+        assert_eq!(
+            estimate_cost(Some("openai"), "gpt-4o-mini", usage),
+            Some(0.45)
+        );
+        assert_eq!(estimate_cost(Some("ollama"), "llama3", usage), Some(0.0));
+        assert_eq!(estimate_cost(Some("openai"), "some-new-model", usage), None);
+    }
+
+    #[test]
+    fn groups_usage_rows() {
+        let row = |timestamp: &str, feature: &str, failed: bool, cost: Option<f64>| UsageRow {
+            timestamp: timestamp.to_string(),
+            provider: "openai".to_string(),
+            model: Some("gpt-4o".to_string()),
+            feature: Some(feature.to_string()),
+            failed,
+            prompt_tokens: (!failed).then_some(100),
+            completion_tokens: (!failed).then_some(50),
+            estimated_cost_usd: cost,
+        };
+        let rows = [
+            row("2026-10-01T10:00:00Z", "chat", false, Some(0.01)),
+            row("2026-10-01T11:00:00Z", "docs", false, None),
+            row("2026-10-02T09:00:00Z", "chat", true, None),
+        ];
+
+        let by_day = aggregate(&rows, UsageGroupBy::Day);
+        assert_eq!(by_day.len(), 2);
+        assert_eq!(by_day[0].key, "2026-10-01");
+        assert_eq!(by_day[0].total_tokens, 300);
+        assert_eq!(by_day[0].unpriced_requests, 1);
+        assert_eq!((by_day[1].requests, by_day[1].failed), (1, 1));
+
+        let by_feature = aggregate(&rows, UsageGroupBy::Feature);
+        assert_eq!(by_feature[0].key, "chat");
+        assert_eq!(by_feature[0].requests, 2);
+        assert_eq!(by_feature[0].estimated_cost_usd, 0.01);
+    }
+}
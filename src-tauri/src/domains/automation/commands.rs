@@ -1,14 +1,25 @@
+use crate::database::DatabaseManager;
+use crate::domains::ai::tools::ToolSpec;
 use crate::domains::automation::entities::*;
+use crate::domains::automation::services::actions;
 use crate::domains::automation::services::automation_service::AutomationService;
+use crate::domains::automation::services::event_bus::{self, AppEvent};
+use crate::domains::automation::services::run_history::RunHistory;
+use crate::domains::automation::services::scheduler::{
+    CreateScheduledJobRequest, Schedule, ScheduledJob, Scheduler,
+};
+use crate::domains::automation::services::webhook_log::{WebhookLog, WebhookRequest};
+use crate::domains::automation::services::webhook_server::{WebhookListenerStatus, WebhookServer};
 use crate::domains::automation::services::workflow_engine::{
     Workflow, WorkflowContext, WorkflowEngine, WorkflowExecutionResult,
 };
+use crate::domains::settings::services::settings_service::SettingsService;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::OnceLock;
-use tauri::State;
+use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
 
 #[tauri::command]
@@ -55,6 +66,155 @@ pub async fn check_n8n_health(
     automation_service.check_n8n_health().await
 }
 
+/// Create a workflow in n8n from a local template file and attach it to a
+/// project. It's activated unless `activate` is false.
+#[tauri::command]
+pub async fn push_n8n_workflow_template(
+    project_id: i32,
+    template_path: String,
+    name: Option<String>,
+    activate: Option<bool>,
+    automation_service: State<'_, Arc<AutomationService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<ProjectN8nWorkflow, String> {
+    automation_service
+        .push_template(
+            db_manager.get_connection(),
+            project_id,
+            std::path::Path::new(&template_path),
+            name,
+            activate.unwrap_or(true),
+        )
+        .await
+}
+
+#[tauri::command]
+pub async fn list_project_n8n_workflows(
+    project_id: i32,
+    automation_service: State<'_, Arc<AutomationService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<ProjectN8nWorkflow>, String> {
+    automation_service
+        .list_project_workflows(db_manager.get_connection(), project_id)
+        .await
+}
+
+/// Delete a project's workflow from n8n and remove it from the project
+#[tauri::command]
+pub async fn remove_project_n8n_workflow(
+    id: i32,
+    automation_service: State<'_, Arc<AutomationService>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    automation_service
+        .remove_project_workflow(db_manager.get_connection(), id)
+        .await
+}
+
+// Scheduled Job Commands
+
+/// Actions that workflows and scheduled jobs can invoke
+#[tauri::command]
+pub async fn list_automation_actions(app: AppHandle) -> Result<Vec<ToolSpec>, String> {
+    Ok(actions::available(&app))
+}
+
+#[tauri::command]
+pub async fn create_scheduled_job(
+    app: AppHandle,
+    request: CreateScheduledJobRequest,
+    scheduler: State<'_, Arc<Scheduler>>,
+) -> Result<ScheduledJob, String> {
+    if !actions::available(&app)
+        .iter()
+        .any(|spec| spec.name == request.action)
+    {
+        return Err(format!("Unknown action: {}", request.action));
+    }
+    scheduler.create_job(request).await
+}
+
+#[tauri::command]
+pub async fn list_scheduled_jobs(
+    scheduler: State<'_, Arc<Scheduler>>,
+) -> Result<Vec<ScheduledJob>, String> {
+    scheduler.list_jobs().await
+}
+
+#[tauri::command]
+pub async fn set_scheduled_job_enabled(
+    id: i32,
+    enabled: bool,
+    scheduler: State<'_, Arc<Scheduler>>,
+) -> Result<ScheduledJob, String> {
+    scheduler.set_enabled(id, enabled).await
+}
+
+#[tauri::command]
+pub async fn delete_scheduled_job(
+    id: i32,
+    scheduler: State<'_, Arc<Scheduler>>,
+) -> Result<(), String> {
+    scheduler.delete_job(id).await
+}
+
+#[tauri::command]
+pub async fn run_scheduled_job_now(
+    app: AppHandle,
+    id: i32,
+    scheduler: State<'_, Arc<Scheduler>>,
+) -> Result<ScheduledJob, String> {
+    scheduler.run_now(&app, id).await
+}
+
+/// The next few times a schedule would run, to check it before saving
+#[tauri::command]
+pub async fn preview_schedule(
+    schedule: Schedule,
+    count: Option<usize>,
+) -> Result<Vec<chrono::DateTime<chrono::Utc>>, String> {
+    schedule.validate()?;
+    let mut times = Vec::new();
+    let mut after = chrono::Utc::now();
+    while times.len() < count.unwrap_or(5).min(50) {
+        match schedule.next_after(after) {
+            Some(next) => {
+                times.push(next);
+                after = next;
+            }
+            None => break,
+        }
+    }
+    Ok(times)
+}
+
+// Webhook Listener Commands
+
+#[tauri::command]
+pub async fn get_webhook_listener_status(
+    webhook_server: State<'_, Arc<WebhookServer>>,
+) -> Result<WebhookListenerStatus, String> {
+    Ok(webhook_server.status())
+}
+
+/// Apply the saved webhook settings: start, stop or move the listener
+#[tauri::command]
+pub async fn restart_webhook_listener(
+    app: AppHandle,
+    webhook_server: State<'_, Arc<WebhookServer>>,
+) -> Result<WebhookListenerStatus, String> {
+    let settings = SettingsService::new().load_settings()?;
+    webhook_server
+        .restart(&app, &settings.app.automation.webhooks)
+        .await
+}
+
+/// Recent inbound webhook requests, newest first
+#[tauri::command]
+pub async fn list_webhook_requests(limit: Option<usize>) -> Result<Vec<WebhookRequest>, String> {
+    Ok(WebhookLog::new()?.list(limit.unwrap_or(50)))
+}
+
 // Workflow Engine Commands
 static WORKFLOW_ENGINES: OnceLock<Arc<Mutex<HashMap<String, WorkflowEngine>>>> = OnceLock::new();
 
@@ -78,15 +238,14 @@ pub async fn register_embedded_workflow(workflow_json: String) -> Result<String,
         .entry("default".to_string())
         .or_insert_with(WorkflowEngine::new);
 
-    engine
-        .load_from_json(&workflow_json)
-        .map_err(|e| format!("Failed to load workflow: {}", e))?;
+    engine.register_workflow(workflow);
 
     Ok(workflow_id)
 }
 
 #[tauri::command]
 pub async fn execute_embedded_workflow(
+    app: AppHandle,
     workflow_id: String,
     project_path: Option<String>,
     variables: Option<HashMap<String, String>>,
@@ -97,13 +256,100 @@ pub async fn execute_embedded_workflow(
         trigger_data: None,
     };
 
+    // Run outside the lock so other workflows can start meanwhile
+    let workflow = {
+        let engines = get_workflow_engines();
+        let engines_guard = engines.lock().await;
+        let engine = engines_guard
+            .get("default")
+            .ok_or("Workflow engine not initialized")?;
+        engine
+            .get_workflow(&workflow_id)
+            .cloned()
+            .ok_or_else(|| format!("Workflow not found: {}", workflow_id))?
+    };
+
+    run_and_record(&app, &workflow, context).await
+}
+
+/// A registered embedded workflow, if there is one by that id
+pub(crate) async fn find_embedded_workflow(workflow_id: &str) -> Option<Workflow> {
     let engines = get_workflow_engines();
     let engines_guard = engines.lock().await;
-    let engine = engines_guard
-        .get("default")
-        .ok_or("Workflow engine not initialized")?;
+    engines_guard
+        .get("default")?
+        .get_workflow(workflow_id)
+        .cloned()
+}
+
+pub(crate) async fn run_and_record(
+    app: &AppHandle,
+    workflow: &Workflow,
+    context: WorkflowContext,
+) -> Result<WorkflowExecutionResult, String> {
+    let result = WorkflowEngine::run(workflow, context, Some(app.clone())).await?;
+    if let Err(e) = RunHistory::new()?.record(&result).await {
+        eprintln!("[Workflow] Failed to record run {}: {}", result.run_id, e);
+    }
+    Ok(result)
+}
+
+/// Run the workflows an event triggers, one after another. Called by the
+/// event bus dispatcher.
+pub(crate) async fn run_event_workflows(app: &AppHandle, event: &AppEvent) {
+    let workflows = {
+        let engines = get_workflow_engines();
+        let engines_guard = engines.lock().await;
+        match engines_guard.get("default") {
+            Some(engine) => engine.workflows_for_event(event),
+            None => return,
+        }
+    };
+
+    for workflow in workflows {
+        println!(
+            "[Workflow] {} triggered by {}",
+            workflow.id, event.event_type
+        );
+        let context = WorkflowContext {
+            project_path: event
+                .payload
+                .get("project_path")
+                .and_then(|path| path.as_str())
+                .map(PathBuf::from),
+            variables: event.variables(),
+            trigger_data: serde_json::to_value(event).ok(),
+        };
+        if let Err(e) = run_and_record(app, &workflow, context).await {
+            eprintln!("[Workflow] {} failed to run: {}", workflow.id, e);
+        }
+    }
+}
+
+/// Publish an event from the frontend, e.g. one only the UI knows about
+#[tauri::command]
+pub async fn publish_app_event(event_type: String, payload: Option<Value>) -> Result<(), String> {
+    if event_type.trim().is_empty() {
+        return Err("Event type is required".to_string());
+    }
+    event_bus::publish(&event_type, payload.unwrap_or(Value::Null));
+    Ok(())
+}
+
+/// Recent embedded workflow runs, newest first
+#[tauri::command]
+pub async fn list_workflow_runs(
+    workflow_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<WorkflowExecutionResult>, String> {
+    Ok(RunHistory::new()?.list(workflow_id.as_deref(), limit.unwrap_or(50)))
+}
 
-    engine.execute_workflow(&workflow_id, context).await
+#[tauri::command]
+pub async fn get_workflow_run(run_id: String) -> Result<WorkflowExecutionResult, String> {
+    RunHistory::new()?
+        .get(&run_id)
+        .ok_or_else(|| format!("Run not found: {}", run_id))
 }
 
 #[tauri::command]
@@ -130,18 +376,3 @@ pub async fn list_embedded_workflows() -> Result<Vec<Value>, String> {
 
     Ok(result)
 }
-
-#[tauri::command]
-pub async fn check_workflow_trigger(
-    workflow_id: String,
-    trigger_data: Value,
-) -> Result<bool, String> {
-    let engines = get_workflow_engines();
-    let engines_guard = engines.lock().await;
-    let engine = engines_guard
-        .get("default")
-        .ok_or("Workflow engine not initialized")?;
-
-    let result = engine.should_trigger(&workflow_id, &trigger_data);
-    Ok(result)
-}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+pub mod project_n8n_workflow;
+pub mod scheduled_job;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowExecution {
     pub id: String,
@@ -56,3 +59,29 @@ pub struct WorkflowTrigger {
     pub workflow_id: String,
     pub project_data: serde_json::Value,
 }
+
+/// A workflow pushed to n8n for a project, with its webhook URLs decoded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectN8nWorkflow {
+    pub id: i32,
+    pub project_id: i32,
+    pub n8n_workflow_id: String,
+    pub name: String,
+    pub template: Option<String>,
+    pub webhook_urls: Vec<String>,
+    pub active: bool,
+}
+
+impl From<project_n8n_workflow::Model> for ProjectN8nWorkflow {
+    fn from(model: project_n8n_workflow::Model) -> Self {
+        Self {
+            id: model.id,
+            project_id: model.project_id,
+            n8n_workflow_id: model.n8n_workflow_id,
+            name: model.name,
+            template: model.template,
+            webhook_urls: serde_json::from_str(&model.webhook_urls).unwrap_or_default(),
+            active: model.active,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! An app action the scheduler runs on a cron expression, at a set time or on an interval.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "scheduled_jobs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub name: String,
+    pub action: String,
+    pub args: String,     // JSON object
+    pub schedule: String, // JSON Schedule
+    pub misfire_policy: String,
+    pub enabled: bool,
+    pub next_run_at: Option<DateTimeWithTimeZone>,
+    pub last_run_at: Option<DateTimeWithTimeZone>,
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: Option<DateTimeWithTimeZone>,
+    pub updated_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,36 @@
+//! Workflows and scheduled jobs act on the app through the domain tool
+//! registry: the same actions the AI assistant can call, such as
+//! `run_pipeline`, `run_custom_script` or `refresh_deployment_statuses`.
+
+use crate::domains::ai::services::ai_service::AIService;
+use crate::domains::ai::tools::{ToolCall, ToolSpec};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+/// Run a registered action. `call_id` identifies the call in logs.
+pub async fn invoke(
+    app: &AppHandle,
+    call_id: &str,
+    action: &str,
+    args: Value,
+) -> Result<Value, String> {
+    let registry = app
+        .try_state::<Arc<AIService>>()
+        .ok_or("The action registry isn't available")?
+        .tools()
+        .clone();
+    let call = ToolCall {
+        id: call_id.to_string(),
+        tool: action.to_string(),
+        arguments: args,
+    };
+    registry.execute(app.clone(), &call).await
+}
+
+/// Every action that can be invoked, with its argument schema
+pub fn available(app: &AppHandle) -> Vec<ToolSpec> {
+    app.try_state::<Arc<AIService>>()
+        .map(|service| service.tools().specs())
+        .unwrap_or_default()
+}
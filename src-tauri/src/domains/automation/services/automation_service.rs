@@ -1,5 +1,12 @@
+use crate::domains::automation::entities::project_n8n_workflow::{
+    ActiveModel as MappingActive, Column as MappingColumn, Entity as MappingEntity,
+};
 use crate::domains::automation::entities::*;
-use serde_json::Value;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde_json::{json, Value};
+use std::path::Path;
+
+const WEBHOOK_NODE: &str = "n8n-nodes-base.webhook";
 
 pub struct AutomationService {
     base_url: String,
@@ -161,4 +168,285 @@ impl AutomationService {
 
         Ok(response.status().is_success())
     }
+
+    /// Create a workflow in n8n from a local template, owned by a project.
+    /// Webhook paths get a per-project prefix so copies made for different
+    /// projects don't collide; their URLs are stored with the mapping.
+    pub async fn push_template(
+        &self,
+        db: &DatabaseConnection,
+        project_id: i32,
+        template_path: &Path,
+        name: Option<String>,
+        activate: bool,
+    ) -> Result<ProjectN8nWorkflow, String> {
+        crate::entities::project::Entity::find_by_id(project_id)
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to load project: {}", e))?
+            .ok_or_else(|| "Project not found".to_string())?;
+
+        let content = tokio::fs::read_to_string(template_path)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", template_path.display(), e))?;
+        let template: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse workflow template: {}", e))?;
+        let template_file = template_path
+            .file_name()
+            .map(|file| file.to_string_lossy().to_string());
+        let name = name
+            .or_else(|| {
+                template
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(String::from)
+            })
+            .or_else(|| template_file.clone())
+            .unwrap_or_else(|| "Portal workflow".to_string());
+        let (body, webhook_paths) = prepare_template(&template, &name, project_id)?;
+
+        let response = self
+            .api(reqwest::Method::POST, "workflows")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to create workflow: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Failed to create workflow: {} {}", status, text));
+        }
+        let created: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse created workflow: {}", e))?;
+        let workflow_id = match created.get("id") {
+            Some(Value::String(id)) => id.clone(),
+            Some(Value::Number(id)) => id.to_string(),
+            _ => return Err("n8n did not return the new workflow's id".to_string()),
+        };
+
+        let active = activate && self.activate_workflow(&workflow_id).await;
+        let webhook_urls: Vec<String> = webhook_paths
+            .iter()
+            .map(|path| format!("{}/webhook/{}", self.base_url, path))
+            .collect();
+
+        let mapping = MappingActive {
+            project_id: Set(project_id),
+            n8n_workflow_id: Set(workflow_id.clone()),
+            name: Set(name),
+            template: Set(template_file),
+            webhook_urls: Set(serde_json::to_string(&webhook_urls).unwrap_or_default()),
+            active: Set(active),
+            ..Default::default()
+        };
+        match mapping.insert(db).await {
+            Ok(model) => Ok(model.into()),
+            Err(e) => {
+                // Don't leave a workflow in n8n that nothing owns
+                let _ = self.delete_remote_workflow(&workflow_id).await;
+                Err(format!("Failed to save workflow mapping: {}", e))
+            }
+        }
+    }
+
+    pub async fn list_project_workflows(
+        &self,
+        db: &DatabaseConnection,
+        project_id: i32,
+    ) -> Result<Vec<ProjectN8nWorkflow>, String> {
+        let mappings = MappingEntity::find()
+            .filter(MappingColumn::ProjectId.eq(project_id))
+            .all(db)
+            .await
+            .map_err(|e| format!("Failed to load project workflows: {}", e))?;
+        Ok(mappings.into_iter().map(Into::into).collect())
+    }
+
+    /// Delete a project's workflow from n8n and forget it
+    pub async fn remove_project_workflow(
+        &self,
+        db: &DatabaseConnection,
+        id: i32,
+    ) -> Result<(), String> {
+        let mapping = MappingEntity::find_by_id(id)
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to load project workflow: {}", e))?
+            .ok_or_else(|| "Project workflow not found".to_string())?;
+        self.delete_remote_workflow(&mapping.n8n_workflow_id)
+            .await?;
+        MappingEntity::delete_by_id(id)
+            .exec(db)
+            .await
+            .map_err(|e| format!("Failed to remove project workflow: {}", e))?;
+        Ok(())
+    }
+
+    /// Delete workflows from n8n and forget them, for a project that has been
+    /// deleted. Workflows n8n can't delete are logged; the mappings go anyway.
+    pub async fn cleanup_workflows(
+        &self,
+        db: &DatabaseConnection,
+        workflows: &[ProjectN8nWorkflow],
+    ) {
+        for workflow in workflows {
+            if let Err(e) = self.delete_remote_workflow(&workflow.n8n_workflow_id).await {
+                eprintln!(
+                    "[Automation] Failed to delete n8n workflow {} of project {}: {}",
+                    workflow.n8n_workflow_id, workflow.project_id, e
+                );
+            }
+        }
+        let ids: Vec<i32> = workflows.iter().map(|workflow| workflow.id).collect();
+        if let Err(e) = MappingEntity::delete_many()
+            .filter(MappingColumn::Id.is_in(ids))
+            .exec(db)
+            .await
+        {
+            eprintln!("[Automation] Failed to remove project workflows: {}", e);
+        }
+    }
+
+    /// Activate a workflow so its webhooks answer. Failures leave it inactive.
+    async fn activate_workflow(&self, workflow_id: &str) -> bool {
+        match self
+            .api(
+                reqwest::Method::POST,
+                &format!("workflows/{}/activate", workflow_id),
+            )
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => true,
+            Ok(response) => {
+                eprintln!(
+                    "[Automation] Failed to activate workflow {}: {}",
+                    workflow_id,
+                    response.status()
+                );
+                false
+            }
+            Err(e) => {
+                eprintln!(
+                    "[Automation] Failed to activate workflow {}: {}",
+                    workflow_id, e
+                );
+                false
+            }
+        }
+    }
+
+    /// Delete a workflow in n8n; one that is already gone counts as deleted
+    async fn delete_remote_workflow(&self, workflow_id: &str) -> Result<(), String> {
+        let response = self
+            .api(
+                reqwest::Method::DELETE,
+                &format!("workflows/{}", workflow_id),
+            )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to delete workflow: {}", e))?;
+        let status = response.status();
+        if status.is_success() || status == reqwest::StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(format!("Failed to delete workflow: {}", status))
+        }
+    }
+
+    /// A request to n8n's public API, which takes its key in `X-N8N-API-KEY`
+    fn api(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request =
+            reqwest::Client::new().request(method, format!("{}/api/v1/{}", self.base_url, path));
+        match &self.api_key {
+            Some(api_key) => request.header("X-N8N-API-KEY", api_key),
+            None => request,
+        }
+    }
+}
+
+/// The body n8n's API accepts for a template (it rejects read-only fields
+/// such as `active` and `id`), with each webhook node's path prefixed for the
+/// project and given a fresh webhook id. Returns the body and webhook paths.
+fn prepare_template(
+    template: &Value,
+    name: &str,
+    project_id: i32,
+) -> Result<(Value, Vec<String>), String> {
+    let mut nodes = template
+        .get("nodes")
+        .and_then(Value::as_array)
+        .cloned()
+        .ok_or_else(|| "Workflow template has no nodes".to_string())?;
+
+    let mut webhook_paths = Vec::new();
+    for node in &mut nodes {
+        if node.get("type").and_then(Value::as_str) != Some(WEBHOOK_NODE) {
+            continue;
+        }
+        let original = node
+            .pointer("/parameters/path")
+            .and_then(Value::as_str)
+            .unwrap_or("webhook")
+            .trim_matches('/')
+            .to_string();
+        let path = format!("portal-{}-{}", project_id, original);
+        node["parameters"]["path"] = json!(path);
+        node["webhookId"] = json!(uuid::Uuid::new_v4().to_string());
+        webhook_paths.push(path);
+    }
+
+    let body = json!({
+        "name": name,
+        "nodes": nodes,
+        "connections": template.get("connections").cloned().unwrap_or_else(|| json!({})),
+        "settings": template.get("settings").cloned().unwrap_or_else(|| json!({})),
+    });
+    Ok((body, webhook_paths))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_webhooks_and_drops_read_only_fields() {
+        let template = json!({
+            "name": "Health Check",
+            "active": true,
+            "versionId": "1",
+            "nodes": [
+                {
+                    "name": "Webhook",
+                    "type": "n8n-nodes-base.webhook",
+                    "parameters": { "path": "/health-check", "httpMethod": "POST" },
+                    "webhookId": "health-check"
+                },
+                {
+                    "name": "Respond",
+                    "type": "n8n-nodes-base.respondToWebhook",
+                    "parameters": { "path": "untouched" }
+                }
+            ],
+            "connections": { "Webhook": { "main": [[{ "node": "Respond" }]] } }
+        });
+
+        let (body, paths) = prepare_template(&template, "Health", 7).unwrap();
+        assert_eq!(paths, vec!["portal-7-health-check".to_string()]);
+        assert_eq!(body["name"], "Health");
+        assert_eq!(
+            body["nodes"][0]["parameters"]["path"],
+            "portal-7-health-check"
+        );
+        assert_eq!(body["nodes"][0]["parameters"]["httpMethod"], "POST");
+        assert_ne!(body["nodes"][0]["webhookId"], "health-check");
+        assert_eq!(body["nodes"][1]["parameters"]["path"], "untouched");
+        assert_eq!(body["connections"], template["connections"]);
+        assert!(body.get("active").is_none());
+        assert!(body.get("versionId").is_none());
+
+        assert!(prepare_template(&json!({ "name": "Empty" }), "Empty", 7).is_err());
+    }
 }
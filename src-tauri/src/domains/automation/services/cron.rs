@@ -0,0 +1,214 @@
+//! Cron expression parsing and next-run times.
+//!
+//! Standard five-field expressions (minute hour day-of-month month
+//! day-of-week) with `*`, lists, ranges, steps, month and weekday names, and
+//! the `@hourly`-style shorthands. As in cron, when both day fields are
+//! restricted a day matching either one matches.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+/// Enough for any expression that can match at all, including Feb 29
+const MAX_STEPS: usize = 100_000;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpression {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronExpression {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Expected 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            ));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, &WEEKDAYS, "weekday")?;
+        // 7 is Sunday too
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[], "minute")?,
+            hours: parse_field(hour, 0, 23, &[], "hour")?,
+            days: parse_field(day, 1, 31, &[], "day")?,
+            months: parse_field(month, 1, 12, &MONTHS, "month")?,
+            weekdays,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+
+    /// The first matching minute strictly after `after`
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        for _ in 0..MAX_STEPS {
+            if !has(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !has(self.hours, time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !has(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+}
+
+fn has(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// A field as a bitmask of the values it allows
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    label: &str,
+) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        let lower = text.to_lowercase();
+        if let Some(index) = names.iter().position(|name| *name == lower) {
+            // Month names count from 1, weekday names from 0
+            return Ok(index as u32 + if names.len() == 12 { 1 } else { 0 });
+        }
+        let number: u32 = text
+            .parse()
+            .map_err(|_| format!("Invalid {} value: {}", label, text))?;
+        if number < min || number > max {
+            return Err(format!("{} {} is outside {}-{}", label, number, min, max));
+        }
+        Ok(number)
+    };
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid {} step: {}", label, part))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `5/15` runs from 5 to the end of the range
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start > end {
+            return Err(format!("Invalid {} range: {}", label, part));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn parses_and_finds_next_runs() {
+        let every_15 = CronExpression::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            every_15.next_after(at("2024-05-01 10:07")),
+            Some(at("2024-05-01 10:15"))
+        );
+        assert_eq!(
+            every_15.next_after(at("2024-05-01 10:45")),
+            Some(at("2024-05-01 11:00"))
+        );
+
+        // Weekdays at 9:30; 2024-05-04 is a Saturday
+        let weekdays = CronExpression::parse("30 9 * * mon-fri").unwrap();
+        assert_eq!(
+            weekdays.next_after(at("2024-05-03 09:30")),
+            Some(at("2024-05-06 09:30"))
+        );
+
+        let leap_day = CronExpression::parse("0 0 29 feb *").unwrap();
+        assert_eq!(
+            leap_day.next_after(at("2024-03-01 00:00")),
+            Some(at("2028-02-29 00:00"))
+        );
+
+        // Either day field matches when both are set: the 13th or a Friday
+        let either = CronExpression::parse("0 12 13 * 5").unwrap();
+        assert_eq!(
+            either.next_after(at("2024-05-01 00:00")),
+            Some(at("2024-05-03 12:00"))
+        );
+
+        assert_eq!(
+            CronExpression::parse("@daily").unwrap(),
+            CronExpression::parse("0 0 * * *").unwrap()
+        );
+        assert_eq!(
+            CronExpression::parse("0 0 * * 7").unwrap(),
+            CronExpression::parse("0 0 * * sun").unwrap()
+        );
+        assert!(CronExpression::parse("60 * * * *").is_err());
+        assert!(CronExpression::parse("* * *").is_err());
+        assert!(CronExpression::parse("*/0 * * * *").is_err());
+        assert!(CronExpression::parse("0 0 31 2 *")
+            .unwrap()
+            .next_after(at("2024-01-01 00:00"))
+            .is_none());
+    }
+}
@@ -0,0 +1,170 @@
+//! App events and the dispatcher that delivers them.
+//!
+//! Domains publish app events here: a project created, a pipeline failing,
+//! a pod crash looping, a credential about to expire. The dispatcher started
+//! at app setup forwards each one to the frontend as `app-event` and runs
+//! every embedded workflow with a matching `Event` trigger, along with every
+//! custom script with a matching event trigger, so automations react to the
+//! app instead of being polled.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+pub const APP_EVENT: &str = "app-event";
+
+pub const PROJECT_CREATED: &str = "project.created";
+pub const PROJECT_OPENED: &str = "project.opened";
+pub const PIPELINE_FAILED: &str = "pipeline.failed";
+pub const PIPELINE_FINISHED: &str = "pipeline.finished";
+pub const POD_CRASH_LOOPING: &str = "pod.crash_looping";
+pub const CREDENTIAL_EXPIRING: &str = "credential.expiring";
+
+/// Events published faster than the dispatcher runs workflows queue up to here
+const CHANNEL_CAPACITY: usize = 256;
+
+static BUS: once_cell::sync::Lazy<broadcast::Sender<AppEvent>> =
+    once_cell::sync::Lazy::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppEvent {
+    pub id: String,
+    /// e.g. "pipeline.failed"
+    pub event_type: String,
+    pub payload: Value,
+    pub occurred_at: String,
+}
+
+impl AppEvent {
+    /// Workflow variables for a run this event starts: `event_type` and
+    /// `event_<field>` for each plain field of the payload
+    pub fn variables(&self) -> HashMap<String, String> {
+        let mut variables = payload_variables("event", &self.payload);
+        variables.insert("event_type".to_string(), self.event_type.clone());
+        variables
+    }
+
+    /// Whether a trigger's event type covers this event: exact, `*`, or a
+    /// prefix such as `pipeline.*`
+    pub fn matches(&self, pattern: &str) -> bool {
+        match pattern.strip_suffix('*') {
+            Some(prefix) => self.event_type.starts_with(prefix),
+            None => self.event_type == pattern,
+        }
+    }
+
+    /// Whether every filter field equals the payload's field of that name
+    pub fn matches_filter(&self, filter: &HashMap<String, String>) -> bool {
+        filter
+            .iter()
+            .all(|(key, expected)| match self.payload.get(key) {
+                Some(Value::String(text)) => text == expected,
+                // Numbers and booleans compare by value, "3" with 3
+                Some(value) => serde_json::from_str::<Value>(expected).is_ok_and(|v| v == *value),
+                None => false,
+            })
+    }
+}
+
+/// `<prefix>_<field>` for each plain (string, number or boolean) field of a
+/// JSON object
+pub fn payload_variables(prefix: &str, payload: &Value) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    if let Value::Object(fields) = payload {
+        for (key, value) in fields {
+            let text = match value {
+                Value::String(text) => text.clone(),
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => continue,
+            };
+            variables.insert(format!("{}_{}", prefix, key), text);
+        }
+    }
+    variables
+}
+
+/// Publish an event. Nothing is lost by publishing before the dispatcher
+/// starts; there is just nobody to run.
+pub fn publish(event_type: &str, payload: Value) {
+    let event = AppEvent {
+        id: uuid::Uuid::new_v4().to_string(),
+        event_type: event_type.to_string(),
+        payload,
+        occurred_at: chrono::Utc::now().to_rfc3339(),
+    };
+    // Only fails when nothing subscribes
+    let _ = BUS.send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<AppEvent> {
+    BUS.subscribe()
+}
+
+/// Relay events to the frontend and run the workflows they trigger, for as
+/// long as the app runs
+pub fn start_dispatcher(app: AppHandle) {
+    let mut events = subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let _ = app.emit(APP_EVENT, &event);
+                    crate::domains::automation::commands::run_event_workflows(&app, &event).await;
+                    crate::domains::custom_scripts::services::script_triggers::run_event_scripts(
+                        &app, &event,
+                    )
+                    .await;
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!(
+                        "[Automation] Event bus fell behind; {} events dropped",
+                        skipped
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_events_and_exposes_payload() {
+        let event = AppEvent {
+            id: "1".to_string(),
+            event_type: PIPELINE_FAILED.to_string(),
+            payload: serde_json::json!({
+                "project_id": "7",
+                "pipeline_id": 3,
+                "step_executions": [],
+            }),
+            occurred_at: "2024-05-01T00:00:00Z".to_string(),
+        };
+
+        assert!(event.matches("pipeline.failed"));
+        assert!(event.matches("pipeline.*"));
+        assert!(event.matches("*"));
+        assert!(!event.matches("pipeline"));
+        assert!(!event.matches("project.*"));
+
+        assert!(event.matches_filter(&HashMap::from([
+            ("project_id".to_string(), "7".to_string()),
+            ("pipeline_id".to_string(), "3".to_string()),
+        ])));
+        assert!(!event.matches_filter(&HashMap::from([(
+            "project_id".to_string(),
+            "8".to_string()
+        )])));
+
+        let variables = event.variables();
+        assert_eq!(variables["event_type"], "pipeline.failed");
+        assert_eq!(variables["event_project_id"], "7");
+        assert_eq!(variables["event_pipeline_id"], "3");
+        assert!(!variables.contains_key("event_step_executions"));
+    }
+}
@@ -1,5 +1,9 @@
+pub mod actions;
 pub mod automation_service;
+pub mod cron;
+pub mod event_bus;
+pub mod run_history;
+pub mod scheduler;
+pub mod webhook_log;
+pub mod webhook_server;
 pub mod workflow_engine;
-
-// FUTURE: Workflow engine will be used when implementing embedded workflow execution
-// pub use workflow_engine::*;
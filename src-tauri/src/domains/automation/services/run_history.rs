@@ -0,0 +1,81 @@
+//! History of embedded workflow runs.
+//!
+//! Every embedded workflow run, with its trace, kept in
+//! `~/.portal-desktop/automation/runs.json`. Only the latest runs are kept
+//! and long step output is cut short so the file stays small.
+
+use super::workflow_engine::WorkflowExecutionResult;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+
+/// Serializes writers across concurrent runs
+static HISTORY_LOCK: once_cell::sync::Lazy<Mutex<()>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+const MAX_RUNS: usize = 200;
+const MAX_OUTPUT_CHARS: usize = 4000;
+
+pub struct RunHistory {
+    path: PathBuf,
+}
+
+impl RunHistory {
+    pub fn new() -> Result<Self, String> {
+        let path = dirs::home_dir()
+            .ok_or("Could not determine home directory")?
+            .join(".portal-desktop")
+            .join("automation")
+            .join("runs.json");
+        Ok(Self { path })
+    }
+
+    fn load(&self) -> Vec<WorkflowExecutionResult> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Add a finished run, newest first
+    pub async fn record(&self, run: &WorkflowExecutionResult) -> Result<(), String> {
+        let _guard = HISTORY_LOCK.lock().await;
+        let mut runs = self.load();
+
+        let mut run = run.clone();
+        for step in &mut run.output {
+            step.output = step.output.take().map(|text| truncate(&text));
+            step.error = step.error.take().map(|text| truncate(&text));
+        }
+        runs.insert(0, run);
+        runs.truncate(MAX_RUNS);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let content = serde_json::to_string_pretty(&runs)
+            .map_err(|e| format!("Failed to serialize run history: {}", e))?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write run history: {}", e))
+    }
+
+    /// Recent runs, newest first, optionally for one workflow
+    pub fn list(&self, workflow_id: Option<&str>, limit: usize) -> Vec<WorkflowExecutionResult> {
+        self.load()
+            .into_iter()
+            .filter(|run| workflow_id.is_none_or(|id| run.workflow_id == id))
+            .take(limit)
+            .collect()
+    }
+
+    pub fn get(&self, run_id: &str) -> Option<WorkflowExecutionResult> {
+        self.load().into_iter().find(|run| run.run_id == run_id)
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((cut, _)) => format!("{}… (truncated)", &text[..cut]),
+        None => text.to_string(),
+    }
+}
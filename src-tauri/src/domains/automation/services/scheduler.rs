@@ -0,0 +1,400 @@
+//! Runs app actions on cron expressions, at set times, or on intervals.
+//!
+//! Jobs live in SQLite and invoke any registered app action (run a pipeline,
+//! run a custom script, refresh deployment statuses, ...). A timer started at
+//! app setup runs whatever is due. A run missed while the app was closed is
+//! a misfire: by default it runs once on the next tick, or it is skipped when
+//! the job says so. Either way only one run is made up, however many were
+//! missed.
+
+use super::actions;
+use super::cron::CronExpression;
+use crate::database::DatabaseManager;
+use crate::domains::automation::entities::scheduled_job::{
+    ActiveModel as JobActive, Column as JobColumn, Entity as JobEntity, Model as JobModel,
+};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+pub const JOB_RUN_EVENT: &str = "scheduled-job-run";
+
+const TICK: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A run this late counts as missed rather than just caught by the next tick
+const MISFIRE_GRACE_SECS: i64 = 120;
+
+const MIN_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Schedule {
+    /// Five-field cron expression in local time
+    Cron {
+        expression: String,
+    },
+    /// Once, at a set time
+    At {
+        run_at: DateTime<Utc>,
+    },
+    Interval {
+        seconds: u64,
+    },
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Schedule::Cron { expression } => CronExpression::parse(expression).map(|_| ()),
+            Schedule::At { .. } => Ok(()),
+            Schedule::Interval { seconds } if *seconds < MIN_INTERVAL_SECS => Err(format!(
+                "Intervals must be at least {} seconds",
+                MIN_INTERVAL_SECS
+            )),
+            Schedule::Interval { .. } => Ok(()),
+        }
+    }
+
+    /// When the job runs next after `after`; `None` when it never will
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Cron { expression } => {
+                let cron = CronExpression::parse(expression).ok()?;
+                let mut from = after.with_timezone(&Local).naive_local();
+                loop {
+                    let next = cron.next_after(from)?;
+                    // Times skipped by a daylight saving change don't exist
+                    match Local.from_local_datetime(&next).earliest() {
+                        Some(time) => return Some(time.with_timezone(&Utc)),
+                        None => from = next,
+                    }
+                }
+            }
+            Schedule::At { run_at } => (*run_at > after).then_some(*run_at),
+            Schedule::Interval { seconds } => {
+                Some(after + chrono::Duration::seconds(*seconds as i64))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MisfirePolicy {
+    /// Run once as soon as the app is back
+    RunOnce,
+    /// Wait for the next scheduled time
+    Skip,
+}
+
+impl MisfirePolicy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MisfirePolicy::RunOnce => "run_once",
+            MisfirePolicy::Skip => "skip",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "skip" => MisfirePolicy::Skip,
+            _ => MisfirePolicy::RunOnce,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateScheduledJobRequest {
+    pub name: String,
+    pub action: String,
+    #[serde(default)]
+    pub args: Value,
+    pub schedule: Schedule,
+    pub misfire_policy: Option<MisfirePolicy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: i32,
+    pub name: String,
+    pub action: String,
+    pub args: Value,
+    pub schedule: Option<Schedule>,
+    pub misfire_policy: MisfirePolicy,
+    pub enabled: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// success, failed or skipped
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+}
+
+impl From<JobModel> for ScheduledJob {
+    fn from(model: JobModel) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            action: model.action,
+            args: serde_json::from_str(&model.args).unwrap_or(Value::Null),
+            schedule: serde_json::from_str(&model.schedule).ok(),
+            misfire_policy: MisfirePolicy::parse(&model.misfire_policy),
+            enabled: model.enabled,
+            next_run_at: model.next_run_at.map(|time| time.with_timezone(&Utc)),
+            last_run_at: model.last_run_at.map(|time| time.with_timezone(&Utc)),
+            last_status: model.last_status,
+            last_error: model.last_error,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Scheduler {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl Scheduler {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    pub async fn create_job(
+        &self,
+        request: CreateScheduledJobRequest,
+    ) -> Result<ScheduledJob, String> {
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err("Job name is required".to_string());
+        }
+        request.schedule.validate()?;
+        let next_run_at = request
+            .schedule
+            .next_after(Utc::now())
+            .ok_or("This schedule never runs")?;
+
+        let args = if request.args.is_null() {
+            Value::Object(Default::default())
+        } else {
+            request.args
+        };
+        let job = JobActive {
+            name: Set(name.to_string()),
+            action: Set(request.action),
+            args: Set(args.to_string()),
+            schedule: Set(serde_json::to_string(&request.schedule).map_err(|e| e.to_string())?),
+            misfire_policy: Set(request
+                .misfire_policy
+                .unwrap_or(MisfirePolicy::RunOnce)
+                .as_str()
+                .to_string()),
+            enabled: Set(true),
+            next_run_at: Set(Some(next_run_at.fixed_offset())),
+            ..Default::default()
+        };
+        let job = job
+            .insert(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to create scheduled job: {}", e))?;
+        Ok(job.into())
+    }
+
+    pub async fn list_jobs(&self) -> Result<Vec<ScheduledJob>, String> {
+        let jobs = JobEntity::find()
+            .order_by_asc(JobColumn::Name)
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load scheduled jobs: {}", e))?;
+        Ok(jobs.into_iter().map(Into::into).collect())
+    }
+
+    /// Pause or resume a job. Resuming schedules it from now, so runs missed
+    /// while paused aren't made up.
+    pub async fn set_enabled(&self, id: i32, enabled: bool) -> Result<ScheduledJob, String> {
+        let job = self.get(id).await?;
+        let next_run_at = if enabled {
+            let schedule: Schedule = serde_json::from_str(&job.schedule)
+                .map_err(|e| format!("Invalid schedule: {}", e))?;
+            Some(
+                schedule
+                    .next_after(Utc::now())
+                    .ok_or("This schedule never runs again")?
+                    .fixed_offset(),
+            )
+        } else {
+            job.next_run_at
+        };
+
+        let mut active: JobActive = job.into();
+        active.enabled = Set(enabled);
+        active.next_run_at = Set(next_run_at);
+        active.updated_at = Set(Some(Utc::now().fixed_offset()));
+        let job = active
+            .update(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to update scheduled job: {}", e))?;
+        Ok(job.into())
+    }
+
+    pub async fn delete_job(&self, id: i32) -> Result<(), String> {
+        JobEntity::delete_by_id(id)
+            .exec(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to delete scheduled job: {}", e))?;
+        Ok(())
+    }
+
+    /// Run a job right away, leaving its schedule as it is
+    pub async fn run_now(&self, app: &AppHandle, id: i32) -> Result<ScheduledJob, String> {
+        let job = self.get(id).await?;
+        self.execute(app, job).await
+    }
+
+    /// Run due jobs until the app exits. The first tick runs right away and
+    /// picks up anything missed while the app was closed.
+    pub async fn run(&self, app: AppHandle) {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.tick(&app).await {
+                eprintln!("[Scheduler] {}", e);
+            }
+        }
+    }
+
+    async fn tick(&self, app: &AppHandle) -> Result<(), String> {
+        let now = Utc::now();
+        let due = JobEntity::find()
+            .filter(JobColumn::Enabled.eq(true))
+            .filter(JobColumn::NextRunAt.lte(now.fixed_offset()))
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load due jobs: {}", e))?;
+
+        for job in due {
+            let Some(planned) = job.next_run_at else {
+                continue;
+            };
+            let misfired = (now - planned.with_timezone(&Utc)).num_seconds() > MISFIRE_GRACE_SECS;
+            let next_run_at = serde_json::from_str::<Schedule>(&job.schedule)
+                .ok()
+                .and_then(|schedule| schedule.next_after(now));
+
+            // Move the job on before it runs so a slow run isn't started twice
+            let mut active: JobActive = job.clone().into();
+            active.next_run_at = Set(next_run_at.map(|time| time.fixed_offset()));
+            active.enabled = Set(next_run_at.is_some());
+            let job = active
+                .update(self.db_manager.get_connection())
+                .await
+                .map_err(|e| format!("Failed to reschedule job {}: {}", job.name, e))?;
+
+            if misfired && MisfirePolicy::parse(&job.misfire_policy) == MisfirePolicy::Skip {
+                println!(
+                    "[Scheduler] Skipping missed run of {} planned for {}",
+                    job.name, planned
+                );
+                let _ = self.record(job, "skipped", None).await;
+                continue;
+            }
+
+            let scheduler = self.clone();
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let name = job.name.clone();
+                if let Err(e) = scheduler.execute(&app, job).await {
+                    eprintln!("[Scheduler] Failed to record run of {}: {}", name, e);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Invoke the job's action and record how it went
+    async fn execute(&self, app: &AppHandle, job: JobModel) -> Result<ScheduledJob, String> {
+        println!("[Scheduler] Running {} ({})", job.name, job.action);
+        let args = serde_json::from_str(&job.args).unwrap_or(Value::Object(Default::default()));
+        let outcome =
+            actions::invoke(app, &format!("scheduled-job-{}", job.id), &job.action, args).await;
+
+        let job = match &outcome {
+            Ok(_) => self.record(job, "success", None).await?,
+            Err(e) => self.record(job, "failed", Some(e.clone())).await?,
+        };
+        let _ = app.emit(
+            JOB_RUN_EVENT,
+            serde_json::json!({
+                "job": job,
+                "output": outcome.as_ref().ok(),
+            }),
+        );
+        Ok(job)
+    }
+
+    async fn record(
+        &self,
+        job: JobModel,
+        status: &str,
+        error: Option<String>,
+    ) -> Result<ScheduledJob, String> {
+        let now = Utc::now().fixed_offset();
+        let mut active: JobActive = job.into();
+        active.last_run_at = Set(Some(now));
+        active.last_status = Set(Some(status.to_string()));
+        active.last_error = Set(error);
+        active.updated_at = Set(Some(now));
+        let job = active
+            .update(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to record job run: {}", e))?;
+        Ok(job.into())
+    }
+
+    async fn get(&self, id: i32) -> Result<JobModel, String> {
+        JobEntity::find_by_id(id)
+            .one(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load scheduled job: {}", e))?
+            .ok_or_else(|| "Scheduled job not found".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedules_next_runs() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap();
+
+        let interval = Schedule::Interval { seconds: 300 };
+        assert_eq!(
+            interval.next_after(now),
+            Some(Utc.with_ymd_and_hms(2024, 5, 1, 10, 5, 0).unwrap())
+        );
+        assert!(Schedule::Interval { seconds: 5 }.validate().is_err());
+
+        let later = Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap();
+        let once = Schedule::At { run_at: later };
+        assert_eq!(once.next_after(now), Some(later));
+        assert_eq!(once.next_after(later), None);
+
+        let hourly = Schedule::Cron {
+            expression: "@hourly".to_string(),
+        };
+        let next = hourly.next_after(now).unwrap();
+        assert!(next > now && next <= now + chrono::Duration::hours(1));
+        assert!(Schedule::Cron {
+            expression: "not cron".to_string()
+        }
+        .validate()
+        .is_err());
+
+        let parsed: Schedule =
+            serde_json::from_str(r#"{"kind":"cron","expression":"0 9 * * mon-fri"}"#).unwrap();
+        assert!(parsed.validate().is_ok());
+        assert_eq!(MisfirePolicy::parse("skip"), MisfirePolicy::Skip);
+        assert_eq!(MisfirePolicy::parse("anything"), MisfirePolicy::RunOnce);
+    }
+}
@@ -0,0 +1,102 @@
+//! Log of the requests the webhook listener receives.
+//!
+//! Every request the webhook listener receives, accepted or not, kept in
+//! `~/.portal-desktop/automation/webhooks.json`. Entries start out
+//! `accepted` and are updated with how the run they started went.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+
+static LOG_LOCK: once_cell::sync::Lazy<Mutex<()>> = once_cell::sync::Lazy::new(Default::default);
+
+const MAX_REQUESTS: usize = 200;
+const MAX_PAYLOAD_CHARS: usize = 4000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRequest {
+    pub id: String,
+    pub received_at: String,
+    pub method: String,
+    pub path: String,
+    pub remote_addr: String,
+    /// Event name the sender put in a header, e.g. GitHub's `X-GitHub-Event`
+    pub event: Option<String>,
+    pub status_code: u16,
+    /// rejected, accepted, succeeded or failed
+    pub outcome: String,
+    pub detail: Option<String>,
+    pub payload: Option<String>,
+}
+
+pub struct WebhookLog {
+    path: PathBuf,
+}
+
+impl WebhookLog {
+    pub fn new() -> Result<Self, String> {
+        let path = dirs::home_dir()
+            .ok_or("Could not determine home directory")?
+            .join(".portal-desktop")
+            .join("automation")
+            .join("webhooks.json");
+        Ok(Self { path })
+    }
+
+    fn load(&self) -> Vec<WebhookRequest> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, requests: &[WebhookRequest]) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let content = serde_json::to_string_pretty(requests)
+            .map_err(|e| format!("Failed to serialize webhook log: {}", e))?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write webhook log: {}", e))
+    }
+
+    /// Add a request, newest first
+    pub async fn record(&self, request: &WebhookRequest) -> Result<(), String> {
+        let _guard = LOG_LOCK.lock().await;
+        let mut requests = self.load();
+        let mut request = request.clone();
+        request.payload = request.payload.take().map(|text| truncate(&text));
+        requests.insert(0, request);
+        requests.truncate(MAX_REQUESTS);
+        self.save(&requests)
+    }
+
+    /// Set how the run a request started went
+    pub async fn finish(
+        &self,
+        id: &str,
+        outcome: &str,
+        detail: Option<String>,
+    ) -> Result<(), String> {
+        let _guard = LOG_LOCK.lock().await;
+        let mut requests = self.load();
+        if let Some(request) = requests.iter_mut().find(|request| request.id == id) {
+            request.outcome = outcome.to_string();
+            request.detail = detail.map(|text| truncate(&text));
+            self.save(&requests)?;
+        }
+        Ok(())
+    }
+
+    pub fn list(&self, limit: usize) -> Vec<WebhookRequest> {
+        self.load().into_iter().take(limit).collect()
+    }
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_PAYLOAD_CHARS) {
+        Some((cut, _)) => format!("{}… (truncated)", &text[..cut]),
+        None => text.to_string(),
+    }
+}
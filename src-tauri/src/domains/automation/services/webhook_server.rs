@@ -0,0 +1,497 @@
+//! An optional local HTTP server so git hosts, CI and other tools can start
+//! runs in the app:
+//!
+//! - `POST /workflows/{id}` runs an embedded workflow. The JSON body is the
+//!   run's trigger data and each plain field becomes a `payload_<field>`
+//!   variable; `?project_path=` sets the project directory.
+//! - `POST /pipelines/{id}` runs a pipeline with the same variables.
+//! - `GET /health` answers without a token.
+//!
+//! Every request carries the configured token. Runs start in the background
+//! and the request is answered `202 Accepted` right away, since webhook
+//! senders give up after a few seconds. Each request lands in the
+//! `WebhookLog`.
+
+use super::actions;
+use super::event_bus::payload_variables;
+use super::webhook_log::{WebhookLog, WebhookRequest};
+use super::workflow_engine::WorkflowContext;
+use crate::domains::settings::services::settings_service::WebhookListenerSettings;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub const WEBHOOK_REQUEST_EVENT: &str = "webhook-request";
+
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookListenerStatus {
+    pub running: bool,
+    /// e.g. "127.0.0.1:8787"
+    pub address: Option<String>,
+}
+
+struct Listening {
+    address: String,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+#[derive(Default)]
+pub struct WebhookServer {
+    listening: Mutex<Option<Listening>>,
+}
+
+impl WebhookServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(&self) -> WebhookListenerStatus {
+        let listening = self.listening.lock().unwrap();
+        WebhookListenerStatus {
+            running: listening.is_some(),
+            address: listening.as_ref().map(|l| l.address.clone()),
+        }
+    }
+
+    /// Stop listening, then start again with `settings` if they enable it
+    pub async fn restart(
+        &self,
+        app: &AppHandle,
+        settings: &WebhookListenerSettings,
+    ) -> Result<WebhookListenerStatus, String> {
+        self.stop();
+        if !settings.enabled {
+            return Ok(self.status());
+        }
+        if settings.token.trim().is_empty() {
+            return Err("Set a webhook token before enabling the listener".to_string());
+        }
+
+        let address = format!("{}:{}", settings.bind_address, settings.port);
+        let listener = TcpListener::bind(&address)
+            .await
+            .map_err(|e| format!("Failed to listen on {}: {}", address, e))?;
+        println!("[Webhooks] Listening on {}", address);
+
+        let app = app.clone();
+        let token = settings.token.clone();
+        let task = tauri::async_runtime::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let app = app.clone();
+                        let token = token.clone();
+                        tauri::async_runtime::spawn(async move {
+                            handle_connection(app, stream, peer, &token).await;
+                        });
+                    }
+                    Err(e) => eprintln!("[Webhooks] Failed to accept a connection: {}", e),
+                }
+            }
+        });
+        *self.listening.lock().unwrap() = Some(Listening { address, task });
+        Ok(self.status())
+    }
+
+    pub fn stop(&self) {
+        if let Some(listening) = self.listening.lock().unwrap().take() {
+            listening.task.abort();
+            println!("[Webhooks] Stopped listening on {}", listening.address);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    /// Lowercased names
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    fn authorized(&self, token: &str) -> bool {
+        let given = self
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .or_else(|| self.header("x-portal-token"))
+            .or_else(|| self.query.get("token").map(String::as_str));
+        given.is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+    }
+
+    /// The JSON body, or the raw text when it isn't JSON
+    fn payload(&self) -> Value {
+        if self.body.is_empty() {
+            return Value::Null;
+        }
+        serde_json::from_slice(&self.body)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&self.body).into_owned()))
+    }
+
+    /// Event name the sender put in a header
+    fn event(&self) -> Option<String> {
+        [
+            "x-github-event",
+            "x-gitlab-event",
+            "x-gitea-event",
+            "x-event-type",
+        ]
+        .iter()
+        .find_map(|name| self.header(name))
+        .map(str::to_string)
+    }
+}
+
+/// What a request asks to run
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Workflow(String),
+    Pipeline(String),
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream, peer: SocketAddr, token: &str) {
+    let request = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => request,
+        Ok(Err(e)) => {
+            let _ = respond(&mut stream, 400, &json!({ "error": e })).await;
+            return;
+        }
+        Err(_) => {
+            let _ = respond(&mut stream, 408, &json!({ "error": "Request timed out" })).await;
+            return;
+        }
+    };
+
+    if request.method == "GET" && request.path == "/health" {
+        let _ = respond(&mut stream, 200, &json!({ "status": "ok" })).await;
+        return;
+    }
+
+    let mut entry = WebhookRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        received_at: chrono::Utc::now().to_rfc3339(),
+        method: request.method.clone(),
+        path: request.path.clone(),
+        remote_addr: peer.to_string(),
+        event: request.event(),
+        status_code: 202,
+        outcome: "accepted".to_string(),
+        detail: None,
+        payload: (!request.body.is_empty())
+            .then(|| String::from_utf8_lossy(&request.body).into_owned()),
+    };
+
+    let accepted = accept(&request, token).await;
+    let (status, body) = match &accepted {
+        Ok(_) => (202, json!({ "request_id": entry.id, "status": "accepted" })),
+        Err((status, error)) => {
+            entry.status_code = *status;
+            entry.outcome = "rejected".to_string();
+            entry.detail = Some(error.clone());
+            (*status, json!({ "error": error }))
+        }
+    };
+    let _ = respond(&mut stream, status, &body).await;
+
+    let log = match WebhookLog::new() {
+        Ok(log) => log,
+        Err(e) => {
+            eprintln!("[Webhooks] {}", e);
+            return;
+        }
+    };
+    if let Err(e) = log.record(&entry).await {
+        eprintln!("[Webhooks] Failed to record request {}: {}", entry.id, e);
+    }
+    let _ = app.emit(WEBHOOK_REQUEST_EVENT, &entry);
+
+    let Ok(target) = accepted else {
+        return;
+    };
+    let outcome = run(&app, &entry.id, target, &request).await;
+    let (outcome, detail) = match outcome {
+        Ok(detail) => ("succeeded", detail),
+        Err(e) => ("failed", Some(e)),
+    };
+    if let Err(e) = log.finish(&entry.id, outcome, detail).await {
+        eprintln!("[Webhooks] Failed to record request {}: {}", entry.id, e);
+    }
+    let _ = app.emit(
+        WEBHOOK_REQUEST_EVENT,
+        json!({ "id": entry.id, "outcome": outcome }),
+    );
+}
+
+/// Check a request and work out what it runs, or the status to refuse it with
+async fn accept(request: &Request, token: &str) -> Result<Target, (u16, String)> {
+    if !request.authorized(token) {
+        return Err((401, "Missing or invalid token".to_string()));
+    }
+    let target = route(&request.path).ok_or((404, format!("No such hook: {}", request.path)))?;
+    if request.method != "POST" {
+        return Err((405, "Use POST".to_string()));
+    }
+    if let Target::Workflow(id) = &target {
+        if crate::domains::automation::commands::find_embedded_workflow(id)
+            .await
+            .is_none()
+        {
+            return Err((404, format!("Workflow not found: {}", id)));
+        }
+    }
+    Ok(target)
+}
+
+fn route(path: &str) -> Option<Target> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments[..] {
+        ["workflows", id] if !id.is_empty() => Some(Target::Workflow(percent_decode(id))),
+        ["pipelines", id] if !id.is_empty() => Some(Target::Pipeline(percent_decode(id))),
+        _ => None,
+    }
+}
+
+/// Run what the request asked for; returns a short description of the result
+async fn run(
+    app: &AppHandle,
+    request_id: &str,
+    target: Target,
+    request: &Request,
+) -> Result<Option<String>, String> {
+    let payload = request.payload();
+    let mut variables = payload_variables("payload", &payload);
+    if let Some(event) = request.event() {
+        variables.insert("webhook_event".to_string(), event);
+    }
+
+    match target {
+        Target::Workflow(id) => {
+            let workflow = crate::domains::automation::commands::find_embedded_workflow(&id)
+                .await
+                .ok_or_else(|| format!("Workflow not found: {}", id))?;
+            let context = WorkflowContext {
+                project_path: request.query.get("project_path").map(PathBuf::from),
+                variables,
+                trigger_data: Some(payload),
+            };
+            let result =
+                crate::domains::automation::commands::run_and_record(app, &workflow, context)
+                    .await?;
+            match result.error {
+                Some(error) if !result.success => Err(error),
+                _ => Ok(Some(format!("Workflow run {}", result.run_id))),
+            }
+        }
+        Target::Pipeline(id) => {
+            let execution = actions::invoke(
+                app,
+                &format!("webhook-{}", request_id),
+                "run_pipeline",
+                json!({ "pipeline_id": id, "variables": variables }),
+            )
+            .await?;
+            Ok(execution
+                .get("id")
+                .and_then(Value::as_str)
+                .map(|id| format!("Pipeline execution {}", id)))
+        }
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        if let Some(end) = find_head_end(&buffer) {
+            break end;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            return Err("Request headers too large".to_string());
+        }
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read request: {}", e))?;
+        if read == 0 {
+            return Err("Connection closed mid-request".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = std::str::from_utf8(&buffer[..head_end])
+        .map_err(|_| "Request headers aren't UTF-8".to_string())?;
+    let mut request = parse_head(head)?;
+    let length: usize = match request.header("content-length") {
+        Some(length) => length
+            .trim()
+            .parse()
+            .map_err(|_| "Invalid Content-Length".to_string())?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(format!("Body over {} bytes", MAX_BODY_BYTES));
+    }
+
+    let mut body = buffer[head_end + 4..].to_vec();
+    while body.len() < length {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read request: {}", e))?;
+        if read == 0 {
+            return Err("Connection closed mid-request".to_string());
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(length);
+    request.body = body;
+    Ok(request)
+}
+
+fn find_head_end(buffer: &[u8]) -> Option<usize> {
+    buffer.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+/// The request line and headers, up to the blank line
+fn parse_head(head: &str) -> Result<Request, String> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(format!("Invalid request line: {}", request_line));
+    };
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Ok(Request {
+        method: method.to_uppercase(),
+        path: path.to_string(),
+        query,
+        headers,
+        body: Vec::new(),
+    })
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Compare tokens without leaking how much of them matched through timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, body: &Value) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        _ => "Error",
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_authorizes_requests() {
+        let mut request = parse_head(
+            "POST /workflows/deploy%20app?project_path=%2Ftmp%2Fapp&token=s3cret HTTP/1.1\r\n\
+             Host: localhost\r\n\
+             X-GitHub-Event: push\r\n\
+             Content-Type: application/json",
+        )
+        .unwrap();
+        request.body = br#"{"ref":"refs/heads/main","forced":false}"#.to_vec();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(
+            route(&request.path),
+            Some(Target::Workflow("deploy app".to_string()))
+        );
+        assert_eq!(request.query["project_path"], "/tmp/app");
+        assert_eq!(request.event().as_deref(), Some("push"));
+        assert!(request.authorized("s3cret"));
+        assert!(!request.authorized("s3cre"));
+
+        let variables = payload_variables("payload", &request.payload());
+        assert_eq!(variables["payload_ref"], "refs/heads/main");
+        assert_eq!(variables["payload_forced"], "false");
+
+        let bearer = parse_head("POST /pipelines/7 HTTP/1.1\r\nAuthorization: Bearer abc").unwrap();
+        assert!(bearer.authorized("abc"));
+        assert_eq!(route(&bearer.path), Some(Target::Pipeline("7".to_string())));
+        assert_eq!(bearer.payload(), Value::Null);
+
+        assert_eq!(route("/workflows/"), None);
+        assert_eq!(route("/other/1"), None);
+        assert!(parse_head("garbage").is_err());
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+}
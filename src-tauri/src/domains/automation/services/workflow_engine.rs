@@ -1,38 +1,183 @@
+use crate::domains::automation::services::actions;
+use crate::domains::automation::services::event_bus::AppEvent;
+use crate::domains::notifications::services::{
+    NewNotification, NotificationLevel, NotificationService,
+};
 use crate::process_ext::NoWindowExt;
+use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
 use tokio::process::Command;
 
+/// Loops stop here, whatever they were asked to do
+const MAX_LOOP_ITERATIONS: usize = 1000;
+
+const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 30;
+
 /// Workflow step types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum WorkflowStep {
-    /// Execute a shell command
+    /// Execute a program with arguments
     Command {
         command: String,
         args: Vec<String>,
         working_dir: Option<String>,
         env: Option<HashMap<String, String>>,
+        /// Variable that receives the trimmed stdout
+        output_var: Option<String>,
+    },
+    /// Run a script through the platform shell (bash, or cmd on Windows)
+    Shell {
+        script: String,
+        working_dir: Option<String>,
+        env: Option<HashMap<String, String>>,
+        output_var: Option<String>,
+    },
+    /// Send an HTTP request; non-2xx responses fail the step
+    Http {
+        #[serde(default = "default_http_method")]
+        method: String,
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        body: Option<String>,
+        timeout_secs: Option<u64>,
+        /// Variable that receives the response body
+        output_var: Option<String>,
     },
     /// Create a file with content
     CreateFile { path: String, content: String },
     /// Conditional step execution
     Condition {
         condition: String, // Expression to evaluate
-        then: Vec<WorkflowStep>,
-        else_: Option<Vec<WorkflowStep>>,
+        then: Vec<WorkflowNode>,
+        else_: Option<Vec<WorkflowNode>>,
     },
     /// Pattern matching trigger
     PatternMatch {
         pattern: String,
-        on_match: Vec<WorkflowStep>,
-        on_no_match: Option<Vec<WorkflowStep>>,
+        on_match: Vec<WorkflowNode>,
+        on_no_match: Option<Vec<WorkflowNode>>,
+    },
+    /// Run `steps` once per item (or `times` times), with the item in
+    /// `item_var` and its position in `index`
+    Loop {
+        #[serde(default)]
+        items: Vec<String>,
+        /// Variable holding a JSON array or one item per line
+        items_var: Option<String>,
+        times: Option<usize>,
+        #[serde(default = "default_item_var")]
+        item_var: String,
+        steps: Vec<WorkflowNode>,
+    },
+    /// Call an app action from the domain tool registry, e.g. `list_pods`
+    Invoke {
+        action: String,
+        #[serde(default)]
+        args: serde_json::Value,
+        output_var: Option<String>,
+    },
+    /// Raise a desktop notification; `link` is an app route opened on click
+    Notify {
+        title: String,
+        body: Option<String>,
+        #[serde(default)]
+        level: NotificationLevel,
+        link: Option<String>,
     },
     /// Wait/delay step
+    #[serde(alias = "Delay")]
     Wait { seconds: u64 },
 }
 
+fn default_http_method() -> String {
+    "GET".to_string()
+}
+
+fn default_item_var() -> String {
+    "item".to_string()
+}
+
+impl WorkflowStep {
+    fn kind(&self) -> &'static str {
+        match self {
+            WorkflowStep::Command { .. } => "command",
+            WorkflowStep::Shell { .. } => "shell",
+            WorkflowStep::Http { .. } => "http",
+            WorkflowStep::CreateFile { .. } => "create_file",
+            WorkflowStep::Condition { .. } => "condition",
+            WorkflowStep::PatternMatch { .. } => "pattern_match",
+            WorkflowStep::Loop { .. } => "loop",
+            WorkflowStep::Invoke { .. } => "invoke",
+            WorkflowStep::Notify { .. } => "notify",
+            WorkflowStep::Wait { .. } => "wait",
+        }
+    }
+
+    /// Steps that run other nodes
+    fn has_children(&self) -> bool {
+        matches!(
+            self,
+            WorkflowStep::Condition { .. }
+                | WorkflowStep::PatternMatch { .. }
+                | WorkflowStep::Loop { .. }
+        )
+    }
+}
+
+/// A step plus how the engine treats it when it fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowNode {
+    /// Shown in the trace next to the step's position
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    /// Record the failure and carry on instead of stopping the run
+    #[serde(default)]
+    pub continue_on_error: bool,
+    #[serde(flatten)]
+    pub step: WorkflowStep,
+}
+
+impl From<WorkflowStep> for WorkflowNode {
+    fn from(step: WorkflowStep) -> Self {
+        Self {
+            id: None,
+            retry: None,
+            continue_on_error: false,
+            step,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first
+    pub max_attempts: u32,
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Double the delay after every failed attempt
+    #[serde(default)]
+    pub exponential: bool,
+}
+
+impl RetryPolicy {
+    fn delay_before(&self, attempt: u32) -> std::time::Duration {
+        let factor = if self.exponential {
+            2u64.saturating_pow(attempt.saturating_sub(2))
+        } else {
+            1
+        };
+        std::time::Duration::from_millis(self.delay_ms.saturating_mul(factor))
+    }
+}
+
 /// Complete workflow definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workflow {
@@ -40,7 +185,7 @@ pub struct Workflow {
     pub name: String,
     pub description: Option<String>,
     pub triggers: Vec<WorkflowTrigger>,
-    pub steps: Vec<WorkflowStep>,
+    pub steps: Vec<WorkflowNode>,
     pub enabled: bool,
 }
 
@@ -56,8 +201,13 @@ pub enum WorkflowTrigger {
     ProjectType { project_type: String },
     /// Manual trigger
     Manual,
-    /// Trigger on event
-    Event { event_type: String },
+    /// Trigger on an app event from the event bus, e.g. "pipeline.failed"
+    /// or "pipeline.*", whose payload has every `filter` field
+    Event {
+        event_type: String,
+        #[serde(default)]
+        filter: HashMap<String, String>,
+    },
 }
 
 /// Workflow execution context
@@ -72,12 +222,16 @@ pub struct WorkflowContext {
 /// Workflow execution result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowExecutionResult {
+    pub run_id: String,
     pub workflow_id: String,
     pub success: bool,
     pub steps_executed: usize,
     pub steps_failed: usize,
+    /// Execution trace, one entry per node run, in the order they started
     pub output: Vec<StepOutput>,
     pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: String,
 }
 
 /// Individual step output
@@ -85,9 +239,15 @@ pub struct WorkflowExecutionResult {
 pub struct StepOutput {
     pub step_index: usize,
     pub step_type: String,
+    /// Position in the workflow: "2" for the third top-level step, "2.then.0"
+    /// inside a branch, "3[1].0" in a loop's second iteration
+    pub path: String,
+    pub node_id: Option<String>,
     pub success: bool,
     pub output: Option<String>,
     pub error: Option<String>,
+    pub attempts: u32,
+    pub started_at: String,
     pub duration_ms: u64,
 }
 
@@ -114,403 +274,598 @@ impl WorkflowEngine {
         self.workflows.insert(workflow.id.clone(), workflow);
     }
 
-    /// Load workflows from YAML/JSON
-    pub fn load_from_json(&mut self, json: &str) -> Result<(), String> {
-        let workflows: Vec<Workflow> =
-            serde_json::from_str(json).map_err(|e| format!("Failed to parse workflows: {}", e))?;
-
-        for workflow in workflows {
-            self.register_workflow(workflow);
-        }
-
-        Ok(())
+    pub fn get_workflow(&self, workflow_id: &str) -> Option<&Workflow> {
+        self.workflows.get(workflow_id)
     }
 
-    /// Execute a workflow
-    pub async fn execute_workflow(
-        &self,
-        workflow_id: &str,
-        context: WorkflowContext,
+    /// Run a workflow to completion. A failing step stops the run unless it
+    /// continues on error; the result still holds the trace up to there.
+    pub async fn run(
+        workflow: &Workflow,
+        mut context: WorkflowContext,
+        app: Option<AppHandle>,
     ) -> Result<WorkflowExecutionResult, String> {
-        let workflow = self
-            .workflows
-            .get(workflow_id)
-            .ok_or_else(|| format!("Workflow not found: {}", workflow_id))?;
-
         if !workflow.enabled {
             return Err("Workflow is disabled".to_string());
         }
 
-        let mut result = WorkflowExecutionResult {
-            workflow_id: workflow_id.to_string(),
-            success: true,
-            steps_executed: 0,
-            steps_failed: 0,
-            output: Vec::new(),
-            error: None,
+        let started_at = chrono::Utc::now().to_rfc3339();
+        let mut runner = Runner {
+            app,
+            workflow_id: workflow.id.clone(),
+            trace: Vec::new(),
         };
+        let outcome = runner
+            .run_nodes(&workflow.steps, String::new(), &mut context)
+            .await;
+
+        let steps_failed = runner.trace.iter().filter(|step| !step.success).count();
+        Ok(WorkflowExecutionResult {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            workflow_id: workflow.id.clone(),
+            success: outcome.is_ok(),
+            steps_executed: runner.trace.len(),
+            steps_failed,
+            output: runner.trace,
+            error: outcome.err(),
+            started_at,
+            finished_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
 
-        for (index, step) in workflow.steps.iter().enumerate() {
-            let step_result = self.execute_step(step, &context, index).await;
+    /// Get all registered workflows
+    pub fn get_workflows(&self) -> Vec<&Workflow> {
+        self.workflows.values().collect()
+    }
 
-            match step_result {
-                Ok(output) => {
-                    result.steps_executed += 1;
-                    result.output.push(output.clone());
+    /// Enabled workflows with a trigger that fires on this event
+    pub fn workflows_for_event(&self, event: &AppEvent) -> Vec<Workflow> {
+        self.workflows
+            .values()
+            .filter(|workflow| {
+                workflow.enabled
+                    && workflow
+                        .triggers
+                        .iter()
+                        .any(|trigger| self.matches_trigger(trigger, event))
+            })
+            .cloned()
+            .collect()
+    }
 
-                    if !output.success {
-                        result.steps_failed += 1;
-                        // Optionally stop on first failure
-                        // result.success = false;
-                        // break;
-                    }
-                }
-                Err(e) => {
-                    result.steps_failed += 1;
-                    result.success = false;
-                    result.output.push(StepOutput {
-                        step_index: index,
-                        step_type: format!("{:?}", step),
-                        success: false,
-                        output: None,
-                        error: Some(e.clone()),
-                        duration_ms: 0,
-                    });
-                    result.error = Some(format!("Step {} failed: {}", index, e));
-                    break;
-                }
+    /// Check if a trigger matches an event
+    fn matches_trigger(&self, trigger: &WorkflowTrigger, event: &AppEvent) -> bool {
+        let data = &event.payload;
+        match trigger {
+            WorkflowTrigger::CommandPattern { pattern } => data
+                .get("command")
+                .and_then(|v| v.as_str())
+                .map(|cmd| cmd.contains(pattern))
+                .unwrap_or(false),
+            WorkflowTrigger::FilePattern { pattern } => data
+                .get("file")
+                .and_then(|v| v.as_str())
+                .map(|file| file.contains(pattern))
+                .unwrap_or(false),
+            WorkflowTrigger::ProjectType { project_type } => data
+                .get("project_type")
+                .and_then(|v| v.as_str())
+                .map(|pt| pt == project_type)
+                .unwrap_or(false),
+            WorkflowTrigger::Event { event_type, filter } => {
+                event.matches(event_type) && event.matches_filter(filter)
             }
+            // Only runs when asked to
+            WorkflowTrigger::Manual => false,
         }
-
-        Ok(result)
     }
+}
 
-    /// Execute a single workflow step
-    async fn execute_step(
-        &self,
-        step: &WorkflowStep,
-        context: &WorkflowContext,
-        index: usize,
-    ) -> Result<StepOutput, String> {
-        let start = std::time::Instant::now();
+/// One run in progress: the app for invoke and notify steps and the trace
+/// so far
+struct Runner {
+    app: Option<AppHandle>,
+    workflow_id: String,
+    trace: Vec<StepOutput>,
+}
 
-        match step {
-            WorkflowStep::Command {
-                command,
-                args,
-                working_dir,
-                env,
-            } => {
-                self.execute_command(command, args, working_dir, env, context)
-                    .await
-            }
-            WorkflowStep::CreateFile { path, content } => {
-                self.create_file(path, content, context).await
-            }
-            WorkflowStep::Condition {
-                condition,
-                then,
-                else_,
-            } => Box::pin(self.execute_condition(condition, then, else_, context, index)).await,
-            WorkflowStep::PatternMatch {
-                pattern,
-                on_match,
-                on_no_match,
-            } => {
-                Box::pin(self.execute_pattern_match(pattern, on_match, on_no_match, context, index))
-                    .await
-            }
-            WorkflowStep::Wait { seconds } => {
-                tokio::time::sleep(tokio::time::Duration::from_secs(*seconds)).await;
-                Ok(StepOutput {
-                    step_index: index,
-                    step_type: "wait".to_string(),
-                    success: true,
-                    output: None,
-                    error: None,
-                    duration_ms: start.elapsed().as_millis() as u64,
-                })
+impl Runner {
+    fn run_nodes<'a>(
+        &'a mut self,
+        nodes: &'a [WorkflowNode],
+        prefix: String,
+        context: &'a mut WorkflowContext,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            for (index, node) in nodes.iter().enumerate() {
+                let path = if prefix.is_empty() {
+                    index.to_string()
+                } else {
+                    format!("{}.{}", prefix, index)
+                };
+                self.run_node(node, path, context).await?;
             }
-        }
+            Ok(())
+        })
     }
 
-    /// Execute a command step
-    async fn execute_command(
-        &self,
-        command: &str,
-        args: &[String],
-        working_dir: &Option<String>,
-        env: &Option<HashMap<String, String>>,
-        context: &WorkflowContext,
-    ) -> Result<StepOutput, String> {
+    /// Run a node with its retries. Its trace entry goes in before any of
+    /// its children's, so the trace reads top to bottom.
+    async fn run_node(
+        &mut self,
+        node: &WorkflowNode,
+        path: String,
+        context: &mut WorkflowContext,
+    ) -> Result<(), String> {
+        let slot = self.trace.len();
+        self.trace.push(StepOutput {
+            step_index: slot,
+            step_type: node.step.kind().to_string(),
+            path: path.clone(),
+            node_id: node.id.clone(),
+            success: false,
+            output: None,
+            error: None,
+            attempts: 0,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            duration_ms: 0,
+        });
         let start = std::time::Instant::now();
+        let max_attempts = node
+            .retry
+            .as_ref()
+            .map_or(1, |retry| retry.max_attempts.max(1));
+
+        let mut attempts = 0;
+        let result = loop {
+            attempts += 1;
+            // A retried branch only keeps its children from the last attempt
+            self.trace.truncate(slot + 1);
+            match self.run_step(&node.step, &path, context).await {
+                Err(e) if attempts < max_attempts => {
+                    println!(
+                        "[Workflow] Step {} failed (attempt {}/{}): {}",
+                        path, attempts, max_attempts, e
+                    );
+                    if let Some(retry) = &node.retry {
+                        tokio::time::sleep(retry.delay_before(attempts + 1)).await;
+                    }
+                }
+                result => break result,
+            }
+        };
 
-        // Substitute variables in command and args
-        let cmd = self.substitute_variables(command, context);
-        let cmd_args: Vec<String> = args
-            .iter()
-            .map(|a| self.substitute_variables(a, context))
-            .collect();
-
-        let mut cmd_builder = Command::new(&cmd);
-        cmd_builder.no_window();
-        cmd_builder.args(&cmd_args);
-
-        // Set working directory
-        if let Some(wd) = working_dir {
-            let wd_path = self.substitute_variables(wd, context);
-            if let Some(project_path) = &context.project_path {
-                cmd_builder.current_dir(project_path.join(wd_path));
-            } else {
-                cmd_builder.current_dir(wd_path);
+        let entry = &mut self.trace[slot];
+        entry.attempts = attempts;
+        entry.duration_ms = start.elapsed().as_millis() as u64;
+        match result {
+            Ok(output) => {
+                entry.success = true;
+                entry.output = output;
+                Ok(())
+            }
+            Err(e) => {
+                entry.error = Some(e.clone());
+                if node.continue_on_error {
+                    Ok(())
+                } else if node.step.has_children() {
+                    // Already names the step that failed
+                    Err(e)
+                } else {
+                    Err(format!("Step {} failed: {}", path, e))
+                }
             }
-        } else if let Some(project_path) = &context.project_path {
-            cmd_builder.current_dir(project_path);
         }
+    }
 
-        // Set environment variables
-        if let Some(env_vars) = env {
-            for (key, value) in env_vars {
-                cmd_builder.env(key, self.substitute_variables(value, context));
+    fn run_step<'a>(
+        &'a mut self,
+        step: &'a WorkflowStep,
+        path: &'a str,
+        context: &'a mut WorkflowContext,
+    ) -> BoxFuture<'a, Result<Option<String>, String>> {
+        Box::pin(async move {
+            match step {
+                WorkflowStep::Command {
+                    command,
+                    args,
+                    working_dir,
+                    env,
+                    output_var,
+                } => {
+                    let mut cmd = Command::new(substitute_variables(command, context));
+                    cmd.args(args.iter().map(|a| substitute_variables(a, context)));
+                    let output = run_process(cmd, working_dir, env, context).await?;
+                    capture(context, output_var, &output);
+                    Ok(Some(output))
+                }
+                WorkflowStep::Shell {
+                    script,
+                    working_dir,
+                    env,
+                    output_var,
+                } => {
+                    let script = substitute_variables(script, context);
+                    let cmd = if cfg!(target_os = "windows") {
+                        let mut cmd = Command::new("cmd");
+                        cmd.args(["/C", &script]);
+                        cmd
+                    } else {
+                        let mut cmd = Command::new("bash");
+                        cmd.args(["-c", &script]);
+                        cmd
+                    };
+                    let output = run_process(cmd, working_dir, env, context).await?;
+                    capture(context, output_var, &output);
+                    Ok(Some(output))
+                }
+                WorkflowStep::Http {
+                    method,
+                    url,
+                    headers,
+                    body,
+                    timeout_secs,
+                    output_var,
+                } => {
+                    let response =
+                        http_request(method, url, headers, body, *timeout_secs, context).await?;
+                    capture(context, output_var, &response);
+                    Ok(Some(response))
+                }
+                WorkflowStep::CreateFile { path, content } => {
+                    create_file(path, content, context).await.map(Some)
+                }
+                WorkflowStep::Condition {
+                    condition,
+                    then,
+                    else_,
+                } => {
+                    let matched = evaluate_condition(condition, context);
+                    let (branch, nodes) = if matched {
+                        ("then", then.as_slice())
+                    } else {
+                        ("else", else_.as_deref().unwrap_or(&[]))
+                    };
+                    self.run_nodes(nodes, format!("{}.{}", path, branch), context)
+                        .await?;
+                    Ok(Some(format!("Condition evaluated: {}", matched)))
+                }
+                WorkflowStep::PatternMatch {
+                    pattern,
+                    on_match,
+                    on_no_match,
+                } => {
+                    // Simple pattern matching (can be enhanced with regex)
+                    let matches = context
+                        .variables
+                        .iter()
+                        .any(|(k, v)| k.contains(pattern) || v.contains(pattern));
+                    let (branch, nodes) = if matches {
+                        ("match", on_match.as_slice())
+                    } else {
+                        ("no_match", on_no_match.as_deref().unwrap_or(&[]))
+                    };
+                    self.run_nodes(nodes, format!("{}.{}", path, branch), context)
+                        .await?;
+                    Ok(Some(format!("Pattern match: {}", matches)))
+                }
+                WorkflowStep::Loop {
+                    items,
+                    items_var,
+                    times,
+                    item_var,
+                    steps,
+                } => {
+                    let items = loop_items(items, items_var.as_deref(), *times, context)?;
+                    for (index, item) in items.iter().enumerate() {
+                        context.variables.insert(item_var.clone(), item.clone());
+                        context
+                            .variables
+                            .insert("index".to_string(), index.to_string());
+                        self.run_nodes(steps, format!("{}[{}]", path, index), context)
+                            .await?;
+                    }
+                    Ok(Some(format!("{} iterations", items.len())))
+                }
+                WorkflowStep::Invoke {
+                    action,
+                    args,
+                    output_var,
+                } => {
+                    let app = self.app.clone().ok_or_else(|| {
+                        format!(
+                            "{} can only be invoked from a workflow run in the app",
+                            action
+                        )
+                    })?;
+                    let output =
+                        match actions::invoke(&app, path, action, substitute_json(args, context))
+                            .await?
+                        {
+                            serde_json::Value::String(text) => text,
+                            value => value.to_string(),
+                        };
+                    capture(context, output_var, &output);
+                    Ok(Some(output))
+                }
+                WorkflowStep::Notify {
+                    title,
+                    body,
+                    level,
+                    link,
+                } => {
+                    let app = self
+                        .app
+                        .clone()
+                        .ok_or("Notifications can only be raised from a workflow run in the app")?;
+                    let service = app
+                        .try_state::<Arc<NotificationService>>()
+                        .ok_or("Notifications aren't available yet")?
+                        .inner()
+                        .clone();
+                    let title = substitute_variables(title, context);
+                    service
+                        .notify(
+                            &app,
+                            NewNotification {
+                                title: title.clone(),
+                                body: body
+                                    .as_ref()
+                                    .map(|body| substitute_variables(body, context)),
+                                level: *level,
+                                source: Some(format!("workflow:{}", self.workflow_id)),
+                                link: link
+                                    .as_ref()
+                                    .map(|link| substitute_variables(link, context)),
+                            },
+                        )
+                        .await?;
+                    Ok(Some(format!("Notified: {}", title)))
+                }
+                WorkflowStep::Wait { seconds } => {
+                    tokio::time::sleep(tokio::time::Duration::from_secs(*seconds)).await;
+                    Ok(None)
+                }
             }
+        })
+    }
+}
+
+/// Run a command in the project (or `working_dir` within it) with the
+/// workflow variables as `WF_*`. A non-zero exit fails with its stderr.
+async fn run_process(
+    mut cmd: Command,
+    working_dir: &Option<String>,
+    env: &Option<HashMap<String, String>>,
+    context: &WorkflowContext,
+) -> Result<String, String> {
+    cmd.no_window();
+
+    // Set working directory
+    if let Some(wd) = working_dir {
+        let wd_path = substitute_variables(wd, context);
+        if let Some(project_path) = &context.project_path {
+            cmd.current_dir(project_path.join(wd_path));
+        } else {
+            cmd.current_dir(wd_path);
         }
+    } else if let Some(project_path) = &context.project_path {
+        cmd.current_dir(project_path);
+    }
 
-        // Add context variables to environment
-        for (key, value) in &context.variables {
-            cmd_builder.env(format!("WF_{}", key), value);
+    // Set environment variables
+    if let Some(env_vars) = env {
+        for (key, value) in env_vars {
+            cmd.env(key, substitute_variables(value, context));
         }
+    }
 
-        let output = cmd_builder
-            .output()
-            .await
-            .map_err(|e| format!("Command execution failed: {}", e))?;
-
-        let duration_ms = start.elapsed().as_millis() as u64;
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-        let success = output.status.success();
-        let error_output = if !success { Some(stderr.clone()) } else { None };
-        let output_text = if !stdout.is_empty() { stdout } else { stderr };
-
-        Ok(StepOutput {
-            step_index: 0, // Will be set by caller
-            step_type: "command".to_string(),
-            success,
-            output: Some(output_text),
-            error: error_output,
-            duration_ms,
-        })
+    // Add context variables to environment
+    for (key, value) in &context.variables {
+        cmd.env(format!("WF_{}", key), value);
     }
 
-    /// Create a file step
-    async fn create_file(
-        &self,
-        path: &str,
-        content: &str,
-        context: &WorkflowContext,
-    ) -> Result<StepOutput, String> {
-        let start = std::time::Instant::now();
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Command execution failed: {}", e))?;
 
-        let file_path = self.substitute_variables(path, context);
-        let file_content = self.substitute_variables(content, context);
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-        let final_path = if let Some(project_path) = &context.project_path {
-            project_path.join(&file_path)
+    if !output.status.success() {
+        return Err(if stderr.trim().is_empty() {
+            format!("Command exited with {}", output.status)
         } else {
-            PathBuf::from(&file_path)
-        };
-
-        // Create parent directories if needed
-        if let Some(parent) = final_path.parent() {
-            tokio::fs::create_dir_all(parent)
-                .await
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
-        }
-
-        tokio::fs::write(&final_path, file_content)
-            .await
-            .map_err(|e| format!("Failed to write file: {}", e))?;
-
-        Ok(StepOutput {
-            step_index: 0,
-            step_type: "create_file".to_string(),
-            success: true,
-            output: Some(format!("File created: {}", final_path.display())),
-            error: None,
-            duration_ms: start.elapsed().as_millis() as u64,
-        })
+            stderr
+        });
     }
+    Ok(if !stdout.is_empty() { stdout } else { stderr })
+}
 
-    /// Execute conditional step
-    fn execute_condition(
-        &self,
-        condition: &str,
-        then: &[WorkflowStep],
-        else_: &Option<Vec<WorkflowStep>>,
-        context: &WorkflowContext,
-        index: usize,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<StepOutput, String>> + Send + '_>>
-    {
-        let condition = condition.to_string();
-        let then = then.to_vec();
-        let else_ = else_.clone();
-        let context = context.clone();
-        let self_ref = self;
-        Box::pin(async move {
-            // Simple condition evaluation (can be enhanced)
-            let condition_result = self_ref.evaluate_condition(&condition, &context);
-
-            let steps_to_execute = if condition_result {
-                &then
-            } else {
-                else_.as_deref().unwrap_or(&[])
-            };
-
-            // Execute steps in the branch
-            for (i, step) in steps_to_execute.iter().enumerate() {
-                self_ref.execute_step(step, &context, index + i + 1).await?;
-            }
-
-            Ok(StepOutput {
-                step_index: index,
-                step_type: "condition".to_string(),
-                success: true,
-                output: Some(format!("Condition evaluated: {}", condition_result)),
-                error: None,
-                duration_ms: 0,
-            })
-        })
+async fn http_request(
+    method: &str,
+    url: &str,
+    headers: &HashMap<String, String>,
+    body: &Option<String>,
+    timeout_secs: Option<u64>,
+    context: &WorkflowContext,
+) -> Result<String, String> {
+    let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", method))?;
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(
+            timeout_secs.unwrap_or(DEFAULT_HTTP_TIMEOUT_SECS),
+        ))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut request = client.request(method, substitute_variables(url, context));
+    for (name, value) in headers {
+        request = request.header(name.as_str(), substitute_variables(value, context));
+    }
+    if let Some(body) = body {
+        request = request.body(substitute_variables(body, context));
     }
 
-    /// Execute pattern match step
-    fn execute_pattern_match(
-        &self,
-        pattern: &str,
-        on_match: &[WorkflowStep],
-        on_no_match: &Option<Vec<WorkflowStep>>,
-        context: &WorkflowContext,
-        index: usize,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<StepOutput, String>> + Send + '_>>
-    {
-        let pattern = pattern.to_string();
-        let on_match = on_match.to_vec();
-        let on_no_match = on_no_match.clone();
-        let context = context.clone();
-        let self_ref = self;
-        Box::pin(async move {
-            // Simple pattern matching (can be enhanced with regex)
-            let matches = context
-                .variables
-                .iter()
-                .any(|(k, v)| k.contains(&pattern) || v.contains(&pattern));
-
-            let steps_to_execute = if matches {
-                &on_match
-            } else {
-                on_no_match.as_deref().unwrap_or(&[])
-            };
-
-            for (i, step) in steps_to_execute.iter().enumerate() {
-                self_ref.execute_step(step, &context, index + i + 1).await?;
-            }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    if status.is_success() {
+        Ok(text)
+    } else {
+        Err(format!("HTTP {}: {}", status, text))
+    }
+}
 
-            Ok(StepOutput {
-                step_index: index,
-                step_type: "pattern_match".to_string(),
-                success: true,
-                output: Some(format!("Pattern match: {}", matches)),
-                error: None,
-                duration_ms: 0,
-            })
-        })
+/// Create a file step
+async fn create_file(
+    path: &str,
+    content: &str,
+    context: &WorkflowContext,
+) -> Result<String, String> {
+    let file_path = substitute_variables(path, context);
+    let file_content = substitute_variables(content, context);
+
+    let final_path = if let Some(project_path) = &context.project_path {
+        project_path.join(&file_path)
+    } else {
+        PathBuf::from(&file_path)
+    };
+
+    // Create parent directories if needed
+    if let Some(parent) = final_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
     }
 
-    /// Substitute variables in a string
-    fn substitute_variables(&self, text: &str, context: &WorkflowContext) -> String {
-        let mut result = text.to_string();
+    tokio::fs::write(&final_path, file_content)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
 
-        for (key, value) in &context.variables {
-            result = result.replace(&format!("${{{}}}", key), value);
-            result = result.replace(&format!("${}", key), value);
-        }
+    Ok(format!("File created: {}", final_path.display()))
+}
 
-        // Common variables
-        if let Some(project_path) = &context.project_path {
-            result = result.replace("${PROJECT_PATH}", &project_path.to_string_lossy());
+/// A loop's items: the listed ones, then those in `items_var`. `times` alone
+/// counts from 0; alongside items it caps them.
+fn loop_items(
+    items: &[String],
+    items_var: Option<&str>,
+    times: Option<usize>,
+    context: &WorkflowContext,
+) -> Result<Vec<String>, String> {
+    let mut list: Vec<String> = items
+        .iter()
+        .map(|item| substitute_variables(item, context))
+        .collect();
+    if let Some(var) = items_var {
+        let value = context
+            .variables
+            .get(var)
+            .ok_or_else(|| format!("Loop variable {} is not set", var))?;
+        match serde_json::from_str::<Vec<serde_json::Value>>(value) {
+            Ok(values) => list.extend(values.into_iter().map(|value| match value {
+                serde_json::Value::String(text) => text,
+                other => other.to_string(),
+            })),
+            Err(_) => list.extend(
+                value
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(String::from),
+            ),
+        }
+    }
+    match times {
+        Some(times) if items.is_empty() && items_var.is_none() => {
+            list = (0..times).map(|i| i.to_string()).collect();
         }
+        Some(times) => list.truncate(times),
+        None => {}
+    }
 
-        result
+    if list.len() > MAX_LOOP_ITERATIONS {
+        return Err(format!(
+            "Loop has {} iterations; the limit is {}",
+            list.len(),
+            MAX_LOOP_ITERATIONS
+        ));
     }
+    Ok(list)
+}
 
-    /// Evaluate a condition (simple boolean logic)
-    fn evaluate_condition(&self, condition: &str, context: &WorkflowContext) -> bool {
-        // Simple evaluation - check if variable exists or equals value
-        // Format: "${VAR}" or "${VAR} == value"
-        let parts: Vec<&str> = condition.split("==").map(|s| s.trim()).collect();
+/// Store a step's output in its output variable, if it has one
+fn capture(context: &mut WorkflowContext, output_var: &Option<String>, output: &str) {
+    if let Some(var) = output_var {
+        context
+            .variables
+            .insert(var.clone(), output.trim().to_string());
+    }
+}
 
-        if parts.len() == 2 {
-            let var_name = parts[0].trim_matches(|c| c == '$' || c == '{' || c == '}');
-            let expected = parts[1].trim_matches('"').trim_matches('\'');
-            return context
-                .variables
-                .get(var_name)
-                .map(|v| v == expected)
-                .unwrap_or(false);
-        }
+/// Substitute variables in a string
+fn substitute_variables(text: &str, context: &WorkflowContext) -> String {
+    let mut result = text.to_string();
 
-        // Check if variable exists
-        let var_name = condition.trim_matches(|c| c == '$' || c == '{' || c == '}');
-        context.variables.contains_key(var_name)
+    for (key, value) in &context.variables {
+        result = result.replace(&format!("${{{}}}", key), value);
+        result = result.replace(&format!("${}", key), value);
     }
 
-    /// Get all registered workflows
-    pub fn get_workflows(&self) -> Vec<&Workflow> {
-        self.workflows.values().collect()
+    // Common variables
+    if let Some(project_path) = &context.project_path {
+        result = result.replace("${PROJECT_PATH}", &project_path.to_string_lossy());
     }
 
-    /// Check if a workflow should trigger based on context
-    pub fn should_trigger(&self, workflow_id: &str, trigger_data: &serde_json::Value) -> bool {
-        let workflow = match self.workflows.get(workflow_id) {
-            Some(w) => w,
-            None => return false,
-        };
+    result
+}
 
-        for trigger in &workflow.triggers {
-            if self.matches_trigger(trigger, trigger_data) {
-                return true;
-            }
+/// Substitute variables in every string of an invoke step's arguments
+fn substitute_json(value: &serde_json::Value, context: &WorkflowContext) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(text) => {
+            serde_json::Value::String(substitute_variables(text, context))
         }
-
-        false
+        serde_json::Value::Array(values) => values
+            .iter()
+            .map(|value| substitute_json(value, context))
+            .collect(),
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), substitute_json(value, context)))
+                .collect(),
+        ),
+        other => other.clone(),
     }
+}
 
-    /// Check if a trigger matches the context
-    fn matches_trigger(&self, trigger: &WorkflowTrigger, data: &serde_json::Value) -> bool {
-        match trigger {
-            WorkflowTrigger::CommandPattern { pattern } => data
-                .get("command")
-                .and_then(|v| v.as_str())
-                .map(|cmd| cmd.contains(pattern))
-                .unwrap_or(false),
-            WorkflowTrigger::FilePattern { pattern } => data
-                .get("file")
-                .and_then(|v| v.as_str())
-                .map(|file| file.contains(pattern))
-                .unwrap_or(false),
-            WorkflowTrigger::ProjectType { project_type } => data
-                .get("project_type")
-                .and_then(|v| v.as_str())
-                .map(|pt| pt == project_type)
-                .unwrap_or(false),
-            WorkflowTrigger::Event { event_type } => data
-                .get("event_type")
-                .and_then(|v| v.as_str())
-                .map(|et| et == event_type)
-                .unwrap_or(false),
-            WorkflowTrigger::Manual => true,
+/// Evaluate a condition (simple boolean logic)
+fn evaluate_condition(condition: &str, context: &WorkflowContext) -> bool {
+    // Format: "${VAR}", "${VAR} == value" or "${VAR} != value"
+    for (operator, equal) in [("!=", false), ("==", true)] {
+        if let Some((left, right)) = condition.split_once(operator) {
+            let var_name = left
+                .trim()
+                .trim_matches(|c| c == '$' || c == '{' || c == '}');
+            let expected = right.trim().trim_matches('"').trim_matches('\'');
+            let value = context.variables.get(var_name).map(String::as_str);
+            return (value == Some(expected)) == equal;
         }
     }
+
+    // Check if variable exists
+    let var_name = condition
+        .trim()
+        .trim_matches(|c| c == '$' || c == '{' || c == '}');
+    context.variables.contains_key(var_name)
 }
 
 #[cfg(test)]
@@ -526,7 +881,7 @@ mod tests {
             name: "Test Workflow".to_string(),
             description: None,
             triggers: vec![WorkflowTrigger::Manual],
-            steps: vec![WorkflowStep::Wait { seconds: 0 }],
+            steps: vec![WorkflowStep::Wait { seconds: 0 }.into()],
             enabled: true,
         };
 
@@ -538,7 +893,70 @@ mod tests {
             trigger_data: None,
         };
 
-        let result = engine.execute_workflow("test", context).await.unwrap();
+        let workflow = engine.get_workflow("test").unwrap();
+        let result = WorkflowEngine::run(workflow, context, None).await.unwrap();
         assert!(result.success);
     }
+
+    #[tokio::test]
+    async fn traces_branches_loops_and_retries() {
+        let workflow: Workflow = serde_json::from_value(serde_json::json!({
+            "id": "trace",
+            "name": "Trace",
+            "triggers": [{ "type": "Manual" }],
+            "enabled": true,
+            "steps": [
+                {
+                    "type": "Loop",
+                    "items": ["a", "b"],
+                    "steps": [{
+                        "type": "Condition",
+                        "condition": "${item} == b",
+                        "then": [{ "type": "Delay", "seconds": 0 }]
+                    }]
+                },
+                {
+                    "type": "Invoke",
+                    "id": "pods",
+                    "action": "list_pods",
+                    "retry": { "max_attempts": 2 },
+                    "continue_on_error": true
+                },
+                { "type": "Wait", "seconds": 0 }
+            ]
+        }))
+        .unwrap();
+        let context = WorkflowContext {
+            project_path: None,
+            variables: HashMap::new(),
+            trigger_data: None,
+        };
+
+        let result = WorkflowEngine::run(&workflow, context.clone(), None)
+            .await
+            .unwrap();
+        assert!(result.success);
+        let paths: Vec<&str> = result
+            .output
+            .iter()
+            .map(|step| step.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["0", "0[0].0", "0[1].0", "0[1].0.then.0", "1", "2"]
+        );
+        let invoke = &result.output[4];
+        assert_eq!(invoke.node_id.as_deref(), Some("pods"));
+        assert_eq!(invoke.attempts, 2);
+        assert!(!invoke.success);
+        assert_eq!(result.steps_failed, 1);
+
+        // Without continue_on_error the failure ends the run
+        let mut strict = workflow.clone();
+        strict.steps[1].continue_on_error = false;
+        let result = WorkflowEngine::run(&strict, context, None).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("Step 1 failed"));
+        assert_eq!(result.output.last().unwrap().path, "1");
+    }
 }
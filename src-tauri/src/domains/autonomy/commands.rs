@@ -1,6 +1,10 @@
 use crate::database::DatabaseManager;
-use crate::domains::autonomy::services::autonomy_service::AutonomousActionRequest;
+use crate::domains::autonomy::services::approval_queue::{self, QueuedApproval};
+use crate::domains::autonomy::services::autonomy_service::{
+    AutonomousActionRequest, AutonomousActionResult,
+};
 use crate::domains::autonomy::services::AutonomyService;
+use sea_orm::DatabaseConnection;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -22,6 +26,52 @@ fn get_services_map() -> Arc<Mutex<HashMap<String, AutonomyService>>> {
 // Note: We can't clone AutonomyService easily, so we'll manage it through the mutex directly
 // Functions will get mutable access when needed
 
+/// Evaluate an action against the default service's level and enabled state.
+/// Shared with other domains that gate actions on autonomy (e.g. AI tool calls).
+pub(crate) async fn evaluate_action(
+    db_conn: &DatabaseConnection,
+    request: AutonomousActionRequest,
+) -> Result<AutonomousActionResult, String> {
+    // We need to handle the mutex properly - acquire lock, get mutable reference, then release before await
+    let service_map = get_services_map();
+    let mut services = service_map.lock().await;
+    // Ensure service exists
+    if !services.contains_key("default") {
+        services.insert("default".to_string(), AutonomyService::new());
+    }
+
+    // Get autonomy level and enabled state before releasing lock
+    let service = services
+        .get("default")
+        .ok_or("Failed to get autonomy service")?;
+    let autonomy_level = service.get_autonomy_level();
+    let autonomy_enabled = service.is_enabled();
+
+    // Release lock before await
+    drop(services);
+
+    // Create a temporary service instance with current settings for this operation
+    let mut temp_service = AutonomyService::new();
+    temp_service.set_autonomy_level(autonomy_level);
+    temp_service.set_enabled(autonomy_enabled);
+    temp_service.evaluate_action(db_conn, request).await
+}
+
+/// Record whether an evaluated action was approved/succeeded, for learning
+pub(crate) async fn record_action_outcome(
+    db_conn: &DatabaseConnection,
+    action_id: &str,
+    action_type: &str,
+    context: &str,
+    success: bool,
+    feedback: Option<String>,
+) -> Result<(), String> {
+    let mut temp_service = AutonomyService::new();
+    temp_service
+        .record_action_outcome(db_conn, action_id, action_type, context, success, feedback)
+        .await
+}
+
 #[command]
 pub async fn evaluate_autonomous_action(
     action_type: String,
@@ -36,33 +86,7 @@ pub async fn evaluate_autonomous_action(
         user_id: None,
     };
 
-    // Ensure service exists and evaluate action
-    // We need to handle the mutex properly - acquire lock, get mutable reference, then release before await
-    let db_conn = db_manager.get_connection_clone();
-    let result = {
-        let service_map = get_services_map();
-        let mut services = service_map.lock().await;
-        // Ensure service exists
-        if !services.contains_key("default") {
-            services.insert("default".to_string(), AutonomyService::new());
-        }
-
-        // Get autonomy level and enabled state before releasing lock
-        let service = services
-            .get("default")
-            .ok_or("Failed to get autonomy service")?;
-        let autonomy_level = service.get_autonomy_level();
-        let autonomy_enabled = service.is_enabled();
-
-        // Release lock before await
-        drop(services);
-
-        // Create a temporary service instance with current settings for this operation
-        let mut temp_service = AutonomyService::new();
-        temp_service.set_autonomy_level(autonomy_level);
-        temp_service.set_enabled(autonomy_enabled);
-        temp_service.evaluate_action(&db_conn, request).await?
-    };
+    let result = evaluate_action(&db_manager.get_connection_clone(), request).await?;
 
     Ok(serde_json::json!({
         "action_id": result.action_id,
@@ -86,19 +110,15 @@ pub async fn record_autonomous_action_outcome(
     feedback: Option<String>,
     db_manager: tauri::State<'_, Arc<DatabaseManager>>,
 ) -> Result<(), String> {
-    let db_conn = db_manager.get_connection_clone();
-
-    let mut temp_service = AutonomyService::new();
-    temp_service
-        .record_action_outcome(
-            &db_conn,
-            &action_id,
-            &action_type,
-            &context,
-            success,
-            feedback,
-        )
-        .await
+    record_action_outcome(
+        &db_manager.get_connection_clone(),
+        &action_id,
+        &action_type,
+        &context,
+        success,
+        feedback,
+    )
+    .await
 }
 
 #[command]
@@ -182,3 +202,23 @@ pub async fn get_approval_stats() -> Result<Value, String> {
 
     Ok(Value::Object(result))
 }
+
+/// Actions waiting on the user; with `include_resolved`, past decisions too
+#[command]
+pub async fn get_approval_queue(
+    include_resolved: Option<bool>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<QueuedApproval>, String> {
+    approval_queue::list(
+        db_manager.get_connection(),
+        include_resolved.unwrap_or(false),
+    )
+    .await
+}
+
+#[command]
+pub async fn clear_resolved_approvals(
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<u64, String> {
+    approval_queue::clear_resolved(db_manager.get_connection()).await
+}
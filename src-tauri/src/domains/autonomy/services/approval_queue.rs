@@ -0,0 +1,251 @@
+//! Persistent queue of autonomous actions waiting on the user.
+//!
+//! An action the autonomy domain doesn't clear is queued with whatever is
+//! needed to carry on once it's resolved, so pending approvals survive
+//! restarts and can be approved or rejected together. Each request expires
+//! after the TTL in the automation settings; expiry is applied whenever the
+//! queue is read, so an expired request can never be approved.
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domains::settings::services::settings_service::SettingsService;
+use crate::entities::approval_request::{ActiveModel, Column, Entity, Model};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+}
+
+impl ApprovalStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Expired => "expired",
+        }
+    }
+
+    fn parse(status: &str) -> Self {
+        match status {
+            "approved" => Self::Approved,
+            "rejected" => Self::Rejected,
+            "expired" => Self::Expired,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// An action to queue for approval
+pub struct NewApproval {
+    /// Id the action is resolved by
+    pub id: String,
+    pub action_type: String,
+    pub context: String,
+    pub description: String,
+    pub safety_level: String,
+    pub reason: String,
+    pub arguments: Value,
+    pub preview: Option<Value>,
+    /// State to resume the action from, returned by `take`
+    pub resume: Value,
+}
+
+/// A queued approval request as shown to the user
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedApproval {
+    pub id: String,
+    pub action_type: String,
+    pub context: String,
+    pub description: String,
+    pub safety_level: String,
+    pub reason: String,
+    pub arguments: Value,
+    pub preview: Option<Value>,
+    pub status: ApprovalStatus,
+    pub feedback: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl From<Model> for QueuedApproval {
+    fn from(model: Model) -> Self {
+        Self {
+            arguments: serde_json::from_str(&model.arguments_json).unwrap_or_default(),
+            preview: model
+                .preview_json
+                .as_deref()
+                .and_then(|preview| serde_json::from_str(preview).ok()),
+            status: ApprovalStatus::parse(&model.status),
+            created_at: model.created_at.with_timezone(&Utc),
+            expires_at: model.expires_at.with_timezone(&Utc),
+            resolved_at: model.resolved_at.map(|at| at.with_timezone(&Utc)),
+            id: model.id,
+            action_type: model.action_type,
+            context: model.context,
+            description: model.description,
+            safety_level: model.safety_level,
+            reason: model.reason,
+            feedback: model.feedback,
+        }
+    }
+}
+
+/// How long a new request waits before it expires, from the settings
+pub fn ttl() -> Duration {
+    let minutes = SettingsService::new()
+        .load_settings()
+        .map(|settings| settings.app.automation.approvals.ttl_minutes)
+        .unwrap_or(24 * 60);
+    Duration::minutes(minutes.max(1) as i64)
+}
+
+pub async fn enqueue(db: &DatabaseConnection, approval: NewApproval) -> Result<(), String> {
+    let now = Utc::now();
+    ActiveModel {
+        id: Set(approval.id),
+        action_type: Set(approval.action_type),
+        context: Set(approval.context),
+        description: Set(approval.description),
+        safety_level: Set(approval.safety_level),
+        reason: Set(approval.reason),
+        arguments_json: Set(approval.arguments.to_string()),
+        preview_json: Set(approval.preview.map(|preview| preview.to_string())),
+        run_json: Set(approval.resume.to_string()),
+        status: Set(ApprovalStatus::Pending.as_str().to_string()),
+        feedback: Set(None),
+        created_at: Set(now.into()),
+        expires_at: Set((now + ttl()).into()),
+        resolved_at: Set(None),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| format!("Failed to queue approval: {}", e))?;
+    Ok(())
+}
+
+/// Mark pending requests past their expiry as expired, returning how many
+pub async fn expire_overdue(db: &DatabaseConnection) -> Result<u64, String> {
+    let now: DateTime<chrono::FixedOffset> = Utc::now().into();
+    let result = Entity::update_many()
+        .col_expr(
+            Column::Status,
+            Expr::value(ApprovalStatus::Expired.as_str()),
+        )
+        .col_expr(Column::ResolvedAt, Expr::value(now))
+        .filter(Column::Status.eq(ApprovalStatus::Pending.as_str()))
+        .filter(Column::ExpiresAt.lte(now))
+        .exec(db)
+        .await
+        .map_err(|e| format!("Failed to expire approvals: {}", e))?;
+    Ok(result.rows_affected)
+}
+
+/// Pending requests, oldest first; with `include_resolved`, resolved and
+/// expired ones too, newest first
+pub async fn list(
+    db: &DatabaseConnection,
+    include_resolved: bool,
+) -> Result<Vec<QueuedApproval>, String> {
+    expire_overdue(db).await?;
+    let mut query = Entity::find();
+    query = if include_resolved {
+        query.order_by_desc(Column::CreatedAt)
+    } else {
+        query
+            .filter(Column::Status.eq(ApprovalStatus::Pending.as_str()))
+            .order_by_asc(Column::CreatedAt)
+    };
+    let requests = query
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to list approvals: {}", e))?;
+    Ok(requests.into_iter().map(QueuedApproval::from).collect())
+}
+
+/// Resolve a pending request, returning the state it was queued with to
+/// resume from. Fails if the request was already resolved or has expired.
+pub async fn take(
+    db: &DatabaseConnection,
+    id: &str,
+    approved: bool,
+    feedback: Option<String>,
+) -> Result<Value, String> {
+    expire_overdue(db).await?;
+    let request = Entity::find_by_id(id.to_string())
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to get approval: {}", e))?
+        .ok_or_else(|| format!("No action {} is waiting for approval", id))?;
+    match ApprovalStatus::parse(&request.status) {
+        ApprovalStatus::Pending => {}
+        ApprovalStatus::Expired => return Err(format!("Approval request {} has expired", id)),
+        _ => return Err(format!("Approval request {} was already resolved", id)),
+    }
+
+    let resume = serde_json::from_str(&request.run_json)
+        .map_err(|e| format!("Failed to read queued action: {}", e))?;
+    let status = if approved {
+        ApprovalStatus::Approved
+    } else {
+        ApprovalStatus::Rejected
+    };
+    let now: DateTime<chrono::FixedOffset> = Utc::now().into();
+    // Only one resolution can win when the same request is resolved twice
+    let updated = Entity::update_many()
+        .col_expr(Column::Status, Expr::value(status.as_str()))
+        .col_expr(Column::Feedback, Expr::value(feedback))
+        .col_expr(Column::ResolvedAt, Expr::value(now))
+        .filter(Column::Id.eq(id))
+        .filter(Column::Status.eq(ApprovalStatus::Pending.as_str()))
+        .exec(db)
+        .await
+        .map_err(|e| format!("Failed to resolve approval: {}", e))?;
+    if updated.rows_affected == 0 {
+        return Err(format!("Approval request {} was already resolved", id));
+    }
+    Ok(resume)
+}
+
+/// Delete resolved and expired requests, returning how many
+pub async fn clear_resolved(db: &DatabaseConnection) -> Result<u64, String> {
+    expire_overdue(db).await?;
+    let result = Entity::delete_many()
+        .filter(Column::Status.ne(ApprovalStatus::Pending.as_str()))
+        .exec(db)
+        .await
+        .map_err(|e| format!("Failed to clear approvals: {}", e))?;
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_round_trip() {
+        for status in [
+            ApprovalStatus::Pending,
+            ApprovalStatus::Approved,
+            ApprovalStatus::Rejected,
+            ApprovalStatus::Expired,
+        ] {
+            assert_eq!(ApprovalStatus::parse(status.as_str()), status);
+            assert_eq!(
+                serde_json::to_value(status).unwrap(),
+                Value::String(status.as_str().to_string())
+            );
+        }
+    }
+}
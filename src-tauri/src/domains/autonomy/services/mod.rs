@@ -1,5 +1,6 @@
 pub mod action_classifier;
 pub mod approval_manager;
+pub mod approval_queue;
 pub mod autonomy_service;
 
 pub use approval_manager::ApprovalManager;
@@ -6,8 +6,10 @@ use std::path::PathBuf;
 use reqwest::Client;
 use tauri::State;
 
+use crate::database::DatabaseManager;
 use crate::domains::ai::platform_config::PlatformConfig;
 use crate::domains::ai::services::AISettingsService;
+use crate::domains::settings::services::project_settings_service::ProjectSettingsService;
 
 use super::service::{AgentTurn, CoderService};
 use super::types::{
@@ -20,11 +22,20 @@ use super::types::{
 pub async fn coder_create_thread(
     service: State<'_, Arc<CoderService>>,
     workspace_root: String,
-    model: Option<String>,
-    llm_provider: Option<String>,
+    mut model: Option<String>,
+    mut llm_provider: Option<String>,
     thread_kind: Option<CoderThreadKind>,
     project_id: Option<i32>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
 ) -> Result<CoderThread, String> {
+    // Threads for a project use its backend and model unless the caller picked one
+    if let (Some(project_id), None, None) = (project_id, &model, &llm_provider) {
+        let overrides = ProjectSettingsService::new(db_manager.inner().clone())
+            .get_overrides(project_id)
+            .await?;
+        model = overrides.ai_model;
+        llm_provider = overrides.llm_provider;
+    }
     Ok(service
         .create_thread(workspace_root, model, llm_provider, thread_kind, project_id)
         .await)
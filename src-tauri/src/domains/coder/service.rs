@@ -557,6 +557,25 @@ impl CoderService {
         }
     }
 
+    /// Whether any agent loop is still running
+    pub async fn has_active_runs(&self) -> bool {
+        !self.active_runs.lock().await.is_empty()
+    }
+
+    /// Drop everything loaded from the database and load it again, e.g. after
+    /// switching workspaces
+    pub async fn reload(&self) {
+        self.threads.lock().await.clear();
+        self.sub_agents.lock().await.clear();
+        self.rules.lock().await.clear();
+        self.changes.lock().await.clear();
+        self.pending_commands.lock().await.clear();
+        self.edit_prefix.lock().await.clear();
+        *self.agent_mode.lock().await = CoderAgentMode::default();
+        *self.permission_mode.lock().await = PermissionMode::default();
+        self.load_from_db().await;
+    }
+
     async fn load_from_db(&self) {
         let conn = self.db.get_connection();
 
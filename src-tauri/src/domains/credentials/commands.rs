@@ -1,10 +1,27 @@
 use crate::database::DatabaseManager;
-use crate::domains::credentials::services::CredentialService;
+use crate::domains::credentials::services::audit_log::AuditLogQuery;
+use crate::domains::credentials::services::credential_import::{self, ImportReport, ImportSource};
+use crate::domains::credentials::services::credential_schema::{CredentialKind, CredentialSchema};
+use crate::domains::credentials::services::credential_service::{
+    DecryptedCredential, TypedCredentialCreateRequest, TypedCredentialUpdateRequest,
+};
+use crate::domains::credentials::services::expiry_monitor::{
+    ExpiringCredential, ExpiryCheckReport,
+};
+use crate::domains::credentials::services::project_env_service::EnvFileReport;
+use crate::domains::credentials::services::ssh_keys::{
+    GenerateSshKeyRequest, SshAgentStatus, SshKeyInfo,
+};
+use crate::domains::credentials::services::{
+    vault_session, CredentialAccessor, CredentialAuditLog, CredentialExpiryMonitor,
+    CredentialService, ProjectEnvService, SshKeyService, VaultStatus,
+};
+use crate::domains::settings::services::settings_service::SettingsService;
 use std::sync::Arc;
 /**
  * Credentials Tauri Commands
  */
-use tauri::State;
+use tauri::{AppHandle, State};
 
 /// Create a new credential
 #[tauri::command]
@@ -120,7 +137,8 @@ pub async fn delete_credential(
     }
 }
 
-/// Decrypt credential value
+/// Decrypt credential value. Audited as a user read: backend callers pass
+/// their own accessor to the service instead.
 #[tauri::command]
 pub async fn decrypt_credential(
     id: String,
@@ -128,7 +146,10 @@ pub async fn decrypt_credential(
 ) -> Result<String, String> {
     let service = CredentialService::new(db.get_connection_clone());
 
-    match service.decrypt_credential(&id).await {
+    match service
+        .decrypt_credential(&id, CredentialAccessor::User)
+        .await
+    {
         Ok(value) => Ok(value),
         Err(e) => Err(e.to_string()),
     }
@@ -153,3 +174,317 @@ pub async fn search_credentials(
         Err(e) => Err(e.to_string()),
     }
 }
+
+/// Field definitions for every typed credential kind
+#[tauri::command]
+pub async fn get_credential_schemas() -> Result<Vec<CredentialSchema>, String> {
+    Ok(CredentialKind::ALL
+        .into_iter()
+        .map(CredentialKind::schema)
+        .collect())
+}
+
+/// Create a credential of a typed kind
+#[tauri::command]
+pub async fn create_typed_credential(
+    request: TypedCredentialCreateRequest,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<serde_json::Value, String> {
+    let service = CredentialService::new(db.get_connection_clone());
+
+    match service.create_typed_credential(request).await {
+        Ok(credential) => Ok(serde_json::to_value(credential).unwrap_or(serde_json::Value::Null)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Update some fields of a typed credential
+#[tauri::command]
+pub async fn update_typed_credential(
+    id: String,
+    request: TypedCredentialUpdateRequest,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<serde_json::Value, String> {
+    let service = CredentialService::new(db.get_connection_clone());
+
+    match service.update_typed_credential(&id, request).await {
+        Ok(credential) => Ok(serde_json::to_value(credential).unwrap_or(serde_json::Value::Null)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Decrypt every field of a credential, audited as a user read
+#[tauri::command]
+pub async fn decrypt_credential_fields(
+    id: String,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<DecryptedCredential, String> {
+    let service = CredentialService::new(db.get_connection_clone());
+
+    service
+        .decrypt_credential_fields(&id, CredentialAccessor::User)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get whether the vault is locked and when it will auto-lock
+#[tauri::command]
+pub async fn get_vault_status() -> Result<VaultStatus, String> {
+    Ok(vault_session().status())
+}
+
+/// Lock the vault, wiping the master key from memory
+#[tauri::command]
+pub async fn lock_vault() -> Result<VaultStatus, String> {
+    Ok(vault_session().lock())
+}
+
+/// Unlock the vault by reading the master key from the OS keychain
+#[tauri::command]
+pub async fn unlock_vault() -> Result<VaultStatus, String> {
+    // Keychain access can block on an OS prompt
+    tokio::task::spawn_blocking(|| vault_session().unlock())
+        .await
+        .map_err(|e| format!("Failed to unlock vault: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Set the inactivity timeout after which the vault locks itself (0 disables it)
+#[tauri::command]
+pub async fn set_vault_auto_lock(
+    minutes: u32,
+    lock_on_startup: Option<bool>,
+) -> Result<VaultStatus, String> {
+    let settings_service = SettingsService::new();
+    let mut settings = settings_service.load_settings()?;
+    settings.app.security.vault_auto_lock_minutes = minutes;
+    if let Some(lock_on_startup) = lock_on_startup {
+        settings.app.security.lock_vault_on_startup = lock_on_startup;
+    }
+    settings.updated_at = chrono::Utc::now();
+    settings_service.save_settings(&settings)?;
+
+    Ok(vault_session().set_auto_lock(minutes))
+}
+
+fn expiry_warning_days() -> u32 {
+    SettingsService::new()
+        .load_settings()
+        .map(|settings| settings.app.security.credential_expiry_warning_days)
+        .unwrap_or(14)
+}
+
+/// Get credentials expiring within `window_days` (defaults to the configured
+/// warning window), including ones already expired
+#[tauri::command]
+pub async fn get_expiring_credentials(
+    window_days: Option<u32>,
+    monitor: State<'_, Arc<CredentialExpiryMonitor>>,
+) -> Result<Vec<ExpiringCredential>, String> {
+    monitor
+        .get_expiring(window_days.unwrap_or_else(expiry_warning_days))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run the expiry check now: mark expired credentials, notify and start any
+/// due rotation scripts
+#[tauri::command]
+pub async fn check_credential_expiry(
+    app: AppHandle,
+    monitor: State<'_, Arc<CredentialExpiryMonitor>>,
+) -> Result<ExpiryCheckReport, String> {
+    monitor
+        .check(&app, expiry_warning_days(), true)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Link a custom script that rotates the credential when it nears expiry, or
+/// unlink it with `None`
+#[tauri::command]
+pub async fn set_credential_rotation_script(
+    id: String,
+    script_id: Option<i32>,
+    monitor: State<'_, Arc<CredentialExpiryMonitor>>,
+) -> Result<serde_json::Value, String> {
+    match monitor.set_rotation_script(&id, script_id).await {
+        Ok(credential) => Ok(serde_json::to_value(credential).unwrap_or(serde_json::Value::Null)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Set how many days ahead credentials count as expiring
+#[tauri::command]
+pub async fn set_credential_expiry_warning_days(days: u32) -> Result<(), String> {
+    let settings_service = SettingsService::new();
+    let mut settings = settings_service.load_settings()?;
+    settings.app.security.credential_expiry_warning_days = days;
+    settings.updated_at = chrono::Utc::now();
+    settings_service.save_settings(&settings)
+}
+
+/// Get the credential-to-env-var mappings of a project
+#[tauri::command]
+pub async fn get_project_env_mappings(
+    project_id: i32,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<serde_json::Value, String> {
+    let mappings = ProjectEnvService::new(db.inner().clone())
+        .list_mappings(project_id)
+        .await?;
+    Ok(serde_json::to_value(mappings).unwrap_or(serde_json::Value::Null))
+}
+
+/// Map an env var in a project to a credential field (the primary secret when
+/// `field` is omitted)
+#[tauri::command]
+pub async fn set_project_env_mapping(
+    project_id: i32,
+    env_var: String,
+    credential_id: String,
+    field: Option<String>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<serde_json::Value, String> {
+    let mapping = ProjectEnvService::new(db.inner().clone())
+        .set_mapping(project_id, &env_var, &credential_id, field)
+        .await?;
+    Ok(serde_json::to_value(mapping).unwrap_or(serde_json::Value::Null))
+}
+
+/// Remove an env var mapping
+#[tauri::command]
+pub async fn delete_project_env_mapping(
+    id: i32,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    ProjectEnvService::new(db.inner().clone())
+        .remove_mapping(id)
+        .await
+}
+
+/// Generate or refresh the project's .env file (or `file_name`) from its mappings
+#[tauri::command]
+pub async fn write_project_env_file(
+    project_id: i32,
+    file_name: Option<String>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<EnvFileReport, String> {
+    ProjectEnvService::new(db.inner().clone())
+        .write_env_file(project_id, file_name)
+        .await
+}
+
+/// Import credentials from a .env file, AWS credentials/config, a kubeconfig or a
+/// 1Password/Bitwarden CSV export. Names already in the vault are skipped; with
+/// `dry_run` nothing is saved.
+#[tauri::command]
+pub async fn import_credentials(
+    source: ImportSource,
+    path: String,
+    dry_run: Option<bool>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<ImportReport, String> {
+    let service = CredentialService::new(db.get_connection_clone());
+
+    credential_import::import_credentials(
+        &service,
+        source,
+        std::path::Path::new(&path),
+        dry_run.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Get credential access audit entries, newest first, optionally for one
+/// credential or one accessor
+#[tauri::command]
+pub async fn get_credential_audit_log(
+    query: Option<AuditLogQuery>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<serde_json::Value, String> {
+    let entries = CredentialAuditLog::new(db.get_connection_clone())
+        .list(query.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(serde_json::to_value(entries).unwrap_or(serde_json::Value::Null))
+}
+
+/// Set how many days audit entries are kept (0 keeps them forever) and drop any
+/// already past it. Returns the number of entries removed.
+#[tauri::command]
+pub async fn set_credential_audit_retention(
+    days: u32,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<u64, String> {
+    let settings_service = SettingsService::new();
+    let mut settings = settings_service.load_settings()?;
+    settings.app.security.credential_audit_retention_days = days;
+    settings.updated_at = chrono::Utc::now();
+    settings_service.save_settings(&settings)?;
+
+    CredentialAuditLog::new(db.get_connection_clone())
+        .prune(days)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Generate an ed25519 or RSA keypair and store it in the vault
+#[tauri::command]
+pub async fn generate_ssh_key(
+    request: GenerateSshKeyRequest,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<serde_json::Value, String> {
+    match SshKeyService::new(db.get_connection_clone())
+        .generate(request)
+        .await
+    {
+        Ok(credential) => Ok(serde_json::to_value(credential).unwrap_or(serde_json::Value::Null)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Get the SSH keys in the vault and whether each is loaded in ssh-agent
+#[tauri::command]
+pub async fn get_ssh_keys(db: State<'_, Arc<DatabaseManager>>) -> Result<Vec<SshKeyInfo>, String> {
+    SshKeyService::new(db.get_connection_clone())
+        .list()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the keys loaded in the running ssh-agent
+#[tauri::command]
+pub async fn get_ssh_agent_status(
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<SshAgentStatus, String> {
+    Ok(SshKeyService::new(db.get_connection_clone())
+        .agent_status()
+        .await)
+}
+
+/// Load a vault SSH key into ssh-agent, optionally for a limited time
+#[tauri::command]
+pub async fn add_ssh_key_to_agent(
+    id: String,
+    lifetime_seconds: Option<u32>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<SshAgentStatus, String> {
+    SshKeyService::new(db.get_connection_clone())
+        .ensure_in_agent(&id, lifetime_seconds)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a vault SSH key from ssh-agent
+#[tauri::command]
+pub async fn remove_ssh_key_from_agent(
+    id: String,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<SshAgentStatus, String> {
+    SshKeyService::new(db.get_connection_clone())
+        .remove_from_agent(&id)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,24 @@
+/**
+ * Credential Access Log Entity
+ */
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "credential_access_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub credential_id: String,
+    pub credential_name: Option<String>,
+    pub accessor: String, // see CredentialAccessor
+    pub action: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub accessed_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
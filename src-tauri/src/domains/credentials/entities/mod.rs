@@ -4,6 +4,8 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+pub mod credential_access_log;
+pub mod project_env_mapping;
 pub mod vault;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
@@ -23,6 +25,8 @@ pub struct Model {
     pub updated_at: DateTime,
     pub last_used: Option<DateTime>,
     pub expires_at: Option<DateTime>,
+    pub kind: String,          // see CredentialKind
+    pub public_fields: String, // JSON object of non-secret fields
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
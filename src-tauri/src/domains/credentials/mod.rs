@@ -12,8 +12,10 @@ pub enum CredentialError {
     EncryptionFailed(String),
     DecryptionFailed(String),
     InvalidMasterPassword,
+    ValidationFailed(Vec<services::credential_schema::FieldError>),
     VaultLocked,
     SessionExpired,
+    SshError(String),
     DatabaseError(sea_orm::DbErr),
     IOError(std::io::Error),
     SerializationError(serde_json::Error),
@@ -28,8 +30,18 @@ impl std::fmt::Display for CredentialError {
             CredentialError::EncryptionFailed(msg) => write!(f, "Encryption failed: {}", msg),
             CredentialError::DecryptionFailed(msg) => write!(f, "Decryption failed: {}", msg),
             CredentialError::InvalidMasterPassword => write!(f, "Invalid master password"),
+            CredentialError::ValidationFailed(errors) => write!(
+                f,
+                "Invalid credential: {}",
+                errors
+                    .iter()
+                    .map(|e| e.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
             CredentialError::VaultLocked => write!(f, "Vault is locked"),
             CredentialError::SessionExpired => write!(f, "Session expired"),
+            CredentialError::SshError(msg) => write!(f, "SSH error: {}", msg),
             CredentialError::DatabaseError(err) => write!(f, "Database error: {}", err),
             CredentialError::IOError(err) => write!(f, "IO error: {}", err),
             CredentialError::SerializationError(err) => write!(f, "Serialization error: {}", err),
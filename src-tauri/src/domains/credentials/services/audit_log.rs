@@ -0,0 +1,205 @@
+/**
+ * Credential Audit Log - Append-only record of secret access
+ *
+ * Every decryption is logged with the part of the app that asked for it, whether
+ * it succeeded, and when. Rows are only ever inserted; the sole removal path is
+ * the retention sweep that drops entries older than the configured window.
+ */
+use super::super::CredentialError;
+use crate::domains::credentials::entities::credential_access_log::{
+    ActiveModel as AccessLogActive, Column as AccessLogColumn, Entity as AccessLogEntity,
+    Model as AccessLogModel,
+};
+use chrono::{Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+
+/// Part of the app a secret was decrypted for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialAccessor {
+    /// Revealed or copied in the credentials UI
+    User,
+    Pipeline,
+    Terminal,
+    /// Written to a project .env file
+    EnvFile,
+    Github,
+    /// Loaded into ssh-agent
+    SshAgent,
+    /// Passed to a custom script as a parameter
+    Script,
+    /// Used to clone a git repository
+    Git,
+}
+
+impl CredentialAccessor {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CredentialAccessor::User => "user",
+            CredentialAccessor::Pipeline => "pipeline",
+            CredentialAccessor::Terminal => "terminal",
+            CredentialAccessor::EnvFile => "env_file",
+            CredentialAccessor::Github => "github",
+            CredentialAccessor::SshAgent => "ssh_agent",
+            CredentialAccessor::Script => "script",
+            CredentialAccessor::Git => "git",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditLogQuery {
+    pub credential_id: Option<String>,
+    pub accessor: Option<CredentialAccessor>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CredentialAuditLog {
+    db: DatabaseConnection,
+}
+
+impl CredentialAuditLog {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn record(
+        &self,
+        credential_id: &str,
+        credential_name: Option<String>,
+        accessor: CredentialAccessor,
+        action: &str,
+        error: Option<String>,
+    ) -> Result<(), CredentialError> {
+        AccessLogActive {
+            credential_id: Set(credential_id.to_string()),
+            credential_name: Set(credential_name),
+            accessor: Set(accessor.as_str().to_string()),
+            action: Set(action.to_string()),
+            success: Set(error.is_none()),
+            error: Set(error),
+            accessed_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Newest entries first
+    pub async fn list(&self, query: AuditLogQuery) -> Result<Vec<AccessLogModel>, CredentialError> {
+        let mut select = AccessLogEntity::find().order_by_desc(AccessLogColumn::AccessedAt);
+        if let Some(credential_id) = query.credential_id {
+            select = select.filter(AccessLogColumn::CredentialId.eq(credential_id));
+        }
+        if let Some(accessor) = query.accessor {
+            select = select.filter(AccessLogColumn::Accessor.eq(accessor.as_str()));
+        }
+        Ok(select
+            .limit(query.limit.unwrap_or(500))
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Drop entries older than `retention_days`; 0 keeps everything. Returns how
+    /// many were removed.
+    pub async fn prune(&self, retention_days: u32) -> Result<u64, CredentialError> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = (Utc::now() - Duration::days(i64::from(retention_days))).naive_utc();
+        let result = AccessLogEntity::delete_many()
+            .filter(AccessLogColumn::AccessedAt.lt(cutoff))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::runner::run_migrations;
+    use sea_orm::Database;
+
+    #[tokio::test]
+    async fn records_lists_and_prunes_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("test.db").display());
+        let db = Database::connect(&url).await.unwrap();
+        run_migrations(&db).await.unwrap();
+        let log = CredentialAuditLog::new(db.clone());
+
+        log.record(
+            "a",
+            Some("API".into()),
+            CredentialAccessor::User,
+            "decrypt_value",
+            None,
+        )
+        .await
+        .unwrap();
+        log.record(
+            "a",
+            Some("API".into()),
+            CredentialAccessor::Pipeline,
+            "decrypt_fields",
+            Some("Vault is locked".into()),
+        )
+        .await
+        .unwrap();
+        log.record(
+            "b",
+            None,
+            CredentialAccessor::Terminal,
+            "decrypt_fields",
+            None,
+        )
+        .await
+        .unwrap();
+
+        let all = log.list(AuditLogQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        let for_a = log
+            .list(AuditLogQuery {
+                credential_id: Some("a".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(for_a.len(), 2);
+        let pipeline = log
+            .list(AuditLogQuery {
+                accessor: Some(CredentialAccessor::Pipeline),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(pipeline.len(), 1);
+        assert!(!pipeline[0].success);
+        assert_eq!(pipeline[0].error.as_deref(), Some("Vault is locked"));
+
+        AccessLogActive {
+            credential_id: Set("a".to_string()),
+            credential_name: Set(None),
+            accessor: Set("user".to_string()),
+            action: Set("decrypt_value".to_string()),
+            success: Set(true),
+            error: Set(None),
+            accessed_at: Set((Utc::now() - Duration::days(40)).naive_utc()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        assert_eq!(log.prune(0).await.unwrap(), 0);
+        assert_eq!(log.prune(30).await.unwrap(), 1);
+        assert_eq!(log.list(AuditLogQuery::default()).await.unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,489 @@
+/**
+ * Credential Import - Pulls secrets in from existing stores
+ *
+ * Parses .env files, AWS credentials/config files, kubeconfig users and
+ * 1Password/Bitwarden CSV exports into typed credentials. Entries whose name is
+ * already in the vault are skipped, and everything imported is tagged with the
+ * store it came from.
+ */
+use super::super::CredentialError;
+use super::credential_schema::CredentialKind;
+use super::credential_service::{CredentialService, TypedCredentialCreateRequest};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    Dotenv,
+    /// `~/.aws/credentials`, with `~/.aws/config` next to it merged in (or the
+    /// other way round)
+    Aws,
+    Kubeconfig,
+    OnePasswordCsv,
+    BitwardenCsv,
+}
+
+impl ImportSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportSource::Dotenv => "dotenv",
+            ImportSource::Aws => "aws",
+            ImportSource::Kubeconfig => "kubeconfig",
+            ImportSource::OnePasswordCsv => "1password",
+            ImportSource::BitwardenCsv => "bitwarden",
+        }
+    }
+}
+
+/// A credential parsed from a store, before it is saved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportCandidate {
+    pub name: String,
+    pub kind: CredentialKind,
+    pub fields: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportFailure {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub source: ImportSource,
+    pub dry_run: bool,
+    /// Names imported, or that would be on a dry run
+    pub imported: Vec<String>,
+    /// Names skipped because a credential with that name already exists
+    pub duplicates: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+/// Import every credential found in the file at `path`. With `dry_run` nothing is
+/// saved; the report shows what would happen.
+pub async fn import_credentials(
+    service: &CredentialService,
+    source: ImportSource,
+    path: &Path,
+    dry_run: bool,
+) -> Result<ImportReport, CredentialError> {
+    let content = std::fs::read_to_string(path)?;
+    let candidates = match source {
+        ImportSource::Dotenv => parse_dotenv(&content),
+        ImportSource::Aws => {
+            let sibling = if path.file_name().is_some_and(|n| n == "config") {
+                "credentials"
+            } else {
+                "config"
+            };
+            let sibling = std::fs::read_to_string(path.with_file_name(sibling)).unwrap_or_default();
+            parse_aws(&content, &sibling)
+        }
+        ImportSource::Kubeconfig => parse_kubeconfig(&content)?,
+        ImportSource::OnePasswordCsv => parse_password_csv(
+            &content,
+            &["title", "name"],
+            &["url", "website", "urls"],
+            &["username"],
+            &["password"],
+            &["notes", "notesplain"],
+        ),
+        ImportSource::BitwardenCsv => parse_password_csv(
+            &content,
+            &["name"],
+            &["login_uri"],
+            &["login_username"],
+            &["login_password"],
+            &["notes"],
+        ),
+    };
+
+    let mut existing: HashSet<String> = service
+        .get_credentials()
+        .await?
+        .into_iter()
+        .map(|c| c.name.to_lowercase())
+        .collect();
+    let import_path = path.to_string_lossy().to_string();
+    let mut report = ImportReport {
+        source,
+        dry_run,
+        imported: Vec::new(),
+        duplicates: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    for candidate in candidates {
+        if !existing.insert(candidate.name.to_lowercase()) {
+            report.duplicates.push(candidate.name);
+            continue;
+        }
+
+        let result = match candidate.kind.schema().validate(&candidate.fields, false) {
+            Err(errors) => Err(CredentialError::ValidationFailed(errors)),
+            Ok(()) if dry_run => Ok(()),
+            Ok(()) => service
+                .create_typed_credential(TypedCredentialCreateRequest {
+                    name: candidate.name.clone(),
+                    kind: candidate.kind,
+                    description: Some(format!("Imported from {}", import_path)),
+                    tags: Some(vec![
+                        "imported".to_string(),
+                        format!("source:{}", source.as_str()),
+                    ]),
+                    fields: candidate.fields,
+                    metadata: Some(HashMap::from([
+                        ("import_source".to_string(), source.as_str().into()),
+                        ("import_path".to_string(), import_path.clone().into()),
+                    ])),
+                    expires_at: None,
+                })
+                .await
+                .map(|_| ()),
+        };
+
+        match result {
+            Ok(()) => report.imported.push(candidate.name),
+            Err(e) => report.failed.push(ImportFailure {
+                name: candidate.name,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+fn generic(name: String, value: String, extra: Vec<(&str, Option<String>)>) -> ImportCandidate {
+    let mut fields = HashMap::from([("value".to_string(), value)]);
+    for (key, value) in extra {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            fields.insert(key.to_string(), value);
+        }
+    }
+    ImportCandidate {
+        name,
+        kind: CredentialKind::Generic,
+        fields,
+    }
+}
+
+/// `KEY=value` lines, optionally prefixed with `export`. Quoted values may span
+/// lines; unquoted values end at ` #`.
+fn parse_dotenv(content: &str) -> Vec<ImportCandidate> {
+    let mut candidates = Vec::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, raw)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let raw = raw.trim();
+
+        let value = match raw.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let mut value = raw[1..].to_string();
+                let end = loop {
+                    if let Some(end) = closing_quote(&value, quote) {
+                        break end;
+                    }
+                    match lines.next() {
+                        Some(next) => {
+                            value.push('\n');
+                            value.push_str(next);
+                        }
+                        None => break value.len(),
+                    }
+                };
+                let value = &value[..end];
+                if quote == '"' {
+                    value
+                        .replace("\\n", "\n")
+                        .replace("\\\"", "\"")
+                        .replace("\\$", "$")
+                        .replace("\\\\", "\\")
+                } else {
+                    value.to_string()
+                }
+            }
+            _ => raw
+                .split(" #")
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        };
+
+        if !key.is_empty() && !value.is_empty() {
+            candidates.push(generic(key.to_string(), value, Vec::new()));
+        }
+    }
+    candidates
+}
+
+/// Byte index of the quote closing a value; backslash escapes only apply inside
+/// double quotes.
+fn closing_quote(value: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            '\\' if quote == '"' && !escaped => escaped = true,
+            c if c == quote && !escaped => return Some(i),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// Section name -> key/value pairs of an INI file
+fn parse_ini(content: &str) -> BTreeMap<String, HashMap<String, String>> {
+    let mut sections: BTreeMap<String, HashMap<String, String>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = Some(name.trim().to_string());
+        } else if let (Some(section), Some((key, value))) = (&current, line.split_once('=')) {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    sections
+}
+
+/// One credential per AWS profile that has a secret key. `config` sections are
+/// named `[profile x]` (except `default`), credentials sections just `[x]`.
+fn parse_aws(content: &str, sibling: &str) -> Vec<ImportCandidate> {
+    let mut profiles: BTreeMap<String, HashMap<String, String>> = BTreeMap::new();
+    for source in [sibling, content] {
+        for (section, values) in parse_ini(source) {
+            let profile = section
+                .strip_prefix("profile ")
+                .map(str::trim)
+                .unwrap_or(&section)
+                .to_string();
+            profiles.entry(profile).or_default().extend(values);
+        }
+    }
+
+    profiles
+        .into_iter()
+        .filter_map(|(profile, mut values)| {
+            let secret = values.remove("aws_secret_access_key")?;
+            Some(generic(
+                format!("aws/{}", profile),
+                secret,
+                vec![
+                    ("access_key_id", values.remove("aws_access_key_id")),
+                    ("session_token", values.remove("aws_session_token")),
+                    ("region", values.remove("region")),
+                ],
+            ))
+        })
+        .collect()
+}
+
+/// Users of a kubeconfig: bearer tokens become API keys, embedded client
+/// certificates become certificate credentials.
+fn parse_kubeconfig(content: &str) -> Result<Vec<ImportCandidate>, CredentialError> {
+    let config: serde_yaml::Value = serde_yaml::from_str(content)
+        .map_err(|e| CredentialError::DeserializationError(format!("Invalid kubeconfig: {}", e)))?;
+    let decode = |value: Option<&serde_yaml::Value>| {
+        let bytes = general_purpose::STANDARD
+            .decode(value?.as_str()?.trim())
+            .ok()?;
+        String::from_utf8(bytes).ok()
+    };
+
+    let mut candidates = Vec::new();
+    for entry in config
+        .get("users")
+        .and_then(|u| u.as_sequence())
+        .into_iter()
+        .flatten()
+    {
+        let (Some(name), Some(user)) = (
+            entry.get("name").and_then(|n| n.as_str()),
+            entry.get("user"),
+        ) else {
+            continue;
+        };
+
+        if let Some(token) = user.get("token").and_then(|t| t.as_str()) {
+            candidates.push(ImportCandidate {
+                name: format!("kube/{}", name),
+                kind: CredentialKind::ApiKey,
+                fields: HashMap::from([("key".to_string(), token.to_string())]),
+            });
+        }
+        if let (Some(key), Some(cert)) = (
+            decode(user.get("client-key-data")),
+            decode(user.get("client-certificate-data")),
+        ) {
+            candidates.push(ImportCandidate {
+                name: format!("kube/{}/client-cert", name),
+                kind: CredentialKind::Certificate,
+                fields: HashMap::from([
+                    ("private_key".to_string(), key),
+                    ("certificate".to_string(), cert),
+                ]),
+            });
+        }
+    }
+    Ok(candidates)
+}
+
+/// Rows of a password manager CSV export that have a password. Columns are found
+/// by header name (case-insensitive), trying each alias in turn.
+fn parse_password_csv(
+    content: &str,
+    title_cols: &[&str],
+    url_cols: &[&str],
+    username_cols: &[&str],
+    password_cols: &[&str],
+    notes_cols: &[&str],
+) -> Vec<ImportCandidate> {
+    let mut records = parse_csv(content).into_iter();
+    let Some(header) = records.next() else {
+        return Vec::new();
+    };
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+    let column = |aliases: &[&str]| {
+        aliases
+            .iter()
+            .find_map(|alias| header.iter().position(|h| h == alias))
+    };
+    let (title, url, username, password, notes) = (
+        column(title_cols),
+        column(url_cols),
+        column(username_cols),
+        column(password_cols),
+        column(notes_cols),
+    );
+    let Some(password) = password else {
+        return Vec::new();
+    };
+
+    records
+        .filter_map(|record| {
+            let get = |col: Option<usize>| {
+                col.and_then(|i| record.get(i))
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+            };
+            let secret = get(Some(password))?;
+            let name = get(title).or_else(|| get(url))?;
+            Some(generic(
+                name,
+                secret,
+                vec![
+                    ("username", get(username)),
+                    ("url", get(url)),
+                    ("notes", get(notes)),
+                ],
+            ))
+        })
+        .collect()
+}
+
+/// RFC 4180 CSV: quoted fields may contain commas, newlines and doubled quotes.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(candidate: &ImportCandidate, field: &str) -> Option<String> {
+        candidate.fields.get(field).cloned()
+    }
+
+    #[test]
+    fn parses_dotenv_and_aws_profiles() {
+        let env = parse_dotenv(
+            "# app\nexport API_KEY=abc123 # prod\nEMPTY=\nPEM=\"line1\nline2\"\nQUOTED='a $b' # note\n",
+        );
+        assert_eq!(
+            env.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            vec!["API_KEY", "PEM", "QUOTED"]
+        );
+        assert_eq!(value(&env[0], "value").as_deref(), Some("abc123"));
+        assert_eq!(value(&env[1], "value").as_deref(), Some("line1\nline2"));
+        assert_eq!(value(&env[2], "value").as_deref(), Some("a $b"));
+
+        let aws = parse_aws(
+            "[default]\naws_access_key_id = AKIA1\naws_secret_access_key = s1\n",
+            "[default]\nregion = eu-west-1\n[profile ci]\nregion = us-east-1\n",
+        );
+        assert_eq!(aws.len(), 1);
+        assert_eq!(aws[0].name, "aws/default");
+        assert_eq!(value(&aws[0], "access_key_id").as_deref(), Some("AKIA1"));
+        assert_eq!(value(&aws[0], "region").as_deref(), Some("eu-west-1"));
+    }
+
+    #[test]
+    fn parses_password_manager_csv() {
+        let csv = "folder,favorite,type,name,notes,fields,reprompt,login_uri,login_username,login_password,login_totp\r\n\
+                   ,,login,GitHub,\"two\nlines\",,0,https://github.com,me,\"p,\"\"w\",\r\n\
+                   ,,note,Wifi,just a note,,0,,,,\r\n";
+        let rows = parse_password_csv(
+            csv,
+            &["name"],
+            &["login_uri"],
+            &["login_username"],
+            &["login_password"],
+            &["notes"],
+        );
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].name, "GitHub");
+        assert_eq!(value(&rows[0], "value").as_deref(), Some("p,\"w"));
+        assert_eq!(value(&rows[0], "notes").as_deref(), Some("two\nlines"));
+        assert_eq!(value(&rows[0], "username").as_deref(), Some("me"));
+    }
+}
@@ -1,14 +1,19 @@
 use super::super::CredentialError;
+use super::audit_log::{CredentialAccessor, CredentialAuditLog};
+use super::credential_schema::CredentialKind;
 use super::encryption_service::{DecryptionRequest, EncryptionResult, EncryptionService};
+use super::vault_session::vault_session;
 use crate::domains::credentials::entities::Column;
 use crate::domains::credentials::entities::{
     ActiveModel as CredentialActive, Entity as CredentialEntity, Model as CredentialModel,
 };
+use crate::log_warn;
 use chrono::Utc;
 /**
  * Credential Service - Business logic for credential management
  */
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
 use serde_json;
 
 #[derive(Debug, Clone)]
@@ -30,12 +35,8 @@ impl CredentialService {
         &self,
         request: CredentialCreateRequest,
     ) -> Result<CredentialModel, CredentialError> {
-        let id = uuid::Uuid::new_v4().to_string();
-        let id_for_verify = id.clone();
-        let now = Utc::now().naive_utc();
-
         // Encrypt the credential value
-        let master_key = self.get_master_key()?;
+        let master_key = self.get_master_key().await?;
         let encryption_result = self
             .encryption
             .encrypt(&request.value, &master_key)?;
@@ -49,15 +50,20 @@ impl CredentialService {
             }
         }
 
+        let now = Utc::now().naive_utc();
         let credential = CredentialActive {
-            id: Set(id),
+            id: Set(uuid::Uuid::new_v4().to_string()),
             name: Set(request.name),
+            kind: Set(CredentialKind::from_legacy_type(&request.credential_type)
+                .as_str()
+                .to_string()),
             credential_type: Set(request.credential_type.to_string()),
             status: Set("active".to_string()),
             description: Set(request.description),
             tags: Set(serde_json::to_string(&request.tags.unwrap_or_default())?),
             encrypted_value: Set(serde_json::to_string(&encryption_result)?),
             encrypted_fields: Set(serde_json::to_string(&encrypted_fields)?),
+            public_fields: Set("{}".to_string()),
             metadata: Set(serde_json::to_string(
                 &request.metadata.unwrap_or_default(),
             )?),
@@ -67,6 +73,63 @@ impl CredentialService {
             expires_at: Set(request.expires_at.map(|d| d.naive_utc())),
         };
 
+        self.insert(credential).await
+    }
+
+    /// Create a credential of a typed kind. Fields are validated against the kind's
+    /// schema and each secret field is encrypted separately.
+    pub async fn create_typed_credential(
+        &self,
+        request: TypedCredentialCreateRequest,
+    ) -> Result<CredentialModel, CredentialError> {
+        let schema = request.kind.schema();
+        schema
+            .validate(&request.fields, false)
+            .map_err(CredentialError::ValidationFailed)?;
+        let split = schema.split(request.fields);
+
+        let master_key = self.get_master_key().await?;
+        let primary = split.primary.unwrap_or_default();
+        let encrypted_value = self.encryption.encrypt(&primary, &master_key)?;
+        let mut encrypted_fields = std::collections::HashMap::new();
+        for (name, value) in split.secret {
+            let field_result = self.encryption.encrypt(&value, &master_key)?;
+            encrypted_fields.insert(name, serde_json::to_string(&field_result)?);
+        }
+
+        let now = Utc::now().naive_utc();
+        let credential = CredentialActive {
+            id: Set(uuid::Uuid::new_v4().to_string()),
+            name: Set(request.name),
+            kind: Set(request.kind.as_str().to_string()),
+            credential_type: Set(request.kind.as_str().to_string()),
+            status: Set("active".to_string()),
+            description: Set(request.description),
+            tags: Set(serde_json::to_string(&request.tags.unwrap_or_default())?),
+            encrypted_value: Set(serde_json::to_string(&encrypted_value)?),
+            encrypted_fields: Set(serde_json::to_string(&encrypted_fields)?),
+            public_fields: Set(serde_json::to_string(&split.public)?),
+            metadata: Set(serde_json::to_string(
+                &request.metadata.unwrap_or_default(),
+            )?),
+            created_at: Set(now),
+            updated_at: Set(now),
+            last_used: Set(None),
+            expires_at: Set(request.expires_at.map(|d| d.naive_utc())),
+        };
+
+        self.insert(credential).await
+    }
+
+    async fn insert(
+        &self,
+        credential: CredentialActive,
+    ) -> Result<CredentialModel, CredentialError> {
+        let id_for_verify = match &credential.id {
+            sea_orm::ActiveValue::Set(id) => id.clone(),
+            _ => String::new(),
+        };
+
         // SQLite string primary keys can fail to return the inserted row even when
         // the insert succeeds. Verify by ID when SeaORM reports RecordNotFound.
         match credential.insert(&self.db).await {
@@ -132,14 +195,14 @@ impl CredentialService {
 
         // Update encrypted value if provided
         if let Some(value) = request.value {
-            let master_key = self.get_master_key()?;
+            let master_key = self.get_master_key().await?;
             let encryption_result = self.encryption.encrypt(&value, &master_key)?;
             credential.encrypted_value = serde_json::to_string(&encryption_result)?;
         }
 
         // Update encrypted fields if provided
         if let Some(fields) = request.fields {
-            let master_key = self.get_master_key()?;
+            let master_key = self.get_master_key().await?;
             let mut encrypted_fields = std::collections::HashMap::new();
             for (key, value) in fields {
                 let field_result = self.encryption.encrypt(&value, &master_key)?;
@@ -159,12 +222,14 @@ impl CredentialService {
         let active_model = CredentialActive {
             id: Set(credential.id.clone()),
             name: Set(credential.name.clone()),
+            kind: Set(credential.kind.clone()),
             credential_type: Set(credential.credential_type.clone()),
             status: Set(credential.status.clone()),
             description: Set(credential.description.clone()),
             tags: Set(credential.tags.clone()),
             encrypted_value: Set(credential.encrypted_value.clone()),
             encrypted_fields: Set(credential.encrypted_fields.clone()),
+            public_fields: Set(credential.public_fields.clone()),
             metadata: Set(credential.metadata.clone()),
             created_at: Set(credential.created_at),
             updated_at: Set(credential.updated_at),
@@ -182,45 +247,218 @@ impl CredentialService {
         Ok(())
     }
 
-    /// Decrypt credential value
-    pub async fn decrypt_credential(&self, id: &str) -> Result<String, CredentialError> {
+    /// Update a typed credential. Only the fields present in `request.fields` change;
+    /// an empty value clears an optional field.
+    pub async fn update_typed_credential(
+        &self,
+        id: &str,
+        request: TypedCredentialUpdateRequest,
+    ) -> Result<CredentialModel, CredentialError> {
+        let credential = self.get_credential(id).await?;
+        let schema = Self::kind_of(&credential).schema();
+        let mut active: CredentialActive = credential.clone().into();
+
+        if let Some(name) = request.name {
+            active.name = Set(name);
+        }
+        if let Some(description) = request.description {
+            active.description = Set(Some(description));
+        }
+        if let Some(tags) = request.tags {
+            active.tags = Set(serde_json::to_string(&tags)?);
+        }
+        if let Some(status) = request.status {
+            active.status = Set(status);
+        }
+        if let Some(expires_at) = request.expires_at {
+            active.expires_at = Set(Some(expires_at.naive_utc()));
+        }
+        if let Some(metadata) = request.metadata {
+            active.metadata = Set(serde_json::to_string(&metadata)?);
+        }
+
+        if let Some(fields) = request.fields {
+            schema
+                .validate(&fields, true)
+                .map_err(CredentialError::ValidationFailed)?;
+
+            let master_key = self.get_master_key().await?;
+            let mut encrypted_fields: std::collections::HashMap<String, String> =
+                serde_json::from_str(&credential.encrypted_fields).unwrap_or_default();
+            let mut public_fields: std::collections::HashMap<String, String> =
+                serde_json::from_str(&credential.public_fields).unwrap_or_default();
+
+            for (name, value) in fields {
+                let secret = schema.field(&name).is_none_or(|f| f.secret);
+                if name == schema.primary_field {
+                    let encrypted = self.encryption.encrypt(&value, &master_key)?;
+                    active.encrypted_value = Set(serde_json::to_string(&encrypted)?);
+                } else if value.trim().is_empty() {
+                    encrypted_fields.remove(&name);
+                    public_fields.remove(&name);
+                } else if secret {
+                    let encrypted = self.encryption.encrypt(&value, &master_key)?;
+                    encrypted_fields.insert(name, serde_json::to_string(&encrypted)?);
+                } else {
+                    public_fields.insert(name, value);
+                }
+            }
+
+            active.encrypted_fields = Set(serde_json::to_string(&encrypted_fields)?);
+            active.public_fields = Set(serde_json::to_string(&public_fields)?);
+        }
+
+        active.updated_at = Set(Utc::now().naive_utc());
+        Ok(active.update(&self.db).await?)
+    }
+
+    /// Decrypt credential value. The access is recorded in the audit log.
+    pub async fn decrypt_credential(
+        &self,
+        id: &str,
+        accessor: CredentialAccessor,
+    ) -> Result<String, CredentialError> {
         let credential = self.get_credential(id).await?;
-        // Ensure EncryptionResult is deserializable (ensure #[derive(serde::Deserialize)] on EncryptionResult)
-        let encryption_data: EncryptionResult =
-            serde_json::from_str(&credential.encrypted_value)
+        let name = credential.name.clone();
+        let result = self.decrypt_value(credential).await;
+        self.audit(id, name, accessor, "decrypt_value", &result)
+            .await;
+        result
+    }
+
+    async fn decrypt_value(&self, credential: CredentialModel) -> Result<String, CredentialError> {
+        let master_key = self.get_master_key().await?;
+        let decrypted = self.decrypt_stored(&credential.encrypted_value, master_key)?;
+
+        self.mark_used(credential).await?;
+        Ok(decrypted)
+    }
+
+    /// Decrypt every field of a credential, keyed by the field names of its kind.
+    /// Legacy credentials return their value under the kind's primary field. The
+    /// access is recorded in the audit log.
+    pub async fn decrypt_credential_fields(
+        &self,
+        id: &str,
+        accessor: CredentialAccessor,
+    ) -> Result<DecryptedCredential, CredentialError> {
+        let credential = self.get_credential(id).await?;
+        let name = credential.name.clone();
+        let result = self.decrypt_fields(credential).await;
+        self.audit(id, name, accessor, "decrypt_fields", &result)
+            .await;
+        result
+    }
+
+    async fn decrypt_fields(
+        &self,
+        credential: CredentialModel,
+    ) -> Result<DecryptedCredential, CredentialError> {
+        let kind = Self::kind_of(&credential);
+        let schema = kind.schema();
+        let master_key = self.get_master_key().await?;
+
+        let mut fields: std::collections::HashMap<String, String> =
+            serde_json::from_str(&credential.public_fields).unwrap_or_default();
+        fields.insert(
+            schema.primary_field.clone(),
+            self.decrypt_stored(&credential.encrypted_value, master_key)?,
+        );
+        let encrypted_fields: std::collections::HashMap<String, String> =
+            serde_json::from_str(&credential.encrypted_fields)
                 .map_err(|e| CredentialError::DeserializationError(e.to_string()))?;
+        for (name, stored) in encrypted_fields {
+            fields.insert(name, self.decrypt_stored(&stored, master_key)?);
+        }
+
+        let id = credential.id.clone();
+        self.mark_used(credential).await?;
+        Ok(DecryptedCredential { id, kind, fields })
+    }
+
+    /// Record a decryption attempt. A failure to write the log is reported but
+    /// doesn't fail the access itself.
+    async fn audit<T>(
+        &self,
+        id: &str,
+        name: String,
+        accessor: CredentialAccessor,
+        action: &str,
+        result: &Result<T, CredentialError>,
+    ) {
+        let error = result.as_ref().err().map(|e| e.to_string());
+        if let Err(e) = CredentialAuditLog::new(self.db.clone())
+            .record(id, Some(name), accessor, action, error)
+            .await
+        {
+            log_warn!(
+                "Credentials",
+                "Failed to write audit log entry for {}: {}",
+                id,
+                e
+            );
+        }
+    }
+
+    pub(crate) fn kind_of(credential: &CredentialModel) -> CredentialKind {
+        CredentialKind::parse(&credential.kind)
+            .unwrap_or_else(|| CredentialKind::from_legacy_type(&credential.credential_type))
+    }
+
+    /// Decrypt an `EncryptionResult` stored as JSON
+    fn decrypt_stored(&self, stored: &str, key: [u8; 32]) -> Result<String, CredentialError> {
+        let encryption_data: EncryptionResult = serde_json::from_str(stored)
+            .map_err(|e| CredentialError::DeserializationError(e.to_string()))?;
 
-        let master_key = self.get_master_key()?;
-        let request = DecryptionRequest {
-            encrypted: encryption_data.encrypted.clone(),
-            iv: encryption_data.iv.clone(),
+        self.encryption.decrypt(DecryptionRequest {
+            encrypted: encryption_data.encrypted,
+            iv: encryption_data.iv,
             tag: encryption_data.tag,
             algorithm: encryption_data.algorithm,
-            key: master_key,
-        };
+            key,
+        })
+    }
 
-        let decrypted = self.encryption.decrypt(request)?;
+    /// Update last used timestamp
+    async fn mark_used(&self, credential: CredentialModel) -> Result<(), CredentialError> {
+        let mut active: CredentialActive = credential.into();
+        active.last_used = Set(Some(Utc::now().naive_utc()));
+        active.update(&self.db).await?;
+        Ok(())
+    }
 
-        // Update last used timestamp
-        let now = Utc::now().naive_utc();
-        let active_model = CredentialActive {
-            id: Set(credential.id),
-            name: Set(credential.name),
-            credential_type: Set(credential.credential_type),
-            status: Set(credential.status),
-            description: Set(credential.description),
-            tags: Set(credential.tags),
-            encrypted_value: Set(credential.encrypted_value),
-            encrypted_fields: Set(credential.encrypted_fields),
-            metadata: Set(credential.metadata),
-            created_at: Set(credential.created_at),
-            updated_at: Set(credential.updated_at),
-            last_used: Set(Some(now)),
-            expires_at: Set(credential.expires_at),
+    /// Every stored secret value with its credential's name, for scrubbing
+    /// them out of text that leaves the machine. Doesn't count as vault
+    /// activity or mark credentials used, and isn't audited since the values
+    /// stay in-process. `None` while the vault is locked.
+    pub async fn secret_values(&self) -> Result<Option<Vec<(String, String)>>, CredentialError> {
+        let key = tokio::task::spawn_blocking(|| vault_session().key_if_unlocked())
+            .await
+            .map_err(|e| {
+                CredentialError::EncryptionFailed(format!("Failed to read master key: {}", e))
+            })?;
+        let Some(key) = key else {
+            return Ok(None);
         };
-        active_model.update(&self.db).await?;
-
-        Ok(decrypted)
+        let mut values = Vec::new();
+        for credential in self.get_credentials().await? {
+            let encrypted_fields: std::collections::HashMap<String, String> =
+                serde_json::from_str(&credential.encrypted_fields).unwrap_or_default();
+            let stored = std::iter::once(credential.encrypted_value.clone())
+                .chain(encrypted_fields.into_values());
+            for stored in stored {
+                match self.decrypt_stored(&stored, key) {
+                    Ok(value) => values.push((credential.name.clone(), value)),
+                    Err(e) => log_warn!(
+                        "Credentials",
+                        "Failed to decrypt {} for redaction: {}",
+                        credential.id,
+                        e
+                    ),
+                }
+            }
+        }
+        Ok(Some(values))
     }
 
     /// Search credentials
@@ -232,74 +470,22 @@ impl CredentialService {
             .filter(
                 sea_orm::Condition::any()
                     .add(Column::Name.contains(query))
-                    .add(Column::Description.contains(query)),
+                    .add(Column::Description.contains(query))
+                    .add(Column::PublicFields.contains(query)),
             )
             .all(&self.db)
             .await?;
         Ok(credentials)
     }
 
-    /// Get the master encryption key.
-    ///
-    /// The key is a random 32 bytes stored in the OS keychain (Windows Credential
-    /// Manager / macOS Keychain / Linux Secret Service), so it is protected by the
-    /// user's OS login and is NOT derivable from anything on disk. Generated on first
-    /// use and persisted thereafter.
-    ///
-    /// Note: credentials encrypted under the previous path-derived key cannot be
-    /// decrypted with this key and must be re-entered (pre-1.0 migration).
-    fn get_master_key(&self) -> Result<[u8; 32], CredentialError> {
-        const SERVICE: &str = crate::app_paths::APP_IDENTIFIER;
-        const KEY_NAME: &str = "credential-master-key-v1";
-
-        let entry = keyring::Entry::new(SERVICE, KEY_NAME).map_err(|e| {
-            CredentialError::EncryptionFailed(format!("Keychain access failed: {}", e))
-        })?;
-
-        match entry.get_secret() {
-            Ok(bytes) if bytes.len() == 32 => {
-                let mut key = [0u8; 32];
-                key.copy_from_slice(&bytes);
-                Ok(key)
-            }
-            Ok(_) => Err(CredentialError::EncryptionFailed(
-                "Stored master key has unexpected length".to_string(),
-            )),
-            Err(keyring::Error::NoEntry) => {
-                // No entry under the current service name: either a genuine first run,
-                // or an install that predates the bundle-identifier rename. Carry the
-                // old key across when there is one, so existing credentials stay
-                // decryptable; otherwise mint a fresh random key.
-                let key = Self::legacy_master_key(KEY_NAME).unwrap_or_else(|| {
-                    use rand::RngCore;
-                    let mut key = [0u8; 32];
-                    rand::rngs::OsRng.fill_bytes(&mut key);
-                    key
-                });
-                entry.set_secret(&key).map_err(|e| {
-                    CredentialError::EncryptionFailed(format!(
-                        "Failed to store master key in keychain: {}",
-                        e
-                    ))
-                })?;
-                Ok(key)
-            }
-            Err(e) => Err(CredentialError::EncryptionFailed(format!(
-                "Failed to read master key from keychain: {}",
-                e
-            ))),
-        }
-    }
-
-    /// Master key stored under the pre-rename bundle identifier, if one is there.
-    /// Drop this once no pre-rename installs remain in the wild.
-    fn legacy_master_key(key_name: &str) -> Option<[u8; 32]> {
-        keyring::Entry::new(crate::app_paths::LEGACY_APP_IDENTIFIER, key_name)
-            .ok()?
-            .get_secret()
-            .ok()?
-            .try_into()
-            .ok()
+    /// Get the master encryption key from the unlocked vault session.
+    async fn get_master_key(&self) -> Result<[u8; 32], CredentialError> {
+        // Keychain access can block on an OS prompt
+        tokio::task::spawn_blocking(|| vault_session().master_key())
+            .await
+            .map_err(|e| {
+                CredentialError::EncryptionFailed(format!("Failed to read master key: {}", e))
+            })?
     }
 }
 
@@ -326,3 +512,32 @@ pub struct CredentialUpdateRequest {
     pub status: Option<String>,
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypedCredentialCreateRequest {
+    pub name: String,
+    pub kind: CredentialKind,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub fields: std::collections::HashMap<String, String>,
+    pub metadata: Option<std::collections::HashMap<String, serde_json::Value>>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypedCredentialUpdateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub fields: Option<std::collections::HashMap<String, String>>,
+    pub metadata: Option<std::collections::HashMap<String, serde_json::Value>>,
+    pub status: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecryptedCredential {
+    pub id: String,
+    pub kind: CredentialKind,
+    pub fields: std::collections::HashMap<String, String>,
+}
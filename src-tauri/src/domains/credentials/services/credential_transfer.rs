@@ -0,0 +1,172 @@
+/**
+ * Credential Transfer - Moves encrypted credentials between machines
+ *
+ * Stored secrets are encrypted with this machine's master key, which never
+ * leaves the OS keychain. For an export they're re-encrypted under a key
+ * derived from a passphrase the user picks, and on import re-encrypted under
+ * the importing machine's master key. Plaintext only exists in memory.
+ */
+use super::super::entities::Model as CredentialModel;
+use super::super::CredentialError;
+use super::encryption_service::{DecryptionRequest, EncryptionResult, EncryptionService};
+use super::vault_session::vault_session;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const PBKDF2_ITERATIONS: u32 = 210_000;
+
+/// Credentials re-encrypted under a passphrase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableCredentials {
+    pub salt: String,
+    pub iterations: u32,
+    pub credentials: Vec<CredentialModel>,
+}
+
+/// Re-encrypt credentials from the master key to `passphrase`. Needs the vault
+/// unlocked.
+pub fn seal(
+    credentials: Vec<CredentialModel>,
+    passphrase: &str,
+) -> Result<PortableCredentials, CredentialError> {
+    let encryption = EncryptionService::new();
+    let master_key = vault_session().master_key()?;
+    let salt = encryption.generate_salt()?;
+    let key = encryption.derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+
+    let credentials = credentials
+        .into_iter()
+        .map(|credential| rekey(&encryption, credential, &master_key, &key))
+        .collect::<Result<_, _>>()?;
+    Ok(PortableCredentials {
+        salt: general_purpose::STANDARD.encode(salt),
+        iterations: PBKDF2_ITERATIONS,
+        credentials,
+    })
+}
+
+/// Re-encrypt exported credentials from `passphrase` to the master key. A wrong
+/// passphrase fails here, before anything is saved.
+pub fn open(
+    portable: PortableCredentials,
+    passphrase: &str,
+) -> Result<Vec<CredentialModel>, CredentialError> {
+    let encryption = EncryptionService::new();
+    let master_key = vault_session().master_key()?;
+    let salt = general_purpose::STANDARD
+        .decode(&portable.salt)
+        .map_err(|e| CredentialError::DeserializationError(e.to_string()))?;
+    if portable.iterations == 0 {
+        return Err(CredentialError::DeserializationError(
+            "Invalid key derivation iterations".to_string(),
+        ));
+    }
+    let key = encryption.derive_key(passphrase, &salt, portable.iterations)?;
+
+    portable
+        .credentials
+        .into_iter()
+        .map(|credential| rekey(&encryption, credential, &key, &master_key))
+        .map(|result| {
+            result.map_err(|e| match e {
+                CredentialError::DecryptionFailed(_) => CredentialError::DecryptionFailed(
+                    "wrong passphrase or damaged export".to_string(),
+                ),
+                e => e,
+            })
+        })
+        .collect()
+}
+
+/// Decrypt a credential's secrets with `from` and encrypt them again with `to`
+fn rekey(
+    encryption: &EncryptionService,
+    mut credential: CredentialModel,
+    from: &[u8; 32],
+    to: &[u8; 32],
+) -> Result<CredentialModel, CredentialError> {
+    credential.encrypted_value = rekey_value(encryption, &credential.encrypted_value, from, to)?;
+
+    let fields: HashMap<String, String> =
+        serde_json::from_str(&credential.encrypted_fields).unwrap_or_default();
+    let fields = fields
+        .into_iter()
+        .map(|(name, value)| Ok((name, rekey_value(encryption, &value, from, to)?)))
+        .collect::<Result<HashMap<_, _>, CredentialError>>()?;
+    credential.encrypted_fields = serde_json::to_string(&fields)?;
+    Ok(credential)
+}
+
+fn rekey_value(
+    encryption: &EncryptionService,
+    stored: &str,
+    from: &[u8; 32],
+    to: &[u8; 32],
+) -> Result<String, CredentialError> {
+    let stored: EncryptionResult = serde_json::from_str(stored)
+        .map_err(|e| CredentialError::DeserializationError(e.to_string()))?;
+    let plaintext = encryption.decrypt(DecryptionRequest {
+        encrypted: stored.encrypted,
+        iv: stored.iv,
+        tag: stored.tag,
+        algorithm: stored.algorithm,
+        key: *from,
+    })?;
+    Ok(serde_json::to_string(&encryption.encrypt(&plaintext, to)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rekeyed_credentials_decrypt_only_under_the_new_key() {
+        let encryption = EncryptionService::new();
+        let from = [1u8; 32];
+        let to = [2u8; 32];
+        let encrypt = |value: &str| {
+            serde_json::to_string(&encryption.encrypt(value, &from).unwrap()).unwrap()
+        };
+        let now = chrono::Utc::now().naive_utc();
+        let credential = CredentialModel {
+            id: "cred-1".to_string(),
+            name: "db".to_string(),
+            credential_type: "password".to_string(),
+            status: "active".to_string(),
+            description: None,
+            tags: "[]".to_string(),
+            encrypted_value: encrypt("hunter2"),
+            encrypted_fields: serde_json::to_string(&HashMap::from([(
+                "token".to_string(),
+                encrypt("abc"),
+            )]))
+            .unwrap(),
+            metadata: "{}".to_string(),
+            created_at: now,
+            updated_at: now,
+            last_used: None,
+            expires_at: None,
+            kind: "password".to_string(),
+            public_fields: "{}".to_string(),
+        };
+
+        let rekeyed = rekey(&encryption, credential, &from, &to).unwrap();
+        let decrypt = |stored: &str, key: [u8; 32]| {
+            let stored: EncryptionResult = serde_json::from_str(stored).unwrap();
+            encryption.decrypt(DecryptionRequest {
+                encrypted: stored.encrypted,
+                iv: stored.iv,
+                tag: stored.tag,
+                algorithm: stored.algorithm,
+                key,
+            })
+        };
+        assert_eq!(decrypt(&rekeyed.encrypted_value, to).unwrap(), "hunter2");
+        assert!(decrypt(&rekeyed.encrypted_value, from).is_err());
+
+        let fields: HashMap<String, String> =
+            serde_json::from_str(&rekeyed.encrypted_fields).unwrap();
+        assert_eq!(decrypt(&fields["token"], to).unwrap(), "abc");
+    }
+}
@@ -0,0 +1,298 @@
+/**
+ * Expiry Monitor - Flags credentials nearing their expires_at and runs rotation scripts
+ *
+ * Checks run on a timer from app setup and on demand. Each check marks credentials
+ * past their expiry as `expired`, emits `credentials-expiring` with everything
+ * inside the warning window, and runs a credential's rotation script (a custom
+ * script linked through its metadata) once per expiry date.
+ */
+use super::super::CredentialError;
+use super::credential_schema::CredentialKind;
+use crate::database::DatabaseManager;
+use crate::domains::automation::services::event_bus;
+use crate::domains::credentials::entities::{
+    ActiveModel as CredentialActive, Column, Entity as CredentialEntity, Model as CredentialModel,
+};
+use crate::domains::custom_scripts::services::CustomScriptService;
+use crate::domains::scripts::commands::ScriptExecutionState;
+use crate::{log_info, log_warn};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const EXPIRING_EVENT: &str = "credentials-expiring";
+
+/// Metadata key holding the id of the custom script that rotates a credential
+pub const ROTATION_SCRIPT_KEY: &str = "rotation_script_id";
+/// Metadata key recording which expiry the rotation script last ran for
+const ROTATION_RAN_FOR_KEY: &str = "rotation_ran_for_expiry";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiringCredential {
+    pub id: String,
+    pub name: String,
+    pub kind: CredentialKind,
+    pub expires_at: DateTime<Utc>,
+    /// Negative once expired
+    pub days_left: i64,
+    pub expired: bool,
+    pub rotation_script_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiryCheckReport {
+    pub window_days: u32,
+    pub expiring: Vec<ExpiringCredential>,
+    /// Credentials whose status this check changed to `expired`
+    pub newly_expired: Vec<String>,
+    /// Credentials whose rotation script was started, with the execution id
+    pub rotations_started: Vec<(String, String)>,
+}
+
+pub struct CredentialExpiryMonitor {
+    db_manager: Arc<DatabaseManager>,
+    /// Credential id -> day it was last announced, so the timer doesn't repeat
+    /// the same warning every tick
+    announced: Mutex<HashMap<String, chrono::NaiveDate>>,
+}
+
+impl CredentialExpiryMonitor {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            db_manager,
+            announced: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Active credentials expiring within `window_days`, including ones already
+    /// expired, soonest first.
+    pub async fn get_expiring(
+        &self,
+        window_days: u32,
+    ) -> Result<Vec<ExpiringCredential>, CredentialError> {
+        let now = Utc::now();
+        let cutoff = (now + Duration::days(i64::from(window_days))).naive_utc();
+
+        let credentials = CredentialEntity::find()
+            .filter(Column::ExpiresAt.is_not_null())
+            .filter(Column::ExpiresAt.lte(cutoff))
+            .filter(Column::Status.is_not_in(["revoked", "inactive"]))
+            .order_by_asc(Column::ExpiresAt)
+            .all(self.db_manager.get_connection())
+            .await?;
+
+        Ok(credentials
+            .iter()
+            .filter_map(|credential| {
+                let expires_at = credential.expires_at?;
+                let (days_left, expired) = expiry_status(expires_at, now);
+                Some(ExpiringCredential {
+                    id: credential.id.clone(),
+                    name: credential.name.clone(),
+                    kind: CredentialKind::parse(&credential.kind).unwrap_or_else(|| {
+                        CredentialKind::from_legacy_type(&credential.credential_type)
+                    }),
+                    expires_at: expires_at.and_utc(),
+                    days_left,
+                    expired,
+                    rotation_script_id: rotation_script_id(credential),
+                })
+            })
+            .collect())
+    }
+
+    /// Run one check. With `announce_all` every expiring credential is emitted,
+    /// otherwise only those not yet announced today.
+    pub async fn check(
+        &self,
+        app: &AppHandle,
+        window_days: u32,
+        announce_all: bool,
+    ) -> Result<ExpiryCheckReport, CredentialError> {
+        let expiring = self.get_expiring(window_days).await?;
+        let mut report = ExpiryCheckReport {
+            window_days,
+            expiring: expiring.clone(),
+            newly_expired: Vec::new(),
+            rotations_started: Vec::new(),
+        };
+
+        for item in expiring.iter() {
+            let Some(credential) = CredentialEntity::find_by_id(&item.id)
+                .one(self.db_manager.get_connection())
+                .await?
+            else {
+                continue;
+            };
+
+            if item.expired && credential.status != "expired" {
+                let mut active: CredentialActive = credential.clone().into();
+                active.status = Set("expired".to_string());
+                active.update(self.db_manager.get_connection()).await?;
+                report.newly_expired.push(item.id.clone());
+            }
+
+            if let Some(script_id) = item.rotation_script_id {
+                match self.run_rotation(app, &credential, script_id).await {
+                    Ok(Some(execution_id)) => report
+                        .rotations_started
+                        .push((item.id.clone(), execution_id)),
+                    Ok(None) => {}
+                    Err(e) => log_warn!(
+                        "Credentials",
+                        "Rotation script for credential {} failed to start: {}",
+                        item.name,
+                        e
+                    ),
+                }
+            }
+        }
+
+        let to_announce: Vec<&ExpiringCredential> = {
+            let today = Utc::now().date_naive();
+            let mut announced = self.announced.lock().unwrap_or_else(|e| e.into_inner());
+            expiring
+                .iter()
+                .filter(|item| {
+                    announce_all || announced.insert(item.id.clone(), today) != Some(today)
+                })
+                .collect()
+        };
+        if !to_announce.is_empty() {
+            let _ = app.emit(EXPIRING_EVENT, &to_announce);
+        }
+        for item in &to_announce {
+            event_bus::publish(
+                event_bus::CREDENTIAL_EXPIRING,
+                serde_json::json!({
+                    "credential_id": item.id,
+                    "name": item.name,
+                    "kind": item.kind,
+                    "expires_at": item.expires_at.to_rfc3339(),
+                    "days_left": item.days_left,
+                    "expired": item.expired,
+                }),
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Start the rotation script unless it already ran for this expiry date.
+    /// Returns the script execution id.
+    async fn run_rotation(
+        &self,
+        app: &AppHandle,
+        credential: &CredentialModel,
+        script_id: i32,
+    ) -> Result<Option<String>, String> {
+        let Some(expires_at) = credential.expires_at else {
+            return Ok(None);
+        };
+        let mut metadata: HashMap<String, serde_json::Value> =
+            serde_json::from_str(&credential.metadata).unwrap_or_default();
+        let expiry_key = expires_at.and_utc().to_rfc3339();
+        if metadata.get(ROTATION_RAN_FOR_KEY).and_then(|v| v.as_str()) == Some(&expiry_key) {
+            return Ok(None);
+        }
+
+        let parameters = HashMap::from([
+            ("credential_id".to_string(), credential.id.clone()),
+            ("credential_name".to_string(), credential.name.clone()),
+            ("expires_at".to_string(), expiry_key.clone()),
+        ]);
+        let service = app
+            .state::<ScriptExecutionState>()
+            .get_or_init(&self.db_manager)
+            .await;
+        let run = CustomScriptService::new(&self.db_manager)
+            .run_script(&service, script_id, parameters, None, "credential-rotation")
+            .await?;
+
+        metadata.insert(
+            ROTATION_RAN_FOR_KEY.to_string(),
+            serde_json::Value::String(expiry_key),
+        );
+        let mut active: CredentialActive = credential.clone().into();
+        active.metadata = Set(serde_json::to_string(&metadata).map_err(|e| e.to_string())?);
+        active
+            .update(self.db_manager.get_connection())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        log_info!(
+            "Credentials",
+            "Started rotation script \"{}\" for credential {}",
+            run.script,
+            credential.name
+        );
+        Ok(Some(run.execution_id))
+    }
+
+    /// Link a custom script to run when the credential nears expiry, or unlink it.
+    pub async fn set_rotation_script(
+        &self,
+        credential_id: &str,
+        script_id: Option<i32>,
+    ) -> Result<CredentialModel, CredentialError> {
+        let credential = CredentialEntity::find_by_id(credential_id)
+            .one(self.db_manager.get_connection())
+            .await?
+            .ok_or_else(|| CredentialError::CredentialNotFound(credential_id.to_string()))?;
+
+        let mut metadata: HashMap<String, serde_json::Value> =
+            serde_json::from_str(&credential.metadata).unwrap_or_default();
+        match script_id {
+            Some(script_id) => {
+                metadata.insert(ROTATION_SCRIPT_KEY.to_string(), script_id.into());
+            }
+            None => {
+                metadata.remove(ROTATION_SCRIPT_KEY);
+            }
+        }
+        metadata.remove(ROTATION_RAN_FOR_KEY);
+
+        let mut active: CredentialActive = credential.into();
+        active.metadata = Set(serde_json::to_string(&metadata)?);
+        active.updated_at = Set(Utc::now().naive_utc());
+        Ok(active.update(self.db_manager.get_connection()).await?)
+    }
+}
+
+fn rotation_script_id(credential: &CredentialModel) -> Option<i32> {
+    let metadata: HashMap<String, serde_json::Value> =
+        serde_json::from_str(&credential.metadata).ok()?;
+    metadata
+        .get(ROTATION_SCRIPT_KEY)?
+        .as_i64()
+        .and_then(|id| i32::try_from(id).ok())
+}
+
+/// Whole days until `expires_at` (rounded down, negative once past) and whether
+/// it has passed.
+fn expiry_status(expires_at: NaiveDateTime, now: DateTime<Utc>) -> (i64, bool) {
+    let remaining = expires_at.and_utc() - now;
+    (
+        remaining.num_seconds().div_euclid(86_400),
+        remaining <= Duration::zero(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_days_left_and_expiry() {
+        let now = Utc::now();
+
+        let (days, expired) = expiry_status((now + Duration::hours(36)).naive_utc(), now);
+        assert_eq!((days, expired), (1, false));
+
+        let (days, expired) = expiry_status((now - Duration::hours(1)).naive_utc(), now);
+        assert_eq!((days, expired), (-1, true));
+    }
+}
@@ -1,7 +1,20 @@
 /**
  * Credentials Domain Services
  */
+pub mod audit_log;
+pub mod credential_import;
+pub mod credential_schema;
 pub mod credential_service;
+pub mod credential_transfer;
 pub mod encryption_service;
+pub mod expiry_monitor;
+pub mod project_env_service;
+pub mod ssh_keys;
+pub mod vault_session;
 
+pub use audit_log::{CredentialAccessor, CredentialAuditLog};
 pub use credential_service::CredentialService;
+pub use expiry_monitor::CredentialExpiryMonitor;
+pub use project_env_service::ProjectEnvService;
+pub use ssh_keys::SshKeyService;
+pub use vault_session::{vault_session, VaultStatus};
@@ -0,0 +1,331 @@
+/**
+ * Project Env Service - Maps credentials to per-project environment variables
+ *
+ * Each mapping names an env var and the credential (and optionally the field of
+ * a typed credential) that supplies it. The resolved values are written to the
+ * project's .env file, or handed straight to terminal sessions and pipeline runs
+ * so secrets never have to be copy-pasted.
+ */
+use super::audit_log::CredentialAccessor;
+use super::credential_service::CredentialService;
+use crate::database::DatabaseManager;
+use crate::domains::credentials::entities::project_env_mapping::{
+    ActiveModel as MappingActive, Column as MappingColumn, Entity as MappingEntity,
+    Model as MappingModel,
+};
+use crate::domains::projects::repositories::project_repository::ProjectRepository;
+use crate::log_warn;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvFileReport {
+    pub path: String,
+    /// Variables written or refreshed in the file
+    pub variables: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct ProjectEnvService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ProjectEnvService {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    pub async fn list_mappings(&self, project_id: i32) -> Result<Vec<MappingModel>, String> {
+        MappingEntity::find()
+            .filter(MappingColumn::ProjectId.eq(project_id))
+            .order_by_asc(MappingColumn::EnvVar)
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load env mappings: {}", e))
+    }
+
+    /// Map `env_var` in a project to a credential, replacing any existing mapping
+    /// for that variable. `field` picks a field of a typed credential; `None` uses
+    /// its primary secret.
+    pub async fn set_mapping(
+        &self,
+        project_id: i32,
+        env_var: &str,
+        credential_id: &str,
+        field: Option<String>,
+    ) -> Result<MappingModel, String> {
+        let env_var = env_var.trim();
+        if !is_valid_env_var(env_var) {
+            return Err(format!(
+                "\"{}\" is not a valid environment variable name",
+                env_var
+            ));
+        }
+
+        let credential = self.credentials().get_credential(credential_id).await?;
+        let field = field.filter(|f| !f.trim().is_empty());
+        if let Some(field) = &field {
+            let schema = CredentialService::kind_of(&credential).schema();
+            if schema.field(field).is_none() && !schema.allows_extra_fields {
+                return Err(format!("{} has no field named {}", schema.label, field));
+            }
+        }
+
+        let now = chrono::Utc::now().into();
+        let existing = MappingEntity::find()
+            .filter(MappingColumn::ProjectId.eq(project_id))
+            .filter(MappingColumn::EnvVar.eq(env_var))
+            .one(self.db_manager.get_connection())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let result = match existing {
+            Some(existing) => {
+                let mut active: MappingActive = existing.into();
+                active.credential_id = Set(credential.id);
+                active.field = Set(field);
+                active.updated_at = Set(Some(now));
+                active.update(self.db_manager.get_connection()).await
+            }
+            None => {
+                MappingActive {
+                    project_id: Set(project_id),
+                    env_var: Set(env_var.to_string()),
+                    credential_id: Set(credential.id),
+                    field: Set(field),
+                    created_at: Set(Some(now)),
+                    updated_at: Set(Some(now)),
+                    ..Default::default()
+                }
+                .insert(self.db_manager.get_connection())
+                .await
+            }
+        };
+        result.map_err(|e| format!("Failed to save env mapping: {}", e))
+    }
+
+    pub async fn remove_mapping(&self, id: i32) -> Result<(), String> {
+        MappingEntity::delete_by_id(id)
+            .exec(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to delete env mapping: {}", e))?;
+        Ok(())
+    }
+
+    /// Decrypt the project's mapped credentials into env var values, audited as
+    /// accessed by `accessor`. Projects without mappings never touch the vault, so
+    /// this is safe to call while it is locked.
+    pub async fn resolve_env(
+        &self,
+        project_id: i32,
+        accessor: CredentialAccessor,
+    ) -> Result<HashMap<String, String>, String> {
+        let mappings = self.list_mappings(project_id).await?;
+        if mappings.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let credentials = self.credentials();
+        let mut decrypted = HashMap::new();
+        let mut env = HashMap::new();
+        for mapping in mappings {
+            if !decrypted.contains_key(&mapping.credential_id) {
+                let credential = credentials
+                    .decrypt_credential_fields(&mapping.credential_id, accessor)
+                    .await?;
+                decrypted.insert(mapping.credential_id.clone(), credential);
+            }
+            let credential = &decrypted[&mapping.credential_id];
+            let field = mapping
+                .field
+                .clone()
+                .unwrap_or_else(|| credential.kind.schema().primary_field);
+
+            match credential.fields.get(&field) {
+                Some(value) => {
+                    env.insert(mapping.env_var, value.clone());
+                }
+                None => log_warn!(
+                    "Credentials",
+                    "Credential {} has no {} field for {}, skipping",
+                    mapping.credential_id,
+                    field,
+                    mapping.env_var
+                ),
+            }
+        }
+        Ok(env)
+    }
+
+    /// Write the project's mapped variables into `file_name` (default `.env`) in
+    /// the project directory. Mapped keys already in the file are updated in place;
+    /// everything else in the file is left alone.
+    pub async fn write_env_file(
+        &self,
+        project_id: i32,
+        file_name: Option<String>,
+    ) -> Result<EnvFileReport, String> {
+        let project = ProjectRepository::new(self.db_manager.clone())
+            .get_by_id(project_id)
+            .await?
+            .ok_or_else(|| "Project not found".to_string())?;
+        let path = env_file_path(&project.path, file_name.as_deref())?;
+
+        let values: BTreeMap<String, String> = self
+            .resolve_env(project_id, CredentialAccessor::EnvFile)
+            .await?
+            .into_iter()
+            .collect();
+        if values.is_empty() {
+            return Err(
+                "No credentials are mapped to environment variables for this project".into(),
+            );
+        }
+
+        let existing = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        std::fs::write(&path, merge_env_file(&existing, &values))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+        }
+
+        Ok(EnvFileReport {
+            path: path.to_string_lossy().to_string(),
+            variables: values.into_keys().collect(),
+        })
+    }
+
+    fn credentials(&self) -> CredentialService {
+        CredentialService::new(self.db_manager.get_connection_clone())
+    }
+}
+
+/// Env file inside the project directory. Only bare file names are accepted so a
+/// mapping can't write outside the project.
+fn env_file_path(project_path: &str, file_name: Option<&str>) -> Result<PathBuf, String> {
+    let file_name = file_name
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .unwrap_or(".env");
+    let is_bare = Path::new(file_name)
+        .file_name()
+        .is_some_and(|name| name == file_name);
+    if !is_bare || file_name == ".." {
+        return Err(format!("\"{}\" must be a file name, not a path", file_name));
+    }
+    Ok(Path::new(project_path).join(file_name))
+}
+
+fn is_valid_env_var(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Quote a value for a .env file when it contains anything beyond plain
+/// characters. Double-quoted values escape backslashes, quotes, `$` and newlines.
+fn format_env_value(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@+,%".contains(c));
+    if plain {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '$' => quoted.push_str("\\$"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Update `KEY=` lines (with or without `export`) for keys in `values` and append
+/// the keys the file doesn't have yet.
+fn merge_env_file(existing: &str, values: &BTreeMap<String, String>) -> String {
+    let mut written = std::collections::HashSet::new();
+    let mut lines: Vec<String> = existing
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let (export, rest) = match trimmed.strip_prefix("export ") {
+                Some(rest) => ("export ", rest.trim_start()),
+                None => ("", trimmed),
+            };
+            let key = rest.split('=').next().unwrap_or_default().trim();
+            match values.get(key) {
+                Some(value) if rest.contains('=') && !trimmed.starts_with('#') => {
+                    written.insert(key.to_string());
+                    format!("{}{}={}", export, key, format_env_value(value))
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect();
+
+    for (key, value) in values {
+        if !written.contains(key) {
+            lines.push(format!("{}={}", key, format_env_value(value)));
+        }
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_mapped_keys_into_existing_env_file() {
+        let values = BTreeMap::from([
+            ("API_KEY".to_string(), "new-key".to_string()),
+            (
+                "DATABASE_URL".to_string(),
+                "postgres://u:p w@db/app".to_string(),
+            ),
+        ]);
+        let existing = "# local settings\nDEBUG=true\nexport API_KEY=old\n";
+
+        assert_eq!(
+            merge_env_file(existing, &values),
+            "# local settings\nDEBUG=true\nexport API_KEY=new-key\nDATABASE_URL=\"postgres://u:p w@db/app\"\n"
+        );
+        assert_eq!(format_env_value("a\"b$c\nd"), "\"a\\\"b\\$c\\nd\"");
+    }
+
+    #[test]
+    fn rejects_bad_names_and_paths() {
+        assert!(is_valid_env_var("_TOKEN2"));
+        assert!(!is_valid_env_var("2TOKEN"));
+        assert!(!is_valid_env_var("MY-TOKEN"));
+
+        assert!(env_file_path("/p", None).unwrap().ends_with(".env"));
+        assert!(env_file_path("/p", Some(".env.local")).is_ok());
+        assert!(env_file_path("/p", Some("../.env")).is_err());
+        assert!(env_file_path("/p", Some("..")).is_err());
+    }
+}
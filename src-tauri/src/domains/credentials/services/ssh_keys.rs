@@ -0,0 +1,501 @@
+/**
+ * SSH Keys - Generates keypairs into the vault and loads them into ssh-agent
+ *
+ * Keys are `ssh_key` credentials: the private key (and passphrase, if any) is
+ * encrypted, the public key is kept in plain text so keys can be listed and
+ * matched against the agent without unlocking the vault. Keys are generated and
+ * encrypted in-process, and only written to disk briefly, in a private temp
+ * directory, while ssh-add needs them. SSH terminal sessions call
+ * `ensure_in_agent` before connecting.
+ */
+use super::super::CredentialError;
+use super::audit_log::CredentialAccessor;
+use super::credential_schema::CredentialKind;
+use super::credential_service::{CredentialService, TypedCredentialCreateRequest};
+use crate::domains::credentials::entities::{
+    Column as CredentialColumn, Entity as CredentialEntity, Model as CredentialModel,
+};
+use crate::process_ext::NoWindowExt;
+use base64::{engine::general_purpose, Engine as _};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ssh_key::private::{Ed25519Keypair, KeypairData, RsaKeypair};
+use ssh_key::rand_core::OsRng;
+use ssh_key::{LineEnding, PrivateKey};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SshKeyAlgorithm {
+    Ed25519,
+    Rsa,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerateSshKeyRequest {
+    pub name: String,
+    pub algorithm: SshKeyAlgorithm,
+    /// Defaults to the credential name
+    pub comment: Option<String>,
+    pub passphrase: Option<String>,
+    /// RSA key size, default 4096
+    pub bits: Option<u32>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshKeyInfo {
+    pub credential_id: String,
+    pub name: String,
+    pub public_key: Option<String>,
+    /// `SHA256:...`, as printed by `ssh-keygen -l`
+    pub fingerprint: Option<String>,
+    pub loaded: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentKey {
+    pub bits: Option<u32>,
+    pub fingerprint: String,
+    pub comment: String,
+    pub key_type: String,
+    /// Vault credential holding this key, if any
+    pub credential_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshAgentStatus {
+    pub available: bool,
+    /// Why the agent can't be reached, when it can't
+    pub error: Option<String>,
+    pub keys: Vec<AgentKey>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SshKeyService {
+    db: DatabaseConnection,
+    credentials: CredentialService,
+}
+
+impl SshKeyService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            credentials: CredentialService::new(db.clone()),
+            db,
+        }
+    }
+
+    /// Generate a keypair and store it in the vault
+    pub async fn generate(
+        &self,
+        request: GenerateSshKeyRequest,
+    ) -> Result<CredentialModel, CredentialError> {
+        let comment = request
+            .comment
+            .clone()
+            .unwrap_or_else(|| request.name.clone());
+        let passphrase = request.passphrase.clone().unwrap_or_default();
+        let bits = request.bits.unwrap_or(4096);
+        if request.algorithm == SshKeyAlgorithm::Rsa && bits < 2048 {
+            return Err(CredentialError::SshError(
+                "RSA keys must be at least 2048 bits".to_string(),
+            ));
+        }
+
+        // RSA generation takes a while; keep it off the async workers
+        let algorithm = request.algorithm;
+        let key_passphrase = passphrase.clone();
+        let (private_key, public_key) = tokio::task::spawn_blocking(move || {
+            generate_keypair(algorithm, bits, &comment, &key_passphrase)
+        })
+        .await
+        .map_err(|e| CredentialError::SshError(format!("Key generation failed: {}", e)))?
+        .map_err(key_error)?;
+
+        let mut fields = HashMap::from([
+            ("private_key".to_string(), private_key),
+            ("public_key".to_string(), public_key.trim().to_string()),
+        ]);
+        if !passphrase.is_empty() {
+            fields.insert("passphrase".to_string(), passphrase);
+        }
+
+        self.credentials
+            .create_typed_credential(TypedCredentialCreateRequest {
+                name: request.name,
+                kind: CredentialKind::SshKey,
+                description: request.description,
+                tags: Some(vec!["ssh".to_string()]),
+                fields,
+                metadata: Some(HashMap::from([(
+                    "ssh_key_algorithm".to_string(),
+                    serde_json::to_value(request.algorithm)?,
+                )])),
+                expires_at: None,
+            })
+            .await
+    }
+
+    /// SSH keys in the vault, with whether each is loaded in the agent
+    pub async fn list(&self) -> Result<Vec<SshKeyInfo>, CredentialError> {
+        let loaded: Vec<String> = self
+            .agent_status()
+            .await
+            .keys
+            .into_iter()
+            .map(|k| k.fingerprint)
+            .collect();
+
+        Ok(self
+            .ssh_credentials()
+            .await?
+            .into_iter()
+            .map(|credential| {
+                let public_key = public_key_of(&credential);
+                let fingerprint = public_key.as_deref().and_then(fingerprint);
+                SshKeyInfo {
+                    loaded: fingerprint.as_ref().is_some_and(|f| loaded.contains(f)),
+                    credential_id: credential.id,
+                    name: credential.name,
+                    public_key,
+                    fingerprint,
+                }
+            })
+            .collect())
+    }
+
+    /// Keys loaded in the running ssh-agent, matched to vault credentials
+    pub async fn agent_status(&self) -> SshAgentStatus {
+        let output = match ssh_command("ssh-add") {
+            Ok(mut command) => command.args(["-l", "-E", "sha256"]).output().await,
+            Err(e) => {
+                return SshAgentStatus {
+                    available: false,
+                    error: Some(e.to_string()),
+                    keys: Vec::new(),
+                }
+            }
+        };
+        let output = match output {
+            Ok(output) => output,
+            Err(e) => {
+                return SshAgentStatus {
+                    available: false,
+                    error: Some(format!("Failed to run ssh-add: {}", e)),
+                    keys: Vec::new(),
+                }
+            }
+        };
+
+        // ssh-add -l exits 1 when the agent has no keys and 2 when there is no agent
+        if output.status.code() == Some(2) {
+            return SshAgentStatus {
+                available: false,
+                error: Some(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+                keys: Vec::new(),
+            };
+        }
+
+        let by_fingerprint: HashMap<String, String> = self
+            .ssh_credentials()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|c| Some((fingerprint(&public_key_of(c)?)?, c.id.clone())))
+            .collect();
+        let keys = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(parse_agent_line)
+            .map(|mut key| {
+                key.credential_id = by_fingerprint.get(&key.fingerprint).cloned();
+                key
+            })
+            .collect();
+
+        SshAgentStatus {
+            available: true,
+            error: None,
+            keys,
+        }
+    }
+
+    /// Load a vault key into ssh-agent unless it is already there. `lifetime_seconds`
+    /// makes the agent forget it again after that long.
+    pub async fn ensure_in_agent(
+        &self,
+        credential_id: &str,
+        lifetime_seconds: Option<u32>,
+    ) -> Result<SshAgentStatus, CredentialError> {
+        let credential = self.credentials.get_credential(credential_id).await?;
+        let status = self.agent_status().await;
+        if !status.available {
+            return Err(CredentialError::SshError(format!(
+                "ssh-agent is not running: {}",
+                status.error.unwrap_or_default()
+            )));
+        }
+        let fingerprint = public_key_of(&credential).and_then(|k| fingerprint(&k));
+        if fingerprint.is_some_and(|f| status.keys.iter().any(|k| k.fingerprint == f)) {
+            return Ok(status);
+        }
+
+        let decrypted = self
+            .credentials
+            .decrypt_credential_fields(credential_id, CredentialAccessor::SshAgent)
+            .await?;
+        let private_key = decrypted.fields.get("private_key").ok_or_else(|| {
+            CredentialError::DecryptionFailed("Credential has no private key".to_string())
+        })?;
+
+        let dir = PrivateTempDir::create()?;
+        let key_path = dir.write("id", private_key)?;
+        let mut command = ssh_command("ssh-add")?;
+        if let Some(lifetime) = lifetime_seconds {
+            command.args(["-t", &lifetime.to_string()]);
+        }
+        command.arg(&key_path);
+
+        if let Some(passphrase) = decrypted.fields.get("passphrase").filter(|p| !p.is_empty()) {
+            supply_passphrase(&mut command, &dir, private_key, passphrase)?;
+        }
+        run(&mut command).await?;
+
+        Ok(self.agent_status().await)
+    }
+
+    /// Remove a vault key from ssh-agent
+    pub async fn remove_from_agent(
+        &self,
+        credential_id: &str,
+    ) -> Result<SshAgentStatus, CredentialError> {
+        let credential = self.credentials.get_credential(credential_id).await?;
+        let public_key = public_key_of(&credential).ok_or_else(|| {
+            CredentialError::DeserializationError(
+                "Credential has no public key to identify it in the agent".to_string(),
+            )
+        })?;
+
+        let dir = PrivateTempDir::create()?;
+        let key_path = dir.write("id.pub", &public_key)?;
+        run(ssh_command("ssh-add")?.arg("-d").arg(&key_path)).await?;
+
+        Ok(self.agent_status().await)
+    }
+
+    async fn ssh_credentials(&self) -> Result<Vec<CredentialModel>, CredentialError> {
+        Ok(CredentialEntity::find()
+            .filter(CredentialColumn::Kind.eq(CredentialKind::SshKey.as_str()))
+            .order_by_asc(CredentialColumn::Name)
+            .all(&self.db)
+            .await?)
+    }
+}
+
+/// Hand ssh-add the passphrase without putting it on a command line. On Unix an
+/// askpass script echoes it from ssh-add's own environment; on Windows, where
+/// askpass needs an executable, the temporary copy of the key is replaced with
+/// one decrypted in-process.
+fn supply_passphrase(
+    command: &mut Command,
+    dir: &PrivateTempDir,
+    private_key: &str,
+    passphrase: &str,
+) -> Result<(), CredentialError> {
+    if cfg!(unix) {
+        let askpass = dir.write(
+            "askpass.sh",
+            "#!/bin/sh\nprintf '%s\\n' \"$PORTAL_SSH_PASSPHRASE\"\n",
+        )?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&askpass, std::fs::Permissions::from_mode(0o700))?;
+        }
+        command
+            .env("SSH_ASKPASS", &askpass)
+            .env("SSH_ASKPASS_REQUIRE", "force")
+            .env(
+                "DISPLAY",
+                std::env::var("DISPLAY").unwrap_or_else(|_| ":0".into()),
+            )
+            .env("PORTAL_SSH_PASSPHRASE", passphrase)
+            .stdin(std::process::Stdio::null());
+    } else {
+        let decrypted = PrivateKey::from_openssh(private_key)
+            .and_then(|key| key.decrypt(passphrase))
+            .and_then(|key| key.to_openssh(LineEnding::LF))
+            .map_err(key_error)?;
+        dir.write("id", &decrypted)?;
+    }
+    Ok(())
+}
+
+/// An OpenSSH private key and public key line, the private key encrypted
+/// under `passphrase` unless it is empty
+fn generate_keypair(
+    algorithm: SshKeyAlgorithm,
+    bits: u32,
+    comment: &str,
+    passphrase: &str,
+) -> Result<(String, String), ssh_key::Error> {
+    let keypair = match algorithm {
+        SshKeyAlgorithm::Ed25519 => KeypairData::from(Ed25519Keypair::random(&mut OsRng)),
+        SshKeyAlgorithm::Rsa => KeypairData::from(RsaKeypair::random(&mut OsRng, bits as usize)?),
+    };
+    let mut key = PrivateKey::new(keypair, comment)?;
+    // Before encrypting, which moves the comment into the encrypted part
+    let public_key = key.public_key().to_openssh()?;
+    if !passphrase.is_empty() {
+        key = key.encrypt(&mut OsRng, passphrase)?;
+    }
+    Ok((key.to_openssh(LineEnding::LF)?.to_string(), public_key))
+}
+
+fn key_error(e: ssh_key::Error) -> CredentialError {
+    CredentialError::SshError(format!("SSH key error: {}", e))
+}
+
+fn ssh_command(program: &str) -> Result<Command, CredentialError> {
+    let path = which::which(program).map_err(|_| {
+        CredentialError::SshError(format!(
+            "{} was not found; install OpenSSH to manage SSH keys",
+            program
+        ))
+    })?;
+    let mut command = Command::new(path);
+    command.no_window();
+    Ok(command)
+}
+
+async fn run(command: &mut Command) -> Result<(), CredentialError> {
+    let output = command.output().await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(CredentialError::SshError(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+fn public_key_of(credential: &CredentialModel) -> Option<String> {
+    let public: HashMap<String, String> = serde_json::from_str(&credential.public_fields).ok()?;
+    public.get("public_key").cloned()
+}
+
+/// SHA256 fingerprint of an OpenSSH public key line
+fn fingerprint(public_key: &str) -> Option<String> {
+    let blob = general_purpose::STANDARD
+        .decode(public_key.split_whitespace().nth(1)?)
+        .ok()?;
+    Some(format!(
+        "SHA256:{}",
+        general_purpose::STANDARD_NO_PAD.encode(Sha256::digest(blob))
+    ))
+}
+
+/// One line of `ssh-add -l -E sha256`: `256 SHA256:abc comment words (ED25519)`
+fn parse_agent_line(line: &str) -> Option<AgentKey> {
+    let (bits, rest) = line.trim().split_once(' ')?;
+    let (fingerprint, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+    if !fingerprint.starts_with("SHA256:") {
+        return None;
+    }
+    let (comment, key_type) = match rest.rsplit_once(" (") {
+        Some((comment, key_type)) => (comment, key_type.trim_end_matches(')')),
+        None => ("", rest.trim_start_matches('(').trim_end_matches(')')),
+    };
+    Some(AgentKey {
+        bits: bits.parse().ok(),
+        fingerprint: fingerprint.to_string(),
+        comment: comment.to_string(),
+        key_type: key_type.to_string(),
+        credential_id: None,
+    })
+}
+
+/// Owner-only temp directory, removed with everything in it when dropped
+struct PrivateTempDir(PathBuf);
+
+impl PrivateTempDir {
+    fn create() -> Result<Self, CredentialError> {
+        let path = std::env::temp_dir().join(format!("portal-ssh-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(Self(path))
+    }
+
+    /// Write an owner-only file. Keys get a trailing newline, which ssh-add
+    /// requires for OpenSSH-format keys.
+    fn write(&self, name: &str, content: &str) -> Result<PathBuf, CredentialError> {
+        let path = self.0.join(name);
+        let mut content = content.to_string();
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        std::fs::write(&path, content)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(path)
+    }
+}
+
+impl Drop for PrivateTempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprints_public_keys_and_parses_agent_listing() {
+        let public_key = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIMxTSI68rxFWLzzlllkdQ+Wehx5g+9ICOxsikbQTajMv test@portal";
+        let expected = "SHA256:1Dd42abbVBwzcT5Q630GcfxfZm/Fg3U+3EqfJHA/1h4";
+        assert_eq!(fingerprint(public_key).as_deref(), Some(expected));
+
+        let key =
+            parse_agent_line(&format!("256 {} test@portal laptop (ED25519)", expected)).unwrap();
+        assert_eq!(key.bits, Some(256));
+        assert_eq!(key.fingerprint, expected);
+        assert_eq!(key.comment, "test@portal laptop");
+        assert_eq!(key.key_type, "ED25519");
+
+        assert!(parse_agent_line("The agent has no identities.").is_none());
+    }
+
+    #[test]
+    fn generates_passphrase_protected_keys_in_process() {
+        let (private_key, public_key) =
+            generate_keypair(SshKeyAlgorithm::Ed25519, 0, "test@portal", "secret").unwrap();
+        assert!(public_key.starts_with("ssh-ed25519 "));
+        assert!(public_key.ends_with(" test@portal"));
+
+        let key = PrivateKey::from_openssh(&private_key).unwrap();
+        assert!(key.is_encrypted());
+        assert!(key.decrypt("wrong").is_err());
+        let decrypted = key.decrypt("secret").unwrap();
+        assert_eq!(
+            fingerprint(&decrypted.public_key().to_openssh().unwrap()),
+            fingerprint(&public_key)
+        );
+
+        let (private_key, _) =
+            generate_keypair(SshKeyAlgorithm::Ed25519, 0, "test@portal", "").unwrap();
+        assert!(!PrivateKey::from_openssh(&private_key)
+            .unwrap()
+            .is_encrypted());
+    }
+}
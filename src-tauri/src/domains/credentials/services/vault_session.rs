@@ -0,0 +1,304 @@
+/**
+ * Vault Session - Keeps the keychain-held master key in memory while unlocked
+ *
+ * The master key lives in the OS keychain (macOS Keychain, Windows Credential
+ * Manager, Secret Service on Linux). Unlocking reads it into memory; locking, or
+ * going idle for longer than the auto-lock timeout, wipes the in-memory copy so
+ * credentials can't be decrypted until the vault is unlocked again.
+ */
+use super::super::CredentialError;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Event emitted when the vault locks because it sat idle
+pub const VAULT_LOCKED_EVENT: &str = "credentials-vault-locked";
+
+const KEY_NAME: &str = "credential-master-key-v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultStatus {
+    pub locked: bool,
+    /// 0 when auto-lock is off
+    pub auto_lock_minutes: u32,
+    /// Seconds of inactivity left before the vault locks itself
+    pub locks_in_seconds: Option<u64>,
+}
+
+struct SessionState {
+    key: Option<[u8; 32]>,
+    locked: bool,
+    last_activity: Instant,
+    auto_lock: Option<Duration>,
+}
+
+impl SessionState {
+    fn idle_expired(&self) -> bool {
+        !self.locked
+            && self
+                .auto_lock
+                .is_some_and(|timeout| self.last_activity.elapsed() >= timeout)
+    }
+
+    /// Fails while the vault can't hand out the key, wiping it if the session
+    /// went idle
+    fn ensure_open(&mut self) -> Result<(), CredentialError> {
+        if self.idle_expired() {
+            self.clear();
+            return Err(CredentialError::SessionExpired);
+        }
+        if self.locked {
+            return Err(CredentialError::VaultLocked);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        if let Some(key) = self.key.as_mut() {
+            key.fill(0);
+        }
+        self.key = None;
+        self.locked = true;
+    }
+}
+
+pub struct VaultSession {
+    state: Mutex<SessionState>,
+}
+
+impl VaultSession {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(SessionState {
+                key: None,
+                locked: false,
+                last_activity: Instant::now(),
+                auto_lock: None,
+            }),
+        }
+    }
+
+    /// Apply the saved security settings. Called once at startup.
+    pub fn configure(&self, auto_lock_minutes: u32, lock_on_startup: bool) {
+        self.set_auto_lock(auto_lock_minutes);
+        if lock_on_startup {
+            self.lock();
+        }
+    }
+
+    /// The master key, for encrypting or decrypting a credential. Counts as activity.
+    /// May read the keychain, so async callers run it with `spawn_blocking`.
+    pub fn master_key(&self) -> Result<[u8; 32], CredentialError> {
+        {
+            let mut state = self.lock_state();
+            state.ensure_open()?;
+            if let Some(key) = state.key {
+                state.last_activity = Instant::now();
+                return Ok(key);
+            }
+        }
+
+        // As in `unlock`, the keychain is read without holding the state, since
+        // it can block on an OS prompt
+        let key = load_or_create_master_key()?;
+        let mut state = self.lock_state();
+        state.ensure_open()?;
+        let key = *state.key.get_or_insert(key);
+        state.last_activity = Instant::now();
+        Ok(key)
+    }
+
+    /// The master key if the vault is unlocked, for background work. Unlike
+    /// `master_key` this doesn't count as activity, so it never keeps the vault
+    /// from auto-locking. May read the keychain, like `master_key`.
+    pub fn key_if_unlocked(&self) -> Option<[u8; 32]> {
+        {
+            let state = self.lock_state();
+            if state.locked || state.idle_expired() {
+                return None;
+            }
+            if let Some(key) = state.key {
+                return Some(key);
+            }
+        }
+
+        let key = load_or_create_master_key().ok()?;
+        let mut state = self.lock_state();
+        if state.locked || state.idle_expired() {
+            return None;
+        }
+        Some(*state.key.get_or_insert(key))
+    }
+
+    /// Read the master key from the keychain and keep it in memory.
+    pub fn unlock(&self) -> Result<VaultStatus, CredentialError> {
+        let key = load_or_create_master_key()?;
+        {
+            let mut state = self.lock_state();
+            state.key = Some(key);
+            state.locked = false;
+            state.last_activity = Instant::now();
+        }
+        Ok(self.status())
+    }
+
+    pub fn lock(&self) -> VaultStatus {
+        self.lock_state().clear();
+        self.status()
+    }
+
+    pub fn set_auto_lock(&self, minutes: u32) -> VaultStatus {
+        {
+            let mut state = self.lock_state();
+            state.auto_lock = (minutes > 0).then(|| Duration::from_secs(u64::from(minutes) * 60));
+            state.last_activity = Instant::now();
+        }
+        self.status()
+    }
+
+    /// Lock the vault if it has been idle past the timeout. Returns true when this
+    /// call locked it.
+    pub fn enforce_auto_lock(&self) -> bool {
+        let mut state = self.lock_state();
+        if state.idle_expired() {
+            state.clear();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn status(&self) -> VaultStatus {
+        let state = self.lock_state();
+        let locked = state.locked || state.idle_expired();
+        VaultStatus {
+            locked,
+            auto_lock_minutes: state
+                .auto_lock
+                .map_or(0, |timeout| (timeout.as_secs() / 60) as u32),
+            locks_in_seconds: state.auto_lock.filter(|_| !locked).map(|timeout| {
+                timeout
+                    .saturating_sub(state.last_activity.elapsed())
+                    .as_secs()
+            }),
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, SessionState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+static VAULT_SESSION: OnceLock<VaultSession> = OnceLock::new();
+
+pub fn vault_session() -> &'static VaultSession {
+    VAULT_SESSION.get_or_init(VaultSession::new)
+}
+
+/// Get the master encryption key from the OS keychain.
+///
+/// The key is a random 32 bytes stored in the OS keychain (Windows Credential
+/// Manager / macOS Keychain / Linux Secret Service), so it is protected by the
+/// user's OS login and is NOT derivable from anything on disk. Generated on first
+/// use and persisted thereafter.
+///
+/// Note: credentials encrypted under the previous path-derived key cannot be
+/// decrypted with this key and must be re-entered (pre-1.0 migration).
+fn load_or_create_master_key() -> Result<[u8; 32], CredentialError> {
+    const SERVICE: &str = crate::app_paths::APP_IDENTIFIER;
+
+    let entry = keyring::Entry::new(SERVICE, KEY_NAME)
+        .map_err(|e| CredentialError::EncryptionFailed(format!("Keychain access failed: {}", e)))?;
+
+    match entry.get_secret() {
+        Ok(bytes) if bytes.len() == 32 => {
+            let mut key = [0u8; 32];
+            key.copy_from_slice(&bytes);
+            Ok(key)
+        }
+        Ok(_) => Err(CredentialError::EncryptionFailed(
+            "Stored master key has unexpected length".to_string(),
+        )),
+        Err(keyring::Error::NoEntry) => {
+            // No entry under the current service name: either a genuine first run,
+            // or an install that predates the bundle-identifier rename. Carry the
+            // old key across when there is one, so existing credentials stay
+            // decryptable; otherwise mint a fresh random key.
+            let key = legacy_master_key().unwrap_or_else(|| {
+                use rand::RngCore;
+                let mut key = [0u8; 32];
+                rand::rngs::OsRng.fill_bytes(&mut key);
+                key
+            });
+            entry.set_secret(&key).map_err(|e| {
+                CredentialError::EncryptionFailed(format!(
+                    "Failed to store master key in keychain: {}",
+                    e
+                ))
+            })?;
+            Ok(key)
+        }
+        Err(e) => Err(CredentialError::EncryptionFailed(format!(
+            "Failed to read master key from keychain: {}",
+            e
+        ))),
+    }
+}
+
+/// Master key stored under the pre-rename bundle identifier, if one is there.
+/// Drop this once no pre-rename installs remain in the wild.
+fn legacy_master_key() -> Option<[u8; 32]> {
+    keyring::Entry::new(crate::app_paths::LEGACY_APP_IDENTIFIER, KEY_NAME)
+        .ok()?
+        .get_secret()
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_session_locks_and_clears_key() {
+        let session = VaultSession::new();
+        {
+            let mut state = session.lock_state();
+            state.key = Some([7u8; 32]);
+            state.auto_lock = Some(Duration::from_secs(60));
+            state.last_activity = Instant::now() - Duration::from_secs(61);
+        }
+
+        assert!(session.status().locked);
+        assert!(matches!(
+            session.master_key(),
+            Err(CredentialError::SessionExpired)
+        ));
+        assert!(session.lock_state().key.is_none());
+        assert!(matches!(
+            session.master_key(),
+            Err(CredentialError::VaultLocked)
+        ));
+    }
+
+    #[test]
+    fn active_session_reports_time_left() {
+        let session = VaultSession::new();
+        {
+            let mut state = session.lock_state();
+            state.key = Some([7u8; 32]);
+        }
+        session.set_auto_lock(5);
+
+        assert_eq!(session.master_key().unwrap(), [7u8; 32]);
+        let status = session.status();
+        assert!(!status.locked);
+        assert_eq!(status.auto_lock_minutes, 5);
+        assert!(status.locks_in_seconds.unwrap() > 290);
+        assert!(!session.enforce_auto_lock());
+
+        assert!(session.lock().locked);
+        assert_eq!(session.status().locks_in_seconds, None);
+    }
+}
@@ -0,0 +1,100 @@
+//! Custom script tools for the AI assistant, workflows and scheduled jobs
+use super::services::CustomScriptService;
+use crate::database::DatabaseManager;
+use crate::domains::ai::tools::{ActionPreview, PreviewMethod, ToolRegistry, ToolSpec};
+use crate::domains::scripts::commands::ScriptExecutionState;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::Manager;
+
+pub fn register_ai_tools(registry: &ToolRegistry) {
+    registry.register(
+        ToolSpec {
+            name: "run_custom_script".to_string(),
+            description: "Start a saved custom script with arguments for its declared \
+                          parameters; returns the script execution id. Scripts that need sudo \
+                          or user input can't be run this way."
+                .to_string(),
+            domain: "custom_scripts".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "script_id": { "type": "integer" },
+                    "parameters": { "type": "object", "additionalProperties": { "type": "string" } },
+                    "working_directory": { "type": "string" },
+                    "triggered_by": {
+                        "type": "string",
+                        "description": "What started the run, recorded in its history"
+                    }
+                },
+                "required": ["script_id"]
+            }),
+        },
+        |app, args: Value| async move {
+            let (script_id, parameters) = script_arguments(&args)?;
+
+            let db_manager = app.state::<Arc<DatabaseManager>>().inner().clone();
+            let execution = app
+                .state::<ScriptExecutionState>()
+                .get_or_init(&db_manager)
+                .await;
+            let run = CustomScriptService::new(&db_manager)
+                .run_script(
+                    &execution,
+                    script_id,
+                    parameters,
+                    args.get("working_directory")
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                    args.get("triggered_by")
+                        .and_then(Value::as_str)
+                        .unwrap_or("automation"),
+                )
+                .await?;
+            serde_json::to_value(run).map_err(|e| e.to_string())
+        },
+    );
+    registry.register_preview("run_custom_script", |app, args: Value| async move {
+        let (script_id, parameters) = script_arguments(&args)?;
+        let db_manager = app.state::<Arc<DatabaseManager>>().inner().clone();
+        let execution = app
+            .state::<ScriptExecutionState>()
+            .get_or_init(&db_manager)
+            .await;
+        let preview = CustomScriptService::new(&db_manager)
+            .preview_run(
+                &execution,
+                script_id,
+                parameters,
+                args.get("working_directory")
+                    .and_then(Value::as_str)
+                    .map(String::from),
+            )
+            .await?;
+        Ok(ActionPreview {
+            method: if preview.dry_run {
+                PreviewMethod::DryRun
+            } else {
+                PreviewMethod::Plan
+            },
+            summary: format!("Run script \"{}\"", preview.script),
+            details: Some(preview.details),
+        })
+    });
+}
+
+fn script_arguments(args: &Value) -> Result<(i32, HashMap<String, String>), String> {
+    let script_id = args
+        .get("script_id")
+        .and_then(Value::as_i64)
+        .ok_or("script_id is required")? as i32;
+    let parameters = args
+        .get("parameters")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("Invalid parameters: {}", e))?
+        .unwrap_or_default();
+    Ok((script_id, parameters))
+}
@@ -1,19 +1,24 @@
 use crate::database::DatabaseManager;
-use crate::domains::custom_scripts::services::CustomScriptService;
-use serde::{Deserialize, Serialize};
+use crate::domains::automation::services::scheduler::{
+    CreateScheduledJobRequest, MisfirePolicy, Schedule, ScheduledJob, Scheduler,
+};
+use crate::domains::custom_scripts::entities::script_library::Model as ScriptLibrary;
+use crate::domains::custom_scripts::services::script_library::{
+    ConflictResolution, LibraryUpdateStatus, NewScriptLibrary, ScriptLibraryService,
+    ScriptSyncState, SyncReport,
+};
+use crate::domains::custom_scripts::services::script_parameters::ScriptParameter;
+use crate::domains::custom_scripts::services::script_triggers::EventTrigger;
+use crate::domains::custom_scripts::services::{CustomScriptService, ScriptRun};
+use crate::domains::scripts::commands::ScriptExecutionState;
+use crate::domains::scripts::services::ScriptExecutionInfo;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::command;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScriptParameter {
-    pub name: String,
-    pub label: String,
-    pub parameter_type: String, // "file", "string", "number", "boolean", "password"
-    pub required: bool,
-    pub default_value: Option<String>,
-    pub description: Option<String>,
-    pub file_filters: Option<Vec<String>>, // For file type: e.g., ["*.ovpn", "*.txt"]
-}
+/// Action scheduled script runs invoke
+const RUN_SCRIPT_ACTION: &str = "run_custom_script";
 
 #[command]
 pub async fn get_all_custom_scripts(
@@ -88,13 +93,19 @@ pub async fn update_custom_script(
         .await
 }
 
+/// Delete a script along with its schedules
 #[command]
 pub async fn delete_custom_script(
     id: i32,
     db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+    scheduler: tauri::State<'_, Arc<Scheduler>>,
 ) -> Result<(), String> {
     let service = CustomScriptService::new(&db_manager);
-    service.delete_script(id).await
+    service.delete_script(id).await?;
+    for job in script_schedules(&scheduler, id).await? {
+        scheduler.delete_job(job.id).await?;
+    }
+    Ok(())
 }
 
 #[command]
@@ -106,6 +117,228 @@ pub async fn record_script_run(
     service.record_script_run(id).await
 }
 
+/// A script's parameters, typed, for rendering its form
+#[command]
+pub async fn get_custom_script_parameters(
+    id: i32,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<ScriptParameter>, String> {
+    let service = CustomScriptService::new(&db_manager);
+    service.get_parameters(id).await
+}
+
+/// Run a script with its form values. Booleans and numbers may be sent as
+/// JSON values; everything is checked against the script's parameters.
+#[command]
+pub async fn run_custom_script(
+    id: i32,
+    arguments: Option<HashMap<String, Value>>,
+    working_directory: Option<String>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+    execution_state: tauri::State<'_, ScriptExecutionState>,
+) -> Result<ScriptRun, String> {
+    let arguments = arguments
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                Value::Null => return None,
+                Value::String(text) => text,
+                other => other.to_string(),
+            };
+            Some((name, value))
+        })
+        .collect();
+    let execution = execution_state.get_or_init(&db_manager).await;
+    let service = CustomScriptService::new(&db_manager);
+    service
+        .run_script(&execution, id, arguments, working_directory, "user")
+        .await
+}
+
+/// A script's runs, newest first: status, exit code, output and what
+/// started each one
+#[command]
+pub async fn get_custom_script_runs(
+    id: i32,
+    limit: Option<u64>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+    execution_state: tauri::State<'_, ScriptExecutionState>,
+) -> Result<Vec<ScriptExecutionInfo>, String> {
+    let execution = execution_state.get_or_init(&db_manager).await;
+    let service = CustomScriptService::new(&db_manager);
+    service
+        .get_runs(&execution, id, Some(limit.unwrap_or(50)))
+        .await
+}
+
+#[command]
+pub async fn get_custom_script_event_triggers(
+    id: i32,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<EventTrigger>, String> {
+    let service = CustomScriptService::new(&db_manager);
+    service.get_event_triggers(id).await
+}
+
+/// Replace the app events that run a script
+#[command]
+pub async fn set_custom_script_event_triggers(
+    id: i32,
+    triggers: Vec<EventTrigger>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<crate::entities::custom_script::Model, String> {
+    let service = CustomScriptService::new(&db_manager);
+    service.set_event_triggers(id, triggers).await
+}
+
+/// Choose what runs a script: shell, bash, powershell, python or node, with
+/// a version prefix for python and node
+#[command]
+pub async fn set_custom_script_runtime(
+    id: i32,
+    runtime: String,
+    runtime_version: Option<String>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<crate::entities::custom_script::Model, String> {
+    let service = CustomScriptService::new(&db_manager);
+    service.set_runtime(id, &runtime, runtime_version).await
+}
+
+/// Run a script on a schedule with fixed arguments
+#[command]
+pub async fn schedule_custom_script(
+    id: i32,
+    schedule: Schedule,
+    misfire_policy: Option<MisfirePolicy>,
+    arguments: Option<HashMap<String, String>>,
+    working_directory: Option<String>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+    scheduler: tauri::State<'_, Arc<Scheduler>>,
+) -> Result<ScheduledJob, String> {
+    let service = CustomScriptService::new(&db_manager);
+    let script = service
+        .get_script(id)
+        .await?
+        .ok_or_else(|| format!("Custom script {} not found", id))?;
+    if script.requires_sudo || script.is_interactive {
+        return Err(format!(
+            "\"{}\" needs sudo or user input, so it can't run on a schedule",
+            script.name
+        ));
+    }
+
+    scheduler
+        .create_job(CreateScheduledJobRequest {
+            name: format!("Run {}", script.name),
+            action: RUN_SCRIPT_ACTION.to_string(),
+            args: json!({
+                "script_id": id,
+                "parameters": arguments.unwrap_or_default(),
+                "working_directory": working_directory,
+                "triggered_by": "schedule",
+            }),
+            schedule,
+            misfire_policy,
+        })
+        .await
+}
+
+/// Scheduled jobs that run a script
+#[command]
+pub async fn list_custom_script_schedules(
+    id: i32,
+    scheduler: tauri::State<'_, Arc<Scheduler>>,
+) -> Result<Vec<ScheduledJob>, String> {
+    script_schedules(&scheduler, id).await
+}
+
+async fn script_schedules(scheduler: &Scheduler, id: i32) -> Result<Vec<ScheduledJob>, String> {
+    Ok(scheduler
+        .list_jobs()
+        .await?
+        .into_iter()
+        .filter(|job| {
+            job.action == RUN_SCRIPT_ACTION
+                && job.args.get("script_id").and_then(Value::as_i64) == Some(id as i64)
+        })
+        .collect())
+}
+
+#[command]
+pub async fn list_script_libraries(
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<ScriptLibrary>, String> {
+    ScriptLibraryService::new(db_manager.inner().clone())
+        .list()
+        .await
+}
+
+/// Add a git repository as a script library and pull its scripts
+#[command]
+pub async fn add_script_library(
+    library: NewScriptLibrary,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<SyncReport, String> {
+    let service = ScriptLibraryService::new(db_manager.inner().clone());
+    let library = service.add(library).await?;
+    service.sync(library.id).await
+}
+
+/// Remove a library; its scripts are deleted unless `keep_scripts`, which
+/// keeps them as local scripts
+#[command]
+pub async fn remove_script_library(
+    id: i32,
+    keep_scripts: Option<bool>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    ScriptLibraryService::new(db_manager.inner().clone())
+        .remove(id, keep_scripts.unwrap_or(false))
+        .await
+}
+
+#[command]
+pub async fn sync_script_library(
+    id: i32,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<SyncReport, String> {
+    ScriptLibraryService::new(db_manager.inner().clone())
+        .sync(id)
+        .await
+}
+
+#[command]
+pub async fn check_script_library_updates(
+    id: i32,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<LibraryUpdateStatus, String> {
+    ScriptLibraryService::new(db_manager.inner().clone())
+        .check_updates(id)
+        .await
+}
+
+/// Whether each script is local, synced, locally modified or in conflict
+#[command]
+pub async fn get_custom_script_sync_states(
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<ScriptSyncState>, String> {
+    ScriptLibraryService::new(db_manager.inner().clone())
+        .sync_states()
+        .await
+}
+
+#[command]
+pub async fn resolve_custom_script_conflict(
+    id: i32,
+    resolution: ConflictResolution,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<crate::entities::custom_script::Model, String> {
+    ScriptLibraryService::new(db_manager.inner().clone())
+        .resolve_conflict(id, resolution)
+        .await
+}
+
 #[command]
 pub async fn select_file(
     app_handle: tauri::AppHandle,
@@ -1,4 +1,6 @@
+pub mod ai_tools;
 pub mod commands;
+pub mod entities;
 pub mod repositories;
 pub mod services;
 
@@ -59,6 +59,7 @@ impl CustomScriptRepository {
             updated_at: Set(None),
             last_run_at: Set(None),
             run_count: Set(0),
+            event_triggers_json: Set("[]".to_string()),
             ..Default::default()
         };
 
@@ -123,6 +124,52 @@ impl CustomScriptRepository {
         Ok(result)
     }
 
+    pub async fn set_event_triggers(
+        &self,
+        id: i32,
+        event_triggers_json: String,
+    ) -> Result<CustomScriptModel, String> {
+        let connection = self.db_manager.get_connection();
+
+        let mut script: CustomScriptActiveModel = CustomScriptEntity::find_by_id(id)
+            .one(connection)
+            .await
+            .map_err(|e| format!("Failed to find custom script: {}", e))?
+            .ok_or_else(|| "Custom script not found".to_string())?
+            .into();
+        script.event_triggers_json = Set(event_triggers_json);
+        script.updated_at = Set(Some(chrono::Utc::now().into()));
+
+        script
+            .update(connection)
+            .await
+            .map_err(|e| format!("Failed to update custom script triggers: {}", e))
+    }
+
+    pub async fn set_runtime(
+        &self,
+        id: i32,
+        runtime: String,
+        runtime_version: Option<String>,
+    ) -> Result<CustomScriptModel, String> {
+        let connection = self.db_manager.get_connection();
+
+        let mut script: CustomScriptActiveModel = CustomScriptEntity::find_by_id(id)
+            .one(connection)
+            .await
+            .map_err(|e| format!("Failed to find custom script: {}", e))?
+            .ok_or_else(|| "Custom script not found".to_string())?
+            .into();
+        script.runtime = Set(runtime);
+        script.runtime_version = Set(runtime_version);
+        script.updated_at = Set(Some(chrono::Utc::now().into()));
+
+        script
+            .update(connection)
+            .await
+            .map_err(|e| format!("Failed to update custom script runtime: {}", e))
+    }
+
     pub async fn delete(&self, id: i32) -> Result<(), String> {
         let connection = self.db_manager.get_connection();
         CustomScriptEntity::delete_by_id(id)
@@ -1,16 +1,55 @@
+use super::script_parameters::{
+    parse_parameters, resolve_arguments, ParameterType, ScriptParameter,
+};
+use super::script_runtime::{resolve_interpreter, ScriptRuntime};
+use super::script_triggers::{parse_triggers, EventTrigger};
 use crate::database::DatabaseManager;
 use crate::domains::custom_scripts::repositories::CustomScriptRepository;
+use crate::domains::scripts::services::{
+    ExecuteScriptRequest, ScriptExecutionInfo, ScriptExecutionService,
+};
 use crate::entities::custom_script::Model as CustomScriptModel;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Boolean parameter a script declares to say it can run without making
+/// changes
+pub const DRY_RUN_PARAMETER: &str = "dry_run";
+/// How long a dry run may take before its preview gives up on it
+const DRY_RUN_TIMEOUT: Duration = Duration::from_secs(30);
+/// Lines of dry-run output kept in a preview
+const DRY_RUN_OUTPUT_LINES: usize = 60;
+
+/// What a run of a script would do
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptPreview {
+    pub script: String,
+    /// Whether the script was actually run with `dry_run` set; otherwise the
+    /// details are the command and arguments a run would use
+    pub dry_run: bool,
+    pub details: String,
+}
+
+/// A started custom script run
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptRun {
+    pub execution_id: String,
+    pub script_id: i32,
+    pub script: String,
+}
 
 pub struct CustomScriptService {
     repository: CustomScriptRepository,
+    db_manager: Arc<DatabaseManager>,
 }
 
 impl CustomScriptService {
     pub fn new(db_manager: &Arc<DatabaseManager>) -> Self {
         Self {
             repository: CustomScriptRepository::new(db_manager.clone()),
+            db_manager: db_manager.clone(),
         }
     }
 
@@ -33,6 +72,7 @@ impl CustomScriptService {
         requires_sudo: bool,
         is_interactive: bool,
     ) -> Result<CustomScriptModel, String> {
+        parse_parameters(&parameters_json)?;
         self.repository
             .create(
                 name,
@@ -59,6 +99,9 @@ impl CustomScriptService {
         requires_sudo: Option<bool>,
         is_interactive: Option<bool>,
     ) -> Result<CustomScriptModel, String> {
+        if let Some(parameters_json) = &parameters_json {
+            parse_parameters(parameters_json)?;
+        }
         self.repository
             .update(
                 id,
@@ -81,4 +124,211 @@ impl CustomScriptService {
     pub async fn record_script_run(&self, id: i32) -> Result<CustomScriptModel, String> {
         self.repository.increment_run_count(id).await
     }
+
+    pub async fn get_parameters(&self, id: i32) -> Result<Vec<ScriptParameter>, String> {
+        let script = self.require_script(id).await?;
+        parse_parameters(&script.parameters_json)
+    }
+
+    pub async fn get_event_triggers(&self, id: i32) -> Result<Vec<EventTrigger>, String> {
+        let script = self.require_script(id).await?;
+        parse_triggers(&script.event_triggers_json)
+    }
+
+    pub async fn set_event_triggers(
+        &self,
+        id: i32,
+        triggers: Vec<EventTrigger>,
+    ) -> Result<CustomScriptModel, String> {
+        let event_triggers_json = serde_json::to_string(&triggers)
+            .map_err(|e| format!("Failed to serialize event triggers: {}", e))?;
+        parse_triggers(&event_triggers_json)?;
+        self.repository
+            .set_event_triggers(id, event_triggers_json)
+            .await
+    }
+
+    /// Set what runs a script. The version only applies to Python and Node,
+    /// as a prefix of an SDK version ("3.12", "20").
+    pub async fn set_runtime(
+        &self,
+        id: i32,
+        runtime: &str,
+        runtime_version: Option<String>,
+    ) -> Result<CustomScriptModel, String> {
+        let runtime = ScriptRuntime::parse(runtime)?;
+        let runtime_version = runtime_version
+            .map(|version| version.trim().to_string())
+            .filter(|version| !version.is_empty());
+        if runtime_version.is_some()
+            && !matches!(runtime, ScriptRuntime::Python | ScriptRuntime::Node)
+        {
+            return Err(format!(
+                "Only python and node runtimes take a version, not {}",
+                runtime.as_str()
+            ));
+        }
+        self.repository
+            .set_runtime(id, runtime.as_str().to_string(), runtime_version)
+            .await
+    }
+
+    /// A script's runs, newest first
+    pub async fn get_runs(
+        &self,
+        execution: &ScriptExecutionService,
+        id: i32,
+        limit: Option<u64>,
+    ) -> Result<Vec<ScriptExecutionInfo>, String> {
+        execution.get_executions_by_custom_script(id, limit).await
+    }
+
+    /// Start a script with its arguments checked against its parameters.
+    /// The resolved arguments are recorded with the run, secrets masked, and
+    /// `triggered_by` says what started it (`user`, `schedule`,
+    /// `event:<type>`). Scripts that need sudo or user input belong in a
+    /// terminal instead. Scripts with a Python or Node runtime may install it
+    /// first.
+    pub async fn run_script(
+        &self,
+        execution: &ScriptExecutionService,
+        id: i32,
+        arguments: HashMap<String, String>,
+        working_directory: Option<String>,
+        triggered_by: &str,
+    ) -> Result<ScriptRun, String> {
+        let script = self.require_script(id).await?;
+        check_unattended(&script)?;
+
+        let declared = parse_parameters(&script.parameters_json)?;
+        let arguments =
+            resolve_arguments(&declared, arguments, self.db_manager.get_connection()).await?;
+        let runtime = ScriptRuntime::parse(&script.runtime)?;
+        let interpreter = resolve_interpreter(runtime, script.runtime_version.as_deref()).await?;
+        let execution_id = execution
+            .execute_script(ExecuteScriptRequest {
+                block_id: None,
+                command: script.command.clone(),
+                parameters: arguments.values,
+                working_directory,
+                secret_parameters: arguments.secrets,
+                custom_script_id: Some(id),
+                triggered_by: Some(triggered_by.to_string()),
+                interpreter,
+            })
+            .await?;
+        let _ = self.record_script_run(id).await;
+
+        Ok(ScriptRun {
+            execution_id,
+            script_id: id,
+            script: script.name,
+        })
+    }
+
+    /// Work out what `run_script` would do with these arguments. A script
+    /// declaring a boolean `dry_run` parameter is run with it set to true and
+    /// its output returned; any other script isn't run, and the preview lists
+    /// the command and the arguments it would get, secrets masked.
+    pub async fn preview_run(
+        &self,
+        execution: &ScriptExecutionService,
+        id: i32,
+        mut arguments: HashMap<String, String>,
+        working_directory: Option<String>,
+    ) -> Result<ScriptPreview, String> {
+        let script = self.require_script(id).await?;
+        check_unattended(&script)?;
+        let declared = parse_parameters(&script.parameters_json)?;
+        let supports_dry_run = declared.iter().any(|parameter| {
+            parameter.name == DRY_RUN_PARAMETER
+                && parameter.parameter_type == ParameterType::Boolean
+        });
+
+        if !supports_dry_run {
+            let resolved =
+                resolve_arguments(&declared, arguments, self.db_manager.get_connection()).await?;
+            let mut names: Vec<&String> = resolved.values.keys().collect();
+            names.sort();
+            let mut details = format!("Runtime: {}\n", script.runtime);
+            if let Some(directory) = &working_directory {
+                details.push_str(&format!("Working directory: {}\n", directory));
+            }
+            details.push_str(&format!("Command:\n{}", script.command));
+            if !names.is_empty() {
+                details.push_str("\nArguments:");
+                for name in names {
+                    let value = if resolved.secrets.contains(name) {
+                        "(secret)"
+                    } else {
+                        resolved.values[name].as_str()
+                    };
+                    details.push_str(&format!("\n  {} = {}", name, value));
+                }
+            }
+            return Ok(ScriptPreview {
+                script: script.name,
+                dry_run: false,
+                details,
+            });
+        }
+
+        arguments.insert(DRY_RUN_PARAMETER.to_string(), "true".to_string());
+        let run = self
+            .run_script(execution, id, arguments, working_directory, "dry_run")
+            .await?;
+        let deadline = tokio::time::Instant::now() + DRY_RUN_TIMEOUT;
+        let finished = loop {
+            let info = execution
+                .get_execution(&run.execution_id)
+                .await?
+                .ok_or("Dry run execution disappeared")?;
+            if !matches!(info.status.as_str(), "pending" | "running") {
+                break info;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                let _ = execution.cancel_execution(&run.execution_id).await;
+                return Err(format!(
+                    "Dry run of \"{}\" didn't finish within {} seconds",
+                    script.name,
+                    DRY_RUN_TIMEOUT.as_secs()
+                ));
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        };
+
+        let lines: Vec<&str> = finished.output.lines().collect();
+        let mut details = format!("Dry run {}", finished.status);
+        if let Some(code) = finished.exit_code {
+            details.push_str(&format!(" (exit {})", code));
+        }
+        if let Some(error) = &finished.error {
+            details.push_str(&format!(": {}", error));
+        }
+        for line in &lines[lines.len().saturating_sub(DRY_RUN_OUTPUT_LINES)..] {
+            details.push('\n');
+            details.push_str(line);
+        }
+        Ok(ScriptPreview {
+            script: script.name,
+            dry_run: true,
+            details,
+        })
+    }
+
+    async fn require_script(&self, id: i32) -> Result<CustomScriptModel, String> {
+        self.get_script(id)
+            .await?
+            .ok_or_else(|| format!("Custom script {} not found", id))
+    }
+}
+
+fn check_unattended(script: &CustomScriptModel) -> Result<(), String> {
+    if script.requires_sudo || script.is_interactive {
+        return Err(format!(
+            "\"{}\" needs sudo or user input, so it can't run unattended",
+            script.name
+        ));
+    }
+    Ok(())
 }
@@ -1,3 +1,7 @@
 pub mod custom_script_service;
+pub mod script_library;
+pub mod script_parameters;
+pub mod script_runtime;
+pub mod script_triggers;
 
 pub use custom_script_service::*;
@@ -0,0 +1,737 @@
+//! Custom scripts synced from a team's git repository.
+//!
+//! A team can keep its scripts in a git repository and point the app at it.
+//! The repository holds a manifest (`portal-scripts.yaml` by default, YAML or
+//! JSON) listing each script with a stable `id`; syncing clones or fetches the
+//! repository into `~/.portal-desktop/script-libraries/<id>` and creates or
+//! updates a custom script per entry.
+//!
+//! Synced scripts remember the manifest entry they were last synced from, so
+//! local edits can be told apart from upstream changes:
+//! - edited upstream only: the script takes the upstream version
+//! - edited locally only: the local edits are kept
+//! - edited on both sides: the script is left as it is and the upstream
+//!   version is held as pending until the conflict is resolved
+//!
+//! Scripts removed from the manifest are deleted, unless they were edited
+//! locally; those are kept as local scripts.
+
+use super::script_parameters::parse_parameters;
+use super::script_runtime::ScriptRuntime;
+use crate::database::DatabaseManager;
+use crate::domains::custom_scripts::entities::script_library::{
+    ActiveModel as LibraryActive, Entity as LibraryEntity, Model as ScriptLibrary,
+};
+use crate::entities::custom_script::{
+    ActiveModel as CustomScriptActive, Column as CustomScriptColumn, Entity as CustomScriptEntity,
+    Model as CustomScriptModel,
+};
+use crate::process_ext::NoWindowExt;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::process::Command;
+
+const DEFAULT_MANIFEST: &str = "portal-scripts.yaml";
+
+/// The parts of a script a library manages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptDefinition {
+    pub name: String,
+    pub description: Option<String>,
+    pub command: String,
+    /// Parameter declarations, as in `parameters_json`
+    #[serde(default)]
+    pub parameters: Vec<Value>,
+    pub category: Option<String>,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub requires_sudo: bool,
+    #[serde(default)]
+    pub is_interactive: bool,
+    #[serde(default)]
+    pub runtime: ScriptRuntime,
+    #[serde(default)]
+    pub runtime_version: Option<String>,
+}
+
+impl ScriptDefinition {
+    fn of(script: &CustomScriptModel) -> Self {
+        Self {
+            name: script.name.clone(),
+            description: script.description.clone(),
+            command: script.command.clone(),
+            parameters: serde_json::from_str(&script.parameters_json).unwrap_or_default(),
+            category: script.category.clone(),
+            icon: script.icon.clone(),
+            requires_sudo: script.requires_sudo,
+            is_interactive: script.is_interactive,
+            runtime: ScriptRuntime::parse(&script.runtime).unwrap_or_default(),
+            runtime_version: script.runtime_version.clone(),
+        }
+    }
+
+    fn apply(&self, script: &mut CustomScriptActive) {
+        script.name = Set(self.name.clone());
+        script.description = Set(self.description.clone());
+        script.command = Set(self.command.clone());
+        script.parameters_json =
+            Set(serde_json::to_string(&self.parameters).unwrap_or_else(|_| "[]".to_string()));
+        script.category = Set(self.category.clone());
+        script.icon = Set(self.icon.clone());
+        script.requires_sudo = Set(self.requires_sudo);
+        script.is_interactive = Set(self.is_interactive);
+        script.runtime = Set(self.runtime.as_str().to_string());
+        script.runtime_version = Set(self.runtime_version.clone());
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    scripts: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestEntry {
+    /// Stable key of the script within the library
+    id: String,
+    #[serde(flatten)]
+    definition: ManifestDefinition,
+}
+
+/// A manifest entry gives its command inline or as a file in the repository
+#[derive(Debug, Deserialize)]
+struct ManifestDefinition {
+    name: String,
+    description: Option<String>,
+    command: Option<String>,
+    file: Option<String>,
+    #[serde(default)]
+    parameters: Vec<Value>,
+    category: Option<String>,
+    icon: Option<String>,
+    #[serde(default)]
+    requires_sudo: bool,
+    #[serde(default)]
+    is_interactive: bool,
+    runtime: Option<String>,
+    runtime_version: Option<String>,
+}
+
+/// What a sync does with a script that exists on both sides
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyncAction {
+    /// Nothing changed upstream, or both sides made the same change
+    Unchanged,
+    /// Take the upstream version
+    Update,
+    /// Changed on both sides; hold the upstream version back
+    Conflict,
+}
+
+/// Compare a script against the version it was last synced from and the
+/// current upstream version
+pub fn plan_sync(
+    base: Option<&ScriptDefinition>,
+    local: &ScriptDefinition,
+    upstream: &ScriptDefinition,
+) -> SyncAction {
+    if local == upstream {
+        return SyncAction::Unchanged;
+    }
+    match base {
+        Some(base) if base == upstream => SyncAction::Unchanged,
+        Some(base) if base == local => SyncAction::Update,
+        // Without a base there's no telling who changed it
+        _ => SyncAction::Conflict,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewScriptLibrary {
+    pub name: String,
+    pub repository_url: String,
+    pub branch: Option<String>,
+    pub manifest_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    pub library_id: i32,
+    pub commit: Option<String>,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub conflicts: Vec<String>,
+    pub removed: Vec<String>,
+    /// Removed upstream but edited locally, so kept as local scripts
+    pub detached: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryUpdateStatus {
+    pub library_id: i32,
+    pub current_commit: Option<String>,
+    pub remote_commit: String,
+    pub update_available: bool,
+}
+
+/// Where a script stands with its library
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptSyncState {
+    pub script_id: i32,
+    pub library_id: Option<i32>,
+    /// local, synced, modified or conflict
+    pub status: String,
+    /// The upstream version of a conflicting script
+    pub upstream: Option<ScriptDefinition>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    KeepLocal,
+    TakeUpstream,
+}
+
+pub struct ScriptLibraryService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ScriptLibraryService {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    pub async fn list(&self) -> Result<Vec<ScriptLibrary>, String> {
+        LibraryEntity::find()
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load script libraries: {}", e))
+    }
+
+    pub async fn add(&self, library: NewScriptLibrary) -> Result<ScriptLibrary, String> {
+        let name = library.name.trim();
+        let repository_url = library.repository_url.trim();
+        if name.is_empty() || repository_url.is_empty() {
+            return Err("A script library needs a name and a repository URL".to_string());
+        }
+        // Both end up on git's command line
+        if repository_url.starts_with('-')
+            || library
+                .branch
+                .as_deref()
+                .is_some_and(|b| b.starts_with('-'))
+        {
+            return Err("Repository URLs and branches can't start with -".to_string());
+        }
+        let manifest_path = library
+            .manifest_path
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .unwrap_or_else(|| DEFAULT_MANIFEST.to_string());
+
+        LibraryActive {
+            name: Set(name.to_string()),
+            repository_url: Set(repository_url.to_string()),
+            branch: Set(library.branch.filter(|branch| !branch.trim().is_empty())),
+            manifest_path: Set(manifest_path),
+            created_at: Set(Some(chrono::Utc::now().fixed_offset())),
+            ..Default::default()
+        }
+        .insert(self.db_manager.get_connection())
+        .await
+        .map_err(|e| format!("Failed to save script library: {}", e))
+    }
+
+    /// Remove a library and its checkout. Its scripts are deleted, or kept
+    /// as local scripts with `keep_scripts`.
+    pub async fn remove(&self, id: i32, keep_scripts: bool) -> Result<(), String> {
+        let db = self.db_manager.get_connection();
+        for script in self.library_scripts(id).await? {
+            if keep_scripts {
+                Self::detach(script).update(db).await
+            } else {
+                CustomScriptEntity::delete_by_id(script.id)
+                    .exec(db)
+                    .await
+                    .map(|_| script)
+            }
+            .map_err(|e| format!("Failed to update library scripts: {}", e))?;
+        }
+        LibraryEntity::delete_by_id(id)
+            .exec(db)
+            .await
+            .map_err(|e| format!("Failed to remove script library: {}", e))?;
+
+        let checkout = checkout_dir(id)?;
+        if checkout.exists() {
+            let _ = std::fs::remove_dir_all(checkout);
+        }
+        Ok(())
+    }
+
+    /// Pull the library and bring its scripts up to date
+    pub async fn sync(&self, id: i32) -> Result<SyncReport, String> {
+        let library = self.require(id).await?;
+        let result = self.pull_and_apply(&library).await;
+
+        let mut active: LibraryActive = library.into();
+        match &result {
+            Ok(report) => {
+                active.last_commit = Set(report.commit.clone());
+                active.last_synced_at = Set(Some(chrono::Utc::now().fixed_offset()));
+                active.last_error = Set(None);
+            }
+            Err(e) => active.last_error = Set(Some(e.clone())),
+        }
+        active
+            .update(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to save script library: {}", e))?;
+        result
+    }
+
+    /// Whether the library's branch has moved past the synced commit
+    pub async fn check_updates(&self, id: i32) -> Result<LibraryUpdateStatus, String> {
+        let library = self.require(id).await?;
+        let reference = library.branch.as_deref().unwrap_or("HEAD");
+        let output = git(None, &["ls-remote", &library.repository_url, reference]).await?;
+        let remote_commit = output
+            .split_whitespace()
+            .next()
+            .ok_or_else(|| format!("{} has no {}", library.repository_url, reference))?
+            .to_string();
+
+        Ok(LibraryUpdateStatus {
+            library_id: id,
+            update_available: library.last_commit.as_deref() != Some(remote_commit.as_str()),
+            current_commit: library.last_commit,
+            remote_commit,
+        })
+    }
+
+    pub async fn sync_states(&self) -> Result<Vec<ScriptSyncState>, String> {
+        let scripts = CustomScriptEntity::find()
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load custom scripts: {}", e))?;
+        Ok(scripts.iter().map(sync_state).collect())
+    }
+
+    /// Settle a conflict by keeping the local edits or taking the upstream
+    /// version. Keeping local edits makes the held-back version the new
+    /// base, so only a later upstream change conflicts again.
+    pub async fn resolve_conflict(
+        &self,
+        script_id: i32,
+        resolution: ConflictResolution,
+    ) -> Result<CustomScriptModel, String> {
+        let db = self.db_manager.get_connection();
+        let script = CustomScriptEntity::find_by_id(script_id)
+            .one(db)
+            .await
+            .map_err(|e| format!("Failed to load custom script: {}", e))?
+            .ok_or_else(|| format!("Custom script {} not found", script_id))?;
+        let upstream: ScriptDefinition = script
+            .pending_upstream_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .ok_or_else(|| format!("\"{}\" has no sync conflict", script.name))?;
+
+        let mut active: CustomScriptActive = script.into();
+        if let ConflictResolution::TakeUpstream = resolution {
+            upstream.apply(&mut active);
+        }
+        active.upstream_json = Set(Some(upstream.to_json()));
+        active.pending_upstream_json = Set(None);
+        active.updated_at = Set(Some(chrono::Utc::now().into()));
+        active
+            .update(db)
+            .await
+            .map_err(|e| format!("Failed to update custom script: {}", e))
+    }
+
+    async fn pull_and_apply(&self, library: &ScriptLibrary) -> Result<SyncReport, String> {
+        let checkout = checkout_dir(library.id)?;
+        let commit = pull(library, &checkout).await?;
+        let upstream = read_manifest(&checkout, &library.manifest_path)?;
+
+        let db = self.db_manager.get_connection();
+        let mut local: HashMap<String, CustomScriptModel> = self
+            .library_scripts(library.id)
+            .await?
+            .into_iter()
+            .filter_map(|script| Some((script.library_key.clone()?, script)))
+            .collect();
+        let mut report = SyncReport {
+            library_id: library.id,
+            commit: Some(commit),
+            ..Default::default()
+        };
+
+        for (key, definition) in upstream {
+            let Some(script) = local.remove(&key) else {
+                let mut active = CustomScriptActive {
+                    library_id: Set(Some(library.id)),
+                    library_key: Set(Some(key)),
+                    upstream_json: Set(Some(definition.to_json())),
+                    event_triggers_json: Set("[]".to_string()),
+                    run_count: Set(0),
+                    created_at: Set(Some(chrono::Utc::now().into())),
+                    ..Default::default()
+                };
+                definition.apply(&mut active);
+                active
+                    .insert(db)
+                    .await
+                    .map_err(|e| format!("Failed to create {}: {}", definition.name, e))?;
+                report.created.push(definition.name);
+                continue;
+            };
+
+            let base: Option<ScriptDefinition> = script
+                .upstream_json
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok());
+            let name = script.name.clone();
+            let action = plan_sync(base.as_ref(), &ScriptDefinition::of(&script), &definition);
+            let had_conflict = script.pending_upstream_json.is_some();
+            let mut active: CustomScriptActive = script.into();
+            match action {
+                SyncAction::Unchanged if base.as_ref() == Some(&definition) && !had_conflict => {
+                    continue
+                }
+                SyncAction::Unchanged => {
+                    active.upstream_json = Set(Some(definition.to_json()));
+                    active.pending_upstream_json = Set(None);
+                }
+                SyncAction::Update => {
+                    definition.apply(&mut active);
+                    active.upstream_json = Set(Some(definition.to_json()));
+                    active.pending_upstream_json = Set(None);
+                    active.updated_at = Set(Some(chrono::Utc::now().into()));
+                    report.updated.push(definition.name.clone());
+                }
+                SyncAction::Conflict => {
+                    active.pending_upstream_json = Set(Some(definition.to_json()));
+                    report.conflicts.push(name.clone());
+                }
+            }
+            active
+                .update(db)
+                .await
+                .map_err(|e| format!("Failed to update {}: {}", name, e))?;
+        }
+
+        // Whatever is left was removed from the manifest
+        for script in local.into_values() {
+            let name = script.name.clone();
+            let edited = sync_state(&script).status != "synced";
+            if edited {
+                Self::detach(script).update(db).await.map(|_| ())
+            } else {
+                CustomScriptEntity::delete_by_id(script.id)
+                    .exec(db)
+                    .await
+                    .map(|_| ())
+            }
+            .map_err(|e| format!("Failed to remove {}: {}", name, e))?;
+            if edited {
+                report.detached.push(name);
+            } else {
+                report.removed.push(name);
+            }
+        }
+        Ok(report)
+    }
+
+    async fn library_scripts(&self, id: i32) -> Result<Vec<CustomScriptModel>, String> {
+        CustomScriptEntity::find()
+            .filter(CustomScriptColumn::LibraryId.eq(id))
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load library scripts: {}", e))
+    }
+
+    async fn require(&self, id: i32) -> Result<ScriptLibrary, String> {
+        LibraryEntity::find_by_id(id)
+            .one(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load script library: {}", e))?
+            .ok_or_else(|| format!("Script library {} not found", id))
+    }
+
+    /// Turn a synced script into a local one
+    fn detach(script: CustomScriptModel) -> CustomScriptActive {
+        let mut active: CustomScriptActive = script.into();
+        active.library_id = Set(None);
+        active.library_key = Set(None);
+        active.upstream_json = Set(None);
+        active.pending_upstream_json = Set(None);
+        active
+    }
+}
+
+fn sync_state(script: &CustomScriptModel) -> ScriptSyncState {
+    let upstream: Option<ScriptDefinition> = script
+        .pending_upstream_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok());
+    let base: Option<ScriptDefinition> = script
+        .upstream_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok());
+    let status = if script.library_id.is_none() {
+        "local"
+    } else if upstream.is_some() {
+        "conflict"
+    } else if base.as_ref() == Some(&ScriptDefinition::of(script)) {
+        "synced"
+    } else {
+        "modified"
+    };
+
+    ScriptSyncState {
+        script_id: script.id,
+        library_id: script.library_id,
+        status: status.to_string(),
+        upstream,
+    }
+}
+
+fn checkout_dir(id: i32) -> Result<PathBuf, String> {
+    Ok(dirs::home_dir()
+        .ok_or("Could not determine home directory")?
+        .join(".portal-desktop")
+        .join("script-libraries")
+        .join(id.to_string()))
+}
+
+/// Clone the library, or fetch it into the existing checkout, and return
+/// the checked out commit
+async fn pull(library: &ScriptLibrary, checkout: &Path) -> Result<String, String> {
+    if checkout.join(".git").exists() {
+        let reference = library.branch.as_deref().unwrap_or("HEAD");
+        git(
+            Some(checkout),
+            &["fetch", "--depth", "1", &library.repository_url, reference],
+        )
+        .await?;
+        git(Some(checkout), &["reset", "--hard", "FETCH_HEAD"]).await?;
+    } else {
+        if let Some(parent) = checkout.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let checkout_str = checkout.to_string_lossy().to_string();
+        let mut args = vec!["clone", "--depth", "1"];
+        if let Some(branch) = &library.branch {
+            args.extend(["--branch", branch.as_str()]);
+        }
+        args.extend([library.repository_url.as_str(), checkout_str.as_str()]);
+        git(None, &args).await?;
+    }
+    Ok(git(Some(checkout), &["rev-parse", "HEAD"])
+        .await?
+        .trim()
+        .to_string())
+}
+
+async fn git(dir: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut command = Command::new("git");
+    command.no_window();
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = command
+        .args(args)
+        // Fail instead of waiting on a credential prompt nobody sees
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Read the manifest's scripts, keyed by their id
+fn read_manifest(
+    checkout: &Path,
+    manifest_path: &str,
+) -> Result<Vec<(String, ScriptDefinition)>, String> {
+    let path = repo_file(checkout, manifest_path)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read manifest {}: {}", manifest_path, e))?;
+    parse_manifest(&content, |file| {
+        let path = repo_file(checkout, file)?;
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", file, e))
+    })
+}
+
+/// Parse a YAML or JSON manifest; `read_file` loads the scripts given as
+/// files
+fn parse_manifest(
+    content: &str,
+    read_file: impl Fn(&str) -> Result<String, String>,
+) -> Result<Vec<(String, ScriptDefinition)>, String> {
+    let manifest: Manifest =
+        serde_yaml::from_str(content).map_err(|e| format!("Invalid manifest: {}", e))?;
+
+    let mut seen = std::collections::HashSet::new();
+    let mut scripts = Vec::new();
+    for entry in manifest.scripts {
+        let key = entry.id.trim().to_string();
+        if key.is_empty() {
+            return Err(format!("\"{}\" has no id", entry.definition.name));
+        }
+        if !seen.insert(key.clone()) {
+            return Err(format!("Script id \"{}\" is used twice", key));
+        }
+        let definition = entry.definition;
+        let command = match (definition.command, definition.file) {
+            (Some(command), None) => command,
+            (None, Some(file)) => read_file(&file)?,
+            _ => {
+                return Err(format!(
+                    "\"{}\" needs either a command or a file",
+                    definition.name
+                ))
+            }
+        };
+        let parameters_json = serde_json::to_string(&definition.parameters)
+            .map_err(|e| format!("{}: {}", definition.name, e))?;
+        parse_parameters(&parameters_json).map_err(|e| format!("{}: {}", definition.name, e))?;
+        let runtime = match &definition.runtime {
+            Some(runtime) => {
+                ScriptRuntime::parse(runtime).map_err(|e| format!("{}: {}", definition.name, e))?
+            }
+            None => ScriptRuntime::Shell,
+        };
+
+        scripts.push((
+            key,
+            ScriptDefinition {
+                name: definition.name,
+                description: definition.description,
+                command,
+                parameters: definition.parameters,
+                category: definition.category,
+                icon: definition.icon,
+                requires_sudo: definition.requires_sudo,
+                is_interactive: definition.is_interactive,
+                runtime,
+                runtime_version: definition.runtime_version,
+            },
+        ));
+    }
+    Ok(scripts)
+}
+
+/// A path in the checkout, refusing ones that lead out of it
+fn repo_file(checkout: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(relative);
+    if relative.is_absolute()
+        || relative
+            .components()
+            .any(|part| matches!(part, std::path::Component::ParentDir))
+    {
+        return Err(format!(
+            "{} must be a path inside the repository",
+            relative.display()
+        ));
+    }
+    Ok(checkout.join(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(command: &str) -> ScriptDefinition {
+        ScriptDefinition {
+            name: "Deploy".to_string(),
+            description: None,
+            command: command.to_string(),
+            parameters: Vec::new(),
+            category: None,
+            icon: None,
+            requires_sudo: false,
+            is_interactive: false,
+            runtime: ScriptRuntime::Shell,
+            runtime_version: None,
+        }
+    }
+
+    #[test]
+    fn plans_sync_from_the_last_synced_version() {
+        let base = definition("v1");
+        let edited = definition("local");
+        let upstream = definition("v2");
+
+        assert_eq!(plan_sync(Some(&base), &base, &base), SyncAction::Unchanged);
+        assert_eq!(plan_sync(Some(&base), &base, &upstream), SyncAction::Update);
+        assert_eq!(
+            plan_sync(Some(&base), &edited, &base),
+            SyncAction::Unchanged
+        );
+        assert_eq!(
+            plan_sync(Some(&base), &edited, &upstream),
+            SyncAction::Conflict
+        );
+        assert_eq!(
+            plan_sync(Some(&base), &upstream, &upstream),
+            SyncAction::Unchanged
+        );
+        assert_eq!(plan_sync(None, &edited, &upstream), SyncAction::Conflict);
+    }
+
+    #[test]
+    fn parses_manifests_with_inline_and_file_commands() {
+        let scripts = parse_manifest(
+            r#"
+scripts:
+  - id: deploy
+    name: Deploy
+    command: ./deploy.sh "$env"
+    parameters:
+      - { name: env, label: Environment, parameter_type: enum, options: [staging, prod] }
+  - id: cleanup
+    name: Cleanup
+    file: scripts/cleanup.sh
+    runtime: Bash
+"#,
+            |file| Ok(format!("# {}\nrm -rf tmp", file)),
+        )
+        .unwrap();
+        assert_eq!(scripts[0].0, "deploy");
+        assert_eq!(scripts[0].1.parameters.len(), 1);
+        assert_eq!(scripts[1].1.command, "# scripts/cleanup.sh\nrm -rf tmp");
+        assert_eq!(scripts[0].1.runtime, ScriptRuntime::Shell);
+        assert_eq!(scripts[1].1.runtime, ScriptRuntime::Bash);
+
+        assert!(
+            parse_manifest("scripts:\n  - { id: a, name: A }\n", |_| Ok(String::new())).is_err()
+        );
+        assert!(parse_manifest(
+            "scripts:\n  - { id: a, name: A, command: x }\n  - { id: a, name: B, command: y }\n",
+            |_| Ok(String::new())
+        )
+        .is_err());
+        assert!(repo_file(Path::new("/tmp/lib"), "../secrets").is_err());
+        assert!(repo_file(Path::new("/tmp/lib"), "/etc/passwd").is_err());
+    }
+}
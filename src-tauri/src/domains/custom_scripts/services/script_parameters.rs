@@ -0,0 +1,217 @@
+//! Custom scripts declare typed parameters in `parameters_json` so the UI can
+//! render a form for them. Before a run each argument is checked against its
+//! declaration and normalized: booleans become `true`/`false`, enum values
+//! must be one of the options, file paths must exist and credential
+//! references are swapped for the credential's secret. The script execution
+//! service then passes the values to the command as env vars, never as
+//! command text.
+
+use crate::domains::credentials::services::{CredentialAccessor, CredentialService};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterType {
+    String,
+    Number,
+    Boolean,
+    /// One of the parameter's `options`
+    Enum,
+    #[serde(alias = "file")]
+    FilePath,
+    /// Id of a stored credential, passed as the credential's secret
+    #[serde(alias = "credential")]
+    CredentialRef,
+    /// Typed in for each run and never recorded
+    Password,
+}
+
+impl ParameterType {
+    /// Values of these types are masked in run history and output
+    pub fn is_secret(&self) -> bool {
+        matches!(self, ParameterType::CredentialRef | ParameterType::Password)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptParameter {
+    pub name: String,
+    pub label: String,
+    pub parameter_type: ParameterType,
+    #[serde(default)]
+    pub required: bool,
+    pub default_value: Option<String>,
+    pub description: Option<String>,
+    pub file_filters: Option<Vec<String>>, // For file type: e.g., ["*.ovpn", "*.txt"]
+    /// Choices of an enum parameter
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// Field of a credential reference to pass; the credential kind's
+    /// primary field when unset
+    pub credential_field: Option<String>,
+}
+
+/// Arguments ready for a run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolvedArguments {
+    pub values: HashMap<String, String>,
+    /// Names of the arguments to mask
+    pub secrets: Vec<String>,
+}
+
+/// Parse and check a script's parameter declarations
+pub fn parse_parameters(parameters_json: &str) -> Result<Vec<ScriptParameter>, String> {
+    if parameters_json.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let parameters: Vec<ScriptParameter> = serde_json::from_str(parameters_json)
+        .map_err(|e| format!("Invalid script parameters: {}", e))?;
+
+    let mut seen = std::collections::HashSet::new();
+    for parameter in &parameters {
+        let name = &parameter.name;
+        if name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!(
+                "Parameter names may only use letters, digits and _: \"{}\"",
+                name
+            ));
+        }
+        if !seen.insert(name.as_str()) {
+            return Err(format!("Parameter \"{}\" is declared twice", name));
+        }
+        if parameter.parameter_type == ParameterType::Enum {
+            if parameter.options.is_empty() {
+                return Err(format!("Enum parameter \"{}\" has no options", name));
+            }
+            if let Some(default) = &parameter.default_value {
+                if !parameter.options.contains(default) {
+                    return Err(format!(
+                        "Default \"{}\" of \"{}\" isn't one of its options",
+                        default, name
+                    ));
+                }
+            }
+        }
+    }
+    Ok(parameters)
+}
+
+/// Check the given arguments against the declarations, fill in defaults and
+/// resolve credential references. Arguments a script doesn't declare are
+/// passed through as they are.
+pub async fn resolve_arguments(
+    declared: &[ScriptParameter],
+    mut given: HashMap<String, String>,
+    db: &DatabaseConnection,
+) -> Result<ResolvedArguments, String> {
+    let mut resolved = ResolvedArguments::default();
+    for parameter in declared {
+        let value = given
+            .remove(&parameter.name)
+            .filter(|value| !value.is_empty())
+            .or_else(|| parameter.default_value.clone());
+        let Some(value) = value else {
+            if parameter.required {
+                return Err(format!("{} is required", parameter.label));
+            }
+            continue;
+        };
+
+        let value = match parameter.parameter_type {
+            ParameterType::CredentialRef => {
+                let credential = CredentialService::new(db.clone())
+                    .decrypt_credential_fields(&value, CredentialAccessor::Script)
+                    .await
+                    .map_err(|e| format!("{}: {}", parameter.label, e))?;
+                let field = parameter
+                    .credential_field
+                    .clone()
+                    .unwrap_or_else(|| credential.kind.schema().primary_field);
+                credential.fields.get(&field).cloned().ok_or_else(|| {
+                    format!("{}: the credential has no {} field", parameter.label, field)
+                })?
+            }
+            _ => check_value(parameter, &value)?,
+        };
+        if parameter.parameter_type.is_secret() {
+            resolved.secrets.push(parameter.name.clone());
+        }
+        resolved.values.insert(parameter.name.clone(), value);
+    }
+
+    resolved.values.extend(given);
+    Ok(resolved)
+}
+
+/// Check and normalize a value of any type but a credential reference
+fn check_value(parameter: &ScriptParameter, value: &str) -> Result<String, String> {
+    let label = &parameter.label;
+    match parameter.parameter_type {
+        ParameterType::String | ParameterType::Password | ParameterType::CredentialRef => {
+            Ok(value.to_string())
+        }
+        ParameterType::Number => value
+            .trim()
+            .parse::<f64>()
+            .map(|_| value.trim().to_string())
+            .map_err(|_| format!("{} must be a number", label)),
+        ParameterType::Boolean => match value.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+            "false" | "no" | "off" | "0" => Ok("false".to_string()),
+            _ => Err(format!("{} must be true or false", label)),
+        },
+        ParameterType::Enum if parameter.options.iter().any(|option| option == value) => {
+            Ok(value.to_string())
+        }
+        ParameterType::Enum => Err(format!(
+            "{} must be one of: {}",
+            label,
+            parameter.options.join(", ")
+        )),
+        ParameterType::FilePath if Path::new(value).exists() => Ok(value.to_string()),
+        ParameterType::FilePath => Err(format!("{}: {} doesn't exist", label, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_declarations_and_checks_values() {
+        let parameters = parse_parameters(
+            r#"[
+                {"name": "env", "label": "Environment", "parameter_type": "enum",
+                 "options": ["staging", "prod"], "default_value": "staging"},
+                {"name": "dry_run", "label": "Dry run", "parameter_type": "boolean"},
+                {"name": "config", "label": "Config", "parameter_type": "file"},
+                {"name": "token", "label": "Token", "parameter_type": "credential_ref"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(parameters[2].parameter_type, ParameterType::FilePath);
+        assert!(parameters[3].parameter_type.is_secret());
+
+        assert_eq!(check_value(&parameters[0], "prod").unwrap(), "prod");
+        assert!(check_value(&parameters[0], "dev").is_err());
+        assert_eq!(check_value(&parameters[1], "Yes").unwrap(), "true");
+        assert!(check_value(&parameters[1], "maybe").is_err());
+        assert!(check_value(&parameters[2], "/definitely/not/here").is_err());
+
+        assert!(
+            parse_parameters(r#"[{"name": "a b", "label": "A", "parameter_type": "string"}]"#)
+                .is_err()
+        );
+        assert!(parse_parameters(
+            r#"[{"name": "mode", "label": "Mode", "parameter_type": "enum"}]"#
+        )
+        .is_err());
+        assert!(parse_parameters("").unwrap().is_empty());
+    }
+}
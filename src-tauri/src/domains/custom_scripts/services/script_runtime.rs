@@ -0,0 +1,231 @@
+//! A custom script runs in the shell by default, with its command as a
+//! command line. It can declare another runtime instead: bash, PowerShell,
+//! Python or Node, with its command being the script's source. Python and
+//! Node come from the SDK domain's installed versions rather than whatever is
+//! on PATH: the newest installed version matching the script's
+//! `runtime_version` ("3.12", "20") is used, and when none is installed the
+//! newest matching release is installed first.
+
+use crate::domains::scripts::services::ScriptInterpreter;
+use crate::domains::sdk::download::installer::{bin_dir, SdkInstaller};
+use crate::domains::sdk::download::version_fetcher::VersionFetcher;
+use crate::domains::sdk::download::InstallProgress;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptRuntime {
+    /// `sh -c` or `cmd /C`, with parameter placeholders in the command
+    #[default]
+    Shell,
+    Bash,
+    #[serde(alias = "pwsh")]
+    Powershell,
+    Python,
+    #[serde(alias = "nodejs")]
+    Node,
+}
+
+impl ScriptRuntime {
+    pub fn parse(runtime: &str) -> Result<Self, String> {
+        serde_json::from_value(serde_json::Value::String(runtime.trim().to_lowercase())).map_err(
+            |_| {
+                format!(
+                    "Unknown runtime \"{}\"; use shell, bash, powershell, python or node",
+                    runtime
+                )
+            },
+        )
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScriptRuntime::Shell => "shell",
+            ScriptRuntime::Bash => "bash",
+            ScriptRuntime::Powershell => "powershell",
+            ScriptRuntime::Python => "python",
+            ScriptRuntime::Node => "node",
+        }
+    }
+
+    /// SDK the runtime's versions come from
+    fn sdk_type(&self) -> Option<&'static str> {
+        match self {
+            ScriptRuntime::Python => Some("python"),
+            ScriptRuntime::Node => Some("nodejs"),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ScriptRuntime::Shell | ScriptRuntime::Bash => "sh",
+            ScriptRuntime::Powershell => "ps1",
+            ScriptRuntime::Python => "py",
+            ScriptRuntime::Node => "js",
+        }
+    }
+}
+
+/// The interpreter to run a script with; `None` for the shell
+pub async fn resolve_interpreter(
+    runtime: ScriptRuntime,
+    version: Option<&str>,
+) -> Result<Option<ScriptInterpreter>, String> {
+    let version = version.map(str::trim).filter(|v| !v.is_empty());
+    let (program, args) = match runtime {
+        ScriptRuntime::Shell => return Ok(None),
+        ScriptRuntime::Bash => (find_on_path(&["bash"])?, Vec::new()),
+        ScriptRuntime::Powershell => (
+            find_on_path(&["pwsh", "powershell"])?,
+            [
+                "-NoProfile",
+                "-NonInteractive",
+                "-ExecutionPolicy",
+                "Bypass",
+                "-File",
+            ]
+            .map(String::from)
+            .to_vec(),
+        ),
+        ScriptRuntime::Python | ScriptRuntime::Node => {
+            let sdk_type = runtime.sdk_type().unwrap_or_default();
+            (
+                sdk_executable(runtime, sdk_type, version).await?,
+                Vec::new(),
+            )
+        }
+    };
+    Ok(Some(ScriptInterpreter {
+        program: program.to_string_lossy().to_string(),
+        args,
+        extension: runtime.extension().to_string(),
+    }))
+}
+
+fn find_on_path(programs: &[&str]) -> Result<PathBuf, String> {
+    programs
+        .iter()
+        .find_map(|program| which::which(program).ok())
+        .ok_or_else(|| format!("{} isn't installed or isn't on PATH", programs[0]))
+}
+
+/// The runtime's executable from an SDK install, installing the newest
+/// matching release when no installed version matches
+async fn sdk_executable(
+    runtime: ScriptRuntime,
+    sdk_type: &str,
+    version: Option<&str>,
+) -> Result<PathBuf, String> {
+    let installer = SdkInstaller::new()?;
+    let installed = installer.list_installed(sdk_type);
+    let install_dir = match newest_matching(version, &installed) {
+        Some(found) => installer.install_dir(sdk_type, &found),
+        None => {
+            let release = newest_release(sdk_type, version).await?;
+            println!("[Scripts] Installing {} {} for a script", sdk_type, release);
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<InstallProgress>();
+            let drain = tokio::spawn(async move { while receiver.recv().await.is_some() {} });
+            let result = installer.install(sdk_type, &release, sender).await;
+            let _ = drain.await;
+            result.map_err(|e| format!("Failed to install {} {}: {}", sdk_type, release, e))?
+        }
+    };
+
+    let bin = bin_dir(sdk_type, &install_dir);
+    executable_in(runtime, &bin)
+        .ok_or_else(|| format!("No {} executable in {}", runtime.as_str(), bin.display()))
+}
+
+fn executable_in(runtime: ScriptRuntime, bin: &Path) -> Option<PathBuf> {
+    let names: &[&str] = match (runtime, cfg!(target_os = "windows")) {
+        (ScriptRuntime::Python, true) => &["python.exe", "python3.exe"],
+        (ScriptRuntime::Python, false) => &["python3", "python"],
+        (ScriptRuntime::Node, true) => &["node.exe"],
+        (ScriptRuntime::Node, false) => &["node"],
+        _ => &[],
+    };
+    names
+        .iter()
+        .map(|name| bin.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Newest release of an SDK matching a version prefix; the newest release
+/// of all when no version is given
+async fn newest_release(sdk_type: &str, version: Option<&str>) -> Result<String, String> {
+    // The version lists name Node.js "node"
+    let fetcher_type = if sdk_type == "nodejs" {
+        "node"
+    } else {
+        sdk_type
+    };
+    let releases: Vec<String> = VersionFetcher::new(fetcher_type.to_string())
+        .fetch_versions()
+        .await
+        .map_err(|e| format!("Failed to list {} releases: {}", sdk_type, e))?
+        .into_iter()
+        .map(|info| info.version.trim_start_matches('v').to_string())
+        .collect();
+    newest_matching(version, &releases).ok_or_else(|| match version {
+        Some(version) => format!("No {} release matches {}", sdk_type, version),
+        None => format!("No {} releases found", sdk_type),
+    })
+}
+
+/// The newest version matching a prefix ("3.12" matches 3.12.4, not 3.120)
+fn newest_matching(requested: Option<&str>, versions: &[String]) -> Option<String> {
+    let requested = requested.map(|v| v.trim_start_matches('v'));
+    versions
+        .iter()
+        .filter(|version| match requested {
+            Some(requested) => {
+                *version == requested || version.starts_with(&format!("{}.", requested))
+            }
+            None => true,
+        })
+        .max_by_key(|version| {
+            version
+                .split(|c: char| !c.is_ascii_digit())
+                .filter_map(|part| part.parse::<u64>().ok())
+                .collect::<Vec<_>>()
+        })
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_runtimes_and_matches_versions() {
+        assert_eq!(
+            ScriptRuntime::parse("Python").unwrap(),
+            ScriptRuntime::Python
+        );
+        assert_eq!(
+            ScriptRuntime::parse("pwsh").unwrap(),
+            ScriptRuntime::Powershell
+        );
+        assert_eq!(ScriptRuntime::parse("nodejs").unwrap(), ScriptRuntime::Node);
+        assert!(ScriptRuntime::parse("perl").is_err());
+
+        let versions: Vec<String> = ["3.11.9", "3.12.1", "3.12.10", "3.120.0"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            newest_matching(Some("3.12"), &versions),
+            Some("3.12.10".to_string())
+        );
+        assert_eq!(
+            newest_matching(Some("3.11.9"), &versions),
+            Some("3.11.9".to_string())
+        );
+        assert_eq!(newest_matching(Some("3.13"), &versions), None);
+        assert_eq!(
+            newest_matching(None, &versions),
+            Some("3.120.0".to_string())
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Custom scripts can run on app events as well as by hand or on a schedule.
+//! Each script keeps a list of event triggers in `event_triggers_json`; the
+//! event bus dispatcher hands every event to `run_event_scripts`, which runs
+//! each script with a matching trigger. The run gets the event's variables
+//! (`event_type`, `event_<field>`) plus the trigger's own arguments, and
+//! starts in the event's project when it names one.
+
+use super::CustomScriptService;
+use crate::database::DatabaseManager;
+use crate::domains::automation::services::event_bus::AppEvent;
+use crate::domains::scripts::commands::ScriptExecutionState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventTrigger {
+    /// Exact type, `*`, or a prefix such as `pipeline.*`
+    pub event_type: String,
+    /// Payload fields the event must have, e.g. `{"project_id": "3"}`
+    #[serde(default)]
+    pub filter: HashMap<String, String>,
+    /// Arguments for the run, over the event's variables
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+impl EventTrigger {
+    pub fn fires_on(&self, event: &AppEvent) -> bool {
+        event.matches(&self.event_type) && event.matches_filter(&self.filter)
+    }
+
+    /// The event's variables with the trigger's arguments on top
+    pub fn arguments_for(&self, event: &AppEvent) -> HashMap<String, String> {
+        let mut arguments = event.variables();
+        arguments.extend(self.arguments.clone());
+        arguments
+    }
+}
+
+pub fn parse_triggers(event_triggers_json: &str) -> Result<Vec<EventTrigger>, String> {
+    if event_triggers_json.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let triggers: Vec<EventTrigger> = serde_json::from_str(event_triggers_json)
+        .map_err(|e| format!("Invalid event triggers: {}", e))?;
+    if triggers.iter().any(|t| t.event_type.trim().is_empty()) {
+        return Err("Event triggers need an event type".to_string());
+    }
+    Ok(triggers)
+}
+
+/// Run every script with a trigger for this event
+pub async fn run_event_scripts(app: &AppHandle, event: &AppEvent) {
+    let db_manager = app.state::<Arc<DatabaseManager>>().inner().clone();
+    let service = CustomScriptService::new(&db_manager);
+    let scripts = match service.get_all_scripts().await {
+        Ok(scripts) => scripts,
+        Err(e) => {
+            eprintln!(
+                "[Scripts] Failed to load scripts for {}: {}",
+                event.event_type, e
+            );
+            return;
+        }
+    };
+
+    let working_directory = event
+        .payload
+        .get("project_path")
+        .and_then(|path| path.as_str())
+        .map(String::from);
+    for script in scripts {
+        let Some(trigger) = parse_triggers(&script.event_triggers_json)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|trigger| trigger.fires_on(event))
+        else {
+            continue;
+        };
+
+        println!(
+            "[Scripts] {} triggered by {}",
+            script.name, event.event_type
+        );
+        let execution = app
+            .state::<ScriptExecutionState>()
+            .get_or_init(&db_manager)
+            .await;
+        if let Err(e) = service
+            .run_script(
+                &execution,
+                script.id,
+                trigger.arguments_for(event),
+                working_directory.clone(),
+                &format!("event:{}", event.event_type),
+            )
+            .await
+        {
+            eprintln!("[Scripts] {} failed to start: {}", script.name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fires_on_matching_events_with_their_variables() {
+        let triggers = parse_triggers(
+            r#"[{"event_type": "pipeline.*", "filter": {"status": "failed"},
+                 "arguments": {"channel": "ops"}}]"#,
+        )
+        .unwrap();
+        let event = AppEvent {
+            id: "1".to_string(),
+            event_type: "pipeline.finished".to_string(),
+            payload: serde_json::json!({"status": "failed", "pipeline_id": 4}),
+            occurred_at: "2026-10-18T00:00:00Z".to_string(),
+        };
+
+        assert!(triggers[0].fires_on(&event));
+        let arguments = triggers[0].arguments_for(&event);
+        assert_eq!(arguments["event_pipeline_id"], "4");
+        assert_eq!(arguments["channel"], "ops");
+
+        let succeeded = AppEvent {
+            payload: serde_json::json!({"status": "success"}),
+            ..event
+        };
+        assert!(!triggers[0].fires_on(&succeeded));
+
+        assert!(parse_triggers("").unwrap().is_empty());
+        assert!(parse_triggers(r#"[{"event_type": " "}]"#).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+use crate::database::backup::{BackupInfo, BackupReason};
+use crate::database::data_sync::{ConflictResolution, DataSync, DataSyncReport, DATA_SYNC_EVENT};
+use crate::database::encryption::{self, EncryptionStatus, LockedDatabase};
+use crate::database::maintenance::{self, CheckpointResult, IntegrityReport, TableSize};
+use crate::database::transfer::{self, ImportMode, TransferSummary};
+use crate::database::workspaces::{Workspace, WorkspaceList, Workspaces, WORKSPACE_SWITCHED_EVENT};
+use crate::database::{DatabaseManager, DATABASE_FILE};
+use crate::domains::ai::services::AIService;
+use crate::domains::ai::usage::AIUsageLog;
+use crate::domains::coder::CoderService;
+use crate::domains::deployments::services::deployment_service::DeploymentService;
+use crate::domains::documents::services::document_sync_service::DocumentSyncWatchers;
+use crate::domains::projects::pipelines::services::ExecutionService;
+use crate::domains::settings::services::settings_service::{DataSyncSettings, SettingsService};
+use crate::log_warn;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[tauri::command]
+pub async fn backup_database(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<BackupInfo, String> {
+    db_manager.create_backup(BackupReason::Manual).await
+}
+
+#[tauri::command]
+pub async fn list_backups(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<BackupInfo>, String> {
+    db_manager.backups().list()
+}
+
+/// Replace the database with a backup. The current data is backed up first.
+#[tauri::command]
+pub async fn restore_database(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    file_name: String,
+) -> Result<(), String> {
+    db_manager.restore_backup(file_name).await
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseHealth {
+    pub integrity: IntegrityReport,
+    /// Newest backup, offered for restore when the integrity check failed
+    pub latest_backup: Option<BackupInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VacuumResult {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseSizeBreakdown {
+    pub file_bytes: u64,
+    pub wal_bytes: u64,
+    pub tables: Vec<TableSize>,
+}
+
+/// Result of the last integrity check, which runs at startup
+#[tauri::command]
+pub async fn get_database_health(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<DatabaseHealth, String> {
+    Ok(DatabaseHealth {
+        integrity: db_manager.health(),
+        latest_backup: db_manager.backups().list()?.into_iter().next(),
+    })
+}
+
+/// Run SQLite's integrity check; `quick` skips verifying index contents
+#[tauri::command]
+pub async fn check_database_integrity(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    quick: Option<bool>,
+) -> Result<IntegrityReport, String> {
+    db_manager.check_integrity(quick.unwrap_or(false)).await
+}
+
+/// Compact the database and refresh its statistics
+#[tauri::command]
+pub async fn vacuum_database(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<VacuumResult, String> {
+    let (file_bytes, wal_bytes) = db_manager.file_sizes();
+    maintenance::vacuum(db_manager.get_connection()).await?;
+    // VACUUM writes through the log; fold it back in so the sizes compare
+    maintenance::checkpoint(db_manager.get_connection()).await?;
+    let (file_after, wal_after) = db_manager.file_sizes();
+    Ok(VacuumResult {
+        bytes_before: file_bytes + wal_bytes,
+        bytes_after: file_after + wal_after,
+    })
+}
+
+#[tauri::command]
+pub async fn checkpoint_database(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<CheckpointResult, String> {
+    maintenance::checkpoint(db_manager.get_connection()).await
+}
+
+#[tauri::command]
+pub async fn get_database_size_breakdown(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<DatabaseSizeBreakdown, String> {
+    let tables = maintenance::table_sizes(db_manager.get_connection()).await?;
+    let (file_bytes, wal_bytes) = db_manager.file_sizes();
+    Ok(DatabaseSizeBreakdown {
+        file_bytes,
+        wal_bytes,
+        tables,
+    })
+}
+
+/// Write projects, tasks, documents, pipelines and settings to a JSON file.
+/// Credentials are included, re-encrypted under `passphrase`, only when one is
+/// given.
+#[tauri::command]
+pub async fn export_all_data(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<TransferSummary, String> {
+    let bundle =
+        transfer::export_bundle(db_manager.get_connection(), passphrase.as_deref()).await?;
+    let summary = TransferSummary {
+        projects: bundle.projects.len(),
+        tasks: bundle.tasks.len(),
+        documents: bundle.documents.len(),
+        pipelines: bundle.pipelines.len(),
+        credentials: bundle
+            .credentials
+            .as_ref()
+            .map_or(0, |credentials| credentials.credentials.len()),
+        settings: bundle.settings.is_some(),
+        skipped: 0,
+    };
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize data export: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    Ok(summary)
+}
+
+/// Load a file written by `export_all_data`. The database is backed up first.
+#[tauri::command]
+pub async fn import_all_data(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    path: String,
+    mode: ImportMode,
+    passphrase: Option<String>,
+) -> Result<TransferSummary, String> {
+    let json =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let bundle = transfer::parse_bundle(&json)?;
+    db_manager.create_backup(BackupReason::PreImport).await?;
+    transfer::import_bundle(
+        db_manager.get_connection(),
+        bundle,
+        mode,
+        passphrase.as_deref(),
+    )
+    .await
+}
+
+/// What syncing with the data sync folder would change, without changing
+/// anything
+#[tauri::command]
+pub async fn get_data_sync_status(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<DataSyncReport, String> {
+    let (sync, folder) = data_sync(&db_manager)?;
+    sync.sync(&folder, true).await
+}
+
+#[tauri::command]
+pub async fn sync_data_now(
+    app: AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<DataSyncReport, String> {
+    let (sync, folder) = data_sync(&db_manager)?;
+    let report = sync.sync(&folder, false).await?;
+    if !report.imported.is_empty() {
+        let _ = app.emit(DATA_SYNC_EVENT, &report);
+    }
+    Ok(report)
+}
+
+/// Settle sync conflicts by keeping one side; `items` limits it to those
+/// items, otherwise all conflicts are settled
+#[tauri::command]
+pub async fn resolve_data_sync_conflicts(
+    app: AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    items: Option<Vec<String>>,
+    resolution: ConflictResolution,
+) -> Result<DataSyncReport, String> {
+    let (sync, folder) = data_sync(&db_manager)?;
+    let report = sync.resolve(&folder, items, resolution).await?;
+    if !report.imported.is_empty() {
+        let _ = app.emit(DATA_SYNC_EVENT, &report);
+    }
+    Ok(report)
+}
+
+/// Choose the folder to sync through, or turn sync off with `None`
+#[tauri::command]
+pub async fn set_data_sync_folder(folder: Option<String>) -> Result<DataSyncSettings, String> {
+    let folder = folder
+        .map(|folder| folder.trim().to_string())
+        .filter(|folder| !folder.is_empty());
+    if let Some(folder) = &folder {
+        if !Path::new(folder).is_absolute() {
+            return Err("The sync folder must be an absolute path".to_string());
+        }
+    }
+    let service = SettingsService::new();
+    let mut settings = service.load_settings()?;
+    settings.app.data_sync.folder = folder;
+    service.save_settings(&settings)?;
+    Ok(settings.app.data_sync)
+}
+
+/// Sync for the active workspace, and the folder it syncs with
+fn data_sync(db_manager: &DatabaseManager) -> Result<(DataSync, PathBuf), String> {
+    let folder = SettingsService::new()
+        .load_settings()?
+        .app
+        .data_sync
+        .folder
+        .ok_or_else(|| "Data sync is off; choose a sync folder first".to_string())?;
+    let sync = DataSync::new(
+        db_manager.get_connection_clone(),
+        &db_manager.workspace_dir(),
+    );
+    Ok((sync, PathBuf::from(folder)))
+}
+
+#[tauri::command]
+pub async fn get_database_encryption_status(app: AppHandle) -> Result<EncryptionStatus, String> {
+    let locked = app.try_state::<LockedDatabase>().is_some();
+    Ok(encryption::status(&data_dir(&app)?, locked))
+}
+
+/// Encrypt the database under `passphrase`. The app restarts to carry it out.
+#[tauri::command]
+pub async fn enable_database_encryption(
+    app: AppHandle,
+    passphrase: String,
+) -> Result<EncryptionStatus, String> {
+    let status = encryption::enable(&data_dir(&app)?, &passphrase)?;
+    app.request_restart();
+    Ok(status)
+}
+
+/// Decrypt the database. The app restarts to carry it out.
+#[tauri::command]
+pub async fn disable_database_encryption(
+    app: AppHandle,
+    passphrase: String,
+) -> Result<EncryptionStatus, String> {
+    let data_dir = data_dir(&app)?;
+    let status = encryption::disable(&data_dir, &active_database(&data_dir), &passphrase)?;
+    app.request_restart();
+    Ok(status)
+}
+
+/// Unlock a database the app started without the key for, then restart
+/// into it
+#[tauri::command]
+pub async fn unlock_database(app: AppHandle, passphrase: String) -> Result<(), String> {
+    let data_dir = data_dir(&app)?;
+    encryption::unlock(&data_dir, &active_database(&data_dir), &passphrase)?;
+    app.request_restart();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_workspaces(app: AppHandle) -> Result<WorkspaceList, String> {
+    Ok(Workspaces::new(data_dir(&app)?).list())
+}
+
+/// Add a workspace with its own, empty database. It isn't switched to.
+#[tauri::command]
+pub async fn create_workspace(app: AppHandle, name: String) -> Result<Workspace, String> {
+    Workspaces::new(data_dir(&app)?).create(&name)
+}
+
+/// Switch to another workspace's database without restarting. Services that
+/// keep data from the database in memory are reloaded, then
+/// `workspace-switched` tells the frontend to refetch.
+#[tauri::command]
+pub async fn switch_workspace(
+    app: AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: String,
+) -> Result<Workspace, String> {
+    let workspaces = Workspaces::new(data_dir(&app)?);
+    let workspace = workspaces.get(&id)?;
+
+    // Their results would land in the other workspace's database
+    if app.state::<Arc<ExecutionService>>().has_running() {
+        return Err("Wait for running pipelines to finish before switching workspaces".to_string());
+    }
+    if app.state::<Arc<CoderService>>().has_active_runs().await {
+        return Err("Stop running agents before switching workspaces".to_string());
+    }
+
+    db_manager.switch_to(workspaces.dir(&workspace.id)).await?;
+    workspaces.set_active(&workspace.id)?;
+    reload_workspace_state(&app, db_manager.inner()).await;
+
+    let _ = app.emit(WORKSPACE_SWITCHED_EVENT, &workspace);
+    Ok(workspace)
+}
+
+/// Point services that hold data or a connection from the previous workspace
+/// at the active one. Everything else reads through `DatabaseManager` on
+/// each call and follows the switch by itself.
+async fn reload_workspace_state(app: &AppHandle, db_manager: &Arc<DatabaseManager>) {
+    let ai_service = app.state::<Arc<AIService>>();
+    ai_service.attach_usage_log(AIUsageLog::new(db_manager.get_connection_clone()));
+    ai_service.attach_redaction_credentials(db_manager.get_connection_clone());
+
+    app.state::<Arc<CoderService>>().reload().await;
+    if let Err(e) = app.state::<Arc<DeploymentService>>().reload().await {
+        log_warn!("Workspaces", "Failed to reload deployments: {}", e);
+    }
+    app.state::<DocumentSyncWatchers>()
+        .resume(db_manager.clone(), app.clone())
+        .await;
+}
+
+/// Database of the active workspace
+fn active_database(data_dir: &Path) -> PathBuf {
+    let workspaces = Workspaces::new(data_dir.to_path_buf());
+    workspaces.dir(&workspaces.active().id).join(DATABASE_FILE)
+}
+
+fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    if let Some(locked) = app.try_state::<LockedDatabase>() {
+        return Ok(locked.data_dir.clone());
+    }
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
@@ -0,0 +1,27 @@
+//! Deployment tools for the AI assistant, workflows and scheduled jobs
+use super::services::deployment_service::DeploymentService;
+use crate::domains::ai::tools::{ToolRegistry, ToolSpec};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::Manager;
+
+pub fn register_ai_tools(registry: &ToolRegistry) {
+    registry.register(
+        ToolSpec {
+            name: "refresh_deployment_statuses".to_string(),
+            description: "Re-check the container status of every deployment and return them"
+                .to_string(),
+            domain: "deployments".to_string(),
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        |app, _args: Value| async move {
+            let service = app
+                .try_state::<Arc<DeploymentService>>()
+                .ok_or("Deployments aren't available yet")?
+                .inner()
+                .clone();
+            let deployments = service.refresh_deployment_statuses().await?;
+            serde_json::to_value(deployments).map_err(|e| e.to_string())
+        },
+    );
+}
@@ -9,14 +9,16 @@ use sea_orm::{DatabaseConnection, EntityTrait, QueryOrder, Set};
 use std::sync::Arc;
 
 pub struct DeploymentRepository {
-    conn: DatabaseConnection,
+    db_manager: Arc<crate::database::DatabaseManager>,
 }
 
 impl DeploymentRepository {
     pub fn new(db_manager: Arc<crate::database::DatabaseManager>) -> Self {
-        Self {
-            conn: db_manager.get_connection_clone(),
-        }
+        Self { db_manager }
+    }
+
+    fn conn(&self) -> &DatabaseConnection {
+        self.db_manager.get_connection()
     }
 
     pub async fn save(&self, deployment: &Deployment) -> Result<(), String> {
@@ -47,7 +49,7 @@ impl DeploymentRepository {
                     ])
                     .to_owned(),
             )
-            .exec(&self.conn)
+            .exec(self.conn())
             .await
             .map_err(|e| format!("Failed to save deployment: {}", e))?;
 
@@ -57,7 +59,7 @@ impl DeploymentRepository {
     pub async fn find_all(&self) -> Result<Vec<Deployment>, String> {
         let rows = DeploymentEntity::find()
             .order_by_desc(Column::CreatedAt)
-            .all(&self.conn)
+            .all(self.conn())
             .await
             .map_err(|e| format!("Failed to load deployments: {}", e))?;
 
@@ -66,7 +68,7 @@ impl DeploymentRepository {
 
     pub async fn find_by_id(&self, id: &str) -> Result<Option<Deployment>, String> {
         let row = DeploymentEntity::find_by_id(id)
-            .one(&self.conn)
+            .one(self.conn())
             .await
             .map_err(|e| format!("Failed to load deployment: {}", e))?;
 
@@ -78,7 +80,7 @@ impl DeploymentRepository {
 
     pub async fn delete(&self, id: &str) -> Result<(), String> {
         DeploymentEntity::delete_by_id(id)
-            .exec(&self.conn)
+            .exec(self.conn())
             .await
             .map_err(|e| format!("Failed to delete deployment: {}", e))?;
         Ok(())
@@ -1,10 +1,8 @@
-use crate::process_ext::NoWindowExt;
+use crate::command_executor::supervisor::{supervisor, OutputStream, ProcessSpec, SupervisedChild};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
@@ -25,7 +23,7 @@ pub enum ProcessStatus {
 
 pub struct CliService {
     // Map deployment_id -> (Child process, ProcessInfo)
-    processes: Arc<Mutex<HashMap<String, (Child, ProcessInfo)>>>,
+    processes: Arc<Mutex<HashMap<String, (SupervisedChild, ProcessInfo)>>>,
     // Map deployment_id -> logs
     logs: Arc<Mutex<HashMap<String, Vec<String>>>>,
 }
@@ -56,9 +54,8 @@ impl CliService {
         let args = &parts[1..];
 
         // Build command
-        let mut cmd = Command::new(program);
-        cmd.no_window();
-        cmd.args(args);
+        let mut spec = ProcessSpec::new("deployments", program);
+        spec.args(args);
 
         // Set working directory
         if let Some(wd) = working_directory {
@@ -66,23 +63,18 @@ impl CliService {
             if !path.exists() {
                 return Err(format!("Working directory does not exist: {}", wd));
             }
-            cmd.current_dir(path);
+            spec.current_dir(path);
         }
 
         // Set environment variables
-        for (key, value) in environment {
-            cmd.env(key, value);
-        }
+        spec.envs(environment);
 
         // Spawn process
-        let mut child = cmd
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .kill_on_drop(true)
-            .spawn()
+        let mut child = supervisor()
+            .spawn(&spec)
             .map_err(|e| format!("Failed to spawn process: {}", e))?;
 
-        let pid = child.id().ok_or("Failed to get process ID")?;
+        let pid = child.pid().ok_or("Failed to get process ID")?;
 
         // Initialize logs
         {
@@ -90,33 +82,19 @@ impl CliService {
             logs.insert(deployment_id.to_string(), Vec::new());
         }
 
-        // Spawn tasks to read stdout and stderr
-        let deployment_id_stdout = deployment_id.to_string();
-        let deployment_id_stderr = deployment_id.to_string();
-        let logs_stdout = Arc::clone(&self.logs);
-        let logs_stderr = Arc::clone(&self.logs);
-
-        let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
-        let stderr = child.stderr.take().ok_or("Failed to get stderr")?;
-
-        // Read stdout
-        let mut stdout_reader = BufReader::new(stdout).lines();
-        tokio::spawn(async move {
-            while let Ok(Some(line)) = stdout_reader.next_line().await {
-                let mut logs = logs_stdout.lock().unwrap();
-                if let Some(log_vec) = logs.get_mut(&deployment_id_stdout) {
-                    log_vec.push(format!("[STDOUT] {}", line));
-                }
-            }
-        });
-
-        // Read stderr
-        let mut stderr_reader = BufReader::new(stderr).lines();
+        // Collect stdout and stderr into the logs
+        let deployment_id_output = deployment_id.to_string();
+        let logs_output = Arc::clone(&self.logs);
+        let mut output = child.take_output();
         tokio::spawn(async move {
-            while let Ok(Some(line)) = stderr_reader.next_line().await {
-                let mut logs = logs_stderr.lock().unwrap();
-                if let Some(log_vec) = logs.get_mut(&deployment_id_stderr) {
-                    log_vec.push(format!("[STDERR] {}", line));
+            while let Some(output) = output.recv().await {
+                let prefix = match output.stream {
+                    OutputStream::Stdout => "[STDOUT]",
+                    OutputStream::Stderr => "[STDERR]",
+                };
+                let mut logs = logs_output.lock().unwrap();
+                if let Some(log_vec) = logs.get_mut(&deployment_id_output) {
+                    log_vec.push(format!("{} {}", prefix, output.line));
                 }
             }
         });
@@ -146,13 +124,8 @@ impl CliService {
         }; // Lock is released here
 
         if let Some(mut child) = child_opt {
-            // Try graceful shutdown first
-            if let Err(e) = child.kill().await {
-                return Err(format!("Failed to kill process: {}", e));
-            }
-
-            // Wait for process to exit
-            let _ = child.wait().await;
+            // Takes whatever it started along with it, and waits for it to exit
+            child.kill_tree().await;
 
             Ok(())
         } else {
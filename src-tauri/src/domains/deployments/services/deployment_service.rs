@@ -56,6 +56,13 @@ impl DeploymentService {
         })
     }
 
+    /// Reload the cached deployments, e.g. after switching workspaces
+    pub async fn reload(&self) -> Result<(), String> {
+        let deployments = self.repo.find_all().await?;
+        *self.cache.write().await = deployments;
+        Ok(())
+    }
+
     async fn persist(&self, deployment: &Deployment) -> Result<(), String> {
         self.repo.save(deployment).await?;
         let mut cache = self.cache.write().await;
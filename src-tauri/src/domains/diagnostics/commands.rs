@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use tauri::State;
+
+use super::doctor::{Doctor, DoctorReport};
+use crate::database::DatabaseManager;
+use crate::domains::automation::services::automation_service::AutomationService;
+
+/// Check Docker, Kubernetes, SDK managers, n8n, Ollama, the database and disk
+/// space, with a suggested fix for each problem
+#[tauri::command]
+pub async fn run_doctor(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    automation_service: State<'_, Arc<AutomationService>>,
+) -> Result<DoctorReport, String> {
+    Ok(Doctor::new(
+        db_manager.inner().clone(),
+        automation_service.inner().clone(),
+    )
+    .run()
+    .await)
+}
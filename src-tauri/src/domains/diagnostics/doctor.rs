@@ -0,0 +1,417 @@
+//! System doctor: checks the tools and services the app depends on and
+//! suggests a fix for anything missing or broken.
+//!
+//! Checks run concurrently and each has a timeout, so one hung service can't
+//! hold up the report. `Warning` means an optional feature is unavailable;
+//! `Error` means something the app relies on is broken.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::database::DatabaseManager;
+use crate::domains::automation::services::automation_service::AutomationService;
+use crate::domains::disk::disk::disk_usage;
+use crate::domains::kubernetes::manager::KubernetesManager;
+use crate::domains::sdk::factory::SDKManagerFactory;
+use crate::process_ext::NoWindowExt;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+const OLLAMA_URL: &str = "http://localhost:11434";
+const GIB: u64 = 1024 * 1024 * 1024;
+/// Below this much free space the database and backups may fail to write
+const DISK_ERROR_BYTES: u64 = GIB;
+const DISK_WARNING_BYTES: u64 = 5 * GIB;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warning or error
+    pub fix: Option<String>,
+}
+
+impl DoctorCheck {
+    fn ok(id: &'static str, name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            id,
+            name,
+            status: CheckStatus::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(
+        id: &'static str,
+        name: &'static str,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Warning,
+            fix: Some(fix.into()),
+            ..Self::ok(id, name, detail)
+        }
+    }
+
+    fn error(
+        id: &'static str,
+        name: &'static str,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Error,
+            fix: Some(fix.into()),
+            ..Self::ok(id, name, detail)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    /// Worst status of any check
+    pub status: CheckStatus,
+    pub checks: Vec<DoctorCheck>,
+    pub ran_at: DateTime<Utc>,
+}
+
+pub struct Doctor {
+    db_manager: Arc<DatabaseManager>,
+    automation_service: Arc<AutomationService>,
+}
+
+impl Doctor {
+    pub fn new(
+        db_manager: Arc<DatabaseManager>,
+        automation_service: Arc<AutomationService>,
+    ) -> Self {
+        Self {
+            db_manager,
+            automation_service,
+        }
+    }
+
+    pub async fn run(&self) -> DoctorReport {
+        let (docker, kubernetes, sdk_managers, n8n, ollama, database, disk) = tokio::join!(
+            check_docker(),
+            check_kubernetes(),
+            check_sdk_managers(),
+            self.check_n8n(),
+            check_ollama(),
+            self.check_database(),
+            self.check_disk_space(),
+        );
+        let checks = vec![
+            docker,
+            kubernetes,
+            sdk_managers,
+            n8n,
+            ollama,
+            database,
+            disk,
+        ];
+        DoctorReport {
+            status: checks
+                .iter()
+                .map(|check| check.status)
+                .max()
+                .unwrap_or(CheckStatus::Ok),
+            checks,
+            ran_at: Utc::now(),
+        }
+    }
+
+    async fn check_n8n(&self) -> DoctorCheck {
+        const ID: &str = "n8n";
+        const NAME: &str = "n8n";
+        match tokio::time::timeout(CHECK_TIMEOUT, self.automation_service.check_n8n_health()).await
+        {
+            Ok(Ok(true)) => DoctorCheck::ok(ID, NAME, "n8n is reachable"),
+            Ok(Ok(false)) => DoctorCheck::warning(
+                ID,
+                NAME,
+                "n8n responded but reports it isn't healthy",
+                "Check the n8n logs and restart it",
+            ),
+            Ok(Err(_)) | Err(_) => DoctorCheck::warning(
+                ID,
+                NAME,
+                "n8n isn't reachable, so workflow automations won't run",
+                "Start n8n, e.g. with `npx n8n` or `docker run -p 5678:5678 n8nio/n8n`",
+            ),
+        }
+    }
+
+    async fn check_database(&self) -> DoctorCheck {
+        const ID: &str = "database";
+        const NAME: &str = "Database integrity";
+        match self.db_manager.check_integrity(true).await {
+            Ok(report) if report.ok => DoctorCheck::ok(ID, NAME, "No problems found"),
+            Ok(report) => DoctorCheck::error(
+                ID,
+                NAME,
+                format!("SQLite reported: {}", report.problems.join("; ")),
+                "Restore a recent backup from Settings > Database",
+            ),
+            Err(e) => DoctorCheck::error(
+                ID,
+                NAME,
+                format!("Integrity check failed: {}", e),
+                "Restart the app; if this persists, restore a recent backup",
+            ),
+        }
+    }
+
+    async fn check_disk_space(&self) -> DoctorCheck {
+        let data_dir = self.db_manager.workspace_dir();
+        let disks = tokio::task::spawn_blocking(disk_usage)
+            .await
+            .unwrap_or_default();
+        // The volume holding the data is the one with the longest matching
+        // mount point
+        let disk = disks
+            .iter()
+            .filter(|disk| data_dir.starts_with(Path::new(&disk.mount_point)))
+            .max_by_key(|disk| disk.mount_point.len());
+        match disk {
+            Some(disk) => disk_space(&disk.mount_point, disk.available_bytes),
+            None => DoctorCheck::warning(
+                "disk_space",
+                "Disk space",
+                format!("Couldn't find the volume holding {}", data_dir.display()),
+                "Check that the app data folder is on a mounted drive",
+            ),
+        }
+    }
+}
+
+fn disk_space(mount_point: &str, available_bytes: u64) -> DoctorCheck {
+    const ID: &str = "disk_space";
+    const NAME: &str = "Disk space";
+    let detail = format!(
+        "{:.1} GB free on {}",
+        available_bytes as f64 / GIB as f64,
+        mount_point
+    );
+    let fix = "Free up space; Disk Cleanup can find regenerable build folders and caches";
+    if available_bytes < DISK_ERROR_BYTES {
+        DoctorCheck::error(ID, NAME, detail, fix)
+    } else if available_bytes < DISK_WARNING_BYTES {
+        DoctorCheck::warning(ID, NAME, detail, fix)
+    } else {
+        DoctorCheck::ok(ID, NAME, detail)
+    }
+}
+
+/// Run `program` with a timeout. `Ok` holds stdout on success; `Err` holds
+/// stderr, or why it couldn't run.
+async fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = tokio::time::timeout(
+        CHECK_TIMEOUT,
+        Command::new(program)
+            .no_window()
+            .args(args)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("`{}` timed out", program))?
+    .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+async fn check_docker() -> DoctorCheck {
+    const ID: &str = "docker";
+    const NAME: &str = "Docker";
+    if which::which("docker").is_err() {
+        return DoctorCheck::warning(
+            ID,
+            NAME,
+            "Docker isn't installed, so Docker pipeline steps can't run",
+            "Install Docker Desktop or Docker Engine",
+        );
+    }
+    match run("docker", &["info", "--format", "{{.ServerVersion}}"]).await {
+        Ok(version) => DoctorCheck::ok(ID, NAME, format!("Docker daemon {} is running", version)),
+        Err(stderr) => docker_failure(&stderr),
+    }
+}
+
+fn docker_failure(stderr: &str) -> DoctorCheck {
+    const ID: &str = "docker";
+    const NAME: &str = "Docker";
+    if stderr.to_lowercase().contains("permission denied") {
+        DoctorCheck::error(
+            ID,
+            NAME,
+            "The Docker socket refused access",
+            "Add your user to the docker group (`sudo usermod -aG docker $USER`) and log in again",
+        )
+    } else {
+        DoctorCheck::warning(
+            ID,
+            NAME,
+            "Docker is installed but the daemon isn't reachable",
+            "Start Docker Desktop or the docker service",
+        )
+    }
+}
+
+async fn check_kubernetes() -> DoctorCheck {
+    const ID: &str = "kubernetes";
+    const NAME: &str = "kubectl and kubeconfig";
+    let kubectl = which::which("kubectl").is_ok();
+    let Some(path) = KubernetesManager::kubeconfig_path() else {
+        return DoctorCheck::warning(
+            ID,
+            NAME,
+            "No kubeconfig found, so clusters can't be browsed",
+            "Set KUBECONFIG or create ~/.kube/config, e.g. with your cloud CLI",
+        );
+    };
+    let context = match kube::config::Kubeconfig::read_from(&path) {
+        Ok(kubeconfig) => kubeconfig.current_context,
+        Err(e) => {
+            return DoctorCheck::error(
+                ID,
+                NAME,
+                format!("{} can't be read: {}", path.display(), e),
+                "Fix or regenerate the kubeconfig",
+            )
+        }
+    };
+    let detail = format!(
+        "{} (context: {})",
+        path.display(),
+        context.as_deref().unwrap_or("none")
+    );
+    if !kubectl {
+        return DoctorCheck::warning(
+            ID,
+            NAME,
+            format!("kubectl isn't installed; kubeconfig is {}", detail),
+            "Install kubectl to use port forwarding and exec",
+        );
+    }
+    if context.is_none() {
+        return DoctorCheck::warning(
+            ID,
+            NAME,
+            detail,
+            "Pick a context with `kubectl config use-context <name>`",
+        );
+    }
+    DoctorCheck::ok(ID, NAME, detail)
+}
+
+async fn check_sdk_managers() -> DoctorCheck {
+    const ID: &str = "sdk_managers";
+    const NAME: &str = "SDK managers";
+    let factory = SDKManagerFactory::new();
+    let installed = tokio::time::timeout(CHECK_TIMEOUT, factory.detect_installed_managers())
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or_default();
+    let mut names: Vec<String> = installed
+        .iter()
+        .filter_map(|info| {
+            let name = info.get("display_name").or_else(|| info.get("name"))?;
+            Some(match info.get("version") {
+                Some(version) => format!("{} {}", name, version),
+                None => name.clone(),
+            })
+        })
+        .collect();
+    names.sort();
+    if names.is_empty() {
+        DoctorCheck::warning(
+            ID,
+            NAME,
+            "No SDK version managers found",
+            "Install one for your languages, e.g. nvm, pyenv, rustup or SDKMAN!",
+        )
+    } else {
+        DoctorCheck::ok(ID, NAME, names.join(", "))
+    }
+}
+
+async fn check_ollama() -> DoctorCheck {
+    const ID: &str = "ollama";
+    const NAME: &str = "Ollama";
+    let response = tokio::time::timeout(
+        CHECK_TIMEOUT,
+        reqwest::Client::new()
+            .get(format!("{}/api/version", OLLAMA_URL))
+            .send(),
+    )
+    .await;
+    if let Ok(Ok(response)) = response {
+        if response.status().is_success() {
+            let version = response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|body| body["version"].as_str().map(String::from))
+                .unwrap_or_else(|| "unknown".to_string());
+            return DoctorCheck::ok(ID, NAME, format!("Ollama {} is running", version));
+        }
+    }
+    if which::which("ollama").is_ok() {
+        DoctorCheck::warning(
+            ID,
+            NAME,
+            "Ollama is installed but not running, so local models are unavailable",
+            "Run `ollama serve` or start the Ollama app",
+        )
+    } else {
+        DoctorCheck::warning(
+            ID,
+            NAME,
+            "Ollama isn't installed, so local models are unavailable",
+            "Install Ollama from https://ollama.com",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn low_disk_space_warns_then_fails() {
+        assert_eq!(disk_space("/", 20 * GIB).status, CheckStatus::Ok);
+        assert_eq!(disk_space("/", 2 * GIB).status, CheckStatus::Warning);
+        let check = disk_space("/", GIB / 2);
+        assert_eq!(check.status, CheckStatus::Error);
+        assert_eq!(check.detail, "0.5 GB free on /");
+    }
+
+    #[test]
+    fn docker_permission_errors_are_failures() {
+        let denied =
+            docker_failure("permission denied while trying to connect to the Docker daemon socket");
+        assert_eq!(denied.status, CheckStatus::Error);
+        let stopped = docker_failure("Cannot connect to the Docker daemon. Is it running?");
+        assert_eq!(stopped.status, CheckStatus::Warning);
+    }
+}
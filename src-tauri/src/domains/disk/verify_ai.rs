@@ -34,6 +34,8 @@ pub async fn verify_with_ai(
         // Deterministic-ish review; give room for the per-item JSON list.
         temperature: Some(0.2),
         max_tokens: Some(4096),
+        feature: Some("disk_review".to_string()),
+        bypass_cache: false,
         ..Default::default()
     };
 
@@ -0,0 +1,140 @@
+//! Document tools for the AI assistant
+use super::commands::{get_document, search_documents, update_document, UpdateDocumentCommand};
+use super::services::document_diff::{diff_lines, render_changes};
+use crate::database::DatabaseManager;
+use crate::domains::ai::tools::{ActionPreview, PreviewMethod, ToolRegistry, ToolSpec};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::Manager;
+
+pub fn register_ai_tools(registry: &ToolRegistry) {
+    registry.register(
+        ToolSpec {
+            name: "list_documents".to_string(),
+            description: "Find documents whose title or content matches a query; returns ids \
+                          and titles"
+                .to_string(),
+            domain: "documents".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": { "query": { "type": "string" } },
+                "required": ["query"]
+            }),
+        },
+        |app, args: Value| async move {
+            let query = args
+                .get("query")
+                .and_then(Value::as_str)
+                .ok_or("query is required")?
+                .to_string();
+            let documents = search_documents(app.state::<Arc<DatabaseManager>>(), query).await?;
+            Ok(documents
+                .into_iter()
+                .map(|doc| json!({ "id": doc.id, "title": doc.title }))
+                .collect())
+        },
+    );
+
+    registry.register(
+        ToolSpec {
+            name: "read_document".to_string(),
+            description: "Read a document's title and content by id".to_string(),
+            domain: "documents".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": { "id": { "type": "integer" } },
+                "required": ["id"]
+            }),
+        },
+        |app, args: Value| async move {
+            let id = document_id(&args)?;
+            let document = get_document(app.state::<Arc<DatabaseManager>>(), id)
+                .await?
+                .ok_or_else(|| format!("Document {} not found", id))?;
+            serde_json::to_value(document).map_err(|e| e.to_string())
+        },
+    );
+
+    registry.register(
+        ToolSpec {
+            name: "update_document".to_string(),
+            description: "Replace a document's title and/or content by id".to_string(),
+            domain: "documents".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer" },
+                    "title": { "type": "string" },
+                    "content": { "type": "string", "description": "The full new content" }
+                },
+                "required": ["id"]
+            }),
+        },
+        |app, args: Value| async move {
+            let id = document_id(&args)?;
+            let document = update_document(
+                app.state::<Arc<DatabaseManager>>(),
+                id,
+                UpdateDocumentCommand {
+                    title: args.get("title").and_then(Value::as_str).map(String::from),
+                    content: args
+                        .get("content")
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                    is_archived: None,
+                    tags: None,
+                    project_id: None,
+                },
+            )
+            .await?;
+            Ok(json!({ "id": document.id, "title": document.title }))
+        },
+    );
+
+    registry.register_preview("update_document", |app, args: Value| async move {
+        let id = document_id(&args)?;
+        let document = get_document(app.state::<Arc<DatabaseManager>>(), id)
+            .await?
+            .ok_or_else(|| format!("Document {} not found", id))?;
+        let title = args.get("title").and_then(Value::as_str);
+        let content = args.get("content").and_then(Value::as_str);
+
+        let mut changes = Vec::new();
+        let mut details = Vec::new();
+        if let Some(title) = title.filter(|title| *title != document.title) {
+            changes.push("title".to_string());
+            details.push(format!("Title: {} -> {}", document.title, title));
+        }
+        if let Some(content) = content {
+            let diff = diff_lines(&document.content, content);
+            if diff.additions + diff.deletions > 0 {
+                changes.push(format!(
+                    "content (+{} -{} lines)",
+                    diff.additions, diff.deletions
+                ));
+                details.push(render_changes(&diff, 2));
+            }
+        }
+        let summary = if changes.is_empty() {
+            format!("No changes to \"{}\"", document.title)
+        } else {
+            format!(
+                "Change the {} of \"{}\"",
+                changes.join(" and "),
+                document.title
+            )
+        };
+        Ok(ActionPreview {
+            method: PreviewMethod::Diff,
+            summary,
+            details: (!details.is_empty()).then(|| details.join("\n\n")),
+        })
+    });
+}
+
+fn document_id(args: &Value) -> Result<i32, String> {
+    args.get("id")
+        .and_then(Value::as_i64)
+        .and_then(|id| i32::try_from(id).ok())
+        .ok_or_else(|| "id must be a document id".to_string())
+}
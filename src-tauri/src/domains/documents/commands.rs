@@ -4,10 +4,32 @@ use crate::domains::ai::services::AIService;
 use crate::domains::documents::repositories::document_repository::{
     CreateDocumentRequest, UpdateDocumentRequest,
 };
+use crate::domains::documents::repositories::document_template_repository::{
+    CreateDocumentTemplateRequest, UpdateDocumentTemplateRequest,
+};
 use crate::domains::documents::services::ai_document_generator::{
     AIDocumentGenerator, DocumentContext, GeneratedDocumentStructure,
 };
-use crate::domains::documents::services::document_service::DocumentService;
+use crate::domains::documents::services::document_export::{
+    export_pdf, render_document_html, ExportFormat,
+};
+use crate::domains::documents::services::document_link_service::{
+    DocumentBacklink, DocumentGraph, DocumentLinkService,
+};
+use crate::domains::documents::services::document_qa_service::{
+    DocumentIndexStats, DocumentQaService, DocumentSummary, ProjectDocsAnswer,
+};
+use crate::domains::documents::services::document_service::{
+    DocumentRevisionDiff, DocumentSearchParams, DocumentSearchResults, DocumentService,
+};
+use crate::domains::documents::services::document_sync_service::{
+    ConflictResolution, DocumentSyncReport, DocumentSyncService, DocumentSyncWatchers,
+};
+use crate::domains::documents::services::document_template_service::{
+    CreateDocumentFromTemplateRequest, DocumentFromTemplate, DocumentTemplateService,
+};
+use crate::entities::document_sync_folder::Model as DocumentSyncFolderModel;
+use crate::entities::document_template::Model as DocumentTemplateModel;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::State;
@@ -21,6 +43,7 @@ pub struct DocumentResponse {
     pub content_draft: Option<String>,
     pub is_draft: bool,
     pub tags: Option<String>, // JSON array of strings
+    pub project_id: Option<i32>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
     pub last_edited_at: Option<chrono::DateTime<chrono::Utc>>,
@@ -36,6 +59,7 @@ impl From<crate::entities::document::Model> for DocumentResponse {
             content_draft: model.content_draft,
             is_draft: model.is_draft,
             tags: model.tags,
+            project_id: model.project_id,
             created_at: model.created_at.map(|dt| dt.into()),
             updated_at: model.updated_at.map(|dt| dt.into()),
             last_edited_at: model.last_edited_at.map(|dt| dt.into()),
@@ -43,12 +67,38 @@ impl From<crate::entities::document::Model> for DocumentResponse {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentRevisionResponse {
+    pub id: i32,
+    pub document_id: i32,
+    pub revision_number: i32,
+    pub title: String,
+    pub content: String,
+    pub tags: Option<String>, // JSON array of strings
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<crate::entities::document_revision::Model> for DocumentRevisionResponse {
+    fn from(model: crate::entities::document_revision::Model) -> Self {
+        Self {
+            id: model.id,
+            document_id: model.document_id,
+            revision_number: model.revision_number,
+            title: model.title,
+            content: model.content,
+            tags: model.tags,
+            created_at: model.created_at.map(|dt| dt.into()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateDocumentCommand {
     pub title: String,
     pub content: String,
     pub is_archived: Option<bool>,
     pub tags: Option<Vec<String>>,
+    pub project_id: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +107,7 @@ pub struct UpdateDocumentCommand {
     pub content: Option<String>,
     pub is_archived: Option<bool>,
     pub tags: Option<Vec<String>>,
+    pub project_id: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +138,7 @@ pub async fn create_document(
         content: command.content,
         is_archived: command.is_archived,
         tags: command.tags,
+        project_id: command.project_id,
     };
 
     service
@@ -139,6 +191,7 @@ pub async fn update_document(
         content: command.content,
         is_archived: command.is_archived,
         tags: command.tags,
+        project_id: command.project_id,
     };
 
     service
@@ -183,6 +236,66 @@ pub async fn save_document(
         .map_err(|e| format!("Failed to save document: {}", e))
 }
 
+#[tauri::command]
+pub async fn get_document_revisions(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    document_id: i32,
+) -> Result<Vec<DocumentRevisionResponse>, String> {
+    let conn = db_manager.get_connection_clone();
+    let service = DocumentService::new(conn);
+
+    service
+        .list_revisions(document_id)
+        .await
+        .map(|revisions| revisions.into_iter().map(|r| r.into()).collect())
+        .map_err(|e| format!("Failed to get document revisions: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_document_revision(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    revision_id: i32,
+) -> Result<Option<DocumentRevisionResponse>, String> {
+    let conn = db_manager.get_connection_clone();
+    let service = DocumentService::new(conn);
+
+    service
+        .get_revision(revision_id)
+        .await
+        .map(|opt| opt.map(|r| r.into()))
+        .map_err(|e| format!("Failed to get document revision: {}", e))
+}
+
+#[tauri::command]
+pub async fn diff_document_revisions(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    from_revision_id: i32,
+    to_revision_id: i32,
+) -> Result<DocumentRevisionDiff, String> {
+    let conn = db_manager.get_connection_clone();
+    let service = DocumentService::new(conn);
+
+    service
+        .diff_revisions(from_revision_id, to_revision_id)
+        .await
+        .map_err(|e| format!("Failed to diff document revisions: {}", e))
+}
+
+#[tauri::command]
+pub async fn restore_document_revision(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    revision_id: i32,
+) -> Result<DocumentResponse, String> {
+    let conn = db_manager.get_connection_clone();
+    let service = DocumentService::new(conn);
+
+    service
+        .restore_revision(revision_id)
+        .await
+        .map(|doc| doc.into())
+        .map_err(|e| format!("Failed to restore document revision: {}", e))
+}
+
 #[tauri::command]
 pub async fn delete_document(
     db_manager: State<'_, Arc<DatabaseManager>>,
@@ -212,6 +325,298 @@ pub async fn search_documents(
         .map_err(|e| format!("Failed to search documents: {}", e))
 }
 
+#[tauri::command]
+pub async fn search_documents_full_text(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    params: DocumentSearchParams,
+) -> Result<DocumentSearchResults, String> {
+    let conn = db_manager.get_connection_clone();
+    let service = DocumentService::new(conn);
+
+    service
+        .search_full_text(params)
+        .await
+        .map_err(|e| format!("Failed to search documents: {}", e))
+}
+
+/// Link a project to a folder of markdown files and start watching it.
+#[tauri::command]
+pub async fn set_document_sync_folder(
+    app: tauri::AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    watchers: State<'_, DocumentSyncWatchers>,
+    project_id: i32,
+    folder_path: String,
+) -> Result<DocumentSyncFolderModel, String> {
+    let service = DocumentSyncService::new(db_manager.get_connection_clone());
+
+    let folder = service
+        .set_folder(project_id, &folder_path)
+        .await
+        .map_err(|e| format!("Failed to set document sync folder: {}", e))?;
+    watchers.start(project_id, db_manager.inner().clone(), app);
+
+    Ok(folder)
+}
+
+#[tauri::command]
+pub async fn get_document_sync_folder(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    project_id: i32,
+) -> Result<Option<DocumentSyncFolderModel>, String> {
+    let service = DocumentSyncService::new(db_manager.get_connection_clone());
+
+    service
+        .get_folder(project_id)
+        .await
+        .map_err(|e| format!("Failed to get document sync folder: {}", e))
+}
+
+/// Stop syncing a project. Files already on disk are left in place.
+#[tauri::command]
+pub async fn remove_document_sync_folder(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    watchers: State<'_, DocumentSyncWatchers>,
+    project_id: i32,
+) -> Result<(), String> {
+    watchers.stop(project_id);
+    let service = DocumentSyncService::new(db_manager.get_connection_clone());
+
+    service
+        .remove_folder(project_id)
+        .await
+        .map_err(|e| format!("Failed to remove document sync folder: {}", e))
+}
+
+#[tauri::command]
+pub async fn export_documents_to_folder(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    project_id: i32,
+) -> Result<DocumentSyncReport, String> {
+    let service = DocumentSyncService::new(db_manager.get_connection_clone());
+
+    service
+        .export_project(project_id)
+        .await
+        .map_err(|e| format!("Failed to export documents: {}", e))
+}
+
+#[tauri::command]
+pub async fn import_documents_from_folder(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    project_id: i32,
+) -> Result<DocumentSyncReport, String> {
+    let service = DocumentSyncService::new(db_manager.get_connection_clone());
+
+    service
+        .import_project(project_id)
+        .await
+        .map_err(|e| format!("Failed to import documents: {}", e))
+}
+
+#[tauri::command]
+pub async fn sync_documents_folder(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    project_id: i32,
+) -> Result<DocumentSyncReport, String> {
+    let service = DocumentSyncService::new(db_manager.get_connection_clone());
+
+    service
+        .sync_project(project_id)
+        .await
+        .map_err(|e| format!("Failed to sync documents: {}", e))
+}
+
+#[tauri::command]
+pub async fn resolve_document_sync_conflict(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    project_id: i32,
+    file_path: String,
+    resolution: ConflictResolution,
+) -> Result<DocumentResponse, String> {
+    let service = DocumentSyncService::new(db_manager.get_connection_clone());
+
+    service
+        .resolve_conflict(project_id, &file_path, resolution)
+        .await
+        .map(|doc| doc.into())
+        .map_err(|e| format!("Failed to resolve sync conflict: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_document_templates(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<DocumentTemplateModel>, String> {
+    let service = DocumentTemplateService::new(db_manager.inner());
+
+    service
+        .get_templates()
+        .await
+        .map_err(|e| format!("Failed to get document templates: {}", e))
+}
+
+#[tauri::command]
+pub async fn create_document_template(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    request: CreateDocumentTemplateRequest,
+) -> Result<DocumentTemplateModel, String> {
+    let service = DocumentTemplateService::new(db_manager.inner());
+
+    service
+        .create_template(request)
+        .await
+        .map_err(|e| format!("Failed to create document template: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_document_template(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: i32,
+    request: UpdateDocumentTemplateRequest,
+) -> Result<DocumentTemplateModel, String> {
+    let service = DocumentTemplateService::new(db_manager.inner());
+
+    service
+        .update_template(id, request)
+        .await
+        .map_err(|e| format!("Failed to update document template: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_document_template(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: i32,
+) -> Result<(), String> {
+    let service = DocumentTemplateService::new(db_manager.inner());
+
+    service
+        .delete_template(id)
+        .await
+        .map_err(|e| format!("Failed to delete document template: {}", e))
+}
+
+/// Create a document from a template, filling in `{{variables}}` and, when
+/// requested, letting the AI draft the template's sections.
+#[tauri::command]
+pub async fn create_document_from_template(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    ai_service: State<'_, Arc<AIService>>,
+    request: CreateDocumentFromTemplateRequest,
+) -> Result<DocumentFromTemplate, String> {
+    let service = DocumentTemplateService::new(db_manager.inner());
+
+    service
+        .create_document(request, ai_service.inner())
+        .await
+        .map_err(|e| format!("Failed to create document from template: {}", e))
+}
+
+/// Documents linking to `document_id` via `[[Title]]`
+#[tauri::command]
+pub async fn get_document_backlinks(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    document_id: i32,
+) -> Result<Vec<DocumentBacklink>, String> {
+    let service = DocumentLinkService::new(db_manager.get_connection_clone());
+
+    service
+        .get_backlinks(document_id)
+        .await
+        .map_err(|e| format!("Failed to get document backlinks: {}", e))
+}
+
+/// Nodes and edges across documents, tasks and projects for the knowledge graph
+#[tauri::command]
+pub async fn get_document_graph(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    project_id: Option<i32>,
+) -> Result<DocumentGraph, String> {
+    let service = DocumentLinkService::new(db_manager.get_connection_clone());
+
+    service
+        .get_graph(project_id)
+        .await
+        .map_err(|e| format!("Failed to get document graph: {}", e))
+}
+
+/// Re-parse links for every document. Returns how many documents were processed.
+#[tauri::command]
+pub async fn rebuild_document_links(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<usize, String> {
+    let service = DocumentLinkService::new(db_manager.get_connection_clone());
+
+    service
+        .rebuild_all()
+        .await
+        .map_err(|e| format!("Failed to rebuild document links: {}", e))
+}
+
+/// Export a document as styled HTML or PDF. Without `path`, asks where to save
+/// it; returns the written path, or `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_document(
+    app: tauri::AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: i32,
+    format: ExportFormat,
+    path: Option<String>,
+) -> Result<Option<String>, String> {
+    use crate::domains::documents::services::markdown_sync::slugify;
+    use crate::domains::projects::services::project_service::ProjectService;
+
+    let service = DocumentService::new(db_manager.get_connection_clone());
+    let document = service
+        .get_document(id)
+        .await
+        .map_err(|e| format!("Failed to get document: {}", e))?
+        .ok_or_else(|| "Document not found".to_string())?;
+
+    let output = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            use std::sync::mpsc;
+            use tauri_plugin_dialog::DialogExt;
+
+            let (tx, rx) = mpsc::channel();
+            let extension = format.extension();
+            app.dialog()
+                .file()
+                .set_title("Export Document")
+                .set_file_name(format!("{}.{}", slugify(&document.title), extension))
+                .add_filter(extension.to_uppercase(), &[extension])
+                .save_file(move |path| {
+                    let _ = tx.send(path);
+                });
+
+            match rx.recv() {
+                Ok(Some(path)) => std::path::PathBuf::from(path.to_string()),
+                _ => return Ok(None),
+            }
+        }
+    };
+
+    // Relative image paths in the document are resolved against its project.
+    let base_dir = match document.project_id {
+        Some(project_id) => ProjectService::new(db_manager.inner())
+            .get_project(project_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|project| std::path::PathBuf::from(project.path)),
+        None => None,
+    };
+
+    let html = render_document_html(&document, base_dir.as_deref());
+    match format {
+        ExportFormat::Html => std::fs::write(&output, html)
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?,
+        ExportFormat::Pdf => export_pdf(&html, &output).await?,
+    }
+
+    Ok(Some(output.to_string_lossy().to_string()))
+}
+
 #[tauri::command]
 pub async fn generate_document_with_ai(
     ai_service: State<'_, Arc<AIService>>,
@@ -251,3 +656,67 @@ pub async fn generate_document_with_ai(
             format!("Failed to generate document: {}", e)
         })
 }
+
+/// Chunk and embed documents for Q&A. Unchanged documents are skipped unless `force`.
+#[tauri::command]
+pub async fn index_project_docs(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    ai_service: State<'_, Arc<AIService>>,
+    project_id: Option<i32>,
+    embedding_model: Option<String>,
+    force: Option<bool>,
+) -> Result<DocumentIndexStats, String> {
+    let service = DocumentQaService::new(
+        db_manager.get_connection_clone(),
+        ai_service.inner().clone(),
+        embedding_model,
+    );
+
+    service
+        .index_documents(project_id, force.unwrap_or(false))
+        .await
+}
+
+/// Answer a question from a project's documents (all documents when no project),
+/// citing the chunks used
+#[tauri::command]
+pub async fn ask_project_docs(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    ai_service: State<'_, Arc<AIService>>,
+    project_id: Option<i32>,
+    question: String,
+    top_k: Option<usize>,
+    embedding_model: Option<String>,
+    provider_type: Option<ProviderType>,
+) -> Result<ProjectDocsAnswer, String> {
+    if question.len() > 4000 {
+        return Err("Question is too long (max 4000 characters)".to_string());
+    }
+
+    let service = DocumentQaService::new(
+        db_manager.get_connection_clone(),
+        ai_service.inner().clone(),
+        embedding_model,
+    );
+
+    service
+        .ask(project_id, &question, top_k, provider_type)
+        .await
+}
+
+#[tauri::command]
+pub async fn summarize_document(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    ai_service: State<'_, Arc<AIService>>,
+    id: i32,
+    embedding_model: Option<String>,
+    provider_type: Option<ProviderType>,
+) -> Result<DocumentSummary, String> {
+    let service = DocumentQaService::new(
+        db_manager.get_connection_clone(),
+        ai_service.inner().clone(),
+        embedding_model,
+    );
+
+    service.summarize(id, provider_type).await
+}
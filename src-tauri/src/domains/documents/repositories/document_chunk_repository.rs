@@ -0,0 +1,74 @@
+use crate::entities::document_chunk::{
+    ActiveModel, Column, Entity as DocumentChunkEntity, Model as DocumentChunkModel,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+
+/// A chunk ready to be stored
+#[derive(Debug, Clone)]
+pub struct NewDocumentChunk {
+    pub heading: Option<String>,
+    pub content: String,
+    pub embedding: Option<Vec<u8>>,
+}
+
+pub struct DocumentChunkRepository {
+    db: DatabaseConnection,
+}
+
+impl DocumentChunkRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Replace every chunk of `document_id`.
+    pub async fn replace_for_document(
+        &self,
+        document_id: i32,
+        content_hash: &str,
+        embedding_model: Option<&str>,
+        chunks: Vec<NewDocumentChunk>,
+    ) -> Result<(), sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+
+        DocumentChunkEntity::delete_many()
+            .filter(Column::DocumentId.eq(document_id))
+            .exec(&txn)
+            .await?;
+
+        let now = chrono::Utc::now();
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            ActiveModel {
+                document_id: Set(document_id),
+                chunk_index: Set(index as i32),
+                heading: Set(chunk.heading),
+                content: Set(chunk.content),
+                content_hash: Set(content_hash.to_string()),
+                embedding_model: Set(chunk
+                    .embedding
+                    .as_ref()
+                    .and(embedding_model.map(str::to_string))),
+                embedding: Set(chunk.embedding),
+                created_at: Set(Some(now.into())),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+        }
+
+        txn.commit().await
+    }
+
+    pub async fn find_by_document_id(
+        &self,
+        document_id: i32,
+    ) -> Result<Vec<DocumentChunkModel>, sea_orm::DbErr> {
+        DocumentChunkEntity::find()
+            .filter(Column::DocumentId.eq(document_id))
+            .order_by_asc(Column::ChunkIndex)
+            .all(&self.db)
+            .await
+    }
+}
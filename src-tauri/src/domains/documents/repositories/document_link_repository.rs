@@ -0,0 +1,115 @@
+use crate::entities::document_link::{
+    ActiveModel, Column, Entity as DocumentLinkEntity, Model as DocumentLinkModel,
+};
+use crate::entities::{project, task};
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QuerySelect, Set, TransactionTrait,
+};
+
+/// A link ready to be stored
+#[derive(Debug, Clone)]
+pub struct NewDocumentLink {
+    pub target_type: String,
+    pub target_id: Option<i32>,
+    pub target_title: String,
+}
+
+pub struct DocumentLinkRepository {
+    db: DatabaseConnection,
+}
+
+impl DocumentLinkRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Replace every outgoing link of `source_document_id`.
+    pub async fn replace_for_document(
+        &self,
+        source_document_id: i32,
+        links: Vec<NewDocumentLink>,
+    ) -> Result<(), sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+
+        DocumentLinkEntity::delete_many()
+            .filter(Column::SourceDocumentId.eq(source_document_id))
+            .exec(&txn)
+            .await?;
+
+        let now = chrono::Utc::now();
+        for link in links {
+            ActiveModel {
+                source_document_id: Set(source_document_id),
+                target_type: Set(link.target_type),
+                target_id: Set(link.target_id),
+                target_title: Set(link.target_title),
+                created_at: Set(Some(now.into())),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+        }
+
+        txn.commit().await
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<DocumentLinkModel>, sea_orm::DbErr> {
+        DocumentLinkEntity::find().all(&self.db).await
+    }
+
+    /// Links pointing at a document, either resolved by id or still unresolved but
+    /// written with its current title.
+    pub async fn find_backlinks(
+        &self,
+        document_id: i32,
+        title: &str,
+    ) -> Result<Vec<DocumentLinkModel>, sea_orm::DbErr> {
+        DocumentLinkEntity::find()
+            .filter(Column::TargetType.eq("document"))
+            .filter(
+                Condition::any().add(Column::TargetId.eq(document_id)).add(
+                    Condition::all().add(Column::TargetId.is_null()).add(
+                        Expr::expr(Func::lower(Expr::col(Column::TargetTitle)))
+                            .eq(title.to_lowercase()),
+                    ),
+                ),
+            )
+            .filter(Column::SourceDocumentId.ne(document_id))
+            .all(&self.db)
+            .await
+    }
+
+    /// Which of `ids` are existing tasks
+    pub async fn existing_task_ids(&self, ids: Vec<i32>) -> Result<Vec<i32>, sea_orm::DbErr> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        task::Entity::find()
+            .select_only()
+            .column(task::Column::Id)
+            .filter(task::Column::Id.is_in(ids))
+            .into_tuple()
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_tasks(&self, ids: Vec<i32>) -> Result<Vec<task::Model>, sea_orm::DbErr> {
+        task::Entity::find()
+            .filter(task::Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+    }
+
+    /// `(id, name)` of every project
+    pub async fn project_names(&self) -> Result<Vec<(i32, String)>, sea_orm::DbErr> {
+        project::Entity::find()
+            .select_only()
+            .column(project::Column::Id)
+            .column(project::Column::Name)
+            .into_tuple()
+            .all(&self.db)
+            .await
+    }
+}
@@ -2,8 +2,8 @@ use crate::entities::document::{
     ActiveModel, Column, Entity as DocumentEntity, Model as DocumentModel,
 };
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, NotSet, QueryFilter,
-    QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbBackend, EntityTrait, FromQueryResult,
+    NotSet, QueryFilter, QueryOrder, Set, Statement,
 };
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +13,7 @@ pub struct CreateDocumentRequest {
     pub content: String,
     pub is_archived: Option<bool>,
     pub tags: Option<Vec<String>>,
+    pub project_id: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,8 +22,28 @@ pub struct UpdateDocumentRequest {
     pub content: Option<String>,
     pub is_archived: Option<bool>,
     pub tags: Option<Vec<String>>,
+    pub project_id: Option<i32>,
 }
 
+/// One row from the FTS index, joined back to its document
+#[derive(Debug, Clone, FromQueryResult)]
+pub struct DocumentSearchRow {
+    pub id: i32,
+    pub title: String,
+    pub tags: Option<String>,
+    pub project_id: Option<i32>,
+    pub is_archived: bool,
+    pub updated_at: Option<sea_orm::prelude::DateTimeWithTimeZone>,
+    /// bm25 score; lower is better
+    pub score: f64,
+    pub title_highlight: String,
+    pub snippet: String,
+}
+
+/// Markers wrapped around matched terms in highlights and snippets
+pub const HIGHLIGHT_START: &str = "<mark>";
+pub const HIGHLIGHT_END: &str = "</mark>";
+
 pub struct DocumentRepository {
     db: DatabaseConnection,
 }
@@ -50,6 +71,7 @@ impl DocumentRepository {
             content_draft: Set(None),
             is_draft: Set(false),
             tags: Set(Some(tags_json)),
+            project_id: Set(request.project_id),
             created_at: Set(Some(now.into())),
             updated_at: Set(Some(now.into())),
             last_edited_at: Set(Some(now.into())),
@@ -83,6 +105,9 @@ impl DocumentRepository {
             let tags_json = serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string());
             active_model.tags = Set(Some(tags_json));
         }
+        if let Some(project_id) = request.project_id {
+            active_model.project_id = Set(Some(project_id));
+        }
 
         active_model.updated_at = Set(Some(chrono::Utc::now().into()));
         active_model.is_draft = Set(false);
@@ -161,6 +186,13 @@ impl DocumentRepository {
         DocumentEntity::find_by_id(id).one(&self.db).await
     }
 
+    pub async fn find_by_ids(&self, ids: Vec<i32>) -> Result<Vec<DocumentModel>, sea_orm::DbErr> {
+        DocumentEntity::find()
+            .filter(Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+    }
+
     pub async fn find_all(&self) -> Result<Vec<DocumentModel>, sea_orm::DbErr> {
         DocumentEntity::find()
             .order_by_desc(Column::CreatedAt)
@@ -168,6 +200,17 @@ impl DocumentRepository {
             .await
     }
 
+    pub async fn find_by_project_id(
+        &self,
+        project_id: i32,
+    ) -> Result<Vec<DocumentModel>, sea_orm::DbErr> {
+        DocumentEntity::find()
+            .filter(Column::ProjectId.eq(project_id))
+            .order_by_asc(Column::Id)
+            .all(&self.db)
+            .await
+    }
+
     pub async fn search(&self, query: &str) -> Result<Vec<DocumentModel>, sea_orm::DbErr> {
         DocumentEntity::find()
             .filter(
@@ -180,6 +223,40 @@ impl DocumentRepository {
             .await
     }
 
+    /// Ranked full-text search over the `documents_fts` index.
+    ///
+    /// `fts_query` must already be a valid FTS5 expression (see [`build_fts_query`]).
+    /// Title matches weigh 10x and tag matches 5x a body match.
+    pub async fn search_full_text(
+        &self,
+        fts_query: &str,
+        include_archived: bool,
+    ) -> Result<Vec<DocumentSearchRow>, sea_orm::DbErr> {
+        let mut sql = format!(
+            r#"SELECT d.id, d.title, d.tags, d.project_id, d.is_archived, d.updated_at,
+                      bm25(documents_fts, 10.0, 1.0, 5.0) AS score,
+                      highlight(documents_fts, 0, '{start}', '{end}') AS title_highlight,
+                      snippet(documents_fts, 1, '{start}', '{end}', '…', 16) AS snippet
+               FROM documents_fts
+               JOIN documents d ON d.id = documents_fts.rowid
+               WHERE documents_fts MATCH ?"#,
+            start = HIGHLIGHT_START,
+            end = HIGHLIGHT_END
+        );
+        if !include_archived {
+            sql.push_str(" AND d.is_archived = 0");
+        }
+        sql.push_str(" ORDER BY score");
+
+        DocumentSearchRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            sql,
+            [fts_query.into()],
+        ))
+        .all(&self.db)
+        .await
+    }
+
     pub async fn find_by_tag(&self, tag: &str) -> Result<Vec<DocumentModel>, sea_orm::DbErr> {
         // Simple tag search - in a production system, you'd want proper JSON query support
         DocumentEntity::find()
@@ -189,3 +266,54 @@ impl DocumentRepository {
             .await
     }
 }
+
+/// Turn free-form user input into a safe FTS5 query.
+///
+/// Each whitespace-separated term is quoted (so FTS operators and punctuation in
+/// user input are matched literally) and the last term gets a `*` for
+/// search-as-you-type prefix matching. Returns `None` when nothing searchable is left.
+pub fn build_fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .collect();
+
+    let last = terms.len().checked_sub(1)?;
+    Some(
+        terms
+            .iter()
+            .enumerate()
+            .map(|(i, term)| {
+                if i == last {
+                    format!("\"{}\"*", term)
+                } else {
+                    format!("\"{}\"", term)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_terms_and_prefixes_the_last_one() {
+        assert_eq!(
+            build_fts_query("deploy  kube").as_deref(),
+            Some("\"deploy\" \"kube\"*")
+        );
+    }
+
+    #[test]
+    fn neutralises_fts_syntax_in_user_input() {
+        assert_eq!(
+            build_fts_query("title:foo OR \"bar").as_deref(),
+            Some("\"title:foo\" \"OR\" \"bar\"*")
+        );
+        assert_eq!(build_fts_query("   \"\"  "), None);
+    }
+}
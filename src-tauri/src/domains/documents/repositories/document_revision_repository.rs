@@ -0,0 +1,72 @@
+use crate::entities::document::Model as DocumentModel;
+use crate::entities::document_revision::{
+    ActiveModel, Column, Entity as DocumentRevisionEntity, Model as DocumentRevisionModel,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, NotSet, QueryFilter,
+    QueryOrder, Set,
+};
+
+pub struct DocumentRevisionRepository {
+    db: DatabaseConnection,
+}
+
+impl DocumentRevisionRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Snapshot `document` as its next revision.
+    pub async fn create_from_document(
+        &self,
+        document: &DocumentModel,
+    ) -> Result<DocumentRevisionModel, sea_orm::DbErr> {
+        let revision_number = self
+            .find_latest(document.id)
+            .await?
+            .map(|r| r.revision_number + 1)
+            .unwrap_or(1);
+
+        let active_model = ActiveModel {
+            id: NotSet,
+            document_id: Set(document.id),
+            revision_number: Set(revision_number),
+            title: Set(document.title.clone()),
+            content: Set(document.content.clone()),
+            tags: Set(document.tags.clone()),
+            created_at: Set(Some(chrono::Utc::now().into())),
+        };
+
+        active_model.insert(&self.db).await
+    }
+
+    pub async fn find_by_id(
+        &self,
+        id: i32,
+    ) -> Result<Option<DocumentRevisionModel>, sea_orm::DbErr> {
+        DocumentRevisionEntity::find_by_id(id).one(&self.db).await
+    }
+
+    /// All revisions of a document, newest first
+    pub async fn find_by_document_id(
+        &self,
+        document_id: i32,
+    ) -> Result<Vec<DocumentRevisionModel>, sea_orm::DbErr> {
+        DocumentRevisionEntity::find()
+            .filter(Column::DocumentId.eq(document_id))
+            .order_by_desc(Column::RevisionNumber)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_latest(
+        &self,
+        document_id: i32,
+    ) -> Result<Option<DocumentRevisionModel>, sea_orm::DbErr> {
+        DocumentRevisionEntity::find()
+            .filter(Column::DocumentId.eq(document_id))
+            .order_by_desc(Column::RevisionNumber)
+            .one(&self.db)
+            .await
+    }
+}
@@ -0,0 +1,113 @@
+use crate::entities::document_sync_folder::{
+    ActiveModel as FolderActiveModel, Column as FolderColumn, Entity as DocumentSyncFolderEntity,
+    Model as DocumentSyncFolderModel,
+};
+use crate::entities::document_sync_state::{
+    ActiveModel as StateActiveModel, Entity as DocumentSyncStateEntity,
+    Model as DocumentSyncStateModel,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, NotSet, QueryFilter, Set,
+};
+
+pub struct DocumentSyncRepository {
+    db: DatabaseConnection,
+}
+
+impl DocumentSyncRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn find_folder(
+        &self,
+        project_id: i32,
+    ) -> Result<Option<DocumentSyncFolderModel>, sea_orm::DbErr> {
+        DocumentSyncFolderEntity::find()
+            .filter(FolderColumn::ProjectId.eq(project_id))
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn find_all_folders(&self) -> Result<Vec<DocumentSyncFolderModel>, sea_orm::DbErr> {
+        DocumentSyncFolderEntity::find().all(&self.db).await
+    }
+
+    /// Point a project at `folder_path`, replacing any previous folder.
+    pub async fn set_folder(
+        &self,
+        project_id: i32,
+        folder_path: String,
+    ) -> Result<DocumentSyncFolderModel, sea_orm::DbErr> {
+        if let Some(existing) = self.find_folder(project_id).await? {
+            let mut active_model: FolderActiveModel = existing.into();
+            active_model.folder_path = Set(folder_path);
+            active_model.last_synced_at = Set(None);
+            return active_model.update(&self.db).await;
+        }
+
+        let active_model = FolderActiveModel {
+            id: NotSet,
+            project_id: Set(project_id),
+            folder_path: Set(folder_path),
+            created_at: Set(Some(chrono::Utc::now().into())),
+            last_synced_at: Set(None),
+        };
+        active_model.insert(&self.db).await
+    }
+
+    pub async fn touch_folder(&self, project_id: i32) -> Result<(), sea_orm::DbErr> {
+        if let Some(existing) = self.find_folder(project_id).await? {
+            let mut active_model: FolderActiveModel = existing.into();
+            active_model.last_synced_at = Set(Some(chrono::Utc::now().into()));
+            active_model.update(&self.db).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn delete_folder(&self, project_id: i32) -> Result<(), sea_orm::DbErr> {
+        DocumentSyncFolderEntity::delete_many()
+            .filter(FolderColumn::ProjectId.eq(project_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn find_state(
+        &self,
+        document_id: i32,
+    ) -> Result<Option<DocumentSyncStateModel>, sea_orm::DbErr> {
+        DocumentSyncStateEntity::find_by_id(document_id)
+            .one(&self.db)
+            .await
+    }
+
+    /// Record that `document_id` and `file_path` were in sync with these hashes.
+    pub async fn upsert_state(
+        &self,
+        document_id: i32,
+        file_path: String,
+        file_hash: String,
+        document_hash: String,
+    ) -> Result<DocumentSyncStateModel, sea_orm::DbErr> {
+        let now = Some(chrono::Utc::now().into());
+
+        if let Some(existing) = self.find_state(document_id).await? {
+            let mut active_model: StateActiveModel = existing.into();
+            active_model.file_path = Set(file_path);
+            active_model.file_hash = Set(file_hash);
+            active_model.document_hash = Set(document_hash);
+            active_model.synced_at = Set(now);
+            return active_model.update(&self.db).await;
+        }
+
+        let active_model = StateActiveModel {
+            document_id: Set(document_id),
+            file_path: Set(file_path),
+            file_hash: Set(file_hash),
+            document_hash: Set(document_hash),
+            synced_at: Set(now),
+        };
+        active_model.insert(&self.db).await
+    }
+}
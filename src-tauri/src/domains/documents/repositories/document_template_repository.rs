@@ -0,0 +1,118 @@
+use crate::entities::document_template::{
+    ActiveModel, Column, Entity as DocumentTemplateEntity, Model as DocumentTemplateModel,
+};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDocumentTemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub title_template: String,
+    pub body_template: String,
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateDocumentTemplateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub title_template: Option<String>,
+    pub body_template: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+pub struct DocumentTemplateRepository {
+    db: DatabaseConnection,
+}
+
+impl DocumentTemplateRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        request: CreateDocumentTemplateRequest,
+    ) -> Result<DocumentTemplateModel, sea_orm::DbErr> {
+        let tags_json = request
+            .tags
+            .map(|tags| serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string()));
+
+        let active_model = ActiveModel {
+            name: Set(request.name),
+            description: Set(request.description),
+            category: Set(request.category.unwrap_or_else(|| "custom".to_string())),
+            title_template: Set(request.title_template),
+            body_template: Set(request.body_template),
+            tags: Set(tags_json),
+            is_builtin: Set(false),
+            created_at: Set(Some(chrono::Utc::now().into())),
+            updated_at: Set(Some(chrono::Utc::now().into())),
+            ..Default::default()
+        };
+
+        active_model.insert(&self.db).await
+    }
+
+    pub async fn update(
+        &self,
+        id: i32,
+        request: UpdateDocumentTemplateRequest,
+    ) -> Result<DocumentTemplateModel, sea_orm::DbErr> {
+        let mut active_model: ActiveModel = DocumentTemplateEntity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound("Template not found".to_string()))?
+            .into();
+
+        if let Some(name) = request.name {
+            active_model.name = Set(name);
+        }
+        if let Some(description) = request.description {
+            active_model.description = Set(Some(description));
+        }
+        if let Some(category) = request.category {
+            active_model.category = Set(category);
+        }
+        if let Some(title_template) = request.title_template {
+            active_model.title_template = Set(title_template);
+        }
+        if let Some(body_template) = request.body_template {
+            active_model.body_template = Set(body_template);
+        }
+        if let Some(tags) = request.tags {
+            let tags_json = serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string());
+            active_model.tags = Set(Some(tags_json));
+        }
+
+        active_model.updated_at = Set(Some(chrono::Utc::now().into()));
+
+        active_model.update(&self.db).await
+    }
+
+    pub async fn delete(&self, id: i32) -> Result<(), sea_orm::DbErr> {
+        DocumentTemplateEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Built-in templates first, then custom ones by name
+    pub async fn find_all(&self) -> Result<Vec<DocumentTemplateModel>, sea_orm::DbErr> {
+        DocumentTemplateEntity::find()
+            .order_by_desc(Column::IsBuiltin)
+            .order_by_asc(Column::Name)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_by_id(
+        &self,
+        id: i32,
+    ) -> Result<Option<DocumentTemplateModel>, sea_orm::DbErr> {
+        DocumentTemplateEntity::find_by_id(id).one(&self.db).await
+    }
+}
@@ -1 +1,6 @@
+pub mod document_chunk_repository;
+pub mod document_link_repository;
 pub mod document_repository;
+pub mod document_revision_repository;
+pub mod document_sync_repository;
+pub mod document_template_repository;
@@ -75,6 +75,8 @@ impl AIDocumentGenerator {
             model: None,
             llm_provider: None,
             extra_options: None,
+            feature: Some("docs".to_string()),
+            bypass_cache: false,
         };
 
         let result = self
@@ -0,0 +1,208 @@
+//! Splitting documents into passages for retrieval, and scoring passages
+//! against a question.
+//!
+//! Chunks follow markdown structure: a heading starts a new chunk, and paragraphs
+//! under it are packed together until `MAX_CHUNK_CHARS`. Fenced code blocks are
+//! kept whole where they fit so code isn't cut mid-block.
+
+use std::collections::HashSet;
+
+pub const MAX_CHUNK_CHARS: usize = 1500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextChunk {
+    /// Nearest heading above the chunk
+    pub heading: Option<String>,
+    pub content: String,
+}
+
+pub fn chunk_markdown(content: &str) -> Vec<TextChunk> {
+    let mut chunks = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut blocks: Vec<String> = Vec::new();
+    let mut block = String::new();
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+        }
+
+        if let Some(title) = (!in_fence).then(|| heading_text(trimmed)).flatten() {
+            push_block(&mut blocks, &mut block);
+            pack(&mut chunks, heading.take(), std::mem::take(&mut blocks));
+            heading = (!title.is_empty()).then(|| title.to_string());
+            continue;
+        }
+
+        if !in_fence && trimmed.is_empty() {
+            push_block(&mut blocks, &mut block);
+            continue;
+        }
+
+        if !block.is_empty() {
+            block.push('\n');
+        }
+        block.push_str(line);
+    }
+    push_block(&mut blocks, &mut block);
+    pack(&mut chunks, heading, blocks);
+
+    chunks
+}
+
+/// Text of an ATX heading line (`## Title`), `None` for other lines.
+fn heading_text(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches('#');
+    let level = line.len() - rest.len();
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' '))).then(|| rest.trim())
+}
+
+fn push_block(blocks: &mut Vec<String>, block: &mut String) {
+    let text = std::mem::take(block);
+    if !text.trim().is_empty() {
+        blocks.push(text);
+    }
+}
+
+/// Pack a section's blocks into chunks no longer than `MAX_CHUNK_CHARS`.
+fn pack(chunks: &mut Vec<TextChunk>, heading: Option<String>, blocks: Vec<String>) {
+    let mut current = String::new();
+    let mut flush = |current: &mut String| {
+        if !current.trim().is_empty() {
+            chunks.push(TextChunk {
+                heading: heading.clone(),
+                content: std::mem::take(current),
+            });
+        }
+    };
+
+    for block in blocks {
+        for piece in split_long(&block) {
+            if !current.is_empty() && current.len() + piece.len() + 2 > MAX_CHUNK_CHARS {
+                flush(&mut current);
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&piece);
+        }
+    }
+    flush(&mut current);
+}
+
+/// Split a block longer than `MAX_CHUNK_CHARS` at line, then word boundaries.
+fn split_long(block: &str) -> Vec<String> {
+    if block.len() <= MAX_CHUNK_CHARS {
+        return vec![block.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for word in block.split_inclusive(['\n', ' ']) {
+        if !current.is_empty() && current.len() + word.len() > MAX_CHUNK_CHARS {
+            pieces.push(std::mem::take(&mut current).trim_end().to_string());
+        }
+        current.push_str(word);
+    }
+    if !current.trim().is_empty() {
+        pieces.push(current.trim_end().to_string());
+    }
+    pieces
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+pub fn encode_embedding(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+pub fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// Fraction of the question's terms that appear in `text`. Used to rank chunks
+/// when no embedding model is available.
+pub fn keyword_score(question: &str, text: &str) -> f32 {
+    let terms = terms(question);
+    if terms.is_empty() {
+        return 0.0;
+    }
+    let words = terms_of(text);
+    let hits = terms.iter().filter(|t| words.contains(*t)).count();
+    hits as f32 / terms.len() as f32
+}
+
+fn terms(text: &str) -> HashSet<String> {
+    const STOP_WORDS: [&str; 26] = [
+        "a", "an", "and", "are", "as", "at", "be", "by", "do", "does", "for", "how", "in", "is",
+        "it", "of", "on", "or", "that", "the", "to", "we", "what", "when", "with", "you",
+    ];
+    terms_of(text)
+        .into_iter()
+        .filter(|t| t.len() > 1 && !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+fn terms_of(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_follow_headings_and_keep_code_fences() {
+        let chunks = chunk_markdown(
+            "Intro line.\n\n# Setup\n\nInstall it.\n\n```sh\n# not a heading\n\nnpm i\n```\n\n## Usage\nRun it.",
+        );
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].heading, None);
+        assert_eq!(chunks[1].heading.as_deref(), Some("Setup"));
+        assert!(chunks[1].content.contains("# not a heading\n\nnpm i"));
+        assert_eq!(chunks[2].heading.as_deref(), Some("Usage"));
+        assert_eq!(chunks[2].content, "Run it.");
+    }
+
+    #[test]
+    fn splits_long_sections_and_scores_similarity() {
+        let long = "word ".repeat(MAX_CHUNK_CHARS);
+        let chunks = chunk_markdown(&long);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.content.len() <= MAX_CHUNK_CHARS));
+
+        let vector = [0.5f32, -1.0, 2.0];
+        assert_eq!(decode_embedding(&encode_embedding(&vector)), vector);
+        assert!((cosine_similarity(&vector, &vector) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+
+        assert_eq!(
+            keyword_score("How do we deploy the API?", "Deploy the api"),
+            1.0
+        );
+        assert_eq!(keyword_score("rollback", "Deploy the api"), 0.0);
+    }
+}
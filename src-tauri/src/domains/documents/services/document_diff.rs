@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+
+/// Above this many cells in the LCS table the changed region is reported as a
+/// straight replacement instead of being diffed line by line.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// 1-based line number in the old text, if the line exists there
+    pub old_line: Option<usize>,
+    /// 1-based line number in the new text, if the line exists there
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LineDiff {
+    pub lines: Vec<DiffLine>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Line-level diff of `old` against `new`.
+///
+/// Common leading and trailing lines are matched directly; the changed middle is
+/// aligned with a longest-common-subsequence table.
+pub fn diff_lines(old: &str, new: &str) -> LineDiff {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut diff = LineDiff::default();
+    let mut old_no = 0;
+    let mut new_no = 0;
+
+    let mut push = |diff: &mut LineDiff, kind: DiffLineKind, text: &str| {
+        let (old_line, new_line) = match kind {
+            DiffLineKind::Unchanged => {
+                old_no += 1;
+                new_no += 1;
+                (Some(old_no), Some(new_no))
+            }
+            DiffLineKind::Removed => {
+                old_no += 1;
+                diff.deletions += 1;
+                (Some(old_no), None)
+            }
+            DiffLineKind::Added => {
+                new_no += 1;
+                diff.additions += 1;
+                (None, Some(new_no))
+            }
+        };
+        diff.lines.push(DiffLine {
+            kind,
+            old_line,
+            new_line,
+            text: text.to_string(),
+        });
+    };
+
+    for line in &old_lines[..prefix] {
+        push(&mut diff, DiffLineKind::Unchanged, line);
+    }
+    for (kind, line) in align(old_mid, new_mid) {
+        push(&mut diff, kind, line);
+    }
+    for line in &old_lines[old_lines.len() - suffix..] {
+        push(&mut diff, DiffLineKind::Unchanged, line);
+    }
+
+    diff
+}
+
+/// Plain-text rendering of the changed lines, `+`/`-` prefixed, with up to
+/// `context` unchanged lines around each change and `...` where lines are
+/// skipped.
+pub fn render_changes(diff: &LineDiff, context: usize) -> String {
+    let changed: Vec<usize> = diff
+        .lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.kind != DiffLineKind::Unchanged)
+        .map(|(i, _)| i)
+        .collect();
+    let shown = |i: usize| changed.iter().any(|&c| c.abs_diff(i) <= context);
+
+    let mut out = Vec::new();
+    let mut skipped = false;
+    for (i, line) in diff.lines.iter().enumerate() {
+        if !shown(i) {
+            skipped = true;
+            continue;
+        }
+        if skipped && !out.is_empty() {
+            out.push("...".to_string());
+        }
+        skipped = false;
+        let marker = match line.kind {
+            DiffLineKind::Unchanged => ' ',
+            DiffLineKind::Added => '+',
+            DiffLineKind::Removed => '-',
+        };
+        out.push(format!("{} {}", marker, line.text));
+    }
+    out.join("\n")
+}
+
+fn align<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffLineKind, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+
+    if n * m > MAX_LCS_CELLS {
+        return old
+            .iter()
+            .map(|l| (DiffLineKind::Removed, *l))
+            .chain(new.iter().map(|l| (DiffLineKind::Added, *l)))
+            .collect();
+    }
+
+    // lcs[i][j] = LCS length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            result.push((DiffLineKind::Unchanged, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push((DiffLineKind::Removed, old[i]));
+            i += 1;
+        } else {
+            result.push((DiffLineKind::Added, new[j]));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|l| (DiffLineKind::Removed, *l)));
+    result.extend(new[j..].iter().map(|l| (DiffLineKind::Added, *l)));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_changed_lines_with_numbers() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nB\nc\nd\ne");
+
+        let kinds: Vec<(DiffLineKind, &str)> = diff
+            .lines
+            .iter()
+            .map(|l| (l.kind, l.text.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiffLineKind::Unchanged, "a"),
+                (DiffLineKind::Removed, "b"),
+                (DiffLineKind::Added, "B"),
+                (DiffLineKind::Unchanged, "c"),
+                (DiffLineKind::Unchanged, "d"),
+                (DiffLineKind::Added, "e"),
+            ]
+        );
+        assert_eq!((diff.additions, diff.deletions), (2, 1));
+        assert_eq!(diff.lines[5].new_line, Some(5));
+        assert_eq!(diff.lines[4].old_line, Some(4));
+    }
+
+    #[test]
+    fn renders_changes_with_context() {
+        let diff = diff_lines("1\n2\n3\n4\n5\n6\n7", "1\n2\nthree\n4\n5\n6\n7\n8");
+        assert_eq!(
+            render_changes(&diff, 1),
+            "  2\n- 3\n+ three\n  4\n...\n  7\n+ 8"
+        );
+        assert_eq!(render_changes(&diff_lines("same", "same"), 1), "");
+    }
+
+    #[test]
+    fn identical_texts_have_no_changes() {
+        let diff = diff_lines("same\ntext", "same\ntext");
+        assert_eq!((diff.additions, diff.deletions), (0, 0));
+        assert_eq!(diff.lines.len(), 2);
+    }
+}
@@ -0,0 +1,301 @@
+//! Export documents as standalone HTML or PDF.
+//!
+//! HTML is self-contained: code blocks are highlighted with inline styles and local
+//! images are embedded as data URIs, so the file renders the same anywhere. PDF is
+//! produced by printing that HTML with a headless Chromium-family browser, which
+//! keeps the output identical to the HTML export without a layout engine of our own.
+
+use crate::domains::tasks::services::attachment_service::guess_mime_type;
+use crate::entities::document::Model as DocumentModel;
+use crate::process_ext::NoWindowExt;
+use base64::Engine;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use syntect::highlighting::ThemeSet;
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+const PDF_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Images larger than this are linked rather than embedded.
+const MAX_EMBEDDED_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+
+const STYLESHEET: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; line-height: 1.6; color: #1f2328; max-width: 860px; margin: 2rem auto; padding: 0 1.5rem; }
+h1, h2, h3, h4 { line-height: 1.25; margin-top: 1.6em; }
+h1 { border-bottom: 1px solid #d0d7de; padding-bottom: .3em; }
+code { font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 85%; background: #f6f8fa; padding: .2em .4em; border-radius: 4px; }
+pre { padding: 1em; overflow-x: auto; border-radius: 6px; font-size: 85%; line-height: 1.45; }
+pre code { background: none; padding: 0; font-size: 100%; }
+blockquote { margin: 0; padding: 0 1em; color: #59636e; border-left: .25em solid #d0d7de; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d0d7de; padding: 6px 13px; }
+img { max-width: 100%; }
+@media print { body { margin: 0; max-width: none; } pre { white-space: pre-wrap; } }
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Html,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    THEME_SET.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Render `document` as a complete HTML page. Relative image paths are resolved
+/// against `base_dir`.
+pub fn render_document_html(document: &DocumentModel, base_dir: Option<&Path>) -> String {
+    let body = render_markdown_html(&document.content, base_dir);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(&document.title),
+        STYLESHEET,
+        body
+    )
+}
+
+pub fn render_markdown_html(markdown: &str, base_dir: Option<&Path>) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+
+    let mut events = Vec::new();
+    let mut code_block: Option<(String, String)> = None; // (language, source)
+
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(lang) => {
+                        lang.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((language, String::new()));
+            }
+            Event::Text(text) if code_block.is_some() => {
+                if let Some((_, source)) = code_block.as_mut() {
+                    source.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((language, source)) = code_block.take() {
+                    events.push(Event::Html(CowStr::from(highlight_code(
+                        &language, &source,
+                    ))));
+                }
+            }
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                let dest_url = embed_image(&dest_url, base_dir)
+                    .map(CowStr::from)
+                    .unwrap_or(dest_url);
+                events.push(Event::Start(Tag::Image {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }));
+            }
+            other => events.push(other),
+        }
+    }
+
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+    output
+}
+
+fn highlight_code(language: &str, source: &str) -> String {
+    let syntaxes = syntax_set();
+    let syntax = (!language.is_empty())
+        .then(|| syntaxes.find_syntax_by_token(language))
+        .flatten();
+
+    match syntax {
+        Some(syntax) => {
+            let theme = &theme_set().themes["InspiredGitHub"];
+            highlighted_html_for_string(source, syntaxes, syntax, theme)
+                .unwrap_or_else(|_| plain_code_block(source))
+        }
+        None => plain_code_block(source),
+    }
+}
+
+fn plain_code_block(source: &str) -> String {
+    format!(
+        "<pre style=\"background-color:#f6f8fa;\"><code>{}</code></pre>\n",
+        escape_html(source)
+    )
+}
+
+/// Data URI for a local image, or `None` to leave the URL as written.
+fn embed_image(url: &str, base_dir: Option<&Path>) -> Option<String> {
+    if url.starts_with("data:") || url.starts_with("http://") || url.starts_with("https://") {
+        return None;
+    }
+
+    let raw_path = url.strip_prefix("file://").unwrap_or(url);
+    let path = PathBuf::from(raw_path);
+    let path = if path.is_absolute() {
+        path
+    } else {
+        base_dir?.join(path)
+    };
+
+    let size = std::fs::metadata(&path).ok()?.len();
+    if size > MAX_EMBEDDED_IMAGE_BYTES {
+        return None;
+    }
+    let bytes = std::fs::read(&path).ok()?;
+    let mime = guess_mime_type(&path.to_string_lossy());
+    if !mime.starts_with("image/") {
+        return None;
+    }
+
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Print `html` to `output` with a headless browser.
+pub async fn export_pdf(html: &str, output: &Path) -> Result<(), String> {
+    let browser = find_pdf_browser().ok_or(
+        "PDF export needs Chrome, Chromium, Edge or Brave installed. Export as HTML instead, or install one of them.",
+    )?;
+
+    let html_path =
+        std::env::temp_dir().join(format!("portal-export-{}.html", uuid::Uuid::new_v4()));
+    std::fs::write(&html_path, html)
+        .map_err(|e| format!("Failed to write temporary HTML: {}", e))?;
+
+    // Clear any previous export so a failed render can't look like success.
+    let _ = std::fs::remove_file(output);
+    let result = tokio::time::timeout(
+        PDF_TIMEOUT,
+        tokio::process::Command::new(&browser)
+            .no_window()
+            .arg("--headless=new")
+            .arg("--disable-gpu")
+            .arg("--no-pdf-header-footer")
+            .arg(format!("--print-to-pdf={}", output.display()))
+            .arg(&html_path)
+            .output(),
+    )
+    .await;
+    let _ = std::fs::remove_file(&html_path);
+
+    let output_status = result
+        .map_err(|_| "PDF rendering timed out".to_string())?
+        .map_err(|e| format!("Failed to run {}: {}", browser.display(), e))?;
+
+    if !output.exists() {
+        return Err(format!(
+            "PDF rendering failed: {}",
+            String::from_utf8_lossy(&output_status.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn find_pdf_browser() -> Option<PathBuf> {
+    const COMMANDS: [&str; 7] = [
+        "chromium",
+        "chromium-browser",
+        "google-chrome",
+        "google-chrome-stable",
+        "microsoft-edge",
+        "msedge",
+        "brave-browser",
+    ];
+    if let Some(path) = COMMANDS.iter().find_map(|cmd| which::which(cmd).ok()) {
+        return Some(path);
+    }
+
+    let known_paths: &[&str] = if cfg!(target_os = "macos") {
+        &[
+            "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+            "/Applications/Chromium.app/Contents/MacOS/Chromium",
+            "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+            "/Applications/Brave Browser.app/Contents/MacOS/Brave Browser",
+        ]
+    } else if cfg!(windows) {
+        &[
+            r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+            r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+            r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+            r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+        ]
+    } else {
+        &[]
+    };
+
+    known_paths.iter().map(PathBuf::from).find(|p| p.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_fenced_code_and_renders_tables() {
+        let html = render_markdown_html(
+            "| a | b |\n|---|---|\n| 1 | 2 |\n\n```rust\nfn main() {}\n```\n\n```\n<raw>\n```",
+            None,
+        );
+
+        assert!(html.contains("<table>"));
+        // syntect emits inline-styled spans for known languages
+        assert!(html.contains("<span style="));
+        assert!(html.contains("&lt;raw&gt;"));
+    }
+
+    #[test]
+    fn embeds_local_images_relative_to_base_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pixel.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        let html = render_markdown_html(
+            "![pixel](pixel.png) ![remote](https://example.com/a.png)",
+            Some(dir.path()),
+        );
+
+        assert!(html.contains("src=\"data:image/png;base64,iVBORw==\""));
+        assert!(html.contains("src=\"https://example.com/a.png\""));
+    }
+}
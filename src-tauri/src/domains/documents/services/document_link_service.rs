@@ -0,0 +1,347 @@
+use crate::domains::documents::repositories::document_link_repository::{
+    DocumentLinkRepository, NewDocumentLink,
+};
+use crate::domains::documents::repositories::document_repository::DocumentRepository;
+use crate::domains::documents::services::document_links::{parse_links, LinkTarget};
+use crate::entities::document::Model as DocumentModel;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Longest context line returned with a backlink
+const CONTEXT_MAX_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentBacklink {
+    pub source_document_id: i32,
+    pub source_title: String,
+    /// The line of the source document containing the link
+    pub context: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// Unique across kinds, e.g. `document:4`, `task:12`, `project:1`
+    pub id: String,
+    /// document, task, project, or missing for links to documents that don't exist
+    pub kind: String,
+    pub entity_id: Option<i32>,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    /// `link` for links written in content, `project` for a document's project
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl DocumentGraph {
+    fn add_node(&mut self, seen: &mut HashSet<String>, node: GraphNode) {
+        if seen.insert(node.id.clone()) {
+            self.nodes.push(node);
+        }
+    }
+}
+
+pub struct DocumentLinkService {
+    links: DocumentLinkRepository,
+    documents: DocumentRepository,
+}
+
+impl DocumentLinkService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            links: DocumentLinkRepository::new(db.clone()),
+            documents: DocumentRepository::new(db),
+        }
+    }
+
+    /// Re-parse `document` and replace its stored outgoing links.
+    pub async fn refresh(&self, document: &DocumentModel) -> Result<(), sea_orm::DbErr> {
+        let parsed = parse_links(&document.content);
+        if parsed.is_empty() {
+            return self
+                .links
+                .replace_for_document(document.id, Vec::new())
+                .await;
+        }
+
+        let titles = self.document_titles().await?;
+        let task_ids: Vec<i32> = parsed
+            .iter()
+            .filter_map(|link| match link {
+                LinkTarget::Task(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        let existing_tasks: HashSet<i32> = self
+            .links
+            .existing_task_ids(task_ids)
+            .await?
+            .into_iter()
+            .collect();
+        let projects = if parsed.iter().any(|l| matches!(l, LinkTarget::Project(_))) {
+            self.links.project_names().await?
+        } else {
+            Vec::new()
+        };
+
+        let links = parsed
+            .into_iter()
+            .filter_map(|link| {
+                let kind = link.kind().to_string();
+                match link {
+                    LinkTarget::Document(title) => Some(NewDocumentLink {
+                        target_type: kind,
+                        target_id: titles.get(&title.to_lowercase()).copied(),
+                        target_title: title,
+                    }),
+                    // References to tasks that don't exist are just text.
+                    LinkTarget::Task(id) => existing_tasks.contains(&id).then(|| NewDocumentLink {
+                        target_type: kind,
+                        target_id: Some(id),
+                        target_title: format!("task:{}", id),
+                    }),
+                    LinkTarget::Project(name) => {
+                        let id = name
+                            .parse::<i32>()
+                            .ok()
+                            .filter(|id| projects.iter().any(|(project_id, _)| project_id == id));
+                        let id = id.or_else(|| {
+                            projects
+                                .iter()
+                                .find(|(_, project)| project.eq_ignore_ascii_case(&name))
+                                .map(|(project_id, _)| *project_id)
+                        })?;
+                        Some(NewDocumentLink {
+                            target_type: kind,
+                            target_id: Some(id),
+                            target_title: name,
+                        })
+                    }
+                }
+            })
+            .collect();
+
+        self.links.replace_for_document(document.id, links).await
+    }
+
+    /// Re-parse every document. Used after upgrading, when documents predate the
+    /// links table.
+    pub async fn rebuild_all(&self) -> Result<usize, sea_orm::DbErr> {
+        let documents = self.documents.find_all().await?;
+        for document in &documents {
+            self.refresh(document).await?;
+        }
+        Ok(documents.len())
+    }
+
+    pub async fn get_backlinks(
+        &self,
+        document_id: i32,
+    ) -> Result<Vec<DocumentBacklink>, sea_orm::DbErr> {
+        let document = self
+            .documents
+            .find_by_id(document_id)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound("Document not found".to_string()))?;
+
+        let links = self
+            .links
+            .find_backlinks(document_id, &document.title)
+            .await?;
+        let source_ids: Vec<i32> = links
+            .iter()
+            .map(|l| l.source_document_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let sources = self.documents.find_by_ids(source_ids).await?;
+
+        let mut backlinks: Vec<DocumentBacklink> = sources
+            .into_iter()
+            .map(|source| {
+                let context = links
+                    .iter()
+                    .filter(|l| l.source_document_id == source.id)
+                    .find_map(|l| context_line(&source.content, &l.target_title));
+                DocumentBacklink {
+                    source_document_id: source.id,
+                    source_title: source.title,
+                    context,
+                }
+            })
+            .collect();
+        backlinks.sort_by(|a, b| a.source_title.cmp(&b.source_title));
+
+        Ok(backlinks)
+    }
+
+    /// Documents (optionally only one project's) with everything they link to.
+    pub async fn get_graph(
+        &self,
+        project_id: Option<i32>,
+    ) -> Result<DocumentGraph, sea_orm::DbErr> {
+        let documents: Vec<DocumentModel> = match project_id {
+            Some(project_id) => self.documents.find_by_project_id(project_id).await?,
+            None => self.documents.find_all().await?,
+        };
+        let titles = self.document_titles().await?;
+        let included: HashSet<i32> = documents.iter().map(|d| d.id).collect();
+        let links: Vec<_> = self
+            .links
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(|l| included.contains(&l.source_document_id))
+            .collect();
+
+        let task_ids: Vec<i32> = links
+            .iter()
+            .filter(|l| l.target_type == "task")
+            .filter_map(|l| l.target_id)
+            .collect();
+        let tasks: HashMap<i32, String> = self
+            .links
+            .find_tasks(task_ids)
+            .await?
+            .into_iter()
+            .map(|t| (t.id, t.title))
+            .collect();
+        let projects: HashMap<i32, String> =
+            self.links.project_names().await?.into_iter().collect();
+        let all_titles: HashMap<i32, String> = self
+            .documents
+            .find_all()
+            .await?
+            .into_iter()
+            .map(|d| (d.id, d.title))
+            .collect();
+
+        let mut graph = DocumentGraph::default();
+        let mut seen = HashSet::new();
+
+        for document in &documents {
+            graph.add_node(
+                &mut seen,
+                GraphNode {
+                    id: format!("document:{}", document.id),
+                    kind: "document".to_string(),
+                    entity_id: Some(document.id),
+                    label: document.title.clone(),
+                },
+            );
+            if let Some(project_id) = document.project_id {
+                graph.add_node(
+                    &mut seen,
+                    GraphNode {
+                        id: format!("project:{}", project_id),
+                        kind: "project".to_string(),
+                        entity_id: Some(project_id),
+                        label: projects
+                            .get(&project_id)
+                            .cloned()
+                            .unwrap_or_else(|| format!("Project {}", project_id)),
+                    },
+                );
+                graph.edges.push(GraphEdge {
+                    source: format!("document:{}", document.id),
+                    target: format!("project:{}", project_id),
+                    kind: "project".to_string(),
+                });
+            }
+        }
+
+        for link in links {
+            let node = match (link.target_type.as_str(), link.target_id) {
+                ("document", target_id) => {
+                    // Unresolved links may have been resolved by a document created since.
+                    match target_id
+                        .or_else(|| titles.get(&link.target_title.to_lowercase()).copied())
+                    {
+                        Some(id) => GraphNode {
+                            id: format!("document:{}", id),
+                            kind: "document".to_string(),
+                            entity_id: Some(id),
+                            label: all_titles
+                                .get(&id)
+                                .cloned()
+                                .unwrap_or_else(|| link.target_title.clone()),
+                        },
+                        None => GraphNode {
+                            id: format!("missing:{}", link.target_title.to_lowercase()),
+                            kind: "missing".to_string(),
+                            entity_id: None,
+                            label: link.target_title.clone(),
+                        },
+                    }
+                }
+                ("task", Some(id)) => GraphNode {
+                    id: format!("task:{}", id),
+                    kind: "task".to_string(),
+                    entity_id: Some(id),
+                    label: tasks
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| format!("Task {}", id)),
+                },
+                ("project", Some(id)) => GraphNode {
+                    id: format!("project:{}", id),
+                    kind: "project".to_string(),
+                    entity_id: Some(id),
+                    label: projects
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| link.target_title.clone()),
+                },
+                _ => continue,
+            };
+
+            graph.edges.push(GraphEdge {
+                source: format!("document:{}", link.source_document_id),
+                target: node.id.clone(),
+                kind: "link".to_string(),
+            });
+            graph.add_node(&mut seen, node);
+        }
+
+        Ok(graph)
+    }
+
+    /// Lowercased title -> id. When titles collide the oldest document wins.
+    async fn document_titles(&self) -> Result<HashMap<String, i32>, sea_orm::DbErr> {
+        let mut titles = HashMap::new();
+        for document in self.documents.find_all().await? {
+            titles
+                .entry(document.title.to_lowercase())
+                .and_modify(|id: &mut i32| *id = (*id).min(document.id))
+                .or_insert(document.id);
+        }
+        Ok(titles)
+    }
+}
+
+fn context_line(content: &str, target_title: &str) -> Option<String> {
+    let needle = target_title.to_lowercase();
+    let line = content
+        .lines()
+        .find(|line| line.to_lowercase().contains(&needle))?
+        .trim();
+
+    if line.chars().count() > CONTEXT_MAX_CHARS {
+        Some(format!(
+            "{}…",
+            line.chars().take(CONTEXT_MAX_CHARS).collect::<String>()
+        ))
+    } else {
+        Some(line.to_string())
+    }
+}
@@ -0,0 +1,148 @@
+//! Link syntax recognised in document content:
+//!
+//! - `[[Document title]]` or `[[Document title|label]]` links to another document
+//! - `[[task:12]]` and plain `task#12` reference a task
+//! - `[[project:3]]` or `[[project:Portal]]` reference a project by id or name
+//!
+//! Anything inside fenced code blocks or inline code is ignored.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LinkTarget {
+    Document(String),
+    Task(i32),
+    Project(String),
+}
+
+impl LinkTarget {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LinkTarget::Document(_) => "document",
+            LinkTarget::Task(_) => "task",
+            LinkTarget::Project(_) => "project",
+        }
+    }
+}
+
+/// Every distinct link in `content`, in order of first appearance.
+pub fn parse_links(content: &str) -> Vec<LinkTarget> {
+    static TASK_REF: OnceLock<Regex> = OnceLock::new();
+    let task_ref = TASK_REF.get_or_init(|| Regex::new(r"(?i)\btask#(\d+)\b").unwrap());
+
+    let mut links: Vec<LinkTarget> = Vec::new();
+    let mut push = |link: LinkTarget| {
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    };
+
+    let mut in_fence = false;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let line = strip_inline_code(line);
+        let mut found: Vec<(usize, LinkTarget)> = Vec::new();
+
+        let mut offset = 0;
+        while let Some(start) = line[offset..].find("[[") {
+            let start = offset + start;
+            let Some(len) = line[start + 2..].find("]]") else {
+                break;
+            };
+            if let Some(link) = parse_wikilink(&line[start + 2..start + 2 + len]) {
+                found.push((start, link));
+            }
+            offset = start + 2 + len + 2;
+        }
+
+        for capture in task_ref.captures_iter(&line) {
+            if let Ok(id) = capture[1].parse() {
+                found.push((
+                    capture.get(0).map_or(0, |m| m.start()),
+                    LinkTarget::Task(id),
+                ));
+            }
+        }
+
+        found.sort_by_key(|(position, _)| *position);
+        for (_, link) in found {
+            push(link);
+        }
+    }
+
+    links
+}
+
+fn parse_wikilink(inner: &str) -> Option<LinkTarget> {
+    let target = inner.split('|').next().unwrap_or_default().trim();
+    if target.is_empty() {
+        return None;
+    }
+
+    if let Some(id) = strip_prefix_ci(target, "task:") {
+        return id
+            .trim()
+            .trim_start_matches('#')
+            .parse()
+            .ok()
+            .map(LinkTarget::Task);
+    }
+    if let Some(project) = strip_prefix_ci(target, "project:") {
+        let project = project.trim();
+        return (!project.is_empty()).then(|| LinkTarget::Project(project.to_string()));
+    }
+    Some(LinkTarget::Document(target.to_string()))
+}
+
+fn strip_prefix_ci<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    value
+        .get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &value[prefix.len()..])
+}
+
+fn strip_inline_code(line: &str) -> String {
+    line.split('`')
+        .enumerate()
+        .filter(|(i, _)| i % 2 == 0)
+        .map(|(_, part)| part)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_document_task_and_project_links() {
+        let links = parse_links(
+            "See [[Release checklist|the checklist]] and [[task:#12]].\n\
+             Fixes task#7, part of [[project:Portal]]. Again: [[Release checklist]]",
+        );
+
+        assert_eq!(
+            links,
+            vec![
+                LinkTarget::Document("Release checklist".to_string()),
+                LinkTarget::Task(12),
+                LinkTarget::Task(7),
+                LinkTarget::Project("Portal".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_links_in_code() {
+        let links = parse_links("```\n[[Not a link]]\n```\n`[[Also not]]` but [[Real]]");
+        assert_eq!(links, vec![LinkTarget::Document("Real".to_string())]);
+    }
+}
@@ -0,0 +1,500 @@
+//! Summaries and question answering over project documents.
+//!
+//! Documents are chunked and embedded lazily: each request re-indexes only the
+//! documents whose content changed since they were last chunked. Embeddings come
+//! from the agent-platform provider, falling back to a local Ollama; when neither
+//! can embed, chunks are still stored and ranked by keyword overlap instead.
+
+use crate::domains::ai::embeddings::Embedder;
+use crate::domains::ai::providers::{GenerationOptions, ProviderType};
+use crate::domains::ai::services::AIService;
+use crate::domains::documents::repositories::document_chunk_repository::{
+    DocumentChunkRepository, NewDocumentChunk,
+};
+use crate::domains::documents::repositories::document_repository::DocumentRepository;
+use crate::domains::documents::services::document_chunks::{
+    chunk_markdown, cosine_similarity, decode_embedding, encode_embedding, keyword_score,
+};
+use crate::domains::documents::services::markdown_sync::file_hash;
+use crate::entities::document::Model as DocumentModel;
+use crate::entities::document_chunk::Model as DocumentChunkModel;
+use crate::log_warn;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const DEFAULT_TOP_K: usize = 6;
+const MAX_TOP_K: usize = 20;
+/// Documents shorter than this are summarized whole
+const SUMMARY_CONTEXT_CHARS: usize = 12_000;
+const EXCERPT_CHARS: usize = 240;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentQaSource {
+    pub document_id: i32,
+    pub document_title: String,
+    pub chunk_index: i32,
+    pub heading: Option<String>,
+    pub excerpt: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDocsAnswer {
+    pub answer: String,
+    /// Chunks given to the model, numbered as cited in `answer` ([1] is the first)
+    pub sources: Vec<DocumentQaSource>,
+    /// `embeddings` or `keyword`
+    pub retrieval: String,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSummary {
+    pub document_id: i32,
+    pub summary: String,
+    pub model: String,
+    /// Chunks sent to the model; 0 when the whole document fit
+    pub chunks_used: usize,
+    /// True when only part of a long document was sent
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentIndexStats {
+    pub documents: usize,
+    /// Documents re-chunked because they changed or weren't indexed yet
+    pub reindexed: usize,
+    pub chunks: usize,
+    pub embedding_model: Option<String>,
+}
+
+pub struct DocumentQaService {
+    documents: DocumentRepository,
+    chunks: DocumentChunkRepository,
+    ai_service: Arc<AIService>,
+    embedder: Embedder,
+}
+
+impl DocumentQaService {
+    pub fn new(
+        db: DatabaseConnection,
+        ai_service: Arc<AIService>,
+        embedding_model: Option<String>,
+    ) -> Self {
+        Self {
+            documents: DocumentRepository::new(db.clone()),
+            chunks: DocumentChunkRepository::new(db),
+            embedder: Embedder::new(ai_service.clone(), embedding_model),
+            ai_service,
+        }
+    }
+
+    /// Chunk and embed every document of a project (or all documents).
+    pub async fn index_documents(
+        &self,
+        project_id: Option<i32>,
+        force: bool,
+    ) -> Result<DocumentIndexStats, String> {
+        let documents = self.load_documents(project_id).await?;
+        let mut stats = DocumentIndexStats {
+            documents: documents.len(),
+            ..Default::default()
+        };
+
+        for document in &documents {
+            let (chunks, reindexed) = self.index_document(document, force).await?;
+            stats.chunks += chunks.len();
+            if reindexed {
+                stats.reindexed += 1;
+            }
+        }
+        if self.embedder.is_available() {
+            stats.embedding_model = Some(self.embedder.model().to_string());
+        }
+
+        Ok(stats)
+    }
+
+    /// Answer `question` from the most relevant chunks of a project's documents.
+    pub async fn ask(
+        &self,
+        project_id: Option<i32>,
+        question: &str,
+        top_k: Option<usize>,
+        provider_type: Option<ProviderType>,
+    ) -> Result<ProjectDocsAnswer, String> {
+        let question = question.trim();
+        if question.is_empty() {
+            return Err("Question cannot be empty".to_string());
+        }
+        let top_k = top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K);
+
+        let documents = self.load_documents(project_id).await?;
+        let mut titles = HashMap::new();
+        let mut chunks = Vec::new();
+        for document in &documents {
+            titles.insert(document.id, document.title.clone());
+            chunks.extend(self.index_document(document, false).await?.0);
+        }
+
+        let (ranked, retrieval) = self.rank(question, &titles, chunks).await;
+        let ranked: Vec<(f32, DocumentChunkModel)> = ranked.into_iter().take(top_k).collect();
+
+        if ranked.is_empty() {
+            return Ok(ProjectDocsAnswer {
+                answer: "I couldn't find anything in these documents about that.".to_string(),
+                sources: Vec::new(),
+                retrieval,
+                model: None,
+            });
+        }
+
+        let mut context = String::new();
+        for (i, (_, chunk)) in ranked.iter().enumerate() {
+            context.push_str(&format!(
+                "[{}] {}{}\n{}\n\n",
+                i + 1,
+                titles.get(&chunk.document_id).cloned().unwrap_or_default(),
+                chunk
+                    .heading
+                    .as_deref()
+                    .map(|h| format!(" › {}", h))
+                    .unwrap_or_default(),
+                chunk.content
+            ));
+        }
+
+        let system = "You answer questions about a software project using only the numbered \
+            document excerpts provided. Cite the excerpts you rely on inline as [1], [2], etc. \
+            If the excerpts don't contain the answer, say so plainly instead of guessing. \
+            Answer in markdown and keep it concise.";
+        let prompt = format!("Excerpts:\n\n{}Question: {}", context, question);
+
+        let result = self
+            .ai_service
+            .generate_with_system(
+                system,
+                &prompt,
+                Some(GenerationOptions {
+                    temperature: Some(0.2),
+                    max_tokens: Some(1500),
+                    timeout_ms: Some(120000),
+                    model: None,
+                    llm_provider: None,
+                    extra_options: None,
+                    feature: Some("docs".to_string()),
+                    bypass_cache: false,
+                }),
+                provider_type,
+            )
+            .await
+            .map_err(|e| format!("Failed to answer question: {}", e))?;
+
+        Ok(ProjectDocsAnswer {
+            answer: result.content.trim().to_string(),
+            sources: ranked
+                .into_iter()
+                .map(|(score, chunk)| DocumentQaSource {
+                    document_id: chunk.document_id,
+                    document_title: titles.get(&chunk.document_id).cloned().unwrap_or_default(),
+                    chunk_index: chunk.chunk_index,
+                    heading: chunk.heading,
+                    excerpt: excerpt(&chunk.content),
+                    score,
+                })
+                .collect(),
+            retrieval,
+            model: Some(result.model),
+        })
+    }
+
+    /// Summarize a document. Long documents are cut down to the chunks closest to
+    /// the document as a whole, kept in their original order.
+    pub async fn summarize(
+        &self,
+        document_id: i32,
+        provider_type: Option<ProviderType>,
+    ) -> Result<DocumentSummary, String> {
+        let document = self
+            .documents
+            .find_by_id(document_id)
+            .await
+            .map_err(|e| format!("Failed to load document: {}", e))?
+            .ok_or_else(|| "Document not found".to_string())?;
+        if document.content.trim().is_empty() {
+            return Err("Document is empty".to_string());
+        }
+
+        let (body, chunks_used, partial) = if document.content.len() <= SUMMARY_CONTEXT_CHARS {
+            (document.content.clone(), 0, false)
+        } else {
+            let (chunks, _) = self.index_document(&document, false).await?;
+            let selected = select_summary_chunks(chunks, self.embedder.model());
+            let count = selected.len();
+            let body = selected
+                .into_iter()
+                .map(|chunk| match chunk.heading {
+                    Some(heading) => format!("## {}\n{}", heading, chunk.content),
+                    None => chunk.content,
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n[…]\n\n");
+            (body, count, true)
+        };
+
+        let system = "You summarize technical documents for a developer. Start with a one \
+            sentence overview, then list the key points, decisions and open questions as \
+            markdown bullets. Use only what the document says.";
+        let note = if partial {
+            "\nOnly excerpts of a longer document are included; sections are separated by [\u{2026}].\n"
+        } else {
+            ""
+        };
+        let prompt = format!(
+            "Title: {}\n{}\n{}\n\nSummarize this document.",
+            document.title, note, body
+        );
+
+        let result = self
+            .ai_service
+            .generate_with_system(
+                system,
+                &prompt,
+                Some(GenerationOptions {
+                    temperature: Some(0.3),
+                    max_tokens: Some(1200),
+                    timeout_ms: Some(120000),
+                    model: None,
+                    llm_provider: None,
+                    extra_options: None,
+                    feature: Some("docs".to_string()),
+                    bypass_cache: false,
+                }),
+                provider_type,
+            )
+            .await
+            .map_err(|e| format!("Failed to summarize document: {}", e))?;
+
+        Ok(DocumentSummary {
+            document_id,
+            summary: result.content.trim().to_string(),
+            model: result.model,
+            chunks_used,
+            partial,
+        })
+    }
+
+    async fn load_documents(&self, project_id: Option<i32>) -> Result<Vec<DocumentModel>, String> {
+        let documents = match project_id {
+            Some(project_id) => self.documents.find_by_project_id(project_id).await,
+            None => self.documents.find_all().await,
+        }
+        .map_err(|e| format!("Failed to load documents: {}", e))?;
+
+        Ok(documents.into_iter().filter(|d| !d.is_archived).collect())
+    }
+
+    /// Current chunks of `document`, rebuilding them if the content changed or they
+    /// are missing embeddings that can now be computed. Returns whether it rebuilt.
+    async fn index_document(
+        &self,
+        document: &DocumentModel,
+        force: bool,
+    ) -> Result<(Vec<DocumentChunkModel>, bool), String> {
+        let hash = file_hash(document.content.as_bytes());
+        let existing = self
+            .chunks
+            .find_by_document_id(document.id)
+            .await
+            .map_err(|e| format!("Failed to load document chunks: {}", e))?;
+
+        let fresh = !existing.is_empty() && existing.iter().all(|c| c.content_hash == hash);
+        let embedded = existing
+            .iter()
+            .all(|c| c.embedding_model.as_deref() == Some(self.embedder.model()));
+        let can_embed = self.embedder.is_available();
+        if !force && fresh && (embedded || !can_embed) {
+            return Ok((existing, false));
+        }
+        if !force && existing.is_empty() && document.content.trim().is_empty() {
+            return Ok((existing, false));
+        }
+
+        let pieces = chunk_markdown(&document.content);
+        let texts: Vec<String> = pieces
+            .iter()
+            .map(|chunk| match &chunk.heading {
+                Some(heading) => format!("{}\n{}\n\n{}", document.title, heading, chunk.content),
+                None => format!("{}\n\n{}", document.title, chunk.content),
+            })
+            .collect();
+        let embeddings = self.embed(&texts).await;
+
+        let new_chunks = pieces
+            .into_iter()
+            .enumerate()
+            .map(|(i, chunk)| NewDocumentChunk {
+                heading: chunk.heading,
+                content: chunk.content,
+                embedding: embeddings
+                    .as_ref()
+                    .and_then(|vectors| vectors.get(i))
+                    .map(|v| encode_embedding(v)),
+            })
+            .collect();
+        self.chunks
+            .replace_for_document(document.id, &hash, Some(self.embedder.model()), new_chunks)
+            .await
+            .map_err(|e| format!("Failed to store document chunks: {}", e))?;
+
+        let chunks = self
+            .chunks
+            .find_by_document_id(document.id)
+            .await
+            .map_err(|e| format!("Failed to load document chunks: {}", e))?;
+        Ok((chunks, true))
+    }
+
+    /// Chunks ordered best-first with their scores, plus the retrieval method used.
+    async fn rank(
+        &self,
+        question: &str,
+        titles: &HashMap<i32, String>,
+        chunks: Vec<DocumentChunkModel>,
+    ) -> (Vec<(f32, DocumentChunkModel)>, String) {
+        let has_embeddings = chunks
+            .iter()
+            .any(|c| c.embedding_model.as_deref() == Some(self.embedder.model()));
+        let query = if has_embeddings {
+            self.embed(&[question.to_string()])
+                .await
+                .and_then(|mut vectors| vectors.pop())
+        } else {
+            None
+        };
+
+        let (mut ranked, retrieval): (Vec<(f32, DocumentChunkModel)>, &str) = match query {
+            Some(query) => (
+                chunks
+                    .into_iter()
+                    .filter(|c| c.embedding_model.as_deref() == Some(self.embedder.model()))
+                    .filter_map(|c| {
+                        let vector = decode_embedding(c.embedding.as_deref()?);
+                        Some((cosine_similarity(&query, &vector), c))
+                    })
+                    .collect(),
+                "embeddings",
+            ),
+            None => (
+                chunks
+                    .into_iter()
+                    .map(|c| {
+                        let title = titles.get(&c.document_id).map(String::as_str);
+                        let text = format!(
+                            "{} {} {}",
+                            title.unwrap_or_default(),
+                            c.heading.as_deref().unwrap_or_default(),
+                            c.content
+                        );
+                        (keyword_score(question, &text), c)
+                    })
+                    .filter(|(score, _)| *score > 0.0)
+                    .collect(),
+                "keyword",
+            ),
+        };
+
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        (ranked, retrieval.to_string())
+    }
+
+    /// Embed `texts`, or `None` when no embedding model is available
+    async fn embed(&self, texts: &[String]) -> Option<Vec<Vec<f32>>> {
+        if texts.is_empty() || !self.embedder.is_available() {
+            return None;
+        }
+        match self.embedder.embed(texts).await {
+            Ok(vectors) => Some(vectors),
+            Err(e) => {
+                log_warn!("Documents", "{}, falling back to keyword search", e);
+                None
+            }
+        }
+    }
+}
+
+/// Pick chunks for summarizing a long document: those most similar to the
+/// document's mean embedding, or evenly spaced chunks when there are no
+/// embeddings. Returned in document order and within `SUMMARY_CONTEXT_CHARS`.
+fn select_summary_chunks(
+    chunks: Vec<DocumentChunkModel>,
+    embedding_model: &str,
+) -> Vec<DocumentChunkModel> {
+    let vectors: Vec<Option<Vec<f32>>> = chunks
+        .iter()
+        .map(|c| {
+            (c.embedding_model.as_deref() == Some(embedding_model))
+                .then(|| c.embedding.as_deref().map(decode_embedding))
+                .flatten()
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..chunks.len()).collect();
+    if vectors.iter().all(Option::is_some) && !vectors.is_empty() {
+        let dims = vectors[0].as_ref().map_or(0, Vec::len);
+        let mut centroid = vec![0.0f32; dims];
+        for vector in vectors.iter().flatten() {
+            for (sum, x) in centroid.iter_mut().zip(vector) {
+                *sum += x;
+            }
+        }
+        let scores: Vec<f32> = vectors
+            .iter()
+            .flatten()
+            .map(|v| cosine_similarity(&centroid, v))
+            .collect();
+        order.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+    } else {
+        // Interleave from both ends towards the middle so the intro and the
+        // conclusion survive the cut.
+        let mut spread = Vec::with_capacity(order.len());
+        let (mut low, mut high) = (0usize, order.len());
+        while low < high {
+            spread.push(low);
+            low += 1;
+            if low < high {
+                high -= 1;
+                spread.push(high);
+            }
+        }
+        order = spread;
+    }
+
+    let mut budget = SUMMARY_CONTEXT_CHARS;
+    let mut selected: Vec<usize> = Vec::new();
+    for i in order {
+        let len = chunks[i].content.len();
+        if len <= budget {
+            budget -= len;
+            selected.push(i);
+        }
+    }
+    selected.sort_unstable();
+
+    let mut chunks: Vec<Option<DocumentChunkModel>> = chunks.into_iter().map(Some).collect();
+    selected
+        .into_iter()
+        .filter_map(|i| chunks[i].take())
+        .collect()
+}
+
+fn excerpt(content: &str) -> String {
+    let flat = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > EXCERPT_CHARS {
+        format!("{}…", flat.chars().take(EXCERPT_CHARS).collect::<String>())
+    } else {
+        flat
+    }
+}
@@ -1,17 +1,95 @@
 use crate::domains::documents::repositories::document_repository::{
-    CreateDocumentRequest, DocumentRepository, UpdateDocumentRequest,
+    build_fts_query, CreateDocumentRequest, DocumentRepository, DocumentSearchRow,
+    UpdateDocumentRequest,
 };
+use crate::domains::documents::repositories::document_revision_repository::DocumentRevisionRepository;
+use crate::domains::documents::services::document_diff::{diff_lines, LineDiff};
+use crate::domains::documents::services::document_link_service::DocumentLinkService;
 use crate::entities::document::Model as DocumentModel;
+use crate::entities::document_revision::Model as DocumentRevisionModel;
+use crate::log_warn;
+use chrono::{DateTime, Utc};
 use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Filters for full-text document search
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentSearchParams {
+    pub query: String,
+    pub project_id: Option<i32>,
+    /// Only documents carrying every one of these tags
+    pub tags: Option<Vec<String>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+    pub include_archived: Option<bool>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSearchHit {
+    pub id: i32,
+    pub title: String,
+    /// Title with matched terms wrapped in `<mark>`
+    pub title_highlight: String,
+    /// Best-matching excerpt of the content with matched terms wrapped in `<mark>`
+    pub snippet: String,
+    pub tags: Vec<String>,
+    pub project_id: Option<i32>,
+    pub is_archived: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// bm25 score; lower is a better match
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCount<T> {
+    pub value: T,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentSearchFacets {
+    pub projects: Vec<FacetCount<Option<i32>>>,
+    pub tags: Vec<FacetCount<String>>,
+    pub updated_earliest: Option<DateTime<Utc>>,
+    pub updated_latest: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentSearchResults {
+    /// Matches after filters, before `limit` is applied
+    pub total: usize,
+    pub hits: Vec<DocumentSearchHit>,
+    /// Counts over every text match, ignoring the facet filters, so the UI can
+    /// show what widening a filter would bring back
+    pub facets: DocumentSearchFacets,
+}
+
+/// Line diff between two revisions of the same document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentRevisionDiff {
+    pub document_id: i32,
+    pub from_revision: i32,
+    pub to_revision: i32,
+    pub title_changed: bool,
+    pub diff: LineDiff,
+}
 
 pub struct DocumentService {
     repository: DocumentRepository,
+    revisions: DocumentRevisionRepository,
+    links: DocumentLinkService,
 }
 
 impl DocumentService {
     pub fn new(db: DatabaseConnection) -> Self {
         Self {
-            repository: DocumentRepository::new(db),
+            repository: DocumentRepository::new(db.clone()),
+            revisions: DocumentRevisionRepository::new(db.clone()),
+            links: DocumentLinkService::new(db),
         }
     }
 
@@ -19,7 +97,9 @@ impl DocumentService {
         &self,
         request: CreateDocumentRequest,
     ) -> Result<DocumentModel, sea_orm::DbErr> {
-        self.repository.create(request).await
+        let document = self.repository.create(request).await?;
+        self.refresh_links(&document).await;
+        Ok(document)
     }
 
     pub async fn update_document(
@@ -27,7 +107,9 @@ impl DocumentService {
         id: i32,
         request: UpdateDocumentRequest,
     ) -> Result<DocumentModel, sea_orm::DbErr> {
-        self.repository.update(id, request).await
+        let document = self.repository.update(id, request).await?;
+        self.refresh_links(&document).await;
+        Ok(document)
     }
 
     pub async fn update_draft(
@@ -46,11 +128,85 @@ impl DocumentService {
         tags: Option<Vec<String>>,
         is_archived: Option<bool>,
     ) -> Result<DocumentModel, sea_orm::DbErr> {
-        self.repository
+        let document = self
+            .repository
             .save_document(id, title, content, tags, is_archived)
+            .await?;
+        self.revisions.create_from_document(&document).await?;
+        self.refresh_links(&document).await;
+        Ok(document)
+    }
+
+    /// The links table is derived data, so a failure to update it shouldn't fail
+    /// the save; `rebuild_document_links` can repair it.
+    async fn refresh_links(&self, document: &DocumentModel) {
+        if let Err(e) = self.links.refresh(document).await {
+            log_warn!(
+                "Documents",
+                "Failed to update links for document {}: {}",
+                document.id,
+                e
+            );
+        }
+    }
+
+    /// Revisions of a document, newest first
+    pub async fn list_revisions(
+        &self,
+        document_id: i32,
+    ) -> Result<Vec<DocumentRevisionModel>, sea_orm::DbErr> {
+        self.revisions.find_by_document_id(document_id).await
+    }
+
+    pub async fn get_revision(
+        &self,
+        revision_id: i32,
+    ) -> Result<Option<DocumentRevisionModel>, sea_orm::DbErr> {
+        self.revisions.find_by_id(revision_id).await
+    }
+
+    /// Diff two revisions of the same document, `from` being the older side.
+    pub async fn diff_revisions(
+        &self,
+        from_revision_id: i32,
+        to_revision_id: i32,
+    ) -> Result<DocumentRevisionDiff, sea_orm::DbErr> {
+        let from = self.require_revision(from_revision_id).await?;
+        let to = self.require_revision(to_revision_id).await?;
+        if from.document_id != to.document_id {
+            return Err(sea_orm::DbErr::Custom(
+                "Revisions belong to different documents".to_string(),
+            ));
+        }
+
+        Ok(DocumentRevisionDiff {
+            document_id: from.document_id,
+            from_revision: from.revision_number,
+            to_revision: to.revision_number,
+            title_changed: from.title != to.title,
+            diff: diff_lines(&from.content, &to.content),
+        })
+    }
+
+    /// Load an old revision's content into the document's draft. Nothing is
+    /// overwritten until the draft is saved, which records a new revision.
+    pub async fn restore_revision(
+        &self,
+        revision_id: i32,
+    ) -> Result<DocumentModel, sea_orm::DbErr> {
+        let revision = self.require_revision(revision_id).await?;
+        self.repository
+            .update_draft(revision.document_id, revision.content)
             .await
     }
 
+    async fn require_revision(&self, id: i32) -> Result<DocumentRevisionModel, sea_orm::DbErr> {
+        self.revisions
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound("Revision not found".to_string()))
+    }
+
     pub async fn delete_document(&self, id: i32) -> Result<(), sea_orm::DbErr> {
         self.repository.delete(id).await
     }
@@ -63,11 +219,124 @@ impl DocumentService {
         self.repository.find_all().await
     }
 
+    pub async fn get_documents_by_project(
+        &self,
+        project_id: i32,
+    ) -> Result<Vec<DocumentModel>, sea_orm::DbErr> {
+        self.repository.find_by_project_id(project_id).await
+    }
+
+    /// Documents matching `query`, best match first.
     pub async fn search_documents(
         &self,
         query: &str,
     ) -> Result<Vec<DocumentModel>, sea_orm::DbErr> {
-        self.repository.search(query).await
+        let Some(fts_query) = build_fts_query(query) else {
+            return self.repository.search(query).await;
+        };
+
+        let ranked_ids: Vec<i32> = self
+            .repository
+            .search_full_text(&fts_query, true)
+            .await?
+            .into_iter()
+            .map(|row| row.id)
+            .collect();
+
+        let mut by_id: HashMap<i32, DocumentModel> = self
+            .repository
+            .find_by_ids(ranked_ids.clone())
+            .await?
+            .into_iter()
+            .map(|doc| (doc.id, doc))
+            .collect();
+
+        Ok(ranked_ids
+            .into_iter()
+            .filter_map(|id| by_id.remove(&id))
+            .collect())
+    }
+
+    /// Ranked full-text search with snippets and facet counts.
+    pub async fn search_full_text(
+        &self,
+        params: DocumentSearchParams,
+    ) -> Result<DocumentSearchResults, sea_orm::DbErr> {
+        let Some(fts_query) = build_fts_query(&params.query) else {
+            return Ok(DocumentSearchResults::default());
+        };
+
+        let rows = self
+            .repository
+            .search_full_text(&fts_query, params.include_archived.unwrap_or(false))
+            .await?;
+
+        let hits: Vec<DocumentSearchHit> = rows.into_iter().map(DocumentSearchHit::from).collect();
+        let facets = Self::compute_facets(&hits);
+
+        let wanted_tags = params.tags.unwrap_or_default();
+        let filtered: Vec<DocumentSearchHit> = hits
+            .into_iter()
+            .filter(|hit| params.project_id.is_none() || hit.project_id == params.project_id)
+            .filter(|hit| wanted_tags.iter().all(|tag| hit.tags.contains(tag)))
+            .filter(|hit| match (params.updated_after, hit.updated_at) {
+                (Some(after), Some(updated)) => updated >= after,
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .filter(|hit| match (params.updated_before, hit.updated_at) {
+                (Some(before), Some(updated)) => updated <= before,
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .collect();
+
+        let total = filtered.len();
+        let hits = filtered
+            .into_iter()
+            .take(params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+            .collect();
+
+        Ok(DocumentSearchResults {
+            total,
+            hits,
+            facets,
+        })
+    }
+
+    fn compute_facets(hits: &[DocumentSearchHit]) -> DocumentSearchFacets {
+        let mut projects: HashMap<Option<i32>, usize> = HashMap::new();
+        let mut tags: HashMap<String, usize> = HashMap::new();
+        let mut facets = DocumentSearchFacets::default();
+
+        for hit in hits {
+            *projects.entry(hit.project_id).or_default() += 1;
+            for tag in &hit.tags {
+                *tags.entry(tag.clone()).or_default() += 1;
+            }
+            if let Some(updated) = hit.updated_at {
+                facets.updated_earliest =
+                    Some(facets.updated_earliest.map_or(updated, |e| e.min(updated)));
+                facets.updated_latest =
+                    Some(facets.updated_latest.map_or(updated, |l| l.max(updated)));
+            }
+        }
+
+        facets.projects = projects
+            .into_iter()
+            .map(|(value, count)| FacetCount { value, count })
+            .collect();
+        facets.projects.sort_by_key(|f| std::cmp::Reverse(f.count));
+
+        facets.tags = tags
+            .into_iter()
+            .map(|(value, count)| FacetCount { value, count })
+            .collect();
+        facets
+            .tags
+            .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+        facets
     }
 
     pub async fn get_documents_by_tag(
@@ -77,3 +346,22 @@ impl DocumentService {
         self.repository.find_by_tag(tag).await
     }
 }
+
+impl From<DocumentSearchRow> for DocumentSearchHit {
+    fn from(row: DocumentSearchRow) -> Self {
+        Self {
+            id: row.id,
+            title: row.title,
+            title_highlight: row.title_highlight,
+            snippet: row.snippet,
+            tags: row
+                .tags
+                .and_then(|t| serde_json::from_str(&t).ok())
+                .unwrap_or_default(),
+            project_id: row.project_id,
+            is_archived: row.is_archived,
+            updated_at: row.updated_at.map(|dt| dt.into()),
+            score: row.score,
+        }
+    }
+}
@@ -0,0 +1,536 @@
+//! Two-way sync between a project's documents and a folder of markdown files.
+//!
+//! For every synced document we remember the file it maps to and a hash of each
+//! side as of the last sync. On export or import, a side whose hash moved has been
+//! edited since; if both moved, the document is reported as a conflict and left
+//! alone until [`DocumentSyncService::resolve_conflict`] picks a winner.
+//!
+//! Deleting a file does not delete its document, and vice versa.
+
+use crate::database::DatabaseManager;
+use crate::domains::documents::repositories::document_repository::CreateDocumentRequest;
+use crate::domains::documents::repositories::document_sync_repository::DocumentSyncRepository;
+use crate::domains::documents::services::document_service::DocumentService;
+use crate::domains::documents::services::markdown_sync::{
+    document_hash, file_hash, hash_model, parse_markdown, render_markdown, slugify,
+};
+use crate::entities::document::Model as DocumentModel;
+use crate::entities::document_sync_folder::Model as DocumentSyncFolderModel;
+use crate::{log_info, log_warn};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+
+/// How often watched folders are rescanned for external edits
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Event emitted after a watched folder's changes were imported
+pub const SYNC_EVENT: &str = "documents-sync-changed";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub document_id: Option<i32>,
+    pub title: String,
+    pub file_path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentSyncReport {
+    pub project_id: i32,
+    pub exported: usize,
+    pub imported: usize,
+    pub created: usize,
+    pub unchanged: usize,
+    pub conflicts: Vec<SyncConflict>,
+    /// Files that could not be read or parsed
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Overwrite the document with the file's contents
+    KeepFile,
+    /// Overwrite the file with the document's contents
+    KeepDocument,
+}
+
+pub struct DocumentSyncService {
+    documents: DocumentService,
+    sync: DocumentSyncRepository,
+}
+
+impl DocumentSyncService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            documents: DocumentService::new(db.clone()),
+            sync: DocumentSyncRepository::new(db),
+        }
+    }
+
+    pub async fn get_folder(
+        &self,
+        project_id: i32,
+    ) -> Result<Option<DocumentSyncFolderModel>, String> {
+        self.sync
+            .find_folder(project_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn list_folders(&self) -> Result<Vec<DocumentSyncFolderModel>, String> {
+        self.sync
+            .find_all_folders()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn set_folder(
+        &self,
+        project_id: i32,
+        folder_path: &str,
+    ) -> Result<DocumentSyncFolderModel, String> {
+        let folder = PathBuf::from(folder_path);
+        std::fs::create_dir_all(&folder)
+            .map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+
+        self.sync
+            .set_folder(project_id, folder.to_string_lossy().to_string())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn remove_folder(&self, project_id: i32) -> Result<(), String> {
+        self.sync
+            .delete_folder(project_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Write the project's documents into its sync folder.
+    pub async fn export_project(&self, project_id: i32) -> Result<DocumentSyncReport, String> {
+        let folder = self.require_folder(project_id).await?;
+        let mut report = DocumentSyncReport {
+            project_id,
+            ..Default::default()
+        };
+
+        let documents = self
+            .documents
+            .get_documents_by_project(project_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for document in documents {
+            if let Err(e) = self.export_document(&folder, &document, &mut report).await {
+                report.errors.push(format!("{}: {}", document.title, e));
+            }
+        }
+
+        self.finish(project_id, &report).await;
+        Ok(report)
+    }
+
+    /// Read every `.md` file in the project's sync folder back into documents.
+    pub async fn import_project(&self, project_id: i32) -> Result<DocumentSyncReport, String> {
+        let folder = self.require_folder(project_id).await?;
+        let mut report = DocumentSyncReport {
+            project_id,
+            ..Default::default()
+        };
+
+        for path in markdown_files(&folder)? {
+            if let Err(e) = self.import_file(project_id, &path, &mut report).await {
+                report.errors.push(format!("{}: {}", path.display(), e));
+            }
+        }
+
+        self.finish(project_id, &report).await;
+        Ok(report)
+    }
+
+    /// Import external edits, then export anything changed in the app.
+    pub async fn sync_project(&self, project_id: i32) -> Result<DocumentSyncReport, String> {
+        let mut report = self.import_project(project_id).await?;
+        let exported = self.export_project(project_id).await?;
+
+        report.exported = exported.exported;
+        // Both passes see the same conflicts; only keep the export side's new ones.
+        for conflict in exported.conflicts {
+            if !report
+                .conflicts
+                .iter()
+                .any(|c| c.document_id == conflict.document_id)
+            {
+                report.conflicts.push(conflict);
+            }
+        }
+        report.errors.extend(exported.errors);
+        Ok(report)
+    }
+
+    /// Settle a conflict reported for `file_path` by keeping one side.
+    pub async fn resolve_conflict(
+        &self,
+        project_id: i32,
+        file_path: &str,
+        resolution: ConflictResolution,
+    ) -> Result<DocumentModel, String> {
+        let path = PathBuf::from(file_path);
+        let raw =
+            std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+        let parsed = parse_markdown(&String::from_utf8_lossy(&raw), &file_stem(&path))?;
+
+        let document = match parsed.front_matter.id {
+            Some(id) => self.find_project_document(project_id, id).await?,
+            None => None,
+        }
+        .ok_or_else(|| format!("{} is not linked to a document in this project", file_path))?;
+
+        match resolution {
+            ConflictResolution::KeepFile => {
+                let updated = self
+                    .documents
+                    .save_document(
+                        document.id,
+                        Some(parsed.title),
+                        Some(parsed.content),
+                        Some(parsed.front_matter.tags),
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                self.record(&updated, &path, file_hash(&raw)).await?;
+                Ok(updated)
+            }
+            ConflictResolution::KeepDocument => {
+                self.write_document(&document, &path).await?;
+                Ok(document)
+            }
+        }
+    }
+
+    async fn export_document(
+        &self,
+        folder: &Path,
+        document: &DocumentModel,
+        report: &mut DocumentSyncReport,
+    ) -> Result<(), String> {
+        let doc_hash = hash_model(document);
+        let state = self
+            .sync
+            .find_state(document.id)
+            .await
+            .map_err(|e| e.to_string())?
+            // A state pointing into a different folder is from before the folder moved.
+            .filter(|s| Path::new(&s.file_path).parent() == Some(folder));
+
+        let Some(state) = state else {
+            let path = unique_path(folder, document);
+            return self.write_document(document, &path).await.map(|_| {
+                report.exported += 1;
+            });
+        };
+
+        let path = PathBuf::from(&state.file_path);
+        let on_disk = std::fs::read(&path).ok().map(|bytes| file_hash(&bytes));
+        let file_changed = on_disk.as_deref().is_some_and(|h| h != state.file_hash);
+        let document_changed = doc_hash != state.document_hash;
+
+        match (file_changed, document_changed) {
+            (true, true) => report.conflicts.push(SyncConflict {
+                document_id: Some(document.id),
+                title: document.title.clone(),
+                file_path: state.file_path,
+                reason: "Edited in the app and on disk since the last sync".to_string(),
+            }),
+            // External edit waiting to be imported; don't clobber it.
+            (true, false) => report.unchanged += 1,
+            (false, false) if on_disk.is_some() => report.unchanged += 1,
+            _ => {
+                self.write_document(document, &path).await?;
+                report.exported += 1;
+            }
+        }
+        Ok(())
+    }
+
+    async fn import_file(
+        &self,
+        project_id: i32,
+        path: &Path,
+        report: &mut DocumentSyncReport,
+    ) -> Result<(), String> {
+        let raw = std::fs::read(path).map_err(|e| e.to_string())?;
+        let raw_hash = file_hash(&raw);
+        let parsed = parse_markdown(&String::from_utf8_lossy(&raw), &file_stem(path))?;
+
+        let existing = match parsed.front_matter.id {
+            Some(id) => self.find_project_document(project_id, id).await?,
+            None => None,
+        };
+
+        let Some(document) = existing else {
+            let created = self
+                .documents
+                .create_document(CreateDocumentRequest {
+                    title: parsed.title,
+                    content: parsed.content,
+                    is_archived: Some(parsed.front_matter.archived),
+                    tags: Some(parsed.front_matter.tags),
+                    project_id: Some(project_id),
+                })
+                .await
+                .map_err(|e| e.to_string())?;
+            // Rewrite the file so its front matter carries the new id.
+            self.write_document(&created, path).await?;
+            report.created += 1;
+            return Ok(());
+        };
+
+        let file_doc_hash =
+            document_hash(&parsed.title, &parsed.content, &parsed.front_matter.tags);
+        let current_hash = hash_model(&document);
+        let state = self
+            .sync
+            .find_state(document.id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let document_changed = match &state {
+            Some(state) if state.file_hash == raw_hash => {
+                report.unchanged += 1;
+                return Ok(());
+            }
+            Some(state) => state.document_hash != current_hash,
+            // Never synced: only safe to link if both sides already agree.
+            None => file_doc_hash != current_hash,
+        };
+
+        if file_doc_hash == current_hash {
+            self.record(&document, path, raw_hash).await?;
+            report.unchanged += 1;
+        } else if document_changed {
+            report.conflicts.push(SyncConflict {
+                document_id: Some(document.id),
+                title: document.title.clone(),
+                file_path: path.to_string_lossy().to_string(),
+                reason: if state.is_some() {
+                    "Edited in the app and on disk since the last sync".to_string()
+                } else {
+                    "File and document differ and have never been synced".to_string()
+                },
+            });
+        } else {
+            let updated = self
+                .documents
+                .save_document(
+                    document.id,
+                    Some(parsed.title),
+                    Some(parsed.content),
+                    Some(parsed.front_matter.tags),
+                    Some(parsed.front_matter.archived),
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            self.record(&updated, path, raw_hash).await?;
+            report.imported += 1;
+        }
+        Ok(())
+    }
+
+    async fn write_document(&self, document: &DocumentModel, path: &Path) -> Result<(), String> {
+        let rendered = render_markdown(document);
+        std::fs::write(path, &rendered)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        self.record(document, path, file_hash(rendered.as_bytes()))
+            .await
+    }
+
+    async fn record(
+        &self,
+        document: &DocumentModel,
+        path: &Path,
+        file_hash: String,
+    ) -> Result<(), String> {
+        self.sync
+            .upsert_state(
+                document.id,
+                path.to_string_lossy().to_string(),
+                file_hash,
+                hash_model(document),
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn find_project_document(
+        &self,
+        project_id: i32,
+        id: i32,
+    ) -> Result<Option<DocumentModel>, String> {
+        // An id from another project (e.g. a file copied between folders) is
+        // treated as a new document rather than overwriting the original.
+        Ok(self
+            .documents
+            .get_document(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(|d| d.project_id == Some(project_id)))
+    }
+
+    async fn require_folder(&self, project_id: i32) -> Result<PathBuf, String> {
+        let folder = self
+            .get_folder(project_id)
+            .await?
+            .ok_or_else(|| format!("No sync folder configured for project {}", project_id))?;
+        let path = PathBuf::from(folder.folder_path);
+        std::fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    async fn finish(&self, project_id: i32, report: &DocumentSyncReport) {
+        if let Err(e) = self.sync.touch_folder(project_id).await {
+            log_warn!("DocumentSync", "Failed to update last sync time: {}", e);
+        }
+        if report.imported + report.created + report.exported > 0 {
+            log_info!(
+                "DocumentSync",
+                "Project {}: {} imported, {} created, {} exported, {} conflict(s)",
+                project_id,
+                report.imported,
+                report.created,
+                report.exported,
+                report.conflicts.len()
+            );
+        }
+    }
+}
+
+/// Background folder watchers, one per project. Polls rather than relying on OS
+/// file events so behaviour is the same across platforms and network drives.
+#[derive(Default)]
+pub struct DocumentSyncWatchers {
+    tasks: Mutex<HashMap<i32, JoinHandle<()>>>,
+}
+
+impl DocumentSyncWatchers {
+    pub fn start(&self, project_id: i32, db_manager: Arc<DatabaseManager>, app: AppHandle) {
+        self.stop(project_id);
+
+        let handle = tauri::async_runtime::spawn(async move {
+            let service = DocumentSyncService::new(db_manager.get_connection_clone());
+            let mut last_snapshot: Option<HashMap<PathBuf, (SystemTime, u64)>> = None;
+
+            loop {
+                let folder = match service.get_folder(project_id).await {
+                    Ok(Some(folder)) => PathBuf::from(folder.folder_path),
+                    // Folder was unlinked; nothing left to watch.
+                    Ok(None) => break,
+                    Err(e) => {
+                        log_warn!("DocumentSync", "Failed to load sync folder: {}", e);
+                        tokio::time::sleep(WATCH_INTERVAL).await;
+                        continue;
+                    }
+                };
+
+                let snapshot = snapshot_folder(&folder);
+                if last_snapshot.as_ref().is_some_and(|last| *last != snapshot) {
+                    match service.import_project(project_id).await {
+                        Ok(report) => {
+                            let _ = app.emit(SYNC_EVENT, &report);
+                        }
+                        Err(e) => log_warn!("DocumentSync", "Import failed: {}", e),
+                    }
+                    // The import may have rewritten front matter; don't react to that.
+                    last_snapshot = Some(snapshot_folder(&folder));
+                } else {
+                    last_snapshot = Some(snapshot);
+                }
+
+                tokio::time::sleep(WATCH_INTERVAL).await;
+            }
+        });
+
+        self.tasks.lock().unwrap().insert(project_id, handle);
+    }
+
+    pub fn stop(&self, project_id: i32) {
+        if let Some(handle) = self.tasks.lock().unwrap().remove(&project_id) {
+            handle.abort();
+        }
+    }
+
+    pub fn stop_all(&self) {
+        for (_, handle) in self.tasks.lock().unwrap().drain() {
+            handle.abort();
+        }
+    }
+
+    /// Watch every linked folder in the active workspace, replacing any
+    /// watchers already running
+    pub async fn resume(&self, db_manager: Arc<DatabaseManager>, app: AppHandle) {
+        self.stop_all();
+        let folders = DocumentSyncService::new(db_manager.get_connection_clone())
+            .list_folders()
+            .await
+            .unwrap_or_default();
+        for folder in folders {
+            self.start(folder.project_id, db_manager.clone(), app.clone());
+        }
+    }
+}
+
+fn markdown_files(folder: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(folder)
+        .map_err(|e| format!("Failed to read {}: {}", folder.display(), e))?;
+
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn snapshot_folder(folder: &Path) -> HashMap<PathBuf, (SystemTime, u64)> {
+    markdown_files(folder)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|path| {
+            let meta = std::fs::metadata(&path).ok()?;
+            Some((path, (meta.modified().ok()?, meta.len())))
+        })
+        .collect()
+}
+
+/// `<slug>.md`, or `<slug>-<id>.md` if another file already has that name.
+fn unique_path(folder: &Path, document: &DocumentModel) -> PathBuf {
+    let slug = slugify(&document.title);
+    let path = folder.join(format!("{}.md", slug));
+    if path.exists() {
+        folder.join(format!("{}-{}.md", slug, document.id))
+    } else {
+        path
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Untitled".to_string())
+}
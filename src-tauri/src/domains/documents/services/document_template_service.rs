@@ -0,0 +1,332 @@
+use crate::database::DatabaseManager;
+use crate::domains::ai::providers::{GenerationOptions, ProviderType};
+use crate::domains::ai::services::AIService;
+use crate::domains::documents::repositories::document_repository::CreateDocumentRequest;
+use crate::domains::documents::repositories::document_template_repository::{
+    CreateDocumentTemplateRequest, DocumentTemplateRepository, UpdateDocumentTemplateRequest,
+};
+use crate::domains::documents::services::document_service::DocumentService;
+use crate::domains::documents::services::markdown_sync::parse_tags;
+use crate::domains::projects::services::project_service::ProjectService;
+use crate::domains::tasks::services::task_service::TaskService;
+use crate::entities::document::Model as DocumentModel;
+use crate::entities::document_template::Model as DocumentTemplateModel;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Marker for sections the AI pre-fill should write
+const FILL_MARKER: &str = "<!-- fill:";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDocumentFromTemplateRequest {
+    pub template_id: i32,
+    /// Substituted for `{{title}}`; defaults to the template name
+    pub title: Option<String>,
+    pub project_id: Option<i32>,
+    /// Rendered as a markdown list into `{{task_links}}`
+    pub task_ids: Option<Vec<i32>>,
+    /// Extra variables; these override the built-in ones
+    pub variables: Option<HashMap<String, String>>,
+    /// Ask the AI service to write the `<!-- fill: ... -->` sections
+    pub ai_prefill: Option<bool>,
+    pub provider_type: Option<ProviderType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentFromTemplate {
+    pub document: DocumentModel,
+    /// Placeholders that had no value and were left in the text
+    pub unresolved_variables: Vec<String>,
+    pub ai_prefilled: bool,
+    /// Set when pre-fill was requested but failed; the document is still created
+    pub ai_error: Option<String>,
+}
+
+pub struct DocumentTemplateService {
+    templates: DocumentTemplateRepository,
+    documents: DocumentService,
+    tasks: TaskService,
+    projects: ProjectService,
+}
+
+impl DocumentTemplateService {
+    pub fn new(db_manager: &Arc<DatabaseManager>) -> Self {
+        let db = db_manager.get_connection_clone();
+        Self {
+            templates: DocumentTemplateRepository::new(db.clone()),
+            documents: DocumentService::new(db.clone()),
+            tasks: TaskService::new(db),
+            projects: ProjectService::new(db_manager),
+        }
+    }
+
+    pub async fn get_templates(&self) -> Result<Vec<DocumentTemplateModel>, String> {
+        self.templates.find_all().await.map_err(|e| e.to_string())
+    }
+
+    pub async fn create_template(
+        &self,
+        request: CreateDocumentTemplateRequest,
+    ) -> Result<DocumentTemplateModel, String> {
+        self.templates
+            .create(request)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn update_template(
+        &self,
+        id: i32,
+        request: UpdateDocumentTemplateRequest,
+    ) -> Result<DocumentTemplateModel, String> {
+        self.templates
+            .update(id, request)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Built-in templates can be edited but not deleted.
+    pub async fn delete_template(&self, id: i32) -> Result<(), String> {
+        let template = self.require_template(id).await?;
+        if template.is_builtin {
+            return Err(format!("'{}' is a built-in template", template.name));
+        }
+        self.templates.delete(id).await.map_err(|e| e.to_string())
+    }
+
+    pub async fn create_document(
+        &self,
+        request: CreateDocumentFromTemplateRequest,
+        ai_service: &AIService,
+    ) -> Result<DocumentFromTemplate, String> {
+        let template = self.require_template(request.template_id).await?;
+        let variables = self.build_variables(&template, &request).await?;
+
+        let (title, mut unresolved) = render_template(&template.title_template, &variables);
+        let (mut content, body_unresolved) = render_template(&template.body_template, &variables);
+        for name in body_unresolved {
+            if !unresolved.contains(&name) {
+                unresolved.push(name);
+            }
+        }
+
+        let mut ai_prefilled = false;
+        let mut ai_error = None;
+        if request.ai_prefill.unwrap_or(false) && content.contains(FILL_MARKER) {
+            match Self::prefill(ai_service, &content, &variables, request.provider_type).await {
+                Ok(filled) => {
+                    content = filled;
+                    ai_prefilled = true;
+                }
+                Err(e) => ai_error = Some(e),
+            }
+        }
+
+        let document = self
+            .documents
+            .create_document(CreateDocumentRequest {
+                title: title.trim().to_string(),
+                content,
+                is_archived: Some(false),
+                tags: Some(parse_tags(template.tags.as_deref())),
+                project_id: request.project_id,
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(DocumentFromTemplate {
+            document,
+            unresolved_variables: unresolved,
+            ai_prefilled,
+            ai_error,
+        })
+    }
+
+    async fn build_variables(
+        &self,
+        template: &DocumentTemplateModel,
+        request: &CreateDocumentFromTemplateRequest,
+    ) -> Result<HashMap<String, String>, String> {
+        let now = chrono::Local::now();
+        let mut variables = HashMap::new();
+        variables.insert(
+            "title".to_string(),
+            request
+                .title
+                .clone()
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or_else(|| template.name.clone()),
+        );
+        variables.insert("date".to_string(), now.format("%Y-%m-%d").to_string());
+        variables.insert(
+            "datetime".to_string(),
+            now.format("%Y-%m-%d %H:%M").to_string(),
+        );
+
+        if let Some(project_id) = request.project_id {
+            let project = self
+                .projects
+                .get_project(project_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Project {} not found", project_id))?;
+            variables.insert("project_name".to_string(), project.name);
+            variables.insert("project_path".to_string(), project.path);
+        } else {
+            variables.insert("project_name".to_string(), "—".to_string());
+            variables.insert("project_path".to_string(), "—".to_string());
+        }
+
+        let task_ids = request.task_ids.clone().unwrap_or_default();
+        let task_links = if task_ids.is_empty() {
+            "_None_".to_string()
+        } else {
+            let tasks = self
+                .tasks
+                .get_tasks_by_ids(task_ids)
+                .await
+                .map_err(|e| e.to_string())?;
+            tasks
+                .iter()
+                .map(|t| format!("- [#{}] {} ({})", t.id, t.title, t.status))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        variables.insert("task_links".to_string(), task_links);
+
+        if let Some(extra) = &request.variables {
+            variables.extend(extra.clone());
+        }
+
+        Ok(variables)
+    }
+
+    async fn prefill(
+        ai_service: &AIService,
+        content: &str,
+        variables: &HashMap<String, String>,
+        provider_type: Option<ProviderType>,
+    ) -> Result<String, String> {
+        let system_message = r#"You fill in markdown document templates for a software team.
+
+Replace every `<!-- fill: hint -->` comment with concise, concrete content that follows its hint.
+Keep every heading, list item and line that is not a fill comment exactly as it is.
+Where you lack information, write a short TODO for the author instead of inventing facts.
+Respond with the complete markdown document only, no code fences or commentary."#;
+
+        let mut prompt = String::new();
+        for key in ["title", "project_name", "date"] {
+            if let Some(value) = variables.get(key) {
+                prompt.push_str(&format!("{}: {}\n", key, value));
+            }
+        }
+        prompt.push_str("\n=== TEMPLATE ===\n");
+        prompt.push_str(content);
+
+        let options = GenerationOptions {
+            temperature: Some(0.4),
+            max_tokens: Some(3000),
+            timeout_ms: Some(120000),
+            model: None,
+            llm_provider: None,
+            extra_options: None,
+            feature: Some("docs".to_string()),
+            bypass_cache: false,
+        };
+
+        let result = ai_service
+            .generate_with_system(system_message, &prompt, Some(options), provider_type)
+            .await
+            .map_err(|e| format!("AI pre-fill failed: {}", e))?;
+
+        let filled = strip_code_fence(&result.content);
+        if filled.trim().is_empty() {
+            return Err("AI pre-fill returned an empty document".to_string());
+        }
+        Ok(filled.to_string())
+    }
+
+    async fn require_template(&self, id: i32) -> Result<DocumentTemplateModel, String> {
+        self.templates
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Template {} not found", id))
+    }
+}
+
+/// Substitute `{{name}}` placeholders (whitespace inside the braces is ignored).
+/// Unknown names are left in place and returned so the UI can point them out.
+pub fn render_template(
+    template: &str,
+    variables: &HashMap<String, String>,
+) -> (String, Vec<String>) {
+    let mut output = String::with_capacity(template.len());
+    let mut unresolved = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + 2 + len + 2];
+        let name = rest[start + 2..start + 2 + len].trim();
+
+        output.push_str(&rest[..start]);
+        match variables.get(name) {
+            Some(value) => output.push_str(value),
+            None => {
+                output.push_str(placeholder);
+                if !unresolved.iter().any(|n| n == name) {
+                    unresolved.push(name.to_string());
+                }
+            }
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+    output.push_str(rest);
+
+    (output, unresolved)
+}
+
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(inner) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let inner = inner.split_once('\n').map(|(_, body)| body).unwrap_or("");
+    inner.strip_suffix("```").unwrap_or(inner).trim_end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_known_variables_and_reports_the_rest() {
+        let mut variables = HashMap::new();
+        variables.insert("title".to_string(), "Use SQLite".to_string());
+        variables.insert("date".to_string(), "2026-10-17".to_string());
+
+        let (rendered, unresolved) = render_template(
+            "# {{title}}\n{{ date }} by {{author}} {{author}} {{",
+            &variables,
+        );
+
+        assert_eq!(
+            rendered,
+            "# Use SQLite\n2026-10-17 by {{author}} {{author}} {{"
+        );
+        assert_eq!(unresolved, vec!["author"]);
+    }
+
+    #[test]
+    fn strips_markdown_fence_around_ai_output() {
+        assert_eq!(
+            strip_code_fence("```markdown\n# Doc\nbody\n```"),
+            "# Doc\nbody"
+        );
+        assert_eq!(strip_code_fence("# Doc\n"), "# Doc");
+    }
+}
@@ -0,0 +1,213 @@
+//! Markdown file format used when syncing documents to disk.
+//!
+//! Each document is written as a `.md` file with a YAML front-matter block holding
+//! the metadata that doesn't fit in markdown:
+//!
+//! ```text
+//! ---
+//! id: 12
+//! title: Release checklist
+//! tags:
+//! - ops
+//! ---
+//!
+//! # Release checklist
+//! ...
+//! ```
+//!
+//! Files without front matter are accepted on import; the title then comes from
+//! the first `# ` heading or, failing that, the file name.
+
+use crate::entities::document::Model as DocumentModel;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentFrontMatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A markdown file split into front matter and body
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownDocument {
+    pub front_matter: DocumentFrontMatter,
+    pub title: String,
+    pub content: String,
+}
+
+/// Render a document as markdown with front matter.
+pub fn render_markdown(document: &DocumentModel) -> String {
+    let front_matter = DocumentFrontMatter {
+        id: Some(document.id),
+        title: Some(document.title.clone()),
+        tags: parse_tags(document.tags.as_deref()),
+        archived: document.is_archived,
+        updated_at: document.updated_at.map(|dt| dt.into()),
+    };
+    let yaml = serde_yaml::to_string(&front_matter).unwrap_or_default();
+
+    format!("---\n{}---\n\n{}", yaml, document.content)
+}
+
+/// Parse a markdown file. `fallback_title` is used when neither the front matter
+/// nor a top-level heading provides one.
+pub fn parse_markdown(raw: &str, fallback_title: &str) -> Result<MarkdownDocument, String> {
+    let (front_matter, body) = match split_front_matter(raw) {
+        Some((yaml, body)) => {
+            let front_matter: DocumentFrontMatter =
+                serde_yaml::from_str(yaml).map_err(|e| format!("Invalid front matter: {}", e))?;
+            (front_matter, body)
+        }
+        None => (DocumentFrontMatter::default(), raw),
+    };
+
+    let title = front_matter
+        .title
+        .clone()
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| {
+            body.lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .map(|t| t.trim().to_string())
+        })
+        .unwrap_or_else(|| fallback_title.to_string());
+
+    Ok(MarkdownDocument {
+        front_matter,
+        title,
+        content: body.to_string(),
+    })
+}
+
+/// Returns the YAML between the `---` fences and the body after them, minus the
+/// blank separator line [`render_markdown`] adds.
+fn split_front_matter(raw: &str) -> Option<(&str, &str)> {
+    let rest = raw
+        .strip_prefix("---\n")
+        .or_else(|| raw.strip_prefix("---\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let yaml = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            let body = body
+                .strip_prefix("\r\n")
+                .or_else(|| body.strip_prefix('\n'))
+                .unwrap_or(body);
+            return Some((yaml, body));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Hash of the fields that are synced, used to tell whether a document changed
+/// since it was last written to or read from disk.
+pub fn document_hash(title: &str, content: &str, tags: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(title.as_bytes());
+    hasher.update([0]);
+    hasher.update(content.as_bytes());
+    hasher.update([0]);
+    hasher.update(tags.join("\n").as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+pub fn hash_model(document: &DocumentModel) -> String {
+    document_hash(
+        &document.title,
+        &document.content,
+        &parse_tags(document.tags.as_deref()),
+    )
+}
+
+pub fn file_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+pub fn parse_tags(tags: Option<&str>) -> Vec<String> {
+    tags.and_then(|t| serde_json::from_str(t).ok())
+        .unwrap_or_default()
+}
+
+/// File-system friendly name for a document title, without extension.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug.chars().take(80).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> DocumentModel {
+        DocumentModel {
+            id: 7,
+            title: "Release: checklist".to_string(),
+            content: "# Release\n\n- tag\n- ship\n".to_string(),
+            is_archived: false,
+            content_draft: None,
+            is_draft: false,
+            tags: Some("[\"ops\",\"release\"]".to_string()),
+            project_id: Some(1),
+            created_at: None,
+            updated_at: None,
+            last_edited_at: None,
+        }
+    }
+
+    #[test]
+    fn render_then_parse_round_trips() {
+        let doc = document();
+        let parsed = parse_markdown(&render_markdown(&doc), "fallback").unwrap();
+
+        assert_eq!(parsed.front_matter.id, Some(7));
+        assert_eq!(parsed.title, doc.title);
+        assert_eq!(parsed.content, doc.content);
+        assert_eq!(parsed.front_matter.tags, vec!["ops", "release"]);
+        assert_eq!(
+            document_hash(&parsed.title, &parsed.content, &parsed.front_matter.tags),
+            hash_model(&doc)
+        );
+    }
+
+    #[test]
+    fn plain_markdown_takes_title_from_heading_or_file_name() {
+        let parsed = parse_markdown("intro\n# Notes\nbody", "notes-file").unwrap();
+        assert_eq!(parsed.title, "Notes");
+        assert_eq!(parsed.front_matter, DocumentFrontMatter::default());
+
+        let parsed = parse_markdown("just text", "notes-file").unwrap();
+        assert_eq!(parsed.title, "notes-file");
+        assert_eq!(parsed.content, "just text");
+    }
+
+    #[test]
+    fn slugs_are_lowercase_and_dash_separated() {
+        assert_eq!(slugify("Release: checklist (v2)"), "release-checklist-v2");
+        assert_eq!(slugify("???"), "untitled");
+    }
+}
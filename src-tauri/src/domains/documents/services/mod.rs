@@ -1,2 +1,11 @@
 pub mod ai_document_generator;
+pub mod document_chunks;
+pub mod document_diff;
+pub mod document_export;
+pub mod document_link_service;
+pub mod document_links;
+pub mod document_qa_service;
 pub mod document_service;
+pub mod document_sync_service;
+pub mod document_template_service;
+pub mod markdown_sync;
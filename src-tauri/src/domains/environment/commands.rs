@@ -1,7 +1,26 @@
+use crate::database::DatabaseManager;
 use crate::domains::environment::platform::platform_env;
 use crate::domains::environment::types::{
     EnvApplyResult, EnvChange, EnvPermissions, EnvVariable,
 };
+use crate::domains::learning::services::anomaly_detector::{self, ENV_CHANGED};
+use std::sync::Arc;
+use tauri::State;
+
+/// Note changed variables for failure insights; names only, never values
+async fn note_env_change(
+    db_manager: &State<'_, Arc<DatabaseManager>>,
+    names: Vec<&str>,
+    scope: &str,
+) {
+    anomaly_detector::record_change(
+        db_manager.get_connection(),
+        ENV_CHANGED,
+        serde_json::json!({ "names": names, "scope": scope }),
+        None,
+    )
+    .await;
+}
 
 #[tauri::command]
 pub async fn env_list_variables() -> Result<Vec<EnvVariable>, String> {
@@ -14,23 +33,58 @@ pub async fn env_get_permissions() -> Result<EnvPermissions, String> {
 }
 
 #[tauri::command]
-pub async fn env_set_variable(name: String, value: String, scope: String) -> Result<(), String> {
+pub async fn env_set_variable(
+    name: String,
+    value: String,
+    scope: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
     if name.trim().is_empty() {
         return Err("Variable name cannot be empty".into());
     }
-    platform_env().set_variable(&name, &value, &scope)
+    platform_env().set_variable(&name, &value, &scope)?;
+    note_env_change(&db_manager, vec![&name], &scope).await;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn env_delete_variable(name: String, scope: String) -> Result<(), String> {
+pub async fn env_delete_variable(
+    name: String,
+    scope: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
     if name.trim().is_empty() {
         return Err("Variable name cannot be empty".into());
     }
-    platform_env().delete_variable(&name, &scope)
+    platform_env().delete_variable(&name, &scope)?;
+    note_env_change(&db_manager, vec![&name], &scope).await;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn env_apply_changes(changes: Vec<EnvChange>) -> Result<EnvApplyResult, String> {
+pub async fn env_apply_changes(
+    changes: Vec<EnvChange>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<EnvApplyResult, String> {
+    let names: Vec<String> = changes.iter().map(|change| change.name.clone()).collect();
+    let scope = if changes.iter().any(|change| change.scope == "system") {
+        "system"
+    } else {
+        "user"
+    };
+    let result = apply_changes(changes)?;
+    if result.success {
+        note_env_change(
+            &db_manager,
+            names.iter().map(String::as_str).collect(),
+            scope,
+        )
+        .await;
+    }
+    Ok(result)
+}
+
+fn apply_changes(changes: Vec<EnvChange>) -> Result<EnvApplyResult, String> {
     let platform = platform_env();
     let permissions = platform.get_permissions()?;
 
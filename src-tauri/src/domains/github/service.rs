@@ -14,6 +14,7 @@ use crate::database::DatabaseManager;
 use crate::domains::credentials::services::credential_service::{
     CredentialCreateRequest, CredentialService, CredentialUpdateRequest,
 };
+use crate::domains::credentials::services::CredentialAccessor;
 use crate::domains::projects::entities::ProjectResponse;
 use crate::domains::projects::services::ProjectService;
 use crate::domains::settings::services::settings_service::SettingsService;
@@ -1155,7 +1156,7 @@ impl GitHubService {
             .await?
             .ok_or_else(|| "GitHub is not connected.".to_string())?;
         CredentialService::new(self.db.get_connection_clone())
-            .decrypt_credential(&connection.credential_id)
+            .decrypt_credential(&connection.credential_id, CredentialAccessor::Github)
             .await
             .map_err(|e| format!("Failed to decrypt GitHub credential: {e}"))
     }
@@ -5,8 +5,16 @@ use crate::domains::ide::entities::{
 use crate::domains::ide::repositories::framework_ide_mapping_repository::FrameworkIdeMappingRepository;
 use crate::domains::ide::repositories::framework_repository::FrameworkRepository;
 use crate::domains::ide::repositories::ide_repository::IdeRepository;
-use crate::domains::ide::services::ide_service::IdeService;
+use crate::domains::ide::services::devcontainer;
+use crate::domains::ide::services::extension_recommendations::{
+    self, ExtensionInstallReport, RecommendedExtension,
+};
+use crate::domains::ide::services::ide_service::{
+    supports_dev_containers, DetectedIde, IdeService,
+};
 use crate::domains::learning::repositories::learned_pattern_repository::LearnedPatternRepository;
+use crate::domains::projects::services::quick_open;
+use crate::domains::settings::services::project_settings_service::ProjectSettingsService;
 use std::sync::Arc;
 use tauri::{Manager, State};
 
@@ -18,7 +26,7 @@ pub fn init_ide_storage<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
 #[tauri::command]
 pub async fn detect_installed_ides(
     ide_service: State<'_, Arc<IdeService>>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<DetectedIde>, String> {
     Ok(ide_service.detect_installed_ides())
 }
 
@@ -93,23 +101,152 @@ pub async fn set_default_ide(id: i64, db: State<'_, Arc<DatabaseManager>>) -> Re
     Ok(id)
 }
 
+/// The default IDE, or the one a project overrides it with
 #[tauri::command]
 pub async fn get_default_ide(
+    project_id: Option<i32>,
     db: State<'_, Arc<DatabaseManager>>,
 ) -> Result<Option<IdeConfig>, String> {
+    if let Some(project_id) = project_id {
+        return Ok(ProjectSettingsService::new(db.inner().clone())
+            .effective(project_id)
+            .await?
+            .ide);
+    }
+
     let conn = db.get_connection_clone();
     let ide = IdeRepository::get_default(&conn)
         .await
         .map_err(|e| format!("Failed to get default IDE: {}", e))?;
 
-    Ok(ide.map(|ide| IdeConfig {
-        id: Some(ide.id as i64),
-        name: ide.name,
-        executable: ide.executable,
-        is_default: ide.is_default,
-        created_at: ide.created_at.map(|dt| dt.to_rfc3339()),
-        updated_at: ide.updated_at.map(|dt| dt.to_rfc3339()),
-    }))
+    Ok(ide.map(IdeConfig::from))
+}
+
+/// Open a project in an IDE, optionally at a file and line. The IDE is
+/// `ide_id`, else the project's IDE, else the default one.
+#[tauri::command]
+pub async fn open_in_ide(
+    path: String,
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    ide_id: Option<i64>,
+    project_id: Option<i32>,
+    ide_service: State<'_, Arc<IdeService>>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    let conn = db.get_connection_clone();
+    let mut ide = match ide_id {
+        Some(id) => IdeRepository::get_by_id(&conn, id as i32)
+            .await
+            .map_err(|e| format!("Failed to get IDE: {}", e))?
+            .map(IdeConfig::from),
+        None => None,
+    };
+    if ide.is_none() {
+        if let Some(project_id) = project_id {
+            ide = ProjectSettingsService::new(db.inner().clone())
+                .effective(project_id)
+                .await?
+                .ide;
+        }
+    }
+    if ide.is_none() {
+        ide = IdeRepository::get_default(&conn)
+            .await
+            .map_err(|e| format!("Failed to get default IDE: {}", e))?
+            .map(IdeConfig::from);
+    }
+    let ide = ide.ok_or_else(|| "No IDE configured; add one in settings".to_string())?;
+
+    ide_service.open(
+        &ide.executable,
+        std::path::Path::new(&path),
+        file.as_deref().map(std::path::Path::new),
+        line,
+        column,
+    )?;
+    if let Some(file) = file {
+        quick_open::record_opened(&std::path::Path::new(&path).join(file), "ide").await;
+    }
+    Ok(())
+}
+
+/// Open a project inside its dev container. The IDE is `ide_id`, else the
+/// default IDE; either must be VS Code or built on it.
+#[tauri::command]
+pub async fn open_in_devcontainer(
+    path: String,
+    ide_id: Option<i64>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    let project = std::path::Path::new(&path);
+    let config = devcontainer::detect(project)
+        .ok_or_else(|| format!("No devcontainer.json found in {}", path))?;
+
+    let executable = match ide_or_default(&db, ide_id).await? {
+        Some(ide) if supports_dev_containers(&ide.executable) => ide.executable,
+        Some(ide) if ide_id.is_some() => {
+            return Err(format!("{} can't open dev containers", ide.name));
+        }
+        // VS Code on the PATH, when the default IDE can't
+        _ => "code".to_string(),
+    };
+    devcontainer::open(&executable, project, &config)
+}
+
+/// IDE `ide_id`, or the default IDE without one
+async fn ide_or_default(
+    db: &DatabaseManager,
+    ide_id: Option<i64>,
+) -> Result<Option<crate::entities::ide::Model>, String> {
+    let conn = db.get_connection();
+    match ide_id {
+        Some(id) => IdeRepository::get_by_id(conn, id as i32)
+            .await
+            .map_err(|e| format!("Failed to get IDE: {}", e)),
+        None => IdeRepository::get_default(conn)
+            .await
+            .map_err(|e| format!("Failed to get default IDE: {}", e)),
+    }
+}
+
+/// Editor extensions recommended for `frameworks` in the IDE `ide_id`, or
+/// the default IDE, and whether each is installed
+#[tauri::command]
+pub async fn get_recommended_extensions(
+    frameworks: Vec<String>,
+    ide_id: Option<i64>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<RecommendedExtension>, String> {
+    match ide_or_default(&db, ide_id).await? {
+        Some(ide) => extension_recommendations::recommendations(&ide.executable, &frameworks).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Install the recommended extensions that are missing, reporting progress
+/// as `ide-extension-install-progress` events
+#[tauri::command]
+pub async fn install_recommended_extensions(
+    app: tauri::AppHandle,
+    frameworks: Vec<String>,
+    ide_id: Option<i64>,
+    db: State<'_, Arc<DatabaseManager>>,
+) -> Result<ExtensionInstallReport, String> {
+    let ide = ide_or_default(&db, ide_id)
+        .await?
+        .ok_or_else(|| "No IDE configured; add one in settings".to_string())?;
+    extension_recommendations::install_missing(&app, &ide.executable, &frameworks).await
+}
+
+/// Rebuild a project's dev container
+#[tauri::command]
+pub async fn rebuild_devcontainer(path: String) -> Result<String, String> {
+    let project = std::path::Path::new(&path);
+    let config = devcontainer::detect(project)
+        .ok_or_else(|| format!("No devcontainer.json found in {}", path))?;
+    devcontainer::rebuild(project, &config).await
 }
 
 #[tauri::command]
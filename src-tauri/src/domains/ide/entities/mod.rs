@@ -12,6 +12,19 @@ pub struct IdeConfig {
     pub updated_at: Option<String>,
 }
 
+impl From<crate::entities::ide::Model> for IdeConfig {
+    fn from(ide: crate::entities::ide::Model) -> Self {
+        Self {
+            id: Some(ide.id as i64),
+            name: ide.name,
+            executable: ide.executable,
+            is_default: ide.is_default,
+            created_at: ide.created_at.map(|dt| dt.to_rfc3339()),
+            updated_at: ide.updated_at.map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrameworkIdeMapping {
     pub id: Option<i64>,
@@ -0,0 +1,263 @@
+//! Dev container detection and launching.
+//!
+//! A project with a `devcontainer.json` can be opened inside its container by
+//! VS Code and editors built on it. Rebuilding uses the `devcontainer` CLI when
+//! it's installed; otherwise the project's containers are removed through the
+//! deployments domain's Docker service, so the next open builds them afresh.
+
+use serde::{Deserialize, Serialize};
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+use tokio::process::Command;
+
+use crate::domains::deployments::services::docker_service::DockerService;
+use crate::process_ext::NoWindowExt;
+
+/// Label the dev container tooling puts on a project's containers
+const LOCAL_FOLDER_LABEL: &str = "devcontainer.local_folder";
+
+/// A project's dev container configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Devcontainer {
+    /// `devcontainer.json`, relative to the project
+    pub config_path: String,
+    pub name: Option<String>,
+    /// Where the project is mounted inside the container
+    pub workspace_folder: String,
+}
+
+/// Find the project's `devcontainer.json`: `.devcontainer/devcontainer.json`,
+/// then `.devcontainer.json`, then the first of `.devcontainer/*/`
+pub fn detect(project: &Path) -> Option<Devcontainer> {
+    let mut candidates = vec![
+        PathBuf::from(".devcontainer").join("devcontainer.json"),
+        PathBuf::from(".devcontainer.json"),
+    ];
+    if let Ok(entries) = std::fs::read_dir(project.join(".devcontainer")) {
+        let mut nested: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| {
+                PathBuf::from(".devcontainer")
+                    .join(entry.file_name())
+                    .join("devcontainer.json")
+            })
+            .collect();
+        nested.sort();
+        candidates.extend(nested);
+    }
+
+    let config_path = candidates
+        .into_iter()
+        .find(|candidate| project.join(candidate).is_file())?;
+    let config = std::fs::read_to_string(project.join(&config_path))
+        .ok()
+        .and_then(|contents| {
+            serde_json::from_str::<serde_json::Value>(&strip_jsonc(&contents)).ok()
+        })
+        .unwrap_or_default();
+    let folder_name = project
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Some(Devcontainer {
+        config_path: config_path.to_string_lossy().replace('\\', "/"),
+        name: config["name"].as_str().map(str::to_string),
+        workspace_folder: config["workspaceFolder"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("/workspaces/{}", folder_name)),
+    })
+}
+
+/// `devcontainer.json` allows comments and trailing commas; drop them so it
+/// parses as JSON
+fn strip_jsonc(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '/' if skip_comment(&mut chars) => {}
+            ',' => {
+                // Keep the comma unless it's the last in an object or array
+                if !matches!(next_significant(chars.clone()), Some('}' | ']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Skip the comment a `/` just read starts, if it starts one
+fn skip_comment(chars: &mut Peekable<Chars>) -> bool {
+    match chars.peek() {
+        Some('/') => while chars.next_if(|&next| next != '\n').is_some() {},
+        Some('*') => {
+            chars.next();
+            let mut previous = ' ';
+            for next in chars.by_ref() {
+                if previous == '*' && next == '/' {
+                    break;
+                }
+                previous = next;
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// The next character that isn't whitespace or inside a comment
+fn next_significant(mut chars: Peekable<Chars>) -> Option<char> {
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if skip_comment(&mut chars) => {}
+            c => return Some(c),
+        }
+    }
+    None
+}
+
+/// The `--folder-uri` that opens `project` inside its dev container
+pub fn folder_uri(project: &Path, devcontainer: &Devcontainer) -> String {
+    let host_path = project.to_string_lossy();
+    // The default config is implied; any other has to be named
+    let target = if devcontainer.config_path == ".devcontainer/devcontainer.json" {
+        host_path.into_owned()
+    } else {
+        serde_json::json!({
+            "hostPath": host_path,
+            "configFile": {
+                "$mid": 1,
+                "path": project.join(&devcontainer.config_path).to_string_lossy().replace('\\', "/"),
+                "scheme": "file",
+            },
+        })
+        .to_string()
+    };
+    format!(
+        "vscode-remote://dev-container+{}{}",
+        hex::encode(target),
+        devcontainer.workspace_folder
+    )
+}
+
+/// Open `project` inside its dev container with `executable`, a VS Code
+/// family editor
+pub fn open(executable: &str, project: &Path, devcontainer: &Devcontainer) -> Result<(), String> {
+    std::process::Command::new(executable)
+        .no_window()
+        .arg("--folder-uri")
+        .arg(folder_uri(project, devcontainer))
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", executable, e))?;
+    Ok(())
+}
+
+/// Rebuild the project's dev container, returning what was done
+pub async fn rebuild(project: &Path, devcontainer: &Devcontainer) -> Result<String, String> {
+    let output = Command::new("devcontainer")
+        .no_window()
+        .arg("up")
+        .arg("--workspace-folder")
+        .arg(project)
+        .arg("--config")
+        .arg(project.join(&devcontainer.config_path))
+        .arg("--remove-existing-container")
+        .kill_on_drop(true)
+        .output()
+        .await;
+    if let Ok(output) = output {
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+        }
+        return Err(format!(
+            "Dev container rebuild failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // No CLI; remove the containers instead
+    let docker = DockerService::new();
+    let host_path = project.to_string_lossy();
+    let containers: Vec<_> = docker
+        .list_containers()
+        .await?
+        .into_iter()
+        .filter(|container| {
+            container
+                .labels
+                .get(LOCAL_FOLDER_LABEL)
+                .is_some_and(|folder| *folder == host_path)
+        })
+        .collect();
+    for container in &containers {
+        docker.remove_container(&container.id).await?;
+    }
+    Ok(format!(
+        "Removed {} dev container(s); the container is rebuilt when the project is next opened in it",
+        containers.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jsonc_comments_and_trailing_commas_are_dropped() {
+        let source = r#"{
+            // The container
+            "name": "Portal // dev", /* inline */
+            "forwardPorts": [3000, 5173,],
+            "workspaceFolder": "/src",
+        }"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(source)).unwrap();
+        assert_eq!(value["name"], "Portal // dev");
+        assert_eq!(value["forwardPorts"], serde_json::json!([3000, 5173]));
+        assert_eq!(value["workspaceFolder"], "/src");
+    }
+
+    #[test]
+    fn detects_config_and_builds_folder_uri() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("app");
+        std::fs::create_dir_all(project.join(".devcontainer")).unwrap();
+        std::fs::write(
+            project.join(".devcontainer/devcontainer.json"),
+            "{ \"name\": \"App\", }",
+        )
+        .unwrap();
+
+        let devcontainer = detect(&project).unwrap();
+        assert_eq!(devcontainer.config_path, ".devcontainer/devcontainer.json");
+        assert_eq!(devcontainer.name.as_deref(), Some("App"));
+        assert_eq!(devcontainer.workspace_folder, "/workspaces/app");
+        assert_eq!(
+            folder_uri(&project, &devcontainer),
+            format!(
+                "vscode-remote://dev-container+{}/workspaces/app",
+                hex::encode(project.to_string_lossy().as_bytes())
+            )
+        );
+        assert!(detect(dir.path()).is_none());
+    }
+}
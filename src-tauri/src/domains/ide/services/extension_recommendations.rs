@@ -0,0 +1,349 @@
+//! Recommended editor extensions per framework.
+//!
+//! VS Code family editors list their extensions with `--list-extensions` and
+//! install with `--install-extension`. JetBrains IDEs keep plugins in
+//! per-product folders under the user's data directory and install with
+//! `installPlugins`, which only works while the IDE is closed.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+use tokio::process::Command;
+
+use super::ide_service::LaunchStyle;
+use crate::process_ext::NoWindowExt;
+
+pub const EXTENSION_INSTALL_PROGRESS_EVENT: &str = "ide-extension-install-progress";
+
+/// A JetBrains plugin, by marketplace id and the folder it installs into
+type JetBrainsPlugin = (&'static str, &'static str);
+
+const JS_TOOLING: &[&str] = &["dbaeumer.vscode-eslint", "esbenp.prettier-vscode"];
+const PRETTIER_PLUGIN: JetBrainsPlugin = ("intellij.prettierJS", "prettierJS");
+const PYTHON_PLUGIN: JetBrainsPlugin = ("PythonCore", "python-ce");
+const RUBY_PLUGIN: JetBrainsPlugin = ("org.jetbrains.plugins.ruby", "ruby");
+const PHP_PLUGIN: JetBrainsPlugin = ("com.jetbrains.php", "php");
+const RUST_PLUGIN: JetBrainsPlugin = ("com.jetbrains.rust", "rust");
+const ELIXIR_PLUGIN: JetBrainsPlugin = ("org.elixir_lang", "intellij-elixir");
+
+/// Framework, its VS Code extensions, and its JetBrains plugins
+const RECOMMENDATIONS: &[(&str, &[&str], &[JetBrainsPlugin])] = &[
+    (
+        "React",
+        &[
+            "dbaeumer.vscode-eslint",
+            "esbenp.prettier-vscode",
+            "dsznajder.es7-react-js-snippets",
+        ],
+        &[PRETTIER_PLUGIN],
+    ),
+    ("Vue", &["Vue.volar"], &[PRETTIER_PLUGIN]),
+    ("Nuxt", &["Vue.volar"], &[PRETTIER_PLUGIN]),
+    ("Angular", &["Angular.ng-template"], &[PRETTIER_PLUGIN]),
+    (
+        "Svelte",
+        &["svelte.svelte-vscode"],
+        &[("dev.blachut.svelte.lang", "Svelte")],
+    ),
+    ("Next.js", JS_TOOLING, &[PRETTIER_PLUGIN]),
+    ("Remix", JS_TOOLING, &[PRETTIER_PLUGIN]),
+    ("Gatsby", JS_TOOLING, &[PRETTIER_PLUGIN]),
+    ("Node.js", JS_TOOLING, &[PRETTIER_PLUGIN]),
+    ("Express", JS_TOOLING, &[PRETTIER_PLUGIN]),
+    ("Fastify", JS_TOOLING, &[PRETTIER_PLUGIN]),
+    ("NestJS", JS_TOOLING, &[PRETTIER_PLUGIN]),
+    ("Electron", JS_TOOLING, &[PRETTIER_PLUGIN]),
+    (
+        "Django",
+        &["ms-python.python", "batisteo.vscode-django"],
+        &[PYTHON_PLUGIN],
+    ),
+    ("Flask", &["ms-python.python"], &[PYTHON_PLUGIN]),
+    ("FastAPI", &["ms-python.python"], &[PYTHON_PLUGIN]),
+    ("Ruby", &["Shopify.ruby-lsp"], &[RUBY_PLUGIN]),
+    ("Rails", &["Shopify.ruby-lsp"], &[RUBY_PLUGIN]),
+    ("Sinatra", &["Shopify.ruby-lsp"], &[RUBY_PLUGIN]),
+    (
+        "PHP",
+        &["bmewburn.vscode-intelephense-client"],
+        &[PHP_PLUGIN],
+    ),
+    (
+        "Laravel",
+        &["bmewburn.vscode-intelephense-client"],
+        &[PHP_PLUGIN],
+    ),
+    (
+        "Symfony",
+        &["bmewburn.vscode-intelephense-client"],
+        &[PHP_PLUGIN],
+    ),
+    ("Java", &["vscjava.vscode-java-pack"], &[]),
+    (
+        "Spring",
+        &["vscjava.vscode-java-pack", "vmware.vscode-boot-dev-pack"],
+        &[],
+    ),
+    (
+        "Quarkus",
+        &["vscjava.vscode-java-pack", "redhat.vscode-quarkus"],
+        &[],
+    ),
+    (
+        "Tauri",
+        &["tauri-apps.tauri-vscode", "rust-lang.rust-analyzer"],
+        &[RUST_PLUGIN],
+    ),
+    ("Rust", &["rust-lang.rust-analyzer"], &[RUST_PLUGIN]),
+    ("Go", &["golang.go"], &[]),
+    (
+        "Flutter",
+        &["Dart-Code.flutter"],
+        &[("io.flutter", "flutter-intellij"), ("Dart", "Dart")],
+    ),
+    ("Elixir", &["elixir-lsp.elixir-ls"], &[ELIXIR_PLUGIN]),
+    ("Phoenix", &["elixir-lsp.elixir-ls"], &[ELIXIR_PLUGIN]),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecommendedExtension {
+    pub id: String,
+    /// Frameworks it's recommended for
+    pub frameworks: Vec<String>,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtensionInstallReport {
+    pub installed: Vec<String>,
+    /// Extension id and why it failed
+    pub failed: Vec<(String, String)>,
+}
+
+#[derive(Clone, Serialize)]
+struct ExtensionInstallProgress {
+    extension: String,
+    index: usize,
+    total: usize,
+    /// "installing", "installed" or "failed"
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Extensions for `frameworks` in the IDE at `executable`, without
+/// duplicates. Editors other than VS Code and JetBrains get none.
+fn recommended_for(executable: &str, frameworks: &[String]) -> Vec<(String, Vec<String>)> {
+    let style = LaunchStyle::of(executable);
+    let mut recommended: Vec<(String, Vec<String>)> = Vec::new();
+    for framework in frameworks {
+        let Some((name, vscode, jetbrains)) = RECOMMENDATIONS
+            .iter()
+            .find(|(name, _, _)| name.eq_ignore_ascii_case(framework))
+        else {
+            continue;
+        };
+        let ids: Vec<&str> = match style {
+            LaunchStyle::VsCode => vscode.to_vec(),
+            LaunchStyle::JetBrains => jetbrains.iter().map(|(id, _)| *id).collect(),
+            _ => Vec::new(),
+        };
+        for id in ids {
+            match recommended.iter_mut().find(|(existing, _)| existing == id) {
+                Some((_, for_frameworks)) => for_frameworks.push(name.to_string()),
+                None => recommended.push((id.to_string(), vec![name.to_string()])),
+            }
+        }
+    }
+    recommended
+}
+
+/// Lowercased ids of the extensions installed in the IDE at `executable`
+async fn installed_extensions(executable: &str) -> Result<HashSet<String>, String> {
+    match LaunchStyle::of(executable) {
+        LaunchStyle::VsCode => {
+            let output = Command::new(executable)
+                .no_window()
+                .arg("--list-extensions")
+                .output()
+                .await
+                .map_err(|e| format!("Failed to list extensions: {}", e))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "Failed to list extensions: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_lowercase())
+                .filter(|line| !line.is_empty())
+                .collect())
+        }
+        LaunchStyle::JetBrains => Ok(dirs::data_dir()
+            .map(|data| installed_jetbrains_plugins(&data.join("JetBrains")))
+            .unwrap_or_default()),
+        _ => Ok(HashSet::new()),
+    }
+}
+
+/// Ids of the known plugins found in any JetBrains product's plugin folder.
+/// Products keep plugins in `<product>/plugins`, or directly in `<product>`
+/// on Linux.
+fn installed_jetbrains_plugins(jetbrains_dir: &Path) -> HashSet<String> {
+    let mut folders = HashSet::new();
+    for product in std::fs::read_dir(jetbrains_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+    {
+        for dir in [product.path(), product.path().join("plugins")] {
+            for plugin in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+                folders.insert(plugin.file_name().to_string_lossy().to_lowercase());
+            }
+        }
+    }
+    RECOMMENDATIONS
+        .iter()
+        .flat_map(|(_, _, plugins)| plugins.iter())
+        .filter(|(_, folder)| folders.contains(&folder.to_lowercase()))
+        .map(|(id, _)| id.to_lowercase())
+        .collect()
+}
+
+/// Extensions recommended for `frameworks` in the IDE at `executable`, and
+/// whether each is installed
+pub async fn recommendations(
+    executable: &str,
+    frameworks: &[String],
+) -> Result<Vec<RecommendedExtension>, String> {
+    let recommended = recommended_for(executable, frameworks);
+    if recommended.is_empty() {
+        return Ok(Vec::new());
+    }
+    let installed = installed_extensions(executable).await?;
+    Ok(recommended
+        .into_iter()
+        .map(|(id, frameworks)| RecommendedExtension {
+            installed: installed.contains(&id.to_lowercase()),
+            id,
+            frameworks,
+        })
+        .collect())
+}
+
+/// Install the recommended extensions that are missing, one at a time,
+/// emitting `EXTENSION_INSTALL_PROGRESS_EVENT` for each
+pub async fn install_missing(
+    app: &AppHandle,
+    executable: &str,
+    frameworks: &[String],
+) -> Result<ExtensionInstallReport, String> {
+    let install_arg = match LaunchStyle::of(executable) {
+        LaunchStyle::VsCode => "--install-extension",
+        LaunchStyle::JetBrains => "installPlugins",
+        _ => return Err("Only VS Code and JetBrains IDEs can install extensions".to_string()),
+    };
+    let missing: Vec<String> = recommendations(executable, frameworks)
+        .await?
+        .into_iter()
+        .filter(|extension| !extension.installed)
+        .map(|extension| extension.id)
+        .collect();
+
+    let mut report = ExtensionInstallReport {
+        installed: Vec::new(),
+        failed: Vec::new(),
+    };
+    let total = missing.len();
+    for (index, extension) in missing.into_iter().enumerate() {
+        let progress = |status, error| ExtensionInstallProgress {
+            extension: extension.clone(),
+            index,
+            total,
+            status,
+            error,
+        };
+        let _ = app.emit(
+            EXTENSION_INSTALL_PROGRESS_EVENT,
+            progress("installing", None),
+        );
+        let result = Command::new(executable)
+            .no_window()
+            .arg(install_arg)
+            .arg(&extension)
+            .output()
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|output| {
+                if output.status.success() {
+                    Ok(())
+                } else {
+                    Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+                }
+            });
+        match result {
+            Ok(()) => {
+                let _ = app.emit(
+                    EXTENSION_INSTALL_PROGRESS_EVENT,
+                    progress("installed", None),
+                );
+                report.installed.push(extension);
+            }
+            Err(e) => {
+                let _ = app.emit(
+                    EXTENSION_INSTALL_PROGRESS_EVENT,
+                    progress("failed", Some(e.clone())),
+                );
+                report.failed.push((extension, e));
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recommendations_follow_the_ide_and_merge_duplicates() {
+        let frameworks = vec![
+            "tauri".to_string(),
+            "Rust".to_string(),
+            "Unknown".to_string(),
+        ];
+        assert_eq!(
+            recommended_for("/usr/bin/code", &frameworks),
+            vec![
+                (
+                    "tauri-apps.tauri-vscode".to_string(),
+                    vec!["Tauri".to_string()]
+                ),
+                (
+                    "rust-lang.rust-analyzer".to_string(),
+                    vec!["Tauri".to_string(), "Rust".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(
+            recommended_for("idea64.exe", &frameworks),
+            vec![(
+                "com.jetbrains.rust".to_string(),
+                vec!["Tauri".to_string(), "Rust".to_string()]
+            )]
+        );
+        assert!(recommended_for("vim", &frameworks).is_empty());
+    }
+
+    #[test]
+    fn jetbrains_plugins_are_found_by_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("IntelliJIdea2024.2/plugins/Dart")).unwrap();
+        std::fs::create_dir_all(dir.path().join("PyCharm2024.2/python-ce")).unwrap();
+        let installed = installed_jetbrains_plugins(dir.path());
+        assert!(installed.contains("dart") && installed.contains("pythoncore"));
+        assert!(!installed.contains("io.flutter"));
+    }
+}
@@ -1,315 +1,382 @@
 use crate::process_ext::NoWindowExt;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-pub struct IdeService;
+/// How an IDE's command line takes a file position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LaunchStyle {
+    /// `code <project> --goto <file>:<line>:<column>`
+    VsCode,
+    /// `idea <project> --line <line> --column <column> <file>`
+    JetBrains,
+    /// `subl <project> <file>:<line>:<column>`
+    PathWithPosition,
+    /// `vim +<line> <file>`
+    Vim,
+    /// `emacs +<line>:<column> <file>`
+    Emacs,
+    /// `<editor> <project> <file>`, without a position
+    Plain,
+}
 
-impl IdeService {
-    pub fn new() -> Self {
-        Self
+impl LaunchStyle {
+    pub(crate) fn of(executable: &str) -> Self {
+        let stem = Path::new(executable)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        // JetBrains launchers come as `idea64.exe` on Windows
+        match stem.trim_end_matches("64") {
+            "code" | "code-insiders" | "codium" | "cursor" | "windsurf" => Self::VsCode,
+            "idea" | "pycharm" | "webstorm" | "clion" | "goland" | "phpstorm" | "rubymine"
+            | "rider" | "rustrover" | "studio" => Self::JetBrains,
+            "subl" | "sublime_text" | "zed" | "zeditor" => Self::PathWithPosition,
+            "vim" | "nvim" | "gvim" => Self::Vim,
+            "emacs" | "emacsclient" => Self::Emacs,
+            _ => Self::Plain,
+        }
     }
+}
 
-    /// Detect installed IDEs on the system
-    pub fn detect_installed_ides(&self) -> Vec<String> {
-        let mut detected: Vec<String> = Vec::new();
+/// Whether `executable` is VS Code or an editor built on it, which can open
+/// dev containers
+pub fn supports_dev_containers(executable: &str) -> bool {
+    LaunchStyle::of(executable) == LaunchStyle::VsCode
+}
 
-        // Common IDE executables to check
-        let common_ides = if cfg!(windows) {
-            vec![
-                ("code", "Visual Studio Code"),
-                ("idea64.exe", "IntelliJ IDEA"),
-                ("idea.exe", "IntelliJ IDEA"),
-                ("studio64.exe", "Android Studio"),
-                ("studio.exe", "Android Studio"),
-                ("rider64.exe", "JetBrains Rider"),
-                ("rider.exe", "JetBrains Rider"),
-                ("pycharm64.exe", "PyCharm"),
-                ("pycharm.exe", "PyCharm"),
-                ("webstorm64.exe", "WebStorm"),
-                ("webstorm.exe", "WebStorm"),
-                ("clion64.exe", "CLion"),
-                ("clion.exe", "CLion"),
-                ("goland64.exe", "GoLand"),
-                ("goland.exe", "GoLand"),
-                ("phpstorm64.exe", "PhpStorm"),
-                ("phpstorm.exe", "PhpStorm"),
-                ("rubymine64.exe", "RubyMine"),
-                ("rubymine.exe", "RubyMine"),
-                ("sublime_text.exe", "Sublime Text"),
-                ("notepad++.exe", "Notepad++"),
-                ("devenv.exe", "Visual Studio"),
-            ]
-        } else {
-            vec![
-                ("code", "Visual Studio Code"),
-                ("idea", "IntelliJ IDEA"),
-                ("studio", "Android Studio"),
-                ("rider", "JetBrains Rider"),
-                ("pycharm", "PyCharm"),
-                ("webstorm", "WebStorm"),
-                ("clion", "CLion"),
-                ("goland", "GoLand"),
-                ("phpstorm", "PhpStorm"),
-                ("rubymine", "RubyMine"),
-                ("sublime_text", "Sublime Text"),
-                ("subl", "Sublime Text"),
-                ("vim", "Vim"),
-                ("nvim", "Neovim"),
-                ("emacs", "Emacs"),
-                ("atom", "Atom"),
-            ]
-        };
+/// Arguments that open `project` in `executable`, and `file` at `line` and
+/// `column` if given. A relative `file` is taken from the project root.
+pub fn open_args(
+    executable: &str,
+    project: &Path,
+    file: Option<&Path>,
+    line: Option<u32>,
+    column: Option<u32>,
+) -> Vec<String> {
+    let project_arg = project.to_string_lossy().into_owned();
+    let Some(file) = file else {
+        return vec![project_arg];
+    };
+    let file = project.join(file).to_string_lossy().into_owned();
+    // `file:line:column`, as far as the position is known
+    let position = match (line, column) {
+        (Some(line), Some(column)) => format!("{}:{}:{}", file, line, column),
+        (Some(line), None) => format!("{}:{}", file, line),
+        _ => file.clone(),
+    };
 
-        // Check PATH for executables
-        for (executable, _name) in &common_ides {
-            if self.is_executable_in_path(executable) {
-                if let Ok(full_path) = self.find_executable_path(executable) {
-                    detected.push(full_path);
+    match LaunchStyle::of(executable) {
+        LaunchStyle::VsCode => vec![project_arg, "--goto".to_string(), position],
+        LaunchStyle::JetBrains => {
+            let mut args = vec![project_arg];
+            if let Some(line) = line {
+                args.extend(["--line".to_string(), line.to_string()]);
+                if let Some(column) = column {
+                    args.extend(["--column".to_string(), column.to_string()]);
                 }
             }
+            args.push(file);
+            args
         }
+        LaunchStyle::PathWithPosition => vec![project_arg, position],
+        LaunchStyle::Vim => match line {
+            Some(line) => vec![format!("+{}", line), file],
+            None => vec![file],
+        },
+        LaunchStyle::Emacs => match (line, column) {
+            (Some(line), Some(column)) => vec![format!("+{}:{}", line, column), file],
+            (Some(line), None) => vec![format!("+{}", line), file],
+            _ => vec![file],
+        },
+        LaunchStyle::Plain => vec![project_arg, file],
+    }
+}
 
-        // Check common installation directories
-        if cfg!(windows) {
-            // Get user home directory for AppData paths
-            if let Ok(home_dir) = std::env::var("USERPROFILE") {
-                let home = PathBuf::from(&home_dir);
+/// An IDE found on the system
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DetectedIde {
+    pub name: String,
+    /// Launcher to store as the IDE's executable
+    pub executable: String,
+    pub version: Option<String>,
+}
 
-                // JetBrains Toolbox installation path (most common on Windows)
-                // Toolbox creates symlinks in AppData\Local\JetBrains\Toolbox\bin
-                let jetbrains_toolbox_bin = home.join(r"AppData\Local\JetBrains\Toolbox\bin");
-                if jetbrains_toolbox_bin.exists() {
-                    if let Ok(entries) = std::fs::read_dir(&jetbrains_toolbox_bin) {
-                        for entry in entries.flatten() {
-                            let exe_path = entry.path();
-                            if let Some(ext) = exe_path.extension() {
-                                if ext == "exe" {
-                                    if let Ok(canonical) = std::fs::canonicalize(&exe_path) {
-                                        if let Some(path_str) = canonical.to_str() {
-                                            if !detected.contains(&path_str.to_string()) {
-                                                detected.push(path_str.to_string());
-                                            }
-                                        }
-                                    } else if let Some(path_str) = exe_path.to_str() {
-                                        if !detected.contains(&path_str.to_string()) {
-                                            detected.push(path_str.to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+/// Launchers looked up on the PATH, with the IDE each starts. JetBrains
+/// Toolbox puts its launcher scripts on the PATH when shell scripts are
+/// enabled.
+const PATH_LAUNCHERS: &[(&str, &str)] = &[
+    ("code", "Visual Studio Code"),
+    ("code-insiders", "Visual Studio Code - Insiders"),
+    ("codium", "VSCodium"),
+    ("cursor", "Cursor"),
+    ("zed", "Zed"),
+    ("zeditor", "Zed"),
+    ("subl", "Sublime Text"),
+    ("sublime_text", "Sublime Text"),
+    ("idea", "IntelliJ IDEA"),
+    ("studio", "Android Studio"),
+    ("rider", "JetBrains Rider"),
+    ("pycharm", "PyCharm"),
+    ("webstorm", "WebStorm"),
+    ("clion", "CLion"),
+    ("goland", "GoLand"),
+    ("phpstorm", "PhpStorm"),
+    ("rubymine", "RubyMine"),
+    ("rustrover", "RustRover"),
+    ("vim", "Vim"),
+    ("nvim", "Neovim"),
+    ("emacs", "Emacs"),
+    ("notepad++", "Notepad++"),
+    ("devenv", "Visual Studio"),
+];
 
-                // JetBrains Toolbox apps directory - check actual IDE installations
-                let toolbox_apps = home.join(r"AppData\Local\JetBrains\Toolbox\apps");
-                if toolbox_apps.exists() {
-                    if let Ok(entries) = std::fs::read_dir(&toolbox_apps) {
-                        for entry in entries.flatten() {
-                            let ide_dir = entry.path();
-                            if ide_dir.is_dir() {
-                                // Each IDE has a "ch-0" or "ch-1" directory for versions
-                                if let Ok(version_dirs) = std::fs::read_dir(&ide_dir) {
-                                    for version_entry in version_dirs.flatten() {
-                                        let version_dir = version_entry.path();
-                                        if version_dir.is_dir() {
-                                            // Look for bin directory with executables
-                                            let bin_dir = version_dir.join("bin");
-                                            if bin_dir.exists() {
-                                                let launchers = vec![
-                                                    "idea64.exe",
-                                                    "idea.exe",
-                                                    "pycharm64.exe",
-                                                    "pycharm.exe",
-                                                    "webstorm64.exe",
-                                                    "webstorm.exe",
-                                                    "clion64.exe",
-                                                    "clion.exe",
-                                                    "goland64.exe",
-                                                    "goland.exe",
-                                                    "phpstorm64.exe",
-                                                    "phpstorm.exe",
-                                                    "rider64.exe",
-                                                    "rider.exe",
-                                                    "rubymine64.exe",
-                                                    "rubymine.exe",
-                                                    "studio64.exe",
-                                                    "studio.exe",
-                                                ];
-                                                for launcher in launchers {
-                                                    let exe_path = bin_dir.join(launcher);
-                                                    if exe_path.exists() {
-                                                        if let Ok(canonical) =
-                                                            std::fs::canonicalize(&exe_path)
-                                                        {
-                                                            if let Some(path_str) =
-                                                                canonical.to_str()
-                                                            {
-                                                                if !detected
-                                                                    .contains(&path_str.to_string())
-                                                                {
-                                                                    detected
-                                                                        .push(path_str.to_string());
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+/// Where editors install when they aren't on the PATH
+fn known_install_paths() -> Vec<(PathBuf, &'static str)> {
+    let mut paths = Vec::new();
+    if cfg!(windows) {
+        let mut roots: Vec<PathBuf> = dirs::data_local_dir()
+            .map(|local| local.join("Programs"))
+            .into_iter()
+            .collect();
+        for var in ["ProgramFiles", "ProgramFiles(x86)"] {
+            roots.extend(std::env::var_os(var).map(PathBuf::from));
+        }
+        for root in roots {
+            for (relative, name) in [
+                (r"Microsoft VS Code\Code.exe", "Visual Studio Code"),
+                (
+                    r"Microsoft VS Code Insiders\Code - Insiders.exe",
+                    "Visual Studio Code - Insiders",
+                ),
+                (r"VSCodium\VSCodium.exe", "VSCodium"),
+                (r"cursor\Cursor.exe", "Cursor"),
+                (r"Zed\Zed.exe", "Zed"),
+                (r"Sublime Text\sublime_text.exe", "Sublime Text"),
+                (r"Notepad++\notepad++.exe", "Notepad++"),
+            ] {
+                paths.push((root.join(relative), name));
+            }
+        }
+    } else if cfg!(target_os = "macos") {
+        let mut roots = vec![PathBuf::from("/Applications")];
+        roots.extend(dirs::home_dir().map(|home| home.join("Applications")));
+        for root in roots {
+            for (relative, name) in [
+                (
+                    "Visual Studio Code.app/Contents/Resources/app/bin/code",
+                    "Visual Studio Code",
+                ),
+                (
+                    "Visual Studio Code - Insiders.app/Contents/Resources/app/bin/code-insiders",
+                    "Visual Studio Code - Insiders",
+                ),
+                ("VSCodium.app/Contents/Resources/app/bin/codium", "VSCodium"),
+                ("Cursor.app/Contents/Resources/app/bin/cursor", "Cursor"),
+                ("Zed.app/Contents/MacOS/zed", "Zed"),
+                (
+                    "Sublime Text.app/Contents/SharedSupport/bin/subl",
+                    "Sublime Text",
+                ),
+            ] {
+                paths.push((root.join(relative), name));
+            }
+        }
+    } else {
+        for (path, name) in [
+            ("/usr/share/code/bin/code", "Visual Studio Code"),
+            (
+                "/usr/share/code-insiders/bin/code-insiders",
+                "Visual Studio Code - Insiders",
+            ),
+            ("/usr/share/codium/bin/codium", "VSCodium"),
+            ("/snap/bin/code", "Visual Studio Code"),
+            ("/snap/bin/code-insiders", "Visual Studio Code - Insiders"),
+            ("/snap/bin/codium", "VSCodium"),
+            ("/opt/sublime_text/sublime_text", "Sublime Text"),
+        ] {
+            paths.push((PathBuf::from(path), name));
+        }
+        paths.extend(dirs::home_dir().map(|home| (home.join(".local/bin/zed"), "Zed")));
+    }
+    paths
+}
 
-                // Check for individual JetBrains IDE installations (non-Toolbox)
-                let jetbrains_apps = home.join(r"AppData\Local\JetBrains");
-                if jetbrains_apps.exists() {
-                    if let Ok(entries) = std::fs::read_dir(&jetbrains_apps) {
-                        for entry in entries.flatten() {
-                            let app_dir = entry.path();
-                            // Skip Toolbox directory, we already checked it
-                            if app_dir.file_name().unwrap().to_string_lossy() == "Toolbox" {
-                                continue;
-                            }
-                            // Look for bin directories with .exe files
-                            let bin_dir = app_dir.join("bin");
-                            if bin_dir.exists() {
-                                if let Ok(bin_entries) = std::fs::read_dir(&bin_dir) {
-                                    for bin_entry in bin_entries.flatten() {
-                                        let exe_path = bin_entry.path();
-                                        if let Some(ext) = exe_path.extension() {
-                                            if ext == "exe" {
-                                                if let Ok(canonical) =
-                                                    std::fs::canonicalize(&exe_path)
-                                                {
-                                                    if let Some(path_str) = canonical.to_str() {
-                                                        if !detected.contains(&path_str.to_string())
-                                                        {
-                                                            detected.push(path_str.to_string());
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+/// Directories JetBrains IDEs install into, and how deep to look for them.
+/// Toolbox keeps each IDE under `apps/<product>/<channel>/<build>` (or
+/// `apps/<product>` since Toolbox 2), and on macOS the build is an app
+/// bundle.
+fn jetbrains_roots() -> Vec<(PathBuf, usize)> {
+    let mut roots: Vec<(PathBuf, usize)> = dirs::data_local_dir()
+        .map(|local| (local.join("JetBrains").join("Toolbox").join("apps"), 4))
+        .into_iter()
+        .collect();
+    if cfg!(windows) {
+        roots.extend(dirs::data_local_dir().map(|local| (local.join("Programs"), 1)));
+        for var in ["ProgramFiles", "ProgramFiles(x86)"] {
+            roots
+                .extend(std::env::var_os(var).map(|dir| (PathBuf::from(dir).join("JetBrains"), 1)));
+        }
+    } else if cfg!(target_os = "macos") {
+        roots.push((PathBuf::from("/Applications"), 1));
+        roots.extend(dirs::home_dir().map(|home| (home.join("Applications"), 1)));
+    } else {
+        roots.push((PathBuf::from("/opt"), 1));
+        roots.push((PathBuf::from("/snap"), 2));
+    }
+    roots
+}
 
-                // VS Code paths
-                let vs_code_paths = vec![
-                    home.join(r"AppData\Local\Programs\Microsoft VS Code\Code.exe"),
-                    PathBuf::from(r"C:\Program Files\Microsoft VS Code\Code.exe"),
-                    PathBuf::from(r"C:\Program Files (x86)\Microsoft VS Code\Code.exe"),
-                ];
-                for path in vs_code_paths {
-                    if path.exists() {
-                        if let Ok(canonical) = std::fs::canonicalize(&path) {
-                            if let Some(path_str) = canonical.to_str() {
-                                if !detected.contains(&path_str.to_string()) {
-                                    detected.push(path_str.to_string());
-                                }
-                            }
-                        }
-                    }
-                }
+/// Collect the JetBrains IDEs under `dir`, found by their
+/// `product-info.json`, looking `depth` levels down
+fn find_jetbrains_ides(dir: &Path, depth: usize, found: &mut Vec<DetectedIde>) {
+    for info in [
+        dir.join("product-info.json"),
+        dir.join("Contents")
+            .join("Resources")
+            .join("product-info.json"),
+    ] {
+        if let Some(ide) = read_product_info(&info) {
+            found.push(ide);
+            return;
+        }
+    }
+    if depth == 0 || dir.extension().is_some_and(|ext| ext == "app") {
+        return;
+    }
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        // Toolbox keeps plugins and old builds in hidden folders
+        if path.is_dir() && !entry.file_name().to_string_lossy().starts_with('.') {
+            find_jetbrains_ides(&path, depth - 1, found);
+        }
+    }
+}
 
-                // Check Program Files for JetBrains IDEs
-                let program_files_paths = vec![
-                    PathBuf::from(r"C:\Program Files\JetBrains"),
-                    PathBuf::from(r"C:\Program Files (x86)\JetBrains"),
-                ];
-                for program_files in program_files_paths {
-                    if program_files.exists() {
-                        if let Ok(entries) = std::fs::read_dir(&program_files) {
-                            for entry in entries.flatten() {
-                                let ide_dir = entry.path();
-                                let bin_dir = ide_dir.join("bin");
-                                if bin_dir.exists() {
-                                    // Look for launcher exes
-                                    let launchers = vec![
-                                        "idea64.exe",
-                                        "idea.exe",
-                                        "pycharm64.exe",
-                                        "pycharm.exe",
-                                        "webstorm64.exe",
-                                        "webstorm.exe",
-                                        "clion64.exe",
-                                        "clion.exe",
-                                        "goland64.exe",
-                                        "goland.exe",
-                                        "phpstorm64.exe",
-                                        "phpstorm.exe",
-                                        "rider64.exe",
-                                        "rider.exe",
-                                        "rubymine64.exe",
-                                        "rubymine.exe",
-                                    ];
-                                    for launcher in launchers {
-                                        let exe_path = bin_dir.join(launcher);
-                                        if exe_path.exists() {
-                                            if let Ok(canonical) = std::fs::canonicalize(&exe_path)
-                                            {
-                                                if let Some(path_str) = canonical.to_str() {
-                                                    if !detected.contains(&path_str.to_string()) {
-                                                        detected.push(path_str.to_string());
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+/// Name, version and this platform's launcher from a JetBrains
+/// `product-info.json`
+fn read_product_info(path: &Path) -> Option<DetectedIde> {
+    let info: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    let os = if cfg!(windows) {
+        "Windows"
+    } else if cfg!(target_os = "macos") {
+        "macOS"
+    } else {
+        "Linux"
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        other => other,
+    };
+    let launches: Vec<&serde_json::Value> = info["launch"]
+        .as_array()?
+        .iter()
+        .filter(|launch| {
+            launch["os"]
+                .as_str()
+                .is_some_and(|o| o.eq_ignore_ascii_case(os))
+        })
+        .collect();
+    let launch = launches
+        .iter()
+        .find(|launch| launch["arch"].as_str() == Some(arch))
+        .or(launches.first())?;
+    let executable = path.parent()?.join(launch["launcherPath"].as_str()?);
+    if !executable.exists() {
+        return None;
+    }
+    Some(DetectedIde {
+        name: info["name"].as_str()?.to_string(),
+        executable: executable.to_string_lossy().into_owned(),
+        version: info["version"].as_str().map(str::to_string),
+    })
+}
+
+/// Version of a VS Code family editor, from the `package.json` of the app
+/// its launcher belongs to
+fn vscode_version(launcher: &Path) -> Option<String> {
+    let launcher = std::fs::canonicalize(launcher).unwrap_or_else(|_| launcher.to_path_buf());
+    launcher.ancestors().skip(1).take(4).find_map(|dir| {
+        let manifest = if dir.file_name()? == "app" {
+            dir.join("package.json")
         } else {
-            let common_paths = vec![
-                PathBuf::from("/usr/bin/code"),
-                PathBuf::from("/usr/local/bin/code"),
-                PathBuf::from("/snap/bin/code"),
-                PathBuf::from("/opt/idea/bin/idea.sh"),
-                PathBuf::from("/opt/pycharm/bin/pycharm.sh"),
-                PathBuf::from(
-                    "/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
-                ),
-                PathBuf::from("/Applications/IntelliJ IDEA.app/Contents/MacOS/idea"),
-            ];
-            for path in common_paths {
-                if path.exists() {
-                    if let Ok(canonical) = std::fs::canonicalize(&path) {
-                        if let Some(path_str) = canonical.to_str() {
-                            if !detected.contains(&path_str.to_string()) {
-                                detected.push(path_str.to_string());
-                            }
-                        }
-                    }
-                }
+            dir.join("resources").join("app").join("package.json")
+        };
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(manifest).ok()?).ok()?;
+        manifest["version"].as_str().map(str::to_string)
+    })
+}
+
+pub struct IdeService;
+
+impl IdeService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Detect installed IDEs: launchers on the PATH, editors in their usual
+    /// install locations, and JetBrains IDEs by their product info, including
+    /// those Toolbox manages
+    pub fn detect_installed_ides(&self) -> Vec<DetectedIde> {
+        let mut detected = Vec::new();
+        for (launcher, name) in PATH_LAUNCHERS {
+            if let Ok(path) = self.find_executable_path(launcher) {
+                detected.push(DetectedIde {
+                    name: name.to_string(),
+                    executable: path,
+                    version: None,
+                });
+            }
+        }
+        for (path, name) in known_install_paths() {
+            if path.exists() {
+                detected.push(DetectedIde {
+                    name: name.to_string(),
+                    executable: path.to_string_lossy().into_owned(),
+                    version: None,
+                });
             }
         }
+        for (root, depth) in jetbrains_roots() {
+            find_jetbrains_ides(&root, depth, &mut detected);
+        }
 
+        // The same install can be reached through several symlinks
+        let mut seen = std::collections::HashSet::new();
+        detected.retain(|ide| {
+            let path = Path::new(&ide.executable);
+            seen.insert(std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()))
+        });
+        for ide in &mut detected {
+            if ide.version.is_none() && LaunchStyle::of(&ide.executable) == LaunchStyle::VsCode {
+                ide.version = vscode_version(Path::new(&ide.executable));
+            }
+        }
         detected
     }
 
-    fn is_executable_in_path(&self, executable: &str) -> bool {
-        if cfg!(windows) {
-            // On Windows, try "where" command
-            if let Ok(output) = Command::new("where").no_window().arg(executable).output() {
-                return output.status.success();
-            }
-        } else {
-            // On Unix, try "which" command
-            if let Ok(output) = Command::new("which").arg(executable).output() {
-                return output.status.success();
-            }
+    /// Open `project` in the IDE at `executable`, jumping to `file` at `line`
+    /// and `column` if given
+    pub fn open(
+        &self,
+        executable: &str,
+        project: &Path,
+        file: Option<&Path>,
+        line: Option<u32>,
+        column: Option<u32>,
+    ) -> Result<(), String> {
+        if !project.exists() {
+            return Err(format!("Path does not exist: {}", project.display()));
         }
-        false
+        Command::new(executable)
+            .no_window()
+            .args(open_args(executable, project, file, line, column))
+            .current_dir(project)
+            .spawn()
+            .map_err(|e| format!("Failed to launch {}: {}", executable, e))?;
+        Ok(())
     }
 
     fn find_executable_path(&self, executable: &str) -> Result<String, String> {
@@ -346,3 +413,86 @@ impl IdeService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toolbox_ides_are_found_by_product_info() {
+        let apps = tempfile::tempdir().unwrap();
+        let build = apps.path().join("IDEA-U/ch-0/241.14494.240");
+        let (os, launcher) = if cfg!(windows) {
+            ("Windows", "bin/idea64.exe")
+        } else if cfg!(target_os = "macos") {
+            ("macOS", "../MacOS/idea")
+        } else {
+            ("Linux", "bin/idea.sh")
+        };
+        std::fs::create_dir_all(build.join(launcher).parent().unwrap()).unwrap();
+        std::fs::write(build.join(launcher), "").unwrap();
+        std::fs::write(
+            build.join("product-info.json"),
+            serde_json::json!({
+                "name": "IntelliJ IDEA",
+                "version": "2024.1",
+                "launch": [{ "os": os, "launcherPath": launcher }],
+            })
+            .to_string(),
+        )
+        .unwrap();
+        std::fs::create_dir_all(apps.path().join("IDEA-U/ch-0/.241.14494.240.plugins")).unwrap();
+
+        let mut found = Vec::new();
+        find_jetbrains_ides(apps.path(), 4, &mut found);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "IntelliJ IDEA");
+        assert_eq!(found[0].version.as_deref(), Some("2024.1"));
+        assert!(found[0].executable.ends_with(launcher));
+    }
+
+    #[test]
+    fn vscode_version_comes_from_its_package_json() {
+        let install = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(install.path().join("bin")).unwrap();
+        std::fs::create_dir_all(install.path().join("resources/app")).unwrap();
+        std::fs::write(install.path().join("bin/code"), "").unwrap();
+        std::fs::write(
+            install.path().join("resources/app/package.json"),
+            r#"{ "name": "code-oss-dev", "version": "1.95.2" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vscode_version(&install.path().join("bin/code")).as_deref(),
+            Some("1.95.2")
+        );
+    }
+
+    #[test]
+    fn file_positions_follow_each_ide_convention() {
+        let project = Path::new("/work/app");
+        let file = Some(Path::new("src/main.rs"));
+        assert_eq!(
+            open_args("/usr/bin/code", project, file, Some(12), Some(4)),
+            vec!["/work/app", "--goto", "/work/app/src/main.rs:12:4"]
+        );
+        assert_eq!(
+            open_args("idea64.exe", project, file, Some(12), None),
+            vec!["/work/app", "--line", "12", "/work/app/src/main.rs"]
+        );
+        assert_eq!(
+            open_args(
+                "nvim",
+                project,
+                Some(Path::new("/tmp/x.rs")),
+                Some(3),
+                Some(1)
+            ),
+            vec!["+3", "/tmp/x.rs"]
+        );
+        assert_eq!(
+            open_args("code", project, None, Some(3), None),
+            vec!["/work/app"]
+        );
+    }
+}
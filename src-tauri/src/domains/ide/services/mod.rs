@@ -1 +1,3 @@
+pub mod devcontainer;
+pub mod extension_recommendations;
 pub mod ide_service;
@@ -0,0 +1,77 @@
+//! Kubernetes tools for the AI assistant
+use super::manager::KubernetesManager;
+use crate::domains::ai::tools::{ActionPreview, PreviewMethod, ToolRegistry, ToolSpec};
+use serde_json::{json, Value};
+
+pub fn register_ai_tools(registry: &ToolRegistry) {
+    registry.register(
+        ToolSpec {
+            name: "list_pods".to_string(),
+            description: "List pods in a namespace of the connected cluster, with status, \
+                          readiness and restart counts"
+                .to_string(),
+            domain: "kubernetes".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "namespace": { "type": "string", "description": "Defaults to \"default\"" }
+                }
+            }),
+        },
+        |_app, args: Value| async move {
+            let namespace = args.get("namespace").and_then(Value::as_str);
+            // The manager uses a static client, so a fresh instance is fine
+            let pods = KubernetesManager::new().list_pods(namespace).await?;
+            serde_json::to_value(pods).map_err(|e| e.to_string())
+        },
+    );
+
+    registry.register(
+        ToolSpec {
+            name: "delete_pod".to_string(),
+            description: "Delete a pod from the connected cluster; a pod run by a controller \
+                          is replaced by a new one"
+                .to_string(),
+            domain: "kubernetes".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "namespace": { "type": "string", "description": "Defaults to \"default\"" }
+                },
+                "required": ["name"]
+            }),
+        },
+        |_app, args: Value| async move {
+            let (namespace, name) = pod_target(&args)?;
+            KubernetesManager::new()
+                .delete_pod(&namespace, &name)
+                .await?;
+            Ok(json!({ "deleted": name, "namespace": namespace }))
+        },
+    );
+
+    registry.register_preview("delete_pod", |_app, args: Value| async move {
+        let (namespace, name) = pod_target(&args)?;
+        let details = KubernetesManager::new()
+            .dry_run_delete_pod(&namespace, &name)
+            .await?;
+        Ok(ActionPreview {
+            method: PreviewMethod::DryRun,
+            summary: format!("Delete pod {} in namespace {}", name, namespace),
+            details: Some(details),
+        })
+    });
+}
+
+fn pod_target(args: &Value) -> Result<(String, String), String> {
+    let name = args
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or("name is required")?;
+    let namespace = args
+        .get("namespace")
+        .and_then(Value::as_str)
+        .unwrap_or("default");
+    Ok((namespace.to_string(), name.to_string()))
+}
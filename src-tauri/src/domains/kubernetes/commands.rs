@@ -1,11 +1,16 @@
+use crate::database::DatabaseManager;
+use crate::domains::kubernetes::kubeconfig;
 use crate::domains::kubernetes::manager::KubernetesManager;
 use crate::domains::kubernetes::manager::{
     GenerateKubeconfigRequest, GenerateKubeconfigResult, KubeSetupDetectionResult,
 };
+use crate::domains::kubernetes::port_forwards::{self, SavedPortForward, SavedPortForwardInput};
 use crate::domains::kubernetes::types::{
     ConfigMapInfo, CronJobInfo, DaemonSetInfo, EventInfo, IngressInfo, JobInfo, ResourceMetrics,
     SecretInfo, StatefulSetInfo, *,
 };
+use std::path::Path;
+use std::sync::Arc;
 use tauri::State;
 use tokio::sync::Mutex;
 
@@ -27,6 +32,7 @@ pub async fn k8s_load_clusters(
 #[tauri::command]
 pub async fn k8s_connect_cluster(
     manager: State<'_, Mutex<KubernetesManager>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
     cluster_name: String,
 ) -> Result<(), String> {
     // Do the async connection work first
@@ -38,6 +44,21 @@ pub async fn k8s_connect_cluster(
     let mut mgr = manager.lock().await;
     mgr.current_cluster = cluster;
 
+    // Bring back the cluster's saved port forwards without holding up the connection
+    let db = db_manager.get_connection().clone();
+    tokio::spawn(async move {
+        let restore = match port_forwards::list_saved(&db, Some(&cluster_name)).await {
+            Ok(saved) => KubernetesManager::new().restore_port_forwards(&saved).await,
+            Err(e) => {
+                eprintln!("Failed to load saved port forwards: {}", e);
+                return;
+            }
+        };
+        for failure in restore.failed {
+            eprintln!("Failed to restore port forward {}", failure);
+        }
+    });
+
     Ok(())
 }
 
@@ -134,6 +155,48 @@ pub async fn k8s_list_cronjobs(
     mgr.list_cronjobs(namespace.as_deref()).await
 }
 
+#[tauri::command]
+pub async fn k8s_trigger_cronjob(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    name: String,
+) -> Result<JobInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.trigger_cronjob(&namespace, &name).await
+}
+
+#[tauri::command]
+pub async fn k8s_suspend_cronjob(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    name: String,
+) -> Result<CronJobInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.set_cronjob_suspended(&namespace, &name, true).await
+}
+
+#[tauri::command]
+pub async fn k8s_resume_cronjob(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    name: String,
+) -> Result<CronJobInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.set_cronjob_suspended(&namespace, &name, false).await
+}
+
+#[tauri::command]
+pub async fn k8s_delete_finished_jobs(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    label_selector: Option<String>,
+    status: Option<String>,
+) -> Result<JobCleanupResult, String> {
+    let mgr = KubernetesManager::new();
+    mgr.delete_finished_jobs(&namespace, label_selector.as_deref(), status.as_deref())
+        .await
+}
+
 #[tauri::command]
 pub async fn k8s_list_ingresses(
     _manager: State<'_, Mutex<KubernetesManager>>,
@@ -182,6 +245,55 @@ pub async fn k8s_apply_resource_yaml(
     mgr.apply_resource_yaml(&namespace, &yaml_content).await
 }
 
+#[tauri::command]
+pub async fn k8s_validate_resource_yaml(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    yaml_content: String,
+) -> Result<YamlValidation, String> {
+    let mgr = KubernetesManager::new();
+    mgr.validate_resource_yaml(&namespace, &yaml_content).await
+}
+
+#[tauri::command]
+pub async fn k8s_get_config_entries(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    kind: ConfigResourceKind,
+    namespace: String,
+    name: String,
+) -> Result<ConfigEntries, String> {
+    let mgr = KubernetesManager::new();
+    mgr.get_config_entries(kind, &namespace, &name).await
+}
+
+#[tauri::command]
+pub async fn k8s_set_config_entry(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    kind: ConfigResourceKind,
+    namespace: String,
+    name: String,
+    entry: ConfigEntry,
+    resource_version: Option<String>,
+) -> Result<ConfigEntries, String> {
+    let mgr = KubernetesManager::new();
+    mgr.set_config_entry(kind, &namespace, &name, entry, resource_version.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn k8s_remove_config_entry(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    kind: ConfigResourceKind,
+    namespace: String,
+    name: String,
+    key: String,
+    resource_version: Option<String>,
+) -> Result<ConfigEntries, String> {
+    let mgr = KubernetesManager::new();
+    mgr.remove_config_entry(kind, &namespace, &name, &key, resource_version.as_deref())
+        .await
+}
+
 #[tauri::command]
 pub async fn k8s_delete_configmap(
     _manager: State<'_, Mutex<KubernetesManager>>,
@@ -239,6 +351,142 @@ pub async fn k8s_list_namespaces(
     mgr.list_namespaces().await
 }
 
+#[tauri::command]
+pub async fn k8s_create_namespace(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    name: String,
+    labels: Option<std::collections::HashMap<String, String>>,
+) -> Result<NamespaceInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.create_namespace(&name, labels).await
+}
+
+#[tauri::command]
+pub async fn k8s_delete_namespace(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    name: String,
+) -> Result<(), String> {
+    let mgr = KubernetesManager::new();
+    mgr.delete_namespace(&name).await
+}
+
+#[tauri::command]
+pub async fn k8s_get_namespace_summary(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    name: String,
+) -> Result<NamespaceSummary, String> {
+    let mgr = KubernetesManager::new();
+    mgr.namespace_summary(&name).await
+}
+
+#[tauri::command]
+pub async fn k8s_list_persistent_volumes(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+) -> Result<Vec<PersistentVolumeInfo>, String> {
+    let mgr = KubernetesManager::new();
+    mgr.list_persistent_volumes().await
+}
+
+#[tauri::command]
+pub async fn k8s_get_persistent_volume(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    name: String,
+) -> Result<PersistentVolumeInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.get_persistent_volume(&name).await
+}
+
+#[tauri::command]
+pub async fn k8s_delete_persistent_volume(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    name: String,
+) -> Result<(), String> {
+    let mgr = KubernetesManager::new();
+    mgr.delete_persistent_volume(&name).await
+}
+
+#[tauri::command]
+pub async fn k8s_list_persistent_volume_claims(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: Option<String>,
+) -> Result<Vec<PersistentVolumeClaimInfo>, String> {
+    let mgr = KubernetesManager::new();
+    mgr.list_persistent_volume_claims(namespace.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn k8s_get_persistent_volume_claim(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    name: String,
+) -> Result<PersistentVolumeClaimInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.get_persistent_volume_claim(&namespace, &name).await
+}
+
+#[tauri::command]
+pub async fn k8s_delete_persistent_volume_claim(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    name: String,
+) -> Result<(), String> {
+    let mgr = KubernetesManager::new();
+    mgr.delete_persistent_volume_claim(&namespace, &name).await
+}
+
+/// Grow a claim to `size` (e.g. "20Gi") if its storage class allows it
+#[tauri::command]
+pub async fn k8s_expand_persistent_volume_claim(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    name: String,
+    size: String,
+) -> Result<PersistentVolumeClaimInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.expand_persistent_volume_claim(&namespace, &name, &size)
+        .await
+}
+
+#[tauri::command]
+pub async fn k8s_list_nodes(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+) -> Result<Vec<NodeInfo>, String> {
+    let mgr = KubernetesManager::new();
+    mgr.list_nodes().await
+}
+
+#[tauri::command]
+pub async fn k8s_cordon_node(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    node_name: String,
+) -> Result<NodeInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.cordon_node(&node_name).await
+}
+
+#[tauri::command]
+pub async fn k8s_uncordon_node(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    node_name: String,
+) -> Result<NodeInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.uncordon_node(&node_name).await
+}
+
+/// Cordon a node and evict its pods, emitting `k8s:drain-progress`
+#[tauri::command]
+pub async fn k8s_drain_node(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    window: tauri::Window,
+    node_name: String,
+    options: Option<DrainOptions>,
+) -> Result<DrainResult, String> {
+    let mgr = KubernetesManager::new();
+    mgr.drain_node(&node_name, options.unwrap_or_default(), window)
+        .await
+}
+
 #[tauri::command]
 pub async fn k8s_get_current_cluster(
     manager: State<'_, Mutex<KubernetesManager>>,
@@ -272,6 +520,40 @@ pub async fn k8s_generate_kubeconfig(
     mgr.generate_kubeconfig(request).await
 }
 
+#[tauri::command]
+pub async fn k8s_list_kube_contexts(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+) -> Result<Vec<KubeContextInfo>, String> {
+    kubeconfig::list_contexts()
+}
+
+/// Merge a kubeconfig file into the user's, renaming entries that collide.
+/// With `dry_run`, only report what would change.
+#[tauri::command]
+pub async fn k8s_import_kubeconfig(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    path: String,
+    dry_run: Option<bool>,
+) -> Result<KubeconfigImport, String> {
+    kubeconfig::import(Path::new(&path), dry_run.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn k8s_find_stale_contexts(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    check_reachability: Option<bool>,
+) -> Result<Vec<StaleContext>, String> {
+    kubeconfig::find_stale(check_reachability.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn k8s_remove_kube_contexts(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    names: Vec<String>,
+) -> Result<KubeconfigRemoval, String> {
+    kubeconfig::remove(&names)
+}
+
 #[tauri::command]
 pub async fn k8s_get_pod_yaml(
     _manager: State<'_, Mutex<KubernetesManager>>,
@@ -351,6 +633,26 @@ pub async fn k8s_exec_pod(
         .await
 }
 
+/// Add an ephemeral debug container to a pod and wait for it to start. The
+/// returned attach command opens it in a terminal.
+#[tauri::command]
+pub async fn k8s_debug_pod(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    pod_name: String,
+    image: Option<String>,
+    target_container: Option<String>,
+) -> Result<DebugContainerInfo, String> {
+    let mgr = KubernetesManager::new();
+    mgr.debug_pod(
+        &namespace,
+        &pod_name,
+        image.as_deref(),
+        target_container.as_deref(),
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn k8s_start_port_forward(
     _manager: State<'_, Mutex<KubernetesManager>>,
@@ -364,6 +666,32 @@ pub async fn k8s_start_port_forward(
         .await
 }
 
+/// Forward to a pod, deployment, service or other workload. Targets other
+/// than pods find another ready pod when theirs goes away, unless
+/// `auto_restore` is false.
+#[tauri::command]
+pub async fn k8s_start_resource_port_forward(
+    _manager: State<'_, Mutex<KubernetesManager>>,
+    namespace: String,
+    target_kind: String,
+    target_name: String,
+    local_port: u16,
+    remote_port: u16,
+    auto_restore: Option<bool>,
+) -> Result<PortForwardInfo, String> {
+    let auto_restore = auto_restore.unwrap_or(target_kind != "pod");
+    let mgr = KubernetesManager::new();
+    mgr.start_resource_port_forward(
+        &namespace,
+        &target_kind,
+        &target_name,
+        local_port,
+        remote_port,
+        auto_restore,
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn k8s_list_port_forwards(
     _manager: State<'_, Mutex<KubernetesManager>>,
@@ -381,6 +709,88 @@ pub async fn k8s_stop_port_forward(
     mgr.stop_port_forward(&id).await
 }
 
+/// Name saved port forwards are kept under for the connected cluster
+async fn current_cluster_name(manager: &Mutex<KubernetesManager>) -> String {
+    manager
+        .lock()
+        .await
+        .current_cluster
+        .as_ref()
+        .map(|cluster| cluster.name.clone())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+#[tauri::command]
+pub async fn k8s_list_saved_port_forwards(
+    manager: State<'_, Mutex<KubernetesManager>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<SavedPortForward>, String> {
+    let cluster = current_cluster_name(&manager).await;
+    port_forwards::list_saved(db_manager.get_connection(), Some(&cluster)).await
+}
+
+/// Save a port forward on the connected cluster. Pods are saved as the
+/// workload that owns them when the forward is restored automatically.
+#[tauri::command]
+pub async fn k8s_save_port_forward(
+    manager: State<'_, Mutex<KubernetesManager>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    mut input: SavedPortForwardInput,
+) -> Result<SavedPortForward, String> {
+    let cluster = current_cluster_name(&manager).await;
+    if input.auto_restore {
+        let mgr = KubernetesManager::new();
+        let (kind, name) = mgr
+            .resolve_forward_target(&input.namespace, &input.target_kind, &input.target_name)
+            .await?;
+        input.target_kind = kind;
+        input.target_name = name;
+    }
+    port_forwards::create_saved(db_manager.get_connection(), &cluster, input).await
+}
+
+#[tauri::command]
+pub async fn k8s_update_saved_port_forward(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: String,
+    input: SavedPortForwardInput,
+) -> Result<SavedPortForward, String> {
+    port_forwards::update_saved(db_manager.get_connection(), &id, input).await
+}
+
+/// Delete a saved port forward, stopping it if it's running
+#[tauri::command]
+pub async fn k8s_delete_saved_port_forward(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: String,
+) -> Result<(), String> {
+    port_forwards::delete_saved(db_manager.get_connection(), &id).await?;
+    KubernetesManager::new().stop_saved_port_forward(&id).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn k8s_start_saved_port_forward(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: String,
+) -> Result<PortForwardInfo, String> {
+    let saved = port_forwards::get_saved(db_manager.get_connection(), &id).await?;
+    let mgr = KubernetesManager::new();
+    mgr.start_saved_port_forward(&saved).await
+}
+
+/// Start the connected cluster's auto-restore forwards that aren't running
+#[tauri::command]
+pub async fn k8s_restore_port_forwards(
+    manager: State<'_, Mutex<KubernetesManager>>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<PortForwardRestore, String> {
+    let cluster = current_cluster_name(&manager).await;
+    let saved = port_forwards::list_saved(db_manager.get_connection(), Some(&cluster)).await?;
+    let mgr = KubernetesManager::new();
+    Ok(mgr.restore_port_forwards(&saved).await)
+}
+
 #[tauri::command]
 pub async fn k8s_stop_all_watches(
     _manager: State<'_, Mutex<KubernetesManager>>,
@@ -0,0 +1,199 @@
+//! Key-by-key editing of ConfigMaps and Secrets.
+//!
+//! Values are text or binary. Text lives in a ConfigMap's `data`, binary in
+//! its `binaryData`; a Secret holds both as bytes, which are shown as text
+//! when they're valid UTF-8. Binary values travel base64 encoded, so the
+//! frontend never deals with a Secret's encoding itself.
+
+use crate::domains::kubernetes::types::{ConfigEntry, ConfigValueKind};
+use base64::{engine::general_purpose, Engine as _};
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use k8s_openapi::ByteString;
+
+/// Check `key` is a valid ConfigMap or Secret key
+pub fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > 253 {
+        return Err("Keys must be between 1 and 253 characters".to_string());
+    }
+    if key == "." || key == ".." {
+        return Err(format!("'{}' is not a valid key", key));
+    }
+    if let Some(c) = key
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+    {
+        return Err(format!(
+            "Invalid character {:?} in key '{}': keys may only contain letters, digits, '-', '_' and '.'",
+            c, key
+        ));
+    }
+    Ok(())
+}
+
+fn value_bytes(entry: &ConfigEntry) -> Result<Vec<u8>, String> {
+    match entry.kind {
+        ConfigValueKind::Text => Ok(entry.value.as_bytes().to_vec()),
+        ConfigValueKind::Binary => general_purpose::STANDARD
+            .decode(entry.value.trim())
+            .map_err(|e| format!("Value of '{}' is not valid base64: {}", entry.key, e)),
+    }
+}
+
+pub fn configmap_entries(configmap: &ConfigMap) -> Vec<ConfigEntry> {
+    let text = configmap
+        .data
+        .iter()
+        .flatten()
+        .map(|(key, value)| ConfigEntry {
+            key: key.clone(),
+            kind: ConfigValueKind::Text,
+            value: value.clone(),
+        });
+    let binary = configmap
+        .binary_data
+        .iter()
+        .flatten()
+        .map(|(key, value)| ConfigEntry {
+            key: key.clone(),
+            kind: ConfigValueKind::Binary,
+            value: general_purpose::STANDARD.encode(&value.0),
+        });
+    let mut entries: Vec<ConfigEntry> = text.chain(binary).collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    entries
+}
+
+pub fn secret_entries(secret: &Secret) -> Vec<ConfigEntry> {
+    secret
+        .data
+        .iter()
+        .flatten()
+        .map(|(key, value)| match std::str::from_utf8(&value.0) {
+            Ok(text) => ConfigEntry {
+                key: key.clone(),
+                kind: ConfigValueKind::Text,
+                value: text.to_string(),
+            },
+            Err(_) => ConfigEntry {
+                key: key.clone(),
+                kind: ConfigValueKind::Binary,
+                value: general_purpose::STANDARD.encode(&value.0),
+            },
+        })
+        .collect()
+}
+
+/// Add or replace a key, moving it between `data` and `binaryData` when its
+/// kind changes
+pub fn set_configmap_entry(configmap: &mut ConfigMap, entry: &ConfigEntry) -> Result<(), String> {
+    validate_key(&entry.key)?;
+    let bytes = value_bytes(entry)?;
+    let data = configmap.data.get_or_insert_with(Default::default);
+    let binary_data = configmap.binary_data.get_or_insert_with(Default::default);
+    match entry.kind {
+        ConfigValueKind::Text => {
+            binary_data.remove(&entry.key);
+            data.insert(entry.key.clone(), entry.value.clone());
+        }
+        ConfigValueKind::Binary => {
+            data.remove(&entry.key);
+            binary_data.insert(entry.key.clone(), ByteString(bytes));
+        }
+    }
+    Ok(())
+}
+
+pub fn set_secret_entry(secret: &mut Secret, entry: &ConfigEntry) -> Result<(), String> {
+    validate_key(&entry.key)?;
+    let bytes = value_bytes(entry)?;
+    secret
+        .data
+        .get_or_insert_with(Default::default)
+        .insert(entry.key.clone(), ByteString(bytes));
+    Ok(())
+}
+
+pub fn remove_configmap_entry(configmap: &mut ConfigMap, key: &str) -> Result<(), String> {
+    let text = configmap.data.as_mut().and_then(|data| data.remove(key));
+    let binary = configmap
+        .binary_data
+        .as_mut()
+        .and_then(|data| data.remove(key));
+    if text.is_none() && binary.is_none() {
+        return Err(format!("Key '{}' not found", key));
+    }
+    Ok(())
+}
+
+pub fn remove_secret_entry(secret: &mut Secret, key: &str) -> Result<(), String> {
+    secret
+        .data
+        .as_mut()
+        .and_then(|data| data.remove(key))
+        .map(|_| ())
+        .ok_or_else(|| format!("Key '{}' not found", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, kind: ConfigValueKind, value: &str) -> ConfigEntry {
+        ConfigEntry {
+            key: key.to_string(),
+            kind,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn validates_keys() {
+        assert!(validate_key("app.properties").is_ok());
+        assert!(validate_key("DB_HOST-1").is_ok());
+        assert!(validate_key("").is_err());
+        assert!(validate_key("..").is_err());
+        assert!(validate_key("config/app").is_err());
+        assert!(validate_key(&"a".repeat(254)).is_err());
+    }
+
+    #[test]
+    fn configmap_keys_move_between_text_and_binary() {
+        let mut configmap = ConfigMap::default();
+        set_configmap_entry(&mut configmap, &entry("logo", ConfigValueKind::Text, "x")).unwrap();
+        set_configmap_entry(
+            &mut configmap,
+            &entry("logo", ConfigValueKind::Binary, "AAE="),
+        )
+        .unwrap();
+        assert_eq!(
+            configmap_entries(&configmap),
+            vec![entry("logo", ConfigValueKind::Binary, "AAE=")]
+        );
+        assert!(
+            set_configmap_entry(&mut configmap, &entry("x", ConfigValueKind::Binary, "!")).is_err()
+        );
+
+        remove_configmap_entry(&mut configmap, "logo").unwrap();
+        assert!(configmap_entries(&configmap).is_empty());
+        assert!(remove_configmap_entry(&mut configmap, "logo").is_err());
+    }
+
+    #[test]
+    fn secret_values_are_decoded_when_text() {
+        let mut secret = Secret::default();
+        set_secret_entry(
+            &mut secret,
+            &entry("password", ConfigValueKind::Text, "hunter2"),
+        )
+        .unwrap();
+        set_secret_entry(&mut secret, &entry("key", ConfigValueKind::Binary, "/w==")).unwrap();
+        assert_eq!(secret.data.as_ref().unwrap()["password"].0, b"hunter2");
+        assert_eq!(
+            secret_entries(&secret),
+            vec![
+                entry("key", ConfigValueKind::Binary, "/w=="),
+                entry("password", ConfigValueKind::Text, "hunter2"),
+            ]
+        );
+    }
+}
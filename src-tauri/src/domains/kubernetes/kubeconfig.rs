@@ -0,0 +1,634 @@
+//! Importing kubeconfig files into the user's kubeconfig, and removing
+//! contexts from it.
+//!
+//! Kubeconfigs are handled as plain YAML so that rewriting the user's file
+//! keeps every field, including ones the kube client doesn't model. An
+//! imported cluster, user or context whose name is taken by a different
+//! entry is renamed (`name-2`, `name-3`, ...) rather than overwriting it.
+//! Every write first copies the old file aside and then replaces it
+//! atomically.
+
+use chrono::Utc;
+use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::{Client, Config};
+use serde_yaml::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::domains::kubernetes::manager::KubernetesManager;
+use crate::domains::kubernetes::types::{
+    KubeContextInfo, KubeconfigImport, KubeconfigRemoval, KubeconfigRename, StaleContext,
+};
+
+const SECTIONS: [&str; 3] = ["clusters", "users", "contexts"];
+
+/// How long a context's cluster gets to answer when checking it's reachable
+const REACHABILITY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+fn section<'a>(config: &'a Value, section: &str) -> &'a [Value] {
+    config
+        .get(section)
+        .and_then(Value::as_sequence)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn section_mut<'a>(config: &'a mut Value, section: &str) -> &'a mut Vec<Value> {
+    if !matches!(config.get(section), Some(Value::Sequence(_))) {
+        if let Some(config) = config.as_mapping_mut() {
+            config.insert(section.into(), Value::Sequence(Vec::new()));
+        }
+    }
+    match config.get_mut(section) {
+        Some(Value::Sequence(entries)) => entries,
+        _ => unreachable!("parsed kubeconfigs are mappings"),
+    }
+}
+
+fn name_of(entry: &Value) -> &str {
+    entry
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+fn set_name(entry: &mut Value, name: &str) {
+    if let Some(entry) = entry.as_mapping_mut() {
+        entry.insert("name".into(), name.into());
+    }
+}
+
+fn find<'a>(config: &'a Value, name_in: &str, name: &str) -> Option<&'a Value> {
+    section(config, name_in)
+        .iter()
+        .find(|entry| name_of(entry) == name)
+}
+
+fn names(config: &Value, name_in: &str) -> HashSet<String> {
+    section(config, name_in)
+        .iter()
+        .map(|entry| name_of(entry).to_string())
+        .collect()
+}
+
+/// The cluster or user a context refers to
+fn context_ref<'a>(context: &'a Value, field: &str) -> Option<&'a str> {
+    context.get("context")?.get(field)?.as_str()
+}
+
+fn current_context(config: &Value) -> Option<&str> {
+    config
+        .get("current-context")
+        .and_then(Value::as_str)
+        .filter(|name| !name.is_empty())
+}
+
+/// `name`, or the first of `name-2`, `name-3`, ... that isn't taken
+fn unique_name(taken: &HashSet<String>, name: &str) -> String {
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("some suffix is free")
+}
+
+/// Parse a kubeconfig, checking it has the shape of one
+pub fn parse(text: &str) -> Result<Value, String> {
+    let config: Value = if text.trim().is_empty() {
+        Value::Mapping(Mapping::new())
+    } else {
+        serde_yaml::from_str(text).map_err(|e| format!("Not a valid kubeconfig: {}", e))?
+    };
+    if !config.is_mapping() {
+        return Err("Not a valid kubeconfig: expected a mapping".to_string());
+    }
+    for name_in in SECTIONS {
+        if !matches!(
+            config.get(name_in),
+            None | Some(Value::Null) | Some(Value::Sequence(_))
+        ) {
+            return Err(format!(
+                "Not a valid kubeconfig: '{}' is not a list",
+                name_in
+            ));
+        }
+        if section(&config, name_in)
+            .iter()
+            .any(|entry| name_of(entry).is_empty())
+        {
+            return Err(format!(
+                "Not a valid kubeconfig: an entry in '{}' has no name",
+                name_in
+            ));
+        }
+    }
+    Ok(config)
+}
+
+pub fn contexts(config: &Value) -> Vec<KubeContextInfo> {
+    let current = current_context(config);
+    section(config, "contexts")
+        .iter()
+        .map(|context| {
+            let cluster = context_ref(context, "cluster");
+            KubeContextInfo {
+                name: name_of(context).to_string(),
+                cluster: cluster.map(str::to_string),
+                user: context_ref(context, "user").map(str::to_string),
+                namespace: context_ref(context, "namespace").map(str::to_string),
+                server: cluster
+                    .and_then(|cluster| find(config, "clusters", cluster))
+                    .and_then(|cluster| cluster.get("cluster")?.get("server")?.as_str())
+                    .map(str::to_string),
+                current: current == Some(name_of(context)),
+            }
+        })
+        .collect()
+}
+
+/// Merge `incoming` into `target`, returning the contexts added and what had
+/// to be renamed. Entries identical to ones already there are reused.
+pub fn merge(target: &mut Value, incoming: &Value) -> (Vec<String>, Vec<KubeconfigRename>) {
+    if let Some(config) = target.as_mapping_mut() {
+        for (key, value) in [("apiVersion", "v1"), ("kind", "Config")] {
+            if !config.contains_key(key) {
+                config.insert(key.into(), value.into());
+            }
+        }
+    }
+
+    let mut added = Vec::new();
+    let mut renamed = Vec::new();
+    // New name of each incoming entry, by kind
+    let mut merged: HashMap<&str, HashMap<String, String>> = HashMap::new();
+    for (name_in, field) in [
+        ("clusters", "cluster"),
+        ("users", "user"),
+        ("contexts", "context"),
+    ] {
+        for entry in section(incoming, name_in) {
+            let mut entry = entry.clone();
+            let name = name_of(&entry).to_string();
+            if field == "context" {
+                // Point the context at its cluster and user as merged
+                if let Some(context) = entry.get_mut("context").and_then(Value::as_mapping_mut) {
+                    for key in ["cluster", "user"] {
+                        let merged_as = context
+                            .get(key)
+                            .and_then(Value::as_str)
+                            .and_then(|name| merged.get(key)?.get(name));
+                        if let Some(to) = merged_as.cloned() {
+                            context.insert(key.into(), to.into());
+                        }
+                    }
+                }
+            }
+            let to = match imported_as(target, name_in, field, &name, &entry) {
+                Some(existing) => existing,
+                None => {
+                    let to = unique_name(&names(target, name_in), &name);
+                    if to != name {
+                        set_name(&mut entry, &to);
+                        renamed.push(KubeconfigRename {
+                            kind: field.to_string(),
+                            from: name.clone(),
+                            to: to.clone(),
+                        });
+                    }
+                    section_mut(target, name_in).push(entry);
+                    if field == "context" {
+                        added.push(to.clone());
+                    }
+                    to
+                }
+            };
+            merged.entry(field).or_default().insert(name, to);
+        }
+    }
+
+    if current_context(target).is_none() {
+        let current = current_context(incoming)
+            .and_then(|name| merged.get("context")?.get(name))
+            .cloned();
+        if let (Some(current), Some(config)) = (current, target.as_mapping_mut()) {
+            config.insert("current-context".into(), current.into());
+        }
+    }
+    (added, renamed)
+}
+
+/// Name of an entry identical to `entry` that's already in `config` as
+/// `name`, or as a renamed copy of it from an earlier import
+fn imported_as(
+    config: &Value,
+    name_in: &str,
+    field: &str,
+    name: &str,
+    entry: &Value,
+) -> Option<String> {
+    section(config, name_in)
+        .iter()
+        .filter(|existing| {
+            let existing = name_of(existing);
+            existing == name
+                || existing
+                    .strip_prefix(name)
+                    .and_then(|rest| rest.strip_prefix('-'))
+                    .is_some_and(|n| n.parse::<u32>().is_ok())
+        })
+        .find(|existing| existing.get(field) == entry.get(field))
+        .map(|existing| name_of(existing).to_string())
+}
+
+/// Contexts whose cluster or user is missing from the kubeconfig
+pub fn dangling_contexts(config: &Value) -> Vec<StaleContext> {
+    section(config, "contexts")
+        .iter()
+        .filter_map(|context| {
+            let reason = match context_ref(context, "cluster") {
+                None => "The context has no cluster".to_string(),
+                Some(cluster) if find(config, "clusters", cluster).is_none() => {
+                    format!("Cluster {} is missing from the kubeconfig", cluster)
+                }
+                Some(_) => match context_ref(context, "user") {
+                    Some(user) if find(config, "users", user).is_none() => {
+                        format!("User {} is missing from the kubeconfig", user)
+                    }
+                    _ => return None,
+                },
+            };
+            Some(StaleContext {
+                name: name_of(context).to_string(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Remove the named contexts, and the clusters and users only they used.
+/// Returns the contexts removed.
+pub fn remove_contexts(config: &mut Value, names: &[String]) -> Vec<String> {
+    let mut removed = Vec::new();
+    let mut released: Vec<(&str, String)> = Vec::new();
+    section_mut(config, "contexts").retain(|context| {
+        if !names.iter().any(|name| name == name_of(context)) {
+            return true;
+        }
+        removed.push(name_of(context).to_string());
+        for (name_in, field) in [("clusters", "cluster"), ("users", "user")] {
+            if let Some(name) = context_ref(context, field) {
+                released.push((name_in, name.to_string()));
+            }
+        }
+        false
+    });
+
+    for (name_in, field) in [("clusters", "cluster"), ("users", "user")] {
+        let in_use: HashSet<String> = section(config, "contexts")
+            .iter()
+            .filter_map(|context| context_ref(context, field))
+            .map(str::to_string)
+            .collect();
+        let unused: HashSet<&String> = released
+            .iter()
+            .filter(|(section, name)| *section == name_in && !in_use.contains(name))
+            .map(|(_, name)| name)
+            .collect();
+        section_mut(config, name_in).retain(|entry| !unused.contains(&name_of(entry).to_string()));
+    }
+
+    if current_context(config).is_some_and(|current| removed.iter().any(|name| name == current)) {
+        let next = section(config, "contexts")
+            .first()
+            .map(|context| name_of(context).to_string());
+        if let Some(config) = config.as_mapping_mut() {
+            match next {
+                Some(next) => config.insert("current-context".into(), next.into()),
+                None => config.remove("current-context"),
+            };
+        }
+    }
+    removed
+}
+
+/// Why a context's cluster can't be used right now, if it can't
+pub async fn unreachable_reason(config: &Value, context: &str) -> Option<String> {
+    let kubeconfig: Kubeconfig = match serde_yaml::from_value(config.clone()) {
+        Ok(kubeconfig) => kubeconfig,
+        Err(e) => return Some(format!("Can't read the kubeconfig: {}", e)),
+    };
+    let options = KubeConfigOptions {
+        context: Some(context.to_string()),
+        ..Default::default()
+    };
+    let client = match Config::from_custom_kubeconfig(kubeconfig, &options).await {
+        Ok(config) => match Client::try_from(config) {
+            Ok(client) => client,
+            Err(e) => return Some(format!("Can't create a client: {}", e)),
+        },
+        Err(e) => return Some(format!("Can't load the context: {}", e)),
+    };
+    match tokio::time::timeout(REACHABILITY_TIMEOUT, client.apiserver_version()).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(format!("Cluster unreachable: {}", e)),
+        Err(_) => Some("Cluster didn't respond".to_string()),
+    }
+}
+
+/// Kubeconfig that imports go into: the one in use, or where kubectl looks
+/// for one when there's none yet
+pub fn target_path() -> Result<PathBuf, String> {
+    if let Some(path) = KubernetesManager::kubeconfig_path() {
+        return Ok(path);
+    }
+    std::env::var_os("KUBECONFIG")
+        .and_then(|paths| std::env::split_paths(&paths).next())
+        .filter(|path| !path.as_os_str().is_empty())
+        .or_else(|| dirs::home_dir().map(|home| home.join(".kube").join("config")))
+        .ok_or_else(|| "Couldn't find where to keep the kubeconfig".to_string())
+}
+
+pub fn read_file(path: &Path) -> Result<Value, String> {
+    if !path.exists() {
+        return parse("");
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse(&text)
+}
+
+/// Write `config` to `path`, copying any existing file aside first.
+/// Returns the copy's path.
+pub fn write_file(path: &Path, config: &Value) -> Result<Option<PathBuf>, String> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "config".to_string());
+    let backup = if path.exists() {
+        let backup = path.with_file_name(format!(
+            "{}.backup-{}",
+            file_name,
+            Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        std::fs::copy(path, &backup)
+            .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_yaml::to_string(config)
+        .map_err(|e| format!("Failed to serialize kubeconfig: {}", e))?;
+    let temp = path.with_file_name(format!("{}.tmp", file_name));
+    // Left over from a write that didn't finish
+    let _ = std::fs::remove_file(&temp);
+    // Created owner-only, since it holds cluster tokens and client keys
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&temp)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    }
+    #[cfg(not(unix))]
+    std::fs::write(&temp, text)
+        .map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    std::fs::rename(&temp, path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+    Ok(backup)
+}
+
+pub fn list_contexts() -> Result<Vec<KubeContextInfo>, String> {
+    Ok(contexts(&read_file(&target_path()?)?))
+}
+
+/// Merge the kubeconfig at `source` into the user's. With `dry_run`, only
+/// report what would change.
+pub fn import(source: &Path, dry_run: bool) -> Result<KubeconfigImport, String> {
+    let path = target_path()?;
+    if path.exists() && source.canonicalize().ok() == path.canonicalize().ok() {
+        return Err("That kubeconfig is the one already in use".to_string());
+    }
+    let text = std::fs::read_to_string(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let incoming = parse(&text)?;
+    if section(&incoming, "contexts").is_empty() {
+        return Err(format!("{} has no contexts to import", source.display()));
+    }
+
+    let mut config = read_file(&path)?;
+    let (added, renamed) = merge(&mut config, &incoming);
+    let backup = if dry_run || added.is_empty() {
+        None
+    } else {
+        write_file(&path, &config)?
+    };
+    Ok(KubeconfigImport {
+        path: path.display().to_string(),
+        added,
+        renamed,
+        backup: backup.map(|backup| backup.display().to_string()),
+        dry_run,
+    })
+}
+
+/// Contexts that can't be used: ones whose cluster or user is missing and,
+/// with `check_reachability`, ones whose cluster doesn't answer
+pub async fn find_stale(check_reachability: bool) -> Result<Vec<StaleContext>, String> {
+    let config = read_file(&target_path()?)?;
+    let mut stale = dangling_contexts(&config);
+    if check_reachability {
+        let checks = contexts(&config)
+            .into_iter()
+            .filter(|context| stale.iter().all(|found| found.name != context.name))
+            .map(|context| {
+                let config = &config;
+                async move {
+                    unreachable_reason(config, &context.name)
+                        .await
+                        .map(|reason| StaleContext {
+                            name: context.name,
+                            reason,
+                        })
+                }
+            });
+        stale.extend(
+            futures_util::future::join_all(checks)
+                .await
+                .into_iter()
+                .flatten(),
+        );
+    }
+    Ok(stale)
+}
+
+pub fn remove(names: &[String]) -> Result<KubeconfigRemoval, String> {
+    let path = target_path()?;
+    let mut config = read_file(&path)?;
+    let removed = remove_contexts(&mut config, names);
+    let backup = if removed.is_empty() {
+        None
+    } else {
+        write_file(&path, &config)?
+    };
+    Ok(KubeconfigRemoval {
+        removed,
+        backup: backup.map(|backup| backup.display().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXISTING: &str = "
+apiVersion: v1
+kind: Config
+current-context: prod
+clusters:
+- name: prod
+  cluster:
+    server: https://prod.example.com
+- name: shared
+  cluster:
+    server: https://shared.example.com
+users:
+- name: admin
+  user:
+    token: old
+contexts:
+- name: prod
+  context:
+    cluster: prod
+    user: admin
+- name: shared
+  context:
+    cluster: shared
+    user: admin
+";
+
+    const DOWNLOADED: &str = "
+current-context: prod
+clusters:
+- name: prod
+  cluster:
+    server: https://new-prod.example.com
+- name: shared
+  cluster:
+    server: https://shared.example.com
+users:
+- name: admin
+  user:
+    token: new
+contexts:
+- name: prod
+  context:
+    cluster: prod
+    user: admin
+- name: shared
+  context:
+    cluster: shared
+    user: admin
+";
+
+    #[test]
+    fn merging_renames_collisions_and_skips_duplicates() {
+        let mut config = parse(EXISTING).unwrap();
+        let (added, renamed) = merge(&mut config, &parse(DOWNLOADED).unwrap());
+
+        let rename = |kind: &str, from: &str, to: &str| KubeconfigRename {
+            kind: kind.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        assert_eq!(
+            renamed,
+            vec![
+                rename("cluster", "prod", "prod-2"),
+                rename("user", "admin", "admin-2"),
+                rename("context", "prod", "prod-2"),
+                rename("context", "shared", "shared-2"),
+            ]
+        );
+        assert_eq!(added, vec!["prod-2", "shared-2"]);
+
+        let contexts = contexts(&config);
+        let prod = contexts.iter().find(|c| c.name == "prod-2").unwrap();
+        assert_eq!(prod.cluster.as_deref(), Some("prod-2"));
+        assert_eq!(prod.user.as_deref(), Some("admin-2"));
+        assert_eq!(prod.server.as_deref(), Some("https://new-prod.example.com"));
+        // The shared cluster was identical, so it's reused
+        let shared = contexts.iter().find(|c| c.name == "shared-2").unwrap();
+        assert_eq!(shared.cluster.as_deref(), Some("shared"));
+        assert_eq!(current_context(&config), Some("prod"));
+        assert!(dangling_contexts(&config).is_empty());
+
+        // Importing the same file again changes nothing
+        let (added, renamed) = merge(&mut config, &parse(DOWNLOADED).unwrap());
+        assert!(added.is_empty() && renamed.is_empty());
+    }
+
+    #[test]
+    fn removing_contexts_prunes_what_only_they_used() {
+        let mut config = parse(EXISTING).unwrap();
+        let removed = remove_contexts(&mut config, &["prod".to_string()]);
+        assert_eq!(removed, vec!["prod"]);
+        assert_eq!(
+            names(&config, "clusters"),
+            HashSet::from(["shared".to_string()])
+        );
+        // Still used by the shared context
+        assert_eq!(
+            names(&config, "users"),
+            HashSet::from(["admin".to_string()])
+        );
+        assert_eq!(current_context(&config), Some("shared"));
+    }
+
+    #[test]
+    fn finds_dangling_contexts_and_rejects_non_kubeconfigs() {
+        let mut config = parse(EXISTING).unwrap();
+        section_mut(&mut config, "clusters").retain(|cluster| name_of(cluster) != "shared");
+        assert_eq!(
+            dangling_contexts(&config),
+            vec![StaleContext {
+                name: "shared".to_string(),
+                reason: "Cluster shared is missing from the kubeconfig".to_string(),
+            }]
+        );
+
+        assert!(parse("- just\n- a list").is_err());
+        assert!(parse("contexts: yes").is_err());
+        assert!(parse("contexts:\n- context: {}").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_owner_only_over_a_leftover_temp_file() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config");
+        std::fs::write(dir.path().join("config.tmp"), "stale").unwrap();
+        let config = parse(EXISTING).unwrap();
+
+        assert_eq!(write_file(&path, &config).unwrap(), None);
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(
+            parse(&std::fs::read_to_string(&path).unwrap()).unwrap(),
+            config
+        );
+        assert!(write_file(&path, &config).unwrap().is_some());
+    }
+}
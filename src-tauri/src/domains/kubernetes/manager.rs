@@ -1,18 +1,32 @@
 use crate::command_executor::{CommandExecutor, CommandOptions};
+use crate::domains::automation::services::event_bus;
+use crate::domains::kubernetes::config_data;
+use crate::domains::kubernetes::port_forwards::{SavedPortForward, TARGET_KINDS};
 use crate::domains::kubernetes::types::*;
+use crate::domains::kubernetes::validation;
 use crate::process_ext::NoWindowExt;
 use base64::{engine::general_purpose, Engine as _};
 use futures_util::StreamExt;
-use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
 use k8s_openapi::api::batch::v1::{CronJob, Job};
-use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Pod, Secret, Service};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, Namespace, Node, PersistentVolume, PersistentVolumeClaim, Pod, ResourceQuota,
+    Secret, Service,
+};
 use k8s_openapi::api::networking::v1::Ingress;
-use kube::api::{ListParams, LogParams, Patch, PatchParams, PostParams};
+use k8s_openapi::api::storage::v1::StorageClass;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use k8s_openapi::NamespaceResourceScope;
+use kube::api::{DeleteParams, EvictParams, ListParams, LogParams, Patch, PatchParams, PostParams};
 use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::core::{DynamicObject, GroupVersion};
+use kube::discovery::Scope;
 use kube::runtime::watcher::{watcher, Config as WatcherConfig, Event};
 use kube::{Api, Client, Config};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::OnceLock;
@@ -78,6 +92,8 @@ static K8S_CLIENT: OnceLock<Client> = OnceLock::new();
 // Global storage for active port forward processes
 type PortForwardMap = Arc<Mutex<HashMap<String, (Child, PortForwardInfo)>>>;
 static PORT_FORWARDS: OnceLock<PortForwardMap> = OnceLock::new();
+static PORT_FORWARD_WATCHDOG: OnceLock<JoinHandle<()>> = OnceLock::new();
+const PORT_FORWARD_RESTART_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
 
 // Global storage for active watch tasks
 type WatchTaskMap = Arc<Mutex<HashMap<String, JoinHandle<()>>>>;
@@ -179,7 +195,7 @@ impl KubernetesManager {
 
     /// Resolve kubeconfig path: KUBECONFIG env (first path if multiple) or ~/.kube/config.
     /// Desktop apps often don't inherit shell env, so we explicitly use dirs::home_dir() for ~.
-    fn kubeconfig_path() -> Option<PathBuf> {
+    pub(crate) fn kubeconfig_path() -> Option<PathBuf> {
         std::env::var("KUBECONFIG")
             .ok()
             .and_then(|s| s.split(':').next().map(|p| PathBuf::from(p)))
@@ -337,16 +353,34 @@ impl KubernetesManager {
 
         // Spawn a task to watch for events with error recovery
         let handle = tokio::spawn(async move {
+            // Pods already announced as crash looping, until they recover
+            let mut crash_looping: HashSet<String> = HashSet::new();
             loop {
                 match stream.next().await {
                     Some(Ok(Event::Applied(pod))) => {
                         let pod_info = Self::pod_to_info_static(&pod);
+                        let key = format!("{}/{}", pod_info.namespace, pod_info.name);
+                        let containers = Self::crash_looping_containers(&pod_info);
+                        if containers.is_empty() {
+                            crash_looping.remove(&key);
+                        } else if crash_looping.insert(key) {
+                            event_bus::publish(
+                                event_bus::POD_CRASH_LOOPING,
+                                serde_json::json!({
+                                    "namespace": pod_info.namespace,
+                                    "pod": pod_info.name,
+                                    "containers": containers,
+                                    "restarts": pod_info.restarts,
+                                }),
+                            );
+                        }
                         if let Err(e) = window_clone.emit("k8s:pod-updated", &pod_info) {
                             eprintln!("Failed to emit pod update: {}", e);
                         }
                     }
                     Some(Ok(Event::Deleted(pod))) => {
                         let pod_info = Self::pod_to_info_static(&pod);
+                        crash_looping.remove(&format!("{}/{}", pod_info.namespace, pod_info.name));
                         if let Err(e) = window_clone.emit("k8s:pod-deleted", &pod_info) {
                             eprintln!("Failed to emit pod deletion: {}", e);
                         }
@@ -691,6 +725,118 @@ impl KubernetesManager {
         }
     }
 
+    /// Start a job from a CronJob's template now, outside its schedule, like
+    /// `kubectl create job --from=cronjob/<name>`
+    pub async fn trigger_cronjob(&self, namespace: &str, name: &str) -> Result<JobInfo, String> {
+        use kube::Resource;
+
+        let client = Self::get_client()?;
+        let cronjobs: Api<CronJob> = Api::namespaced(client.clone(), namespace);
+        let cronjob = cronjobs
+            .get(name)
+            .await
+            .map_err(|e| format!("Failed to get cronjob: {}", e))?;
+        let template = cronjob
+            .spec
+            .as_ref()
+            .map(|spec| spec.job_template.clone())
+            .ok_or_else(|| format!("CronJob {} has no job template", name))?;
+
+        let template_metadata = template.metadata.unwrap_or_default();
+        let mut annotations = template_metadata.annotations.unwrap_or_default();
+        annotations.insert(
+            "cronjob.kubernetes.io/instantiate".to_string(),
+            "manual".to_string(),
+        );
+        let job = Job {
+            metadata: ObjectMeta {
+                name: Some(manual_job_name(name, chrono::Utc::now())),
+                namespace: Some(namespace.to_string()),
+                labels: template_metadata.labels,
+                annotations: Some(annotations),
+                owner_references: cronjob.controller_owner_ref(&()).map(|owner| vec![owner]),
+                ..Default::default()
+            },
+            spec: template.spec,
+            status: None,
+        };
+
+        let jobs: Api<Job> = Api::namespaced(client, namespace);
+        jobs.create(&PostParams::default(), &job)
+            .await
+            .map(|job| self.job_to_info(&job))
+            .map_err(|e| format!("Failed to trigger cronjob: {}", e))
+    }
+
+    pub async fn set_cronjob_suspended(
+        &self,
+        namespace: &str,
+        name: &str,
+        suspend: bool,
+    ) -> Result<CronJobInfo, String> {
+        let client = Self::get_client()?;
+        let api: Api<CronJob> = Api::namespaced(client, namespace);
+
+        let patch = serde_json::json!({ "spec": { "suspend": suspend } });
+        api.patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .map(|cronjob| self.cronjob_to_info(&cronjob))
+            .map_err(|e| {
+                let action = if suspend { "suspend" } else { "resume" };
+                format!("Failed to {} cronjob: {}", action, e)
+            })
+    }
+
+    /// Delete the finished jobs matching `label_selector`, and their pods.
+    /// `status` narrows it to jobs that succeeded or failed.
+    pub async fn delete_finished_jobs(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+        status: Option<&str>,
+    ) -> Result<JobCleanupResult, String> {
+        if let Some(status) = status {
+            if status != "succeeded" && status != "failed" {
+                return Err(format!(
+                    "Unknown job status '{}', expected succeeded or failed",
+                    status
+                ));
+            }
+        }
+        let client = Self::get_client()?;
+        let api: Api<Job> = Api::namespaced(client, namespace);
+
+        let mut params = ListParams::default();
+        if let Some(selector) = label_selector.filter(|selector| !selector.trim().is_empty()) {
+            params = params.labels(selector);
+        }
+        let jobs = api
+            .list(&params)
+            .await
+            .map_err(|e| format!("Failed to list jobs: {}", e))?;
+
+        let mut result = JobCleanupResult {
+            deleted: Vec::new(),
+            failed: Vec::new(),
+        };
+        for job in jobs.items {
+            let Some(finished) = finished_job_status(&job) else {
+                continue;
+            };
+            if status.is_some_and(|status| status != finished) {
+                continue;
+            }
+            let name = job.metadata.name.unwrap_or_default();
+            // Jobs orphan their pods by default, so delete them in the background
+            match api.delete(&name, &DeleteParams::background()).await {
+                Ok(_) => result.deleted.push(name),
+                Err(kube::Error::Api(e)) if e.code == 404 => result.deleted.push(name),
+                Err(e) => result.failed.push(format!("{}: {}", name, e)),
+            }
+        }
+        Ok(result)
+    }
+
     pub async fn list_ingresses(
         &self,
         namespace: Option<&str>,
@@ -735,6 +881,170 @@ impl KubernetesManager {
         }
     }
 
+    pub async fn get_config_entries(
+        &self,
+        kind: ConfigResourceKind,
+        namespace: &str,
+        name: &str,
+    ) -> Result<ConfigEntries, String> {
+        let client = Self::get_client()?;
+        match kind {
+            ConfigResourceKind::ConfigMap => {
+                let api: Api<ConfigMap> = Api::namespaced(client, namespace);
+                let configmap = api
+                    .get(name)
+                    .await
+                    .map_err(|e| format!("Failed to get configmap: {}", e))?;
+                Ok(Self::config_entries(
+                    kind,
+                    &configmap.metadata,
+                    config_data::configmap_entries(&configmap),
+                ))
+            }
+            ConfigResourceKind::Secret => {
+                let api: Api<Secret> = Api::namespaced(client, namespace);
+                let secret = api
+                    .get(name)
+                    .await
+                    .map_err(|e| format!("Failed to get secret: {}", e))?;
+                Ok(Self::config_entries(
+                    kind,
+                    &secret.metadata,
+                    config_data::secret_entries(&secret),
+                ))
+            }
+        }
+    }
+
+    /// Add or update one key. With `resource_version`, the change is refused
+    /// if the resource has changed since that version was read.
+    pub async fn set_config_entry(
+        &self,
+        kind: ConfigResourceKind,
+        namespace: &str,
+        name: &str,
+        entry: ConfigEntry,
+        resource_version: Option<&str>,
+    ) -> Result<ConfigEntries, String> {
+        let client = Self::get_client()?;
+        match kind {
+            ConfigResourceKind::ConfigMap => {
+                let api: Api<ConfigMap> = Api::namespaced(client, namespace);
+                let configmap = Self::edit_object(&api, name, resource_version, |configmap| {
+                    config_data::set_configmap_entry(configmap, &entry)
+                })
+                .await?;
+                Ok(Self::config_entries(
+                    kind,
+                    &configmap.metadata,
+                    config_data::configmap_entries(&configmap),
+                ))
+            }
+            ConfigResourceKind::Secret => {
+                let api: Api<Secret> = Api::namespaced(client, namespace);
+                let secret = Self::edit_object(&api, name, resource_version, |secret| {
+                    config_data::set_secret_entry(secret, &entry)
+                })
+                .await?;
+                Ok(Self::config_entries(
+                    kind,
+                    &secret.metadata,
+                    config_data::secret_entries(&secret),
+                ))
+            }
+        }
+    }
+
+    pub async fn remove_config_entry(
+        &self,
+        kind: ConfigResourceKind,
+        namespace: &str,
+        name: &str,
+        key: &str,
+        resource_version: Option<&str>,
+    ) -> Result<ConfigEntries, String> {
+        let client = Self::get_client()?;
+        match kind {
+            ConfigResourceKind::ConfigMap => {
+                let api: Api<ConfigMap> = Api::namespaced(client, namespace);
+                let configmap = Self::edit_object(&api, name, resource_version, |configmap| {
+                    config_data::remove_configmap_entry(configmap, key)
+                })
+                .await?;
+                Ok(Self::config_entries(
+                    kind,
+                    &configmap.metadata,
+                    config_data::configmap_entries(&configmap),
+                ))
+            }
+            ConfigResourceKind::Secret => {
+                let api: Api<Secret> = Api::namespaced(client, namespace);
+                let secret = Self::edit_object(&api, name, resource_version, |secret| {
+                    config_data::remove_secret_entry(secret, key)
+                })
+                .await?;
+                Ok(Self::config_entries(
+                    kind,
+                    &secret.metadata,
+                    config_data::secret_entries(&secret),
+                ))
+            }
+        }
+    }
+
+    /// Read an object, change it and write it back. The write carries the
+    /// version that was read, so the server refuses it if anything else
+    /// wrote in between.
+    async fn edit_object<K>(
+        api: &Api<K>,
+        name: &str,
+        resource_version: Option<&str>,
+        edit: impl FnOnce(&mut K) -> Result<(), String>,
+    ) -> Result<K, String>
+    where
+        K: kube::Resource
+            + Clone
+            + std::fmt::Debug
+            + serde::Serialize
+            + serde::de::DeserializeOwned,
+    {
+        let stale = || {
+            format!(
+                "{} was changed by someone else; reload it and try again",
+                name
+            )
+        };
+        let mut object = api
+            .get(name)
+            .await
+            .map_err(|e| format!("Failed to get {}: {}", name, e))?;
+        if let Some(expected) = resource_version {
+            if object.meta().resource_version.as_deref() != Some(expected) {
+                return Err(stale());
+            }
+        }
+        edit(&mut object)?;
+        match api.replace(name, &PostParams::default(), &object).await {
+            Ok(object) => Ok(object),
+            Err(kube::Error::Api(e)) if e.code == 409 => Err(stale()),
+            Err(e) => Err(format!("Failed to update {}: {}", name, e)),
+        }
+    }
+
+    fn config_entries(
+        kind: ConfigResourceKind,
+        metadata: &ObjectMeta,
+        entries: Vec<ConfigEntry>,
+    ) -> ConfigEntries {
+        ConfigEntries {
+            kind,
+            name: metadata.name.clone().unwrap_or_default(),
+            namespace: metadata.namespace.clone().unwrap_or_default(),
+            resource_version: metadata.resource_version.clone(),
+            entries,
+        }
+    }
+
     pub async fn list_secrets(&self, namespace: Option<&str>) -> Result<Vec<SecretInfo>, String> {
         let client = Self::get_client()?;
 
@@ -772,141 +1082,657 @@ impl KubernetesManager {
         }
     }
 
-    pub fn get_current_cluster(&self) -> Option<&KubernetesCluster> {
-        self.current_cluster.as_ref()
-    }
+    pub async fn create_namespace(
+        &self,
+        name: &str,
+        labels: Option<HashMap<String, String>>,
+    ) -> Result<NamespaceInfo, String> {
+        validate_namespace_name(name)?;
+        let client = Self::get_client()?;
+        let api: Api<Namespace> = Api::all(client);
 
-    pub fn is_connected(&self) -> bool {
-        K8S_CLIENT.get().is_some()
+        let namespace = Namespace {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                labels: labels.map(|labels| labels.into_iter().collect()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        api.create(&PostParams::default(), &namespace)
+            .await
+            .map(|namespace| self.namespace_to_info(&namespace))
+            .map_err(|e| format!("Failed to create namespace: {}", e))
     }
 
-    async fn tool_status(command: &str) -> KubeSetupToolStatus {
-        KubeSetupToolStatus {
-            installed: CommandExecutor::command_exists(command).await,
-            command: command.to_string(),
+    /// Delete a namespace and everything in it. The namespaces Kubernetes
+    /// itself relies on are refused.
+    pub async fn delete_namespace(&self, name: &str) -> Result<(), String> {
+        if PROTECTED_NAMESPACES.contains(&name) {
+            return Err(format!("The {} namespace can't be deleted", name));
+        }
+        let client = Self::get_client()?;
+        let api: Api<Namespace> = Api::all(client);
+        match api.delete(name, &DeleteParams::default()).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("Failed to delete namespace: {}", e)),
         }
     }
 
-    async fn detect_kind_targets(&self) -> Result<Vec<KubeSetupTarget>, String> {
-        let result = CommandExecutor::execute_with_args("kind", &["get", "clusters"], None).await?;
-        if !result.success {
-            return Err(format!("kind cluster detection failed: {}", result.stderr));
-        }
+    /// Workload counts, quota usage and recent warnings for a namespace
+    pub async fn namespace_summary(&self, name: &str) -> Result<NamespaceSummary, String> {
+        let client = Self::get_client()?;
+        let namespaces: Api<Namespace> = Api::all(client.clone());
+        let pods: Api<Pod> = Api::namespaced(client.clone(), name);
+        let quotas: Api<ResourceQuota> = Api::namespaced(client.clone(), name);
+        type K8sEvent = k8s_openapi::api::core::v1::Event;
+        let events: Api<K8sEvent> = Api::namespaced(client.clone(), name);
+        let warnings = ListParams::default().fields("type=Warning");
 
-        let targets = result
-            .stdout
-            .lines()
-            .map(str::trim)
-            .filter(|line| !line.is_empty())
-            .map(|name| KubeSetupTarget {
-                id: format!("kind:{name}"),
-                label: format!("kind: {name}"),
-                provider: "kind".to_string(),
-                metadata: HashMap::from([("cluster_name".to_string(), name.to_string())]),
+        let (
+            namespace,
+            pods,
+            quotas,
+            events,
+            deployments,
+            statefulsets,
+            daemonsets,
+            jobs,
+            cronjobs,
+            services,
+            configmaps,
+            secrets,
+            persistent_volume_claims,
+        ) = tokio::try_join!(
+            async {
+                namespaces
+                    .get(name)
+                    .await
+                    .map_err(|e| format!("Failed to get namespace: {}", e))
+            },
+            async {
+                pods.list(&ListParams::default())
+                    .await
+                    .map_err(|e| format!("Failed to list pods: {}", e))
+            },
+            async {
+                quotas
+                    .list(&ListParams::default())
+                    .await
+                    .map_err(|e| format!("Failed to list resource quotas: {}", e))
+            },
+            async {
+                events
+                    .list(&warnings)
+                    .await
+                    .map_err(|e| format!("Failed to list events: {}", e))
+            },
+            Self::count::<Deployment>(&client, name),
+            Self::count::<StatefulSet>(&client, name),
+            Self::count::<DaemonSet>(&client, name),
+            Self::count::<Job>(&client, name),
+            Self::count::<CronJob>(&client, name),
+            Self::count::<Service>(&client, name),
+            Self::count::<ConfigMap>(&client, name),
+            Self::count::<Secret>(&client, name),
+            Self::count::<PersistentVolumeClaim>(&client, name),
+        )?;
+
+        let running_pods = pods
+            .items
+            .iter()
+            .filter(|pod| {
+                pod.status
+                    .as_ref()
+                    .and_then(|status| status.phase.as_deref())
+                    == Some("Running")
             })
-            .collect();
+            .count();
+        let mut events = events.items;
+        events.sort_by_key(|event| {
+            std::cmp::Reverse(
+                event
+                    .last_timestamp
+                    .as_ref()
+                    .map(|time| time.0)
+                    .or_else(|| event.event_time.as_ref().map(|time| time.0))
+                    .or_else(|| {
+                        event
+                            .metadata
+                            .creation_timestamp
+                            .as_ref()
+                            .map(|time| time.0)
+                    }),
+            )
+        });
 
-        Ok(targets)
+        Ok(NamespaceSummary {
+            namespace: self.namespace_to_info(&namespace),
+            workloads: WorkloadCounts {
+                pods: pods.items.len(),
+                running_pods,
+                deployments,
+                statefulsets,
+                daemonsets,
+                jobs,
+                cronjobs,
+                services,
+                configmaps,
+                secrets,
+                persistent_volume_claims,
+            },
+            quotas: quotas
+                .items
+                .iter()
+                .map(|quota| ResourceQuotaInfo {
+                    name: quota.metadata.name.clone().unwrap_or_default(),
+                    usage: quota_usage(quota),
+                })
+                .collect(),
+            warnings: events
+                .iter()
+                .take(NAMESPACE_SUMMARY_WARNINGS)
+                .map(|event| self.event_to_info(event))
+                .collect(),
+        })
     }
 
-    async fn detect_minikube_targets(&self) -> Result<Vec<KubeSetupTarget>, String> {
-        let result = CommandExecutor::execute_with_args(
-            "minikube",
-            &["profile", "list", "-o", "json"],
-            None,
-        )
-        .await?;
-        if !result.success {
-            return Err(format!(
-                "minikube profile detection failed: {}",
-                result.stderr
-            ));
-        }
-
-        let parsed: Value = serde_json::from_str(&result.stdout)
-            .map_err(|e| format!("Failed to parse minikube profiles: {}", e))?;
-        let valid = parsed
-            .get("valid")
-            .and_then(|v| v.as_array())
-            .cloned()
-            .unwrap_or_default();
+    /// Number of `K` in a namespace, listing only their metadata
+    async fn count<K>(client: &Client, namespace: &str) -> Result<usize, String>
+    where
+        K: kube::Resource<Scope = NamespaceResourceScope>
+            + Clone
+            + std::fmt::Debug
+            + serde::de::DeserializeOwned,
+        K::DynamicType: Default,
+    {
+        let api: Api<K> = Api::namespaced(client.clone(), namespace);
+        api.list_metadata(&ListParams::default())
+            .await
+            .map(|list| list.items.len())
+            .map_err(|e| format!("Failed to list {}: {}", K::plural(&Default::default()), e))
+    }
 
-        let mut targets = Vec::new();
-        for item in valid {
-            let Some(name) = item.get("Name").and_then(|v| v.as_str()) else {
-                continue;
-            };
-            targets.push(KubeSetupTarget {
-                id: format!("minikube:{name}"),
-                label: format!("minikube: {name}"),
-                provider: "minikube".to_string(),
-                metadata: HashMap::from([("profile".to_string(), name.to_string())]),
-            });
-        }
+    pub async fn list_nodes(&self) -> Result<Vec<NodeInfo>, String> {
+        let client = Self::get_client()?;
+        let api: Api<Node> = Api::all(client);
 
-        Ok(targets)
+        let nodes = api
+            .list(&ListParams::default())
+            .await
+            .map_err(|e| format!("Failed to list nodes: {}", e))?;
+        Ok(nodes
+            .items
+            .iter()
+            .map(|node| self.node_to_info(node))
+            .collect())
     }
 
-    async fn detect_gcloud_targets(&self) -> Result<Vec<KubeSetupTarget>, String> {
-        let result = CommandExecutor::execute_with_args(
-            "gcloud",
-            &["container", "clusters", "list", "--format=json"],
-            None,
-        )
-        .await?;
-        if !result.success {
-            return Err(format!(
-                "gcloud cluster detection failed: {}",
-                result.stderr
-            ));
-        }
-
-        let parsed: Value = serde_json::from_str(&result.stdout)
-            .map_err(|e| format!("Failed to parse gcloud clusters: {}", e))?;
-        let Some(items) = parsed.as_array() else {
-            return Ok(Vec::new());
-        };
+    pub async fn list_persistent_volumes(&self) -> Result<Vec<PersistentVolumeInfo>, String> {
+        let client = Self::get_client()?;
+        let api: Api<PersistentVolume> = Api::all(client);
 
-        let mut targets = Vec::new();
-        for item in items {
-            let Some(name) = item.get("name").and_then(|v| v.as_str()) else {
-                continue;
-            };
-            let location = item
-                .get("location")
-                .and_then(|v| v.as_str())
-                .unwrap_or_default();
-            let project = item
-                .get("location")
-                .and_then(|_| item.get("selfLink"))
-                .and_then(|v| v.as_str())
-                .and_then(|self_link| self_link.split("/projects/").nth(1))
-                .and_then(|rest| rest.split('/').next())
-                .unwrap_or_default()
-                .to_string();
+        let volumes = api
+            .list(&ListParams::default())
+            .await
+            .map_err(|e| format!("Failed to list persistent volumes: {}", e))?;
+        Ok(volumes
+            .items
+            .iter()
+            .map(|volume| self.persistent_volume_to_info(volume))
+            .collect())
+    }
 
-            let mut metadata = HashMap::from([
-                ("cluster_name".to_string(), name.to_string()),
-                ("location".to_string(), location.to_string()),
-            ]);
+    pub async fn get_persistent_volume(&self, name: &str) -> Result<PersistentVolumeInfo, String> {
+        let client = Self::get_client()?;
+        let api: Api<PersistentVolume> = Api::all(client);
 
-            if !project.is_empty() {
-                metadata.insert("project".to_string(), project.clone());
-            }
+        api.get(name)
+            .await
+            .map(|volume| self.persistent_volume_to_info(&volume))
+            .map_err(|e| format!("Failed to get persistent volume: {}", e))
+    }
 
-            targets.push(KubeSetupTarget {
-                id: format!("gcloud:{name}:{location}"),
-                label: if project.is_empty() {
-                    format!("gke: {name} ({location})")
-                } else {
-                    format!("gke: {name} ({location}, {project})")
-                },
-                provider: "gcloud".to_string(),
-                metadata,
-            });
+    pub async fn delete_persistent_volume(&self, name: &str) -> Result<(), String> {
+        let client = Self::get_client()?;
+        let api: Api<PersistentVolume> = Api::all(client);
+        match api.delete(name, &DeleteParams::default()).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("Failed to delete PersistentVolume: {}", e)),
         }
+    }
 
-        Ok(targets)
+    pub async fn list_persistent_volume_claims(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<Vec<PersistentVolumeClaimInfo>, String> {
+        let client = Self::get_client()?;
+
+        let namespace = namespace.unwrap_or("default");
+        let api: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), namespace);
+        let claims = api
+            .list(&ListParams::default())
+            .await
+            .map_err(|e| format!("Failed to list persistent volume claims: {}", e))?;
+        let users = Self::claim_users(client, namespace).await?;
+        Ok(claims
+            .items
+            .iter()
+            .map(|claim| self.persistent_volume_claim_to_info(claim, &users))
+            .collect())
+    }
+
+    pub async fn get_persistent_volume_claim(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<PersistentVolumeClaimInfo, String> {
+        let client = Self::get_client()?;
+        let api: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), namespace);
+
+        let claim = api
+            .get(name)
+            .await
+            .map_err(|e| format!("Failed to get persistent volume claim: {}", e))?;
+        let users = Self::claim_users(client, namespace).await?;
+        Ok(self.persistent_volume_claim_to_info(&claim, &users))
+    }
+
+    pub async fn delete_persistent_volume_claim(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<(), String> {
+        let client = Self::get_client()?;
+        let api: Api<PersistentVolumeClaim> = Api::namespaced(client, namespace);
+        match api.delete(name, &DeleteParams::default()).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("Failed to delete PersistentVolumeClaim: {}", e)),
+        }
+    }
+
+    /// Request a larger size for a claim. Only claims whose storage class
+    /// allows volume expansion can grow, and claims can't shrink.
+    pub async fn expand_persistent_volume_claim(
+        &self,
+        namespace: &str,
+        name: &str,
+        size: &str,
+    ) -> Result<PersistentVolumeClaimInfo, String> {
+        let client = Self::get_client()?;
+        let api: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), namespace);
+
+        let claim = api
+            .get(name)
+            .await
+            .map_err(|e| format!("Failed to get persistent volume claim: {}", e))?;
+        let spec = claim
+            .spec
+            .as_ref()
+            .ok_or("PersistentVolumeClaim spec not found")?;
+        let class_name = spec
+            .storage_class_name
+            .as_deref()
+            .ok_or("The claim has no storage class, so it can't be expanded")?;
+        let class = Api::<StorageClass>::all(client.clone())
+            .get(class_name)
+            .await
+            .map_err(|e| format!("Failed to get storage class {}: {}", class_name, e))?;
+        if class.allow_volume_expansion != Some(true) {
+            return Err(format!(
+                "Storage class {} doesn't allow volume expansion",
+                class_name
+            ));
+        }
+
+        let new_size =
+            parse_quantity(size).ok_or_else(|| format!("Invalid storage size: {}", size))?;
+        let current = spec
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.requests.as_ref())
+            .and_then(|requests| requests.get("storage"))
+            .map(|quantity| quantity.0.clone());
+        if let Some(current) = current {
+            if parse_quantity(&current).is_some_and(|current| new_size <= current) {
+                return Err(format!(
+                    "New size {} must be larger than the current request of {}",
+                    size, current
+                ));
+            }
+        }
+
+        let patch = serde_json::json!({
+            "spec": { "resources": { "requests": { "storage": size } } }
+        });
+        let claim = api
+            .patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .map_err(|e| format!("Failed to expand persistent volume claim: {}", e))?;
+        let users = Self::claim_users(client, namespace).await?;
+        Ok(self.persistent_volume_claim_to_info(&claim, &users))
+    }
+
+    /// Pods in a namespace by the claims they mount
+    async fn claim_users(
+        client: Client,
+        namespace: &str,
+    ) -> Result<HashMap<String, Vec<String>>, String> {
+        let pods: Api<Pod> = Api::namespaced(client, namespace);
+        let pods = pods
+            .list(&ListParams::default())
+            .await
+            .map_err(|e| format!("Failed to list pods: {}", e))?;
+        let mut users: HashMap<String, Vec<String>> = HashMap::new();
+        for pod in &pods.items {
+            let pod_name = pod.metadata.name.clone().unwrap_or_default();
+            let claims = pod
+                .spec
+                .iter()
+                .flat_map(|spec| spec.volumes.iter().flatten())
+                .filter_map(|volume| volume.persistent_volume_claim.as_ref());
+            for claim in claims {
+                let pods = users.entry(claim.claim_name.clone()).or_default();
+                if !pods.contains(&pod_name) {
+                    pods.push(pod_name.clone());
+                }
+            }
+        }
+        Ok(users)
+    }
+
+    /// Mark a node unschedulable, so no new pods land on it
+    pub async fn cordon_node(&self, node_name: &str) -> Result<NodeInfo, String> {
+        let client = Self::get_client()?;
+        let api: Api<Node> = Api::all(client);
+
+        api.cordon(node_name)
+            .await
+            .map(|node| self.node_to_info(&node))
+            .map_err(|e| format!("Failed to cordon node: {}", e))
+    }
+
+    pub async fn uncordon_node(&self, node_name: &str) -> Result<NodeInfo, String> {
+        let client = Self::get_client()?;
+        let api: Api<Node> = Api::all(client);
+
+        api.uncordon(node_name)
+            .await
+            .map(|node| self.node_to_info(&node))
+            .map_err(|e| format!("Failed to uncordon node: {}", e))
+    }
+
+    /// Cordon a node and evict its pods, like `kubectl drain`. Pods that
+    /// can't be drained safely under `options` stop the drain before the node
+    /// is cordoned. Evictions a disruption budget blocks are retried until
+    /// the timeout; each pod's progress is emitted as `k8s:drain-progress`.
+    pub async fn drain_node(
+        &self,
+        node_name: &str,
+        options: DrainOptions,
+        window: Window,
+    ) -> Result<DrainResult, String> {
+        let client = Self::get_client()?;
+        let all_pods: Api<Pod> = Api::all(client.clone());
+
+        let pods = all_pods
+            .list(&ListParams::default().fields(&format!("spec.nodeName={}", node_name)))
+            .await
+            .map_err(|e| format!("Failed to list pods on node: {}", e))?;
+        let mut to_evict = Vec::new();
+        let mut skipped = Vec::new();
+        let mut refused = Vec::new();
+        for pod in pods.items {
+            let id = format!(
+                "{}/{}",
+                pod.metadata.namespace.as_deref().unwrap_or("default"),
+                pod.metadata.name.as_deref().unwrap_or_default()
+            );
+            match drain_disposition(&pod, &options) {
+                DrainDisposition::Evict => to_evict.push(pod),
+                DrainDisposition::Skip(reason) => skipped.push(format!("{}: {}", id, reason)),
+                DrainDisposition::Refuse(reason) => refused.push(format!("{}: {}", id, reason)),
+            }
+        }
+        if !refused.is_empty() {
+            return Err(format!(
+                "Cannot drain node {}:\n{}",
+                node_name,
+                refused.join("\n")
+            ));
+        }
+
+        self.cordon_node(node_name).await?;
+
+        let deadline = tokio::time::Instant::now()
+            + std::time::Duration::from_secs(options.timeout_seconds.unwrap_or(300));
+        let eviction = EvictParams {
+            delete_options: options.grace_period_seconds.map(|seconds| DeleteParams {
+                grace_period_seconds: Some(seconds),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let total = to_evict.len();
+        let mut completed = 0;
+        let progress =
+            |namespace: &str, pod: &str, phase: &str, message: Option<String>, completed| {
+                let _ = window.emit(
+                    "k8s:drain-progress",
+                    DrainProgress {
+                        node: node_name.to_string(),
+                        namespace: namespace.to_string(),
+                        pod: pod.to_string(),
+                        phase: phase.to_string(),
+                        message,
+                        completed,
+                        total,
+                    },
+                );
+            };
+
+        let mut evicted = Vec::new();
+        for pod in &to_evict {
+            let namespace = pod.metadata.namespace.as_deref().unwrap_or("default");
+            let name = pod.metadata.name.as_deref().unwrap_or_default();
+            let api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+            progress(namespace, name, "evicting", None, completed);
+            loop {
+                match api.evict(name, &eviction).await {
+                    Ok(_) => break,
+                    // Already gone
+                    Err(kube::Error::Api(e)) if e.code == 404 => break,
+                    // A disruption budget doesn't allow it yet
+                    Err(kube::Error::Api(e)) if e.code == 429 => {
+                        if tokio::time::Instant::now() >= deadline {
+                            return Err(format!(
+                                "Timed out evicting pod {}/{}: {}",
+                                namespace, name, e.message
+                            ));
+                        }
+                        progress(namespace, name, "blocked", Some(e.message), completed);
+                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    }
+                    Err(e) => {
+                        progress(namespace, name, "failed", Some(e.to_string()), completed);
+                        return Err(format!("Failed to evict pod {}/{}: {}", namespace, name, e));
+                    }
+                }
+            }
+            completed += 1;
+            progress(namespace, name, "evicted", None, completed);
+            evicted.push(format!("{}/{}", namespace, name));
+        }
+
+        // Wait for the evicted pods to terminate. A pod recreated under the
+        // same name (StatefulSets) has a new uid and counts as gone.
+        for pod in &to_evict {
+            let namespace = pod.metadata.namespace.as_deref().unwrap_or("default");
+            let name = pod.metadata.name.as_deref().unwrap_or_default();
+            let api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+            loop {
+                let current = api
+                    .get_opt(name)
+                    .await
+                    .map_err(|e| format!("Failed to check pod {}/{}: {}", namespace, name, e))?;
+                match current {
+                    Some(current) if current.metadata.uid == pod.metadata.uid => {
+                        if tokio::time::Instant::now() >= deadline {
+                            return Err(format!(
+                                "Timed out waiting for pod {}/{} to terminate",
+                                namespace, name
+                            ));
+                        }
+                        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                    }
+                    _ => break,
+                }
+            }
+            progress(namespace, name, "gone", None, completed);
+        }
+
+        Ok(DrainResult {
+            node: node_name.to_string(),
+            evicted,
+            skipped,
+        })
+    }
+
+    pub fn get_current_cluster(&self) -> Option<&KubernetesCluster> {
+        self.current_cluster.as_ref()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        K8S_CLIENT.get().is_some()
+    }
+
+    async fn tool_status(command: &str) -> KubeSetupToolStatus {
+        KubeSetupToolStatus {
+            installed: CommandExecutor::command_exists(command).await,
+            command: command.to_string(),
+        }
+    }
+
+    async fn detect_kind_targets(&self) -> Result<Vec<KubeSetupTarget>, String> {
+        let result = CommandExecutor::execute_with_args("kind", &["get", "clusters"], None).await?;
+        if !result.success {
+            return Err(format!("kind cluster detection failed: {}", result.stderr));
+        }
+
+        let targets = result
+            .stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|name| KubeSetupTarget {
+                id: format!("kind:{name}"),
+                label: format!("kind: {name}"),
+                provider: "kind".to_string(),
+                metadata: HashMap::from([("cluster_name".to_string(), name.to_string())]),
+            })
+            .collect();
+
+        Ok(targets)
+    }
+
+    async fn detect_minikube_targets(&self) -> Result<Vec<KubeSetupTarget>, String> {
+        let result = CommandExecutor::execute_with_args(
+            "minikube",
+            &["profile", "list", "-o", "json"],
+            None,
+        )
+        .await?;
+        if !result.success {
+            return Err(format!(
+                "minikube profile detection failed: {}",
+                result.stderr
+            ));
+        }
+
+        let parsed: Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse minikube profiles: {}", e))?;
+        let valid = parsed
+            .get("valid")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut targets = Vec::new();
+        for item in valid {
+            let Some(name) = item.get("Name").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            targets.push(KubeSetupTarget {
+                id: format!("minikube:{name}"),
+                label: format!("minikube: {name}"),
+                provider: "minikube".to_string(),
+                metadata: HashMap::from([("profile".to_string(), name.to_string())]),
+            });
+        }
+
+        Ok(targets)
+    }
+
+    async fn detect_gcloud_targets(&self) -> Result<Vec<KubeSetupTarget>, String> {
+        let result = CommandExecutor::execute_with_args(
+            "gcloud",
+            &["container", "clusters", "list", "--format=json"],
+            None,
+        )
+        .await?;
+        if !result.success {
+            return Err(format!(
+                "gcloud cluster detection failed: {}",
+                result.stderr
+            ));
+        }
+
+        let parsed: Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("Failed to parse gcloud clusters: {}", e))?;
+        let Some(items) = parsed.as_array() else {
+            return Ok(Vec::new());
+        };
+
+        let mut targets = Vec::new();
+        for item in items {
+            let Some(name) = item.get("name").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let location = item
+                .get("location")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let project = item
+                .get("location")
+                .and_then(|_| item.get("selfLink"))
+                .and_then(|v| v.as_str())
+                .and_then(|self_link| self_link.split("/projects/").nth(1))
+                .and_then(|rest| rest.split('/').next())
+                .unwrap_or_default()
+                .to_string();
+
+            let mut metadata = HashMap::from([
+                ("cluster_name".to_string(), name.to_string()),
+                ("location".to_string(), location.to_string()),
+            ]);
+
+            if !project.is_empty() {
+                metadata.insert("project".to_string(), project.clone());
+            }
+
+            targets.push(KubeSetupTarget {
+                id: format!("gcloud:{name}:{location}"),
+                label: if project.is_empty() {
+                    format!("gke: {name} ({location})")
+                } else {
+                    format!("gke: {name} ({location}, {project})")
+                },
+                provider: "gcloud".to_string(),
+                metadata,
+            });
+        }
+
+        Ok(targets)
     }
 
     async fn detect_az_targets(&self) -> Result<Vec<KubeSetupTarget>, String> {
@@ -1092,6 +1918,15 @@ impl KubernetesManager {
         Self::pod_to_info_static(pod)
     }
 
+    /// Containers waiting in CrashLoopBackOff
+    fn crash_looping_containers(pod: &PodInfo) -> Vec<String> {
+        pod.containers
+            .iter()
+            .filter(|c| c.state.contains("CrashLoopBackOff"))
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
     fn pod_to_info_static(pod: &Pod) -> PodInfo {
         let metadata = &pod.metadata;
         let status = pod.status.as_ref();
@@ -1622,19 +2457,184 @@ impl KubernetesManager {
         }
     }
 
-    pub async fn get_resource_yaml(
+    fn persistent_volume_to_info(&self, volume: &PersistentVolume) -> PersistentVolumeInfo {
+        let metadata = &volume.metadata;
+        let spec = volume.spec.as_ref();
+
+        let labels: std::collections::HashMap<String, String> = metadata
+            .labels
+            .as_ref()
+            .map(|btree| btree.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+
+        PersistentVolumeInfo {
+            name: metadata.name.clone().unwrap_or_default(),
+            capacity: spec
+                .and_then(|s| s.capacity.as_ref())
+                .and_then(|capacity| capacity.get("storage"))
+                .map(|quantity| quantity.0.clone()),
+            access_modes: spec
+                .and_then(|s| s.access_modes.clone())
+                .unwrap_or_default(),
+            reclaim_policy: spec.and_then(|s| s.persistent_volume_reclaim_policy.clone()),
+            status: volume
+                .status
+                .as_ref()
+                .and_then(|s| s.phase.clone())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            claim: spec.and_then(|s| s.claim_ref.as_ref()).map(|claim| {
+                format!(
+                    "{}/{}",
+                    claim.namespace.as_deref().unwrap_or("default"),
+                    claim.name.as_deref().unwrap_or_default()
+                )
+            }),
+            storage_class: spec.and_then(|s| s.storage_class_name.clone()),
+            volume_mode: spec.and_then(|s| s.volume_mode.clone()),
+            age: resource_age(metadata),
+            labels,
+        }
+    }
+
+    fn persistent_volume_claim_to_info(
         &self,
-        kind: &str,
-        namespace: &str,
-        name: &str,
-    ) -> Result<String, String> {
-        let client = Self::get_client()?;
+        claim: &PersistentVolumeClaim,
+        users: &HashMap<String, Vec<String>>,
+    ) -> PersistentVolumeClaimInfo {
+        let metadata = &claim.metadata;
+        let spec = claim.spec.as_ref();
+        let status = claim.status.as_ref();
+        let name = metadata.name.clone().unwrap_or_default();
 
-        match kind.to_lowercase().as_str() {
-            "pod" => {
-                let api: Api<Pod> = Api::namespaced(client, namespace);
-                match api.get(name).await {
-                    Ok(resource) => serde_json::to_string_pretty(&resource)
+        let labels: std::collections::HashMap<String, String> = metadata
+            .labels
+            .as_ref()
+            .map(|btree| btree.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+
+        PersistentVolumeClaimInfo {
+            namespace: metadata
+                .namespace
+                .clone()
+                .unwrap_or_else(|| "default".to_string()),
+            status: status
+                .and_then(|s| s.phase.clone())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            volume: spec.and_then(|s| s.volume_name.clone()),
+            capacity: status
+                .and_then(|s| s.capacity.as_ref())
+                .and_then(|capacity| capacity.get("storage"))
+                .map(|quantity| quantity.0.clone()),
+            requested: spec
+                .and_then(|s| s.resources.as_ref())
+                .and_then(|resources| resources.requests.as_ref())
+                .and_then(|requests| requests.get("storage"))
+                .map(|quantity| quantity.0.clone()),
+            access_modes: status
+                .and_then(|s| s.access_modes.clone())
+                .or_else(|| spec.and_then(|s| s.access_modes.clone()))
+                .unwrap_or_default(),
+            storage_class: spec.and_then(|s| s.storage_class_name.clone()),
+            volume_mode: spec.and_then(|s| s.volume_mode.clone()),
+            used_by: users.get(&name).cloned().unwrap_or_default(),
+            age: resource_age(metadata),
+            labels,
+            name,
+        }
+    }
+
+    fn node_to_info(&self, node: &Node) -> NodeInfo {
+        let metadata = &node.metadata;
+        let status = node.status.as_ref();
+        let unschedulable = node
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.unschedulable)
+            .unwrap_or(false);
+
+        let conditions: Vec<NodeConditionInfo> = status
+            .and_then(|s| s.conditions.as_ref())
+            .map(|conditions| {
+                conditions
+                    .iter()
+                    .map(|c| NodeConditionInfo {
+                        condition_type: c.type_.clone(),
+                        status: c.status.clone(),
+                        reason: c.reason.clone(),
+                        message: c.message.clone(),
+                        last_transition_time: c
+                            .last_transition_time
+                            .as_ref()
+                            .map(|t| t.0.to_rfc3339()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut node_status = match conditions
+            .iter()
+            .find(|c| c.condition_type == "Ready")
+            .map(|c| c.status.as_str())
+        {
+            Some("True") => "Ready",
+            Some("False") => "NotReady",
+            _ => "Unknown",
+        }
+        .to_string();
+        if unschedulable {
+            node_status.push_str(",SchedulingDisabled");
+        }
+
+        let labels: std::collections::HashMap<String, String> = metadata
+            .labels
+            .as_ref()
+            .map(|btree| btree.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        let mut roles: Vec<String> = labels
+            .keys()
+            .filter_map(|key| key.strip_prefix("node-role.kubernetes.io/"))
+            .filter(|role| !role.is_empty())
+            .map(|role| role.to_string())
+            .collect();
+        roles.sort();
+
+        let node_info = status.and_then(|s| s.node_info.as_ref());
+
+        NodeInfo {
+            name: metadata.name.clone().unwrap_or_default(),
+            status: node_status,
+            roles,
+            unschedulable,
+            internal_ip: status
+                .and_then(|s| s.addresses.as_ref())
+                .and_then(|addresses| {
+                    addresses
+                        .iter()
+                        .find(|address| address.type_ == "InternalIP")
+                        .map(|address| address.address.clone())
+                }),
+            kubelet_version: node_info.map(|info| info.kubelet_version.clone()),
+            os_image: node_info.map(|info| info.os_image.clone()),
+            capacity: quantities(status.and_then(|s| s.capacity.as_ref())),
+            allocatable: quantities(status.and_then(|s| s.allocatable.as_ref())),
+            conditions,
+            age: resource_age(metadata),
+            labels,
+        }
+    }
+
+    pub async fn get_resource_yaml(
+        &self,
+        kind: &str,
+        namespace: &str,
+        name: &str,
+    ) -> Result<String, String> {
+        let client = Self::get_client()?;
+
+        match kind.to_lowercase().as_str() {
+            "pod" => {
+                let api: Api<Pod> = Api::namespaced(client, namespace);
+                match api.get(name).await {
+                    Ok(resource) => serde_json::to_string_pretty(&resource)
                         .map_err(|e| format!("Failed to serialize resource: {}", e)),
                     Err(e) => Err(format!("Failed to get resource: {}", e)),
                 }
@@ -1727,6 +2727,114 @@ impl KubernetesManager {
         self.get_resource_yaml("secret", namespace, name).await
     }
 
+    /// Check resource YAML before it's applied: locally for syntax and the
+    /// required fields, then with a strict server-side dry run so the
+    /// cluster validates it against its own schema. Problems come back as
+    /// diagnostics placed on lines of the YAML.
+    pub async fn validate_resource_yaml(
+        &self,
+        namespace: &str,
+        yaml_content: &str,
+    ) -> Result<YamlValidation, String> {
+        let invalid = |diagnostics: Vec<YamlDiagnostic>| YamlValidation {
+            valid: false,
+            diagnostics,
+        };
+
+        let mut json_value: Value = match serde_yaml::from_str(yaml_content) {
+            Ok(value) => value,
+            Err(e) => return Ok(invalid(vec![validation::parse_error_diagnostic(&e)])),
+        };
+        let field = |value: &Value, path: &str| {
+            path.split('.')
+                .try_fold(value, |value, key| value.get(key))
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string())
+        };
+        let mut diagnostics = Vec::new();
+        for path in ["apiVersion", "kind", "metadata.name"] {
+            if field(&json_value, path).is_none() {
+                diagnostics.push(validation::field_diagnostic(
+                    yaml_content,
+                    path,
+                    format!("Missing '{}' field", path),
+                ));
+            }
+        }
+        if let Some(yaml_namespace) = field(&json_value, "metadata.namespace") {
+            if yaml_namespace != namespace {
+                diagnostics.push(validation::field_diagnostic(
+                    yaml_content,
+                    "metadata.namespace",
+                    format!(
+                        "Namespace mismatch: YAML specifies '{}' but request is for '{}'",
+                        yaml_namespace, namespace
+                    ),
+                ));
+            }
+        }
+        if !diagnostics.is_empty() {
+            return Ok(invalid(diagnostics));
+        }
+
+        let api_version = field(&json_value, "apiVersion").unwrap_or_default();
+        let kind = field(&json_value, "kind").unwrap_or_default();
+        let name = field(&json_value, "metadata.name").unwrap_or_default();
+        let client = Self::get_client()?;
+
+        let gvk = match api_version.parse::<GroupVersion>() {
+            Ok(group_version) => group_version.with_kind(&kind),
+            Err(e) => {
+                return Ok(invalid(vec![validation::field_diagnostic(
+                    yaml_content,
+                    "apiVersion",
+                    format!("Invalid apiVersion: {}", e),
+                )]))
+            }
+        };
+        let (resource, capabilities) = match kube::discovery::pinned_kind(&client, &gvk).await {
+            Ok(found) => found,
+            Err(kube::Error::Api(e)) if e.code == 404 => {
+                return Ok(invalid(vec![validation::field_diagnostic(
+                    yaml_content,
+                    "kind",
+                    format!("The cluster has no {} in {}", kind, api_version),
+                )]))
+            }
+            Err(e) => return Err(format!("Failed to look up {}: {}", kind, e)),
+        };
+
+        let api: Api<DynamicObject> = if capabilities.scope == Scope::Namespaced {
+            if let Some(metadata) = json_value
+                .get_mut("metadata")
+                .and_then(|m| m.as_object_mut())
+            {
+                metadata.insert(
+                    "namespace".to_string(),
+                    Value::String(namespace.to_string()),
+                );
+            }
+            Api::namespaced_with(client, namespace, &resource)
+        } else {
+            Api::all_with(client, &resource)
+        };
+        let object: DynamicObject = serde_json::from_value(json_value)
+            .map_err(|e| format!("Failed to read {}: {}", kind, e))?;
+        let params = PatchParams::apply("portal-desktop")
+            .dry_run()
+            .validation_strict();
+        match api.patch(&name, &params, &Patch::Apply(&object)).await {
+            Ok(_) => Ok(YamlValidation {
+                valid: true,
+                diagnostics: Vec::new(),
+            }),
+            Err(kube::Error::Api(e)) if (400..500).contains(&e.code) => Ok(invalid(
+                validation::server_diagnostics(&e.message, yaml_content),
+            )),
+            Err(e) => Err(format!("Failed to validate {}: {}", kind, e)),
+        }
+    }
+
     pub async fn apply_resource_yaml(
         &self,
         namespace: &str,
@@ -1916,6 +3024,55 @@ impl KubernetesManager {
         }
     }
 
+    /// Send a pod deletion as a server-side dry run: the API server validates
+    /// and admits it without removing anything. Returns what the deletion
+    /// would do.
+    pub async fn dry_run_delete_pod(
+        &self,
+        namespace: &str,
+        pod_name: &str,
+    ) -> Result<String, String> {
+        let client = Self::get_client()?;
+        let api: Api<Pod> = Api::namespaced(client, namespace);
+
+        let pod = api
+            .get(pod_name)
+            .await
+            .map_err(|e| format!("Failed to get pod: {}", e))?;
+        api.delete(
+            pod_name,
+            &kube::api::DeleteParams {
+                dry_run: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| format!("Dry run rejected by the API server: {}", e))?;
+
+        let phase = pod
+            .status
+            .as_ref()
+            .and_then(|status| status.phase.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let controller = pod
+            .metadata
+            .owner_references
+            .unwrap_or_default()
+            .into_iter()
+            .find(|owner| owner.controller == Some(true));
+        let outcome = match controller {
+            Some(owner) => format!(
+                "It is managed by {} {}, which will start a replacement.",
+                owner.kind, owner.name
+            ),
+            None => "No controller manages it, so it will not be recreated.".to_string(),
+        };
+        Ok(format!(
+            "pod/{} deleted (server dry run)\nNamespace: {}\nPhase: {}\n{}",
+            pod_name, namespace, phase, outcome
+        ))
+    }
+
     pub async fn scale_deployment(
         &self,
         namespace: &str,
@@ -2192,6 +3349,112 @@ impl KubernetesManager {
         }
     }
 
+    /// Add an ephemeral debug container to a pod, sharing `target_container`'s
+    /// process namespace (the first container's by default), and wait for it
+    /// to start. Lets distroless pods, which have no shell, be inspected with
+    /// the tools in `image`.
+    pub async fn debug_pod(
+        &self,
+        namespace: &str,
+        pod_name: &str,
+        image: Option<&str>,
+        target_container: Option<&str>,
+    ) -> Result<DebugContainerInfo, String> {
+        let client = Self::get_client()?;
+        let pods: Api<Pod> = Api::namespaced(client, namespace);
+        let pod = pods
+            .get(pod_name)
+            .await
+            .map_err(|e| format!("Failed to get pod: {}", e))?;
+        let spec = pod.spec.as_ref().ok_or("Pod has no spec")?;
+
+        let target_container = match target_container {
+            Some(target) if !spec.containers.iter().any(|c| c.name == target) => {
+                return Err(format!("Pod {} has no container {}", pod_name, target));
+            }
+            Some(target) => Some(target.to_string()),
+            None => spec.containers.first().map(|c| c.name.clone()),
+        };
+        let image = image
+            .map(str::trim)
+            .filter(|image| !image.is_empty())
+            .unwrap_or(DEFAULT_DEBUG_IMAGE)
+            .to_string();
+        let taken: HashSet<&str> = spec
+            .containers
+            .iter()
+            .map(|c| c.name.as_str())
+            .chain(
+                spec.init_containers
+                    .iter()
+                    .flatten()
+                    .map(|c| c.name.as_str()),
+            )
+            .chain(
+                spec.ephemeral_containers
+                    .iter()
+                    .flatten()
+                    .map(|c| c.name.as_str()),
+            )
+            .collect();
+        let container = debug_container_name(&taken);
+
+        let patch = serde_json::json!({
+            "spec": {
+                "ephemeralContainers": [{
+                    "name": container,
+                    "image": image,
+                    "targetContainerName": target_container,
+                    "stdin": true,
+                    "tty": true,
+                    "terminationMessagePolicy": "File",
+                }]
+            }
+        });
+        pods.patch_ephemeral_containers(
+            pod_name,
+            &PatchParams::default(),
+            &Patch::Strategic(patch),
+        )
+        .await
+        .map_err(|e| match e {
+            kube::Error::Api(response) if response.code == 404 => {
+                "The cluster doesn't support ephemeral containers".to_string()
+            }
+            e => format!("Failed to add debug container: {}", e),
+        })?;
+
+        let deadline = tokio::time::Instant::now() + DEBUG_CONTAINER_START_TIMEOUT;
+        loop {
+            let pod = pods
+                .get(pod_name)
+                .await
+                .map_err(|e| format!("Failed to get pod: {}", e))?;
+            if debug_container_started(&pod, &container)? {
+                break;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(format!(
+                    "Debug container {} hasn't started yet; attach to it once it has",
+                    container
+                ));
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+
+        Ok(DebugContainerInfo {
+            attach_command: format!(
+                "kubectl attach -it {} -c {} -n {}",
+                pod_name, container, namespace
+            ),
+            namespace: namespace.to_string(),
+            pod_name: pod_name.to_string(),
+            container,
+            target_container,
+            image,
+        })
+    }
+
     pub async fn start_port_forward(
         &self,
         namespace: &str,
@@ -2199,6 +3462,128 @@ impl KubernetesManager {
         local_port: u16,
         remote_port: u16,
     ) -> Result<PortForwardInfo, String> {
+        self.start_resource_port_forward(namespace, "pod", pod_name, local_port, remote_port, false)
+            .await
+    }
+
+    /// Forward to a pod, or to a ready pod behind a workload or service like
+    /// `kubectl port-forward svc/<name>`. For a service, `remote_port` is the
+    /// service's port and is translated to the port it targets on the pod.
+    /// With `auto_restore`, another ready pod is found whenever the forward
+    /// drops.
+    pub async fn start_resource_port_forward(
+        &self,
+        namespace: &str,
+        target_kind: &str,
+        target_name: &str,
+        local_port: u16,
+        remote_port: u16,
+        auto_restore: bool,
+    ) -> Result<PortForwardInfo, String> {
+        if !TARGET_KINDS.contains(&target_kind) {
+            return Err(format!("Can't forward to a {}", target_kind));
+        }
+        self.start_forward(PortForwardInfo {
+            id: Uuid::new_v4().to_string(),
+            namespace: namespace.to_string(),
+            pod_name: target_name.to_string(),
+            target: format!("{}/{}", target_kind, target_name),
+            local_port,
+            remote_port,
+            status: "Active".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            url: format!("http://localhost:{}", local_port),
+            saved_id: None,
+            auto_restore,
+        })
+        .await
+    }
+
+    pub async fn start_saved_port_forward(
+        &self,
+        saved: &SavedPortForward,
+    ) -> Result<PortForwardInfo, String> {
+        self.start_forward(PortForwardInfo {
+            id: Uuid::new_v4().to_string(),
+            namespace: saved.namespace.clone(),
+            pod_name: saved.target_name.clone(),
+            target: saved.target(),
+            local_port: saved.local_port,
+            remote_port: saved.remote_port,
+            status: "Active".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            url: format!("http://localhost:{}", saved.local_port),
+            saved_id: Some(saved.id.clone()),
+            auto_restore: saved.auto_restore,
+        })
+        .await
+    }
+
+    /// Start the auto-restore forwards among `saved` that aren't running
+    pub async fn restore_port_forwards(&self, saved: &[SavedPortForward]) -> PortForwardRestore {
+        let running: HashSet<String> = self
+            .list_port_forwards()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|info| info.saved_id)
+            .collect();
+
+        let mut restore = PortForwardRestore {
+            started: Vec::new(),
+            failed: Vec::new(),
+        };
+        for forward in saved
+            .iter()
+            .filter(|forward| forward.auto_restore && !running.contains(&forward.id))
+        {
+            match self.start_saved_port_forward(forward).await {
+                Ok(info) => restore.started.push(info),
+                Err(e) => restore.failed.push(format!(
+                    "{} ({}:{}): {}",
+                    forward.target(),
+                    forward.local_port,
+                    forward.remote_port,
+                    e
+                )),
+            }
+        }
+        restore
+    }
+
+    /// Stable target for a saved forward: a pod is swapped for the workload
+    /// that owns it, so the forward still resolves once the pod is replaced
+    pub async fn resolve_forward_target(
+        &self,
+        namespace: &str,
+        kind: &str,
+        name: &str,
+    ) -> Result<(String, String), String> {
+        if kind != "pod" {
+            return Ok((kind.to_string(), name.to_string()));
+        }
+        let client = Self::get_client()?;
+        let pods: Api<Pod> = Api::namespaced(client.clone(), namespace);
+        let pod = pods
+            .get(name)
+            .await
+            .map_err(|e| format!("Failed to get pod: {}", e))?;
+        let Some((owner_kind, owner_name)) = controller_of(&pod.metadata) else {
+            return Ok((kind.to_string(), name.to_string()));
+        };
+        if owner_kind != "replicaset" {
+            return Ok((owner_kind, owner_name));
+        }
+        // Deployments own their pods through a ReplicaSet
+        let replicasets: Api<ReplicaSet> = Api::namespaced(client, namespace);
+        let replicaset = replicasets
+            .get(&owner_name)
+            .await
+            .map_err(|e| format!("Failed to get replicaset: {}", e))?;
+        Ok(controller_of(&replicaset.metadata).unwrap_or((owner_kind, owner_name)))
+    }
+
+    async fn start_forward(&self, mut info: PortForwardInfo) -> Result<PortForwardInfo, String> {
         // Get or create port forward map
         let port_forwards = PORT_FORWARDS
             .get_or_init(|| Arc::new(Mutex::new(HashMap::new())))
@@ -2207,25 +3592,115 @@ impl KubernetesManager {
         // Check if port is already in use
         {
             let forwards = port_forwards.lock().await;
-            for (_, info) in forwards.values() {
-                if info.local_port == local_port {
-                    return Err(format!("Local port {} is already in use", local_port));
+            for (_, running) in forwards.values() {
+                if running.local_port == info.local_port {
+                    return Err(format!("Local port {} is already in use", info.local_port));
                 }
             }
         }
 
+        let (child, pod_name) = Self::spawn_port_forward(&info).await?;
+        info.pod_name = pod_name;
+        {
+            let mut forwards = port_forwards.lock().await;
+            forwards.insert(info.id.clone(), (child, info.clone()));
+        }
+        if info.auto_restore {
+            Self::ensure_port_forward_watchdog();
+        }
+        Ok(info)
+    }
+
+    /// Pod a forward to `target` reaches, and the port on it. Pods are used
+    /// as they are; other targets resolve to one of their ready pods, with a
+    /// service's port translated to the port it targets.
+    async fn backing_pod(
+        namespace: &str,
+        target: &str,
+        port: u16,
+    ) -> Result<(String, u16), String> {
+        let (kind, name) = target.split_once('/').unwrap_or(("pod", target));
+        if kind == "pod" {
+            return Ok((name.to_string(), port));
+        }
+        let client = Self::get_client()?;
+        let failed = |e: kube::Error| format!("Failed to get {}: {}", target, e);
+
+        let mut service = None;
+        let selector = match kind {
+            "service" => {
+                let found = Api::<Service>::namespaced(client.clone(), namespace)
+                    .get(name)
+                    .await
+                    .map_err(failed)?;
+                let labels = found.spec.as_ref().and_then(|spec| spec.selector.clone());
+                service = Some(found);
+                labels.map(|labels| LabelSelector {
+                    match_labels: Some(labels),
+                    ..Default::default()
+                })
+            }
+            "deployment" => Api::<Deployment>::namespaced(client.clone(), namespace)
+                .get(name)
+                .await
+                .map_err(failed)?
+                .spec
+                .map(|spec| spec.selector),
+            "replicaset" => Api::<ReplicaSet>::namespaced(client.clone(), namespace)
+                .get(name)
+                .await
+                .map_err(failed)?
+                .spec
+                .map(|spec| spec.selector),
+            "statefulset" => Api::<StatefulSet>::namespaced(client.clone(), namespace)
+                .get(name)
+                .await
+                .map_err(failed)?
+                .spec
+                .map(|spec| spec.selector),
+            "daemonset" => Api::<DaemonSet>::namespaced(client.clone(), namespace)
+                .get(name)
+                .await
+                .map_err(failed)?
+                .spec
+                .map(|spec| spec.selector),
+            _ => return Err(format!("Can't forward to a {}", kind)),
+        };
+        let query = selector
+            .map(|selector| label_selector_query(&selector))
+            .filter(|query| !query.is_empty())
+            .ok_or_else(|| format!("{} has no selector, so no pods to forward to", target))?;
+
+        let pods = Api::<Pod>::namespaced(client, namespace)
+            .list(&ListParams::default().labels(&query))
+            .await
+            .map_err(|e| format!("Failed to list pods: {}", e))?;
+        let pod = pick_ready_pod(&pods.items)
+            .ok_or_else(|| format!("No ready pods behind {}", target))?;
+        let pod_port = match &service {
+            Some(service) => service_target_port(service, pod, port)?,
+            None => port,
+        };
+        Ok((pod.metadata.name.clone().unwrap_or_default(), pod_port))
+    }
+
+    /// Run `kubectl port-forward` to the pod behind `info`, failing if it
+    /// exits straight away. Returns the process and the pod's name.
+    async fn spawn_port_forward(info: &PortForwardInfo) -> Result<(Child, String), String> {
         // Use kubectl port-forward for simplicity
         use std::process::Stdio;
         use tokio::process::Command;
 
+        let (pod_name, pod_port) =
+            Self::backing_pod(&info.namespace, &info.target, info.remote_port).await?;
         let mut cmd = Command::new("kubectl");
         cmd.no_window();
-        cmd.args(&[
+        cmd.args([
             "port-forward",
             "-n",
-            namespace,
+            &info.namespace,
             &format!("pod/{}", pod_name),
-            &format!("{}:{}", local_port, remote_port),
+            &format!("{}:{}", info.local_port, pod_port),
         ]);
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -2244,28 +3719,7 @@ impl KubernetesManager {
                             Err(format!("Port forward failed with status: {}", status))
                         }
                     }
-                    Ok(None) => {
-                        // Process is running, create info and store it
-                        let id = Uuid::new_v4().to_string();
-                        let info = PortForwardInfo {
-                            id: id.clone(),
-                            namespace: namespace.to_string(),
-                            pod_name: pod_name.to_string(),
-                            local_port,
-                            remote_port,
-                            status: "Active".to_string(),
-                            created_at: chrono::Utc::now().to_rfc3339(),
-                            url: format!("http://localhost:{}", local_port),
-                        };
-
-                        // Store the process and info
-                        {
-                            let mut forwards = port_forwards.lock().await;
-                            forwards.insert(id.clone(), (child, info.clone()));
-                        }
-
-                        Ok(info)
-                    }
+                    Ok(None) => Ok((child, pod_name)),
                     Err(e) => Err(format!("Failed to check port forward status: {}", e)),
                 }
             }
@@ -2273,6 +3727,54 @@ impl KubernetesManager {
         }
     }
 
+    /// Start the task that restarts auto-restore forwards when they drop
+    fn ensure_port_forward_watchdog() {
+        PORT_FORWARD_WATCHDOG.get_or_init(|| {
+            tokio::spawn(async {
+                loop {
+                    tokio::time::sleep(PORT_FORWARD_RESTART_INTERVAL).await;
+                    Self::restart_dropped_port_forwards().await;
+                }
+            })
+        });
+    }
+
+    /// Restart auto-restore forwards whose kubectl has exited, which is what
+    /// happens when the pod behind them goes away. Workloads and services are
+    /// resolved to a ready pod again.
+    async fn restart_dropped_port_forwards() {
+        let Some(port_forwards) = PORT_FORWARDS.get() else {
+            return;
+        };
+        let mut dropped = Vec::new();
+        for (child, info) in port_forwards.lock().await.values_mut() {
+            if info.auto_restore && !matches!(child.try_wait(), Ok(None)) {
+                dropped.push(info.clone());
+            }
+        }
+
+        for info in dropped {
+            let restarted = Self::spawn_port_forward(&info).await;
+            let mut forwards = port_forwards.lock().await;
+            match (forwards.get_mut(&info.id), restarted) {
+                (Some((child, running)), Ok((new_child, pod_name))) => {
+                    *child = new_child;
+                    running.pod_name = pod_name;
+                    running.status = "Active".to_string();
+                }
+                (Some((_, running)), Err(e)) => {
+                    eprintln!("Failed to restore port forward {}: {}", info.target, e);
+                    running.status = "Reconnecting".to_string();
+                }
+                // Stopped while restarting
+                (None, Ok((mut new_child, _))) => {
+                    let _ = new_child.start_kill();
+                }
+                (None, Err(_)) => {}
+            }
+        }
+    }
+
     pub async fn list_port_forwards(&self) -> Result<Vec<PortForwardInfo>, String> {
         let port_forwards = PORT_FORWARDS
             .get_or_init(|| Arc::new(Mutex::new(HashMap::new())))
@@ -2286,16 +3788,17 @@ impl KubernetesManager {
         for (id, (child, info)) in forwards.iter_mut() {
             // Check if process is still running
             match child.try_wait() {
-                Ok(Some(_)) => {
-                    // Process has exited, mark for removal
-                    dead_ids.push(id.clone());
-                }
                 Ok(None) => {
                     // Process is still running
                     active_forwards.push(info.clone());
                 }
-                Err(_) => {
-                    // Error checking, assume it's dead
+                _ if info.auto_restore => {
+                    // Dropped, and the watchdog will restart it
+                    info.status = "Reconnecting".to_string();
+                    active_forwards.push(info.clone());
+                }
+                _ => {
+                    // Process has exited, or can't be checked; mark for removal
                     dead_ids.push(id.clone());
                 }
             }
@@ -2331,6 +3834,21 @@ impl KubernetesManager {
         }
     }
 
+    /// Stop the running forwards started from a saved forward
+    pub async fn stop_saved_port_forward(&self, saved_id: &str) {
+        let ids: Vec<String> = self
+            .list_port_forwards()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|info| info.saved_id.as_deref() == Some(saved_id))
+            .map(|info| info.id)
+            .collect();
+        for id in ids {
+            let _ = self.stop_port_forward(&id).await;
+        }
+    }
+
     /// Stop a specific watch task
     async fn stop_watch(&self, resource_type: &str, namespace: &str) {
         let watch_tasks = WATCH_TASKS.get();
@@ -2364,3 +3882,670 @@ impl KubernetesManager {
         }
     }
 }
+
+/// What a drain does with a pod on the node
+#[derive(Debug, PartialEq)]
+enum DrainDisposition {
+    Evict,
+    /// Left running: the drain goes ahead without it
+    Skip(&'static str),
+    /// Can't be evicted safely, so the drain doesn't start
+    Refuse(&'static str),
+}
+
+/// Follows `kubectl drain`: mirror pods are skipped, DaemonSet pods skipped
+/// or refused, and running pods without a controller or with emptyDir data
+/// refused unless the options allow losing them.
+fn drain_disposition(pod: &Pod, options: &DrainOptions) -> DrainDisposition {
+    if pod
+        .metadata
+        .annotations
+        .as_ref()
+        .is_some_and(|annotations| annotations.contains_key("kubernetes.io/config.mirror"))
+    {
+        return DrainDisposition::Skip("mirror pod managed by the kubelet");
+    }
+    let controller = pod
+        .metadata
+        .owner_references
+        .as_ref()
+        .and_then(|owners| owners.iter().find(|owner| owner.controller == Some(true)));
+    if controller.is_some_and(|owner| owner.kind == "DaemonSet") {
+        return if options.ignore_daemonsets {
+            DrainDisposition::Skip("managed by a DaemonSet")
+        } else {
+            DrainDisposition::Refuse("managed by a DaemonSet; set ignore_daemonsets to skip it")
+        };
+    }
+    let finished = matches!(
+        pod.status
+            .as_ref()
+            .and_then(|status| status.phase.as_deref()),
+        Some("Succeeded") | Some("Failed")
+    );
+    if finished {
+        return DrainDisposition::Evict;
+    }
+    if controller.is_none() && !options.force {
+        return DrainDisposition::Refuse(
+            "not managed by a controller, so it won't be recreated; set force to evict it",
+        );
+    }
+    let uses_empty_dir = pod
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.volumes.as_ref())
+        .is_some_and(|volumes| volumes.iter().any(|volume| volume.empty_dir.is_some()));
+    if uses_empty_dir && !options.delete_emptydir_data {
+        return DrainDisposition::Refuse(
+            "uses emptyDir storage that eviction deletes; set delete_emptydir_data to evict it",
+        );
+    }
+    DrainDisposition::Evict
+}
+
+/// Resource quantities as the strings the API reports them in
+/// "succeeded" or "failed" once a job has finished, from its conditions
+fn finished_job_status(job: &Job) -> Option<&'static str> {
+    let conditions = job.status.as_ref()?.conditions.as_ref()?;
+    conditions
+        .iter()
+        .filter(|condition| condition.status == "True")
+        .find_map(|condition| match condition.type_.as_str() {
+            "Complete" => Some("succeeded"),
+            "Failed" => Some("failed"),
+            _ => None,
+        })
+}
+
+/// Name for a job triggered by hand from a CronJob, kept within the 63
+/// characters a job name can have
+fn manual_job_name(cronjob: &str, now: chrono::DateTime<chrono::Utc>) -> String {
+    let suffix = format!("-manual-{}", now.timestamp());
+    let prefix: String = cronjob.chars().take(63 - suffix.len()).collect();
+    format!("{}{}", prefix.trim_end_matches('-'), suffix)
+}
+
+fn quantities(map: Option<&BTreeMap<String, Quantity>>) -> HashMap<String, String> {
+    map.map(|m| m.iter().map(|(k, v)| (k.clone(), v.0.clone())).collect())
+        .unwrap_or_default()
+}
+
+/// Kind (lowercased, as kubectl takes it) and name of the controller that
+/// owns a resource
+fn controller_of(metadata: &ObjectMeta) -> Option<(String, String)> {
+    metadata
+        .owner_references
+        .iter()
+        .flatten()
+        .find(|owner| owner.controller == Some(true))
+        .map(|owner| (owner.kind.to_lowercase(), owner.name.clone()))
+}
+
+/// A label selector as a list query, e.g. `app=web,tier in (front,back)`
+fn label_selector_query(selector: &LabelSelector) -> String {
+    let labels = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(key, value)| format!("{}={}", key, value));
+    let expressions = selector
+        .match_expressions
+        .iter()
+        .flatten()
+        .map(|expression| {
+            let values = expression.values.clone().unwrap_or_default().join(",");
+            match expression.operator.as_str() {
+                "In" => format!("{} in ({})", expression.key, values),
+                "NotIn" => format!("{} notin ({})", expression.key, values),
+                "DoesNotExist" => format!("!{}", expression.key),
+                _ => expression.key.clone(),
+            }
+        });
+    labels.chain(expressions).collect::<Vec<_>>().join(",")
+}
+
+/// First pod that's ready and not shutting down
+fn pick_ready_pod(pods: &[Pod]) -> Option<&Pod> {
+    pods.iter().find(|pod| {
+        pod.metadata.deletion_timestamp.is_none()
+            && pod
+                .status
+                .as_ref()
+                .and_then(|status| status.conditions.as_ref())
+                .is_some_and(|conditions| {
+                    conditions
+                        .iter()
+                        .any(|condition| condition.type_ == "Ready" && condition.status == "True")
+                })
+    })
+}
+
+/// Port on `pod` that a service's `port` sends traffic to, following a
+/// named target port to the container port of that name
+fn service_target_port(service: &Service, pod: &Pod, port: u16) -> Result<u16, String> {
+    let service_name = service.metadata.name.as_deref().unwrap_or_default();
+    let service_port = service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.ports.as_ref())
+        .into_iter()
+        .flatten()
+        .find(|service_port| service_port.port == i32::from(port))
+        .ok_or_else(|| format!("Service {} has no port {}", service_name, port))?;
+
+    let target = match &service_port.target_port {
+        None => return Ok(port),
+        Some(IntOrString::Int(target)) => *target,
+        Some(IntOrString::String(target)) => match target.parse() {
+            Ok(target) => target,
+            Err(_) => pod
+                .spec
+                .iter()
+                .flat_map(|spec| &spec.containers)
+                .flat_map(|container| container.ports.iter().flatten())
+                .find(|container_port| container_port.name.as_deref() == Some(target.as_str()))
+                .map(|container_port| container_port.container_port)
+                .ok_or_else(|| {
+                    format!(
+                        "Pod {} has no port named {}",
+                        pod.metadata.name.as_deref().unwrap_or_default(),
+                        target
+                    )
+                })?,
+        },
+    };
+    u16::try_from(target).map_err(|_| format!("Invalid target port {}", target))
+}
+
+/// Image for debug containers when none is given
+const DEFAULT_DEBUG_IMAGE: &str = "busybox:1.36";
+
+/// How long a debug container gets to start, which includes pulling its image
+const DEBUG_CONTAINER_START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A `debugger-xxxxx` name, as kubectl picks, not used by another container
+fn debug_container_name(taken: &HashSet<&str>) -> String {
+    loop {
+        let name = format!("debugger-{}", &Uuid::new_v4().simple().to_string()[..5]);
+        if !taken.contains(name.as_str()) {
+            return name;
+        }
+    }
+}
+
+/// Whether the pod's ephemeral container `name` is running, or why it won't
+fn debug_container_started(pod: &Pod, name: &str) -> Result<bool, String> {
+    let state = pod
+        .status
+        .as_ref()
+        .and_then(|status| status.ephemeral_container_statuses.as_ref())
+        .and_then(|statuses| statuses.iter().find(|status| status.name == name))
+        .and_then(|status| status.state.as_ref());
+    let Some(state) = state else {
+        return Ok(false);
+    };
+    if state.running.is_some() {
+        return Ok(true);
+    }
+    if let Some(terminated) = &state.terminated {
+        return Err(format!(
+            "Debug container exited: {}",
+            terminated
+                .message
+                .as_deref()
+                .or(terminated.reason.as_deref())
+                .unwrap_or("no reason given")
+        ));
+    }
+    match state.waiting.as_ref() {
+        Some(waiting)
+            if matches!(
+                waiting.reason.as_deref(),
+                Some(
+                    "ErrImagePull"
+                        | "ImagePullBackOff"
+                        | "InvalidImageName"
+                        | "CreateContainerError"
+                        | "CreateContainerConfigError"
+                )
+            ) =>
+        {
+            Err(format!(
+                "Debug container can't start: {}",
+                waiting
+                    .message
+                    .as_deref()
+                    .or(waiting.reason.as_deref())
+                    .unwrap_or_default()
+            ))
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Namespaces Kubernetes relies on, which are never deleted
+const PROTECTED_NAMESPACES: [&str; 4] =
+    ["default", "kube-system", "kube-public", "kube-node-lease"];
+
+/// Warning events shown in a namespace summary
+const NAMESPACE_SUMMARY_WARNINGS: usize = 20;
+
+/// Check `name` is a valid namespace name (an RFC 1123 label)
+fn validate_namespace_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 63
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-')
+        && !name.ends_with('-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid namespace name '{}': use at most 63 lowercase letters, digits and '-', starting and ending with a letter or digit",
+            name
+        ))
+    }
+}
+
+/// Used and hard limits of a quota, by resource
+fn quota_usage(quota: &ResourceQuota) -> Vec<QuotaUsage> {
+    let status = quota.status.as_ref();
+    let hard = status
+        .and_then(|status| status.hard.as_ref())
+        .or_else(|| quota.spec.as_ref().and_then(|spec| spec.hard.as_ref()));
+    let used = status.and_then(|status| status.used.as_ref());
+    hard.into_iter()
+        .flatten()
+        .map(|(resource, hard)| {
+            let used = used
+                .and_then(|used| used.get(resource))
+                .map(|used| used.0.clone())
+                .unwrap_or_else(|| "0".to_string());
+            let percent = match (parse_quantity(&used), parse_quantity(&hard.0)) {
+                (Some(used), Some(hard)) if hard > 0.0 => Some(used / hard * 100.0),
+                _ => None,
+            };
+            QuotaUsage {
+                resource: resource.clone(),
+                hard: hard.0.clone(),
+                used,
+                percent,
+            }
+        })
+        .collect()
+}
+
+/// Parse a quantity such as 10Gi, 500M, 1.5Ti or 250m into a number
+fn parse_quantity(quantity: &str) -> Option<f64> {
+    let quantity = quantity.trim();
+    let split = quantity
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(quantity.len());
+    let (number, suffix) = quantity.split_at(split);
+    let multiplier: f64 = match suffix {
+        "" => 1.0,
+        "m" => 1e-3,
+        "Ki" => 1024f64,
+        "Mi" => 1024f64.powi(2),
+        "Gi" => 1024f64.powi(3),
+        "Ti" => 1024f64.powi(4),
+        "Pi" => 1024f64.powi(5),
+        "Ei" => 1024f64.powi(6),
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        _ => return None,
+    };
+    let number: f64 = number.parse().ok()?;
+    (number >= 0.0).then_some(number * multiplier)
+}
+
+/// Age of a resource from its creation timestamp
+fn resource_age(metadata: &ObjectMeta) -> String {
+    metadata
+        .creation_timestamp
+        .as_ref()
+        .map(|created| format_age(chrono::Utc::now() - created.0))
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Age in kubectl's short form: 45s, 12m, 5h, 3d
+fn format_age(age: chrono::Duration) -> String {
+    if age.num_days() > 0 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h", age.num_hours())
+    } else if age.num_minutes() > 0 {
+        format!("{}m", age.num_minutes())
+    } else {
+        format!("{}s", age.num_seconds().max(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{EmptyDirVolumeSource, PodSpec, Volume};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+
+    fn owned_pod(kind: &str) -> Pod {
+        let mut pod = Pod::default();
+        pod.metadata.owner_references = Some(vec![OwnerReference {
+            kind: kind.to_string(),
+            controller: Some(true),
+            ..Default::default()
+        }]);
+        pod
+    }
+
+    #[test]
+    fn drain_skips_or_refuses_unsafe_pods() {
+        let options = DrainOptions::default();
+        assert_eq!(
+            drain_disposition(&owned_pod("ReplicaSet"), &options),
+            DrainDisposition::Evict
+        );
+        assert!(matches!(
+            drain_disposition(&owned_pod("DaemonSet"), &options),
+            DrainDisposition::Skip(_)
+        ));
+        assert!(matches!(
+            drain_disposition(&Pod::default(), &options),
+            DrainDisposition::Refuse(_)
+        ));
+
+        let mut with_scratch = owned_pod("ReplicaSet");
+        with_scratch.spec = Some(PodSpec {
+            volumes: Some(vec![Volume {
+                name: "scratch".to_string(),
+                empty_dir: Some(EmptyDirVolumeSource::default()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+        assert!(matches!(
+            drain_disposition(&with_scratch, &options),
+            DrainDisposition::Refuse(_)
+        ));
+        let options = DrainOptions {
+            delete_emptydir_data: true,
+            ..DrainOptions::default()
+        };
+        assert_eq!(
+            drain_disposition(&with_scratch, &options),
+            DrainDisposition::Evict
+        );
+    }
+
+    #[test]
+    fn parses_quantities() {
+        assert_eq!(parse_quantity("10Gi"), Some(10.0 * 1024f64.powi(3)));
+        assert_eq!(parse_quantity("500M"), Some(5e8));
+        assert_eq!(parse_quantity("1024"), Some(1024.0));
+        assert_eq!(parse_quantity("250m"), Some(0.25));
+        assert!(parse_quantity("1Gi").unwrap() > parse_quantity("1G").unwrap());
+        assert_eq!(parse_quantity("10GB"), None);
+        assert_eq!(parse_quantity("Gi"), None);
+    }
+
+    #[test]
+    fn formats_ages_like_kubectl() {
+        assert_eq!(format_age(chrono::Duration::seconds(45)), "45s");
+        assert_eq!(format_age(chrono::Duration::minutes(90)), "1h");
+        assert_eq!(format_age(chrono::Duration::days(3)), "3d");
+    }
+
+    #[test]
+    fn finished_jobs_are_read_from_conditions() {
+        use k8s_openapi::api::batch::v1::{JobCondition, JobStatus};
+
+        let job = |conditions: &[(&str, &str)]| Job {
+            status: Some(JobStatus {
+                conditions: Some(
+                    conditions
+                        .iter()
+                        .map(|(type_, status)| JobCondition {
+                            type_: type_.to_string(),
+                            status: status.to_string(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            finished_job_status(&job(&[("Complete", "True")])),
+            Some("succeeded")
+        );
+        assert_eq!(
+            finished_job_status(&job(&[("Suspended", "False"), ("Failed", "True")])),
+            Some("failed")
+        );
+        assert_eq!(finished_job_status(&job(&[("Complete", "False")])), None);
+        assert_eq!(finished_job_status(&Job::default()), None);
+    }
+
+    #[test]
+    fn manual_job_names_fit_the_name_limit() {
+        let now = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(manual_job_name("backup", now), "backup-manual-1700000000");
+
+        let long = format!("{}-x", "a".repeat(44));
+        let name = manual_job_name(&long, now);
+        assert!(name.len() <= 63);
+        assert_eq!(name, format!("{}-manual-1700000000", "a".repeat(44)));
+    }
+
+    #[test]
+    fn validates_namespace_names() {
+        assert!(validate_namespace_name("team-a2").is_ok());
+        assert!(validate_namespace_name("Team").is_err());
+        assert!(validate_namespace_name("-a").is_err());
+        assert!(validate_namespace_name("a.b").is_err());
+        assert!(validate_namespace_name(&"a".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn quota_usage_reports_percent_used() {
+        use k8s_openapi::api::core::v1::ResourceQuotaStatus;
+
+        let quantities = |pairs: &[(&str, &str)]| {
+            Some(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), Quantity(v.to_string())))
+                    .collect(),
+            )
+        };
+        let quota = ResourceQuota {
+            status: Some(ResourceQuotaStatus {
+                hard: quantities(&[("limits.cpu", "2"), ("requests.memory", "1Gi")]),
+                used: quantities(&[("limits.cpu", "500m")]),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            quota_usage(&quota),
+            vec![
+                QuotaUsage {
+                    resource: "limits.cpu".to_string(),
+                    hard: "2".to_string(),
+                    used: "500m".to_string(),
+                    percent: Some(25.0),
+                },
+                QuotaUsage {
+                    resource: "requests.memory".to_string(),
+                    hard: "1Gi".to_string(),
+                    used: "0".to_string(),
+                    percent: Some(0.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_the_controlling_owner() {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+
+        let metadata = ObjectMeta {
+            owner_references: Some(vec![
+                OwnerReference {
+                    kind: "ConfigMap".to_string(),
+                    name: "settings".to_string(),
+                    ..Default::default()
+                },
+                OwnerReference {
+                    kind: "StatefulSet".to_string(),
+                    name: "db".to_string(),
+                    controller: Some(true),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            controller_of(&metadata),
+            Some(("statefulset".to_string(), "db".to_string()))
+        );
+        assert_eq!(controller_of(&ObjectMeta::default()), None);
+    }
+
+    #[test]
+    fn builds_label_selector_queries() {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelectorRequirement;
+
+        let selector = LabelSelector {
+            match_labels: Some(BTreeMap::from([("app".to_string(), "web".to_string())])),
+            match_expressions: Some(vec![
+                LabelSelectorRequirement {
+                    key: "tier".to_string(),
+                    operator: "In".to_string(),
+                    values: Some(vec!["front".to_string(), "back".to_string()]),
+                },
+                LabelSelectorRequirement {
+                    key: "canary".to_string(),
+                    operator: "DoesNotExist".to_string(),
+                    values: None,
+                },
+            ]),
+        };
+        assert_eq!(
+            label_selector_query(&selector),
+            "app=web,tier in (front,back),!canary"
+        );
+    }
+
+    #[test]
+    fn forwards_to_a_ready_pod_on_the_service_target_port() {
+        use k8s_openapi::api::core::v1::{
+            Container, ContainerPort, PodCondition, PodStatus, ServicePort, ServiceSpec,
+        };
+
+        let pod = |name: &str, ready: &str| Pod {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    ports: Some(vec![ContainerPort {
+                        name: Some("http".to_string()),
+                        container_port: 8080,
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                conditions: Some(vec![PodCondition {
+                    type_: "Ready".to_string(),
+                    status: ready.to_string(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+        };
+        let pods = [pod("web-1", "False"), pod("web-2", "True")];
+        let ready = pick_ready_pod(&pods).unwrap();
+        assert_eq!(ready.metadata.name.as_deref(), Some("web-2"));
+
+        let service = Service {
+            spec: Some(ServiceSpec {
+                ports: Some(vec![
+                    ServicePort {
+                        port: 80,
+                        target_port: Some(IntOrString::String("http".to_string())),
+                        ..Default::default()
+                    },
+                    ServicePort {
+                        port: 9090,
+                        target_port: Some(IntOrString::Int(9091)),
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(service_target_port(&service, ready, 80), Ok(8080));
+        assert_eq!(service_target_port(&service, ready, 9090), Ok(9091));
+        assert!(service_target_port(&service, ready, 443).is_err());
+    }
+
+    #[test]
+    fn debug_containers_report_why_they_wont_start() {
+        use k8s_openapi::api::core::v1::{
+            ContainerState, ContainerStateRunning, ContainerStateWaiting, ContainerStatus,
+            PodStatus,
+        };
+
+        let pod = |state: ContainerState| Pod {
+            status: Some(PodStatus {
+                ephemeral_container_statuses: Some(vec![ContainerStatus {
+                    name: "debugger-abcde".to_string(),
+                    state: Some(state),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let waiting = |reason: &str| ContainerState {
+            waiting: Some(ContainerStateWaiting {
+                reason: Some(reason.to_string()),
+                message: None,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            debug_container_started(&Pod::default(), "debugger-abcde"),
+            Ok(false)
+        );
+        assert_eq!(
+            debug_container_started(&pod(waiting("ContainerCreating")), "debugger-abcde"),
+            Ok(false)
+        );
+        assert_eq!(
+            debug_container_started(&pod(waiting("ImagePullBackOff")), "debugger-abcde"),
+            Err("Debug container can't start: ImagePullBackOff".to_string())
+        );
+        let running = ContainerState {
+            running: Some(ContainerStateRunning::default()),
+            ..Default::default()
+        };
+        assert_eq!(
+            debug_container_started(&pod(running), "debugger-abcde"),
+            Ok(true)
+        );
+    }
+}
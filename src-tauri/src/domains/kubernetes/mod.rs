@@ -1,7 +1,12 @@
+pub mod ai_tools;
 pub mod commands;
+pub mod config_data;
+pub mod kubeconfig;
 pub mod manager;
+pub mod port_forwards;
 pub mod services;
 pub mod types;
+pub mod validation;
 
 // KubernetesManager is used via State in commands, not directly imported
 // pub use manager::KubernetesManager;
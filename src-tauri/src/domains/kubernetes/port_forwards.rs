@@ -0,0 +1,251 @@
+//! Saved port forwards.
+//!
+//! A saved forward names what it forwards to rather than a pod, so it can be
+//! started again after the pod it first reached is gone: `kubectl
+//! port-forward` resolves a workload or service to one of its pods each time
+//! it starts. Forwards marked auto-restore are started on connecting to
+//! their cluster and restarted whenever they drop.
+
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entities::port_forward::{ActiveModel, Column, Entity, Model};
+
+/// Kinds of resource a forward can target
+pub const TARGET_KINDS: [&str; 6] = [
+    "pod",
+    "deployment",
+    "replicaset",
+    "statefulset",
+    "daemonset",
+    "service",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPortForward {
+    pub id: String,
+    pub cluster: String,
+    pub namespace: String,
+    pub target_kind: String,
+    pub target_name: String,
+    pub local_port: u16,
+    pub remote_port: u16,
+    pub auto_restore: bool,
+}
+
+impl SavedPortForward {
+    /// Target as kubectl takes it, e.g. `deployment/web`
+    pub fn target(&self) -> String {
+        format!("{}/{}", self.target_kind, self.target_name)
+    }
+}
+
+impl From<Model> for SavedPortForward {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            cluster: model.cluster,
+            namespace: model.namespace,
+            target_kind: model.target_kind,
+            target_name: model.target_name,
+            local_port: model.local_port as u16,
+            remote_port: model.remote_port as u16,
+            auto_restore: model.auto_restore,
+        }
+    }
+}
+
+/// Fields of a saved forward, when creating or updating one
+#[derive(Debug, Clone, Deserialize)]
+pub struct SavedPortForwardInput {
+    pub namespace: String,
+    pub target_kind: String,
+    pub target_name: String,
+    pub local_port: u16,
+    pub remote_port: u16,
+    #[serde(default = "default_auto_restore")]
+    pub auto_restore: bool,
+}
+
+fn default_auto_restore() -> bool {
+    true
+}
+
+impl SavedPortForwardInput {
+    fn validate(&self) -> Result<(), String> {
+        if self.namespace.trim().is_empty() || self.target_name.trim().is_empty() {
+            return Err("Namespace and target name are required".to_string());
+        }
+        if !TARGET_KINDS.contains(&self.target_kind.as_str()) {
+            return Err(format!(
+                "Can't forward to a {}; expected one of {}",
+                self.target_kind,
+                TARGET_KINDS.join(", ")
+            ));
+        }
+        if self.local_port == 0 || self.remote_port == 0 {
+            return Err("Ports must be between 1 and 65535".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Saved forwards on `cluster`, or on every cluster
+pub async fn list_saved(
+    db: &DatabaseConnection,
+    cluster: Option<&str>,
+) -> Result<Vec<SavedPortForward>, String> {
+    let mut query = Entity::find();
+    if let Some(cluster) = cluster {
+        query = query.filter(Column::Cluster.eq(cluster));
+    }
+    let mut saved: Vec<SavedPortForward> = query
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to list saved port forwards: {}", e))?
+        .into_iter()
+        .map(SavedPortForward::from)
+        .collect();
+    saved.sort_by_key(|forward| forward.local_port);
+    Ok(saved)
+}
+
+pub async fn get_saved(db: &DatabaseConnection, id: &str) -> Result<SavedPortForward, String> {
+    Entity::find_by_id(id.to_string())
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to get saved port forward: {}", e))?
+        .map(SavedPortForward::from)
+        .ok_or_else(|| format!("Saved port forward {} not found", id))
+}
+
+/// Refuse a second saved forward on the same local port of a cluster, as
+/// only one of them could ever run
+async fn check_local_port(
+    db: &DatabaseConnection,
+    cluster: &str,
+    local_port: u16,
+    except: Option<&str>,
+) -> Result<(), String> {
+    let taken = list_saved(db, Some(cluster))
+        .await?
+        .into_iter()
+        .any(|forward| forward.local_port == local_port && Some(forward.id.as_str()) != except);
+    if taken {
+        return Err(format!(
+            "Local port {} is already used by a saved port forward",
+            local_port
+        ));
+    }
+    Ok(())
+}
+
+pub async fn create_saved(
+    db: &DatabaseConnection,
+    cluster: &str,
+    input: SavedPortForwardInput,
+) -> Result<SavedPortForward, String> {
+    input.validate()?;
+    check_local_port(db, cluster, input.local_port, None).await?;
+    let now = Utc::now();
+    ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        cluster: Set(cluster.to_string()),
+        namespace: Set(input.namespace.trim().to_string()),
+        target_kind: Set(input.target_kind),
+        target_name: Set(input.target_name.trim().to_string()),
+        local_port: Set(input.local_port as i32),
+        remote_port: Set(input.remote_port as i32),
+        auto_restore: Set(input.auto_restore),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+    }
+    .insert(db)
+    .await
+    .map(SavedPortForward::from)
+    .map_err(|e| format!("Failed to save port forward: {}", e))
+}
+
+pub async fn update_saved(
+    db: &DatabaseConnection,
+    id: &str,
+    input: SavedPortForwardInput,
+) -> Result<SavedPortForward, String> {
+    input.validate()?;
+    let model = Entity::find_by_id(id.to_string())
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to get saved port forward: {}", e))?
+        .ok_or_else(|| format!("Saved port forward {} not found", id))?;
+    check_local_port(db, &model.cluster, input.local_port, Some(id)).await?;
+
+    let mut forward: ActiveModel = model.into();
+    forward.namespace = Set(input.namespace.trim().to_string());
+    forward.target_kind = Set(input.target_kind);
+    forward.target_name = Set(input.target_name.trim().to_string());
+    forward.local_port = Set(input.local_port as i32);
+    forward.remote_port = Set(input.remote_port as i32);
+    forward.auto_restore = Set(input.auto_restore);
+    forward.updated_at = Set(Utc::now().into());
+    forward
+        .update(db)
+        .await
+        .map(SavedPortForward::from)
+        .map_err(|e| format!("Failed to update saved port forward: {}", e))
+}
+
+pub async fn delete_saved(db: &DatabaseConnection, id: &str) -> Result<(), String> {
+    let result = Entity::delete_by_id(id.to_string())
+        .exec(db)
+        .await
+        .map_err(|e| format!("Failed to delete saved port forward: {}", e))?;
+    if result.rows_affected == 0 {
+        return Err(format!("Saved port forward {} not found", id));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_targets_and_ports() {
+        let input = SavedPortForwardInput {
+            namespace: "default".to_string(),
+            target_kind: "deployment".to_string(),
+            target_name: "web".to_string(),
+            local_port: 8080,
+            remote_port: 80,
+            auto_restore: true,
+        };
+        assert!(input.validate().is_ok());
+        assert!(SavedPortForwardInput {
+            target_kind: "ingress".to_string(),
+            ..input.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(SavedPortForwardInput {
+            local_port: 0,
+            ..input
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn auto_restore_defaults_on() {
+        let input: SavedPortForwardInput = serde_json::from_value(serde_json::json!({
+            "namespace": "default",
+            "target_kind": "service",
+            "target_name": "api",
+            "local_port": 5432,
+            "remote_port": 5432,
+        }))
+        .unwrap();
+        assert!(input.auto_restore);
+    }
+}
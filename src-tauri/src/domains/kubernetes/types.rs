@@ -126,6 +126,164 @@ pub struct NamespaceInfo {
     pub labels: std::collections::HashMap<String, String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkloadCounts {
+    pub pods: usize,
+    pub running_pods: usize,
+    pub deployments: usize,
+    pub statefulsets: usize,
+    pub daemonsets: usize,
+    pub jobs: usize,
+    pub cronjobs: usize,
+    pub services: usize,
+    pub configmaps: usize,
+    pub secrets: usize,
+    pub persistent_volume_claims: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub resource: String,
+    pub hard: String,
+    pub used: String,
+    /// Share of `hard` in use, when both are quantities
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceQuotaInfo {
+    pub name: String,
+    pub usage: Vec<QuotaUsage>,
+}
+
+/// What's in a namespace, for its overview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceSummary {
+    pub namespace: NamespaceInfo,
+    pub workloads: WorkloadCounts,
+    pub quotas: Vec<ResourceQuotaInfo>,
+    /// Most recent warning events, newest first
+    pub warnings: Vec<EventInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub name: String,
+    /// Ready, NotReady or Unknown, plus SchedulingDisabled when cordoned
+    pub status: String,
+    pub roles: Vec<String>,
+    pub unschedulable: bool,
+    pub internal_ip: Option<String>,
+    pub kubelet_version: Option<String>,
+    pub os_image: Option<String>,
+    pub capacity: std::collections::HashMap<String, String>,
+    pub allocatable: std::collections::HashMap<String, String>,
+    pub conditions: Vec<NodeConditionInfo>,
+    pub age: String,
+    pub labels: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeConditionInfo {
+    #[serde(rename = "type")]
+    pub condition_type: String,
+    pub status: String,
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub last_transition_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrainOptions {
+    /// Skip DaemonSet pods instead of refusing to drain
+    #[serde(default = "default_true")]
+    pub ignore_daemonsets: bool,
+    /// Evict pods using emptyDir volumes, losing their data
+    #[serde(default)]
+    pub delete_emptydir_data: bool,
+    /// Evict pods no controller will recreate
+    #[serde(default)]
+    pub force: bool,
+    pub grace_period_seconds: Option<u32>,
+    /// How long to keep evicting and waiting for pods to go, 300 by default
+    pub timeout_seconds: Option<u64>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for DrainOptions {
+    fn default() -> Self {
+        Self {
+            ignore_daemonsets: true,
+            delete_emptydir_data: false,
+            force: false,
+            grace_period_seconds: None,
+            timeout_seconds: None,
+        }
+    }
+}
+
+/// Emitted as `k8s:drain-progress` for each pod while a node drains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrainProgress {
+    pub node: String,
+    pub namespace: String,
+    pub pod: String,
+    /// evicting, blocked (by a disruption budget), evicted, gone or failed
+    pub phase: String,
+    pub message: Option<String>,
+    /// Pods evicted so far, out of `total`
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrainResult {
+    pub node: String,
+    /// Pods evicted, as namespace/name
+    pub evicted: Vec<String>,
+    /// Pods left in place (DaemonSet and mirror pods), with why
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistentVolumeInfo {
+    pub name: String,
+    pub capacity: Option<String>,
+    pub access_modes: Vec<String>,
+    pub reclaim_policy: Option<String>,
+    /// Available, Bound, Released or Failed
+    pub status: String,
+    /// Claim bound to the volume, as namespace/name
+    pub claim: Option<String>,
+    pub storage_class: Option<String>,
+    pub volume_mode: Option<String>,
+    pub age: String,
+    pub labels: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistentVolumeClaimInfo {
+    pub name: String,
+    pub namespace: String,
+    /// Pending, Bound or Lost
+    pub status: String,
+    pub volume: Option<String>,
+    /// Size provisioned for the claim
+    pub capacity: Option<String>,
+    /// Size the claim asks for; above `capacity` while an expansion is underway
+    pub requested: Option<String>,
+    pub access_modes: Vec<String>,
+    pub storage_class: Option<String>,
+    pub volume_mode: Option<String>,
+    /// Pods in the namespace mounting the claim
+    pub used_by: Vec<String>,
+    pub age: String,
+    pub labels: std::collections::HashMap<String, String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobInfo {
     pub name: String,
@@ -154,6 +312,14 @@ pub struct CronJobInfo {
     pub image: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobCleanupResult {
+    /// Finished jobs deleted, with their pods
+    pub deleted: Vec<String>,
+    /// Jobs that couldn't be deleted, with why
+    pub failed: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IngressInfo {
     pub name: String,
@@ -184,6 +350,103 @@ pub struct SecretInfo {
     pub secret_type: Option<String>,
 }
 
+/// Resource whose keys are edited one at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigResourceKind {
+    ConfigMap,
+    Secret,
+}
+
+/// How a key's value is carried: `Text` as-is, `Binary` base64 encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigValueKind {
+    Text,
+    Binary,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub kind: ConfigValueKind,
+    pub value: String,
+}
+
+/// Keys of a ConfigMap or Secret, with the version they were read at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigEntries {
+    pub kind: ConfigResourceKind,
+    pub name: String,
+    pub namespace: String,
+    /// Pass back when editing so changes made since are not overwritten
+    pub resource_version: Option<String>,
+    pub entries: Vec<ConfigEntry>,
+}
+
+/// A problem found in resource YAML
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YamlDiagnostic {
+    /// 1-based line the problem is on, when it could be placed
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Field the problem is about, as the API server names it
+    pub path: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YamlValidation {
+    pub valid: bool,
+    pub diagnostics: Vec<YamlDiagnostic>,
+}
+
+/// A context in the user's kubeconfig
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubeContextInfo {
+    pub name: String,
+    pub cluster: Option<String>,
+    pub user: Option<String>,
+    pub namespace: Option<String>,
+    pub server: Option<String>,
+    pub current: bool,
+}
+
+/// An entry renamed on import because the kubeconfig already had a
+/// different one by that name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KubeconfigRename {
+    /// context, cluster or user
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubeconfigImport {
+    /// Kubeconfig merged into
+    pub path: String,
+    /// Contexts added, by their new names
+    pub added: Vec<String>,
+    pub renamed: Vec<KubeconfigRename>,
+    /// Copy of the kubeconfig from before the import
+    pub backup: Option<String>,
+    /// Nothing was written; this is what the import would do
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubeconfigRemoval {
+    pub removed: Vec<String>,
+    pub backup: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaleContext {
+    pub name: String,
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogEntry {
     pub timestamp: String,
@@ -192,6 +455,19 @@ pub struct LogEntry {
     pub container: String,
 }
 
+/// An ephemeral debug container added to a pod, like `kubectl debug`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugContainerInfo {
+    pub namespace: String,
+    pub pod_name: String,
+    pub container: String,
+    /// Container whose process namespace the debug container shares
+    pub target_container: Option<String>,
+    pub image: String,
+    /// Command that attaches a terminal to the debug container
+    pub attach_command: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortForwardRequest {
     pub namespace: String,
@@ -204,12 +480,27 @@ pub struct PortForwardRequest {
 pub struct PortForwardInfo {
     pub id: String,
     pub namespace: String,
+    /// Pod forwarded to, or the workload or service for other targets
     pub pod_name: String,
+    /// What's forwarded to, as kubectl takes it, e.g. deployment/web
+    pub target: String,
     pub local_port: u16,
     pub remote_port: u16,
+    /// Active, or Reconnecting while an auto-restore forward is restarted
     pub status: String,
     pub created_at: String,
     pub url: String,
+    /// Saved forward this was started from
+    pub saved_id: Option<String>,
+    /// Restarted when it drops, e.g. because its pod was replaced
+    pub auto_restore: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortForwardRestore {
+    pub started: Vec<PortForwardInfo>,
+    /// Forwards that couldn't be started, with why
+    pub failed: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
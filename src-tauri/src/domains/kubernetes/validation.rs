@@ -0,0 +1,311 @@
+//! Diagnostics for resource YAML.
+//!
+//! The API server validates a strict dry run against the cluster's schema
+//! and reports problems by field path, in one of three shapes: strict
+//! decoding (`unknown field "spec.foo"`), server-side apply
+//! (`.spec.replicas: expected numeric, got string`) and invalid objects
+//! (`Deployment.apps "web" is invalid: [spec.replicas: Invalid value: ...]`).
+//! Those paths are located in the YAML so each problem gets a line.
+
+use crate::domains::kubernetes::types::YamlDiagnostic;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static DECODING_ERROR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(unknown|duplicate) field "([^"]+)""#).expect("valid pattern"));
+static APPLY_ERROR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)(?:^|\s)(\.[\w.\[\]="/-]+): ([^\n]+)"#).expect("valid pattern"));
+static INVALID_FIELD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|, )([A-Za-z][\w.\[\]/-]*): ").expect("valid pattern"));
+
+/// Diagnostic for YAML that doesn't parse
+pub fn parse_error_diagnostic(error: &serde_yaml::Error) -> YamlDiagnostic {
+    YamlDiagnostic {
+        line: error.location().map(|location| location.line()),
+        column: error.location().map(|location| location.column()),
+        path: None,
+        message: error.to_string(),
+    }
+}
+
+/// Diagnostic about `path`, placed on its line in `yaml`
+pub fn field_diagnostic(yaml: &str, path: &str, message: String) -> YamlDiagnostic {
+    YamlDiagnostic {
+        line: locate(yaml, path),
+        column: None,
+        path: Some(path.trim_start_matches('.').to_string()),
+        message,
+    }
+}
+
+/// Diagnostics from an API server validation error message
+pub fn server_diagnostics(message: &str, yaml: &str) -> Vec<YamlDiagnostic> {
+    let diagnostics: Vec<YamlDiagnostic> = field_errors(message)
+        .into_iter()
+        .map(|(path, detail)| field_diagnostic(yaml, &path, detail))
+        .collect();
+    if diagnostics.is_empty() {
+        vec![YamlDiagnostic {
+            line: None,
+            column: None,
+            path: None,
+            message: message.to_string(),
+        }]
+    } else {
+        diagnostics
+    }
+}
+
+/// Field paths and what's wrong with them, from an error message
+fn field_errors(message: &str) -> Vec<(String, String)> {
+    let decoding: Vec<(String, String)> = DECODING_ERROR
+        .captures_iter(message)
+        .map(|captures| (captures[2].to_string(), format!("{} field", &captures[1])))
+        .collect();
+    if !decoding.is_empty() {
+        return decoding;
+    }
+
+    let apply: Vec<(String, String)> = APPLY_ERROR
+        .captures_iter(message)
+        .map(|captures| (captures[1].to_string(), captures[2].trim().to_string()))
+        .collect();
+    if !apply.is_empty() {
+        return apply;
+    }
+
+    let Some((_, invalid)) = message.split_once(" is invalid: ") else {
+        return Vec::new();
+    };
+    let invalid = invalid
+        .trim()
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .unwrap_or(invalid.trim());
+    let fields: Vec<_> = INVALID_FIELD.captures_iter(invalid).collect();
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, captures)| {
+            let whole = captures.get(0).expect("match");
+            let end = fields
+                .get(i + 1)
+                .map(|next| next.get(0).expect("match").start())
+                .unwrap_or(invalid.len());
+            (
+                captures[1].to_string(),
+                invalid[whole.end()..end].trim().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    /// List item whose `key` field is `value`, e.g. `containers[name="web"]`
+    Match(String, String),
+}
+
+fn parse_path(path: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for part in path.trim_start_matches('.').split('.') {
+        let (key, mut rest) = part.split_once('[').unwrap_or((part, ""));
+        if !key.is_empty() {
+            segments.push(Segment::Key(key.to_string()));
+        }
+        while let Some((selector, after)) = rest.split_once(']') {
+            if let Ok(index) = selector.parse() {
+                segments.push(Segment::Index(index));
+            } else if let Some((key, value)) = selector.split_once('=') {
+                segments.push(Segment::Match(
+                    key.to_string(),
+                    value.trim_matches('"').to_string(),
+                ));
+            }
+            rest = after.trim_start_matches('[');
+        }
+    }
+    segments
+}
+
+/// A line of block YAML: where its key sits, past any `- ` item markers
+struct Line<'a> {
+    indent: usize,
+    /// Column of the first `-` when the line starts a list item
+    item: Option<usize>,
+    key_col: usize,
+    key: Option<&'a str>,
+    value: &'a str,
+}
+
+fn parse_line(raw: &str) -> Option<Line<'_>> {
+    let content = raw.trim_start();
+    if content.is_empty() || content.starts_with('#') || content.starts_with("---") {
+        return None;
+    }
+    let indent = raw.len() - content.len();
+    let mut col = indent;
+    let mut rest = content;
+    let mut item = None;
+    while rest == "-" || rest.starts_with("- ") {
+        item.get_or_insert(col);
+        let after = rest[1..].trim_start();
+        col += rest.len() - after.len();
+        rest = after;
+    }
+    let (key, value) = match rest
+        .find(": ")
+        .or_else(|| rest.ends_with(':').then(|| rest.len() - 1))
+    {
+        Some(at) => (
+            Some(rest[..at].trim().trim_matches(|c| c == '"' || c == '\'')),
+            rest[at + 1..].trim(),
+        ),
+        None => (None, rest),
+    };
+    Some(Line {
+        indent,
+        item,
+        key_col: col,
+        key,
+        value,
+    })
+}
+
+/// 1-based line of the field at `path`, or of its deepest ancestor present
+/// in the YAML when the field itself isn't (e.g. a missing required field)
+pub fn locate(yaml: &str, path: &str) -> Option<usize> {
+    let lines: Vec<Option<Line>> = yaml.lines().map(parse_line).collect();
+    let (mut lo, mut hi) = (0, lines.len());
+    let mut found = None;
+
+    for segment in parse_path(path) {
+        let Some(first) = (lo..hi).find_map(|i| lines[i].as_ref()) else {
+            break;
+        };
+        match segment {
+            Segment::Key(key) => {
+                let level = first.key_col;
+                let at = (lo..hi).find(|&i| {
+                    lines[i]
+                        .as_ref()
+                        .is_some_and(|line| line.key_col == level && line.key == Some(key.as_str()))
+                });
+                let Some(at) = at else { break };
+                found = Some(at);
+                lo = at + 1;
+                hi = (lo..hi)
+                    .find(|&i| {
+                        lines[i].as_ref().is_some_and(|line| {
+                            line.indent < level || (line.indent == level && line.item.is_none())
+                        })
+                    })
+                    .unwrap_or(hi);
+            }
+            Segment::Index(_) | Segment::Match(..) => {
+                let Some(dash) = first.item else { break };
+                let starts: Vec<usize> = (lo..hi)
+                    .filter(|&i| {
+                        lines[i]
+                            .as_ref()
+                            .is_some_and(|line| line.item == Some(dash) && line.indent == dash)
+                    })
+                    .collect();
+                let item_end = |n: usize| starts.get(n + 1).copied().unwrap_or(hi);
+                let chosen = match &segment {
+                    Segment::Index(index) => (*index < starts.len()).then_some(*index),
+                    Segment::Match(key, value) => (0..starts.len()).find(|&n| {
+                        let level = lines[starts[n]].as_ref().map(|line| line.key_col);
+                        (starts[n]..item_end(n)).any(|i| {
+                            lines[i].as_ref().is_some_and(|line| {
+                                Some(line.key_col) == level
+                                    && line.key == Some(key.as_str())
+                                    && line.value.trim_matches(|c| c == '"' || c == '\'') == value
+                            })
+                        })
+                    }),
+                    Segment::Key(_) => None,
+                };
+                let Some(n) = chosen else { break };
+                found = Some(starts[n]);
+                lo = starts[n];
+                hi = item_end(n);
+            }
+        }
+    }
+    found.map(|i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPLOYMENT: &str = "apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  replicas: two
+  template:
+    spec:
+      containers:
+      - name: sidecar
+        image: proxy
+      - name: web
+        image: nginx
+        ports:
+          - containerPort: 80
+            protocol: TCP
+";
+
+    #[test]
+    fn locates_nested_fields_and_list_items() {
+        assert_eq!(locate(DEPLOYMENT, ".spec.replicas"), Some(6));
+        assert_eq!(
+            locate(DEPLOYMENT, "spec.template.spec.containers[1].image"),
+            Some(13)
+        );
+        assert_eq!(
+            locate(
+                DEPLOYMENT,
+                r#".spec.template.spec.containers[name="web"].ports[0].protocol"#
+            ),
+            Some(16)
+        );
+        // Missing fields point at their closest ancestor
+        assert_eq!(
+            locate(DEPLOYMENT, "spec.template.spec.containers[0].resources"),
+            Some(10)
+        );
+        assert_eq!(locate(DEPLOYMENT, "status.phase"), None);
+    }
+
+    #[test]
+    fn reads_each_server_error_shape() {
+        let decoding = r#"strict decoding error: unknown field "spec.replica", unknown field "spec.template.spec.containers[0].imagee""#;
+        let diagnostics = server_diagnostics(decoding, DEPLOYMENT);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[1].line, Some(10));
+        assert_eq!(diagnostics[1].message, "unknown field");
+
+        let apply = "failed to create typed patch object (default/web; apps/v1, Kind=Deployment): .spec.replicas: expected numeric (int or float), got string";
+        let diagnostics = server_diagnostics(apply, DEPLOYMENT);
+        assert_eq!(diagnostics[0].path.as_deref(), Some("spec.replicas"));
+        assert_eq!(diagnostics[0].line, Some(6));
+
+        let invalid = r#"Deployment.apps "web" is invalid: [spec.template.spec.containers[1].image: Required value, spec.replicas: Invalid value: -1: must be greater than or equal to 0]"#;
+        let diagnostics = server_diagnostics(invalid, DEPLOYMENT);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].message, "Required value");
+        assert_eq!(diagnostics[0].line, Some(13));
+        assert_eq!(
+            diagnostics[1].message,
+            "Invalid value: -1: must be greater than or equal to 0"
+        );
+
+        let other = server_diagnostics("the server could not find the requested resource", "");
+        assert_eq!(other[0].line, None);
+    }
+}
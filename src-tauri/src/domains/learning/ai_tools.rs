@@ -0,0 +1,68 @@
+//! Learning tools for the AI assistant
+use super::services::context_manager::{self, day_bounds, resolve_day};
+use crate::database::DatabaseManager;
+use crate::domains::ai::tools::{ToolRegistry, ToolSpec};
+use chrono::{DateTime, Duration, Local, Utc};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::Manager;
+
+pub fn register_ai_tools(registry: &ToolRegistry) {
+    registry.register(
+        ToolSpec {
+            name: "get_session_timeline".to_string(),
+            description: "What the user did in a period: terminal commands, document edits, \
+                          pipeline runs, tasks touched and SDK or environment changes, grouped \
+                          into work sessions. Use it for questions like \"what was I doing \
+                          yesterday?\". Give a day, or since/until; the default is the last 24 \
+                          hours."
+                .to_string(),
+            domain: "learning".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "day": { "type": "string", "description": "today, yesterday or YYYY-MM-DD" },
+                    "since": { "type": "string", "description": "RFC 3339 timestamp" },
+                    "until": { "type": "string", "description": "RFC 3339 timestamp" },
+                    "project_path": { "type": "string" }
+                }
+            }),
+        },
+        |app, args: Value| async move {
+            let timestamp = |key: &str| -> Result<Option<DateTime<Utc>>, String> {
+                args.get(key)
+                    .and_then(Value::as_str)
+                    .map(|value| {
+                        DateTime::parse_from_rfc3339(value)
+                            .map(|at| at.with_timezone(&Utc))
+                            .map_err(|e| format!("{} must be an RFC 3339 timestamp: {}", key, e))
+                    })
+                    .transpose()
+            };
+            let (since, until) = match args.get("day").and_then(Value::as_str) {
+                Some(day) => resolve_day(day, Local::now().date_naive())
+                    .and_then(day_bounds)
+                    .ok_or_else(|| format!("Unrecognised day: {}", day))?,
+                None => {
+                    let until = timestamp("until")?.unwrap_or_else(Utc::now);
+                    let since = timestamp("since")?.unwrap_or(until - Duration::days(1));
+                    (since, until)
+                }
+            };
+            let project = args.get("project_path").and_then(Value::as_str);
+            let db_manager = app.state::<Arc<DatabaseManager>>();
+            let timeline = context_manager::session_timeline(
+                db_manager.get_connection(),
+                since,
+                until,
+                project,
+            )
+            .await?;
+            Ok(json!({
+                "since": since.with_timezone(&Local).to_rfc3339(),
+                "until": until.with_timezone(&Local).to_rfc3339(),
+                "timeline": timeline.summary(),
+            }))
+        },
+    );
+}
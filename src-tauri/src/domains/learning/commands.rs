@@ -1,11 +1,23 @@
 use crate::database::DatabaseManager;
+use crate::domains::learning::services::anomaly_detector::{self, INSIGHT_EVENT};
+use crate::domains::learning::services::command_predictor::{CommandPrediction, CommandPredictor};
+use crate::domains::learning::services::context_manager::{self, SessionTimeline};
+use crate::domains::learning::services::data_control::{
+    self, LearningDataExport, LearningDataScope, PurgeResult,
+};
+use crate::domains::learning::services::learning_service::{Suggestion, SuggestionFeedback};
 use crate::domains::learning::services::{LearningService, MLIntensity, MemoryManager};
-use serde_json::{json, Value};
+use crate::{log_info, log_warn};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
 use std::sync::Arc;
-use tauri::command;
+use tauri::{command, Emitter};
 
+/// Record an event; a failed command that used to be reliable also raises
+/// an insight
 #[command]
 pub async fn record_learning_event(
+    app: tauri::AppHandle,
     event_type: String,
     event_data: Value,
     outcome: Option<String>,
@@ -14,9 +26,17 @@ pub async fn record_learning_event(
 ) -> Result<i32, String> {
     let db = db_manager.get_connection();
     let service = LearningService::with_default();
-    service
+    let id = service
         .record_event(&db, event_type, event_data, outcome, context)
-        .await
+        .await?;
+    match anomaly_detector::check(&db, id).await {
+        Ok(Some(insight)) => {
+            let _ = app.emit(INSIGHT_EVENT, &insight);
+        }
+        Ok(None) => {}
+        Err(e) => log_warn!("Learning", "Failed to check for command anomalies: {}", e),
+    }
+    Ok(id)
 }
 
 #[command]
@@ -51,7 +71,7 @@ pub async fn get_suggestions(
     pattern_type: String,
     context: Option<String>,
     db_manager: tauri::State<'_, Arc<DatabaseManager>>,
-) -> Result<Vec<Value>, String> {
+) -> Result<Vec<Suggestion>, String> {
     let db = db_manager.get_connection();
     let service = LearningService::with_default();
     service
@@ -59,6 +79,41 @@ pub async fn get_suggestions(
         .await
 }
 
+#[command]
+pub async fn submit_suggestion_feedback(
+    pattern_id: i32,
+    feedback: SuggestionFeedback,
+    snooze_hours: Option<u32>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    let db = db_manager.get_connection();
+    let service = LearningService::with_default();
+    service
+        .record_suggestion_feedback(&db, pattern_id, feedback, snooze_hours)
+        .await
+}
+
+/// Likely next terminal commands after `recent_commands` (oldest first),
+/// favouring history from terminals opened in `project_path`
+#[command]
+pub async fn predict_next_commands(
+    recent_commands: Vec<String>,
+    prefix: Option<String>,
+    project_path: Option<String>,
+    limit: Option<usize>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<CommandPrediction>, String> {
+    let db = db_manager.get_connection();
+    let predictor = CommandPredictor::load(&db, project_path.as_deref())
+        .await
+        .map_err(|e| format!("Failed to load command history: {}", e))?;
+    Ok(predictor.predict(
+        &recent_commands,
+        prefix.as_deref().unwrap_or_default(),
+        limit.unwrap_or(5),
+    ))
+}
+
 #[command]
 pub async fn learn_preference(
     preference_type: String,
@@ -207,25 +262,7 @@ pub async fn get_all_patterns(
         patterns
     };
 
-    let mut result = Vec::new();
-    for pattern in patterns {
-        let pattern_data: Value =
-            serde_json::from_str(&pattern.pattern_data).unwrap_or_else(|_| json!(null));
-
-        result.push(json!({
-            "id": pattern.id,
-            "pattern_type": pattern.pattern_type,
-            "pattern_data": pattern_data,
-            "context": pattern.context,
-            "frequency": pattern.frequency,
-            "last_used": pattern.last_used.map(|d| d.to_string()),
-            "success_rate": pattern.success_rate,
-            "is_important": pattern.is_important,
-            "created_at": pattern.created_at.map(|d| d.to_string()),
-        }));
-    }
-
-    Ok(result)
+    Ok(patterns.iter().map(data_control::pattern_json).collect())
 }
 
 #[command]
@@ -240,22 +277,7 @@ pub async fn get_recent_events(
         .await
         .map_err(|e| format!("Failed to get events: {}", e))?;
 
-    let mut result = Vec::new();
-    for event in events {
-        let event_data: Value =
-            serde_json::from_str(&event.event_data).unwrap_or_else(|_| json!(null));
-
-        result.push(json!({
-            "id": event.id,
-            "event_type": event.event_type,
-            "event_data": event_data,
-            "outcome": event.outcome,
-            "context": event.context,
-            "created_at": event.created_at.map(|d| d.to_string()),
-        }));
-    }
-
-    Ok(result)
+    Ok(events.iter().map(data_control::event_json).collect())
 }
 
 #[command]
@@ -269,24 +291,52 @@ pub async fn get_all_preferences(
         .await
         .map_err(|e| format!("Failed to get preferences: {}", e))?;
 
-    let mut result = Vec::new();
-    for pref in preferences {
-        let preference_value: Value =
-            serde_json::from_str(&pref.preference_value).unwrap_or_else(|_| json!(null));
-
-        result.push(json!({
-            "id": pref.id,
-            "preference_type": pref.preference_type,
-            "context": pref.context,
-            "preference_value": preference_value,
-            "confidence": pref.confidence,
-            "learned_from": pref.learned_from,
-            "is_important": pref.is_important,
-            "created_at": pref.created_at.map(|d| d.to_string()),
-            "updated_at": pref.updated_at.map(|d| d.to_string()),
-        }));
-    }
+    Ok(preferences
+        .iter()
+        .map(data_control::preference_json)
+        .collect())
+}
 
+/// What was done between `since` and `until` (the last day by default),
+/// grouped into work sessions
+#[command]
+pub async fn get_session_timeline(
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    project_path: Option<String>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<SessionTimeline, String> {
+    let db = db_manager.get_connection();
+    let until = until.unwrap_or_else(Utc::now);
+    let since = since.unwrap_or(until - Duration::days(1));
+    context_manager::session_timeline(&db, since, until, project_path.as_deref()).await
+}
+
+/// Everything learned within `scope`, for the user to inspect
+#[command]
+pub async fn export_learning_data(
+    scope: Option<LearningDataScope>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<LearningDataExport, String> {
+    let db = db_manager.get_connection();
+    data_control::export(&db, scope.unwrap_or_default()).await
+}
+
+/// Delete everything learned within `scope`; with no scope, all of it
+#[command]
+pub async fn purge_learning_data(
+    scope: Option<LearningDataScope>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<PurgeResult, String> {
+    let db = db_manager.get_connection();
+    let result = data_control::purge(&db, &scope.unwrap_or_default()).await?;
+    log_info!(
+        "Learning",
+        "Purged {} events, {} patterns and {} preferences",
+        result.events_deleted,
+        result.patterns_deleted,
+        result.preferences_deleted
+    );
     Ok(result)
 }
 
@@ -110,6 +110,23 @@ impl LearnedPatternRepository {
         Ok(())
     }
 
+    /// Delete the given records, returning how many were removed
+    pub async fn delete_by_ids(
+        db: &DatabaseConnection,
+        ids: Vec<i32>,
+    ) -> Result<u64, sea_orm::DbErr> {
+        let mut deleted = 0;
+        // Stay under SQLite's bound parameter limit
+        for chunk in ids.chunks(500) {
+            deleted += Entity::delete_many()
+                .filter(learned_pattern::Column::Id.is_in(chunk.iter().copied()))
+                .exec(db)
+                .await?
+                .rows_affected;
+        }
+        Ok(deleted)
+    }
+
     /// Find or create pattern by matching pattern_data
     pub async fn find_or_create(
         db: &DatabaseConnection,
@@ -135,6 +152,24 @@ impl LearnedPatternRepository {
         }
     }
 
+    /// Store the weight and snooze that suggestion feedback left on a pattern
+    pub async fn apply_feedback(
+        db: &DatabaseConnection,
+        id: i32,
+        weight: f64,
+        snoozed_until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Model, sea_orm::DbErr> {
+        let mut active_model: ActiveModel = Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::Custom(format!("Pattern with id {} not found", id)))?
+            .into();
+
+        active_model.weight = Set(weight);
+        active_model.snoozed_until = Set(snoozed_until.map(Into::into));
+        active_model.update(db).await
+    }
+
     /// Mark pattern as important (never auto-delete)
     pub async fn mark_important(
         db: &DatabaseConnection,
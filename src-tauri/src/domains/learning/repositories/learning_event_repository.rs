@@ -1,7 +1,8 @@
 use crate::entities::learning_event;
 use crate::entities::learning_event::{ActiveModel, Entity, Model};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
 };
 
 pub struct LearningEventRepository;
@@ -48,6 +49,52 @@ impl LearningEventRepository {
         Ok(all.into_iter().take(limit as usize).collect())
     }
 
+    /// Most recent events in a context, or in any context without one
+    pub async fn get_recent_by_context(
+        db: &DatabaseConnection,
+        context: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<Model>, sea_orm::DbErr> {
+        let mut query = Entity::find();
+        if let Some(ctx) = context {
+            query = query.filter(learning_event::Column::Context.eq(ctx));
+        }
+        query
+            .order_by_desc(learning_event::Column::CreatedAt)
+            .limit(limit)
+            .all(db)
+            .await
+    }
+
+    /// Most recent events of a type
+    pub async fn get_recent_by_type(
+        db: &DatabaseConnection,
+        event_type: &str,
+        limit: u64,
+    ) -> Result<Vec<Model>, sea_orm::DbErr> {
+        Entity::find()
+            .filter(learning_event::Column::EventType.eq(event_type))
+            .order_by_desc(learning_event::Column::CreatedAt)
+            .limit(limit)
+            .all(db)
+            .await
+    }
+
+    /// Events of any of the types created after `since`, newest first
+    pub async fn get_by_types_since(
+        db: &DatabaseConnection,
+        event_types: &[&str],
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Model>, sea_orm::DbErr> {
+        let since_value: sea_orm::Value = since.into();
+        Entity::find()
+            .filter(learning_event::Column::EventType.is_in(event_types.iter().copied()))
+            .filter(learning_event::Column::CreatedAt.gt(since_value))
+            .order_by_desc(learning_event::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
     /// Create a new learning event
     pub async fn create(
         db: &DatabaseConnection,
@@ -91,6 +138,23 @@ impl LearningEventRepository {
         Ok(())
     }
 
+    /// Delete the given records, returning how many were removed
+    pub async fn delete_by_ids(
+        db: &DatabaseConnection,
+        ids: Vec<i32>,
+    ) -> Result<u64, sea_orm::DbErr> {
+        let mut deleted = 0;
+        // Stay under SQLite's bound parameter limit
+        for chunk in ids.chunks(500) {
+            deleted += Entity::delete_many()
+                .filter(learning_event::Column::Id.is_in(chunk.iter().copied()))
+                .exec(db)
+                .await?
+                .rows_affected;
+        }
+        Ok(deleted)
+    }
+
     /// Delete old events (cleanup)
     pub async fn delete_older_than(
         db: &DatabaseConnection,
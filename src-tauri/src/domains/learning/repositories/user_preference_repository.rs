@@ -104,6 +104,23 @@ impl UserPreferenceRepository {
         Ok(())
     }
 
+    /// Delete the given records, returning how many were removed
+    pub async fn delete_by_ids(
+        db: &DatabaseConnection,
+        ids: Vec<i32>,
+    ) -> Result<u64, sea_orm::DbErr> {
+        let mut deleted = 0;
+        // Stay under SQLite's bound parameter limit
+        for chunk in ids.chunks(500) {
+            deleted += Entity::delete_many()
+                .filter(user_preference::Column::Id.is_in(chunk.iter().copied()))
+                .exec(db)
+                .await?
+                .rows_affected;
+        }
+        Ok(deleted)
+    }
+
     /// Find or create preference by type and context
     pub async fn find_or_create(
         db: &DatabaseConnection,
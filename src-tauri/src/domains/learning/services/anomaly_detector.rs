@@ -0,0 +1,388 @@
+//! Spotting commands that start failing after having been reliable.
+//!
+//! Every failed `command_executed` event is compared with the earlier runs of
+//! the same command in the same project. When a command that used to succeed
+//! fails twice in its last few runs, the detector raises an insight with the
+//! changes recorded since it last worked, such as SDK version switches and
+//! edited environment variables, as likely causes. Insights are kept as
+//! `command_anomaly` events and emitted as `INSIGHT_EVENT`.
+
+use chrono::{DateTime, Utc};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::domains::learning::repositories::LearningEventRepository;
+use crate::domains::learning::services::LearningService;
+use crate::entities::learning_event;
+use crate::log_warn;
+
+pub const INSIGHT_EVENT: &str = "learning-insight";
+/// A default or project SDK version changed
+pub const SDK_SWITCHED: &str = "sdk_version_switched";
+/// Environment variables were set or deleted
+pub const ENV_CHANGED: &str = "env_var_changed";
+
+const COMMAND_EVENT: &str = "command_executed";
+const ANOMALY_EVENT: &str = "command_anomaly";
+/// Recent command events a command's history is read from
+const HISTORY_LIMIT: u64 = 1000;
+/// Runs before the recent ones needed to call a command reliable
+const MIN_BASELINE_RUNS: usize = 5;
+const RELIABLE_SUCCESS_RATE: f64 = 0.8;
+const RECENT_RUNS: usize = 3;
+/// Failures among the recent runs that make an anomaly
+const RECENT_FAILURES: usize = 2;
+/// How far back causes are looked for when the command's last success is
+/// unknown
+const CAUSE_LOOKBACK_DAYS: i64 = 7;
+const MAX_CAUSES: usize = 5;
+
+/// A change recorded before the failures started
+#[derive(Debug, Clone, Serialize)]
+pub struct LikelyCause {
+    pub event_id: i32,
+    pub event_type: String,
+    pub description: String,
+    pub at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandInsight {
+    /// The command, reduced to its program and subcommand
+    pub command: String,
+    pub project: Option<String>,
+    pub message: String,
+    /// Success rate before the recent runs
+    pub baseline_success_rate: f64,
+    pub baseline_runs: usize,
+    pub recent_failures: usize,
+    pub recent_runs: usize,
+    pub exit_code: Option<i64>,
+    pub error: Option<String>,
+    /// The error wasn't seen in the command's earlier failures
+    pub new_error: bool,
+    pub likely_causes: Vec<LikelyCause>,
+}
+
+/// One run of a command, read from a `command_executed` event
+#[derive(Debug, Clone)]
+struct Run {
+    command: String,
+    project: Option<String>,
+    success: bool,
+    exit_code: Option<i64>,
+    error: Option<String>,
+    at: Option<DateTime<Utc>>,
+}
+
+impl Run {
+    fn from_event(event: &learning_event::Model) -> Option<Self> {
+        let data: Value = serde_json::from_str(&event.event_data).ok()?;
+        let command = command_key(data["command"].as_str()?)?;
+        Some(Self {
+            command,
+            project: data["cwd"]
+                .as_str()
+                .map(str::to_string)
+                .or_else(|| event.context.clone()),
+            success: event.outcome.as_deref() != Some("failure"),
+            exit_code: data["exit_code"].as_i64(),
+            error: data["error"].as_str().and_then(error_signature),
+            at: event.created_at.map(|at| at.with_timezone(&Utc)),
+        })
+    }
+}
+
+/// How a command's latest runs compare with its history
+#[derive(Debug, PartialEq)]
+struct Assessment {
+    baseline_success_rate: f64,
+    baseline_runs: usize,
+    recent_failures: usize,
+    recent_runs: usize,
+    new_error: bool,
+    last_success_at: Option<DateTime<Utc>>,
+}
+
+/// Whether the last of `runs` (oldest first) makes a reliable command
+/// anomalous. Only the run that brings the recent failures up to
+/// `RECENT_FAILURES` counts, so a failing streak is reported once.
+fn assess(runs: &[Run]) -> Option<Assessment> {
+    let current = runs.last()?;
+    if current.success || runs.len() < MIN_BASELINE_RUNS + RECENT_RUNS {
+        return None;
+    }
+    let (baseline, recent) = runs.split_at(runs.len() - RECENT_RUNS);
+    let recent_failures = recent.iter().filter(|run| !run.success).count();
+    if recent_failures != RECENT_FAILURES {
+        return None;
+    }
+    let baseline_success_rate =
+        baseline.iter().filter(|run| run.success).count() as f64 / baseline.len() as f64;
+    if baseline_success_rate < RELIABLE_SUCCESS_RATE {
+        return None;
+    }
+    let new_error = current
+        .error
+        .as_ref()
+        .is_some_and(|error| !baseline.iter().any(|run| run.error.as_ref() == Some(error)));
+    Some(Assessment {
+        baseline_success_rate,
+        baseline_runs: baseline.len(),
+        recent_failures,
+        recent_runs: recent.len(),
+        new_error,
+        last_success_at: runs
+            .iter()
+            .rev()
+            .find(|run| run.success)
+            .and_then(|run| run.at),
+    })
+}
+
+/// Check the event just recorded, raising an insight if it's a reliable
+/// command starting to fail
+pub async fn check(
+    db: &DatabaseConnection,
+    event_id: i32,
+) -> Result<Option<CommandInsight>, String> {
+    let event = LearningEventRepository::get_by_id(db, event_id)
+        .await
+        .map_err(|e| format!("Failed to get learning event: {}", e))?;
+    let Some(event) = event.filter(|event| {
+        event.event_type == COMMAND_EVENT && event.outcome.as_deref() == Some("failure")
+    }) else {
+        return Ok(None);
+    };
+    let Some(current) = Run::from_event(&event) else {
+        return Ok(None);
+    };
+
+    let mut runs: Vec<Run> =
+        LearningEventRepository::get_recent_by_type(db, COMMAND_EVENT, HISTORY_LIMIT)
+            .await
+            .map_err(|e| format!("Failed to get command history: {}", e))?
+            .iter()
+            .filter(|other| other.id != event.id)
+            .filter_map(Run::from_event)
+            .filter(|run| run.command == current.command && run.project == current.project)
+            .collect();
+    runs.reverse();
+    runs.push(current.clone());
+    let Some(assessment) = assess(&runs) else {
+        return Ok(None);
+    };
+
+    let since = assessment
+        .last_success_at
+        .unwrap_or_else(|| Utc::now() - chrono::Duration::days(CAUSE_LOOKBACK_DAYS));
+    let likely_causes =
+        LearningEventRepository::get_by_types_since(db, &[SDK_SWITCHED, ENV_CHANGED], since)
+            .await
+            .map_err(|e| format!("Failed to get recent changes: {}", e))?
+            .iter()
+            // A change scoped to another project can't be the cause
+            .filter(|change| match (&change.context, &current.project) {
+                (None, _) => true,
+                (Some(scope), Some(project)) => Path::new(project).starts_with(scope),
+                (Some(_), None) => false,
+            })
+            .take(MAX_CAUSES)
+            .map(|change| LikelyCause {
+                event_id: change.id,
+                event_type: change.event_type.clone(),
+                description: describe_change(&change.event_type, &change.event_data),
+                at: change.created_at.map(|at| at.with_timezone(&Utc)),
+            })
+            .collect();
+
+    let insight = CommandInsight {
+        message: message(&current, &assessment),
+        command: current.command,
+        project: current.project,
+        baseline_success_rate: assessment.baseline_success_rate,
+        baseline_runs: assessment.baseline_runs,
+        recent_failures: assessment.recent_failures,
+        recent_runs: assessment.recent_runs,
+        exit_code: current.exit_code,
+        error: current.error,
+        new_error: assessment.new_error,
+        likely_causes,
+    };
+    LearningService::with_default()
+        .record_event(
+            db,
+            ANOMALY_EVENT.to_string(),
+            serde_json::to_value(&insight).unwrap_or_default(),
+            None,
+            event.context,
+        )
+        .await?;
+    Ok(Some(insight))
+}
+
+/// Record a change that could make commands fail, for `check` to point at.
+/// Never fails the caller; skipped when learning is turned off.
+pub async fn record_change(
+    db: &DatabaseConnection,
+    event_type: &str,
+    data: Value,
+    project: Option<String>,
+) {
+    let service = LearningService::with_default();
+    let enabled = service
+        .get_preference(db, "ml_enabled", Some("global"))
+        .await
+        .ok()
+        .flatten()
+        .and_then(|value| value["enabled"].as_bool())
+        .unwrap_or(true);
+    if !enabled {
+        return;
+    }
+    if let Err(e) = service
+        .record_event(db, event_type.to_string(), data, None, project)
+        .await
+    {
+        log_warn!("Learning", "Failed to record {}: {}", event_type, e);
+    }
+}
+
+/// The program and up to two subcommands, e.g. `npm run build` for
+/// `NODE_ENV=production npm run build -- --watch`
+fn command_key(command: &str) -> Option<String> {
+    let words: Vec<&str> = command
+        .split_whitespace()
+        .skip_while(|word| word.contains('=') && !word.starts_with('-'))
+        .take_while(|word| !word.starts_with('-') && !word.contains(['"', '\'']))
+        .take(3)
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// The line of an error excerpt that says what went wrong, with numbers
+/// masked so the same error from different runs compares equal
+fn error_signature(excerpt: &str) -> Option<String> {
+    const MARKERS: [&str; 6] = ["error", "fatal", "not found", "cannot", "failed", "denied"];
+    let lines: Vec<&str> = excerpt
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let line = lines
+        .iter()
+        .find(|line| {
+            let lower = line.to_lowercase();
+            MARKERS.iter().any(|marker| lower.contains(marker))
+        })
+        .or(lines.last())?;
+    let signature: String = line
+        .chars()
+        .map(|c| if c.is_ascii_digit() { '#' } else { c })
+        .take(160)
+        .collect();
+    Some(signature)
+}
+
+pub(crate) fn describe_change(event_type: &str, event_data: &str) -> String {
+    let data: Value = serde_json::from_str(event_data).unwrap_or_default();
+    match event_type {
+        SDK_SWITCHED => {
+            let sdk = data["sdk_type"].as_str().unwrap_or("An SDK");
+            let version = data["version"].as_str().unwrap_or("another version");
+            match data["scope"].as_str() {
+                Some("project") => format!("{} was pinned to {} in the project", sdk, version),
+                _ => format!("The default {} was switched to {}", sdk, version),
+            }
+        }
+        ENV_CHANGED => {
+            let names: Vec<&str> = data["names"]
+                .as_array()
+                .map(|names| names.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            match names.as_slice() {
+                [] => "Environment variables were changed".to_string(),
+                [name] => format!("The environment variable {} was changed", name),
+                names => format!(
+                    "The environment variables {} were changed",
+                    names.join(", ")
+                ),
+            }
+        }
+        other => other.replace('_', " "),
+    }
+}
+
+fn message(current: &Run, assessment: &Assessment) -> String {
+    let mut message = format!(
+        "`{}` failed {} of its last {} runs after succeeding {:.0}% of the time",
+        current.command,
+        assessment.recent_failures,
+        assessment.recent_runs,
+        assessment.baseline_success_rate * 100.0
+    );
+    if let Some(error) = current.error.as_ref().filter(|_| assessment.new_error) {
+        message.push_str(&format!(", with a new error: {}", error));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(success: bool, error: Option<&str>) -> Run {
+        Run {
+            command: "npm run build".to_string(),
+            project: Some("/work/app".to_string()),
+            success,
+            exit_code: (!success).then_some(1),
+            error: error.map(str::to_string),
+            at: None,
+        }
+    }
+
+    #[test]
+    fn commands_are_keyed_by_program_and_subcommand() {
+        assert_eq!(
+            command_key("NODE_ENV=production npm run build -- --watch").as_deref(),
+            Some("npm run build")
+        );
+        assert_eq!(
+            command_key("git commit -m 'wip'").as_deref(),
+            Some("git commit")
+        );
+        assert_eq!(command_key("   "), None);
+        assert_eq!(
+            error_signature("> build\nError: Cannot find module 'vite' at line 42\n").as_deref(),
+            Some("Error: Cannot find module 'vite' at line ##")
+        );
+    }
+
+    #[test]
+    fn reliable_command_starting_to_fail_is_flagged_once() {
+        let mut runs: Vec<Run> = (0..6).map(|_| run(true, None)).collect();
+        runs.push(run(false, Some("Error: Cannot find module 'vite'")));
+        assert_eq!(assess(&runs), None);
+
+        runs.push(run(false, Some("Error: Cannot find module 'vite'")));
+        let assessment = assess(&runs).unwrap();
+        assert_eq!(assessment.baseline_runs, 5);
+        assert_eq!(assessment.baseline_success_rate, 1.0);
+        assert_eq!(assessment.recent_failures, 2);
+        assert!(assessment.new_error);
+
+        // Still failing: already reported
+        runs.push(run(false, None));
+        assert_eq!(assess(&runs), None);
+    }
+
+    #[test]
+    fn unreliable_commands_are_not_flagged() {
+        let mut runs: Vec<Run> = (0..6).map(|i| run(i % 2 == 0, None)).collect();
+        runs.extend([run(true, None), run(false, None), run(false, None)]);
+        assert_eq!(assess(&runs), None);
+    }
+}
@@ -0,0 +1,262 @@
+//! Next-command prediction from the persisted terminal history.
+//!
+//! Each terminal tab's history is a command sequence. The predictor counts
+//! which command followed the last one or two commands (bigrams and
+//! trigrams) and falls back to plain frequency, scoring with stupid backoff.
+//! Tabs whose working directory is inside the project being predicted for
+//! count more than the rest, and failed commands count less, so the
+//! suggestions lean towards what worked in this project. Everything stays on
+//! the device; the model is rebuilt from the database on each prediction.
+
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::entities::{terminal_command_history, terminal_session};
+
+/// Most recent history rows the model is built from
+const HISTORY_LIMIT: u64 = 5000;
+/// Weight of a sequence from a tab in the project, against 1 for other tabs
+const IN_CONTEXT_WEIGHT: f64 = 3.0;
+/// Weight of a command that exited with an error, against 1 for success
+const FAILURE_WEIGHT: f64 = 0.25;
+/// Score multiplier for each order backed off from
+const BACKOFF: f64 = 0.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PredictionSource {
+    /// Followed the last two commands before
+    Trigram,
+    /// Followed the last command before
+    Bigram,
+    /// Frequently run
+    Frequency,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandPrediction {
+    pub command: String,
+    /// 0 to 1
+    pub score: f64,
+    pub source: PredictionSource,
+}
+
+/// A tab's commands in the order they ran, with their exit codes
+pub struct CommandSequence {
+    pub commands: Vec<(String, Option<i32>)>,
+    pub in_context: bool,
+}
+
+/// Weighted command n-gram counts
+#[derive(Debug, Default)]
+pub struct CommandPredictor {
+    unigrams: HashMap<String, f64>,
+    bigrams: HashMap<String, HashMap<String, f64>>,
+    trigrams: HashMap<(String, String), HashMap<String, f64>>,
+}
+
+impl CommandPredictor {
+    pub fn train(sequences: &[CommandSequence]) -> Self {
+        let mut predictor = Self::default();
+        for sequence in sequences {
+            let context_weight = if sequence.in_context {
+                IN_CONTEXT_WEIGHT
+            } else {
+                1.0
+            };
+            let commands: Vec<(String, f64)> = sequence
+                .commands
+                .iter()
+                .filter_map(|(command, exit_code)| {
+                    let command = normalize(command)?;
+                    let weight = match exit_code {
+                        Some(code) if *code != 0 => FAILURE_WEIGHT,
+                        _ => 1.0,
+                    };
+                    Some((command, weight * context_weight))
+                })
+                .collect();
+            for (i, (command, weight)) in commands.iter().enumerate() {
+                *predictor.unigrams.entry(command.clone()).or_default() += weight;
+                if i >= 1 {
+                    let previous = commands[i - 1].0.clone();
+                    *predictor
+                        .bigrams
+                        .entry(previous)
+                        .or_default()
+                        .entry(command.clone())
+                        .or_default() += weight;
+                }
+                if i >= 2 {
+                    let key = (commands[i - 2].0.clone(), commands[i - 1].0.clone());
+                    *predictor
+                        .trigrams
+                        .entry(key)
+                        .or_default()
+                        .entry(command.clone())
+                        .or_default() += weight;
+                }
+            }
+        }
+        predictor
+    }
+
+    /// The likeliest commands to follow `recent` (oldest first), limited to
+    /// those starting with `prefix`
+    pub fn predict(&self, recent: &[String], prefix: &str, limit: usize) -> Vec<CommandPrediction> {
+        let recent: Vec<String> = recent.iter().filter_map(|c| normalize(c)).collect();
+        let prefix = prefix.trim_start();
+        let mut best: HashMap<String, (f64, PredictionSource)> = HashMap::new();
+        let mut consider = |counts: Option<&HashMap<String, f64>>, scale: f64, source| {
+            let Some(counts) = counts else {
+                return;
+            };
+            let total: f64 = counts.values().sum();
+            for (command, count) in counts {
+                // Completing the prefix exactly as typed adds nothing
+                if !command.starts_with(prefix) || command == prefix {
+                    continue;
+                }
+                let score = scale * count / total;
+                let entry = best.entry(command.clone()).or_insert((0.0, source));
+                if score > entry.0 {
+                    *entry = (score, source);
+                }
+            }
+        };
+
+        if let [.., second_last, last] = recent.as_slice() {
+            let key = (second_last.clone(), last.clone());
+            consider(self.trigrams.get(&key), 1.0, PredictionSource::Trigram);
+        }
+        if let Some(last) = recent.last() {
+            consider(self.bigrams.get(last), BACKOFF, PredictionSource::Bigram);
+        }
+        consider(
+            Some(&self.unigrams),
+            BACKOFF * BACKOFF,
+            PredictionSource::Frequency,
+        );
+
+        let mut predictions: Vec<CommandPrediction> = best
+            .into_iter()
+            .map(|(command, (score, source))| CommandPrediction {
+                command,
+                score,
+                source,
+            })
+            .collect();
+        predictions.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.command.cmp(&b.command))
+        });
+        predictions.truncate(limit);
+        predictions
+    }
+
+    /// Build the model from the saved terminal history. Tabs working inside
+    /// `project_path` make up the in-context sequences.
+    pub async fn load(
+        db: &DatabaseConnection,
+        project_path: Option<&str>,
+    ) -> Result<Self, sea_orm::DbErr> {
+        let mut rows = terminal_command_history::Entity::find()
+            // Intercepted commands never reached the shell
+            .filter(
+                Condition::any()
+                    .add(terminal_command_history::Column::Intercepted.is_null())
+                    .add(terminal_command_history::Column::Intercepted.eq(false)),
+            )
+            .order_by_desc(terminal_command_history::Column::Timestamp)
+            .limit(HISTORY_LIMIT)
+            .all(db)
+            .await?;
+        rows.reverse();
+
+        let in_context_tabs: Vec<String> = match project_path {
+            Some(project) => terminal_session::Entity::find()
+                .all(db)
+                .await?
+                .into_iter()
+                .filter(|session| Path::new(&session.working_directory).starts_with(project))
+                .map(|session| session.tab_id)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut by_tab: HashMap<String, Vec<(String, Option<i32>)>> = HashMap::new();
+        for row in rows {
+            by_tab
+                .entry(row.tab_id)
+                .or_default()
+                .push((row.command, row.exit_code));
+        }
+        let sequences: Vec<CommandSequence> = by_tab
+            .into_iter()
+            .map(|(tab_id, commands)| CommandSequence {
+                in_context: in_context_tabs.contains(&tab_id),
+                commands,
+            })
+            .collect();
+        Ok(Self::train(&sequences))
+    }
+}
+
+/// Collapse whitespace so `git  status` and `git status` count as one
+fn normalize(command: &str) -> Option<String> {
+    let command = command.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!command.is_empty()).then_some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(commands: &[&str], in_context: bool) -> CommandSequence {
+        CommandSequence {
+            commands: commands.iter().map(|c| (c.to_string(), Some(0))).collect(),
+            in_context,
+        }
+    }
+
+    #[test]
+    fn predicts_from_the_longest_known_history() {
+        let predictor = CommandPredictor::train(&[
+            sequence(&["git add .", "git commit", "git push"], true),
+            sequence(&["git add .", "git commit", "git push"], true),
+            sequence(&["cargo build", "git commit", "cargo test"], false),
+        ]);
+        let recent = ["git add .".to_string(), "git  commit".to_string()];
+
+        let predictions = predictor.predict(&recent, "", 3);
+        assert_eq!(predictions[0].command, "git push");
+        assert_eq!(predictions[0].source, PredictionSource::Trigram);
+        assert_eq!(predictions[0].score, 1.0);
+        assert!(predictions.iter().any(|p| p.command == "cargo test"));
+
+        let predictions = predictor.predict(&recent, "cargo", 3);
+        assert_eq!(predictions[0].command, "cargo test");
+        assert_eq!(predictions[0].source, PredictionSource::Bigram);
+        assert!(predictions.iter().all(|p| p.command.starts_with("cargo")));
+    }
+
+    #[test]
+    fn failed_commands_count_less() {
+        let predictor = CommandPredictor::train(&[CommandSequence {
+            commands: vec![
+                ("npm test".to_string(), Some(1)),
+                ("npm test".to_string(), Some(1)),
+                ("npm run dev".to_string(), Some(0)),
+            ],
+            in_context: false,
+        }]);
+        let predictions = predictor.predict(&[], "npm", 2);
+        assert_eq!(predictions[0].command, "npm run dev");
+    }
+}
@@ -1,4 +1,24 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde::Serialize;
 use std::collections::HashMap;
+use std::path::Path;
+
+use crate::domains::learning::repositories::LearningEventRepository;
+use crate::domains::learning::services::anomaly_detector::{self, ENV_CHANGED, SDK_SWITCHED};
+use crate::entities::{
+    document, document_revision, pipeline, pipeline_execution, project, task,
+    terminal_command_history, terminal_session,
+};
+
+/// Idle time that ends one work session and starts the next
+const SESSION_GAP_MINUTES: i64 = 30;
+/// Most recent history rows read for the terminal part of a timeline
+const COMMAND_ROW_LIMIT: u64 = 2000;
+/// Entries kept in a timeline; the oldest are dropped past this
+const MAX_ENTRIES: usize = 500;
 
 /// Context manager for understanding and managing learning contexts
 pub struct ContextManager;
@@ -172,6 +192,359 @@ impl ContextManager {
     }
 }
 
+/// What a timeline entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    /// A terminal command
+    Command,
+    /// A document edit
+    File,
+    /// A pipeline run
+    Pipeline,
+    /// A task created or updated
+    Task,
+    /// An SDK version switch or environment variable change
+    Environment,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    pub at: DateTime<Utc>,
+    pub kind: TimelineKind,
+    pub title: String,
+    pub detail: Option<String>,
+    /// Directory of the project the entry belongs to, when known
+    pub project: Option<String>,
+    /// Times the entry happened back to back; the entry is the first of them
+    pub count: u32,
+}
+
+/// A stretch of activity without a long idle gap
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkSession {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub projects: Vec<String>,
+    pub entries: Vec<TimelineEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionTimeline {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub sessions: Vec<WorkSession>,
+}
+
+impl SessionTimeline {
+    /// Plain text rendering, newest session first, for grounding the AI
+    /// assistant
+    pub fn summary(&self) -> String {
+        if self.sessions.is_empty() {
+            return "No recorded activity in this period.".to_string();
+        }
+        let mut lines = Vec::new();
+        for session in self.sessions.iter().rev() {
+            lines.push(format!(
+                "Session {} to {}{}",
+                local_time(session.start, "%Y-%m-%d %H:%M"),
+                local_time(session.end, "%H:%M"),
+                if session.projects.is_empty() {
+                    String::new()
+                } else {
+                    format!(" in {}", session.projects.join(", "))
+                }
+            ));
+            for entry in &session.entries {
+                let mut line = format!(
+                    "- {} {:?}: {}",
+                    local_time(entry.at, "%H:%M"),
+                    entry.kind,
+                    entry.title
+                );
+                if entry.count > 1 {
+                    line.push_str(&format!(" (x{})", entry.count));
+                }
+                if let Some(detail) = &entry.detail {
+                    line.push_str(&format!(" [{}]", detail));
+                }
+                lines.push(line);
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+fn local_time(at: DateTime<Utc>, format: &str) -> String {
+    at.with_timezone(&Local).format(format).to_string()
+}
+
+/// The local day `day` names: `today`, `yesterday` or `YYYY-MM-DD`
+pub fn resolve_day(day: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match day.trim().to_lowercase().as_str() {
+        "today" => Some(today),
+        "yesterday" => today.pred_opt(),
+        other => NaiveDate::parse_from_str(other, "%Y-%m-%d").ok(),
+    }
+}
+
+/// Start and end of a local day, in UTC
+pub fn day_bounds(day: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = |date: NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()
+            .map(|at| at.with_timezone(&Utc))
+    };
+    Some((start(day)?, start(day.succ_opt()?)?))
+}
+
+/// Everything done between `since` and `until` across the terminal,
+/// documents, pipelines, tasks and SDK/environment changes, grouped into
+/// work sessions. With `project`, only entries inside that directory are
+/// kept; tasks aren't tied to a directory and are left out then.
+pub async fn session_timeline(
+    db: &DatabaseConnection,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    project: Option<&str>,
+) -> Result<SessionTimeline, String> {
+    let projects: HashMap<i32, String> = project::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to get projects: {}", e))?
+        .into_iter()
+        .map(|p| (p.id, p.path))
+        .collect();
+    let in_range = |at: DateTime<Utc>| at >= since && at < until;
+    let mut entries = Vec::new();
+
+    // Terminal commands
+    let tab_directories: HashMap<String, String> = terminal_session::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to get terminal sessions: {}", e))?
+        .into_iter()
+        .map(|session| (session.tab_id, session.working_directory))
+        .collect();
+    let commands = terminal_command_history::Entity::find()
+        .filter(
+            Condition::any()
+                .add(terminal_command_history::Column::Intercepted.is_null())
+                .add(terminal_command_history::Column::Intercepted.eq(false)),
+        )
+        .order_by_desc(terminal_command_history::Column::Timestamp)
+        .limit(COMMAND_ROW_LIMIT)
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to get command history: {}", e))?;
+    for row in commands {
+        let Ok(at) = DateTime::parse_from_rfc3339(&row.timestamp) else {
+            continue;
+        };
+        let at = at.with_timezone(&Utc);
+        if !in_range(at) {
+            continue;
+        }
+        entries.push(entry(
+            at,
+            TimelineKind::Command,
+            row.command.trim().to_string(),
+            row.exit_code
+                .filter(|code| *code != 0)
+                .map(|code| format!("exit {}", code)),
+            tab_directories.get(&row.tab_id).cloned(),
+        ));
+    }
+
+    // Document edits
+    let since_value: sea_orm::Value = since.into();
+    let until_value: sea_orm::Value = until.into();
+    let revisions = document_revision::Entity::find()
+        .filter(document_revision::Column::CreatedAt.gte(since_value.clone()))
+        .filter(document_revision::Column::CreatedAt.lt(until_value.clone()))
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to get document revisions: {}", e))?;
+    let document_projects: HashMap<i32, Option<i32>> = document::Entity::find()
+        .filter(document::Column::Id.is_in(revisions.iter().map(|r| r.document_id)))
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to get documents: {}", e))?
+        .into_iter()
+        .map(|doc| (doc.id, doc.project_id))
+        .collect();
+    for revision in revisions {
+        let Some(at) = revision.created_at else {
+            continue;
+        };
+        let project_path = document_projects
+            .get(&revision.document_id)
+            .copied()
+            .flatten()
+            .and_then(|id| projects.get(&id).cloned());
+        entries.push(entry(
+            at.with_timezone(&Utc),
+            TimelineKind::File,
+            revision.title,
+            Some(format!("revision {}", revision.revision_number)),
+            project_path,
+        ));
+    }
+
+    // Pipeline runs
+    let pipeline_names: HashMap<i32, String> = pipeline::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to get pipelines: {}", e))?
+        .into_iter()
+        .map(|p| (p.id, p.name))
+        .collect();
+    let executions = pipeline_execution::Entity::find()
+        .filter(pipeline_execution::Column::StartedAt.gte(since_value.clone()))
+        .filter(pipeline_execution::Column::StartedAt.lt(until_value.clone()))
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to get pipeline executions: {}", e))?;
+    for execution in executions {
+        let name = pipeline_names
+            .get(&execution.pipeline_id)
+            .cloned()
+            .unwrap_or_else(|| format!("Pipeline {}", execution.pipeline_id));
+        entries.push(entry(
+            execution.started_at.with_timezone(&Utc),
+            TimelineKind::Pipeline,
+            name,
+            Some(execution.status),
+            projects.get(&execution.project_id).cloned(),
+        ));
+    }
+
+    // Tasks
+    if project.is_none() {
+        let tasks = task::Entity::find()
+            .filter(task::Column::UpdatedAt.gte(since_value))
+            .filter(task::Column::UpdatedAt.lt(until_value))
+            .all(db)
+            .await
+            .map_err(|e| format!("Failed to get tasks: {}", e))?;
+        for task in tasks {
+            let Some(at) = task.updated_at else {
+                continue;
+            };
+            entries.push(entry(
+                at.with_timezone(&Utc),
+                TimelineKind::Task,
+                task.title,
+                Some(task.status),
+                None,
+            ));
+        }
+    }
+
+    // SDK and environment changes
+    let changes =
+        LearningEventRepository::get_by_types_since(db, &[SDK_SWITCHED, ENV_CHANGED], since)
+            .await
+            .map_err(|e| format!("Failed to get environment changes: {}", e))?;
+    for change in changes {
+        let Some(at) = change.created_at.map(|at| at.with_timezone(&Utc)) else {
+            continue;
+        };
+        if !in_range(at) {
+            continue;
+        }
+        entries.push(entry(
+            at,
+            TimelineKind::Environment,
+            anomaly_detector::describe_change(&change.event_type, &change.event_data),
+            None,
+            change.context,
+        ));
+    }
+
+    if let Some(project) = project {
+        entries.retain(|entry| {
+            entry
+                .project
+                .as_deref()
+                .is_some_and(|path| Path::new(path).starts_with(project))
+        });
+    }
+    Ok(SessionTimeline {
+        since,
+        until,
+        sessions: group_sessions(entries),
+    })
+}
+
+fn entry(
+    at: DateTime<Utc>,
+    kind: TimelineKind,
+    title: String,
+    detail: Option<String>,
+    project: Option<String>,
+) -> TimelineEntry {
+    TimelineEntry {
+        at,
+        kind,
+        title,
+        detail,
+        project,
+        count: 1,
+    }
+}
+
+/// Order entries, fold back-to-back repeats into one and split them where
+/// the user was idle for longer than the session gap
+fn group_sessions(mut entries: Vec<TimelineEntry>) -> Vec<WorkSession> {
+    entries.sort_by_key(|entry| entry.at);
+    let mut folded: Vec<TimelineEntry> = Vec::new();
+    for entry in entries {
+        if let Some(last) = folded.last_mut() {
+            if last.kind == entry.kind
+                && last.title == entry.title
+                && last.detail == entry.detail
+                && last.project == entry.project
+            {
+                last.count += 1;
+                continue;
+            }
+        }
+        folded.push(entry);
+    }
+    if folded.len() > MAX_ENTRIES {
+        folded.drain(..folded.len() - MAX_ENTRIES);
+    }
+
+    let gap = Duration::minutes(SESSION_GAP_MINUTES);
+    let mut sessions: Vec<WorkSession> = Vec::new();
+    for entry in folded {
+        match sessions.last_mut() {
+            Some(session) if entry.at - session.end <= gap => {
+                session.end = entry.at;
+                session.entries.push(entry);
+            }
+            _ => sessions.push(WorkSession {
+                start: entry.at,
+                end: entry.at,
+                projects: Vec::new(),
+                entries: vec![entry],
+            }),
+        }
+    }
+    for session in &mut sessions {
+        for entry in &session.entries {
+            if let Some(project) = &entry.project {
+                if !session.projects.contains(project) {
+                    session.projects.push(project.clone());
+                }
+            }
+        }
+    }
+    sessions
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,6 +563,56 @@ mod tests {
         assert!(hierarchy.contains(&"global".to_string()));
     }
 
+    #[test]
+    fn timeline_folds_repeats_and_splits_on_idle_gaps() {
+        let at = |minute: i64| {
+            "2026-10-17T09:00:00Z".parse::<DateTime<Utc>>().unwrap() + Duration::minutes(minute)
+        };
+        let command = |minute, title: &str| {
+            entry(
+                at(minute),
+                TimelineKind::Command,
+                title.to_string(),
+                None,
+                Some("/work/app".to_string()),
+            )
+        };
+        let sessions = group_sessions(vec![
+            command(5, "npm test"),
+            command(0, "npm test"),
+            command(10, "git commit"),
+            entry(
+                at(90),
+                TimelineKind::Task,
+                "Ship it".to_string(),
+                None,
+                None,
+            ),
+        ]);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].entries.len(), 2);
+        assert_eq!(sessions[0].entries[0].count, 2);
+        assert_eq!(sessions[0].entries[0].at, at(0));
+        assert_eq!(sessions[0].end, at(10));
+        assert_eq!(sessions[0].projects, vec!["/work/app".to_string()]);
+        assert!(sessions[1].projects.is_empty());
+    }
+
+    #[test]
+    fn resolves_named_days() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        assert_eq!(
+            resolve_day("Yesterday", today),
+            NaiveDate::from_ymd_opt(2026, 10, 17)
+        );
+        assert_eq!(
+            resolve_day("2026-10-01", today),
+            NaiveDate::from_ymd_opt(2026, 10, 1)
+        );
+        assert_eq!(resolve_day("last week", today), None);
+    }
+
     #[test]
     fn test_contexts_match() {
         assert!(ContextManager::contexts_match(
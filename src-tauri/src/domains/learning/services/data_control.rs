@@ -0,0 +1,355 @@
+//! Exporting and purging what the learning system has stored.
+//!
+//! A scope narrows the data to a project, to the adapter (domain) it was
+//! learned from, to a time range and to the kinds of data wanted. Export and
+//! purge take the same scope, so what an export shows is exactly what a purge
+//! with that scope removes. Preferences set by the user, like the ML
+//! intensity, are settings rather than learned data: they are exported but
+//! never purged.
+
+use chrono::{DateTime, Utc};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::domains::learning::repositories::{
+    LearnedPatternRepository, LearningEventRepository, UserPreferenceRepository,
+};
+use crate::entities::{learned_pattern, learning_event, user_preference};
+
+/// `learned_from` of preferences the user set directly
+const USER_SETTING: &str = "user_setting";
+
+/// The domain a piece of learning data came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LearningAdapterKind {
+    Projects,
+    /// SDK versions, and environment variables as part of the toolchain
+    Sdk,
+    Terminal,
+    Ide,
+    Tasks,
+}
+
+impl LearningAdapterKind {
+    fn of_pattern_type(pattern_type: &str) -> Option<Self> {
+        match pattern_type {
+            "command" | "workflow" => Some(Self::Terminal),
+            "framework" | "config" => Some(Self::Projects),
+            "code" => Some(Self::Ide),
+            _ => None,
+        }
+    }
+
+    fn of_event(event_type: &str, data: &Value) -> Option<Self> {
+        match event_type {
+            "command_executed" | "command_anomaly" => Some(Self::Terminal),
+            "sdk_version_switched" | "env_var_changed" => Some(Self::Sdk),
+            "project_created" => Some(Self::Projects),
+            // Feedback belongs with the pattern it was about
+            t if t.starts_with("suggestion_") || t == "pattern_learned" => data["pattern_type"]
+                .as_str()
+                .and_then(Self::of_pattern_type),
+            t if t.starts_with("task_") => Some(Self::Tasks),
+            t if t.starts_with("ide_") => Some(Self::Ide),
+            _ => None,
+        }
+    }
+
+    fn of_preference_type(preference_type: &str) -> Option<Self> {
+        match preference_type {
+            "sdk_version" => Some(Self::Sdk),
+            "ide_config" => Some(Self::Ide),
+            "terminal_setup" => Some(Self::Terminal),
+            t if t.starts_with("task_") => Some(Self::Tasks),
+            t if t.starts_with("project_") || t.starts_with("framework") => Some(Self::Projects),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LearningDataKind {
+    Events,
+    Patterns,
+    Preferences,
+}
+
+/// Which learning data to export or purge. Every field narrows the
+/// selection; the default selects everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LearningDataScope {
+    /// Project directory; matches data learned in it or below it
+    pub project: Option<String>,
+    pub adapter: Option<LearningAdapterKind>,
+    /// Only data created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only data created before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Kinds of data to include; all of them when empty
+    pub kinds: Vec<LearningDataKind>,
+}
+
+impl LearningDataScope {
+    fn includes(&self, kind: LearningDataKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+
+    fn matches(
+        &self,
+        adapter: Option<LearningAdapterKind>,
+        locations: &[Option<&str>],
+        created_at: Option<DateTime<Utc>>,
+    ) -> bool {
+        if self.adapter.is_some() && adapter != self.adapter {
+            return false;
+        }
+        if let Some(project) = &self.project {
+            let inside = locations
+                .iter()
+                .flatten()
+                .any(|location| Path::new(location).starts_with(project));
+            if !inside {
+                return false;
+            }
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        // Undated data can't be placed in a time range
+        created_at.is_some_and(|at| {
+            self.since.is_none_or(|since| at >= since) && self.until.is_none_or(|until| at < until)
+        })
+    }
+
+    fn matches_event(&self, event: &learning_event::Model) -> bool {
+        let data: Value = serde_json::from_str(&event.event_data).unwrap_or_default();
+        self.matches(
+            LearningAdapterKind::of_event(&event.event_type, &data),
+            &[
+                event.context.as_deref(),
+                data["cwd"].as_str(),
+                data["project_path"].as_str(),
+            ],
+            event.created_at.map(|at| at.with_timezone(&Utc)),
+        )
+    }
+
+    fn matches_pattern(&self, pattern: &learned_pattern::Model) -> bool {
+        self.matches(
+            LearningAdapterKind::of_pattern_type(&pattern.pattern_type),
+            &[pattern.context.as_deref()],
+            pattern.created_at.map(|at| at.with_timezone(&Utc)),
+        )
+    }
+
+    fn matches_preference(&self, preference: &user_preference::Model) -> bool {
+        self.matches(
+            LearningAdapterKind::of_preference_type(&preference.preference_type),
+            &[preference.context.as_deref()],
+            preference.created_at.map(|at| at.with_timezone(&Utc)),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LearningDataExport {
+    pub exported_at: DateTime<Utc>,
+    pub scope: LearningDataScope,
+    pub events: Vec<Value>,
+    pub patterns: Vec<Value>,
+    pub preferences: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeResult {
+    pub events_deleted: u64,
+    pub patterns_deleted: u64,
+    pub preferences_deleted: u64,
+}
+
+pub async fn export(
+    db: &DatabaseConnection,
+    scope: LearningDataScope,
+) -> Result<LearningDataExport, String> {
+    let (events, patterns, preferences) = select(db, &scope).await?;
+    Ok(LearningDataExport {
+        exported_at: Utc::now(),
+        events: events.iter().map(event_json).collect(),
+        patterns: patterns.iter().map(pattern_json).collect(),
+        preferences: preferences.iter().map(preference_json).collect(),
+        scope,
+    })
+}
+
+pub async fn purge(
+    db: &DatabaseConnection,
+    scope: &LearningDataScope,
+) -> Result<PurgeResult, String> {
+    let (events, patterns, preferences) = select(db, scope).await?;
+    let events_deleted =
+        LearningEventRepository::delete_by_ids(db, events.iter().map(|e| e.id).collect())
+            .await
+            .map_err(|e| format!("Failed to delete events: {}", e))?;
+    let patterns_deleted =
+        LearnedPatternRepository::delete_by_ids(db, patterns.iter().map(|p| p.id).collect())
+            .await
+            .map_err(|e| format!("Failed to delete patterns: {}", e))?;
+    let learned_preferences = preferences
+        .iter()
+        .filter(|p| p.learned_from.as_deref() != Some(USER_SETTING))
+        .map(|p| p.id)
+        .collect();
+    let preferences_deleted = UserPreferenceRepository::delete_by_ids(db, learned_preferences)
+        .await
+        .map_err(|e| format!("Failed to delete preferences: {}", e))?;
+    Ok(PurgeResult {
+        events_deleted,
+        patterns_deleted,
+        preferences_deleted,
+    })
+}
+
+async fn select(
+    db: &DatabaseConnection,
+    scope: &LearningDataScope,
+) -> Result<
+    (
+        Vec<learning_event::Model>,
+        Vec<learned_pattern::Model>,
+        Vec<user_preference::Model>,
+    ),
+    String,
+> {
+    let mut events = Vec::new();
+    if scope.includes(LearningDataKind::Events) {
+        events = LearningEventRepository::get_all(db)
+            .await
+            .map_err(|e| format!("Failed to get events: {}", e))?;
+        events.retain(|event| scope.matches_event(event));
+    }
+    let mut patterns = Vec::new();
+    if scope.includes(LearningDataKind::Patterns) {
+        patterns = LearnedPatternRepository::get_all(db)
+            .await
+            .map_err(|e| format!("Failed to get patterns: {}", e))?;
+        patterns.retain(|pattern| scope.matches_pattern(pattern));
+    }
+    let mut preferences = Vec::new();
+    if scope.includes(LearningDataKind::Preferences) {
+        preferences = UserPreferenceRepository::get_all(db)
+            .await
+            .map_err(|e| format!("Failed to get preferences: {}", e))?;
+        preferences.retain(|preference| scope.matches_preference(preference));
+    }
+    Ok((events, patterns, preferences))
+}
+
+/// An event as shown to the user, with its data parsed
+pub fn event_json(event: &learning_event::Model) -> Value {
+    let event_data: Value = serde_json::from_str(&event.event_data).unwrap_or_default();
+    json!({
+        "id": event.id,
+        "event_type": event.event_type,
+        "event_data": event_data,
+        "outcome": event.outcome,
+        "context": event.context,
+        "created_at": event.created_at.map(|d| d.to_string()),
+    })
+}
+
+/// A pattern as shown to the user, with its data parsed
+pub fn pattern_json(pattern: &learned_pattern::Model) -> Value {
+    let pattern_data: Value = serde_json::from_str(&pattern.pattern_data).unwrap_or_default();
+    json!({
+        "id": pattern.id,
+        "pattern_type": pattern.pattern_type,
+        "pattern_data": pattern_data,
+        "context": pattern.context,
+        "frequency": pattern.frequency,
+        "last_used": pattern.last_used.map(|d| d.to_string()),
+        "success_rate": pattern.success_rate,
+        "is_important": pattern.is_important,
+        "weight": pattern.weight,
+        "snoozed_until": pattern.snoozed_until.map(|d| d.to_string()),
+        "created_at": pattern.created_at.map(|d| d.to_string()),
+    })
+}
+
+/// A preference as shown to the user, with its value parsed
+pub fn preference_json(preference: &user_preference::Model) -> Value {
+    let preference_value: Value =
+        serde_json::from_str(&preference.preference_value).unwrap_or_default();
+    json!({
+        "id": preference.id,
+        "preference_type": preference.preference_type,
+        "context": preference.context,
+        "preference_value": preference_value,
+        "confidence": preference.confidence,
+        "learned_from": preference.learned_from,
+        "is_important": preference.is_important,
+        "created_at": preference.created_at.map(|d| d.to_string()),
+        "updated_at": preference.updated_at.map(|d| d.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: &str, data: Value, context: Option<&str>) -> learning_event::Model {
+        learning_event::Model {
+            id: 1,
+            event_type: event_type.to_string(),
+            event_data: data.to_string(),
+            outcome: None,
+            context: context.map(str::to_string),
+            created_at: Some(DateTime::parse_from_rfc3339("2026-10-10T12:00:00+00:00").unwrap()),
+        }
+    }
+
+    #[test]
+    fn scope_narrows_by_project_adapter_and_time() {
+        let command = event(
+            "command_executed",
+            json!({ "command": "npm test", "cwd": "/work/app/web" }),
+            None,
+        );
+        let sdk_switch = event(
+            "sdk_version_switched",
+            json!({ "sdk_type": "node" }),
+            Some("/work/app"),
+        );
+
+        let project = LearningDataScope {
+            project: Some("/work/app".to_string()),
+            ..Default::default()
+        };
+        assert!(project.matches_event(&command));
+        assert!(project.matches_event(&sdk_switch));
+        assert!(!project.matches_event(&event("project_created", json!({}), None)));
+
+        let terminal = LearningDataScope {
+            adapter: Some(LearningAdapterKind::Terminal),
+            ..Default::default()
+        };
+        assert!(terminal.matches_event(&command));
+        assert!(!terminal.matches_event(&sdk_switch));
+        assert!(terminal.matches_event(&event(
+            "suggestion_rejected",
+            json!({ "pattern_id": 3, "pattern_type": "command" }),
+            None,
+        )));
+
+        let last_week = LearningDataScope {
+            since: Some("2026-10-11T00:00:00Z".parse().unwrap()),
+            ..Default::default()
+        };
+        assert!(!last_week.matches_event(&command));
+        assert!(LearningDataScope::default().matches_event(&command));
+    }
+}
@@ -1,12 +1,149 @@
 use crate::domains::learning::repositories::{
     LearnedPatternRepository, LearningEventRepository, UserPreferenceRepository,
 };
+use crate::domains::learning::services::pattern_matcher::PatternMatcher;
 use crate::domains::learning::services::{MLIntensity, MLIntensityManager};
+use crate::entities::learned_pattern;
+use chrono::{DateTime, Utc};
 use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 // FUTURE: PreferenceEngine is imported directly for now, will be re-exported when advanced learning features are fully implemented
 use crate::domains::learning::services::preference_engine::PreferenceEngine;
 
+/// Recent events searched for ones behind a suggestion
+const EVENT_LOOKBACK: u64 = 200;
+const MAX_CONTRIBUTING_EVENTS: usize = 3;
+const DEFAULT_SNOOZE_HOURS: u32 = 24;
+const MIN_WEIGHT: f64 = 0.05;
+const MAX_WEIGHT: f64 = 4.0;
+
+/// How a pattern's context relates to the one suggestions are for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextMatch {
+    Exact,
+    /// One context narrows the other, e.g. a framework and framework + tool
+    Partial,
+    /// Learned in another context, or without one
+    None,
+}
+
+impl ContextMatch {
+    fn of(target: Option<&str>, pattern: Option<&str>) -> Self {
+        match (target, pattern) {
+            (Some(target), Some(pattern)) if target == pattern => Self::Exact,
+            (Some(target), Some(pattern))
+                if pattern.starts_with(target) || target.starts_with(pattern) =>
+            {
+                Self::Partial
+            }
+            _ => Self::None,
+        }
+    }
+
+    fn bonus(self) -> f64 {
+        match self {
+            Self::Exact => 1.5,
+            Self::Partial => 1.2,
+            Self::None => 1.0,
+        }
+    }
+}
+
+/// A learning event that fed into a suggestion
+#[derive(Debug, Clone, Serialize)]
+pub struct ContributingEvent {
+    pub id: i32,
+    pub event_type: String,
+    pub outcome: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Where a suggestion came from
+#[derive(Debug, Clone, Serialize)]
+pub struct SuggestionProvenance {
+    pub pattern_type: String,
+    pub context_match: ContextMatch,
+    /// Multiplier left by earlier feedback; 1 without any
+    pub weight: f64,
+    pub last_used: Option<DateTime<Utc>>,
+    /// Recent events that mention the pattern, newest first
+    pub events: Vec<ContributingEvent>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub pattern_id: i32,
+    pub pattern_data: Value,
+    pub frequency: i32,
+    pub success_rate: f64,
+    pub context: Option<String>,
+    /// 0 to 1
+    pub confidence: f64,
+    /// Why this was suggested, for display
+    pub explanation: String,
+    pub provenance: SuggestionProvenance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionFeedback {
+    Accept,
+    Reject,
+    Snooze,
+}
+
+impl SuggestionFeedback {
+    /// The pattern's weight after this feedback
+    fn weight(self, weight: f64) -> f64 {
+        match self {
+            Self::Accept => (weight * 1.25).min(MAX_WEIGHT),
+            Self::Reject => (weight * 0.5).max(MIN_WEIGHT),
+            Self::Snooze => weight,
+        }
+    }
+}
+
+/// Whether an event's data mentions any text value of the pattern, e.g. the
+/// command a command pattern was learned from
+fn event_mentions(event_data: &str, pattern_data: &Value) -> bool {
+    match pattern_data {
+        Value::String(text) => text.len() > 1 && event_data.contains(text.as_str()),
+        Value::Array(items) => items.iter().any(|item| event_mentions(event_data, item)),
+        Value::Object(fields) => fields
+            .values()
+            .any(|value| event_mentions(event_data, value)),
+        _ => false,
+    }
+}
+
+/// One or two sentences on why a pattern is suggested
+fn explain(pattern: &learned_pattern::Model, context_match: ContextMatch) -> String {
+    let times = match pattern.frequency {
+        1 => "once".to_string(),
+        n => format!("{} times", n),
+    };
+    let place = match (context_match, pattern.context.as_deref()) {
+        (ContextMatch::Exact, _) => "in this context".to_string(),
+        (ContextMatch::Partial, Some(context)) => format!("in the related context {}", context),
+        (_, Some(context)) => format!("in {}", context),
+        (_, None) => "across projects".to_string(),
+    };
+    let mut explanation = format!(
+        "Used {} {}, succeeding {:.0}% of the time.",
+        times,
+        place,
+        pattern.success_rate * 100.0
+    );
+    if pattern.weight > 1.0 {
+        explanation.push_str(" Ranked higher because you accepted it before.");
+    } else if pattern.weight < 1.0 {
+        explanation.push_str(" Ranked lower because you rejected it before.");
+    }
+    explanation
+}
+
 /// Core learning orchestrator
 pub struct LearningService {
     intensity_manager: MLIntensityManager,
@@ -102,72 +239,137 @@ impl LearningService {
         Ok(())
     }
 
-    /// Get suggestions based on context
-    /// Uses pattern matching to rank and filter suggestions
+    /// Get suggestions based on context, each with its confidence and why it
+    /// was suggested. Snoozed patterns are left out.
     pub async fn get_suggestions(
         &self,
         db: &DatabaseConnection,
         pattern_type: &str,
         context: Option<&str>,
-    ) -> Result<Vec<Value>, String> {
+    ) -> Result<Vec<Suggestion>, String> {
         let patterns = LearnedPatternRepository::get_by_type_and_context(db, pattern_type, context)
             .await
             .map_err(|e| format!("Failed to get patterns: {}", e))?;
 
-        // Use pattern matcher to score and rank patterns
-        let mut scored_patterns: Vec<(f64, _)> = patterns
+        let now = Utc::now();
+        // Score: frequency * success_rate, scaled by context match and feedback
+        let mut scored_patterns: Vec<(f64, ContextMatch, _)> = patterns
             .iter()
+            .filter(|pattern| !pattern.snoozed_until.is_some_and(|until| until > now))
             .map(|pattern| {
-                // Calculate score: frequency * success_rate with context matching bonus
-                let base_score = pattern.frequency as f64 * pattern.success_rate;
-
-                // Context matching bonus (higher score for exact context match)
-                let context_bonus = if let (Some(ctx), Some(pattern_ctx)) =
-                    (context, &pattern.context)
-                {
-                    if ctx == pattern_ctx.as_str() {
-                        1.5 // Exact match
-                    } else if pattern_ctx.starts_with(ctx) || ctx.starts_with(pattern_ctx.as_str())
-                    {
-                        1.2 // Partial match
-                    } else {
-                        1.0
-                    }
-                } else {
-                    1.0
-                };
-
-                let final_score = base_score * context_bonus;
-                (final_score, pattern)
+                let context_match = ContextMatch::of(context, pattern.context.as_deref());
+                let score = pattern.frequency as f64
+                    * pattern.success_rate
+                    * context_match.bonus()
+                    * pattern.weight;
+                (score, context_match, pattern)
             })
             .collect();
 
         // Sort by score (highest first)
         scored_patterns.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored_patterns.truncate(self.max_patterns_per_context());
 
-        let max_patterns = self.max_patterns_per_context();
-        let top_patterns: Vec<_> = scored_patterns
-            .into_iter()
-            .take(max_patterns)
-            .map(|(_, pattern)| pattern.clone())
-            .collect();
+        let events = LearningEventRepository::get_recent_by_context(db, context, EVENT_LOOKBACK)
+            .await
+            .map_err(|e| format!("Failed to get learning events: {}", e))?;
 
         let mut suggestions = Vec::new();
-        for pattern in top_patterns {
-            if let Ok(data) = serde_json::from_str::<Value>(&pattern.pattern_data) {
-                suggestions.push(json!({
-                    "pattern_id": pattern.id,
-                    "pattern_data": data,
-                    "frequency": pattern.frequency,
-                    "success_rate": pattern.success_rate,
-                    "context": pattern.context,
-                }));
-            }
+        for (_, context_match, pattern) in scored_patterns {
+            let Ok(data) = serde_json::from_str::<Value>(&pattern.pattern_data) else {
+                continue;
+            };
+            let contributing_events = events
+                .iter()
+                .filter(|event| event_mentions(&event.event_data, &data))
+                .take(MAX_CONTRIBUTING_EVENTS)
+                .map(|event| ContributingEvent {
+                    id: event.id,
+                    event_type: event.event_type.clone(),
+                    outcome: event.outcome.clone(),
+                    created_at: event.created_at.map(|at| at.with_timezone(&Utc)),
+                })
+                .collect();
+            let confidence = (PatternMatcher::weighted_context_score(
+                pattern.context.as_deref(),
+                context,
+                pattern.frequency,
+                pattern.success_rate,
+            ) * pattern.weight)
+                .clamp(0.0, 1.0);
+            suggestions.push(Suggestion {
+                pattern_id: pattern.id,
+                pattern_data: data,
+                frequency: pattern.frequency,
+                success_rate: pattern.success_rate,
+                context: pattern.context.clone(),
+                confidence,
+                explanation: explain(pattern, context_match),
+                provenance: SuggestionProvenance {
+                    pattern_type: pattern.pattern_type.clone(),
+                    context_match,
+                    weight: pattern.weight,
+                    last_used: pattern.last_used.map(|at| at.with_timezone(&Utc)),
+                    events: contributing_events,
+                },
+            });
         }
 
         Ok(suggestions)
     }
 
+    /// Apply the user's response to a suggestion: accepting boosts the
+    /// pattern and counts as a successful use, rejecting halves its weight,
+    /// and snoozing hides it for `snooze_hours` (a day by default)
+    pub async fn record_suggestion_feedback(
+        &self,
+        db: &DatabaseConnection,
+        pattern_id: i32,
+        feedback: SuggestionFeedback,
+        snooze_hours: Option<u32>,
+    ) -> Result<(), String> {
+        let pattern = LearnedPatternRepository::get_by_id(db, pattern_id)
+            .await
+            .map_err(|e| format!("Failed to get pattern: {}", e))?
+            .ok_or_else(|| format!("Pattern with id {} not found", pattern_id))?;
+
+        let snoozed_until = match feedback {
+            SuggestionFeedback::Snooze => Some(
+                Utc::now()
+                    + chrono::Duration::hours(i64::from(
+                        snooze_hours.unwrap_or(DEFAULT_SNOOZE_HOURS),
+                    )),
+            ),
+            _ => pattern.snoozed_until.map(|until| until.with_timezone(&Utc)),
+        };
+        LearnedPatternRepository::apply_feedback(
+            db,
+            pattern_id,
+            feedback.weight(pattern.weight),
+            snoozed_until,
+        )
+        .await
+        .map_err(|e| format!("Failed to apply suggestion feedback: {}", e))?;
+        if feedback == SuggestionFeedback::Accept {
+            self.record_pattern_outcome(db, pattern_id, true).await?;
+        }
+
+        let (event_type, outcome) = match feedback {
+            SuggestionFeedback::Accept => ("suggestion_accepted", "success"),
+            SuggestionFeedback::Reject => ("suggestion_rejected", "failure"),
+            SuggestionFeedback::Snooze => ("suggestion_snoozed", "ignored"),
+        };
+        self.record_event(
+            db,
+            event_type.to_string(),
+            json!({ "pattern_id": pattern_id, "pattern_type": pattern.pattern_type }),
+            Some(outcome.to_string()),
+            pattern.context,
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Learn user preference
     /// Uses preference engine to calculate confidence based on context and frequency
     pub async fn learn_preference(
@@ -251,3 +453,49 @@ impl LearningService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feedback_adjusts_weight_within_bounds() {
+        assert_eq!(SuggestionFeedback::Accept.weight(1.0), 1.25);
+        assert_eq!(SuggestionFeedback::Accept.weight(MAX_WEIGHT), MAX_WEIGHT);
+        assert_eq!(SuggestionFeedback::Reject.weight(0.06), MIN_WEIGHT);
+        assert_eq!(SuggestionFeedback::Snooze.weight(0.5), 0.5);
+    }
+
+    #[test]
+    fn suggestions_are_explained() {
+        let pattern = learned_pattern::Model {
+            id: 1,
+            pattern_type: "command".to_string(),
+            pattern_data: r#"{"command":"npm run dev"}"#.to_string(),
+            context: Some("fw_react".to_string()),
+            frequency: 12,
+            last_used: None,
+            success_rate: 0.92,
+            is_important: false,
+            weight: 0.5,
+            snoozed_until: None,
+            created_at: None,
+        };
+        assert_eq!(
+            explain(
+                &pattern,
+                ContextMatch::of(Some("fw_react"), Some("fw_react"))
+            ),
+            "Used 12 times in this context, succeeding 92% of the time. \
+             Ranked lower because you rejected it before."
+        );
+        assert!(event_mentions(
+            r#"{"command":"npm run dev","exit_code":0}"#,
+            &serde_json::from_str(&pattern.pattern_data).unwrap()
+        ));
+        assert!(!event_mentions(
+            r#"{"command":"cargo build"}"#,
+            &json!({ "command": "npm run dev" })
+        ));
+    }
+}
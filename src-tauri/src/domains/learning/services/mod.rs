@@ -1,7 +1,10 @@
 pub mod adapters;
+pub mod anomaly_detector;
 pub mod code_pattern_analyzer;
+pub mod command_predictor;
 pub mod context_analyzer;
 pub mod context_manager;
+pub mod data_control;
 pub mod learning_service;
 pub mod memory_manager;
 pub mod ml_intensity_manager;
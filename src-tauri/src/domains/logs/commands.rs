@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+
+use crate::domains::settings::services::settings_service::{LoggingSettings, SettingsService};
+use crate::utils::log_store::{LogQuery, LogRecord};
+use crate::utils::logger::{self, LogLevel};
+
+/// Recent backend logs, filtered by level, domain, time and text
+#[tauri::command]
+pub async fn get_app_logs(query: Option<LogQuery>) -> Result<Vec<LogRecord>, String> {
+    Ok(logger::logger().query(&query.unwrap_or_default()))
+}
+
+/// Zip the log files with basic system details at `path`, for attaching to a
+/// bug report
+#[tauri::command]
+pub async fn export_logs_zip(app: AppHandle, path: String) -> Result<String, String> {
+    let system = serde_json::json!({
+        "app_version": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+    });
+    let system = serde_json::to_vec_pretty(&system)
+        .map_err(|e| format!("Failed to serialize system details: {}", e))?;
+    logger::logger().export_zip(Path::new(&path), &[("system.json", system)])?;
+    Ok(path)
+}
+
+/// Set the lowest level logged, globally when `target` is omitted or for one
+/// domain such as "kubernetes". Clearing a domain's level (`level` omitted)
+/// returns it to the global level. Applies right away and is saved to
+/// settings.
+#[tauri::command]
+pub async fn set_log_level(
+    target: Option<String>,
+    level: Option<String>,
+) -> Result<LoggingSettings, String> {
+    let level = level
+        .map(|level| level.parse::<LogLevel>())
+        .transpose()?
+        .map(|level| level.to_string().to_lowercase());
+    let service = SettingsService::new();
+    let mut settings = service.load_settings()?;
+    let logging = &mut settings.app.logging;
+    match target
+        .map(|target| target.trim().to_lowercase())
+        .filter(|target| !target.is_empty() && target != "global")
+    {
+        Some(domain) => {
+            logging
+                .domains
+                .retain(|existing, _| !existing.eq_ignore_ascii_case(&domain));
+            if let Some(level) = level {
+                logging.domains.insert(domain, level);
+            }
+        }
+        None => {
+            logging.level = level.ok_or("A level is required for the global target")?;
+        }
+    }
+    // Saving applies the new levels to the logger
+    service.save_settings(&settings)?;
+    Ok(settings.app.logging)
+}
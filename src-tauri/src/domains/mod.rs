@@ -5,7 +5,9 @@ pub mod coder;
 pub mod credentials;
 pub mod custom_scripts;
 pub mod dashboard;
+pub mod database;
 pub mod deployments;
+pub mod diagnostics;
 pub mod disk;
 pub mod documents;
 pub mod environment;
@@ -14,7 +16,9 @@ pub mod ide;
 pub mod kubernetes;
 pub mod languages;
 pub mod learning;
+pub mod logs;
 pub mod network;
+pub mod notifications;
 pub mod package_managers;
 pub mod projects;
 pub mod scripts;
@@ -26,5 +30,4 @@ pub mod terminal;
 pub mod updates;
 
 // Future domains will be added here:
-// pub mod notifications;
 // pub mod performance;
@@ -0,0 +1,40 @@
+//! Notification tools for the AI assistant, workflows and scheduled jobs
+use super::services::{NewNotification, NotificationService};
+use crate::domains::ai::tools::{ToolRegistry, ToolSpec};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::Manager;
+
+pub fn register_ai_tools(registry: &ToolRegistry) {
+    registry.register(
+        ToolSpec {
+            name: "send_notification".to_string(),
+            description: "Show a desktop notification, kept in the notification center; \
+                          `link` is an app route opened when it is clicked"
+                .to_string(),
+            domain: "notifications".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "body": { "type": "string" },
+                    "level": { "type": "string", "enum": ["info", "success", "warning", "error"] },
+                    "source": { "type": "string" },
+                    "link": { "type": "string" }
+                },
+                "required": ["title"]
+            }),
+        },
+        |app, args: Value| async move {
+            let notification: NewNotification =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+            let service = app
+                .try_state::<Arc<NotificationService>>()
+                .ok_or("Notifications aren't available yet")?
+                .inner()
+                .clone();
+            let saved = service.notify(&app, notification).await?;
+            serde_json::to_value(saved).map_err(|e| e.to_string())
+        },
+    );
+}
@@ -0,0 +1,50 @@
+use super::entities::notification::Model as Notification;
+use super::services::{NewNotification, NotificationService};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn send_notification(
+    app: AppHandle,
+    notification: NewNotification,
+    service: State<'_, Arc<NotificationService>>,
+) -> Result<Notification, String> {
+    service.notify(&app, notification).await
+}
+
+/// The notification center, newest first
+#[tauri::command]
+pub async fn list_notifications(
+    include_dismissed: Option<bool>,
+    limit: Option<u64>,
+    service: State<'_, Arc<NotificationService>>,
+) -> Result<Vec<Notification>, String> {
+    service
+        .list(include_dismissed.unwrap_or(false), limit.unwrap_or(100))
+        .await
+}
+
+#[tauri::command]
+pub async fn dismiss_notification(
+    id: i32,
+    service: State<'_, Arc<NotificationService>>,
+) -> Result<(), String> {
+    service.dismiss(id).await
+}
+
+#[tauri::command]
+pub async fn dismiss_all_notifications(
+    service: State<'_, Arc<NotificationService>>,
+) -> Result<(), String> {
+    service.dismiss_all().await
+}
+
+/// Open a notification's link in the app, as clicking it on the desktop does
+#[tauri::command]
+pub async fn open_notification(
+    app: AppHandle,
+    id: i32,
+    service: State<'_, Arc<NotificationService>>,
+) -> Result<Notification, String> {
+    service.open(&app, id).await
+}
@@ -0,0 +1,9 @@
+//! Notifications domain.
+//!
+//! Native desktop notifications raised by workflows, pipelines and scheduled
+//! jobs, and the notification center that keeps them.
+
+pub mod ai_tools;
+pub mod commands;
+pub mod entities;
+pub mod services;
@@ -0,0 +1,124 @@
+//! Native desktop notifications.
+//!
+//! Shown with each platform's own tool, so no notification daemon bindings
+//! are needed: `notify-send` on Linux, `osascript` on macOS and a WinRT
+//! toast through PowerShell on Windows. Text is passed as arguments or
+//! environment variables, never spliced into a script. Only `notify-send`
+//! reports clicks; elsewhere the notification center opens the link.
+
+use super::notification_service::NotificationLevel;
+use crate::process_ext::NoWindowExt;
+use tokio::process::Command;
+
+// osascript notifications always show as coming from Script Editor
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+const APP_NAME: &str = "Portal Desktop";
+
+/// Show a notification; `on_click` runs if the user clicks it and the
+/// platform says so. Failures are logged, not returned: the notification is
+/// in the notification center either way.
+pub fn show<F>(title: String, body: String, level: NotificationLevel, on_click: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        match show_native(&title, &body, level).await {
+            Ok(true) => on_click(),
+            Ok(false) => {}
+            Err(e) => eprintln!("[Notifications] Failed to show notification: {}", e),
+        }
+    });
+}
+
+/// Returns whether the notification was clicked
+#[cfg(target_os = "linux")]
+async fn show_native(title: &str, body: &str, level: NotificationLevel) -> Result<bool, String> {
+    let urgency = match level {
+        NotificationLevel::Error => "critical",
+        NotificationLevel::Warning => "normal",
+        _ => "low",
+    };
+    let run = |with_action: bool| {
+        let mut cmd = Command::new("notify-send");
+        cmd.no_window()
+            .args(["--app-name", APP_NAME, "--urgency", urgency]);
+        if with_action {
+            // Waits until the notification closes and prints the clicked action
+            cmd.args(["--action", "default=Open", "--wait"]);
+        }
+        cmd.arg("--").arg(title).arg(body);
+        cmd.output()
+    };
+
+    let output = run(true)
+        .await
+        .map_err(|e| format!("notify-send isn't available: {}", e))?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).trim() == "default");
+    }
+    // notify-send before 0.7.9 has no actions
+    let output = run(false)
+        .await
+        .map_err(|e| format!("notify-send isn't available: {}", e))?;
+    if output.status.success() {
+        Ok(false)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn show_native(title: &str, body: &str, _level: NotificationLevel) -> Result<bool, String> {
+    let output = Command::new("osascript")
+        .no_window()
+        .args([
+            "-e",
+            "on run argv",
+            "-e",
+            "display notification (item 2 of argv) with title (item 1 of argv)",
+            "-e",
+            "end run",
+            title,
+            body,
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if output.status.success() {
+        Ok(false)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn show_native(title: &str, body: &str, _level: NotificationLevel) -> Result<bool, String> {
+    const SCRIPT: &str = r#"
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+$template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02)
+$text = $template.GetElementsByTagName('text')
+$text.Item(0).AppendChild($template.CreateTextNode($env:PORTAL_NOTIFY_TITLE)) | Out-Null
+$text.Item(1).AppendChild($template.CreateTextNode($env:PORTAL_NOTIFY_BODY)) | Out-Null
+$toast = [Windows.UI.Notifications.ToastNotification]::new($template)
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:PORTAL_NOTIFY_APP).Show($toast)
+"#;
+    let output = Command::new("powershell")
+        .no_window()
+        .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+        .env("PORTAL_NOTIFY_TITLE", title)
+        .env("PORTAL_NOTIFY_BODY", body)
+        .env("PORTAL_NOTIFY_APP", APP_NAME)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+    if output.status.success() {
+        Ok(false)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+async fn show_native(_title: &str, _body: &str, _level: NotificationLevel) -> Result<bool, String> {
+    Err(format!("{} can't show notifications on this platform", APP_NAME))
+}
@@ -0,0 +1,4 @@
+pub mod desktop;
+pub mod notification_service;
+
+pub use notification_service::{NewNotification, NotificationLevel, NotificationService};
@@ -0,0 +1,241 @@
+//! Raises notifications for workflows, pipelines and scheduled jobs. Every
+//! notification is stored for the notification center and sent to the
+//! frontend as `notification`; it is also shown on the desktop when the
+//! notification settings allow its level. Opening one, from the desktop or
+//! the center, brings the window forward and emits `notification-open` with
+//! its link for the frontend to navigate to.
+
+use super::desktop;
+use crate::database::DatabaseManager;
+use crate::domains::notifications::entities::notification::{
+    ActiveModel as NotificationActive, Column as NotificationColumn, Entity as NotificationEntity,
+    Model as Notification,
+};
+use crate::domains::settings::services::settings_service::{
+    NotificationSettings, SettingsService,
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const NOTIFICATION_EVENT: &str = "notification";
+pub const NOTIFICATION_OPEN_EVENT: &str = "notification-open";
+
+/// Scheme of deep links into the app; `portal://projects/3` is `/projects/3`
+const LINK_SCHEME: &str = "portal://";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            NotificationLevel::Info => "info",
+            NotificationLevel::Success => "success",
+            NotificationLevel::Warning => "warning",
+            NotificationLevel::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NewNotification {
+    pub title: String,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub level: NotificationLevel,
+    /// What raised it, e.g. `workflow:deploy` or `pipeline:3`
+    pub source: Option<String>,
+    /// In-app route to open on click, e.g. `/projects/3/pipelines`
+    pub link: Option<String>,
+}
+
+pub struct NotificationService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl NotificationService {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    pub async fn notify(
+        &self,
+        app: &AppHandle,
+        notification: NewNotification,
+    ) -> Result<Notification, String> {
+        let title = notification.title.trim();
+        if title.is_empty() {
+            return Err("Notification title is required".to_string());
+        }
+        let link = notification.link.as_deref().map(normalize_link).transpose()?;
+
+        let saved = NotificationActive {
+            title: Set(title.to_string()),
+            body: Set(notification.body.clone()),
+            level: Set(notification.level.as_str().to_string()),
+            source: Set(notification.source.clone()),
+            link: Set(link),
+            dismissed: Set(false),
+            created_at: Set(Some(chrono::Utc::now().fixed_offset())),
+            ..Default::default()
+        }
+        .insert(self.db_manager.get_connection())
+        .await
+        .map_err(|e| format!("Failed to save notification: {}", e))?;
+        let _ = app.emit(NOTIFICATION_EVENT, &saved);
+
+        let settings = SettingsService::new()
+            .load_settings()
+            .map(|settings| settings.app.notifications)
+            .ok();
+        if settings.is_none_or(|settings| shows_on_desktop(&settings, notification.level)) {
+            let app = app.clone();
+            let id = saved.id;
+            desktop::show(
+                saved.title.clone(),
+                saved.body.clone().unwrap_or_default(),
+                notification.level,
+                move || {
+                    tauri::async_runtime::spawn(async move {
+                        let service = app.state::<Arc<NotificationService>>().inner().clone();
+                        if let Err(e) = service.open(&app, id).await {
+                            eprintln!("[Notifications] Failed to open notification: {}", e);
+                        }
+                    });
+                },
+            );
+        }
+        Ok(saved)
+    }
+
+    /// Newest first
+    pub async fn list(
+        &self,
+        include_dismissed: bool,
+        limit: u64,
+    ) -> Result<Vec<Notification>, String> {
+        let mut query = NotificationEntity::find();
+        if !include_dismissed {
+            query = query.filter(NotificationColumn::Dismissed.eq(false));
+        }
+        query
+            .order_by_desc(NotificationColumn::Id)
+            .limit(limit)
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load notifications: {}", e))
+    }
+
+    pub async fn dismiss(&self, id: i32) -> Result<(), String> {
+        NotificationEntity::update_many()
+            .col_expr(NotificationColumn::Dismissed, Expr::value(true))
+            .filter(NotificationColumn::Id.eq(id))
+            .exec(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to dismiss notification: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn dismiss_all(&self) -> Result<(), String> {
+        NotificationEntity::update_many()
+            .col_expr(NotificationColumn::Dismissed, Expr::value(true))
+            .filter(NotificationColumn::Dismissed.eq(false))
+            .exec(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to dismiss notifications: {}", e))?;
+        Ok(())
+    }
+
+    /// Bring the app forward at the notification's link, dismissing it
+    pub async fn open(&self, app: &AppHandle, id: i32) -> Result<Notification, String> {
+        let notification = NotificationEntity::find_by_id(id)
+            .one(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load notification: {}", e))?
+            .ok_or_else(|| "Notification not found".to_string())?;
+
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        let _ = app.emit(NOTIFICATION_OPEN_EVENT, &notification);
+        self.dismiss(id).await?;
+        Ok(notification)
+    }
+}
+
+fn shows_on_desktop(settings: &NotificationSettings, level: NotificationLevel) -> bool {
+    settings.enabled
+        && settings.desktop_notifications
+        && match level {
+            NotificationLevel::Info => settings.types.info,
+            NotificationLevel::Success => settings.types.success,
+            NotificationLevel::Warning => settings.types.warning,
+            NotificationLevel::Error => settings.types.error,
+        }
+}
+
+/// Links are in-app routes; `portal://` deep links are accepted as routes
+fn normalize_link(link: &str) -> Result<String, String> {
+    let link = link.trim();
+    let route = match link.strip_prefix(LINK_SCHEME) {
+        Some(rest) => format!("/{}", rest.trim_start_matches('/')),
+        None => link.to_string(),
+    };
+    if !route.starts_with('/') || route.starts_with("//") {
+        return Err(format!(
+            "Notification links must be app routes like /projects/3: {}",
+            link
+        ));
+    }
+    Ok(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::settings::services::settings_service::NotificationTypeSettings;
+
+    #[test]
+    fn routes_links_and_filters_levels() {
+        assert_eq!(normalize_link("/projects/3").unwrap(), "/projects/3");
+        assert_eq!(
+            normalize_link("portal://projects/3/pipelines").unwrap(),
+            "/projects/3/pipelines"
+        );
+        assert!(normalize_link("https://example.com").is_err());
+        assert!(normalize_link("//example.com").is_err());
+
+        let mut settings = NotificationSettings {
+            enabled: true,
+            desktop_notifications: true,
+            sound_enabled: true,
+            show_in_taskbar: true,
+            types: NotificationTypeSettings {
+                success: false,
+                info: true,
+                warning: true,
+                error: true,
+                updates: true,
+                security: true,
+            },
+        };
+        assert!(shows_on_desktop(&settings, NotificationLevel::Error));
+        assert!(!shows_on_desktop(&settings, NotificationLevel::Success));
+        settings.desktop_notifications = false;
+        assert!(!shows_on_desktop(&settings, NotificationLevel::Error));
+    }
+}
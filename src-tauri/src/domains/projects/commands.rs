@@ -1,6 +1,18 @@
 use crate::database::DatabaseManager;
+use crate::domains::automation::services::automation_service::AutomationService;
+use crate::domains::automation::services::event_bus;
 use crate::domains::projects::entities::ProjectAnalysis;
+use crate::domains::projects::services::framework_detection::FrameworkCandidate;
+use crate::domains::projects::services::project_clone::{
+    CloneProjectRequest, ClonedProject, ProjectCloneService,
+};
+use crate::domains::projects::services::project_group_service::{
+    ProjectGroupNode, ProjectGroupService,
+};
+use crate::domains::projects::services::quick_open::{self, IndexedProject, QuickOpenResult};
 use crate::domains::projects::services::ProjectService;
+use crate::entities::project_group::Model as ProjectGroupModel;
+use std::collections::HashSet;
 use std::sync::Arc;
 use tauri::command;
 
@@ -38,7 +50,7 @@ pub async fn create_project(
     db_manager: tauri::State<'_, Arc<DatabaseManager>>,
 ) -> Result<crate::domains::projects::entities::ProjectResponse, String> {
     let service = ProjectService::new(&db_manager);
-    service
+    let project = service
         .create_project(
             name,
             description,
@@ -53,7 +65,43 @@ pub async fn create_project(
             dev_port,
             prod_port,
         )
-        .await
+        .await?;
+
+    event_bus::publish(
+        event_bus::PROJECT_CREATED,
+        serde_json::json!({
+            "project_id": project.id,
+            "name": project.name,
+            "project_path": project.path,
+        }),
+    );
+    Ok(project)
+}
+
+/// Clone a git repository, analyze it and register it as a project in one
+/// step. Progress is emitted as `project-clone-progress` events carrying
+/// `clone_id`, generated when not given.
+#[command]
+pub async fn clone_project(
+    request: CloneProjectRequest,
+    clone_id: Option<String>,
+    app_handle: tauri::AppHandle,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<ClonedProject, String> {
+    let clone_id = clone_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cloned = ProjectCloneService::new(&db_manager)
+        .clone_project(&app_handle, &clone_id, request)
+        .await?;
+
+    event_bus::publish(
+        event_bus::PROJECT_CREATED,
+        serde_json::json!({
+            "project_id": cloned.project.id,
+            "name": cloned.project.name,
+            "project_path": cloned.project.path,
+        }),
+    );
+    Ok(cloned)
 }
 
 #[command]
@@ -99,9 +147,24 @@ pub async fn update_project(
 pub async fn delete_project(
     id: i32,
     db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+    automation_service: tauri::State<'_, Arc<AutomationService>>,
 ) -> Result<bool, String> {
+    // Looked up first: the mappings go with the project
+    let n8n_workflows = automation_service
+        .list_project_workflows(db_manager.get_connection(), id)
+        .await
+        .unwrap_or_default();
+
     let service = ProjectService::new(&db_manager);
-    service.delete_project(id).await
+    let deleted = service.delete_project(id).await?;
+
+    // The project's n8n workflows would otherwise outlive it
+    if deleted && !n8n_workflows.is_empty() {
+        automation_service
+            .cleanup_workflows(db_manager.get_connection(), &n8n_workflows)
+            .await;
+    }
+    Ok(deleted)
 }
 
 #[command]
@@ -119,7 +182,19 @@ pub async fn open_project(
     db_manager: tauri::State<'_, Arc<DatabaseManager>>,
 ) -> Result<Option<crate::domains::projects::entities::ProjectResponse>, String> {
     let service = ProjectService::new(&db_manager);
-    service.open_project(id).await
+    let project = service.open_project(id).await?;
+
+    if let Some(project) = &project {
+        event_bus::publish(
+            event_bus::PROJECT_OPENED,
+            serde_json::json!({
+                "project_id": project.id,
+                "name": project.name,
+                "project_path": project.path,
+            }),
+        );
+    }
+    Ok(project)
 }
 
 #[command]
@@ -131,16 +206,100 @@ pub async fn refresh_project_metadata(
     service.refresh_project_metadata(id).await
 }
 
+/// Projects matching the filters. With `group_id`, only the projects in that
+/// group and, unless `include_subgroups` is false, the groups nested in it.
 #[command]
 pub async fn get_projects_with_filters(
     status_filter: Option<String>,
     sort_by: String,
     search_query: Option<String>,
+    group_id: Option<i32>,
+    include_subgroups: Option<bool>,
     db_manager: tauri::State<'_, Arc<DatabaseManager>>,
 ) -> Result<Vec<crate::domains::projects::entities::ProjectResponse>, String> {
+    let group_ids = match group_id {
+        Some(group_id) if include_subgroups.unwrap_or(true) => Some(
+            ProjectGroupService::new(&db_manager)
+                .group_and_subgroup_ids(group_id)
+                .await?,
+        ),
+        Some(group_id) => Some(HashSet::from([group_id])),
+        None => None,
+    };
     let service = ProjectService::new(&db_manager);
     service
-        .get_projects_with_filters(status_filter, sort_by, search_query)
+        .get_projects_with_filters(status_filter, sort_by, search_query, group_ids.as_ref())
+        .await
+}
+
+/// The project group hierarchy, with the projects in each group
+#[command]
+pub async fn get_project_groups(
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<ProjectGroupNode>, String> {
+    ProjectGroupService::new(&db_manager).get_tree().await
+}
+
+#[command]
+pub async fn create_project_group(
+    name: String,
+    kind: String,
+    parent_id: Option<i32>,
+    color: Option<String>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<ProjectGroupModel, String> {
+    ProjectGroupService::new(&db_manager)
+        .create(name, kind, parent_id, color)
+        .await
+}
+
+/// Rename a group or change its kind or color; an empty color clears it
+#[command]
+pub async fn update_project_group(
+    id: i32,
+    name: Option<String>,
+    kind: Option<String>,
+    color: Option<String>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<ProjectGroupModel, String> {
+    let color = color.map(|color| Some(color).filter(|color| !color.is_empty()));
+    ProjectGroupService::new(&db_manager)
+        .update(id, name, kind, color)
+        .await
+}
+
+/// Delete a group; its subgroups and projects move up to its parent
+#[command]
+pub async fn delete_project_group(
+    id: i32,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    ProjectGroupService::new(&db_manager).delete(id).await
+}
+
+/// Drop a group under `parent_id` (top level without one) at `position`
+#[command]
+pub async fn move_project_group(
+    id: i32,
+    parent_id: Option<i32>,
+    position: Option<usize>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    ProjectGroupService::new(&db_manager)
+        .move_group(id, parent_id, position)
+        .await
+}
+
+/// Drop a project into `group_id` (ungrouped without one) at `position`
+#[command]
+pub async fn move_project_to_group(
+    project_id: i32,
+    group_id: Option<i32>,
+    position: Option<usize>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    ProjectGroupService::new(&db_manager)
+        .move_project(project_id, group_id, position)
         .await
 }
 
@@ -191,6 +350,17 @@ pub async fn detect_framework(
     service.detect_frameworks(&path).await
 }
 
+/// Frameworks the project looks like, with confidence scores and the files
+/// that gave them away
+#[command]
+pub async fn detect_framework_candidates(
+    path: String,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<FrameworkCandidate>, String> {
+    let service = ProjectService::new(&db_manager);
+    service.detect_framework_candidates(&path).await
+}
+
 #[command]
 pub async fn analyze_project_directory(
     path: String,
@@ -200,6 +370,74 @@ pub async fn analyze_project_directory(
     service.analyze_project_directory(&path).await
 }
 
+/// Fuzzy-search the files of the projects, all of them without
+/// `project_ids`; recently opened files rank higher
+#[command]
+pub async fn quick_open_search(
+    query: String,
+    project_ids: Option<Vec<i32>>,
+    limit: Option<usize>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<QuickOpenResult>, String> {
+    let projects = indexed_projects(&db_manager, project_ids).await?;
+    quick_open::search(&projects, &query, limit.unwrap_or(50)).await
+}
+
+/// Files recently opened in the projects, newest first
+#[command]
+pub async fn get_recent_files(
+    project_ids: Option<Vec<i32>>,
+    limit: Option<usize>,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<QuickOpenResult>, String> {
+    let projects = indexed_projects(&db_manager, project_ids).await?;
+    quick_open::recent_in(&projects, limit.unwrap_or(50))
+}
+
+/// Record a file opened from the quick-open palette
+#[command]
+pub async fn record_recent_file(path: String) -> Result<(), String> {
+    quick_open::RecentFiles::new()?
+        .record(std::path::Path::new(&path), "palette")
+        .await
+}
+
+/// Index a project's files again; returns how many there are
+#[command]
+pub async fn refresh_file_index(
+    project_id: i32,
+    db_manager: tauri::State<'_, Arc<DatabaseManager>>,
+) -> Result<usize, String> {
+    let projects = indexed_projects(&db_manager, Some(vec![project_id])).await?;
+    let project = projects
+        .first()
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    Ok(quick_open::project_files(&project.root, true).await?.len())
+}
+
+async fn indexed_projects(
+    db_manager: &Arc<DatabaseManager>,
+    project_ids: Option<Vec<i32>>,
+) -> Result<Vec<IndexedProject>, String> {
+    let projects = ProjectService::new(db_manager)
+        .get_all_projects()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(projects
+        .into_iter()
+        .filter(|project| {
+            project_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&project.id))
+        })
+        .map(|project| IndexedProject {
+            id: project.id,
+            name: project.name,
+            root: std::path::PathBuf::from(project.path),
+        })
+        .collect())
+}
+
 #[command]
 pub async fn open_project_in_explorer(path: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::database::ProjectModel;
+use crate::domains::ide::services::devcontainer::{self, Devcontainer};
 
 /// API response for projects including many-to-many relationship IDs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,11 +28,18 @@ pub struct ProjectResponse {
     pub git_commit: Option<String>,
     pub has_uncommitted_changes: bool,
     pub last_commit: Option<DateTime<Utc>>,
+    /// Group the project is in; `None` when ungrouped
+    pub group_id: Option<i32>,
+    /// Order among the projects of its group
+    pub position: i32,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
     pub framework_ids: Vec<i32>,
     pub package_manager_ids: Vec<i32>,
     pub language_ids: Vec<i32>,
+    /// Dev container configuration found in the project
+    #[serde(default)]
+    pub devcontainer: Option<Devcontainer>,
 }
 
 impl ProjectResponse {
@@ -41,6 +49,7 @@ impl ProjectResponse {
         package_manager_ids: Vec<i32>,
         language_ids: Vec<i32>,
     ) -> Self {
+        let devcontainer = devcontainer::detect(std::path::Path::new(&model.path));
         Self {
             id: model.id,
             name: model.name,
@@ -63,11 +72,14 @@ impl ProjectResponse {
             git_commit: model.git_commit,
             has_uncommitted_changes: model.has_uncommitted_changes,
             last_commit: model.last_commit.map(|dt| dt.into()),
+            group_id: model.group_id,
+            position: model.position,
             created_at: model.created_at.map(|dt| dt.into()),
             updated_at: model.updated_at.map(|dt| dt.into()),
             framework_ids,
             package_manager_ids,
             language_ids,
+            devcontainer,
         }
     }
 }
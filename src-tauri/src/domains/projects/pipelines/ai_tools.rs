@@ -0,0 +1,64 @@
+//! Pipeline tools for the AI assistant
+use super::commands::execute_pipeline;
+use super::services::{ExecutionService, PipelineService};
+use crate::database::DatabaseManager;
+use crate::domains::ai::tools::{ActionPreview, PreviewMethod, ToolRegistry, ToolSpec};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::Manager;
+
+pub fn register_ai_tools(registry: &ToolRegistry) {
+    registry.register(
+        ToolSpec {
+            name: "run_pipeline".to_string(),
+            description: "Start a pipeline run; returns the execution with its id and status"
+                .to_string(),
+            domain: "pipelines".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "pipeline_id": { "type": "string" },
+                    "variables": { "type": "object", "additionalProperties": { "type": "string" } }
+                },
+                "required": ["pipeline_id"]
+            }),
+        },
+        |app, args: Value| async move {
+            let state = app.state::<Arc<ExecutionService>>();
+            execute_pipeline(args, app.clone(), state).await
+        },
+    );
+    registry.register_preview("run_pipeline", |app, args: Value| async move {
+        let pipeline_id = args
+            .get("pipeline_id")
+            .and_then(Value::as_str)
+            .and_then(|id| id.parse::<i32>().ok())
+            .ok_or("pipeline_id must be a pipeline id")?;
+        let db_manager = app.state::<Arc<DatabaseManager>>().inner().clone();
+        let pipeline = PipelineService::new(db_manager)
+            .get_pipeline(pipeline_id)
+            .await?
+            .ok_or_else(|| format!("Pipeline {} not found", pipeline_id))?;
+        let steps = pipeline["steps"].as_array().cloned().unwrap_or_default();
+        let plan: Vec<String> = steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let name = step["name"].as_str().or(step["id"].as_str()).unwrap_or("?");
+                match step["config"]["command"].as_str() {
+                    Some(command) => format!("{}. {}: {}", i + 1, name, command),
+                    None => format!("{}. {}", i + 1, name),
+                }
+            })
+            .collect();
+        Ok(ActionPreview {
+            method: PreviewMethod::Plan,
+            summary: format!(
+                "Run pipeline \"{}\" ({} steps)",
+                pipeline["name"].as_str().unwrap_or_default(),
+                steps.len()
+            ),
+            details: (!plan.is_empty()).then(|| plan.join("\n")),
+        })
+    });
+}
@@ -1,7 +1,11 @@
+use crate::domains::projects::pipelines::services::block_registry::{
+    BlockRegistry, BlockTemplate, BlockTemplateSummary,
+};
 use crate::domains::projects::pipelines::services::{
     ExecutionRequestData, ExecutionService, PipelineService,
 };
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::State;
 
@@ -132,6 +136,42 @@ pub async fn delete_block(
     service.delete_block(&block_id).await
 }
 
+/// Built-in and imported block templates, newest version of each
+#[tauri::command]
+pub async fn get_block_templates() -> Result<Vec<BlockTemplateSummary>, String> {
+    Ok(BlockRegistry::new()?.list())
+}
+
+/// Import block templates from a YAML or JSON file or an http(s) URL
+#[tauri::command]
+pub async fn import_block_templates(source: String) -> Result<Vec<BlockTemplate>, String> {
+    BlockRegistry::new()?.import(source.trim()).await
+}
+
+#[tauri::command]
+pub async fn remove_block_template(key: String, version: Option<String>) -> Result<(), String> {
+    BlockRegistry::new()?.remove(&key, version.as_deref()).await
+}
+
+/// Create a block from a template, with its parameters filled from `values`.
+/// Returns the block and the pipeline variables the template expects.
+#[tauri::command]
+pub async fn instantiate_block_template(
+    key: String,
+    version: Option<String>,
+    values: Option<HashMap<String, String>>,
+    service: State<'_, Arc<PipelineService>>,
+) -> Result<Value, String> {
+    let template = BlockRegistry::new()?.get(&key, version.as_deref())?;
+    let block = service
+        .create_block(template.block_request(&values.unwrap_or_default()))
+        .await?;
+    Ok(json!({
+        "block": block,
+        "variables": template.variables,
+    }))
+}
+
 #[tauri::command]
 pub async fn get_step_execution_logs(
     execution_id: String,
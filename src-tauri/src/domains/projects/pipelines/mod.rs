@@ -1,3 +1,4 @@
+pub mod ai_tools;
 pub mod commands;
 pub mod entities;
 pub mod executors;
@@ -62,6 +62,7 @@ impl PipelineRepository {
         enabled: bool,
         preset_key: Option<String>,
         category: Option<String>,
+        notifications_json: String,
     ) -> Result<PipelineModel, String> {
         let connection = self.db_manager.get_connection();
 
@@ -76,6 +77,7 @@ impl PipelineRepository {
             enabled: Set(enabled),
             preset_key: Set(preset_key),
             category: Set(category),
+            notifications_json: Set(notifications_json),
             ..Default::default()
         };
 
@@ -99,6 +101,7 @@ impl PipelineRepository {
         enabled: Option<bool>,
         preset_key: Option<Option<String>>,
         category: Option<Option<String>>,
+        notifications_json: Option<String>,
     ) -> Result<PipelineModel, String> {
         let connection = self.db_manager.get_connection();
 
@@ -136,6 +139,9 @@ impl PipelineRepository {
         if let Some(category) = category {
             pipeline.category = Set(category);
         }
+        if let Some(notifications_json) = notifications_json {
+            pipeline.notifications_json = Set(notifications_json);
+        }
 
         let result = pipeline
             .update(connection)
@@ -0,0 +1,530 @@
+//! Block templates are shareable definitions of pipeline blocks, written as
+//! YAML or JSON: one template, or `blocks: [...]` for several. Each has a
+//! stable `key` and a `version`; the app ships a few (build, test, lint,
+//! docker build, deploy to Kubernetes) and more can be imported from a file
+//! or an http(s) URL into `~/.portal-desktop/pipelines/block-templates.json`.
+//! Every imported version is kept, and the newest is used unless another is
+//! asked for.
+//!
+//! Instantiating a template creates a block from it: `${param}` placeholders
+//! in its command are filled from the given values or the parameters'
+//! defaults, and the pipeline variables the template expects are returned
+//! for the pipeline to declare.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Serializes imports and removals
+static REGISTRY_LOCK: once_cell::sync::Lazy<Mutex<()>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+const BUILTIN_SOURCE: &str = "builtin";
+const CATEGORIES: &[&str] = &["build", "test", "deploy", "utility", "custom"];
+
+const BUILTIN_TEMPLATES: &str = r#"
+blocks:
+  - key: build
+    name: Build
+    description: Build the project with its package manager
+    category: build
+    version: 1.0.0
+    command: ${packageManager} run ${script}
+    parameters:
+      - { name: packageManager, type: select, description: Package manager, required: true, defaultValue: npm, options: [npm, pnpm, yarn, bun] }
+      - { name: script, type: string, description: Package script to run, required: true, defaultValue: build }
+    tags: [build, node]
+    icon: hammer
+  - key: test
+    name: Test
+    description: Run the project's tests
+    category: test
+    version: 1.0.0
+    command: ${packageManager} run ${script}
+    parameters:
+      - { name: packageManager, type: select, description: Package manager, required: true, defaultValue: npm, options: [npm, pnpm, yarn, bun] }
+      - { name: script, type: string, description: Package script to run, required: true, defaultValue: test }
+    tags: [test, node]
+    icon: flask-conical
+  - key: lint
+    name: Lint
+    description: Check the code with the project's linter
+    category: utility
+    version: 1.0.0
+    command: ${packageManager} run ${script}
+    parameters:
+      - { name: packageManager, type: select, description: Package manager, required: true, defaultValue: npm, options: [npm, pnpm, yarn, bun] }
+      - { name: script, type: string, description: Package script to run, required: true, defaultValue: lint }
+    tags: [lint, node]
+    icon: sparkles
+  - key: docker-build
+    name: Docker Build
+    description: Build a Docker image and tag it
+    category: build
+    version: 1.0.0
+    command: docker build -t ${image}:${tag} -f ${dockerfile} ${context}
+    parameters:
+      - { name: image, type: string, description: Image name, required: true, defaultValue: "${IMAGE_NAME}" }
+      - { name: tag, type: string, description: Image tag, required: true, defaultValue: latest }
+      - { name: dockerfile, type: file, description: Dockerfile, required: true, defaultValue: Dockerfile }
+      - { name: context, type: directory, description: Build context, required: true, defaultValue: . }
+    variables:
+      - { name: IMAGE_NAME, value: "", type: string, description: Image to build, scope: pipeline }
+    tags: [docker, build]
+    icon: container
+  - key: deploy-k8s
+    name: Deploy to Kubernetes
+    description: Apply manifests and wait for the deployment to roll out
+    category: deploy
+    version: 1.0.0
+    command: kubectl apply -n ${namespace} -f ${manifests} && kubectl rollout status deployment/${deployment} -n ${namespace} --timeout=${timeout}
+    parameters:
+      - { name: manifests, type: directory, description: Manifest file or directory, required: true, defaultValue: k8s }
+      - { name: namespace, type: string, description: Namespace, required: true, defaultValue: "${K8S_NAMESPACE}" }
+      - { name: deployment, type: string, description: Deployment to wait for, required: true }
+      - { name: timeout, type: string, description: Rollout timeout, required: false, defaultValue: 5m }
+    variables:
+      - { name: K8S_NAMESPACE, value: default, type: string, description: Namespace to deploy to, scope: pipeline }
+    tags: [kubernetes, deploy]
+    icon: ship
+"#;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTemplate {
+    /// Stable identifier, shared by all versions
+    pub key: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_category")]
+    pub category: String,
+    /// Dotted version; YAML's unquoted `1.0` is taken as "1.0"
+    #[serde(deserialize_with = "version_string")]
+    pub version: String,
+    /// Command with `${param}` placeholders
+    pub command: String,
+    #[serde(default = "default_execution_type")]
+    pub execution_type: String,
+    /// Parameter declarations, as blocks have them
+    #[serde(default)]
+    pub parameters: Vec<Value>,
+    /// Pipeline variables the command expects
+    #[serde(default)]
+    pub variables: Vec<Value>,
+    #[serde(default)]
+    pub default_config: Value,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub icon: Option<String>,
+    pub author: Option<String>,
+}
+
+fn default_category() -> String {
+    "custom".to_string()
+}
+
+fn default_execution_type() -> String {
+    "command".to_string()
+}
+
+fn version_string<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(version) => Ok(version),
+        Value::Number(version) => Ok(version.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a version, found {}",
+            other
+        ))),
+    }
+}
+
+impl BlockTemplate {
+    fn validate(&self) -> Result<(), String> {
+        if self.key.trim().is_empty() || self.key.contains(char::is_whitespace) {
+            return Err(format!("Block template key is invalid: {:?}", self.key));
+        }
+        if self.name.trim().is_empty() || self.command.trim().is_empty() {
+            return Err(format!(
+                "Block template {} needs a name and a command",
+                self.key
+            ));
+        }
+        if version_key(&self.version).is_empty() {
+            return Err(format!(
+                "Block template {} has an invalid version: {}",
+                self.key, self.version
+            ));
+        }
+        if !CATEGORIES.contains(&self.category.as_str()) {
+            return Err(format!(
+                "Block template {} has an unknown category: {}",
+                self.key, self.category
+            ));
+        }
+        if self
+            .parameters
+            .iter()
+            .any(|p| p.get("name").and_then(Value::as_str).is_none())
+        {
+            return Err(format!(
+                "Block template {} has a parameter without a name",
+                self.key
+            ));
+        }
+        Ok(())
+    }
+
+    /// The command with `values` filled in, falling back to each
+    /// parameter's default; placeholders with neither are left as they are
+    pub fn fill_command(&self, values: &HashMap<String, String>) -> String {
+        let mut command = self.command.clone();
+        for parameter in &self.parameters {
+            let Some(name) = parameter.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            let value = values.get(name).cloned().or_else(|| {
+                parameter.get("defaultValue").and_then(|v| match v {
+                    Value::String(text) => Some(text.clone()),
+                    Value::Null => None,
+                    other => Some(other.to_string()),
+                })
+            });
+            if let Some(value) = value {
+                command = command.replace(&format!("${{{}}}", name), &value);
+            }
+        }
+        command
+    }
+
+    /// The create_block request for a block made from this template
+    pub fn block_request(&self, values: &HashMap<String, String>) -> Value {
+        let mut default_config = match &self.default_config {
+            Value::Object(config) => config.clone(),
+            _ => Default::default(),
+        };
+        for (name, value) in values {
+            default_config.insert(name.clone(), json!(value));
+        }
+        let mut tags = self.tags.clone();
+        tags.push(format!("template:{}", self.key));
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "category": self.category,
+            "version": self.version,
+            "command": self.fill_command(values),
+            "executionType": self.execution_type,
+            "parameters": self.parameters,
+            "defaultConfig": default_config,
+            "tags": tags,
+            "icon": self.icon,
+            "author": self.author,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredTemplate {
+    #[serde(flatten)]
+    pub template: BlockTemplate,
+    /// File or URL it was imported from, or "builtin"
+    pub source: String,
+    pub imported_at: Option<String>,
+}
+
+/// A template's newest version, with the versions there are
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTemplateSummary {
+    #[serde(flatten)]
+    pub latest: RegisteredTemplate,
+    pub versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TemplateDocument {
+    Many { blocks: Vec<BlockTemplate> },
+    One(Box<BlockTemplate>),
+}
+
+/// The templates in a YAML or JSON document
+pub fn parse_templates(content: &str) -> Result<Vec<BlockTemplate>, String> {
+    let templates = match serde_yaml::from_str::<TemplateDocument>(content)
+        .map_err(|e| format!("Invalid block template: {}", e))?
+    {
+        TemplateDocument::Many { blocks } => blocks,
+        TemplateDocument::One(template) => vec![*template],
+    };
+    if templates.is_empty() {
+        return Err("No block templates found".to_string());
+    }
+    for template in &templates {
+        template.validate()?;
+    }
+    Ok(templates)
+}
+
+fn builtin_templates() -> Vec<RegisteredTemplate> {
+    parse_templates(BUILTIN_TEMPLATES)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mut template| {
+            template.author.get_or_insert_with(|| "Portal".to_string());
+            RegisteredTemplate {
+                template,
+                source: BUILTIN_SOURCE.to_string(),
+                imported_at: None,
+            }
+        })
+        .collect()
+}
+
+/// Newest version first within each key, keys in name order
+fn summarize(templates: Vec<RegisteredTemplate>) -> Vec<BlockTemplateSummary> {
+    let mut by_key: HashMap<String, Vec<RegisteredTemplate>> = HashMap::new();
+    for template in templates {
+        by_key
+            .entry(template.template.key.clone())
+            .or_default()
+            .push(template);
+    }
+    let mut summaries: Vec<BlockTemplateSummary> = by_key
+        .into_values()
+        .filter_map(|mut versions| {
+            versions.sort_by(|a, b| compare_versions(&b.template.version, &a.template.version));
+            let names = versions
+                .iter()
+                .map(|t| t.template.version.clone())
+                .collect();
+            Some(BlockTemplateSummary {
+                latest: versions.into_iter().next()?,
+                versions: names,
+            })
+        })
+        .collect();
+    summaries.sort_by(|a, b| a.latest.template.name.cmp(&b.latest.template.name));
+    summaries
+}
+
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    version_key(a).cmp(&version_key(b))
+}
+
+/// Numeric ordering, so 1.10.0 sorts after 1.9.0
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+pub struct BlockRegistry {
+    path: PathBuf,
+}
+
+impl BlockRegistry {
+    pub fn new() -> Result<Self, String> {
+        let path = dirs::home_dir()
+            .ok_or("Could not determine home directory")?
+            .join(".portal-desktop")
+            .join("pipelines")
+            .join("block-templates.json");
+        Ok(Self { path })
+    }
+
+    fn load(&self) -> Vec<RegisteredTemplate> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, templates: &[RegisteredTemplate]) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let content = serde_json::to_string_pretty(templates)
+            .map_err(|e| format!("Failed to serialize block templates: {}", e))?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write block templates: {}", e))
+    }
+
+    /// Built-in and imported templates, newest version of each
+    pub fn list(&self) -> Vec<BlockTemplateSummary> {
+        summarize(builtin_templates().into_iter().chain(self.load()).collect())
+    }
+
+    /// A template by key; the newest version unless one is given
+    pub fn get(&self, key: &str, version: Option<&str>) -> Result<BlockTemplate, String> {
+        builtin_templates()
+            .into_iter()
+            .chain(self.load())
+            .map(|registered| registered.template)
+            .filter(|template| template.key == key && version.is_none_or(|v| template.version == v))
+            .max_by(|a, b| compare_versions(&a.version, &b.version))
+            .ok_or_else(|| match version {
+                Some(version) => format!("Block template not found: {}@{}", key, version),
+                None => format!("Block template not found: {}", key),
+            })
+    }
+
+    /// Import the templates in a file or at an http(s) URL. Importing a
+    /// version that's already there replaces it; built-in versions can't be
+    /// replaced.
+    pub async fn import(&self, source: &str) -> Result<Vec<BlockTemplate>, String> {
+        let content = if source.starts_with("http://") || source.starts_with("https://") {
+            fetch(source).await?
+        } else {
+            tokio::fs::read_to_string(source)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", source, e))?
+        };
+        let templates = parse_templates(&content)?;
+
+        let builtins = builtin_templates();
+        if let Some(template) = templates.iter().find(|t| {
+            builtins
+                .iter()
+                .any(|b| b.template.key == t.key && b.template.version == t.version)
+        }) {
+            return Err(format!(
+                "{}@{} is a built-in template; import it under a new version",
+                template.key, template.version
+            ));
+        }
+
+        let _guard = REGISTRY_LOCK.lock().await;
+        let mut registered = self.load();
+        let imported_at = chrono::Utc::now().to_rfc3339();
+        for template in &templates {
+            registered.retain(|r| {
+                !(r.template.key == template.key && r.template.version == template.version)
+            });
+            registered.push(RegisteredTemplate {
+                template: template.clone(),
+                source: source.to_string(),
+                imported_at: Some(imported_at.clone()),
+            });
+        }
+        self.save(&registered)?;
+        Ok(templates)
+    }
+
+    /// Remove an imported template, one version or all of them
+    pub async fn remove(&self, key: &str, version: Option<&str>) -> Result<(), String> {
+        let _guard = REGISTRY_LOCK.lock().await;
+        let mut registered = self.load();
+        let before = registered.len();
+        registered.retain(|r| {
+            !(r.template.key == key && version.is_none_or(|v| r.template.version == v))
+        });
+        if registered.len() == before {
+            return Err(
+                if builtin_templates().iter().any(|b| b.template.key == key) {
+                    format!("{} is a built-in template and can't be removed", key)
+                } else {
+                    format!("Block template not found: {}", key)
+                },
+            );
+        }
+        self.save(&registered)
+    }
+}
+
+async fn fetch(url: &str) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status()
+        ));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", url, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_builtin_and_imported_templates() {
+        let builtins = builtin_templates();
+        let keys: Vec<&str> = builtins.iter().map(|b| b.template.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["build", "test", "lint", "docker-build", "deploy-k8s"]
+        );
+
+        let single = parse_templates(
+            "key: notify-slack\nname: Notify Slack\ncategory: utility\nversion: 1.2.0\n\
+             command: curl -X POST -d '${message}' ${webhook}\n\
+             parameters:\n  - { name: message, type: string, required: true }\n",
+        )
+        .unwrap();
+        assert_eq!(single[0].execution_type, "command");
+
+        let json =
+            r#"{"blocks": [{"key": "a", "name": "A", "version": "2.0.0", "command": "echo a"}]}"#;
+        assert_eq!(parse_templates(json).unwrap()[0].category, "custom");
+        let unquoted = parse_templates("key: a\nname: A\nversion: 1.5\ncommand: echo").unwrap();
+        assert_eq!(unquoted[0].version, "1.5");
+
+        assert!(parse_templates("key: a\nname: A\nversion: x\ncommand: echo").is_err());
+        assert!(parse_templates("key: a b\nname: A\nversion: 1.0.0\ncommand: echo").is_err());
+        assert!(parse_templates("blocks: []").is_err());
+    }
+
+    #[test]
+    fn instantiates_and_orders_versions() {
+        let docker = builtin_templates()
+            .into_iter()
+            .find(|b| b.template.key == "docker-build")
+            .unwrap()
+            .template;
+        let values = HashMap::from([("tag".to_string(), "v2".to_string())]);
+        assert_eq!(
+            docker.fill_command(&values),
+            "docker build -t ${IMAGE_NAME}:v2 -f Dockerfile ."
+        );
+        let request = docker.block_request(&values);
+        assert_eq!(request["defaultConfig"]["tag"], "v2");
+        assert_eq!(request["version"], "1.0.0");
+        assert!(request["tags"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("template:docker-build")));
+
+        let versions = ["1.9.0", "1.10.0", "1.2.0"].map(|version| RegisteredTemplate {
+            template: BlockTemplate {
+                version: version.to_string(),
+                ..docker.clone()
+            },
+            source: "file".to_string(),
+            imported_at: None,
+        });
+        let summaries = summarize(versions.to_vec());
+        assert_eq!(summaries[0].latest.template.version, "1.10.0");
+        assert_eq!(summaries[0].versions, ["1.10.0", "1.9.0", "1.2.0"]);
+    }
+}
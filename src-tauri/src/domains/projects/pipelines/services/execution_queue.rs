@@ -0,0 +1,222 @@
+//! Decides when pipeline executions may start: at most `max_total` run at
+//! once across all projects and at most the project's limit per project, and
+//! a pipeline whose `executionContext.concurrency` forbids overlapping runs
+//! waits for its previous run. Executions that can't start yet wait in
+//! submission order, though one blocked by its project or pipeline doesn't
+//! hold up the others.
+
+use serde_json::Value;
+use std::collections::VecDeque;
+
+/// What happens when a pipeline is executed while a run of it is active
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConcurrencyPolicy {
+    /// Runs of the same pipeline may overlap
+    #[default]
+    Allow,
+    /// The new run waits for the active one to finish
+    Queue,
+    /// The active run is cancelled and the new one starts once it has stopped
+    CancelPrevious,
+}
+
+impl ConcurrencyPolicy {
+    /// Reads `concurrency` from a pipeline's execution context
+    pub fn from_execution_context(context: &Value) -> Self {
+        match context.get("concurrency").and_then(Value::as_str) {
+            Some("queue") => Self::Queue,
+            Some("cancelPrevious" | "cancel_previous") => Self::CancelPrevious,
+            _ => Self::Allow,
+        }
+    }
+
+    fn exclusive(self) -> bool {
+        self != Self::Allow
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueuedExecution {
+    pub execution_id: String,
+    pub pipeline_id: i32,
+    pub project_id: i32,
+    /// Executions the project may run at once
+    pub project_limit: usize,
+    pub policy: ConcurrencyPolicy,
+}
+
+#[derive(Debug)]
+pub struct ExecutionQueue {
+    max_total: usize,
+    running: Vec<QueuedExecution>,
+    waiting: VecDeque<QueuedExecution>,
+}
+
+impl ExecutionQueue {
+    pub fn new(max_total: usize) -> Self {
+        Self {
+            max_total: max_total.max(1),
+            running: Vec::new(),
+            waiting: VecDeque::new(),
+        }
+    }
+
+    /// Picks up a changed global limit; takes effect as slots free up
+    pub fn set_max_total(&mut self, max_total: usize) {
+        self.max_total = max_total.max(1);
+    }
+
+    /// Adds an execution and returns the IDs of the executions that may
+    /// start now, which include the new one unless it has to wait
+    pub fn submit(&mut self, execution: QueuedExecution) -> Vec<String> {
+        self.waiting.push_back(execution);
+        self.dispatch()
+    }
+
+    /// Removes a finished or cancelled execution and returns the IDs of the
+    /// waiting executions that may start in its place
+    pub fn finish(&mut self, execution_id: &str) -> Vec<String> {
+        self.running.retain(|e| e.execution_id != execution_id);
+        self.waiting.retain(|e| e.execution_id != execution_id);
+        self.dispatch()
+    }
+
+    /// Active executions, running or waiting, of `pipeline_id`
+    pub fn active_for_pipeline(&self, pipeline_id: i32) -> Vec<String> {
+        self.running
+            .iter()
+            .chain(self.waiting.iter())
+            .filter(|e| e.pipeline_id == pipeline_id)
+            .map(|e| e.execution_id.clone())
+            .collect()
+    }
+
+    /// 1-based position of a waiting execution
+    pub fn position(&self, execution_id: &str) -> Option<usize> {
+        self.waiting
+            .iter()
+            .position(|e| e.execution_id == execution_id)
+            .map(|i| i + 1)
+    }
+
+    pub fn waiting_ids(&self) -> Vec<String> {
+        self.waiting
+            .iter()
+            .map(|e| e.execution_id.clone())
+            .collect()
+    }
+
+    fn can_start(&self, execution: &QueuedExecution) -> bool {
+        if self.running.len() >= self.max_total {
+            return false;
+        }
+        let project_running = self
+            .running
+            .iter()
+            .filter(|e| e.project_id == execution.project_id)
+            .count();
+        if project_running >= execution.project_limit {
+            return false;
+        }
+        // Either side forbidding overlap keeps runs of a pipeline apart
+        !self.running.iter().any(|e| {
+            e.pipeline_id == execution.pipeline_id
+                && (execution.policy.exclusive() || e.policy.exclusive())
+        })
+    }
+
+    /// Starts waiting executions in order, skipping those still blocked by
+    /// their project or pipeline so they don't hold up other projects
+    fn dispatch(&mut self) -> Vec<String> {
+        let mut started = Vec::new();
+        let mut index = 0;
+        while index < self.waiting.len() && self.running.len() < self.max_total {
+            // An earlier waiting run of the same pipeline goes first
+            let blocked_by_earlier = self
+                .waiting
+                .iter()
+                .take(index)
+                .any(|e| e.pipeline_id == self.waiting[index].pipeline_id);
+            if !blocked_by_earlier && self.can_start(&self.waiting[index]) {
+                if let Some(execution) = self.waiting.remove(index) {
+                    started.push(execution.execution_id.clone());
+                    self.running.push(execution);
+                }
+            } else {
+                index += 1;
+            }
+        }
+        started
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn execution(id: &str, pipeline_id: i32, project_id: i32) -> QueuedExecution {
+        QueuedExecution {
+            execution_id: id.to_string(),
+            pipeline_id,
+            project_id,
+            project_limit: 2,
+            policy: ConcurrencyPolicy::Allow,
+        }
+    }
+
+    #[test]
+    fn queues_beyond_global_and_project_limits() {
+        let mut queue = ExecutionQueue::new(3);
+        assert_eq!(queue.submit(execution("a", 1, 1)), vec!["a".to_string()]);
+        assert_eq!(queue.submit(execution("b", 2, 1)), vec!["b".to_string()]);
+        // Project 1 is at its limit of 2; other projects aren't held up
+        assert!(queue.submit(execution("c", 3, 1)).is_empty());
+        assert_eq!(queue.submit(execution("d", 4, 2)), vec!["d".to_string()]);
+        // All 3 slots are taken
+        assert!(queue.submit(execution("e", 5, 3)).is_empty());
+        assert_eq!(queue.waiting_ids(), vec!["c".to_string(), "e".to_string()]);
+        assert_eq!(queue.position("e"), Some(2));
+
+        assert_eq!(queue.finish("a"), vec!["c".to_string()]);
+        assert_eq!(queue.position("e"), Some(1));
+        assert_eq!(queue.finish("d"), vec!["e".to_string()]);
+        assert!(queue.waiting_ids().is_empty());
+    }
+
+    #[test]
+    fn exclusive_pipelines_wait_for_their_previous_run() {
+        let context = json!({ "type": "sdk", "concurrency": "queue" });
+        let policy = ConcurrencyPolicy::from_execution_context(&context);
+        assert_eq!(policy, ConcurrencyPolicy::Queue);
+        assert_eq!(
+            ConcurrencyPolicy::from_execution_context(&json!({ "concurrency": "cancelPrevious" })),
+            ConcurrencyPolicy::CancelPrevious
+        );
+        assert_eq!(
+            ConcurrencyPolicy::from_execution_context(&json!({})),
+            ConcurrencyPolicy::Allow
+        );
+
+        let mut queue = ExecutionQueue::new(8);
+        let exclusive = |id: &str| QueuedExecution {
+            policy,
+            ..execution(id, 1, 1)
+        };
+        assert_eq!(queue.submit(exclusive("a")), vec!["a".to_string()]);
+        assert!(queue.submit(exclusive("b")).is_empty());
+        assert!(queue.submit(exclusive("c")).is_empty());
+        assert_eq!(queue.position("c"), Some(2));
+        assert_eq!(
+            queue.active_for_pipeline(1),
+            vec!["a".to_string(), "b".to_string(), "c".to_string()]
+        );
+
+        assert_eq!(queue.finish("a"), vec!["b".to_string()]);
+        // Cancelling a waiting run moves the ones behind it up
+        assert!(queue.finish("c").is_empty());
+        assert_eq!(queue.active_for_pipeline(1), vec!["b".to_string()]);
+        // Other pipelines of the project still run alongside
+        assert_eq!(queue.submit(execution("d", 2, 1)), vec!["d".to_string()]);
+    }
+}
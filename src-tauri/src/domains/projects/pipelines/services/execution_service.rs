@@ -1,9 +1,22 @@
+use crate::command_executor::supervisor::{supervisor, ProcessSpec, SupervisedChild};
 use crate::database::DatabaseManager;
+use crate::domains::automation::services::event_bus;
+use crate::domains::credentials::services::{CredentialAccessor, ProjectEnvService};
+use crate::domains::notifications::services::{NewNotification, NotificationService};
 use crate::domains::projects::entities::ProjectResponse;
 use crate::domains::projects::pipelines::repositories::{ExecutionRepository, PipelineRepository};
+use crate::domains::projects::pipelines::services::execution_queue::{
+    ConcurrencyPolicy, ExecutionQueue, QueuedExecution,
+};
+use crate::domains::projects::pipelines::services::notification_hooks::{
+    execution_summary, parse_hooks, run_hooks, HOOK_STATUSES,
+};
+use crate::domains::projects::pipelines::services::step_cache::{StepCache, StepCacheConfig};
 use crate::domains::projects::pipelines::utils::dependency_resolver::resolve_execution_order;
 use crate::domains::projects::repositories::project_repository::ProjectRepository;
-use crate::process_ext::NoWindowExt;
+use crate::domains::sdk::project::toolchain::ProjectToolchain;
+use crate::domains::settings::services::project_settings_service::ProjectSettingsService;
+use crate::domains::settings::services::settings_service::{PipelineSettings, SettingsService};
 use crate::utils::pnpm_workspace::{prepare_shell_command, warn_if_broken_pnpm_workspace};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -11,10 +24,8 @@ use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
-use tokio::sync::watch;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{oneshot, watch};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,7 +62,7 @@ enum StepRunOutcome {
 
 struct RunningExecution {
     cancel_tx: watch::Sender<bool>,
-    children: Arc<Mutex<Vec<Child>>>,
+    children: Arc<Mutex<Vec<SupervisedChild>>>,
 }
 
 #[derive(Clone)]
@@ -59,7 +70,13 @@ pub struct ExecutionService {
     execution_repo: ExecutionRepository,
     pipeline_repo: PipelineRepository,
     project_repo: ProjectRepository,
+    project_env: ProjectEnvService,
+    project_settings: ProjectSettingsService,
+    /// Executions that are running or waiting in the queue
     running: Arc<Mutex<HashMap<String, RunningExecution>>>,
+    queue: Arc<Mutex<ExecutionQueue>>,
+    /// Starts a waiting execution once the queue admits it
+    start_signals: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
 }
 
 impl ExecutionService {
@@ -67,8 +84,14 @@ impl ExecutionService {
         Self {
             execution_repo: ExecutionRepository::new(db_manager.clone()),
             pipeline_repo: PipelineRepository::new(db_manager.clone()),
-            project_repo: ProjectRepository::new(db_manager),
+            project_repo: ProjectRepository::new(db_manager.clone()),
+            project_env: ProjectEnvService::new(db_manager.clone()),
+            project_settings: ProjectSettingsService::new(db_manager),
             running: Arc::new(Mutex::new(HashMap::new())),
+            queue: Arc::new(Mutex::new(ExecutionQueue::new(
+                PipelineSettings::default().max_total_executions as usize,
+            ))),
+            start_signals: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -117,13 +140,53 @@ impl ExecutionService {
         variables.insert("PACKAGE_MANAGER".to_string(), detected_pm.clone());
         let build_command = project.build_command.clone();
 
+        // Secrets go to the step processes as env vars only; unlike variables
+        // they are never persisted with the execution.
+        let mut secret_env = self
+            .project_env
+            .resolve_env(project.id, CredentialAccessor::Pipeline)
+            .await?;
+        secret_env.extend(request.secrets.unwrap_or_default());
+        let secret_env = Arc::new(secret_env);
+
+        // Steps run with the SDK versions the project pins, as its terminals do
+        let toolchain = ProjectToolchain::resolve(Path::new(&project.path))
+            .await
+            .unwrap_or_default();
+        let toolchain = Arc::new(toolchain);
+
         let step_executions = build_initial_step_executions(&steps);
         let step_executions_json = serde_json::to_string(&step_executions)
             .map_err(|e| format!("Failed to serialize steps: {}", e))?;
         let variables_json = serde_json::to_string(&variables)
             .map_err(|e| format!("Failed to serialize variables: {}", e))?;
 
-        self.execution_repo
+        let policy = ConcurrencyPolicy::from_execution_context(
+            &serde_json::from_str::<Value>(&pipeline.execution_context_json).unwrap_or_default(),
+        );
+        let project_limit = self
+            .project_settings
+            .max_concurrent_executions(project.id)
+            .await?;
+        let max_total = SettingsService::new()
+            .load_settings()?
+            .app
+            .pipelines
+            .max_total_executions;
+        let (cancel_tx, mut cancel_rx) = watch::channel(false);
+        let (start_tx, start_rx) = oneshot::channel();
+        let children: Arc<Mutex<Vec<SupervisedChild>>> = Arc::new(Mutex::new(Vec::new()));
+
+        self.running.lock().unwrap().insert(
+            execution_id.clone(),
+            RunningExecution {
+                cancel_tx,
+                children: Arc::clone(&children),
+            },
+        );
+
+        if let Err(e) = self
+            .execution_repo
             .create(
                 execution_id.clone(),
                 pipeline_id,
@@ -133,71 +196,175 @@ impl ExecutionService {
                 step_executions_json,
                 variables_json,
             )
-            .await?;
-
-        let (cancel_tx, cancel_rx) = watch::channel(false);
-        let children: Arc<Mutex<Vec<Child>>> = Arc::new(Mutex::new(Vec::new()));
-
+            .await
         {
-            let mut running = self.running.lock().unwrap();
-            running.insert(
-                execution_id.clone(),
-                RunningExecution {
-                    cancel_tx,
-                    children: Arc::clone(&children),
-                },
-            );
+            self.running.lock().unwrap().remove(&execution_id);
+            return Err(e);
         }
 
         self.execution_repo
             .update_status(&execution_id, "queued".to_string(), None)
             .await?;
 
+        if policy == ConcurrencyPolicy::CancelPrevious {
+            let previous = self.queue.lock().unwrap().active_for_pipeline(pipeline_id);
+            for previous_id in previous {
+                let _ = self.cancel_execution(&previous_id, Some(app.clone())).await;
+            }
+        }
+
+        self.start_signals
+            .lock()
+            .unwrap()
+            .insert(execution_id.clone(), start_tx);
+        let started = {
+            let mut queue = self.queue.lock().unwrap();
+            queue.set_max_total(max_total as usize);
+            queue.submit(QueuedExecution {
+                execution_id: execution_id.clone(),
+                pipeline_id,
+                project_id: project.id,
+                project_limit: project_limit as usize,
+                policy,
+            })
+        };
+        self.signal_started(started);
+
         let service = self.clone();
         let exec_id = execution_id.clone();
         let app_handle = app.clone();
 
         tokio::spawn(async move {
-            let result = service
-                .run_execution(
-                    exec_id.clone(),
-                    steps,
-                    project.path,
-                    variables,
-                    build_command,
-                    detected_pm,
-                    children,
-                    cancel_rx,
-                    app_handle.clone(),
-                )
-                .await;
+            // Waits for a slot; cancelling a queued execution just drops it
+            let started = tokio::select! {
+                started = start_rx => started.is_ok(),
+                _ = cancel_rx.wait_for(|cancelled| *cancelled) => false,
+            };
 
-            if let Err(e) = result {
-                let _ = service
-                    .execution_repo
-                    .update_status(&exec_id, "failed".to_string(), Some(e))
+            if started {
+                let result = service
+                    .run_execution(
+                        exec_id.clone(),
+                        pipeline_id,
+                        steps,
+                        project.path,
+                        variables,
+                        secret_env,
+                        toolchain,
+                        build_command,
+                        detected_pm,
+                        children,
+                        cancel_rx,
+                        app_handle.clone(),
+                    )
                     .await;
-                if let Ok(Some(exec)) = service.get_execution(&exec_id).await {
-                    service.emit_execution_update(&app_handle, exec);
+
+                if let Err(e) = result {
+                    let _ = service
+                        .execution_repo
+                        .update_status(&exec_id, "failed".to_string(), Some(e))
+                        .await;
+                    if let Ok(Some(exec)) = service.get_execution(&exec_id).await {
+                        service.emit_execution_update(&app_handle, exec);
+                    }
                 }
             }
 
-            let mut running = service.running.lock().unwrap();
-            running.remove(&exec_id);
+            service.release(&exec_id, &app_handle).await;
         });
 
+        self.emit_queue_positions(&app).await;
+
         Ok(execution_id)
     }
 
+    /// Frees the execution's slot and starts the executions queued behind
+    /// it, then runs the pipeline's notification hooks
+    async fn release(&self, execution_id: &str, app: &AppHandle) {
+        self.running.lock().unwrap().remove(execution_id);
+        self.start_signals.lock().unwrap().remove(execution_id);
+        let started = self.queue.lock().unwrap().finish(execution_id);
+        self.signal_started(started);
+        self.emit_queue_positions(app).await;
+        self.run_notification_hooks(execution_id, app).await;
+    }
+
+    async fn run_notification_hooks(&self, execution_id: &str, app: &AppHandle) {
+        let Ok(Some(execution)) = self.execution_repo.get_by_id(execution_id).await else {
+            return;
+        };
+        // A pipeline left running a dev server hasn't finished
+        if !HOOK_STATUSES.contains(&execution.status.as_str()) {
+            return;
+        }
+        let Ok(Some(pipeline)) = self.pipeline_repo.get_by_id(execution.pipeline_id).await else {
+            return;
+        };
+        let hooks = match parse_hooks(&pipeline.notifications_json) {
+            Ok(hooks) if !hooks.is_empty() => hooks,
+            Ok(_) => return,
+            Err(e) => {
+                eprintln!("[Pipelines] {}: {}", pipeline.name, e);
+                return;
+            }
+        };
+        let project = self
+            .project_repo
+            .get_by_id(execution.project_id)
+            .await
+            .ok()
+            .flatten();
+        let summary = execution_summary(
+            &Self::execution_to_json(&execution),
+            &pipeline.name,
+            project.as_ref().map_or("", |p| p.name.as_str()),
+        );
+        run_hooks(app, &hooks, &summary, project.map(|p| p.path)).await;
+    }
+
+    fn signal_started(&self, execution_ids: Vec<String>) {
+        let mut signals = self.start_signals.lock().unwrap();
+        for execution_id in execution_ids {
+            if let Some(start_tx) = signals.remove(&execution_id) {
+                let _ = start_tx.send(());
+            }
+        }
+    }
+
+    /// Lets waiting executions report where they are in the queue
+    async fn emit_queue_positions(&self, app: &AppHandle) {
+        let waiting = self.queue.lock().unwrap().waiting_ids();
+        for execution_id in waiting {
+            if let Ok(Some(exec)) = self.get_execution(&execution_id).await {
+                self.emit_execution_update(app, exec);
+            }
+        }
+    }
+
+    /// Adds `queuePosition` to the JSON of an execution waiting for a slot
+    fn with_queue_position(&self, mut execution: Value) -> Value {
+        let position = execution
+            .get("id")
+            .and_then(Value::as_str)
+            .and_then(|id| self.queue.lock().unwrap().position(id));
+        if let (Some(position), Some(obj)) = (position, execution.as_object_mut()) {
+            obj.insert("queuePosition".to_string(), json!(position));
+        }
+        execution
+    }
+
     async fn run_execution(
         &self,
         execution_id: String,
+        pipeline_id: i32,
         steps: Vec<Value>,
         project_path: String,
         variables: HashMap<String, String>,
+        secret_env: Arc<HashMap<String, String>>,
+        toolchain: Arc<ProjectToolchain>,
         _build_command: Option<String>,
         detected_pm: String,
-        children: Arc<Mutex<Vec<Child>>>,
+        children: Arc<Mutex<Vec<SupervisedChild>>>,
         mut cancel_rx: watch::Receiver<bool>,
         app: AppHandle,
     ) -> Result<(), String> {
@@ -242,6 +409,30 @@ impl ExecutionService {
                     .to_string();
 
                 let config = step.get("config").cloned().unwrap_or(json!({}));
+
+                // Notification steps raise a desktop notification instead of
+                // running a command
+                if let Some(notify) = config.get("notify") {
+                    self.set_step_running(&execution_id, &step_id).await?;
+                    let result = notify_step(&app, &execution_id, notify, &variables).await;
+                    let error = result.as_ref().err().map(String::as_str);
+                    let status = if error.is_some() { "failed" } else { "success" };
+                    self.finalize_step(&execution_id, &step_id, status, None, error)
+                        .await?;
+                    if let Err(e) = result {
+                        self.execution_repo
+                            .update_status(&execution_id, "failed".to_string(), Some(e))
+                            .await?;
+                    }
+                    if let Ok(Some(exec)) = self.get_execution(&execution_id).await {
+                        self.emit_execution_update(&app, exec);
+                    }
+                    if status == "failed" {
+                        return Ok(());
+                    }
+                    continue;
+                }
+
                 let command_template = config
                     .get("command")
                     .and_then(|v| v.as_str())
@@ -264,6 +455,35 @@ impl ExecutionService {
                     self.emit_execution_update(&app, exec);
                 }
 
+                let cache = match StepCacheConfig::from_step(&config) {
+                    Some(cache_config) if !long_running => {
+                        self.prepare_step_cache(
+                            &execution_id,
+                            pipeline_id,
+                            &step_id,
+                            &project_path,
+                            &command,
+                            cache_config,
+                            &app,
+                        )
+                        .await
+                    }
+                    _ => None,
+                };
+                if let Some(cache) = &cache {
+                    if self
+                        .restore_step_cache(&execution_id, &step_id, cache, &app)
+                        .await
+                    {
+                        self.finalize_step(&execution_id, &step_id, "skipped", None, None)
+                            .await?;
+                        if let Ok(Some(exec)) = self.get_execution(&execution_id).await {
+                            self.emit_execution_update(&app, exec);
+                        }
+                        continue;
+                    }
+                }
+
                 let step_result = self
                     .run_step_command(
                         &execution_id,
@@ -271,6 +491,8 @@ impl ExecutionService {
                         &command,
                         &project_path,
                         long_running,
+                        &secret_env,
+                        &toolchain,
                         Arc::clone(&children),
                         &mut cancel_rx,
                         &app,
@@ -279,6 +501,19 @@ impl ExecutionService {
 
                 match step_result {
                     Ok(StepRunOutcome::Completed { exit_code, success }) => {
+                        if let (true, Some(cache)) = (success, &cache) {
+                            if let Err(e) = cache.store().await {
+                                append_step_log(
+                                    &self.execution_repo,
+                                    &execution_id,
+                                    &step_id,
+                                    &format!("warning: outputs not cached: {}", e),
+                                    "stderr",
+                                    &app,
+                                )
+                                .await;
+                            }
+                        }
                         let status = if success { "success" } else { "failed" };
                         self.finalize_step(&execution_id, &step_id, status, Some(exit_code), None)
                             .await?;
@@ -380,7 +615,9 @@ impl ExecutionService {
         command: &str,
         working_directory: &str,
         long_running: bool,
-        children: Arc<Mutex<Vec<Child>>>,
+        secret_env: &Arc<HashMap<String, String>>,
+        toolchain: &ProjectToolchain,
+        children: Arc<Mutex<Vec<SupervisedChild>>>,
         cancel_rx: &mut watch::Receiver<bool>,
         app: &AppHandle,
     ) -> Result<StepRunOutcome, String> {
@@ -404,6 +641,31 @@ impl ExecutionService {
             app,
         )
         .await;
+        if !toolchain.sdks.is_empty() {
+            append_step_log(
+                &self.execution_repo,
+                execution_id,
+                step_id,
+                &format!("sdk: {}", toolchain.summary()),
+                "stdout",
+                app,
+            )
+            .await;
+        }
+        for missing in &toolchain.missing {
+            append_step_log(
+                &self.execution_repo,
+                execution_id,
+                step_id,
+                &format!(
+                    "warning: {} is pinned for this project but not installed",
+                    missing
+                ),
+                "stderr",
+                app,
+            )
+            .await;
+        }
 
         let pm_prefix = exec_command.split_whitespace().next();
         if matches!(pm_prefix, Some("npm" | "yarn" | "pnpm"))
@@ -434,28 +696,15 @@ impl ExecutionService {
             }
         }
 
-        let mut cmd = if cfg!(target_os = "windows") {
-            let mut c = Command::new("cmd");
-            c.no_window();
-            c.args(["/C", &exec_command]);
-            c
-        } else {
-            let mut c = Command::new("sh");
-            c.args(["-c", &exec_command]);
-            c
-        };
-
-        cmd.current_dir(working_directory);
-        cmd.stdout(std::process::Stdio::piped());
-        cmd.stderr(std::process::Stdio::piped());
-        cmd.kill_on_drop(true);
+        let mut spec = ProcessSpec::shell("pipelines", &exec_command);
+        spec.current_dir(working_directory);
+        spec.envs(toolchain.env(std::env::var_os("PATH")));
+        spec.envs(secret_env.iter());
 
-        let mut child = cmd
-            .spawn()
+        let mut child = supervisor()
+            .spawn(&spec)
             .map_err(|e| format!("Failed to spawn process: {}", e))?;
-
-        let stdout = child.stdout.take();
-        let stderr = child.stderr.take();
+        let mut output = child.take_output();
 
         {
             let mut procs = children.lock().unwrap();
@@ -466,32 +715,21 @@ impl ExecutionService {
         let sid = step_id.to_string();
         let repo = self.execution_repo.clone();
         let app_clone = app.clone();
-
-        if let Some(stdout) = stdout {
-            let exec_id = exec_id.clone();
-            let sid = sid.clone();
-            let repo = repo.clone();
-            let app_clone = app_clone.clone();
-            tokio::spawn(async move {
-                let mut reader = BufReader::new(stdout).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    append_step_log(&repo, &exec_id, &sid, &line, "stdout", &app_clone).await;
-                }
-            });
-        }
-
-        if let Some(stderr) = stderr {
-            let exec_id = exec_id.clone();
-            let sid = sid.clone();
-            let repo = repo.clone();
-            let app_clone = app_clone.clone();
-            tokio::spawn(async move {
-                let mut reader = BufReader::new(stderr).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    append_step_log(&repo, &exec_id, &sid, &line, "stderr", &app_clone).await;
-                }
-            });
-        }
+        let secret_env = Arc::clone(secret_env);
+        tokio::spawn(async move {
+            while let Some(output) = output.recv().await {
+                let line = mask_secrets(&output.line, &secret_env);
+                append_step_log(
+                    &repo,
+                    &exec_id,
+                    &sid,
+                    &line,
+                    output.stream.as_str(),
+                    &app_clone,
+                )
+                .await;
+            }
+        });
 
         if long_running {
             return Ok(StepRunOutcome::LongRunning);
@@ -541,6 +779,83 @@ impl ExecutionService {
         })
     }
 
+    /// Fingerprint a step's cache inputs. A cache that can't be used is
+    /// logged and the step runs as if it had none.
+    async fn prepare_step_cache(
+        &self,
+        execution_id: &str,
+        pipeline_id: i32,
+        step_id: &str,
+        working_directory: &str,
+        command: &str,
+        config: StepCacheConfig,
+        app: &AppHandle,
+    ) -> Option<StepCache> {
+        let prepared = StepCache::prepare(
+            pipeline_id,
+            step_id,
+            Path::new(working_directory),
+            command,
+            config,
+        )
+        .await;
+        if let Err(e) = &prepared {
+            append_step_log(
+                &self.execution_repo,
+                execution_id,
+                step_id,
+                &format!("warning: step cache unavailable: {}", e),
+                "stderr",
+                app,
+            )
+            .await;
+        }
+        prepared.ok()
+    }
+
+    /// Restore a step's outputs from its cache; true when it needn't run
+    async fn restore_step_cache(
+        &self,
+        execution_id: &str,
+        step_id: &str,
+        cache: &StepCache,
+        app: &AppHandle,
+    ) -> bool {
+        let (line, stream, hit) = match cache.restore().await {
+            Ok(Some(restored)) => (
+                format!(
+                    "cache hit: {} inputs unchanged, restored {} outputs",
+                    cache.input_count, restored
+                ),
+                "stdout",
+                true,
+            ),
+            Ok(None) => (
+                format!(
+                    "cache miss: no cached run matches these {} inputs",
+                    cache.input_count
+                ),
+                "stdout",
+                false,
+            ),
+            Err(e) => (
+                format!("warning: cache not restored: {}", e),
+                "stderr",
+                false,
+            ),
+        };
+        append_step_log(
+            &self.execution_repo,
+            execution_id,
+            step_id,
+            &line,
+            stream,
+            app,
+        )
+        .await;
+        hit
+    }
+
     async fn set_step_running(&self, execution_id: &str, step_id: &str) -> Result<(), String> {
         self.update_step_fields(execution_id, step_id, |step| {
             step.status = "running".to_string();
@@ -624,6 +939,31 @@ impl ExecutionService {
     }
 
     fn emit_execution_update(&self, app: &AppHandle, execution: Value) {
+        // An execution only reaches a final status once, so these publish once
+        let status = execution.get("status").and_then(Value::as_str);
+        if status == Some("failed") {
+            event_bus::publish(
+                event_bus::PIPELINE_FAILED,
+                json!({
+                    "execution_id": execution.get("id"),
+                    "pipeline_id": execution.get("pipelineId"),
+                    "project_id": execution.get("projectId"),
+                    "error": execution.get("error"),
+                }),
+            );
+        }
+        if matches!(status, Some("success" | "failed" | "cancelled")) {
+            event_bus::publish(
+                event_bus::PIPELINE_FINISHED,
+                json!({
+                    "execution_id": execution.get("id"),
+                    "pipeline_id": execution.get("pipelineId"),
+                    "project_id": execution.get("projectId"),
+                    "status": status,
+                    "error": execution.get("error"),
+                }),
+            );
+        }
         let _ = app.emit("pipeline-execution-update", execution);
     }
 
@@ -658,7 +998,7 @@ impl ExecutionService {
         &self,
         e: crate::entities::pipeline_execution::Model,
     ) -> Result<Value, String> {
-        let mut item = Self::execution_to_json(&e);
+        let mut item = self.with_queue_position(Self::execution_to_json(&e));
 
         if let Some(pipeline) = self.pipeline_repo.get_by_id(e.pipeline_id).await? {
             if let Some(obj) = item.as_object_mut() {
@@ -675,9 +1015,14 @@ impl ExecutionService {
         Ok(item)
     }
 
+    /// Whether any pipeline execution is still running or queued
+    pub fn has_running(&self) -> bool {
+        !self.running.lock().unwrap().is_empty()
+    }
+
     pub async fn get_execution(&self, execution_id: &str) -> Result<Option<Value>, String> {
         let execution = self.execution_repo.get_by_id(execution_id).await?;
-        Ok(execution.map(|e| Self::execution_to_json(&e)))
+        Ok(execution.map(|e| self.with_queue_position(Self::execution_to_json(&e))))
     }
 
     pub async fn get_step_logs(
@@ -817,6 +1162,17 @@ impl ExecutionService {
     }
 }
 
+/// Hide injected secret values in step output before it is shown or stored.
+/// Very short values are left alone; masking them would garble ordinary output.
+fn mask_secrets(line: &str, secrets: &HashMap<String, String>) -> String {
+    secrets
+        .values()
+        .filter(|value| value.len() >= 4)
+        .fold(line.to_string(), |line, value| {
+            line.replace(value.as_str(), "****")
+        })
+}
+
 async fn append_step_log(
     repo: &ExecutionRepository,
     execution_id: &str,
@@ -876,6 +1232,35 @@ fn build_initial_step_executions(steps: &[Value]) -> Vec<StepExecutionState> {
         .collect()
 }
 
+/// Raise a step's `notify` config (title, body, level, link) as a
+/// notification, with pipeline variables substituted
+async fn notify_step(
+    app: &AppHandle,
+    execution_id: &str,
+    notify: &Value,
+    variables: &HashMap<String, String>,
+) -> Result<(), String> {
+    let mut notification: NewNotification = serde_json::from_value(notify.clone())
+        .map_err(|e| format!("Invalid notify step: {}", e))?;
+    notification.title = substitute_variables(&notification.title, variables);
+    notification.body = notification
+        .body
+        .map(|body| substitute_variables(&body, variables));
+    notification.link = notification
+        .link
+        .map(|link| substitute_variables(&link, variables));
+    notification
+        .source
+        .get_or_insert_with(|| format!("pipeline-execution:{}", execution_id));
+
+    let service = app
+        .try_state::<Arc<NotificationService>>()
+        .ok_or("Notifications aren't available yet")?
+        .inner()
+        .clone();
+    service.notify(app, notification).await.map(|_| ())
+}
+
 fn substitute_variables(template: &str, variables: &HashMap<String, String>) -> String {
     let mut result = template.to_string();
     for (key, value) in variables {
@@ -1018,43 +1403,13 @@ fn resolve_package_manager(project: &ProjectResponse) -> String {
 }
 
 /// Kill spawned processes and their child tree (e.g. node under cmd on Windows).
-async fn kill_children_async(children: &Arc<Mutex<Vec<Child>>>) {
-    let mut child_handles: Vec<Child> = {
+async fn kill_children_async(children: &Arc<Mutex<Vec<SupervisedChild>>>) {
+    let mut child_handles: Vec<SupervisedChild> = {
         let mut procs = children.lock().unwrap();
         std::mem::take(&mut *procs)
     };
 
     for mut child in child_handles.drain(..) {
-        kill_process_tree(&mut child).await;
-    }
-}
-
-async fn kill_process_tree(child: &mut Child) {
-    if let Some(pid) = child.id() {
-        if cfg!(target_os = "windows") {
-            // cmd /C npm run dev spawns node as a child — taskkill /T terminates the tree
-            let _ = Command::new("taskkill")
-                .no_window()
-                .args(["/PID", &pid.to_string(), "/T", "/F"])
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .status()
-                .await;
-        } else if cfg!(unix) {
-            // Terminate child processes first (e.g. node under sh -c)
-            let _ = Command::new("pkill")
-                .args(["-TERM", "-P", &pid.to_string()])
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .status()
-                .await;
-            let _ = child.start_kill();
-        } else {
-            let _ = child.start_kill();
-        }
-    } else {
-        let _ = child.start_kill();
+        child.kill_tree().await;
     }
-
-    let _ = tokio::time::timeout(tokio::time::Duration::from_secs(5), child.wait()).await;
 }
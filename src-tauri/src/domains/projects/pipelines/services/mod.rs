@@ -1,5 +1,9 @@
+pub mod block_registry;
+pub mod execution_queue;
 pub mod execution_service;
+pub mod notification_hooks;
 pub mod pipeline_service;
+pub mod step_cache;
 
 pub use execution_service::*;
 pub use pipeline_service::*;
@@ -0,0 +1,334 @@
+//! Notifications raised when pipeline executions finish.
+//!
+//! A pipeline keeps a list of hooks in `notifications_json`, each run when
+//! one of its executions finishes with a status the hook is `on` (success,
+//! failed or cancelled; all of them when empty). A hook raises a desktop
+//! notification, runs a custom script, runs an embedded workflow or POSTs to
+//! a webhook, and gets the execution summary: as the JSON body, as the
+//! workflow's trigger data, or as `pipeline_<field>` arguments.
+
+use crate::database::DatabaseManager;
+use crate::domains::automation::commands::{find_embedded_workflow, run_and_record};
+use crate::domains::automation::services::event_bus::payload_variables;
+use crate::domains::automation::services::workflow_engine::WorkflowContext;
+use crate::domains::custom_scripts::services::CustomScriptService;
+use crate::domains::notifications::services::{
+    NewNotification, NotificationLevel, NotificationService,
+};
+use crate::domains::scripts::commands::ScriptExecutionState;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Final statuses a hook can fire on
+pub const HOOK_STATUSES: &[&str] = &["success", "failed", "cancelled"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum HookAction {
+    Desktop,
+    Script {
+        script_id: i32,
+        /// Arguments for the run, over the summary's variables
+        #[serde(default)]
+        arguments: HashMap<String, String>,
+    },
+    Workflow {
+        workflow_id: String,
+    },
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationHook {
+    #[serde(flatten)]
+    pub action: HookAction,
+    #[serde(default)]
+    pub on: Vec<String>,
+}
+
+impl NotificationHook {
+    pub fn fires_on(&self, status: &str) -> bool {
+        self.on.is_empty() || self.on.iter().any(|on| on == status)
+    }
+}
+
+pub fn parse_hooks(notifications_json: &str) -> Result<Vec<NotificationHook>, String> {
+    if notifications_json.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let hooks: Vec<NotificationHook> = serde_json::from_str(notifications_json)
+        .map_err(|e| format!("Invalid notification hooks: {}", e))?;
+    for hook in &hooks {
+        if let Some(status) = hook
+            .on
+            .iter()
+            .find(|status| !HOOK_STATUSES.contains(&status.as_str()))
+        {
+            return Err(format!(
+                "Notification hooks fire on {}, not {}",
+                HOOK_STATUSES.join(", "),
+                status
+            ));
+        }
+        match &hook.action {
+            HookAction::Webhook { url, .. }
+                if !(url.starts_with("http://") || url.starts_with("https://")) =>
+            {
+                return Err(format!("Webhook URL must be http(s): {}", url));
+            }
+            HookAction::Workflow { workflow_id } if workflow_id.trim().is_empty() => {
+                return Err("Workflow hooks need a workflow".to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(hooks)
+}
+
+/// What hooks get about a finished execution, from its JSON as the
+/// execution service returns it
+pub fn execution_summary(execution: &Value, pipeline_name: &str, project_name: &str) -> Value {
+    let duration_ms = match (
+        execution.get("startedAt").and_then(Value::as_str),
+        execution.get("finishedAt").and_then(Value::as_str),
+    ) {
+        (Some(started), Some(finished)) => chrono::DateTime::parse_from_rfc3339(started)
+            .ok()
+            .zip(chrono::DateTime::parse_from_rfc3339(finished).ok())
+            .map(|(started, finished)| (finished - started).num_milliseconds()),
+        _ => None,
+    };
+    let steps: Vec<Value> = execution
+        .get("stepExecutions")
+        .and_then(Value::as_array)
+        .map(|steps| {
+            steps
+                .iter()
+                .map(|step| {
+                    json!({
+                        "name": step.get("stepName"),
+                        "status": step.get("status"),
+                        "exitCode": step.get("exitCode"),
+                        "durationMs": step.get("duration"),
+                        "error": step.get("error"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let failed_step = steps
+        .iter()
+        .find(|step| step["status"] == "failed")
+        .and_then(|step| step["name"].as_str())
+        .map(str::to_string);
+
+    json!({
+        "executionId": execution.get("id"),
+        "pipelineId": execution.get("pipelineId"),
+        "pipelineName": pipeline_name,
+        "projectId": execution.get("projectId"),
+        "projectName": project_name,
+        "status": execution.get("status"),
+        "error": execution.get("error"),
+        "failedStep": failed_step,
+        "startedAt": execution.get("startedAt"),
+        "finishedAt": execution.get("finishedAt"),
+        "durationMs": duration_ms,
+        "steps": steps,
+    })
+}
+
+/// Run the hooks that fire on the summary's status, one after another. A
+/// failing hook is logged and doesn't stop the rest.
+pub async fn run_hooks(
+    app: &AppHandle,
+    hooks: &[NotificationHook],
+    summary: &Value,
+    project_path: Option<String>,
+) {
+    let status = summary["status"].as_str().unwrap_or_default();
+    for hook in hooks.iter().filter(|hook| hook.fires_on(status)) {
+        if let Err(e) = run_hook(app, &hook.action, summary, project_path.clone()).await {
+            eprintln!(
+                "[Pipelines] Notification hook for execution {} failed: {}",
+                summary["executionId"].as_str().unwrap_or_default(),
+                e
+            );
+        }
+    }
+}
+
+async fn run_hook(
+    app: &AppHandle,
+    action: &HookAction,
+    summary: &Value,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    match action {
+        HookAction::Desktop => {
+            let service = app
+                .try_state::<Arc<NotificationService>>()
+                .ok_or("Notifications aren't available yet")?
+                .inner()
+                .clone();
+            service.notify(app, desktop_notification(summary)).await?;
+        }
+        HookAction::Script {
+            script_id,
+            arguments,
+        } => {
+            let db_manager = app.state::<Arc<DatabaseManager>>().inner().clone();
+            let execution = app
+                .state::<ScriptExecutionState>()
+                .get_or_init(&db_manager)
+                .await;
+            let mut script_arguments = payload_variables("pipeline", summary);
+            script_arguments.extend(arguments.clone());
+            CustomScriptService::new(&db_manager)
+                .run_script(
+                    &execution,
+                    *script_id,
+                    script_arguments,
+                    project_path,
+                    &format!(
+                        "pipeline:{}",
+                        summary["pipelineId"].as_str().unwrap_or_default()
+                    ),
+                )
+                .await?;
+        }
+        HookAction::Workflow { workflow_id } => {
+            let workflow = find_embedded_workflow(workflow_id)
+                .await
+                .ok_or_else(|| format!("Workflow not found: {}", workflow_id))?;
+            let context = WorkflowContext {
+                project_path: project_path.map(PathBuf::from),
+                variables: payload_variables("pipeline", summary),
+                trigger_data: Some(summary.clone()),
+            };
+            let result = run_and_record(app, &workflow, context).await?;
+            if let Some(error) = result.error {
+                return Err(format!("Workflow {} failed: {}", workflow_id, error));
+            }
+        }
+        HookAction::Webhook { url, headers } => {
+            let client = reqwest::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+            let mut request = client.post(url).json(summary);
+            for (name, value) in headers {
+                request = request.header(name.as_str(), value.as_str());
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| format!("Webhook request failed: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("Webhook returned HTTP {}", response.status()));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn desktop_notification(summary: &Value) -> NewNotification {
+    let pipeline = summary["pipelineName"].as_str().unwrap_or("Pipeline");
+    let project = summary["projectName"].as_str().unwrap_or_default();
+    let (level, outcome) = match summary["status"].as_str() {
+        Some("success") => (NotificationLevel::Success, "succeeded"),
+        Some("failed") => (NotificationLevel::Error, "failed"),
+        _ => (NotificationLevel::Warning, "was cancelled"),
+    };
+    let body = match (summary["failedStep"].as_str(), summary["error"].as_str()) {
+        (Some(step), Some(error)) => format!("{}: {} ({})", project, error, step),
+        (_, Some(error)) => format!("{}: {}", project, error),
+        _ => project.to_string(),
+    };
+    NewNotification {
+        title: format!("{} {}", pipeline, outcome),
+        body: Some(body),
+        level,
+        source: Some(format!(
+            "pipeline:{}",
+            summary["pipelineId"].as_str().unwrap_or_default()
+        )),
+        link: summary["projectId"]
+            .as_str()
+            .map(|project_id| format!("/projects/{}/pipelines", project_id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hooks_and_summarizes_executions() {
+        let hooks = parse_hooks(
+            r#"[{"type": "desktop", "on": ["failed"]},
+                {"type": "webhook", "url": "https://example.com/hook",
+                 "headers": {"X-Token": "t"}},
+                {"type": "script", "scriptId": 4, "arguments": {"channel": "ops"}}]"#,
+        )
+        .unwrap();
+        assert_eq!(hooks[0].action, HookAction::Desktop);
+        assert!(hooks[0].fires_on("failed"));
+        assert!(!hooks[0].fires_on("success"));
+        assert!(hooks[1].fires_on("cancelled"));
+        assert_eq!(
+            hooks[2].action,
+            HookAction::Script {
+                script_id: 4,
+                arguments: HashMap::from([("channel".to_string(), "ops".to_string())]),
+            }
+        );
+
+        assert!(parse_hooks("").unwrap().is_empty());
+        assert!(parse_hooks(r#"[{"type": "desktop", "on": ["running"]}]"#).is_err());
+        assert!(parse_hooks(r#"[{"type": "webhook", "url": "file:///etc"}]"#).is_err());
+        assert!(parse_hooks(r#"[{"type": "email"}]"#).is_err());
+
+        let execution = json!({
+            "id": "e1",
+            "pipelineId": "3",
+            "projectId": "7",
+            "status": "failed",
+            "startedAt": "2026-10-18T10:00:00+00:00",
+            "finishedAt": "2026-10-18T10:01:30+00:00",
+            "error": "Step 'Build' exited with code 2",
+            "stepExecutions": [
+                {"stepName": "Install", "status": "success", "exitCode": 0, "duration": 4000},
+                {"stepName": "Build", "status": "failed", "exitCode": 2, "duration": 86000},
+            ],
+        });
+        let summary = execution_summary(&execution, "CI", "web");
+        assert_eq!(summary["durationMs"], 90_000);
+        assert_eq!(summary["failedStep"], "Build");
+        assert_eq!(summary["steps"][1]["exitCode"], 2);
+        assert_eq!(
+            payload_variables("pipeline", &summary)["pipeline_status"],
+            "failed"
+        );
+
+        let notification = desktop_notification(&summary);
+        assert_eq!(notification.title, "CI failed");
+        assert_eq!(notification.level, NotificationLevel::Error);
+        assert_eq!(notification.link.as_deref(), Some("/projects/7/pipelines"));
+    }
+}
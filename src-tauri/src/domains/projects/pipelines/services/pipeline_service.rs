@@ -1,5 +1,6 @@
 use crate::database::DatabaseManager;
 use crate::domains::projects::pipelines::repositories::{BlockRepository, PipelineRepository};
+use crate::domains::projects::pipelines::services::notification_hooks::parse_hooks;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
@@ -17,6 +18,10 @@ pub struct PipelineRequest {
     pub enabled: bool,
     pub preset_key: Option<String>,
     pub category: Option<String>,
+    /// JSON array of NotificationHook; None leaves an existing pipeline's
+    /// hooks as they are
+    #[serde(default)]
+    pub notifications_json: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,6 +37,7 @@ struct FrontendPipelineRequest {
     enabled: Option<bool>,
     preset_key: Option<String>,
     category: Option<String>,
+    notifications: Option<Value>,
 }
 
 impl FrontendPipelineRequest {
@@ -64,6 +70,11 @@ impl FrontendPipelineRequest {
             enabled: self.enabled.unwrap_or(true),
             preset_key: self.preset_key,
             category: self.category,
+            notifications_json: self
+                .notifications
+                .map(|notifications| serde_json::to_string(&notifications))
+                .transpose()
+                .map_err(|e| format!("Failed to serialize notifications: {}", e))?,
         })
     }
 }
@@ -92,6 +103,9 @@ impl PipelineService {
     }
 
     pub async fn create_pipeline(&self, request: PipelineRequest) -> Result<i32, String> {
+        if let Some(notifications_json) = &request.notifications_json {
+            parse_hooks(notifications_json)?;
+        }
         let pipeline = self
             .pipeline_repo
             .create(
@@ -105,6 +119,9 @@ impl PipelineService {
                 request.enabled,
                 request.preset_key,
                 request.category,
+                request
+                    .notifications_json
+                    .unwrap_or_else(|| "[]".to_string()),
             )
             .await?;
         Ok(pipeline.id)
@@ -123,6 +140,7 @@ impl PipelineService {
             "enabled": p.enabled,
             "presetKey": p.preset_key,
             "category": p.category,
+            "notifications": serde_json::from_str::<Value>(&p.notifications_json).unwrap_or(json!([])),
             "createdAt": p.created_at.map(|d| d.to_rfc3339()),
             "updatedAt": p.updated_at.map(|d| d.to_rfc3339()),
         })
@@ -138,6 +156,9 @@ impl PipelineService {
         pipeline_id: i32,
         request: PipelineRequest,
     ) -> Result<i32, String> {
+        if let Some(notifications_json) = &request.notifications_json {
+            parse_hooks(notifications_json)?;
+        }
         let _pipeline = self
             .pipeline_repo
             .update(
@@ -151,6 +172,7 @@ impl PipelineService {
                 Some(request.enabled),
                 None,
                 None,
+                request.notifications_json,
             )
             .await?;
         Ok(pipeline_id)
@@ -234,7 +256,11 @@ impl PipelineService {
                 name.to_string(),
                 description.to_string(),
                 category.to_string(),
-                "1.0.0".to_string(),
+                request
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("1.0.0")
+                    .to_string(),
                 parameters_json,
                 command.to_string(),
                 execution_type.to_string(),
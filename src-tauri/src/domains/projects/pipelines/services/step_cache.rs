@@ -0,0 +1,356 @@
+//! Skips command steps whose inputs haven't changed since their last successful run.
+//!
+//! A command step can declare `cache: { inputs, outputs, key }` in its
+//! config. Its fingerprint hashes the command, the key, the outputs and
+//! every file the input globs match under the working directory. When a run
+//! finds the fingerprint of the step's last successful run, the step is
+//! skipped and its outputs are copied back from
+//! `~/.portal-desktop/pipelines/cache` instead. Each step keeps one entry.
+
+use crate::utils::glob::glob_match;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST: &str = "manifest.json";
+const OUTPUTS_DIR: &str = "outputs";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct StepCacheConfig {
+    /// Folded into the fingerprint; change it to invalidate the cache
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Globs relative to the working directory. `*` and `?` stay within a
+    /// path segment, `**` spans any number; a plain directory means
+    /// everything under it.
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// Files or directories the step produces, relative to the working
+    /// directory
+    #[serde(default)]
+    pub outputs: Vec<String>,
+}
+
+impl StepCacheConfig {
+    /// The cache settings in a step's config, if it declares inputs
+    pub fn from_step(config: &Value) -> Option<Self> {
+        let cache: Self = serde_json::from_value(config.get("cache")?.clone()).ok()?;
+        (!cache.inputs.is_empty()).then_some(cache)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheManifest {
+    fingerprint: String,
+    outputs: Vec<String>,
+    created_at: String,
+}
+
+/// A step's cache entry, and the fingerprint of its inputs for this run
+#[derive(Debug, Clone)]
+pub struct StepCache {
+    dir: PathBuf,
+    working_dir: PathBuf,
+    outputs: Vec<String>,
+    fingerprint: String,
+    /// Input files that went into the fingerprint
+    pub input_count: usize,
+}
+
+impl StepCache {
+    /// Fingerprint the step's inputs as they are now
+    pub async fn prepare(
+        pipeline_id: i32,
+        step_id: &str,
+        working_dir: &Path,
+        command: &str,
+        config: StepCacheConfig,
+    ) -> Result<Self, String> {
+        let dir = cache_root()?
+            .join(pipeline_id.to_string())
+            .join(hex::encode(Sha256::digest(step_id.as_bytes())));
+        let working_dir = working_dir.to_path_buf();
+        let command = command.to_string();
+        tokio::task::spawn_blocking(move || {
+            let outputs = config
+                .outputs
+                .iter()
+                .map(|output| relative_path(output))
+                .collect::<Result<Vec<_>, _>>()?;
+            let files = matching_files(&working_dir, &config.inputs)?;
+
+            let mut hasher = Sha256::new();
+            for part in [
+                command.as_str(),
+                config.key.as_deref().unwrap_or_default(),
+                &outputs.join("\n"),
+            ] {
+                hasher.update(part.as_bytes());
+                hasher.update([0]);
+            }
+            for file in &files {
+                let content = fs::read(working_dir.join(file))
+                    .map_err(|e| format!("Failed to read input {}: {}", file, e))?;
+                hasher.update(file.as_bytes());
+                hasher.update([0]);
+                hasher.update(Sha256::digest(&content));
+            }
+
+            Ok(Self {
+                dir,
+                working_dir,
+                outputs,
+                fingerprint: hex::encode(hasher.finalize()),
+                input_count: files.len(),
+            })
+        })
+        .await
+        .map_err(|e| format!("Cache task failed: {}", e))?
+    }
+
+    /// Copy the cached outputs back if the last successful run had the same
+    /// inputs. Returns how many outputs were restored, or None on a miss.
+    pub async fn restore(&self) -> Result<Option<usize>, String> {
+        let cache = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let Ok(manifest) = fs::read_to_string(cache.dir.join(MANIFEST)) else {
+                return Ok(None);
+            };
+            let manifest: CacheManifest = serde_json::from_str(&manifest)
+                .map_err(|e| format!("Failed to read cache manifest: {}", e))?;
+            let stored = cache.dir.join(OUTPUTS_DIR);
+            if manifest.fingerprint != cache.fingerprint
+                || !manifest
+                    .outputs
+                    .iter()
+                    .all(|output| stored.join(output).exists())
+            {
+                return Ok(None);
+            }
+
+            for output in &manifest.outputs {
+                let target = cache.working_dir.join(output);
+                remove_path(&target)?;
+                copy_path(&stored.join(output), &target)?;
+            }
+            Ok(Some(manifest.outputs.len()))
+        })
+        .await
+        .map_err(|e| format!("Cache task failed: {}", e))?
+    }
+
+    /// Keep this run's outputs, replacing the step's previous entry
+    pub async fn store(&self) -> Result<(), String> {
+        let cache = self.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Some(missing) = cache
+                .outputs
+                .iter()
+                .find(|output| !cache.working_dir.join(output).exists())
+            {
+                return Err(format!("Output {} was not produced", missing));
+            }
+
+            remove_path(&cache.dir)?;
+            let stored = cache.dir.join(OUTPUTS_DIR);
+            fs::create_dir_all(&stored)
+                .map_err(|e| format!("Failed to create {}: {}", stored.display(), e))?;
+            for output in &cache.outputs {
+                copy_path(&cache.working_dir.join(output), &stored.join(output))?;
+            }
+
+            let manifest = CacheManifest {
+                fingerprint: cache.fingerprint.clone(),
+                outputs: cache.outputs.clone(),
+                created_at: Utc::now().to_rfc3339(),
+            };
+            let manifest = serde_json::to_string_pretty(&manifest)
+                .map_err(|e| format!("Failed to serialize cache manifest: {}", e))?;
+            fs::write(cache.dir.join(MANIFEST), manifest)
+                .map_err(|e| format!("Failed to write cache manifest: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Cache task failed: {}", e))?
+    }
+}
+
+fn cache_root() -> Result<PathBuf, String> {
+    Ok(dirs::home_dir()
+        .ok_or("Could not determine home directory")?
+        .join(".portal-desktop")
+        .join("pipelines")
+        .join("cache"))
+}
+
+/// `path` with `/` separators, checked to stay inside the working directory
+fn relative_path(path: &str) -> Result<String, String> {
+    let normalized = path.trim().replace('\\', "/");
+    let normalized = normalized.trim_start_matches("./").trim_end_matches('/');
+    let escapes = Path::new(normalized)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)));
+    if normalized.is_empty() || escapes {
+        return Err(format!(
+            "Cache path {} must be relative to the working directory",
+            path
+        ));
+    }
+    Ok(normalized.to_string())
+}
+
+/// Files under `root` matched by any of `patterns`, relative to `root`
+fn matching_files(root: &Path, patterns: &[String]) -> Result<BTreeSet<String>, String> {
+    let mut files = BTreeSet::new();
+    for pattern in patterns {
+        let pattern = relative_path(pattern)?;
+        let segments: Vec<&str> = pattern.split('/').collect();
+        let literal = segments
+            .iter()
+            .take_while(|segment| !segment.contains(['*', '?']))
+            .count();
+        let base = segments[..literal].join("/");
+
+        let mut found = Vec::new();
+        walk(root, &base, &mut found)?;
+        if literal == segments.len() {
+            // A plain path: the file itself, or everything in the directory
+            files.extend(found);
+        } else {
+            files.extend(found.into_iter().filter(|file| glob_match(&pattern, file)));
+        }
+    }
+    Ok(files)
+}
+
+/// Collect the files at or below `relative`, skipping `.git`
+fn walk(root: &Path, relative: &str, found: &mut Vec<String>) -> Result<(), String> {
+    let path = root.join(relative);
+    let Ok(metadata) = fs::metadata(&path) else {
+        return Ok(());
+    };
+    if metadata.is_file() {
+        found.push(relative.to_string());
+        return Ok(());
+    }
+    let entries =
+        fs::read_dir(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" {
+            continue;
+        }
+        let child = if relative.is_empty() {
+            name
+        } else {
+            format!("{}/{}", relative, name)
+        };
+        walk(root, &child, found)?;
+    }
+    Ok(())
+}
+
+fn remove_path(path: &Path) -> Result<(), String> {
+    let result = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(_) => return Ok(()),
+    };
+    result.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+fn copy_path(from: &Path, to: &Path) -> Result<(), String> {
+    if from.is_dir() {
+        fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+        let entries =
+            fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        for entry in entries.flatten() {
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::copy(from, to)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_cache_config_and_paths() {
+        assert!(relative_path("../secrets").is_err());
+        assert!(relative_path("/etc/passwd").is_err());
+        assert_eq!(relative_path("./dist/").unwrap(), "dist");
+
+        assert_eq!(
+            StepCacheConfig::from_step(&json!({ "command": "make" })),
+            None
+        );
+        assert_eq!(
+            StepCacheConfig::from_step(
+                &json!({ "cache": { "inputs": ["src"], "outputs": ["dist"] } })
+            )
+            .map(|cache| cache.outputs),
+            Some(vec!["dist".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn restores_outputs_while_inputs_are_unchanged() {
+        let root = std::env::temp_dir().join(format!("portal-step-cache-{}", std::process::id()));
+        let project = root.join("project");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(project.join("src").join("app.ts"), "one").unwrap();
+        fs::write(project.join("notes.md"), "ignored").unwrap();
+
+        let config = StepCacheConfig {
+            key: None,
+            inputs: vec!["src/**/*.ts".to_string()],
+            outputs: vec!["dist".to_string()],
+        };
+        let cache_dir = root.join("cache");
+        let prepare = |config: StepCacheConfig| {
+            let (project, cache_dir) = (project.clone(), cache_dir.clone());
+            async move {
+                let mut cache = StepCache::prepare(1, "build", &project, "tsc", config)
+                    .await
+                    .unwrap();
+                cache.dir = cache_dir;
+                cache
+            }
+        };
+
+        let cache = prepare(config.clone()).await;
+        assert_eq!(cache.input_count, 1);
+        assert_eq!(cache.restore().await, Ok(None));
+        assert!(cache.store().await.is_err(), "dist wasn't built yet");
+
+        fs::create_dir_all(project.join("dist")).unwrap();
+        fs::write(project.join("dist").join("app.js"), "built").unwrap();
+        cache.store().await.unwrap();
+        fs::remove_dir_all(project.join("dist")).unwrap();
+
+        fs::write(project.join("notes.md"), "not an input").unwrap();
+        let cache = prepare(config.clone()).await;
+        assert_eq!(cache.restore().await, Ok(Some(1)));
+        assert_eq!(
+            fs::read_to_string(project.join("dist").join("app.js")).unwrap(),
+            "built"
+        );
+
+        fs::write(project.join("src").join("app.ts"), "two").unwrap();
+        assert_eq!(prepare(config.clone()).await.restore().await, Ok(None));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
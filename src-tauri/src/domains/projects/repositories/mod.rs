@@ -1 +1,2 @@
+pub mod project_group_repository;
 pub mod project_repository;
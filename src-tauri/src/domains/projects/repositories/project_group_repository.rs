@@ -0,0 +1,149 @@
+use crate::database::DatabaseManager;
+use crate::entities::project::{
+    ActiveModel as ProjectActiveModel, Column as ProjectColumn, Entity as ProjectEntity,
+};
+use crate::entities::project_group::{ActiveModel, Column, Entity, Model};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ProjectGroupRepository {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ProjectGroupRepository {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// All groups, ordered by position
+    pub async fn get_all(&self) -> Result<Vec<Model>, String> {
+        Entity::find()
+            .order_by_asc(Column::Position)
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to fetch project groups: {}", e))
+    }
+
+    pub async fn get_by_id(&self, id: i32) -> Result<Option<Model>, String> {
+        Entity::find_by_id(id)
+            .one(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to fetch project group: {}", e))
+    }
+
+    pub async fn create(
+        &self,
+        name: String,
+        kind: String,
+        parent_id: Option<i32>,
+        color: Option<String>,
+        position: i32,
+    ) -> Result<Model, String> {
+        let now = chrono::Utc::now();
+        ActiveModel {
+            name: Set(name),
+            kind: Set(kind),
+            parent_id: Set(parent_id),
+            color: Set(color),
+            position: Set(position),
+            created_at: Set(Some(now.into())),
+            updated_at: Set(Some(now.into())),
+            ..Default::default()
+        }
+        .insert(self.db_manager.get_connection())
+        .await
+        .map_err(|e| format!("Failed to create project group: {}", e))
+    }
+
+    pub async fn update(
+        &self,
+        id: i32,
+        name: Option<String>,
+        kind: Option<String>,
+        color: Option<Option<String>>,
+    ) -> Result<Model, String> {
+        let mut group: ActiveModel = self
+            .get_by_id(id)
+            .await?
+            .ok_or_else(|| "Project group not found".to_string())?
+            .into();
+        if let Some(name) = name {
+            group.name = Set(name);
+        }
+        if let Some(kind) = kind {
+            group.kind = Set(kind);
+        }
+        if let Some(color) = color {
+            group.color = Set(color);
+        }
+        group.updated_at = Set(Some(chrono::Utc::now().into()));
+        group
+            .update(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to update project group: {}", e))
+    }
+
+    /// Put a group under `parent_id` at `position`
+    pub async fn set_placement(
+        &self,
+        id: i32,
+        parent_id: Option<i32>,
+        position: i32,
+    ) -> Result<(), String> {
+        ActiveModel {
+            id: Set(id),
+            parent_id: Set(parent_id),
+            position: Set(position),
+            updated_at: Set(Some(chrono::Utc::now().into())),
+            ..Default::default()
+        }
+        .update(self.db_manager.get_connection())
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to move project group: {}", e))
+    }
+
+    pub async fn delete(&self, id: i32) -> Result<(), String> {
+        Entity::delete_by_id(id)
+            .exec(self.db_manager.get_connection())
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to delete project group: {}", e))
+    }
+
+    /// IDs of the projects in a group, or of the ungrouped ones, by position
+    pub async fn get_project_ids(&self, group_id: Option<i32>) -> Result<Vec<i32>, String> {
+        let filter = match group_id {
+            Some(group_id) => ProjectColumn::GroupId.eq(group_id),
+            None => ProjectColumn::GroupId.is_null(),
+        };
+        let projects = ProjectEntity::find()
+            .filter(filter)
+            .order_by_asc(ProjectColumn::Position)
+            .order_by_asc(ProjectColumn::Name)
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to fetch group projects: {}", e))?;
+        Ok(projects.into_iter().map(|project| project.id).collect())
+    }
+
+    /// Put a project in `group_id` at `position`
+    pub async fn set_project_placement(
+        &self,
+        project_id: i32,
+        group_id: Option<i32>,
+        position: i32,
+    ) -> Result<(), String> {
+        ProjectActiveModel {
+            id: Set(project_id),
+            group_id: Set(group_id),
+            position: Set(position),
+            ..Default::default()
+        }
+        .update(self.db_manager.get_connection())
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to move project: {}", e))
+    }
+}
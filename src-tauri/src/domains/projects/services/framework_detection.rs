@@ -0,0 +1,328 @@
+//! Frameworks are recognized by detectors declared as data. Each detector has
+//! rules: a glob for files or directories in the project (matched against
+//! paths relative to its root, a few levels deep) and optionally a regex the
+//! content of a matching file must contain, worth a `weight`. A project's
+//! confidence for a framework is the sum of the weights of the rules it
+//! matches, capped at 1; frameworks reaching the detector's `min_confidence`
+//! are candidates, most confident first.
+//!
+//! The built-in detectors can be extended or overridden, by name, with
+//! `~/.portal-desktop/framework-detectors.json`: an array of detectors in the
+//! same shape.
+
+use crate::utils::glob::glob_match;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory levels below the project root that rules can see
+const MAX_DEPTH: usize = 3;
+/// Files larger than this aren't searched for content
+const MAX_CONTENT_BYTES: u64 = 1024 * 1024;
+/// Directories listed but not descended into
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    ".git",
+    "target",
+    "dist",
+    "build",
+    "vendor",
+    ".venv",
+    "venv",
+    "__pycache__",
+    ".next",
+    ".svelte-kit",
+];
+
+const BUILTIN_DETECTORS: &str = r#"[
+  {"name": "Tauri", "rules": [
+    {"path": "tauri.conf.json", "weight": 1.0},
+    {"path": "src-tauri/tauri.conf.json", "weight": 1.0}]},
+  {"name": "Angular", "rules": [
+    {"path": "angular.json", "weight": 0.9},
+    {"path": "package.json", "contains": "\"@angular/core\"", "weight": 0.6}]},
+  {"name": "Vue.js", "rules": [
+    {"path": "vue.config.{js,ts}", "weight": 0.8},
+    {"path": "package.json", "contains": "\"vue\"\\s*:", "weight": 0.6}]},
+  {"name": "Next.js", "rules": [
+    {"path": "next.config.{js,mjs,ts}", "weight": 0.9},
+    {"path": "package.json", "contains": "\"next\"\\s*:", "weight": 0.6}]},
+  {"name": "Nuxt.js", "rules": [
+    {"path": "nuxt.config.{js,ts}", "weight": 0.9},
+    {"path": "package.json", "contains": "\"nuxt\"\\s*:", "weight": 0.6}]},
+  {"name": "Svelte", "rules": [
+    {"path": "svelte.config.{js,ts}", "weight": 0.9},
+    {"path": "package.json", "contains": "\"svelte\"\\s*:", "weight": 0.6}]},
+  {"name": "Vite", "rules": [
+    {"path": "vite.config.{js,mjs,ts}", "weight": 0.9}]},
+  {"name": "Webpack", "rules": [
+    {"path": "webpack.config.{js,ts}", "weight": 0.9}]},
+  {"name": "Remix", "rules": [
+    {"path": "remix.config.js", "weight": 0.9},
+    {"path": "package.json", "contains": "\"@remix-run/", "weight": 0.6}]},
+  {"name": "Gatsby", "rules": [
+    {"path": "gatsby-config.{js,ts}", "weight": 0.9}]},
+  {"name": "Node.js", "rules": [
+    {"path": "package.json", "weight": 0.8},
+    {"path": "node_modules", "weight": 0.5}]},
+  {"name": "Python", "rules": [
+    {"path": "requirements.txt", "weight": 0.8},
+    {"path": "pyproject.toml", "weight": 0.8},
+    {"path": "setup.py", "weight": 0.6}]},
+  {"name": "Django", "rules": [
+    {"path": "manage.py", "contains": "django", "weight": 0.9},
+    {"path": "requirements.txt", "contains": "(?i)^django\\b", "weight": 0.4}]},
+  {"name": "Java/Maven", "rules": [
+    {"path": "pom.xml", "weight": 1.0}]},
+  {"name": "Java/Gradle", "rules": [
+    {"path": "build.gradle", "weight": 1.0},
+    {"path": "build.gradle.kts", "weight": 1.0}]},
+  {"name": "Spring Boot", "rules": [
+    {"path": "pom.xml", "contains": "spring-boot", "weight": 0.9},
+    {"path": "build.gradle*", "contains": "org.springframework.boot", "weight": 0.9}]},
+  {"name": "Rust", "rules": [
+    {"path": "Cargo.toml", "weight": 1.0}]},
+  {"name": "Go", "rules": [
+    {"path": "go.mod", "weight": 1.0}]},
+  {"name": "PHP", "rules": [
+    {"path": "composer.json", "weight": 1.0}]},
+  {"name": "Laravel", "rules": [
+    {"path": "artisan", "weight": 0.6},
+    {"path": "composer.json", "contains": "laravel/framework", "weight": 0.6}]},
+  {"name": "Ruby", "rules": [
+    {"path": "Gemfile", "weight": 1.0}]},
+  {"name": "Ruby on Rails", "rules": [
+    {"path": "Gemfile", "contains": "gem ['\"]rails['\"]", "weight": 0.9},
+    {"path": "config/routes.rb", "weight": 0.4}]},
+  {"name": ".NET", "rules": [
+    {"path": "*.{csproj,fsproj,sln}", "weight": 1.0},
+    {"path": "*/*.{csproj,fsproj}", "weight": 0.6}]},
+  {"name": "Flutter", "rules": [
+    {"path": "pubspec.yaml", "contains": "flutter:", "weight": 1.0}]},
+  {"name": "Docker", "rules": [
+    {"path": "Dockerfile", "weight": 1.0}]},
+  {"name": "Docker Compose", "rules": [
+    {"path": "{docker-compose,compose}.{yml,yaml}", "weight": 1.0}]}
+]"#;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectionRule {
+    /// Glob relative to the project root; `{a,b}` picks alternatives
+    pub path: String,
+    /// Regex a matching file's content must contain
+    #[serde(default)]
+    pub contains: Option<String>,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameworkDetector {
+    pub name: String,
+    pub rules: Vec<DetectionRule>,
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f64,
+}
+
+fn default_min_confidence() -> f64 {
+    0.5
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameworkCandidate {
+    pub name: String,
+    /// 0 to 1
+    pub confidence: f64,
+    /// Paths of the files or directories the matching rules found
+    pub evidence: Vec<String>,
+}
+
+pub fn parse_detectors(content: &str) -> Result<Vec<FrameworkDetector>, String> {
+    let detectors: Vec<FrameworkDetector> =
+        serde_json::from_str(content).map_err(|e| format!("Invalid framework detectors: {}", e))?;
+    for detector in &detectors {
+        if detector.name.trim().is_empty() || detector.rules.is_empty() {
+            return Err("Framework detectors need a name and rules".to_string());
+        }
+        for rule in &detector.rules {
+            if let Some(pattern) = &rule.contains {
+                Regex::new(pattern).map_err(|e| {
+                    format!("{}: invalid pattern {}: {}", detector.name, pattern, e)
+                })?;
+            }
+        }
+    }
+    Ok(detectors)
+}
+
+/// The built-in detectors, with the user's on top: a user detector replaces
+/// the built-in one of the same name
+pub fn detectors() -> Vec<FrameworkDetector> {
+    let mut detectors = parse_detectors(BUILTIN_DETECTORS).unwrap_or_default();
+    let Some(path) = user_detectors_path() else {
+        return detectors;
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return detectors;
+    };
+    match parse_detectors(&content) {
+        Ok(user) => {
+            for detector in user {
+                match detectors.iter_mut().find(|d| d.name == detector.name) {
+                    Some(existing) => *existing = detector,
+                    None => detectors.push(detector),
+                }
+            }
+        }
+        Err(e) => eprintln!("[Projects] Ignoring {}: {}", path.display(), e),
+    }
+    detectors
+}
+
+pub fn user_detectors_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| {
+        home.join(".portal-desktop")
+            .join("framework-detectors.json")
+    })
+}
+
+/// Frameworks the project at `root` looks like, most confident first
+pub fn detect(root: &Path, detectors: &[FrameworkDetector]) -> Vec<FrameworkCandidate> {
+    let mut entries = Vec::new();
+    list_entries(root, "", 0, &mut entries);
+
+    let mut candidates: Vec<FrameworkCandidate> = detectors
+        .iter()
+        .filter_map(|detector| {
+            let mut confidence = 0.0;
+            let mut evidence = Vec::new();
+            for rule in &detector.rules {
+                if let Some(path) = rule_match(root, rule, &entries) {
+                    confidence += rule.weight;
+                    if !evidence.contains(&path) {
+                        evidence.push(path);
+                    }
+                }
+            }
+            let confidence = f64::min(confidence, 1.0);
+            (confidence > 0.0 && confidence >= detector.min_confidence).then(|| {
+                FrameworkCandidate {
+                    name: detector.name.clone(),
+                    confidence,
+                    evidence,
+                }
+            })
+        })
+        .collect();
+    // Stable, so equally confident frameworks keep the detectors' order
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    candidates
+}
+
+/// The first entry the rule matches, if any
+fn rule_match(root: &Path, rule: &DetectionRule, entries: &[String]) -> Option<String> {
+    let patterns = expand_braces(&rule.path);
+    let content = rule
+        .contains
+        .as_deref()
+        .and_then(|pattern| Regex::new(&format!("(?m){}", pattern)).ok());
+    entries
+        .iter()
+        .filter(|entry| patterns.iter().any(|pattern| glob_match(pattern, entry)))
+        .find(|entry| match &content {
+            None => true,
+            Some(regex) => {
+                let path = root.join(entry.as_str());
+                std::fs::metadata(&path).is_ok_and(|m| m.is_file() && m.len() <= MAX_CONTENT_BYTES)
+                    && std::fs::read_to_string(&path).is_ok_and(|text| regex.is_match(&text))
+            }
+        })
+        .cloned()
+}
+
+/// `a.{js,ts}` -> `a.js`, `a.ts`
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let (Some(open), Some(close)) = (pattern.find('{'), pattern.find('}')) else {
+        return vec![pattern.to_string()];
+    };
+    if close < open {
+        return vec![pattern.to_string()];
+    }
+    pattern[open + 1..close]
+        .split(',')
+        .flat_map(|choice| {
+            expand_braces(&format!(
+                "{}{}{}",
+                &pattern[..open],
+                choice,
+                &pattern[close + 1..]
+            ))
+        })
+        .collect()
+}
+
+/// `/`-separated paths of the files and directories under `dir`
+fn list_entries(dir: &Path, prefix: &str, depth: usize, entries: &mut Vec<String>) {
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = format!("{}{}", prefix, name);
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if is_dir && depth + 1 < MAX_DEPTH && !SKIPPED_DIRS.contains(&name.as_str()) {
+            list_entries(&entry.path(), &format!("{}/", path), depth + 1, entries);
+        }
+        entries.push(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_frameworks_from_rules() {
+        let root =
+            std::env::temp_dir().join(format!("portal-framework-detection-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src-tauri")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules").join("svelte")).unwrap();
+        std::fs::write(root.join("src-tauri").join("tauri.conf.json"), "{}").unwrap();
+        std::fs::write(root.join("svelte.config.js"), "export default {}").unwrap();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"devDependencies": {"svelte": "^5.0.0", "vite": "^6.0.0"}}"#,
+        )
+        .unwrap();
+
+        let detectors = parse_detectors(BUILTIN_DETECTORS).unwrap();
+        let candidates = detect(&root, &detectors);
+        let names: Vec<&str> = candidates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Tauri", "Svelte", "Node.js"]);
+        assert_eq!(candidates[0].evidence, ["src-tauri/tauri.conf.json"]);
+        assert_eq!(candidates[1].evidence, ["svelte.config.js", "package.json"]);
+
+        // A user detector that needs more evidence than there is
+        let custom = parse_detectors(
+            r#"[{"name": "SvelteKit", "min_confidence": 0.7, "rules": [
+                {"path": "svelte.config.{js,ts}", "contains": "@sveltejs/kit", "weight": 0.5},
+                {"path": "package.json", "contains": "\"@sveltejs/kit\"", "weight": 0.5},
+                {"path": "package.json", "contains": "\"svelte\"", "weight": 0.3}]}]"#,
+        )
+        .unwrap();
+        let candidates = detect(&root, &custom);
+        assert!(candidates.is_empty());
+
+        assert!(
+            parse_detectors(r#"[{"name": "X", "rules": [{"path": "a", "contains": "("}]}]"#)
+                .is_err()
+        );
+        assert_eq!(expand_braces("a.{js,ts}"), ["a.js", "a.ts"]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
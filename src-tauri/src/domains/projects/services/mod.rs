@@ -1,3 +1,7 @@
+pub mod framework_detection;
+pub mod project_clone;
+pub mod project_group_service;
 pub mod project_service;
+pub mod quick_open;
 
 pub use project_service::*;
@@ -0,0 +1,333 @@
+//! Projects are organized into nested groups (a client, a team, personal
+//! work). Groups and the projects in each are ordered by a position the user
+//! sets by dragging; moving something rewrites the positions of its new
+//! siblings so they stay 0, 1, 2, ... A group can't be moved into itself or
+//! one of its subgroups. Deleting a group moves its subgroups and projects up
+//! to its parent.
+
+use crate::database::DatabaseManager;
+use crate::domains::projects::repositories::project_group_repository::ProjectGroupRepository;
+use crate::entities::project_group::Model;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+pub const GROUP_KINDS: &[&str] = &["client", "team", "personal"];
+
+/// A group with its subgroups and the projects directly in it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectGroupNode {
+    pub id: i32,
+    pub name: String,
+    pub kind: String,
+    pub parent_id: Option<i32>,
+    pub color: Option<String>,
+    pub position: i32,
+    /// Projects in the group, by position
+    pub project_ids: Vec<i32>,
+    pub children: Vec<ProjectGroupNode>,
+}
+
+pub struct ProjectGroupService {
+    repository: ProjectGroupRepository,
+}
+
+impl ProjectGroupService {
+    pub fn new(db_manager: &Arc<DatabaseManager>) -> Self {
+        Self {
+            repository: ProjectGroupRepository::new(db_manager.clone()),
+        }
+    }
+
+    /// The group hierarchy, top-level groups first
+    pub async fn get_tree(&self) -> Result<Vec<ProjectGroupNode>, String> {
+        let groups = self.repository.get_all().await?;
+        let mut project_ids = Vec::with_capacity(groups.len());
+        for group in &groups {
+            project_ids.push((
+                group.id,
+                self.repository.get_project_ids(Some(group.id)).await?,
+            ));
+        }
+        Ok(build_tree(&groups, &project_ids))
+    }
+
+    pub async fn create(
+        &self,
+        name: String,
+        kind: String,
+        parent_id: Option<i32>,
+        color: Option<String>,
+    ) -> Result<Model, String> {
+        let name = validate_name(&name)?;
+        validate_kind(&kind)?;
+        let groups = self.repository.get_all().await?;
+        if let Some(parent_id) = parent_id {
+            if !groups.iter().any(|group| group.id == parent_id) {
+                return Err(format!("Project group not found: {}", parent_id));
+            }
+        }
+        let position = siblings(&groups, parent_id, None).len() as i32;
+        self.repository
+            .create(name, kind, parent_id, color, position)
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        id: i32,
+        name: Option<String>,
+        kind: Option<String>,
+        color: Option<Option<String>>,
+    ) -> Result<Model, String> {
+        let name = name.as_deref().map(validate_name).transpose()?;
+        if let Some(kind) = &kind {
+            validate_kind(kind)?;
+        }
+        self.repository.update(id, name, kind, color).await
+    }
+
+    /// Put a group under `parent_id` (the top level without one) at
+    /// `position` among its siblings, at the end without one
+    pub async fn move_group(
+        &self,
+        id: i32,
+        parent_id: Option<i32>,
+        position: Option<usize>,
+    ) -> Result<(), String> {
+        let groups = self.repository.get_all().await?;
+        if !groups.iter().any(|group| group.id == id) {
+            return Err(format!("Project group not found: {}", id));
+        }
+        if let Some(parent_id) = parent_id {
+            if !groups.iter().any(|group| group.id == parent_id) {
+                return Err(format!("Project group not found: {}", parent_id));
+            }
+            if descendant_ids(&groups, id).contains(&parent_id) {
+                return Err("A group can't be moved into itself or its subgroups".to_string());
+            }
+        }
+        let order = insert_at(siblings(&groups, parent_id, Some(id)), id, position);
+        for (index, group_id) in order.into_iter().enumerate() {
+            self.repository
+                .set_placement(group_id, parent_id, index as i32)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Put a project in `group_id` (ungrouped without one) at `position`
+    /// among the group's projects, at the end without one
+    pub async fn move_project(
+        &self,
+        project_id: i32,
+        group_id: Option<i32>,
+        position: Option<usize>,
+    ) -> Result<(), String> {
+        if let Some(group_id) = group_id {
+            if self.repository.get_by_id(group_id).await?.is_none() {
+                return Err(format!("Project group not found: {}", group_id));
+            }
+        }
+        let mut others = self.repository.get_project_ids(group_id).await?;
+        others.retain(|id| *id != project_id);
+        let order = insert_at(others, project_id, position);
+        for (index, id) in order.into_iter().enumerate() {
+            self.repository
+                .set_project_placement(id, group_id, index as i32)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Delete a group, moving its subgroups and projects to its parent
+    pub async fn delete(&self, id: i32) -> Result<(), String> {
+        let groups = self.repository.get_all().await?;
+        let group = groups
+            .iter()
+            .find(|group| group.id == id)
+            .ok_or_else(|| format!("Project group not found: {}", id))?;
+
+        let mut position = siblings(&groups, group.parent_id, Some(id)).len() as i32;
+        for child in siblings(&groups, Some(id), None) {
+            self.repository
+                .set_placement(child, group.parent_id, position)
+                .await?;
+            position += 1;
+        }
+        let mut position = self
+            .repository
+            .get_project_ids(group.parent_id)
+            .await?
+            .len() as i32;
+        for project_id in self.repository.get_project_ids(Some(id)).await? {
+            self.repository
+                .set_project_placement(project_id, group.parent_id, position)
+                .await?;
+            position += 1;
+        }
+        self.repository.delete(id).await
+    }
+
+    /// `group_id` and the IDs of all groups nested in it
+    pub async fn group_and_subgroup_ids(&self, group_id: i32) -> Result<HashSet<i32>, String> {
+        Ok(descendant_ids(&self.repository.get_all().await?, group_id))
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Project groups need a name".to_string());
+    }
+    Ok(name.to_string())
+}
+
+fn validate_kind(kind: &str) -> Result<(), String> {
+    if GROUP_KINDS.contains(&kind) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Project groups are {}, not {}",
+            GROUP_KINDS.join(", "),
+            kind
+        ))
+    }
+}
+
+/// IDs of the groups under `parent_id` by position, leaving out `except`
+fn siblings(groups: &[Model], parent_id: Option<i32>, except: Option<i32>) -> Vec<i32> {
+    let mut siblings: Vec<&Model> = groups
+        .iter()
+        .filter(|group| group.parent_id == parent_id && Some(group.id) != except)
+        .collect();
+    siblings.sort_by_key(|group| (group.position, group.id));
+    siblings.into_iter().map(|group| group.id).collect()
+}
+
+/// `ids` with `id` inserted at `position`, or appended
+fn insert_at(mut ids: Vec<i32>, id: i32, position: Option<usize>) -> Vec<i32> {
+    let index = position.unwrap_or(ids.len()).min(ids.len());
+    ids.insert(index, id);
+    ids
+}
+
+/// `id` and the IDs of all groups nested in it
+fn descendant_ids(groups: &[Model], id: i32) -> HashSet<i32> {
+    let mut found = HashSet::from([id]);
+    let mut pending = vec![id];
+    while let Some(parent) = pending.pop() {
+        for group in groups
+            .iter()
+            .filter(|group| group.parent_id == Some(parent))
+        {
+            if found.insert(group.id) {
+                pending.push(group.id);
+            }
+        }
+    }
+    found
+}
+
+/// Nest the groups under their parents. Groups whose parent is gone are
+/// treated as top-level.
+fn build_tree(groups: &[Model], project_ids: &[(i32, Vec<i32>)]) -> Vec<ProjectGroupNode> {
+    fn nodes(
+        groups: &[Model],
+        project_ids: &[(i32, Vec<i32>)],
+        parent_id: Option<i32>,
+        seen: &mut HashSet<i32>,
+    ) -> Vec<ProjectGroupNode> {
+        let mut level = Vec::new();
+        for id in siblings(groups, parent_id, None) {
+            if !seen.insert(id) {
+                continue;
+            }
+            let Some(group) = groups.iter().find(|group| group.id == id) else {
+                continue;
+            };
+            level.push(ProjectGroupNode {
+                id,
+                name: group.name.clone(),
+                kind: group.kind.clone(),
+                parent_id: group.parent_id,
+                color: group.color.clone(),
+                position: group.position,
+                project_ids: project_ids
+                    .iter()
+                    .find(|(group_id, _)| *group_id == id)
+                    .map(|(_, ids)| ids.clone())
+                    .unwrap_or_default(),
+                children: nodes(groups, project_ids, Some(id), seen),
+            });
+        }
+        level
+    }
+
+    let ids: HashSet<i32> = groups.iter().map(|group| group.id).collect();
+    let orphaned: Vec<Model> = groups
+        .iter()
+        .map(|group| match group.parent_id {
+            Some(parent_id) if !ids.contains(&parent_id) => Model {
+                parent_id: None,
+                ..group.clone()
+            },
+            _ => group.clone(),
+        })
+        .collect();
+    nodes(&orphaned, project_ids, None, &mut HashSet::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(id: i32, parent_id: Option<i32>, position: i32) -> Model {
+        Model {
+            id,
+            name: format!("Group {}", id),
+            kind: "client".to_string(),
+            parent_id,
+            color: None,
+            position,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn nests_and_orders_groups() {
+        let groups = vec![
+            group(1, None, 1),
+            group(2, None, 0),
+            group(3, Some(1), 1),
+            group(4, Some(1), 0),
+            group(5, Some(4), 0),
+            group(6, Some(99), 0),
+        ];
+        let tree = build_tree(&groups, &[(4, vec![10, 11])]);
+        let top: Vec<i32> = tree.iter().map(|node| node.id).collect();
+        assert_eq!(top, vec![2, 6, 1]);
+        let parent = &tree[2];
+        assert_eq!(
+            parent
+                .children
+                .iter()
+                .map(|node| node.id)
+                .collect::<Vec<_>>(),
+            vec![4, 3]
+        );
+        assert_eq!(parent.children[0].project_ids, vec![10, 11]);
+        assert_eq!(parent.children[0].children[0].id, 5);
+
+        assert_eq!(descendant_ids(&groups, 1), HashSet::from([1, 3, 4, 5]));
+        assert_eq!(siblings(&groups, Some(1), Some(4)), vec![3]);
+        assert_eq!(insert_at(vec![3], 4, Some(0)), vec![4, 3]);
+        assert_eq!(insert_at(vec![3], 4, Some(9)), vec![3, 4]);
+        assert_eq!(insert_at(vec![3], 4, None), vec![3, 4]);
+
+        assert!(validate_kind("team").is_ok());
+        assert!(validate_kind("tag").is_err());
+        assert!(validate_name("  ").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::process_ext::NoWindowExt;
 use chrono::Utc;
 use regex::Regex;
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
@@ -9,6 +10,7 @@ use crate::database::DatabaseManager;
 use crate::domains::projects::entities::ProjectAnalysis;
 use crate::domains::projects::entities::ProjectResponse;
 use crate::domains::projects::repositories::project_repository::ProjectRepository;
+use crate::domains::projects::services::framework_detection::{self, FrameworkCandidate};
 use crate::error::{AppError, AppResult};
 use std::sync::Arc;
 
@@ -245,6 +247,7 @@ impl ProjectService {
         status_filter: Option<String>,
         sort_by: String,
         search_query: Option<String>,
+        group_ids: Option<&HashSet<i32>>,
     ) -> Result<Vec<ProjectResponse>, String> {
         // Use repository methods instead of direct database access
         let mut projects = self.repository.get_all().await?;
@@ -255,6 +258,10 @@ impl ProjectService {
             projects.retain(|p| p.status == status);
         }
 
+        if let Some(group_ids) = group_ids {
+            projects.retain(|p| p.group_id.is_some_and(|id| group_ids.contains(&id)));
+        }
+
         if let Some(query_str) = search_query {
             let query_lower = query_str.to_lowercase();
             projects.retain(|p| {
@@ -283,6 +290,10 @@ impl ProjectService {
                 .unwrap_or_default()
                 .cmp(&a.last_opened.unwrap_or_default()),
             "size" => b.size.cmp(&a.size),
+            "position" => a
+                .position
+                .cmp(&b.position)
+                .then_with(|| a.name.cmp(&b.name)),
             _ => a.name.cmp(&b.name),
         });
 
@@ -317,59 +328,24 @@ impl ProjectService {
         Ok(name)
     }
 
+    /// Names of the frameworks the project looks like, most likely first
     pub async fn detect_frameworks(&self, path: &str) -> Result<Vec<String>, String> {
-        let path_obj = Path::new(path);
-        let mut frameworks = Vec::new();
-
-        // Check for common framework indicators (check ALL, not just first)
-        let framework_indicators = [
-            ("tauri.conf.json", "Tauri"),
-            ("angular.json", "Angular"),
-            ("vue.config.js", "Vue.js"),
-            ("next.config.js", "Next.js"),
-            ("nuxt.config.js", "Nuxt.js"),
-            ("svelte.config.js", "Svelte"),
-            ("svelte.config.ts", "Svelte"),
-            ("vite.config.js", "Vite"),
-            ("vite.config.ts", "Vite"),
-            ("webpack.config.js", "Webpack"),
-            ("remix.config.js", "Remix"),
-            ("gatsby-config.js", "Gatsby"),
-            ("package.json", "Node.js"), // Keep this for general Node.js detection
-            ("requirements.txt", "Python"),
-            ("pom.xml", "Java/Maven"),
-            ("build.gradle", "Java/Gradle"),
-            ("Cargo.toml", "Rust"),
-            ("go.mod", "Go"),
-            ("composer.json", "PHP"),
-            ("Gemfile", "Ruby"),
-            ("Dockerfile", "Docker"),
-            ("docker-compose.yml", "Docker Compose"),
-        ];
-
-        for (file, framework) in framework_indicators.iter() {
-            if path_obj.join(file).exists() {
-                frameworks.push((*framework).to_string());
-            }
-        }
-
-        // Check for framework-specific directories (only if no frameworks found yet)
-        if frameworks.is_empty() {
-            let framework_dirs = [
-                ("node_modules", "Node.js"),
-                ("vendor", "PHP"),
-                ("target", "Java/Maven"),
-                ("build", "Java/Gradle"),
-            ];
-
-            for (dir, framework) in framework_dirs.iter() {
-                if path_obj.join(dir).exists() {
-                    frameworks.push((*framework).to_string());
-                }
-            }
-        }
+        Ok(self
+            .detect_framework_candidates(path)
+            .await?
+            .into_iter()
+            .map(|candidate| candidate.name)
+            .collect())
+    }
 
-        Ok(frameworks)
+    pub async fn detect_framework_candidates(
+        &self,
+        path: &str,
+    ) -> Result<Vec<FrameworkCandidate>, String> {
+        Ok(framework_detection::detect(
+            Path::new(path),
+            &framework_detection::detectors(),
+        ))
     }
 
     pub async fn detect_languages(&self, path: &str) -> Result<Vec<String>, String> {
@@ -0,0 +1,545 @@
+//! Cross-project quick open: an index of each project's files, and the files
+//! opened recently from an IDE, a terminal or the palette itself.
+//!
+//! Indexing skips `.git` and whatever the project's `.gitignore` files
+//! ignore; an index is kept in memory for a minute. Recently opened files are
+//! kept in `~/.portal-desktop/recent-files.json`, newest first.
+
+use crate::utils::glob::glob_match;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Serializes writers of the recent files
+static RECENT_LOCK: once_cell::sync::Lazy<Mutex<()>> = once_cell::sync::Lazy::new(Default::default);
+/// File indexes by project root
+static INDEXES: once_cell::sync::Lazy<Mutex<HashMap<PathBuf, FileIndex>>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+const INDEX_TTL: Duration = Duration::from_secs(60);
+const MAX_INDEXED_FILES: usize = 50_000;
+const MAX_RECENT_FILES: usize = 200;
+/// Score on top of the match for a recently opened file, less for older ones
+const RECENT_BONUS: i64 = 40;
+
+/// Programs whose file arguments count as opening the file from a terminal
+const TERMINAL_OPENERS: &[&str] = &[
+    "vim", "nvim", "vi", "nano", "emacs", "micro", "hx", "helix", "code", "cursor", "subl", "zed",
+    "cat", "bat", "less", "more", "head", "tail", "open", "xdg-open",
+];
+
+struct FileIndex {
+    files: Arc<Vec<String>>,
+    indexed_at: Instant,
+}
+
+/// A project whose files quick open searches
+#[derive(Debug, Clone)]
+pub struct IndexedProject {
+    pub id: i32,
+    pub name: String,
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuickOpenResult {
+    pub project_id: i32,
+    pub project_name: String,
+    /// Relative to the project root, `/`-separated
+    pub path: String,
+    pub absolute_path: String,
+    pub score: i64,
+    /// When the file was last opened, if it was recently
+    pub opened_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentFile {
+    pub path: String,
+    /// ide, terminal or palette
+    pub source: String,
+    pub opened_at: String,
+}
+
+pub struct RecentFiles {
+    path: PathBuf,
+}
+
+impl RecentFiles {
+    pub fn new() -> Result<Self, String> {
+        let path = dirs::home_dir()
+            .ok_or("Could not determine home directory")?
+            .join(".portal-desktop")
+            .join("recent-files.json");
+        Ok(Self { path })
+    }
+
+    fn load(&self) -> Vec<RecentFile> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Move `path` to the front of the recent files
+    pub async fn record(&self, path: &Path, source: &str) -> Result<(), String> {
+        let _guard = RECENT_LOCK.lock().await;
+        let path = path.to_string_lossy().to_string();
+        let mut files = self.load();
+        files.retain(|file| file.path != path);
+        files.insert(
+            0,
+            RecentFile {
+                path,
+                source: source.to_string(),
+                opened_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        files.truncate(MAX_RECENT_FILES);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let content = serde_json::to_string_pretty(&files)
+            .map_err(|e| format!("Failed to serialize recent files: {}", e))?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| format!("Failed to write recent files: {}", e))
+    }
+
+    /// Recently opened files that still exist, newest first
+    pub fn list(&self) -> Vec<RecentFile> {
+        self.load()
+            .into_iter()
+            .filter(|file| Path::new(&file.path).is_file())
+            .collect()
+    }
+}
+
+/// Record a file as opened, logging rather than failing
+pub async fn record_opened(path: &Path, source: &str) {
+    let result = match RecentFiles::new() {
+        Ok(recent) => recent.record(path, source).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        eprintln!(
+            "[Projects] Failed to record {} as opened: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+/// Record the files a finished terminal command opened, like `vim src/main.rs`
+pub fn record_terminal_command(command: &str, working_directory: &str) {
+    let files: Vec<PathBuf> = file_arguments(command)
+        .into_iter()
+        .map(|arg| Path::new(working_directory).join(arg))
+        .filter(|path| path.is_file())
+        .collect();
+    if files.is_empty() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        for file in files {
+            record_opened(&file, "terminal").await;
+        }
+    });
+}
+
+/// The arguments of `command` naming files, when its program opens files
+fn file_arguments(command: &str) -> Vec<&str> {
+    let mut words = command.split_whitespace();
+    let Some(program) = words.next() else {
+        return Vec::new();
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    if !TERMINAL_OPENERS.contains(&program) {
+        return Vec::new();
+    }
+    words
+        .take_while(|word| !matches!(*word, "|" | "&&" | "||" | ";" | ">" | ">>"))
+        .filter(|word| !word.starts_with('-') && !word.starts_with('+'))
+        .map(|word| word.trim_matches(|c| c == '"' || c == '\''))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Files of the project at `root`, relative and `/`-separated, indexed again
+/// when `refresh` is set or the index is older than a minute
+pub async fn project_files(root: &Path, refresh: bool) -> Result<Arc<Vec<String>>, String> {
+    if !refresh {
+        let indexes = INDEXES.lock().await;
+        if let Some(index) = indexes
+            .get(root)
+            .filter(|index| index.indexed_at.elapsed() < INDEX_TTL)
+        {
+            return Ok(index.files.clone());
+        }
+    }
+    let walk_root = root.to_path_buf();
+    let files = tokio::task::spawn_blocking(move || index_files(&walk_root))
+        .await
+        .map_err(|e| format!("File indexing failed: {}", e))??;
+    let files = Arc::new(files);
+    INDEXES.lock().await.insert(
+        root.to_path_buf(),
+        FileIndex {
+            files: files.clone(),
+            indexed_at: Instant::now(),
+        },
+    );
+    Ok(files)
+}
+
+/// Search the projects' files and the recently opened ones in them. Recent
+/// files rank higher; with an empty query only they are returned.
+pub async fn search(
+    projects: &[IndexedProject],
+    query: &str,
+    limit: usize,
+) -> Result<Vec<QuickOpenResult>, String> {
+    let recent = RecentFiles::new()?.list();
+    let recent_rank: HashMap<&str, (usize, &RecentFile)> = recent
+        .iter()
+        .enumerate()
+        .map(|(rank, file)| (file.path.as_str(), (rank, file)))
+        .collect();
+    let query = query.trim();
+
+    let mut results = Vec::new();
+    if query.is_empty() {
+        for file in &recent {
+            if let Some((project, relative)) = owning_project(projects, Path::new(&file.path)) {
+                results.push(result(project, relative, 0, Some(file.opened_at.clone())));
+            }
+        }
+        results.truncate(limit);
+        return Ok(results);
+    }
+
+    for project in projects {
+        let files = match project_files(&project.root, false).await {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("[Projects] Skipping {} in quick open: {}", project.name, e);
+                continue;
+            }
+        };
+        for relative in files.iter() {
+            let Some(mut score) = fuzzy_score(query, relative) else {
+                continue;
+            };
+            let absolute = project.root.join(relative).to_string_lossy().to_string();
+            let opened_at = recent_rank.get(absolute.as_str()).map(|(rank, file)| {
+                score += RECENT_BONUS - (*rank as i64 * RECENT_BONUS / MAX_RECENT_FILES as i64);
+                file.opened_at.clone()
+            });
+            results.push(result(project, relative.clone(), score, opened_at));
+        }
+    }
+    results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    results.truncate(limit);
+    Ok(results)
+}
+
+/// Recently opened files in the projects, newest first
+pub fn recent_in(
+    projects: &[IndexedProject],
+    limit: usize,
+) -> Result<Vec<QuickOpenResult>, String> {
+    Ok(RecentFiles::new()?
+        .list()
+        .into_iter()
+        .filter_map(|file| {
+            owning_project(projects, Path::new(&file.path))
+                .map(|(project, relative)| result(project, relative, 0, Some(file.opened_at)))
+        })
+        .take(limit)
+        .collect())
+}
+
+fn result(
+    project: &IndexedProject,
+    relative: String,
+    score: i64,
+    opened_at: Option<String>,
+) -> QuickOpenResult {
+    QuickOpenResult {
+        project_id: project.id,
+        project_name: project.name.clone(),
+        absolute_path: project.root.join(&relative).to_string_lossy().to_string(),
+        path: relative,
+        score,
+        opened_at,
+    }
+}
+
+/// The project `path` is in, the innermost one for nested projects, and the
+/// path relative to it
+fn owning_project<'a>(
+    projects: &'a [IndexedProject],
+    path: &Path,
+) -> Option<(&'a IndexedProject, String)> {
+    projects
+        .iter()
+        .filter_map(|project| {
+            path.strip_prefix(&project.root)
+                .ok()
+                .map(|relative| (project, relative))
+        })
+        .max_by_key(|(project, _)| project.root.components().count())
+        .map(|(project, relative)| (project, to_slash_path(relative)))
+}
+
+fn to_slash_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Score `path` for `query` when all of the query's characters appear in it
+/// in order, ignoring case. Consecutive characters, characters starting a
+/// word and matches in the file name score higher; gaps between matches and
+/// long paths score lower.
+pub fn fuzzy_score(query: &str, path: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let chars: Vec<char> = path.chars().collect();
+    let name_start = chars
+        .iter()
+        .rposition(|c| *c == '/')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    // Prefer matching the end of the query in the file name: match the
+    // query backwards from the end of the path
+    let mut positions = Vec::with_capacity(query.len());
+    let mut index = chars.len();
+    for wanted in query.iter().rev() {
+        let found = chars[..index]
+            .iter()
+            .rposition(|c| c.to_lowercase().eq(std::iter::once(*wanted)))?;
+        positions.push(found);
+        index = found;
+    }
+    positions.reverse();
+
+    let mut score = 0i64;
+    for (i, &position) in positions.iter().enumerate() {
+        score += 1;
+        if let Some(&previous) = i.checked_sub(1).map(|p| &positions[p]) {
+            match position - previous - 1 {
+                0 => score += 8,
+                gap => score -= gap.min(3) as i64,
+            }
+        }
+        let starts_word = match position.checked_sub(1).map(|p| chars[p]) {
+            None | Some('/' | '_' | '-' | '.' | ' ') => true,
+            Some(c) => c.is_lowercase() && chars[position].is_uppercase(),
+        };
+        if starts_word {
+            score += 6;
+        }
+        if position >= name_start {
+            score += 3;
+        }
+    }
+    if positions.first() == Some(&name_start) {
+        score += 10;
+    }
+    Some(score - chars.len() as i64 / 8)
+}
+
+/// A `.gitignore` rule, relative to the directory of its file
+struct IgnoreRule {
+    base: String,
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+}
+
+impl IgnoreRule {
+    fn parse(base: &str, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        // Patterns without a slash match at any depth below the file
+        let pattern = match line.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if line.contains('/') => line.to_string(),
+            None => format!("**/{}", line),
+        };
+        Some(Self {
+            base: base.to_string(),
+            pattern,
+            negated,
+            dir_only,
+        })
+    }
+
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let path = if self.base.is_empty() {
+            relative
+        } else {
+            match relative
+                .strip_prefix(self.base.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                Some(rest) => rest,
+                None => return false,
+            }
+        };
+        glob_match(&self.pattern, path)
+    }
+}
+
+fn is_ignored(rules: &[IgnoreRule], relative: &str, is_dir: bool) -> bool {
+    // The last matching rule decides
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(relative, is_dir))
+        .is_some_and(|rule| !rule.negated)
+}
+
+fn index_files(root: &Path) -> Result<Vec<String>, String> {
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    let mut files = Vec::new();
+    let mut rules = Vec::new();
+    walk(root, "", &mut rules, &mut files);
+    files.sort();
+    Ok(files)
+}
+
+fn walk(dir: &Path, relative: &str, rules: &mut Vec<IgnoreRule>, files: &mut Vec<String>) {
+    let rules_before = rules.len();
+    if let Ok(content) = std::fs::read_to_string(dir.join(".gitignore")) {
+        rules.extend(
+            content
+                .lines()
+                .filter_map(|line| IgnoreRule::parse(relative, line)),
+        );
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        rules.truncate(rules_before);
+        return;
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        if files.len() >= MAX_INDEXED_FILES {
+            break;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" {
+            continue;
+        }
+        let child = if relative.is_empty() {
+            name
+        } else {
+            format!("{}/{}", relative, name)
+        };
+        // Symlinks aren't followed, so links to directories can't loop
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let is_dir = file_type.is_dir();
+        if is_ignored(rules, &child, is_dir) {
+            continue;
+        }
+        if is_dir {
+            walk(&entry.path(), &child, rules, files);
+        } else {
+            files.push(child);
+        }
+    }
+    rules.truncate(rules_before);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_files_respecting_gitignore() {
+        let root = std::env::temp_dir().join(format!("portal-quick-open-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for file in [
+            "src/main.rs",
+            "src/lib.rs",
+            "target/debug/app",
+            "logs/today.log",
+            "logs/keep.log",
+            "web/dist/app.js",
+            "web/src/App.svelte",
+            ".git/HEAD",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "/target\n*.log\n!keep.log\n").unwrap();
+        std::fs::write(root.join("web/.gitignore"), "dist/\n").unwrap();
+
+        let files = index_files(&root).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                ".gitignore",
+                "logs/keep.log",
+                "src/lib.rs",
+                "src/main.rs",
+                "web/.gitignore",
+                "web/src/App.svelte",
+            ]
+        );
+    }
+
+    #[test]
+    fn ranks_fuzzy_matches() {
+        assert!(fuzzy_score("xyz", "src/main.rs").is_none());
+        let main = fuzzy_score("main", "src/main.rs").unwrap();
+        let scattered = fuzzy_score("main", "src/my_admin_index.rs").unwrap();
+        assert!(main > scattered);
+        // File names beat directories with the same letters
+        let name = fuzzy_score("app", "web/src/App.svelte").unwrap();
+        let dir = fuzzy_score("app", "apps/web/index.ts").unwrap();
+        assert!(name > dir);
+        assert!(fuzzy_score("src main", "src/main.rs").is_some());
+
+        assert_eq!(
+            file_arguments("vim -O src/main.rs +12 README.md"),
+            vec!["src/main.rs", "README.md"]
+        );
+        assert_eq!(
+            file_arguments("cat Cargo.toml | grep name"),
+            vec!["Cargo.toml"]
+        );
+        assert!(file_arguments("cargo build").is_empty());
+    }
+}
@@ -5,25 +5,28 @@ use crate::domains::scripts::services::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
 
 /// Shared state for script execution service
 pub struct ScriptExecutionState {
     pub service: Arc<Mutex<Option<ScriptExecutionService>>>,
+    app: AppHandle,
 }
 
 impl ScriptExecutionState {
-    pub fn new() -> Self {
+    pub fn new(app: AppHandle) -> Self {
         Self {
             service: Arc::new(Mutex::new(None)),
+            app,
         }
     }
 
     pub async fn get_or_init(&self, db_manager: &Arc<DatabaseManager>) -> ScriptExecutionService {
         let mut service = self.service.lock().await;
         if service.is_none() {
-            *service = Some(ScriptExecutionService::new(db_manager.clone()));
+            *service =
+                Some(ScriptExecutionService::new(db_manager.clone()).with_app(self.app.clone()));
         }
         service.as_ref().unwrap().clone()
     }
@@ -52,6 +55,10 @@ pub async fn execute_script(
         command: params.command,
         parameters: params.parameters.unwrap_or_default(),
         working_directory: params.working_directory,
+        secret_parameters: Vec::new(),
+        custom_script_id: None,
+        triggered_by: None,
+        interpreter: None,
     };
 
     service.execute_script(request).await
@@ -15,6 +15,7 @@ impl ScriptExecutionRepository {
         Self { db_manager }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         &self,
         id: String,
@@ -23,6 +24,7 @@ impl ScriptExecutionRepository {
         parameters_json: String,
         working_directory: Option<String>,
         triggered_by: String,
+        custom_script_id: Option<i32>,
     ) -> Result<Model, String> {
         let db = self.db_manager.get_connection();
         let id_clone = id.clone();
@@ -41,6 +43,7 @@ impl ScriptExecutionRepository {
             started_at: Set(chrono::Utc::now().into()),
             finished_at: Set(None),
             triggered_by: Set(triggered_by),
+            custom_script_id: Set(custom_script_id),
         };
 
         // Note: For SQLite with string primary keys, insert() tries to return the inserted record
@@ -104,6 +107,27 @@ impl ScriptExecutionRepository {
             .map_err(|e| format!("Failed to get script executions: {}", e))
     }
 
+    pub async fn get_by_custom_script(
+        &self,
+        custom_script_id: i32,
+        limit: Option<u64>,
+    ) -> Result<Vec<Model>, String> {
+        let db = self.db_manager.get_connection();
+
+        let mut query = Entity::find()
+            .filter(Column::CustomScriptId.eq(custom_script_id))
+            .order_by_desc(Column::StartedAt);
+
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+
+        query
+            .all(db)
+            .await
+            .map_err(|e| format!("Failed to get script executions: {}", e))
+    }
+
     pub async fn get_running(&self) -> Result<Vec<Model>, String> {
         let db = self.db_manager.get_connection();
 
@@ -1,5 +1,5 @@
 pub mod script_execution_service;
 
 pub use script_execution_service::{
-    ExecuteScriptRequest, ScriptExecutionInfo, ScriptExecutionService,
+    ExecuteScriptRequest, ScriptExecutionInfo, ScriptExecutionService, ScriptInterpreter,
 };
@@ -1,12 +1,12 @@
+use crate::command_executor::supervisor::{supervisor, OutputStream, ProcessSpec, SupervisedChild};
 use crate::database::DatabaseManager;
 use crate::domains::scripts::repositories::ScriptExecutionRepository;
-use crate::process_ext::NoWindowExt;
+use crate::entities::script_execution::Model as ScriptExecutionModel;
 use crate::utils::pnpm_workspace::prepare_shell_command;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::{Child, Command};
+use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,8 +16,43 @@ pub struct ExecuteScriptRequest {
     pub command: String,
     pub parameters: HashMap<String, String>,
     pub working_directory: Option<String>,
+    /// Parameters whose values are masked in the run's record and output
+    #[serde(default)]
+    pub secret_parameters: Vec<String>,
+    /// Custom script the run belongs to, for its run history
+    #[serde(default)]
+    pub custom_script_id: Option<i32>,
+    /// What started the run, e.g. `schedule` or `event:project.opened`;
+    /// `user` when unset
+    #[serde(default)]
+    pub triggered_by: Option<String>,
+    /// Run the command as a source file with this interpreter instead of
+    /// through the shell
+    #[serde(skip)]
+    pub interpreter: Option<ScriptInterpreter>,
 }
 
+/// An interpreter that runs a script's source from a file
+#[derive(Debug, Clone)]
+pub struct ScriptInterpreter {
+    pub program: String,
+    /// Arguments before the script file
+    pub args: Vec<String>,
+    /// Extension the source file is written with, e.g. `py`
+    pub extension: String,
+}
+
+/// Emitted for every line a run prints
+pub const SCRIPT_OUTPUT_EVENT: &str = "script-execution-output";
+/// Emitted when a run exits
+pub const SCRIPT_FINISHED_EVENT: &str = "script-execution-finished";
+
+/// Env var a parameter's value is passed in
+const PARAMETER_ENV_PREFIX: &str = "PORTAL_PARAM_";
+
+/// Stands in for secret values in records and output
+const MASK: &str = "••••••";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ScriptExecutionInfo {
@@ -34,15 +69,39 @@ pub struct ScriptExecutionInfo {
     pub started_at: String,
     pub finished_at: Option<String>,
     pub triggered_by: String,
+    pub custom_script_id: Option<i32>,
+}
+
+impl From<ScriptExecutionModel> for ScriptExecutionInfo {
+    fn from(e: ScriptExecutionModel) -> Self {
+        Self {
+            id: e.id,
+            block_id: e.block_id,
+            command: e.command,
+            parameters: serde_json::from_str(&e.parameters_json).unwrap_or_default(),
+            working_directory: e.working_directory,
+            status: e.status,
+            exit_code: e.exit_code,
+            pid: e.pid,
+            output: e.output,
+            error: e.error,
+            started_at: e.started_at.to_rfc3339(),
+            finished_at: e.finished_at.map(|d| d.to_rfc3339()),
+            triggered_by: e.triggered_by,
+            custom_script_id: e.custom_script_id,
+        }
+    }
 }
 
 pub struct ScriptExecutionService {
     repository: ScriptExecutionRepository,
     db_manager: Arc<DatabaseManager>,
     // Map execution_id -> Child process
-    running_processes: Arc<Mutex<HashMap<String, Child>>>,
+    running_processes: Arc<Mutex<HashMap<String, SupervisedChild>>>,
     // Map execution_id -> live output buffer (for real-time updates)
     output_buffers: Arc<Mutex<HashMap<String, Vec<String>>>>,
+    // Streams output and exits to the frontend when set
+    app: Option<AppHandle>,
 }
 
 impl ScriptExecutionService {
@@ -52,19 +111,129 @@ impl ScriptExecutionService {
             db_manager,
             running_processes: Arc::new(Mutex::new(HashMap::new())),
             output_buffers: Arc::new(Mutex::new(HashMap::new())),
+            app: None,
         }
     }
 
-    /// Resolve parameter placeholders in command
-    fn resolve_command(command: &str, parameters: &HashMap<String, String>) -> String {
-        let mut resolved = command.to_string();
-        for (key, value) in parameters {
-            // Replace ${key} style placeholders
-            resolved = resolved.replace(&format!("${{{}}}", key), value);
-            // Also support $key style without braces
-            resolved = resolved.replace(&format!("${}", key), value);
+    /// Stream each run's output and exit to the frontend
+    pub fn with_app(mut self, app: AppHandle) -> Self {
+        self.app = Some(app);
+        self
+    }
+
+    /// Resolve `${key}` and `$key` parameter placeholders in a command. The
+    /// values never become part of the command: each is passed in an env var
+    /// and the placeholder becomes a quoted reference to it, so a value can't
+    /// inject shell syntax or split into several arguments. Returns the
+    /// command and the env vars to run it with.
+    fn resolve_command(
+        command: &str,
+        parameters: &HashMap<String, String>,
+        windows: bool,
+    ) -> (String, HashMap<String, String>) {
+        #[derive(PartialEq)]
+        enum Quote {
+            None,
+            Single,
+            Double,
+        }
+        let chars: Vec<char> = command.chars().collect();
+        let mut resolved = String::with_capacity(command.len());
+        let mut quote = Quote::None;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let escape = if windows { '^' } else { '\\' };
+            match c {
+                _ if c == escape && quote != Quote::Single && i + 1 < chars.len() => {
+                    resolved.push(c);
+                    resolved.push(chars[i + 1]);
+                    i += 2;
+                    continue;
+                }
+                '\'' if !windows && quote != Quote::Double => {
+                    quote = if quote == Quote::Single {
+                        Quote::None
+                    } else {
+                        Quote::Single
+                    };
+                }
+                '"' if quote != Quote::Single => {
+                    quote = if quote == Quote::Double {
+                        Quote::None
+                    } else {
+                        Quote::Double
+                    };
+                }
+                '$' => {
+                    let (name, end) = if chars.get(i + 1) == Some(&'{') {
+                        match chars[i + 2..].iter().position(|c| *c == '}') {
+                            Some(len) => (chars[i + 2..i + 2 + len].iter().collect(), i + 3 + len),
+                            None => (String::new(), i + 1),
+                        }
+                    } else {
+                        let len = chars[i + 1..]
+                            .iter()
+                            .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+                            .count();
+                        (chars[i + 1..i + 1 + len].iter().collect(), i + 1 + len)
+                    };
+                    if parameters.contains_key(&name) {
+                        let var = Self::env_var(&name);
+                        let reference = if windows {
+                            format!("!{}!", var)
+                        } else {
+                            format!("${{{}}}", var)
+                        };
+                        match quote {
+                            Quote::None => resolved.push_str(&format!("\"{}\"", reference)),
+                            Quote::Double => resolved.push_str(&reference),
+                            // Step out of the single quotes to expand it
+                            Quote::Single => resolved.push_str(&format!("'\"{}\"'", reference)),
+                        }
+                        i = end;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            resolved.push(c);
+            i += 1;
         }
-        resolved
+
+        (resolved, Self::parameter_env(parameters))
+    }
+
+    /// Env var a parameter's value is passed in
+    fn env_var(name: &str) -> String {
+        let suffix: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}{}", PARAMETER_ENV_PREFIX, suffix)
+    }
+
+    fn parameter_env(parameters: &HashMap<String, String>) -> HashMap<String, String> {
+        parameters
+            .iter()
+            .map(|(name, value)| (Self::env_var(name), value.clone()))
+            .collect()
+    }
+
+    /// Replace secret values in a line of output
+    fn mask_secrets(line: &str, secrets: &[String]) -> String {
+        secrets
+            .iter()
+            .filter(|secret| !secret.is_empty())
+            .fold(line.to_string(), |line, secret| {
+                line.replace(secret.as_str(), MASK)
+            })
     }
 
     /// Build a short error message from exit code + captured output.
@@ -96,15 +265,47 @@ impl ScriptExecutionService {
     /// Execute a script and track it persistently
     pub async fn execute_script(&self, request: ExecuteScriptRequest) -> Result<String, String> {
         let execution_id = Uuid::new_v4().to_string();
-        let parameters_json = serde_json::to_string(&request.parameters)
+        let recorded: HashMap<&String, &str> = request
+            .parameters
+            .iter()
+            .map(|(name, value)| {
+                let value = if request.secret_parameters.contains(name) {
+                    MASK
+                } else {
+                    value.as_str()
+                };
+                (name, value)
+            })
+            .collect();
+        let parameters_json = serde_json::to_string(&recorded)
             .map_err(|e| format!("Failed to serialize parameters: {}", e))?;
-
-        // Resolve command with parameters, then apply shell rewrites (e.g. broken pnpm workspace)
-        let resolved_command = Self::resolve_command(&request.command, &request.parameters);
-        let exec_command = if let Some(ref wd) = request.working_directory {
-            prepare_shell_command(&resolved_command, wd)
+        let secrets: Arc<Vec<String>> = Arc::new(
+            request
+                .secret_parameters
+                .iter()
+                .filter_map(|name| request.parameters.get(name).cloned())
+                .collect(),
+        );
+
+        let (exec_command, parameter_env) = if request.interpreter.is_some() {
+            // Interpreted sources read their parameters from the env
+            (
+                request.command.clone(),
+                Self::parameter_env(&request.parameters),
+            )
         } else {
-            resolved_command
+            // Resolve command with parameters, then apply shell rewrites (e.g. broken pnpm workspace)
+            let (resolved_command, parameter_env) = Self::resolve_command(
+                &request.command,
+                &request.parameters,
+                cfg!(target_os = "windows"),
+            );
+            let exec_command = if let Some(ref wd) = request.working_directory {
+                prepare_shell_command(&resolved_command, wd)
+            } else {
+                resolved_command
+            };
+            (exec_command, parameter_env)
         };
 
         // Create execution record in database
@@ -116,7 +317,11 @@ impl ScriptExecutionService {
                 exec_command.clone(),
                 parameters_json,
                 request.working_directory.clone(),
-                "user".to_string(),
+                request
+                    .triggered_by
+                    .clone()
+                    .unwrap_or_else(|| "user".to_string()),
+                request.custom_script_id,
             )
             .await?;
 
@@ -140,32 +345,60 @@ impl ScriptExecutionService {
             return Err("Command cannot be empty".to_string());
         }
 
+        // Interpreted sources run from a file, removed once the run ends
+        let script_file = match &request.interpreter {
+            Some(interpreter) => {
+                let path = std::env::temp_dir().join(format!(
+                    "portal-script-{}.{}",
+                    execution_id, interpreter.extension
+                ));
+                if let Err(e) = tokio::fs::write(&path, &exec_command).await {
+                    self.repository
+                        .update_status(
+                            &execution_id,
+                            "failed".to_string(),
+                            None,
+                            Some(format!("Failed to write script file: {}", e)),
+                        )
+                        .await?;
+                    return Err(format!("Failed to write script file: {}", e));
+                }
+                Some(path)
+            }
+            None => None,
+        };
+
         // Build command - use shell to handle complex commands with pipes, redirects, etc.
-        let mut cmd = if cfg!(target_os = "windows") {
-            let mut c = Command::new("cmd");
-            c.no_window();
-            c.args(["/C", &exec_command]);
-            c
+        let mut spec = if let (Some(interpreter), Some(path)) = (&request.interpreter, &script_file)
+        {
+            let mut spec = ProcessSpec::new("scripts", interpreter.program.clone());
+            spec.args(&interpreter.args);
+            spec.arg(path.to_string_lossy());
+            spec
+        } else if cfg!(target_os = "windows") {
+            // Delayed expansion (/V:ON) expands the !PORTAL_PARAM_*! references
+            // after the command is parsed, so their values stay data
+            let mut spec = ProcessSpec::new("scripts", "cmd");
+            spec.args(["/V:ON", "/C", &exec_command]);
+            spec
         } else {
-            let mut c = Command::new("sh");
-            c.args(["-c", &exec_command]);
-            c
+            ProcessSpec::shell("scripts", &exec_command)
         };
 
+        spec.envs(&parameter_env);
+
         // Set working directory
         if let Some(ref wd) = request.working_directory {
-            cmd.current_dir(wd);
+            spec.current_dir(wd);
         }
 
-        // Configure stdio
-        cmd.stdout(std::process::Stdio::piped());
-        cmd.stderr(std::process::Stdio::piped());
-        cmd.kill_on_drop(true);
-
         // Spawn process
-        let mut child = match cmd.spawn() {
+        let mut child = match supervisor().spawn(&spec) {
             Ok(c) => c,
             Err(e) => {
+                if let Some(path) = &script_file {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
                 self.repository
                     .update_status(
                         &execution_id,
@@ -179,14 +412,13 @@ impl ScriptExecutionService {
         };
 
         // Get PID and update record
-        let pid = child.id().map(|p| p as i32);
+        let pid = child.pid().map(|p| p as i32);
         if let Some(pid) = pid {
             self.repository.update_pid(&execution_id, pid).await?;
         }
 
-        // Take stdout/stderr before moving child
-        let stdout = child.stdout.take();
-        let stderr = child.stderr.take();
+        // Take the output before moving child
+        let mut output = child.take_output();
 
         // Store child process for tracking
         {
@@ -199,60 +431,45 @@ impl ScriptExecutionService {
         let repo = self.repository.clone();
         let output_buffers = Arc::clone(&self.output_buffers);
         let running_processes = Arc::clone(&self.running_processes);
-
-        tokio::spawn(async move {
-            // Read stdout + stderr in parallel (sequential reads can deadlock on full pipes)
-            let exec_id_out = exec_id_clone.clone();
-            let buffers_out = Arc::clone(&output_buffers);
-            let stdout_task = async move {
-                let mut lines = Vec::new();
-                if let Some(stdout) = stdout {
-                    let mut reader = BufReader::new(stdout).lines();
-                    while let Ok(Some(line)) = reader.next_line().await {
-                        let formatted = format!("{}\n", line);
-                        {
-                            let mut bufs = buffers_out.lock().unwrap();
-                            if let Some(buf) = bufs.get_mut(&exec_id_out) {
-                                buf.push(formatted.clone());
-                            }
-                        }
-                        lines.push(formatted);
-                    }
+        let app = self.app.clone();
+        let custom_script_id = request.custom_script_id;
+        let emit_line = {
+            let app = app.clone();
+            let execution_id = execution_id.clone();
+            move |line: &str, stream: &str| {
+                if let Some(app) = &app {
+                    let _ = app.emit(
+                        SCRIPT_OUTPUT_EVENT,
+                        serde_json::json!({
+                            "executionId": execution_id,
+                            "line": line,
+                            "stream": stream,
+                        }),
+                    );
                 }
-                lines
-            };
+            }
+        };
 
-            let exec_id_err = exec_id_clone.clone();
-            let buffers_err = Arc::clone(&output_buffers);
-            let stderr_task = async move {
-                let mut lines = Vec::new();
-                if let Some(stderr) = stderr {
-                    let mut reader = BufReader::new(stderr).lines();
-                    while let Ok(Some(line)) = reader.next_line().await {
-                        let formatted = format!("[stderr] {}\n", line);
-                        {
-                            let mut bufs = buffers_err.lock().unwrap();
-                            if let Some(buf) = bufs.get_mut(&exec_id_err) {
-                                buf.push(formatted.clone());
-                            }
-                        }
-                        lines.push(formatted);
+        tokio::spawn(async move {
+            let mut combined_output = String::new();
+            while let Some(output) = output.recv().await {
+                let masked = Self::mask_secrets(&output.line, &secrets);
+                emit_line(&masked, output.stream.as_str());
+                let formatted = match output.stream {
+                    OutputStream::Stdout => format!("{}\n", masked),
+                    OutputStream::Stderr => format!("[stderr] {}\n", masked),
+                };
+                {
+                    let mut bufs = output_buffers.lock().unwrap();
+                    if let Some(buf) = bufs.get_mut(&exec_id_clone) {
+                        buf.push(formatted.clone());
                     }
                 }
-                lines
-            };
-
-            let (stdout_lines, stderr_lines) = tokio::join!(stdout_task, stderr_task);
-            let mut combined_output = String::new();
-            for line in &stdout_lines {
-                combined_output.push_str(line);
-            }
-            for line in &stderr_lines {
-                combined_output.push_str(line);
+                combined_output.push_str(&formatted);
             }
 
             // Wait for process to complete - extract child from map first, then await
-            let child_opt: Option<Child> = {
+            let child_opt: Option<SupervisedChild> = {
                 let mut processes = running_processes.lock().unwrap();
                 processes.remove(&exec_id_clone)
             };
@@ -268,9 +485,7 @@ impl ScriptExecutionService {
 
             // Update final status in database
             let (status, exit_code, error) = match wait_result {
-                Ok(status) if status.success() => {
-                    ("success".to_string(), status.code(), None)
-                }
+                Ok(status) if status.success() => ("success".to_string(), status.code(), None),
                 Ok(status) => {
                     let code = status.code();
                     (
@@ -290,8 +505,24 @@ impl ScriptExecutionService {
                 ),
             };
 
+            if let Some(path) = script_file {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+
             // Save output and final status
             let _ = repo.append_output(&exec_id_clone, &combined_output).await;
+            if let Some(app) = &app {
+                let _ = app.emit(
+                    SCRIPT_FINISHED_EVENT,
+                    serde_json::json!({
+                        "executionId": exec_id_clone,
+                        "status": status,
+                        "exitCode": exit_code,
+                        "error": error,
+                        "customScriptId": custom_script_id,
+                    }),
+                );
+            }
             let _ = repo
                 .update_status(&exec_id_clone, status, exit_code, error)
                 .await;
@@ -313,21 +544,7 @@ impl ScriptExecutionService {
     ) -> Result<Option<ScriptExecutionInfo>, String> {
         let execution = self.repository.get_by_id(execution_id).await?;
 
-        Ok(execution.map(|e| ScriptExecutionInfo {
-            id: e.id,
-            block_id: e.block_id,
-            command: e.command,
-            parameters: serde_json::from_str(&e.parameters_json).unwrap_or_default(),
-            working_directory: e.working_directory,
-            status: e.status,
-            exit_code: e.exit_code,
-            pid: e.pid,
-            output: e.output,
-            error: e.error,
-            started_at: e.started_at.to_rfc3339(),
-            finished_at: e.finished_at.map(|d| d.to_rfc3339()),
-            triggered_by: e.triggered_by,
-        }))
+        Ok(execution.map(ScriptExecutionInfo::from))
     }
 
     /// Get live output for a running execution
@@ -339,13 +556,13 @@ impl ScriptExecutionService {
     /// Cancel a running execution
     pub async fn cancel_execution(&self, execution_id: &str) -> Result<(), String> {
         // Try to kill the process - extract from map first
-        let child_opt: Option<Child> = {
+        let child_opt: Option<SupervisedChild> = {
             let mut processes = self.running_processes.lock().unwrap();
             processes.remove(execution_id)
         };
 
         if let Some(mut child) = child_opt {
-            let _ = child.kill().await;
+            child.kill_tree().await;
         }
 
         // Update database status
@@ -372,21 +589,24 @@ impl ScriptExecutionService {
 
         Ok(executions
             .into_iter()
-            .map(|e| ScriptExecutionInfo {
-                id: e.id,
-                block_id: e.block_id,
-                command: e.command,
-                parameters: serde_json::from_str(&e.parameters_json).unwrap_or_default(),
-                working_directory: e.working_directory,
-                status: e.status,
-                exit_code: e.exit_code,
-                pid: e.pid,
-                output: e.output,
-                error: e.error,
-                started_at: e.started_at.to_rfc3339(),
-                finished_at: e.finished_at.map(|d| d.to_rfc3339()),
-                triggered_by: e.triggered_by,
-            })
+            .map(ScriptExecutionInfo::from)
+            .collect())
+    }
+
+    /// Get a custom script's runs, newest first
+    pub async fn get_executions_by_custom_script(
+        &self,
+        custom_script_id: i32,
+        limit: Option<u64>,
+    ) -> Result<Vec<ScriptExecutionInfo>, String> {
+        let executions = self
+            .repository
+            .get_by_custom_script(custom_script_id, limit)
+            .await?;
+
+        Ok(executions
+            .into_iter()
+            .map(ScriptExecutionInfo::from)
             .collect())
     }
 
@@ -396,21 +616,7 @@ impl ScriptExecutionService {
 
         Ok(executions
             .into_iter()
-            .map(|e| ScriptExecutionInfo {
-                id: e.id,
-                block_id: e.block_id,
-                command: e.command,
-                parameters: serde_json::from_str(&e.parameters_json).unwrap_or_default(),
-                working_directory: e.working_directory,
-                status: e.status,
-                exit_code: e.exit_code,
-                pid: e.pid,
-                output: e.output,
-                error: e.error,
-                started_at: e.started_at.to_rfc3339(),
-                finished_at: e.finished_at.map(|d| d.to_rfc3339()),
-                triggered_by: e.triggered_by,
-            })
+            .map(ScriptExecutionInfo::from)
             .collect())
     }
 
@@ -423,21 +629,7 @@ impl ScriptExecutionService {
 
         Ok(executions
             .into_iter()
-            .map(|e| ScriptExecutionInfo {
-                id: e.id,
-                block_id: e.block_id,
-                command: e.command,
-                parameters: serde_json::from_str(&e.parameters_json).unwrap_or_default(),
-                working_directory: e.working_directory,
-                status: e.status,
-                exit_code: e.exit_code,
-                pid: e.pid,
-                output: e.output,
-                error: e.error,
-                started_at: e.started_at.to_rfc3339(),
-                finished_at: e.finished_at.map(|d| d.to_rfc3339()),
-                triggered_by: e.triggered_by,
-            })
+            .map(ScriptExecutionInfo::from)
             .collect())
     }
 
@@ -492,6 +684,7 @@ impl ScriptExecutionService {
         }
         #[cfg(windows)]
         {
+            use crate::process_ext::NoWindowExt;
             use std::process::Command as StdCommand;
             // Use tasklist to check if process exists
             StdCommand::new("tasklist")
@@ -523,6 +716,47 @@ impl Clone for ScriptExecutionService {
             db_manager: self.db_manager.clone(),
             running_processes: Arc::clone(&self.running_processes),
             output_buffers: Arc::clone(&self.output_buffers),
+            app: self.app.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_parameters_as_quoted_env_references() {
+        let parameters = HashMap::from([
+            ("file".to_string(), "a b; rm -rf ~".to_string()),
+            ("mode".to_string(), "$(whoami)".to_string()),
+        ]);
+
+        let (command, env) = ScriptExecutionService::resolve_command(
+            "cat ${file} \"$mode\" '${file}' $HOME \\$file",
+            &parameters,
+            false,
+        );
+        assert_eq!(
+            command,
+            "cat \"${PORTAL_PARAM_FILE}\" \"${PORTAL_PARAM_MODE}\" ''\"${PORTAL_PARAM_FILE}\"'' $HOME \\$file"
+        );
+        assert_eq!(env["PORTAL_PARAM_FILE"], "a b; rm -rf ~");
+        assert_eq!(env["PORTAL_PARAM_MODE"], "$(whoami)");
+
+        let (command, _) = ScriptExecutionService::resolve_command(
+            "type ${file} & echo \"$mode\"",
+            &parameters,
+            true,
+        );
+        assert_eq!(
+            command,
+            "type \"!PORTAL_PARAM_FILE!\" & echo \"!PORTAL_PARAM_MODE!\""
+        );
+
+        assert_eq!(
+            ScriptExecutionService::mask_secrets("token=abc123", &["abc123".to_string()]),
+            "token=••••••"
+        );
+    }
+}
@@ -4,9 +4,13 @@
  * Tauri commands for SDK manager-specific operations (version management, installation, etc.)
  */
 use crate::command_executor::{CommandExecutor, CommandOptions, ShellType};
+use crate::database::DatabaseManager;
+use crate::domains::learning::services::anomaly_detector::{self, SDK_SWITCHED};
 use crate::domains::sdk::configs::{get_all_sdk_configs, SDKManagerConfig};
 use crate::domains::sdk::factory::SDKManagerFactory;
 use serde::Serialize;
+use std::sync::Arc;
+use tauri::State;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SDKManagerWorkflowSupport {
@@ -344,15 +348,29 @@ pub async fn install_sdk_manager(manager_name: String) -> Result<String, String>
 pub async fn switch_version_via_manager(
     manager_name: String,
     version: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
 ) -> Result<String, String> {
     let factory = SDKManagerFactory::new();
 
     if let Some(manager) = factory.get_manager(&manager_name) {
         match manager.switch_version(&version).await {
-            Ok(_) => Ok(format!(
-                "Successfully switched to version {} for {}",
-                version, manager_name
-            )),
+            Ok(_) => {
+                anomaly_detector::record_change(
+                    db_manager.get_connection(),
+                    SDK_SWITCHED,
+                    serde_json::json!({
+                        "sdk_type": manager_name,
+                        "version": version,
+                        "scope": "global",
+                    }),
+                    None,
+                )
+                .await;
+                Ok(format!(
+                    "Successfully switched to version {} for {}",
+                    version, manager_name
+                ))
+            }
             Err(e) => Err(format!(
                 "Failed to switch to version {} for {}: {}",
                 version, manager_name, e
@@ -1,5 +1,32 @@
+use crate::database::DatabaseManager;
+use crate::domains::learning::services::anomaly_detector::{self, SDK_SWITCHED};
+use crate::domains::sdk::download::installer::SdkInstaller;
+use crate::domains::sdk::download::mirrors::{
+    download_settings, is_offline, CachedArchive, OFFICIAL_BASES,
+};
+use crate::domains::sdk::download::InstallProgress;
 use crate::domains::sdk::manager_detector::{detect_sdk_managers as detect_managers, SDKInfo};
 use crate::domains::sdk::ollama_manager::{OllamaManager, OllamaModel, OllamaVersion};
+use crate::domains::sdk::ollama_modelfile::{self, ModelExport, ModelfileSpec};
+use crate::domains::sdk::project::asdf::{AsdfImportReport, AsdfImporter};
+use crate::domains::sdk::project::lockfile::{LockRestoreReport, LockfileManager, PortalLock};
+use crate::domains::sdk::project::shell_integration::{Shell, ShellHookStatus, ShellIntegration};
+use crate::domains::sdk::project::shims::{Shim, ShimManager};
+use crate::domains::sdk::project::version_file::{VersionFileInfo, VersionFileManager};
+use crate::domains::sdk::services::disk_usage::{CleanupResult, DiskUsageReport, DiskUsageScanner};
+use crate::domains::sdk::services::php_config::{
+    IniSetting, IniValue, PhpConfigManager, PhpExtension, PhpInstallation,
+};
+use crate::domains::sdk::services::port_manager::{PortCheck, PortManager};
+use crate::domains::sdk::services::service_manager::{
+    note_user_action, MonitorSettings, MonitoredService, ServiceMonitor,
+};
+use crate::domains::sdk::services::service_provisioner::{
+    ProvisionOptions, ProvisionedInstance, ServiceProvisioner,
+};
+use crate::domains::sdk::services::vhost_manager::{
+    Site, SiteConfig, VhostManager, VhostServerStatus,
+};
 use crate::domains::sdk::version_fetcher::{
     fetch_go_versions, fetch_java_versions, fetch_nodejs_versions, fetch_php_versions,
     fetch_python_versions, fetch_ruby_versions, fetch_rust_versions, SDKVersion,
@@ -8,8 +35,10 @@ use crate::domains::sdk::version_installer::{
     install_go_version, install_java_version, install_nodejs_version, install_php_version,
     install_python_version, install_ruby_version, install_rust_version,
 };
+use crate::domains::settings::services::settings_service::{SdkDownloadSettings, SettingsService};
 use serde::{Deserialize, Serialize};
-use tauri::Emitter;
+use std::sync::Arc;
+use tauri::{Emitter, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ServiceStatus {
@@ -56,10 +85,18 @@ pub async fn get_project_versions(project_path: String) -> Result<Vec<SDKVersion
 }
 
 #[tauri::command]
-pub async fn setup_shell_integration(sdk_type: String) -> Result<String, String> {
-    println!("[SDK] Setting up shell integration for: {}", sdk_type);
+pub async fn setup_shell_integration(
+    shells: Option<Vec<Shell>>,
+) -> Result<Vec<ShellHookStatus>, String> {
+    let shells = shells.unwrap_or_else(ShellIntegration::default_shells);
+    println!("[SDK] Installing shell hooks for: {:?}", shells);
+
+    Ok(ShellIntegration::install_hooks(&shells).await?)
+}
 
-    Err("Shell integration is not yet implemented".to_string())
+#[tauri::command]
+pub async fn get_shell_hook_status() -> Result<Vec<ShellHookStatus>, String> {
+    Ok(ShellIntegration::hook_status()?)
 }
 
 #[tauri::command]
@@ -100,15 +137,18 @@ pub async fn find_projects_with_versions(sdk_type: String) -> Result<Vec<String>
 pub async fn get_terminal_integration_status() -> Result<bool, String> {
     println!("[SDK] Getting terminal integration status");
 
-    // Terminal integration not yet implemented
-    Ok(false)
+    Ok(ShellIntegration::hook_status()?
+        .iter()
+        .any(|status| status.installed))
 }
 
 #[tauri::command]
-pub async fn remove_terminal_integration() -> Result<String, String> {
+pub async fn remove_terminal_integration(shells: Option<Vec<Shell>>) -> Result<String, String> {
     println!("[SDK] Removing terminal integration");
 
-    Err("Terminal integration is not yet implemented".to_string())
+    let shells = shells.unwrap_or_else(|| Shell::ALL.to_vec());
+    ShellIntegration::uninstall_hooks(&shells).await?;
+    Ok("Shell hooks removed".to_string())
 }
 
 // Real version fetching
@@ -116,6 +156,11 @@ pub async fn remove_terminal_integration() -> Result<String, String> {
 pub async fn fetch_available_versions(sdk_type: String) -> Result<Vec<SDKVersion>, String> {
     println!("[SDK] Fetching available versions for {}", sdk_type);
 
+    let direct_type = shim_sdk_type(&sdk_type);
+    if SdkInstaller::supports(&direct_type) && is_offline() {
+        return offline_versions(&direct_type);
+    }
+
     match sdk_type.as_str() {
         "node" | "nodejs" => fetch_nodejs_versions().await,
         "python" => fetch_python_versions().await,
@@ -145,9 +190,74 @@ pub async fn fetch_available_versions(sdk_type: String) -> Result<Vec<SDKVersion
     }
 }
 
+/// Offline, the versions that can be installed are the cached archives
+fn offline_versions(sdk_type: &str) -> Result<Vec<SDKVersion>, String> {
+    let installer = SdkInstaller::new()?;
+    let installed = installer.list_installed(sdk_type);
+    Ok(installer
+        .archive_cache()
+        .list(Some(sdk_type))
+        .into_iter()
+        .map(|archive| SDKVersion {
+            installed: installed.contains(&archive.version),
+            version: archive.version,
+            active: false,
+            size: None,
+            release_date: None,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_sdk_download_settings() -> Result<SdkDownloadSettings, String> {
+    Ok(download_settings())
+}
+
+/// Save mirror base URLs (by SDK type) and offline mode
+#[tauri::command]
+pub async fn set_sdk_download_settings(
+    download_settings: SdkDownloadSettings,
+) -> Result<SdkDownloadSettings, String> {
+    for (sdk_type, mirror) in &download_settings.mirrors {
+        if !OFFICIAL_BASES.iter().any(|(sdk, _)| sdk == sdk_type) {
+            return Err(format!("{} has no download source to mirror", sdk_type));
+        }
+        if !mirror.is_empty() && !mirror.starts_with("https://") && !mirror.starts_with("http://") {
+            return Err(format!(
+                "Mirror for {} is not an http(s) URL: {}",
+                sdk_type, mirror
+            ));
+        }
+    }
+    println!(
+        "[SDK] Saving download settings ({} mirrors, offline: {})",
+        download_settings.mirrors.len(),
+        download_settings.offline
+    );
+
+    let settings_service = SettingsService::new();
+    let mut settings = settings_service.load_settings()?;
+    settings.app.sdk_downloads = download_settings;
+    settings.updated_at = chrono::Utc::now();
+    settings_service.save_settings(&settings)?;
+    Ok(settings.app.sdk_downloads)
+}
+
+/// Archives in the download cache, which offline mode installs from
+#[tauri::command]
+pub async fn list_cached_sdk_archives(
+    sdk_type: Option<String>,
+) -> Result<Vec<CachedArchive>, String> {
+    let sdk_type = sdk_type.map(|sdk_type| shim_sdk_type(&sdk_type));
+    Ok(SdkInstaller::new()?
+        .archive_cache()
+        .list(sdk_type.as_deref()))
+}
+
 // Real version installation
 #[tauri::command]
 pub async fn download_and_install_version(
+    app: tauri::AppHandle,
     sdk_type: String,
     version: String,
 ) -> Result<String, String> {
@@ -156,7 +266,7 @@ pub async fn download_and_install_version(
         sdk_type, version
     );
 
-    match sdk_type.as_str() {
+    let via_manager = match sdk_type.as_str() {
         "nodejs" => install_nodejs_version(&version).await,
         "python" => install_python_version(&version).await,
         "java" => install_java_version(&version).await,
@@ -164,10 +274,82 @@ pub async fn download_and_install_version(
         "go" => install_go_version(&version).await,
         "php" => install_php_version(&version).await,
         "ruby" => install_ruby_version(&version).await,
-        _ => Err(format!("Unknown SDK type: {}", sdk_type)),
+        _ => return Err(format!("Unknown SDK type: {}", sdk_type)),
+    };
+
+    match via_manager {
+        Ok(message) => Ok(message),
+        // Fallback to direct download (like FlyEnv does)
+        Err(manager_error) if SdkInstaller::supports(&sdk_type) => {
+            println!(
+                "[SDK] {}; installing {} {} directly",
+                manager_error, sdk_type, version
+            );
+            install_directly(app, &sdk_type, &version)
+                .await
+                .map_err(|e| format!("{} Direct download failed: {}", manager_error, e))
+        }
+        Err(manager_error) => Err(manager_error),
     }
 }
 
+/// Run the direct download pipeline, forwarding progress as
+/// `sdk-install-progress` events
+async fn install_directly(
+    app: tauri::AppHandle,
+    sdk_type: &str,
+    version: &str,
+) -> Result<String, String> {
+    let installer = SdkInstaller::new()?;
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<InstallProgress>();
+
+    let (event_sdk, event_version) = (sdk_type.to_string(), version.to_string());
+    let forwarder = tokio::spawn(async move {
+        while let Some(progress) = receiver.recv().await {
+            let _ = app.emit(
+                "sdk-install-progress",
+                serde_json::json!({
+                    "sdk_type": event_sdk,
+                    "version": event_version,
+                    "stage": progress.stage,
+                    "progress": progress.progress,
+                    "message": progress.message,
+                }),
+            );
+        }
+    });
+
+    let result = installer.install(sdk_type, version, sender).await;
+    let _ = forwarder.await;
+
+    let install_dir = result?;
+    rehash_shims();
+    Ok(format!(
+        "{} {} installed to {}",
+        sdk_type,
+        version,
+        install_dir.display()
+    ))
+}
+
+/// Versions installed by the direct download pipeline
+#[tauri::command]
+pub async fn get_direct_sdk_installs(sdk_type: String) -> Result<Vec<String>, String> {
+    Ok(SdkInstaller::new()?.list_installed(&sdk_type))
+}
+
+#[tauri::command]
+pub async fn remove_direct_sdk_install(
+    sdk_type: String,
+    version: String,
+) -> Result<String, String> {
+    println!("[SDK] Removing direct install of {} {}", sdk_type, version);
+
+    SdkInstaller::new()?.uninstall(&sdk_type, &version).await?;
+    rehash_shims();
+    Ok(format!("{} {} removed", sdk_type, version))
+}
+
 // Real SDK manager detection
 #[tauri::command]
 pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
@@ -178,10 +360,33 @@ pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
 #[tauri::command]
 pub async fn start_sdk_service(sdk_type: String) -> Result<String, String> {
     println!("[SDK] Starting SDK service: {}", sdk_type);
+    // A clash would otherwise only show up as the service failing to start
+    let status = get_service_status(sdk_type.clone()).await?;
+    if !status.running {
+        let check = check_service_port(sdk_type.clone(), status.port).await?;
+        if !check.available {
+            return Err(check.conflict_message());
+        }
+    }
     // Delegate to the real start_service implementation
     start_service(sdk_type).await
 }
 
+/// Whether a service's port (its configured or default one, unless `port`
+/// is given) is free, who holds it if not, and the next free port
+#[tauri::command]
+pub async fn check_service_port(sdk_type: String, port: Option<u16>) -> Result<PortCheck, String> {
+    let port_manager = PortManager::new();
+    let port = match port {
+        Some(port) => port,
+        None => get_service_port(&sdk_type)
+            .await
+            .or_else(|| port_manager.get_default_port(&sdk_type))
+            .ok_or_else(|| format!("No known port for {}", sdk_type))?,
+    };
+    Ok(port_manager.check_port(port).await)
+}
+
 #[tauri::command]
 pub async fn stop_sdk_service(sdk_type: String) -> Result<String, String> {
     println!("[SDK] Stopping SDK service: {}", sdk_type);
@@ -387,6 +592,100 @@ pub async fn install_ollama_model(
     Ok("Model installation started".to_string())
 }
 
+/// The Modelfile of an installed model, as `ollama show --modelfile` gives it
+#[tauri::command]
+pub async fn get_ollama_modelfile(model_name: String) -> Result<String, String> {
+    OllamaManager::show_modelfile(&model_name).await
+}
+
+/// Export an installed model to `<destination>/<model>/`, with its weights
+/// unless `include_weights` is false
+#[tauri::command]
+pub async fn export_ollama_model(
+    model_name: String,
+    destination: String,
+    include_weights: Option<bool>,
+) -> Result<ModelExport, String> {
+    println!(
+        "[SDK] Exporting Ollama model {} to {}",
+        model_name, destination
+    );
+
+    ollama_modelfile::export_model(
+        &model_name,
+        std::path::Path::new(&destination),
+        include_weights.unwrap_or(true),
+    )
+    .await
+}
+
+/// Import a model from a local GGUF file, a Modelfile, or both; progress
+/// comes through the same events as an install
+#[tauri::command]
+pub async fn import_ollama_model(
+    app: tauri::AppHandle,
+    model_name: String,
+    gguf_path: Option<String>,
+    modelfile_path: Option<String>,
+) -> Result<String, String> {
+    println!("[SDK] Importing Ollama model: {}", model_name);
+
+    ollama_modelfile::validate_model_name(&model_name)?;
+    let modelfile = ollama_modelfile::prepare_import(
+        &model_name,
+        gguf_path.as_deref().map(std::path::Path::new),
+        modelfile_path.as_deref().map(std::path::Path::new),
+    )
+    .await?;
+    spawn_model_creation(app, model_name, modelfile);
+
+    Ok("Model import started".to_string())
+}
+
+/// Build a custom model from a Modelfile authored in the app
+#[tauri::command]
+pub async fn create_ollama_model(
+    app: tauri::AppHandle,
+    model_name: String,
+    modelfile: ModelfileSpec,
+) -> Result<String, String> {
+    println!("[SDK] Creating Ollama model: {}", model_name);
+
+    ollama_modelfile::validate_model_name(&model_name)?;
+    let modelfile = ollama_modelfile::prepare_build(&model_name, &modelfile).await?;
+    spawn_model_creation(app, model_name, modelfile);
+
+    Ok("Model creation started".to_string())
+}
+
+/// The Modelfile text the builder would create, for previewing
+#[tauri::command]
+pub async fn render_ollama_modelfile(modelfile: ModelfileSpec) -> Result<String, String> {
+    modelfile.render()
+}
+
+/// Run `ollama create` in the background, reporting through
+/// `ollama-model-progress` like installs do
+fn spawn_model_creation(app: tauri::AppHandle, model_name: String, modelfile: std::path::PathBuf) {
+    tokio::spawn(async move {
+        let result =
+            OllamaManager::create_model_with_progress(&model_name, &modelfile, app.clone()).await;
+        let _ = tokio::fs::remove_file(&modelfile).await;
+        if let Err(e) = result {
+            eprintln!("[SDK] Model creation failed: {}", e);
+            let _ = app.emit(
+                "ollama-model-progress",
+                serde_json::json!({
+                    "model": model_name,
+                    "status": "error",
+                    "message": e,
+                    "progress": 0
+                }),
+            );
+        }
+    });
+}
+
 #[tauri::command]
 pub async fn remove_ollama_model(model_name: String) -> Result<String, String> {
     println!("[SDK] Removing Ollama model: {}", model_name);
@@ -453,7 +752,7 @@ pub async fn get_runtime_available_models(
 pub async fn start_service(sdk_type: String) -> Result<String, String> {
     println!("[SDK] Starting service: {}", sdk_type);
 
-    match sdk_type.as_str() {
+    let result = match sdk_type.as_str() {
         "ollama" => OllamaManager::start_service().await,
         "docker" => start_docker_service().await,
         "postgresql" | "postgres" => start_system_service("postgresql").await,
@@ -466,14 +765,18 @@ pub async fn start_service(sdk_type: String) -> Result<String, String> {
             // Try systemctl first, then fallback to direct command
             start_system_service(&sdk_type).await
         }
+    };
+    if result.is_ok() {
+        note_user_action(&sdk_type, false);
     }
+    result
 }
 
 #[tauri::command]
 pub async fn stop_service(sdk_type: String) -> Result<String, String> {
     println!("[SDK] Stopping service: {}", sdk_type);
 
-    match sdk_type.as_str() {
+    let result = match sdk_type.as_str() {
         "ollama" => OllamaManager::stop_service().await,
         "docker" => stop_docker_service().await,
         "postgresql" | "postgres" => stop_system_service("postgresql").await,
@@ -486,7 +789,11 @@ pub async fn stop_service(sdk_type: String) -> Result<String, String> {
             // Try systemctl first, then fallback to direct command
             stop_system_service(&sdk_type).await
         }
+    };
+    if result.is_ok() {
+        note_user_action(&sdk_type, true);
     }
+    result
 }
 
 /// Start a system service using systemctl or service command
@@ -701,47 +1008,389 @@ pub async fn restart_service(service_id: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-pub async fn get_service_health(service_id: String) -> Result<serde_json::Value, String> {
+pub async fn get_service_health(
+    monitor: State<'_, Arc<ServiceMonitor>>,
+    service_id: String,
+) -> Result<serde_json::Value, String> {
     println!("[SDK] Getting service health for: {}", service_id);
 
-    // Get basic status - detailed health metrics not yet implemented
     let status = get_service_status(service_id.clone()).await?;
+    let monitored = monitor.service(&service_id).await;
 
     let health = serde_json::json!({
         "service_id": service_id,
         "status": if status.running { "healthy" } else { "stopped" },
         "running": status.running,
         "port": status.port,
-        "pid": status.pid
+        "pid": status.pid,
+        "monitor": monitored
     });
 
     Ok(health)
 }
 
+/// Latest probe results, uptime and history for every monitored service
+#[tauri::command]
+pub async fn get_service_monitor_status(
+    monitor: State<'_, Arc<ServiceMonitor>>,
+) -> Result<Vec<MonitoredService>, String> {
+    Ok(monitor.snapshot().await)
+}
+
+#[tauri::command]
+pub async fn get_service_monitor_settings(
+    monitor: State<'_, Arc<ServiceMonitor>>,
+) -> Result<MonitorSettings, String> {
+    Ok(monitor.settings().await)
+}
+
+#[tauri::command]
+pub async fn set_service_monitor_settings(
+    monitor: State<'_, Arc<ServiceMonitor>>,
+    settings: MonitorSettings,
+) -> Result<(), String> {
+    println!("[SDK] Updating service monitor settings");
+    monitor
+        .update_settings(settings)
+        .await
+        .map_err(String::from)
+}
+
+/// Provision a named, isolated database instance with its own data
+/// directory and rendered config
+#[tauri::command]
+pub async fn provision_service_instance(
+    service_type: String,
+    name: String,
+    options: Option<ProvisionOptions>,
+) -> Result<ProvisionedInstance, String> {
+    println!("[SDK] Provisioning {} instance {}", service_type, name);
+    Ok(ServiceProvisioner::new()?
+        .provision(&service_type, &name, options.unwrap_or_default())
+        .await?)
+}
+
+#[tauri::command]
+pub async fn list_service_instances() -> Result<Vec<ProvisionedInstance>, String> {
+    Ok(ServiceProvisioner::new()?.list().await)
+}
+
+#[tauri::command]
+pub async fn start_service_instance(
+    service_type: String,
+    name: String,
+    auto_assign_port: Option<bool>,
+) -> Result<ProvisionedInstance, String> {
+    Ok(ServiceProvisioner::new()?
+        .start(&service_type, &name, auto_assign_port.unwrap_or(false))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn stop_service_instance(service_type: String, name: String) -> Result<(), String> {
+    println!("[SDK] Stopping {} instance {}", service_type, name);
+    Ok(ServiceProvisioner::new()?
+        .stop(&service_type, &name)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn remove_service_instance(
+    service_type: String,
+    name: String,
+    delete_data: Option<bool>,
+) -> Result<(), String> {
+    println!("[SDK] Removing {} instance {}", service_type, name);
+    Ok(ServiceProvisioner::new()?
+        .remove(&service_type, &name, delete_data.unwrap_or(false))
+        .await?)
+}
+
+/// PHP installs found on this machine, one per version
+#[tauri::command]
+pub async fn list_php_installations() -> Result<Vec<PhpInstallation>, String> {
+    Ok(PhpConfigManager::installations().await)
+}
+
+/// Extensions for a PHP version (the `php` on PATH when none is given)
+#[tauri::command]
+pub async fn list_php_extensions(version: Option<String>) -> Result<Vec<PhpExtension>, String> {
+    Ok(PhpConfigManager::list_extensions(version.as_deref()).await?)
+}
+
+#[tauri::command]
+pub async fn set_php_extension_enabled(
+    version: Option<String>,
+    extension: String,
+    enabled: bool,
+) -> Result<(), String> {
+    Ok(PhpConfigManager::set_extension_enabled(version.as_deref(), &extension, enabled).await?)
+}
+
+/// The effective values of the common php.ini directives, typed
+#[tauri::command]
+pub async fn get_php_ini_settings(version: Option<String>) -> Result<Vec<IniSetting>, String> {
+    Ok(PhpConfigManager::ini_settings(version.as_deref()).await?)
+}
+
+#[tauri::command]
+pub async fn set_php_ini_value(
+    version: Option<String>,
+    key: String,
+    value: IniValue,
+) -> Result<(), String> {
+    Ok(PhpConfigManager::set_ini_value(version.as_deref(), &key, value).await?)
+}
+
+/// Sites configured for a web server (nginx, apache or caddy), or for all
+#[tauri::command]
+pub async fn list_vhosts(server: Option<String>) -> Result<Vec<Site>, String> {
+    Ok(VhostManager::new()?.list(server.as_deref()))
+}
+
+/// Where a web server's sites live and whether its main config loads them
+#[tauri::command]
+pub async fn get_vhost_server_status(server: String) -> Result<VhostServerStatus, String> {
+    Ok(VhostManager::new()?.status(&server).await?)
+}
+
+/// Create or replace a site, validated with the server's config test and
+/// followed by a reload
+#[tauri::command]
+pub async fn save_vhost(server: String, site: SiteConfig) -> Result<Site, String> {
+    println!("[SDK] Saving {} site {}", server, site.domain);
+    Ok(VhostManager::new()?.save_site(&server, site).await?)
+}
+
+#[tauri::command]
+pub async fn remove_vhost(server: String, domain: String) -> Result<(), String> {
+    println!("[SDK] Removing {} site {}", server, domain);
+    Ok(VhostManager::new()?.remove_site(&server, &domain).await?)
+}
+
+/// Run the server's config test (`nginx -t` and the like) and return its output
 #[tauri::command]
-pub async fn set_path_environment(sdk_type: String, version: String) -> Result<String, String> {
+pub async fn test_vhost_config(server: String) -> Result<String, String> {
+    Ok(VhostManager::new()?.test_config(&server).await?)
+}
+
+/// How the service monitor restarts a service under a restart policy
+pub fn restart_monitored_service(
+    service: String,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<String, String>> + Send>> {
+    Box::pin(start_service(service))
+}
+
+/// Make a directly installed version the default everywhere a project
+/// doesn't pin one. PATH itself only ever gains the shims directory; the
+/// shims pick the version when they run.
+#[tauri::command]
+pub async fn set_path_environment(
+    sdk_type: String,
+    version: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<String, String> {
     println!(
         "[SDK] Setting PATH environment for: {} version {}",
         sdk_type, version
     );
 
-    Err("PATH environment management is not yet implemented".to_string())
+    let sdk_type = shim_sdk_type(&sdk_type);
+    let shims = ShimManager::new()?;
+    let resolved = shims.set_global_version(&sdk_type, &version).await?;
+    shims.rehash()?;
+    shims.ensure_on_path().await?;
+    anomaly_detector::record_change(
+        db_manager.get_connection(),
+        SDK_SWITCHED,
+        serde_json::json!({ "sdk_type": sdk_type, "version": resolved, "scope": "global" }),
+        None,
+    )
+    .await;
+
+    Ok(format!(
+        "{} {} is now the default (shims in {})",
+        sdk_type,
+        resolved,
+        shims.shims_dir().display()
+    ))
 }
 
 #[tauri::command]
 pub async fn get_path_status(sdk_type: String) -> Result<serde_json::Value, String> {
     println!("[SDK] Getting PATH status for: {}", sdk_type);
 
-    // Basic PATH status - detailed management not yet implemented
+    let sdk_type = shim_sdk_type(&sdk_type);
+    let shims = ShimManager::new()?;
     let status = serde_json::json!({
         "sdk_type": sdk_type,
-        "in_path": false,
-        "path_entries": []
+        "in_path": shims.is_on_path(),
+        "path_entries": [shims.shims_dir()],
+        "global_version": shims.global_versions().await.get(&sdk_type),
+        "shims": shims.shims_for(&sdk_type)
     });
 
     Ok(status)
 }
 
+/// Regenerate the shims after SDKs were installed or removed outside Portal
+#[tauri::command]
+pub async fn rehash_sdk_shims() -> Result<Vec<Shim>, String> {
+    Ok(ShimManager::new()?.rehash()?)
+}
+
+/// Direct installs keep Node.js under "nodejs"
+fn shim_sdk_type(sdk_type: &str) -> String {
+    match sdk_type {
+        "node" => "nodejs".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Keep the shims in step with the installed versions
+fn rehash_shims() {
+    if let Err(e) = ShimManager::new().and_then(|shims| shims.rehash()) {
+        println!("[SDK] Failed to rehash shims: {}", e);
+    }
+}
+
+/// Snapshot the project's SDK versions, plus the named service instances
+/// (`<type>/<name>`), into `portal.lock`
+#[tauri::command]
+pub async fn capture_portal_lock(
+    project_path: String,
+    services: Option<Vec<String>>,
+) -> Result<PortalLock, String> {
+    println!("[SDK] Capturing portal.lock for {}", project_path);
+
+    let project = std::path::Path::new(&project_path);
+    let lock = LockfileManager::capture(project, &services.unwrap_or_default()).await?;
+    LockfileManager::write(project, &lock).await?;
+    Ok(lock)
+}
+
+#[tauri::command]
+pub async fn read_portal_lock(project_path: String) -> Result<PortalLock, String> {
+    Ok(LockfileManager::read(std::path::Path::new(&project_path)).await?)
+}
+
+/// Install, pin and provision everything in the project's `portal.lock`.
+/// Each entry is attempted on its own; failures are reported, not fatal.
+#[tauri::command]
+pub async fn restore_portal_lock(
+    app: tauri::AppHandle,
+    project_path: String,
+) -> Result<LockRestoreReport, String> {
+    println!("[SDK] Restoring portal.lock for {}", project_path);
+
+    let project = std::path::Path::new(&project_path);
+    let lock = LockfileManager::read(project).await?;
+    let mut report = LockRestoreReport::default();
+
+    let installer = SdkInstaller::new()?;
+    for sdk in &lock.sdks {
+        let label = format!("{} {}", sdk.sdk_type, sdk.version);
+        if !installer
+            .list_installed(&sdk.sdk_type)
+            .contains(&sdk.version)
+        {
+            match download_and_install_version(
+                app.clone(),
+                sdk.sdk_type.clone(),
+                sdk.version.clone(),
+            )
+            .await
+            {
+                Ok(_) => report.installed.push(label.clone()),
+                Err(e) => {
+                    report.skipped.push(format!("{}: {}", label, e));
+                    continue;
+                }
+            }
+        }
+        match VersionFileManager::update_version(project, &sdk.sdk_type, &sdk.version).await {
+            Ok(_) => report.activated.push(label),
+            Err(e) => report.skipped.push(format!("{}: {}", label, e)),
+        }
+    }
+    rehash_shims();
+
+    let provisioner = ServiceProvisioner::new()?;
+    let existing = provisioner.list().await;
+    for service in &lock.services {
+        let label = format!("{}/{}", service.service_type, service.name);
+        let instance = existing
+            .iter()
+            .find(|i| i.service_type == service.service_type && i.name == service.name);
+        if instance.is_none() {
+            if service.auth_enabled {
+                report
+                    .skipped
+                    .push(format!("{}: uses a password; provision it with one", label));
+                continue;
+            }
+            let options = ProvisionOptions {
+                port: service.port,
+                host: service.host.clone(),
+                username: service.username.clone(),
+                password: None,
+            };
+            match provisioner
+                .provision(&service.service_type, &service.name, options)
+                .await
+            {
+                Ok(_) => report.provisioned.push(label.clone()),
+                Err(e) => {
+                    report.skipped.push(format!("{}: {}", label, e));
+                    continue;
+                }
+            }
+        }
+        if instance.is_some_and(|i| i.running) {
+            continue;
+        }
+        match provisioner
+            .start(&service.service_type, &service.name, false)
+            .await
+        {
+            Ok(_) => report.started.push(label),
+            Err(e) => report.skipped.push(format!("{}: {}", label, e)),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Space taken by each directly installed SDK version and Ollama model
+#[tauri::command]
+pub async fn get_sdk_disk_usage() -> Result<DiskUsageReport, String> {
+    println!("[SDK] Computing disk usage");
+
+    Ok(DiskUsageScanner::new()?.scan().await?)
+}
+
+/// Remove SDK versions and models unused for `older_than_days`. Only
+/// previews what would go unless `dry_run` is explicitly false.
+#[tauri::command]
+pub async fn cleanup_unused_sdk_versions(
+    older_than_days: u32,
+    dry_run: Option<bool>,
+) -> Result<CleanupResult, String> {
+    let dry_run = dry_run.unwrap_or(true);
+    println!(
+        "[SDK] Cleaning up versions unused for {} days (dry run: {})",
+        older_than_days, dry_run
+    );
+
+    let result = DiskUsageScanner::new()?
+        .cleanup(older_than_days, dry_run)
+        .await?;
+    if !dry_run && !result.removed.is_empty() {
+        rehash_shims();
+    }
+    Ok(result)
+}
+
 #[tauri::command]
 pub async fn create_alias(
     sdk_type: String,
@@ -772,25 +1421,76 @@ pub async fn list_aliases(sdk_type: String) -> Result<Vec<serde_json::Value>, St
 }
 
 #[tauri::command]
-pub async fn detect_version_files(project_path: String) -> Result<Vec<serde_json::Value>, String> {
+pub async fn detect_version_files(project_path: String) -> Result<Vec<VersionFileInfo>, String> {
     println!("[SDK] Detecting version files in: {}", project_path);
 
-    // Version file detection not yet implemented
-    Ok(vec![])
+    VersionFileManager::detect_version_files(std::path::Path::new(&project_path))
+        .await
+        .map_err(|e| e.to_string())
 }
 
+/// `format` is "tool-versions" for asdf's .tool-versions, "portal" for
+/// .portal-version, or omitted for the SDK's own file (.nvmrc, ...)
 #[tauri::command]
 pub async fn create_version_file(
     project_path: String,
     sdk_type: String,
     version: String,
+    format: Option<String>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
 ) -> Result<String, String> {
     println!(
         "[SDK] Creating version file: {} for {} at {}",
         sdk_type, version, project_path
     );
 
-    Err("Version file creation is not yet implemented".to_string())
+    let path = std::path::Path::new(&project_path);
+    let sdk_type = shim_sdk_type(&sdk_type);
+    let file_name = match format.as_deref() {
+        Some("tool-versions") => {
+            VersionFileManager::update_tool_versions(path, &sdk_type, &version).await?;
+            ".tool-versions".to_string()
+        }
+        Some("portal") => {
+            VersionFileManager::update_version(path, &sdk_type, &version).await?;
+            ".portal-version".to_string()
+        }
+        None | Some("standard") => {
+            VersionFileManager::create_standard_version_file(path, &sdk_type, &version).await?;
+            VersionFileManager::get_recommended_format(&sdk_type).0
+        }
+        Some(other) => return Err(format!("Unknown version file format: {}", other)),
+    };
+    anomaly_detector::record_change(
+        db_manager.get_connection(),
+        SDK_SWITCHED,
+        serde_json::json!({ "sdk_type": sdk_type, "version": version, "scope": "project" }),
+        Some(project_path.clone()),
+    )
+    .await;
+
+    Ok(path.join(file_name).to_string_lossy().to_string())
+}
+
+/// Bring over an asdf setup: link its installs, adopt `~/.tool-versions`
+/// as the global default and, given a project, its `.tool-versions`
+#[tauri::command]
+pub async fn import_asdf_versions(
+    project_path: Option<String>,
+) -> Result<AsdfImportReport, String> {
+    println!("[SDK] Importing asdf versions");
+
+    let importer = AsdfImporter::new()?;
+    if !importer.is_available() && project_path.is_none() {
+        return Err("No asdf installation or ~/.tool-versions found".to_string());
+    }
+    let report = importer
+        .import(project_path.as_deref().map(std::path::Path::new))
+        .await?;
+    if !report.linked.is_empty() {
+        rehash_shims();
+    }
+    Ok(report)
 }
 
 #[tauri::command]
@@ -5,8 +5,8 @@
  */
 use super::{DownloadProgress, InstallProgress, InstallStage, VersionInfo};
 use crate::domains::sdk::SDKError;
-use reqwest::Client;
-use std::path::PathBuf;
+use reqwest::{Client, StatusCode};
+use std::path::{Path, PathBuf};
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc;
@@ -91,8 +91,28 @@ impl BinaryDownloader {
         Ok(cache_path)
     }
 
+    /// Download `url` into the cache as `file_name`, resuming a partial
+    /// download from an earlier attempt
+    pub async fn download_to_cache(
+        &self,
+        url: &str,
+        file_name: &str,
+        progress_sender: mpsc::UnboundedSender<InstallProgress>,
+    ) -> Result<PathBuf, SDKError> {
+        tokio::fs::create_dir_all(&self.cache_dir)
+            .await
+            .map_err(|e| {
+                SDKError::ManagerNotFound(format!("Failed to create cache directory: {}", e))
+            })?;
+
+        let cache_path = self.cache_dir.join(file_name);
+        self.download_resumable(url, &cache_path, progress_sender)
+            .await?;
+        Ok(cache_path)
+    }
+
     /// Download file with progress tracking
-    async fn download_with_progress(
+    pub async fn download_with_progress(
         &self,
         url: &str,
         output_path: &PathBuf,
@@ -109,10 +129,127 @@ impl BinaryDownloader {
             .await
             .map_err(|e| SDKError::ManagerNotFound(format!("Failed to create file: {}", e)))?;
 
+        let downloaded = self
+            .stream_to_file(response, &mut file, 0, total_size, &progress_sender)
+            .await?;
+
+        // Send completion
+        progress_sender
+            .send(InstallProgress {
+                stage: InstallStage::Extracting,
+                progress: DownloadProgress {
+                    total_bytes: total_size,
+                    downloaded_bytes: downloaded,
+                    percentage: 100.0,
+                    speed: 0,
+                    eta: None,
+                },
+                message: "Download complete, extracting...".to_string(),
+            })
+            .map_err(|_| SDKError::ManagerNotFound("Failed to send progress".to_string()))?;
+
+        Ok(())
+    }
+
+    /// Download to `<output_path>.part`, picking up where an earlier attempt
+    /// stopped, and rename it into place once complete. Servers that ignore
+    /// the range request get a fresh download.
+    pub async fn download_resumable(
+        &self,
+        url: &str,
+        output_path: &Path,
+        progress_sender: mpsc::UnboundedSender<InstallProgress>,
+    ) -> Result<(), SDKError> {
+        let partial_path = partial_path(output_path);
+        let existing = tokio::fs::metadata(&partial_path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        let mut request = self.client.get(url);
+        if existing > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SDKError::ManagerNotFound(format!("Failed to start download: {}", e)))?;
+
+        // The part file already holds everything the server has
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            tokio::fs::rename(&partial_path, output_path).await?;
+            return Ok(());
+        }
+        if !response.status().is_success() {
+            return Err(SDKError::ManagerNotFound(format!(
+                "Download failed with HTTP {}",
+                response.status()
+            )));
+        }
+
+        let resumed = existing > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+        let (mut file, offset) = if resumed {
+            let file = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&partial_path)
+                .await?;
+            (file, existing)
+        } else {
+            (File::create(&partial_path).await?, 0)
+        };
+        let total_size = response.content_length().unwrap_or(0) + offset;
+
+        if resumed {
+            let _ = progress_sender.send(InstallProgress {
+                stage: InstallStage::Downloading,
+                progress: DownloadProgress {
+                    total_bytes: total_size,
+                    downloaded_bytes: offset,
+                    percentage: percentage(offset, total_size),
+                    speed: 0,
+                    eta: None,
+                },
+                message: format!("Resuming download at {} bytes", offset),
+            });
+        }
+
+        let downloaded = self
+            .stream_to_file(response, &mut file, offset, total_size, &progress_sender)
+            .await?;
+        file.flush().await?;
+        drop(file);
+
+        tokio::fs::rename(&partial_path, output_path).await?;
+
+        let _ = progress_sender.send(InstallProgress {
+            stage: InstallStage::Downloading,
+            progress: DownloadProgress {
+                total_bytes: total_size,
+                downloaded_bytes: downloaded,
+                percentage: 100.0,
+                speed: 0,
+                eta: None,
+            },
+            message: "Download complete".to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Write a response body to `file`, reporting progress from `offset`.
+    /// Returns the total bytes on disk afterwards.
+    async fn stream_to_file(
+        &self,
+        response: reqwest::Response,
+        file: &mut File,
+        offset: u64,
+        total_size: u64,
+        progress_sender: &mpsc::UnboundedSender<InstallProgress>,
+    ) -> Result<u64, SDKError> {
         let mut stream = response.bytes_stream();
-        let mut downloaded: u64 = 0;
+        let mut downloaded: u64 = offset;
         let mut last_update = std::time::Instant::now();
-        let mut last_downloaded = 0u64;
+        let mut last_downloaded = offset;
 
         use futures_util::StreamExt;
 
@@ -131,11 +268,7 @@ impl BinaryDownloader {
             if now.duration_since(last_update).as_millis() > 100
                 || downloaded - last_downloaded > 1024 * 1024
             {
-                let percentage = if total_size > 0 {
-                    (downloaded as f64 / total_size as f64) * 100.0
-                } else {
-                    0.0
-                };
+                let percentage = percentage(downloaded, total_size);
 
                 let speed = if now.duration_since(last_update).as_secs() > 0 {
                     (downloaded - last_downloaded) / now.duration_since(last_update).as_secs()
@@ -172,22 +305,7 @@ impl BinaryDownloader {
             }
         }
 
-        // Send completion
-        progress_sender
-            .send(InstallProgress {
-                stage: InstallStage::Extracting,
-                progress: DownloadProgress {
-                    total_bytes: total_size,
-                    downloaded_bytes: downloaded,
-                    percentage: 100.0,
-                    speed: 0,
-                    eta: None,
-                },
-                message: "Download complete, extracting...".to_string(),
-            })
-            .map_err(|_| SDKError::ManagerNotFound("Failed to send progress".to_string()))?;
-
-        Ok(())
+        Ok(downloaded)
     }
 
     /// Extract filename from URL
@@ -197,29 +315,13 @@ impl BinaryDownloader {
 
     /// Get current platform and architecture
     fn get_platform_info(&self) -> (String, String) {
-        let os = std::env::consts::OS;
-        let arch = std::env::consts::ARCH;
-
-        let platform = match os {
-            "macos" => "darwin",
-            "windows" => "win32",
-            "linux" => "linux",
-            _ => "unknown",
-        };
-
-        let architecture = match arch {
-            "x86_64" => "x64",
-            "aarch64" => "arm64",
-            _ => "unknown",
-        };
-
-        (platform.to_string(), architecture.to_string())
+        super::current_platform()
     }
 
     /// Verify download checksum if available
     pub async fn verify_checksum(
         &self,
-        file_path: &PathBuf,
+        file_path: &Path,
         expected_checksum: &str,
     ) -> Result<bool, SDKError> {
         use sha2::{Digest, Sha256};
@@ -247,6 +349,20 @@ impl BinaryDownloader {
         let hash = hasher.finalize();
         let hex_hash = format!("{:x}", hash);
 
-        Ok(hex_hash == expected_checksum)
+        Ok(hex_hash.eq_ignore_ascii_case(expected_checksum.trim()))
+    }
+}
+
+fn partial_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    output_path.with_file_name(name)
+}
+
+fn percentage(downloaded: u64, total_size: u64) -> f64 {
+    if total_size > 0 {
+        (downloaded as f64 / total_size as f64) * 100.0
+    } else {
+        0.0
     }
 }
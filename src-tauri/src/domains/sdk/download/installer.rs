@@ -0,0 +1,363 @@
+/**
+ * SDK Installer
+ *
+ * Installs SDK versions straight from their official sources when no
+ * version manager is available: resolve the archive for this platform,
+ * download it (resuming an interrupted download), verify its SHA-256,
+ * extract it and lay it out under `~/.portal-desktop/sdks/<sdk>/<version>`.
+ * Offline, only archives already in the download cache are installed.
+ */
+use super::archive_handler::ArchiveHandler;
+use super::binary_downloader::BinaryDownloader;
+use super::mirrors::{download_settings, ArchiveCache, CachedArchive};
+use super::sources::{GoSource, JavaSource, NodejsSource, PythonSource, RustSource};
+use super::{current_platform, DownloadArtifact, DownloadProgress, InstallProgress, InstallStage};
+use crate::domains::sdk::SDKError;
+use crate::process_ext::NoWindowExt;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+/// SDK types with a direct download source
+pub const DIRECT_INSTALL_SDKS: [&str; 5] = ["nodejs", "python", "java", "rust", "go"];
+
+pub struct SdkInstaller {
+    root: PathBuf,
+}
+
+impl SdkInstaller {
+    pub fn new() -> Result<Self, SDKError> {
+        Ok(Self { root: sdks_root()? })
+    }
+
+    pub fn supports(sdk_type: &str) -> bool {
+        DIRECT_INSTALL_SDKS.contains(&sdk_type)
+    }
+
+    pub fn install_dir(&self, sdk_type: &str, version: &str) -> PathBuf {
+        self.root.join(sdk_type).join(version)
+    }
+
+    pub fn archive_cache(&self) -> ArchiveCache {
+        ArchiveCache::new(self.root.join(".downloads"))
+    }
+
+    /// Resolve the archive for a version on the current platform
+    pub async fn resolve(
+        &self,
+        sdk_type: &str,
+        version: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        if download_settings().offline {
+            let cached = self
+                .archive_cache()
+                .find(sdk_type, version)
+                .ok_or_else(|| {
+                    SDKError::VersionNotFound(format!(
+                        "{} {} has no cached archive to install offline",
+                        sdk_type, version
+                    ))
+                })?;
+            return Ok(DownloadArtifact {
+                version: cached.version,
+                url: String::new(),
+                file_name: cached.file_name,
+                sha256: cached.sha256,
+            });
+        }
+
+        let (os, arch) = current_platform();
+        match sdk_type {
+            "nodejs" => {
+                NodejsSource::new()
+                    .resolve_download(version, &os, &arch)
+                    .await
+            }
+            "python" => {
+                PythonSource::new()
+                    .resolve_download(version, &os, &arch)
+                    .await
+            }
+            "java" => {
+                JavaSource::new()
+                    .resolve_download(version, &os, &arch)
+                    .await
+            }
+            "rust" => {
+                RustSource::new()
+                    .resolve_download(version, &os, &arch)
+                    .await
+            }
+            "go" => GoSource::new().resolve_download(version, &os, &arch).await,
+            _ => Err(SDKError::ManagerNotFound(format!(
+                "No direct download source for {}",
+                sdk_type
+            ))),
+        }
+    }
+
+    /// Download, verify, extract and lay out a version. Returns the
+    /// install directory.
+    pub async fn install(
+        &self,
+        sdk_type: &str,
+        version: &str,
+        progress: mpsc::UnboundedSender<InstallProgress>,
+    ) -> Result<PathBuf, SDKError> {
+        if sdk_type == "rust" && cfg!(target_os = "windows") {
+            return Err(SDKError::ManagerNotFound(
+                "Direct Rust installs need a Unix shell; install rustup instead".to_string(),
+            ));
+        }
+
+        send(
+            &progress,
+            InstallStage::FetchingVersions,
+            0.0,
+            format!("Resolving {} {} download", sdk_type, version),
+        );
+        let artifact = self.resolve(sdk_type, version).await?;
+        let install_dir = self.install_dir(sdk_type, &artifact.version);
+        if install_dir.exists() {
+            send(
+                &progress,
+                InstallStage::Complete,
+                100.0,
+                format!("{} {} is already installed", sdk_type, artifact.version),
+            );
+            return Ok(install_dir);
+        }
+
+        // Nothing unverified is installed, so don't download it either
+        let expected = artifact.sha256.clone().ok_or_else(|| {
+            SDKError::ChecksumMismatch(format!(
+                "{} (no SHA-256 is published to verify it against)",
+                artifact.file_name
+            ))
+        })?;
+
+        let cache = self.archive_cache();
+        let downloader = BinaryDownloader::new(self.root.join(".downloads"));
+        let cached = cache.path(&artifact.file_name);
+        let archive = if cached.is_file() {
+            cached
+        } else {
+            downloader
+                .download_to_cache(&artifact.url, &artifact.file_name, progress.clone())
+                .await?
+        };
+
+        send(
+            &progress,
+            InstallStage::Verifying,
+            100.0,
+            format!("Verifying {}", artifact.file_name),
+        );
+        if !downloader.verify_checksum(&archive, &expected).await? {
+            // A corrupt archive would otherwise be reused on retry
+            let _ = tokio::fs::remove_file(&archive).await;
+            return Err(SDKError::ChecksumMismatch(artifact.file_name));
+        }
+
+        cache.record(CachedArchive {
+            sdk_type: sdk_type.to_string(),
+            version: artifact.version.clone(),
+            file_name: artifact.file_name.clone(),
+            sha256: artifact.sha256.clone(),
+        })?;
+
+        // Extract beside the final directory so a failed install leaves
+        // nothing half-written in its place
+        let staging = self
+            .root
+            .join(sdk_type)
+            .join(format!(".staging-{}", artifact.version));
+        if staging.exists() {
+            tokio::fs::remove_dir_all(&staging).await?;
+        }
+        let result = self
+            .unpack(sdk_type, &archive, &staging, &install_dir, &progress)
+            .await;
+        let _ = tokio::fs::remove_dir_all(&staging).await;
+        if let Err(e) = result {
+            let _ = tokio::fs::remove_dir_all(&install_dir).await;
+            return Err(e);
+        }
+        if !download_settings().keep_archives {
+            let _ = tokio::fs::remove_file(&archive).await;
+        }
+
+        send(
+            &progress,
+            InstallStage::Configuring,
+            100.0,
+            "Checking installed binaries".to_string(),
+        );
+        let bin = bin_dir(sdk_type, &install_dir);
+        if !bin.is_dir() {
+            let _ = tokio::fs::remove_dir_all(&install_dir).await;
+            return Err(SDKError::CommandFailed(format!(
+                "Install of {} {} has no {}",
+                sdk_type,
+                artifact.version,
+                bin.display()
+            )));
+        }
+
+        send(
+            &progress,
+            InstallStage::Complete,
+            100.0,
+            format!("{} {} installed", sdk_type, artifact.version),
+        );
+        Ok(install_dir)
+    }
+
+    async fn unpack(
+        &self,
+        sdk_type: &str,
+        archive: &PathBuf,
+        staging: &PathBuf,
+        install_dir: &Path,
+        progress: &mpsc::UnboundedSender<InstallProgress>,
+    ) -> Result<(), SDKError> {
+        ArchiveHandler
+            .extract_archive(archive, staging, progress.clone())
+            .await?;
+
+        send(
+            progress,
+            InstallStage::Installing,
+            100.0,
+            format!("Installing into {}", install_dir.display()),
+        );
+        if let Some(parent) = install_dir.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        if sdk_type != "rust" {
+            tokio::fs::rename(staging, install_dir).await?;
+            return Ok(());
+        }
+
+        // The standalone Rust tarball is an installer rather than a
+        // ready-made tree; it lays rustc, cargo and the std library out
+        // under a prefix
+        let output = tokio::process::Command::new("sh")
+            .no_window()
+            .arg("install.sh")
+            .arg(format!("--prefix={}", install_dir.display()))
+            .arg("--disable-ldconfig")
+            .current_dir(staging)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(SDKError::CommandFailed(format!(
+                "Rust installer failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Versions installed directly for an SDK, sorted
+    pub fn list_installed(&self, sdk_type: &str) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(self.root.join(sdk_type)) else {
+            return Vec::new();
+        };
+        let mut versions: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        versions.sort();
+        versions
+    }
+
+    pub async fn uninstall(&self, sdk_type: &str, version: &str) -> Result<(), SDKError> {
+        if version.is_empty() || version.contains(['/', '\\']) || version.starts_with('.') {
+            return Err(SDKError::InvalidVersion(version.to_string()));
+        }
+        let install_dir = self.install_dir(sdk_type, version);
+        if !install_dir.exists() {
+            return Err(SDKError::VersionNotFound(format!(
+                "{} {}",
+                sdk_type, version
+            )));
+        }
+        tokio::fs::remove_dir_all(&install_dir).await?;
+        Ok(())
+    }
+}
+
+/// Directory holding an installed SDK's executables
+pub fn bin_dir(sdk_type: &str, install_dir: &Path) -> PathBuf {
+    bin_dir_for(sdk_type, install_dir, std::env::consts::OS)
+}
+
+fn bin_dir_for(sdk_type: &str, install_dir: &Path, os: &str) -> PathBuf {
+    match (sdk_type, os) {
+        // macOS JDKs are bundles
+        ("java", "macos") => install_dir.join("Contents").join("Home").join("bin"),
+        // Windows Node and Python builds keep their executables at the root
+        ("nodejs" | "python", "windows") => install_dir.to_path_buf(),
+        _ => install_dir.join("bin"),
+    }
+}
+
+/// Where direct installs live, alongside the Ollama data
+pub fn sdks_root() -> Result<PathBuf, SDKError> {
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .map_err(|_| SDKError::ManagerNotFound("Could not determine home directory".to_string()))?;
+
+    Ok(if cfg!(target_os = "windows") {
+        PathBuf::from(home)
+            .join("AppData")
+            .join("Local")
+            .join("portal-desktop")
+            .join("sdks")
+    } else {
+        PathBuf::from(home).join(".portal-desktop").join("sdks")
+    })
+}
+
+fn send(
+    progress: &mpsc::UnboundedSender<InstallProgress>,
+    stage: InstallStage,
+    percentage: f64,
+    message: String,
+) {
+    let _ = progress.send(InstallProgress {
+        stage,
+        progress: DownloadProgress {
+            total_bytes: 0,
+            downloaded_bytes: 0,
+            percentage,
+            speed: 0,
+            eta: None,
+        },
+        message,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lays_out_bin_dirs_per_platform() {
+        let dir = Path::new("/sdks/java/21.0.4.7");
+        assert_eq!(
+            bin_dir_for("java", dir, "macos"),
+            dir.join("Contents").join("Home").join("bin")
+        );
+        assert_eq!(bin_dir_for("java", dir, "linux"), dir.join("bin"));
+        assert_eq!(
+            bin_dir_for("python", Path::new("/sdks/python/3.12.4"), "windows"),
+            Path::new("/sdks/python/3.12.4")
+        );
+        assert!(SdkInstaller::supports("go"));
+        assert!(!SdkInstaller::supports("php"));
+    }
+}
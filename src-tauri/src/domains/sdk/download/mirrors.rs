@@ -0,0 +1,193 @@
+use crate::domains::sdk::SDKError;
+/**
+ * Download Mirrors and Offline Cache
+ *
+ * Mirror base URLs per SDK source and offline mode, kept in the app
+ * settings. A mirror stands in for the official base URL of its source
+ * (`https://nodejs.org/dist` -> `https://npmmirror.com/mirrors/node`), so
+ * it has to keep the official layout below that point, as Artifactory and
+ * Nexus remote repositories do.
+ *
+ * Verified archives are recorded in `.downloads/archives.json`; offline,
+ * versions are listed and installed only from archives recorded there.
+ */
+use crate::domains::settings::services::settings_service::{SdkDownloadSettings, SettingsService};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Official base URL of each direct download source
+pub const OFFICIAL_BASES: [(&str, &str); 5] = [
+    ("nodejs", "https://nodejs.org/dist"),
+    (
+        "python",
+        "https://github.com/astral-sh/python-build-standalone/releases/download",
+    ),
+    ("java", "https://github.com/adoptium"),
+    ("rust", "https://static.rust-lang.org"),
+    ("go", "https://go.dev/dl"),
+];
+
+const ARCHIVE_INDEX: &str = "archives.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedArchive {
+    pub sdk_type: String,
+    pub version: String,
+    pub file_name: String,
+    pub sha256: Option<String>,
+}
+
+pub fn download_settings() -> SdkDownloadSettings {
+    SettingsService::new()
+        .load_settings()
+        .map(|settings| settings.app.sdk_downloads)
+        .unwrap_or_default()
+}
+
+pub fn is_offline() -> bool {
+    download_settings().offline
+}
+
+/// Base URL for an SDK's downloads: its mirror, or the official one
+pub fn download_base(sdk_type: &str) -> String {
+    let official = official_base(sdk_type).unwrap_or_default();
+    download_settings()
+        .mirrors
+        .get(sdk_type)
+        .map(|mirror| mirror.trim_end_matches('/').to_string())
+        .filter(|mirror| !mirror.is_empty())
+        .unwrap_or_else(|| official.to_string())
+}
+
+/// Move a URL an API handed out onto the SDK's mirror, if it has one
+pub fn mirrored_url(sdk_type: &str, url: &str) -> String {
+    match official_base(sdk_type) {
+        Some(official) => rebase(url, official, &download_base(sdk_type)),
+        None => url.to_string(),
+    }
+}
+
+fn official_base(sdk_type: &str) -> Option<&'static str> {
+    OFFICIAL_BASES
+        .iter()
+        .find(|(sdk, _)| *sdk == sdk_type)
+        .map(|(_, base)| *base)
+}
+
+/// `url` with `official` replaced by `mirror`; URLs elsewhere are kept
+fn rebase(url: &str, official: &str, mirror: &str) -> String {
+    match url.strip_prefix(official) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '?']) => {
+            format!("{}{}", mirror.trim_end_matches('/'), rest)
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// The verified archives in a download cache
+pub struct ArchiveCache {
+    dir: PathBuf,
+}
+
+impl ArchiveCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn path(&self, file_name: &str) -> PathBuf {
+        self.dir.join(file_name)
+    }
+
+    /// Recorded archives that are still on disk
+    pub fn list(&self, sdk_type: Option<&str>) -> Vec<CachedArchive> {
+        self.read_index()
+            .into_iter()
+            .filter(|archive| sdk_type.is_none_or(|sdk| archive.sdk_type == sdk))
+            .filter(|archive| self.path(&archive.file_name).is_file())
+            .collect()
+    }
+
+    /// The newest cached archive for `version`, which may be a prefix of
+    /// the cached version ("20" for 20.11.1)
+    pub fn find(&self, sdk_type: &str, version: &str) -> Option<CachedArchive> {
+        let version = version.trim_start_matches('v');
+        self.list(Some(sdk_type))
+            .into_iter()
+            .filter(|archive| {
+                archive.version == version
+                    || archive.version.starts_with(&format!("{}.", version))
+                    || archive.version.starts_with(&format!("{}+", version))
+            })
+            .max_by(|a, b| {
+                version_compare::compare(&a.version, &b.version)
+                    .ok()
+                    .and_then(|ordering| ordering.ord())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+
+    pub fn record(&self, archive: CachedArchive) -> Result<(), SDKError> {
+        let mut index = self.read_index();
+        index.retain(|entry| {
+            !(entry.sdk_type == archive.sdk_type && entry.version == archive.version)
+        });
+        index.push(archive);
+
+        std::fs::create_dir_all(&self.dir)?;
+        let content = serde_json::to_string_pretty(&index).map_err(|e| {
+            SDKError::CommandFailed(format!("Failed to serialize archive index: {}", e))
+        })?;
+        std::fs::write(self.dir.join(ARCHIVE_INDEX), content)?;
+        Ok(())
+    }
+
+    fn read_index(&self) -> Vec<CachedArchive> {
+        read_index(&self.dir.join(ARCHIVE_INDEX))
+    }
+}
+
+fn read_index(path: &Path) -> Vec<CachedArchive> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebases_official_urls_onto_mirrors() {
+        let mirror = "https://npmmirror.com/mirrors/node/";
+        assert_eq!(
+            rebase(
+                "https://nodejs.org/dist/v20.11.1/SHASUMS256.txt",
+                "https://nodejs.org/dist",
+                mirror
+            ),
+            "https://npmmirror.com/mirrors/node/v20.11.1/SHASUMS256.txt"
+        );
+        assert_eq!(
+            rebase(
+                "https://go.dev/dl/?mode=json",
+                "https://go.dev/dl",
+                "https://golang.google.cn/dl"
+            ),
+            "https://golang.google.cn/dl/?mode=json"
+        );
+        // Only whole path segments of the official base are replaced
+        assert_eq!(
+            rebase(
+                "https://nodejs.org/distfoo",
+                "https://nodejs.org/dist",
+                mirror
+            ),
+            "https://nodejs.org/distfoo"
+        );
+        assert_eq!(
+            rebase("https://example.com/x", "https://nodejs.org/dist", mirror),
+            "https://example.com/x"
+        );
+    }
+}
@@ -1,5 +1,7 @@
 pub mod archive_handler;
 pub mod binary_downloader;
+pub mod installer;
+pub mod mirrors;
 pub mod sources;
 /**
  * Download Infrastructure Module
@@ -42,6 +44,7 @@ pub struct InstallProgress {
 pub enum InstallStage {
     FetchingVersions,
     Downloading,
+    Verifying,
     Extracting,
     Installing,
     Configuring,
@@ -49,6 +52,38 @@ pub enum InstallStage {
     Error(String),
 }
 
+/// A resolved download for one SDK version on one platform
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadArtifact {
+    /// The version this artifact installs, which may be more precise than
+    /// the one requested (e.g. a Java major version)
+    pub version: String,
+    pub url: String,
+    pub file_name: String,
+    /// Hex-encoded SHA-256 of the archive; archives without one are not
+    /// installed
+    pub sha256: Option<String>,
+}
+
+/// Current platform and architecture in Node.js naming (`linux`/`darwin`/
+/// `win32`, `x64`/`arm64`), which the sources map to their own
+pub fn current_platform() -> (String, String) {
+    let platform = match std::env::consts::OS {
+        "macos" => "darwin",
+        "windows" => "win32",
+        "linux" => "linux",
+        _ => "unknown",
+    };
+
+    let architecture = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        _ => "unknown",
+    };
+
+    (platform.to_string(), architecture.to_string())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceConfig {
     pub port: Option<u16>,
@@ -2,7 +2,8 @@
  * Go Source Implementation
  * Fetches versions from official Go API
  */
-use super::super::VersionInfo;
+use super::super::mirrors::download_base;
+use super::super::{DownloadArtifact, VersionInfo};
 use crate::domains::sdk::SDKError;
 use reqwest::Client;
 use serde::Deserialize;
@@ -11,22 +12,19 @@ use std::collections::HashMap;
 #[derive(Deserialize)]
 struct GoRelease {
     version: String,
-    #[allow(dead_code)]
     stable: bool,
-    #[allow(dead_code)]
     files: Vec<GoFile>,
 }
 
 #[derive(Deserialize)]
 struct GoFile {
-    #[allow(dead_code)]
     filename: String,
-    #[allow(dead_code)]
     os: String,
-    #[allow(dead_code)]
     arch: String,
-    #[allow(dead_code)]
     sha256: String,
+    /// `archive`, `installer` or `source`
+    #[serde(default)]
+    kind: String,
 }
 
 pub struct GoSource {
@@ -44,7 +42,7 @@ impl GoSource {
     pub async fn fetch_versions(&self) -> Result<Vec<VersionInfo>, SDKError> {
         let response = self
             .client
-            .get("https://go.dev/dl/?mode=json")
+            .get(format!("{}/?mode=json", download_base("go")))
             .send()
             .await
             .map_err(|e| {
@@ -67,7 +65,7 @@ impl GoSource {
             let mut download_urls = HashMap::new();
             for file in &release.files {
                 let platform_arch = format!("{}-{}", file.os, file.arch);
-                let url = format!("https://go.dev/dl/{}", file.filename);
+                let url = format!("{}/{}", download_base("go"), file.filename);
                 download_urls.insert(platform_arch, url);
             }
 
@@ -93,4 +91,57 @@ impl GoSource {
 
         Ok(versions)
     }
+
+    /// Resolve the archive for a version from the full release list, which
+    /// (unlike the default listing) includes older releases
+    pub async fn resolve_download(
+        &self,
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        let go_os = match os {
+            "win32" => "windows",
+            other => other,
+        };
+        let go_arch = match arch {
+            "x64" => "amd64",
+            other => other,
+        };
+        let version = version.trim_start_matches("go");
+
+        let response = self
+            .client
+            .get(format!("{}/?mode=json&include=all", download_base("go")))
+            .send()
+            .await
+            .map_err(|e| {
+                SDKError::ManagerNotFound(format!("Failed to fetch Go releases: {}", e))
+            })?;
+
+        let releases: Vec<GoRelease> = response.json().await.map_err(|e| {
+            SDKError::ManagerNotFound(format!("Failed to parse Go releases: {}", e))
+        })?;
+
+        let tag = format!("go{}", version);
+        let release = releases
+            .into_iter()
+            .find(|release| release.version == tag)
+            .ok_or_else(|| SDKError::VersionNotFound(format!("Go {}", version)))?;
+
+        let file = release
+            .files
+            .into_iter()
+            .find(|file| file.kind == "archive" && file.os == go_os && file.arch == go_arch)
+            .ok_or_else(|| {
+                SDKError::VersionNotFound(format!("No Go {} build for {}-{}", version, os, arch))
+            })?;
+
+        Ok(DownloadArtifact {
+            version: version.to_string(),
+            url: format!("{}/{}", download_base("go"), file.filename),
+            file_name: file.filename,
+            sha256: Some(file.sha256),
+        })
+    }
 }
@@ -2,7 +2,8 @@
  * Java Source Implementation
  * Fetches versions from Adoptium API
  */
-use super::super::VersionInfo;
+use super::super::mirrors::mirrored_url;
+use super::super::{DownloadArtifact, VersionInfo};
 use crate::domains::sdk::SDKError;
 use reqwest::Client;
 use serde::Deserialize;
@@ -35,12 +36,18 @@ struct AdoptiumBinary {
 
 #[derive(Deserialize)]
 struct AdoptiumPackage {
-    #[allow(dead_code)]
     name: String,
     link: String,
     checksum: String,
 }
 
+/// One binary from the `assets/latest` endpoint
+#[derive(Deserialize)]
+struct AdoptiumLatestAsset {
+    binary: AdoptiumBinary,
+    version: AdoptiumVersion,
+}
+
 pub struct JavaSource {
     client: Client,
 }
@@ -70,13 +77,7 @@ impl JavaSource {
         let mut versions = Vec::new();
 
         for release in releases {
-            let version = format!(
-                "{}.{}.{}.{}",
-                release.version.major,
-                release.version.minor,
-                release.version.security,
-                release.version.patch
-            );
+            let version = release.version.to_string();
 
             let mut download_urls = HashMap::new();
             for binary in &release.binaries {
@@ -106,4 +107,76 @@ impl JavaSource {
 
         Ok(versions)
     }
+
+    /// Resolve the latest Temurin JDK for the version's major release.
+    /// Adoptium only serves the newest build of each major here, so the
+    /// artifact's version may be newer than the one requested.
+    pub async fn resolve_download(
+        &self,
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        let major: u32 = version
+            .split('.')
+            .next()
+            .and_then(|major| major.parse().ok())
+            .ok_or_else(|| SDKError::InvalidVersion(version.to_string()))?;
+        let adoptium_os = match os {
+            "darwin" => "mac",
+            "win32" => "windows",
+            other => other,
+        };
+        let adoptium_arch = match arch {
+            "arm64" => "aarch64",
+            other => other,
+        };
+
+        let response = self
+            .client
+            .get(format!(
+                "https://api.adoptium.net/v3/assets/latest/{}/hotspot",
+                major
+            ))
+            .query(&[
+                ("os", adoptium_os),
+                ("architecture", adoptium_arch),
+                ("image_type", "jdk"),
+            ])
+            .send()
+            .await
+            .map_err(|e| {
+                SDKError::ManagerNotFound(format!("Failed to fetch Java builds: {}", e))
+            })?;
+
+        let assets: Vec<AdoptiumLatestAsset> = response.json().await.map_err(|e| {
+            SDKError::ManagerNotFound(format!("Failed to parse Java builds: {}", e))
+        })?;
+
+        let asset = assets
+            .into_iter()
+            .find(|asset| {
+                asset.binary.os == adoptium_os && asset.binary.architecture == adoptium_arch
+            })
+            .ok_or_else(|| {
+                SDKError::VersionNotFound(format!("No Java {} build for {}-{}", major, os, arch))
+            })?;
+
+        Ok(DownloadArtifact {
+            version: asset.version.to_string(),
+            url: mirrored_url("java", &asset.binary.package.link),
+            file_name: asset.binary.package.name,
+            sha256: Some(asset.binary.package.checksum),
+        })
+    }
+}
+
+impl std::fmt::Display for AdoptiumVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.security, self.patch
+        )
+    }
 }
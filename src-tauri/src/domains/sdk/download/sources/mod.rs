@@ -16,17 +16,20 @@ pub use python_source::PythonSource;
 pub use rust_source::RustSource;
 
 use super::super::SDKError;
-use super::VersionInfo;
+use super::{DownloadArtifact, VersionInfo};
+use reqwest::Client;
 
 /// Trait for version sources
 pub trait VersionSource {
     async fn fetch_versions(&self) -> Result<Vec<VersionInfo>, SDKError>;
-    async fn get_download_url(
+    /// Resolve the archive (and its checksum) for a version on a platform,
+    /// with `os`/`arch` as returned by `current_platform()`
+    async fn resolve_download(
         &self,
         version: &str,
         os: &str,
         arch: &str,
-    ) -> Result<String, SDKError>;
+    ) -> Result<DownloadArtifact, SDKError>;
 }
 
 impl VersionSource for NodejsSource {
@@ -34,19 +37,13 @@ impl VersionSource for NodejsSource {
         self.fetch_versions().await
     }
 
-    async fn get_download_url(
+    async fn resolve_download(
         &self,
         version: &str,
         os: &str,
         arch: &str,
-    ) -> Result<String, SDKError> {
-        let urls = self.get_download_urls(version);
-        let platform_arch = format!("{}-{}", os, arch);
-        urls.get(&platform_arch)
-            .ok_or_else(|| {
-                SDKError::ManagerNotFound(format!("No download URL for platform {}", platform_arch))
-            })
-            .map(|url| url.clone())
+    ) -> Result<DownloadArtifact, SDKError> {
+        self.resolve_download(version, os, arch).await
     }
 }
 
@@ -55,15 +52,13 @@ impl VersionSource for PythonSource {
         self.fetch_versions().await
     }
 
-    async fn get_download_url(
+    async fn resolve_download(
         &self,
-        _version: &str,
-        _os: &str,
-        _arch: &str,
-    ) -> Result<String, SDKError> {
-        Err(SDKError::ManagerNotFound(
-            "Python source doesn't support direct URL generation".to_string(),
-        ))
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        self.resolve_download(version, os, arch).await
     }
 }
 
@@ -72,15 +67,13 @@ impl VersionSource for JavaSource {
         self.fetch_versions().await
     }
 
-    async fn get_download_url(
+    async fn resolve_download(
         &self,
-        _version: &str,
-        _os: &str,
-        _arch: &str,
-    ) -> Result<String, SDKError> {
-        Err(SDKError::ManagerNotFound(
-            "Java source doesn't support direct URL generation".to_string(),
-        ))
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        self.resolve_download(version, os, arch).await
     }
 }
 
@@ -89,15 +82,13 @@ impl VersionSource for RustSource {
         self.fetch_versions().await
     }
 
-    async fn get_download_url(
+    async fn resolve_download(
         &self,
-        _version: &str,
-        _os: &str,
-        _arch: &str,
-    ) -> Result<String, SDKError> {
-        Err(SDKError::ManagerNotFound(
-            "Rust source doesn't support direct URL generation".to_string(),
-        ))
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        self.resolve_download(version, os, arch).await
     }
 }
 
@@ -106,14 +97,91 @@ impl VersionSource for GoSource {
         self.fetch_versions().await
     }
 
-    async fn get_download_url(
+    async fn resolve_download(
         &self,
-        _version: &str,
-        _os: &str,
-        _arch: &str,
-    ) -> Result<String, SDKError> {
-        Err(SDKError::ManagerNotFound(
-            "Go source doesn't support direct URL generation".to_string(),
-        ))
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        self.resolve_download(version, os, arch).await
+    }
+}
+
+/// Rust-style target triple for a platform, used by Rust and the
+/// standalone Python builds
+fn target_triple(os: &str, arch: &str) -> Option<&'static str> {
+    match (os, arch) {
+        ("darwin", "x64") => Some("x86_64-apple-darwin"),
+        ("darwin", "arm64") => Some("aarch64-apple-darwin"),
+        ("linux", "x64") => Some("x86_64-unknown-linux-gnu"),
+        ("linux", "arm64") => Some("aarch64-unknown-linux-gnu"),
+        ("win32", "x64") => Some("x86_64-pc-windows-msvc"),
+        ("win32", "arm64") => Some("aarch64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+/// Fetch a small text file such as a checksum list
+async fn fetch_text(client: &Client, url: &str) -> Result<String, SDKError> {
+    let response = client
+        .get(url)
+        .header("User-Agent", "Portal-Desktop")
+        .send()
+        .await
+        .map_err(|e| SDKError::ManagerNotFound(format!("Failed to fetch {}: {}", url, e)))?;
+
+    if !response.status().is_success() {
+        return Err(SDKError::VersionNotFound(format!(
+            "{} returned HTTP {}",
+            url,
+            response.status()
+        )));
+    }
+
+    response
+        .text()
+        .await
+        .map_err(|e| SDKError::ManagerNotFound(format!("Failed to read {}: {}", url, e)))
+}
+
+/// The hash for `file_name` in a `sha256sum`-style list (`<hash>  <name>`).
+/// A bare hash, as in single-file `.sha256` files, matches any name.
+fn checksum_from_list(list: &str, file_name: &str) -> Option<String> {
+    list.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let matches = match parts.next() {
+            Some(name) => {
+                let name = name.trim_start_matches('*');
+                name == file_name || name.ends_with(&format!("/{}", file_name))
+            }
+            None => true,
+        };
+        (matches && hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| hash.to_lowercase())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_checksums_in_lists() {
+        let sums = "\
+1111111111111111111111111111111111111111111111111111111111111111  node-v20.11.0-darwin-arm64.tar.gz
+ABCDEFabcdef0123456789abcdef0123456789abcdef0123456789abcdef0123  node-v20.11.0-linux-x64.tar.xz
+";
+        assert_eq!(
+            checksum_from_list(sums, "node-v20.11.0-linux-x64.tar.xz").as_deref(),
+            Some("abcdefabcdef0123456789abcdef0123456789abcdef0123456789abcdef0123")
+        );
+        assert_eq!(checksum_from_list(sums, "node-v20.11.0-win-x64.zip"), None);
+
+        let single = format!("{}\n", "2".repeat(64));
+        assert_eq!(
+            checksum_from_list(&single, "rust-1.80.0-x86_64-unknown-linux-gnu.tar.gz"),
+            Some("2".repeat(64))
+        );
     }
 }
@@ -1,8 +1,9 @@
 /**
  * Node.js Source Implementation
- * Fetches versions from official Node.js distribution site
+ * Fetches versions from official Node.js distribution site, or its mirror
  */
-use super::super::VersionInfo;
+use super::super::mirrors::download_base;
+use super::super::{DownloadArtifact, VersionInfo};
 use crate::domains::sdk::SDKError;
 use regex::Regex;
 use reqwest::Client;
@@ -23,7 +24,7 @@ impl NodejsSource {
     pub async fn fetch_versions(&self) -> Result<Vec<VersionInfo>, SDKError> {
         let response = self
             .client
-            .get("https://nodejs.org/dist/")
+            .get(format!("{}/", download_base("nodejs")))
             .send()
             .await
             .map_err(|e| {
@@ -80,19 +81,13 @@ impl NodejsSource {
     /// Get download URLs for Node.js version
     pub fn get_download_urls(&self, version: &str) -> HashMap<String, String> {
         let mut urls = HashMap::new();
-        let base_url = format!("https://nodejs.org/dist/v{}/", version);
+        let base_url = format!("{}/v{}/", download_base("nodejs"), version);
 
         // Determine platform and architecture
         let (platform, arch) = self.get_platform_info();
 
-        let filename = match (platform.as_str(), arch.as_str()) {
-            ("darwin", "x64") => format!("node-v{}-darwin-x64.tar.gz", version),
-            ("darwin", "arm64") => format!("node-v{}-darwin-arm64.tar.gz", version),
-            ("linux", "x64") => format!("node-v{}-linux-x64.tar.xz", version),
-            ("linux", "arm64") => format!("node-v{}-linux-arm64.tar.xz", version),
-            ("win32", "x64") => format!("node-v{}-win-x64.zip", version),
-            ("win32", "arm64") => format!("node-v{}-win-arm64.zip", version),
-            _ => return urls,
+        let Some(filename) = archive_name(version, &platform, &arch) else {
+            return urls;
         };
 
         urls.insert(
@@ -102,6 +97,33 @@ impl NodejsSource {
         urls
     }
 
+    /// Resolve the archive for a version, checked against the release's
+    /// SHASUMS256.txt
+    pub async fn resolve_download(
+        &self,
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        let version = version.trim_start_matches('v');
+        let file_name = archive_name(version, os, arch).ok_or_else(|| {
+            SDKError::VersionNotFound(format!("No Node.js build for {}-{}", os, arch))
+        })?;
+        let base_url = format!("{}/v{}/", download_base("nodejs"), version);
+
+        let sums = super::fetch_text(&self.client, &format!("{}SHASUMS256.txt", base_url)).await?;
+        let sha256 = super::checksum_from_list(&sums, &file_name).ok_or_else(|| {
+            SDKError::VersionNotFound(format!("Node.js {} has no {}", version, file_name))
+        })?;
+
+        Ok(DownloadArtifact {
+            version: version.to_string(),
+            url: format!("{}{}", base_url, file_name),
+            file_name,
+            sha256: Some(sha256),
+        })
+    }
+
     /// Get current platform and architecture
     fn get_platform_info(&self) -> (String, String) {
         let os = std::env::consts::OS;
@@ -123,3 +145,17 @@ impl NodejsSource {
         (platform.to_string(), architecture.to_string())
     }
 }
+
+/// Archive name for a version on a platform, as published under nodejs.org/dist
+fn archive_name(version: &str, platform: &str, arch: &str) -> Option<String> {
+    let suffix = match (platform, arch) {
+        ("darwin", "x64") => "darwin-x64.tar.gz",
+        ("darwin", "arm64") => "darwin-arm64.tar.gz",
+        ("linux", "x64") => "linux-x64.tar.xz",
+        ("linux", "arm64") => "linux-arm64.tar.xz",
+        ("win32", "x64") => "win-x64.zip",
+        ("win32", "arm64") => "win-arm64.zip",
+        _ => return None,
+    };
+    Some(format!("node-v{}-{}", version, suffix))
+}
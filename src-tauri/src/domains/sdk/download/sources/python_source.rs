@@ -1,8 +1,10 @@
 /**
  * Python Source Implementation
- * Fetches versions from GitHub releases API
+ * Fetches versions from GitHub releases API; installable builds come from
+ * python-build-standalone, since CPython only publishes source releases
  */
-use super::super::VersionInfo;
+use super::super::mirrors::mirrored_url;
+use super::super::{DownloadArtifact, VersionInfo};
 use crate::domains::sdk::SDKError;
 use reqwest::Client;
 use serde::Deserialize;
@@ -11,20 +13,23 @@ use std::collections::HashMap;
 #[derive(Deserialize)]
 struct GitHubRelease {
     tag_name: String,
-    #[allow(dead_code)]
+    #[serde(default)]
     published_at: String,
-    #[allow(dead_code)]
     assets: Vec<GitHubAsset>,
 }
 
 #[derive(Deserialize)]
 struct GitHubAsset {
-    #[allow(dead_code)]
     name: String,
-    #[allow(dead_code)]
     browser_download_url: String,
 }
 
+/// Relocatable CPython builds, published in dated releases
+const STANDALONE_RELEASES_URL: &str =
+    "https://api.github.com/repos/astral-sh/python-build-standalone/releases";
+/// Release pages searched for a version before giving up
+const STANDALONE_MAX_PAGES: u32 = 5;
+
 pub struct PythonSource {
     client: Client,
 }
@@ -100,6 +105,76 @@ impl PythonSource {
         Ok(versions)
     }
 
+    /// Resolve the python-build-standalone `install_only` archive for a
+    /// version, searching the most recent releases that ship it
+    pub async fn resolve_download(
+        &self,
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        let triple = super::target_triple(os, arch).ok_or_else(|| {
+            SDKError::VersionNotFound(format!("No Python build for {}-{}", os, arch))
+        })?;
+
+        for page in 1..=STANDALONE_MAX_PAGES {
+            let response = self
+                .client
+                .get(STANDALONE_RELEASES_URL)
+                .query(&[("per_page", "20"), ("page", &page.to_string())])
+                .header("User-Agent", "Portal-Desktop")
+                .send()
+                .await
+                .map_err(|e| {
+                    SDKError::ManagerNotFound(format!("Failed to fetch Python builds: {}", e))
+                })?;
+
+            let releases: Vec<GitHubRelease> = response.json().await.map_err(|e| {
+                SDKError::ManagerNotFound(format!("Failed to parse Python builds: {}", e))
+            })?;
+            if releases.is_empty() {
+                break;
+            }
+
+            for release in &releases {
+                let Some(asset) = release
+                    .assets
+                    .iter()
+                    .find(|asset| is_install_only_build(&asset.name, version, triple))
+                else {
+                    continue;
+                };
+
+                // Newer releases publish one SHA256SUMS; older ones a
+                // .sha256 file per asset
+                let sums_url = release
+                    .assets
+                    .iter()
+                    .find(|a| a.name == "SHA256SUMS" || a.name == format!("{}.sha256", asset.name))
+                    .map(|a| mirrored_url("python", &a.browser_download_url));
+                let sha256 = match sums_url {
+                    Some(url) => super::checksum_from_list(
+                        &super::fetch_text(&self.client, &url).await?,
+                        &asset.name,
+                    ),
+                    None => None,
+                };
+
+                return Ok(DownloadArtifact {
+                    version: version.to_string(),
+                    url: mirrored_url("python", &asset.browser_download_url),
+                    file_name: asset.name.clone(),
+                    sha256,
+                });
+            }
+        }
+
+        Err(SDKError::VersionNotFound(format!(
+            "No standalone Python {} build for {}",
+            version, triple
+        )))
+    }
+
     /// Extract platform info from Python asset name
     fn extract_python_platform(&self, filename: &str) -> Option<String> {
         if filename.contains("macos") && filename.contains("x86_64") {
@@ -119,3 +194,10 @@ impl PythonSource {
         }
     }
 }
+
+/// Whether an asset is the full (not stripped/debug) relocatable build,
+/// e.g. `cpython-3.12.4+20240713-x86_64-unknown-linux-gnu-install_only.tar.gz`
+fn is_install_only_build(name: &str, version: &str, triple: &str) -> bool {
+    name.starts_with(&format!("cpython-{}+", version))
+        && name.ends_with(&format!("-{}-install_only.tar.gz", triple))
+}
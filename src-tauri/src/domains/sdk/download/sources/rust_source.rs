@@ -2,7 +2,8 @@
  * Rust Source Implementation
  * Fetches versions from official channel manifests
  */
-use super::super::VersionInfo;
+use super::super::mirrors::download_base;
+use super::super::{DownloadArtifact, VersionInfo};
 use crate::domains::sdk::SDKError;
 use reqwest::Client;
 use std::collections::HashMap;
@@ -22,7 +23,10 @@ impl RustSource {
     pub async fn fetch_versions(&self) -> Result<Vec<VersionInfo>, SDKError> {
         let response = self
             .client
-            .get("https://static.rust-lang.org/dist/channel-rust-stable.toml")
+            .get(format!(
+                "{}/dist/channel-rust-stable.toml",
+                download_base("rust")
+            ))
             .send()
             .await
             .map_err(|e| {
@@ -41,19 +45,15 @@ impl RustSource {
         let platform_arch = format!("{}-{}", platform, arch);
 
         // Rust uses different naming conventions
-        let rust_platform = match (platform.as_str(), arch.as_str()) {
-            ("darwin", "x64") => "x86_64-apple-darwin",
-            ("darwin", "arm64") => "aarch64-apple-darwin",
-            ("linux", "x64") => "x86_64-unknown-linux-gnu",
-            ("linux", "arm64") => "aarch64-unknown-linux-gnu",
-            ("win32", "x64") => "x86_64-pc-windows-msvc",
-            ("win32", "arm64") => "aarch64-pc-windows-msvc",
-            _ => return Ok(vec![]),
+        let Some(rust_platform) = super::target_triple(&platform, &arch) else {
+            return Ok(vec![]);
         };
 
         let base_url = format!(
-            "https://static.rust-lang.org/dist/rust-{}-{}.tar.gz",
-            version, rust_platform
+            "{}/dist/rust-{}-{}.tar.gz",
+            download_base("rust"),
+            version,
+            rust_platform
         );
         download_urls.insert(platform_arch, base_url);
 
@@ -67,6 +67,33 @@ impl RustSource {
         }])
     }
 
+    /// Resolve the standalone installer tarball for a version, checked
+    /// against the `.sha256` file published next to it
+    pub async fn resolve_download(
+        &self,
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<DownloadArtifact, SDKError> {
+        let triple = super::target_triple(os, arch).ok_or_else(|| {
+            SDKError::VersionNotFound(format!("No Rust build for {}-{}", os, arch))
+        })?;
+        let file_name = format!("rust-{}-{}.tar.gz", version, triple);
+        let url = format!("{}/dist/{}", download_base("rust"), file_name);
+
+        let sums = super::fetch_text(&self.client, &format!("{}.sha256", url)).await?;
+        let sha256 = super::checksum_from_list(&sums, &file_name).ok_or_else(|| {
+            SDKError::VersionNotFound(format!("No checksum published for {}", file_name))
+        })?;
+
+        Ok(DownloadArtifact {
+            version: version.to_string(),
+            url,
+            file_name,
+            sha256: Some(sha256),
+        })
+    }
+
     /// Extract version from Rust TOML manifest
     fn extract_rust_version(&self, manifest: &str) -> Result<String, SDKError> {
         for line in manifest.lines() {
@@ -1,6 +1,6 @@
 use crate::domains::sdk::package_managers::{
-    CargoManager, ChocolateyManager, HomebrewManager, NpmManager, PipManager, ScoopManager,
-    WingetManager,
+    CargoManager, ChocolateyManager, ComposerManager, HomebrewManager, NpmManager, PipManager,
+    ScoopManager, WingetManager,
 };
 use crate::domains::sdk::traits::package_manager::PackageManager;
 /**
@@ -29,6 +29,7 @@ impl PackageManagerFactory {
         factory.register_manager("homebrew", Box::new(HomebrewManager::new()));
         factory.register_manager("npm", Box::new(NpmManager::new()));
         factory.register_manager("pip", Box::new(PipManager::new()));
+        factory.register_manager("composer", Box::new(ComposerManager::new()));
 
         factory
     }
@@ -1,5 +1,6 @@
 use crate::domains::sdk::managers::{
-    NvmManager, PhpenvManager, PyenvManager, RbenvManager, RustupManager, SdkmanManager,
+    NvmManager, NvmWindowsManager, PhpenvManager, PyenvManager, RbenvManager, RustupManager,
+    ScoopSdkManager, SdkmanManager,
 };
 use crate::domains::sdk::traits::sdk_manager::SDKManager;
 use crate::domains::sdk::SDKError;
@@ -24,7 +25,15 @@ impl SDKManagerFactory {
         };
 
         // Register all available managers
-        factory.register_manager("nvm", Box::new(NvmManager::new()));
+        if cfg!(target_os = "windows") {
+            factory.register_manager("nvm", Box::new(NvmWindowsManager::new()));
+            factory.register_manager("scoop-nodejs", Box::new(ScoopSdkManager::nodejs()));
+            factory.register_manager("scoop-python", Box::new(ScoopSdkManager::python()));
+            factory.register_manager("scoop-java", Box::new(ScoopSdkManager::java()));
+            factory.register_manager("scoop-go", Box::new(ScoopSdkManager::go()));
+        } else {
+            factory.register_manager("nvm", Box::new(NvmManager::new()));
+        }
         factory.register_manager("rustup", Box::new(RustupManager::new()));
         factory.register_manager("pyenv", Box::new(PyenvManager::new()));
         factory.register_manager("sdk", Box::new(SdkmanManager::new()));
@@ -9,6 +9,8 @@ pub struct SDKInfo {
     pub version: String,
     pub path: String,
     pub installed: bool,
+    /// Version manager or package manager the binary came from, if known
+    pub manager: Option<String>,
 }
 
 pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
@@ -27,27 +29,31 @@ pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
             managers.push(SDKInfo {
                 name: "Node.js".to_string(),
                 version: version.trim_start_matches('v').to_string(),
+                manager: owning_manager(&path),
                 path,
                 installed: true,
             });
         }
     }
 
-    // Check for Python
-    if let Ok(output) = Command::new("python3")
-        .no_window()
-        .arg("--version")
-        .output()
-    {
+    // Check for Python; Windows installs only have `python`, and `python3`
+    // there is usually the Microsoft Store stub
+    let python = if cfg!(target_os = "windows") {
+        "python"
+    } else {
+        "python3"
+    };
+    if let Ok(output) = Command::new(python).no_window().arg("--version").output() {
         if output.status.success() {
             let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            let path = which("python3")
+            let path = which(python)
                 .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|_| "python3".to_string());
+                .unwrap_or_else(|_| python.to_string());
 
             managers.push(SDKInfo {
                 name: "Python".to_string(),
                 version: version.trim_start_matches("Python ").to_string(),
+                manager: owning_manager(&path),
                 path,
                 installed: true,
             });
@@ -69,6 +75,7 @@ pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
                     .nth(1)
                     .unwrap_or("unknown")
                     .to_string(),
+                manager: owning_manager(&path),
                 path,
                 installed: true,
             });
@@ -88,6 +95,7 @@ pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
                     managers.push(SDKInfo {
                         name: "Java".to_string(),
                         version: version.to_string(),
+                        manager: owning_manager(&path),
                         path,
                         installed: true,
                     });
@@ -111,6 +119,7 @@ pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
                     .nth(2)
                     .unwrap_or("unknown")
                     .to_string(),
+                manager: owning_manager(&path),
                 path,
                 installed: true,
             });
@@ -132,6 +141,7 @@ pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
                     .nth(1)
                     .unwrap_or("unknown")
                     .to_string(),
+                manager: owning_manager(&path),
                 path,
                 installed: true,
             });
@@ -153,6 +163,7 @@ pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
                     .nth(1)
                     .unwrap_or("unknown")
                     .to_string(),
+                manager: owning_manager(&path),
                 path,
                 installed: true,
             });
@@ -161,3 +172,72 @@ pub async fn detect_sdk_managers() -> Result<Vec<SDKInfo>, String> {
 
     Ok(managers)
 }
+
+/// Which manager installed the binary at `path`
+fn owning_manager(path: &str) -> Option<String> {
+    manager_for_path(path, std::env::var("NVM_SYMLINK").ok().as_deref()).map(str::to_string)
+}
+
+fn manager_for_path(path: &str, nvm_symlink: Option<&str>) -> Option<&'static str> {
+    let normalize = |p: &str| p.replace('\\', "/").to_lowercase();
+    let path = normalize(path);
+
+    // nvm-windows links the active version into a fixed directory
+    if let Some(symlink) = nvm_symlink.map(normalize) {
+        if !symlink.is_empty() && path.starts_with(symlink.trim_end_matches('/')) {
+            return Some("nvm");
+        }
+    }
+
+    const MARKERS: [(&str, &str); 12] = [
+        ("/.portal-desktop/sdks/", "portal"),
+        ("/portal-desktop/sdks/", "portal"),
+        ("/scoop/", "scoop"),
+        ("/microsoft/winget/", "winget"),
+        ("/chocolatey/", "chocolatey"),
+        ("/.nvm/", "nvm"),
+        ("/nvm/", "nvm"),
+        ("/.pyenv/", "pyenv"),
+        ("/.sdkman/", "sdk"),
+        ("/.cargo/bin/", "rustup"),
+        ("/.rbenv/", "rbenv"),
+        ("/homebrew/", "homebrew"),
+    ];
+    MARKERS
+        .iter()
+        .find(|(marker, _)| path.contains(marker))
+        .map(|(_, manager)| *manager)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_binaries_to_managers() {
+        assert_eq!(
+            manager_for_path(r"C:\Users\dev\scoop\shims\node.exe", None),
+            Some("scoop")
+        );
+        assert_eq!(
+            manager_for_path(
+                r"C:\Users\dev\AppData\Local\Microsoft\WinGet\Links\go.exe",
+                None
+            ),
+            Some("winget")
+        );
+        assert_eq!(
+            manager_for_path(r"C:\nvm4w\nodejs\node.exe", Some(r"C:\nvm4w\nodejs")),
+            Some("nvm")
+        );
+        assert_eq!(
+            manager_for_path(r"C:\Users\dev\.cargo\bin\rustc.exe", None),
+            Some("rustup")
+        );
+        assert_eq!(
+            manager_for_path("/home/dev/.pyenv/shims/python3", None),
+            Some("pyenv")
+        );
+        assert_eq!(manager_for_path("/usr/bin/python3", None), None);
+    }
+}
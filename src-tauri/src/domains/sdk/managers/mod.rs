@@ -5,15 +5,19 @@
  * that implement the unified trait interfaces.
  */
 pub mod nvm_manager;
+pub mod nvm_windows_manager;
 pub mod phpenv_manager;
 pub mod pyenv_manager;
 pub mod rbenv_manager;
 pub mod rustup_manager;
+pub mod scoop_sdk_manager;
 pub mod sdkman_manager;
 
 pub use nvm_manager::NvmManager;
+pub use nvm_windows_manager::NvmWindowsManager;
 pub use phpenv_manager::PhpenvManager;
 pub use pyenv_manager::PyenvManager;
 pub use rbenv_manager::RbenvManager;
 pub use rustup_manager::RustupManager;
+pub use scoop_sdk_manager::ScoopSdkManager;
 pub use sdkman_manager::SdkmanManager;
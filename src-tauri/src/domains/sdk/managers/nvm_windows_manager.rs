@@ -0,0 +1,311 @@
+use super::super::traits::sdk_manager::{SDKManager, SDKManagerDefaults, SDKManagerHelpers};
+use super::super::SDKError;
+use crate::command_executor::CommandExecutor;
+/**
+ * NVM for Windows Manager Implementation
+ *
+ * nvm-windows (coreybutler/nvm-windows) is a standalone `nvm.exe` rather
+ * than the shell function the Unix NVM is, with its own command output.
+ * Switching versions repoints the NVM_SYMLINK directory, so it is global;
+ * per-project versions are pinned in `.nvmrc` for the shell hooks.
+ */
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+pub struct NvmWindowsManager;
+
+impl NvmWindowsManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// nvm-windows reports many failures on stdout with a zero exit code,
+    /// so callers that change state verify the result themselves
+    async fn execute_command(&self, args: &[&str]) -> Result<String, SDKError> {
+        let result = CommandExecutor::execute_with_args("nvm", args, None)
+            .await
+            .map_err(|e| SDKError::ManagerNotFound(format!("Failed to execute command: {}", e)))?;
+
+        if result.success {
+            Ok(result.stdout)
+        } else {
+            Err(SDKError::CommandFailed(format!(
+                "nvm {} failed: {}",
+                args.join(" "),
+                if result.stderr.trim().is_empty() {
+                    result.stdout.trim()
+                } else {
+                    result.stderr.trim()
+                }
+            )))
+        }
+    }
+}
+
+/// Versions from `nvm list`, e.g. `  * 20.11.0 (Currently using 64-bit executable)`
+fn parse_installed(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let version = line
+                .trim()
+                .trim_start_matches('*')
+                .split_whitespace()
+                .next()?;
+            is_version(version).then(|| version.trim_start_matches('v').to_string())
+        })
+        .collect()
+}
+
+/// The version marked current in `nvm list`
+fn parse_current(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find(|line| line.trim_start().starts_with('*'))
+        .and_then(|line| parse_installed(line).into_iter().next())
+}
+
+/// Versions from the `nvm list available` table, whose rows look like
+/// `|    21.6.1    |   20.11.0    |   0.12.18    |   0.11.16    |`
+fn parse_available(output: &str) -> Vec<String> {
+    let mut versions: Vec<String> = output
+        .lines()
+        .flat_map(|line| line.split('|'))
+        .map(str::trim)
+        .filter(|cell| is_version(cell))
+        .map(str::to_string)
+        .collect();
+    versions.dedup();
+    versions
+}
+
+fn is_version(text: &str) -> bool {
+    let text = text.trim_start_matches('v');
+    !text.is_empty()
+        && text.split('.').count() == 3
+        && text
+            .split('.')
+            .all(|part| part.chars().all(|c| c.is_ascii_digit()) && !part.is_empty())
+}
+
+#[async_trait]
+impl SDKManager for NvmWindowsManager {
+    fn name(&self) -> &'static str {
+        "nvm"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "NVM for Windows"
+    }
+
+    fn sdk_type(&self) -> &'static str {
+        "node"
+    }
+
+    fn category(&self) -> &'static str {
+        "language"
+    }
+
+    async fn is_installed(&self) -> Result<bool, SDKError> {
+        Ok(self.get_manager_version().await.is_ok())
+    }
+
+    async fn get_manager_version(&self) -> Result<String, SDKError> {
+        let output = self.execute_command(&["version"]).await?;
+        let version = output.trim().trim_start_matches('v').to_string();
+        if is_version(&version) {
+            Ok(version)
+        } else {
+            Err(SDKError::ManagerNotFound("nvm-windows".to_string()))
+        }
+    }
+
+    // === Version Management ===
+    async fn list_versions(&self) -> Result<Vec<String>, SDKError> {
+        let output = self.execute_command(&["list"]).await?;
+        Ok(parse_installed(&output))
+    }
+
+    async fn get_current_version(&self) -> Result<Option<String>, SDKError> {
+        // `nvm current` only exists in newer releases; `nvm list` marks it too
+        let output = self.execute_command(&["list"]).await?;
+        Ok(parse_current(&output))
+    }
+
+    async fn switch_version(&self, version: &str) -> Result<(), SDKError> {
+        let version = version.trim_start_matches('v');
+        let output = self.execute_command(&["use", version]).await?;
+
+        if self.get_current_version().await?.as_deref() != Some(version) {
+            // Usually the symlink couldn't be changed without elevation
+            return Err(SDKError::CommandFailed(format!(
+                "nvm use {} did not switch versions: {}",
+                version,
+                output.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn switch_version_for_project(
+        &self,
+        version: &str,
+        project_path: &str,
+    ) -> Result<(), SDKError> {
+        SDKManager::set_project_config(self, project_path, "node_version", version).await
+    }
+
+    async fn is_version_installed(&self, version: &str) -> Result<bool, SDKError> {
+        let version = version.trim_start_matches('v');
+        Ok(self.list_versions().await?.iter().any(|v| v == version))
+    }
+
+    // === Installation (Optional) ===
+    async fn install_version(&self, version: &str) -> Result<(), SDKError> {
+        let version = version.trim_start_matches('v');
+        let output = self.execute_command(&["install", version]).await?;
+        if !self.is_version_installed(version).await? {
+            return Err(SDKError::CommandFailed(format!(
+                "nvm install {} failed: {}",
+                version,
+                output.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn uninstall_version(&self, version: &str) -> Result<(), SDKError> {
+        self.execute_command(&["uninstall", version.trim_start_matches('v')])
+            .await?;
+        Ok(())
+    }
+
+    async fn list_available_versions(&self) -> Result<Vec<String>, SDKError> {
+        let output = self.execute_command(&["list", "available"]).await?;
+        Ok(parse_available(&output))
+    }
+
+    fn supports_installation(&self) -> bool {
+        true
+    }
+
+    // === Environment Management ===
+    async fn create_project_environment(
+        &self,
+        version: &str,
+        project_path: &str,
+    ) -> Result<String, SDKError> {
+        let script = format!(
+            "# NVM for Windows Project Environment\n# Generated for project: {}\n# Node version: {}\n\nnvm use {}\n",
+            project_path, version, version
+        );
+        Ok(script)
+    }
+
+    async fn get_environment_variables(
+        &self,
+        version: &str,
+    ) -> Result<HashMap<String, String>, SDKError> {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("NODE_VERSION".to_string(), version.to_string());
+        for key in ["NVM_HOME", "NVM_SYMLINK"] {
+            if let Ok(value) = std::env::var(key) {
+                env_vars.insert(key.to_string(), value);
+            }
+        }
+        Ok(env_vars)
+    }
+
+    // === Configuration ===
+    async fn get_project_config(
+        &self,
+        project_path: &str,
+    ) -> Result<HashMap<String, String>, SDKError> {
+        let mut config = HashMap::new();
+        let nvmrc_path = std::path::Path::new(project_path).join(".nvmrc");
+        if let Ok(content) = std::fs::read_to_string(&nvmrc_path) {
+            config.insert("node_version".to_string(), content.trim().to_string());
+        }
+        Ok(config)
+    }
+
+    async fn set_project_config(
+        &self,
+        project_path: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), SDKError> {
+        if key == "node_version" {
+            let nvmrc_path = std::path::Path::new(project_path).join(".nvmrc");
+            std::fs::write(&nvmrc_path, value)
+                .map_err(|e| SDKError::ManagerNotFound(format!("Failed to write .nvmrc: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    // === Help & Validation ===
+    async fn get_help(&self) -> Result<String, SDKError> {
+        Ok("NVM for Windows - Manage multiple Node.js versions on Windows\n\nUsage:\n  nvm install <version>    Install a Node.js version\n  nvm use <version>        Switch to a version (may need an elevated prompt)\n  nvm list                 List installed versions\n  nvm list available       List installable versions".to_string())
+    }
+
+    async fn get_usage_examples(&self) -> Result<Vec<String>, SDKError> {
+        Ok(vec![
+            "nvm install 20.11.0".to_string(),
+            "nvm use 20.11.0".to_string(),
+            "nvm list".to_string(),
+            "nvm list available".to_string(),
+        ])
+    }
+
+    async fn validate_setup(&self) -> Result<Vec<String>, SDKError> {
+        let mut issues = Vec::new();
+
+        if !self.is_installed().await? {
+            issues.push("NVM for Windows is not installed".to_string());
+        }
+        if std::env::var("NVM_SYMLINK").is_err() {
+            issues.push(
+                "NVM_SYMLINK is not set; reopen Portal after installing nvm-windows".to_string(),
+            );
+        }
+
+        Ok(issues)
+    }
+
+    // === Information ===
+    async fn get_info(&self) -> Result<HashMap<String, String>, SDKError> {
+        let mut info = HashMap::new();
+        info.insert("name".to_string(), self.display_name().to_string());
+        info.insert("version".to_string(), self.get_manager_version().await?);
+        info.insert("sdk_type".to_string(), self.sdk_type().to_string());
+        info.insert("category".to_string(), self.category().to_string());
+        Ok(info)
+    }
+}
+
+#[async_trait]
+impl SDKManagerDefaults for NvmWindowsManager {}
+
+#[async_trait]
+impl SDKManagerHelpers for NvmWindowsManager {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nvm_windows_output() {
+        let list = "\n  * 20.11.0 (Currently using 64-bit executable)\n    18.19.0\n";
+        assert_eq!(parse_installed(list), vec!["20.11.0", "18.19.0"]);
+        assert_eq!(parse_current(list).as_deref(), Some("20.11.0"));
+        assert_eq!(parse_current("No installations recognized."), None);
+
+        let available = "\n|   CURRENT    |     LTS      |  OLD STABLE  | OLD UNSTABLE |\n\
+                         |--------------|--------------|--------------|--------------|\n\
+                         |    21.6.1    |   20.11.0    |   0.12.18    |   0.11.16    |\n";
+        assert_eq!(
+            parse_available(available),
+            vec!["21.6.1", "20.11.0", "0.12.18", "0.11.16"]
+        );
+    }
+}
@@ -68,24 +68,19 @@ impl SDKManager for RustupManager {
 
     // === Version Management ===
     async fn list_versions(&self) -> Result<Vec<String>, SDKError> {
-        let output = self.execute_command("rustup", &["show"]).await?;
-        let versions: Vec<String> = output
-            .lines()
-            .filter_map(|line| {
-                if line.contains("rust-") {
-                    Some(line.trim().to_string())
-                } else {
-                    None
-                }
-            })
-            .collect();
-        Ok(versions)
+        // `rustup show` output differs between releases and platforms; the
+        // toolchain list is one toolchain per line everywhere
+        let output = self
+            .execute_command("rustup", &["toolchain", "list"])
+            .await?;
+        Ok(output.lines().filter_map(toolchain_name).collect())
     }
 
     async fn get_current_version(&self) -> Result<Option<String>, SDKError> {
-        let output = self.execute_command("rustc", &["--version"]).await?;
-        let version = output.trim().replace("rustc ", "");
-        Ok(Some(version))
+        let output = self
+            .execute_command("rustup", &["show", "active-toolchain"])
+            .await?;
+        Ok(output.lines().find_map(toolchain_name))
     }
 
     async fn switch_version(&self, version: &str) -> Result<(), SDKError> {
@@ -100,7 +95,7 @@ impl SDKManager for RustupManager {
         project_path: &str,
     ) -> Result<(), SDKError> {
         // Create rust-toolchain file for project-specific version
-        let toolchain_file = format!("{}/rust-toolchain", project_path);
+        let toolchain_file = std::path::Path::new(project_path).join("rust-toolchain");
         std::fs::write(&toolchain_file, version).map_err(|e| {
             SDKError::ManagerNotFound(format!("Failed to write rust-toolchain: {}", e))
         })?;
@@ -108,8 +103,11 @@ impl SDKManager for RustupManager {
     }
 
     async fn is_version_installed(&self, version: &str) -> Result<bool, SDKError> {
-        let output = self.execute_command("rustup", &["show"]).await?;
-        Ok(output.contains(version))
+        // Toolchains are listed with their host triple, e.g.
+        // `stable-x86_64-pc-windows-msvc` for `stable`
+        Ok(self.list_versions().await?.iter().any(|toolchain| {
+            toolchain == version || toolchain.starts_with(&format!("{}-", version))
+        }))
     }
 
     // === Installation (Optional) ===
@@ -152,10 +150,17 @@ impl SDKManager for RustupManager {
         version: &str,
         project_path: &str,
     ) -> Result<String, SDKError> {
-        let script = format!(
-            "#!/bin/bash\n# Rust Project Environment\n# Generated for project: {}\n# Rust version: {}\n\n# Set Rust toolchain for this project\nexport RUSTUP_TOOLCHAIN={}\n",
-            project_path, version, version
-        );
+        let script = if cfg!(target_os = "windows") {
+            format!(
+                "# Rust Project Environment\n# Generated for project: {}\n# Rust version: {}\n\n# Set Rust toolchain for this project\n$env:RUSTUP_TOOLCHAIN = \"{}\"\n",
+                project_path, version, version
+            )
+        } else {
+            format!(
+                "#!/bin/bash\n# Rust Project Environment\n# Generated for project: {}\n# Rust version: {}\n\n# Set Rust toolchain for this project\nexport RUSTUP_TOOLCHAIN={}\n",
+                project_path, version, version
+            )
+        };
         Ok(script)
     }
 
@@ -239,3 +244,36 @@ impl SDKManagerDefaults for RustupManager {}
 
 #[async_trait]
 impl SDKManagerHelpers for RustupManager {}
+
+/// The toolchain on a `rustup toolchain list` or `rustup show
+/// active-toolchain` line, without the `(default)`-style annotations
+fn toolchain_name(line: &str) -> Option<String> {
+    let name = line.split_whitespace().next()?;
+    if name.starts_with("no") || name.starts_with("error") {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_toolchain_lines() {
+        let list = "stable-x86_64-pc-windows-msvc (default)\nnightly-x86_64-pc-windows-msvc\n";
+        assert_eq!(
+            list.lines().filter_map(toolchain_name).collect::<Vec<_>>(),
+            vec![
+                "stable-x86_64-pc-windows-msvc",
+                "nightly-x86_64-pc-windows-msvc"
+            ]
+        );
+        assert_eq!(
+            toolchain_name("1.79.0-aarch64-apple-darwin (overridden by '/work/rust-toolchain')")
+                .as_deref(),
+            Some("1.79.0-aarch64-apple-darwin")
+        );
+        assert_eq!(toolchain_name("no installed toolchains"), None);
+    }
+}
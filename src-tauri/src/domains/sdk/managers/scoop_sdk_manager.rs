@@ -0,0 +1,367 @@
+use super::super::traits::sdk_manager::{SDKManager, SDKManagerDefaults, SDKManagerHelpers};
+use super::super::SDKError;
+use crate::command_executor::CommandExecutor;
+/**
+ * Scoop SDK Manager Implementation
+ *
+ * Scoop keeps every installed version of an app side by side under
+ * `<scoop>/apps/<app>/<version>`, with a `current` junction pointing at the
+ * active one, so it can act as a version manager on Windows: `scoop reset
+ * <app>@<version>` repoints `current` and its shims.
+ */
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub struct ScoopSdkManager {
+    name: &'static str,
+    display_name: &'static str,
+    sdk_type: &'static str,
+    /// Scoop app holding the SDK
+    app: &'static str,
+}
+
+impl ScoopSdkManager {
+    pub fn nodejs() -> Self {
+        Self::new("scoop-nodejs", "Scoop (Node.js)", "node", "nodejs")
+    }
+
+    pub fn python() -> Self {
+        Self::new("scoop-python", "Scoop (Python)", "python", "python")
+    }
+
+    pub fn java() -> Self {
+        Self::new("scoop-java", "Scoop (OpenJDK)", "java", "openjdk")
+    }
+
+    pub fn go() -> Self {
+        Self::new("scoop-go", "Scoop (Go)", "go", "go")
+    }
+
+    fn new(
+        name: &'static str,
+        display_name: &'static str,
+        sdk_type: &'static str,
+        app: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            display_name,
+            sdk_type,
+            app,
+        }
+    }
+
+    fn app_dir(&self) -> PathBuf {
+        scoop_root().join("apps").join(self.app)
+    }
+
+    /// Scoop is a PowerShell script behind a `.cmd` shim, so it has to go
+    /// through the shell rather than being spawned directly
+    async fn execute_scoop(&self, args: &str) -> Result<String, SDKError> {
+        let result = CommandExecutor::execute_shell(&format!("scoop {}", args), None)
+            .await
+            .map_err(|e| SDKError::ManagerNotFound(format!("Failed to execute command: {}", e)))?;
+
+        if result.success {
+            Ok(result.stdout)
+        } else {
+            Err(SDKError::CommandFailed(format!(
+                "scoop {} failed: {}",
+                args,
+                result.stderr.trim()
+            )))
+        }
+    }
+}
+
+/// `$env:SCOOP`, or the default per-user install
+fn scoop_root() -> PathBuf {
+    if let Ok(root) = std::env::var("SCOOP") {
+        return PathBuf::from(root);
+    }
+    dirs::home_dir().unwrap_or_default().join("scoop")
+}
+
+/// Version directories among an app directory's entries
+fn version_dirs<I: IntoIterator<Item = String>>(entries: I) -> Vec<String> {
+    let mut versions: Vec<String> = entries
+        .into_iter()
+        .filter(|name| name != "current" && !name.starts_with('.'))
+        .collect();
+    versions.sort();
+    versions
+}
+
+/// The `Version` field of `scoop info <app>`
+fn parse_info_version(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Version").then(|| value.trim().to_string())
+    })
+}
+
+/// Keep a version argument from smuggling extra shell syntax into `cmd /C`
+fn check_version(version: &str) -> Result<(), SDKError> {
+    if version.is_empty()
+        || !version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'))
+    {
+        return Err(SDKError::InvalidVersion(version.to_string()));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl SDKManager for ScoopSdkManager {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn display_name(&self) -> &'static str {
+        self.display_name
+    }
+
+    fn sdk_type(&self) -> &'static str {
+        self.sdk_type
+    }
+
+    fn category(&self) -> &'static str {
+        "language"
+    }
+
+    async fn is_installed(&self) -> Result<bool, SDKError> {
+        Ok(self.app_dir().join("current").exists())
+    }
+
+    async fn get_manager_version(&self) -> Result<String, SDKError> {
+        let output = self.execute_scoop("--version").await?;
+        // The first line is the Scoop version, e.g. "Current Scoop version:"
+        // followed by the git revision; keep whatever it reports
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.ends_with(':'))
+            .unwrap_or("unknown")
+            .to_string())
+    }
+
+    // === Version Management ===
+    async fn list_versions(&self) -> Result<Vec<String>, SDKError> {
+        let Ok(entries) = std::fs::read_dir(self.app_dir()) else {
+            return Ok(Vec::new());
+        };
+        Ok(version_dirs(
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok()),
+        ))
+    }
+
+    async fn get_current_version(&self) -> Result<Option<String>, SDKError> {
+        let manifest = self.app_dir().join("current").join("manifest.json");
+        let Ok(content) = std::fs::read_to_string(&manifest) else {
+            return Ok(None);
+        };
+        let manifest: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+            SDKError::CommandFailed(format!("Invalid Scoop manifest for {}: {}", self.app, e))
+        })?;
+        Ok(manifest
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string))
+    }
+
+    async fn switch_version(&self, version: &str) -> Result<(), SDKError> {
+        check_version(version)?;
+        if !self.is_version_installed(version).await? {
+            return Err(SDKError::VersionNotFound(format!(
+                "{} {}",
+                self.app, version
+            )));
+        }
+        self.execute_scoop(&format!("reset {}@{}", self.app, version))
+            .await?;
+        Ok(())
+    }
+
+    async fn switch_version_for_project(
+        &self,
+        _version: &str,
+        _project_path: &str,
+    ) -> Result<(), SDKError> {
+        Err(SDKError::ManagerNotFound(format!(
+            "{} switches versions globally; pin project versions in a version file instead",
+            self.display_name
+        )))
+    }
+
+    async fn is_version_installed(&self, version: &str) -> Result<bool, SDKError> {
+        Ok(self.list_versions().await?.iter().any(|v| v == version))
+    }
+
+    // === Installation (Optional) ===
+    async fn install_version(&self, version: &str) -> Result<(), SDKError> {
+        check_version(version)?;
+        let output = self
+            .execute_scoop(&format!("install {}@{}", self.app, version))
+            .await?;
+        // Scoop refuses a second version of an installed app on stdout
+        if !self.is_version_installed(version).await? {
+            return Err(SDKError::CommandFailed(format!(
+                "scoop install {}@{} failed: {}",
+                self.app,
+                version,
+                output.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn uninstall_version(&self, version: &str) -> Result<(), SDKError> {
+        check_version(version)?;
+        let versions = self.list_versions().await?;
+        if !versions.iter().any(|v| v == version) {
+            return Err(SDKError::VersionNotFound(format!(
+                "{} {}",
+                self.app, version
+            )));
+        }
+
+        if versions.len() == 1 {
+            self.execute_scoop(&format!("uninstall {}", self.app))
+                .await?;
+            return Ok(());
+        }
+        if self.get_current_version().await?.as_deref() == Some(version) {
+            return Err(SDKError::CommandFailed(format!(
+                "{} {} is the active version; switch to another before removing it",
+                self.app, version
+            )));
+        }
+        // An inactive version is just a directory, as `scoop cleanup` treats it
+        std::fs::remove_dir_all(self.app_dir().join(version))?;
+        Ok(())
+    }
+
+    async fn list_available_versions(&self) -> Result<Vec<String>, SDKError> {
+        // Buckets only carry the latest manifest of each app
+        let output = self.execute_scoop(&format!("info {}", self.app)).await?;
+        Ok(parse_info_version(&output).into_iter().collect())
+    }
+
+    fn supports_installation(&self) -> bool {
+        true
+    }
+
+    // === Environment Management ===
+    async fn create_project_environment(
+        &self,
+        version: &str,
+        project_path: &str,
+    ) -> Result<String, SDKError> {
+        let script = format!(
+            "# Scoop Project Environment\n# Generated for project: {}\n# {} version: {}\n\n$env:PATH = \"{}\\$env:PATH\"\n",
+            project_path,
+            self.app,
+            version,
+            self.app_dir().join(version).display()
+        );
+        Ok(script)
+    }
+
+    async fn get_environment_variables(
+        &self,
+        version: &str,
+    ) -> Result<HashMap<String, String>, SDKError> {
+        let mut env_vars = HashMap::new();
+        env_vars.insert(
+            format!("{}_HOME", self.sdk_type.to_uppercase()),
+            self.app_dir().join(version).display().to_string(),
+        );
+        Ok(env_vars)
+    }
+
+    // === Configuration ===
+    async fn get_project_config(
+        &self,
+        _project_path: &str,
+    ) -> Result<HashMap<String, String>, SDKError> {
+        Ok(HashMap::new())
+    }
+
+    async fn set_project_config(
+        &self,
+        _project_path: &str,
+        _key: &str,
+        _value: &str,
+    ) -> Result<(), SDKError> {
+        Err(SDKError::ManagerNotFound(format!(
+            "Project configuration not supported for {}",
+            self.display_name
+        )))
+    }
+
+    // === Help & Validation ===
+    async fn get_help(&self) -> Result<String, SDKError> {
+        Ok(format!(
+            "Scoop - Windows command-line installer\n\nUsage:\n  scoop install {app}@<version>   Install a version\n  scoop reset {app}@<version>     Switch to an installed version\n  scoop list                      List installed apps",
+            app = self.app
+        ))
+    }
+
+    async fn get_usage_examples(&self) -> Result<Vec<String>, SDKError> {
+        Ok(vec![
+            format!("scoop install {}", self.app),
+            format!("scoop reset {}@<version>", self.app),
+            "scoop list".to_string(),
+        ])
+    }
+
+    async fn validate_setup(&self) -> Result<Vec<String>, SDKError> {
+        let mut issues = Vec::new();
+
+        if !scoop_root().join("shims").is_dir() {
+            issues.push("Scoop is not installed".to_string());
+        } else if !self.is_installed().await? {
+            issues.push(format!("{} is not installed with Scoop", self.app));
+        }
+
+        Ok(issues)
+    }
+
+    // === Information ===
+    async fn get_info(&self) -> Result<HashMap<String, String>, SDKError> {
+        let mut info = HashMap::new();
+        info.insert("name".to_string(), self.display_name().to_string());
+        info.insert("version".to_string(), self.get_manager_version().await?);
+        info.insert("sdk_type".to_string(), self.sdk_type().to_string());
+        info.insert("category".to_string(), self.category().to_string());
+        Ok(info)
+    }
+}
+
+#[async_trait]
+impl SDKManagerDefaults for ScoopSdkManager {}
+
+#[async_trait]
+impl SDKManagerHelpers for ScoopSdkManager {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_scoop_versions() {
+        let entries = ["current", "20.11.0", "18.19.0"].map(String::from);
+        assert_eq!(version_dirs(entries), vec!["18.19.0", "20.11.0"]);
+
+        let info = "Name        : nodejs\nDescription : As an asynchronous event driven JavaScript runtime\nVersion     : 21.6.1\nBucket      : main\n";
+        assert_eq!(parse_info_version(info).as_deref(), Some("21.6.1"));
+
+        assert!(check_version("21.0.2-13").is_ok());
+        assert!(check_version("20 & calc").is_err());
+    }
+}
@@ -9,6 +9,7 @@ pub mod factory;
 pub mod manager_detector;
 pub mod managers;
 pub mod ollama_manager;
+pub mod ollama_modelfile;
 pub mod package_managers;
 pub mod project;
 pub mod services;
@@ -30,6 +31,8 @@ pub enum SDKError {
     IOError(#[from] std::io::Error),
     #[error("Invalid version format: {0}")]
     InvalidVersion(String),
+    #[error("Checksum mismatch for {0}")]
+    ChecksumMismatch(String),
 }
 
 impl From<SDKError> for String {
@@ -3,7 +3,7 @@ use reqwest;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use tauri::{AppHandle, Emitter};
 use tokio::io::{AsyncBufReadExt, BufReader};
@@ -534,6 +534,50 @@ impl OllamaManager {
         model_name: &str,
         app: AppHandle,
     ) -> Result<String, String> {
+        Self::run_with_progress(
+            model_name,
+            &["pull", model_name],
+            "downloading",
+            "Starting download...",
+            std::time::Duration::from_secs(300),
+            &app,
+        )
+        .await?;
+        Ok(format!("Model {} installed successfully", model_name))
+    }
+
+    /// Create a model from a Modelfile (`ollama create`), with the same
+    /// progress events as an install. Relative paths in the Modelfile are
+    /// resolved against its directory.
+    pub async fn create_model_with_progress(
+        model_name: &str,
+        modelfile: &Path,
+        app: AppHandle,
+    ) -> Result<String, String> {
+        let modelfile = modelfile.to_string_lossy().to_string();
+        Self::run_with_progress(
+            model_name,
+            &["create", model_name, "-f", &modelfile],
+            "creating",
+            "Creating model...",
+            // Importing copies the whole weights file into Ollama's store
+            std::time::Duration::from_secs(1800),
+            &app,
+        )
+        .await?;
+        Ok(format!("Model {} created successfully", model_name))
+    }
+
+    /// Run an ollama subcommand, relaying its output as
+    /// `ollama-model-progress` events under `status` until it completes
+    async fn run_with_progress(
+        model_name: &str,
+        args: &[&str],
+        status: &str,
+        start_message: &str,
+        timeout_duration: std::time::Duration,
+        app: &AppHandle,
+    ) -> Result<(), String> {
         if !Self::is_installed().await {
             return Err("Ollama is not installed".to_string());
         }
@@ -550,7 +594,7 @@ impl OllamaManager {
             serde_json::json!({
                 "model": model_name,
                 "status": "started",
-                "message": "Starting download...",
+                "message": start_message,
                 "progress": 0
             }),
         );
@@ -558,12 +602,11 @@ impl OllamaManager {
         // Use spawn to stream output in real-time
         let mut child = TokioCommand::new("ollama")
             .no_window()
-            .arg("pull")
-            .arg(model_name)
+            .args(args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
-            .map_err(|e| format!("Failed to start ollama pull command: {}", e))?;
+            .map_err(|e| format!("Failed to start ollama {} command: {}", args[0], e))?;
 
         let mut stdout = child
             .stdout
@@ -582,22 +625,25 @@ impl OllamaManager {
         let mut stderr_lines = stderr_reader.lines();
 
         let mut output_lines = Vec::new();
-        let timeout_duration = std::time::Duration::from_secs(300);
         let start_time = std::time::Instant::now();
 
         loop {
             if start_time.elapsed() > timeout_duration {
                 let _ = child.kill().await;
+                let message = format!(
+                    "Command timed out after {} minutes",
+                    timeout_duration.as_secs() / 60
+                );
                 let _ = app.emit(
                     "ollama-model-progress",
                     serde_json::json!({
                         "model": model_name,
                         "status": "error",
-                        "message": "Command timed out after 5 minutes",
+                        "message": message,
                         "progress": 0
                     }),
                 );
-                return Err("Command timed out after 5 minutes".to_string());
+                return Err(message);
             }
 
             tokio::select! {
@@ -612,7 +658,7 @@ impl OllamaManager {
 
                             let _ = app.emit("ollama-model-progress", serde_json::json!({
                                 "model": model_name,
-                                "status": "downloading",
+                                "status": status,
                                 "message": message.clone(),
                                 "progress": progress
                             }));
@@ -638,7 +684,7 @@ impl OllamaManager {
                                     "message": line_str.clone(),
                                     "progress": 0
                                 }));
-                                return Err(format!("Failed to install model: {}", line_str));
+                                return Err(format!("Failed to {} model: {}", args[0], line_str));
                             }
                         }
                         Ok(None) => {}
@@ -647,8 +693,8 @@ impl OllamaManager {
                 }
                 _ = tokio::time::sleep(std::time::Duration::from_millis(100)) => {
                     match child.try_wait() {
-                        Ok(Some(status)) => {
-                            if status.success() {
+                        Ok(Some(exit_status)) => {
+                            if exit_status.success() {
                                 break;
                             } else {
                                 let error_output = output_lines.join("\n");
@@ -658,7 +704,7 @@ impl OllamaManager {
                                     "message": error_output.clone(),
                                     "progress": 0
                                 }));
-                                return Err(format!("Failed to install model. Exit code: {:?}", status.code()));
+                                return Err(format!("Failed to {} model. Exit code: {:?}", args[0], exit_status.code()));
                             }
                         }
                         Ok(None) => {}
@@ -671,18 +717,22 @@ impl OllamaManager {
         }
 
         match child.wait().await {
-            Ok(status) => {
-                if status.success() {
+            Ok(exit_status) => {
+                if exit_status.success() {
                     let _ = app.emit(
                         "ollama-model-progress",
                         serde_json::json!({
                             "model": model_name,
                             "status": "completed",
-                            "message": "Installation complete!",
+                            "message": if status == "downloading" {
+                                "Installation complete!"
+                            } else {
+                                "Model created!"
+                            },
                             "progress": 100
                         }),
                     );
-                    Ok(format!("Model {} installed successfully", model_name))
+                    Ok(())
                 } else {
                     let error_output = output_lines.join("\n");
                     let _ = app.emit(
@@ -695,8 +745,9 @@ impl OllamaManager {
                         }),
                     );
                     Err(format!(
-                        "Failed to install model. Exit code: {:?}",
-                        status.code()
+                        "Failed to {} model. Exit code: {:?}",
+                        args[0],
+                        exit_status.code()
                     ))
                 }
             }
@@ -704,6 +755,30 @@ impl OllamaManager {
         }
     }
 
+    /// The Modelfile Ollama reconstructs for an installed model. Its `FROM`
+    /// points at the weights blob in Ollama's store.
+    pub async fn show_modelfile(model_name: &str) -> Result<String, String> {
+        if !Self::is_installed().await {
+            return Err("Ollama is not installed".to_string());
+        }
+
+        let output = TokioCommand::new("ollama")
+            .no_window()
+            .arg("show")
+            .arg(model_name)
+            .arg("--modelfile")
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run ollama show: {}", e))?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to read Modelfile: {}", error));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
     /// Parse progress percentage from Ollama's output line
     fn parse_progress_from_line(line: &str) -> u8 {
         // Look for percentage patterns like "50%" or "progress: 75%"
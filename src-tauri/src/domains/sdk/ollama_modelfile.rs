@@ -0,0 +1,341 @@
+/**
+ * Ollama Modelfiles
+ *
+ * Builds Modelfiles from what the app collects, exports installed models
+ * as a Modelfile plus their weights, and prepares local GGUF files and
+ * Modelfiles for `ollama create`. Ollama resolves relative paths against
+ * the Modelfile's directory, so anything handed to it is rewritten with
+ * absolute paths and written to a scratch file first.
+ */
+use crate::domains::sdk::ollama_manager::OllamaManager;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelParameter {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelMessage {
+    /// system, user or assistant
+    pub role: String,
+    pub content: String,
+}
+
+/// A Modelfile as the builder edits it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelfileSpec {
+    /// An installed model name or a path to a GGUF file
+    pub from: String,
+    /// Repeatable, e.g. several `stop` sequences
+    #[serde(default)]
+    pub parameters: Vec<ModelParameter>,
+    pub system: Option<String>,
+    pub template: Option<String>,
+    pub adapter: Option<String>,
+    pub license: Option<String>,
+    #[serde(default)]
+    pub messages: Vec<ModelMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelExport {
+    pub directory: PathBuf,
+    pub modelfile: PathBuf,
+    /// Weights and adapters copied alongside the Modelfile
+    pub files: Vec<PathBuf>,
+}
+
+impl ModelfileSpec {
+    pub fn render(&self) -> Result<String, String> {
+        if self.from.trim().is_empty() {
+            return Err("A Modelfile needs a FROM model or file".to_string());
+        }
+        let mut lines = vec![format!("FROM {}", single_line("FROM", &self.from)?)];
+        if let Some(adapter) = &self.adapter {
+            lines.push(format!("ADAPTER {}", single_line("ADAPTER", adapter)?));
+        }
+        for parameter in &self.parameters {
+            if parameter.name.is_empty()
+                || !parameter
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                return Err(format!("Invalid parameter name: {}", parameter.name));
+            }
+            lines.push(format!(
+                "PARAMETER {} {}",
+                parameter.name,
+                single_line("PARAMETER", &parameter.value)?
+            ));
+        }
+        if let Some(template) = &self.template {
+            lines.push(format!("TEMPLATE {}", quoted("TEMPLATE", template)?));
+        }
+        if let Some(system) = &self.system {
+            lines.push(format!("SYSTEM {}", quoted("SYSTEM", system)?));
+        }
+        for message in &self.messages {
+            if !matches!(message.role.as_str(), "system" | "user" | "assistant") {
+                return Err(format!("Invalid message role: {}", message.role));
+            }
+            lines.push(format!(
+                "MESSAGE {} {}",
+                message.role,
+                quoted("MESSAGE", &message.content)?
+            ));
+        }
+        if let Some(license) = &self.license {
+            lines.push(format!("LICENSE {}", quoted("LICENSE", license)?));
+        }
+
+        let mut modelfile = lines.join("\n");
+        modelfile.push('\n');
+        Ok(modelfile)
+    }
+}
+
+/// Write an installed model's Modelfile into `<destination>/<model>/`. With
+/// `include_weights` its weights and adapters are copied next to it and
+/// referenced relatively, so the directory imports anywhere; without, it
+/// builds on top of the installed model.
+pub async fn export_model(
+    model_name: &str,
+    destination: &Path,
+    include_weights: bool,
+) -> Result<ModelExport, String> {
+    let modelfile = OllamaManager::show_modelfile(model_name).await?;
+    let directory = destination.join(file_safe_name(model_name));
+    tokio::fs::create_dir_all(&directory)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+
+    let mut files = Vec::new();
+    let mut lines = Vec::new();
+    for line in modelfile.lines() {
+        let Some((instruction, argument)) = blob_reference(line) else {
+            lines.push(line.to_string());
+            continue;
+        };
+        if !include_weights {
+            // Only FROM can fall back to the model by name
+            if instruction == "FROM" {
+                lines.push(format!("FROM {}", model_name));
+            }
+            continue;
+        }
+
+        let file_name = match instruction {
+            "FROM" => "model.gguf".to_string(),
+            _ => format!("adapter-{}.gguf", files.len()),
+        };
+        let target = directory.join(&file_name);
+        println!(
+            "[SDK] Exporting {} {} to {}",
+            model_name,
+            instruction.to_lowercase(),
+            target.display()
+        );
+        tokio::fs::copy(&argument, &target)
+            .await
+            .map_err(|e| format!("Failed to copy {}: {}", argument.display(), e))?;
+        files.push(target);
+        lines.push(format!("{} ./{}", instruction, file_name));
+    }
+
+    let modelfile_path = directory.join("Modelfile");
+    tokio::fs::write(&modelfile_path, lines.join("\n") + "\n")
+        .await
+        .map_err(|e| format!("Failed to write Modelfile: {}", e))?;
+
+    Ok(ModelExport {
+        directory,
+        modelfile: modelfile_path,
+        files,
+    })
+}
+
+/// A scratch Modelfile for importing a local GGUF, a Modelfile, or a GGUF
+/// with the settings of a Modelfile
+pub async fn prepare_import(
+    model_name: &str,
+    gguf: Option<&Path>,
+    modelfile: Option<&Path>,
+) -> Result<PathBuf, String> {
+    let (content, base_dir) = match modelfile {
+        Some(path) => (
+            tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+            path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        ),
+        None => (String::new(), PathBuf::new()),
+    };
+    let gguf = match gguf {
+        Some(path) => Some(
+            std::fs::canonicalize(path)
+                .map_err(|e| format!("GGUF file {} not found: {}", path.display(), e))?,
+        ),
+        None => None,
+    };
+    if gguf.is_none() && modelfile.is_none() {
+        return Err("Choose a GGUF file, a Modelfile, or both".to_string());
+    }
+
+    write_scratch_modelfile(
+        model_name,
+        &absolutize(&content, &base_dir, gguf.as_deref()),
+    )
+    .await
+}
+
+/// A scratch Modelfile rendered from the builder
+pub async fn prepare_build(model_name: &str, spec: &ModelfileSpec) -> Result<PathBuf, String> {
+    write_scratch_modelfile(model_name, &spec.render()?).await
+}
+
+/// Names `ollama create` accepts
+pub fn validate_model_name(model_name: &str) -> Result<(), String> {
+    let valid = !model_name.is_empty()
+        && model_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | ':' | '/'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid model name: {}", model_name))
+    }
+}
+
+async fn write_scratch_modelfile(model_name: &str, content: &str) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join("portal-ollama");
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.Modelfile", file_safe_name(model_name)));
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| format!("Failed to write Modelfile: {}", e))?;
+    Ok(path)
+}
+
+/// Rewrite relative FROM and ADAPTER paths against `base_dir`, and point
+/// FROM at `gguf` when given (adding the line if there is none)
+fn absolutize(content: &str, base_dir: &Path, gguf: Option<&Path>) -> String {
+    let mut has_from = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let Some((instruction, argument)) = instruction_argument(line) else {
+                return line.to_string();
+            };
+            if instruction == "FROM" {
+                has_from = true;
+                if let Some(gguf) = gguf {
+                    return format!("FROM {}", gguf.display());
+                }
+            }
+            let relative = base_dir.join(argument);
+            if Path::new(argument).is_relative() && relative.exists() {
+                format!("{} {}", instruction, relative.display())
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if let (false, Some(gguf)) = (has_from, gguf) {
+        lines.insert(0, format!("FROM {}", gguf.display()));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// A FROM or ADAPTER line and its argument
+fn instruction_argument(line: &str) -> Option<(&'static str, &str)> {
+    let (instruction, argument) = line.trim().split_once(char::is_whitespace)?;
+    let instruction = match instruction.to_uppercase().as_str() {
+        "FROM" => "FROM",
+        "ADAPTER" => "ADAPTER",
+        _ => return None,
+    };
+    Some((instruction, argument.trim()))
+}
+
+/// A FROM or ADAPTER line pointing at a file in Ollama's store
+fn blob_reference(line: &str) -> Option<(&'static str, PathBuf)> {
+    let (instruction, argument) = instruction_argument(line)?;
+    let path = PathBuf::from(argument);
+    (path.is_absolute() && path.is_file()).then_some((instruction, path))
+}
+
+fn file_safe_name(model_name: &str) -> String {
+    model_name.replace([':', '/', '\\'], "-")
+}
+
+fn single_line<'a>(instruction: &str, value: &'a str) -> Result<&'a str, String> {
+    if value.contains(['\n', '\r']) {
+        Err(format!("{} must fit on one line", instruction))
+    } else {
+        Ok(value.trim())
+    }
+}
+
+/// Triple-quoted when it spans lines or has quotes of its own
+fn quoted(instruction: &str, value: &str) -> Result<String, String> {
+    if value.contains("\"\"\"") {
+        return Err(format!("{} can't contain \"\"\"", instruction));
+    }
+    if value.contains(['\n', '"']) {
+        Ok(format!("\"\"\"{}\"\"\"", value))
+    } else {
+        Ok(format!("\"{}\"", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_and_rewrites_modelfiles() {
+        let spec = ModelfileSpec {
+            from: "llama3:8b".to_string(),
+            parameters: vec![
+                ModelParameter {
+                    name: "temperature".to_string(),
+                    value: "0.2".to_string(),
+                },
+                ModelParameter {
+                    name: "stop".to_string(),
+                    value: "<|eot_id|>".to_string(),
+                },
+            ],
+            system: Some("You review Rust code.\nBe brief.".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            spec.render().unwrap(),
+            "FROM llama3:8b\nPARAMETER temperature 0.2\nPARAMETER stop <|eot_id|>\n\
+             SYSTEM \"\"\"You review Rust code.\nBe brief.\"\"\"\n"
+        );
+        assert!(ModelfileSpec::default().render().is_err());
+
+        let gguf = Path::new("/models/coder.gguf");
+        assert_eq!(
+            absolutize(
+                "FROM ./old.gguf\nPARAMETER num_ctx 4096\n",
+                Path::new("/tmp"),
+                Some(gguf)
+            ),
+            "FROM /models/coder.gguf\nPARAMETER num_ctx 4096\n"
+        );
+        assert_eq!(
+            absolutize("", Path::new(""), Some(gguf)),
+            "FROM /models/coder.gguf\n"
+        );
+        assert!(validate_model_name("me/coder:latest").is_ok());
+        assert!(validate_model_name("bad name").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use super::super::traits::package_manager::{
+    InstalledPackage, Package, PackageDetails, PackageManager, PackageUpdate,
+};
+use super::super::SDKError;
+use crate::command_executor::CommandExecutor;
+/**
+ * Composer Package Manager Implementation
+ *
+ * Composer (PHP) implementation - cross-platform. Packages are managed in
+ * Composer's global project, so their binaries land in the global vendor/bin.
+ */
+use async_trait::async_trait;
+use serde_json::Value;
+
+pub struct ComposerManager;
+
+impl ComposerManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn execute_composer(&self, args: &[&str]) -> Result<String, SDKError> {
+        let mut full_args = args.to_vec();
+        full_args.push("--no-interaction");
+        let result = CommandExecutor::execute_with_args("composer", &full_args, None)
+            .await
+            .map_err(|e| SDKError::CommandFailed(format!("Composer command failed: {}", e)))?;
+
+        if result.success {
+            Ok(result.stdout)
+        } else {
+            Err(SDKError::CommandFailed(format!(
+                "Composer error: {}",
+                result.stderr
+            )))
+        }
+    }
+
+    fn parse_json(output: &str) -> Result<Value, SDKError> {
+        serde_json::from_str(output)
+            .map_err(|e| SDKError::CommandFailed(format!("Failed to parse Composer JSON: {}", e)))
+    }
+}
+
+#[async_trait]
+impl PackageManager for ComposerManager {
+    fn name(&self) -> &'static str {
+        "composer"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Composer"
+    }
+
+    fn platform(&self) -> &'static str {
+        "cross-platform"
+    }
+
+    async fn is_available(&self) -> Result<bool, SDKError> {
+        Ok(CommandExecutor::command_exists("composer").await)
+    }
+
+    async fn get_version(&self) -> Result<String, SDKError> {
+        // "Composer version 2.7.1 2024-02-09 15:26:28"
+        let output = self.execute_composer(&["--version"]).await?;
+        Ok(output
+            .split_whitespace()
+            .nth(2)
+            .unwrap_or(output.trim())
+            .to_string())
+    }
+
+    async fn search_packages(&self, query: &str) -> Result<Vec<Package>, SDKError> {
+        let output = self
+            .execute_composer(&["search", query, "--format=json"])
+            .await?;
+        let json = Self::parse_json(&output)?;
+
+        let mut packages = Vec::new();
+        if let Some(array) = json.as_array() {
+            for item in array {
+                if let Some(name) = item.get("name").and_then(|v| v.as_str()) {
+                    packages.push(Package {
+                        id: name.to_string(),
+                        name: name.to_string(),
+                        version: None,
+                        publisher: name.split('/').next().map(|s| s.to_string()),
+                        description: item
+                            .get("description")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        homepage: item
+                            .get("url")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string()),
+                        license: None,
+                        source: "composer".to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(packages)
+    }
+
+    async fn get_installed_packages(&self) -> Result<Vec<InstalledPackage>, SDKError> {
+        let output = self
+            .execute_composer(&["global", "show", "--direct", "--format=json"])
+            .await?;
+        let json = Self::parse_json(&output)?;
+
+        let mut packages = Vec::new();
+        if let Some(installed) = json.get("installed").and_then(|v| v.as_array()) {
+            for item in installed {
+                if let Some(name) = item.get("name").and_then(|v| v.as_str()) {
+                    let version = item
+                        .get("version")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+
+                    packages.push(InstalledPackage {
+                        id: name.to_string(),
+                        name: name.to_string(),
+                        version: version.clone(),
+                        installed_version: Some(version),
+                        available_version: None,
+                        source: "composer".to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(packages)
+    }
+
+    async fn get_package_details(&self, id: &str) -> Result<PackageDetails, SDKError> {
+        let output = self
+            .execute_composer(&["show", "--all", id, "--format=json"])
+            .await?;
+        let json = Self::parse_json(&output)?;
+
+        let version = json
+            .get("versions")
+            .and_then(|v| v.as_array())
+            .and_then(|versions| versions.first())
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let license = json
+            .get("licenses")
+            .and_then(|v| v.as_array())
+            .and_then(|licenses| licenses.first())
+            .and_then(|l| l.get("osi").or_else(|| l.get("name")))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let dependencies = json
+            .get("requires")
+            .and_then(|v| v.as_object())
+            .map(|deps| deps.keys().cloned().collect())
+            .unwrap_or_default();
+
+        Ok(PackageDetails {
+            id: id.to_string(),
+            name: json
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or(id)
+                .to_string(),
+            version,
+            publisher: id.split('/').next().map(|s| s.to_string()),
+            description: json
+                .get("description")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            homepage: json
+                .get("homepage")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            license,
+            dependencies,
+            source: "composer".to_string(),
+        })
+    }
+
+    async fn install_package(&self, id: &str, version: Option<&str>) -> Result<(), SDKError> {
+        let package = match version {
+            Some(version) => format!("{}:{}", id, version),
+            None => id.to_string(),
+        };
+        self.execute_composer(&["global", "require", &package])
+            .await?;
+        Ok(())
+    }
+
+    async fn upgrade_package(&self, id: &str) -> Result<(), SDKError> {
+        self.execute_composer(&["global", "update", id, "--with-dependencies"])
+            .await?;
+        Ok(())
+    }
+
+    async fn uninstall_package(&self, id: &str) -> Result<(), SDKError> {
+        self.execute_composer(&["global", "remove", id]).await?;
+        Ok(())
+    }
+
+    async fn check_updates(&self) -> Result<Vec<PackageUpdate>, SDKError> {
+        let output = self
+            .execute_composer(&["global", "outdated", "--direct", "--format=json"])
+            .await?;
+        let json = Self::parse_json(&output)?;
+
+        let mut updates = Vec::new();
+        if let Some(installed) = json.get("installed").and_then(|v| v.as_array()) {
+            for item in installed {
+                if let (Some(name), Some(current), Some(latest)) = (
+                    item.get("name").and_then(|v| v.as_str()),
+                    item.get("version").and_then(|v| v.as_str()),
+                    item.get("latest").and_then(|v| v.as_str()),
+                ) {
+                    updates.push(PackageUpdate {
+                        id: name.to_string(),
+                        name: name.to_string(),
+                        current_version: current.to_string(),
+                        available_version: latest.to_string(),
+                        source: "composer".to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(updates)
+    }
+
+    fn supports_search(&self) -> bool {
+        true
+    }
+
+    fn supports_updates(&self) -> bool {
+        true
+    }
+
+    fn requires_elevation(&self) -> bool {
+        false
+    }
+}
@@ -1,5 +1,6 @@
 pub mod cargo_manager;
 pub mod chocolatey_manager;
+pub mod composer_manager;
 pub mod homebrew_manager;
 pub mod npm_manager;
 pub mod pip_manager;
@@ -13,6 +14,7 @@ pub mod winget_manager;
 
 pub use cargo_manager::CargoManager;
 pub use chocolatey_manager::ChocolateyManager;
+pub use composer_manager::ComposerManager;
 pub use homebrew_manager::HomebrewManager;
 pub use npm_manager::NpmManager;
 pub use pip_manager::PipManager;
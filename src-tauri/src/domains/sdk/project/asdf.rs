@@ -0,0 +1,184 @@
+/**
+ * asdf Import
+ *
+ * Carries an existing asdf setup over: versions asdf already installed are
+ * linked into the direct-install directory instead of being downloaded
+ * again, and the pins in `~/.tool-versions` and a project's
+ * `.tool-versions` are recorded as the global default and in the
+ * project's `.portal-version`.
+ */
+use super::shims::ShimManager;
+use super::version_file::{sdk_for_asdf_plugin, VersionFileManager};
+use crate::domains::sdk::download::installer::{sdks_root, DIRECT_INSTALL_SDKS};
+use crate::domains::sdk::SDKError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportedVersion {
+    pub sdk_type: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AsdfImportReport {
+    /// asdf installs now available to the shims
+    pub linked: Vec<ImportedVersion>,
+    /// Pins written, globally and for the project
+    pub pinned: Vec<ImportedVersion>,
+    /// What couldn't be carried over, and why
+    pub skipped: Vec<String>,
+}
+
+pub struct AsdfImporter {
+    /// `$ASDF_DATA_DIR`, or `~/.asdf`
+    data_dir: PathBuf,
+    /// Holds the global `.tool-versions`
+    home: PathBuf,
+    sdks_root: PathBuf,
+}
+
+impl AsdfImporter {
+    pub fn new() -> Result<Self, SDKError> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            SDKError::ManagerNotFound("Could not determine home directory".to_string())
+        })?;
+        let data_dir = std::env::var_os("ASDF_DATA_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".asdf"));
+        Ok(Self {
+            data_dir,
+            home,
+            sdks_root: sdks_root()?,
+        })
+    }
+
+    /// Whether there is anything to import
+    pub fn is_available(&self) -> bool {
+        self.data_dir.join("installs").is_dir() || self.home.join(".tool-versions").is_file()
+    }
+
+    /// Link asdf's installs, then adopt its global pins and, given a
+    /// project, that project's `.tool-versions`
+    pub async fn import(&self, project_path: Option<&Path>) -> Result<AsdfImportReport, SDKError> {
+        let mut report = AsdfImportReport::default();
+        self.link_installs(&mut report)?;
+
+        let global_file = self.home.join(".tool-versions");
+        if global_file.is_file() {
+            let shims = ShimManager::new()?;
+            for (sdk_type, version) in VersionFileManager::read_tool_versions(&global_file).await? {
+                // Only installed versions, so nothing global starts failing
+                match shims.set_global_version(&sdk_type, &version).await {
+                    Ok(_) => report.pinned.push(ImportedVersion { sdk_type, version }),
+                    Err(e) => report
+                        .skipped
+                        .push(format!("Global {} {}: {}", sdk_type, version, e)),
+                }
+            }
+        }
+
+        if let Some(project_path) = project_path {
+            let tool_versions = project_path.join(".tool-versions");
+            if tool_versions.is_file() {
+                for (sdk_type, version) in
+                    VersionFileManager::read_tool_versions(&tool_versions).await?
+                {
+                    VersionFileManager::update_version(project_path, &sdk_type, &version).await?;
+                    report.pinned.push(ImportedVersion { sdk_type, version });
+                }
+            }
+        }
+
+        println!(
+            "[SDK] asdf import: {} linked, {} pinned, {} skipped",
+            report.linked.len(),
+            report.pinned.len(),
+            report.skipped.len()
+        );
+        Ok(report)
+    }
+
+    /// Symlink `installs/<plugin>/<version>` into the direct-install layout.
+    /// Versions already present there are left alone.
+    fn link_installs(&self, report: &mut AsdfImportReport) -> Result<(), SDKError> {
+        let Ok(plugins) = fs::read_dir(self.data_dir.join("installs")) else {
+            return Ok(());
+        };
+        for plugin in plugins.flatten() {
+            let plugin_name = plugin.file_name().to_string_lossy().to_string();
+            let sdk_type = sdk_for_asdf_plugin(&plugin_name);
+            if !DIRECT_INSTALL_SDKS.contains(&sdk_type.as_str()) {
+                report.skipped.push(format!(
+                    "asdf plugin {} has no Portal equivalent",
+                    plugin_name
+                ));
+                continue;
+            }
+
+            let Ok(versions) = fs::read_dir(plugin.path()) else {
+                continue;
+            };
+            for version in versions.flatten() {
+                let version_name = version.file_name().to_string_lossy().to_string();
+                if version_name.starts_with('.') || !version.path().is_dir() {
+                    continue;
+                }
+                let link = self.sdks_root.join(&sdk_type).join(&version_name);
+                if fs::symlink_metadata(&link).is_ok() {
+                    continue;
+                }
+                fs::create_dir_all(self.sdks_root.join(&sdk_type))?;
+                link_dir(&install_home(&sdk_type, &version.path()), &link)?;
+                report.linked.push(ImportedVersion {
+                    sdk_type: sdk_type.clone(),
+                    version: version_name,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The directory laid out like a direct install; asdf-golang nests the
+/// toolchain in a `go` subdirectory
+fn install_home(sdk_type: &str, install_dir: &Path) -> PathBuf {
+    let nested = install_dir.join("go");
+    if sdk_type == "go" && nested.join("bin").is_dir() {
+        nested
+    } else {
+        install_dir.to_path_buf()
+    }
+}
+
+#[cfg(unix)]
+fn link_dir(target: &Path, link: &Path) -> Result<(), SDKError> {
+    std::os::unix::fs::symlink(target, link)?;
+    Ok(())
+}
+
+/// asdf doesn't run on Windows, so there is never anything to link
+#[cfg(not(unix))]
+fn link_dir(target: &Path, _link: &Path) -> Result<(), SDKError> {
+    Err(SDKError::CommandFailed(format!(
+        "Cannot link {} outside Unix",
+        target.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_nested_go_toolchain() {
+        let dir = std::env::temp_dir().join(format!("portal-asdf-{}", std::process::id()));
+        fs::create_dir_all(dir.join("go").join("bin")).unwrap();
+
+        assert_eq!(install_home("go", &dir), dir.join("go"));
+        assert_eq!(install_home("nodejs", &dir), dir);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
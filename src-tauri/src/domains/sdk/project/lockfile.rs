@@ -0,0 +1,257 @@
+/**
+ * Portal Lock
+ *
+ * `portal.lock` snapshots a project's toolchain: the exact SDK version
+ * behind every pin in its version files, and the provisioned service
+ * instances it relies on. Committed with the project, it lets another
+ * machine install, pin and provision the same environment in one step.
+ */
+use super::version_file::{VersionFileFormat, VersionFileManager};
+use crate::domains::sdk::download::current_platform;
+use crate::domains::sdk::download::installer::SdkInstaller;
+use crate::domains::sdk::services::service_provisioner::ServiceProvisioner;
+use crate::domains::sdk::SDKError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const LOCK_FILE: &str = "portal.lock";
+
+/// Bumped when the format changes incompatibly
+const LOCK_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedSdk {
+    pub sdk_type: String,
+    /// The exact version in use
+    pub version: String,
+    /// What the project pins, e.g. "20" in `.nvmrc`
+    pub requested: String,
+    /// The version file the pin came from
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedService {
+    pub service_type: String,
+    pub name: String,
+    pub port: Option<u16>,
+    pub host: Option<String>,
+    pub username: Option<String>,
+    /// Passwords are never written; instances with auth need one on restore
+    pub auth_enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalLock {
+    pub lock_version: u32,
+    pub generated_at: String,
+    /// Where the snapshot was taken, e.g. "linux-x64"
+    pub platform: String,
+    #[serde(default)]
+    pub sdks: Vec<LockedSdk>,
+    #[serde(default)]
+    pub services: Vec<LockedService>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LockRestoreReport {
+    pub installed: Vec<String>,
+    /// SDK versions pinned for the project
+    pub activated: Vec<String>,
+    pub provisioned: Vec<String>,
+    pub started: Vec<String>,
+    /// What couldn't be restored, and why
+    pub skipped: Vec<String>,
+}
+
+pub struct LockfileManager;
+
+impl LockfileManager {
+    /// Snapshot the project's SDK pins and the given service instances
+    /// ("postgresql/orders"), keeping the services an earlier lock listed
+    pub async fn capture(project_path: &Path, services: &[String]) -> Result<PortalLock, SDKError> {
+        let installer = SdkInstaller::new()?;
+        let mut sdks = Vec::new();
+        for (sdk_type, (requested, source)) in project_pins(project_path).await? {
+            let installed = installer.list_installed(&sdk_type);
+            let version = resolve_installed(&requested, &installed).unwrap_or(requested.clone());
+            sdks.push(LockedSdk {
+                sdk_type,
+                version,
+                requested,
+                source,
+            });
+        }
+
+        let mut wanted: Vec<(String, String)> = Vec::new();
+        if let Ok(previous) = Self::read(project_path).await {
+            wanted.extend(
+                previous
+                    .services
+                    .into_iter()
+                    .map(|service| (service.service_type, service.name)),
+            );
+        }
+        for service in services {
+            let (service_type, name) = service.split_once('/').ok_or_else(|| {
+                SDKError::CommandFailed(format!(
+                    "Expected a service as <type>/<name>, got {}",
+                    service
+                ))
+            })?;
+            let key = (service_type.to_string(), name.to_string());
+            if !wanted.contains(&key) {
+                wanted.push(key);
+            }
+        }
+
+        let instances = ServiceProvisioner::new()?.list().await;
+        let mut locked_services = Vec::new();
+        for (service_type, name) in wanted {
+            let Some(instance) = instances
+                .iter()
+                .find(|i| i.service_type == service_type && i.name == name)
+            else {
+                if services.contains(&format!("{}/{}", service_type, name)) {
+                    return Err(SDKError::ManagerNotFound(format!(
+                        "No {} instance named {}",
+                        service_type, name
+                    )));
+                }
+                // Removed since the last lock
+                continue;
+            };
+            locked_services.push(LockedService {
+                service_type,
+                name,
+                port: instance.config.port,
+                host: instance.config.host.clone(),
+                username: instance.username.clone(),
+                auth_enabled: instance.auth_enabled,
+            });
+        }
+
+        let (os, arch) = current_platform();
+        Ok(PortalLock {
+            lock_version: LOCK_VERSION,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            platform: format!("{}-{}", os, arch),
+            sdks,
+            services: locked_services,
+        })
+    }
+
+    pub async fn read(project_path: &Path) -> Result<PortalLock, SDKError> {
+        let path = project_path.join(LOCK_FILE);
+        let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            SDKError::ManagerNotFound(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let lock: PortalLock = toml::from_str(&content).map_err(|e| {
+            SDKError::CommandFailed(format!("Failed to parse {}: {}", LOCK_FILE, e))
+        })?;
+        if lock.lock_version > LOCK_VERSION {
+            return Err(SDKError::CommandFailed(format!(
+                "{} was written by a newer Portal (lock version {})",
+                LOCK_FILE, lock.lock_version
+            )));
+        }
+        Ok(lock)
+    }
+
+    pub async fn write(project_path: &Path, lock: &PortalLock) -> Result<(), SDKError> {
+        let content = toml::to_string_pretty(lock)
+            .map_err(|e| SDKError::CommandFailed(format!("Failed to serialize lock: {}", e)))?;
+        tokio::fs::write(
+            project_path.join(LOCK_FILE),
+            format!(
+                "# Generated by Portal Desktop. Restore it with \"Restore environment\".\n{}",
+                content
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// One pin per SDK; `.portal-version` wins over the other files, as it does
+/// for the shims
+async fn project_pins(project_path: &Path) -> Result<BTreeMap<String, (String, String)>, SDKError> {
+    let mut pins: BTreeMap<String, (String, String)> = BTreeMap::new();
+    for info in VersionFileManager::detect_version_files(project_path).await? {
+        let version = info.version.trim().trim_start_matches('v').to_string();
+        // Aliases like `lts/*` aren't versions that can be locked
+        if version.is_empty() || version.contains(|c: char| c.is_whitespace() || c == '/') {
+            continue;
+        }
+        let source = info
+            .file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let authoritative = matches!(info.format, VersionFileFormat::PortalVersion);
+        if authoritative || !pins.contains_key(&info.sdk_type) {
+            pins.insert(info.sdk_type, (version, source));
+        }
+    }
+    Ok(pins)
+}
+
+/// The newest installed version matching a pin ("20" matches 20.11.1)
+fn resolve_installed(requested: &str, installed: &[String]) -> Option<String> {
+    installed
+        .iter()
+        .filter(|version| *version == requested || version.starts_with(&format!("{}.", requested)))
+        .max_by(|a, b| {
+            version_compare::compare(a, b)
+                .map(|cmp| cmp.ord().unwrap_or(std::cmp::Ordering::Equal))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_pins_and_round_trips() {
+        let installed = vec![
+            "18.19.0".to_string(),
+            "20.9.0".to_string(),
+            "20.11.1".to_string(),
+        ];
+        assert_eq!(
+            resolve_installed("20", &installed),
+            Some("20.11.1".to_string())
+        );
+        assert_eq!(
+            resolve_installed("18.19.0", &installed),
+            Some("18.19.0".to_string())
+        );
+        assert_eq!(resolve_installed("2", &installed), None);
+
+        let lock = PortalLock {
+            lock_version: LOCK_VERSION,
+            generated_at: "2024-05-01T00:00:00Z".to_string(),
+            platform: "linux-x64".to_string(),
+            sdks: vec![LockedSdk {
+                sdk_type: "nodejs".to_string(),
+                version: "20.11.1".to_string(),
+                requested: "20".to_string(),
+                source: ".nvmrc".to_string(),
+            }],
+            services: vec![LockedService {
+                service_type: "postgresql".to_string(),
+                name: "orders".to_string(),
+                port: Some(5433),
+                host: Some("127.0.0.1".to_string()),
+                username: Some("postgres".to_string()),
+                auth_enabled: false,
+            }],
+        };
+        let parsed: PortalLock = toml::from_str(&toml::to_string_pretty(&lock).unwrap()).unwrap();
+        assert_eq!(parsed.sdks, lock.sdks);
+        assert_eq!(parsed.services, lock.services);
+    }
+}
@@ -1,5 +1,9 @@
+pub mod asdf;
 pub mod environment_manager;
+pub mod lockfile;
 pub mod shell_integration;
+pub mod shims;
+pub mod toolchain;
 /**
  * Project-Level Version Isolation Module
  *
@@ -1,256 +1,256 @@
 /**
  * Shell Integration Module
  *
- * Provides shell integration for automatic version switching
+ * Provides shell integration for automatic version switching. Each shell
+ * gets a hook that runs on directory change, finds the nearest
+ * .portal-version and puts the pinned SDK installs first on PATH, undoing
+ * the previous directory's additions. The hooks live in Portal-owned files;
+ * shell configs only get a marked block that sources them, so installing
+ * again replaces the block and uninstalling removes it cleanly.
  */
+use super::super::download::installer::sdks_root;
 use super::super::SDKError;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+const BLOCK_START: &str = "# >>> portal-desktop >>>";
+const BLOCK_END: &str = "# <<< portal-desktop <<<";
+/// First line of the hook earlier versions appended to rc files directly
+const LEGACY_HOOK_MARKER: &str = "# Portal SDK Environment Hook";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[allow(clippy::enum_variant_names)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+}
+
+impl Shell {
+    pub const ALL: [Shell; 4] = [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellHookStatus {
+    pub shell: Shell,
+    /// The file the shell loads the hook from
+    pub config_file: PathBuf,
+    pub installed: bool,
+}
 
 pub struct ShellIntegration;
 
 impl ShellIntegration {
-    /// Setup shell integration for a project
-    pub async fn setup_shell_integration(
-        project_path: &Path,
-        environment: &HashMap<String, String>,
-    ) -> Result<(), SDKError> {
-        let shell = Self::detect_shell()?;
-
-        match shell.as_str() {
-            "zsh" => Self::setup_zsh_integration(project_path, environment).await,
-            "bash" => Self::setup_bash_integration(project_path, environment).await,
-            "fish" => Self::setup_fish_integration(project_path, environment).await,
-            _ => Err(SDKError::ManagerNotFound(format!(
-                "Unsupported shell: {}",
-                shell
-            ))),
-        }
-    }
-
-    /// Detect the current shell
-    fn detect_shell() -> Result<String, SDKError> {
-        if let Ok(shell) = std::env::var("SHELL") {
-            if shell.contains("zsh") {
-                return Ok("zsh".to_string());
-            } else if shell.contains("bash") {
-                return Ok("bash".to_string());
-            } else if shell.contains("fish") {
-                return Ok("fish".to_string());
+    /// Install (or refresh) the hooks for the given shells
+    pub async fn install_hooks(shells: &[Shell]) -> Result<Vec<ShellHookStatus>, SDKError> {
+        let sdks_root = sdks_root()?;
+        for shell in shells {
+            let hook = Self::generate_hook(*shell, &sdks_root);
+            let config_file = Self::config_file(*shell)?;
+
+            if *shell == Shell::Fish {
+                // conf.d files are loaded automatically
+                Self::write_file(&config_file, &hook)?;
+                continue;
             }
-        }
 
-        // Default to bash if detection fails
-        Ok("bash".to_string())
-    }
+            let hook_file = Self::hook_file(*shell)?;
+            Self::write_file(&hook_file, &hook)?;
 
-    /// Setup zsh integration
-    async fn setup_zsh_integration(
-        project_path: &Path,
-        environment: &HashMap<String, String>,
-    ) -> Result<(), SDKError> {
-        let hook_script = Self::generate_zsh_hook(project_path, environment);
-        let hook_file = dirs::home_dir()
-            .ok_or_else(|| SDKError::ManagerNotFound("Home directory not found".to_string()))?
-            .join(".zshrc");
-
-        Self::append_to_shell_config(&hook_file, &hook_script)?;
-        Ok(())
-    }
+            let content = fs::read_to_string(&config_file).unwrap_or_default();
+            let updated = upsert_block(&content, &Self::source_block(*shell, &hook_file));
+            if updated != content {
+                Self::write_file(&config_file, &updated)?;
+            }
+            println!(
+                "[ShellIntegration] Installed {:?} hook in {}",
+                shell,
+                config_file.display()
+            );
+        }
 
-    /// Setup bash integration
-    async fn setup_bash_integration(
-        project_path: &Path,
-        environment: &HashMap<String, String>,
-    ) -> Result<(), SDKError> {
-        let hook_script = Self::generate_bash_hook(project_path, environment);
-        let hook_file = dirs::home_dir()
-            .ok_or_else(|| SDKError::ManagerNotFound("Home directory not found".to_string()))?
-            .join(".bashrc");
-
-        Self::append_to_shell_config(&hook_file, &hook_script)?;
-        Ok(())
+        Self::hook_status()
     }
 
-    /// Setup fish integration
-    async fn setup_fish_integration(
-        project_path: &Path,
-        environment: &HashMap<String, String>,
-    ) -> Result<(), SDKError> {
-        let hook_script = Self::generate_fish_hook(project_path, environment);
-        let hook_file = dirs::config_dir()
-            .ok_or_else(|| SDKError::ManagerNotFound("Config directory not found".to_string()))?
-            .join("fish")
-            .join("conf.d")
-            .join("portal.fish");
-
-        fs::create_dir_all(hook_file.parent().unwrap())?;
-        fs::write(&hook_file, hook_script)?;
-        Ok(())
-    }
+    /// Remove the hooks for the given shells
+    pub async fn uninstall_hooks(shells: &[Shell]) -> Result<Vec<ShellHookStatus>, SDKError> {
+        for shell in shells {
+            let config_file = Self::config_file(*shell)?;
 
-    /// Generate zsh hook script
-    fn generate_zsh_hook(_project_path: &Path, environment: &HashMap<String, String>) -> String {
-        let mut script = String::new();
-        script.push_str("# Portal SDK Environment Hook\n");
-        script.push_str("portal_chpwd() {\n");
-        script.push_str("  if [[ -f .portal-version ]]; then\n");
-        script.push_str("    source .portal-version\n");
+            if *shell == Shell::Fish {
+                if config_file.exists() {
+                    fs::remove_file(&config_file)?;
+                }
+                continue;
+            }
 
-        for (key, value) in environment {
-            script.push_str(&format!("    export {}={}\n", key, value));
+            if config_file.exists() {
+                let content = fs::read_to_string(&config_file)?;
+                let updated = remove_block(&content);
+                if updated != content {
+                    fs::write(&config_file, updated)?;
+                }
+            }
+            let hook_file = Self::hook_file(*shell)?;
+            if hook_file.exists() {
+                fs::remove_file(&hook_file)?;
+            }
+            println!(
+                "[ShellIntegration] Removed {:?} hook from {}",
+                shell,
+                config_file.display()
+            );
         }
 
-        script.push_str("    echo \"Portal environment activated\"\n");
-        script.push_str("  else\n");
-        script.push_str("    # Deactivate portal environment\n");
-        script.push_str("    unset PORTAL_ACTIVE\n");
-        script.push_str("  fi\n");
-        script.push_str("}\n");
-        script.push_str("autoload -U add-zsh-hook\n");
-        script.push_str("add-zsh-hook chpwd portal_chpwd\n");
-        script.push_str("# Run on startup\n");
-        script.push_str("portal_chpwd\n");
-
-        script
+        Self::hook_status()
     }
 
-    /// Generate bash hook script
-    fn generate_bash_hook(_project_path: &Path, environment: &HashMap<String, String>) -> String {
-        let mut script = String::new();
-        script.push_str("# Portal SDK Environment Hook\n");
-        script.push_str("portal_chpwd() {\n");
-        script.push_str("  if [[ -f .portal-version ]]; then\n");
-        script.push_str("    source .portal-version\n");
-
-        for (key, value) in environment {
-            script.push_str(&format!("    export {}={}\n", key, value));
-        }
-
-        script.push_str("    echo \"Portal environment activated\"\n");
-        script.push_str("  else\n");
-        script.push_str("    # Deactivate portal environment\n");
-        script.push_str("    unset PORTAL_ACTIVE\n");
-        script.push_str("  fi\n");
-        script.push_str("}\n");
-        script.push_str("PROMPT_COMMAND=\"portal_chpwd; $PROMPT_COMMAND\"\n");
-        script.push_str("# Run on startup\n");
-        script.push_str("portal_chpwd\n");
-
-        script
+    /// Whether each shell's hook is installed
+    pub fn hook_status() -> Result<Vec<ShellHookStatus>, SDKError> {
+        Shell::ALL
+            .iter()
+            .map(|shell| {
+                let config_file = Self::config_file(*shell)?;
+                let installed = match shell {
+                    Shell::Fish => config_file.exists(),
+                    _ => fs::read_to_string(&config_file)
+                        .map(|content| content.contains(BLOCK_START))
+                        .unwrap_or(false),
+                };
+                Ok(ShellHookStatus {
+                    shell: *shell,
+                    config_file,
+                    installed,
+                })
+            })
+            .collect()
     }
 
-    /// Generate fish hook script
-    fn generate_fish_hook(_project_path: &Path, environment: &HashMap<String, String>) -> String {
-        let mut script = String::new();
-        script.push_str("# Portal SDK Environment Hook\n");
-        script.push_str("function portal_chpwd --on-variable PWD\n");
-        script.push_str("  if test -f .portal-version\n");
-        script.push_str("    source .portal-version\n");
-
-        for (key, value) in environment {
-            script.push_str(&format!("    set -gx {} {}\n", key, value));
-        }
-
-        script.push_str("    echo \"Portal environment activated\"\n");
-        script.push_str("  else\n");
-        script.push_str("    # Deactivate portal environment\n");
-        script.push_str("    set -e PORTAL_ACTIVE\n");
-        script.push_str("  end\n");
-        script.push_str("end\n");
-        script.push_str("# Run on startup\n");
-        script.push_str("portal_chpwd\n");
-
-        script
+    /// Shells worth hooking by default: the login shell, any shell with an
+    /// existing config, and PowerShell on Windows
+    pub fn default_shells() -> Vec<Shell> {
+        let current = Self::detect_shell();
+        Shell::ALL
+            .into_iter()
+            .filter(|shell| {
+                Some(*shell) == current
+                    || (*shell == Shell::PowerShell && cfg!(target_os = "windows"))
+                    || match shell {
+                        Shell::Fish => Self::config_file(*shell)
+                            .ok()
+                            .and_then(|file| file.parent().map(Path::is_dir))
+                            .unwrap_or(false),
+                        _ => Self::config_file(*shell)
+                            .map(|file| file.exists())
+                            .unwrap_or(false),
+                    }
+            })
+            .collect()
     }
 
-    /// Append script to shell config file
-    fn append_to_shell_config(config_file: &Path, script: &str) -> Result<(), SDKError> {
-        let mut content = if config_file.exists() {
-            fs::read_to_string(config_file)?
+    /// Detect the current shell
+    fn detect_shell() -> Option<Shell> {
+        let shell = std::env::var("SHELL").ok()?;
+        if shell.contains("zsh") {
+            Some(Shell::Zsh)
+        } else if shell.contains("bash") {
+            Some(Shell::Bash)
+        } else if shell.contains("fish") {
+            Some(Shell::Fish)
+        } else if shell.contains("pwsh") {
+            Some(Shell::PowerShell)
         } else {
-            String::new()
-        };
-
-        // Check if portal integration already exists
-        if content.contains("# Portal SDK Environment Hook") {
-            return Ok(()); // Already integrated
+            None
         }
+    }
 
-        content.push_str("\n");
-        content.push_str(script);
-        content.push_str("\n");
-
-        fs::write(config_file, content)?;
-        Ok(())
+    /// The config file the shell reads at startup
+    fn config_file(shell: Shell) -> Result<PathBuf, SDKError> {
+        let home = dirs::home_dir()
+            .ok_or_else(|| SDKError::ManagerNotFound("Home directory not found".to_string()))?;
+        // fish and PowerShell use ~/.config on every Unix, including macOS
+        let xdg_config = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".config"));
+
+        Ok(match shell {
+            Shell::Bash => home.join(".bashrc"),
+            Shell::Zsh => std::env::var_os("ZDOTDIR")
+                .map(PathBuf::from)
+                .unwrap_or(home)
+                .join(".zshrc"),
+            Shell::Fish => xdg_config.join("fish").join("conf.d").join("portal.fish"),
+            Shell::PowerShell if cfg!(target_os = "windows") => dirs::document_dir()
+                .unwrap_or_else(|| home.join("Documents"))
+                .join("PowerShell")
+                .join("Microsoft.PowerShell_profile.ps1"),
+            Shell::PowerShell => xdg_config
+                .join("powershell")
+                .join("Microsoft.PowerShell_profile.ps1"),
+        })
     }
 
-    /// Remove shell integration
-    pub async fn remove_shell_integration() -> Result<(), SDKError> {
-        let shell = Self::detect_shell()?;
-
-        match shell.as_str() {
-            "zsh" => Self::remove_zsh_integration().await,
-            "bash" => Self::remove_bash_integration().await,
-            "fish" => Self::remove_fish_integration().await,
-            _ => Err(SDKError::ManagerNotFound(format!(
-                "Unsupported shell: {}",
-                shell
-            ))),
-        }
+    /// Where the hook itself is kept, next to the SDK installs
+    fn hook_file(shell: Shell) -> Result<PathBuf, SDKError> {
+        let root = sdks_root()?;
+        let dir = root.parent().unwrap_or(&root).join("shell");
+        Ok(dir.join(match shell {
+            Shell::Bash => "portal.bash",
+            Shell::Zsh => "portal.zsh",
+            Shell::Fish => "portal.fish",
+            Shell::PowerShell => "portal.ps1",
+        }))
     }
 
-    /// Remove zsh integration
-    async fn remove_zsh_integration() -> Result<(), SDKError> {
-        let hook_file = dirs::home_dir()
-            .ok_or_else(|| SDKError::ManagerNotFound("Home directory not found".to_string()))?
-            .join(".zshrc");
-
-        if hook_file.exists() {
-            let content = fs::read_to_string(&hook_file)?;
-            let lines: Vec<&str> = content.lines().collect();
-            let filtered_lines: Vec<&str> = lines
-                .into_iter()
-                .filter(|line| !line.contains("# Portal SDK Environment Hook"))
-                .collect();
-
-            fs::write(&hook_file, filtered_lines.join("\n"))?;
-        }
-        Ok(())
+    fn source_block(shell: Shell, hook_file: &Path) -> String {
+        let line = match shell {
+            Shell::PowerShell => {
+                let path = ps_quote(&hook_file.to_string_lossy());
+                format!("if (Test-Path {}) {{ . {} }}", path, path)
+            }
+            _ => {
+                let path = sh_quote(&hook_file.to_string_lossy());
+                format!("[ -f {} ] && . {}", path, path)
+            }
+        };
+        format!("{}\n{}\n{}\n", BLOCK_START, line, BLOCK_END)
     }
 
-    /// Remove bash integration
-    async fn remove_bash_integration() -> Result<(), SDKError> {
-        let hook_file = dirs::home_dir()
-            .ok_or_else(|| SDKError::ManagerNotFound("Home directory not found".to_string()))?
-            .join(".bashrc");
-
-        if hook_file.exists() {
-            let content = fs::read_to_string(&hook_file)?;
-            let lines: Vec<&str> = content.lines().collect();
-            let filtered_lines: Vec<&str> = lines
-                .into_iter()
-                .filter(|line| !line.contains("# Portal SDK Environment Hook"))
-                .collect();
-
-            fs::write(&hook_file, filtered_lines.join("\n"))?;
+    fn write_file(path: &Path, content: &str) -> Result<(), SDKError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
         }
+        fs::write(path, content)?;
         Ok(())
     }
 
-    /// Remove fish integration
-    async fn remove_fish_integration() -> Result<(), SDKError> {
-        let hook_file = dirs::config_dir()
-            .ok_or_else(|| SDKError::ManagerNotFound("Config directory not found".to_string()))?
-            .join("fish")
-            .join("conf.d")
-            .join("portal.fish");
-
-        if hook_file.exists() {
-            fs::remove_file(&hook_file)?;
+    /// Generate the hook script for a shell
+    pub fn generate_hook(shell: Shell, sdks_root: &Path) -> String {
+        let root = sdks_root.to_string_lossy();
+        // The shims directory sits beside the SDK installs
+        let shims = sdks_root
+            .parent()
+            .unwrap_or(sdks_root)
+            .join("shims")
+            .to_string_lossy()
+            .to_string();
+        let posix_header = POSIX_HEADER
+            .replace("{root}", &sh_quote(&root))
+            .replace("{shims}", &sh_quote(&shims));
+        match shell {
+            Shell::Bash => format!("{}{}{}", posix_header, POSIX_APPLY, BASH_INSTALL),
+            Shell::Zsh => format!("{}{}{}", posix_header, POSIX_APPLY, ZSH_INSTALL),
+            Shell::Fish => FISH_HOOK
+                .replace("{root}", &fish_quote(&root))
+                .replace("{shims}", &fish_quote(&shims)),
+            Shell::PowerShell => POWERSHELL_HOOK
+                .replace("{root}", &ps_quote(&root))
+                .replace("{shims}", &ps_quote(&shims)),
         }
-        Ok(())
     }
 
     /// Check if shell integration is active
@@ -276,3 +276,321 @@ impl ShellIntegration {
         env
     }
 }
+
+/// Replace the managed block in a config file, or append it. Hooks from
+/// earlier versions are dropped on the way.
+fn upsert_block(content: &str, block: &str) -> String {
+    let mut updated = remove_block(content);
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    if !updated.is_empty() && !updated.ends_with("\n\n") {
+        updated.push('\n');
+    }
+    updated.push_str(block);
+    updated
+}
+
+/// Drop the managed block (and any legacy hook) from a config file
+fn remove_block(content: &str) -> String {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() == BLOCK_START {
+            for inner in lines.by_ref() {
+                if inner.trim() == BLOCK_END {
+                    break;
+                }
+            }
+            continue;
+        }
+        if line.trim() == LEGACY_HOOK_MARKER {
+            // The legacy hook ran itself last thing
+            for inner in lines.by_ref() {
+                if inner.trim() == "portal_chpwd" {
+                    break;
+                }
+            }
+            continue;
+        }
+        kept.push(line);
+    }
+
+    while kept.last().is_some_and(|line| line.trim().is_empty()) {
+        kept.pop();
+    }
+    if kept.is_empty() {
+        return String::new();
+    }
+    let mut result = kept.join("\n");
+    result.push('\n');
+    result
+}
+
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+const POSIX_HEADER: &str = r#"# Portal Desktop SDK hook - generated, changes are overwritten
+__portal_sdks_root={root}
+
+# Shims resolve the pinned version per command, even outside this shell
+case ":$PATH:" in
+  *:{shims}:*) ;;
+  *) PATH={shims}:"$PATH"; export PATH ;;
+esac
+
+__portal_version_file() {
+  __portal_dir="$PWD"
+  while [ -n "$__portal_dir" ]; do
+    if [ -f "$__portal_dir/.portal-version" ]; then
+      printf '%s\n' "$__portal_dir/.portal-version"
+      return 0
+    fi
+    [ "$__portal_dir" = "/" ] && break
+    __portal_dir="$(dirname "$__portal_dir")"
+  done
+  return 1
+}
+"#;
+
+const POSIX_APPLY: &str = r#"
+__portal_apply() {
+  [ "$PWD" = "$__portal_last_pwd" ] && return
+  __portal_last_pwd="$PWD"
+
+  # Undo the previous directory's additions
+  if [ -n "$__portal_path_added" ]; then
+    PATH="${PATH#"$__portal_path_added":}"
+    __portal_path_added=""
+  fi
+
+  local file pairs added sdk version home bin
+  if ! file="$(__portal_version_file)"; then
+    unset PORTAL_ACTIVE
+    export PATH
+    return
+  fi
+
+  # "sdk version" per line from the [versions] table
+  pairs="$(awk '/^[[:space:]]*\[/ { s = ($0 ~ /^[[:space:]]*\[versions\]/); next }
+                s && index($0, "=") { k = substr($0, 1, index($0, "=") - 1); v = substr($0, index($0, "=") + 1)
+                                      gsub(/[[:space:]"\047]/, "", k); gsub(/[[:space:]"\047]/, "", v); print k, v }' "$file")"
+
+  while read -r sdk version; do
+    [ -n "$sdk" ] && [ -n "$version" ] || continue
+    [ "$sdk" = "node" ] && sdk="nodejs"
+    home="$__portal_sdks_root/$sdk/$version"
+    if [ ! -d "$home" ]; then
+      # Allow "20" to match the newest installed 20.x
+      home="$(ls -1 "$__portal_sdks_root/$sdk" 2>/dev/null | awk -v p="$version." 'index($0, p) == 1' | tail -n 1)"
+      [ -n "$home" ] || continue
+      home="$__portal_sdks_root/$sdk/$home"
+    fi
+    for bin in "$home/bin" "$home/Contents/Home/bin" "$home"; do
+      if [ -d "$bin" ]; then
+        added="${added:+$added:}$bin"
+        break
+      fi
+    done
+  done <<EOF
+$pairs
+EOF
+
+  if [ -n "$added" ]; then
+    PATH="$added:$PATH"
+    __portal_path_added="$added"
+    export PORTAL_ACTIVE=1
+  else
+    unset PORTAL_ACTIVE
+  fi
+  export PATH
+}
+"#;
+
+const BASH_INSTALL: &str = r#"
+case ";${PROMPT_COMMAND};" in
+  *";__portal_apply;"*) ;;
+  *) PROMPT_COMMAND="__portal_apply${PROMPT_COMMAND:+;$PROMPT_COMMAND}" ;;
+esac
+__portal_apply
+"#;
+
+const ZSH_INSTALL: &str = r#"
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd __portal_apply
+__portal_apply
+"#;
+
+const FISH_HOOK: &str = r#"# Portal Desktop SDK hook - generated, changes are overwritten
+set -g __portal_sdks_root {root}
+
+# Shims resolve the pinned version per command, even outside this shell
+contains -- {shims} $PATH; or set -gx PATH {shims} $PATH
+
+function __portal_apply --on-variable PWD
+    # Undo the previous directory's additions
+    for entry in $__portal_path_added
+        if set -l index (contains -i -- $entry $PATH)
+            set -e PATH[$index]
+        end
+    end
+    set -g __portal_path_added
+
+    set -l dir $PWD
+    set -l file
+    while true
+        if test -f "$dir/.portal-version"
+            set file "$dir/.portal-version"
+            break
+        end
+        test "$dir" = /; and break
+        set dir (dirname $dir)
+    end
+    if test -z "$file"
+        set -e PORTAL_ACTIVE
+        return
+    end
+
+    set -l added
+    for line in (awk '/^[[:space:]]*\[/ { s = ($0 ~ /^[[:space:]]*\[versions\]/); next }
+                      s && index($0, "=") { k = substr($0, 1, index($0, "=") - 1); v = substr($0, index($0, "=") + 1);
+                                            gsub(/[[:space:]"\047]/, "", k); gsub(/[[:space:]"\047]/, "", v); print k, v }' $file)
+        set -l parts (string split ' ' -- $line)
+        set -l sdk $parts[1]
+        set -l ver $parts[2]
+        test "$sdk" = node; and set sdk nodejs
+        set -l home "$__portal_sdks_root/$sdk/$ver"
+        if not test -d $home
+            # Allow "20" to match the newest installed 20.x
+            set -l match (ls -1 "$__portal_sdks_root/$sdk" 2>/dev/null | awk -v p="$ver." 'index($0, p) == 1' | tail -n 1)
+            test -n "$match"; or continue
+            set home "$__portal_sdks_root/$sdk/$match"
+        end
+        for bin in $home/bin $home/Contents/Home/bin $home
+            if test -d $bin
+                set -a added $bin
+                break
+            end
+        end
+    end
+
+    if set -q added[1]
+        set -gx PATH $added $PATH
+        set -g __portal_path_added $added
+        set -gx PORTAL_ACTIVE 1
+    else
+        set -e PORTAL_ACTIVE
+    end
+end
+
+__portal_apply
+"#;
+
+const POWERSHELL_HOOK: &str = r#"# Portal Desktop SDK hook - generated, changes are overwritten
+$global:PortalSdksRoot = {root}
+
+# Shims resolve the pinned version per command, even outside this shell
+if (($env:PATH -split [regex]::Escape([IO.Path]::PathSeparator)) -notcontains {shims}) {
+    $env:PATH = {shims} + [IO.Path]::PathSeparator + $env:PATH
+}
+
+function global:Invoke-PortalHook {
+    if ($global:PortalLastPwd -eq $PWD.ProviderPath) { return }
+    $global:PortalLastPwd = $PWD.ProviderPath
+    $separator = [IO.Path]::PathSeparator
+
+    # Undo the previous directory's additions
+    if ($global:PortalPathAdded) {
+        $env:PATH = ($env:PATH -split [regex]::Escape($separator) |
+            Where-Object { $global:PortalPathAdded -notcontains $_ }) -join $separator
+        $global:PortalPathAdded = @()
+    }
+
+    $dir = $PWD.ProviderPath
+    $file = $null
+    while ($dir) {
+        $candidate = Join-Path $dir '.portal-version'
+        if (Test-Path -LiteralPath $candidate -PathType Leaf) { $file = $candidate; break }
+        $dir = Split-Path -Parent $dir
+    }
+    if (-not $file) {
+        Remove-Item Env:PORTAL_ACTIVE -ErrorAction SilentlyContinue
+        return
+    }
+
+    $added = @()
+    $inVersions = $false
+    foreach ($line in Get-Content -LiteralPath $file) {
+        $line = $line.Trim()
+        if ($line.StartsWith('[')) { $inVersions = $line -eq '[versions]'; continue }
+        if (-not $inVersions -or $line -notmatch '^([^=\s]+)\s*=\s*["'']?([^"'']+)["'']?') { continue }
+        $sdk = $Matches[1]
+        $version = $Matches[2].Trim()
+        if ($sdk -eq 'node') { $sdk = 'nodejs' }
+        $sdkDir = Join-Path $global:PortalSdksRoot $sdk
+        $sdkHome = Join-Path $sdkDir $version
+        if (-not (Test-Path -LiteralPath $sdkHome)) {
+            # Allow "20" to match the newest installed 20.x
+            $match = Get-ChildItem -LiteralPath $sdkDir -Directory -ErrorAction SilentlyContinue |
+                Where-Object { $_.Name.StartsWith("$version.") } | Sort-Object Name | Select-Object -Last 1
+            if (-not $match) { continue }
+            $sdkHome = $match.FullName
+        }
+        foreach ($bin in @((Join-Path $sdkHome 'bin'), (Join-Path $sdkHome 'Contents/Home/bin'), $sdkHome)) {
+            if (Test-Path -LiteralPath $bin -PathType Container) { $added += $bin; break }
+        }
+    }
+
+    if ($added.Count -gt 0) {
+        $env:PATH = (@($added) + $env:PATH) -join $separator
+        $env:PORTAL_ACTIVE = '1'
+    } else {
+        Remove-Item Env:PORTAL_ACTIVE -ErrorAction SilentlyContinue
+    }
+    $global:PortalPathAdded = $added
+}
+
+if (-not $global:PortalOriginalPrompt) { $global:PortalOriginalPrompt = $function:prompt }
+function global:prompt {
+    Invoke-PortalHook
+    & $global:PortalOriginalPrompt
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reinstalling_replaces_the_block_and_uninstall_removes_it() {
+        let rc = "export EDITOR=vim\n\n# Portal SDK Environment Hook\nportal_chpwd() {\n  true\n}\n# Run on startup\nportal_chpwd\nalias ll='ls -l'\n";
+        let block = ShellIntegration::source_block(
+            Shell::Bash,
+            Path::new("/home/me/.portal-desktop/shell/portal.bash"),
+        );
+
+        let installed = upsert_block(rc, &block);
+        assert_eq!(
+            installed,
+            format!("export EDITOR=vim\n\nalias ll='ls -l'\n\n{}", block)
+        );
+        assert_eq!(upsert_block(&installed, &block), installed);
+        assert_eq!(
+            remove_block(&installed),
+            "export EDITOR=vim\n\nalias ll='ls -l'\n"
+        );
+
+        let hook = ShellIntegration::generate_hook(Shell::Zsh, Path::new("/home/o'neil/sdks"));
+        assert!(hook.contains(r"__portal_sdks_root='/home/o'\''neil/sdks'"));
+        assert!(hook.contains("add-zsh-hook chpwd __portal_apply"));
+    }
+}
@@ -0,0 +1,502 @@
+/**
+ * SDK Shims
+ *
+ * asdf-style shims for directly installed SDKs. `~/.portal-desktop/shims`
+ * holds one tiny script per executable (`node`, `python3`, `cargo`, ...)
+ * that hands off to a shared resolver. At invocation time the resolver
+ * walks up from the working directory to the nearest `.portal-version`
+ * (or asdf `.tool-versions`) pinning that SDK, falls back to the global default in
+ * `~/.portal-desktop/.portal-version`, and execs the matching install.
+ * The shims directory goes on PATH once; switching versions only rewrites
+ * a version file, so it takes effect on the next command.
+ */
+use super::shell_integration::ShellIntegration;
+use super::version_file::VersionFileManager;
+use crate::domains::sdk::download::installer::{
+    bin_dir, sdks_root, SdkInstaller, DIRECT_INSTALL_SDKS,
+};
+use crate::domains::sdk::SDKError;
+use crate::process_ext::NoWindowExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Second line of every shim, so stale ones can be told from user files
+const SHIM_MARKER: &str = "Portal Desktop shim";
+const RESOLVER: &str = ".portal-resolve";
+const POWERSHELL_RESOLVER: &str = ".portal-resolve.ps1";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shim {
+    pub name: String,
+    pub sdk_type: String,
+}
+
+pub struct ShimManager {
+    sdks_root: PathBuf,
+    shims_dir: PathBuf,
+    /// Holds the `.portal-version` with the global defaults
+    global_dir: PathBuf,
+}
+
+impl ShimManager {
+    pub fn new() -> Result<Self, SDKError> {
+        let sdks_root = sdks_root()?;
+        let base = sdks_root.parent().unwrap_or(&sdks_root).to_path_buf();
+        Ok(Self {
+            shims_dir: base.join("shims"),
+            global_dir: base,
+            sdks_root,
+        })
+    }
+
+    pub fn shims_dir(&self) -> &Path {
+        &self.shims_dir
+    }
+
+    /// Regenerate the shims for every executable of every installed SDK,
+    /// dropping shims whose executables are gone
+    pub fn rehash(&self) -> Result<Vec<Shim>, SDKError> {
+        fs::create_dir_all(&self.shims_dir)?;
+        let shims = plan_shims(self.installed_executables());
+
+        if cfg!(target_os = "windows") {
+            self.write_shim_file(
+                POWERSHELL_RESOLVER,
+                &self.render(POWERSHELL_RESOLVER_SCRIPT, ps_quote),
+            )?;
+        } else {
+            self.write_shim_file(RESOLVER, &self.render(POSIX_RESOLVER_SCRIPT, sh_quote))?;
+        }
+
+        let mut wanted = Vec::new();
+        for shim in &shims {
+            let file_name = shim_file_name(&shim.name);
+            self.write_shim_file(&file_name, &self.shim_script(shim))?;
+            wanted.push(file_name);
+        }
+
+        for entry in fs::read_dir(&self.shims_dir)?.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.starts_with('.') || wanted.contains(&file_name) {
+                continue;
+            }
+            let is_ours = fs::read_to_string(entry.path())
+                .is_ok_and(|content| content.lines().take(2).any(|l| l.contains(SHIM_MARKER)));
+            if is_ours {
+                fs::remove_file(entry.path())?;
+            }
+        }
+
+        println!(
+            "[SDK] Rehashed {} shims in {}",
+            shims.len(),
+            self.shims_dir.display()
+        );
+        Ok(shims)
+    }
+
+    /// Shims that would be generated for an SDK
+    pub fn shims_for(&self, sdk_type: &str) -> Vec<String> {
+        plan_shims(self.installed_executables())
+            .into_iter()
+            .filter(|shim| shim.sdk_type == sdk_type)
+            .map(|shim| shim.name)
+            .collect()
+    }
+
+    /// Make `version` the default outside projects that pin one. Accepts
+    /// a prefix ("20") of an installed version. Returns the version set.
+    pub async fn set_global_version(
+        &self,
+        sdk_type: &str,
+        version: &str,
+    ) -> Result<String, SDKError> {
+        let installed = SdkInstaller::new()?.list_installed(sdk_type);
+        let resolved = matching_installed(&installed, version).ok_or_else(|| {
+            SDKError::VersionNotFound(format!(
+                "{} {} is not installed (installed: {})",
+                sdk_type,
+                version,
+                if installed.is_empty() {
+                    "none".to_string()
+                } else {
+                    installed.join(", ")
+                }
+            ))
+        })?;
+
+        fs::create_dir_all(&self.global_dir)?;
+        VersionFileManager::update_version(&self.global_dir, sdk_type, version).await?;
+        Ok(resolved.clone())
+    }
+
+    pub async fn global_versions(&self) -> HashMap<String, String> {
+        VersionFileManager::read_version_file(&self.global_dir)
+            .await
+            .map(|env| env.versions)
+            .unwrap_or_default()
+    }
+
+    /// Whether this process's PATH already has the shims
+    pub fn is_on_path(&self) -> bool {
+        std::env::var_os("PATH")
+            .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir == self.shims_dir))
+    }
+
+    /// Put the shims directory on PATH for new shells: through the shell
+    /// hooks on Unix, and the user's PATH on Windows
+    pub async fn ensure_on_path(&self) -> Result<(), SDKError> {
+        if !cfg!(target_os = "windows") {
+            ShellIntegration::install_hooks(&ShellIntegration::default_shells()).await?;
+            return Ok(());
+        }
+
+        let script = format!(
+            "$dir = {dir}; $path = [Environment]::GetEnvironmentVariable('Path', 'User'); \
+             if (-not (($path -split ';') -contains $dir)) {{ \
+             [Environment]::SetEnvironmentVariable('Path', (@($dir) + @($path -split ';' | Where-Object {{ $_ }})) -join ';', 'User') }}",
+            dir = ps_quote(&self.shims_dir.to_string_lossy())
+        );
+        let output = tokio::process::Command::new("powershell")
+            .no_window()
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(SDKError::CommandFailed(format!(
+                "Failed to add {} to PATH: {}",
+                self.shims_dir.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// (sdk, executable name) for every installed version, in SDK order
+    fn installed_executables(&self) -> Vec<(String, String)> {
+        let Ok(installer) = SdkInstaller::new() else {
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        for sdk in DIRECT_INSTALL_SDKS {
+            for version in installer.list_installed(sdk) {
+                let bin = bin_dir(sdk, &installer.install_dir(sdk, &version));
+                let Ok(entries) = fs::read_dir(&bin) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    if let Some(name) = executable_name(&entry.path()) {
+                        found.push((sdk.to_string(), name));
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    fn render(&self, template: &str, quote: fn(&str) -> String) -> String {
+        template
+            .replace("{root}", &quote(&self.sdks_root.to_string_lossy()))
+            .replace("{shims}", &quote(&self.shims_dir.to_string_lossy()))
+            .replace(
+                "{global}",
+                &quote(&self.global_dir.join(".portal-version").to_string_lossy()),
+            )
+    }
+
+    fn shim_script(&self, shim: &Shim) -> String {
+        if cfg!(target_os = "windows") {
+            format!(
+                "@echo off\r\nrem {marker} - generated, changes are overwritten\r\n\
+                 powershell -NoProfile -ExecutionPolicy Bypass -File \"{resolver}\" {sdk} {name} %*\r\n\
+                 exit /b %ERRORLEVEL%\r\n",
+                marker = SHIM_MARKER,
+                resolver = self.shims_dir.join(POWERSHELL_RESOLVER).display(),
+                sdk = shim.sdk_type,
+                name = shim.name
+            )
+        } else {
+            format!(
+                "#!/bin/sh\n# {marker} - generated, changes are overwritten\nexec /bin/sh {resolver} {sdk} {name} \"$@\"\n",
+                marker = SHIM_MARKER,
+                resolver = sh_quote(&self.shims_dir.join(RESOLVER).to_string_lossy()),
+                sdk = sh_quote(&shim.sdk_type),
+                name = sh_quote(&shim.name)
+            )
+        }
+    }
+
+    fn write_shim_file(&self, file_name: &str, content: &str) -> Result<(), SDKError> {
+        let path = self.shims_dir.join(file_name);
+        fs::write(&path, content)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(())
+    }
+}
+
+/// One shim per executable name; when two SDKs ship the same name the
+/// first SDK in install order keeps it
+fn plan_shims(found: Vec<(String, String)>) -> Vec<Shim> {
+    let mut shims: BTreeMap<String, String> = BTreeMap::new();
+    for (sdk_type, name) in found {
+        shims.entry(name).or_insert(sdk_type);
+    }
+    shims
+        .into_iter()
+        .map(|(name, sdk_type)| Shim { name, sdk_type })
+        .collect()
+}
+
+/// The command name for an executable file, if `path` is one
+fn executable_name(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    if cfg!(target_os = "windows") {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        if !matches!(extension.as_str(), "exe" | "cmd" | "bat") {
+            return None;
+        }
+        return Some(path.file_stem()?.to_str()?.to_string());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return None;
+        }
+    }
+    Some(path.file_name()?.to_str()?.to_string())
+}
+
+/// The installed version `version` names: itself, or the newest one it's a
+/// prefix of ("20" for 20.x)
+pub(crate) fn matching_installed<'a>(installed: &'a [String], version: &str) -> Option<&'a String> {
+    installed
+        .iter()
+        .filter(|v| *v == version || v.starts_with(&format!("{}.", version)))
+        .max_by_key(|v| version_key(v))
+}
+
+/// Numeric ordering for version directories, so 20.11 sorts after 20.9
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+fn shim_file_name(name: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("{}.cmd", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+const POSIX_RESOLVER_SCRIPT: &str = r#"#!/bin/sh
+# Portal Desktop shim resolver - generated, changes are overwritten
+sdks_root={root}
+shims_dir={shims}
+global_file={global}
+sdk="$1"
+name="$2"
+shift 2
+
+# The version pinned for $sdk in a .portal-version [versions] table
+pinned() {
+  [ -f "$1" ] || return 0
+  awk -v want="$sdk" '/^[[:space:]]*\[/ { s = ($0 ~ /^[[:space:]]*\[versions\]/); next }
+       s && index($0, "=") { k = substr($0, 1, index($0, "=") - 1); v = substr($0, index($0, "=") + 1)
+                             gsub(/[[:space:]"\047]/, "", k); gsub(/[[:space:]"\047]/, "", v)
+                             if (k == "node") k = "nodejs"
+                             if (k == want && v != "") { print v; exit } }' "$1"
+}
+
+# The first version listed for $sdk in an asdf .tool-versions
+tool_pinned() {
+  [ -f "$1" ] || return 0
+  awk -v want="$sdk" '{ sub(/#.*/, "") } NF >= 2 { k = $1; if (k == "golang") k = "go"; if (k == "node") k = "nodejs"
+                       if (k == want && $2 != "system" && $2 !~ /^(ref|path):/) { print $2; exit } }' "$1"
+}
+
+version=""
+dir="$PWD"
+while [ -n "$dir" ]; do
+  version="$(pinned "$dir/.portal-version")"
+  [ -n "$version" ] && break
+  version="$(tool_pinned "$dir/.tool-versions")"
+  [ -n "$version" ] && break
+  [ "$dir" = "/" ] && break
+  dir="$(dirname "$dir")"
+done
+[ -n "$version" ] || version="$(pinned "$global_file")"
+
+if [ -n "$version" ]; then
+  home="$sdks_root/$sdk/$version"
+  if [ ! -d "$home" ]; then
+    # Allow "20" to match the newest installed 20.x
+    match="$(ls -1 "$sdks_root/$sdk" 2>/dev/null | awk -v p="$version." 'index($0, p) == 1' |
+             sort -t. -k1,1n -k2,2n -k3,3n | tail -n 1)"
+    [ -n "$match" ] && home="$sdks_root/$sdk/$match"
+  fi
+  for bin in "$home/bin" "$home/Contents/Home/bin" "$home"; do
+    if [ -f "$bin/$name" ] && [ -x "$bin/$name" ]; then
+      exec "$bin/$name" "$@"
+    fi
+  done
+  echo "portal: $name is not available in $sdk $version; install it in Portal Desktop" >&2
+  exit 127
+fi
+
+# Nothing pinned: use whichever $name comes next on PATH
+old_ifs="$IFS"
+IFS=:
+for dir in $PATH; do
+  [ "$dir" = "$shims_dir" ] && continue
+  if [ -f "$dir/$name" ] && [ -x "$dir/$name" ]; then
+    IFS="$old_ifs"
+    exec "$dir/$name" "$@"
+  fi
+done
+IFS="$old_ifs"
+echo "portal: no $sdk version is set for $PWD; pin one in .portal-version or set a default" >&2
+exit 127
+"#;
+
+const POWERSHELL_RESOLVER_SCRIPT: &str = r#"# Portal Desktop shim resolver - generated, changes are overwritten
+param([string]$Sdk, [string]$Name)
+$SdksRoot = {root}
+$ShimsDir = {shims}
+$GlobalFile = {global}
+$Extensions = @('.exe', '.cmd', '.bat', '')
+
+# The version pinned for $Sdk in a .portal-version [versions] table
+function Get-PortalPinned([string]$File) {
+    if (-not (Test-Path -LiteralPath $File -PathType Leaf)) { return $null }
+    $inVersions = $false
+    foreach ($line in Get-Content -LiteralPath $File) {
+        $line = $line.Trim()
+        if ($line.StartsWith('[')) { $inVersions = $line -eq '[versions]'; continue }
+        if ($inVersions -and $line -match '^([^=\s]+)\s*=\s*["'']?([^"'']+)["'']?') {
+            $key = $Matches[1]
+            if ($key -eq 'node') { $key = 'nodejs' }
+            if ($key -eq $Sdk) { return $Matches[2].Trim() }
+        }
+    }
+    return $null
+}
+
+# The first version listed for $Sdk in an asdf .tool-versions
+function Get-ToolPinned([string]$File) {
+    if (-not (Test-Path -LiteralPath $File -PathType Leaf)) { return $null }
+    foreach ($line in Get-Content -LiteralPath $File) {
+        $fields = @(($line -replace '#.*$', '').Trim() -split '\s+' | Where-Object { $_ })
+        if ($fields.Count -lt 2) { continue }
+        $key = $fields[0]
+        if ($key -eq 'golang') { $key = 'go' }
+        if ($key -eq 'node') { $key = 'nodejs' }
+        if ($key -eq $Sdk -and $fields[1] -ne 'system' -and $fields[1] -notmatch '^(ref|path):') { return $fields[1] }
+    }
+    return $null
+}
+
+function Invoke-PortalTarget([string]$Dir) {
+    foreach ($extension in $Extensions) {
+        $candidate = Join-Path $Dir "$Name$extension"
+        if (Test-Path -LiteralPath $candidate -PathType Leaf) {
+            & $candidate @args
+            exit $LASTEXITCODE
+        }
+    }
+}
+
+$version = $null
+$dir = (Get-Location).ProviderPath
+while ($dir) {
+    $version = Get-PortalPinned (Join-Path $dir '.portal-version')
+    if ($version) { break }
+    $version = Get-ToolPinned (Join-Path $dir '.tool-versions')
+    if ($version) { break }
+    $dir = Split-Path -Parent $dir
+}
+if (-not $version) { $version = Get-PortalPinned $GlobalFile }
+
+if ($version) {
+    $sdkDir = Join-Path $SdksRoot $Sdk
+    $sdkHome = Join-Path $sdkDir $version
+    if (-not (Test-Path -LiteralPath $sdkHome)) {
+        # Allow "20" to match the newest installed 20.x
+        $match = Get-ChildItem -LiteralPath $sdkDir -Directory -ErrorAction SilentlyContinue |
+            Where-Object { $_.Name.StartsWith("$version.") } | Sort-Object Name | Select-Object -Last 1
+        if ($match) { $sdkHome = $match.FullName }
+    }
+    foreach ($bin in @((Join-Path $sdkHome 'bin'), $sdkHome)) {
+        Invoke-PortalTarget $bin @args
+    }
+    [Console]::Error.WriteLine("portal: $Name is not available in $Sdk $version; install it in Portal Desktop")
+    exit 127
+}
+
+# Nothing pinned: use whichever $Name comes next on PATH
+foreach ($dir in $env:PATH -split ';') {
+    if (-not $dir -or $dir.TrimEnd('\') -eq $ShimsDir.TrimEnd('\')) { continue }
+    Invoke-PortalTarget $dir @args
+}
+[Console]::Error.WriteLine("portal: no $Sdk version is set for $PWD; pin one in .portal-version or set a default")
+exit 127
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plans_one_shim_per_executable() {
+        let found = vec![
+            ("nodejs".to_string(), "node".to_string()),
+            ("nodejs".to_string(), "npm".to_string()),
+            ("nodejs".to_string(), "node".to_string()),
+            ("python".to_string(), "python3".to_string()),
+            ("go".to_string(), "npm".to_string()),
+        ];
+        let shims = plan_shims(found);
+        assert_eq!(
+            shims,
+            vec![
+                Shim {
+                    name: "node".to_string(),
+                    sdk_type: "nodejs".to_string()
+                },
+                Shim {
+                    name: "npm".to_string(),
+                    sdk_type: "nodejs".to_string()
+                },
+                Shim {
+                    name: "python3".to_string(),
+                    sdk_type: "python".to_string()
+                },
+            ]
+        );
+        assert!(version_key("20.11.1") > version_key("20.9.0"));
+        assert_eq!(
+            sh_quote("/home/o'neil/.portal-desktop"),
+            r"'/home/o'\''neil/.portal-desktop'"
+        );
+    }
+}
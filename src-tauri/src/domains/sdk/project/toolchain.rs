@@ -0,0 +1,194 @@
+/**
+ * Project Toolchains
+ *
+ * The SDK versions a project pins, resolved to direct installs the way the
+ * shims resolve them in its terminals: the nearest `.portal-version` or
+ * `.tool-versions` (or a standard file such as `.nvmrc`) in the project or
+ * a directory above it, else the global default. Processes the app starts
+ * outside a shell, such as pipeline steps, get the matching installs put
+ * first on PATH instead of relying on the shims being set up.
+ */
+use super::shims::{matching_installed, ShimManager};
+use super::version_file::VersionFileManager;
+use crate::domains::sdk::download::installer::{bin_dir, SdkInstaller, DIRECT_INSTALL_SDKS};
+use crate::domains::sdk::SDKError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedSdk {
+    pub sdk_type: String,
+    /// Version as pinned, e.g. "20" or "v20.11.1"
+    pub requested: String,
+    /// Installed version it resolved to
+    pub version: String,
+    pub bin_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectToolchain {
+    pub sdks: Vec<ResolvedSdk>,
+    /// Pinned versions that aren't installed, as "<sdk> <version>"
+    pub missing: Vec<String>,
+}
+
+impl ProjectToolchain {
+    pub async fn resolve(project_path: &Path) -> Result<Self, SDKError> {
+        let installer = SdkInstaller::new()?;
+        let mut toolchain = Self::default();
+        for (sdk_type, requested) in pinned_versions(project_path).await {
+            if !DIRECT_INSTALL_SDKS.contains(&sdk_type.as_str()) {
+                continue;
+            }
+            // Aliases such as lts/iron or stable are left to version managers
+            let Some(version) = normalize_version(&requested) else {
+                continue;
+            };
+            match matching_installed(&installer.list_installed(&sdk_type), version) {
+                Some(installed) => toolchain.sdks.push(ResolvedSdk {
+                    bin_dir: bin_dir(&sdk_type, &installer.install_dir(&sdk_type, installed)),
+                    version: installed.clone(),
+                    sdk_type,
+                    requested,
+                }),
+                None => toolchain
+                    .missing
+                    .push(format!("{} {}", sdk_type, requested)),
+            }
+        }
+        Ok(toolchain)
+    }
+
+    /// Env vars that select the toolchain: PATH with each SDK's executables
+    /// ahead of `path`, plus the home variables some SDKs need
+    pub fn env(&self, path: Option<OsString>) -> HashMap<String, String> {
+        let mut env = HashMap::new();
+        if self.sdks.is_empty() {
+            return env;
+        }
+        let dirs = self
+            .sdks
+            .iter()
+            .map(|sdk| sdk.bin_dir.clone())
+            .chain(path.iter().flat_map(std::env::split_paths));
+        if let Ok(joined) = std::env::join_paths(dirs) {
+            env.insert("PATH".to_string(), joined.to_string_lossy().to_string());
+        }
+        for sdk in &self.sdks {
+            let home_var = match sdk.sdk_type.as_str() {
+                "java" => "JAVA_HOME",
+                "go" => "GOROOT",
+                _ => continue,
+            };
+            if let Some(home) = sdk.bin_dir.parent() {
+                env.insert(home_var.to_string(), home.to_string_lossy().to_string());
+            }
+        }
+        env
+    }
+
+    /// One line for logs, e.g. "nodejs 20.11.1, go 1.22.0"
+    pub fn summary(&self) -> String {
+        self.sdks
+            .iter()
+            .map(|sdk| format!("{} {}", sdk.sdk_type, sdk.version))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// SDK type -> version for every SDK pinned for `project_path`; the nearest
+/// pin wins, then the global default
+async fn pinned_versions(project_path: &Path) -> BTreeMap<String, String> {
+    let mut pins = BTreeMap::new();
+    for dir in project_path.ancestors() {
+        // .portal-version comes first, then .tool-versions, then the rest
+        if let Ok(files) = VersionFileManager::detect_version_files(dir).await {
+            for file in files {
+                pins.entry(sdk_type_for(&file.sdk_type))
+                    .or_insert(file.version);
+            }
+        }
+    }
+    if let Ok(shims) = ShimManager::new() {
+        for (sdk_type, version) in shims.global_versions().await {
+            pins.entry(sdk_type_for(&sdk_type)).or_insert(version);
+        }
+    }
+    pins
+}
+
+fn sdk_type_for(pinned: &str) -> String {
+    match pinned {
+        "node" => "nodejs".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// The version number in a pin ("v20.11.1" -> "20.11.1"), or None for an
+/// alias that names no particular version
+fn normalize_version(pinned: &str) -> Option<&str> {
+    let version = pinned.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_path_and_homes_for_resolved_sdks() {
+        assert_eq!(normalize_version(" v20.11.1\n"), Some("20.11.1"));
+        assert_eq!(normalize_version("lts/iron"), None);
+        assert_eq!(normalize_version("stable"), None);
+
+        let installed = vec!["20.9.0".to_string(), "20.11.1".to_string()];
+        assert_eq!(
+            matching_installed(&installed, "20").map(String::as_str),
+            Some("20.11.1")
+        );
+        assert_eq!(matching_installed(&installed, "2"), None);
+
+        let root = PathBuf::from("sdks");
+        let toolchain = ProjectToolchain {
+            sdks: vec![
+                ResolvedSdk {
+                    sdk_type: "go".to_string(),
+                    requested: "1.22".to_string(),
+                    version: "1.22.0".to_string(),
+                    bin_dir: root.join("go").join("1.22.0").join("bin"),
+                },
+                ResolvedSdk {
+                    sdk_type: "nodejs".to_string(),
+                    requested: "20".to_string(),
+                    version: "20.11.1".to_string(),
+                    bin_dir: root.join("nodejs").join("20.11.1").join("bin"),
+                },
+            ],
+            missing: Vec::new(),
+        };
+        let base = std::env::join_paths([PathBuf::from("usr").join("bin")]).unwrap();
+        let env = toolchain.env(Some(base));
+        let path: Vec<PathBuf> = std::env::split_paths(&env["PATH"]).collect();
+        assert_eq!(
+            path,
+            vec![
+                root.join("go").join("1.22.0").join("bin"),
+                root.join("nodejs").join("20.11.1").join("bin"),
+                PathBuf::from("usr").join("bin"),
+            ]
+        );
+        assert_eq!(
+            env["GOROOT"],
+            root.join("go").join("1.22.0").to_string_lossy()
+        );
+        assert!(!env.contains_key("JAVA_HOME"));
+        assert_eq!(toolchain.summary(), "go 1.22.0, nodejs 20.11.1");
+        assert!(ProjectToolchain::default().env(None).is_empty());
+    }
+}
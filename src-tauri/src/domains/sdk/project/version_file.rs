@@ -3,6 +3,7 @@
  *
  * Manages version files for project-level version isolation
  * Supports multiple version file formats: .portal-version, .nvmrc, .python-version, etc.
+ * and asdf's .tool-versions, which pins several SDKs in one file.
  */
 use super::ProjectEnvironment;
 use crate::domains::sdk::SDKError;
@@ -30,6 +31,7 @@ pub enum VersionFileFormat {
     RustToolchain, // rust-toolchain.toml (TOML)
     PhpVersion,    // .php-version (plain text)
     GoVersion,     // go.mod (Go module)
+    ToolVersions,  // .tool-versions (asdf, one SDK per line)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -248,6 +250,13 @@ impl VersionFileManager {
             ("go.mod", "go", VersionFileFormat::GoVersion),
         ];
 
+        let tool_versions_path = project_path.join(".tool-versions");
+        if tool_versions_path.exists() {
+            if let Ok(version_info) = Self::parse_tool_versions_file(&tool_versions_path).await {
+                version_files.extend(version_info);
+            }
+        }
+
         for (filename, sdk_type, format) in standard_files {
             let file_path = project_path.join(filename);
             if file_path.exists() {
@@ -303,6 +312,63 @@ impl VersionFileManager {
         Ok(version_files)
     }
 
+    /// Parse asdf's .tool-versions file
+    async fn parse_tool_versions_file(file_path: &Path) -> Result<Vec<VersionFileInfo>, SDKError> {
+        let metadata = fs::metadata(file_path).await.map_err(|e| {
+            SDKError::ManagerNotFound(format!("Failed to get file metadata: {}", e))
+        })?;
+        let last_modified = metadata
+            .modified()
+            .map(chrono::DateTime::<chrono::Utc>::from)
+            .unwrap_or_else(|_| chrono::Utc::now())
+            .to_rfc3339();
+
+        Ok(Self::read_tool_versions(file_path)
+            .await?
+            .into_iter()
+            .map(|(sdk_type, version)| VersionFileInfo {
+                file_path: file_path.to_path_buf(),
+                sdk_type,
+                version,
+                format: VersionFileFormat::ToolVersions,
+                last_modified: last_modified.clone(),
+            })
+            .collect())
+    }
+
+    /// The (SDK type, version) pairs a .tool-versions file pins
+    pub async fn read_tool_versions(file_path: &Path) -> Result<Vec<(String, String)>, SDKError> {
+        let content = fs::read_to_string(file_path).await.map_err(|e| {
+            SDKError::ManagerNotFound(format!("Failed to read .tool-versions: {}", e))
+        })?;
+        Ok(parse_tool_versions(&content))
+    }
+
+    /// Pin a version in a project's .tool-versions, keeping its other
+    /// entries and comments
+    pub async fn update_tool_versions(
+        project_path: &Path,
+        sdk_type: &str,
+        version: &str,
+    ) -> Result<(), SDKError> {
+        let file_path = project_path.join(".tool-versions");
+        let content = if file_path.exists() {
+            fs::read_to_string(&file_path).await.map_err(|e| {
+                SDKError::ManagerNotFound(format!("Failed to read .tool-versions: {}", e))
+            })?
+        } else {
+            String::new()
+        };
+
+        fs::write(&file_path, set_tool_version(&content, sdk_type, version))
+            .await
+            .map_err(|e| {
+                SDKError::ManagerNotFound(format!("Failed to write .tool-versions: {}", e))
+            })?;
+
+        Ok(())
+    }
+
     /// Parse standard version files (plain text or TOML)
     async fn parse_standard_version_file(
         file_path: &Path,
@@ -462,3 +528,86 @@ impl VersionFileManager {
         }
     }
 }
+
+/// Portal's SDK type for an asdf plugin name
+pub fn sdk_for_asdf_plugin(plugin: &str) -> String {
+    match plugin {
+        "golang" => "go".to_string(),
+        "node" => "nodejs".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// The asdf plugin name for one of Portal's SDK types
+pub fn asdf_plugin_for_sdk(sdk_type: &str) -> &str {
+    match sdk_type {
+        "go" => "golang",
+        "node" => "nodejs",
+        other => other,
+    }
+}
+
+/// Entries of a .tool-versions file. asdf falls back through the versions
+/// listed on a line; only the first is kept, and `system`, `ref:` and
+/// `path:` entries are skipped as there is nothing to pin for them.
+fn parse_tool_versions(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            let plugin = fields.next()?;
+            let version = fields.next()?;
+            if version == "system" || version.starts_with("ref:") || version.starts_with("path:") {
+                return None;
+            }
+            Some((sdk_for_asdf_plugin(plugin), version.to_string()))
+        })
+        .collect()
+}
+
+/// `content` with the line for `sdk_type` replaced, or appended
+fn set_tool_version(content: &str, sdk_type: &str, version: &str) -> String {
+    let plugin = asdf_plugin_for_sdk(sdk_type);
+    let entry = format!("{} {}", plugin, version);
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let current = line.split_whitespace().next().map(sdk_for_asdf_plugin);
+            if !replaced && current.as_deref() == Some(&sdk_for_asdf_plugin(plugin)) {
+                replaced = true;
+                entry.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(entry);
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_writes_tool_versions() {
+        let content = "# pinned for CI\nnodejs 20.11.1 18.19.0\ngolang 1.22.0\npython system\n";
+        assert_eq!(
+            parse_tool_versions(content),
+            vec![
+                ("nodejs".to_string(), "20.11.1".to_string()),
+                ("go".to_string(), "1.22.0".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            set_tool_version(content, "go", "1.23.1"),
+            "# pinned for CI\nnodejs 20.11.1 18.19.0\ngolang 1.23.1\npython system\n"
+        );
+        assert_eq!(set_tool_version("", "rust", "1.79.0"), "rust 1.79.0\n");
+    }
+}
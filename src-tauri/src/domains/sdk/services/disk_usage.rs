@@ -0,0 +1,351 @@
+/**
+ * Disk Usage
+ *
+ * Reports the space taken by directly installed SDK versions and Ollama
+ * models and removes the ones that have gone unused. Neither keeps a usage
+ * log, so `last_used` is the newest access or modification time of an
+ * install's executables, or of a model's weights blob, which Ollama reads
+ * on every load. On mounts without access times that is the install time,
+ * which is why cleanup previews by default.
+ */
+use super::super::download::installer::{bin_dir, sdks_root, SdkInstaller, DIRECT_INSTALL_SDKS};
+use super::super::ollama_manager::OllamaManager;
+use super::super::project::shims::ShimManager;
+use crate::domains::sdk::SDKError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageKind {
+    SdkVersion,
+    OllamaModel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageEntry {
+    pub kind: UsageKind,
+    /// "ollama" for models
+    pub sdk_type: String,
+    /// The SDK version, or the model name
+    pub version: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub last_used: Option<DateTime<Utc>>,
+    /// Linked in from another tool (asdf); removing it frees nothing
+    pub linked: bool,
+    /// The global default, which is never cleaned up
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageReport {
+    pub entries: Vec<DiskUsageEntry>,
+    /// Models share layers, so this can overstate what they take together
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupResult {
+    pub dry_run: bool,
+    /// What was removed, or would be on a dry run
+    pub removed: Vec<DiskUsageEntry>,
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+pub struct DiskUsageScanner {
+    sdks_root: PathBuf,
+    /// `$OLLAMA_MODELS`, or `~/.ollama/models`
+    models_dir: PathBuf,
+}
+
+impl DiskUsageScanner {
+    pub fn new() -> Result<Self, SDKError> {
+        let models_dir = match std::env::var_os("OLLAMA_MODELS") {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::home_dir()
+                .ok_or_else(|| {
+                    SDKError::ManagerNotFound("Could not determine home directory".to_string())
+                })?
+                .join(".ollama")
+                .join("models"),
+        };
+        Ok(Self {
+            sdks_root: sdks_root()?,
+            models_dir,
+        })
+    }
+
+    /// Every installed SDK version and model, largest first
+    pub async fn scan(&self) -> Result<DiskUsageReport, SDKError> {
+        let global = ShimManager::new()?.global_versions().await;
+        let sdks_root = self.sdks_root.clone();
+        let models_dir = self.models_dir.clone();
+
+        let mut entries = tokio::task::spawn_blocking(move || {
+            let mut entries = scan_sdk_versions(&sdks_root, &global);
+            entries.extend(scan_models(&models_dir));
+            entries
+        })
+        .await
+        .map_err(|e| SDKError::CommandFailed(format!("Disk usage scan failed: {}", e)))?;
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.size_bytes));
+        let total_bytes = entries.iter().map(|entry| entry.size_bytes).sum();
+        Ok(DiskUsageReport {
+            entries,
+            total_bytes,
+        })
+    }
+
+    /// Remove whatever hasn't been used in `older_than_days`, or only list
+    /// it when `dry_run` is set
+    pub async fn cleanup(
+        &self,
+        older_than_days: u32,
+        dry_run: bool,
+    ) -> Result<CleanupResult, SDKError> {
+        let now = SystemTime::now();
+        let candidates: Vec<DiskUsageEntry> = self
+            .scan()
+            .await?
+            .entries
+            .into_iter()
+            .filter(|entry| is_unused(entry, now, older_than_days))
+            .collect();
+
+        let mut result = CleanupResult {
+            dry_run,
+            removed: Vec::new(),
+            freed_bytes: 0,
+            errors: Vec::new(),
+        };
+        let installer = SdkInstaller::new()?;
+        for entry in candidates {
+            if !dry_run {
+                let outcome = match entry.kind {
+                    UsageKind::SdkVersion => installer
+                        .uninstall(&entry.sdk_type, &entry.version)
+                        .await
+                        .map_err(|e| e.to_string()),
+                    UsageKind::OllamaModel => OllamaManager::remove_model(&entry.version)
+                        .await
+                        .map(|_| ()),
+                };
+                if let Err(e) = outcome {
+                    result
+                        .errors
+                        .push(format!("{} {}: {}", entry.sdk_type, entry.version, e));
+                    continue;
+                }
+                println!(
+                    "[SDK] Removed unused {} {} ({} bytes)",
+                    entry.sdk_type, entry.version, entry.size_bytes
+                );
+            }
+            result.freed_bytes += entry.size_bytes;
+            result.removed.push(entry);
+        }
+
+        Ok(result)
+    }
+}
+
+fn scan_sdk_versions(sdks_root: &Path, global: &HashMap<String, String>) -> Vec<DiskUsageEntry> {
+    let mut entries = Vec::new();
+    for sdk in DIRECT_INSTALL_SDKS {
+        let Ok(versions) = fs::read_dir(sdks_root.join(sdk)) else {
+            continue;
+        };
+        for version in versions.flatten() {
+            let path = version.path();
+            let name = version.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || !path.is_dir() {
+                continue;
+            }
+            let linked = fs::symlink_metadata(&path).is_ok_and(|meta| meta.is_symlink());
+            let pinned = global
+                .get(sdk)
+                .is_some_and(|pin| *pin == name || name.starts_with(&format!("{}.", pin)));
+
+            entries.push(DiskUsageEntry {
+                kind: UsageKind::SdkVersion,
+                sdk_type: sdk.to_string(),
+                version: name,
+                size_bytes: if linked { 0 } else { dir_size(&path) },
+                last_used: newest_use(&bin_dir(sdk, &path)).map(DateTime::<Utc>::from),
+                path,
+                linked,
+                pinned,
+            });
+        }
+    }
+    entries
+}
+
+/// Models from their manifests, laid out as
+/// `manifests/<registry>/<namespace>/<model>/<tag>`
+fn scan_models(models_dir: &Path) -> Vec<DiskUsageEntry> {
+    let mut entries = Vec::new();
+    for manifest in files_at_depth(&models_dir.join("manifests"), 4) {
+        let Ok(content) = fs::read_to_string(&manifest) else {
+            continue;
+        };
+        let Ok(manifest_json) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+        let parts: Vec<String> = manifest
+            .strip_prefix(models_dir.join("manifests"))
+            .unwrap_or(&manifest)
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let [registry, namespace, model, tag] = parts.as_slice() else {
+            continue;
+        };
+
+        let layers: Vec<(String, u64)> = manifest_json
+            .get("layers")
+            .and_then(|layers| layers.as_array())
+            .into_iter()
+            .flatten()
+            .chain(manifest_json.get("config"))
+            .filter_map(|layer| {
+                Some((
+                    layer.get("digest")?.as_str()?.to_string(),
+                    layer.get("size")?.as_u64()?,
+                ))
+            })
+            .collect();
+        // The weights are the largest layer, read whenever the model loads
+        let weights = layers
+            .iter()
+            .max_by_key(|(_, size)| *size)
+            .map(|(digest, _)| models_dir.join("blobs").join(digest.replace(':', "-")));
+
+        entries.push(DiskUsageEntry {
+            kind: UsageKind::OllamaModel,
+            sdk_type: "ollama".to_string(),
+            version: model_name(registry, namespace, model, tag),
+            size_bytes: layers.iter().map(|(_, size)| size).sum(),
+            last_used: weights
+                .as_deref()
+                .and_then(newest_use)
+                .map(DateTime::<Utc>::from),
+            path: manifest,
+            linked: false,
+            pinned: false,
+        });
+    }
+    entries
+}
+
+/// The name `ollama list` shows for a manifest
+fn model_name(registry: &str, namespace: &str, model: &str, tag: &str) -> String {
+    if registry == "registry.ollama.ai" && namespace == "library" {
+        format!("{}:{}", model, tag)
+    } else if registry == "registry.ollama.ai" {
+        format!("{}/{}:{}", namespace, model, tag)
+    } else {
+        format!("{}/{}/{}:{}", registry, namespace, model, tag)
+    }
+}
+
+fn is_unused(entry: &DiskUsageEntry, now: SystemTime, older_than_days: u32) -> bool {
+    if entry.pinned {
+        return false;
+    }
+    let cutoff = now - Duration::from_secs(u64::from(older_than_days) * 24 * 60 * 60);
+    entry
+        .last_used
+        .is_none_or(|last_used| SystemTime::from(last_used) < cutoff)
+}
+
+/// Total size of the files under `path`, not following links
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+/// The latest access or modification of `path` or, for a directory, the
+/// files directly in it
+fn newest_use(path: &Path) -> Option<SystemTime> {
+    let touched = |meta: fs::Metadata| {
+        [meta.accessed().ok(), meta.modified().ok()]
+            .into_iter()
+            .flatten()
+            .max()
+    };
+    let own = fs::metadata(path).ok().and_then(touched);
+    let children = fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok().and_then(touched));
+    own.into_iter().chain(children).max()
+}
+
+fn files_at_depth(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .flat_map(|entry| {
+            let path = entry.path();
+            match depth {
+                0 => Vec::new(),
+                1 if path.is_file() => vec![path],
+                1 => Vec::new(),
+                _ => files_at_depth(&path, depth - 1),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_unused_versions() {
+        let now = SystemTime::now();
+        let entry = |days_ago: u64, pinned: bool| DiskUsageEntry {
+            kind: UsageKind::SdkVersion,
+            sdk_type: "nodejs".to_string(),
+            version: "18.19.0".to_string(),
+            path: PathBuf::new(),
+            size_bytes: 0,
+            last_used: Some(DateTime::<Utc>::from(
+                now - Duration::from_secs(days_ago * 24 * 60 * 60),
+            )),
+            linked: false,
+            pinned,
+        };
+
+        assert!(is_unused(&entry(45, false), now, 30));
+        assert!(!is_unused(&entry(3, false), now, 30));
+        assert!(!is_unused(&entry(45, true), now, 30));
+
+        assert_eq!(
+            model_name("registry.ollama.ai", "library", "llama3", "8b"),
+            "llama3:8b"
+        );
+        assert_eq!(
+            model_name("registry.ollama.ai", "user", "coder", "latest"),
+            "user/coder:latest"
+        );
+    }
+}
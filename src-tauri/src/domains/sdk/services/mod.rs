@@ -1,6 +1,8 @@
 pub mod custom_directory_manager;
+pub mod disk_usage;
 pub mod language_config_service;
 pub mod navigation_service;
+pub mod php_config;
 pub mod port_manager;
 pub mod process_tracker;
 pub mod sdk_service;
@@ -10,7 +12,9 @@ pub mod sdk_service;
  * Handles service lifecycle management for databases and web servers
  */
 pub mod service_manager;
+pub mod service_provisioner;
 pub mod terminal_integration;
+pub mod vhost_manager;
 
 pub use port_manager::PortManager;
 pub use process_tracker::ProcessTracker;
@@ -0,0 +1,711 @@
+/**
+ * PHP Configuration
+ *
+ * Extensions and php.ini settings for each installed PHP. Installs are
+ * found wherever they usually live (phpenv, Homebrew's versioned formulae,
+ * Debian's `phpX.Y` binaries, and whatever `php` is on PATH) and each one
+ * is asked for its own configuration, so edits land in the php.ini and
+ * scan directory that version actually loads.
+ */
+use crate::command_executor::CommandExecutor;
+use crate::domains::sdk::SDKError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extensions that have to be loaded with `zend_extension=`
+const ZEND_EXTENSIONS: [&str; 4] = ["opcache", "xdebug", "ioncube", "blackfire"];
+
+/// Prints what this file needs to know about the PHP running it
+const PROBE_SCRIPT: &str = r#"echo json_encode([
+    "version" => PHP_VERSION,
+    "ini_file" => php_ini_loaded_file(),
+    "ini_dir" => PHP_CONFIG_FILE_PATH,
+    "scan_dir" => PHP_CONFIG_FILE_SCAN_DIR,
+    "extension_dir" => ini_get("extension_dir"),
+    "extensions" => get_loaded_extensions(),
+    "zend_extensions" => get_loaded_extensions(true),
+    "ini" => ini_get_all(null, false),
+]);"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhpInstallation {
+    pub version: String,
+    pub binary: PathBuf,
+    /// The php.ini in use; edits create it when PHP loads none
+    pub ini_file: PathBuf,
+    /// Where extra `.ini` files are picked up from, if anywhere
+    pub scan_dir: Option<PathBuf>,
+    pub extension_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhpExtension {
+    pub name: String,
+    pub loaded: bool,
+    pub zend: bool,
+    /// Built as a loadable module, so it can be turned on and off; built-in
+    /// extensions can't be
+    pub shared: bool,
+    /// The file whose line loads it
+    pub enabled_in: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IniValueKind {
+    Bool,
+    Int,
+    /// A byte count with an optional K, M or G suffix, or -1 for no limit
+    Size,
+    String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum IniValue {
+    Bool(bool),
+    Int(i64),
+    Size(String),
+    String(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IniSetting {
+    pub key: String,
+    pub kind: IniValueKind,
+    /// The effective value, None when the directive doesn't exist (its
+    /// extension isn't loaded)
+    pub value: Option<IniValue>,
+}
+
+/// The directives offered for editing, with their types
+pub const INI_DIRECTIVES: [(&str, IniValueKind); 20] = [
+    ("memory_limit", IniValueKind::Size),
+    ("upload_max_filesize", IniValueKind::Size),
+    ("post_max_size", IniValueKind::Size),
+    ("max_execution_time", IniValueKind::Int),
+    ("max_input_time", IniValueKind::Int),
+    ("max_input_vars", IniValueKind::Int),
+    ("max_file_uploads", IniValueKind::Int),
+    ("file_uploads", IniValueKind::Bool),
+    ("allow_url_fopen", IniValueKind::Bool),
+    ("display_errors", IniValueKind::Bool),
+    ("display_startup_errors", IniValueKind::Bool),
+    ("log_errors", IniValueKind::Bool),
+    ("error_log", IniValueKind::String),
+    ("short_open_tag", IniValueKind::Bool),
+    ("default_charset", IniValueKind::String),
+    ("date.timezone", IniValueKind::String),
+    ("realpath_cache_size", IniValueKind::Size),
+    ("opcache.enable", IniValueKind::Bool),
+    ("opcache.memory_consumption", IniValueKind::Int),
+    ("xdebug.mode", IniValueKind::String),
+];
+
+#[derive(Debug, Deserialize)]
+struct Probe {
+    version: String,
+    ini_file: serde_json::Value,
+    ini_dir: String,
+    scan_dir: String,
+    extension_dir: serde_json::Value,
+    extensions: Vec<String>,
+    zend_extensions: Vec<String>,
+    ini: BTreeMap<String, Option<String>>,
+}
+
+pub struct PhpConfigManager;
+
+impl PhpConfigManager {
+    /// Every PHP found, one per version
+    pub async fn installations() -> Vec<PhpInstallation> {
+        let mut installations: Vec<PhpInstallation> = Vec::new();
+        for binary in candidate_binaries() {
+            let Ok(probe) = probe(&binary).await else {
+                continue;
+            };
+            if installations.iter().any(|i| i.version == probe.version) {
+                continue;
+            }
+            installations.push(installation(binary, &probe));
+        }
+        installations
+    }
+
+    /// The install matching `version` (a prefix such as "8.2"), or the
+    /// `php` on PATH
+    async fn find(version: Option<&str>) -> Result<(PhpInstallation, Probe), SDKError> {
+        let candidates = match version {
+            Some(_) => candidate_binaries(),
+            None => vec![PathBuf::from("php")],
+        };
+        for binary in candidates {
+            let Ok(probe) = probe(&binary).await else {
+                continue;
+            };
+            if version.is_none_or(|v| matches_version(&probe.version, v)) {
+                return Ok((installation(binary, &probe), probe));
+            }
+        }
+        Err(SDKError::VersionNotFound(format!(
+            "PHP {} is not installed",
+            version.unwrap_or("")
+        )))
+    }
+
+    /// Loaded extensions plus the modules in the extension directory
+    pub async fn list_extensions(version: Option<&str>) -> Result<Vec<PhpExtension>, SDKError> {
+        let (installation, probe) = Self::find(version).await?;
+        let ini_files = ini_files(&installation);
+        let zend: HashSet<String> = probe
+            .zend_extensions
+            .iter()
+            .map(|name| name.to_lowercase())
+            .collect();
+        let shared: HashSet<String> = installation
+            .extension_dir
+            .as_deref()
+            .map(shared_modules)
+            .unwrap_or_default();
+
+        let mut names: Vec<String> = probe
+            .extensions
+            .iter()
+            .chain(&probe.zend_extensions)
+            .map(|name| module_name(name))
+            .chain(shared.iter().cloned())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let loaded: HashSet<String> = probe
+            .extensions
+            .iter()
+            .chain(&probe.zend_extensions)
+            .map(|name| module_name(name))
+            .collect();
+
+        Ok(names
+            .into_iter()
+            .map(|name| PhpExtension {
+                loaded: loaded.contains(&name),
+                zend: zend.iter().any(|z| module_name(z) == name)
+                    || ZEND_EXTENSIONS.contains(&name.as_str()),
+                shared: shared.contains(&name),
+                enabled_in: ini_files
+                    .iter()
+                    .find(|file| {
+                        fs::read_to_string(file)
+                            .is_ok_and(|content| active_extension_line(&content, &name))
+                    })
+                    .cloned(),
+                name,
+            })
+            .collect())
+    }
+
+    /// Turn an extension on or off. Enabling uncomments its line where one
+    /// exists and otherwise adds it to php.ini; a change PHP then fails to
+    /// load is rolled back.
+    pub async fn set_extension_enabled(
+        version: Option<&str>,
+        extension: &str,
+        enabled: bool,
+    ) -> Result<(), SDKError> {
+        let name = module_name(extension);
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(SDKError::CommandFailed(format!(
+                "Invalid extension name: {}",
+                extension
+            )));
+        }
+        let (installation, probe) = Self::find(version).await?;
+        let files = ini_files(&installation);
+
+        let mut changed: Vec<(PathBuf, String)> = Vec::new();
+        for file in &files {
+            let Ok(content) = fs::read_to_string(file) else {
+                continue;
+            };
+            if let Some(updated) = toggle_extension(&content, &name, enabled) {
+                fs::write(file, updated)?;
+                changed.push((file.clone(), content));
+                // One uncommented line is enough; every active one has to go
+                if enabled {
+                    break;
+                }
+            }
+        }
+
+        if enabled && changed.is_empty() {
+            let loaded = probe
+                .extensions
+                .iter()
+                .chain(&probe.zend_extensions)
+                .any(|loaded| module_name(loaded) == name);
+            if loaded {
+                return Ok(());
+            }
+            let directive = if ZEND_EXTENSIONS.contains(&name.as_str()) {
+                "zend_extension"
+            } else {
+                "extension"
+            };
+            let original = fs::read_to_string(&installation.ini_file).unwrap_or_default();
+            let mut updated = original.clone();
+            if !updated.is_empty() && !updated.ends_with('\n') {
+                updated.push('\n');
+            }
+            updated.push_str(&format!("{}={}\n", directive, name));
+            if let Some(parent) = installation.ini_file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&installation.ini_file, updated)?;
+            changed.push((installation.ini_file.clone(), original));
+        }
+
+        if !enabled && changed.is_empty() {
+            let loaded = probe
+                .extensions
+                .iter()
+                .any(|loaded| module_name(loaded) == name);
+            return if loaded {
+                Err(SDKError::CommandFailed(format!(
+                    "{} is built into PHP {} and can't be disabled",
+                    name, installation.version
+                )))
+            } else {
+                Ok(())
+            };
+        }
+
+        if enabled {
+            let after = probe_with_errors(&installation.binary).await;
+            let loaded = after.as_ref().is_ok_and(|(probe, _)| {
+                probe
+                    .extensions
+                    .iter()
+                    .chain(&probe.zend_extensions)
+                    .any(|loaded| module_name(loaded) == name)
+            });
+            if !loaded {
+                for (file, original) in &changed {
+                    fs::write(file, original)?;
+                }
+                let reason = match after {
+                    Ok((_, stderr)) if !stderr.trim().is_empty() => stderr.trim().to_string(),
+                    Ok(_) => "the module was not found".to_string(),
+                    Err(e) => e.to_string(),
+                };
+                return Err(SDKError::CommandFailed(format!(
+                    "PHP {} could not load {}: {}",
+                    installation.version, name, reason
+                )));
+            }
+        }
+
+        println!(
+            "[SDK] {} PHP extension {} for {}",
+            if enabled { "Enabled" } else { "Disabled" },
+            name,
+            installation.version
+        );
+        Ok(())
+    }
+
+    /// The effective values of the editable directives
+    pub async fn ini_settings(version: Option<&str>) -> Result<Vec<IniSetting>, SDKError> {
+        let (_, probe) = Self::find(version).await?;
+        Ok(INI_DIRECTIVES
+            .iter()
+            .map(|(key, kind)| IniSetting {
+                key: key.to_string(),
+                kind: *kind,
+                value: probe
+                    .ini
+                    .get(*key)
+                    .map(|raw| parse_ini_value(*kind, raw.as_deref().unwrap_or(""))),
+            })
+            .collect())
+    }
+
+    /// Write a directive to php.ini. Directives outside the known list are
+    /// accepted as long as the key looks like one.
+    pub async fn set_ini_value(
+        version: Option<&str>,
+        key: &str,
+        value: IniValue,
+    ) -> Result<(), SDKError> {
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            return Err(SDKError::CommandFailed(format!(
+                "Invalid php.ini directive: {}",
+                key
+            )));
+        }
+        if let Some((_, kind)) = INI_DIRECTIVES.iter().find(|(known, _)| *known == key) {
+            if value_kind(&value) != *kind {
+                return Err(SDKError::CommandFailed(format!(
+                    "{} takes a {:?} value",
+                    key, kind
+                )));
+            }
+        }
+        let rendered = render_ini_value(&value)?;
+
+        let (installation, _) = Self::find(version).await?;
+        let content = fs::read_to_string(&installation.ini_file).unwrap_or_default();
+        if let Some(parent) = installation.ini_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            &installation.ini_file,
+            set_ini_line(&content, key, &rendered),
+        )?;
+
+        println!(
+            "[SDK] Set {} = {} in {}",
+            key,
+            rendered,
+            installation.ini_file.display()
+        );
+        Ok(())
+    }
+}
+
+async fn probe(binary: &Path) -> Result<Probe, SDKError> {
+    probe_with_errors(binary).await.map(|(probe, _)| probe)
+}
+
+/// The probe, along with any startup warnings such as a module that failed
+/// to load
+async fn probe_with_errors(binary: &Path) -> Result<(Probe, String), SDKError> {
+    let result =
+        CommandExecutor::execute_with_args(&binary.to_string_lossy(), &["-r", PROBE_SCRIPT], None)
+            .await
+            .map_err(|e| {
+                SDKError::CommandFailed(format!("Failed to run {}: {}", binary.display(), e))
+            })?;
+    if !result.success {
+        return Err(SDKError::CommandFailed(result.stderr));
+    }
+    // Startup warnings are printed ahead of the JSON
+    let json = result
+        .stdout
+        .find('{')
+        .map(|start| &result.stdout[start..])
+        .unwrap_or(&result.stdout);
+    let probe = serde_json::from_str(json)
+        .map_err(|e| SDKError::CommandFailed(format!("Unexpected PHP output: {}", e)))?;
+    let warnings = result
+        .stdout
+        .find('{')
+        .map(|start| result.stdout[..start].to_string())
+        .unwrap_or_default();
+    Ok((probe, format!("{}{}", warnings, result.stderr)))
+}
+
+fn installation(binary: PathBuf, probe: &Probe) -> PhpInstallation {
+    let ini_file = probe
+        .ini_file
+        .as_str()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(&probe.ini_dir).join("php.ini"));
+    PhpInstallation {
+        version: probe.version.clone(),
+        binary,
+        ini_file,
+        scan_dir: Some(probe.scan_dir.as_str())
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from),
+        extension_dir: probe
+            .extension_dir
+            .as_str()
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from),
+    }
+}
+
+/// php.ini followed by the scan directory's files, in load order
+fn ini_files(installation: &PhpInstallation) -> Vec<PathBuf> {
+    let mut files = vec![installation.ini_file.clone()];
+    if let Some(Ok(entries)) = installation.scan_dir.as_deref().map(fs::read_dir) {
+        let mut extra: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ini"))
+            .collect();
+        extra.sort();
+        files.extend(extra);
+    }
+    files
+}
+
+fn candidate_binaries() -> Vec<PathBuf> {
+    let exe = if cfg!(windows) { "php.exe" } else { "php" };
+    let mut candidates: Vec<PathBuf> = which::which("php").into_iter().collect();
+
+    if let Some(home) = dirs::home_dir() {
+        let phpenv_root = std::env::var_os("PHPENV_ROOT")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".phpenv"));
+        candidates.extend(
+            subdirs(&phpenv_root.join("versions"))
+                .into_iter()
+                .map(|dir| dir.join("bin").join(exe)),
+        );
+    }
+    for prefix in ["/opt/homebrew", "/usr/local", "/home/linuxbrew/.linuxbrew"] {
+        candidates.extend(
+            subdirs(&Path::new(prefix).join("opt"))
+                .into_iter()
+                .filter(|dir| {
+                    dir.file_name().is_some_and(|name| {
+                        name == "php" || name.to_string_lossy().starts_with("php@")
+                    })
+                })
+                .map(|dir| dir.join("bin").join(exe)),
+        );
+    }
+    // Debian and Ubuntu install side by side as php8.2 and the like
+    if let Ok(entries) = fs::read_dir("/usr/bin") {
+        candidates.extend(entries.flatten().map(|entry| entry.path()).filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("php"))
+                .is_some_and(|rest| {
+                    !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit() || c == '.')
+                })
+        }));
+    }
+
+    let mut seen = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|path| path.is_file())
+        .filter(|path| seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
+        .collect()
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+}
+
+fn matches_version(actual: &str, wanted: &str) -> bool {
+    actual == wanted || actual.starts_with(&format!("{}.", wanted))
+}
+
+/// Loadable modules in the extension directory
+fn shared_modules(extension_dir: &Path) -> HashSet<String> {
+    fs::read_dir(extension_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    (name.ends_with(".so") || name.ends_with(".dll")).then(|| module_name(&name))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The name an extension goes by, from a loaded name ("Zend OPcache"), a
+/// file name (`php_curl.dll`) or a path
+fn module_name(name: &str) -> String {
+    let name = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(name)
+        .to_lowercase();
+    let name = name
+        .strip_suffix(".so")
+        .or_else(|| name.strip_suffix(".dll"))
+        .unwrap_or(&name);
+    let name = name.strip_prefix("php_").unwrap_or(name);
+    match name {
+        "zend opcache" => "opcache".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// The extension an `extension=` or `zend_extension=` line loads
+fn extension_on_line(line: &str) -> Option<String> {
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    if key != "extension" && key != "zend_extension" {
+        return None;
+    }
+    Some(module_name(value.trim().trim_matches('"')))
+}
+
+fn active_extension_line(content: &str, name: &str) -> bool {
+    content
+        .lines()
+        .any(|line| extension_on_line(line).is_some_and(|ext| ext == name))
+}
+
+/// Uncomment the first line loading `name`, or comment out every active
+/// one. None when nothing changes.
+fn toggle_extension(content: &str, name: &str, enabled: bool) -> Option<String> {
+    let mut changed = false;
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if enabled && !changed {
+                if let Some(commented) = trimmed.strip_prefix(';') {
+                    if extension_on_line(commented).is_some_and(|ext| ext == name) {
+                        changed = true;
+                        return commented.trim_start().to_string();
+                    }
+                }
+            }
+            if !enabled && extension_on_line(trimmed).is_some_and(|ext| ext == name) {
+                changed = true;
+                return format!(";{}", trimmed);
+            }
+            line.to_string()
+        })
+        .collect();
+
+    changed.then(|| {
+        let mut updated = lines.join("\n");
+        if content.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated
+    })
+}
+
+/// Replace the directive's active line, or else its first commented-out
+/// one, or else append it
+fn set_ini_line(content: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{} = {}", key, value);
+    let directive_of = |line: &str| {
+        line.split_once('=')
+            .map(|(directive, _)| directive.trim().to_string())
+    };
+    let mut lines: Vec<String> = content.lines().map(|line| line.to_string()).collect();
+
+    let active = lines
+        .iter()
+        .position(|line| directive_of(line.trim_start()).is_some_and(|d| d == key));
+    let commented = || {
+        lines.iter().position(|line| {
+            line.trim_start()
+                .strip_prefix(';')
+                .and_then(directive_of)
+                .is_some_and(|d| d == key)
+        })
+    };
+    match active.or_else(commented) {
+        Some(index) => lines[index] = new_line,
+        None => lines.push(new_line),
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+fn value_kind(value: &IniValue) -> IniValueKind {
+    match value {
+        IniValue::Bool(_) => IniValueKind::Bool,
+        IniValue::Int(_) => IniValueKind::Int,
+        IniValue::Size(_) => IniValueKind::Size,
+        IniValue::String(_) => IniValueKind::String,
+    }
+}
+
+fn render_ini_value(value: &IniValue) -> Result<String, SDKError> {
+    match value {
+        IniValue::Bool(true) => Ok("On".to_string()),
+        IniValue::Bool(false) => Ok("Off".to_string()),
+        IniValue::Int(n) => Ok(n.to_string()),
+        IniValue::Size(size) => {
+            let digits = size.trim_end_matches(['K', 'M', 'G', 'k', 'm', 'g']);
+            let valid = size == "-1"
+                || (!digits.is_empty()
+                    && digits.chars().all(|c| c.is_ascii_digit())
+                    && size.len() - digits.len() <= 1);
+            if valid {
+                Ok(size.to_uppercase())
+            } else {
+                Err(SDKError::CommandFailed(format!(
+                    "Invalid size: {} (expected e.g. 128M or -1)",
+                    size
+                )))
+            }
+        }
+        IniValue::String(text) => {
+            if text.contains(['"', '\n', '\r']) {
+                Err(SDKError::CommandFailed(format!(
+                    "php.ini values can't contain quotes or newlines: {}",
+                    text
+                )))
+            } else {
+                Ok(format!("\"{}\"", text))
+            }
+        }
+    }
+}
+
+/// `ini_get` gives strings; booleans come back as "1", "" or whatever was
+/// written
+fn parse_ini_value(kind: IniValueKind, raw: &str) -> IniValue {
+    match kind {
+        IniValueKind::Bool => IniValue::Bool(matches!(
+            raw.to_lowercase().as_str(),
+            "1" | "on" | "true" | "yes" | "stderr" | "stdout"
+        )),
+        IniValueKind::Int => raw
+            .trim()
+            .parse()
+            .map(IniValue::Int)
+            .unwrap_or_else(|_| IniValue::String(raw.to_string())),
+        IniValueKind::Size => IniValue::Size(raw.to_string()),
+        IniValueKind::String => IniValue::String(raw.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_ini_lines() {
+        let ini = "[PHP]\n;extension=curl\nextension=gd\nmemory_limit = 128M\n;date.timezone =\n";
+
+        let enabled = toggle_extension(ini, "curl", true).unwrap();
+        assert!(enabled.contains("\nextension=curl\n"));
+        let disabled = toggle_extension(ini, "gd", false).unwrap();
+        assert!(disabled.contains("\n;extension=gd\n"));
+        assert!(toggle_extension(ini, "gd", true).is_none());
+        assert!(active_extension_line("extension=php_gd.dll", "gd"));
+
+        let updated = set_ini_line(ini, "memory_limit", "512M");
+        assert!(updated.contains("\nmemory_limit = 512M\n"));
+        let updated = set_ini_line(ini, "date.timezone", "\"UTC\"");
+        assert!(updated.contains("\ndate.timezone = \"UTC\"\n"));
+        assert!(set_ini_line(ini, "max_input_vars", "5000").ends_with("max_input_vars = 5000\n"));
+
+        assert_eq!(
+            render_ini_value(&IniValue::Size("256m".into())).unwrap(),
+            "256M"
+        );
+        assert!(render_ini_value(&IniValue::Size("lots".into())).is_err());
+        assert_eq!(
+            parse_ini_value(IniValueKind::Bool, "1"),
+            IniValue::Bool(true)
+        );
+        assert_eq!(module_name("Zend OPcache"), "opcache");
+    }
+}
@@ -4,9 +4,50 @@
  * Manages port allocation and conflict detection
  */
 use crate::domains::sdk::SDKError;
+use crate::process_ext::NoWindowExt;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::net::TcpListener;
 
+/// The process listening on a port
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortOwner {
+    pub pid: u32,
+    pub process_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortCheck {
+    pub port: u16,
+    pub available: bool,
+    /// Who holds the port; unknown when the OS won't say (another user's
+    /// process without elevation)
+    pub owner: Option<PortOwner>,
+    /// The next free port, offered when this one is taken
+    pub suggested_port: Option<u16>,
+}
+
+impl PortCheck {
+    /// A message for a start that would fail on this port
+    pub fn conflict_message(&self) -> String {
+        let holder = match &self.owner {
+            Some(PortOwner {
+                pid,
+                process_name: Some(name),
+            }) => format!("{} (pid {})", name, pid),
+            Some(PortOwner { pid, .. }) => format!("pid {}", pid),
+            None => "another process".to_string(),
+        };
+        match self.suggested_port {
+            Some(free) => format!(
+                "Port {} is already in use by {}; port {} is free",
+                self.port, holder, free
+            ),
+            None => format!("Port {} is already in use by {}", self.port, holder),
+        }
+    }
+}
+
 pub struct PortManager {
     allocated_ports: HashSet<u16>,
     default_ports: std::collections::HashMap<String, u16>,
@@ -97,6 +138,82 @@ impl PortManager {
         None
     }
 
+    /// Whether `port` can be listened on, and if not, who has it and which
+    /// port to use instead
+    pub async fn check_port(&self, port: u16) -> PortCheck {
+        // Binding catches listeners on the same address; connecting catches
+        // ones that bound a specific interface or set SO_REUSEADDR
+        let listening = tokio::time::timeout(
+            std::time::Duration::from_millis(300),
+            tokio::net::TcpStream::connect(("127.0.0.1", port)),
+        )
+        .await
+        .is_ok_and(|connected| connected.is_ok());
+        if self.is_port_available(port).await && !listening {
+            return PortCheck {
+                port,
+                available: true,
+                owner: None,
+                suggested_port: None,
+            };
+        }
+
+        PortCheck {
+            port,
+            available: false,
+            owner: Self::find_owner(port).await,
+            suggested_port: self
+                .find_available_port_in_range(port.saturating_add(1), port.saturating_add(100))
+                .await,
+        }
+    }
+
+    /// The process listening on `port`, from lsof or ss, or netstat on
+    /// Windows
+    pub async fn find_owner(port: u16) -> Option<PortOwner> {
+        if cfg!(target_os = "windows") {
+            let output = tokio::process::Command::new("netstat")
+                .no_window()
+                .args(["-ano", "-p", "TCP"])
+                .output()
+                .await
+                .ok()?;
+            let pid = parse_netstat(&String::from_utf8_lossy(&output.stdout), port)?;
+            let tasklist = tokio::process::Command::new("tasklist")
+                .no_window()
+                .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+                .output()
+                .await
+                .ok();
+            let process_name = tasklist.and_then(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .split(',')
+                    .next()
+                    .map(|name| name.trim().trim_matches('"').to_string())
+                    .filter(|name| !name.is_empty() && !name.starts_with("INFO:"))
+            });
+            return Some(PortOwner { pid, process_name });
+        }
+
+        let lsof = tokio::process::Command::new("lsof")
+            .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-Fpc"])
+            .output()
+            .await;
+        if let Some(owner) = lsof
+            .ok()
+            .and_then(|output| parse_lsof(&String::from_utf8_lossy(&output.stdout)))
+        {
+            return Some(owner);
+        }
+
+        let ss = tokio::process::Command::new("ss")
+            .args(["-Hltnp", &format!("sport = :{}", port)])
+            .output()
+            .await
+            .ok()?;
+        parse_ss(&String::from_utf8_lossy(&ss.stdout))
+    }
+
     /// Check port conflicts with system services
     pub async fn check_system_conflicts(&self, port: u16) -> bool {
         // Try to bind to the port to see if it's available
@@ -106,3 +223,87 @@ impl PortManager {
         }
     }
 }
+
+/// `lsof -F pc` prints a `p<pid>` line, then `c<command>`
+fn parse_lsof(output: &str) -> Option<PortOwner> {
+    let mut owner: Option<PortOwner> = None;
+    for line in output.lines() {
+        if let Some(pid) = line.strip_prefix('p') {
+            if owner.is_some() {
+                break;
+            }
+            owner = Some(PortOwner {
+                pid: pid.parse().ok()?,
+                process_name: None,
+            });
+        } else if let (Some(command), Some(owner)) = (line.strip_prefix('c'), owner.as_mut()) {
+            owner.process_name = Some(command.to_string());
+        }
+    }
+    owner
+}
+
+/// `ss -p` ends each socket with `users:(("nginx",pid=812,fd=6),...)`
+fn parse_ss(output: &str) -> Option<PortOwner> {
+    let users = output
+        .lines()
+        .find_map(|line| line.split_once("users:((\""))?
+        .1;
+    let (name, rest) = users.split_once('"')?;
+    let pid = rest
+        .split_once("pid=")?
+        .1
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()?;
+    Some(PortOwner {
+        pid,
+        process_name: Some(name.to_string()),
+    })
+}
+
+/// The pid from netstat's `TCP  0.0.0.0:5432  0.0.0.0:0  LISTENING  4120`
+fn parse_netstat(output: &str, port: u16) -> Option<u32> {
+    output.lines().find_map(|line| {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        match columns.as_slice() {
+            [_, local, _, "LISTENING", pid]
+                if local.rsplit(':').next() == Some(port.to_string().as_str()) =>
+            {
+                pid.parse().ok()
+            }
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_port_owners() {
+        assert_eq!(
+            parse_lsof("p812\ncnginx\np813\ncnginx\n"),
+            Some(PortOwner {
+                pid: 812,
+                process_name: Some("nginx".to_string())
+            })
+        );
+        assert_eq!(parse_lsof(""), None);
+
+        let ss = "LISTEN 0 244 127.0.0.1:5432 0.0.0.0:* users:((\"postgres\",pid=1290,fd=7))\n";
+        assert_eq!(
+            parse_ss(ss),
+            Some(PortOwner {
+                pid: 1290,
+                process_name: Some("postgres".to_string())
+            })
+        );
+
+        let netstat = "  TCP    0.0.0.0:3306     0.0.0.0:0    LISTENING    4120\n  TCP    [::]:5432    [::]:0    LISTENING    77\n";
+        assert_eq!(parse_netstat(netstat, 5432), Some(77));
+        assert_eq!(parse_netstat(netstat, 6379), None);
+    }
+}
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::process::{Command, Stdio};
 // use crate::domains::sdk::entities::ActiveModel as SDKInstallationActive; // TODO: Use when entities are needed
 use super::super::factory::SDKManagerFactory;
+use super::super::project::version_file::VersionFileManager;
 use super::super::SDKError;
 // Using println! for logging as per codebase convention
 
@@ -650,8 +651,8 @@ impl SDKService {
     }
 
     /// FlyEnv-style project-level environment isolation
-    /// Automatically switch SDK versions when entering project directories
-    /// This creates temporary project-specific environment overrides
+    /// Records the versions pinned by the project's config files in its
+    /// .portal-version, which the shell hooks read to switch PATH on cd
     pub async fn setup_project_environment(&self, project_path: &str) -> Result<(), SDKError> {
         use std::fs;
         use std::path::Path;
@@ -665,113 +666,61 @@ impl SDKService {
             ".ruby-version",   // Ruby
             ".php-version",    // PHP
             "rust-toolchain",  // Rust
-            "go.mod",          // Go
         ];
 
         for config_file in config_files {
             let config_path = project_path.join(config_file);
             if config_path.exists() {
                 if let Ok(content) = fs::read_to_string(&config_path) {
-                    let version = content.trim();
+                    let version = content.trim().trim_start_matches('v');
+                    // Only plain version files; TOML toolchain files and
+                    // aliases like `lts/*` aren't versions we can pin
+                    if version.is_empty()
+                        || version.contains(|c: char| c.is_whitespace() || c == '/')
+                    {
+                        continue;
+                    }
                     let sdk_type = self.get_sdk_type_from_config(&config_file);
 
                     if let Some(sdk_type) = sdk_type {
-                        println!("[SDKService] Auto-switching {} to version {} for project {} (temporary)", 
-                                    sdk_type, version, project_path.display());
-
-                        // Create temporary project-specific environment
-                        self.create_temporary_project_environment(project_path, &sdk_type, version)
+                        println!(
+                            "[SDKService] Pinning {} {} for project {}",
+                            sdk_type,
+                            version,
+                            project_path.display()
+                        );
+
+                        VersionFileManager::update_version(project_path, &sdk_type, version)
                             .await?;
                     }
                 }
             }
         }
 
-        Ok(())
-    }
-
-    /// Create temporary project-specific environment override
-    /// This doesn't change the system-wide environment, only affects terminals in this project
-    async fn create_temporary_project_environment(
-        &self,
-        project_path: &std::path::Path,
-        sdk_type: &str,
-        version: &str,
-    ) -> Result<(), SDKError> {
-        use std::fs;
-
-        // Create project-specific environment script
-        let env_script_path = project_path.join(".portal_env");
-        let mut env_script = String::new();
-
-        // Add SDK-specific environment setup
-        match sdk_type {
-            "node" => {
-                env_script.push_str(&format!(
-                    "export NVM_DIR=\"$HOME/.nvm\"\n\
-                     [ -s \"$NVM_DIR/nvm.sh\" ] && \\. \"$NVM_DIR/nvm.sh\"\n\
-                     nvm use {}\n",
-                    version
-                ));
-            }
-            "python" => {
-                env_script.push_str(&format!(
-                    "export PYENV_VERSION={}\n\
-                     export PATH=\"$(pyenv root)/versions/{}/bin:$PATH\"\n",
-                    version, version
-                ));
-            }
-            "rust" => {
-                env_script.push_str(&format!(
-                    "export RUSTUP_TOOLCHAIN={}\n\
-                     export PATH=\"$HOME/.rustup/toolchains/{}/bin:$PATH\"\n",
-                    version, version
-                ));
-            }
-            "ruby" => {
-                env_script.push_str(&format!(
-                    "export RBENV_VERSION={}\n\
-                     export PATH=\"$(rbenv root)/versions/{}/bin:$PATH\"\n",
-                    version, version
-                ));
-            }
-            "php" => {
-                env_script.push_str(&format!(
-                    "export PHPENV_VERSION={}\n\
-                     export PATH=\"$(phpenv root)/versions/{}/bin:$PATH\"\n",
-                    version, version
-                ));
-            }
-            _ => {
+        // asdf's .tool-versions pins several SDKs and wins over the
+        // single-SDK files, as it does under asdf
+        let tool_versions = project_path.join(".tool-versions");
+        if tool_versions.exists() {
+            for (sdk_type, version) in
+                VersionFileManager::read_tool_versions(&tool_versions).await?
+            {
                 println!(
-                    "[SDKService] No specific environment setup for {}",
-                    sdk_type
+                    "[SDKService] Pinning {} {} from .tool-versions for project {}",
+                    sdk_type,
+                    version,
+                    project_path.display()
                 );
-                return Ok(());
+                VersionFileManager::update_version(project_path, &sdk_type, &version).await?;
             }
         }
 
-        // Write the environment script
-        fs::write(&env_script_path, env_script).map_err(|e| {
-            SDKError::CommandFailed(format!(
-                "Failed to create project environment script: {}",
-                e
-            ))
-        })?;
-
-        println!(
-            "[SDKService] Created temporary environment script for project {}: {}",
-            project_path.display(),
-            env_script_path.display()
-        );
-
         Ok(())
     }
 
     /// Get SDK type from configuration file name
     fn get_sdk_type_from_config(&self, config_file: &str) -> Option<String> {
         match config_file {
-            ".nvmrc" => Some("node".to_string()),
+            ".nvmrc" => Some("nodejs".to_string()),
             ".python-version" => Some("python".to_string()),
             ".ruby-version" => Some("ruby".to_string()),
             ".php-version" => Some("php".to_string()),
@@ -4,16 +4,19 @@
  * Manages service lifecycle for databases and web servers
  */
 use super::{ServiceConfig, ServiceInstance, ServiceLog, ServiceStatus};
+use crate::command_executor::supervisor::{supervisor, ProcessSpec};
 use crate::domains::sdk::SDKError;
 use crate::process_ext::NoWindowExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Command;
-use tokio::process::Command as AsyncCommand;
 use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
 use uuid::Uuid;
 
+/// Supervisor owner of service processes
+const SERVICE_OWNER: &str = "sdk-services";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceHealth {
     pub is_healthy: bool,
@@ -197,27 +200,18 @@ impl ServiceManager {
             }
         };
 
-        let child = cmd
-            .spawn()
+        // Services keep running when the app exits, as they always have
+        cmd.discard_output().persistent();
+        let child = supervisor()
+            .spawn(&cmd)
             .map_err(|e| SDKError::ManagerNotFound(format!("Failed to start service: {}", e)))?;
 
-        Ok(child.id().unwrap_or(0) as u32)
+        Ok(child.detach().unwrap_or(0))
     }
 
-    /// Stop the service process
+    /// Stop the service process along with any workers it forked
     async fn stop_service_process(&self, pid: u32) -> Result<(), SDKError> {
-        if cfg!(target_os = "windows") {
-            Command::new("taskkill")
-                .no_window()
-                .args(&["/PID", &pid.to_string(), "/F"])
-                .output()
-                .map_err(|e| SDKError::ManagerNotFound(format!("Failed to stop service: {}", e)))?;
-        } else {
-            Command::new("kill")
-                .args(&["-TERM", &pid.to_string()])
-                .output()
-                .map_err(|e| SDKError::ManagerNotFound(format!("Failed to stop service: {}", e)))?;
-        }
+        supervisor().kill_tree(pid).await;
         Ok(())
     }
 
@@ -226,15 +220,14 @@ impl ServiceManager {
         &self,
         version: &str,
         service: &ServiceInstance,
-    ) -> Result<AsyncCommand, SDKError> {
+    ) -> Result<ProcessSpec, SDKError> {
         let data_dir = service
             .config
             .data_dir
             .clone()
             .unwrap_or_else(|| format!("~/.portal/data/postgresql-{}", version));
 
-        let mut cmd = AsyncCommand::new("postgres");
-        cmd.no_window();
+        let mut cmd = ProcessSpec::new(SERVICE_OWNER, "postgres");
         cmd.args(&[
             "-D",
             &data_dir,
@@ -254,15 +247,14 @@ impl ServiceManager {
         &self,
         version: &str,
         service: &ServiceInstance,
-    ) -> Result<AsyncCommand, SDKError> {
+    ) -> Result<ProcessSpec, SDKError> {
         let data_dir = service
             .config
             .data_dir
             .clone()
             .unwrap_or_else(|| format!("~/.portal/data/mysql-{}", version));
 
-        let mut cmd = AsyncCommand::new("mysqld");
-        cmd.no_window();
+        let mut cmd = ProcessSpec::new(SERVICE_OWNER, "mysqld");
         cmd.args(&[
             "--datadir",
             &data_dir,
@@ -278,9 +270,8 @@ impl ServiceManager {
         &self,
         _version: &str,
         service: &ServiceInstance,
-    ) -> Result<AsyncCommand, SDKError> {
-        let mut cmd = AsyncCommand::new("redis-server");
-        cmd.no_window();
+    ) -> Result<ProcessSpec, SDKError> {
+        let mut cmd = ProcessSpec::new(SERVICE_OWNER, "redis-server");
         cmd.args(&["--port", &service.port.unwrap_or(6379).to_string()]);
 
         if let Some(config_file) = &service.config.config_file {
@@ -295,9 +286,8 @@ impl ServiceManager {
         &self,
         _version: &str,
         service: &ServiceInstance,
-    ) -> Result<AsyncCommand, SDKError> {
-        let mut cmd = AsyncCommand::new("nginx");
-        cmd.no_window();
+    ) -> Result<ProcessSpec, SDKError> {
+        let mut cmd = ProcessSpec::new(SERVICE_OWNER, "nginx");
         cmd.args(&["-g", "daemon off;"]);
 
         if let Some(config_file) = &service.config.config_file {
@@ -312,9 +302,8 @@ impl ServiceManager {
         &self,
         _version: &str,
         service: &ServiceInstance,
-    ) -> Result<AsyncCommand, SDKError> {
-        let mut cmd = AsyncCommand::new("httpd");
-        cmd.no_window();
+    ) -> Result<ProcessSpec, SDKError> {
+        let mut cmd = ProcessSpec::new(SERVICE_OWNER, "httpd");
         cmd.args(&["-D", "FOREGROUND"]);
 
         if let Some(config_file) = &service.config.config_file {
@@ -542,3 +531,468 @@ impl ServiceManager {
         }
     }
 }
+
+/// Status-change events from the background monitor
+pub const SERVICE_STATUS_EVENT: &str = "service-status-changed";
+
+/// Services the monitor probes, with the port each listens on by default
+const MONITORED_SERVICES: [(&str, u16); 6] = [
+    ("postgresql", 5432),
+    ("mysql", 3306),
+    ("mongodb", 27017),
+    ("redis", 6379),
+    ("nginx", 80),
+    ("ollama", 11434),
+];
+/// Samples kept per service
+const HISTORY_LIMIT: usize = 120;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Services stopped from Portal, which restart policies leave alone
+static USER_STOPPED: once_cell::sync::Lazy<std::sync::Mutex<std::collections::HashSet<String>>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+/// Restarts a service by name; the service commands supply this
+pub type ServiceRestarter =
+    fn(
+        String,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<String, String>> + Send>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    Never,
+    /// Restart a service that went down after being seen up
+    OnFailure,
+    /// Keep the service up, even if it was never seen running
+    Always,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    /// Attempts before giving up, until the service is healthy again
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    /// Wait before the first attempt; each later attempt waits one more
+    #[serde(default = "default_backoff_seconds")]
+    pub backoff_seconds: u64,
+}
+
+fn default_max_restarts() -> u32 {
+    3
+}
+
+fn default_backoff_seconds() -> u64 {
+    10
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            max_restarts: default_max_restarts(),
+            backoff_seconds: default_backoff_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSettings {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    /// Restart policy per service; services not listed are never restarted
+    #[serde(default)]
+    pub policies: HashMap<String, RestartPolicy>,
+}
+
+impl Default for MonitorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_seconds: 15,
+            policies: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitoredStatus {
+    Unknown,
+    Up,
+    Down,
+    Restarting,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthSample {
+    pub timestamp: String,
+    pub healthy: bool,
+    pub latency_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitoredService {
+    pub name: String,
+    pub port: u16,
+    pub status: MonitoredStatus,
+    /// When the current status began
+    pub since: Option<String>,
+    pub last_check: Option<String>,
+    pub latency_ms: Option<u64>,
+    /// Share of healthy samples in the history
+    pub uptime_percent: f64,
+    pub restarts: u32,
+    pub history: std::collections::VecDeque<HealthSample>,
+    #[serde(skip)]
+    seen_up: bool,
+    #[serde(skip)]
+    restart_attempts: u32,
+    #[serde(skip)]
+    last_restart: Option<std::time::Instant>,
+}
+
+impl MonitoredService {
+    fn new(name: &str, port: u16) -> Self {
+        Self {
+            name: name.to_string(),
+            port,
+            status: MonitoredStatus::Unknown,
+            since: None,
+            last_check: None,
+            latency_ms: None,
+            uptime_percent: 0.0,
+            restarts: 0,
+            history: std::collections::VecDeque::new(),
+            seen_up: false,
+            restart_attempts: 0,
+            last_restart: None,
+        }
+    }
+
+    /// Record a probe. Returns the previous status if it changed.
+    fn record(
+        &mut self,
+        healthy: bool,
+        latency_ms: Option<u64>,
+        now: &str,
+    ) -> Option<MonitoredStatus> {
+        self.history.push_back(HealthSample {
+            timestamp: now.to_string(),
+            healthy,
+            latency_ms,
+        });
+        while self.history.len() > HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        let healthy_samples = self.history.iter().filter(|s| s.healthy).count();
+        self.uptime_percent = healthy_samples as f64 * 100.0 / self.history.len() as f64;
+        self.last_check = Some(now.to_string());
+        self.latency_ms = latency_ms;
+
+        let status = if healthy {
+            self.seen_up = true;
+            self.restart_attempts = 0;
+            MonitoredStatus::Up
+        } else if self.status == MonitoredStatus::Restarting {
+            // Still coming up; the restart attempt decides what's next
+            MonitoredStatus::Restarting
+        } else {
+            MonitoredStatus::Down
+        };
+        if status == self.status {
+            return None;
+        }
+        let previous = std::mem::replace(&mut self.status, status);
+        self.since = Some(now.to_string());
+        Some(previous)
+    }
+
+    /// Whether the policy calls for a restart attempt now
+    fn restart_due(&self, policy: &RestartPolicy, user_stopped: bool) -> bool {
+        if user_stopped || self.status == MonitoredStatus::Up {
+            return false;
+        }
+        let eligible = match policy.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => self.seen_up,
+            RestartMode::Always => true,
+        };
+        if !eligible || self.restart_attempts >= policy.max_restarts {
+            return false;
+        }
+        let wait = Duration::from_secs(policy.backoff_seconds * (self.restart_attempts as u64 + 1));
+        self.last_restart.is_none_or(|at| at.elapsed() >= wait)
+    }
+}
+
+/// Background health monitor for the database, cache, web and AI services
+/// Portal manages. Probes run in parallel on an interval; status changes are
+/// emitted as `service-status-changed` events.
+pub struct ServiceMonitor {
+    settings: tokio::sync::RwLock<MonitorSettings>,
+    services: tokio::sync::RwLock<HashMap<String, MonitoredService>>,
+    settings_path: std::path::PathBuf,
+}
+
+impl ServiceMonitor {
+    pub fn new() -> Self {
+        let settings_path = crate::app_paths::config_dir().join("service_monitor.json");
+        let settings = std::fs::read_to_string(&settings_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let services = MONITORED_SERVICES
+            .iter()
+            .map(|(name, default_port)| {
+                let port = configured_port(name).unwrap_or(*default_port);
+                (name.to_string(), MonitoredService::new(name, port))
+            })
+            .collect();
+
+        Self {
+            settings: tokio::sync::RwLock::new(settings),
+            services: tokio::sync::RwLock::new(services),
+            settings_path,
+        }
+    }
+
+    pub async fn settings(&self) -> MonitorSettings {
+        self.settings.read().await.clone()
+    }
+
+    pub async fn update_settings(&self, mut settings: MonitorSettings) -> Result<(), SDKError> {
+        settings.interval_seconds = settings.interval_seconds.clamp(5, 3600);
+        settings.policies = settings
+            .policies
+            .into_iter()
+            .map(|(name, policy)| (canonical_service_name(&name), policy))
+            .collect();
+
+        if let Some(parent) = self.settings_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&settings).map_err(|e| {
+            SDKError::CommandFailed(format!("Failed to save monitor settings: {}", e))
+        })?;
+        std::fs::write(&self.settings_path, content)?;
+
+        *self.settings.write().await = settings;
+        Ok(())
+    }
+
+    pub async fn snapshot(&self) -> Vec<MonitoredService> {
+        let mut services: Vec<MonitoredService> =
+            self.services.read().await.values().cloned().collect();
+        services.sort_by(|a, b| a.name.cmp(&b.name));
+        services
+    }
+
+    pub async fn service(&self, name: &str) -> Option<MonitoredService> {
+        self.services
+            .read()
+            .await
+            .get(&canonical_service_name(name))
+            .cloned()
+    }
+
+    /// Probe on the configured interval until the app exits
+    pub async fn run(&self, app: tauri::AppHandle, restarter: ServiceRestarter) {
+        loop {
+            let settings = self.settings().await;
+            if settings.enabled {
+                self.check_all(&app, &settings, restarter).await;
+            }
+            sleep(Duration::from_secs(settings.interval_seconds.max(5))).await;
+        }
+    }
+
+    async fn check_all(
+        &self,
+        app: &tauri::AppHandle,
+        settings: &MonitorSettings,
+        restarter: ServiceRestarter,
+    ) {
+        use tauri::Emitter;
+
+        let targets: Vec<(String, u16)> = self
+            .services
+            .read()
+            .await
+            .values()
+            .map(|service| (service.name.clone(), service.port))
+            .collect();
+        let probes =
+            futures_util::future::join_all(targets.iter().map(|(_, port)| probe_port(*port))).await;
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut restarts = Vec::new();
+        {
+            let mut services = self.services.write().await;
+            let user_stopped = USER_STOPPED
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            for ((name, _), latency) in targets.iter().zip(probes) {
+                let Some(service) = services.get_mut(name) else {
+                    continue;
+                };
+                let changed = service.record(latency.is_some(), latency, &now);
+
+                // A service that was never up isn't news
+                if let Some(previous) = changed.filter(|previous| {
+                    !(*previous == MonitoredStatus::Unknown
+                        && service.status == MonitoredStatus::Down)
+                }) {
+                    let _ = app.emit(
+                        SERVICE_STATUS_EVENT,
+                        serde_json::json!({
+                            "service": name,
+                            "previous": previous,
+                            "status": service.status,
+                            "latency_ms": latency,
+                            "timestamp": now,
+                        }),
+                    );
+                }
+
+                let policy = settings.policies.get(name).cloned().unwrap_or_default();
+                if service.restart_due(&policy, user_stopped.contains(name)) {
+                    service.restart_attempts += 1;
+                    service.last_restart = Some(std::time::Instant::now());
+                    service.status = MonitoredStatus::Restarting;
+                    service.since = Some(now.clone());
+                    restarts.push((name.clone(), service.restart_attempts, policy.max_restarts));
+                }
+            }
+        }
+
+        for (name, attempt, max_attempts) in restarts {
+            let _ = app.emit(
+                SERVICE_STATUS_EVENT,
+                serde_json::json!({
+                    "service": name,
+                    "previous": MonitoredStatus::Down,
+                    "status": MonitoredStatus::Restarting,
+                    "attempt": attempt,
+                    "max_attempts": max_attempts,
+                    "timestamp": now,
+                }),
+            );
+            let result = restarter(name.clone()).await;
+
+            let mut services = self.services.write().await;
+            let Some(service) = services.get_mut(&name) else {
+                continue;
+            };
+            match result {
+                Ok(_) => {
+                    service.restarts += 1;
+                    println!(
+                        "[ServiceMonitor] Restarted {} (attempt {}/{})",
+                        name, attempt, max_attempts
+                    );
+                }
+                Err(e) => {
+                    // Back to down so the next attempt waits out its backoff
+                    service.status = MonitoredStatus::Down;
+                    eprintln!(
+                        "[ServiceMonitor] Restart of {} failed (attempt {}/{}): {}",
+                        name, attempt, max_attempts, e
+                    );
+                    let _ = app.emit(
+                        SERVICE_STATUS_EVENT,
+                        serde_json::json!({
+                            "service": name,
+                            "previous": MonitoredStatus::Restarting,
+                            "status": MonitoredStatus::Down,
+                            "error": e,
+                            "timestamp": chrono::Utc::now().to_rfc3339(),
+                        }),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Record that the user stopped or started a service from Portal, so
+/// restart policies don't fight them
+pub fn note_user_action(service: &str, stopped: bool) {
+    let name = canonical_service_name(service);
+    let mut user_stopped = USER_STOPPED.lock().unwrap_or_else(|e| e.into_inner());
+    if stopped {
+        user_stopped.insert(name);
+    } else {
+        user_stopped.remove(&name);
+    }
+}
+
+/// The monitor's name for a service type alias
+pub fn canonical_service_name(service: &str) -> String {
+    match service.to_lowercase().as_str() {
+        "postgres" => "postgresql".to_string(),
+        "mongo" | "mongod" => "mongodb".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn configured_port(service: &str) -> Option<u16> {
+    let config = crate::domains::sdk::configs::language_config::get_sdk_config(service)?;
+    let port = config.service_config?.get("port")?.as_u64()?;
+    u16::try_from(port).ok()
+}
+
+/// Connect latency in milliseconds, or `None` if nothing is listening
+async fn probe_port(port: u16) -> Option<u64> {
+    let started = std::time::Instant::now();
+    match tokio::time::timeout(
+        PROBE_TIMEOUT,
+        tokio::net::TcpStream::connect(("127.0.0.1", port)),
+    )
+    .await
+    {
+        Ok(Ok(_)) => Some(started.elapsed().as_millis() as u64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_status_changes_and_restart_policy() {
+        let mut redis = MonitoredService::new("redis", 6379);
+        let on_failure = RestartPolicy {
+            mode: RestartMode::OnFailure,
+            max_restarts: 1,
+            backoff_seconds: 0,
+        };
+
+        assert_eq!(
+            redis.record(false, None, "t0"),
+            Some(MonitoredStatus::Unknown)
+        );
+        assert!(!redis.restart_due(&on_failure, false), "never seen up");
+
+        assert_eq!(
+            redis.record(true, Some(3), "t1"),
+            Some(MonitoredStatus::Down)
+        );
+        assert_eq!(redis.record(true, Some(2), "t2"), None);
+        assert_eq!(redis.record(false, None, "t3"), Some(MonitoredStatus::Up));
+        assert_eq!(redis.uptime_percent, 50.0);
+        assert!(redis.restart_due(&on_failure, false));
+        assert!(!redis.restart_due(&on_failure, true), "stopped by the user");
+        assert!(!redis.restart_due(&RestartPolicy::default(), false));
+
+        redis.restart_attempts = 1;
+        assert!(!redis.restart_due(&on_failure, false), "out of attempts");
+        redis.record(true, Some(1), "t4");
+        assert_eq!(redis.restart_attempts, 0);
+        assert_eq!(canonical_service_name("Postgres"), "postgresql");
+    }
+}
@@ -0,0 +1,686 @@
+/**
+ * Service Provisioner
+ *
+ * Provisions isolated, named database instances (PostgreSQL, MySQL,
+ * MongoDB, Redis) under `~/.portal-desktop/services/<service>/<name>`:
+ * each gets its own data directory, a config file rendered from a template
+ * with its port and auth, and a log file, so several instances of one
+ * database can run side by side without touching the system service.
+ */
+use super::{PortManager, ServiceConfig};
+use crate::domains::sdk::SDKError;
+use crate::process_ext::NoWindowExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::sync::Mutex;
+
+/// Serializes changes to the instance registry across commands
+static REGISTRY_LOCK: once_cell::sync::Lazy<Mutex<()>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+/// Services that can be provisioned, with their default ports
+pub const PROVISIONABLE_SERVICES: [(&str, u16); 4] = [
+    ("postgresql", 5432),
+    ("mysql", 3306),
+    ("mongodb", 27017),
+    ("redis", 6379),
+];
+
+const POSTGRESQL_TEMPLATE: &str = "# Managed by Portal Desktop for instance {{name}}
+listen_addresses = '{{host}}'
+port = {{port}}
+unix_socket_directories = '{{instance_dir}}'
+password_encryption = scram-sha-256
+";
+
+const MYSQL_TEMPLATE: &str = "# Managed by Portal Desktop for instance {{name}}
+[mysqld]
+datadir = {{data_dir}}
+port = {{port}}
+bind-address = {{host}}
+socket = {{instance_dir}}/mysqld.sock
+pid-file = {{instance_dir}}/mysqld.pid
+mysqlx = OFF
+{{auth}}
+[client]
+port = {{port}}
+socket = {{instance_dir}}/mysqld.sock
+";
+
+const MONGODB_TEMPLATE: &str = "# Managed by Portal Desktop for instance {{name}}
+storage:
+  dbPath: \"{{data_dir}}\"
+net:
+  port: {{port}}
+  bindIp: {{host}}
+";
+
+const REDIS_TEMPLATE: &str = "# Managed by Portal Desktop for instance {{name}}
+port {{port}}
+bind {{host}}
+dir \"{{data_dir}}\"
+daemonize no
+appendonly yes
+{{auth}}
+";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProvisionOptions {
+    pub port: Option<u16>,
+    pub host: Option<String>,
+    /// Superuser name, for PostgreSQL
+    pub username: Option<String>,
+    /// Enables authentication; not supported for MongoDB
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisionedInstance {
+    pub name: String,
+    pub service_type: String,
+    pub config: ServiceConfig,
+    pub username: Option<String>,
+    pub auth_enabled: bool,
+    pub created_at: String,
+    pub pid: Option<u32>,
+    #[serde(default)]
+    pub running: bool,
+}
+
+/// Provisions and runs named database instances
+pub struct ServiceProvisioner {
+    root: PathBuf,
+}
+
+impl ServiceProvisioner {
+    pub fn new() -> Result<Self, SDKError> {
+        let root = dirs::home_dir()
+            .ok_or_else(|| {
+                SDKError::ManagerNotFound("Could not determine home directory".to_string())
+            })?
+            .join(".portal-desktop")
+            .join("services");
+        Ok(Self { root })
+    }
+
+    fn registry_path(&self) -> PathBuf {
+        self.root.join("instances.json")
+    }
+
+    fn instance_dir(&self, service_type: &str, name: &str) -> PathBuf {
+        self.root.join(service_type).join(name)
+    }
+
+    fn load(&self) -> Vec<ProvisionedInstance> {
+        std::fs::read_to_string(self.registry_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, instances: &[ProvisionedInstance]) -> Result<(), SDKError> {
+        std::fs::create_dir_all(&self.root)?;
+        let content = serde_json::to_string_pretty(instances)
+            .map_err(|e| SDKError::CommandFailed(format!("Failed to save instances: {}", e)))?;
+        std::fs::write(self.registry_path(), content)?;
+        Ok(())
+    }
+
+    /// Every provisioned instance, with whether it's running now
+    pub async fn list(&self) -> Vec<ProvisionedInstance> {
+        let mut instances = self.load();
+        for instance in &mut instances {
+            instance.running = match instance.config.port {
+                Some(port) => is_listening(port).await,
+                None => false,
+            };
+        }
+        instances
+    }
+
+    /// Initialize a data directory and render the config for a new instance
+    pub async fn provision(
+        &self,
+        service_type: &str,
+        name: &str,
+        options: ProvisionOptions,
+    ) -> Result<ProvisionedInstance, SDKError> {
+        let _guard = REGISTRY_LOCK.lock().await;
+        validate_name(name)?;
+        let default_port = default_port(service_type).ok_or_else(|| {
+            SDKError::ManagerNotFound(format!("{} can't be provisioned", service_type))
+        })?;
+        if service_type == "mongodb" && options.password.is_some() {
+            return Err(SDKError::CommandFailed(
+                "MongoDB instances start without auth; create users with mongosh after starting it"
+                    .to_string(),
+            ));
+        }
+
+        let mut instances = self.load();
+        if instances
+            .iter()
+            .any(|i| i.service_type == service_type && i.name == name)
+        {
+            return Err(SDKError::CommandFailed(format!(
+                "A {} instance named {} already exists",
+                service_type, name
+            )));
+        }
+
+        let taken: Vec<u16> = instances.iter().filter_map(|i| i.config.port).collect();
+        let port = match options.port {
+            Some(port) if taken.contains(&port) => {
+                return Err(SDKError::CommandFailed(format!(
+                    "Port {} is already used by another instance",
+                    port
+                )))
+            }
+            Some(port) => port,
+            None => free_port(default_port, &taken)?,
+        };
+        let host = options.host.unwrap_or_else(|| "127.0.0.1".to_string());
+
+        let instance_dir = self.instance_dir(service_type, name);
+        if instance_dir.exists() {
+            return Err(SDKError::CommandFailed(format!(
+                "{} already exists; remove it first",
+                instance_dir.display()
+            )));
+        }
+        let data_dir = instance_dir.join("data");
+        std::fs::create_dir_all(&data_dir)?;
+
+        let config_file = instance_dir.join(config_file_name(service_type));
+        let result = self
+            .initialize(
+                service_type,
+                name,
+                &host,
+                port,
+                &instance_dir,
+                &data_dir,
+                &config_file,
+                &options.username,
+                &options.password,
+            )
+            .await;
+        if let Err(e) = result {
+            let _ = std::fs::remove_dir_all(&instance_dir);
+            return Err(e);
+        }
+
+        let instance = ProvisionedInstance {
+            name: name.to_string(),
+            service_type: service_type.to_string(),
+            config: ServiceConfig {
+                port: Some(port),
+                host: Some(host),
+                data_dir: Some(data_dir.display().to_string()),
+                config_file: Some(config_file.display().to_string()),
+                environment: HashMap::new(),
+            },
+            username: match service_type {
+                "postgresql" => Some(options.username.unwrap_or_else(|| "postgres".to_string())),
+                "mysql" => Some("root".to_string()),
+                "redis" if options.password.is_some() => Some("default".to_string()),
+                _ => None,
+            },
+            auth_enabled: options.password.is_some(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            pid: None,
+            running: false,
+        };
+        instances.push(instance.clone());
+        self.save(&instances)?;
+
+        println!(
+            "[SDK] Provisioned {} instance {} on port {}",
+            service_type, name, port
+        );
+        Ok(instance)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn initialize(
+        &self,
+        service_type: &str,
+        name: &str,
+        host: &str,
+        port: u16,
+        instance_dir: &Path,
+        data_dir: &Path,
+        config_file: &Path,
+        username: &Option<String>,
+        password: &Option<String>,
+    ) -> Result<(), SDKError> {
+        let mut values = HashMap::from([
+            ("name", name.to_string()),
+            ("host", host.to_string()),
+            ("port", port.to_string()),
+            ("instance_dir", forward_slashes(instance_dir)),
+            ("data_dir", forward_slashes(data_dir)),
+            ("auth", String::new()),
+        ]);
+
+        match service_type {
+            "postgresql" => {
+                let mut cmd = tokio::process::Command::new(find_binary("initdb")?);
+                cmd.no_window()
+                    .arg("-D")
+                    .arg(data_dir)
+                    .arg("-U")
+                    .arg(username.as_deref().unwrap_or("postgres"))
+                    .arg("--encoding=UTF8");
+                // initdb reads the password from a file so it never shows up
+                // in the process list
+                let pwfile = instance_dir.join(".pwfile");
+                match password {
+                    Some(password) => {
+                        std::fs::write(&pwfile, password)?;
+                        cmd.arg("--auth=scram-sha-256")
+                            .arg(format!("--pwfile={}", pwfile.display()));
+                    }
+                    None => {
+                        cmd.arg("--auth=trust");
+                    }
+                }
+                let output = cmd.output().await;
+                let _ = std::fs::remove_file(&pwfile);
+                check_output("initdb", output?)?;
+
+                std::fs::write(config_file, render_template(POSTGRESQL_TEMPLATE, &values))?;
+                // Postgres reads its own postgresql.conf from the data
+                // directory; pull the rendered settings in from there
+                use std::io::Write;
+                let mut conf = std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(data_dir.join("postgresql.conf"))?;
+                writeln!(conf, "\ninclude '{}'", forward_slashes(config_file))?;
+            }
+            "mysql" => {
+                if let Some(password) = password {
+                    // Applied on every start, which keeps it in sync with the
+                    // config if the password is changed there
+                    let init_file = instance_dir.join("init.sql");
+                    std::fs::write(
+                        &init_file,
+                        format!(
+                            "ALTER USER 'root'@'localhost' IDENTIFIED BY '{}';\n",
+                            password.replace('\\', "\\\\").replace('\'', "''")
+                        ),
+                    )?;
+                    values.insert(
+                        "auth",
+                        format!("init-file = {}", forward_slashes(&init_file)),
+                    );
+                }
+                std::fs::write(config_file, render_template(MYSQL_TEMPLATE, &values))?;
+                let output = tokio::process::Command::new(find_binary("mysqld")?)
+                    .no_window()
+                    .arg(format!("--defaults-file={}", config_file.display()))
+                    .arg("--initialize-insecure")
+                    .output()
+                    .await?;
+                check_output("mysqld --initialize", output)?;
+            }
+            "mongodb" => {
+                std::fs::write(config_file, render_template(MONGODB_TEMPLATE, &values))?;
+            }
+            "redis" => {
+                if let Some(password) = password {
+                    values.insert(
+                        "auth",
+                        format!("requirepass \"{}\"", password.replace('"', "\\\"")),
+                    );
+                }
+                std::fs::write(config_file, render_template(REDIS_TEMPLATE, &values))?;
+            }
+            _ => unreachable!("checked by default_port"),
+        }
+        Ok(())
+    }
+
+    /// Start an instance in the background, logging to its directory. When
+    /// its port is taken, `auto_assign_port` moves it to the next free port
+    /// and keeps it there; otherwise the start fails naming the holder.
+    pub async fn start(
+        &self,
+        service_type: &str,
+        name: &str,
+        auto_assign_port: bool,
+    ) -> Result<ProvisionedInstance, SDKError> {
+        let _guard = REGISTRY_LOCK.lock().await;
+        let mut instances = self.load();
+        let taken: Vec<u16> = instances
+            .iter()
+            .filter(|i| !(i.service_type == service_type && i.name == name))
+            .filter_map(|i| i.config.port)
+            .collect();
+        let instance = find(&mut instances, service_type, name)?;
+        let port = instance.config.port.unwrap_or_default();
+        let mut check = PortManager::new().check_port(port).await;
+        if !check.available {
+            check.suggested_port = free_port(port.saturating_add(1), &taken).ok();
+            let Some(free) = check.suggested_port.filter(|_| auto_assign_port) else {
+                return Err(SDKError::CommandFailed(check.conflict_message()));
+            };
+            if let Some(config_file) = &instance.config.config_file {
+                let content = std::fs::read_to_string(config_file)?;
+                std::fs::write(config_file, replace_port(&content, port, free))?;
+            }
+            instance.config.port = Some(free);
+            println!(
+                "[SDK] Port {} is in use; moved {} instance {} to {}",
+                port, service_type, name, free
+            );
+        }
+
+        let instance_dir = self.instance_dir(service_type, name);
+        let config_file = instance.config.config_file.clone().unwrap_or_default();
+        let data_dir = instance.config.data_dir.clone().unwrap_or_default();
+        let mut cmd = match service_type {
+            "postgresql" => {
+                let mut cmd = std::process::Command::new(find_binary("postgres")?);
+                cmd.arg("-D").arg(&data_dir);
+                cmd
+            }
+            "mysql" => {
+                let mut cmd = std::process::Command::new(find_binary("mysqld")?);
+                cmd.arg(format!("--defaults-file={}", config_file));
+                cmd
+            }
+            "mongodb" => {
+                let mut cmd = std::process::Command::new(find_binary("mongod")?);
+                cmd.arg("--config").arg(&config_file);
+                cmd
+            }
+            "redis" => {
+                let mut cmd = std::process::Command::new(find_binary("redis-server")?);
+                cmd.arg(&config_file);
+                cmd
+            }
+            _ => {
+                return Err(SDKError::ManagerNotFound(format!(
+                    "{} can't be provisioned",
+                    service_type
+                )))
+            }
+        };
+
+        let log = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(instance_dir.join(format!("{}.log", service_type)))?;
+        let child = cmd
+            .no_window()
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()
+            .map_err(|e| SDKError::CommandFailed(format!("Failed to start {}: {}", name, e)))?;
+
+        instance.pid = Some(child.id());
+        instance.running = true;
+        let started = instance.clone();
+        self.save(&instances)?;
+
+        println!(
+            "[SDK] Started {} instance {} (pid {})",
+            service_type,
+            name,
+            child.id()
+        );
+        Ok(started)
+    }
+
+    pub async fn stop(&self, service_type: &str, name: &str) -> Result<(), SDKError> {
+        let _guard = REGISTRY_LOCK.lock().await;
+        let mut instances = self.load();
+        let instance = find(&mut instances, service_type, name)?;
+        let Some(pid) = instance.pid.take() else {
+            return Err(SDKError::CommandFailed(format!("{} is not running", name)));
+        };
+
+        // A polite stop lets each database flush and remove its pid file
+        let output = if cfg!(target_os = "windows") {
+            std::process::Command::new("taskkill")
+                .no_window()
+                .args(["/PID", &pid.to_string()])
+                .output()?
+        } else {
+            std::process::Command::new("kill")
+                .args(["-TERM", &pid.to_string()])
+                .output()?
+        };
+        instance.running = false;
+        self.save(&instances)?;
+
+        if !output.status.success() {
+            // Already gone, most likely; the registry is cleaned up either way
+            println!(
+                "[SDK] Stopping {} (pid {}): {}",
+                name,
+                pid,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Forget an instance, deleting its directory when `delete_data` is set
+    pub async fn remove(
+        &self,
+        service_type: &str,
+        name: &str,
+        delete_data: bool,
+    ) -> Result<(), SDKError> {
+        let running = self
+            .list()
+            .await
+            .iter()
+            .any(|i| i.service_type == service_type && i.name == name && i.running);
+        if running {
+            return Err(SDKError::CommandFailed(format!(
+                "Stop {} before removing it",
+                name
+            )));
+        }
+
+        let _guard = REGISTRY_LOCK.lock().await;
+        let mut instances = self.load();
+        find(&mut instances, service_type, name)?;
+        instances.retain(|i| !(i.service_type == service_type && i.name == name));
+        self.save(&instances)?;
+
+        if delete_data {
+            let instance_dir = self.instance_dir(service_type, name);
+            if instance_dir.exists() {
+                std::fs::remove_dir_all(instance_dir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn find<'a>(
+    instances: &'a mut [ProvisionedInstance],
+    service_type: &str,
+    name: &str,
+) -> Result<&'a mut ProvisionedInstance, SDKError> {
+    instances
+        .iter_mut()
+        .find(|i| i.service_type == service_type && i.name == name)
+        .ok_or_else(|| {
+            SDKError::ManagerNotFound(format!("No {} instance named {}", service_type, name))
+        })
+}
+
+fn default_port(service_type: &str) -> Option<u16> {
+    PROVISIONABLE_SERVICES
+        .iter()
+        .find(|(service, _)| *service == service_type)
+        .map(|(_, port)| *port)
+}
+
+fn config_file_name(service_type: &str) -> &'static str {
+    match service_type {
+        "postgresql" => "portal.conf",
+        "mysql" => "my.cnf",
+        "mongodb" => "mongod.conf",
+        _ => "redis.conf",
+    }
+}
+
+/// Instance names become directory names
+fn validate_name(name: &str) -> Result<(), SDKError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(SDKError::CommandFailed(format!(
+            "Invalid instance name '{}': use letters, digits, '-' and '_'",
+            name
+        )))
+    }
+}
+
+/// Replace `{{key}}` placeholders
+fn render_template(template: &str, values: &HashMap<&str, String>) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (key, value)| {
+            text.replace(&format!("{{{{{}}}}}", key), value)
+        })
+}
+
+/// Config files on every platform accept forward slashes, and Windows
+/// backslashes would need escaping in several of them
+fn forward_slashes(path: &Path) -> String {
+    path.display().to_string().replace('\\', "/")
+}
+
+/// The first port from `start` up that's neither taken by another instance
+/// nor in use on this machine
+fn free_port(start: u16, taken: &[u16]) -> Result<u16, SDKError> {
+    (start..start.saturating_add(100))
+        .find(|port| {
+            !taken.contains(port) && std::net::TcpListener::bind(("127.0.0.1", *port)).is_ok()
+        })
+        .ok_or_else(|| SDKError::CommandFailed(format!("No free port near {}", start)))
+}
+
+/// Point a rendered config's `port` lines (`port = 5432`, `port: 27017`,
+/// `port 6379`) at a new port
+fn replace_port(content: &str, old: u16, new: u16) -> String {
+    let old = old.to_string();
+    content
+        .lines()
+        .map(|line| {
+            let value = line
+                .trim_start()
+                .strip_prefix("port")
+                .map(|rest| rest.trim_start_matches([' ', '=', ':']).trim_end());
+            if value == Some(old.as_str()) {
+                line.replacen(&old, &new.to_string(), 1)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+async fn is_listening(port: u16) -> bool {
+    tokio::time::timeout(
+        std::time::Duration::from_millis(500),
+        tokio::net::TcpStream::connect(("127.0.0.1", port)),
+    )
+    .await
+    .is_ok_and(|result| result.is_ok())
+}
+
+/// Locate a server binary; Debian-family PostgreSQL keeps its server
+/// binaries out of PATH under /usr/lib/postgresql/<version>/bin
+fn find_binary(name: &str) -> Result<PathBuf, SDKError> {
+    if let Ok(path) = which::which(name) {
+        return Ok(path);
+    }
+    if let Ok(entries) = std::fs::read_dir("/usr/lib/postgresql") {
+        let mut candidates: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path().join("bin").join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        candidates.sort();
+        if let Some(newest) = candidates.pop() {
+            return Ok(newest);
+        }
+    }
+    Err(SDKError::ManagerNotFound(format!(
+        "{} was not found; install it first",
+        name
+    )))
+}
+
+fn check_output(step: &str, output: std::process::Output) -> Result<(), SDKError> {
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SDKError::CommandFailed(format!(
+            "{} failed: {}",
+            step,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_instance_configs() {
+        let values = HashMap::from([
+            ("name", "cache".to_string()),
+            ("host", "127.0.0.1".to_string()),
+            ("port", "6380".to_string()),
+            (
+                "data_dir",
+                "/home/dev/.portal-desktop/services/redis/cache/data".to_string(),
+            ),
+            ("auth", "requirepass \"s3cret\"".to_string()),
+        ]);
+        let config = render_template(REDIS_TEMPLATE, &values);
+        assert!(config.contains("port 6380\n"));
+        assert!(config.contains("dir \"/home/dev/.portal-desktop/services/redis/cache/data\"\n"));
+        assert!(config.contains("requirepass \"s3cret\"\n"));
+        assert!(!config.contains("{{"));
+
+        assert!(validate_name("orders-db_2").is_ok());
+        assert!(validate_name("../etc").is_err());
+        assert_eq!(default_port("mongodb"), Some(27017));
+        assert_eq!(default_port("nginx"), None);
+    }
+
+    #[test]
+    fn moves_config_to_new_port() {
+        let mysql = "[mysqld]\nport = 3306\nsocket = /tmp/3306.sock\n[client]\nport = 3306\n";
+        assert_eq!(
+            replace_port(mysql, 3306, 3307),
+            "[mysqld]\nport = 3307\nsocket = /tmp/3306.sock\n[client]\nport = 3307\n"
+        );
+        assert_eq!(
+            replace_port("net:\n  port: 27017\n", 27017, 27018),
+            "net:\n  port: 27018\n"
+        );
+        assert_eq!(replace_port("port 6379\n", 6379, 6380), "port 6380\n");
+    }
+}
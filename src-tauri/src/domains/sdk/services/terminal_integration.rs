@@ -58,6 +58,7 @@ impl TerminalIntegrationService {
             ".php-version",
             "rust-toolchain",
             "go.mod",
+            ".tool-versions",
             ".portal_env",
         ];
 
@@ -0,0 +1,532 @@
+/**
+ * Virtual Host Manager
+ *
+ * Generates site configuration for nginx, Apache and Caddy from a
+ * structured description (domain, document root or proxy target, TLS) under
+ * `~/.portal-desktop/vhosts/<server>/sites`. nginx and Apache pick the
+ * directory up through one include line in their main config; Caddy is
+ * pointed at a Caddyfile here that imports it. Every change is checked
+ * with the server's own config test and rolled back if it fails, then the
+ * server is reloaded.
+ */
+use crate::domains::sdk::SDKError;
+use crate::process_ext::NoWindowExt;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+/// Serializes changes to the site registry across commands
+static REGISTRY_LOCK: once_cell::sync::Lazy<Mutex<()>> =
+    once_cell::sync::Lazy::new(Default::default);
+
+pub const VHOST_SERVERS: [&str; 3] = ["nginx", "apache", "caddy"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert_file: String,
+    pub key_file: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteConfig {
+    pub domain: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Directory served as static files
+    pub root: Option<String>,
+    /// Upstream proxied to, e.g. `localhost:3000`; takes precedence over `root`
+    pub proxy_target: Option<String>,
+    pub tls: Option<TlsConfig>,
+    /// Defaults to 80, or 443 with TLS
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Site {
+    pub server: String,
+    pub config: SiteConfig,
+    pub config_file: PathBuf,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VhostServerStatus {
+    pub server: String,
+    pub installed: bool,
+    pub sites_dir: PathBuf,
+    /// Line the main config needs to load the sites
+    pub include_directive: String,
+    /// Whether the main config has it; unknown when it can't be read
+    pub included: Option<bool>,
+}
+
+pub struct VhostManager {
+    root: PathBuf,
+}
+
+impl VhostManager {
+    pub fn new() -> Result<Self, SDKError> {
+        let root = dirs::home_dir()
+            .ok_or_else(|| {
+                SDKError::ManagerNotFound("Could not determine home directory".to_string())
+            })?
+            .join(".portal-desktop")
+            .join("vhosts");
+        Ok(Self { root })
+    }
+
+    fn registry_path(&self) -> PathBuf {
+        self.root.join("sites.json")
+    }
+
+    fn sites_dir(&self, server: &str) -> PathBuf {
+        self.root.join(server).join("sites")
+    }
+
+    /// The Caddyfile Portal runs Caddy with
+    fn caddyfile(&self) -> PathBuf {
+        self.root.join("caddy").join("Caddyfile")
+    }
+
+    fn load(&self) -> Vec<Site> {
+        std::fs::read_to_string(self.registry_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, sites: &[Site]) -> Result<(), SDKError> {
+        std::fs::create_dir_all(&self.root)?;
+        let content = serde_json::to_string_pretty(sites)
+            .map_err(|e| SDKError::CommandFailed(format!("Failed to save sites: {}", e)))?;
+        std::fs::write(self.registry_path(), content)?;
+        Ok(())
+    }
+
+    pub fn list(&self, server: Option<&str>) -> Vec<Site> {
+        self.load()
+            .into_iter()
+            .filter(|site| server.is_none_or(|server| site.server == server))
+            .collect()
+    }
+
+    pub async fn status(&self, server: &str) -> Result<VhostServerStatus, SDKError> {
+        check_server(server)?;
+        let include_directive = include_directive(server, &self.sites_dir(server));
+        let included = match server {
+            "caddy" => Some(self.caddyfile().is_file()),
+            _ => main_config(server).await.map(|config| {
+                std::fs::read_to_string(config).is_ok_and(|content| {
+                    content.contains(&*self.sites_dir(server).to_string_lossy())
+                })
+            }),
+        };
+        Ok(VhostServerStatus {
+            server: server.to_string(),
+            installed: server_binary(server).is_some(),
+            sites_dir: self.sites_dir(server),
+            include_directive,
+            included,
+        })
+    }
+
+    /// Write a site's config, keeping the previous one if the server
+    /// rejects the result, then reload the server
+    pub async fn save_site(&self, server: &str, config: SiteConfig) -> Result<Site, SDKError> {
+        check_server(server)?;
+        validate_site(&config)?;
+        let _guard = REGISTRY_LOCK.lock().await;
+
+        let sites_dir = self.sites_dir(server);
+        std::fs::create_dir_all(&sites_dir)?;
+        if server == "caddy" && !self.caddyfile().is_file() {
+            std::fs::write(
+                self.caddyfile(),
+                format!(
+                    "# Managed by Portal Desktop\n{}\n",
+                    include_directive(server, &sites_dir)
+                ),
+            )?;
+        }
+
+        let config_file = sites_dir.join(site_file_name(server, &config.domain));
+        let previous = std::fs::read_to_string(&config_file).ok();
+        std::fs::write(&config_file, render_site(server, &config))?;
+
+        if let Err(e) = self.test_config(server).await {
+            match previous {
+                Some(previous) => std::fs::write(&config_file, previous)?,
+                None => std::fs::remove_file(&config_file)?,
+            }
+            return Err(e);
+        }
+
+        let site = Site {
+            server: server.to_string(),
+            config,
+            config_file,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let mut sites = self.load();
+        sites.retain(|s| !(s.server == server && s.config.domain == site.config.domain));
+        sites.push(site.clone());
+        self.save(&sites)?;
+
+        self.reload(server).await?;
+        println!("[SDK] Configured {} site {}", server, site.config.domain);
+        Ok(site)
+    }
+
+    pub async fn remove_site(&self, server: &str, domain: &str) -> Result<(), SDKError> {
+        check_server(server)?;
+        let _guard = REGISTRY_LOCK.lock().await;
+
+        let mut sites = self.load();
+        let Some(index) = sites
+            .iter()
+            .position(|s| s.server == server && s.config.domain == domain)
+        else {
+            return Err(SDKError::CommandFailed(format!(
+                "No {} site for {}",
+                server, domain
+            )));
+        };
+        let site = sites.remove(index);
+        if site.config_file.exists() {
+            std::fs::remove_file(&site.config_file)?;
+        }
+        self.save(&sites)?;
+
+        self.test_config(server).await?;
+        self.reload(server).await
+    }
+
+    /// Run the server's config test (`nginx -t` and the like)
+    pub async fn test_config(&self, server: &str) -> Result<String, SDKError> {
+        let caddyfile = self.caddyfile().to_string_lossy().to_string();
+        let args: Vec<&str> = match server {
+            "nginx" | "apache" => vec!["-t"],
+            _ => vec!["validate", "--config", &caddyfile, "--adapter", "caddyfile"],
+        };
+        run_server(server, &args).await
+    }
+
+    async fn reload(&self, server: &str) -> Result<(), SDKError> {
+        let caddyfile = self.caddyfile().to_string_lossy().to_string();
+        let args: Vec<&str> = match server {
+            "nginx" => vec!["-s", "reload"],
+            "apache" => vec!["-k", "graceful"],
+            _ => vec!["reload", "--config", &caddyfile, "--adapter", "caddyfile"],
+        };
+        match run_server(server, &args).await {
+            Ok(_) => Ok(()),
+            // A stopped server picks the site up when it starts
+            Err(e) if !is_running(server).await => {
+                println!("[SDK] {} is not running, skipped reload: {}", server, e);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn check_server(server: &str) -> Result<(), SDKError> {
+    if VHOST_SERVERS.contains(&server) {
+        Ok(())
+    } else {
+        Err(SDKError::ManagerNotFound(format!(
+            "Virtual hosts are not supported for {}",
+            server
+        )))
+    }
+}
+
+fn validate_site(config: &SiteConfig) -> Result<(), SDKError> {
+    for domain in std::iter::once(&config.domain).chain(&config.aliases) {
+        let valid = !domain.is_empty()
+            && domain
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '*'));
+        if !valid {
+            return Err(SDKError::CommandFailed(format!(
+                "Invalid domain: {:?}",
+                domain
+            )));
+        }
+    }
+    if config.root.is_none() && config.proxy_target.is_none() {
+        return Err(SDKError::CommandFailed(
+            "A site needs a document root or a proxy target".to_string(),
+        ));
+    }
+
+    // Values end up inside config syntax, so keep them to one plain token
+    let values = [
+        config.root.as_deref(),
+        config.proxy_target.as_deref(),
+        config.tls.as_ref().map(|tls| tls.cert_file.as_str()),
+        config.tls.as_ref().map(|tls| tls.key_file.as_str()),
+    ];
+    for value in values.into_iter().flatten() {
+        if value.is_empty() || value.contains(['"', ';', '{', '}', '\n', '\r', '#']) {
+            return Err(SDKError::CommandFailed(format!(
+                "Invalid site setting: {:?}",
+                value
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn site_file_name(server: &str, domain: &str) -> String {
+    let stem = domain.replace('*', "_wildcard_");
+    match server {
+        "caddy" => format!("{}.caddy", stem),
+        _ => format!("{}.conf", stem),
+    }
+}
+
+fn include_directive(server: &str, sites_dir: &Path) -> String {
+    let dir = sites_dir.to_string_lossy();
+    match server {
+        "nginx" => format!("include \"{}/*.conf\";", dir),
+        "apache" => format!("IncludeOptional \"{}/*.conf\"", dir),
+        _ => format!("import {}/*.caddy", dir),
+    }
+}
+
+fn proxy_url(target: &str) -> String {
+    if target.starts_with("http://") || target.starts_with("https://") {
+        target.trim_end_matches('/').to_string()
+    } else {
+        format!("http://{}", target.trim_end_matches('/'))
+    }
+}
+
+/// The config block for a site
+fn render_site(server: &str, config: &SiteConfig) -> String {
+    let port = config
+        .port
+        .unwrap_or(if config.tls.is_some() { 443 } else { 80 });
+    let names = std::iter::once(config.domain.as_str())
+        .chain(config.aliases.iter().map(String::as_str))
+        .collect::<Vec<_>>();
+    let mut out = format!("# Managed by Portal Desktop for {}\n", config.domain);
+
+    match server {
+        "nginx" => {
+            out.push_str("server {\n");
+            match &config.tls {
+                Some(tls) => {
+                    out.push_str(&format!("    listen {} ssl;\n", port));
+                    out.push_str(&format!("    ssl_certificate {};\n", tls.cert_file));
+                    out.push_str(&format!("    ssl_certificate_key {};\n", tls.key_file));
+                }
+                None => out.push_str(&format!("    listen {};\n", port)),
+            }
+            out.push_str(&format!("    server_name {};\n", names.join(" ")));
+            match (&config.proxy_target, &config.root) {
+                (Some(target), _) => {
+                    out.push_str("    location / {\n");
+                    out.push_str(&format!("        proxy_pass {};\n", proxy_url(target)));
+                    out.push_str("        proxy_http_version 1.1;\n");
+                    out.push_str("        proxy_set_header Host $host;\n");
+                    out.push_str("        proxy_set_header X-Real-IP $remote_addr;\n");
+                    out.push_str(
+                        "        proxy_set_header X-Forwarded-For $proxy_add_x_forwarded_for;\n",
+                    );
+                    out.push_str("        proxy_set_header X-Forwarded-Proto $scheme;\n");
+                    out.push_str("        proxy_set_header Upgrade $http_upgrade;\n");
+                    out.push_str("        proxy_set_header Connection \"upgrade\";\n");
+                    out.push_str("    }\n");
+                }
+                (None, Some(root)) => {
+                    out.push_str(&format!("    root \"{}\";\n", root));
+                    out.push_str("    index index.html index.htm index.php;\n");
+                    out.push_str("    location / {\n");
+                    out.push_str("        try_files $uri $uri/ =404;\n");
+                    out.push_str("    }\n");
+                }
+                (None, None) => {}
+            }
+            out.push_str("}\n");
+        }
+        "apache" => {
+            out.push_str(&format!("<VirtualHost *:{}>\n", port));
+            out.push_str(&format!("    ServerName {}\n", config.domain));
+            if !config.aliases.is_empty() {
+                out.push_str(&format!("    ServerAlias {}\n", config.aliases.join(" ")));
+            }
+            if let Some(tls) = &config.tls {
+                out.push_str("    SSLEngine on\n");
+                out.push_str(&format!("    SSLCertificateFile \"{}\"\n", tls.cert_file));
+                out.push_str(&format!("    SSLCertificateKeyFile \"{}\"\n", tls.key_file));
+            }
+            match (&config.proxy_target, &config.root) {
+                (Some(target), _) => {
+                    let url = proxy_url(target);
+                    out.push_str("    ProxyPreserveHost On\n");
+                    out.push_str(&format!("    ProxyPass / {}/\n", url));
+                    out.push_str(&format!("    ProxyPassReverse / {}/\n", url));
+                }
+                (None, Some(root)) => {
+                    out.push_str(&format!("    DocumentRoot \"{}\"\n", root));
+                    out.push_str(&format!("    <Directory \"{}\">\n", root));
+                    out.push_str("        AllowOverride All\n");
+                    out.push_str("        Require all granted\n");
+                    out.push_str("    </Directory>\n");
+                }
+                (None, None) => {}
+            }
+            out.push_str("</VirtualHost>\n");
+        }
+        _ => {
+            // Without certificates, stay on plain HTTP rather than letting
+            // Caddy try to obtain public ones for a local domain
+            let addresses = names
+                .iter()
+                .map(|name| match &config.tls {
+                    Some(_) => format!("{}:{}", name, port),
+                    None => format!("http://{}:{}", name, port),
+                })
+                .collect::<Vec<_>>();
+            out.push_str(&format!("{} {{\n", addresses.join(", ")));
+            if let Some(tls) = &config.tls {
+                out.push_str(&format!("    tls {} {}\n", tls.cert_file, tls.key_file));
+            }
+            match (&config.proxy_target, &config.root) {
+                (Some(target), _) => {
+                    out.push_str(&format!("    reverse_proxy {}\n", proxy_url(target)));
+                }
+                (None, Some(root)) => {
+                    out.push_str(&format!("    root * \"{}\"\n", root));
+                    out.push_str("    file_server\n");
+                }
+                (None, None) => {}
+            }
+            out.push_str("}\n");
+        }
+    }
+    out
+}
+
+/// The server's control binary; Apache goes by several names
+fn server_binary(server: &str) -> Option<PathBuf> {
+    let candidates: &[&str] = match server {
+        "nginx" => &["nginx"],
+        "apache" => &["apachectl", "apache2ctl", "httpd"],
+        "caddy" => &["caddy"],
+        _ => &[],
+    };
+    candidates.iter().find_map(|name| which::which(name).ok())
+}
+
+async fn run_server(server: &str, args: &[&str]) -> Result<String, SDKError> {
+    let binary = server_binary(server)
+        .ok_or_else(|| SDKError::ManagerNotFound(format!("{} is not installed", server)))?;
+    let output = tokio::process::Command::new(&binary)
+        .no_window()
+        .args(args)
+        .output()
+        .await?;
+    // nginx and Apache report config test results on stderr
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if output.status.success() {
+        Ok(text.trim().to_string())
+    } else {
+        Err(SDKError::CommandFailed(format!(
+            "{} {} failed: {}",
+            server,
+            args.join(" "),
+            text.trim()
+        )))
+    }
+}
+
+async fn is_running(server: &str) -> bool {
+    let process = match server {
+        "apache" => "apache2|httpd",
+        other => other,
+    };
+    if cfg!(target_os = "windows") {
+        return false;
+    }
+    tokio::process::Command::new("pgrep")
+        .args(["-x", process])
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Main config file, from the build settings `nginx -V`/`apachectl -V` print
+async fn main_config(server: &str) -> Option<PathBuf> {
+    let output = run_server(server, &["-V"]).await.ok()?;
+    match server {
+        "nginx" => output
+            .split_whitespace()
+            .find_map(|arg| arg.strip_prefix("--conf-path="))
+            .map(PathBuf::from),
+        _ => {
+            let define = |name: &str| {
+                output.lines().find_map(|line| {
+                    let value = line.trim().strip_prefix(&format!("-D {}=", name))?;
+                    Some(value.trim_matches('"').to_string())
+                })
+            };
+            let config = PathBuf::from(define("SERVER_CONFIG_FILE")?);
+            Some(match define("HTTPD_ROOT") {
+                Some(root) if config.is_relative() => PathBuf::from(root).join(config),
+                _ => config,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_site_blocks() {
+        let proxy = SiteConfig {
+            domain: "app.test".to_string(),
+            aliases: vec!["www.app.test".to_string()],
+            root: None,
+            proxy_target: Some("localhost:3000".to_string()),
+            tls: None,
+            port: None,
+        };
+        let nginx = render_site("nginx", &proxy);
+        assert!(nginx.contains("listen 80;"));
+        assert!(nginx.contains("server_name app.test www.app.test;"));
+        assert!(nginx.contains("proxy_pass http://localhost:3000;"));
+
+        let caddy = render_site("caddy", &proxy);
+        assert!(caddy.contains("http://app.test:80, http://www.app.test:80 {"));
+        assert!(caddy.contains("reverse_proxy http://localhost:3000"));
+
+        let static_site = SiteConfig {
+            root: Some("/srv/site".to_string()),
+            proxy_target: None,
+            tls: Some(TlsConfig {
+                cert_file: "/certs/app.pem".to_string(),
+                key_file: "/certs/app-key.pem".to_string(),
+            }),
+            ..proxy
+        };
+        let apache = render_site("apache", &static_site);
+        assert!(apache.contains("<VirtualHost *:443>"));
+        assert!(apache.contains("DocumentRoot \"/srv/site\""));
+        assert!(apache.contains("SSLCertificateFile \"/certs/app.pem\""));
+
+        let injected = SiteConfig {
+            root: Some("/srv; include /etc/passwd".to_string()),
+            ..static_site
+        };
+        assert!(validate_site(&injected).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::domains::sdk::download::mirrors::download_base;
 use crate::process_ext::NoWindowExt;
 use reqwest;
 use serde::{Deserialize, Serialize};
@@ -14,7 +15,7 @@ pub struct SDKVersion {
 }
 
 pub async fn fetch_nodejs_versions() -> Result<Vec<SDKVersion>, String> {
-    let url = "https://nodejs.org/dist/index.json";
+    let url = format!("{}/index.json", download_base("nodejs"));
     let response = reqwest::get(url).await.map_err(|e| e.to_string())?;
     let json: Vec<Value> = response.json().await.map_err(|e| e.to_string())?;
 
@@ -124,7 +125,7 @@ pub async fn fetch_rust_versions() -> Result<Vec<SDKVersion>, String> {
 }
 
 pub async fn fetch_go_versions() -> Result<Vec<SDKVersion>, String> {
-    let url = "https://go.dev/dl/?mode=json";
+    let url = format!("{}/?mode=json", download_base("go"));
     let response = reqwest::get(url).await.map_err(|e| e.to_string())?;
     let json: Vec<Value> = response.json().await.map_err(|e| e.to_string())?;
 
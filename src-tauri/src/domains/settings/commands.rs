@@ -1,4 +1,12 @@
+use super::services::global_shortcuts;
+use super::services::keybindings_service::{
+    detect_conflicts, Keybinding, KeybindingConflict, KeybindingsService,
+};
+use super::services::project_settings_service::{
+    EffectiveProjectSettings, ProjectSettingsOverrides, ProjectSettingsService,
+};
 use super::services::settings_service::{Settings, SettingsService, SettingsUpdate};
+use crate::database::DatabaseManager;
 use std::sync::Arc;
 use tauri::State;
 
@@ -54,3 +62,106 @@ pub async fn import_settings_command(
 ) -> Result<Settings, String> {
     _state.import_settings(&settings_json)
 }
+
+#[tauri::command]
+pub async fn get_project_settings(
+    project_id: i32,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<ProjectSettingsOverrides, String> {
+    ProjectSettingsService::new(db_manager.inner().clone())
+        .get_overrides(project_id)
+        .await
+}
+
+#[tauri::command]
+pub async fn set_project_settings(
+    project_id: i32,
+    overrides: ProjectSettingsOverrides,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<ProjectSettingsOverrides, String> {
+    ProjectSettingsService::new(db_manager.inner().clone())
+        .set_overrides(project_id, overrides)
+        .await
+}
+
+/// Global settings merged with a project's overrides
+#[tauri::command]
+pub async fn get_effective_project_settings(
+    project_id: i32,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<EffectiveProjectSettings, String> {
+    ProjectSettingsService::new(db_manager.inner().clone())
+        .effective(project_id)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_keybindings(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<Keybinding>, String> {
+    let bindings = KeybindingsService::new(db_manager.inner().clone())
+        .list()
+        .await?;
+    Ok(global_shortcuts::annotate(bindings))
+}
+
+/// Bind an action to a shortcut, or disable it with `null`
+#[tauri::command]
+pub async fn set_keybinding(
+    app_handle: tauri::AppHandle,
+    action: String,
+    shortcut: Option<String>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<Keybinding>, String> {
+    let bindings = KeybindingsService::new(db_manager.inner().clone())
+        .set(&action, shortcut)
+        .await?;
+    global_shortcuts::register(&app_handle, &bindings);
+    Ok(global_shortcuts::annotate(bindings))
+}
+
+/// Restore the default shortcut of one action, or of all when omitted
+#[tauri::command]
+pub async fn reset_keybindings(
+    app_handle: tauri::AppHandle,
+    action: Option<String>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<Keybinding>, String> {
+    let bindings = KeybindingsService::new(db_manager.inner().clone())
+        .reset(action.as_deref())
+        .await?;
+    global_shortcuts::register(&app_handle, &bindings);
+    Ok(global_shortcuts::annotate(bindings))
+}
+
+#[tauri::command]
+pub async fn get_keybinding_conflicts(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<KeybindingConflict>, String> {
+    let bindings = KeybindingsService::new(db_manager.inner().clone())
+        .list()
+        .await?;
+    Ok(detect_conflicts(&bindings))
+}
+
+#[tauri::command]
+pub async fn export_keybindings(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<String, String> {
+    KeybindingsService::new(db_manager.inner().clone())
+        .export()
+        .await
+}
+
+#[tauri::command]
+pub async fn import_keybindings(
+    app_handle: tauri::AppHandle,
+    keybindings_json: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<Keybinding>, String> {
+    let bindings = KeybindingsService::new(db_manager.inner().clone())
+        .import(&keybindings_json)
+        .await?;
+    global_shortcuts::register(&app_handle, &bindings);
+    Ok(global_shortcuts::annotate(bindings))
+}
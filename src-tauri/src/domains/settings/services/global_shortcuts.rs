@@ -0,0 +1,116 @@
+//! OS-level registration of global keybindings.
+//!
+//! Global shortcuts fire while the app is in the background. Pressing one
+//! brings the main window forward and emits `KEYBINDING_TRIGGERED_EVENT` with
+//! the action, which the frontend handles like an in-app shortcut.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use super::keybindings_service::{Keybinding, KeybindingScope};
+use crate::log_warn;
+
+pub const KEYBINDING_TRIGGERED_EVENT: &str = "keybinding-triggered";
+
+#[derive(Default)]
+struct Registered {
+    /// Action for each registered shortcut, by shortcut id
+    actions: HashMap<u32, String>,
+    /// Why registration failed, by action
+    errors: HashMap<String, String>,
+}
+
+static REGISTERED: Lazy<Mutex<Registered>> = Lazy::new(Default::default);
+
+#[derive(Clone, Serialize)]
+struct KeybindingTriggered {
+    action: String,
+}
+
+/// The global-shortcut plugin, dispatching presses to their actions
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let action = match REGISTERED.lock() {
+                Ok(registered) => registered.actions.get(&shortcut.id()).cloned(),
+                Err(_) => None,
+            };
+            let Some(action) = action else {
+                return;
+            };
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit(KEYBINDING_TRIGGERED_EVENT, KeybindingTriggered { action });
+        })
+        .build()
+}
+
+/// Replace the registered global shortcuts with those in `bindings`. A
+/// shortcut the OS refuses (usually because another app holds it) is skipped
+/// and reported through `annotate`.
+pub fn register<R: Runtime>(app: &AppHandle<R>, bindings: &[Keybinding]) {
+    let global_shortcut = app.global_shortcut();
+    if let Err(e) = global_shortcut.unregister_all() {
+        log_warn!("Settings", "Failed to unregister global shortcuts: {}", e);
+    }
+
+    let mut registered = Registered::default();
+    for binding in bindings {
+        let Some(shortcut) = binding
+            .shortcut
+            .as_deref()
+            .filter(|_| binding.scope == KeybindingScope::Global)
+        else {
+            continue;
+        };
+        let result = Shortcut::from_str(shortcut)
+            .map_err(|e| e.to_string())
+            .and_then(|parsed| {
+                global_shortcut
+                    .register(parsed)
+                    .map(|_| parsed.id())
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(id) => {
+                registered.actions.insert(id, binding.action.clone());
+            }
+            Err(e) => {
+                log_warn!(
+                    "Settings",
+                    "Failed to register global shortcut {} for {}: {}",
+                    shortcut,
+                    binding.action,
+                    e
+                );
+                registered.errors.insert(binding.action.clone(), e);
+            }
+        }
+    }
+    if let Ok(mut current) = REGISTERED.lock() {
+        *current = registered;
+    }
+}
+
+/// Fill in why any global shortcut in `bindings` couldn't be registered
+pub fn annotate(mut bindings: Vec<Keybinding>) -> Vec<Keybinding> {
+    if let Ok(registered) = REGISTERED.lock() {
+        for binding in &mut bindings {
+            binding.registration_error = registered.errors.get(&binding.action).cloned();
+        }
+    }
+    bindings
+}
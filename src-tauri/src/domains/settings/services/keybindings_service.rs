@@ -0,0 +1,558 @@
+//! Keyboard shortcut registry.
+//!
+//! Every action has a built-in shortcut; users can rebind or disable it, and
+//! only those overrides are stored. Global actions are registered with the OS
+//! (see `global_shortcuts`) and fire even when the app is in the background;
+//! app actions are handled by the frontend while a window has focus.
+
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::database::DatabaseManager;
+use crate::entities::keybinding::{ActiveModel, Column, Entity, Model};
+use crate::log_warn;
+
+const EXPORT_FORMAT: &str = "portal-desktop-keybindings";
+const EXPORT_VERSION: u32 = 1;
+
+/// Where a shortcut is listened for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeybindingScope {
+    /// Registered with the OS; works while the app is in the background
+    Global,
+    /// Handled by the frontend while a window has focus
+    App,
+}
+
+struct KeybindingAction {
+    id: &'static str,
+    label: &'static str,
+    scope: KeybindingScope,
+    default_shortcut: Option<&'static str>,
+}
+
+const ACTIONS: &[KeybindingAction] = &[
+    KeybindingAction {
+        id: "quick_open_project",
+        label: "Quick open project",
+        scope: KeybindingScope::Global,
+        default_shortcut: Some("CmdOrCtrl+Alt+P"),
+    },
+    KeybindingAction {
+        id: "new_task",
+        label: "New task",
+        scope: KeybindingScope::Global,
+        default_shortcut: Some("CmdOrCtrl+Alt+T"),
+    },
+    KeybindingAction {
+        id: "show_window",
+        label: "Show Portal",
+        scope: KeybindingScope::Global,
+        default_shortcut: None,
+    },
+    KeybindingAction {
+        id: "command_palette",
+        label: "Command palette",
+        scope: KeybindingScope::App,
+        default_shortcut: Some("CmdOrCtrl+K"),
+    },
+    KeybindingAction {
+        id: "open_settings",
+        label: "Open settings",
+        scope: KeybindingScope::App,
+        default_shortcut: Some("CmdOrCtrl+,"),
+    },
+    KeybindingAction {
+        id: "toggle_sidebar",
+        label: "Toggle sidebar",
+        scope: KeybindingScope::App,
+        default_shortcut: Some("CmdOrCtrl+B"),
+    },
+    KeybindingAction {
+        id: "new_terminal",
+        label: "New terminal",
+        scope: KeybindingScope::App,
+        default_shortcut: Some("CmdOrCtrl+Shift+`"),
+    },
+    KeybindingAction {
+        id: "search_documents",
+        label: "Search documents",
+        scope: KeybindingScope::App,
+        default_shortcut: Some("CmdOrCtrl+Shift+F"),
+    },
+];
+
+fn find_action(id: &str) -> Result<&'static KeybindingAction, String> {
+    ACTIONS
+        .iter()
+        .find(|action| action.id == id)
+        .ok_or_else(|| format!("Unknown keybinding action: {}", id))
+}
+
+/// A parsed key chord, e.g. `CmdOrCtrl+Shift+P`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    cmd_or_ctrl: bool,
+    cmd: bool,
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    key: String,
+}
+
+impl Chord {
+    pub fn parse(shortcut: &str) -> Result<Self, String> {
+        let mut chord = Chord {
+            cmd_or_ctrl: false,
+            cmd: false,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            key: String::new(),
+        };
+        // `+` is a key too, as in "Ctrl++"
+        let shortcut = shortcut.trim();
+        let (modifiers, key) = match shortcut.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => match shortcut.rsplit_once('+') {
+                Some((modifiers, key)) => (modifiers, key),
+                None => ("", shortcut),
+            },
+        };
+        for modifier in modifiers.split('+').filter(|m| !m.trim().is_empty()) {
+            let flag = match modifier.trim().to_ascii_lowercase().as_str() {
+                "cmdorctrl" | "commandorcontrol" | "cmdorcontrol" | "commandorctrl" | "mod" => {
+                    &mut chord.cmd_or_ctrl
+                }
+                "cmd" | "command" | "super" | "meta" | "win" => &mut chord.cmd,
+                "ctrl" | "control" => &mut chord.ctrl,
+                "alt" | "option" => &mut chord.alt,
+                "shift" => &mut chord.shift,
+                other => return Err(format!("Unknown modifier \"{}\" in {}", other, shortcut)),
+            };
+            *flag = true;
+        }
+        chord.key = normalize_key(key.trim())
+            .ok_or_else(|| format!("Invalid key in shortcut: {}", shortcut))?;
+        Ok(chord)
+    }
+
+    fn has_modifier(&self) -> bool {
+        self.cmd_or_ctrl || self.cmd || self.ctrl || self.alt || self.shift
+    }
+
+    /// The keys actually pressed on this platform, for comparing chords
+    /// written with `CmdOrCtrl` against ones naming Cmd or Ctrl directly
+    fn resolved(&self) -> (bool, bool, bool, bool, &str) {
+        let mac = cfg!(target_os = "macos");
+        (
+            self.cmd || (self.cmd_or_ctrl && mac),
+            self.ctrl || (self.cmd_or_ctrl && !mac),
+            self.alt,
+            self.shift,
+            &self.key,
+        )
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (self.cmd_or_ctrl, "CmdOrCtrl"),
+            (self.cmd, "Cmd"),
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+        ];
+        for (_, name) in modifiers.iter().filter(|(on, _)| *on) {
+            write!(f, "{}+", name)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+fn normalize_key(key: &str) -> Option<String> {
+    const NAMED: &[&str] = &[
+        "Space",
+        "Enter",
+        "Tab",
+        "Escape",
+        "Backspace",
+        "Delete",
+        "Insert",
+        "Home",
+        "End",
+        "PageUp",
+        "PageDown",
+        "ArrowUp",
+        "ArrowDown",
+        "ArrowLeft",
+        "ArrowRight",
+    ];
+    let lower = key.to_ascii_lowercase();
+    let alias = match lower.as_str() {
+        "esc" => Some("Escape"),
+        "return" => Some("Enter"),
+        "del" => Some("Delete"),
+        "up" => Some("ArrowUp"),
+        "down" => Some("ArrowDown"),
+        "left" => Some("ArrowLeft"),
+        "right" => Some("ArrowRight"),
+        _ => None,
+    };
+    if let Some(alias) = alias {
+        return Some(alias.to_string());
+    }
+    if let Some(named) = NAMED.iter().find(|name| name.eq_ignore_ascii_case(key)) {
+        return Some(named.to_string());
+    }
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&n).then(|| format!("F{}", n));
+    }
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => Some(c.to_ascii_uppercase().to_string()),
+        (Some(c), None) if "`-=[]\\;',./+".contains(c) => Some(c.to_string()),
+        _ => None,
+    }
+}
+
+/// An action with the shortcut currently bound to it
+#[derive(Debug, Clone, Serialize)]
+pub struct Keybinding {
+    pub action: String,
+    pub label: String,
+    pub scope: KeybindingScope,
+    /// `None` when the action is disabled
+    pub shortcut: Option<String>,
+    pub default_shortcut: Option<String>,
+    pub customized: bool,
+    /// Why the OS refused a global shortcut, e.g. another app holds it
+    pub registration_error: Option<String>,
+}
+
+/// Actions bound to the same keys
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeybindingConflict {
+    pub shortcut: String,
+    pub actions: Vec<String>,
+}
+
+/// Shareable set of overrides; actions missing from it use their default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeybindingsExport {
+    pub format: String,
+    pub version: u32,
+    pub keybindings: BTreeMap<String, Option<String>>,
+}
+
+/// Bind the built-in actions, applying `overrides` (action to shortcut, or
+/// `None` to disable)
+fn resolve(overrides: &HashMap<String, Option<String>>) -> Vec<Keybinding> {
+    ACTIONS
+        .iter()
+        .map(|action| {
+            let default_shortcut = action.default_shortcut.map(String::from);
+            let (shortcut, customized) = match overrides.get(action.id) {
+                Some(shortcut) => (shortcut.clone(), *shortcut != default_shortcut),
+                None => (default_shortcut.clone(), false),
+            };
+            Keybinding {
+                action: action.id.to_string(),
+                label: action.label.to_string(),
+                scope: action.scope,
+                shortcut,
+                default_shortcut,
+                customized,
+                registration_error: None,
+            }
+        })
+        .collect()
+}
+
+/// Group actions whose shortcuts press the same keys. Global shortcuts are
+/// taken by the OS before the app sees them, so they clash with app ones too.
+pub fn detect_conflicts(bindings: &[Keybinding]) -> Vec<KeybindingConflict> {
+    let mut conflicts: Vec<(Chord, KeybindingConflict)> = Vec::new();
+    for binding in bindings {
+        let Some(chord) = binding
+            .shortcut
+            .as_deref()
+            .and_then(|shortcut| Chord::parse(shortcut).ok())
+        else {
+            continue;
+        };
+        match conflicts
+            .iter_mut()
+            .find(|(other, _)| other.resolved() == chord.resolved())
+        {
+            Some((_, conflict)) => conflict.actions.push(binding.action.clone()),
+            None => conflicts.push((
+                chord.clone(),
+                KeybindingConflict {
+                    shortcut: chord.to_string(),
+                    actions: vec![binding.action.clone()],
+                },
+            )),
+        }
+    }
+    conflicts
+        .into_iter()
+        .map(|(_, conflict)| conflict)
+        .filter(|conflict| conflict.actions.len() > 1)
+        .collect()
+}
+
+/// Parse and normalize a shortcut for `action`
+fn validate(action: &KeybindingAction, shortcut: &str) -> Result<String, String> {
+    let chord = Chord::parse(shortcut)?;
+    let function_key = chord.key.len() > 1 && chord.key.starts_with('F');
+    if action.scope == KeybindingScope::Global && !chord.has_modifier() && !function_key {
+        return Err(format!(
+            "Global shortcut for \"{}\" needs a modifier key",
+            action.label
+        ));
+    }
+    Ok(chord.to_string())
+}
+
+pub struct KeybindingsService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl KeybindingsService {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Every action with its effective shortcut
+    pub async fn list(&self) -> Result<Vec<Keybinding>, String> {
+        Ok(resolve(&self.overrides().await?))
+    }
+
+    /// Bind `action` to `shortcut`, or disable it with `None`. Fails if the
+    /// keys are already bound to another action.
+    pub async fn set(
+        &self,
+        action_id: &str,
+        shortcut: Option<String>,
+    ) -> Result<Vec<Keybinding>, String> {
+        let action = find_action(action_id)?;
+        let shortcut = shortcut
+            .filter(|shortcut| !shortcut.trim().is_empty())
+            .map(|shortcut| validate(action, &shortcut))
+            .transpose()?;
+
+        let mut overrides = self.overrides().await?;
+        overrides.insert(action_id.to_string(), shortcut.clone());
+        let bindings = resolve(&overrides);
+        if let Some(conflict) = detect_conflicts(&bindings)
+            .into_iter()
+            .find(|conflict| conflict.actions.iter().any(|a| a == action_id))
+        {
+            let other = conflict
+                .actions
+                .iter()
+                .find(|a| *a != action_id)
+                .and_then(|a| find_action(a).ok())
+                .map(|a| a.label)
+                .unwrap_or_default();
+            return Err(format!(
+                "{} is already bound to \"{}\"",
+                conflict.shortcut, other
+            ));
+        }
+
+        let conn = self.db_manager.get_connection();
+        Entity::delete_many()
+            .filter(Column::Action.eq(action_id))
+            .exec(conn)
+            .await
+            .map_err(|e| format!("Failed to save keybinding: {}", e))?;
+        // Binding an action back to its default needs no override
+        if shortcut.as_deref() != action.default_shortcut {
+            new_override(action_id, shortcut)
+                .insert(conn)
+                .await
+                .map_err(|e| format!("Failed to save keybinding: {}", e))?;
+        }
+        Ok(bindings)
+    }
+
+    /// Restore one action's default shortcut, or every action's with `None`
+    pub async fn reset(&self, action_id: Option<&str>) -> Result<Vec<Keybinding>, String> {
+        let mut delete = Entity::delete_many();
+        if let Some(action_id) = action_id {
+            find_action(action_id)?;
+            delete = delete.filter(Column::Action.eq(action_id));
+        }
+        delete
+            .exec(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to reset keybindings: {}", e))?;
+        self.list().await
+    }
+
+    pub async fn export(&self) -> Result<String, String> {
+        let export = KeybindingsExport {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            keybindings: self.overrides().await?.into_iter().collect(),
+        };
+        serde_json::to_string_pretty(&export)
+            .map_err(|e| format!("Failed to export keybindings: {}", e))
+    }
+
+    /// Replace all overrides with an exported set. Actions this version
+    /// doesn't know are skipped; invalid or conflicting shortcuts reject the
+    /// whole import.
+    pub async fn import(&self, json: &str) -> Result<Vec<Keybinding>, String> {
+        let export: KeybindingsExport = serde_json::from_str(json)
+            .map_err(|e| format!("Failed to import keybindings: {}", e))?;
+        if export.format != EXPORT_FORMAT {
+            return Err("Not a keybindings export".to_string());
+        }
+        if export.version > EXPORT_VERSION {
+            return Err(format!(
+                "Keybindings export version {} is newer than this app supports",
+                export.version
+            ));
+        }
+
+        let mut overrides = HashMap::new();
+        for (action_id, shortcut) in export.keybindings {
+            let Ok(action) = find_action(&action_id) else {
+                log_warn!(
+                    "Settings",
+                    "Skipping unknown keybinding action: {}",
+                    action_id
+                );
+                continue;
+            };
+            let shortcut = shortcut
+                .filter(|shortcut| !shortcut.trim().is_empty())
+                .map(|shortcut| validate(action, &shortcut))
+                .transpose()?;
+            if shortcut.as_deref() != action.default_shortcut {
+                overrides.insert(action_id, shortcut);
+            }
+        }
+        let bindings = resolve(&overrides);
+        if let Some(conflict) = detect_conflicts(&bindings).first() {
+            return Err(format!(
+                "{} is bound to more than one action: {}",
+                conflict.shortcut,
+                conflict.actions.join(", ")
+            ));
+        }
+
+        let txn = self
+            .db_manager
+            .get_connection()
+            .begin()
+            .await
+            .map_err(|e| e.to_string())?;
+        Entity::delete_many()
+            .exec(&txn)
+            .await
+            .map_err(|e| format!("Failed to import keybindings: {}", e))?;
+        for (action_id, shortcut) in overrides {
+            new_override(&action_id, shortcut)
+                .insert(&txn)
+                .await
+                .map_err(|e| format!("Failed to import keybindings: {}", e))?;
+        }
+        txn.commit().await.map_err(|e| e.to_string())?;
+        Ok(bindings)
+    }
+
+    /// Stored overrides by action. Rows for actions that no longer exist or
+    /// with shortcuts that no longer parse are ignored.
+    async fn overrides(&self) -> Result<HashMap<String, Option<String>>, String> {
+        let rows: Vec<Model> = Entity::find()
+            .all(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load keybindings: {}", e))?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let action = find_action(&row.action).ok()?;
+                let shortcut = match row.shortcut {
+                    Some(shortcut) => Some(validate(action, &shortcut).ok()?),
+                    None => None,
+                };
+                Some((row.action, shortcut))
+            })
+            .collect())
+    }
+}
+
+fn new_override(action: &str, shortcut: Option<String>) -> ActiveModel {
+    ActiveModel {
+        action: Set(action.to_string()),
+        shortcut: Set(shortcut),
+        updated_at: Set(Some(chrono::Utc::now().into())),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chords_are_normalized() {
+        let chord = Chord::parse(" shift + cmdorctrl+p ").unwrap();
+        assert_eq!(chord.to_string(), "CmdOrCtrl+Shift+P");
+        assert_eq!(Chord::parse("Ctrl++").unwrap().to_string(), "Ctrl++");
+        assert_eq!(Chord::parse("alt+esc").unwrap().to_string(), "Alt+Escape");
+        assert!(Chord::parse("Hyper+P").is_err());
+        assert!(Chord::parse("Ctrl+PP").is_err());
+    }
+
+    #[test]
+    fn global_shortcuts_need_a_modifier() {
+        let quick_open = find_action("quick_open_project").unwrap();
+        assert!(validate(quick_open, "P").is_err());
+        assert!(validate(quick_open, "F9").is_ok());
+        assert!(validate(find_action("command_palette").unwrap(), "P").is_ok());
+    }
+
+    #[test]
+    fn defaults_do_not_conflict() {
+        assert!(detect_conflicts(&resolve(&HashMap::new())).is_empty());
+    }
+
+    #[test]
+    fn overrides_apply_and_conflicts_are_found() {
+        let platform_ctrl = if cfg!(target_os = "macos") {
+            "Cmd+K"
+        } else {
+            "Ctrl+K"
+        };
+        let overrides = HashMap::from([
+            ("new_task".to_string(), Some(platform_ctrl.to_string())),
+            ("toggle_sidebar".to_string(), None),
+        ]);
+        let bindings = resolve(&overrides);
+        let sidebar = bindings
+            .iter()
+            .find(|b| b.action == "toggle_sidebar")
+            .unwrap();
+        assert_eq!(sidebar.shortcut, None);
+        assert!(sidebar.customized);
+
+        // CmdOrCtrl+K and the platform's own modifier press the same keys
+        assert_eq!(
+            detect_conflicts(&bindings),
+            vec![KeybindingConflict {
+                shortcut: platform_ctrl.to_string(),
+                actions: vec!["new_task".to_string(), "command_palette".to_string()],
+            }]
+        );
+    }
+}
@@ -1 +1,5 @@
+pub mod global_shortcuts;
+pub mod keybindings_service;
+pub mod project_settings_service;
+pub mod settings_schema;
 pub mod settings_service;
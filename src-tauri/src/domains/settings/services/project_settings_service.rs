@@ -0,0 +1,321 @@
+//! Per-project overrides of global settings.
+//!
+//! A project can pin its own IDE, terminal shell, agent backend and model, and
+//! pipeline concurrency. Anything it leaves unset falls back to the global
+//! value, so the effective settings for a project are always complete.
+
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::settings_service::{SettingsService, MAX_CONCURRENT_EXECUTIONS};
+use crate::database::DatabaseManager;
+use crate::domains::ai::platform_config::PlatformConfig;
+use crate::domains::ai::services::AISettingsService;
+use crate::domains::ide::entities::IdeConfig;
+use crate::domains::ide::repositories::ide_repository::IdeRepository;
+use crate::domains::projects::repositories::project_repository::ProjectRepository;
+use crate::entities::project_setting::{ActiveModel, Column, Entity, Model};
+
+/// What a project overrides; `None` keeps the global value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSettingsOverrides {
+    #[serde(default)]
+    pub ide_id: Option<i32>,
+    #[serde(default)]
+    pub shell: Option<String>,
+    /// Backend and model are overridden together: a model only makes sense
+    /// with the backend it was picked for
+    #[serde(default)]
+    pub llm_provider: Option<String>,
+    #[serde(default)]
+    pub ai_model: Option<String>,
+    #[serde(default)]
+    pub max_concurrent_executions: Option<u32>,
+}
+
+impl ProjectSettingsOverrides {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn overrides_ai(&self) -> bool {
+        self.llm_provider.is_some() || self.ai_model.is_some()
+    }
+}
+
+impl From<Model> for ProjectSettingsOverrides {
+    fn from(model: Model) -> Self {
+        Self {
+            ide_id: model.ide_id,
+            shell: model.shell,
+            llm_provider: model.llm_provider,
+            ai_model: model.ai_model,
+            max_concurrent_executions: model
+                .max_concurrent_executions
+                .and_then(|n| u32::try_from(n).ok()),
+        }
+    }
+}
+
+/// Settings in effect when working in a project
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveProjectSettings {
+    pub project_id: i32,
+    /// IDE to open the project with; `None` when no IDE is set up
+    pub ide: Option<IdeConfig>,
+    /// Shell for the project's terminals; `None` uses the system login shell
+    pub shell: Option<String>,
+    /// Agent backend; `None` uses the platform's default
+    pub llm_provider: Option<String>,
+    /// Agent model; `None` uses the backend's default
+    pub ai_model: Option<String>,
+    pub max_concurrent_executions: u32,
+    /// Keys taken from the project rather than global settings
+    pub overridden: Vec<String>,
+}
+
+impl EffectiveProjectSettings {
+    /// Lay a project's overrides over the global settings. `ide` is the IDE
+    /// `overrides.ide_id` points at, if it still exists.
+    fn merge(
+        global: EffectiveProjectSettings,
+        overrides: ProjectSettingsOverrides,
+        ide: Option<IdeConfig>,
+    ) -> Self {
+        let mut effective = global;
+        let mut overridden = Vec::new();
+        if ide.is_some() {
+            effective.ide = ide;
+            overridden.push("ide");
+        }
+        if let Some(shell) = overrides.shell.clone() {
+            effective.shell = Some(shell);
+            overridden.push("shell");
+        }
+        if overrides.overrides_ai() {
+            effective.llm_provider = overrides.llm_provider.clone();
+            effective.ai_model = overrides.ai_model.clone();
+            overridden.push("ai");
+        }
+        if let Some(limit) = overrides.max_concurrent_executions {
+            effective.max_concurrent_executions = limit;
+            overridden.push("max_concurrent_executions");
+        }
+        effective.overridden = overridden.into_iter().map(String::from).collect();
+        effective
+    }
+}
+
+#[derive(Clone)]
+pub struct ProjectSettingsService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ProjectSettingsService {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    pub async fn get_overrides(&self, project_id: i32) -> Result<ProjectSettingsOverrides, String> {
+        Ok(self
+            .find(project_id)
+            .await?
+            .map(ProjectSettingsOverrides::from)
+            .unwrap_or_default())
+    }
+
+    /// Replace a project's overrides. Blank values are treated as unset.
+    pub async fn set_overrides(
+        &self,
+        project_id: i32,
+        overrides: ProjectSettingsOverrides,
+    ) -> Result<ProjectSettingsOverrides, String> {
+        ProjectRepository::new(self.db_manager.clone())
+            .get_by_id(project_id)
+            .await?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+        let overrides = self.validate(overrides).await?;
+
+        let conn = self.db_manager.get_connection();
+        let existing = self.find(project_id).await?;
+        if overrides.is_empty() {
+            if let Some(existing) = existing {
+                Entity::delete_by_id(existing.id)
+                    .exec(conn)
+                    .await
+                    .map_err(|e| format!("Failed to clear project settings: {}", e))?;
+            }
+            return Ok(overrides);
+        }
+
+        let mut model: ActiveModel = match existing {
+            Some(existing) => existing.into(),
+            None => ActiveModel {
+                project_id: Set(project_id),
+                ..Default::default()
+            },
+        };
+        model.ide_id = Set(overrides.ide_id);
+        model.shell = Set(overrides.shell.clone());
+        model.llm_provider = Set(overrides.llm_provider.clone());
+        model.ai_model = Set(overrides.ai_model.clone());
+        model.max_concurrent_executions =
+            Set(overrides.max_concurrent_executions.map(|n| n as i32));
+        model.updated_at = Set(Some(chrono::Utc::now().into()));
+        model
+            .save(conn)
+            .await
+            .map_err(|e| format!("Failed to save project settings: {}", e))?;
+        Ok(overrides)
+    }
+
+    /// Global settings with the project's overrides applied
+    pub async fn effective(&self, project_id: i32) -> Result<EffectiveProjectSettings, String> {
+        let overrides = self.get_overrides(project_id).await?;
+        let conn = self.db_manager.get_connection();
+        // An IDE deleted since it was picked falls back to the default
+        let ide = match overrides.ide_id {
+            Some(id) => IdeRepository::get_by_id(conn, id)
+                .await
+                .map_err(|e| format!("Failed to load IDE: {}", e))?
+                .map(IdeConfig::from),
+            None => None,
+        };
+        Ok(EffectiveProjectSettings::merge(
+            self.global(project_id).await?,
+            overrides,
+            ide,
+        ))
+    }
+
+    /// Pipeline executions the project may run at once
+    pub async fn max_concurrent_executions(&self, project_id: i32) -> Result<u32, String> {
+        match self
+            .get_overrides(project_id)
+            .await?
+            .max_concurrent_executions
+        {
+            Some(limit) => Ok(limit),
+            None => Ok(SettingsService::new()
+                .load_settings()?
+                .app
+                .pipelines
+                .max_concurrent_executions),
+        }
+    }
+
+    async fn global(&self, project_id: i32) -> Result<EffectiveProjectSettings, String> {
+        let settings = SettingsService::new().load_settings()?;
+        let ide = IdeRepository::get_default(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load default IDE: {}", e))?
+            .map(IdeConfig::from);
+        Ok(EffectiveProjectSettings {
+            project_id,
+            ide,
+            shell: settings.terminal.default_shell,
+            llm_provider: None,
+            ai_model: PlatformConfig::resolve(&AISettingsService::new()).default_model,
+            max_concurrent_executions: settings.app.pipelines.max_concurrent_executions,
+            overridden: Vec::new(),
+        })
+    }
+
+    async fn validate(
+        &self,
+        overrides: ProjectSettingsOverrides,
+    ) -> Result<ProjectSettingsOverrides, String> {
+        let overrides = ProjectSettingsOverrides {
+            shell: non_blank(overrides.shell),
+            llm_provider: non_blank(overrides.llm_provider),
+            ai_model: non_blank(overrides.ai_model),
+            ..overrides
+        };
+        if let Some(limit) = overrides.max_concurrent_executions {
+            if !(1..=MAX_CONCURRENT_EXECUTIONS).contains(&limit) {
+                return Err(format!(
+                    "Concurrent pipeline executions must be between 1 and {}",
+                    MAX_CONCURRENT_EXECUTIONS
+                ));
+            }
+        }
+        if let Some(ide_id) = overrides.ide_id {
+            IdeRepository::get_by_id(self.db_manager.get_connection(), ide_id)
+                .await
+                .map_err(|e| format!("Failed to load IDE: {}", e))?
+                .ok_or_else(|| format!("IDE not found: {}", ide_id))?;
+        }
+        Ok(overrides)
+    }
+
+    async fn find(&self, project_id: i32) -> Result<Option<Model>, String> {
+        Entity::find()
+            .filter(Column::ProjectId.eq(project_id))
+            .one(self.db_manager.get_connection())
+            .await
+            .map_err(|e| format!("Failed to load project settings: {}", e))
+    }
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn global() -> EffectiveProjectSettings {
+        EffectiveProjectSettings {
+            project_id: 7,
+            ide: None,
+            shell: Some("/bin/zsh".to_string()),
+            llm_provider: None,
+            ai_model: Some("global-model".to_string()),
+            max_concurrent_executions: 4,
+            overridden: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn unset_overrides_keep_global_values() {
+        let effective =
+            EffectiveProjectSettings::merge(global(), ProjectSettingsOverrides::default(), None);
+        assert_eq!(effective.shell.as_deref(), Some("/bin/zsh"));
+        assert_eq!(effective.ai_model.as_deref(), Some("global-model"));
+        assert_eq!(effective.max_concurrent_executions, 4);
+        assert!(effective.overridden.is_empty());
+    }
+
+    #[test]
+    fn overrides_replace_global_values() {
+        let overrides = ProjectSettingsOverrides {
+            shell: Some("/bin/bash".to_string()),
+            llm_provider: Some("ollama".to_string()),
+            max_concurrent_executions: Some(1),
+            ..Default::default()
+        };
+        let effective = EffectiveProjectSettings::merge(global(), overrides, None);
+        assert_eq!(effective.shell.as_deref(), Some("/bin/bash"));
+        // The global model belongs to another backend, so it isn't kept
+        assert_eq!(effective.llm_provider.as_deref(), Some("ollama"));
+        assert_eq!(effective.ai_model, None);
+        assert_eq!(effective.max_concurrent_executions, 1);
+        assert_eq!(
+            effective.overridden,
+            vec!["shell", "ai", "max_concurrent_executions"]
+        );
+    }
+
+    #[test]
+    fn blank_values_are_unset() {
+        assert_eq!(non_blank(Some("  ".to_string())), None);
+        assert_eq!(
+            non_blank(Some(" fish ".to_string())).as_deref(),
+            Some("fish")
+        );
+    }
+}
@@ -0,0 +1,402 @@
+//! Versioned settings documents: migrations, per-key defaults and validation.
+//!
+//! `settings.json` and settings exports carry a `version`. Reading one runs
+//! the migrations from that version up, fills every missing key from the
+//! defaults, checks each key against the type of its default, then checks
+//! values against [`RULES`]. Anything that fails is reported by its dotted
+//! path and replaced with the default, so a bad value costs that one key
+//! rather than the whole file. Files from before versioning are version 1.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::settings_service::{Settings, MAX_CONCURRENT_EXECUTIONS};
+
+pub const SETTINGS_VERSION: u64 = 3;
+const VERSION_KEY: &str = "version";
+
+/// Step from each version to the next; the first takes version 1 to 2
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[v1_to_v2, v2_to_v3];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsIssue {
+    /// Dotted path of the key, e.g. `terminal.font_size`
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SettingsIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.path, self.message)
+    }
+}
+
+enum Rule {
+    OneOf(&'static [&'static str]),
+    Range(f64, f64),
+    HexColor,
+}
+
+const LOG_LEVELS: &[&str] = &["debug", "info", "warn", "error"];
+
+/// Value constraints beyond types; `*` matches every key of an object
+const RULES: &[(&str, Rule)] = &[
+    ("app.theme", Rule::OneOf(&["light", "dark", "system"])),
+    ("app.time_format", Rule::OneOf(&["12h", "24h"])),
+    ("app.window_state.width", Rule::Range(400.0, 10_000.0)),
+    ("app.window_state.height", Rule::Range(300.0, 10_000.0)),
+    ("app.updates.check_interval", Rule::Range(1.0, 720.0)),
+    ("app.updates.channel", Rule::OneOf(&["stable", "beta"])),
+    (
+        "app.security.vault_auto_lock_minutes",
+        Rule::Range(0.0, 1440.0),
+    ),
+    (
+        "app.security.credential_expiry_warning_days",
+        Rule::Range(1.0, 365.0),
+    ),
+    ("app.automation.webhooks.port", Rule::Range(1.0, 65_535.0)),
+    (
+        "app.automation.approvals.ttl_minutes",
+        Rule::Range(1.0, 43_200.0),
+    ),
+    ("app.backups.interval_hours", Rule::Range(1.0, 720.0)),
+    ("app.backups.keep", Rule::Range(1.0, 100.0)),
+    (
+        "app.pipelines.max_concurrent_executions",
+        Rule::Range(1.0, MAX_CONCURRENT_EXECUTIONS as f64),
+    ),
+    (
+        "app.pipelines.max_total_executions",
+        Rule::Range(1.0, MAX_CONCURRENT_EXECUTIONS as f64),
+    ),
+    ("app.data_sync.interval_minutes", Rule::Range(1.0, 1440.0)),
+    ("app.logging.level", Rule::OneOf(LOG_LEVELS)),
+    ("app.logging.domains.*", Rule::OneOf(LOG_LEVELS)),
+    ("editor.font_size", Rule::Range(6.0, 72.0)),
+    ("editor.line_height", Rule::Range(0.5, 4.0)),
+    ("editor.tab_size", Rule::Range(1.0, 16.0)),
+    ("terminal.font_size", Rule::Range(6.0, 72.0)),
+    ("terminal.line_height", Rule::Range(0.5, 4.0)),
+    (
+        "terminal.cursor_style",
+        Rule::OneOf(&["block", "underline", "bar"]),
+    ),
+    ("terminal.scrollback", Rule::Range(0.0, 1_000_000.0)),
+    (
+        "terminal.bell_style",
+        Rule::OneOf(&["none", "visual", "sound"]),
+    ),
+    (
+        "terminal.selection_mode",
+        Rule::OneOf(&["normal", "column"]),
+    ),
+    ("terminal.theme.background", Rule::HexColor),
+    ("terminal.theme.foreground", Rule::HexColor),
+    ("terminal.theme.cursor", Rule::HexColor),
+    ("terminal.theme.selection", Rule::HexColor),
+    ("terminal.theme.colors.*", Rule::HexColor),
+    ("theme.primary_color", Rule::HexColor),
+    ("theme.secondary_color", Rule::HexColor),
+    ("theme.accent_color", Rule::HexColor),
+    ("theme.background_color", Rule::HexColor),
+    ("theme.surface_color", Rule::HexColor),
+    ("theme.text_color", Rule::HexColor),
+    ("theme.border_radius", Rule::Range(0.0, 64.0)),
+    ("theme.shadow_intensity", Rule::Range(0.0, 1.0)),
+    (
+        "theme.animation_speed",
+        Rule::OneOf(&["slow", "normal", "fast"]),
+    ),
+];
+
+/// Read a settings document of any version up to the current one. Returns the
+/// settings with invalid values replaced by their defaults, and what was
+/// replaced.
+pub fn read(
+    document: Value,
+    defaults: &Settings,
+) -> Result<(Settings, Vec<SettingsIssue>), String> {
+    let Value::Object(mut document) = document else {
+        return Err("Settings must be a JSON object".to_string());
+    };
+
+    let version = match document.remove(VERSION_KEY) {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| "version must be a positive whole number".to_string())?,
+    };
+    if version > SETTINGS_VERSION {
+        return Err(format!(
+            "These settings are from a newer version of the app (v{}); this one reads up to v{}",
+            version, SETTINGS_VERSION
+        ));
+    }
+    for migrate in &MIGRATIONS[(version - 1) as usize..] {
+        migrate(&mut document);
+    }
+
+    let defaults = serde_json::to_value(defaults)
+        .map_err(|e| format!("Failed to serialize default settings: {}", e))?;
+    let mut merged = defaults.clone();
+    let mut issues = Vec::new();
+    merge(&mut merged, document, "", &mut issues);
+    check_rules(&mut merged, Some(&defaults), &mut issues);
+
+    let settings =
+        serde_json::from_value(merged).map_err(|e| format!("Failed to parse settings: {}", e))?;
+    Ok((settings, issues))
+}
+
+/// Problems with settings about to be saved
+pub fn validate(settings: &Settings) -> Vec<SettingsIssue> {
+    let mut issues = Vec::new();
+    if let Ok(mut document) = serde_json::to_value(settings) {
+        check_rules(&mut document, None, &mut issues);
+    }
+    issues
+}
+
+/// Settings as stored and exported, stamped with the current version
+pub fn to_document(settings: &Settings) -> Result<Value, String> {
+    let mut document = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    if let Value::Object(map) = &mut document {
+        map.insert(VERSION_KEY.to_string(), Value::from(SETTINGS_VERSION));
+    }
+    Ok(document)
+}
+
+/// One message for a list of issues
+pub fn describe(issues: &[SettingsIssue]) -> String {
+    let issues: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+    format!("Invalid settings: {}", issues.join("; "))
+}
+
+/// The settings page stored the bar cursor as "line", which xterm doesn't
+/// know
+fn v1_to_v2(document: &mut Map<String, Value>) {
+    if let Some(style) = document
+        .get_mut("terminal")
+        .and_then(|terminal| terminal.get_mut("cursor_style"))
+    {
+        if *style == "line" {
+            *style = Value::from("bar");
+        }
+    }
+}
+
+/// Only stable and beta releases are published; alpha moves to beta
+fn v2_to_v3(document: &mut Map<String, Value>) {
+    if let Some(channel) = document
+        .get_mut("app")
+        .and_then(|app| app.get_mut("updates"))
+        .and_then(|updates| updates.get_mut("channel"))
+    {
+        if *channel == "alpha" {
+            *channel = Value::from("beta");
+        }
+    }
+}
+
+/// Lay `document` over the defaults in `target`, keeping the default wherever
+/// the document's value has the wrong type. Keys the schema doesn't have are
+/// dropped.
+fn merge(
+    target: &mut Value,
+    document: Map<String, Value>,
+    path: &str,
+    issues: &mut Vec<SettingsIssue>,
+) {
+    let Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in document {
+        let Some(default) = target.get_mut(&key) else {
+            continue;
+        };
+        let path = join(path, &key);
+        match (&*default, value) {
+            // Optional keys default to null and take anything
+            (Value::Null, value) => *default = value,
+            // Structs merge key by key; maps (empty by default) are replaced
+            (Value::Object(fields), Value::Object(value)) if !fields.is_empty() => {
+                merge(default, value, &path, issues)
+            }
+            (Value::Number(number), Value::Number(value)) if number.is_u64() && !value.is_u64() => {
+                issues.push(issue(&path, "must be a whole number of 0 or more"));
+            }
+            (_, value) if kind(default) == kind(&value) => *default = value,
+            (_, value) => issues.push(issue(
+                &path,
+                &format!("must be {}, not {}", kind(default), kind(&value)),
+            )),
+        }
+    }
+}
+
+fn check_rules(document: &mut Value, defaults: Option<&Value>, issues: &mut Vec<SettingsIssue>) {
+    for (pattern, rule) in RULES {
+        let segments: Vec<&str> = pattern.split('.').collect();
+        for path in expand(document, &segments) {
+            let pointer = format!("/{}", path.join("/"));
+            let Some(value) = document.pointer(&pointer) else {
+                continue;
+            };
+            let Some(message) = broken(rule, value) else {
+                continue;
+            };
+            issues.push(issue(&path.join("."), &message));
+            if let (Some(default), Some(value)) = (
+                defaults.and_then(|defaults| defaults.pointer(&pointer)),
+                document.pointer_mut(&pointer),
+            ) {
+                *value = default.clone();
+            }
+        }
+    }
+}
+
+/// Concrete paths matching `segments`, expanding `*`
+fn expand(document: &Value, segments: &[&str]) -> Vec<Vec<String>> {
+    let Some((first, rest)) = segments.split_first() else {
+        return vec![Vec::new()];
+    };
+    let keys: Vec<&String> = match (document, *first) {
+        (Value::Object(map), "*") => map.keys().collect(),
+        (Value::Object(map), key) => map
+            .get_key_value(key)
+            .map(|(key, _)| key)
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    };
+    keys.into_iter()
+        .flat_map(|key| {
+            expand(&document[key.as_str()], rest)
+                .into_iter()
+                .map(move |mut path| {
+                    path.insert(0, key.clone());
+                    path
+                })
+        })
+        .collect()
+}
+
+/// Why `value` breaks `rule`, if it does
+fn broken(rule: &Rule, value: &Value) -> Option<String> {
+    match rule {
+        Rule::OneOf(allowed) => (!value.as_str().is_some_and(|value| allowed.contains(&value)))
+            .then(|| format!("must be one of: {}", allowed.join(", "))),
+        Rule::Range(min, max) => (!value
+            .as_f64()
+            .is_some_and(|value| (*min..=*max).contains(&value)))
+        .then(|| format!("must be between {} and {}", min, max)),
+        Rule::HexColor => (!value.as_str().is_some_and(is_hex_color))
+            .then(|| "must be a hex color like #1e1e1e".to_string()),
+    }
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.strip_prefix('#').is_some_and(|digits| {
+        matches!(digits.len(), 3 | 4 | 6 | 8) && digits.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "true or false",
+        Value::Number(_) => "a number",
+        Value::String(_) => "text",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn issue(path: &str, message: &str) -> SettingsIssue {
+    SettingsIssue {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::settings::services::settings_service::SettingsService;
+    use serde_json::json;
+
+    fn defaults() -> Settings {
+        SettingsService::new().get_default_settings()
+    }
+
+    #[test]
+    fn upgrades_unversioned_settings_and_fills_missing_keys() {
+        let document = json!({
+            "terminal": { "cursor_style": "line", "font_size": 16 },
+            "editor": { "tab_size": 2 },
+            "app": { "updates": { "channel": "alpha" } },
+        });
+        let (settings, issues) = read(document, &defaults()).unwrap();
+        assert!(issues.is_empty(), "{:?}", issues);
+        assert_eq!(settings.terminal.cursor_style, "bar");
+        assert_eq!(settings.app.updates.channel, "beta");
+        assert_eq!(settings.terminal.font_size, 16);
+        assert_eq!(settings.editor.tab_size, 2);
+        assert_eq!(settings.app.theme, "system");
+
+        let stored = to_document(&settings).unwrap();
+        assert_eq!(stored[VERSION_KEY], SETTINGS_VERSION);
+        let (again, issues) = read(stored, &defaults()).unwrap();
+        assert!(issues.is_empty());
+        assert_eq!(again.terminal.cursor_style, "bar");
+    }
+
+    #[test]
+    fn reports_bad_values_by_path_and_keeps_the_defaults() {
+        let document = json!({
+            "version": 2,
+            "editor": { "font_size": 400, "word_wrap": "yes" },
+            "terminal": { "scrollback": -1, "theme": { "colors": { "red": "crimson" } } },
+            "app": { "theme": "neon" },
+        });
+        let (settings, issues) = read(document, &defaults()).unwrap();
+        let mut paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                "app.theme",
+                "editor.font_size",
+                "editor.word_wrap",
+                "terminal.scrollback",
+                "terminal.theme.colors.red",
+            ]
+        );
+        assert_eq!(settings.editor.font_size, 14);
+        assert!(settings.editor.word_wrap);
+        assert_eq!(settings.terminal.theme.colors.red, "#cd3131");
+        assert!(describe(&issues).contains("editor.font_size must be between 6 and 72"));
+
+        assert!(validate(&settings).is_empty());
+    }
+
+    #[test]
+    fn refuses_settings_from_newer_versions() {
+        let document = json!({ "version": SETTINGS_VERSION + 1 });
+        assert!(read(document, &defaults()).is_err());
+        assert!(read(json!({ "version": "two" }), &defaults()).is_err());
+        assert!(read(json!([]), &defaults()).is_err());
+    }
+}
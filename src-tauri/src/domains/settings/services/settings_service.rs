@@ -4,6 +4,9 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use super::settings_schema;
+use crate::log_warn;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppSettings {
     // General settings
@@ -28,6 +31,27 @@ pub struct AppSettings {
 
     #[serde(default)]
     pub integrations: IntegrationSettings,
+
+    #[serde(default)]
+    pub security: SecuritySettings,
+
+    #[serde(default)]
+    pub sdk_downloads: SdkDownloadSettings,
+
+    #[serde(default)]
+    pub automation: AutomationSettings,
+
+    #[serde(default)]
+    pub backups: BackupSettings,
+
+    #[serde(default)]
+    pub pipelines: PipelineSettings,
+
+    #[serde(default)]
+    pub data_sync: DataSyncSettings,
+
+    #[serde(default)]
+    pub logging: LoggingSettings,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -85,6 +109,9 @@ pub struct UpdateSettings {
     pub check_interval: u32, // hours
     pub channel: String,
     pub notify_on_update: bool,
+    /// Let background checks download updates over metered connections
+    #[serde(default)]
+    pub download_on_metered: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -98,6 +125,238 @@ pub struct GitHubIntegrationSettings {
     pub client_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecuritySettings {
+    /// Lock the credential vault after this many idle minutes; 0 never locks
+    #[serde(default)]
+    pub vault_auto_lock_minutes: u32,
+    /// Start with the vault locked until it is explicitly unlocked
+    #[serde(default)]
+    pub lock_vault_on_startup: bool,
+    /// Warn about credentials expiring within this many days
+    #[serde(default = "default_credential_expiry_warning_days")]
+    pub credential_expiry_warning_days: u32,
+    /// Keep credential access audit entries for this many days; 0 keeps them forever
+    #[serde(default = "default_credential_audit_retention_days")]
+    pub credential_audit_retention_days: u32,
+}
+
+fn default_credential_expiry_warning_days() -> u32 {
+    14
+}
+
+fn default_credential_audit_retention_days() -> u32 {
+    90
+}
+
+impl Default for SecuritySettings {
+    fn default() -> Self {
+        Self {
+            vault_auto_lock_minutes: 0,
+            lock_vault_on_startup: false,
+            credential_expiry_warning_days: default_credential_expiry_warning_days(),
+            credential_audit_retention_days: default_credential_audit_retention_days(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SdkDownloadSettings {
+    /// Base URL standing in for each SDK source's official one, by SDK type
+    #[serde(default)]
+    pub mirrors: HashMap<String, String>,
+    /// List and install SDK versions only from cached archives
+    #[serde(default)]
+    pub offline: bool,
+    /// Keep downloaded archives after installing, for offline installs
+    #[serde(default)]
+    pub keep_archives: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AutomationSettings {
+    #[serde(default)]
+    pub webhooks: WebhookListenerSettings,
+    #[serde(default)]
+    pub approvals: ApprovalQueueSettings,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApprovalQueueSettings {
+    /// Minutes an autonomous action waits for approval before it expires
+    #[serde(default = "default_approval_ttl_minutes")]
+    pub ttl_minutes: u32,
+}
+
+fn default_approval_ttl_minutes() -> u32 {
+    24 * 60
+}
+
+impl Default for ApprovalQueueSettings {
+    fn default() -> Self {
+        Self {
+            ttl_minutes: default_approval_ttl_minutes(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookListenerSettings {
+    /// Listen for inbound webhooks that trigger workflows and pipelines
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on; use 0.0.0.0 to accept requests from other machines
+    #[serde(default = "default_webhook_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_webhook_port")]
+    pub port: u16,
+    /// Secret callers send as a bearer token, `X-Portal-Token` header or
+    /// `token` query parameter. The listener won't start without one.
+    #[serde(default)]
+    pub token: String,
+}
+
+fn default_webhook_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_webhook_port() -> u16 {
+    8787
+}
+
+impl Default for WebhookListenerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_webhook_bind_address(),
+            port: default_webhook_port(),
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupSettings {
+    /// Back up the database on a schedule
+    #[serde(default = "default_backups_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u32,
+    /// Backups kept in the app data dir; older ones are deleted
+    #[serde(default = "default_backup_keep")]
+    pub keep: u32,
+}
+
+fn default_backups_enabled() -> bool {
+    true
+}
+
+fn default_backup_interval_hours() -> u32 {
+    24
+}
+
+fn default_backup_keep() -> u32 {
+    7
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_backups_enabled(),
+            interval_hours: default_backup_interval_hours(),
+            keep: default_backup_keep(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DataSyncSettings {
+    /// Folder to sync settings and other non-secret data through, e.g. one
+    /// kept in Dropbox or a git repo; sync is off when unset
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Sync in the background; otherwise only when asked to
+    #[serde(default = "default_data_sync_auto")]
+    pub auto_sync: bool,
+    #[serde(default = "default_data_sync_interval_minutes")]
+    pub interval_minutes: u32,
+}
+
+fn default_data_sync_auto() -> bool {
+    true
+}
+
+fn default_data_sync_interval_minutes() -> u32 {
+    5
+}
+
+impl Default for DataSyncSettings {
+    fn default() -> Self {
+        Self {
+            folder: None,
+            auto_sync: default_data_sync_auto(),
+            interval_minutes: default_data_sync_interval_minutes(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoggingSettings {
+    /// Lowest level logged: "debug", "info", "warn" or "error"
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// Levels for individual domains, e.g. "kubernetes" = "debug". A domain
+    /// also covers log contexts that start with it, so "database" covers
+    /// "DatabaseManager".
+    #[serde(default)]
+    pub domains: HashMap<String, String>,
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            domains: HashMap::new(),
+        }
+    }
+}
+
+/// Upper bound for [`PipelineSettings::max_concurrent_executions`], the
+/// per-project override of it and [`PipelineSettings::max_total_executions`]
+pub const MAX_CONCURRENT_EXECUTIONS: u32 = 32;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PipelineSettings {
+    /// Executions of one project's pipelines allowed to run at once
+    #[serde(default = "default_max_concurrent_executions")]
+    pub max_concurrent_executions: u32,
+    /// Executions allowed to run at once across all projects; further ones
+    /// are queued
+    #[serde(default = "default_max_total_executions")]
+    pub max_total_executions: u32,
+}
+
+fn default_max_concurrent_executions() -> u32 {
+    4
+}
+
+fn default_max_total_executions() -> u32 {
+    8
+}
+
+impl Default for PipelineSettings {
+    fn default() -> Self {
+        Self {
+            max_concurrent_executions: default_max_concurrent_executions(),
+            max_total_executions: default_max_total_executions(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EditorSettings {
     // Code editor
@@ -155,6 +414,10 @@ pub struct TerminalSettings {
     // Advanced
     pub encoding: String,
     pub locale: String,
+
+    /// Shell new terminals start; `None` uses the system's login shell
+    #[serde(default)]
+    pub default_shell: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -240,7 +503,8 @@ impl SettingsService {
         Self { settings_path }
     }
 
-    /// Load settings from file
+    /// Load settings from file, upgrading them from older versions. Invalid
+    /// values fall back to their defaults rather than failing the load.
     pub fn load_settings(&self) -> Result<Settings, String> {
         if !self.settings_path.exists() {
             return Ok(self.get_default_settings());
@@ -249,26 +513,36 @@ impl SettingsService {
         let content = fs::read_to_string(&self.settings_path)
             .map_err(|e| format!("Failed to read settings file: {}", e))?;
 
-        let settings: Settings = serde_json::from_str(&content)
+        let document = serde_json::from_str(&content)
             .map_err(|e| format!("Failed to parse settings: {}", e))?;
+        let (settings, issues) = settings_schema::read(document, &self.get_default_settings())?;
+        for issue in issues {
+            log_warn!("Settings", "Using the default for {}", issue);
+        }
 
         Ok(settings)
     }
 
-    /// Save settings to file
+    /// Save settings to file. Invalid values are refused.
     pub fn save_settings(&self, settings: &Settings) -> Result<(), String> {
+        let issues = settings_schema::validate(settings);
+        if !issues.is_empty() {
+            return Err(settings_schema::describe(&issues));
+        }
+
         // Create directory if it doesn't exist
         if let Some(parent) = self.settings_path.parent() {
             fs::create_dir_all(parent)
                 .map_err(|e| format!("Failed to create settings directory: {}", e))?;
         }
 
-        let content = serde_json::to_string_pretty(settings)
+        let content = serde_json::to_string_pretty(&settings_schema::to_document(settings)?)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
         fs::write(&self.settings_path, content)
             .map_err(|e| format!("Failed to write settings file: {}", e))?;
 
+        crate::utils::logger::logger().apply_settings(&settings.app.logging);
         Ok(())
     }
 
@@ -327,8 +601,16 @@ impl SettingsService {
                     check_interval: 24,
                     channel: "stable".to_string(),
                     notify_on_update: true,
+                    download_on_metered: false,
                 },
                 integrations: IntegrationSettings::default(),
+                security: SecuritySettings::default(),
+                sdk_downloads: SdkDownloadSettings::default(),
+                automation: AutomationSettings::default(),
+                backups: BackupSettings::default(),
+                pipelines: PipelineSettings::default(),
+                data_sync: DataSyncSettings::default(),
+                logging: LoggingSettings::default(),
             },
             editor: EditorSettings {
                 font_family: "Monaco, Consolas, 'Courier New', monospace".to_string(),
@@ -390,6 +672,7 @@ impl SettingsService {
                 },
                 encoding: "utf-8".to_string(),
                 locale: "en_US.UTF-8".to_string(),
+                default_shell: None,
             },
             theme: ThemeSettings {
                 primary_color: "#3b82f6".to_string(),
@@ -443,18 +726,29 @@ impl SettingsService {
 
     /// Export settings
     pub fn export_settings(&self, settings: &Settings) -> Result<String, String> {
-        serde_json::to_string_pretty(settings)
+        serde_json::to_string_pretty(&settings_schema::to_document(settings)?)
             .map_err(|e| format!("Failed to export settings: {}", e))
     }
 
     /// Import settings
     pub fn import_settings(&self, settings_json: &str) -> Result<Settings, String> {
-        let settings: Settings = serde_json::from_str(settings_json)
+        let document = serde_json::from_str(settings_json)
             .map_err(|e| format!("Failed to import settings: {}", e))?;
+        let settings = self.parse_document(document)?;
 
         self.save_settings(&settings)?;
         Ok(settings)
     }
+
+    /// Read an exported settings document, upgrading it from older versions.
+    /// Unlike loading, any invalid value is an error.
+    pub fn parse_document(&self, document: serde_json::Value) -> Result<Settings, String> {
+        let (settings, issues) = settings_schema::read(document, &self.get_default_settings())?;
+        if !issues.is_empty() {
+            return Err(settings_schema::describe(&issues));
+        }
+        Ok(settings)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
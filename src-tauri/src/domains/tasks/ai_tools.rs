@@ -0,0 +1,37 @@
+//! Task tools for the AI assistant
+use super::commands::{create_task, CreateTaskCommand};
+use crate::database::DatabaseManager;
+use crate::domains::ai::tools::{ToolRegistry, ToolSpec};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::Manager;
+
+pub fn register_ai_tools(registry: &ToolRegistry) {
+    registry.register(
+        ToolSpec {
+            name: "create_task".to_string(),
+            description: "Create a task".to_string(),
+            domain: "tasks".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "description": { "type": "string" },
+                    "priority": { "type": "string", "enum": ["low", "medium", "high"] },
+                    "due_date": { "type": "string", "description": "RFC 3339 timestamp" }
+                },
+                "required": ["title"]
+            }),
+        },
+        |app, mut args: Value| async move {
+            if let Some(args) = args.as_object_mut() {
+                args.entry("status").or_insert_with(|| json!("pending"));
+                args.entry("priority").or_insert_with(|| json!("medium"));
+            }
+            let command: CreateTaskCommand =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+            let task = create_task(app.state::<Arc<DatabaseManager>>(), command).await?;
+            serde_json::to_value(task).map_err(|e| e.to_string())
+        },
+    );
+}
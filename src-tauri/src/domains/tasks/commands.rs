@@ -4,11 +4,19 @@ use crate::domains::ai::services::AIService;
 use crate::domains::tasks::repositories::task_repository::{
     CreateTaskRequest, TaskFilters, UpdateTaskRequest,
 };
+use crate::domains::tasks::services::ai_task_estimator::{AITaskEstimator, TaskEstimationResult};
 use crate::domains::tasks::services::ai_task_generator::{AITaskGenerator, GeneratedTaskStructure};
+use crate::domains::tasks::services::attachment_service::{
+    AttachmentCleanupReport, AttachmentMetadata, TaskAttachmentService,
+};
+use crate::domains::tasks::services::sprint_planner::{SprintPlan, SprintPlanner};
 use crate::domains::tasks::services::task_service::TaskService;
+use crate::entities::task_attachment::Model as TaskAttachmentModel;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Manager, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskResponse {
@@ -313,6 +321,7 @@ pub async fn update_task(
 
 #[tauri::command]
 pub async fn delete_task(
+    app: tauri::AppHandle,
     db_manager: State<'_, Arc<DatabaseManager>>,
     id: i32,
 ) -> Result<(), String> {
@@ -324,10 +333,25 @@ pub async fn delete_task(
 
     // Check if task exists before deleting
     match task_service.get_task(id).await {
-        Ok(Some(_)) => task_service.delete_task(id).await.map_err(|e| {
-            eprintln!("Failed to delete task: {}", e);
-            e.to_string()
-        }),
+        Ok(Some(_)) => {
+            // Listed first, since deleting the task cascades to their rows
+            let attachments = attachment_service(&app, &db_manager)?;
+            let stored = attachments
+                .list_for_task(id)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            task_service.delete_task(id).await.map_err(|e| {
+                eprintln!("Failed to delete task: {}", e);
+                e.to_string()
+            })?;
+
+            // Blobs left behind by a failure here are swept by cleanup_orphans
+            if let Err(e) = attachments.release_for_deleted_task(id, stored).await {
+                eprintln!("[Tasks] Failed to release task attachments: {}", e);
+            }
+            Ok(())
+        }
         Ok(None) => Err("Task not found".to_string()),
         Err(e) => {
             eprintln!("Failed to check if task exists: {}", e);
@@ -430,6 +454,117 @@ pub async fn get_task_count(db_manager: State<'_, Arc<DatabaseManager>>) -> Resu
     })
 }
 
+// Attachment commands
+
+fn attachment_service(
+    app: &tauri::AppHandle,
+    db_manager: &DatabaseManager,
+) -> Result<TaskAttachmentService, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    Ok(TaskAttachmentService::new(
+        db_manager.get_connection_clone(),
+        &app_data_dir,
+    ))
+}
+
+#[tauri::command]
+pub async fn attach_task_file(
+    app: tauri::AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    task_id: i32,
+    file_path: String,
+) -> Result<TaskAttachmentModel, String> {
+    if task_id <= 0 {
+        return Err("Invalid task ID".to_string());
+    }
+
+    let task_service = TaskService::new(db_manager.get_connection_clone());
+    if task_service
+        .get_task(task_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .is_none()
+    {
+        return Err("Task not found".to_string());
+    }
+
+    attachment_service(&app, &db_manager)?
+        .attach_file(task_id, &PathBuf::from(file_path))
+        .await
+        .map_err(|e| {
+            eprintln!("Failed to attach file: {}", e);
+            e.to_string()
+        })
+}
+
+#[tauri::command]
+pub async fn get_task_attachments(
+    app: tauri::AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    task_id: i32,
+) -> Result<Vec<TaskAttachmentModel>, String> {
+    attachment_service(&app, &db_manager)?
+        .list_for_task(task_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_task_attachment_metadata(
+    app: tauri::AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: i32,
+) -> Result<AttachmentMetadata, String> {
+    attachment_service(&app, &db_manager)?
+        .get_metadata(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn open_task_attachment(
+    app: tauri::AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: i32,
+) -> Result<(), String> {
+    let path = attachment_service(&app, &db_manager)?
+        .copy_for_opening(id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::domains::projects::commands::open_project_in_explorer(path.to_string_lossy().to_string())
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_task_attachment(
+    app: tauri::AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    id: i32,
+) -> Result<(), String> {
+    attachment_service(&app, &db_manager)?
+        .delete(id)
+        .await
+        .map_err(|e| {
+            eprintln!("Failed to delete attachment: {}", e);
+            e.to_string()
+        })
+}
+
+#[tauri::command]
+pub async fn cleanup_orphaned_task_attachments(
+    app: tauri::AppHandle,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<AttachmentCleanupReport, String> {
+    attachment_service(&app, &db_manager)?
+        .cleanup_orphans()
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // New advanced commands
 
 #[tauri::command]
@@ -568,3 +703,96 @@ pub async fn generate_tasks_from_story(
             format!("Failed to generate tasks: {}", e)
         })
 }
+
+/// Completed tasks with tracked time to feed into estimation
+const ESTIMATION_HISTORY_LIMIT: u64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstimateTasksCommand {
+    /// Tasks to estimate; defaults to every open task without an estimate
+    pub task_ids: Option<Vec<i32>>,
+    pub provider_type: Option<ProviderType>,
+}
+
+#[tauri::command]
+pub async fn estimate_tasks(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    ai_service: State<'_, Arc<AIService>>,
+    command: EstimateTasksCommand,
+) -> Result<TaskEstimationResult, String> {
+    let task_service = TaskService::new(db_manager.get_connection_clone());
+
+    let tasks = match command.task_ids {
+        Some(ids) if !ids.is_empty() => task_service.get_tasks_by_ids(ids).await,
+        _ => task_service.get_unestimated_tasks().await,
+    }
+    .map_err(|e| e.to_string())?;
+
+    if tasks.is_empty() {
+        return Err("No tasks to estimate".to_string());
+    }
+
+    if tasks.len() > 50 {
+        return Err("Too many tasks to estimate at once (max 50)".to_string());
+    }
+
+    let history = task_service
+        .get_estimation_history(ESTIMATION_HISTORY_LIMIT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    AITaskEstimator::new(ai_service.inner().clone())
+        .estimate_tasks(&tasks, &history, command.provider_type)
+        .await
+        .map_err(|e| {
+            eprintln!("Failed to estimate tasks: {}", e);
+            e
+        })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanSprintCommand {
+    /// Sprint capacity in minutes
+    pub capacity_minutes: i32,
+    /// Candidate tasks; defaults to every open task
+    pub task_ids: Option<Vec<i32>>,
+}
+
+#[tauri::command]
+pub async fn plan_sprint(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+    command: PlanSprintCommand,
+) -> Result<SprintPlan, String> {
+    if command.capacity_minutes <= 0 {
+        return Err("Sprint capacity must be greater than zero".to_string());
+    }
+
+    let task_service = TaskService::new(db_manager.get_connection_clone());
+
+    let candidates = match command.task_ids {
+        Some(ids) if !ids.is_empty() => task_service.get_tasks_by_ids(ids).await,
+        _ => task_service.get_open_tasks().await,
+    }
+    .map_err(|e| e.to_string())?;
+
+    let completed_ids: HashSet<i32> = task_service
+        .get_tasks(Some(TaskFilters {
+            status: Some(vec!["completed".to_string()]),
+            priority: None,
+            type_: None,
+            parent_id: None,
+            resource_id: None,
+            resource_type: None,
+        }))
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+
+    Ok(SprintPlanner::plan(
+        candidates,
+        command.capacity_minutes,
+        &completed_ids,
+    ))
+}
@@ -2,7 +2,8 @@ use crate::entities::task_attachment::{
     ActiveModel, Column, Entity as TaskAttachmentEntity, Model as TaskAttachmentModel,
 };
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set,
 };
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +14,7 @@ pub struct CreateTaskAttachmentRequest {
     pub url: String,
     pub type_: String,
     pub size: i64,
+    pub content_hash: Option<String>,
 }
 
 pub struct TaskAttachmentRepository {
@@ -34,6 +36,7 @@ impl TaskAttachmentRepository {
             url: Set(request.url),
             type_: Set(request.type_),
             size: Set(request.size),
+            content_hash: Set(request.content_hash),
             ..Default::default()
         };
 
@@ -61,4 +64,25 @@ impl TaskAttachmentRepository {
     pub async fn find_by_id(&self, id: i32) -> Result<Option<TaskAttachmentModel>, sea_orm::DbErr> {
         TaskAttachmentEntity::find_by_id(id).one(&self.db).await
     }
+
+    pub async fn find_all(&self) -> Result<Vec<TaskAttachmentModel>, sea_orm::DbErr> {
+        TaskAttachmentEntity::find().all(&self.db).await
+    }
+
+    pub async fn delete_by_task_id(&self, task_id: i32) -> Result<u64, sea_orm::DbErr> {
+        let result = TaskAttachmentEntity::delete_many()
+            .filter(Column::TaskId.eq(task_id))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// Number of attachment rows still pointing at a stored blob. The blob can be
+    /// removed from disk once this drops to zero.
+    pub async fn count_by_content_hash(&self, content_hash: &str) -> Result<u64, sea_orm::DbErr> {
+        TaskAttachmentEntity::find()
+            .filter(Column::ContentHash.eq(content_hash))
+            .count(&self.db)
+            .await
+    }
 }
@@ -3,7 +3,7 @@ use crate::domains::tasks::entities::task::{
 };
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
-    QueryOrder, Set,
+    QueryOrder, QuerySelect, Set,
 };
 use serde::{Deserialize, Serialize};
 
@@ -306,6 +306,37 @@ impl TaskRepository {
             .await
     }
 
+    /// Completed tasks with tracked time, used as history for estimation.
+    pub async fn find_completed_with_actuals(
+        &self,
+        limit: u64,
+    ) -> Result<Vec<TaskModel>, sea_orm::DbErr> {
+        TaskEntity::find()
+            .filter(Column::Status.eq("completed"))
+            .filter(Column::ActualTime.is_not_null())
+            .order_by_desc(Column::CompletedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_by_ids(&self, ids: Vec<i32>) -> Result<Vec<TaskModel>, sea_orm::DbErr> {
+        TaskEntity::find()
+            .filter(Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+    }
+
+    /// Tasks that are still open (not completed or cancelled).
+    pub async fn find_open(&self) -> Result<Vec<TaskModel>, sea_orm::DbErr> {
+        TaskEntity::find()
+            .filter(Column::Status.ne("completed"))
+            .filter(Column::Status.ne("cancelled"))
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
     pub async fn find_unestimated(&self) -> Result<Vec<TaskModel>, sea_orm::DbErr> {
         TaskEntity::find()
             .filter(Column::EstimatedTime.is_null())
@@ -0,0 +1,294 @@
+use crate::domains::ai::providers::{GenerationOptions, ProviderType};
+use crate::domains::ai::services::AIService;
+use crate::domains::tasks::entities::task::Model as TaskModel;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// How many completed tasks to show the model as reference points
+const MAX_HISTORY_EXAMPLES: usize = 15;
+
+/// Suggested estimate for a single task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEstimate {
+    pub task_id: i32,
+    pub title: String,
+    /// Raw estimate returned by the model (minutes)
+    pub ai_estimate: Option<i32>,
+    /// Estimate after applying the historical accuracy ratio (minutes)
+    pub suggested_estimate: i32,
+    /// Confidence score (0.0 to 1.0)
+    pub confidence: f64,
+    pub rationale: String,
+}
+
+/// Summary of how past estimates compared to tracked time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EstimationHistory {
+    /// Completed tasks with tracked time that were considered
+    pub sample_size: usize,
+    /// Tasks that had both an estimate and tracked time
+    pub calibrated_samples: usize,
+    /// actual / estimated across calibrated samples; 1.0 when there's no data
+    pub accuracy_ratio: f64,
+    /// Median tracked minutes per task type
+    pub median_actual_by_type: HashMap<String, i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskEstimationResult {
+    pub estimates: Vec<TaskEstimate>,
+    pub history: EstimationHistory,
+    pub model_used: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEstimate {
+    task_id: i32,
+    estimated_time: Option<f64>,
+    confidence: Option<f64>,
+    rationale: Option<String>,
+}
+
+/// Suggests task estimates using the configured AI provider, calibrated against
+/// how long similar tasks actually took.
+pub struct AITaskEstimator {
+    ai_service: Arc<AIService>,
+}
+
+impl AITaskEstimator {
+    pub fn new(ai_service: Arc<AIService>) -> Self {
+        Self { ai_service }
+    }
+
+    pub async fn estimate_tasks(
+        &self,
+        tasks: &[TaskModel],
+        history: &[TaskModel],
+        provider_type: Option<ProviderType>,
+    ) -> Result<TaskEstimationResult, String> {
+        let stats = Self::summarize_history(history);
+
+        let system_message = Self::build_system_message();
+        let prompt = Self::build_prompt(tasks, history, &stats);
+        let options = GenerationOptions {
+            temperature: Some(0.3),
+            max_tokens: Some(2000),
+            timeout_ms: Some(120000),
+            model: None,
+            llm_provider: None,
+            extra_options: None,
+            feature: Some("task_estimation".to_string()),
+            bypass_cache: false,
+        };
+
+        let result = self
+            .ai_service
+            .generate_with_system(&system_message, &prompt, Some(options), provider_type)
+            .await
+            .map_err(|e| {
+                format!(
+                    "AI estimation failed: {}. Please check your AI provider configuration.",
+                    e
+                )
+            })?;
+
+        let raw = Self::parse_ai_response(&result.content)?;
+        let estimates = Self::calibrate(tasks, raw, &stats);
+
+        Ok(TaskEstimationResult {
+            estimates,
+            history: stats,
+            model_used: result.model,
+        })
+    }
+
+    fn build_system_message() -> String {
+        r#"You are an engineering estimation assistant. Given tasks and a history of how long similar tasks actually took, estimate each task in minutes.
+
+Your response MUST be valid JSON in this exact format:
+{
+  "estimates": [
+    {
+      "task_id": 12,
+      "estimated_time": 90,
+      "confidence": 0.7,
+      "rationale": "One sentence explaining the estimate"
+    }
+  ]
+}
+
+Guidelines:
+- Return exactly one entry per task, using the task_id given
+- Anchor on the historical actuals, not on optimism
+- Lower the confidence for vague tasks
+- Keep rationales to one sentence"#
+            .to_string()
+    }
+
+    fn build_prompt(
+        tasks: &[TaskModel],
+        history: &[TaskModel],
+        stats: &EstimationHistory,
+    ) -> String {
+        let mut prompt = String::new();
+
+        if !history.is_empty() {
+            prompt.push_str("=== HISTORICAL ACTUALS ===\n");
+            for task in history.iter().take(MAX_HISTORY_EXAMPLES) {
+                prompt.push_str(&format!(
+                    "- {} [{}] actual: {} min",
+                    task.title,
+                    task.type_.as_deref().unwrap_or("Task"),
+                    task.actual_time.unwrap_or_default()
+                ));
+                if let Some(estimate) = task.estimated_time {
+                    prompt.push_str(&format!(", estimated: {} min", estimate));
+                }
+                prompt.push('\n');
+            }
+            if stats.calibrated_samples > 0 {
+                prompt.push_str(&format!(
+                    "\nPast estimates were off by a factor of {:.2} (actual / estimated).\n",
+                    stats.accuracy_ratio
+                ));
+            }
+            prompt.push('\n');
+        }
+
+        prompt.push_str("=== TASKS TO ESTIMATE ===\n");
+        for task in tasks {
+            prompt.push_str(&format!(
+                "task_id: {}\nTitle: {}\nType: {}\nPriority: {}\n",
+                task.id,
+                task.title,
+                task.type_.as_deref().unwrap_or("Task"),
+                task.priority
+            ));
+            if let Some(ref description) = task.description {
+                prompt.push_str(&format!("Description: {}\n", description));
+            }
+            if let Some(ref tags) = task.tags {
+                prompt.push_str(&format!("Tags: {}\n", tags));
+            }
+            prompt.push('\n');
+        }
+
+        prompt
+    }
+
+    fn parse_ai_response(response: &str) -> Result<Vec<RawEstimate>, String> {
+        let json_str = match (response.find('{'), response.rfind('}')) {
+            (Some(start), Some(end)) if end > start => &response[start..=end],
+            _ => response.trim(),
+        };
+
+        let value: serde_json::Value = serde_json::from_str(json_str).map_err(|e| {
+            format!(
+                "Failed to parse AI response as JSON: {}. Response: {}",
+                e, response
+            )
+        })?;
+
+        let estimates = value
+            .get("estimates")
+            .cloned()
+            .ok_or("Missing estimates array")?;
+
+        serde_json::from_value(estimates).map_err(|e| format!("Failed to parse estimates: {}", e))
+    }
+
+    /// Aggregate tracked time from completed tasks.
+    pub fn summarize_history(history: &[TaskModel]) -> EstimationHistory {
+        let mut estimated_total = 0i64;
+        let mut actual_total = 0i64;
+        let mut calibrated_samples = 0;
+        let mut by_type: HashMap<String, Vec<i32>> = HashMap::new();
+
+        for task in history {
+            let Some(actual) = task.actual_time.filter(|a| *a > 0) else {
+                continue;
+            };
+            by_type
+                .entry(task.type_.clone().unwrap_or_else(|| "Task".to_string()))
+                .or_default()
+                .push(actual);
+
+            if let Some(estimate) = task.estimated_time.filter(|e| *e > 0) {
+                estimated_total += estimate as i64;
+                actual_total += actual as i64;
+                calibrated_samples += 1;
+            }
+        }
+
+        let accuracy_ratio = if estimated_total > 0 {
+            actual_total as f64 / estimated_total as f64
+        } else {
+            1.0
+        };
+
+        let median_actual_by_type = by_type
+            .into_iter()
+            .map(|(type_, mut actuals)| {
+                actuals.sort_unstable();
+                (type_, actuals[actuals.len() / 2])
+            })
+            .collect();
+
+        EstimationHistory {
+            sample_size: history.len(),
+            calibrated_samples,
+            accuracy_ratio,
+            median_actual_by_type,
+        }
+    }
+
+    /// Combine model output with history. Tasks the model skipped fall back to the
+    /// median actual for their type.
+    fn calibrate(
+        tasks: &[TaskModel],
+        raw: Vec<RawEstimate>,
+        stats: &EstimationHistory,
+    ) -> Vec<TaskEstimate> {
+        let mut by_id: HashMap<i32, RawEstimate> =
+            raw.into_iter().map(|r| (r.task_id, r)).collect();
+
+        // The model already sees the ratio in the prompt, so only correct halfway to
+        // avoid double-counting it.
+        let correction = 1.0 + (stats.accuracy_ratio - 1.0) / 2.0;
+
+        tasks
+            .iter()
+            .map(|task| {
+                let type_key = task.type_.clone().unwrap_or_else(|| "Task".to_string());
+                let fallback = stats.median_actual_by_type.get(&type_key).copied();
+
+                match by_id.remove(&task.id) {
+                    Some(raw) => {
+                        let ai_estimate = raw.estimated_time.map(|m| m.round() as i32);
+                        let base = ai_estimate.or(fallback).unwrap_or(60);
+                        TaskEstimate {
+                            task_id: task.id,
+                            title: task.title.clone(),
+                            ai_estimate,
+                            suggested_estimate: ((base as f64 * correction).round() as i32).max(5),
+                            confidence: raw.confidence.unwrap_or(0.5).clamp(0.0, 1.0),
+                            rationale: raw.rationale.unwrap_or_default(),
+                        }
+                    }
+                    None => TaskEstimate {
+                        task_id: task.id,
+                        title: task.title.clone(),
+                        ai_estimate: None,
+                        suggested_estimate: fallback.unwrap_or(60),
+                        confidence: if fallback.is_some() { 0.3 } else { 0.1 },
+                        rationale: match fallback {
+                            Some(_) => format!("Median tracked time for {} tasks", type_key),
+                            None => "No AI estimate or history available".to_string(),
+                        },
+                    },
+                }
+            })
+            .collect()
+    }
+}
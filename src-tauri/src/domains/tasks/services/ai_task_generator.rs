@@ -166,6 +166,8 @@ impl AITaskGenerator {
             model: None,
             llm_provider: None,
             extra_options: None,
+            feature: Some("task_generation".to_string()),
+            bypass_cache: false,
         };
 
         let result = self
@@ -0,0 +1,377 @@
+//! Managed storage for task attachments.
+//!
+//! Attached files are copied into `<app_data>/task_attachments/` and named by the
+//! SHA-256 of their contents, so the same file attached to several tasks is stored
+//! once. A blob is removed from disk when the last attachment row referencing it is
+//! deleted; [`TaskAttachmentService::cleanup_orphans`] sweeps anything that slipped
+//! through (crashes mid-delete, rows removed by a foreign-key cascade).
+
+use crate::domains::tasks::repositories::task_attachment_repository::{
+    CreateTaskAttachmentRequest, TaskAttachmentRepository,
+};
+use crate::entities::task_attachment::Model as TaskAttachmentModel;
+use crate::{log_info, log_warn, AppError, AppResult};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const STORAGE_DIR: &str = "task_attachments";
+
+/// Bytes read from text attachments for the preview snippet.
+const TEXT_PREVIEW_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentMetadata {
+    pub id: i32,
+    pub task_id: i32,
+    pub name: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub content_hash: Option<String>,
+    pub stored_path: String,
+    /// False when the managed copy (or, for legacy rows, the original file) is gone.
+    pub exists: bool,
+    pub is_image: bool,
+    pub is_text: bool,
+    /// First few KB of text attachments, lossily decoded as UTF-8.
+    pub text_preview: Option<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttachmentCleanupReport {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+}
+
+pub struct TaskAttachmentService {
+    repository: TaskAttachmentRepository,
+    storage_dir: PathBuf,
+}
+
+impl TaskAttachmentService {
+    pub fn new(db: DatabaseConnection, app_data_dir: &Path) -> Self {
+        Self {
+            repository: TaskAttachmentRepository::new(db),
+            storage_dir: app_data_dir.join(STORAGE_DIR),
+        }
+    }
+
+    /// Copy `source` into managed storage and record it against `task_id`.
+    pub async fn attach_file(&self, task_id: i32, source: &Path) -> AppResult<TaskAttachmentModel> {
+        if !source.is_file() {
+            return Err(AppError::FileNotFound(source.display().to_string()));
+        }
+
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| AppError::Path(format!("Invalid file path: {}", source.display())))?;
+
+        let content_hash = hash_file(source)?;
+        let stored_path = self.blob_path(&content_hash);
+
+        if !stored_path.exists() {
+            if let Some(parent) = stored_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // Copy to a temp name first so a half-written blob never sits at the final path.
+            let tmp_path = stored_path.with_extension("partial");
+            std::fs::copy(source, &tmp_path)?;
+            std::fs::rename(&tmp_path, &stored_path)?;
+        }
+
+        let size = std::fs::metadata(&stored_path)?.len() as i64;
+
+        let attachment = self
+            .repository
+            .create(CreateTaskAttachmentRequest {
+                task_id,
+                name: name.clone(),
+                url: stored_path.to_string_lossy().to_string(),
+                type_: guess_mime_type(&name).to_string(),
+                size,
+                content_hash: Some(content_hash),
+            })
+            .await?;
+
+        log_info!(
+            "TaskAttachments",
+            "Attached {} ({} bytes) to task {}",
+            name,
+            size,
+            task_id
+        );
+
+        Ok(attachment)
+    }
+
+    pub async fn list_for_task(&self, task_id: i32) -> AppResult<Vec<TaskAttachmentModel>> {
+        Ok(self.repository.find_by_task_id(task_id).await?)
+    }
+
+    pub async fn get_metadata(&self, id: i32) -> AppResult<AttachmentMetadata> {
+        let attachment = self.find(id).await?;
+        let path = PathBuf::from(&attachment.url);
+        let exists = path.is_file();
+        let mime_type = attachment.type_.clone();
+        let is_text = is_text_mime(&mime_type);
+
+        let text_preview = if exists && is_text {
+            read_text_preview(&path).ok()
+        } else {
+            None
+        };
+
+        Ok(AttachmentMetadata {
+            id: attachment.id,
+            task_id: attachment.task_id,
+            name: attachment.name,
+            is_image: mime_type.starts_with("image/"),
+            is_text,
+            mime_type,
+            size: attachment.size,
+            content_hash: attachment.content_hash,
+            stored_path: attachment.url,
+            exists,
+            text_preview,
+            created_at: attachment.created_at.map(|dt| dt.into()),
+        })
+    }
+
+    /// Copy an attachment to a temp file under its original name, for opening
+    /// with the OS default app. Blobs have no extension to pick an app by, and
+    /// a copy keeps edits made in that app out of the shared blob.
+    pub async fn copy_for_opening(&self, id: i32) -> AppResult<PathBuf> {
+        let attachment = self.find(id).await?;
+        let blob = PathBuf::from(&attachment.url);
+        if !blob.is_file() {
+            return Err(AppError::FileNotFound(attachment.url));
+        }
+        let name = Path::new(&attachment.name)
+            .file_name()
+            .ok_or_else(|| AppError::Path(format!("Invalid file name: {}", attachment.name)))?;
+
+        let dir = std::env::temp_dir()
+            .join("portal-desktop-attachments")
+            .join(id.to_string());
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(name);
+        std::fs::copy(&blob, &path)?;
+        Ok(path)
+    }
+
+    pub async fn delete(&self, id: i32) -> AppResult<()> {
+        let attachment = self.find(id).await?;
+        self.repository.delete(id).await?;
+        self.release_blob(attachment.content_hash.as_deref()).await;
+        Ok(())
+    }
+
+    /// Clear up after a deleted task: drop its attachment rows, if the
+    /// foreign-key cascade hasn't already, and the blobs nothing else uses.
+    /// `attachments` are the task's, listed before it was deleted.
+    pub async fn release_for_deleted_task(
+        &self,
+        task_id: i32,
+        attachments: Vec<TaskAttachmentModel>,
+    ) -> AppResult<()> {
+        self.repository.delete_by_task_id(task_id).await?;
+
+        let hashes: HashSet<String> = attachments
+            .into_iter()
+            .filter_map(|a| a.content_hash)
+            .collect();
+        for hash in hashes {
+            self.release_blob(Some(&hash)).await;
+        }
+
+        Ok(())
+    }
+
+    /// Remove stored blobs that no attachment row references any more.
+    pub async fn cleanup_orphans(&self) -> AppResult<AttachmentCleanupReport> {
+        let mut report = AttachmentCleanupReport::default();
+        if !self.storage_dir.exists() {
+            return Ok(report);
+        }
+
+        let referenced: HashSet<String> = self
+            .repository
+            .find_all()
+            .await?
+            .into_iter()
+            .filter_map(|a| a.content_hash)
+            .collect();
+
+        for shard in std::fs::read_dir(&self.storage_dir)?.flatten() {
+            let shard_path = shard.path();
+            if !shard_path.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(&shard_path)?.flatten() {
+                let path = entry.path();
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                if referenced.contains(&file_name) {
+                    continue;
+                }
+                let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                match std::fs::remove_file(&path) {
+                    Ok(()) => {
+                        report.files_removed += 1;
+                        report.bytes_freed += len;
+                    }
+                    Err(e) => log_warn!(
+                        "TaskAttachments",
+                        "Failed to remove orphaned attachment {}: {}",
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+            // Only succeeds when the shard is empty, which is what we want.
+            let _ = std::fs::remove_dir(&shard_path);
+        }
+
+        if report.files_removed > 0 {
+            log_info!(
+                "TaskAttachments",
+                "Removed {} orphaned attachment(s), freed {} bytes",
+                report.files_removed,
+                report.bytes_freed
+            );
+        }
+
+        Ok(report)
+    }
+
+    async fn find(&self, id: i32) -> AppResult<TaskAttachmentModel> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Attachment {} not found", id)))
+    }
+
+    /// Delete the blob for `content_hash` if no attachment row still references it.
+    /// Failures are logged rather than surfaced: the row is already gone and the
+    /// orphan sweep will retry.
+    async fn release_blob(&self, content_hash: Option<&str>) {
+        let Some(hash) = content_hash else {
+            return;
+        };
+
+        match self.repository.count_by_content_hash(hash).await {
+            Ok(0) => {
+                let path = self.blob_path(hash);
+                if let Err(e) = std::fs::remove_file(&path) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        log_warn!(
+                            "TaskAttachments",
+                            "Failed to remove attachment blob {}: {}",
+                            path.display(),
+                            e
+                        );
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log_warn!(
+                "TaskAttachments",
+                "Failed to count references to {}: {}",
+                hash,
+                e
+            ),
+        }
+    }
+
+    /// Blobs are sharded by the first two hex chars to keep directories small.
+    fn blob_path(&self, content_hash: &str) -> PathBuf {
+        self.storage_dir.join(&content_hash[..2]).join(content_hash)
+    }
+}
+
+fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 8192];
+
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn read_text_preview(path: &Path) -> std::io::Result<String> {
+    let mut buffer = Vec::with_capacity(TEXT_PREVIEW_BYTES);
+    std::fs::File::open(path)?
+        .take(TEXT_PREVIEW_BYTES as u64)
+        .read_to_end(&mut buffer)?;
+    Ok(String::from_utf8_lossy(&buffer).to_string())
+}
+
+fn is_text_mime(mime: &str) -> bool {
+    mime.starts_with("text/") || matches!(mime, "application/json" | "application/x-yaml")
+}
+
+/// Best-effort MIME type from the file extension. Only needs to be good enough for
+/// the frontend to pick a preview renderer.
+pub fn guess_mime_type(file_name: &str) -> &'static str {
+    let ext = Path::new(file_name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "json" => "application/json",
+        "yaml" | "yml" => "application/x-yaml",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "txt" | "log" | "rs" | "ts" | "js" | "py" | "go" | "java" | "toml" | "sh" | "sql" => {
+            "text/plain"
+        }
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_match_sha256_of_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("note.txt");
+        std::fs::write(&path, b"hello").unwrap();
+
+        assert_eq!(
+            hash_file(&path).unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+
+    #[test]
+    fn guesses_mime_from_extension_case_insensitively() {
+        assert_eq!(guess_mime_type("Screenshot.PNG"), "image/png");
+        assert_eq!(guess_mime_type("notes.md"), "text/markdown");
+        assert_eq!(guess_mime_type("no_extension"), "application/octet-stream");
+        assert!(is_text_mime(guess_mime_type("config.yml")));
+    }
+}
@@ -1,3 +1,6 @@
+pub mod ai_task_estimator;
 pub mod ai_task_generator;
+pub mod attachment_service;
+pub mod sprint_planner;
 pub mod story_parser;
 pub mod task_service;
@@ -0,0 +1,258 @@
+use crate::domains::tasks::entities::task::Model as TaskModel;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// A task selected into the sprint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedTask {
+    pub task_id: i32,
+    pub title: String,
+    pub priority: String,
+    pub estimated_time: i32, // minutes
+    /// Running total of committed minutes after this task
+    pub cumulative_time: i32,
+}
+
+/// A candidate task that didn't make it into the sprint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeferredTask {
+    pub task_id: i32,
+    pub title: String,
+    pub estimated_time: Option<i32>,
+    pub reason: String,
+}
+
+/// Result of packing tasks into a capacity-bound sprint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SprintPlan {
+    pub capacity_minutes: i32,
+    pub committed_minutes: i32,
+    pub remaining_minutes: i32,
+    /// committed / capacity, 0.0 to 1.0
+    pub utilization: f64,
+    pub selected: Vec<PlannedTask>,
+    pub deferred: Vec<DeferredTask>,
+}
+
+/// Packs open tasks into a sprint of fixed capacity.
+///
+/// Candidates are ranked by priority, then due date, then size (smaller first), and
+/// taken greedily while they fit. A task is only selected once every task it is
+/// `blocked_by` is either already completed or selected earlier in the same sprint,
+/// so the plan is always executable in order.
+pub struct SprintPlanner;
+
+impl SprintPlanner {
+    pub fn plan(
+        candidates: Vec<TaskModel>,
+        capacity_minutes: i32,
+        completed_ids: &HashSet<i32>,
+    ) -> SprintPlan {
+        let mut pending: Vec<TaskModel> = Vec::new();
+        let mut deferred = Vec::new();
+
+        for task in candidates {
+            if task.status == "completed" || task.status == "cancelled" {
+                continue;
+            }
+            match task.estimated_time {
+                Some(minutes) if minutes > 0 => pending.push(task),
+                _ => deferred.push(DeferredTask {
+                    task_id: task.id,
+                    title: task.title,
+                    estimated_time: task.estimated_time,
+                    reason: "No estimate".to_string(),
+                }),
+            }
+        }
+
+        pending.sort_by(|a, b| {
+            priority_rank(&b.priority)
+                .cmp(&priority_rank(&a.priority))
+                .then_with(|| match (&a.due_date, &b.due_date) {
+                    (Some(a), Some(b)) => a.cmp(b),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                })
+                .then_with(|| a.estimated_time.cmp(&b.estimated_time))
+        });
+
+        let mut selected: Vec<PlannedTask> = Vec::new();
+        let mut selected_ids: HashSet<i32> = HashSet::new();
+        let mut committed = 0;
+
+        // Selecting a task can unblock one that was skipped earlier, so keep sweeping
+        // until a pass makes no progress.
+        loop {
+            let mut progressed = false;
+            let mut still_pending = Vec::new();
+
+            for task in pending {
+                let estimate = task.estimated_time.unwrap_or_default();
+                let blockers = parse_task_ids(task.blocked_by.as_deref());
+                let unblocked = blockers
+                    .iter()
+                    .all(|id| completed_ids.contains(id) || selected_ids.contains(id));
+
+                if unblocked && committed + estimate <= capacity_minutes {
+                    committed += estimate;
+                    selected_ids.insert(task.id);
+                    selected.push(PlannedTask {
+                        task_id: task.id,
+                        title: task.title,
+                        priority: task.priority,
+                        estimated_time: estimate,
+                        cumulative_time: committed,
+                    });
+                    progressed = true;
+                } else {
+                    still_pending.push(task);
+                }
+            }
+
+            pending = still_pending;
+            if !progressed || pending.is_empty() {
+                break;
+            }
+        }
+
+        for task in pending {
+            let blockers = parse_task_ids(task.blocked_by.as_deref());
+            let blocked = blockers
+                .iter()
+                .any(|id| !completed_ids.contains(id) && !selected_ids.contains(id));
+            deferred.push(DeferredTask {
+                task_id: task.id,
+                title: task.title,
+                estimated_time: task.estimated_time,
+                reason: if blocked {
+                    "Blocked by tasks outside this sprint".to_string()
+                } else {
+                    "Exceeds remaining capacity".to_string()
+                },
+            });
+        }
+
+        let utilization = if capacity_minutes > 0 {
+            committed as f64 / capacity_minutes as f64
+        } else {
+            0.0
+        };
+
+        SprintPlan {
+            capacity_minutes,
+            committed_minutes: committed,
+            remaining_minutes: capacity_minutes - committed,
+            utilization,
+            selected,
+            deferred,
+        }
+    }
+}
+
+fn priority_rank(priority: &str) -> u8 {
+    match priority {
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+/// Parse a JSON array of task IDs. The frontend has written both numbers and
+/// numeric strings into `blocked_by`, so accept either.
+pub fn parse_task_ids(raw: Option<&str>) -> Vec<i32> {
+    let Some(raw) = raw else {
+        return Vec::new();
+    };
+
+    serde_json::from_str::<Vec<serde_json::Value>>(raw)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|v| match v {
+            serde_json::Value::Number(n) => n.as_i64().map(|n| n as i32),
+            serde_json::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: i32, priority: &str, estimate: Option<i32>, blocked_by: Option<&str>) -> TaskModel {
+        TaskModel {
+            id,
+            title: format!("Task {}", id),
+            description: None,
+            status: "pending".to_string(),
+            priority: priority.to_string(),
+            type_: None,
+            parent_id: None,
+            resource_id: None,
+            resource_type: None,
+            due_date: None,
+            completed_at: None,
+            created_at: None,
+            updated_at: None,
+            estimated_time: estimate,
+            actual_time: None,
+            tags: None,
+            assignee: None,
+            recurring_pattern: None,
+            recurring_interval: None,
+            recurring_end_date: None,
+            recurring_last_generated: None,
+            blocked_by: blocked_by.map(|s| s.to_string()),
+            blocks: None,
+        }
+    }
+
+    #[test]
+    fn packs_by_priority_within_capacity() {
+        let plan = SprintPlanner::plan(
+            vec![
+                task(1, "low", Some(60), None),
+                task(2, "high", Some(120), None),
+                task(3, "medium", Some(90), None),
+                task(4, "high", None, None),
+            ],
+            240,
+            &HashSet::new(),
+        );
+
+        let ids: Vec<i32> = plan.selected.iter().map(|t| t.task_id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(plan.committed_minutes, 210);
+        assert_eq!(plan.remaining_minutes, 30);
+        assert!(plan
+            .deferred
+            .iter()
+            .any(|d| d.task_id == 4 && d.reason == "No estimate"));
+        assert!(plan.deferred.iter().any(|d| d.task_id == 1));
+    }
+
+    #[test]
+    fn selects_blocked_task_once_its_blocker_is_in() {
+        // Task 1 is high priority but waits on task 2, which is low priority.
+        let plan = SprintPlanner::plan(
+            vec![
+                task(1, "high", Some(30), Some("[2]")),
+                task(2, "low", Some(30), None),
+                task(3, "high", Some(30), Some("[\"99\"]")),
+            ],
+            120,
+            &HashSet::new(),
+        );
+
+        let ids: Vec<i32> = plan.selected.iter().map(|t| t.task_id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(plan.deferred.len(), 1);
+        assert_eq!(
+            plan.deferred[0].reason,
+            "Blocked by tasks outside this sprint"
+        );
+    }
+}
@@ -110,6 +110,22 @@ impl TaskService {
         self.repository.find_unestimated().await
     }
 
+    pub async fn get_tasks_by_ids(&self, ids: Vec<i32>) -> Result<Vec<TaskModel>, sea_orm::DbErr> {
+        self.repository.find_by_ids(ids).await
+    }
+
+    pub async fn get_open_tasks(&self) -> Result<Vec<TaskModel>, sea_orm::DbErr> {
+        self.repository.find_open().await
+    }
+
+    /// Most recently completed tasks that have tracked time.
+    pub async fn get_estimation_history(
+        &self,
+        limit: u64,
+    ) -> Result<Vec<TaskModel>, sea_orm::DbErr> {
+        self.repository.find_completed_with_actuals(limit).await
+    }
+
     pub async fn get_recurring_tasks(&self) -> Result<Vec<TaskModel>, sea_orm::DbErr> {
         self.repository.find_recurring().await
     }
@@ -1,5 +1,8 @@
 use crate::database::DatabaseManager;
+use crate::domains::credentials::services::{CredentialAccessor, ProjectEnvService};
+use crate::domains::settings::services::project_settings_service::ProjectSettingsService;
 use crate::domains::terminal::manager::TerminalManager;
+use crate::domains::terminal::profiles::{self, ShellProfile, ShellProfileInput};
 use crate::domains::terminal::types::*;
 use crate::entities::terminal_command_history as terminal_command_history_entity;
 use crate::entities::terminal_note as terminal_note_entity;
@@ -14,13 +17,75 @@ use tauri::{command, State, Window};
 
 #[command]
 pub async fn create_terminal_process(
-    request: CreateProcessRequest,
+    mut request: CreateProcessRequest,
     manager: State<'_, TerminalManager>,
+    db_manager: State<'_, Arc<DatabaseManager>>,
     window: Window,
 ) -> Result<TerminalProcess, String> {
+    if let Some(profile_id) = &request.profile_id {
+        let profile = profiles::get_profile(db_manager.get_connection(), profile_id).await?;
+        request.shell = profile.shell.clone();
+        for (key, value) in &profile.env {
+            request
+                .environment
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+        request.profile = Some(profile);
+    }
+    if let Some(project_id) = request.project_id {
+        request.injected_environment = ProjectEnvService::new(db_manager.inner().clone())
+            .resolve_env(project_id, CredentialAccessor::Terminal)
+            .await?;
+        // Interactive shells follow the project's shell override, unless a
+        // profile was picked
+        if request.command.is_none() && request.profile.is_none() {
+            if let Some(shell) = ProjectSettingsService::new(db_manager.inner().clone())
+                .get_overrides(project_id)
+                .await?
+                .shell
+            {
+                request.shell = shell;
+            }
+        }
+    }
     manager.create_process(request, window).await
 }
 
+/// Shell profiles for this OS, built-in ones first
+#[command]
+pub async fn get_shell_profiles(
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<ShellProfile>, String> {
+    let available = get_available_shells().await;
+    profiles::list_profiles(db_manager.get_connection(), &available).await
+}
+
+#[command]
+pub async fn create_shell_profile(
+    profile: ShellProfileInput,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<ShellProfile, String> {
+    profiles::create_profile(db_manager.get_connection(), profile).await
+}
+
+#[command]
+pub async fn update_shell_profile(
+    id: String,
+    profile: ShellProfileInput,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<ShellProfile, String> {
+    profiles::update_profile(db_manager.get_connection(), &id, profile).await
+}
+
+#[command]
+pub async fn delete_shell_profile(
+    id: String,
+    db_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<(), String> {
+    profiles::delete_profile(db_manager.get_connection(), &id).await
+}
+
 #[command]
 pub async fn send_terminal_input(
     process_id: String,
@@ -30,6 +95,16 @@ pub async fn send_terminal_input(
     manager.send_input(process_id, input).await
 }
 
+/// Send the same input to every session in `process_ids`
+#[command]
+pub async fn send_input_to_group(
+    process_ids: Vec<String>,
+    input: String,
+    manager: State<'_, TerminalManager>,
+) -> Result<BroadcastResult, String> {
+    manager.send_input_to_group(process_ids, input).await
+}
+
 #[command]
 pub async fn execute_command(
     request: ExecuteCommandRequest,
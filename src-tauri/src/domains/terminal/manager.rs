@@ -1,5 +1,7 @@
+use crate::command_executor::supervisor::supervisor;
+use crate::domains::projects::services::quick_open;
 use crate::domains::terminal::shell_integration::{
-    ShellIntegrationEventV2, ShellIntegrationParser,
+    ShellIntegrationEvent, ShellIntegrationEventV2, ShellIntegrationParser,
 };
 use crate::domains::terminal::types::*;
 use crate::process_ext::NoWindowExt;
@@ -28,6 +30,28 @@ struct PtySessionResources {
     writer: Box<dyn Write + Send>,
     /// zsh: temp ZDOTDIR directory; bash: temp rcfile. Deleted on teardown.
     temp_rc_path: Option<PathBuf>,
+    /// Shell PID, registered with the process supervisor until teardown
+    pid: Option<u32>,
+}
+
+impl PtySessionResources {
+    fn write_input(&mut self, input: &str) -> Result<(), String> {
+        self.writer
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("Failed to send input: {}", e))?;
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to flush stdin: {}", e))
+    }
+
+    /// Release what outlives the handles: the temp rc file and the
+    /// supervisor registration
+    fn release(&self) {
+        remove_temp_rc(self.temp_rc_path.as_deref());
+        if let Some(pid) = self.pid {
+            supervisor().unregister(pid);
+        }
+    }
 }
 
 pub type ProcessMap = Arc<Mutex<HashMap<String, TerminalProcess>>>;
@@ -67,8 +91,11 @@ impl TerminalManager {
         let oneshot_command = request.command.clone();
         let is_oneshot = oneshot_command.is_some();
 
-        // Resolve the concrete shell executable + base args from the requested shell.
-        let (shell_cmd, mut shell_args) = {
+        // Resolve the concrete shell executable + base args from the profile,
+        // or else from the requested shell.
+        let (shell_cmd, mut shell_args) = if let Some(profile) = &request.profile {
+            (profile.shell.clone(), profile.args.clone())
+        } else {
             let shell_lower = request.shell.to_lowercase();
             if cfg!(target_os = "windows") {
                 if shell_lower.contains("cmd") || shell_lower == "cmd.exe" {
@@ -117,6 +144,11 @@ impl TerminalManager {
         if let Some(cmd) = &oneshot_command {
             shell_args = oneshot_shell_args(&shell_cmd, cmd);
         }
+        // Profiles may name the shell by path; integration keys off its name.
+        let shell_name = Path::new(&shell_cmd)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
 
         let process = TerminalProcess {
             id: process_id.clone(),
@@ -170,6 +202,11 @@ impl TerminalManager {
             request.working_directory.clone()
         };
         let mut environment = request.environment.clone();
+        for (key, value) in &request.injected_environment {
+            environment
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
 
         // Fill in essential env vars from the real process environment when the
         // caller did not provide them. No fake/hardcoded HOME/USER values here.
@@ -265,7 +302,7 @@ if (Get-Command -Name Set-PSReadLineOption -ErrorAction SilentlyContinue) {
         } else if !is_oneshot && !cfg!(target_os = "windows") {
             let shell_lower = request.shell.to_lowercase();
 
-            if shell_cmd == "zsh" && shell_lower.contains("zsh") {
+            if shell_name == "zsh" && shell_lower.contains("zsh") {
                 let zsh_dir = std::env::temp_dir().join(format!(
                     "portal_osc133_zsh_{}",
                     process_id.replace('-', "_")
@@ -307,7 +344,7 @@ add-zsh-hook precmd __portal_precmd
 
                 environment.insert("ZDOTDIR".to_string(), zsh_dir.to_string_lossy().to_string());
                 temp_rc_path = Some(zsh_dir);
-            } else if shell_cmd == "bash" && shell_lower.contains("bash") {
+            } else if shell_name == "bash" && shell_lower.contains("bash") {
                 let bash_rc_path = std::env::temp_dir().join(format!(
                     "portal_osc133_bashrc_{}",
                     process_id.replace('-', "_")
@@ -381,12 +418,39 @@ PROMPT_COMMAND='__portal_osc133_precmd'
         let reader = master
             .try_clone_reader()
             .map_err(|e| format!("Failed to clone PTY reader: {}", e))?;
-        let writer = master
+        let mut writer = master
             .take_writer()
             .map_err(|e| format!("Failed to take PTY writer: {}", e))?;
 
+        // Type the profile's startup script once, as if the user had. The PTY
+        // buffers it until the shell reads its first line.
+        if let Some(script) = request
+            .profile
+            .as_ref()
+            .and_then(|profile| profile.startup_script.as_deref())
+            .filter(|script| !is_oneshot && !script.trim().is_empty())
+        {
+            let input: String = script
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| format!("{}\r", line))
+                .collect();
+            if let Err(e) = writer
+                .write_all(input.as_bytes())
+                .and_then(|_| writer.flush())
+            {
+                eprintln!(
+                    "Failed to run profile startup script for {}: {}",
+                    process_id, e
+                );
+            }
+        }
+
         // Update metadata with the real PID / running status.
         let pid = child.process_id();
+        if let Some(pid) = pid {
+            supervisor().register(pid, "terminal", &process.command, false);
+        }
         {
             let mut processes = self.processes.lock().await;
             if let Some(proc) = processes.get_mut(&process_id) {
@@ -405,6 +469,7 @@ PROMPT_COMMAND='__portal_osc133_precmd'
                     master,
                     writer,
                     temp_rc_path,
+                    pid,
                 },
             );
         }
@@ -438,6 +503,12 @@ PROMPT_COMMAND='__portal_osc133_precmd'
                         let chunk = String::from_utf8_lossy(&buf[..n]).to_string();
 
                         for event in parser.process_output(&chunk) {
+                            if let ShellIntegrationEvent::CommandCompleted(block) = &event {
+                                quick_open::record_terminal_command(
+                                    &block.command,
+                                    &block.working_directory,
+                                );
+                            }
                             let _ = window_for_reader.emit("shell-integration-event", &event);
                             let v2 = ShellIntegrationEventV2 {
                                 process_id: pid_for_thread.clone(),
@@ -478,34 +549,50 @@ PROMPT_COMMAND='__portal_osc133_precmd'
             return Err("No stdin handle found for process".to_string());
         };
 
-        session
-            .writer
-            .write_all(input.as_bytes())
-            .map_err(|e| format!("Failed to send input: {}", e))?;
-        session
-            .writer
-            .flush()
-            .map_err(|e| format!("Failed to flush stdin: {}", e))?;
-
         // Previously slept 50ms here on every write, blocking a tokio worker per
         // keystroke/paste chunk. PTY writes are ordered; the sleep bought nothing.
-        Ok(())
+        session.write_input(&input)
+    }
+
+    /// Broadcast: write the same input to several sessions. The writes happen
+    /// under one lock, so no other input lands between them, and a session
+    /// that has gone away doesn't stop the rest.
+    pub async fn send_input_to_group(
+        &self,
+        process_ids: Vec<String>,
+        input: String,
+    ) -> Result<BroadcastResult, String> {
+        // NOTE: like `send_input`, never logs `input`.
+        let mut sessions = self.sessions.lock().await;
+        broadcast(process_ids, |process_id| {
+            match sessions.get_mut(process_id) {
+                Some(session) => session.write_input(&input),
+                None => Err("No stdin handle found for process".to_string()),
+            }
+        })
     }
 
     pub async fn kill_process(&self, process_id: String) -> Result<(), String> {
-        // Kill the child, then remove the whole session entry (dropping master +
-        // writer, closing the PTY) and delete its temp rc file.
-        let mut kill_err = None;
-        {
+        // Kill the shell and whatever it's running, then remove the whole
+        // session entry (dropping master + writer, closing the PTY) and delete
+        // its temp rc file.
+        let session = {
             let mut sessions = self.sessions.lock().await;
-            if let Some(session) = sessions.remove(&process_id) {
-                let mut session = session;
-                if let Err(e) = session.child.kill() {
+            sessions.remove(&process_id)
+        };
+        let mut kill_err = None;
+        if let Some(mut session) = session {
+            if let Some(pid) = session.pid {
+                supervisor().kill_tree(pid).await;
+            }
+            if let Err(e) = session.child.kill() {
+                // Already gone with its tree
+                if session.child.try_wait().ok().flatten().is_none() {
                     kill_err = Some(format!("Failed to kill process: {}", e));
                 }
-                remove_temp_rc(session.temp_rc_path.as_deref());
-                // session (child/master/writer) dropped here.
             }
+            session.release();
+            // session (child/master/writer) dropped here.
         }
 
         {
@@ -635,7 +722,7 @@ PROMPT_COMMAND='__portal_osc133_precmd'
                                 eprintln!("Error checking process status: {}", e);
                                 // Tear the session down so we don't leak on error.
                                 if let Some(s) = sessions_guard.remove(&process_id) {
-                                    remove_temp_rc(s.temp_rc_path.as_deref());
+                                    s.release();
                                 }
                                 break;
                             }
@@ -654,7 +741,7 @@ PROMPT_COMMAND='__portal_osc133_precmd'
                 {
                     let mut sessions_guard = sessions.lock().await;
                     if let Some(session) = sessions_guard.remove(&process_id) {
-                        remove_temp_rc(session.temp_rc_path.as_deref());
+                        session.release();
                     }
                 }
 
@@ -740,3 +827,56 @@ fn parse_command(input: &str) -> (String, Vec<String>) {
 
     (command, args)
 }
+
+/// Write to each session once with `write`, collecting which ones took it
+fn broadcast(
+    process_ids: Vec<String>,
+    mut write: impl FnMut(&str) -> Result<(), String>,
+) -> Result<BroadcastResult, String> {
+    if process_ids.is_empty() {
+        return Err("No terminal sessions selected for broadcast".to_string());
+    }
+    let mut result = BroadcastResult::default();
+    for process_id in process_ids {
+        if result.delivered.contains(&process_id) || result.failed.contains_key(&process_id) {
+            continue;
+        }
+        match write(&process_id) {
+            Ok(()) => result.delivered.push(process_id),
+            Err(e) => {
+                result.failed.insert(process_id, e);
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn broadcasts_once_per_session_and_reports_failures() {
+        let mut writes = Vec::new();
+        let result = broadcast(
+            ["a", "b", "a", "gone", "b", "gone"]
+                .map(String::from)
+                .to_vec(),
+            |id| {
+                if id == "gone" {
+                    return Err("No stdin handle found for process".to_string());
+                }
+                writes.push(id.to_string());
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(writes, vec!["a", "b"]);
+        assert_eq!(result.delivered, vec!["a", "b"]);
+        assert_eq!(result.failed.len(), 1);
+        assert!(result.failed.contains_key("gone"));
+
+        assert!(broadcast(Vec::new(), |_| Ok(())).is_err());
+    }
+}
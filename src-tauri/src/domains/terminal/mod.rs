@@ -1,5 +1,6 @@
 pub mod commands;
 pub mod manager;
+pub mod profiles;
 pub mod shell_integration;
 pub mod types;
 
@@ -0,0 +1,288 @@
+//! Shell profiles: which shell a terminal starts, with what arguments,
+//! environment and startup script.
+//!
+//! Each OS gets built-in profiles for its usual shells (PowerShell, pwsh and
+//! cmd on Windows; zsh, bash and fish elsewhere), listed when the shell is
+//! installed. They can't be changed; user profiles are stored per OS and can
+//! be created, updated and deleted.
+
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::entities::terminal_profile::{ActiveModel, Column, Entity, Model};
+
+/// Prefix of built-in profile ids
+pub const BUILTIN_PREFIX: &str = "builtin:";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellProfile {
+    pub id: String,
+    pub name: String,
+    pub os: String,
+    /// Shell binary, as a name on PATH or a full path
+    pub shell: String,
+    pub args: Vec<String>,
+    /// Typed into the shell once it starts, one command per line
+    pub startup_script: Option<String>,
+    pub env: HashMap<String, String>,
+    pub icon: Option<String>,
+    pub builtin: bool,
+}
+
+/// Fields of a user profile, when creating or updating one
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShellProfileInput {
+    pub name: String,
+    pub shell: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub startup_script: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+impl ShellProfileInput {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Profile name is required".to_string());
+        }
+        if self.shell.trim().is_empty() {
+            return Err("Profile shell is required".to_string());
+        }
+        if let Some(key) = self
+            .env
+            .keys()
+            .find(|key| key.is_empty() || key.contains('=') || key.contains('\0'))
+        {
+            return Err(format!("Invalid environment variable name: {:?}", key));
+        }
+        Ok(())
+    }
+}
+
+impl From<Model> for ShellProfile {
+    fn from(model: Model) -> Self {
+        Self {
+            args: serde_json::from_str(&model.args_json).unwrap_or_default(),
+            env: serde_json::from_str(&model.env_json).unwrap_or_default(),
+            id: model.id,
+            name: model.name,
+            os: model.os,
+            shell: model.shell,
+            startup_script: model.startup_script,
+            icon: model.icon,
+            builtin: false,
+        }
+    }
+}
+
+/// Built-in profiles for `os`, in the order they're offered
+pub fn default_profiles(os: &str) -> Vec<ShellProfile> {
+    let no_exit = ["-NoLogo", "-NoProfile", "-NoExit"].as_slice();
+    let shells: &[(&str, &str, &str, &[&str])] = if os == "windows" {
+        &[
+            (
+                "powershell",
+                "Windows PowerShell",
+                "powershell.exe",
+                no_exit,
+            ),
+            ("pwsh", "PowerShell", "pwsh.exe", no_exit),
+            ("cmd", "Command Prompt", "cmd.exe", &["/k"]),
+        ]
+    } else {
+        &[
+            ("zsh", "Zsh", "zsh", &[]),
+            ("bash", "Bash", "bash", &[]),
+            ("fish", "Fish", "fish", &[]),
+        ]
+    };
+    shells
+        .iter()
+        .map(|(key, name, shell, args)| ShellProfile {
+            id: format!("{}{}", BUILTIN_PREFIX, key),
+            name: name.to_string(),
+            os: os.to_string(),
+            shell: shell.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            startup_script: None,
+            env: HashMap::new(),
+            icon: Some(key.to_string()),
+            builtin: true,
+        })
+        .collect()
+}
+
+/// Built-in profiles whose shell is in `available`, falling back to the
+/// first one so there's always a profile to start
+fn installed_defaults(os: &str, available: &[String]) -> Vec<ShellProfile> {
+    let defaults = default_profiles(os);
+    let installed: Vec<ShellProfile> = defaults
+        .iter()
+        .filter(|profile| {
+            available
+                .iter()
+                .any(|shell| shell.eq_ignore_ascii_case(&profile.shell))
+        })
+        .cloned()
+        .collect();
+    if installed.is_empty() {
+        defaults.into_iter().take(1).collect()
+    } else {
+        installed
+    }
+}
+
+/// Profiles for this OS: built-in ones for the installed shells, then the
+/// user's, by name
+pub async fn list_profiles(
+    db: &DatabaseConnection,
+    available: &[String],
+) -> Result<Vec<ShellProfile>, String> {
+    let os = std::env::consts::OS;
+    let mut stored: Vec<ShellProfile> = Entity::find()
+        .filter(Column::Os.eq(os))
+        .all(db)
+        .await
+        .map_err(|e| format!("Failed to list shell profiles: {}", e))?
+        .into_iter()
+        .map(ShellProfile::from)
+        .collect();
+    stored.sort_by_key(|profile| profile.name.to_lowercase());
+
+    let mut profiles = installed_defaults(os, available);
+    profiles.extend(stored);
+    Ok(profiles)
+}
+
+pub async fn get_profile(db: &DatabaseConnection, id: &str) -> Result<ShellProfile, String> {
+    if id.starts_with(BUILTIN_PREFIX) {
+        return default_profiles(std::env::consts::OS)
+            .into_iter()
+            .find(|profile| profile.id == id)
+            .ok_or_else(|| format!("Shell profile {} not found", id));
+    }
+    Entity::find_by_id(id.to_string())
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to get shell profile: {}", e))?
+        .map(ShellProfile::from)
+        .ok_or_else(|| format!("Shell profile {} not found", id))
+}
+
+pub async fn create_profile(
+    db: &DatabaseConnection,
+    input: ShellProfileInput,
+) -> Result<ShellProfile, String> {
+    input.validate()?;
+    let now = Utc::now();
+    ActiveModel {
+        id: Set(Uuid::new_v4().to_string()),
+        name: Set(input.name.trim().to_string()),
+        os: Set(std::env::consts::OS.to_string()),
+        shell: Set(input.shell.trim().to_string()),
+        args_json: Set(serde_json::to_string(&input.args).map_err(|e| e.to_string())?),
+        startup_script: Set(input.startup_script),
+        env_json: Set(serde_json::to_string(&input.env).map_err(|e| e.to_string())?),
+        icon: Set(input.icon),
+        created_at: Set(now.into()),
+        updated_at: Set(now.into()),
+    }
+    .insert(db)
+    .await
+    .map(ShellProfile::from)
+    .map_err(|e| format!("Failed to create shell profile: {}", e))
+}
+
+pub async fn update_profile(
+    db: &DatabaseConnection,
+    id: &str,
+    input: ShellProfileInput,
+) -> Result<ShellProfile, String> {
+    if id.starts_with(BUILTIN_PREFIX) {
+        return Err("Built-in shell profiles can't be changed".to_string());
+    }
+    input.validate()?;
+    let mut profile: ActiveModel = Entity::find_by_id(id.to_string())
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to get shell profile: {}", e))?
+        .ok_or_else(|| format!("Shell profile {} not found", id))?
+        .into();
+    profile.name = Set(input.name.trim().to_string());
+    profile.shell = Set(input.shell.trim().to_string());
+    profile.args_json = Set(serde_json::to_string(&input.args).map_err(|e| e.to_string())?);
+    profile.startup_script = Set(input.startup_script);
+    profile.env_json = Set(serde_json::to_string(&input.env).map_err(|e| e.to_string())?);
+    profile.icon = Set(input.icon);
+    profile.updated_at = Set(Utc::now().into());
+    profile
+        .update(db)
+        .await
+        .map(ShellProfile::from)
+        .map_err(|e| format!("Failed to update shell profile: {}", e))
+}
+
+pub async fn delete_profile(db: &DatabaseConnection, id: &str) -> Result<(), String> {
+    if id.starts_with(BUILTIN_PREFIX) {
+        return Err("Built-in shell profiles can't be deleted".to_string());
+    }
+    let result = Entity::delete_by_id(id.to_string())
+        .exec(db)
+        .await
+        .map_err(|e| format!("Failed to delete shell profile: {}", e))?;
+    if result.rows_affected == 0 {
+        return Err(format!("Shell profile {} not found", id));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_follow_the_os() {
+        let windows: Vec<String> = default_profiles("windows")
+            .into_iter()
+            .map(|profile| profile.shell)
+            .collect();
+        assert_eq!(windows, vec!["powershell.exe", "pwsh.exe", "cmd.exe"]);
+
+        let macos: Vec<String> = default_profiles("macos")
+            .into_iter()
+            .map(|profile| profile.shell)
+            .collect();
+        assert_eq!(macos, vec!["zsh", "bash", "fish"]);
+    }
+
+    #[test]
+    fn only_installed_defaults_are_listed() {
+        let available = vec!["bash".to_string(), "sh".to_string()];
+        let installed = installed_defaults("linux", &available);
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].id, "builtin:bash");
+
+        let fallback = installed_defaults("windows", &[]);
+        assert_eq!(fallback[0].id, "builtin:powershell");
+    }
+
+    #[test]
+    fn rejects_invalid_env_names() {
+        let input = ShellProfileInput {
+            name: "Dev".to_string(),
+            shell: "zsh".to_string(),
+            args: Vec::new(),
+            startup_script: None,
+            env: HashMap::from([("A=B".to_string(), "1".to_string())]),
+            icon: None,
+        };
+        assert!(input.validate().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use super::profiles::ShellProfile;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -47,6 +48,27 @@ pub struct CreateProcessRequest {
     /// (script runners, install progress panes).
     #[serde(default)]
     pub command: Option<String>,
+    /// Inject the env vars mapped to credentials for this project
+    #[serde(default)]
+    pub project_id: Option<i32>,
+    /// Resolved secret env vars. Passed to the shell but, unlike `environment`,
+    /// never reported back on the process.
+    #[serde(skip)]
+    pub injected_environment: HashMap<String, String>,
+    /// Start the shell from this profile instead of `shell`
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    /// Profile resolved from `profile_id`
+    #[serde(skip)]
+    pub profile: Option<ShellProfile>,
+}
+
+/// Outcome of broadcasting input to a group of sessions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BroadcastResult {
+    pub delivered: Vec<String>,
+    /// Sessions the input couldn't be written to, with the error
+    pub failed: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
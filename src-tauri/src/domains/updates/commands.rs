@@ -1,6 +1,33 @@
-use super::services::update_service::UpdateService;
+use tauri::AppHandle;
+
+use super::services::update_service::{AppUpdateCheck, UpdateChannel, UpdateService};
 
 #[tauri::command]
 pub async fn get_app_version_command() -> Result<String, String> {
     Ok(UpdateService::get_current_version())
 }
+
+#[tauri::command]
+pub async fn set_update_channel(channel: String) -> Result<UpdateChannel, String> {
+    UpdateService::set_channel(&channel)
+}
+
+/// Newer version on the selected channel, with its parsed changelog
+#[tauri::command]
+pub async fn check_for_app_updates(app: AppHandle) -> Result<AppUpdateCheck, String> {
+    UpdateService::check(&app).await
+}
+
+/// Download the update found by the last check; progress is emitted as
+/// `app-update-progress`. Returns the downloaded version.
+#[tauri::command]
+pub async fn download_app_update(app: AppHandle) -> Result<String, String> {
+    UpdateService::download(&app).await
+}
+
+/// Install the downloaded update: now, restarting into it, or when the app
+/// exits
+#[tauri::command]
+pub async fn install_app_update(app: AppHandle, restart: bool) -> Result<(), String> {
+    UpdateService::install(&app, restart)
+}
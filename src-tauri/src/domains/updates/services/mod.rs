@@ -1 +1,2 @@
+pub mod update_poller;
 pub mod update_service;
@@ -0,0 +1,160 @@
+//! Background update checks on the schedule in settings.
+//!
+//! The poller wakes every few minutes and checks once `check_interval` hours
+//! have passed since the last check. Offline, the check waits for the next
+//! wake-up; on a metered connection the update is announced but only
+//! downloaded if the settings allow it. Each new version is announced once
+//! with `UPDATE_AVAILABLE_EVENT`.
+
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::process::Command;
+
+use super::update_service::UpdateService;
+use crate::domains::settings::services::settings_service::SettingsService;
+use crate::process_ext::NoWindowExt;
+use crate::{log_info, log_warn};
+
+pub const UPDATE_AVAILABLE_EVENT: &str = "app-update-available";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Host the update feeds are served from
+const PROBE_HOST: (&str, u16) = ("github.com", 443);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Connection {
+    Offline,
+    Metered,
+    Unmetered,
+}
+
+/// Check for updates whenever the configured interval has passed. Runs for
+/// the life of the app.
+pub async fn run(app: AppHandle) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    let mut last_check: Option<Instant> = None;
+    let mut announced: Option<String> = None;
+    loop {
+        interval.tick().await;
+        let Ok(settings) = SettingsService::new().load_settings() else {
+            continue;
+        };
+        let updates = settings.app.updates;
+        if !updates.auto_check {
+            continue;
+        }
+        let due = Duration::from_secs(u64::from(updates.check_interval.max(1)) * 60 * 60);
+        if last_check.is_some_and(|last| last.elapsed() < due) {
+            continue;
+        }
+        let connection = connection().await;
+        if connection == Connection::Offline {
+            continue;
+        }
+        last_check = Some(Instant::now());
+
+        let mut check = match UpdateService::check(&app).await {
+            Ok(check) => check,
+            Err(e) => {
+                log_warn!("Updates", "Background update check failed: {}", e);
+                continue;
+            }
+        };
+        let Some(version) = check.version.clone() else {
+            continue;
+        };
+        let may_download = connection == Connection::Unmetered || updates.download_on_metered;
+        if updates.auto_download && !check.downloaded && may_download {
+            match UpdateService::download(&app).await {
+                Ok(_) => check.downloaded = true,
+                Err(e) => log_warn!("Updates", "Failed to download update {}: {}", version, e),
+            }
+        }
+        if announced.as_deref() == Some(version.as_str()) {
+            continue;
+        }
+        log_info!("Updates", "Update {} is available", version);
+        let _ = app.emit(UPDATE_AVAILABLE_EVENT, &check);
+        announced = Some(version);
+    }
+}
+
+/// Whether the update host is reachable, and over what kind of connection
+async fn connection() -> Connection {
+    let reachable = tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(PROBE_HOST))
+        .await
+        .is_ok_and(|result| result.is_ok());
+    if !reachable {
+        Connection::Offline
+    } else if is_metered().await {
+        Connection::Metered
+    } else {
+        Connection::Unmetered
+    }
+}
+
+/// Command reporting whether the active connection is metered
+#[cfg(target_os = "linux")]
+const METERED_PROBE: Option<(&str, &[&str])> =
+    Some(("nmcli", &["-t", "-f", "GENERAL.METERED", "device", "show"]));
+#[cfg(windows)]
+const METERED_PROBE: Option<(&str, &[&str])> = Some((
+    "powershell",
+    &[
+        "-NoProfile",
+        "-Command",
+        "[Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime]::GetInternetConnectionProfile().GetConnectionCost().NetworkCostType",
+    ],
+));
+#[cfg(not(any(target_os = "linux", windows)))]
+const METERED_PROBE: Option<(&str, &[&str])> = None;
+
+/// Whether the OS marks the active connection as metered. Platforms without
+/// a way to tell count as unmetered.
+async fn is_metered() -> bool {
+    let Some((program, args)) = METERED_PROBE else {
+        return false;
+    };
+    let output = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        Command::new(program)
+            .no_window()
+            .args(args)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+    match output {
+        Ok(Ok(output)) if output.status.success() => {
+            parse_metered(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => false,
+    }
+}
+
+/// Read `nmcli`'s `GENERAL.METERED:yes` lines, or Windows' network cost
+/// type, where anything but `Unrestricted` is billed by use
+fn parse_metered(output: &str) -> bool {
+    output.lines().map(str::trim).any(|line| {
+        let value = line.strip_prefix("GENERAL.METERED:").unwrap_or(line);
+        value.starts_with("yes") || value == "Fixed" || value == "Variable"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metered_connections_are_recognized() {
+        assert!(parse_metered(
+            "GENERAL.METERED:no (guessed)\nGENERAL.METERED:yes (guessed)\n"
+        ));
+        assert!(!parse_metered(
+            "GENERAL.METERED:no\nGENERAL.METERED:unknown\n"
+        ));
+        assert!(parse_metered("Variable\r\n"));
+        assert!(!parse_metered("Unrestricted\r\n"));
+    }
+}
@@ -1,6 +1,102 @@
-/// Update service - provides version information
-/// Note: Actual update checking and installation is handled via the frontend
-/// using Tauri's JavaScript updater API (@tauri-apps/api/updater)
+//! App updates through `tauri_plugin_updater`, on the channel picked in
+//! settings.
+//!
+//! Updates are checked and downloaded here rather than in the frontend so the
+//! channel's feed can be chosen at runtime. A downloaded update waits in
+//! `UpdateState` until it is installed, either right away with a restart or
+//! when the app next exits.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::domains::settings::services::settings_service::SettingsService;
+use crate::log_warn;
+
+pub const UPDATE_PROGRESS_EVENT: &str = "app-update-progress";
+
+/// Release feeds by channel. Beta builds are published as prereleases, which
+/// GitHub's `latest` skips, so their manifest hangs off the rolling `beta`
+/// release instead.
+const STABLE_FEED: &str =
+    "https://github.com/tanvoid0/portal-desktop/releases/latest/download/latest.json";
+const BETA_FEED: &str =
+    "https://github.com/tanvoid0/portal-desktop/releases/download/beta/latest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn parse(channel: &str) -> Result<Self, String> {
+        match channel {
+            "stable" => Ok(Self::Stable),
+            "beta" => Ok(Self::Beta),
+            other => Err(format!("Unknown update channel: {}", other)),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+
+    fn feed(self) -> &'static str {
+        match self {
+            Self::Stable => STABLE_FEED,
+            Self::Beta => BETA_FEED,
+        }
+    }
+}
+
+/// A heading of the release notes with the entries under it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangelogSection {
+    /// Empty for entries before the first heading
+    pub title: String,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppUpdateCheck {
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// `None` when the app is up to date
+    pub version: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Release notes as published
+    pub notes: Option<String>,
+    pub changelog: Vec<ChangelogSection>,
+    /// The update is downloaded and waiting to be installed
+    pub downloaded: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateProgress {
+    version: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+struct PendingUpdate {
+    update: Update,
+    /// Downloaded package, once it has been fetched
+    bytes: Option<Vec<u8>>,
+}
+
+/// The update found by the last check, and its package once downloaded
+#[derive(Default)]
+pub struct UpdateState {
+    pending: Mutex<Option<PendingUpdate>>,
+}
+
 pub struct UpdateService;
 
 impl UpdateService {
@@ -8,4 +104,247 @@ impl UpdateService {
     pub fn get_current_version() -> String {
         env!("CARGO_PKG_VERSION").to_string()
     }
+
+    /// Channel picked in settings; stable if settings can't be read
+    pub fn channel() -> UpdateChannel {
+        SettingsService::new()
+            .load_settings()
+            .ok()
+            .and_then(|settings| UpdateChannel::parse(&settings.app.updates.channel).ok())
+            .unwrap_or(UpdateChannel::Stable)
+    }
+
+    pub fn set_channel(channel: &str) -> Result<UpdateChannel, String> {
+        let channel = UpdateChannel::parse(channel)?;
+        let service = SettingsService::new();
+        let mut settings = service.load_settings()?;
+        settings.app.updates.channel = channel.as_str().to_string();
+        service.save_settings(&settings)?;
+        Ok(channel)
+    }
+
+    /// Ask the channel's feed for a newer version. A previously found update
+    /// is replaced, unless it is the same version and already downloaded.
+    pub async fn check(app: &AppHandle) -> Result<AppUpdateCheck, String> {
+        let channel = Self::channel();
+        let feed = Url::parse(channel.feed()).map_err(|e| e.to_string())?;
+        let update = app
+            .updater_builder()
+            .endpoints(vec![feed])
+            .and_then(|builder| builder.build())
+            .map_err(|e| format!("Failed to set up the updater: {}", e))?
+            .check()
+            .await
+            .map_err(|e| format!("Failed to check for updates: {}", e))?;
+
+        let state = app.state::<UpdateState>();
+        let mut pending = state.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let mut result = AppUpdateCheck {
+            current_version: Self::get_current_version(),
+            channel,
+            version: None,
+            published_at: None,
+            notes: None,
+            changelog: Vec::new(),
+            downloaded: false,
+        };
+        let Some(update) = update else {
+            *pending = None;
+            return Ok(result);
+        };
+
+        result.version = Some(update.version.clone());
+        result.published_at = update
+            .date
+            .and_then(|date| DateTime::from_timestamp(date.unix_timestamp(), 0));
+        result.changelog = update
+            .body
+            .as_deref()
+            .map(parse_changelog)
+            .unwrap_or_default();
+        result.notes = update.body.clone();
+        let already_downloaded = pending
+            .as_ref()
+            .is_some_and(|p| p.update.version == update.version && p.bytes.is_some());
+        if !already_downloaded {
+            *pending = Some(PendingUpdate {
+                update,
+                bytes: None,
+            });
+        }
+        result.downloaded = already_downloaded;
+        Ok(result)
+    }
+
+    /// Download the update found by the last check, emitting
+    /// `UPDATE_PROGRESS_EVENT` as it arrives
+    pub async fn download(app: &AppHandle) -> Result<String, String> {
+        let update = {
+            let state = app.state::<UpdateState>();
+            let pending = state.pending.lock().unwrap_or_else(|e| e.into_inner());
+            match pending.as_ref() {
+                Some(pending) if pending.bytes.is_some() => {
+                    return Ok(pending.update.version.clone())
+                }
+                Some(pending) => pending.update.clone(),
+                None => return Err("No update to download; check for updates first".to_string()),
+            }
+        };
+
+        let progress_app = app.clone();
+        let version = update.version.clone();
+        let mut downloaded = 0u64;
+        let bytes = update
+            .download(
+                move |chunk, total| {
+                    downloaded += chunk as u64;
+                    let _ = progress_app.emit(
+                        UPDATE_PROGRESS_EVENT,
+                        UpdateProgress {
+                            version: version.clone(),
+                            downloaded,
+                            total,
+                        },
+                    );
+                },
+                || {},
+            )
+            .await
+            .map_err(|e| format!("Failed to download update: {}", e))?;
+
+        let state = app.state::<UpdateState>();
+        let mut pending = state.pending.lock().unwrap_or_else(|e| e.into_inner());
+        // A check during the download may have found a newer version
+        match pending.as_mut() {
+            Some(pending) if pending.update.version == update.version => {
+                pending.bytes = Some(bytes);
+                Ok(update.version)
+            }
+            _ => Err("A newer update was found while downloading; download again".to_string()),
+        }
+    }
+
+    /// Install the downloaded update. With `restart` the app restarts into
+    /// it; otherwise it's installed when the app exits.
+    pub fn install(app: &AppHandle, restart: bool) -> Result<(), String> {
+        if !restart {
+            let state = app.state::<UpdateState>();
+            let pending = state.pending.lock().unwrap_or_else(|e| e.into_inner());
+            return match pending.as_ref() {
+                Some(pending) if pending.bytes.is_some() => Ok(()),
+                _ => Err("No downloaded update to install".to_string()),
+            };
+        }
+        if !Self::install_pending(app)? {
+            return Err("No downloaded update to install".to_string());
+        }
+        app.restart();
+    }
+
+    /// Install a downloaded update, if there is one. Called on exit so a
+    /// downloaded update is in place for the next launch.
+    pub fn install_pending(app: &AppHandle) -> Result<bool, String> {
+        let Some(state) = app.try_state::<UpdateState>() else {
+            return Ok(false);
+        };
+        let pending = state
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        let Some(PendingUpdate {
+            update,
+            bytes: Some(bytes),
+        }) = pending
+        else {
+            return Ok(false);
+        };
+        update.install(bytes).map_err(|e| {
+            log_warn!("Updates", "Failed to install update: {}", e);
+            format!("Failed to install update: {}", e)
+        })?;
+        Ok(true)
+    }
+}
+
+/// Split markdown release notes into sections by heading, with one item per
+/// bullet. Lines that aren't bullets become items of their own, and
+/// continuation lines join the item above them.
+pub fn parse_changelog(notes: &str) -> Vec<ChangelogSection> {
+    let mut sections: Vec<ChangelogSection> = Vec::new();
+    let mut continuing = false;
+    for line in notes.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continuing = false;
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            sections.push(ChangelogSection {
+                title: trimmed.trim_start_matches('#').trim().to_string(),
+                items: Vec::new(),
+            });
+            continuing = false;
+            continue;
+        }
+        if sections.is_empty() {
+            sections.push(ChangelogSection {
+                title: String::new(),
+                items: Vec::new(),
+            });
+        }
+        let items = &mut sections.last_mut().expect("a section was just added").items;
+        let bullet = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker));
+        let indented = continuing && line.starts_with(char::is_whitespace);
+        if let Some(item) = bullet {
+            items.push(item.trim().to_string());
+        } else if let Some(last) = items.last_mut().filter(|_| indented) {
+            // An indented line continues the item above it
+            last.push(' ');
+            last.push_str(trimmed);
+        } else {
+            items.push(trimmed.to_string());
+        }
+        continuing = true;
+    }
+    sections.retain(|section| !section.items.is_empty() || !section.title.is_empty());
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changelog_is_split_into_sections() {
+        let notes = "Portal 1.2.0\n\n## Features\n- Global shortcuts\n- Log export\n  as a zip\n\n### Fixes\n* Terminal resize\n";
+        assert_eq!(
+            parse_changelog(notes),
+            vec![
+                ChangelogSection {
+                    title: String::new(),
+                    items: vec!["Portal 1.2.0".to_string()],
+                },
+                ChangelogSection {
+                    title: "Features".to_string(),
+                    items: vec![
+                        "Global shortcuts".to_string(),
+                        "Log export as a zip".to_string(),
+                    ],
+                },
+                ChangelogSection {
+                    title: "Fixes".to_string(),
+                    items: vec!["Terminal resize".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn only_stable_and_beta_channels_exist() {
+        assert_eq!(UpdateChannel::parse("beta"), Ok(UpdateChannel::Beta));
+        assert!(UpdateChannel::parse("alpha").is_err());
+    }
 }
@@ -0,0 +1,33 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "approval_requests")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: String,
+    pub action_type: String,
+    pub context: String,
+    #[sea_orm(column_type = "Text")]
+    pub description: String,
+    pub safety_level: String,
+    #[sea_orm(column_type = "Text")]
+    pub reason: String,
+    #[sea_orm(column_type = "Text")]
+    pub arguments_json: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub preview_json: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub run_json: String, // Paused chat run, resumed on approval or rejection
+    pub status: String, // pending, approved, rejected, expired
+    #[sea_orm(column_type = "Text", nullable)]
+    pub feedback: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub expires_at: DateTimeWithTimeZone,
+    pub resolved_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
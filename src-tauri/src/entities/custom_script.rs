@@ -18,6 +18,16 @@ pub struct Model {
     pub updated_at: Option<DateTimeWithTimeZone>,
     pub last_run_at: Option<DateTimeWithTimeZone>,
     pub run_count: i32,
+    #[sea_orm(column_type = "Text")]
+    pub event_triggers_json: String, // JSON array of EventTrigger
+    pub library_id: Option<i32>, // Script library it was synced from; None when local
+    pub library_key: Option<String>, // Its id in the library's manifest
+    #[sea_orm(column_type = "Text", nullable)]
+    pub upstream_json: Option<String>, // Manifest entry as last synced
+    #[sea_orm(column_type = "Text", nullable)]
+    pub pending_upstream_json: Option<String>, // Upstream change held back by a conflict
+    pub runtime: String, // shell, bash, powershell, python or node
+    pub runtime_version: Option<String>, // Python/Node version prefix, e.g. "3.12"
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
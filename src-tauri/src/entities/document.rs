@@ -12,6 +12,7 @@ pub struct Model {
     pub content_draft: Option<String>,
     pub is_draft: bool,
     pub tags: Option<String>, // JSON array of strings
+    pub project_id: Option<i32>,
     pub created_at: Option<DateTimeWithTimeZone>,
     pub updated_at: Option<DateTimeWithTimeZone>,
     pub last_edited_at: Option<DateTimeWithTimeZone>,
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "document_chunks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub document_id: i32,
+    pub chunk_index: i32,
+    pub heading: Option<String>,
+    pub content: String,
+    pub content_hash: String,
+    pub embedding: Option<Vec<u8>>, // little-endian f32s
+    pub embedding_model: Option<String>,
+    pub created_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::document::Entity",
+        from = "Column::DocumentId",
+        to = "super::document::Column::Id"
+    )]
+    Document,
+}
+
+impl Related<super::document::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Document.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -16,6 +16,9 @@ pub struct Model {
     pub success_rate: f64,
     #[sea_orm(default_value = false)]
     pub is_important: bool, // User-marked important patterns are never auto-deleted
+    #[sea_orm(default_value = 1.0)]
+    pub weight: f64, // Multiplier from suggestion feedback
+    pub snoozed_until: Option<DateTimeWithTimeZone>, // Not suggested before this
     pub created_at: Option<DateTimeWithTimeZone>,
 }
 
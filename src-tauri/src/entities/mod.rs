@@ -1,23 +1,34 @@
+pub mod approval_request;
 pub mod block;
 pub mod custom_script;
 pub mod deployment;
 pub mod device_approval;
 pub mod document;
+pub mod document_chunk;
+pub mod document_link;
+pub mod document_revision;
+pub mod document_sync_folder;
+pub mod document_sync_state;
+pub mod document_template;
 pub mod framework;
 pub mod framework_ide_mapping;
 pub mod github_connection;
 pub mod github_project_link;
 pub mod ide;
+pub mod keybinding;
 pub mod language;
 pub mod learned_pattern;
 pub mod learning_event;
 pub mod package_manager;
 pub mod pipeline;
 pub mod pipeline_execution;
+pub mod port_forward;
 pub mod project;
 pub mod project_framework;
+pub mod project_group;
 pub mod project_language;
 pub mod project_package_manager;
+pub mod project_setting;
 pub mod saved_view;
 pub mod script_execution;
 pub mod task;
@@ -26,5 +37,6 @@ pub mod task_comment;
 pub mod task_template;
 pub mod terminal_command_history;
 pub mod terminal_note;
+pub mod terminal_profile;
 pub mod terminal_session;
 pub mod user_preference;
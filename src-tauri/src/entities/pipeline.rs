@@ -20,6 +20,11 @@ pub struct Model {
     pub enabled: bool,
     pub preset_key: Option<String>,
     pub category: Option<String>,
+    /// JSON array of NotificationHook; missing from bundles exported before
+    /// pipelines had hooks
+    #[sea_orm(column_type = "Text")]
+    #[serde(default)]
+    pub notifications_json: String,
     pub created_at: Option<DateTimeWithTimeZone>,
     pub updated_at: Option<DateTimeWithTimeZone>,
 }
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "port_forwards")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: String,
+    pub cluster: String,
+    pub namespace: String,
+    pub target_kind: String,
+    pub target_name: String,
+    pub local_port: i32,
+    pub remote_port: i32,
+    pub auto_restore: bool,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -26,6 +26,8 @@ pub struct Model {
     pub git_commit: Option<String>,
     pub has_uncommitted_changes: bool,
     pub last_commit: Option<DateTimeWithTimeZone>,
+    pub group_id: Option<i32>,
+    pub position: i32,
     pub created_at: Option<DateTimeWithTimeZone>,
     pub updated_at: Option<DateTimeWithTimeZone>,
 }
@@ -40,6 +42,13 @@ pub enum Relation {
 
     #[sea_orm(has_many = "super::project_package_manager::Entity")]
     ProjectPackageManagers,
+
+    #[sea_orm(
+        belongs_to = "super::project_group::Entity",
+        from = "Column::GroupId",
+        to = "super::project_group::Column::Id"
+    )]
+    ProjectGroup,
 }
 
 impl Related<super::framework::Entity> for Entity {
@@ -76,4 +85,10 @@ impl Related<super::package_manager::Entity> for Entity {
     }
 }
 
+impl Related<super::project_group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectGroup.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -11,6 +11,7 @@ pub struct Model {
     pub url: String,
     pub type_: String,
     pub size: i64,
+    pub content_hash: Option<String>, // SHA-256 of the managed copy, if any
     pub created_at: Option<DateTimeWithTimeZone>,
 }
 
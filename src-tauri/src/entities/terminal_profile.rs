@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "terminal_profiles")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: String,
+    pub name: String,
+    pub os: String,
+    pub shell: String,
+    #[sea_orm(column_type = "Text")]
+    pub args_json: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub startup_script: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub env_json: String,
+    pub icon: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -24,7 +24,7 @@ use domains::sdk::services::navigation_service::NavigationService;
 use domains::settings::services::settings_service::SettingsService;
 use domains::terminal::manager::TerminalManager;
 use std::sync::Arc;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -36,6 +36,12 @@ fn greet(name: &str) -> String {
 pub fn run() {
     // Initialize logger
     utils::logger::init_logger(None);
+    // Loaded before taking the logger, which loading may log through
+    let logging = SettingsService::new()
+        .load_settings()
+        .map(|settings| settings.app.logging)
+        .unwrap_or_default();
+    utils::logger::logger().apply_settings(&logging);
     log_info!("Tauri", "Application starting...");
 
     // Initialize domain managers
@@ -54,12 +60,26 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(updater_builder.build())
+        .plugin(domains::settings::services::global_shortcuts::plugin())
         .setup(|app| {
             // Set app handle for logger to emit events to frontend
             utils::logger::set_app_handle(app.handle().clone());
 
+            // Persist logs to rotating files from here on
+            match app.path().app_log_dir() {
+                Ok(log_dir) => {
+                    let attached = utils::logger::logger().attach_log_dir(&log_dir);
+                    if let Err(e) = attached {
+                        log_warn!("Tauri", "Failed to open log directory: {}", e);
+                    }
+                }
+                Err(e) => log_warn!("Tauri", "Failed to resolve log directory: {}", e),
+            }
+
             log_info!("Tauri", "Starting setup function...");
 
+            app.manage(domains::updates::services::update_service::UpdateState::default());
+
             log_info!("Tauri", "Initializing database manager...");
 
             let app_data_dir = app
@@ -67,9 +87,31 @@ pub fn run() {
                 .app_data_dir()
                 .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
 
-            let db_manager =
-                tauri::async_runtime::block_on(async { DatabaseManager::new(app_data_dir).await })
-                    .map_err(|e| format!("Failed to initialize database manager: {}", e))?;
+            // Apply a pending encryption switch and find the key. Without one an
+            // encrypted database stays locked: nothing else starts, and the
+            // frontend asks for the passphrase and restarts the app.
+            let db_key = match database::encryption::prepare(&app_data_dir) {
+                Ok(key) => key,
+                Err(database::encryption::PrepareError::Locked) => {
+                    log_warn!(
+                        "Tauri",
+                        "Database is encrypted and locked; waiting for unlock"
+                    );
+                    app.manage(database::encryption::LockedDatabase {
+                        data_dir: app_data_dir,
+                    });
+                    return Ok(());
+                }
+                Err(e) => return Err(format!("Failed to prepare database: {}", e).into()),
+            };
+
+            // Open the workspace that was active when the app last closed
+            let workspaces = database::workspaces::Workspaces::new(app_data_dir.clone());
+            let workspace_dir = workspaces.dir(&workspaces.active().id);
+            let db_manager = tauri::async_runtime::block_on(async {
+                DatabaseManager::new(app_data_dir, workspace_dir, db_key).await
+            })
+            .map_err(|e| format!("Failed to initialize database manager: {}", e))?;
 
             log_info!("Tauri", "Database manager initialized, managing state...");
 
@@ -85,14 +127,56 @@ pub fn run() {
                 None, // No API key for local n8n
             );
             app.manage(std::sync::Arc::new(automation_service));
+            domains::automation::services::event_bus::start_dispatcher(app.handle().clone());
+
+            app.manage(std::sync::Arc::new(
+                domains::notifications::services::NotificationService::new(db_manager_arc.clone()),
+            ));
 
             // Initialize settings service
             let settings_service = SettingsService::new();
+
+            // Apply credential vault lock settings and enforce auto-lock
+            let security = settings_service
+                .load_settings()
+                .map(|settings| settings.app.security)
+                .unwrap_or_default();
+            let vault = domains::credentials::services::vault_session();
+            vault.configure(
+                security.vault_auto_lock_minutes,
+                security.lock_vault_on_startup,
+            );
+            let vault_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+                loop {
+                    interval.tick().await;
+                    if vault.enforce_auto_lock() {
+                        log_info!("Credentials", "Vault locked after inactivity");
+                        let _ = vault_app.emit(
+                            domains::credentials::services::vault_session::VAULT_LOCKED_EVENT,
+                            vault.status(),
+                        );
+                    }
+                }
+            });
             app.manage(std::sync::Arc::new(settings_service));
 
             // Initialize AI services
             let ai_settings_service = AISettingsService::new();
             let ai_service = AIService::new();
+            ai_service.attach_usage_log(domains::ai::usage::AIUsageLog::new(
+                db_manager_arc.get_connection_clone(),
+            ));
+            ai_service.attach_redaction_credentials(db_manager_arc.get_connection_clone());
+            domains::kubernetes::ai_tools::register_ai_tools(ai_service.tools());
+            domains::projects::pipelines::ai_tools::register_ai_tools(ai_service.tools());
+            domains::tasks::ai_tools::register_ai_tools(ai_service.tools());
+            domains::documents::ai_tools::register_ai_tools(ai_service.tools());
+            domains::custom_scripts::ai_tools::register_ai_tools(ai_service.tools());
+            domains::deployments::ai_tools::register_ai_tools(ai_service.tools());
+            domains::notifications::ai_tools::register_ai_tools(ai_service.tools());
+            domains::learning::ai_tools::register_ai_tools(ai_service.tools());
 
             // Load AI provider configurations and register them
             let settings = ai_settings_service.load_settings().unwrap_or_default();
@@ -104,12 +188,33 @@ pub fn run() {
                 }
             }
 
+            tauri::async_runtime::block_on(
+                ai_service.set_fallback_chain(settings.fallback_chain.clone()),
+            );
+            tauri::async_runtime::block_on(
+                ai_service.set_feature_routes(settings.feature_routes.clone()),
+            );
+            ai_service.configure_response_cache(settings.response_cache.clone());
+
             // Set default provider if configured
             if let Ok(Some(default_type)) = ai_settings_service.get_default_provider() {
                 let _ =
                     tauri::async_runtime::block_on(ai_service.set_default_provider(default_type));
             }
 
+            // Local GGUF models served by a managed llama.cpp server
+            let llama_cpp_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+            let llama_cpp = Arc::new(domains::ai::local_models::LlamaCppRuntime::new(
+                &llama_cpp_dir,
+            ));
+            tauri::async_runtime::block_on(ai_service.register_local_provider(Arc::new(
+                domains::ai::providers::LlamaCppProvider::new(llama_cpp.clone()),
+            )));
+            app.manage(llama_cpp);
+
             app.manage(std::sync::Arc::new(ai_settings_service));
             app.manage(std::sync::Arc::new(ai_service));
 
@@ -130,6 +235,21 @@ pub fn run() {
             // Initialize IDE storage
             domains::ide::commands::init_ide_storage(app.handle());
 
+            // Register global keyboard shortcuts with the OS
+            let keybindings = tauri::async_runtime::block_on(
+                domains::settings::services::keybindings_service::KeybindingsService::new(
+                    db_manager_arc.clone(),
+                )
+                .list(),
+            );
+            match keybindings {
+                Ok(keybindings) => domains::settings::services::global_shortcuts::register(
+                    app.handle(),
+                    &keybindings,
+                ),
+                Err(e) => log_warn!("Settings", "Failed to load keybindings: {}", e),
+            }
+
             // Initialize deployment service
             let deployment_service = tauri::async_runtime::block_on(async {
                 DeploymentService::new(db_manager_arc.clone()).await
@@ -150,10 +270,170 @@ pub fn run() {
             app.manage(Arc::new(pipeline_service));
             app.manage(Arc::new(execution_service));
 
+            // Resume watching document sync folders
+            let document_sync_watchers =
+                domains::documents::services::document_sync_service::DocumentSyncWatchers::default(
+                );
+            tauri::async_runtime::block_on(
+                document_sync_watchers.resume(db_manager_arc.clone(), app.handle().clone()),
+            );
+            app.manage(document_sync_watchers);
+
             // Initialize script execution state
-            let script_execution_state = ScriptExecutionState::new();
+            let script_execution_state = ScriptExecutionState::new(app.handle().clone());
             app.manage(script_execution_state);
 
+            // Probe managed services in the background and apply restart policies
+            let service_monitor =
+                std::sync::Arc::new(domains::sdk::services::service_manager::ServiceMonitor::new());
+            app.manage(service_monitor.clone());
+            let monitor_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                service_monitor
+                    .run(
+                        monitor_app,
+                        domains::sdk::commands::sdk_commands::restart_monitored_service,
+                    )
+                    .await;
+            });
+
+            // Check credential expiry and prune the access audit log hourly; the
+            // first tick runs right away
+            let expiry_monitor = std::sync::Arc::new(
+                domains::credentials::services::CredentialExpiryMonitor::new(
+                    db_manager_arc.clone(),
+                ),
+            );
+            app.manage(expiry_monitor.clone());
+            let expiry_app = app.handle().clone();
+            let audit_db = db_manager_arc.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
+                loop {
+                    interval.tick().await;
+                    let security = SettingsService::new()
+                        .load_settings()
+                        .map(|settings| settings.app.security)
+                        .unwrap_or_default();
+                    if let Err(e) = expiry_monitor
+                        .check(&expiry_app, security.credential_expiry_warning_days, false)
+                        .await
+                    {
+                        log_warn!("Credentials", "Credential expiry check failed: {}", e);
+                    }
+                    if let Err(e) = domains::credentials::services::CredentialAuditLog::new(
+                        audit_db.get_connection_clone(),
+                    )
+                    .prune(security.credential_audit_retention_days)
+                    .await
+                    {
+                        log_warn!("Credentials", "Failed to prune credential audit log: {}", e);
+                    }
+                }
+            });
+
+            // Back up the database when the configured interval has passed;
+            // checked hourly, starting right away. Skipped while the database
+            // is damaged, so rotation can't push out the backups to restore.
+            let backup_db = db_manager_arc.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
+                loop {
+                    interval.tick().await;
+                    if !backup_db.health().ok {
+                        continue;
+                    }
+                    let settings = SettingsService::new()
+                        .load_settings()
+                        .map(|settings| settings.app.backups)
+                        .unwrap_or_default();
+                    let backups = backup_db.backups().clone();
+                    match tokio::task::spawn_blocking(move || backups.backup_if_due(&settings))
+                        .await
+                    {
+                        Ok(Err(e)) => log_warn!("Database", "Scheduled backup failed: {}", e),
+                        Err(e) => log_warn!("Database", "Scheduled backup task failed: {}", e),
+                        Ok(Ok(_)) => {}
+                    }
+                }
+            });
+
+            // Sync settings and non-secret data through the sync folder, if
+            // one is set, every configured interval
+            let sync_db = db_manager_arc.clone();
+            let sync_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+                let mut last_sync: Option<std::time::Instant> = None;
+                loop {
+                    interval.tick().await;
+                    let Ok(settings) = SettingsService::new().load_settings() else {
+                        continue;
+                    };
+                    let sync_settings = settings.app.data_sync;
+                    let Some(folder) = sync_settings.folder.filter(|_| sync_settings.auto_sync)
+                    else {
+                        continue;
+                    };
+                    let due = std::time::Duration::from_secs(
+                        u64::from(sync_settings.interval_minutes) * 60,
+                    );
+                    if last_sync.is_some_and(|last| last.elapsed() < due) {
+                        continue;
+                    }
+                    last_sync = Some(std::time::Instant::now());
+                    let sync = database::data_sync::DataSync::new(
+                        sync_db.get_connection_clone(),
+                        &sync_db.workspace_dir(),
+                    );
+                    match sync.sync(std::path::Path::new(&folder), false).await {
+                        Ok(report) => {
+                            for error in &report.errors {
+                                log_warn!("DataSync", "{}", error);
+                            }
+                            if !report.imported.is_empty() {
+                                let _ =
+                                    sync_app.emit(database::data_sync::DATA_SYNC_EVENT, &report);
+                            }
+                        }
+                        Err(e) => log_warn!("DataSync", "Sync with {} failed: {}", folder, e),
+                    }
+                }
+            });
+
+            // Check for app updates in the background on the configured cadence
+            let update_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                domains::updates::services::update_poller::run(update_app).await
+            });
+
+            // Run scheduled jobs; the first tick catches up on runs missed while
+            // the app was closed
+            let scheduler = std::sync::Arc::new(
+                domains::automation::services::scheduler::Scheduler::new(db_manager_arc.clone()),
+            );
+            app.manage(scheduler.clone());
+            let scheduler_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move { scheduler.run(scheduler_app).await });
+
+            // Listen for inbound webhooks when the settings enable it
+            let webhook_server = std::sync::Arc::new(
+                domains::automation::services::webhook_server::WebhookServer::new(),
+            );
+            app.manage(webhook_server.clone());
+            let webhook_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let Ok(settings) = SettingsService::new().load_settings() else {
+                    return;
+                };
+                if let Err(e) = webhook_server
+                    .restart(&webhook_app, &settings.app.automation.webhooks)
+                    .await
+                {
+                    log_warn!("Automation", "Webhook listener didn't start: {}", e);
+                }
+            });
+
             // Sync any running script executions from previous session
             let db_for_sync = db_manager_arc.clone();
             tauri::async_runtime::spawn(async move {
@@ -219,9 +499,36 @@ pub fn run() {
             domains::coder::coder_multitask_list,
             domains::coder::coder_multitask_cancel,
             domains::coder::coder_multitask_cleanup,
+            // Database backup commands
+            domains::database::commands::backup_database,
+            domains::database::commands::list_backups,
+            domains::database::commands::restore_database,
+            domains::database::commands::get_database_health,
+            domains::database::commands::check_database_integrity,
+            domains::database::commands::vacuum_database,
+            domains::database::commands::checkpoint_database,
+            domains::database::commands::get_database_size_breakdown,
+            domains::database::commands::list_workspaces,
+            domains::database::commands::create_workspace,
+            domains::database::commands::switch_workspace,
+            domains::database::commands::export_all_data,
+            domains::database::commands::import_all_data,
+            domains::database::commands::get_data_sync_status,
+            domains::database::commands::sync_data_now,
+            domains::database::commands::resolve_data_sync_conflicts,
+            domains::database::commands::set_data_sync_folder,
+            domains::database::commands::get_database_encryption_status,
+            domains::database::commands::enable_database_encryption,
+            domains::database::commands::disable_database_encryption,
+            domains::database::commands::unlock_database,
             // Terminal commands
             domains::terminal::create_terminal_process,
+            domains::terminal::get_shell_profiles,
+            domains::terminal::create_shell_profile,
+            domains::terminal::update_shell_profile,
+            domains::terminal::delete_shell_profile,
             domains::terminal::send_terminal_input,
+            domains::terminal::send_input_to_group,
             domains::terminal::execute_command,
             domains::terminal::kill_terminal_process,
             domains::terminal::get_terminal_processes,
@@ -251,19 +558,31 @@ pub fn run() {
             domains::projects::get_all_projects,
             domains::projects::get_project,
             domains::projects::create_project,
+            domains::projects::clone_project,
             domains::projects::update_project,
             domains::projects::delete_project,
             domains::projects::toggle_project_star,
             domains::projects::open_project,
             domains::projects::refresh_project_metadata,
             domains::projects::get_projects_with_filters,
+            domains::projects::get_project_groups,
+            domains::projects::create_project_group,
+            domains::projects::update_project_group,
+            domains::projects::delete_project_group,
+            domains::projects::move_project_group,
+            domains::projects::move_project_to_group,
             domains::projects::get_frameworks,
             domains::projects::get_project_stats,
             domains::dashboard::commands::get_dashboard_overview,
             domains::projects::validate_project_path,
             domains::projects::generate_project_name,
             domains::projects::detect_framework,
+            domains::projects::detect_framework_candidates,
             domains::projects::analyze_project_directory,
+            domains::projects::quick_open_search,
+            domains::projects::get_recent_files,
+            domains::projects::record_recent_file,
+            domains::projects::refresh_file_index,
             domains::projects::open_project_in_explorer,
             domains::projects::select_directory,
             domains::projects::execute_command_in_directory,
@@ -289,6 +608,10 @@ pub fn run() {
             domains::projects::pipelines::create_block,
             domains::projects::pipelines::update_block,
             domains::projects::pipelines::delete_block,
+            domains::projects::pipelines::get_block_templates,
+            domains::projects::pipelines::import_block_templates,
+            domains::projects::pipelines::remove_block_template,
+            domains::projects::pipelines::instantiate_block_template,
             domains::projects::pipelines::get_step_execution_logs,
             domains::projects::pipelines::retry_step_execution,
             // Task commands
@@ -302,10 +625,19 @@ pub fn run() {
             domains::tasks::get_task_count,
             // AI task generation
             domains::tasks::generate_tasks_from_story,
+            domains::tasks::estimate_tasks,
+            domains::tasks::plan_sprint,
             // New advanced task commands
             domains::tasks::get_overdue_tasks,
             domains::tasks::get_due_today_tasks,
             domains::tasks::get_unestimated_tasks,
+            // Task attachment commands
+            domains::tasks::attach_task_file,
+            domains::tasks::get_task_attachments,
+            domains::tasks::get_task_attachment_metadata,
+            domains::tasks::open_task_attachment,
+            domains::tasks::delete_task_attachment,
+            domains::tasks::cleanup_orphaned_task_attachments,
             // Document commands
             domains::documents::commands::create_document,
             domains::documents::commands::get_document,
@@ -315,7 +647,31 @@ pub fn run() {
             domains::documents::commands::save_document,
             domains::documents::commands::delete_document,
             domains::documents::commands::search_documents,
+            domains::documents::commands::search_documents_full_text,
+            domains::documents::commands::get_document_revisions,
+            domains::documents::commands::get_document_revision,
+            domains::documents::commands::diff_document_revisions,
+            domains::documents::commands::restore_document_revision,
+            domains::documents::commands::set_document_sync_folder,
+            domains::documents::commands::get_document_sync_folder,
+            domains::documents::commands::remove_document_sync_folder,
+            domains::documents::commands::export_documents_to_folder,
+            domains::documents::commands::import_documents_from_folder,
+            domains::documents::commands::sync_documents_folder,
+            domains::documents::commands::resolve_document_sync_conflict,
+            domains::documents::commands::get_document_templates,
+            domains::documents::commands::create_document_template,
+            domains::documents::commands::update_document_template,
+            domains::documents::commands::delete_document_template,
+            domains::documents::commands::create_document_from_template,
+            domains::documents::commands::get_document_backlinks,
+            domains::documents::commands::get_document_graph,
+            domains::documents::commands::rebuild_document_links,
+            domains::documents::commands::export_document,
             domains::documents::commands::generate_document_with_ai,
+            domains::documents::commands::index_project_docs,
+            domains::documents::commands::ask_project_docs,
+            domains::documents::commands::summarize_document,
             // GitHub commands
             domains::github::commands::github_get_connection_status,
             domains::github::commands::github_start_device_flow,
@@ -345,17 +701,56 @@ pub fn run() {
             domains::credentials::commands::delete_credential,
             domains::credentials::commands::decrypt_credential,
             domains::credentials::commands::search_credentials,
+            domains::credentials::commands::get_credential_schemas,
+            domains::credentials::commands::create_typed_credential,
+            domains::credentials::commands::update_typed_credential,
+            domains::credentials::commands::decrypt_credential_fields,
+            domains::credentials::commands::get_vault_status,
+            domains::credentials::commands::lock_vault,
+            domains::credentials::commands::unlock_vault,
+            domains::credentials::commands::set_vault_auto_lock,
+            domains::credentials::commands::get_expiring_credentials,
+            domains::credentials::commands::check_credential_expiry,
+            domains::credentials::commands::set_credential_rotation_script,
+            domains::credentials::commands::set_credential_expiry_warning_days,
+            domains::credentials::commands::get_project_env_mappings,
+            domains::credentials::commands::set_project_env_mapping,
+            domains::credentials::commands::delete_project_env_mapping,
+            domains::credentials::commands::write_project_env_file,
+            domains::credentials::commands::import_credentials,
+            domains::credentials::commands::get_credential_audit_log,
+            domains::credentials::commands::set_credential_audit_retention,
+            domains::credentials::commands::generate_ssh_key,
+            domains::credentials::commands::get_ssh_keys,
+            domains::credentials::commands::get_ssh_agent_status,
+            domains::credentials::commands::add_ssh_key_to_agent,
+            domains::credentials::commands::remove_ssh_key_from_agent,
             // Automation commands
             domains::automation::trigger_n8n_workflow,
             domains::automation::get_workflow_status,
             domains::automation::list_available_workflows,
             domains::automation::get_suggested_workflows,
             domains::automation::check_n8n_health,
+            domains::automation::get_webhook_listener_status,
+            domains::automation::restart_webhook_listener,
+            domains::automation::list_webhook_requests,
+            domains::automation::list_automation_actions,
+            domains::automation::create_scheduled_job,
+            domains::automation::list_scheduled_jobs,
+            domains::automation::set_scheduled_job_enabled,
+            domains::automation::delete_scheduled_job,
+            domains::automation::run_scheduled_job_now,
+            domains::automation::preview_schedule,
+            domains::automation::push_n8n_workflow_template,
+            domains::automation::list_project_n8n_workflows,
+            domains::automation::remove_project_n8n_workflow,
             // Embedded workflow engine commands
             domains::automation::register_embedded_workflow,
             domains::automation::execute_embedded_workflow,
             domains::automation::list_embedded_workflows,
-            domains::automation::check_workflow_trigger,
+            domains::automation::publish_app_event,
+            domains::automation::list_workflow_runs,
+            domains::automation::get_workflow_run,
             // Deployment commands
             domains::deployments::commands::create_deployment_command,
             domains::deployments::commands::get_deployments_command,
@@ -411,9 +806,12 @@ pub fn run() {
             // FlyEnv-style download commands
             domains::sdk::commands::sdk_commands::fetch_available_versions,
             domains::sdk::commands::sdk_commands::download_and_install_version,
+            domains::sdk::commands::sdk_commands::get_direct_sdk_installs,
+            domains::sdk::commands::sdk_commands::remove_direct_sdk_install,
             domains::sdk::commands::sdk_commands::detect_sdk_managers,
             domains::sdk::commands::sdk_commands::get_all_available_sdks,
             domains::sdk::commands::sdk_commands::start_sdk_service,
+            domains::sdk::commands::sdk_commands::check_service_port,
             domains::sdk::commands::sdk_commands::stop_sdk_service,
             domains::sdk::commands::sdk_commands::get_service_status,
             domains::sdk::commands::sdk_commands::setup_project_version_file,
@@ -422,6 +820,11 @@ pub fn run() {
             domains::sdk::commands::sdk_commands::get_ollama_models,
             domains::sdk::commands::sdk_commands::install_ollama_model,
             domains::sdk::commands::sdk_commands::remove_ollama_model,
+            domains::sdk::commands::sdk_commands::get_ollama_modelfile,
+            domains::sdk::commands::sdk_commands::export_ollama_model,
+            domains::sdk::commands::sdk_commands::import_ollama_model,
+            domains::sdk::commands::sdk_commands::create_ollama_model,
+            domains::sdk::commands::sdk_commands::render_ollama_modelfile,
             domains::sdk::commands::sdk_commands::get_available_ollama_models,
             // Runtime (AI) model management wrappers
             domains::sdk::commands::sdk_commands::get_runtime_models,
@@ -439,6 +842,7 @@ pub fn run() {
             domains::sdk::commands::sdk_commands::remove_project_version,
             domains::sdk::commands::sdk_commands::get_project_versions,
             domains::sdk::commands::sdk_commands::setup_shell_integration,
+            domains::sdk::commands::sdk_commands::get_shell_hook_status,
             domains::sdk::commands::sdk_commands::activate_project_environment,
             domains::sdk::commands::sdk_commands::deactivate_project_environment,
             domains::sdk::commands::sdk_commands::find_projects_with_versions,
@@ -450,13 +854,41 @@ pub fn run() {
             domains::sdk::commands::sdk_commands::update_service_config,
             domains::sdk::commands::sdk_commands::restart_service,
             domains::sdk::commands::sdk_commands::get_service_health,
+            domains::sdk::commands::sdk_commands::get_service_monitor_status,
+            domains::sdk::commands::sdk_commands::get_service_monitor_settings,
+            domains::sdk::commands::sdk_commands::set_service_monitor_settings,
+            domains::sdk::commands::sdk_commands::provision_service_instance,
+            domains::sdk::commands::sdk_commands::list_service_instances,
+            domains::sdk::commands::sdk_commands::start_service_instance,
+            domains::sdk::commands::sdk_commands::stop_service_instance,
+            domains::sdk::commands::sdk_commands::remove_service_instance,
+            domains::sdk::commands::sdk_commands::list_vhosts,
+            domains::sdk::commands::sdk_commands::get_vhost_server_status,
+            domains::sdk::commands::sdk_commands::save_vhost,
+            domains::sdk::commands::sdk_commands::remove_vhost,
+            domains::sdk::commands::sdk_commands::test_vhost_config,
+            domains::sdk::commands::sdk_commands::list_php_installations,
+            domains::sdk::commands::sdk_commands::list_php_extensions,
+            domains::sdk::commands::sdk_commands::set_php_extension_enabled,
+            domains::sdk::commands::sdk_commands::get_php_ini_settings,
+            domains::sdk::commands::sdk_commands::set_php_ini_value,
             domains::sdk::commands::sdk_commands::set_path_environment,
             domains::sdk::commands::sdk_commands::get_path_status,
+            domains::sdk::commands::sdk_commands::rehash_sdk_shims,
             domains::sdk::commands::sdk_commands::create_alias,
             domains::sdk::commands::sdk_commands::remove_alias,
             domains::sdk::commands::sdk_commands::list_aliases,
             domains::sdk::commands::sdk_commands::detect_version_files,
             domains::sdk::commands::sdk_commands::create_version_file,
+            domains::sdk::commands::sdk_commands::import_asdf_versions,
+            domains::sdk::commands::sdk_commands::get_sdk_disk_usage,
+            domains::sdk::commands::sdk_commands::cleanup_unused_sdk_versions,
+            domains::sdk::commands::sdk_commands::get_sdk_download_settings,
+            domains::sdk::commands::sdk_commands::set_sdk_download_settings,
+            domains::sdk::commands::sdk_commands::list_cached_sdk_archives,
+            domains::sdk::commands::sdk_commands::capture_portal_lock,
+            domains::sdk::commands::sdk_commands::read_portal_lock,
+            domains::sdk::commands::sdk_commands::restore_portal_lock,
             domains::sdk::commands::sdk_commands::get_running_services_count,
             // Settings commands
             domains::settings::commands::get_settings_command,
@@ -465,6 +897,19 @@ pub fn run() {
             domains::settings::commands::reset_settings_command,
             domains::settings::commands::export_settings_command,
             domains::settings::commands::import_settings_command,
+            domains::settings::commands::get_project_settings,
+            domains::settings::commands::set_project_settings,
+            domains::settings::commands::get_effective_project_settings,
+            domains::diagnostics::commands::run_doctor,
+            domains::logs::commands::get_app_logs,
+            domains::logs::commands::export_logs_zip,
+            domains::logs::commands::set_log_level,
+            domains::settings::commands::get_keybindings,
+            domains::settings::commands::set_keybinding,
+            domains::settings::commands::reset_keybindings,
+            domains::settings::commands::get_keybinding_conflicts,
+            domains::settings::commands::export_keybindings,
+            domains::settings::commands::import_keybindings,
             // IDE commands
             domains::ide::commands::detect_installed_ides,
             domains::ide::commands::get_all_ides,
@@ -474,6 +919,11 @@ pub fn run() {
             domains::ide::commands::delete_ide,
             domains::ide::commands::set_default_ide,
             domains::ide::commands::get_default_ide,
+            domains::ide::commands::open_in_ide,
+            domains::ide::commands::open_in_devcontainer,
+            domains::ide::commands::rebuild_devcontainer,
+            domains::ide::commands::get_recommended_extensions,
+            domains::ide::commands::install_recommended_extensions,
             domains::ide::commands::get_all_framework_ide_mappings,
             domains::ide::commands::set_framework_ide_mapping,
             domains::ide::commands::get_framework_ide_mapping,
@@ -499,8 +949,12 @@ pub fn run() {
             domains::learning::commands::learn_pattern,
             domains::learning::commands::record_pattern_outcome,
             domains::learning::commands::get_suggestions,
+            domains::learning::commands::submit_suggestion_feedback,
+            domains::learning::commands::predict_next_commands,
             domains::learning::commands::learn_preference,
             domains::learning::commands::get_preference,
+            domains::learning::commands::export_learning_data,
+            domains::learning::commands::purge_learning_data,
             domains::learning::commands::cleanup_learning_data,
             domains::learning::commands::get_memory_stats,
             domains::learning::commands::get_cleanup_preview,
@@ -512,6 +966,7 @@ pub fn run() {
             domains::learning::commands::get_all_patterns,
             domains::learning::commands::get_recent_events,
             domains::learning::commands::get_all_preferences,
+            domains::learning::commands::get_session_timeline,
             // Autonomy commands
             domains::autonomy::commands::evaluate_autonomous_action,
             domains::autonomy::commands::record_autonomous_action_outcome,
@@ -520,6 +975,8 @@ pub fn run() {
             domains::autonomy::commands::get_autonomy_enabled,
             domains::autonomy::commands::set_autonomy_enabled,
             domains::autonomy::commands::get_approval_stats,
+            domains::autonomy::commands::get_approval_queue,
+            domains::autonomy::commands::clear_resolved_approvals,
             // Kubernetes commands
             domains::kubernetes::commands::k8s_initialize_manager,
             domains::kubernetes::commands::k8s_load_clusters,
@@ -533,9 +990,17 @@ pub fn run() {
             domains::kubernetes::commands::k8s_start_watching_services,
             domains::kubernetes::commands::k8s_start_watching_deployments,
             domains::kubernetes::commands::k8s_exec_pod,
+            domains::kubernetes::commands::k8s_debug_pod,
             domains::kubernetes::commands::k8s_start_port_forward,
+            domains::kubernetes::commands::k8s_start_resource_port_forward,
             domains::kubernetes::commands::k8s_list_port_forwards,
             domains::kubernetes::commands::k8s_stop_port_forward,
+            domains::kubernetes::commands::k8s_list_saved_port_forwards,
+            domains::kubernetes::commands::k8s_save_port_forward,
+            domains::kubernetes::commands::k8s_update_saved_port_forward,
+            domains::kubernetes::commands::k8s_delete_saved_port_forward,
+            domains::kubernetes::commands::k8s_start_saved_port_forward,
+            domains::kubernetes::commands::k8s_restore_port_forwards,
             domains::kubernetes::commands::k8s_stop_all_watches,
             domains::kubernetes::commands::k8s_list_services,
             domains::kubernetes::commands::k8s_list_deployments,
@@ -543,22 +1008,48 @@ pub fn run() {
             domains::kubernetes::commands::k8s_list_daemonsets,
             domains::kubernetes::commands::k8s_list_jobs,
             domains::kubernetes::commands::k8s_list_cronjobs,
+            domains::kubernetes::commands::k8s_trigger_cronjob,
+            domains::kubernetes::commands::k8s_suspend_cronjob,
+            domains::kubernetes::commands::k8s_resume_cronjob,
+            domains::kubernetes::commands::k8s_delete_finished_jobs,
             domains::kubernetes::commands::k8s_list_ingresses,
             domains::kubernetes::commands::k8s_list_events,
             domains::kubernetes::commands::k8s_list_configmaps,
             domains::kubernetes::commands::k8s_list_secrets,
             domains::kubernetes::commands::k8s_get_resource_yaml,
             domains::kubernetes::commands::k8s_apply_resource_yaml,
+            domains::kubernetes::commands::k8s_validate_resource_yaml,
+            domains::kubernetes::commands::k8s_get_config_entries,
+            domains::kubernetes::commands::k8s_set_config_entry,
+            domains::kubernetes::commands::k8s_remove_config_entry,
             domains::kubernetes::commands::k8s_delete_configmap,
             domains::kubernetes::commands::k8s_delete_secret,
             domains::kubernetes::commands::k8s_rollback_deployment,
             domains::kubernetes::commands::k8s_get_pod_metrics,
             domains::kubernetes::commands::k8s_get_all_pods_metrics,
             domains::kubernetes::commands::k8s_list_namespaces,
+            domains::kubernetes::commands::k8s_create_namespace,
+            domains::kubernetes::commands::k8s_delete_namespace,
+            domains::kubernetes::commands::k8s_get_namespace_summary,
+            domains::kubernetes::commands::k8s_list_persistent_volumes,
+            domains::kubernetes::commands::k8s_get_persistent_volume,
+            domains::kubernetes::commands::k8s_delete_persistent_volume,
+            domains::kubernetes::commands::k8s_list_persistent_volume_claims,
+            domains::kubernetes::commands::k8s_get_persistent_volume_claim,
+            domains::kubernetes::commands::k8s_delete_persistent_volume_claim,
+            domains::kubernetes::commands::k8s_expand_persistent_volume_claim,
+            domains::kubernetes::commands::k8s_list_nodes,
+            domains::kubernetes::commands::k8s_cordon_node,
+            domains::kubernetes::commands::k8s_uncordon_node,
+            domains::kubernetes::commands::k8s_drain_node,
             domains::kubernetes::commands::k8s_get_current_cluster,
             domains::kubernetes::commands::k8s_is_connected,
             domains::kubernetes::commands::k8s_detect_setup_tools,
             domains::kubernetes::commands::k8s_generate_kubeconfig,
+            domains::kubernetes::commands::k8s_list_kube_contexts,
+            domains::kubernetes::commands::k8s_import_kubeconfig,
+            domains::kubernetes::commands::k8s_find_stale_contexts,
+            domains::kubernetes::commands::k8s_remove_kube_contexts,
             // AI commands
             // AI Provider commands
             domains::ai::commands::get_ai_provider_config_status,
@@ -572,15 +1063,51 @@ pub fn run() {
             domains::ai::commands::get_ai_platform_catalog,
             domains::ai::commands::generate_ai_text,
             domains::ai::commands::generate_ai_text_with_system,
+            domains::ai::commands::generate_ai_structured,
             // AI Chat commands
             domains::ai::commands::ai_send_message,
             domains::ai::commands::ai_send_message_stream,
+            domains::ai::commands::ai_get_usage_stats,
+            domains::ai::commands::embed_texts,
+            domains::ai::commands::semantic_search,
+            domains::ai::commands::index_embeddings,
+            domains::ai::commands::remove_embeddings,
+            domains::ai::commands::list_local_models,
+            domains::ai::commands::download_local_model,
+            domains::ai::commands::delete_local_model,
+            domains::ai::commands::install_llama_cpp_runtime,
+            domains::ai::commands::get_llama_cpp_status,
+            domains::ai::commands::stop_llama_cpp_server,
+            domains::ai::commands::list_prompt_templates,
+            domains::ai::commands::get_prompt_template,
+            domains::ai::commands::create_prompt_template,
+            domains::ai::commands::update_prompt_template,
+            domains::ai::commands::delete_prompt_template,
+            domains::ai::commands::list_prompt_template_versions,
+            domains::ai::commands::restore_prompt_template_version,
+            domains::ai::commands::render_prompt_template,
+            domains::ai::commands::get_ai_fallback_chain,
+            domains::ai::commands::set_ai_fallback_chain,
+            domains::ai::commands::get_ai_feature_routes,
+            domains::ai::commands::set_ai_feature_routes,
+            domains::ai::commands::get_ai_response_cache_settings,
+            domains::ai::commands::set_ai_response_cache_settings,
+            domains::ai::commands::get_ai_response_cache_stats,
+            domains::ai::commands::clear_ai_response_cache,
+            domains::ai::commands::cancel_ai_stream,
+            domains::ai::commands::regenerate_last_message,
+            domains::ai::commands::ai_send_message_with_tools,
+            domains::ai::commands::ai_resolve_tool_call,
+            domains::ai::commands::ai_resolve_tool_calls,
+            domains::ai::commands::ai_list_tools,
             // AI Conversation commands
             domains::ai::commands::ai_create_conversation,
             domains::ai::commands::ai_save_conversation,
             domains::ai::commands::ai_load_conversation,
             domains::ai::commands::ai_list_conversations,
             domains::ai::commands::ai_delete_conversation,
+            domains::ai::commands::ai_fork_conversation,
+            domains::ai::commands::ai_list_conversation_branches,
             domains::ai::commands::ai_update_conversation_title,
             domains::ai::commands::ai_update_conversation_model,
             // AI Log commands
@@ -597,9 +1124,34 @@ pub fn run() {
             domains::custom_scripts::commands::update_custom_script,
             domains::custom_scripts::commands::delete_custom_script,
             domains::custom_scripts::commands::record_script_run,
+            domains::custom_scripts::commands::get_custom_script_parameters,
+            domains::custom_scripts::commands::run_custom_script,
+            domains::custom_scripts::commands::get_custom_script_runs,
+            domains::custom_scripts::commands::get_custom_script_event_triggers,
+            domains::custom_scripts::commands::set_custom_script_event_triggers,
+            domains::custom_scripts::commands::set_custom_script_runtime,
+            domains::custom_scripts::commands::schedule_custom_script,
+            domains::custom_scripts::commands::list_custom_script_schedules,
+            domains::custom_scripts::commands::list_script_libraries,
+            domains::custom_scripts::commands::add_script_library,
+            domains::custom_scripts::commands::remove_script_library,
+            domains::custom_scripts::commands::sync_script_library,
+            domains::custom_scripts::commands::check_script_library_updates,
+            domains::custom_scripts::commands::get_custom_script_sync_states,
+            domains::custom_scripts::commands::resolve_custom_script_conflict,
             domains::custom_scripts::commands::select_file,
+            // Notification commands
+            domains::notifications::commands::send_notification,
+            domains::notifications::commands::list_notifications,
+            domains::notifications::commands::dismiss_notification,
+            domains::notifications::commands::dismiss_all_notifications,
+            domains::notifications::commands::open_notification,
             // Update commands
             domains::updates::commands::get_app_version_command,
+            domains::updates::commands::set_update_channel,
+            domains::updates::commands::check_for_app_updates,
+            domains::updates::commands::download_app_update,
+            domains::updates::commands::install_app_update,
             // Network commands
             domains::network::commands::get_local_network_ip,
             domains::network::commands::generate_device_passcode,
@@ -645,6 +1197,15 @@ pub fn run() {
             domains::environment::commands::env_refresh_process,
             domains::environment::commands::env_request_elevation,
         ])
-        .run(tauri::generate_context!()) // Note: OUT_DIR linter error is a false positive - resolves after build
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!()) // Note: OUT_DIR linter error is a false positive - resolves after build
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Nothing the app started should outlive it
+            if let tauri::RunEvent::Exit = event {
+                command_executor::supervisor::supervisor().shutdown();
+                // A downloaded update is installed for the next launch
+                let _ =
+                    domains::updates::services::update_service::UpdateService::install_pending(app);
+            }
+        });
 }
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Add content hash to task attachments
+///
+/// Attachments are copied into the app data directory and stored by the SHA-256 of
+/// their contents, so identical files attached to several tasks share one blob.
+/// The hash is nullable because rows created before this migration point at the
+/// user's original file rather than a managed copy.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager
+            .has_column("task_attachments", "content_hash")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TaskAttachments::Table)
+                        .add_column(ColumnDef::new(TaskAttachments::ContentHash).text().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_attachments_content_hash")
+                    .table(TaskAttachments::Table)
+                    .col(TaskAttachments::ContentHash)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_task_attachments_content_hash")
+                    .table(TaskAttachments::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        if manager
+            .has_column("task_attachments", "content_hash")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TaskAttachments::Table)
+                        .drop_column(TaskAttachments::ContentHash)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaskAttachments {
+    Table,
+    ContentHash,
+}
@@ -0,0 +1,116 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+/// Migration: Full-text search for documents
+///
+/// - Adds a nullable `project_id` to documents so search can be faceted by project
+/// - Creates `documents_fts`, an external-content FTS5 index over title, content and
+///   tags, kept in sync with `documents` by triggers
+/// - Backfills the index from existing rows
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_column("documents", "project_id").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Documents::Table)
+                        .add_column(ColumnDef::new(Documents::ProjectId).integer().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_documents_project_id")
+                    .table(Documents::Table)
+                    .col(Documents::ProjectId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        // `prefix` builds extra indexes so `term*` queries stay fast for 2-3 char stems.
+        let statements = [
+            r#"CREATE VIRTUAL TABLE IF NOT EXISTS documents_fts USING fts5(
+                   title, content, tags,
+                   content='documents', content_rowid='id',
+                   tokenize='unicode61 remove_diacritics 2',
+                   prefix='2 3'
+               )"#,
+            r#"CREATE TRIGGER IF NOT EXISTS documents_fts_ai AFTER INSERT ON documents BEGIN
+                   INSERT INTO documents_fts(rowid, title, content, tags)
+                   VALUES (new.id, new.title, new.content, new.tags);
+               END"#,
+            r#"CREATE TRIGGER IF NOT EXISTS documents_fts_ad AFTER DELETE ON documents BEGIN
+                   INSERT INTO documents_fts(documents_fts, rowid, title, content, tags)
+                   VALUES ('delete', old.id, old.title, old.content, old.tags);
+               END"#,
+            r#"CREATE TRIGGER IF NOT EXISTS documents_fts_au AFTER UPDATE OF title, content, tags ON documents BEGIN
+                   INSERT INTO documents_fts(documents_fts, rowid, title, content, tags)
+                   VALUES ('delete', old.id, old.title, old.content, old.tags);
+                   INSERT INTO documents_fts(rowid, title, content, tags)
+                   VALUES (new.id, new.title, new.content, new.tags);
+               END"#,
+            "INSERT INTO documents_fts(documents_fts) VALUES ('rebuild')",
+        ];
+
+        for sql in statements {
+            conn.execute(Statement::from_string(backend, sql.to_string()))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        for sql in [
+            "DROP TRIGGER IF EXISTS documents_fts_au",
+            "DROP TRIGGER IF EXISTS documents_fts_ad",
+            "DROP TRIGGER IF EXISTS documents_fts_ai",
+            "DROP TABLE IF EXISTS documents_fts",
+        ] {
+            conn.execute(Statement::from_string(backend, sql.to_string()))
+                .await?;
+        }
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_documents_project_id")
+                    .table(Documents::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        if manager.has_column("documents", "project_id").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Documents::Table)
+                        .drop_column(Documents::ProjectId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Documents {
+    Table,
+    ProjectId,
+}
@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create document revisions table
+///
+/// Every save of a document snapshots it into document_revisions:
+/// - id: Primary key (auto-increment)
+/// - document_id: Foreign key to documents table
+/// - revision_number: 1-based, increasing per document
+/// - title / content / tags: The document as it was saved
+/// - created_at: When the revision was recorded
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentRevisions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DocumentRevisions::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentRevisions::DocumentId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentRevisions::RevisionNumber)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentRevisions::Title).text().not_null())
+                    .col(ColumnDef::new(DocumentRevisions::Content).text().not_null())
+                    .col(ColumnDef::new(DocumentRevisions::Tags).text().null())
+                    .col(
+                        ColumnDef::new(DocumentRevisions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_document_revisions_document_id")
+                            .from(DocumentRevisions::Table, DocumentRevisions::DocumentId)
+                            .to(Documents::Table, Documents::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_document_revisions_document_revision")
+                    .table(DocumentRevisions::Table)
+                    .col(DocumentRevisions::DocumentId)
+                    .col(DocumentRevisions::RevisionNumber)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DocumentRevisions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DocumentRevisions {
+    Table,
+    Id,
+    DocumentId,
+    RevisionNumber,
+    Title,
+    Content,
+    Tags,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Documents {
+    Table,
+    Id,
+}
@@ -0,0 +1,147 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create document sync tables
+///
+/// Supports two-way sync between documents and a folder of markdown files:
+/// - document_sync_folders: which folder each project's documents sync with
+/// - document_sync_states: per document, the file it was last synced to and the
+///   hashes of both sides at that point, so edits made on either side since the
+///   last sync (or on both, a conflict) can be detected
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentSyncFolders::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DocumentSyncFolders::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentSyncFolders::ProjectId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentSyncFolders::FolderPath)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentSyncFolders::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentSyncFolders::LastSyncedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_document_sync_folders_project_id")
+                            .from(DocumentSyncFolders::Table, DocumentSyncFolders::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentSyncStates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DocumentSyncStates::DocumentId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentSyncStates::FilePath)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentSyncStates::FileHash)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentSyncStates::DocumentHash)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentSyncStates::SyncedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_document_sync_states_document_id")
+                            .from(DocumentSyncStates::Table, DocumentSyncStates::DocumentId)
+                            .to(Documents::Table, Documents::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DocumentSyncStates::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(DocumentSyncFolders::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DocumentSyncFolders {
+    Table,
+    Id,
+    ProjectId,
+    FolderPath,
+    CreatedAt,
+    LastSyncedAt,
+}
+
+#[derive(DeriveIden)]
+enum DocumentSyncStates {
+    Table,
+    DocumentId,
+    FilePath,
+    FileHash,
+    DocumentHash,
+    SyncedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Documents {
+    Table,
+    Id,
+}
@@ -0,0 +1,259 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create document templates table
+///
+/// Templates are markdown with `{{variable}}` placeholders and optional
+/// `<!-- fill: ... -->` hints for AI pre-fill:
+/// - id: Primary key (auto-increment)
+/// - name / description: Shown in the template picker
+/// - category: adr, rfc, runbook, postmortem or custom
+/// - title_template / body_template: Rendered into the new document
+/// - tags: JSON array applied to created documents
+/// - is_builtin: Seeded templates, which can be edited but not deleted
+///
+/// Seeds the ADR, RFC, runbook and postmortem templates.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const BUILTIN_TEMPLATES: [(&str, &str, &str, &str, &str, &str); 4] = [
+    (
+        "Architecture Decision Record",
+        "Record a significant technical decision and its trade-offs",
+        "adr",
+        "ADR: {{title}}",
+        r#"# ADR: {{title}}
+
+- **Project:** {{project_name}}
+- **Date:** {{date}}
+- **Status:** Proposed
+
+## Context
+
+<!-- fill: What is the issue motivating this decision? -->
+
+## Decision
+
+<!-- fill: What change is being proposed or has been agreed? -->
+
+## Consequences
+
+<!-- fill: What becomes easier or harder because of this decision? -->
+
+## Related tasks
+
+{{task_links}}
+"#,
+        r#"["adr","architecture"]"#,
+    ),
+    (
+        "Request for Comments",
+        "Propose a design and collect feedback before building it",
+        "rfc",
+        "RFC: {{title}}",
+        r#"# RFC: {{title}}
+
+- **Project:** {{project_name}}
+- **Date:** {{date}}
+
+## Summary
+
+<!-- fill: One paragraph explanation of the proposal. -->
+
+## Motivation
+
+<!-- fill: Why are we doing this? What problems does it solve? -->
+
+## Detailed design
+
+<!-- fill: How it works, in enough detail to implement. -->
+
+## Alternatives considered
+
+<!-- fill: Other designs and why they were not chosen. -->
+
+## Open questions
+
+<!-- fill: What still needs to be decided? -->
+
+## Related tasks
+
+{{task_links}}
+"#,
+        r#"["rfc","design"]"#,
+    ),
+    (
+        "Runbook",
+        "Step-by-step operating procedure for a service or task",
+        "runbook",
+        "Runbook: {{title}}",
+        r#"# Runbook: {{title}}
+
+- **Project:** {{project_name}}
+- **Location:** `{{project_path}}`
+- **Last reviewed:** {{date}}
+
+## When to use this runbook
+
+<!-- fill: Symptoms or alerts that lead here. -->
+
+## Prerequisites
+
+<!-- fill: Access, tools and credentials needed. -->
+
+## Steps
+
+<!-- fill: Numbered steps, with commands where possible. -->
+
+## Verification
+
+<!-- fill: How to confirm the procedure worked. -->
+
+## Rollback
+
+<!-- fill: How to undo the steps if something goes wrong. -->
+
+## Related tasks
+
+{{task_links}}
+"#,
+        r#"["runbook","ops"]"#,
+    ),
+    (
+        "Postmortem",
+        "Blameless write-up of an incident and follow-up actions",
+        "postmortem",
+        "Postmortem: {{title}}",
+        r#"# Postmortem: {{title}}
+
+- **Project:** {{project_name}}
+- **Date:** {{date}}
+
+## Summary
+
+<!-- fill: What happened and what was the impact? -->
+
+## Timeline
+
+<!-- fill: Key events with timestamps. -->
+
+## Root cause
+
+<!-- fill: Why did it happen? -->
+
+## What went well
+
+<!-- fill: -->
+
+## What went wrong
+
+<!-- fill: -->
+
+## Action items
+
+{{task_links}}
+"#,
+        r#"["postmortem","incident"]"#,
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DocumentTemplates::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DocumentTemplates::Name).text().not_null())
+                    .col(ColumnDef::new(DocumentTemplates::Description).text().null())
+                    .col(
+                        ColumnDef::new(DocumentTemplates::Category)
+                            .text()
+                            .not_null()
+                            .default("custom"),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentTemplates::TitleTemplate)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentTemplates::BodyTemplate)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentTemplates::Tags).text().null())
+                    .col(
+                        ColumnDef::new(DocumentTemplates::IsBuiltin)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentTemplates::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentTemplates::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut insert = Query::insert();
+        insert.into_table(DocumentTemplates::Table).columns([
+            DocumentTemplates::Name,
+            DocumentTemplates::Description,
+            DocumentTemplates::Category,
+            DocumentTemplates::TitleTemplate,
+            DocumentTemplates::BodyTemplate,
+            DocumentTemplates::Tags,
+            DocumentTemplates::IsBuiltin,
+        ]);
+        for (name, description, category, title, body, tags) in BUILTIN_TEMPLATES {
+            insert.values_panic([
+                name.into(),
+                description.into(),
+                category.into(),
+                title.into(),
+                body.into(),
+                tags.into(),
+                true.into(),
+            ]);
+        }
+        manager.exec_stmt(insert).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DocumentTemplates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DocumentTemplates {
+    Table,
+    Id,
+    Name,
+    Description,
+    Category,
+    TitleTemplate,
+    BodyTemplate,
+    Tags,
+    IsBuiltin,
+    CreatedAt,
+    UpdatedAt,
+}
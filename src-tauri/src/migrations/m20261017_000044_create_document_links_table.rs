@@ -0,0 +1,103 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create document links table
+///
+/// Outgoing links parsed from document content, rebuilt on every save:
+/// - id: Primary key (auto-increment)
+/// - source_document_id: Document containing the link
+/// - target_type: document, task or project
+/// - target_id: Resolved target, NULL when a `[[Title]]` names no document yet
+/// - target_title: Link text as written, used to resolve it later
+/// - created_at: When the link was recorded
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentLinks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DocumentLinks::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentLinks::SourceDocumentId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentLinks::TargetType).text().not_null())
+                    .col(ColumnDef::new(DocumentLinks::TargetId).integer().null())
+                    .col(ColumnDef::new(DocumentLinks::TargetTitle).text().not_null())
+                    .col(
+                        ColumnDef::new(DocumentLinks::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_document_links_source_document_id")
+                            .from(DocumentLinks::Table, DocumentLinks::SourceDocumentId)
+                            .to(Documents::Table, Documents::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_document_links_source")
+                    .table(DocumentLinks::Table)
+                    .col(DocumentLinks::SourceDocumentId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_document_links_target")
+                    .table(DocumentLinks::Table)
+                    .col(DocumentLinks::TargetType)
+                    .col(DocumentLinks::TargetId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DocumentLinks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DocumentLinks {
+    Table,
+    Id,
+    SourceDocumentId,
+    TargetType,
+    TargetId,
+    TargetTitle,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Documents {
+    Table,
+    Id,
+}
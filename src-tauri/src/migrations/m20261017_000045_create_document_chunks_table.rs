@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create document chunks table
+///
+/// Documents split into passages for retrieval before AI summaries and Q&A:
+/// - id: Primary key (auto-increment)
+/// - document_id: Document the chunk was cut from
+/// - chunk_index: Position of the chunk within the document
+/// - heading: Nearest markdown heading above the chunk, if any
+/// - content: Chunk text
+/// - content_hash: Hash of the document content the chunks were built from
+/// - embedding: Little-endian f32 vector, NULL when no embedding model was available
+/// - embedding_model: Model that produced `embedding`
+/// - created_at: When the chunk was indexed
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentChunks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DocumentChunks::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentChunks::DocumentId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentChunks::ChunkIndex)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentChunks::Heading).text().null())
+                    .col(ColumnDef::new(DocumentChunks::Content).text().not_null())
+                    .col(
+                        ColumnDef::new(DocumentChunks::ContentHash)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentChunks::Embedding).binary().null())
+                    .col(ColumnDef::new(DocumentChunks::EmbeddingModel).text().null())
+                    .col(
+                        ColumnDef::new(DocumentChunks::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_document_chunks_document_id")
+                            .from(DocumentChunks::Table, DocumentChunks::DocumentId)
+                            .to(Documents::Table, Documents::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_document_chunks_document")
+                    .table(DocumentChunks::Table)
+                    .col(DocumentChunks::DocumentId)
+                    .col(DocumentChunks::ChunkIndex)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DocumentChunks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DocumentChunks {
+    Table,
+    Id,
+    DocumentId,
+    ChunkIndex,
+    Heading,
+    Content,
+    ContentHash,
+    Embedding,
+    EmbeddingModel,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Documents {
+    Table,
+    Id,
+}
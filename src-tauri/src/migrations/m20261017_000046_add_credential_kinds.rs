@@ -0,0 +1,94 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+/// Migration: Typed credential kinds
+///
+/// - kind: Schema the credential follows (api_key, ssh_key, database_dsn,
+///   oauth_token, certificate, generic)
+/// - public_fields: JSON object of the kind's non-secret fields, stored in plain text
+///
+/// Existing rows are mapped to a kind from their free-form `credential_type`. Their
+/// `encrypted_value` stays where it is and becomes the kind's primary secret field,
+/// so nothing has to be re-encrypted.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_column("credentials", "kind").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Credentials::Table)
+                        .add_column(
+                            ColumnDef::new(Credentials::Kind)
+                                .string()
+                                .not_null()
+                                .default("generic"),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        if !manager.has_column("credentials", "public_fields").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Credentials::Table)
+                        .add_column(
+                            ColumnDef::new(Credentials::PublicFields)
+                                .text()
+                                .not_null()
+                                .default("{}"),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        let conn = manager.get_connection();
+        let backend = manager.get_database_backend();
+        let mappings = [
+            ("api_key", "'api_key', 'api_token', 'token'"),
+            ("ssh_key", "'ssh_key', 'ssh'"),
+            ("database_dsn", "'database', 'database_dsn', 'dsn'"),
+            ("oauth_token", "'oauth_token', 'oauth', 'github_token'"),
+            ("certificate", "'certificate', 'cert', 'tls'"),
+        ];
+        for (kind, types) in mappings {
+            conn.execute(Statement::from_string(
+                backend,
+                format!(
+                    "UPDATE credentials SET kind = '{}' WHERE lower(credential_type) IN ({})",
+                    kind, types
+                ),
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Credentials::PublicFields, Credentials::Kind] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Credentials::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Credentials {
+    Table,
+    Kind,
+    PublicFields,
+}
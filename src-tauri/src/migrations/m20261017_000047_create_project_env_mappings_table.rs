@@ -0,0 +1,122 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create project env mappings table
+///
+/// Maps credentials to environment variable names per project, for generating
+/// .env files and injecting secrets into terminals and pipeline runs:
+/// - id: Primary key (auto-increment)
+/// - project_id: Project the variable belongs to
+/// - env_var: Environment variable name, unique per project
+/// - credential_id: Credential supplying the value
+/// - field: Credential field to read; NULL means the kind's primary secret
+/// - created_at / updated_at: Timestamps
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectEnvMappings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectEnvMappings::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectEnvMappings::ProjectId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectEnvMappings::EnvVar)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectEnvMappings::CredentialId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ProjectEnvMappings::Field).string().null())
+                    .col(
+                        ColumnDef::new(ProjectEnvMappings::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectEnvMappings::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_env_mappings_project_id")
+                            .from(ProjectEnvMappings::Table, ProjectEnvMappings::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_env_mappings_credential_id")
+                            .from(ProjectEnvMappings::Table, ProjectEnvMappings::CredentialId)
+                            .to(Credentials::Table, Credentials::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_env_mappings_project_var")
+                    .table(ProjectEnvMappings::Table)
+                    .col(ProjectEnvMappings::ProjectId)
+                    .col(ProjectEnvMappings::EnvVar)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectEnvMappings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectEnvMappings {
+    Table,
+    Id,
+    ProjectId,
+    EnvVar,
+    CredentialId,
+    Field,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Credentials {
+    Table,
+    Id,
+}
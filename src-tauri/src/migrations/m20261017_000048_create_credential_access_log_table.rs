@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create credential access log table
+///
+/// Append-only audit trail of secret decryption:
+/// - id: Primary key (auto-increment)
+/// - credential_id: Credential that was decrypted (kept after the credential is deleted)
+/// - credential_name: Name at the time of access
+/// - accessor: Part of the app that asked for the secret (user, pipeline, terminal, ...)
+/// - action: `decrypt_value` or `decrypt_fields`
+/// - success: Whether the secret was handed out
+/// - error: Why it wasn't, e.g. the vault was locked
+/// - accessed_at: When it happened
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CredentialAccessLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CredentialAccessLog::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CredentialAccessLog::CredentialId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CredentialAccessLog::CredentialName)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(CredentialAccessLog::Accessor)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CredentialAccessLog::Action)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CredentialAccessLog::Success)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CredentialAccessLog::Error).text().null())
+                    .col(
+                        ColumnDef::new(CredentialAccessLog::AccessedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_credential_access_log_credential")
+                    .table(CredentialAccessLog::Table)
+                    .col(CredentialAccessLog::CredentialId)
+                    .col(CredentialAccessLog::AccessedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_credential_access_log_accessed_at")
+                    .table(CredentialAccessLog::Table)
+                    .col(CredentialAccessLog::AccessedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CredentialAccessLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CredentialAccessLog {
+    Table,
+    Id,
+    CredentialId,
+    CredentialName,
+    Accessor,
+    Action,
+    Success,
+    Error,
+    AccessedAt,
+}
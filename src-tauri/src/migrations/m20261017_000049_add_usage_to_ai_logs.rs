@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            (
+                "model",
+                ColumnDef::new(AiLogs::Model).string().null().to_owned(),
+            ),
+            (
+                "feature",
+                ColumnDef::new(AiLogs::Feature).string().null().to_owned(),
+            ),
+            (
+                "prompt_tokens",
+                ColumnDef::new(AiLogs::PromptTokens)
+                    .integer()
+                    .null()
+                    .to_owned(),
+            ),
+            (
+                "completion_tokens",
+                ColumnDef::new(AiLogs::CompletionTokens)
+                    .integer()
+                    .null()
+                    .to_owned(),
+            ),
+            (
+                "estimated_cost_usd",
+                ColumnDef::new(AiLogs::EstimatedCostUsd)
+                    .double()
+                    .null()
+                    .to_owned(),
+            ),
+        ];
+
+        // SQLite only allows one column per ALTER TABLE
+        for (name, mut column) in columns {
+            if !manager.has_column("ai_logs", name).await? {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(AiLogs::Table)
+                            .add_column(&mut column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_ai_logs_feature")
+                    .table(AiLogs::Table)
+                    .col(AiLogs::Feature)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_ai_logs_feature")
+                    .table(AiLogs::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        for (column, name) in [
+            (AiLogs::Model, "model"),
+            (AiLogs::Feature, "feature"),
+            (AiLogs::PromptTokens, "prompt_tokens"),
+            (AiLogs::CompletionTokens, "completion_tokens"),
+            (AiLogs::EstimatedCostUsd, "estimated_cost_usd"),
+        ] {
+            if manager.has_column("ai_logs", name).await? {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(AiLogs::Table)
+                            .drop_column(column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AiLogs {
+    Table,
+    Model,
+    Feature,
+    PromptTokens,
+    CompletionTokens,
+    EstimatedCostUsd,
+}
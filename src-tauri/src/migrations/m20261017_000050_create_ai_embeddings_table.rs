@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create AI embeddings table
+///
+/// Generic vector store for semantic search across the app:
+/// - namespace: What the item is (`tasks`, `documents`, `learning`, ...)
+/// - item_id: Id of the item within its namespace
+/// - content_hash: Hash of the embedded text, so unchanged items aren't re-embedded
+/// - content: Embedded text (truncated), returned with search results
+/// - metadata: Optional JSON passed through to search results
+/// - embedding: Little-endian f32 vector
+/// - model / dimensions: Embedding model and vector length
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AiEmbeddings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AiEmbeddings::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AiEmbeddings::Namespace).text().not_null())
+                    .col(ColumnDef::new(AiEmbeddings::ItemId).text().not_null())
+                    .col(ColumnDef::new(AiEmbeddings::ContentHash).text().not_null())
+                    .col(ColumnDef::new(AiEmbeddings::Content).text().not_null())
+                    .col(ColumnDef::new(AiEmbeddings::Metadata).text().null())
+                    .col(ColumnDef::new(AiEmbeddings::Embedding).binary().not_null())
+                    .col(ColumnDef::new(AiEmbeddings::Model).text().not_null())
+                    .col(
+                        ColumnDef::new(AiEmbeddings::Dimensions)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AiEmbeddings::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ai_embeddings_item")
+                    .table(AiEmbeddings::Table)
+                    .col(AiEmbeddings::Namespace)
+                    .col(AiEmbeddings::ItemId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ai_embeddings_model")
+                    .table(AiEmbeddings::Table)
+                    .col(AiEmbeddings::Namespace)
+                    .col(AiEmbeddings::Model)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AiEmbeddings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AiEmbeddings {
+    Table,
+    Id,
+    Namespace,
+    ItemId,
+    ContentHash,
+    Content,
+    Metadata,
+    Embedding,
+    Model,
+    Dimensions,
+    UpdatedAt,
+}
@@ -0,0 +1,183 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create AI prompt template tables
+///
+/// ai_prompt_templates holds the current revision of each template:
+/// - system_prompt / user_prompt: Text with `{{variable}}` placeholders
+/// - variables: JSON array of declared variables (name, description, default, required)
+/// - tags: JSON array of use-case tags
+/// - version: Current revision number, bumped on every edit
+///
+/// ai_prompt_template_versions keeps a snapshot of every revision so earlier
+/// versions can be referenced or restored.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AiPromptTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AiPromptTemplates::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AiPromptTemplates::Name).string().not_null())
+                    .col(ColumnDef::new(AiPromptTemplates::Description).text().null())
+                    .col(
+                        ColumnDef::new(AiPromptTemplates::SystemPrompt)
+                            .text()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(AiPromptTemplates::UserPrompt).text().null())
+                    .col(
+                        ColumnDef::new(AiPromptTemplates::Variables)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(ColumnDef::new(AiPromptTemplates::Tags).text().null())
+                    .col(
+                        ColumnDef::new(AiPromptTemplates::Version)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplates::CreatedAt)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplates::UpdatedAt)
+                            .string()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AiPromptTemplateVersions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AiPromptTemplateVersions::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplateVersions::TemplateId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplateVersions::Version)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplateVersions::SystemPrompt)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplateVersions::UserPrompt)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplateVersions::Variables)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplateVersions::ChangeNote)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(AiPromptTemplateVersions::CreatedAt)
+                            .string()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ai_prompt_template_versions_template_id")
+                            .from(
+                                AiPromptTemplateVersions::Table,
+                                AiPromptTemplateVersions::TemplateId,
+                            )
+                            .to(AiPromptTemplates::Table, AiPromptTemplates::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ai_prompt_template_versions_version")
+                    .table(AiPromptTemplateVersions::Table)
+                    .col(AiPromptTemplateVersions::TemplateId)
+                    .col(AiPromptTemplateVersions::Version)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(AiPromptTemplateVersions::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(AiPromptTemplates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AiPromptTemplates {
+    Table,
+    Id,
+    Name,
+    Description,
+    SystemPrompt,
+    UserPrompt,
+    Variables,
+    Tags,
+    Version,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum AiPromptTemplateVersions {
+    Table,
+    Id,
+    TemplateId,
+    Version,
+    SystemPrompt,
+    UserPrompt,
+    Variables,
+    ChangeNote,
+    CreatedAt,
+}
@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Track where forked AI conversations branched off
+///
+/// - parent_conversation_id: Conversation this one was forked from
+/// - forked_from_message_id: Last message of the parent shared with the fork
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            (
+                "parent_conversation_id",
+                ColumnDef::new(AiConversations::ParentConversationId)
+                    .string()
+                    .null()
+                    .to_owned(),
+            ),
+            (
+                "forked_from_message_id",
+                ColumnDef::new(AiConversations::ForkedFromMessageId)
+                    .string()
+                    .null()
+                    .to_owned(),
+            ),
+        ];
+
+        // SQLite only allows one column per ALTER TABLE
+        for (name, mut column) in columns {
+            if !manager.has_column("ai_conversations", name).await? {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(AiConversations::Table)
+                            .add_column(&mut column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_ai_conversations_parent")
+                    .table(AiConversations::Table)
+                    .col(AiConversations::ParentConversationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_ai_conversations_parent")
+                    .table(AiConversations::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        for (column, name) in [
+            (
+                AiConversations::ParentConversationId,
+                "parent_conversation_id",
+            ),
+            (
+                AiConversations::ForkedFromMessageId,
+                "forked_from_message_id",
+            ),
+        ] {
+            if manager.has_column("ai_conversations", name).await? {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(AiConversations::Table)
+                            .drop_column(column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AiConversations {
+    Table,
+    ParentConversationId,
+    ForkedFromMessageId,
+}
@@ -0,0 +1,128 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create project n8n workflows table
+///
+/// Workflows pushed to n8n from local templates on behalf of a project, so the
+/// project can trigger them and remove them when it is deleted:
+/// - id: Primary key (auto-increment)
+/// - project_id: Project that owns the workflow
+/// - n8n_workflow_id: Workflow id in n8n, unique per project
+/// - name: Name the workflow was created under
+/// - template: Template file the workflow was created from
+/// - webhook_urls: JSON array of the workflow's webhook URLs
+/// - active: Whether the workflow was activated in n8n
+/// - created_at / updated_at: Timestamps
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectN8nWorkflows::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::ProjectId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::N8nWorkflowId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::Name)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::Template)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::WebhookUrls)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::Active)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectN8nWorkflows::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_n8n_workflows_project_id")
+                            .from(ProjectN8nWorkflows::Table, ProjectN8nWorkflows::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_n8n_workflows_project_workflow")
+                    .table(ProjectN8nWorkflows::Table)
+                    .col(ProjectN8nWorkflows::ProjectId)
+                    .col(ProjectN8nWorkflows::N8nWorkflowId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectN8nWorkflows::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectN8nWorkflows {
+    Table,
+    Id,
+    ProjectId,
+    N8nWorkflowId,
+    Name,
+    Template,
+    WebhookUrls,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
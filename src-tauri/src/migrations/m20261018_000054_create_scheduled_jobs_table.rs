@@ -0,0 +1,119 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create scheduled jobs table
+///
+/// Jobs the automation scheduler runs on a cron expression, once at a time,
+/// or on an interval:
+/// - id: Primary key (auto-increment)
+/// - name: Display name
+/// - action: Registered app action to invoke, e.g. `run_pipeline`
+/// - args: JSON arguments for the action
+/// - schedule: JSON schedule (cron, at or interval)
+/// - misfire_policy: What to do with a run missed while the app was closed
+/// - enabled: Whether the job is scheduled
+/// - next_run_at: When it runs next; NULL once a one-shot job has run
+/// - last_run_at / last_status / last_error: Outcome of the latest run
+/// - created_at / updated_at: Timestamps
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScheduledJobs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ScheduledJobs::Name).string().not_null())
+                    .col(ColumnDef::new(ScheduledJobs::Action).string().not_null())
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Args)
+                            .text()
+                            .not_null()
+                            .default("{}"),
+                    )
+                    .col(ColumnDef::new(ScheduledJobs::Schedule).text().not_null())
+                    .col(
+                        ColumnDef::new(ScheduledJobs::MisfirePolicy)
+                            .string()
+                            .not_null()
+                            .default("run_once"),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::NextRunAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::LastRunAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ScheduledJobs::LastStatus).string().null())
+                    .col(ColumnDef::new(ScheduledJobs::LastError).text().null())
+                    .col(
+                        ColumnDef::new(ScheduledJobs::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ScheduledJobs::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_scheduled_jobs_next_run_at")
+                    .table(ScheduledJobs::Table)
+                    .col(ScheduledJobs::NextRunAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScheduledJobs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScheduledJobs {
+    Table,
+    Id,
+    Name,
+    Action,
+    Args,
+    Schedule,
+    MisfirePolicy,
+    Enabled,
+    NextRunAt,
+    LastRunAt,
+    LastStatus,
+    LastError,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create notifications table
+///
+/// Notifications raised by workflows, pipelines and scheduled jobs, kept for
+/// the notification center:
+/// - id: Primary key (auto-increment)
+/// - title / body: What was shown
+/// - level: info, success, warning or error
+/// - source: What raised it, e.g. `workflow:deploy`
+/// - link: In-app route opened when the notification is clicked
+/// - dismissed: Whether it was dismissed from the notification center
+/// - created_at: When it was raised
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notifications::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Notifications::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Notifications::Title).string().not_null())
+                    .col(ColumnDef::new(Notifications::Body).text().null())
+                    .col(
+                        ColumnDef::new(Notifications::Level)
+                            .string()
+                            .not_null()
+                            .default("info"),
+                    )
+                    .col(ColumnDef::new(Notifications::Source).string().null())
+                    .col(ColumnDef::new(Notifications::Link).string().null())
+                    .col(
+                        ColumnDef::new(Notifications::Dismissed)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(Notifications::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notifications_created_at")
+                    .table(Notifications::Table)
+                    .col(Notifications::CreatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Notifications::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Notifications {
+    Table,
+    Id,
+    Title,
+    Body,
+    Level,
+    Source,
+    Link,
+    Dismissed,
+    CreatedAt,
+}
@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Custom script triggers and run history
+///
+/// - custom_scripts.event_triggers_json: JSON array of app events that run the
+///   script
+/// - script_executions.custom_script_id: Custom script a run belongs to, for
+///   each script's execution history
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager
+            .has_column("custom_scripts", "event_triggers_json")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CustomScripts::Table)
+                        .add_column(
+                            ColumnDef::new(CustomScripts::EventTriggersJson)
+                                .text()
+                                .not_null()
+                                .default("[]"),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        if !manager
+            .has_column("script_executions", "custom_script_id")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(ScriptExecutions::Table)
+                        .add_column(
+                            ColumnDef::new(ScriptExecutions::CustomScriptId)
+                                .integer()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_script_executions_custom_script_id")
+                    .table(ScriptExecutions::Table)
+                    .col(ScriptExecutions::CustomScriptId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_script_executions_custom_script_id")
+                    .table(ScriptExecutions::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ScriptExecutions::Table)
+                    .drop_column(ScriptExecutions::CustomScriptId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CustomScripts::Table)
+                    .drop_column(CustomScripts::EventTriggersJson)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CustomScripts {
+    Table,
+    EventTriggersJson,
+}
+
+#[derive(DeriveIden)]
+enum ScriptExecutions {
+    Table,
+    CustomScriptId,
+}
@@ -0,0 +1,186 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create script_libraries table and link custom scripts to it
+///
+/// Git repositories holding a team's script library:
+/// - id: Primary key (auto-increment)
+/// - name: Display name
+/// - repository_url: Where to clone it from
+/// - branch: Branch to follow; the remote's default when null
+/// - manifest_path: Manifest listing the scripts, relative to the repo root
+/// - last_commit: Commit the scripts were last synced from
+/// - last_synced_at / last_error: Outcome of the last sync
+/// - created_at: When the library was added
+///
+/// custom_scripts gains:
+/// - library_id / library_key: Library and manifest entry a script was
+///   synced from; null for local scripts
+/// - upstream_json: The manifest entry as last synced, to tell local edits
+///   apart
+/// - pending_upstream_json: An upstream change held back because the script
+///   was also edited locally
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScriptLibraries::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScriptLibraries::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ScriptLibraries::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(ScriptLibraries::RepositoryUrl)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ScriptLibraries::Branch).string().null())
+                    .col(
+                        ColumnDef::new(ScriptLibraries::ManifestPath)
+                            .string()
+                            .not_null()
+                            .default("portal-scripts.yaml"),
+                    )
+                    .col(ColumnDef::new(ScriptLibraries::LastCommit).string().null())
+                    .col(
+                        ColumnDef::new(ScriptLibraries::LastSyncedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(ScriptLibraries::LastError).text().null())
+                    .col(
+                        ColumnDef::new(ScriptLibraries::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        if !manager.has_column("custom_scripts", "library_id").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CustomScripts::Table)
+                        .add_column(ColumnDef::new(CustomScripts::LibraryId).integer().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if !manager.has_column("custom_scripts", "library_key").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CustomScripts::Table)
+                        .add_column(ColumnDef::new(CustomScripts::LibraryKey).string().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if !manager
+            .has_column("custom_scripts", "upstream_json")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CustomScripts::Table)
+                        .add_column(ColumnDef::new(CustomScripts::UpstreamJson).text().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if !manager
+            .has_column("custom_scripts", "pending_upstream_json")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CustomScripts::Table)
+                        .add_column(
+                            ColumnDef::new(CustomScripts::PendingUpstreamJson)
+                                .text()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_custom_scripts_library")
+                    .table(CustomScripts::Table)
+                    .col(CustomScripts::LibraryId)
+                    .col(CustomScripts::LibraryKey)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_custom_scripts_library")
+                    .table(CustomScripts::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [
+            CustomScripts::PendingUpstreamJson,
+            CustomScripts::UpstreamJson,
+            CustomScripts::LibraryKey,
+            CustomScripts::LibraryId,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CustomScripts::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(ScriptLibraries::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScriptLibraries {
+    Table,
+    Id,
+    Name,
+    RepositoryUrl,
+    Branch,
+    ManifestPath,
+    LastCommit,
+    LastSyncedAt,
+    LastError,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum CustomScripts {
+    Table,
+    LibraryId,
+    LibraryKey,
+    UpstreamJson,
+    PendingUpstreamJson,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Custom script runtimes
+///
+/// - custom_scripts.runtime: What runs the script: shell, bash, powershell,
+///   python or node
+/// - custom_scripts.runtime_version: Version of the Python or Node runtime,
+///   as a prefix ("3.12", "20"); the newest installed when null
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_column("custom_scripts", "runtime").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CustomScripts::Table)
+                        .add_column(
+                            ColumnDef::new(CustomScripts::Runtime)
+                                .string()
+                                .not_null()
+                                .default("shell"),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if !manager
+            .has_column("custom_scripts", "runtime_version")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CustomScripts::Table)
+                        .add_column(
+                            ColumnDef::new(CustomScripts::RuntimeVersion)
+                                .string()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CustomScripts::Table)
+                    .drop_column(CustomScripts::RuntimeVersion)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CustomScripts::Table)
+                    .drop_column(CustomScripts::Runtime)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CustomScripts {
+    Table,
+    Runtime,
+    RuntimeVersion,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create project_settings table
+///
+/// Per-project overrides of global settings, used when working in that
+/// project. A null column falls back to the global value:
+/// - project_id: Project the overrides apply to (one row per project)
+/// - ide_id: IDE to open the project with instead of the default one
+/// - shell: Shell for the project's terminals
+/// - llm_provider / ai_model: Backend and model for the project's agent threads
+/// - max_concurrent_executions: Pipeline executions allowed to run at once
+/// - updated_at: When the overrides last changed
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectSettings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectSettings::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectSettings::ProjectId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ProjectSettings::IdeId).integer().null())
+                    .col(ColumnDef::new(ProjectSettings::Shell).string().null())
+                    .col(ColumnDef::new(ProjectSettings::LlmProvider).string().null())
+                    .col(ColumnDef::new(ProjectSettings::AiModel).string().null())
+                    .col(
+                        ColumnDef::new(ProjectSettings::MaxConcurrentExecutions)
+                            .integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectSettings::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_settings_project_id")
+                            .from(ProjectSettings::Table, ProjectSettings::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectSettings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectSettings {
+    Table,
+    Id,
+    ProjectId,
+    IdeId,
+    Shell,
+    LlmProvider,
+    AiModel,
+    MaxConcurrentExecutions,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create keybindings table
+///
+/// User overrides of the built-in keyboard shortcuts. Actions without a row
+/// keep their default shortcut:
+/// - action: Action the shortcut triggers (one row per action)
+/// - shortcut: Key chord, e.g. "CmdOrCtrl+Shift+P"; null disables the action
+/// - updated_at: When the override last changed
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Keybindings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Keybindings::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Keybindings::Action)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Keybindings::Shortcut).string().null())
+                    .col(
+                        ColumnDef::new(Keybindings::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Keybindings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Keybindings {
+    Table,
+    Id,
+    Action,
+    Shortcut,
+    UpdatedAt,
+}
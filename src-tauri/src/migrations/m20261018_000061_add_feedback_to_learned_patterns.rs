@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Suggestion feedback on learned patterns
+///
+/// - learned_patterns.weight: Multiplier from accepted and rejected
+///   suggestions, applied to the pattern's score and confidence
+/// - learned_patterns.snoozed_until: The pattern isn't suggested before this
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_column("learned_patterns", "weight").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(LearnedPatterns::Table)
+                        .add_column(
+                            ColumnDef::new(LearnedPatterns::Weight)
+                                .double()
+                                .not_null()
+                                .default(1.0),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if !manager
+            .has_column("learned_patterns", "snoozed_until")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(LearnedPatterns::Table)
+                        .add_column(
+                            ColumnDef::new(LearnedPatterns::SnoozedUntil)
+                                .timestamp_with_time_zone()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LearnedPatterns::Table)
+                    .drop_column(LearnedPatterns::SnoozedUntil)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LearnedPatterns::Table)
+                    .drop_column(LearnedPatterns::Weight)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LearnedPatterns {
+    Table,
+    Weight,
+    SnoozedUntil,
+}
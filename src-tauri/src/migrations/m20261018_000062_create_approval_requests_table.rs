@@ -0,0 +1,124 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create approval_requests table
+///
+/// Autonomous actions waiting on the user, kept so they survive restarts:
+/// - id: Tool call id the request is for
+/// - action_type / context: What the autonomy domain evaluated
+/// - description / safety_level / reason: Shown with the request
+/// - arguments_json: Arguments of the call
+/// - preview_json: Simulated effects of the call, when it has a preview
+/// - run_json: The paused chat run, resumed once the request is resolved
+/// - status: pending, approved, rejected or expired
+/// - feedback: What the user said when resolving it
+/// - expires_at: When a pending request expires
+/// - resolved_at: When it was approved, rejected or expired
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApprovalRequests::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApprovalRequests::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ApprovalRequests::ActionType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApprovalRequests::Context)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApprovalRequests::Description)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApprovalRequests::SafetyLevel)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApprovalRequests::Reason).text().not_null())
+                    .col(
+                        ColumnDef::new(ApprovalRequests::ArgumentsJson)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApprovalRequests::PreviewJson).text().null())
+                    .col(ColumnDef::new(ApprovalRequests::RunJson).text().not_null())
+                    .col(
+                        ColumnDef::new(ApprovalRequests::Status)
+                            .string()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(ColumnDef::new(ApprovalRequests::Feedback).text().null())
+                    .col(
+                        ColumnDef::new(ApprovalRequests::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ApprovalRequests::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApprovalRequests::ResolvedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_approval_requests_status")
+                    .table(ApprovalRequests::Table)
+                    .col(ApprovalRequests::Status)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApprovalRequests::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApprovalRequests {
+    Table,
+    Id,
+    ActionType,
+    Context,
+    Description,
+    SafetyLevel,
+    Reason,
+    ArgumentsJson,
+    PreviewJson,
+    RunJson,
+    Status,
+    Feedback,
+    CreatedAt,
+    ExpiresAt,
+    ResolvedAt,
+}
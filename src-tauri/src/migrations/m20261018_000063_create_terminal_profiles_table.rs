@@ -0,0 +1,97 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create terminal_profiles table
+///
+/// User-defined shell profiles, alongside the built-in ones for each OS:
+/// - os: OS the profile was made on; only that OS's profiles are listed
+/// - shell / args_json: Binary to start and its arguments
+/// - startup_script: Typed into the shell once it starts
+/// - env_json: Extra environment variables
+/// - icon: Shell icon shown with the profile
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TerminalProfiles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TerminalProfiles::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TerminalProfiles::Name).string().not_null())
+                    .col(ColumnDef::new(TerminalProfiles::Os).string().not_null())
+                    .col(ColumnDef::new(TerminalProfiles::Shell).string().not_null())
+                    .col(
+                        ColumnDef::new(TerminalProfiles::ArgsJson)
+                            .text()
+                            .not_null()
+                            .default("[]"),
+                    )
+                    .col(
+                        ColumnDef::new(TerminalProfiles::StartupScript)
+                            .text()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TerminalProfiles::EnvJson)
+                            .text()
+                            .not_null()
+                            .default("{}"),
+                    )
+                    .col(ColumnDef::new(TerminalProfiles::Icon).string().null())
+                    .col(
+                        ColumnDef::new(TerminalProfiles::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(TerminalProfiles::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_terminal_profiles_os")
+                    .table(TerminalProfiles::Table)
+                    .col(TerminalProfiles::Os)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TerminalProfiles::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TerminalProfiles {
+    Table,
+    Id,
+    Name,
+    Os,
+    Shell,
+    ArgsJson,
+    StartupScript,
+    EnvJson,
+    Icon,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create port_forwards table
+///
+/// Port forwards the user saved, so they can be started again later:
+/// - cluster: Cluster the forward was saved on
+/// - target_kind / target_name: What's forwarded to, e.g. deployment/web;
+///   workloads and services are resolved to a pod each time it starts
+/// - auto_restore: Start it on connecting to the cluster and restart it
+///   when its pod is replaced
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PortForwards::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PortForwards::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PortForwards::Cluster).string().not_null())
+                    .col(ColumnDef::new(PortForwards::Namespace).string().not_null())
+                    .col(ColumnDef::new(PortForwards::TargetKind).string().not_null())
+                    .col(ColumnDef::new(PortForwards::TargetName).string().not_null())
+                    .col(ColumnDef::new(PortForwards::LocalPort).integer().not_null())
+                    .col(
+                        ColumnDef::new(PortForwards::RemotePort)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PortForwards::AutoRestore)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(PortForwards::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(PortForwards::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_port_forwards_cluster")
+                    .table(PortForwards::Table)
+                    .col(PortForwards::Cluster)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PortForwards::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PortForwards {
+    Table,
+    Id,
+    Cluster,
+    Namespace,
+    TargetKind,
+    TargetName,
+    LocalPort,
+    RemotePort,
+    AutoRestore,
+    CreatedAt,
+    UpdatedAt,
+}
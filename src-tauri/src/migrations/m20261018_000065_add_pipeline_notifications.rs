@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Add notifications_json to pipelines
+///
+/// - pipelines.notifications_json: JSON array of hooks (desktop notification,
+///   custom script, embedded workflow, webhook) run when an execution finishes
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager
+            .has_column("pipelines", "notifications_json")
+            .await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Pipelines::Table)
+                        .add_column(
+                            ColumnDef::new(Pipelines::NotificationsJson)
+                                .text()
+                                .not_null()
+                                .default("[]"),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Pipelines::Table)
+                    .drop_column(Pipelines::NotificationsJson)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Pipelines {
+    Table,
+    NotificationsJson,
+}
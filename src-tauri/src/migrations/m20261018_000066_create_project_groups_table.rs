@@ -0,0 +1,152 @@
+use sea_orm_migration::prelude::*;
+
+/// Migration: Create project_groups table and group projects
+///
+/// Groups organize projects into a hierarchy:
+/// - name: Group name
+/// - kind: client, team or personal
+/// - parent_id: Group it's nested in; null at the top level
+/// - color: Optional color for the sidebar
+/// - position: Order among its siblings, set by drag and drop
+///
+/// And on projects:
+/// - group_id: Group the project is in; null when ungrouped
+/// - position: Order among the projects of its group
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectGroups::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectGroups::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ProjectGroups::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(ProjectGroups::Kind)
+                            .string()
+                            .not_null()
+                            .default("personal"),
+                    )
+                    .col(ColumnDef::new(ProjectGroups::ParentId).integer().null())
+                    .col(ColumnDef::new(ProjectGroups::Color).string().null())
+                    .col(
+                        ColumnDef::new(ProjectGroups::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectGroups::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ProjectGroups::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_groups_parent_id")
+                            .from(ProjectGroups::Table, ProjectGroups::ParentId)
+                            .to(ProjectGroups::Table, ProjectGroups::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // SQLite adds one column per ALTER TABLE
+        if !manager.has_column("projects", "group_id").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Projects::Table)
+                        .add_column(ColumnDef::new(Projects::GroupId).integer().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if !manager.has_column("projects", "position").await? {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Projects::Table)
+                        .add_column(
+                            ColumnDef::new(Projects::Position)
+                                .integer()
+                                .not_null()
+                                .default(0),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_projects_group_id")
+                    .table(Projects::Table)
+                    .col(Projects::GroupId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_projects_group_id")
+                    .table(Projects::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [Projects::GroupId, Projects::Position] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Projects::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(ProjectGroups::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectGroups {
+    Table,
+    Id,
+    Name,
+    Kind,
+    ParentId,
+    Color,
+    Position,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    GroupId,
+    Position,
+}
@@ -38,6 +38,34 @@ pub mod m20260707_000035_add_llm_provider_to_coder_threads;
 pub mod m20260708_000036_create_github_tables;
 pub mod m20260708_000037_add_coder_multitask_tables;
 pub mod m20260708_000038_add_project_id_to_coder_threads;
+pub mod m20261017_000039_add_content_hash_to_task_attachments;
+pub mod m20261017_000040_create_documents_fts;
+pub mod m20261017_000041_create_document_revisions_table;
+pub mod m20261017_000042_create_document_sync_tables;
+pub mod m20261017_000043_create_document_templates_table;
+pub mod m20261017_000044_create_document_links_table;
+pub mod m20261017_000045_create_document_chunks_table;
+pub mod m20261017_000046_add_credential_kinds;
+pub mod m20261017_000047_create_project_env_mappings_table;
+pub mod m20261017_000048_create_credential_access_log_table;
+pub mod m20261017_000049_add_usage_to_ai_logs;
+pub mod m20261017_000050_create_ai_embeddings_table;
+pub mod m20261017_000051_create_ai_prompt_templates_tables;
+pub mod m20261017_000052_add_branching_to_ai_conversations;
+pub mod m20261018_000053_create_project_n8n_workflows_table;
+pub mod m20261018_000054_create_scheduled_jobs_table;
+pub mod m20261018_000055_create_notifications_table;
+pub mod m20261018_000056_add_custom_script_triggers;
+pub mod m20261018_000057_create_script_libraries;
+pub mod m20261018_000058_add_custom_script_runtime;
+pub mod m20261018_000059_create_project_settings_table;
+pub mod m20261018_000060_create_keybindings_table;
+pub mod m20261018_000061_add_feedback_to_learned_patterns;
+pub mod m20261018_000062_create_approval_requests_table;
+pub mod m20261018_000063_create_terminal_profiles_table;
+pub mod m20261018_000064_create_port_forwards_table;
+pub mod m20261018_000065_add_pipeline_notifications;
+pub mod m20261018_000066_create_project_groups_table;
 pub mod runner;
 
 // Re-export all migrations for easy access
@@ -79,6 +107,34 @@ pub use m20260707_000035_add_llm_provider_to_coder_threads::Migration as addLlmP
 pub use m20260708_000036_create_github_tables::Migration as createGithubTables;
 pub use m20260708_000037_add_coder_multitask_tables::Migration as addCoderMultitaskTables;
 pub use m20260708_000038_add_project_id_to_coder_threads::Migration as addProjectIdToCoderThreads;
+pub use m20261017_000039_add_content_hash_to_task_attachments::Migration as addContentHashToTaskAttachments;
+pub use m20261017_000040_create_documents_fts::Migration as createDocumentsFts;
+pub use m20261017_000041_create_document_revisions_table::Migration as createDocumentRevisionsTable;
+pub use m20261017_000042_create_document_sync_tables::Migration as createDocumentSyncTables;
+pub use m20261017_000043_create_document_templates_table::Migration as createDocumentTemplatesTable;
+pub use m20261017_000044_create_document_links_table::Migration as createDocumentLinksTable;
+pub use m20261017_000045_create_document_chunks_table::Migration as createDocumentChunksTable;
+pub use m20261017_000046_add_credential_kinds::Migration as addCredentialKinds;
+pub use m20261017_000047_create_project_env_mappings_table::Migration as createProjectEnvMappingsTable;
+pub use m20261017_000048_create_credential_access_log_table::Migration as createCredentialAccessLogTable;
+pub use m20261017_000049_add_usage_to_ai_logs::Migration as addUsageToAiLogs;
+pub use m20261017_000050_create_ai_embeddings_table::Migration as createAiEmbeddingsTable;
+pub use m20261017_000051_create_ai_prompt_templates_tables::Migration as createAiPromptTemplatesTables;
+pub use m20261017_000052_add_branching_to_ai_conversations::Migration as addBranchingToAiConversations;
+pub use m20261018_000053_create_project_n8n_workflows_table::Migration as createProjectN8nWorkflowsTable;
+pub use m20261018_000054_create_scheduled_jobs_table::Migration as createScheduledJobsTable;
+pub use m20261018_000055_create_notifications_table::Migration as createNotificationsTable;
+pub use m20261018_000056_add_custom_script_triggers::Migration as addCustomScriptTriggers;
+pub use m20261018_000057_create_script_libraries::Migration as createScriptLibraries;
+pub use m20261018_000058_add_custom_script_runtime::Migration as addCustomScriptRuntime;
+pub use m20261018_000059_create_project_settings_table::Migration as createProjectSettingsTable;
+pub use m20261018_000060_create_keybindings_table::Migration as createKeybindingsTable;
+pub use m20261018_000061_add_feedback_to_learned_patterns::Migration as addFeedbackToLearnedPatterns;
+pub use m20261018_000062_create_approval_requests_table::Migration as createApprovalRequestsTable;
+pub use m20261018_000063_create_terminal_profiles_table::Migration as createTerminalProfilesTable;
+pub use m20261018_000064_create_port_forwards_table::Migration as createPortForwardsTable;
+pub use m20261018_000065_add_pipeline_notifications::Migration as addPipelineNotifications;
+pub use m20261018_000066_create_project_groups_table::Migration as createProjectGroupsTable;
 
 pub struct Migrator;
 
@@ -154,5 +210,33 @@ pub fn get_migrations() -> Vec<Box<dyn MigrationTrait>> {
         Box::new(createGithubTables),
         Box::new(addCoderMultitaskTables),
         Box::new(addProjectIdToCoderThreads),
+        Box::new(addContentHashToTaskAttachments),
+        Box::new(createDocumentsFts),
+        Box::new(createDocumentRevisionsTable),
+        Box::new(createDocumentSyncTables),
+        Box::new(createDocumentTemplatesTable),
+        Box::new(createDocumentLinksTable),
+        Box::new(createDocumentChunksTable),
+        Box::new(addCredentialKinds),
+        Box::new(createProjectEnvMappingsTable),
+        Box::new(createCredentialAccessLogTable),
+        Box::new(addUsageToAiLogs),
+        Box::new(createAiEmbeddingsTable),
+        Box::new(createAiPromptTemplatesTables),
+        Box::new(addBranchingToAiConversations),
+        Box::new(createProjectN8nWorkflowsTable),
+        Box::new(createScheduledJobsTable),
+        Box::new(createNotificationsTable),
+        Box::new(addCustomScriptTriggers),
+        Box::new(createScriptLibraries),
+        Box::new(addCustomScriptRuntime),
+        Box::new(createProjectSettingsTable),
+        Box::new(createKeybindingsTable),
+        Box::new(addFeedbackToLearnedPatterns),
+        Box::new(createApprovalRequestsTable),
+        Box::new(createTerminalProfilesTable),
+        Box::new(createPortForwardsTable),
+        Box::new(addPipelineNotifications),
+        Box::new(createProjectGroupsTable),
     ]
 }
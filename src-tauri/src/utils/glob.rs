@@ -0,0 +1,43 @@
+//! Glob matching for `/`-separated relative paths, shared by pipeline step
+//! caching and project file scanning.
+
+/// Match a `/`-separated path against a glob. `*` and `?` stay within a path
+/// segment; `**` spans any number of segments.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    fn segments(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|skip| segments(rest, &path[skip..])),
+            Some((first, rest)) => path.split_first().is_some_and(|(name, path)| {
+                segment(first.as_bytes(), name.as_bytes()) && segments(rest, path)
+            }),
+        }
+    }
+    fn segment(pattern: &[u8], name: &[u8]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some((b'*', rest)) => (0..=name.len()).any(|skip| segment(rest, &name[skip..])),
+            Some((b'?', rest)) => !name.is_empty() && segment(rest, &name[1..]),
+            Some((c, rest)) => name.first() == Some(c) && segment(rest, &name[1..]),
+        }
+    }
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    segments(&pattern, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs_match_within_and_across_segments() {
+        assert!(glob_match("src/*.rs", "src/main.rs"));
+        assert!(!glob_match("src/*.rs", "src/bin/main.rs"));
+        assert!(glob_match("src/**/*.rs", "src/main.rs"));
+        assert!(glob_match("src/**/*.rs", "src/bin/tool/main.rs"));
+        assert!(glob_match("**/package.json", "package.json"));
+        assert!(glob_match("v?.txt", "v1.txt"));
+        assert!(!glob_match("v?.txt", "v10.txt"));
+    }
+}
@@ -0,0 +1,301 @@
+//! Persistence for backend logs.
+//!
+//! Every record is kept in an in-memory ring buffer for querying and appended
+//! as a JSON line to `portal.log` in the log directory. The file rotates at
+//! `MAX_FILE_BYTES`, keeping `MAX_FILES` files (`portal.log`, `portal.1.log`,
+//! ...) so bug reports carry a few sessions of history.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::logger::LogLevel;
+
+const BUFFER_CAPACITY: usize = 5000;
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_FILES: usize = 5;
+const DEFAULT_QUERY_LIMIT: usize = 500;
+const LOG_FILE: &str = "portal.log";
+
+/// One log entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub timestamp: DateTime<Utc>,
+    pub level: LogLevel,
+    /// Area of the app the entry came from, e.g. "Database"
+    pub domain: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Filter for `LogStore::query`; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogQuery {
+    /// Minimum level
+    #[serde(default)]
+    pub level: Option<LogLevel>,
+    /// Domain, compared case-insensitively
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    /// Text the message must contain, case-insensitively
+    #[serde(default)]
+    pub search: Option<String>,
+    /// Most recent records to return
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl LogQuery {
+    fn matches(&self, record: &LogRecord, search: Option<&str>) -> bool {
+        self.level.is_none_or(|level| record.level >= level)
+            && self.domain.as_deref().is_none_or(|domain| {
+                record
+                    .domain
+                    .as_deref()
+                    .is_some_and(|d| d.eq_ignore_ascii_case(domain))
+            })
+            && self.since.is_none_or(|since| record.timestamp >= since)
+            && self.until.is_none_or(|until| record.timestamp <= until)
+            && search.is_none_or(|search| record.message.to_lowercase().contains(search))
+    }
+}
+
+#[derive(Debug)]
+struct LogFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(dir: &Path) -> std::io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file,
+            size,
+        })
+    }
+
+    fn append(&mut self, record: &LogRecord) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        if self.size > 0 && self.size + line.len() as u64 > MAX_FILE_BYTES {
+            self.rotate()?;
+        }
+        self.file.write_all(&line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift `portal.N.log` to `portal.N+1.log`, dropping the oldest, and
+    /// start a fresh `portal.log`
+    fn rotate(&mut self) -> std::io::Result<()> {
+        for n in (1..MAX_FILES).rev() {
+            let from = rotated_path(&self.dir, n - 1);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.dir, n))?;
+            }
+        }
+        *self = Self::open(&self.dir)?;
+        Ok(())
+    }
+}
+
+/// `portal.log` for 0, `portal.N.log` otherwise
+fn rotated_path(dir: &Path, n: usize) -> PathBuf {
+    match n {
+        0 => dir.join(LOG_FILE),
+        n => dir.join(format!("portal.{}.log", n)),
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    buffer: VecDeque<LogRecord>,
+    file: Option<LogFile>,
+}
+
+/// Ring buffer of recent records, mirrored to rotating files once a log
+/// directory is attached
+#[derive(Debug, Default)]
+pub struct LogStore {
+    inner: Mutex<Inner>,
+}
+
+impl LogStore {
+    /// Start writing to `dir`. Records logged before this are written first,
+    /// so startup isn't missing from the file.
+    pub fn attach_dir(&self, dir: &Path) -> std::io::Result<()> {
+        let mut file = LogFile::open(dir)?;
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.file.is_none() {
+            for record in &inner.buffer {
+                file.append(record)?;
+            }
+        }
+        inner.file = Some(file);
+        Ok(())
+    }
+
+    pub fn push(&self, record: LogRecord) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(file) = inner.file.as_mut() {
+            if let Err(e) = file.append(&record) {
+                // Logging about logging would recurse; stderr is all there is
+                eprintln!("Failed to write log file: {}", e);
+            }
+        }
+        if inner.buffer.len() == BUFFER_CAPACITY {
+            inner.buffer.pop_front();
+        }
+        inner.buffer.push_back(record);
+    }
+
+    /// Buffered records matching `query`, oldest first, limited to the most
+    /// recent ones
+    pub fn query(&self, query: &LogQuery) -> Vec<LogRecord> {
+        let search = query.search.as_deref().map(str::to_lowercase);
+        let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let mut records: Vec<LogRecord> = inner
+            .buffer
+            .iter()
+            .rev()
+            .filter(|record| query.matches(record, search.as_deref()))
+            .take(limit)
+            .cloned()
+            .collect();
+        records.reverse();
+        records
+    }
+
+    /// Write the log files, oldest first, plus `extra` files into a zip at
+    /// `path`. Without a log directory the buffered records stand in.
+    pub fn export_zip(&self, path: &Path, extra: &[(&str, Vec<u8>)]) -> Result<(), String> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+        match inner.file.as_ref() {
+            Some(file) => {
+                for n in (0..MAX_FILES).rev() {
+                    let log_path = rotated_path(&file.dir, n);
+                    if !log_path.exists() {
+                        continue;
+                    }
+                    let bytes = fs::read(&log_path)
+                        .map_err(|e| format!("Failed to read {}: {}", log_path.display(), e))?;
+                    let name = log_path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    entries.push((name, bytes));
+                }
+            }
+            None => {
+                let mut bytes = Vec::new();
+                for record in &inner.buffer {
+                    let line = serde_json::to_vec(record)
+                        .map_err(|e| format!("Failed to serialize log record: {}", e))?;
+                    bytes.extend(line);
+                    bytes.push(b'\n');
+                }
+                entries.push((LOG_FILE.to_string(), bytes));
+            }
+        }
+        drop(inner);
+        entries.extend(
+            extra
+                .iter()
+                .map(|(name, bytes)| (name.to_string(), bytes.clone())),
+        );
+
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, bytes) in entries {
+            zip.start_file(name, options)
+                .map_err(|e| format!("Failed to write log archive: {}", e))?;
+            zip.write_all(&bytes)
+                .map_err(|e| format!("Failed to write log archive: {}", e))?;
+        }
+        zip.finish()
+            .map_err(|e| format!("Failed to write log archive: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(level: LogLevel, domain: &str, message: &str) -> LogRecord {
+        LogRecord {
+            timestamp: Utc::now(),
+            level,
+            domain: Some(domain.to_string()),
+            message: message.to_string(),
+            fields: serde_json::Map::new(),
+        }
+    }
+
+    #[test]
+    fn query_filters_and_keeps_most_recent() {
+        let store = LogStore::default();
+        store.push(record(LogLevel::Info, "Database", "opened"));
+        store.push(record(LogLevel::Warn, "Database", "slow query"));
+        store.push(record(LogLevel::Error, "Terminal", "pty died"));
+        store.push(record(LogLevel::Error, "database", "disk full"));
+
+        let errors = store.query(&LogQuery {
+            level: Some(LogLevel::Warn),
+            domain: Some("DATABASE".to_string()),
+            ..Default::default()
+        });
+        let messages: Vec<_> = errors.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(messages, vec!["slow query", "disk full"]);
+
+        let latest = store.query(&LogQuery {
+            limit: Some(1),
+            search: Some("PTY".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(latest[0].message, "pty died");
+    }
+
+    #[test]
+    fn files_rotate_and_keep_earlier_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LogStore::default();
+        store.push(record(LogLevel::Info, "Tauri", "before attach"));
+        store.attach_dir(dir.path()).unwrap();
+
+        let log = fs::read_to_string(dir.path().join(LOG_FILE)).unwrap();
+        assert!(log.contains("before attach"));
+
+        {
+            let mut inner = store.inner.lock().unwrap();
+            inner.file.as_mut().unwrap().size = MAX_FILE_BYTES;
+        }
+        store.push(record(LogLevel::Info, "Tauri", "after rotation"));
+        let rotated = fs::read_to_string(rotated_path(dir.path(), 1)).unwrap();
+        let current = fs::read_to_string(dir.path().join(LOG_FILE)).unwrap();
+        assert!(rotated.contains("before attach"));
+        assert!(current.contains("after rotation") && !current.contains("before attach"));
+    }
+}
@@ -1,14 +1,25 @@
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 
+use super::log_store::{LogQuery, LogRecord, LogStore};
+use crate::domains::settings::services::settings_service::LoggingSettings;
+
 /// Log levels matching the frontend logger
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum LogLevel {
+    #[serde(alias = "debug")]
     Debug = 0,
+    #[serde(alias = "info")]
     Info = 1,
+    #[serde(alias = "warn")]
     Warn = 2,
+    #[serde(alias = "error")]
     Error = 3,
 }
 
@@ -23,6 +34,20 @@ impl fmt::Display for LogLevel {
     }
 }
 
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level.trim().to_ascii_lowercase().as_str() {
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            _ => Err(format!("Unknown log level: {}", level)),
+        }
+    }
+}
+
 /// Logger configuration
 #[derive(Debug, Clone)]
 pub struct LoggerConfig {
@@ -47,7 +72,10 @@ impl Default for LoggerConfig {
 #[derive(Debug)]
 pub struct Logger {
     config: LoggerConfig,
+    /// Levels for individual domains, keyed in lowercase
+    domain_levels: Vec<(String, LogLevel)>,
     app_handle: Option<Arc<AppHandle>>,
+    store: LogStore,
 }
 
 impl Logger {
@@ -55,7 +83,9 @@ impl Logger {
     pub fn new() -> Self {
         Self {
             config: LoggerConfig::default(),
+            domain_levels: Vec::new(),
             app_handle: None,
+            store: LogStore::default(),
         }
     }
 
@@ -68,7 +98,9 @@ impl Logger {
     pub fn with_config(config: LoggerConfig) -> Self {
         Self {
             config,
+            domain_levels: Vec::new(),
             app_handle: None,
+            store: LogStore::default(),
         }
     }
 
@@ -77,14 +109,68 @@ impl Logger {
         self.config = config;
     }
 
+    /// Take the global and per-domain levels from settings. Levels that don't
+    /// parse are skipped.
+    pub fn apply_settings(&mut self, settings: &LoggingSettings) {
+        if let Ok(level) = settings.level.parse() {
+            self.config.level = level;
+        }
+        self.domain_levels = settings
+            .domains
+            .iter()
+            .filter_map(|(domain, level)| Some((domain.trim().to_lowercase(), level.parse().ok()?)))
+            .collect();
+    }
+
+    /// Lowest level logged for `context`: that of the longest domain it
+    /// starts with, or the global level
+    fn level_for(&self, context: Option<&str>) -> LogLevel {
+        let Some(context) = context.map(str::to_lowercase) else {
+            return self.config.level;
+        };
+        self.domain_levels
+            .iter()
+            .filter(|(domain, _)| context.starts_with(domain.as_str()))
+            .max_by_key(|(domain, _)| domain.len())
+            .map_or(self.config.level, |(_, level)| *level)
+    }
+
+    /// Keep records and persist them to rotating files in `dir`
+    pub fn attach_log_dir(&self, dir: &Path) -> std::io::Result<()> {
+        self.store.attach_dir(dir)
+    }
+
+    /// Stored records matching `query`
+    pub fn query(&self, query: &LogQuery) -> Vec<LogRecord> {
+        self.store.query(query)
+    }
+
+    /// Zip the log files, with `extra` files, for a bug report
+    pub fn export_zip(&self, path: &Path, extra: &[(&str, Vec<u8>)]) -> Result<(), String> {
+        self.store.export_zip(path, extra)
+    }
+
     /// Log a message at the specified level
     pub fn log(&self, level: LogLevel, context: Option<&str>, message: &str) {
-        if level < self.config.level {
+        self.log_with_fields(level, context, message, serde_json::Map::new());
+    }
+
+    /// Log a message with structured fields, e.g. ids or durations, that are
+    /// kept alongside it rather than formatted into it
+    pub fn log_with_fields(
+        &self,
+        level: LogLevel,
+        context: Option<&str>,
+        message: &str,
+        fields: serde_json::Map<String, serde_json::Value>,
+    ) {
+        if level < self.level_for(context) {
             return;
         }
 
+        let now = Utc::now();
         let timestamp = if self.config.enable_timestamps {
-            Utc::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+            now.format("%Y-%m-%d %H:%M:%S%.3f").to_string()
         } else {
             String::new()
         };
@@ -96,10 +182,18 @@ impl Logger {
             level.to_string()
         };
 
+        let fields_str = if fields.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", serde_json::Value::Object(fields.clone()))
+        };
         let log_line = if self.config.enable_timestamps {
-            format!("[{}] [{}] {}{}", timestamp, level_str, context_str, message)
+            format!(
+                "[{}] [{}] {}{}{}",
+                timestamp, level_str, context_str, message, fields_str
+            )
         } else {
-            format!("[{}] {}{}", level_str, context_str, message)
+            format!("[{}] {}{}{}", level_str, context_str, message, fields_str)
         };
 
         if self.config.enable_console {
@@ -120,6 +214,7 @@ impl Logger {
                 "level": level_str,
                 "context": context,
                 "message": message,
+                "fields": fields,
                 "timestamp": timestamp
             });
 
@@ -127,6 +222,14 @@ impl Logger {
                 eprintln!("Failed to emit backend log event: {}", e);
             }
         }
+
+        self.store.push(LogRecord {
+            timestamp: now,
+            level,
+            domain: context.map(String::from),
+            message: message.to_string(),
+            fields,
+        });
     }
 
     /// Log at debug level
@@ -295,3 +398,28 @@ macro_rules! log_error {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn domain_levels_override_the_global_level() {
+        let mut logger = Logger::new();
+        logger.apply_settings(&LoggingSettings {
+            level: "warn".to_string(),
+            domains: HashMap::from([
+                ("database".to_string(), "debug".to_string()),
+                ("DatabaseBackups".to_string(), "error".to_string()),
+                ("sdk".to_string(), "loud".to_string()),
+            ]),
+        });
+        assert_eq!(logger.level_for(None), LogLevel::Warn);
+        assert_eq!(logger.level_for(Some("Kubernetes")), LogLevel::Warn);
+        assert_eq!(logger.level_for(Some("DatabaseManager")), LogLevel::Debug);
+        assert_eq!(logger.level_for(Some("DatabaseBackups")), LogLevel::Error);
+        // An unknown level leaves the domain on the global level
+        assert_eq!(logger.level_for(Some("sdk")), LogLevel::Warn);
+    }
+}
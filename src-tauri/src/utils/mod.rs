@@ -1,2 +1,4 @@
+pub mod glob;
+pub mod log_store;
 pub mod logger;
 pub mod pnpm_workspace;
@@ -39,6 +39,30 @@ export interface ApprovalStats {
   };
 }
 
+export type ApprovalStatus = "pending" | "approved" | "rejected" | "expired";
+
+export interface QueuedApproval {
+  id: string;
+  action_type: string;
+  context: string;
+  description: string;
+  safety_level: string;
+  reason: string;
+  arguments: Record<string, unknown>;
+  preview: Record<string, unknown> | null;
+  status: ApprovalStatus;
+  feedback: string | null;
+  created_at: string;
+  expires_at: string;
+  resolved_at: string | null;
+}
+
+export interface ToolCallResolution {
+  call_id: string;
+  response: Record<string, unknown> | null;
+  error: string | null;
+}
+
 class AutonomyService {
   private initialized = false;
 
@@ -189,6 +213,52 @@ class AutonomyService {
     }
   }
 
+  /**
+   * Get actions waiting for approval, optionally with past decisions
+   */
+  async getApprovalQueue(includeResolved = false): Promise<QueuedApproval[]> {
+    try {
+      return await invoke<QueuedApproval[]>("get_approval_queue", {
+        includeResolved,
+      });
+    } catch (error) {
+      log.error("Failed to get approval queue", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Approve or reject several queued actions at once
+   */
+  async resolveToolCalls(
+    callIds: string[],
+    approved: boolean,
+    feedback?: string,
+  ): Promise<ToolCallResolution[]> {
+    try {
+      return await invoke<ToolCallResolution[]>("ai_resolve_tool_calls", {
+        callIds,
+        approved,
+        feedback,
+      });
+    } catch (error) {
+      log.error("Failed to resolve queued actions", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Delete resolved and expired approval requests
+   */
+  async clearResolvedApprovals(): Promise<number> {
+    try {
+      return await invoke<number>("clear_resolved_approvals");
+    } catch (error) {
+      log.error("Failed to clear resolved approvals", error);
+      throw error;
+    }
+  }
+
   /**
    * Cleanup resources
    */
@@ -0,0 +1,72 @@
+import { invokeClient } from "$lib/utils/invokeClient";
+
+export interface JobInfo {
+  name: string;
+  namespace: string;
+  status: string;
+  completions: number;
+  succeeded: number;
+  failed: number;
+  active: number;
+  parallelism: number | null;
+  backoff_limit: number | null;
+  age: string;
+  image: string | null;
+}
+
+export interface CronJobInfo {
+  name: string;
+  namespace: string;
+  schedule: string;
+  suspend: boolean;
+  active: number;
+  last_schedule_time: string | null;
+  last_successful_time: string | null;
+  age: string;
+  image: string | null;
+}
+
+export interface JobCleanupResult {
+  deleted: string[];
+  /** Jobs that couldn't be deleted, with why */
+  failed: string[];
+}
+
+export const jobApi = {
+  /** Start a job from the cronjob's template now */
+  triggerCronJob(namespace: string, name: string) {
+    return invokeClient.post<JobInfo>("k8s_trigger_cronjob", {
+      namespace,
+      name,
+    });
+  },
+
+  suspendCronJob(namespace: string, name: string) {
+    return invokeClient.post<CronJobInfo>("k8s_suspend_cronjob", {
+      namespace,
+      name,
+    });
+  },
+
+  resumeCronJob(namespace: string, name: string) {
+    return invokeClient.post<CronJobInfo>("k8s_resume_cronjob", {
+      namespace,
+      name,
+    });
+  },
+
+  /** Delete finished jobs (and their pods) matching a label selector */
+  deleteFinishedJobs(
+    namespace: string,
+    options?: {
+      labelSelector?: string | null;
+      status?: "succeeded" | "failed" | null;
+    },
+  ) {
+    return invokeClient.post<JobCleanupResult>("k8s_delete_finished_jobs", {
+      namespace,
+      labelSelector: options?.labelSelector ?? null,
+      status: options?.status ?? null,
+    });
+  },
+};
@@ -0,0 +1,65 @@
+import { invokeClient } from "$lib/utils/invokeClient";
+
+export interface KubeContextInfo {
+  name: string;
+  cluster: string | null;
+  user: string | null;
+  namespace: string | null;
+  server: string | null;
+  current: boolean;
+}
+
+/** Entry renamed on import because the kubeconfig had a different one by that name */
+export interface KubeconfigRename {
+  kind: "cluster" | "user" | "context";
+  from: string;
+  to: string;
+}
+
+export interface KubeconfigImport {
+  /** Kubeconfig merged into */
+  path: string;
+  /** Contexts added, by their name after any rename */
+  added: string[];
+  renamed: KubeconfigRename[];
+  /** Copy of the kubeconfig from before the import */
+  backup: string | null;
+  dry_run: boolean;
+}
+
+export interface KubeconfigRemoval {
+  removed: string[];
+  backup: string | null;
+}
+
+export interface StaleContext {
+  name: string;
+  reason: string;
+}
+
+export const kubeconfigApi = {
+  listContexts() {
+    return invokeClient.post<KubeContextInfo[]>("k8s_list_kube_contexts");
+  },
+
+  /** Merge the kubeconfig at `path` into the user's; `dryRun` only previews */
+  importKubeconfig(path: string, dryRun = false) {
+    return invokeClient.post<KubeconfigImport>("k8s_import_kubeconfig", {
+      path,
+      dryRun,
+    });
+  },
+
+  /** Contexts missing their cluster or user, and optionally unreachable ones */
+  findStaleContexts(checkReachability = false) {
+    return invokeClient.post<StaleContext[]>("k8s_find_stale_contexts", {
+      checkReachability,
+    });
+  },
+
+  removeContexts(names: string[]) {
+    return invokeClient.post<KubeconfigRemoval>("k8s_remove_kube_contexts", {
+      names,
+    });
+  },
+};
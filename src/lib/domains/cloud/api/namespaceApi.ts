@@ -0,0 +1,76 @@
+import { invokeClient } from "$lib/utils/invokeClient";
+import type { NamespaceInfo } from "$lib/domains/cloud/providers/gcp/GCPTypes";
+
+export interface WorkloadCounts {
+  pods: number;
+  running_pods: number;
+  deployments: number;
+  statefulsets: number;
+  daemonsets: number;
+  jobs: number;
+  cronjobs: number;
+  services: number;
+  configmaps: number;
+  secrets: number;
+  persistent_volume_claims: number;
+}
+
+export interface QuotaUsage {
+  resource: string;
+  hard: string;
+  used: string;
+  /** Share of `hard` in use, when both are quantities */
+  percent: number | null;
+}
+
+export interface ResourceQuotaInfo {
+  name: string;
+  usage: QuotaUsage[];
+}
+
+export interface NamespaceEvent {
+  name: string;
+  namespace: string;
+  kind: string;
+  reason: string;
+  message: string;
+  count: number;
+  first_timestamp: string | null;
+  last_timestamp: string | null;
+  involved_object: {
+    kind: string;
+    name: string;
+    namespace: string | null;
+    uid: string | null;
+  };
+  source: { component: string | null; host: string | null } | null;
+  type_: string | null;
+}
+
+export interface NamespaceSummary {
+  namespace: NamespaceInfo;
+  workloads: WorkloadCounts;
+  quotas: ResourceQuotaInfo[];
+  /** Most recent warning events, newest first */
+  warnings: NamespaceEvent[];
+}
+
+export const namespaceApi = {
+  create(name: string, labels?: Record<string, string>) {
+    return invokeClient.post<NamespaceInfo>("k8s_create_namespace", {
+      name,
+      labels: labels ?? null,
+    });
+  },
+
+  /** Delete a namespace and everything in it */
+  delete(name: string) {
+    return invokeClient.post("k8s_delete_namespace", { name });
+  },
+
+  getSummary(name: string) {
+    return invokeClient.post<NamespaceSummary>("k8s_get_namespace_summary", {
+      name,
+    });
+  },
+};
@@ -0,0 +1,71 @@
+import { invokeClient } from "$lib/utils/invokeClient";
+
+export interface NodeCondition {
+  type: string;
+  status: string;
+  reason: string | null;
+  message: string | null;
+  last_transition_time: string | null;
+}
+
+export interface NodeInfo {
+  name: string;
+  /** Ready, NotReady or Unknown, plus SchedulingDisabled when cordoned */
+  status: string;
+  roles: string[];
+  unschedulable: boolean;
+  internal_ip: string | null;
+  kubelet_version: string | null;
+  os_image: string | null;
+  capacity: Record<string, string>;
+  allocatable: Record<string, string>;
+  conditions: NodeCondition[];
+  age: string;
+  labels: Record<string, string>;
+}
+
+export interface DrainOptions {
+  ignore_daemonsets?: boolean;
+  delete_emptydir_data?: boolean;
+  force?: boolean;
+  grace_period_seconds?: number | null;
+  timeout_seconds?: number | null;
+}
+
+/** Payload of the `k8s:drain-progress` event */
+export interface DrainProgress {
+  node: string;
+  namespace: string;
+  pod: string;
+  phase: "evicting" | "blocked" | "evicted" | "gone" | "failed";
+  message: string | null;
+  completed: number;
+  total: number;
+}
+
+export interface DrainResult {
+  node: string;
+  evicted: string[];
+  skipped: string[];
+}
+
+export const nodeApi = {
+  listNodes() {
+    return invokeClient.post<NodeInfo[]>("k8s_list_nodes");
+  },
+
+  cordon(nodeName: string) {
+    return invokeClient.post<NodeInfo>("k8s_cordon_node", { nodeName });
+  },
+
+  uncordon(nodeName: string) {
+    return invokeClient.post<NodeInfo>("k8s_uncordon_node", { nodeName });
+  },
+
+  drain(nodeName: string, options?: DrainOptions) {
+    return invokeClient.post<DrainResult>("k8s_drain_node", {
+      nodeName,
+      options: options ?? null,
+    });
+  },
+};
@@ -1,4 +1,45 @@
 import { invokeClient } from "$lib/utils/invokeClient";
+import type { PortForwardInfo } from "$lib/domains/cloud/providers/gcp/GCPTypes";
+
+export type PortForwardTargetKind =
+  | "pod"
+  | "deployment"
+  | "replicaset"
+  | "statefulset"
+  | "daemonset"
+  | "service";
+
+export interface SavedPortForwardInput {
+  namespace: string;
+  target_kind: PortForwardTargetKind;
+  target_name: string;
+  local_port: number;
+  remote_port: number;
+  /** Start on connecting to the cluster and restart when the pod is replaced */
+  auto_restore?: boolean;
+}
+
+export interface SavedPortForward extends Required<SavedPortForwardInput> {
+  id: string;
+  cluster: string;
+}
+
+export interface PortForwardRestore {
+  started: PortForwardInfo[];
+  failed: string[];
+}
+
+/** Ephemeral debug container added to a pod, like `kubectl debug` */
+export interface DebugContainerInfo {
+  namespace: string;
+  pod_name: string;
+  container: string;
+  /** Container whose processes the debug container can see */
+  target_container: string | null;
+  image: string;
+  /** Opens the debug container in a terminal */
+  attach_command: string;
+}
 
 export const podApi = {
   getLogs(params: {
@@ -29,6 +70,24 @@ export const podApi = {
     return invokeClient.post("k8s_delete_pod", { namespace, podName });
   },
 
+  /**
+   * Add a debug container (busybox unless `image` is given) sharing
+   * `targetContainer`'s processes, for pods without a shell
+   */
+  debugPod(params: {
+    namespace: string;
+    podName: string;
+    image?: string | null;
+    targetContainer?: string | null;
+  }) {
+    return invokeClient.post<DebugContainerInfo>("k8s_debug_pod", {
+      namespace: params.namespace,
+      podName: params.podName,
+      image: params.image ?? null,
+      targetContainer: params.targetContainer ?? null,
+    });
+  },
+
   startPortForward(params: {
     namespace: string;
     podName: string;
@@ -38,11 +97,74 @@ export const podApi = {
     return invokeClient.post("k8s_start_port_forward", params);
   },
 
+  /**
+   * Forward to a service or workload through one of its ready pods, like
+   * `kubectl port-forward svc/<name>`. For a service, `remotePort` is the
+   * service's port. Non-pod targets move to another ready pod when theirs
+   * goes away unless `autoRestore` is false.
+   */
+  startResourcePortForward(params: {
+    namespace: string;
+    targetKind: PortForwardTargetKind;
+    targetName: string;
+    localPort: number;
+    remotePort: number;
+    autoRestore?: boolean;
+  }) {
+    return invokeClient.post<PortForwardInfo>(
+      "k8s_start_resource_port_forward",
+      {
+        namespace: params.namespace,
+        targetKind: params.targetKind,
+        targetName: params.targetName,
+        localPort: params.localPort,
+        remotePort: params.remotePort,
+        autoRestore: params.autoRestore ?? null,
+      },
+    );
+  },
+
   stopPortForward(id: string) {
     return invokeClient.post("k8s_stop_port_forward", { id });
   },
 
   listPortForwards() {
-    return invokeClient.post<unknown[]>("k8s_list_port_forwards");
+    return invokeClient.post<PortForwardInfo[]>("k8s_list_port_forwards");
+  },
+
+  /** Saved port forwards on the connected cluster */
+  listSavedPortForwards() {
+    return invokeClient.post<SavedPortForward[]>(
+      "k8s_list_saved_port_forwards",
+    );
+  },
+
+  /** Save a forward; pods are saved as their workload when auto-restoring */
+  savePortForward(input: SavedPortForwardInput) {
+    return invokeClient.post<SavedPortForward>("k8s_save_port_forward", {
+      input,
+    });
+  },
+
+  updateSavedPortForward(id: string, input: SavedPortForwardInput) {
+    return invokeClient.post<SavedPortForward>(
+      "k8s_update_saved_port_forward",
+      { id, input },
+    );
+  },
+
+  deleteSavedPortForward(id: string) {
+    return invokeClient.post("k8s_delete_saved_port_forward", { id });
+  },
+
+  startSavedPortForward(id: string) {
+    return invokeClient.post<PortForwardInfo>("k8s_start_saved_port_forward", {
+      id,
+    });
+  },
+
+  /** Start the saved auto-restore forwards that aren't running */
+  restorePortForwards() {
+    return invokeClient.post<PortForwardRestore>("k8s_restore_port_forwards");
   },
 };
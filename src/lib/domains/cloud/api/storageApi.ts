@@ -0,0 +1,80 @@
+import { invokeClient } from "$lib/utils/invokeClient";
+
+export interface PersistentVolumeInfo {
+  name: string;
+  capacity: string | null;
+  access_modes: string[];
+  reclaim_policy: string | null;
+  status: string;
+  /** Claim bound to the volume, as namespace/name */
+  claim: string | null;
+  storage_class: string | null;
+  volume_mode: string | null;
+  age: string;
+  labels: Record<string, string>;
+}
+
+export interface PersistentVolumeClaimInfo {
+  name: string;
+  namespace: string;
+  status: string;
+  volume: string | null;
+  capacity: string | null;
+  /** Above `capacity` while an expansion is underway */
+  requested: string | null;
+  access_modes: string[];
+  storage_class: string | null;
+  volume_mode: string | null;
+  /** Pods mounting the claim */
+  used_by: string[];
+  age: string;
+  labels: Record<string, string>;
+}
+
+export const storageApi = {
+  listVolumes() {
+    return invokeClient.post<PersistentVolumeInfo[]>(
+      "k8s_list_persistent_volumes",
+    );
+  },
+
+  getVolume(name: string) {
+    return invokeClient.post<PersistentVolumeInfo>(
+      "k8s_get_persistent_volume",
+      { name },
+    );
+  },
+
+  deleteVolume(name: string) {
+    return invokeClient.post("k8s_delete_persistent_volume", { name });
+  },
+
+  listClaims(namespace?: string | null) {
+    return invokeClient.post<PersistentVolumeClaimInfo[]>(
+      "k8s_list_persistent_volume_claims",
+      { namespace: namespace ?? null },
+    );
+  },
+
+  getClaim(namespace: string, name: string) {
+    return invokeClient.post<PersistentVolumeClaimInfo>(
+      "k8s_get_persistent_volume_claim",
+      { namespace, name },
+    );
+  },
+
+  deleteClaim(namespace: string, name: string) {
+    return invokeClient.post("k8s_delete_persistent_volume_claim", {
+      namespace,
+      name,
+    });
+  },
+
+  /** Grow a claim to `size` (e.g. "20Gi") if its storage class allows it */
+  expandClaim(namespace: string, name: string, size: string) {
+    return invokeClient.post<PersistentVolumeClaimInfo>(
+      "k8s_expand_persistent_volume_claim",
+      { namespace, name, size },
+    );
+  },
+};
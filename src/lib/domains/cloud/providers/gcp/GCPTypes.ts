@@ -69,10 +69,16 @@ export interface NamespaceInfo {
 export interface PortForwardInfo {
   id: string;
   namespace: string;
+  /** Pod forwarded to, or the workload or service for other targets */
   pod_name: string;
+  /** What's forwarded to, e.g. deployment/web */
+  target: string;
   local_port: number;
   remote_port: number;
+  /** Active, or Reconnecting while an auto-restore forward restarts */
   status: string;
   created_at: string;
   url: string;
+  saved_id: string | null;
+  auto_restore: boolean;
 }
@@ -11,6 +11,38 @@ import { invokeClient } from "$lib/utils/invokeClient";
 
 export type PodMetrics = Record<string, unknown>;
 
+/** A problem found in resource YAML; `line` and `column` are 1-based. */
+export interface YamlDiagnostic {
+  line: number | null;
+  column: number | null;
+  /** Field path the problem is about, e.g. `spec.replicas` */
+  path: string | null;
+  message: string;
+}
+
+export interface YamlValidation {
+  valid: boolean;
+  diagnostics: YamlDiagnostic[];
+}
+
+export type ConfigResourceKind = "configmap" | "secret";
+
+/** A ConfigMap or Secret key; binary values are base64 encoded. */
+export interface ConfigEntry {
+  key: string;
+  kind: "text" | "binary";
+  value: string;
+}
+
+export interface ConfigEntries {
+  kind: ConfigResourceKind;
+  name: string;
+  namespace: string;
+  /** Pass back when editing so concurrent changes aren't overwritten. */
+  resource_version: string | null;
+  entries: ConfigEntry[];
+}
+
 export const k8sResourceService = {
   /** Get a resource's manifest as YAML. */
   getResourceYaml(
@@ -30,6 +62,64 @@ export const k8sResourceService = {
     });
   },
 
+  /** Check YAML against the cluster's schema with a strict dry run. */
+  validateResourceYaml(
+    namespace: string,
+    yamlContent: string,
+  ): Promise<YamlValidation> {
+    return invokeClient.request<YamlValidation>("k8s_validate_resource_yaml", {
+      data: { namespace, yamlContent },
+    });
+  },
+
+  /** Keys of a ConfigMap or Secret, with Secret values decoded. */
+  getConfigEntries(
+    kind: ConfigResourceKind,
+    namespace: string,
+    name: string,
+  ): Promise<ConfigEntries> {
+    return invokeClient.request<ConfigEntries>("k8s_get_config_entries", {
+      data: { kind, namespace, name },
+    });
+  },
+
+  /** Add or update one key; fails if the resource changed since `resourceVersion`. */
+  setConfigEntry(
+    kind: ConfigResourceKind,
+    namespace: string,
+    name: string,
+    entry: ConfigEntry,
+    resourceVersion?: string | null,
+  ): Promise<ConfigEntries> {
+    return invokeClient.request<ConfigEntries>("k8s_set_config_entry", {
+      data: {
+        kind,
+        namespace,
+        name,
+        entry,
+        resourceVersion: resourceVersion ?? null,
+      },
+    });
+  },
+
+  removeConfigEntry(
+    kind: ConfigResourceKind,
+    namespace: string,
+    name: string,
+    key: string,
+    resourceVersion?: string | null,
+  ): Promise<ConfigEntries> {
+    return invokeClient.request<ConfigEntries>("k8s_remove_config_entry", {
+      data: {
+        kind,
+        namespace,
+        name,
+        key,
+        resourceVersion: resourceVersion ?? null,
+      },
+    });
+  },
+
   deleteConfigmap(namespace: string, name: string): Promise<void> {
     return invokeClient.request("k8s_delete_configmap", {
       data: { namespace, name },
@@ -22,6 +22,23 @@ export interface FrameworkIdeMapping {
   updated_at?: string;
 }
 
+export interface DetectedIde {
+  name: string;
+  executable: string;
+  version?: string | null;
+}
+
+export interface RecommendedExtension {
+  id: string;
+  frameworks: string[];
+  installed: boolean;
+}
+
+export interface ExtensionInstallReport {
+  installed: string[];
+  failed: [string, string][];
+}
+
 export interface SuggestedFramework {
   name: string;
   icon: string;
@@ -157,13 +174,111 @@ export class IdeService {
     }
   }
 
+  /**
+   * Open a project in an IDE, optionally at a file and line. Without an
+   * IDE id the project's IDE, then the default IDE, is used.
+   */
+  async openInIde(
+    path: string,
+    target: {
+      file?: string;
+      line?: number;
+      column?: number;
+      ideId?: number;
+      projectId?: number;
+    } = {},
+  ): Promise<void> {
+    try {
+      this.log.info("Opening in IDE", { path, file: target.file, line: target.line });
+      await invokeClient.post("open_in_ide", {
+        path,
+        file: target.file ?? null,
+        line: target.line ?? null,
+        column: target.column ?? null,
+        ideId: target.ideId ?? null,
+        projectId: target.projectId ?? null,
+      });
+    } catch (error) {
+      this.log.error("Failed to open in IDE", { error });
+      throw error;
+    }
+  }
+
+  /**
+   * Open a project inside its dev container, with the given or default IDE
+   */
+  async openInDevcontainer(path: string, ideId?: number): Promise<void> {
+    try {
+      this.log.info("Opening in dev container", { path });
+      await invokeClient.post("open_in_devcontainer", { path, ideId: ideId ?? null });
+    } catch (error) {
+      this.log.error("Failed to open in dev container", { error });
+      throw error;
+    }
+  }
+
+  /**
+   * Rebuild a project's dev container
+   */
+  async rebuildDevcontainer(path: string): Promise<string> {
+    try {
+      this.log.info("Rebuilding dev container", { path });
+      return await invokeClient.post<string>("rebuild_devcontainer", { path });
+    } catch (error) {
+      this.log.error("Failed to rebuild dev container", { error });
+      throw error;
+    }
+  }
+
+  /**
+   * Editor extensions recommended for the frameworks, with the given or
+   * default IDE
+   */
+  async getRecommendedExtensions(
+    frameworks: string[],
+    ideId?: number,
+  ): Promise<RecommendedExtension[]> {
+    try {
+      const extensions = await invokeClient.post<RecommendedExtension[]>(
+        "get_recommended_extensions",
+        { frameworks, ideId: ideId ?? null },
+      );
+      return extensions ?? [];
+    } catch (error) {
+      this.log.error("Failed to get recommended extensions", { error });
+      throw error;
+    }
+  }
+
+  /**
+   * Install missing recommended extensions; progress arrives as
+   * `ide-extension-install-progress` events
+   */
+  async installRecommendedExtensions(
+    frameworks: string[],
+    ideId?: number,
+  ): Promise<ExtensionInstallReport> {
+    try {
+      this.log.info("Installing recommended extensions", { frameworks });
+      return await invokeClient.post<ExtensionInstallReport>(
+        "install_recommended_extensions",
+        { frameworks, ideId: ideId ?? null },
+      );
+    } catch (error) {
+      this.log.error("Failed to install recommended extensions", { error });
+      throw error;
+    }
+  }
+
   /**
    * Detect installed IDEs on the system
    */
-  async detectInstalledIdes(): Promise<string[]> {
+  async detectInstalledIdes(): Promise<DetectedIde[]> {
     try {
       this.log.info("Detecting installed IDEs");
-      const ides = await invokeClient.post<string[]>("detect_installed_ides");
+      const ides = await invokeClient.post<DetectedIde[]>(
+        "detect_installed_ides",
+      );
       const safeIdes = ides ?? [];
       this.log.info("IDEs detected", { count: safeIdes.length });
       return safeIdes;
@@ -19,4 +19,9 @@ export type {
   MemoryStats,
   CleanupStats,
   CleanupPreview,
+  LearningAdapterKind,
+  LearningDataKind,
+  LearningDataScope,
+  LearningDataExport,
+  PurgeResult,
 } from "./services/memoryService";
@@ -3,6 +3,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type {
   MLIntensity,
   PatternType,
@@ -10,6 +11,10 @@ import type {
   UserPreference,
   LearningEvent,
   Suggestion,
+  SuggestionFeedback,
+  CommandPrediction,
+  CommandInsight,
+  SessionTimeline,
   RecordLearningEventRequest,
   LearnPatternRequest,
   LearnPreferenceRequest,
@@ -216,6 +221,94 @@ class LearningService {
     }
   }
 
+  /**
+   * Accept, reject or snooze a suggestion; this adjusts how its pattern is
+   * ranked from now on
+   */
+  async submitSuggestionFeedback(
+    patternId: number,
+    feedback: SuggestionFeedback,
+    snoozeHours?: number,
+  ): Promise<void> {
+    try {
+      log.info("Submitting suggestion feedback", {
+        pattern_id: patternId,
+        feedback,
+      });
+
+      await invoke("submit_suggestion_feedback", {
+        patternId,
+        feedback,
+        snoozeHours: snoozeHours ?? null,
+      });
+
+      // Cached suggestions are ranked on the old weights
+      for (const key of cache.getKeys()) {
+        if (key.startsWith("suggestions:")) {
+          cache.delete(key);
+        }
+      }
+
+      log.info("Suggestion feedback submitted", { pattern_id: patternId });
+    } catch (error) {
+      log.error("Failed to submit suggestion feedback", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Predict the next terminal commands from the recent ones (oldest first),
+   * favouring history from terminals opened in the project
+   */
+  async predictNextCommands(
+    recentCommands: string[],
+    prefix?: string,
+    projectPath?: string,
+    limit?: number,
+  ): Promise<CommandPrediction[]> {
+    try {
+      return await invoke<CommandPrediction[]>("predict_next_commands", {
+        recentCommands,
+        prefix: prefix ?? null,
+        projectPath: projectPath ?? null,
+        limit: limit ?? null,
+      });
+    } catch (error) {
+      log.error("Failed to predict next commands", error);
+      throw error;
+    }
+  }
+
+  /**
+   * What was done between `since` and `until` (the last day by default),
+   * grouped into work sessions
+   */
+  async getSessionTimeline(
+    since?: Date,
+    until?: Date,
+    projectPath?: string,
+  ): Promise<SessionTimeline> {
+    try {
+      return await invoke<SessionTimeline>("get_session_timeline", {
+        since: since?.toISOString() ?? null,
+        until: until?.toISOString() ?? null,
+        projectPath: projectPath ?? null,
+      });
+    } catch (error) {
+      log.error("Failed to get session timeline", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Subscribe to insights about reliable commands that started failing
+   */
+  onInsight(callback: (insight: CommandInsight) => void): Promise<UnlistenFn> {
+    return listen<CommandInsight>("learning-insight", (event) =>
+      callback(event.payload),
+    );
+  }
+
   /**
    * Learn user preference
    */
@@ -4,6 +4,11 @@
 
 import { invoke } from "@tauri-apps/api/core";
 import { logger } from "$lib/domains/shared/services/logger";
+import type {
+  LearnedPattern,
+  LearningEvent,
+  UserPreference,
+} from "$lib/domains/learning/types";
 
 const log = logger.createScoped("MemoryService");
 
@@ -29,6 +34,40 @@ export interface CleanupPreview {
   events_over_limit: number;
 }
 
+export type LearningAdapterKind =
+  | "projects"
+  | "sdk"
+  | "terminal"
+  | "ide"
+  | "tasks";
+
+export type LearningDataKind = "events" | "patterns" | "preferences";
+
+/** Narrows export and purge; leave a field out to not narrow by it */
+export interface LearningDataScope {
+  /** Project directory; matches data learned in it or below it */
+  project?: string | null;
+  adapter?: LearningAdapterKind | null;
+  /** ISO timestamps bounding when the data was created */
+  since?: string | null;
+  until?: string | null;
+  kinds?: LearningDataKind[];
+}
+
+export interface LearningDataExport {
+  exported_at: string;
+  scope: LearningDataScope;
+  events: LearningEvent[];
+  patterns: LearnedPattern[];
+  preferences: UserPreference[];
+}
+
+export interface PurgeResult {
+  events_deleted: number;
+  patterns_deleted: number;
+  preferences_deleted: number;
+}
+
 class MemoryService {
   /**
    * Perform automatic cleanup of learning data
@@ -72,6 +111,38 @@ class MemoryService {
       throw error;
     }
   }
+
+  /**
+   * Everything learned within the scope
+   */
+  async exportData(scope: LearningDataScope = {}): Promise<LearningDataExport> {
+    try {
+      return await invoke<LearningDataExport>("export_learning_data", {
+        scope,
+      });
+    } catch (error) {
+      log.error("Failed to export learning data", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Delete everything learned within the scope. Preferences set by the user
+   * are kept.
+   */
+  async purgeData(scope: LearningDataScope = {}): Promise<PurgeResult> {
+    try {
+      log.info("Purging learning data", scope);
+      const result = await invoke<PurgeResult>("purge_learning_data", {
+        scope,
+      });
+      log.info("Learning data purged", result);
+      return result;
+    } catch (error) {
+      log.error("Failed to purge learning data", error);
+      throw error;
+    }
+  }
 }
 
 export const memoryService = new MemoryService();
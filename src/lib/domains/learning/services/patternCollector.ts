@@ -12,6 +12,24 @@ import { logger } from "$lib/domains/shared/services/logger";
 
 const log = logger.createScoped("PatternCollector");
 
+/** How a terminal command ran, beyond whether it succeeded */
+export interface CommandRun {
+  exitCode?: number;
+  /** Output with ANSI codes stripped */
+  output?: string;
+  /** Working directory, which scopes failure insights to a project */
+  cwd?: string;
+}
+
+/** Last few lines of a failed command's output */
+function errorExcerpt(output?: string): string | null {
+  const lines = (output ?? "")
+    .split(/\r?\n/)
+    .map((line) => line.trim())
+    .filter(Boolean);
+  return lines.length > 0 ? lines.slice(-5).join("\n") : null;
+}
+
 export class PatternCollector {
   private enabled = true;
   private mlEnabled = true;
@@ -51,6 +69,7 @@ export class PatternCollector {
     command: string,
     success: boolean,
     context?: string,
+    run?: CommandRun,
   ): Promise<void> {
     if (!this.shouldCollect()) {
       await this.updateMLEnabledState();
@@ -66,9 +85,16 @@ export class PatternCollector {
       });
 
       // Record the event
+      // Failures keep the end of their output so a new error can be told
+      // apart from the usual ones
       await learningService.recordEvent({
         event_type: "command_executed",
-        event_data: { command },
+        event_data: {
+          command,
+          exit_code: run?.exitCode ?? null,
+          cwd: run?.cwd ?? null,
+          error: success ? null : errorExcerpt(run?.output),
+        },
         outcome: success ? "success" : "failure",
         context,
       });
@@ -61,7 +61,7 @@ export class SuggestionEngine {
    */
   async recordSuggestionAccepted(patternId: number): Promise<void> {
     try {
-      await learningService.recordPatternOutcome(patternId, true);
+      await learningService.submitSuggestionFeedback(patternId, "accept");
       log.info("Suggestion acceptance recorded", { pattern_id: patternId });
     } catch (error) {
       log.error("Failed to record suggestion acceptance", error);
@@ -73,12 +73,24 @@ export class SuggestionEngine {
    */
   async recordSuggestionRejected(patternId: number): Promise<void> {
     try {
-      await learningService.recordPatternOutcome(patternId, false);
+      await learningService.submitSuggestionFeedback(patternId, "reject");
       log.info("Suggestion rejection recorded", { pattern_id: patternId });
     } catch (error) {
       log.error("Failed to record suggestion rejection", error);
     }
   }
+
+  /**
+   * Hide a suggestion for a while (a day by default)
+   */
+  async snoozeSuggestion(patternId: number, hours?: number): Promise<void> {
+    try {
+      await learningService.submitSuggestionFeedback(patternId, "snooze", hours);
+      log.info("Suggestion snoozed", { pattern_id: patternId, hours });
+    } catch (error) {
+      log.error("Failed to snooze suggestion", error);
+    }
+  }
 }
 
 export const suggestionEngine = new SuggestionEngine();
@@ -16,6 +16,10 @@ export type EventType =
   | "project_created"
   | "suggestion_accepted"
   | "suggestion_rejected"
+  | "suggestion_snoozed"
+  | "command_anomaly"
+  | "sdk_version_switched"
+  | "env_var_changed"
   | "preference_updated"
   | "pattern_learned";
 
@@ -30,6 +34,9 @@ export interface LearnedPattern {
   last_used: string | null;
   success_rate: number;
   is_important?: boolean;
+  /** Multiplier left by suggestion feedback */
+  weight?: number;
+  snoozed_until?: string | null;
   created_at: string;
 }
 
@@ -54,12 +61,94 @@ export interface LearningEvent {
   created_at: string;
 }
 
+export type ContextMatch = "exact" | "partial" | "none";
+
+export interface ContributingEvent {
+  id: number;
+  event_type: EventType;
+  outcome: EventOutcome | null;
+  created_at: string | null;
+}
+
+export interface SuggestionProvenance {
+  pattern_type: PatternType;
+  context_match: ContextMatch;
+  /** Multiplier left by earlier feedback; 1 without any */
+  weight: number;
+  last_used: string | null;
+  events: ContributingEvent[];
+}
+
 export interface Suggestion {
   pattern_id: number;
   pattern_data: Record<string, unknown>;
   frequency: number;
   success_rate: number;
   context: string | null;
+  /** 0 to 1 */
+  confidence: number;
+  explanation: string;
+  provenance: SuggestionProvenance;
+}
+
+export type SuggestionFeedback = "accept" | "reject" | "snooze";
+
+export interface LikelyCause {
+  event_id: number;
+  event_type: EventType;
+  description: string;
+  at: string | null;
+}
+
+/** A reliable command that started failing, from "learning-insight" */
+export interface CommandInsight {
+  command: string;
+  project: string | null;
+  message: string;
+  baseline_success_rate: number;
+  baseline_runs: number;
+  recent_failures: number;
+  recent_runs: number;
+  exit_code: number | null;
+  error: string | null;
+  new_error: boolean;
+  likely_causes: LikelyCause[];
+}
+
+export type PredictionSource = "trigram" | "bigram" | "frequency";
+
+export interface CommandPrediction {
+  command: string;
+  /** 0 to 1 */
+  score: number;
+  source: PredictionSource;
+}
+
+export type TimelineKind = "command" | "file" | "pipeline" | "task" | "environment";
+
+export interface TimelineEntry {
+  at: string;
+  kind: TimelineKind;
+  title: string;
+  detail: string | null;
+  /** Directory of the project the entry belongs to */
+  project: string | null;
+  /** Times the entry happened back to back */
+  count: number;
+}
+
+/** A stretch of activity without a long idle gap */
+export interface WorkSession {
+  start: string;
+  end: string;
+  projects: string[];
+  entries: TimelineEntry[];
+}
+
+export interface SessionTimeline {
+  since: string;
+  until: string;
+  sessions: WorkSession[];
 }
 
 export interface RecordLearningEventRequest {
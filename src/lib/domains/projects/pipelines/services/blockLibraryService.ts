@@ -4,7 +4,13 @@
 
 import { invoke } from "@tauri-apps/api/core";
 import { logger } from "$lib/domains/shared";
-import type { Block, CreateBlockRequest } from "../types";
+import type {
+  Block,
+  BlockTemplate,
+  BlockTemplateSummary,
+  CreateBlockRequest,
+  PipelineVariable,
+} from "../types";
 
 const log = logger.createScoped("BlockLibraryService");
 
@@ -1154,6 +1160,69 @@ export class BlockLibraryService {
       throw error;
     }
   }
+
+  /**
+   * Built-in and imported block templates, newest version of each
+   */
+  async getTemplates(): Promise<BlockTemplateSummary[]> {
+    try {
+      return await invoke<BlockTemplateSummary[]>("get_block_templates");
+    } catch (error) {
+      log.error("Failed to load block templates", { error });
+      throw error;
+    }
+  }
+
+  /**
+   * Import block templates from a YAML or JSON file path or an http(s) URL
+   */
+  async importTemplates(source: string): Promise<BlockTemplate[]> {
+    try {
+      log.info("Importing block templates", { source });
+      const templates = await invoke<BlockTemplate[]>("import_block_templates", {
+        source,
+      });
+      log.info("Block templates imported", { count: templates.length });
+      return templates;
+    } catch (error) {
+      log.error("Failed to import block templates", { error });
+      throw error;
+    }
+  }
+
+  /**
+   * Remove an imported template; all its versions unless one is given
+   */
+  async removeTemplate(key: string, version?: string): Promise<void> {
+    try {
+      await invoke("remove_block_template", { key, version });
+    } catch (error) {
+      log.error("Failed to remove block template", { error });
+      throw error;
+    }
+  }
+
+  /**
+   * Create a block from a template with its parameters prefilled. Returns the
+   * block and the pipeline variables the template expects.
+   */
+  async instantiateTemplate(
+    key: string,
+    values?: Record<string, string>,
+    version?: string,
+  ): Promise<{ block: Block; variables: PipelineVariable[] }> {
+    try {
+      log.info("Creating block from template", { key, version });
+      return await invoke("instantiate_block_template", {
+        key,
+        version,
+        values,
+      });
+    } catch (error) {
+      log.error("Failed to create block from template", { error });
+      throw error;
+    }
+  }
 }
 
 export const blockLibraryService = BlockLibraryService.getInstance();
@@ -14,6 +14,7 @@ export interface Pipeline {
   enabled: boolean;
   presetKey?: string;
   category?: "install" | "dev" | "build";
+  notifications?: NotificationHook[];
   createdAt: Date;
   updatedAt: Date;
 }
@@ -35,6 +36,34 @@ export interface PipelineStep {
   approvalMessage?: string; // Message shown to user when approval is required
 }
 
+/**
+ * `config.cache` of a command step. While the files matched by `inputs` are
+ * unchanged the step is skipped and its `outputs` restored from the cache.
+ */
+export interface StepCacheConfig {
+  /** Change to invalidate the cache */
+  key?: string;
+  /** Globs relative to the working directory, e.g. "src/**" or "*.json" */
+  inputs: string[];
+  /** Files or directories the step produces, e.g. "dist" */
+  outputs?: string[];
+}
+
+/**
+ * Runs when an execution of the pipeline finishes with one of the `on`
+ * statuses (any of them when empty). Scripts get the execution summary as
+ * `pipeline_<field>` arguments, workflows as trigger data, and webhooks as
+ * the JSON body of a POST.
+ */
+export type NotificationHook = {
+  on?: ("success" | "failed" | "cancelled")[];
+} & (
+  | { type: "desktop" }
+  | { type: "script"; scriptId: number; arguments?: Record<string, string> }
+  | { type: "workflow"; workflowId: string }
+  | { type: "webhook"; url: string; headers?: Record<string, string> }
+);
+
 export interface ExecutionContext {
   type: "sdk" | "docker";
   sdkType?: string; // node, python, rust, go, etc.
@@ -44,6 +73,11 @@ export interface ExecutionContext {
   dockerContext?: string;
   workingDirectory: string;
   environment?: Record<string, string>; // Additional environment variables
+  /**
+   * When the pipeline is run while a run of it is active: overlap (default),
+   * wait for it, or cancel it first
+   */
+  concurrency?: "allow" | "queue" | "cancelPrevious";
 }
 
 export interface PipelineVariable {
@@ -71,6 +105,32 @@ export interface Block {
   updatedAt?: Date;
 }
 
+/**
+ * Shareable block definition, imported from YAML or JSON. Instantiating it
+ * creates a Block with its command's parameters filled in.
+ */
+export interface BlockTemplate {
+  key: string; // Stable across versions
+  name: string;
+  description: string;
+  category: Block["category"];
+  version: string;
+  command: string;
+  executionType: Block["executionType"];
+  parameters: BlockParameter[];
+  variables: PipelineVariable[]; // Pipeline variables the command expects
+  defaultConfig: Record<string, any>;
+  tags: string[];
+  icon?: string;
+  author?: string;
+}
+
+export interface BlockTemplateSummary extends BlockTemplate {
+  source: string; // File or URL it was imported from, or "builtin"
+  importedAt?: string;
+  versions: string[]; // Newest first
+}
+
 export interface BlockParameter {
   name: string;
   type: "string" | "number" | "boolean" | "select" | "file" | "directory";
@@ -97,6 +157,7 @@ export interface PipelineExecution {
   stepExecutions: StepExecution[];
   variables: Record<string, string>; // Resolved variables at execution time
   error?: string;
+  queuePosition?: number; // 1-based, while queued for an execution slot
 }
 
 export interface PipelineExecutionListItem extends PipelineExecution {
@@ -106,6 +167,7 @@ export interface PipelineExecutionListItem extends PipelineExecution {
 
 export type ExecutionStatus =
   | "pending"
+  | "queued"
   | "running"
   | "success"
   | "failed"
@@ -138,6 +200,7 @@ export interface CreatePipelineRequest {
   enabled?: boolean;
   presetKey?: string;
   category?: "install" | "dev" | "build";
+  notifications?: NotificationHook[];
 }
 
 export interface UpdatePipelineRequest {
@@ -148,6 +211,7 @@ export interface UpdatePipelineRequest {
   secrets?: string[];
   executionContext?: ExecutionContext;
   enabled?: boolean;
+  notifications?: NotificationHook[];
 }
 
 export interface CreateBlockRequest {
@@ -2,12 +2,19 @@
  * Project service for managing projects
  */
 
+import { listen } from "@tauri-apps/api/event";
 import { invokeClient } from "$lib/utils/invokeClient";
 import type {
   Project,
+  ClonedProject,
+  CloneProgress,
+  CloneProjectRequest,
   CreateProjectRequest,
   UpdateProjectRequest,
   ProjectStats,
+  ProjectGroup,
+  ProjectGroupKind,
+  QuickOpenResult,
 } from "$lib/domains/projects/types";
 import { fetchAllProjects, fetchProjectById } from "$lib/domains/projects/api/projectApi";
 import { projectUi } from "$lib/domains/projects/state/projectUi.svelte";
@@ -173,6 +180,43 @@ class ProjectService {
     }
   }
 
+  /**
+   * Clone a git repository, analyze it and register it as a project.
+   * onProgress gets the clone's progress as git reports it.
+   */
+  async cloneProject(
+    request: CloneProjectRequest,
+    onProgress?: (progress: CloneProgress) => void,
+  ): Promise<ClonedProject> {
+    const cloneId = crypto.randomUUID();
+    const unlisten = onProgress
+      ? await listen<CloneProgress>("project-clone-progress", (event) => {
+          if (event.payload.clone_id === cloneId) {
+            onProgress(event.payload);
+          }
+        })
+      : undefined;
+
+    try {
+      log.info("Cloning project", { url: request.url });
+
+      const cloned = await invokeClient.post<
+        Omit<ClonedProject, "project"> & { project: Record<string, unknown> }
+      >("clone_project", { request, cloneId });
+      const project = normalizeProject(cloned.project);
+
+      invalidateProjectCaches(String(project.id));
+
+      log.info("Project cloned successfully", { id: project.id });
+      return { ...cloned, project };
+    } catch (error) {
+      log.error("Failed to clone project", { error });
+      throw error;
+    } finally {
+      unlisten?.();
+    }
+  }
+
   /**
    * Update an existing project
    */
@@ -329,6 +373,122 @@ class ProjectService {
     }
   }
 
+  /**
+   * The project group hierarchy, with the projects in each group
+   */
+  async getGroups(): Promise<ProjectGroup[]> {
+    return invokeClient.post<ProjectGroup[]>("get_project_groups");
+  }
+
+  async createGroup(
+    name: string,
+    kind: ProjectGroupKind,
+    parentId?: number | null,
+    color?: string | null,
+  ): Promise<void> {
+    await invokeClient.post("create_project_group", {
+      name,
+      kind,
+      parentId: parentId ?? null,
+      color: color ?? null,
+    });
+  }
+
+  /**
+   * Rename a group or change its kind or color (null clears the color)
+   */
+  async updateGroup(
+    id: number,
+    changes: { name?: string; kind?: ProjectGroupKind; color?: string | null },
+  ): Promise<void> {
+    const { color, ...rest } = changes;
+    await invokeClient.post("update_project_group", {
+      id,
+      ...rest,
+      color: color === null ? "" : color,
+    });
+  }
+
+  /**
+   * Delete a group; its subgroups and projects move up to its parent
+   */
+  async deleteGroup(id: number): Promise<void> {
+    await invokeClient.post("delete_project_group", { id });
+    invalidateProjectsList(queryClient);
+  }
+
+  /**
+   * Drop a group under parentId (top level when null) at position
+   */
+  async moveGroup(
+    id: number,
+    parentId: number | null,
+    position?: number,
+  ): Promise<void> {
+    await invokeClient.post("move_project_group", { id, parentId, position });
+  }
+
+  /**
+   * Drop a project into groupId (ungrouped when null) at position
+   */
+  async moveProjectToGroup(
+    projectId: string,
+    groupId: number | null,
+    position?: number,
+  ): Promise<void> {
+    await invokeClient.post("move_project_to_group", {
+      projectId: parseInt(projectId, 10),
+      groupId,
+      position,
+    });
+    invalidateProjectCaches(projectId);
+  }
+
+  /**
+   * Fuzzy-search files across projects (all of them without projectIds);
+   * recently opened files rank higher, and alone answer an empty query
+   */
+  async quickOpenSearch(
+    query: string,
+    projectIds?: string[],
+    limit?: number,
+  ): Promise<QuickOpenResult[]> {
+    return invokeClient.post<QuickOpenResult[]>("quick_open_search", {
+      query,
+      projectIds: projectIds?.map((id) => parseInt(id, 10)),
+      limit,
+    });
+  }
+
+  /**
+   * Files recently opened from an IDE, a terminal or quick open
+   */
+  async getRecentFiles(
+    projectIds?: string[],
+    limit?: number,
+  ): Promise<QuickOpenResult[]> {
+    return invokeClient.post<QuickOpenResult[]>("get_recent_files", {
+      projectIds: projectIds?.map((id) => parseInt(id, 10)),
+      limit,
+    });
+  }
+
+  /**
+   * Record a file opened from quick open
+   */
+  async recordRecentFile(path: string): Promise<void> {
+    await invokeClient.post("record_recent_file", { path });
+  }
+
+  /**
+   * Index a project's files again, returning how many there are
+   */
+  async refreshFileIndex(id: string): Promise<number> {
+    return invokeClient.post<number>("refresh_file_index", {
+      projectId: parseInt(id, 10),
+    });
+  }
+
   // Note: exportProject() and importProject() removed - no backend support
   // These features can be implemented when backend commands are added
 
@@ -28,9 +28,20 @@ export interface Project extends BaseEntity {
   git_commit?: string;
   has_uncommitted_changes: boolean;
   last_commit?: Date;
+  /** Group the project is in; null when ungrouped */
+  group_id?: number | null;
+  /** Order among the projects of its group */
+  position?: number;
   created_at?: Date;
   updated_at?: Date;
   metadata: ProjectMetadata;
+  devcontainer?: Devcontainer | null;
+}
+
+export interface Devcontainer {
+  config_path: string;
+  name?: string | null;
+  workspace_folder: string;
 }
 
 export enum ProjectStatus {
@@ -132,3 +143,80 @@ export interface ProjectStats {
   most_used_type: string;
   recent_projects: Project[];
 }
+
+/** A project file from quick open, or a recently opened one */
+export interface QuickOpenResult {
+  project_id: number;
+  project_name: string;
+  /** Relative to the project root */
+  path: string;
+  absolute_path: string;
+  score: number;
+  /** Set when the file was opened recently */
+  opened_at: string | null;
+}
+
+export type ProjectGroupKind = "client" | "team" | "personal";
+
+/** A project group with its subgroups and the projects directly in it */
+export interface ProjectGroup {
+  id: number;
+  name: string;
+  kind: ProjectGroupKind;
+  parent_id: number | null;
+  color: string | null;
+  position: number;
+  /** Projects in the group, by position */
+  project_ids: number[];
+  children: ProjectGroup[];
+}
+
+export interface ProjectAnalysis {
+  name: string;
+  frameworks: string[];
+  languages: string[];
+  package_managers: string[];
+  build_command?: string | null;
+  start_command?: string | null;
+  test_command?: string | null;
+  output_directory?: string | null;
+  dev_port?: number | null;
+  prod_port?: number | null;
+}
+
+/** A framework the project looks like, with the files that gave it away */
+export interface FrameworkCandidate {
+  name: string;
+  /** 0 to 1 */
+  confidence: number;
+  evidence: string[];
+}
+
+export interface CloneProjectRequest {
+  url: string;
+  /** Directory the checkout is created in */
+  parent_directory: string;
+  /** The repository's name when not given */
+  directory_name?: string;
+  branch?: string;
+  /** Vault credential for private repositories */
+  credential_id?: string;
+  name?: string;
+  group_id?: number;
+  /** Clone only the latest commit */
+  shallow?: boolean;
+}
+
+export interface CloneProgress {
+  clone_id: string;
+  /** e.g. "Receiving objects", "Resolving deltas", "Analyzing" */
+  phase: string;
+  percent: number | null;
+  message: string;
+}
+
+export interface ClonedProject {
+  project: Project;
+  analysis: ProjectAnalysis;
+  framework_candidates: FrameworkCandidate[];
+}
@@ -91,6 +91,9 @@ export function normalizeProject(raw: RawProject): Project {
       raw.has_uncommitted_changes ?? raw.hasUncommittedChanges,
     ),
     last_commit: toOptionalDate(raw.last_commit ?? raw.lastCommit),
+    group_id:
+      ((raw.group_id ?? raw.groupId) as number | null | undefined) ?? null,
+    position: Number(raw.position ?? 0),
     created_at: createdAt,
     updated_at: updatedAt,
     metadata,
@@ -93,6 +93,7 @@ const DEFAULT_APP_SETTINGS: AppSettings = {
     checkInterval: 24,
     channel: "stable",
     notifyOnUpdate: true,
+    downloadOnMetered: false,
   },
   integrations: {
     github: DEFAULT_GITHUB_INTEGRATION_SETTINGS,
@@ -81,8 +81,9 @@ export interface UpdateSettings {
   autoDownload: boolean;
   autoInstall: boolean;
   checkInterval: number; // hours
-  channel: "stable" | "beta" | "alpha";
+  channel: "stable" | "beta";
   notifyOnUpdate: boolean;
+  downloadOnMetered: boolean;
 }
 
 export interface EditorSettings {
@@ -124,7 +125,7 @@ export interface TerminalSettings {
   fontFamily: string;
   fontSize: number;
   lineHeight: number;
-  cursorStyle: "block" | "underline" | "line";
+  cursorStyle: "block" | "underline" | "bar";
   cursorBlink: boolean;
 
   // Terminal behavior
@@ -1,6 +1,10 @@
 import { TerminalService } from "../services/terminalService";
 import type { TerminalOutput, TerminalProcess } from "../types";
 import { isTauriEnvironment } from "$lib/utils/tauri";
+import {
+  broadcastTargets,
+  removeBroadcastTargets,
+} from "../stores/broadcastStore";
 
 export interface ProcessConfig {
   tabId: string;
@@ -56,6 +60,13 @@ export async function sendProcessInput(
   input: string,
   tabId?: string,
 ): Promise<void> {
+  const targets = broadcastTargets(processId);
+  if (targets) {
+    // Drop sessions that have gone away so broadcast stops retrying them
+    const { failed } = await TerminalService.sendInputToGroup(targets, input);
+    removeBroadcastTargets(Object.keys(failed));
+    return;
+  }
   await TerminalService.sendInput(processId, input, tabId);
 }
 
@@ -36,6 +36,12 @@ export {
   type CapturedCommand,
   type CommandBlockSource,
 } from "./stores/commandBlockStore";
+export {
+  broadcastStore,
+  setBroadcastEnabled,
+  toggleBroadcastTarget,
+  type BroadcastState,
+} from "./stores/broadcastStore";
 
 // Composables
 export { XtermSession } from "./composables/useXtermSession";
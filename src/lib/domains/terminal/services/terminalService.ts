@@ -11,9 +11,13 @@ import type {
   TerminalCommand,
   TerminalContext,
   CreateProcessRequest,
+  ShellProfile,
+  ShellProfileInput,
+  BroadcastResult,
 } from "../types";
 import { commandHistoryStore } from "../stores/commandHistoryStore";
 import { patternCollector } from "$lib/domains/learning";
+import { stripAnsiCodes } from "../utils/textUtils";
 import {
   subscribeTerminalOutput,
   ensureTerminalOutputListener,
@@ -129,6 +133,29 @@ export class TerminalService {
     }
   }
 
+  /**
+   * Send the same input to several sessions at once (broadcast mode)
+   */
+  static async sendInputToGroup(
+    processIds: string[],
+    input: string,
+  ): Promise<BroadcastResult> {
+    try {
+      return await invokeClient.request<BroadcastResult>(
+        "send_input_to_group",
+        {
+          data: {
+            processIds,
+            input,
+          },
+        },
+      );
+    } catch (error) {
+      console.error("Failed to broadcast input:", error);
+      throw error;
+    }
+  }
+
   /**
    * Kill process with full cleanup
    */
@@ -241,6 +268,60 @@ export class TerminalService {
     }
   }
 
+  /**
+   * Get shell profiles for this OS, built-in ones first
+   */
+  static async getShellProfiles(): Promise<ShellProfile[]> {
+    try {
+      return await invokeClient.request<ShellProfile[]>("get_shell_profiles");
+    } catch (error) {
+      console.error("Failed to get shell profiles:", error);
+      throw error;
+    }
+  }
+
+  static async createShellProfile(
+    profile: ShellProfileInput,
+  ): Promise<ShellProfile> {
+    try {
+      return await invokeClient.request<ShellProfile>(
+        "create_shell_profile",
+        {
+          data: { profile },
+        },
+      );
+    } catch (error) {
+      console.error("Failed to create shell profile:", error);
+      throw error;
+    }
+  }
+
+  static async updateShellProfile(
+    id: string,
+    profile: ShellProfileInput,
+  ): Promise<ShellProfile> {
+    try {
+      return await invokeClient.request<ShellProfile>(
+        "update_shell_profile",
+        {
+          data: { id, profile },
+        },
+      );
+    } catch (error) {
+      console.error("Failed to update shell profile:", error);
+      throw error;
+    }
+  }
+
+  static async deleteShellProfile(id: string): Promise<void> {
+    try {
+      await invokeClient.request("delete_shell_profile", { data: { id } });
+    } catch (error) {
+      console.error("Failed to delete shell profile:", error);
+      throw error;
+    }
+  }
+
   /**
    * Resize terminal
    */
@@ -338,13 +419,21 @@ export class TerminalService {
       console.log(`Process ${processId} exited with code:`, exitCode);
 
       // Complete the current command with the real exit code
-      await this.completeCurrentCommand(exitCode ?? undefined, tabId);
+      await this.completeCurrentCommand(
+        exitCode ?? undefined,
+        tabId,
+        process?.working_directory,
+      );
     } catch (error) {
       console.error("Failed to get process exit code:", error);
       // Fallback to output-based detection
       const process = await this.getProcess(processId);
       const tabId = process?.tab_id;
-      await this.completeCurrentCommand(undefined, tabId);
+      await this.completeCurrentCommand(
+        undefined,
+        tabId,
+        process?.working_directory,
+      );
     }
   }
 
@@ -369,6 +458,7 @@ export class TerminalService {
   static async completeCurrentCommand(
     exitCode?: number,
     tabId?: string,
+    workingDirectory?: string,
   ): Promise<void> {
     // console.log('Completing current command:', this.currentCommand?.command, 'tabId:', tabId);
     if (this.currentCommand) {
@@ -400,6 +490,11 @@ export class TerminalService {
           this.currentCommand.command,
           success,
           context,
+          {
+            exitCode: detectedExitCode,
+            output: stripAnsiCodes(this.currentCommand.output),
+            cwd: workingDirectory,
+          },
         );
       } catch (error) {
         // Don't fail command completion if learning fails
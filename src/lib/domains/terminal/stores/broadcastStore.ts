@@ -0,0 +1,48 @@
+/**
+ * Broadcast Store
+ * Sessions that input is fanned out to while broadcast mode is on
+ */
+
+import { get, writable } from "svelte/store";
+
+export interface BroadcastState {
+  enabled: boolean;
+  processIds: string[];
+}
+
+export const broadcastStore = writable<BroadcastState>({
+  enabled: false,
+  processIds: [],
+});
+
+export function setBroadcastEnabled(enabled: boolean): void {
+  broadcastStore.update((state) => ({ ...state, enabled }));
+}
+
+export function toggleBroadcastTarget(processId: string): void {
+  broadcastStore.update((state) => ({
+    ...state,
+    processIds: state.processIds.includes(processId)
+      ? state.processIds.filter((id) => id !== processId)
+      : [...state.processIds, processId],
+  }));
+}
+
+export function removeBroadcastTargets(processIds: string[]): void {
+  broadcastStore.update((state) => ({
+    ...state,
+    processIds: state.processIds.filter((id) => !processIds.includes(id)),
+  }));
+}
+
+/**
+ * Sessions input typed into `processId` goes to, or null when that session
+ * isn't broadcasting
+ */
+export function broadcastTargets(processId: string): string[] | null {
+  const { enabled, processIds } = get(broadcastStore);
+  if (!enabled || !processIds.includes(processId) || processIds.length < 2) {
+    return null;
+  }
+  return processIds;
+}
@@ -85,6 +85,36 @@ export interface CreateProcessRequest {
   rows: number;
   /** When set, the PTY runs this single command and exits (oneshot mode). */
   command?: string;
+  /** Start the shell from this profile instead of `shell`. */
+  profile_id?: string;
+}
+
+export interface BroadcastResult {
+  delivered: string[];
+  /** Sessions the input couldn't be written to, with the error. */
+  failed: Record<string, string>;
+}
+
+export interface ShellProfile {
+  id: string;
+  name: string;
+  os: string;
+  shell: string;
+  args: string[];
+  startup_script: string | null;
+  env: Record<string, string>;
+  icon: string | null;
+  /** Built-in profiles can't be changed or deleted. */
+  builtin: boolean;
+}
+
+export interface ShellProfileInput {
+  name: string;
+  shell: string;
+  args?: string[];
+  startup_script?: string | null;
+  env?: Record<string, string>;
+  icon?: string | null;
 }
 
 export interface TerminalSystemInfo {